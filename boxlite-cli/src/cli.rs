@@ -2,28 +2,37 @@
 //! This module contains all CLI-related code including the main CLI structure,
 //! subcommands, and flag definitions.
 
-use boxlite::runtime::options::{PortProtocol, PortSpec, VolumeSpec};
-use boxlite::{BoxCommand, BoxOptions, BoxliteOptions, BoxliteRuntime};
+use boxlite::runtime::layout::{FilesystemLayout, FsLayoutConfig};
+use boxlite::runtime::options::{
+    CoreDumpOptions, KernelSpec, NetworkPolicy, NetworkSpec, PortProtocol, PortSpec, SidecarSpec,
+    SshOptions, TmpfsSpec, VolumeIdShift, VolumeSpec,
+};
+use boxlite::{BoxCommand, BoxOptions, BoxStatus, BoxliteOptions, BoxliteRuntime, OutputRecording};
 use clap::{Args, Command, Parser, Subcommand, ValueEnum};
 use clap_complete::shells::{Bash, Fish, Zsh};
 use std::io::{IsTerminal, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Helper to parse CLI environment variables and apply them to BoxOptions
-pub fn apply_env_vars(env: &[String], opts: &mut BoxOptions) {
+pub fn apply_env_vars(env: &[String], opts: &mut BoxOptions) -> anyhow::Result<()> {
     apply_env_vars_with_lookup(env, opts, |k| std::env::var(k).ok())
 }
 
 /// Helper to parse CLI environment variables with custom lookup for host variables
-pub fn apply_env_vars_with_lookup<F>(env: &[String], opts: &mut BoxOptions, lookup: F)
+pub fn apply_env_vars_with_lookup<F>(
+    env: &[String],
+    opts: &mut BoxOptions,
+    lookup: F,
+) -> anyhow::Result<()>
 where
     F: Fn(&str) -> Option<String>,
 {
     for env_str in env {
+        let env_str = crate::host_vars::expand(env_str)?;
         if let Some((k, v)) = env_str.split_once('=') {
             opts.env.push((k.to_string(), v.to_string()));
-        } else if let Some(val) = lookup(env_str) {
-            opts.env.push((env_str.to_string(), val));
+        } else if let Some(val) = lookup(&env_str) {
+            opts.env.push((env_str, val));
         } else {
             tracing::warn!(
                 "Environment variable '{}' not found on host, skipping",
@@ -31,6 +40,7 @@ where
             );
         }
     }
+    Ok(())
 }
 
 // ============================================================================
@@ -72,18 +82,90 @@ pub enum Commands {
     /// Restart one or more boxes
     Restart(crate::commands::restart::RestartArgs),
 
+    /// Freeze one or more running boxes
+    Pause(crate::commands::pause::PauseArgs),
+
+    /// Resume one or more paused boxes
+    Unpause(crate::commands::unpause::UnpauseArgs),
+
+    /// Clone a stopped box (copy-on-write)
+    Clone(crate::commands::clone::CloneArgs),
+
+    /// Export a stopped box to a portable archive
+    Export(crate::commands::export::ExportArgs),
+
+    /// Import a box from an archive
+    Import(crate::commands::import::ImportArgs),
+
     /// Pull an image from a registry
     Pull(crate::commands::pull::PullArgs),
 
+    /// Create a tag that refers to a local image
+    Tag(crate::commands::tag::TagArgs),
+
+    /// Push an image to a registry
+    Push(crate::commands::push::PushArgs),
+
+    /// Run a pull-through registry cache
+    RegistryCache(crate::commands::registry_cache::RegistryCacheArgs),
+
+    /// Build an image from a Dockerfile
+    Build(crate::commands::build::BuildArgs),
+
     /// List images
     Images(crate::commands::images::ImagesArgs),
 
+    /// Show disk usage and other host-wide information
+    System(crate::commands::system::SystemArgs),
+
+    /// Check configuration files
+    Config(crate::commands::config::ConfigArgs),
+
     /// Display detailed information on a box
     Inspect(crate::commands::inspect::InspectArgs),
 
     /// Copy files/folders between host and box
     Cp(crate::commands::cp::CpArgs),
 
+    /// Copy a host directory into a box and optionally keep it in sync
+    Sync(crate::commands::sync::SyncArgs),
+
+    /// List, publish or unpublish ports of a box
+    Port(crate::commands::port::PortArgs),
+
+    /// Network observability: connections a box opened
+    Net(crate::commands::net::NetArgs),
+
+    /// Display the processes running in a box
+    Top(crate::commands::top::TopArgs),
+
+    /// Display resource usage of a box, live or recorded
+    Stats(crate::commands::stats::StatsArgs),
+
+    /// Block until a box stops, becomes healthy, or listens on a port
+    Wait(crate::commands::wait::WaitArgs),
+
+    /// Mount a host directory into a running box
+    Mount(crate::commands::mount::MountArgs),
+
+    /// Unmount a directory mounted with `boxlite mount`
+    Umount(crate::commands::umount::UmountArgs),
+
+    /// Open an SSH session to a box started with --ssh
+    Ssh(crate::commands::ssh::SshArgs),
+
+    /// Run a dev container (devcontainer.json) as a box
+    Devcontainer(crate::commands::devcontainer::DevcontainerArgs),
+
+    /// Interactive dashboard of boxes, metrics and console logs
+    Dash(crate::commands::dash::DashArgs),
+
+    /// Debugging aids: core dumps of crashed processes
+    Debug(crate::commands::debug::DebugArgs),
+
+    /// Let LLM agents drive boxes over the Model Context Protocol
+    Mcp(crate::commands::mcp::McpArgs),
+
     /// Generate shell completion script (hidden from help)
     #[command(hide = true)]
     Completion(CompletionArgs),
@@ -98,22 +180,147 @@ pub enum Shell {
     Fish,
 }
 
+/// Values completed from the runtime database rather than the CLI definition.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+#[value(rename_all = "lower")]
+pub enum DynamicValue {
+    /// Names and IDs of all boxes
+    Boxes,
+    /// Names and IDs of running boxes
+    Running,
+    /// Names and IDs of boxes that are not running
+    Stopped,
+    /// Cached image references
+    Images,
+}
+
 /// Arguments for the completion subcommand.
 #[derive(Args, Debug)]
 pub struct CompletionArgs {
     /// Shell to generate completion for (bash, zsh, fish).
-    pub shell: Shell,
+    #[arg(required_unless_present = "dynamic")]
+    pub shell: Option<Shell>,
+
+    /// Print candidates for a dynamic value, one per line (called by the
+    /// generated scripts)
+    #[arg(long, value_name = "KIND", conflicts_with = "shell")]
+    pub dynamic: Option<DynamicValue>,
 }
 
 /// Writes a completion script for the given shell to `out`.
+///
+/// The static clap script is followed by a wrapper that completes box names
+/// and image references via `boxlite completion --dynamic`.
 pub fn generate_completion(shell: &Shell, cmd: &mut Command, name: &str, out: &mut dyn Write) {
     match shell {
         Shell::Bash => clap_complete::generate(Bash, cmd, name, out),
         Shell::Zsh => clap_complete::generate(Zsh, cmd, name, out),
         Shell::Fish => clap_complete::generate(Fish, cmd, name, out),
     }
+    let dynamic = match shell {
+        Shell::Bash => DYNAMIC_BASH,
+        Shell::Zsh => DYNAMIC_ZSH,
+        Shell::Fish => DYNAMIC_FISH,
+    };
+    let _ = out.write_all(dynamic.replace("{name}", name).as_bytes());
 }
 
+/// Writes candidates for `kind` to `out`, one per line.
+///
+/// Reads the database without opening a runtime, so it is fast and works
+/// while boxes are running. Errors yield no candidates rather than noise in
+/// the user's prompt.
+pub fn write_dynamic_candidates(kind: DynamicValue, global: &GlobalFlags, out: &mut dyn Write) {
+    let Ok(options) = global.runtime_options() else {
+        return;
+    };
+    let candidates: Vec<String> = if kind == DynamicValue::Images {
+        BoxliteRuntime::peek_images(&options.home_dir).unwrap_or_default()
+    } else {
+        BoxliteRuntime::peek_boxes(&options.home_dir)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|info| match kind {
+                DynamicValue::Running => info.status.is_running(),
+                DynamicValue::Stopped => !info.status.is_running(),
+                _ => true,
+            })
+            .flat_map(|info| info.name.into_iter().chain([info.id.to_string()]))
+            .collect()
+    };
+    for candidate in candidates {
+        let _ = writeln!(out, "{}", candidate);
+    }
+}
+
+/// Bash: box arguments of exec/rm/start/stop/top/wait and the image of run/create.
+const DYNAMIC_BASH: &str = r#"
+_{name}_dynamic() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" sub="" kind="" positional=0 i
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        if [[ -z "$sub" ]]; then
+            [[ "${COMP_WORDS[i]}" != -* ]] && sub="${COMP_WORDS[i]}"
+        elif [[ "${COMP_WORDS[i]}" != -* ]]; then
+            ((positional++))
+        fi
+    done
+    case "$sub" in
+        rm) kind=boxes ;;
+        stop | top | wait) kind=running ;;
+        start) kind=stopped ;;
+        exec) ((positional == 0)) && kind=running ;;
+        run | create) ((positional == 0)) && kind=images ;;
+    esac
+    if [[ -n "$kind" && "$cur" != -* ]]; then
+        COMPREPLY=($(compgen -W "$({name} completion --dynamic "$kind" 2>/dev/null)" -- "$cur"))
+        return 0
+    fi
+    _{name} "$@"
+}
+if [[ "${BASH_VERSINFO[0]}" -eq 4 && "${BASH_VERSINFO[1]}" -ge 4 || "${BASH_VERSINFO[0]}" -gt 4 ]]; then
+    complete -F _{name}_dynamic -o nosort -o bashdefault -o default {name}
+else
+    complete -F _{name}_dynamic -o bashdefault -o default {name}
+fi
+"#;
+
+/// Zsh: same arguments as bash; falls back to the static `_{name}` function.
+const DYNAMIC_ZSH: &str = r#"
+_{name}_dynamic() {
+    local sub="" kind="" positional=0 i
+    for ((i = 2; i < CURRENT; i++)); do
+        if [[ -z "$sub" ]]; then
+            [[ "${words[i]}" != -* ]] && sub="${words[i]}"
+        elif [[ "${words[i]}" != -* ]]; then
+            ((positional++))
+        fi
+    done
+    case "$sub" in
+        rm) kind=boxes ;;
+        stop | top | wait) kind=running ;;
+        start) kind=stopped ;;
+        exec) ((positional == 0)) && kind=running ;;
+        run | create) ((positional == 0)) && kind=images ;;
+    esac
+    if [[ -n "$kind" && "${words[CURRENT]}" != -* ]]; then
+        local -a candidates
+        candidates=(${(f)"$({name} completion --dynamic "$kind" 2>/dev/null)"})
+        compadd -a candidates
+        return
+    fi
+    _{name} "$@"
+}
+compdef _{name}_dynamic {name}
+"#;
+
+/// Fish: completions add up, so each argument just gets another source.
+const DYNAMIC_FISH: &str = r#"
+complete -c {name} -n "__fish_seen_subcommand_from rm" -f -a "({name} completion --dynamic boxes 2>/dev/null)"
+complete -c {name} -n "__fish_seen_subcommand_from stop exec top wait" -f -a "({name} completion --dynamic running 2>/dev/null)"
+complete -c {name} -n "__fish_seen_subcommand_from start" -f -a "({name} completion --dynamic stopped 2>/dev/null)"
+complete -c {name} -n "__fish_seen_subcommand_from run create" -f -a "({name} completion --dynamic images 2>/dev/null)"
+"#;
+
 // ============================================================================
 // GLOBAL FLAGS
 // ============================================================================
@@ -132,21 +339,103 @@ pub struct GlobalFlags {
     #[arg(long, global = true, value_name = "REGISTRY")]
     pub registry: Vec<String>,
 
+    /// Registry mirror as REGISTRY=MIRROR, e.g. docker.io=mirror.internal
+    /// (can be specified multiple times; tried in order before the registry)
+    #[arg(long, global = true, value_name = "REGISTRY=MIRROR")]
+    pub registry_mirror: Vec<String>,
+
+    /// Proxy URL for registry traffic (defaults to $HTTPS_PROXY / $HTTP_PROXY)
+    #[arg(long, global = true, value_name = "URL")]
+    pub proxy: Option<String>,
+
+    /// Pass HTTP_PROXY, HTTPS_PROXY and NO_PROXY into created boxes
+    #[arg(long, global = true)]
+    pub propagate_proxy: bool,
+
+    /// Bandwidth limit for image layer downloads (in Mbit/s)
+    #[arg(long, global = true, value_name = "MBPS")]
+    pub pull_rate_limit: Option<u64>,
+
+    /// Retry transient image pull and guest boot failures up to N times
+    #[arg(long, global = true, value_name = "N")]
+    pub create_retries: Option<u32>,
+
+    /// Boot boxes from a custom guest rootfs (directory or ext4 image with boxlite-guest)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub guest_rootfs: Option<std::path::PathBuf>,
+
+    /// Container rootfs disk format (erofs/squashfs: compressed read-only image + overlay)
+    #[arg(long, global = true, value_enum, value_name = "FORMAT")]
+    pub rootfs_format: Option<RootfsFormatArg>,
+
+    /// Dedup identical files across newly extracted image layers
+    #[arg(long, global = true)]
+    pub dedup_layers: bool,
+
+    /// Disable the configured retention policy (no automatic pruning of
+    /// stopped boxes or cached images)
+    #[arg(long, global = true)]
+    pub no_prune: bool,
+
     /// Configuration file path (optional)
     ///
     /// Specifies the JSON configuration file containing BoxLite options such as image_registries.
     /// If not provided, uses default options (no config file is loaded from $BOXLITE_HOME).
     #[arg(long, global = true)]
     pub config: Option<String>,
+
+    /// Named profile from the configuration file (e.g. dev, ci, prod)
+    ///
+    /// The profile's options are merged over the file's top-level options;
+    /// CLI flags still take precedence. Requires --config.
+    /// Named `--config-profile` so it does not clash with the built-in
+    /// workload presets of `run`/`create --profile`.
+    /// `BOXLITE_CONFIG_PROFILE` is accepted as an alias of `BOXLITE_PROFILE`.
+    #[arg(long, global = true, env = "BOXLITE_PROFILE", value_name = "NAME")]
+    pub config_profile: Option<String>,
 }
 
 impl GlobalFlags {
     pub fn create_runtime(&self) -> anyhow::Result<BoxliteRuntime> {
+        BoxliteRuntime::new(self.runtime_options()?).map_err(Into::into)
+    }
+
+    /// Directory volumes are created in, as configured for the runtime.
+    pub fn volumes_dir(&self) -> anyhow::Result<PathBuf> {
+        let options = self.runtime_options()?;
+        let layout = FilesystemLayout::from_options(&options, FsLayoutConfig::default());
+        Ok(layout.volumes_dir().to_path_buf())
+    }
+
+    /// The selected config profile: `--config-profile`, `BOXLITE_PROFILE`
+    /// or its alias `BOXLITE_CONFIG_PROFILE`, in that order.
+    pub fn selected_profile(&self) -> Option<String> {
+        self.config_profile
+            .clone()
+            .or_else(|| std::env::var("BOXLITE_CONFIG_PROFILE").ok())
+            .filter(|profile| !profile.is_empty())
+    }
+
+    /// The config file with the selected profile applied, if a file is given.
+    pub fn load_config(&self) -> anyhow::Result<Option<crate::config::Config>> {
+        match (&self.config, self.selected_profile()) {
+            (Some(path), profile) => {
+                crate::config::load_profile(Path::new(path), profile.as_deref()).map(Some)
+            }
+            (None, Some(profile)) => anyhow::bail!(
+                "profile '{}' selected (--config-profile or BOXLITE_PROFILE) but no --config file given",
+                profile
+            ),
+            (None, None) => Ok(None),
+        }
+    }
+
+    /// Runtime options from the config file with CLI overrides applied.
+    pub fn runtime_options(&self) -> anyhow::Result<BoxliteOptions> {
         // Load config file if provided, otherwise use default options
-        let mut options = if let Some(config_path) = &self.config {
-            crate::config::load_config(Path::new(config_path))?
-        } else {
-            BoxliteOptions::default()
+        let mut options = match self.load_config()? {
+            Some(config) => config.options,
+            None => BoxliteOptions::default(),
         };
 
         // CLI --home override home_dir
@@ -164,10 +453,67 @@ impl GlobalFlags {
                 .collect();
         }
 
-        BoxliteRuntime::new(options).map_err(Into::into)
+        // CLI --registry-mirror entries are tried before configured mirrors
+        for entry in self.registry_mirror.iter().rev() {
+            let (registry, mirror) = entry.split_once('=').ok_or_else(|| {
+                anyhow::anyhow!(
+                    "invalid --registry-mirror '{}': expected REGISTRY=MIRROR",
+                    entry
+                )
+            })?;
+            options
+                .registry_mirrors
+                .entry(registry.to_string())
+                .or_default()
+                .insert(0, mirror.to_string());
+        }
+
+        if let Some(proxy) = &self.proxy {
+            options.proxy.http_proxy = Some(proxy.clone());
+            options.proxy.https_proxy = Some(proxy.clone());
+        }
+        if self.propagate_proxy {
+            options.proxy.propagate_to_boxes = true;
+        }
+        if let Some(mbps) = self.pull_rate_limit {
+            options.pull_rate_limit_mbps = Some(mbps);
+        }
+        if let Some(retries) = self.create_retries {
+            options.create_retries = retries;
+        }
+        if let Some(path) = &self.guest_rootfs {
+            options.guest_rootfs_path = Some(std::path::absolute(path)?);
+        }
+        if let Some(format) = self.rootfs_format {
+            options.rootfs_format = match format {
+                RootfsFormatArg::Ext4 => boxlite::RootfsFormat::Ext4,
+                RootfsFormatArg::Erofs => boxlite::RootfsFormat::Erofs,
+                RootfsFormatArg::Squashfs => boxlite::RootfsFormat::Squashfs,
+            };
+        }
+        if self.dedup_layers {
+            options.dedup_layers = true;
+        }
+        if self.no_prune {
+            options.retention = None;
+        }
+
+        Ok(options)
     }
 }
 
+/// Container rootfs disk format.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+#[value(rename_all = "lower")]
+pub enum RootfsFormatArg {
+    /// Writable ext4 disk (default)
+    Ext4,
+    /// Compressed EROFS image under an overlay
+    Erofs,
+    /// Compressed SquashFS image under an overlay
+    Squashfs,
+}
+
 // ============================================================================
 // PROCESS FLAGS
 // ============================================================================
@@ -182,13 +528,22 @@ pub struct ProcessFlags {
     #[arg(short, long)]
     pub tty: bool,
 
-    /// Set environment variables
+    /// Set environment variables; {{uid}}, {{gid}}, {{user}}, {{home}} and
+    /// {{cwd}} expand to host values (\{{ keeps literal braces)
     #[arg(short = 'e', long = "env")]
     pub env: Vec<String>,
 
     /// Working directory inside the box
     #[arg(short = 'w', long = "workdir")]
     pub workdir: Option<String>,
+
+    /// Record stdout/stderr to rotated log files under the box home
+    #[arg(long)]
+    pub record: bool,
+
+    /// Record stdout/stderr to rotated log files in DIR (implies --record)
+    #[arg(long, value_name = "DIR")]
+    pub output_dir: Option<PathBuf>,
 }
 
 impl ProcessFlags {
@@ -203,8 +558,7 @@ impl ProcessFlags {
         F: Fn(&str) -> Option<String>,
     {
         opts.working_dir = self.workdir.clone();
-        apply_env_vars_with_lookup(&self.env, opts, lookup);
-        Ok(())
+        apply_env_vars_with_lookup(&self.env, opts, lookup)
     }
 
     /// Validate process flags
@@ -213,17 +567,36 @@ impl ProcessFlags {
         if !detach && self.tty && !std::io::stdin().is_terminal() {
             anyhow::bail!("the input device is not a TTY.");
         }
+        self.validate_recording(detach)
+    }
 
+    /// Output is recorded by this process, so it must stay attached.
+    pub fn validate_recording(&self, detach: bool) -> anyhow::Result<()> {
+        if detach && self.recording().is_some() {
+            anyhow::bail!("--record and --output-dir cannot be used with --detach");
+        }
         Ok(())
     }
 
+    /// Output recording requested by --record or --output-dir.
+    pub fn recording(&self) -> Option<OutputRecording> {
+        if !self.record && self.output_dir.is_none() {
+            return None;
+        }
+        Some(OutputRecording {
+            dir: self.output_dir.clone(),
+            ..Default::default()
+        })
+    }
+
     /// Configures a BoxCommand with process flags (env, workdir, tty)
-    pub fn configure_command(&self, mut cmd: BoxCommand) -> BoxCommand {
+    pub fn configure_command(&self, mut cmd: BoxCommand) -> anyhow::Result<BoxCommand> {
         for env_str in &self.env {
+            let env_str = crate::host_vars::expand(env_str)?;
             if let Some((k, v)) = env_str.split_once('=') {
                 cmd = cmd.env(k, v);
-            } else if let Ok(val) = std::env::var(env_str) {
-                cmd = cmd.env(env_str, val);
+            } else if let Ok(val) = std::env::var(&env_str) {
+                cmd = cmd.env(&env_str, val);
             }
         }
 
@@ -235,7 +608,11 @@ impl ProcessFlags {
             cmd = cmd.tty(true);
         }
 
-        cmd
+        if let Some(recording) = self.recording() {
+            cmd = cmd.record_output(recording);
+        }
+
+        Ok(cmd)
     }
 }
 
@@ -252,6 +629,18 @@ pub struct ResourceFlags {
     /// Memory limit (in MiB)
     #[arg(long)]
     pub memory: Option<u32>,
+
+    /// Egress bandwidth limit (in Mbit/s)
+    #[arg(long, value_name = "MBPS")]
+    pub network_limit: Option<u64>,
+
+    /// Size of /dev/shm (e.g. 512m, 2g; default 64m)
+    #[arg(long, value_name = "SIZE", value_parser = parse_shm_size)]
+    pub shm_size: Option<u32>,
+
+    /// Host CPU and I/O priority against other boxes
+    #[arg(long, value_enum, value_name = "PRIORITY")]
+    pub priority: Option<PriorityArg>,
 }
 
 impl ResourceFlags {
@@ -265,18 +654,51 @@ impl ResourceFlags {
         if let Some(mem) = self.memory {
             opts.memory_mib = Some(mem);
         }
+        if let Some(mbps) = self.network_limit {
+            opts.network_limit_mbps = Some(mbps);
+        }
+        if let Some(mib) = self.shm_size {
+            opts.shm_size_mib = Some(mib);
+        }
+        if let Some(priority) = self.priority {
+            opts.priority = match priority {
+                PriorityArg::Low => boxlite::BoxPriority::Low,
+                PriorityArg::Normal => boxlite::BoxPriority::Normal,
+                PriorityArg::High => boxlite::BoxPriority::High,
+            };
+        }
     }
 }
 
+/// Host scheduling priority of a box.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+#[value(rename_all = "lower")]
+pub enum PriorityArg {
+    /// Batch work that yields to other boxes
+    Low,
+    /// Kernel defaults
+    Normal,
+    /// Interactive work that should stay responsive
+    High,
+}
+
 // ============================================================================
 // PUBLISH (PORT) FLAGS
 // ============================================================================
 
 #[derive(Args, Debug, Clone)]
 pub struct PublishFlags {
-    /// Publish a box port to the host (format: [hostPort:]boxPort[/tcp|udp], e.g. 18789:18789)
+    /// Publish a box port to the host (format: [[hostIp:]hostPort:]boxPort[/tcp|udp], e.g. 127.0.0.1:8080:80)
     #[arg(short = 'p', long = "publish", value_name = "PORT")]
     pub publish: Vec<String>,
+
+    /// Publish every port the image exposes to a free host port (see `boxlite port`)
+    #[arg(short = 'P', long)]
+    pub publish_all: bool,
+
+    /// Publish on the next free host port when a requested one is taken
+    #[arg(long)]
+    pub publish_retry: bool,
 }
 
 impl PublishFlags {
@@ -291,16 +713,20 @@ impl PublishFlags {
             }
             opts.ports.push(spec);
         }
+        opts.publish_all |= self.publish_all;
+        opts.publish_retry |= self.publish_retry;
         Ok(())
     }
 }
 
-/// Parse a single publish spec: `[hostPort:]boxPort[/tcp|udp]`.
-/// - `boxPort` → host_port=None, guest_port=boxPort
+/// Parse a single publish spec: `[[hostIp:][hostPort]:]boxPort[/tcp|udp]`.
+/// - `boxPort` → host_port=None (a free host port is assigned at start), guest_port=boxPort
 /// - `hostPort:boxPort` → host_port=Some(hostPort), guest_port=boxPort
+/// - `hostIp:hostPort:boxPort` → also binds only to hostIp (IPv6 in brackets: `[::1]:8080:80`)
+/// - `hostIp::boxPort` → binds to hostIp, host_port=None (assigned at start)
 ///
 /// Only TCP is forwarded by the runtime today; UDP is accepted but not yet implemented.
-fn parse_publish_spec(s: &str) -> anyhow::Result<PortSpec> {
+pub fn parse_publish_spec(s: &str) -> anyhow::Result<PortSpec> {
     let s = s.trim();
     if s.is_empty() {
         anyhow::bail!("empty port spec");
@@ -318,19 +744,24 @@ fn parse_publish_spec(s: &str) -> anyhow::Result<PortSpec> {
         }
         None => (s, PortProtocol::Tcp),
     };
+    let (host_ip, rest) = split_host_ip(rest)?;
     let parts: Vec<&str> = rest.splitn(2, ':').map(str::trim).collect();
     let (host_port, guest_port) = match parts.as_slice() {
-        [guest] => {
+        [guest] if host_ip.is_none() => {
             let g = parse_port(guest)?;
             (None, g)
         }
         [host, guest] => {
-            let h = parse_port(host)?;
+            let h = if host.is_empty() && host_ip.is_some() {
+                None
+            } else {
+                Some(parse_port(host)?)
+            };
             let g = parse_port(guest)?;
-            (Some(h), g)
+            (h, g)
         }
         _ => anyhow::bail!(
-            "invalid port spec {:?}; use hostPort:boxPort or boxPort[/tcp]",
+            "invalid port spec {:?}; use [hostIp:]hostPort:boxPort or boxPort[/tcp]",
             s
         ),
     };
@@ -338,10 +769,28 @@ fn parse_publish_spec(s: &str) -> anyhow::Result<PortSpec> {
         host_port,
         guest_port,
         protocol,
-        host_ip: None,
+        host_ip,
     })
 }
 
+/// Split a leading host IP off `hostIp:hostPort:boxPort`, if present.
+fn split_host_ip(s: &str) -> anyhow::Result<(Option<String>, &str)> {
+    let (ip, rest) = if let Some(bracketed) = s.strip_prefix('[') {
+        bracketed
+            .split_once("]:")
+            .ok_or_else(|| anyhow::anyhow!("invalid IPv6 address in port spec {:?}", s))?
+    } else if s.matches(':').count() == 2 {
+        s.split_once(':').unwrap_or_default()
+    } else {
+        return Ok((None, s));
+    };
+    let ip: std::net::IpAddr = ip
+        .trim()
+        .parse()
+        .map_err(|_| anyhow::anyhow!("invalid host IP {:?}", ip))?;
+    Ok((Some(ip.to_string()), rest))
+}
+
 fn parse_port(s: &str) -> anyhow::Result<u16> {
     let n: u16 = s
         .parse()
@@ -360,14 +809,28 @@ fn parse_port(s: &str) -> anyhow::Result<u16> {
 struct ParsedVolumeSpec {
     host_path: Option<String>,
     guest_path: String,
+    options: VolumeMountOptions,
+}
+
+/// Options after the last `:` of a volume spec.
+#[derive(Debug, Default, PartialEq)]
+struct VolumeMountOptions {
     read_only: bool,
+    chown: bool,
+    idmap: bool,
 }
 
 #[derive(Args, Debug, Clone)]
 pub struct VolumeFlags {
-    /// Mount a volume (format: hostPath:boxPath[:options], or boxPath for anonymous volume, e.g. /data:/app/data, /data:ro)
+    /// Mount a volume (format: hostPath:boxPath[:options], or boxPath for anonymous volume, e.g. /data:/app/data, /app.yaml:/etc/app.yaml:ro, /data:ro).
+    /// Options (comma-separated): ro, rw, chown (chown to the box user), idmap (box root owns files as you on the host).
+    /// Host variables expand as in --env, e.g. {{home}}/data:/data
     #[arg(short = 'v', long = "volume", value_name = "VOLUME")]
     pub volume: Vec<String>,
+
+    /// Mount a tmpfs (format: boxPath[:options], e.g. /tmp, /scratch:size=64m,mode=1777)
+    #[arg(long = "tmpfs", value_name = "PATH[:OPTIONS]")]
+    pub tmpfs: Vec<String>,
 }
 
 /// True if the segment is a single ASCII letter (Windows drive, e.g. "C" in "C:\path").
@@ -386,9 +849,19 @@ fn is_windows_absolute_path(path: &str) -> bool {
     b.len() >= 3 && b[0].is_ascii_alphabetic() && b[1] == b':' && (b[2] == b'\\' || b[2] == b'/')
 }
 
-/// Parse options string (e.g. "ro" or "rw,nocopy") and return read_only. Other options are ignored.
-fn parse_volume_read_only(opts: &str) -> bool {
-    opts.split(',').any(|o| o.trim().eq_ignore_ascii_case("ro"))
+/// Parse options string (e.g. "ro" or "rw,chown"). Unknown options are ignored.
+fn parse_volume_options(opts: &str) -> VolumeMountOptions {
+    let mut parsed = VolumeMountOptions::default();
+    for opt in opts.split(',').map(str::trim) {
+        if opt.eq_ignore_ascii_case("ro") {
+            parsed.read_only = true;
+        } else if opt.eq_ignore_ascii_case("chown") || opt == "U" {
+            parsed.chown = true;
+        } else if opt.eq_ignore_ascii_case("idmap") {
+            parsed.idmap = true;
+        }
+    }
+    parsed
 }
 
 /// Parse a single volume spec.
@@ -405,7 +878,7 @@ fn parse_volume_spec(s: &str) -> anyhow::Result<ParsedVolumeSpec> {
     }
     let parts: Vec<&str> = s.split(':').map(str::trim).collect();
 
-    let (host_path, guest_path, read_only) = match parts.len() {
+    let (host_path, guest_path, opts) = match parts.len() {
         1 => {
             // Anonymous volume: box path only (e.g. /data)
             let guest = parts[0].to_string();
@@ -418,7 +891,7 @@ fn parse_volume_spec(s: &str) -> anyhow::Result<ParsedVolumeSpec> {
                     guest
                 );
             }
-            (None, guest, false)
+            (None, guest, "")
         }
         2 => {
             // Either anonymous with options (guest:ro) or bind (host:guest)
@@ -428,25 +901,23 @@ fn parse_volume_spec(s: &str) -> anyhow::Result<ParsedVolumeSpec> {
                 if guest.is_empty() {
                     anyhow::bail!("volume box path must be non-empty");
                 }
-                (None, guest, second.eq_ignore_ascii_case("ro"))
+                (None, guest, second)
             } else {
-                (Some(parts[0].to_string()), parts[1].to_string(), false)
+                (Some(parts[0].to_string()), parts[1].to_string(), "")
             }
         }
         3 => {
             if is_windows_drive(parts[0]) {
                 let host = format!("{}:{}", parts[0], parts[1]);
-                (Some(host), parts[2].to_string(), false)
+                (Some(host), parts[2].to_string(), "")
             } else {
-                let ro = parse_volume_read_only(parts[2]);
-                (Some(parts[0].to_string()), parts[1].to_string(), ro)
+                (Some(parts[0].to_string()), parts[1].to_string(), parts[2])
             }
         }
         4.. => {
             if is_windows_drive(parts[0]) {
                 let host = format!("{}:{}", parts[0], parts[1]);
-                let ro = parse_volume_read_only(parts[3]);
-                (Some(host), parts[2].to_string(), ro)
+                (Some(host), parts[2].to_string(), parts[3])
             } else {
                 anyhow::bail!(
                     "invalid volume spec {:?}; use hostPath:boxPath[:options] (e.g. /data:/app/data or C:\\data:/app/data:ro)",
@@ -473,37 +944,80 @@ fn parse_volume_spec(s: &str) -> anyhow::Result<ParsedVolumeSpec> {
     Ok(ParsedVolumeSpec {
         host_path,
         guest_path,
-        read_only,
+        options: parse_volume_options(opts),
     })
 }
 
-/// Resolve base directory for anonymous volumes: explicit home, or BOXLITE_HOME, or ~/.boxlite, or temp dir.
-fn anonymous_volume_base(home: Option<&std::path::Path>) -> std::path::PathBuf {
-    home.map(std::path::PathBuf::from)
-        .or_else(|| {
-            std::env::var("BOXLITE_HOME")
-                .ok()
-                .map(std::path::PathBuf::from)
-        })
-        .or_else(|| {
-            dirs::home_dir().map(|mut p| {
-                p.push(".boxlite");
-                p
-            })
-        })
-        .unwrap_or_else(std::env::temp_dir)
+/// Parse a tmpfs spec: `boxPath[:size=N[k|m|g],mode=OCTAL]`.
+fn parse_tmpfs_spec(s: &str) -> anyhow::Result<TmpfsSpec> {
+    let (path, opts) = s.trim().split_once(':').unwrap_or((s.trim(), ""));
+    if !path.starts_with('/') {
+        anyhow::bail!("tmpfs path must be absolute (e.g. /tmp), got {:?}", path);
+    }
+    let mut spec = TmpfsSpec {
+        path: path.to_string(),
+        ..Default::default()
+    };
+    for opt in opts.split(',').map(str::trim).filter(|o| !o.is_empty()) {
+        match opt.split_once('=') {
+            Some(("size", size)) => spec.size_bytes = Some(parse_size_bytes(size)?),
+            Some(("mode", mode)) => {
+                spec.mode = Some(
+                    u32::from_str_radix(mode, 8)
+                        .map_err(|_| anyhow::anyhow!("invalid tmpfs mode {:?}", mode))?,
+                )
+            }
+            _ => anyhow::bail!(
+                "unknown tmpfs option {:?}; use size=N[k|m|g] or mode=OCTAL",
+                opt
+            ),
+        }
+    }
+    Ok(spec)
+}
+
+/// Parse a size like `65536`, `64k`, `64m`, `1g` or `1gb` (binary units) into bytes.
+fn parse_size_bytes(s: &str) -> anyhow::Result<u64> {
+    let lower = s.trim().to_ascii_lowercase();
+    let unit_end = lower
+        .strip_suffix("ib")
+        .or_else(|| lower.strip_suffix('b'))
+        .filter(|rest| rest.ends_with(['k', 'm', 'g']))
+        .unwrap_or(&lower);
+    let (digits, multiplier) = match unit_end.char_indices().last() {
+        Some((i, c)) if c.is_ascii_alphabetic() => {
+            let multiplier = match c {
+                'k' => 1 << 10,
+                'm' => 1 << 20,
+                'g' => 1 << 30,
+                _ => anyhow::bail!("invalid size {:?}; use a k, m or g suffix", s),
+            };
+            (&unit_end[..i], multiplier)
+        }
+        _ => (unit_end, 1),
+    };
+    digits
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(multiplier))
+        .ok_or_else(|| anyhow::anyhow!("invalid size {:?}", s))
+}
+
+/// clap parser for `--shm-size`: a human-readable size, rounded up to MiB.
+fn parse_shm_size(s: &str) -> Result<u32, String> {
+    let bytes = parse_size_bytes(s).map_err(|e| e.to_string())?;
+    if bytes == 0 {
+        return Err("shm size must be greater than zero".to_string());
+    }
+    u32::try_from(bytes.div_ceil(1 << 20)).map_err(|_| format!("shm size {:?} is too large", s))
 }
 
 impl VolumeFlags {
-    /// Apply volume flags to options. Pass `home` for anonymous volume storage (e.g. from GlobalFlags).
-    pub fn apply_to(
-        &self,
-        opts: &mut BoxOptions,
-        home: Option<&std::path::Path>,
-    ) -> anyhow::Result<()> {
-        let base = anonymous_volume_base(home);
+    /// Apply volume flags to options. Anonymous volumes are created under
+    /// `volumes_dir` (see [`GlobalFlags::volumes_dir`]).
+    pub fn apply_to(&self, opts: &mut BoxOptions, volumes_dir: &Path) -> anyhow::Result<()> {
         for s in self.volume.iter() {
-            let spec = parse_volume_spec(s)?;
+            let spec = parse_volume_spec(&crate::host_vars::expand(s)?)?;
             let host_path = match spec.host_path {
                 Some(host) => {
                     let mut path = host;
@@ -519,19 +1033,30 @@ impl VolumeFlags {
                     // Anonymous volume: use a random ID for the directory name (same approach as
                     // Podman: cryptographically random ID to avoid collisions under any load).
                     let unique = ulid::Ulid::new().to_string();
-                    let dir = base.join("volumes").join("anonymous").join(unique);
+                    let dir = volumes_dir
+                        .join(boxlite::runtime::layout::dirs::ANONYMOUS_VOLUMES_DIR)
+                        .join(unique);
                     std::fs::create_dir_all(&dir).map_err(|e| {
                         anyhow::anyhow!("failed to create anonymous volume dir {:?}: {}", dir, e)
                     })?;
                     dir.to_string_lossy().into_owned()
                 }
             };
+            let id_shift = spec.options.idmap.then(|| VolumeIdShift {
+                uid: nix::unistd::getuid().as_raw(),
+                gid: nix::unistd::getgid().as_raw(),
+            });
             opts.volumes.push(VolumeSpec {
                 host_path,
                 guest_path: spec.guest_path,
-                read_only: spec.read_only,
+                read_only: spec.options.read_only,
+                chown: spec.options.chown,
+                id_shift,
             });
         }
+        for s in &self.tmpfs {
+            opts.tmpfs.push(parse_tmpfs_spec(s)?);
+        }
         Ok(())
     }
 }
@@ -553,15 +1078,431 @@ pub struct ManagementFlags {
     /// Automatically remove the box when it exits
     #[arg(long)]
     pub rm: bool,
+
+    /// Validate options and print the execution plan as JSON, without creating the box
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Seconds to wait for the guest to become ready after the VM starts
+    #[arg(long, value_name = "SECS")]
+    pub timeout: Option<u64>,
+
+    /// Run a setup command with /bin/sh on first start, before the main command (can be repeated)
+    #[arg(long = "init-cmd", value_name = "CMD")]
+    pub init_cmd: Vec<String>,
+
+    /// Prepare the box for a workload with a built-in profile (browser); explicit flags win
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Run another image as a sidecar container in the same VM (can be repeated)
+    #[arg(long = "sidecar", value_name = "NAME=IMAGE", value_parser = parse_sidecar_spec)]
+    pub sidecar: Vec<SidecarSpec>,
+
+    /// Timezone of the box: host, or a name like Europe/Berlin (sets TZ and /etc/localtime)
+    #[arg(long = "tz", value_name = "host|AREA/CITY")]
+    pub tz: Option<String>,
+
+    /// Locale of the box, e.g. en_US.UTF-8 (sets LANG)
+    #[arg(long, value_name = "LOCALE")]
+    pub locale: Option<String>,
 }
 
 impl ManagementFlags {
     pub fn apply_to(&self, opts: &mut BoxOptions) {
         opts.detach = self.detach;
+        opts.timezone = self.tz.clone();
+        opts.locale = self.locale.clone();
         opts.auto_remove = self.rm;
+        opts.init_commands = self.init_cmd.clone();
+        opts.sidecars = self.sidecar.clone();
+        if let Some(secs) = self.timeout {
+            opts.timeouts.guest_ready_secs = secs;
+        }
+    }
+
+    /// Apply `--profile`. Call after every other flag, so explicit settings win.
+    pub fn apply_profile(&self, opts: &mut BoxOptions) -> anyhow::Result<()> {
+        if let Some(name) = &self.profile {
+            boxlite::builtin_profile(name)?.apply(opts);
+        }
+        Ok(())
+    }
+}
+
+/// clap parser for `--sidecar`: `NAME=IMAGE`.
+fn parse_sidecar_spec(s: &str) -> Result<SidecarSpec, String> {
+    match s.split_once('=') {
+        Some((name, image)) if !name.is_empty() && !image.is_empty() => {
+            Ok(SidecarSpec::new(name, image))
+        }
+        _ => Err(format!("invalid sidecar '{}': expected NAME=IMAGE", s)),
+    }
+}
+
+// ============================================================================
+// BATCH FLAGS
+// ============================================================================
+
+/// Box selection and concurrency for commands acting on many boxes
+/// (start, stop, restart, rm).
+#[derive(Args, Debug, Clone)]
+pub struct BatchFlags {
+    /// Only act on boxes matching KEY=VALUE; supported key: status
+    /// (e.g. status=stopped). Can be repeated; values of one key are OR'd
+    #[arg(long = "filter", value_name = "KEY=VALUE", value_parser = parse_box_filter)]
+    pub filter: Vec<BoxStatus>,
+
+    /// Maximum number of boxes handled at once
+    #[arg(long, value_name = "N", default_value_t = 4)]
+    pub parallel: usize,
+}
+
+impl BatchFlags {
+    /// Boxes to act on: `targets` as given, or else every box (with `all`)
+    /// or the boxes matching `--filter`, by name where they have one.
+    pub async fn select(
+        &self,
+        runtime: &BoxliteRuntime,
+        targets: Vec<String>,
+        all: bool,
+    ) -> anyhow::Result<Vec<String>> {
+        if !targets.is_empty() {
+            return Ok(targets);
+        }
+        if !all && self.filter.is_empty() {
+            anyhow::bail!("no boxes given: name them, or use --all or --filter");
+        }
+        Ok(runtime
+            .list_info()
+            .await?
+            .into_iter()
+            .filter(|info| self.filter.is_empty() || self.filter.contains(&info.status))
+            .map(|info| info.name.unwrap_or_else(|| info.id.to_string()))
+            .collect())
+    }
+
+    /// Run `op` on every target, at most `--parallel` at a time.
+    ///
+    /// Prints each target that succeeded and each error as it finishes; a
+    /// failure does not stop the others. Fails if any target failed.
+    pub async fn run<F, Fut>(&self, targets: Vec<String>, verb: &str, op: F) -> anyhow::Result<()>
+    where
+        F: Fn(String) -> Fut,
+        Fut: std::future::Future<Output = anyhow::Result<()>>,
+    {
+        use futures::StreamExt;
+
+        let total = targets.len();
+        let mut results = futures::stream::iter(targets)
+            .map(|target| {
+                let result = op(target.clone());
+                async move { (target, result.await) }
+            })
+            .buffer_unordered(self.parallel.max(1));
+
+        let mut errors = Vec::new();
+        while let Some((target, result)) = results.next().await {
+            match result {
+                Ok(()) => println!("{}", target),
+                Err(e) => {
+                    eprintln!("Error: failed to {} box '{}': {:#}", verb, target, e);
+                    errors.push(format!("{}: {:#}", target, e));
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            let error_summary = if errors.len() < total {
+                format!("Failed to {} {} of {} box(es)", verb, errors.len(), total)
+            } else {
+                format!("Failed to {} all {} box(es)", verb, errors.len())
+            };
+            anyhow::bail!("{}\nErrors:\n  {}", error_summary, errors.join("\n  "));
+        }
+        Ok(())
+    }
+}
+
+/// clap parser for `--filter`: `status=STATUS`.
+fn parse_box_filter(s: &str) -> Result<BoxStatus, String> {
+    match s.split_once('=') {
+        Some(("status", value)) => value.parse().map_err(|_| {
+            format!(
+                "invalid status '{}': expected configured, running, paused, stopping, stopped or unknown",
+                value
+            )
+        }),
+        Some((key, _)) => Err(format!("unsupported filter '{}': expected status", key)),
+        None => Err(format!("invalid filter '{}': expected KEY=VALUE", s)),
+    }
+}
+
+// ============================================================================
+// KERNEL FLAGS
+// ============================================================================
+
+#[derive(Args, Debug, Clone, Default)]
+pub struct KernelFlags {
+    /// Boot a custom guest kernel image instead of the bundled one
+    #[arg(long, value_name = "PATH")]
+    pub kernel: Option<std::path::PathBuf>,
+
+    /// Initramfs to load with --kernel
+    #[arg(long, value_name = "PATH", requires = "kernel")]
+    pub initrd: Option<std::path::PathBuf>,
+
+    /// Extra kernel command line arguments for --kernel
+    #[arg(long, value_name = "ARGS", requires = "kernel")]
+    pub kernel_cmdline: Option<String>,
+}
+
+impl KernelFlags {
+    pub fn apply_to(&self, opts: &mut BoxOptions) -> anyhow::Result<()> {
+        let Some(kernel) = &self.kernel else {
+            return Ok(());
+        };
+        opts.kernel = Some(KernelSpec {
+            path: std::path::absolute(kernel)?,
+            initrd: self
+                .initrd
+                .as_deref()
+                .map(std::path::absolute)
+                .transpose()?,
+            cmdline: self.kernel_cmdline.clone(),
+            ..Default::default()
+        });
+        Ok(())
+    }
+}
+
+// ============================================================================
+// DEBUG FLAGS
+// ============================================================================
+
+#[derive(Args, Debug, Clone, Default)]
+pub struct DebugFlags {
+    /// Collect core dumps of crashing processes (see `boxlite debug cores`)
+    #[arg(long)]
+    pub core_dumps: bool,
+
+    /// Truncate each core dump at this size (e.g. 256m, 2g; default 512m)
+    #[arg(long, value_name = "SIZE", requires = "core_dumps", value_parser = parse_core_dump_size)]
+    pub core_dump_size: Option<u64>,
+}
+
+impl DebugFlags {
+    pub fn apply_to(&self, opts: &mut BoxOptions) {
+        if !self.core_dumps {
+            return;
+        }
+        let mut core_dumps = CoreDumpOptions::default();
+        if let Some(max_bytes) = self.core_dump_size {
+            core_dumps.max_bytes = max_bytes;
+        }
+        opts.core_dumps = Some(core_dumps);
+    }
+}
+
+/// clap parser for `--core-dump-size`: a non-zero human-readable size.
+fn parse_core_dump_size(s: &str) -> Result<u64, String> {
+    match parse_size_bytes(s).map_err(|e| e.to_string())? {
+        0 => Err("core dump size must be greater than zero".to_string()),
+        bytes => Ok(bytes),
+    }
+}
+
+// ============================================================================
+// SECURITY FLAGS
+// ============================================================================
+
+#[derive(Args, Debug, Clone, Default)]
+pub struct SecurityFlags {
+    /// Allow FUSE mounts in the box (exposes /dev/fuse and grants CAP_SYS_ADMIN)
+    #[arg(long)]
+    pub allow_fuse: bool,
+
+    /// Add a Linux capability, e.g. NET_ADMIN or ALL (can be repeated)
+    #[arg(long = "cap-add", value_name = "CAP")]
+    pub cap_add: Vec<String>,
+
+    /// Drop a Linux capability, e.g. MKNOD or ALL (can be repeated)
+    #[arg(long = "cap-drop", value_name = "CAP")]
+    pub cap_drop: Vec<String>,
+}
+
+impl SecurityFlags {
+    pub fn apply_to(&self, opts: &mut BoxOptions) -> anyhow::Result<()> {
+        if self.allow_fuse {
+            opts.security.allow_fuse = true;
+        }
+        opts.security
+            .capabilities
+            .add
+            .extend(self.cap_add.iter().cloned());
+        opts.security
+            .capabilities
+            .drop
+            .extend(self.cap_drop.iter().cloned());
+        opts.security.capabilities.resolve()?;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// NETWORK FLAGS
+// ============================================================================
+
+/// Egress network mode.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[value(rename_all = "lower")]
+pub enum NetworkMode {
+    /// Allow all outbound traffic (except --network-deny rules)
+    #[default]
+    Open,
+    /// Allow only --network-allow destinations
+    Restricted,
+    /// Block all outbound traffic
+    None,
+    /// No network device at all; boots faster, but no ports or SSH
+    Disabled,
+}
+
+#[derive(Args, Debug, Clone, Default)]
+pub struct NetworkFlags {
+    /// Egress network mode
+    #[arg(long, value_enum, default_value_t = NetworkMode::Open, value_name = "MODE")]
+    pub network: NetworkMode,
+
+    /// Allow outbound traffic to an IP, CIDR or hostname (can be repeated)
+    #[arg(long = "network-allow", value_name = "RULE")]
+    pub network_allow: Vec<String>,
+
+    /// Deny outbound traffic to an IP, CIDR or hostname (can be repeated)
+    #[arg(long = "network-deny", value_name = "RULE")]
+    pub network_deny: Vec<String>,
+
+    /// Container hostname (default: the box name, or its ID)
+    #[arg(long, value_name = "NAME")]
+    pub hostname: Option<String>,
+
+    /// MAC address of the box's network interface, e.g. 02:42:ac:11:00:02
+    #[arg(long = "mac-address", value_name = "MAC")]
+    pub mac_address: Option<String>,
+
+    /// Static IPv4 address of the box inside 192.168.127.0/24, e.g. 192.168.127.10
+    #[arg(long = "ip", value_name = "IP")]
+    pub ip_address: Option<String>,
+
+    /// Block the box from the host's loopback and drop host.boxlite.internal
+    #[arg(long = "no-host-loopback")]
+    pub no_host_loopback: bool,
+
+    /// Log every connection the box opens (see `boxlite net log`)
+    #[arg(long)]
+    pub log_connections: bool,
+}
+
+impl NetworkFlags {
+    pub fn apply_to(&self, opts: &mut BoxOptions) -> anyhow::Result<()> {
+        opts.hostname = self.hostname.clone();
+        opts.mac_address = self.mac_address.clone();
+        opts.ip_address = self.ip_address.clone();
+        opts.host_loopback = !self.no_host_loopback;
+        opts.log_connections = self.log_connections;
+        if !self.network_allow.is_empty() && self.network != NetworkMode::Restricted {
+            anyhow::bail!("--network-allow requires --network restricted");
+        }
+        if self.network == NetworkMode::Disabled {
+            if !self.network_deny.is_empty() {
+                anyhow::bail!("--network-deny cannot be used with --network disabled");
+            }
+            opts.network = NetworkSpec::Disabled;
+            return Ok(());
+        }
+        let mut policy = match self.network {
+            NetworkMode::Open if self.network_deny.is_empty() => return Ok(()),
+            NetworkMode::Open => NetworkPolicy::default(),
+            NetworkMode::Restricted => NetworkPolicy::restricted(self.network_allow.clone()),
+            NetworkMode::None => NetworkPolicy::none(),
+            NetworkMode::Disabled => unreachable!("handled above"),
+        };
+        policy.deny = self.network_deny.clone();
+        policy.validate()?;
+        opts.network_policy = Some(policy);
+        Ok(())
     }
 }
 
+#[derive(Args, Debug, Clone, Default)]
+pub struct SshFlags {
+    /// Run sshd in the box and forward it to a host port (see `boxlite ssh`)
+    #[arg(long)]
+    pub ssh: bool,
+
+    /// Authorized public key, or a file of keys (can be repeated; implies --ssh)
+    #[arg(long = "ssh-key", value_name = "KEY_OR_FILE")]
+    pub ssh_key: Vec<String>,
+}
+
+impl SshFlags {
+    pub fn apply_to(&self, opts: &mut BoxOptions) -> anyhow::Result<()> {
+        if !self.ssh && self.ssh_key.is_empty() {
+            return Ok(());
+        }
+        let mut keys = Vec::new();
+        for key in &self.ssh_key {
+            keys.extend(read_ssh_keys(key)?);
+        }
+        if keys.is_empty() {
+            keys = default_ssh_keys();
+        }
+        if keys.is_empty() {
+            anyhow::bail!("--ssh: no public keys in ~/.ssh/*.pub, pass --ssh-key");
+        }
+        opts.ssh = Some(SshOptions::new(keys));
+        Ok(())
+    }
+}
+
+/// A literal public key (`ssh-ed25519 AAAA...`) or a path to an authorized_keys file.
+fn read_ssh_keys(value: &str) -> anyhow::Result<Vec<String>> {
+    let path = Path::new(value);
+    if !path.is_file() {
+        if value.split_whitespace().count() < 2 {
+            anyhow::bail!("--ssh-key {}: not a public key or a file", value);
+        }
+        return Ok(vec![value.trim().to_string()]);
+    }
+    let content =
+        std::fs::read_to_string(path).map_err(|e| anyhow::anyhow!("--ssh-key {}: {}", value, e))?;
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect())
+}
+
+fn default_ssh_keys() -> Vec<String> {
+    let Some(dir) = dirs::home_dir().map(|home| home.join(".ssh")) else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "pub"))
+        .collect();
+    files.sort();
+    files
+        .iter()
+        .filter_map(|path| read_ssh_keys(&path.to_string_lossy()).ok())
+        .flatten()
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -581,7 +1522,8 @@ mod tests {
             } else {
                 None
             }
-        });
+        })
+        .unwrap();
 
         assert!(
             opts.env
@@ -596,17 +1538,137 @@ mod tests {
         assert!(!opts.env.iter().any(|(k, _)| k == "NON_EXISTENT_VAR"));
     }
 
+    #[test]
+    fn test_parse_box_filter() {
+        assert_eq!(parse_box_filter("status=paused"), Ok(BoxStatus::Paused));
+        assert!(parse_box_filter("status=asleep").is_err());
+        assert!(parse_box_filter("name=web").is_err());
+        assert!(parse_box_filter("running").is_err());
+    }
+
     #[test]
     fn test_resource_flags_cpu_cap() {
         let flags = ResourceFlags {
             cpus: Some(1000),
             memory: None,
+            network_limit: Some(100),
+            shm_size: None,
+            priority: None,
         };
 
         let mut opts = BoxOptions::default();
         flags.apply_to(&mut opts);
 
         assert_eq!(opts.cpus, Some(255));
+        assert_eq!(opts.network_limit_mbps, Some(100));
+    }
+
+    #[test]
+    fn test_security_flags_capabilities() {
+        let flags = SecurityFlags {
+            allow_fuse: false,
+            cap_add: vec!["NET_ADMIN".to_string()],
+            cap_drop: vec!["mknod".to_string()],
+        };
+        let mut opts = BoxOptions::default();
+        flags.apply_to(&mut opts).unwrap();
+        let caps = opts.security.container_capabilities().unwrap();
+        assert!(caps.contains(&"CAP_NET_ADMIN".to_string()));
+        assert!(!caps.contains(&"CAP_MKNOD".to_string()));
+
+        let flags = SecurityFlags {
+            cap_add: vec!["TELEPORT".to_string()],
+            ..Default::default()
+        };
+        assert!(flags.apply_to(&mut BoxOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_kernel_flags_apply_to() {
+        let mut opts = BoxOptions::default();
+        KernelFlags::default().apply_to(&mut opts).unwrap();
+        assert!(opts.kernel.is_none());
+
+        let flags = KernelFlags {
+            kernel: Some("vmlinux".into()),
+            initrd: None,
+            kernel_cmdline: Some("loglevel=7".to_string()),
+        };
+        flags.apply_to(&mut opts).unwrap();
+        let kernel = opts.kernel.unwrap();
+        assert!(kernel.path.is_absolute());
+        assert!(kernel.path.ends_with("vmlinux"));
+        assert_eq!(kernel.cmdline.as_deref(), Some("loglevel=7"));
+    }
+
+    #[test]
+    fn test_ssh_flags_apply_to() {
+        let mut opts = BoxOptions::default();
+        SshFlags::default().apply_to(&mut opts).unwrap();
+        assert!(opts.ssh.is_none());
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("keys");
+        std::fs::write(
+            &file,
+            "# team\nssh-ed25519 AAAA alice\n\nssh-rsa BBBB bob\n",
+        )
+        .unwrap();
+        let flags = SshFlags {
+            ssh: false,
+            ssh_key: vec![
+                "ssh-ed25519 CCCC carol".to_string(),
+                file.to_string_lossy().into_owned(),
+            ],
+        };
+        flags.apply_to(&mut opts).unwrap();
+        let ssh = opts.ssh.unwrap();
+        assert_eq!(
+            ssh.authorized_keys,
+            vec![
+                "ssh-ed25519 CCCC carol",
+                "ssh-ed25519 AAAA alice",
+                "ssh-rsa BBBB bob"
+            ]
+        );
+        assert_eq!(ssh.user, "root");
+
+        let flags = SshFlags {
+            ssh: true,
+            ssh_key: vec!["/nonexistent/id.pub".to_string()],
+        };
+        assert!(flags.apply_to(&mut BoxOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_network_flags_apply_to() {
+        let mut opts = BoxOptions::default();
+        NetworkFlags::default().apply_to(&mut opts).unwrap();
+        assert!(opts.network_policy.is_none());
+
+        let flags = NetworkFlags {
+            network: NetworkMode::Restricted,
+            network_allow: vec!["pypi.org".to_string(), "10.0.0.0/8".to_string()],
+            network_deny: vec!["10.0.0.1".to_string()],
+            hostname: Some("web".to_string()),
+            ip_address: Some("192.168.127.10".to_string()),
+            ..Default::default()
+        };
+        flags.apply_to(&mut opts).unwrap();
+        assert_eq!(opts.hostname.as_deref(), Some("web"));
+        assert_eq!(opts.ip_address.as_deref(), Some("192.168.127.10"));
+        assert!(opts.host_loopback);
+        let policy = opts.network_policy.unwrap();
+        assert!(!policy.default_allow);
+        assert_eq!(policy.allow.len(), 2);
+        assert_eq!(policy.deny, vec!["10.0.0.1".to_string()]);
+
+        let flags = NetworkFlags {
+            network: NetworkMode::None,
+            network_allow: vec!["pypi.org".to_string()],
+            ..Default::default()
+        };
+        assert!(flags.apply_to(&mut BoxOptions::default()).is_err());
     }
 
     #[test]
@@ -640,6 +1702,25 @@ mod tests {
         assert!(matches!(spec.protocol, PortProtocol::Udp));
     }
 
+    #[test]
+    fn test_parse_publish_spec_host_ip() {
+        let spec = super::parse_publish_spec("127.0.0.1:8080:80").unwrap();
+        assert_eq!(spec.host_ip.as_deref(), Some("127.0.0.1"));
+        assert_eq!(spec.host_port, Some(8080));
+        assert_eq!(spec.guest_port, 80);
+
+        let spec = super::parse_publish_spec("[::1]:8080:80/tcp").unwrap();
+        assert_eq!(spec.host_ip.as_deref(), Some("::1"));
+        assert_eq!(spec.host_port, Some(8080));
+
+        let spec = super::parse_publish_spec("127.0.0.1::80").unwrap();
+        assert_eq!(spec.host_ip.as_deref(), Some("127.0.0.1"));
+        assert_eq!(spec.host_port, None);
+
+        assert!(super::parse_publish_spec("localhost:8080:80").is_err());
+        assert!(super::parse_publish_spec("[::1]:80").is_err());
+    }
+
     #[test]
     fn test_parse_publish_spec_invalid_protocol() {
         assert!(super::parse_publish_spec("80:80/sctp").is_err());
@@ -655,9 +1736,13 @@ mod tests {
     fn test_publish_flags_apply_to() {
         let flags = PublishFlags {
             publish: vec!["18789:18789".to_string(), "8080:80/tcp".to_string()],
+            publish_all: true,
+            publish_retry: true,
         };
         let mut opts = BoxOptions::default();
         flags.apply_to(&mut opts).unwrap();
+        assert!(opts.publish_all);
+        assert!(opts.publish_retry);
         assert_eq!(opts.ports.len(), 2);
         assert_eq!(opts.ports[0].host_port, Some(18789));
         assert_eq!(opts.ports[0].guest_port, 18789);
@@ -670,7 +1755,7 @@ mod tests {
         let spec = super::parse_volume_spec("/data:/app/data").unwrap();
         assert_eq!(spec.host_path.as_deref(), Some("/data"));
         assert_eq!(spec.guest_path, "/app/data");
-        assert!(!spec.read_only);
+        assert!(!spec.options.read_only);
     }
 
     #[test]
@@ -678,7 +1763,7 @@ mod tests {
         let spec = super::parse_volume_spec("/data:/app/data:ro").unwrap();
         assert_eq!(spec.host_path.as_deref(), Some("/data"));
         assert_eq!(spec.guest_path, "/app/data");
-        assert!(spec.read_only);
+        assert!(spec.options.read_only);
     }
 
     #[test]
@@ -686,7 +1771,7 @@ mod tests {
         let spec = super::parse_volume_spec("/data:/app/data:rw").unwrap();
         assert_eq!(spec.host_path.as_deref(), Some("/data"));
         assert_eq!(spec.guest_path, "/app/data");
-        assert!(!spec.read_only);
+        assert!(!spec.options.read_only);
     }
 
     #[test]
@@ -694,7 +1779,7 @@ mod tests {
         let spec = super::parse_volume_spec("/data").unwrap();
         assert!(spec.host_path.is_none());
         assert_eq!(spec.guest_path, "/data");
-        assert!(!spec.read_only);
+        assert!(!spec.options.read_only);
     }
 
     #[test]
@@ -702,7 +1787,7 @@ mod tests {
         let spec = super::parse_volume_spec("/data:ro").unwrap();
         assert!(spec.host_path.is_none());
         assert_eq!(spec.guest_path, "/data");
-        assert!(spec.read_only);
+        assert!(spec.options.read_only);
     }
 
     #[test]
@@ -724,7 +1809,7 @@ mod tests {
         let spec = super::parse_volume_spec(r"C:\data:/app/data").unwrap();
         assert_eq!(spec.host_path.as_deref(), Some(r"C:\data"));
         assert_eq!(spec.guest_path, "/app/data");
-        assert!(!spec.read_only);
+        assert!(!spec.options.read_only);
     }
 
     #[test]
@@ -733,7 +1818,7 @@ mod tests {
         let spec = super::parse_volume_spec(r"C:\data:/app/data:ro").unwrap();
         assert_eq!(spec.host_path.as_deref(), Some(r"C:\data"));
         assert_eq!(spec.guest_path, "/app/data");
-        assert!(spec.read_only);
+        assert!(spec.options.read_only);
     }
 
     #[test]
@@ -741,7 +1826,7 @@ mod tests {
         let spec = super::parse_volume_spec(r"D:\path:/mnt:rw").unwrap();
         assert_eq!(spec.host_path.as_deref(), Some(r"D:\path"));
         assert_eq!(spec.guest_path, "/mnt");
-        assert!(!spec.read_only);
+        assert!(!spec.options.read_only);
     }
 
     #[test]
@@ -767,7 +1852,23 @@ mod tests {
         let spec2 = super::parse_volume_spec("/data:/app/data:ro").unwrap();
         assert_eq!(spec2.host_path.as_deref(), Some("/data"));
         assert_eq!(spec2.guest_path, "/app/data");
-        assert!(spec2.read_only);
+        assert!(spec2.options.read_only);
+    }
+
+    #[test]
+    fn test_parse_volume_spec_ownership_options() {
+        let spec = super::parse_volume_spec("/data:/app/data:ro,chown").unwrap();
+        assert_eq!(
+            spec.options,
+            super::VolumeMountOptions {
+                read_only: true,
+                chown: true,
+                idmap: false,
+            }
+        );
+        let spec = super::parse_volume_spec("/data:/app/data:U,idmap").unwrap();
+        assert!(spec.options.chown && spec.options.idmap);
+        assert!(!spec.options.read_only);
     }
 
     #[test]
@@ -777,9 +1878,10 @@ mod tests {
                 "/host/data:/guest/data".to_string(),
                 "/readonly:/ro:ro".to_string(),
             ],
+            tmpfs: vec![],
         };
         let mut opts = BoxOptions::default();
-        flags.apply_to(&mut opts, None).unwrap();
+        flags.apply_to(&mut opts, &std::env::temp_dir()).unwrap();
         assert_eq!(opts.volumes.len(), 2);
         assert_eq!(opts.volumes[0].host_path, "/host/data");
         assert_eq!(opts.volumes[0].guest_path, "/guest/data");
@@ -796,9 +1898,10 @@ mod tests {
                 r"C:\host\data:/guest/data".to_string(),
                 r"D:\readonly:/ro:ro".to_string(),
             ],
+            tmpfs: vec![],
         };
         let mut opts = BoxOptions::default();
-        flags.apply_to(&mut opts, None).unwrap();
+        flags.apply_to(&mut opts, &std::env::temp_dir()).unwrap();
         assert_eq!(opts.volumes.len(), 2);
         assert_eq!(opts.volumes[0].host_path, r"C:\host\data");
         assert_eq!(opts.volumes[0].guest_path, "/guest/data");
@@ -813,9 +1916,10 @@ mod tests {
         let base = std::env::temp_dir();
         let flags = VolumeFlags {
             volume: vec!["/data".to_string(), "/cache:ro".to_string()],
+            tmpfs: vec![],
         };
         let mut opts = BoxOptions::default();
-        flags.apply_to(&mut opts, Some(&base)).unwrap();
+        flags.apply_to(&mut opts, &base).unwrap();
         assert_eq!(opts.volumes.len(), 2);
         assert_eq!(opts.volumes[0].guest_path, "/data");
         assert!(
@@ -828,4 +1932,48 @@ mod tests {
         assert!(opts.volumes[1].read_only);
         assert!(opts.volumes[1].host_path.contains("anonymous"));
     }
+
+    #[test]
+    fn test_parse_sidecar_spec() {
+        let spec = super::parse_sidecar_spec("cache=redis:7").unwrap();
+        assert_eq!(spec.name, "cache");
+        assert_eq!(spec.image, "redis:7");
+
+        assert!(super::parse_sidecar_spec("redis:7").is_err());
+        assert!(super::parse_sidecar_spec("=redis:7").is_err());
+        assert!(super::parse_sidecar_spec("cache=").is_err());
+    }
+
+    #[test]
+    fn test_parse_tmpfs_spec() {
+        let spec = super::parse_tmpfs_spec("/tmp").unwrap();
+        assert_eq!(spec.path, "/tmp");
+        assert_eq!(spec.size_bytes, None);
+        assert_eq!(spec.mode, None);
+
+        let spec = super::parse_tmpfs_spec("/scratch:size=64m,mode=1777").unwrap();
+        assert_eq!(spec.size_bytes, Some(64 << 20));
+        assert_eq!(spec.mode, Some(0o1777));
+
+        assert_eq!(
+            super::parse_tmpfs_spec("/s:size=4096").unwrap().size_bytes,
+            Some(4096)
+        );
+        assert!(super::parse_tmpfs_spec("tmp").is_err());
+        assert!(super::parse_tmpfs_spec("/tmp:size=10x").is_err());
+        assert!(super::parse_tmpfs_spec("/tmp:mode=999").is_err());
+        assert!(super::parse_tmpfs_spec("/tmp:exec").is_err());
+    }
+
+    #[test]
+    fn test_parse_shm_size() {
+        assert_eq!(super::parse_shm_size("512m"), Ok(512));
+        assert_eq!(super::parse_shm_size("2g"), Ok(2048));
+        assert_eq!(super::parse_shm_size("1GiB"), Ok(1024));
+        assert_eq!(super::parse_shm_size("64MB"), Ok(64));
+        // Sizes round up to whole MiB
+        assert_eq!(super::parse_shm_size("1000k"), Ok(1));
+        assert!(super::parse_shm_size("0").is_err());
+        assert!(super::parse_shm_size("lots").is_err());
+    }
 }
@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use boxlite::BuildOptions;
+use clap::Args;
+use tokio::sync::mpsc;
+
+use crate::cli::GlobalFlags;
+
+/// Build an image from a Dockerfile
+///
+/// Supports FROM, RUN, COPY, ENV, WORKDIR and CMD.
+#[derive(Args, Debug)]
+pub struct BuildArgs {
+    /// Build context directory
+    pub context: PathBuf,
+
+    /// Path to the Dockerfile (default: <context>/Dockerfile)
+    #[arg(short = 'f', long = "file")]
+    pub file: Option<PathBuf>,
+
+    /// Name and optionally a tag for the image (e.g. myapp:v1)
+    #[arg(short = 't', long = "tag")]
+    pub tag: String,
+
+    /// Do not use cached steps
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Quiet mode - only show the image ID
+    #[arg(short, long)]
+    pub quiet: bool,
+}
+
+pub async fn execute(args: BuildArgs, global: &GlobalFlags) -> Result<()> {
+    let runtime = global.create_runtime()?;
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let printer = tokio::spawn(async move {
+        while let Some(line) = rx.recv().await {
+            println!("{}", line);
+        }
+    });
+
+    let options = BuildOptions {
+        context: args.context,
+        dockerfile: args.file,
+        tag: args.tag,
+        no_cache: args.no_cache,
+        progress: (!args.quiet).then_some(tx),
+    };
+    let result = runtime.build(options).await;
+    let _ = printer.await;
+
+    let output = result?;
+    if args.quiet {
+        println!("{}", output.image_id);
+    } else {
+        println!(
+            "Built {} ({} steps, {} cached)",
+            output.reference, output.steps, output.cached_steps
+        );
+    }
+    Ok(())
+}
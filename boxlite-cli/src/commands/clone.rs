@@ -0,0 +1,20 @@
+use clap::Args;
+
+/// Clone a stopped box
+#[derive(Args, Debug)]
+pub struct CloneArgs {
+    /// Name or ID of the stopped box to clone
+    pub source: String,
+
+    /// Assign a name to the new box
+    #[arg(long)]
+    pub name: Option<String>,
+}
+
+pub async fn execute(args: CloneArgs, global: &crate::cli::GlobalFlags) -> anyhow::Result<()> {
+    let runtime = global.create_runtime()?;
+
+    let litebox = runtime.clone_box(&args.source, args.name).await?;
+    println!("{}", litebox.id());
+    Ok(())
+}
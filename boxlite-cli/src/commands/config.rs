@@ -0,0 +1,99 @@
+//! Check BoxLite configuration files.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+use clap::{Args, Subcommand};
+
+use crate::cli::GlobalFlags;
+use crate::config::ConfigFormat;
+
+/// Check BoxLite configuration files
+#[derive(Args, Debug)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub command: ConfigCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Check a config file against the options schema
+    ///
+    /// Reports syntax errors, unknown keys (with the closest known key),
+    /// wrong value types and malformed profiles, with line and column where
+    /// they can be located. Every profile is checked.
+    Validate(ValidateArgs),
+
+    /// Write a commented starter config file
+    Init(InitArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct ValidateArgs {
+    /// Config file to check (defaults to --config)
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct InitArgs {
+    /// File to write (prints to stdout if omitted)
+    pub path: Option<PathBuf>,
+
+    /// File format (defaults to PATH's extension, otherwise toml)
+    #[arg(long, value_enum)]
+    pub format: Option<ConfigFormat>,
+
+    /// Overwrite PATH if it exists
+    #[arg(short, long)]
+    pub force: bool,
+}
+
+pub async fn execute(args: ConfigArgs, global: &GlobalFlags) -> Result<()> {
+    match args.command {
+        ConfigCommand::Validate(validate) => {
+            let path = match (validate.path, &global.config) {
+                (Some(path), _) => path,
+                (None, Some(config)) => PathBuf::from(config),
+                (None, None) => bail!("no config file given: pass PATH or --config"),
+            };
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read config file {}", path.display()))?;
+
+            let diagnostics = crate::config::validate(&content, ConfigFormat::from_path(&path));
+            if diagnostics.is_empty() {
+                println!("{}: valid", path.display());
+                return Ok(());
+            }
+            for diagnostic in &diagnostics {
+                eprintln!("{}: {}", path.display(), diagnostic);
+            }
+            bail!(
+                "{} problem(s) found in {}",
+                diagnostics.len(),
+                path.display()
+            )
+        }
+        ConfigCommand::Init(init) => {
+            let format = match (init.format, &init.path) {
+                (Some(format), _) => format,
+                (None, Some(path)) if path.extension().is_some() => ConfigFormat::from_path(path),
+                (None, _) => ConfigFormat::Toml,
+            };
+            let content = crate::config::starter(format);
+            let Some(path) = init.path else {
+                print!("{}", content);
+                return Ok(());
+            };
+            if path.exists() && !init.force {
+                bail!(
+                    "{} already exists (use --force to overwrite)",
+                    path.display()
+                );
+            }
+            std::fs::write(&path, content)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            println!("{}", path.display());
+            Ok(())
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use crate::cli::{GlobalFlags, PublishFlags, ResourceFlags, VolumeFlags};
+use crate::cli::{
+    DebugFlags, GlobalFlags, KernelFlags, NetworkFlags, PublishFlags, ResourceFlags, SecurityFlags,
+    SshFlags, VolumeFlags,
+};
 use boxlite::{BoxOptions, RootfsSpec};
 use clap::Args;
 
@@ -9,10 +12,25 @@ pub struct CreateArgs {
     #[arg(index = 1)]
     pub image: String,
 
+    /// Command (CMD) for the box's main process, replacing the image's
+    #[arg(index = 2, trailing_var_arg = true)]
+    pub command: Vec<String>,
+
+    /// Overwrite the image's ENTRYPOINT; this also drops the image's CMD,
+    /// and "" clears the ENTRYPOINT
+    #[arg(long, value_name = "EXECUTABLE")]
+    pub entrypoint: Option<String>,
+
+    /// Start the box after creating it (by default create only records it;
+    /// no VM is spawned until `boxlite start` or `boxlite exec`)
+    #[arg(long)]
+    pub start: bool,
+
     #[command(flatten)]
     pub management: crate::cli::ManagementFlags,
 
-    /// Set environment variables
+    /// Set environment variables; {{uid}}, {{gid}}, {{user}}, {{home}} and
+    /// {{cwd}} expand to host values (\{{ keeps literal braces)
     #[arg(short = 'e', long = "env")]
     pub env: Vec<String>,
 
@@ -28,13 +46,37 @@ pub struct CreateArgs {
 
     #[command(flatten)]
     pub volume: VolumeFlags,
+
+    #[command(flatten)]
+    pub network: NetworkFlags,
+
+    #[command(flatten)]
+    pub ssh: SshFlags,
+
+    #[command(flatten)]
+    pub security: SecurityFlags,
+
+    #[command(flatten)]
+    pub kernel: KernelFlags,
+
+    #[command(flatten)]
+    pub debug: DebugFlags,
 }
 
 pub async fn execute(args: CreateArgs, global: &GlobalFlags) -> anyhow::Result<()> {
     let rt = global.create_runtime()?;
     let box_options = args.to_box_options(global)?;
 
+    if args.management.dry_run {
+        let plan = rt.plan(box_options, args.management.name.clone()).await?;
+        println!("{}", serde_json::to_string_pretty(&plan)?);
+        return Ok(());
+    }
+
     let litebox = rt.create(box_options, args.management.name.clone()).await?;
+    if args.start {
+        litebox.start().await?;
+    }
     println!("{}", litebox.id());
 
     Ok(())
@@ -46,9 +88,25 @@ impl CreateArgs {
         self.resource.apply_to(&mut options);
         self.management.apply_to(&mut options);
         self.publish.apply_to(&mut options)?;
-        self.volume.apply_to(&mut options, global.home.as_deref())?;
+        self.network.apply_to(&mut options)?;
+        self.ssh.apply_to(&mut options)?;
+        self.security.apply_to(&mut options)?;
+        self.kernel.apply_to(&mut options)?;
+        self.debug.apply_to(&mut options);
+        self.volume.apply_to(&mut options, &global.volumes_dir()?)?;
         options.working_dir = self.workdir.clone();
-        crate::cli::apply_env_vars(&self.env, &mut options);
+        options.entrypoint = self.entrypoint.as_ref().map(|entrypoint| {
+            if entrypoint.is_empty() {
+                Vec::new()
+            } else {
+                vec![entrypoint.clone()]
+            }
+        });
+        if !self.command.is_empty() {
+            options.cmd = Some(self.command.clone());
+        }
+        crate::cli::apply_env_vars(&self.env, &mut options)?;
+        self.management.apply_profile(&mut options)?;
         options.rootfs = RootfsSpec::Image(self.image.clone());
         Ok(options)
     }
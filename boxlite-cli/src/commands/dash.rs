@@ -0,0 +1,380 @@
+//! `boxlite dash`: interactive terminal dashboard.
+//!
+//! Polls the runtime for box status and metrics on an interval and tails the
+//! selected box's console log. Keys act on the selected box.
+
+use crate::cli::GlobalFlags;
+use crate::terminal::StreamManager;
+use boxlite::runtime::layout::{FilesystemLayout, FsLayoutConfig};
+use boxlite::{BoxCommand, BoxInfo, BoxStatus, BoxliteRuntime};
+use clap::Args;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// How long to wait for a key before checking whether a refresh is due.
+const INPUT_POLL: Duration = Duration::from_millis(200);
+
+/// Bytes read from the end of the console log for the logs pane.
+const LOG_TAIL_BYTES: u64 = 64 * 1024;
+
+#[derive(Args, Debug)]
+pub struct DashArgs {
+    /// Refresh interval in seconds
+    #[arg(long, default_value_t = 2, value_name = "SECONDS")]
+    pub interval: u64,
+}
+
+pub async fn execute(args: DashArgs, global: &GlobalFlags) -> anyhow::Result<()> {
+    let options = global.runtime_options()?;
+    let layout = FilesystemLayout::from_options(&options, FsLayoutConfig::default());
+    let rt = BoxliteRuntime::new(options)?;
+    let mut dash = Dashboard::new(rt, layout, Duration::from_secs(args.interval.max(1)));
+
+    let mut terminal = ratatui::init();
+    let result = dash.run(&mut terminal).await;
+    ratatui::restore();
+    result
+}
+
+/// Dashboard action bound to a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Quit,
+    Up,
+    Down,
+    Refresh,
+    Start,
+    Stop,
+    Remove,
+    Shell,
+}
+
+fn key_action(code: KeyCode) -> Option<Action> {
+    match code {
+        KeyCode::Char('q') | KeyCode::Esc => Some(Action::Quit),
+        KeyCode::Up | KeyCode::Char('k') => Some(Action::Up),
+        KeyCode::Down | KeyCode::Char('j') => Some(Action::Down),
+        KeyCode::Char('r') => Some(Action::Refresh),
+        KeyCode::Char('s') => Some(Action::Start),
+        KeyCode::Char('x') => Some(Action::Stop),
+        KeyCode::Char('d') => Some(Action::Remove),
+        KeyCode::Char('e') | KeyCode::Enter => Some(Action::Shell),
+        _ => None,
+    }
+}
+
+/// One table row: box info plus live metrics for running boxes.
+struct BoxRow {
+    info: BoxInfo,
+    cpu_percent: Option<f32>,
+    memory_bytes: Option<u64>,
+}
+
+struct Dashboard {
+    rt: BoxliteRuntime,
+    layout: FilesystemLayout,
+    interval: Duration,
+    rows: Vec<BoxRow>,
+    table: TableState,
+    logs: Vec<String>,
+    message: String,
+}
+
+impl Dashboard {
+    fn new(rt: BoxliteRuntime, layout: FilesystemLayout, interval: Duration) -> Self {
+        Self {
+            rt,
+            layout,
+            interval,
+            rows: Vec::new(),
+            table: TableState::default(),
+            logs: Vec::new(),
+            message: String::new(),
+        }
+    }
+
+    async fn run(&mut self, terminal: &mut DefaultTerminal) -> anyhow::Result<()> {
+        self.refresh().await;
+        let mut last_refresh = Instant::now();
+
+        loop {
+            terminal.draw(|frame| self.render(frame))?;
+
+            if tokio::task::block_in_place(|| event::poll(INPUT_POLL))?
+                && let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+                && let Some(action) = key_action(key.code)
+            {
+                match action {
+                    Action::Quit => return Ok(()),
+                    Action::Up => self.select(-1),
+                    Action::Down => self.select(1),
+                    Action::Shell => {
+                        ratatui::restore();
+                        let result = self.shell().await;
+                        *terminal = ratatui::init();
+                        self.message = match result {
+                            Ok(code) => format!("shell exited with code {}", code),
+                            Err(e) => format!("shell: {}", e),
+                        };
+                    }
+                    action => self.apply(action).await,
+                }
+                self.refresh().await;
+                last_refresh = Instant::now();
+            }
+
+            if last_refresh.elapsed() >= self.interval {
+                self.refresh().await;
+                last_refresh = Instant::now();
+            }
+        }
+    }
+
+    fn selected(&self) -> Option<&BoxInfo> {
+        self.table
+            .selected()
+            .and_then(|i| self.rows.get(i))
+            .map(|row| &row.info)
+    }
+
+    fn select(&mut self, delta: isize) {
+        if self.rows.is_empty() {
+            return;
+        }
+        let current = self.table.selected().unwrap_or(0) as isize;
+        let next = (current + delta).clamp(0, self.rows.len() as isize - 1);
+        self.table.select(Some(next as usize));
+        self.load_logs();
+    }
+
+    async fn refresh(&mut self) {
+        let selected_id = self.selected().map(|info| info.id.clone());
+        let infos = match self.rt.list_info().await {
+            Ok(infos) => infos,
+            Err(e) => {
+                self.message = format!("list: {}", e);
+                return;
+            }
+        };
+
+        let mut rows = Vec::with_capacity(infos.len());
+        for info in infos {
+            let (mut cpu_percent, mut memory_bytes) = (None, None);
+            if info.status.is_running()
+                && let Ok(Some(litebox)) = self.rt.get(info.id.as_str()).await
+                && let Ok(metrics) = litebox.metrics().await
+            {
+                cpu_percent = metrics.cpu_percent();
+                memory_bytes = metrics.memory_bytes();
+            }
+            rows.push(BoxRow {
+                info,
+                cpu_percent,
+                memory_bytes,
+            });
+        }
+        self.rows = rows;
+
+        let index = selected_id
+            .and_then(|id| self.rows.iter().position(|row| row.info.id == id))
+            .or(if self.rows.is_empty() { None } else { Some(0) });
+        self.table.select(index);
+        self.load_logs();
+    }
+
+    fn load_logs(&mut self) {
+        self.logs = match self.selected() {
+            Some(info) => self
+                .layout
+                .box_layout(info.id.as_str(), false)
+                .map(|box_layout| tail_lines(&box_layout.console_output_path(), LOG_TAIL_BYTES))
+                .unwrap_or_default(),
+            None => Vec::new(),
+        };
+    }
+
+    async fn apply(&mut self, action: Action) {
+        if action == Action::Refresh {
+            self.message.clear();
+            return;
+        }
+        let Some(target) = self.selected().map(|info| info.id.to_string()) else {
+            return;
+        };
+        let (result, done) = match action {
+            Action::Start => (self.start(&target).await, "started"),
+            Action::Stop => (self.stop(&target).await, "stopped"),
+            Action::Remove => (
+                self.rt.remove(&target, true).await.map_err(Into::into),
+                "removed",
+            ),
+            _ => return,
+        };
+        self.message = match result {
+            Ok(()) => format!("{} {}", done, target),
+            Err(e) => format!("{}: {}", target, e),
+        };
+    }
+
+    async fn start(&self, target: &str) -> anyhow::Result<()> {
+        let litebox = self.get(target).await?;
+        litebox.start().await.map_err(Into::into)
+    }
+
+    async fn stop(&self, target: &str) -> anyhow::Result<()> {
+        let litebox = self.get(target).await?;
+        litebox.stop().await.map_err(Into::into)
+    }
+
+    async fn get(&self, target: &str) -> anyhow::Result<boxlite::LiteBox> {
+        self.rt
+            .get(target)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("No such box: {}", target))
+    }
+
+    /// Interactive `sh` in the selected box, on the restored terminal.
+    async fn shell(&self) -> anyhow::Result<i32> {
+        let target = self
+            .selected()
+            .map(|info| info.id.to_string())
+            .ok_or_else(|| anyhow::anyhow!("no box selected"))?;
+        let litebox = self.get(&target).await?;
+        let mut execution = litebox.exec(BoxCommand::new("sh").tty(true)).await?;
+        StreamManager::new(&mut execution, true, true).start().await
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let [table_area, logs_area, footer_area] = Layout::vertical([
+            Constraint::Percentage(50),
+            Constraint::Min(3),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let header = Row::new(["NAME", "ID", "STATUS", "IMAGE", "CPU", "MEMORY", "PORTS"])
+            .style(Style::new().add_modifier(Modifier::BOLD));
+        let rows = self.rows.iter().map(|row| {
+            let info = &row.info;
+            Row::new([
+                info.name.clone().unwrap_or_default(),
+                info.id.to_string(),
+                status_label(info.status),
+                info.image.clone(),
+                row.cpu_percent
+                    .map(|cpu| format!("{:.1}%", cpu))
+                    .unwrap_or_else(|| "-".to_string()),
+                row.memory_bytes
+                    .map(|bytes| format!("{} MiB", bytes / (1024 * 1024)))
+                    .unwrap_or_else(|| "-".to_string()),
+                info.ports
+                    .iter()
+                    .map(|p| match p.host_port {
+                        Some(host) => format!("{}->{}", host, p.guest_port),
+                        None => p.guest_port.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(16),
+                Constraint::Length(26),
+                Constraint::Length(10),
+                Constraint::Fill(1),
+                Constraint::Length(7),
+                Constraint::Length(10),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(header)
+        .row_highlight_style(Style::new().reversed())
+        .block(Block::bordered().title(format!(" boxes ({}) ", self.rows.len())));
+        frame.render_stateful_widget(table, table_area, &mut self.table);
+
+        let visible = logs_area.height.saturating_sub(2) as usize;
+        let skip = self.logs.len().saturating_sub(visible);
+        let logs: Vec<Line> = self.logs[skip..].iter().map(Line::raw).collect();
+        let title = match self.selected() {
+            Some(info) => format!(
+                " console: {} ",
+                info.name.as_deref().unwrap_or(info.id.as_str())
+            ),
+            None => " console ".to_string(),
+        };
+        frame.render_widget(
+            Paragraph::new(logs).block(Block::bordered().title(title)),
+            logs_area,
+        );
+
+        let footer = format!(
+            " q quit  j/k select  s start  x stop  d remove  e shell  r refresh   {}",
+            self.message
+        );
+        frame.render_widget(Line::raw(footer).dim(), footer_area);
+    }
+}
+
+fn status_label(status: BoxStatus) -> String {
+    format!("{:?}", status).to_lowercase()
+}
+
+/// Last lines of `path`, reading at most `max_bytes` from its end.
+fn tail_lines(path: &Path, max_bytes: u64) -> Vec<String> {
+    let Ok(mut file) = std::fs::File::open(path) else {
+        return Vec::new();
+    };
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let start = len.saturating_sub(max_bytes);
+    if file.seek(SeekFrom::Start(start)).is_err() {
+        return Vec::new();
+    }
+    let mut buf = Vec::new();
+    if file.read_to_end(&mut buf).is_err() {
+        return Vec::new();
+    }
+    let text = String::from_utf8_lossy(&buf);
+    let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+    // A cut-off first line is noise
+    if start > 0 && !lines.is_empty() {
+        lines.remove(0);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_bindings() {
+        assert_eq!(key_action(KeyCode::Char('q')), Some(Action::Quit));
+        assert_eq!(key_action(KeyCode::Char('j')), Some(Action::Down));
+        assert_eq!(key_action(KeyCode::Up), Some(Action::Up));
+        assert_eq!(key_action(KeyCode::Char('x')), Some(Action::Stop));
+        assert_eq!(key_action(KeyCode::Enter), Some(Action::Shell));
+        assert_eq!(key_action(KeyCode::Char('z')), None);
+    }
+
+    #[test]
+    fn test_tail_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("console.log");
+        std::fs::write(&path, "first\nsecond\nthird\n").unwrap();
+
+        assert_eq!(tail_lines(&path, 1024), ["first", "second", "third"]);
+        // Reading from mid-"second" drops the partial line
+        assert_eq!(tail_lines(&path, 10), ["third"]);
+        assert!(tail_lines(&dir.path().join("missing.log"), 1024).is_empty());
+    }
+}
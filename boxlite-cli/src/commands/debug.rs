@@ -0,0 +1,146 @@
+//! `boxlite debug`: debugging aids for boxes.
+//!
+//! `debug cores` lists the core dumps of a box created with `--core-dumps`
+//! and copies one out. Dumps live on the host, so this works on stopped
+//! boxes too.
+
+use std::io::Write;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, anyhow};
+use boxlite::CoreDump;
+use clap::{Args, Subcommand};
+use serde::Serialize;
+use tabled::Tabled;
+
+use crate::cli::GlobalFlags;
+use crate::commands::images::format_size;
+use crate::formatter::{self, OutputFormat};
+
+/// Debugging aids for boxes
+#[derive(Args, Debug)]
+pub struct DebugArgs {
+    #[command(subcommand)]
+    pub command: DebugCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DebugCommand {
+    /// List the core dumps of a box, or copy one out
+    ///
+    /// Dumps are collected for boxes created with --core-dumps.
+    Cores(CoresArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct CoresArgs {
+    /// Name or ID of the box
+    pub target: String,
+
+    /// Core dump to copy out (a NAME from the listing)
+    pub dump: Option<String>,
+
+    /// Where to copy the dump (a file or directory; default: current directory)
+    #[arg(short, long, value_name = "PATH", requires = "dump")]
+    pub output: Option<PathBuf>,
+
+    /// Output format (table, json, yaml)
+    #[arg(long, default_value = "table")]
+    pub format: String,
+}
+
+#[derive(Tabled, Serialize)]
+struct CoreDumpPresenter {
+    #[tabled(rename = "NAME")]
+    #[serde(rename = "Name")]
+    name: String,
+
+    #[tabled(rename = "COMMAND")]
+    #[serde(rename = "Command")]
+    command: String,
+
+    #[tabled(rename = "PID")]
+    #[serde(rename = "Pid")]
+    pid: u32,
+
+    #[tabled(rename = "SIGNAL")]
+    #[serde(rename = "Signal")]
+    signal: i32,
+
+    #[tabled(rename = "SIZE")]
+    #[serde(skip)]
+    size: String,
+
+    #[tabled(skip)]
+    #[serde(rename = "Size")]
+    size_bytes: u64,
+
+    #[tabled(skip)]
+    #[serde(rename = "Truncated")]
+    truncated: bool,
+
+    #[tabled(rename = "CREATED")]
+    #[serde(rename = "CreatedAt")]
+    created: String,
+}
+
+impl From<CoreDump> for CoreDumpPresenter {
+    fn from(dump: CoreDump) -> Self {
+        let mut size = format_size(dump.size_bytes);
+        if dump.truncated {
+            size.push_str(" (truncated)");
+        }
+        Self {
+            name: dump.name,
+            command: dump.command,
+            pid: dump.pid,
+            signal: dump.signal,
+            size,
+            size_bytes: dump.size_bytes,
+            truncated: dump.truncated,
+            created: formatter::format_time(&dump.created_at),
+        }
+    }
+}
+
+pub async fn execute(args: DebugArgs, global: &GlobalFlags) -> Result<()> {
+    match args.command {
+        DebugCommand::Cores(args) => cores(args, global).await,
+    }
+}
+
+async fn cores(args: CoresArgs, global: &GlobalFlags) -> Result<()> {
+    let format = OutputFormat::from_str(&args.format)?;
+    let runtime = global.create_runtime()?;
+    let litebox = runtime
+        .get(&args.target)
+        .await?
+        .ok_or_else(|| anyhow!("No such box: {}", args.target))?;
+    let dumps = litebox.core_dumps()?;
+
+    let Some(name) = args.dump else {
+        let rows: Vec<CoreDumpPresenter> = dumps.into_iter().map(CoreDumpPresenter::from).collect();
+        return formatter::print_output(
+            &mut std::io::stdout().lock(),
+            &rows,
+            format,
+            |writer, rows| {
+                writeln!(writer, "{}", formatter::create_table(rows))?;
+                Ok(())
+            },
+        );
+    };
+
+    let dump = dumps
+        .into_iter()
+        .find(|dump| dump.name == name)
+        .ok_or_else(|| anyhow!("no core dump '{}' in box '{}'", name, args.target))?;
+    let mut dest = args.output.unwrap_or_else(|| PathBuf::from("."));
+    if dest.is_dir() {
+        dest.push(&dump.name);
+    }
+    std::fs::copy(&dump.path, &dest)
+        .with_context(|| format!("failed to copy {} to {}", dump.name, dest.display()))?;
+    println!("{}", dest.display());
+    Ok(())
+}
@@ -0,0 +1,709 @@
+//! `boxlite devcontainer up`: run a Dev Container (`devcontainer.json`) as a box.
+//!
+//! Supported properties: `image`, `build.dockerfile`/`build.context`,
+//! `mounts` and `workspaceMount` (bind mounts), `workspaceFolder`,
+//! `forwardPorts`, `appPort`, `containerEnv`, `remoteEnv`, `containerUser`,
+//! `remoteUser` and the `onCreateCommand`, `updateContentCommand`,
+//! `postCreateCommand` and `postStartCommand` lifecycle hooks. Other
+//! properties are ignored.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use boxlite::runtime::options::{PortSpec, VolumeSpec};
+use boxlite::{BoxCommand, BoxOptions, BoxliteRuntime, BuildOptions, LiteBox, RootfsSpec};
+use clap::{Args, Subcommand};
+use futures::StreamExt;
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::sync::mpsc;
+
+use crate::cli::{GlobalFlags, parse_publish_spec};
+
+/// Run dev containers (devcontainer.json) without Docker
+#[derive(Args, Debug)]
+pub struct DevcontainerArgs {
+    #[command(subcommand)]
+    pub command: DevcontainerCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DevcontainerCommand {
+    /// Create and start the dev container for a workspace, or start the existing one
+    Up(UpArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct UpArgs {
+    /// Workspace folder on the host
+    #[arg(long = "workspace-folder", default_value = ".")]
+    pub workspace_folder: PathBuf,
+
+    /// devcontainer.json to use (default: .devcontainer/devcontainer.json or .devcontainer.json)
+    #[arg(long = "config")]
+    pub config: Option<PathBuf>,
+
+    /// Box name (default: devcontainer-<workspace folder name>)
+    #[arg(long = "name")]
+    pub name: Option<String>,
+
+    /// Remove an existing box for this workspace and create a new one
+    #[arg(long = "remove-existing-container")]
+    pub remove_existing: bool,
+}
+
+pub async fn execute(args: DevcontainerArgs, global: &GlobalFlags) -> Result<()> {
+    match args.command {
+        DevcontainerCommand::Up(up) => up.run(global).await,
+    }
+}
+
+impl UpArgs {
+    async fn run(&self, global: &GlobalFlags) -> Result<()> {
+        let workspace = std::fs::canonicalize(&self.workspace_folder).with_context(|| {
+            format!(
+                "workspace folder {} not found",
+                self.workspace_folder.display()
+            )
+        })?;
+        let config_path = match &self.config {
+            Some(path) => path.clone(),
+            None => find_config(&workspace)?,
+        };
+        let devcontainer = DevContainer::load(&config_path, &workspace)?;
+        let name = self
+            .name
+            .clone()
+            .unwrap_or_else(|| default_box_name(&workspace));
+
+        let runtime = global.create_runtime()?;
+        let mut existing = runtime.get(&name).await?;
+        if existing.is_some() && self.remove_existing {
+            runtime.remove(&name, true).await?;
+            existing = None;
+        }
+
+        let litebox = match existing {
+            Some(litebox) => {
+                if !litebox.info().status.is_running() {
+                    litebox.start().await?;
+                }
+                devcontainer
+                    .run_lifecycle(&litebox, &["postStartCommand"])
+                    .await?;
+                litebox
+            }
+            None => {
+                let image = devcontainer.resolve_image(&runtime, &name).await?;
+                let options = devcontainer.box_options(image)?;
+                let litebox = runtime.create(options, Some(name)).await?;
+                litebox.start().await?;
+                devcontainer
+                    .run_lifecycle(
+                        &litebox,
+                        &[
+                            "onCreateCommand",
+                            "updateContentCommand",
+                            "postCreateCommand",
+                            "postStartCommand",
+                        ],
+                    )
+                    .await?;
+                litebox
+            }
+        };
+
+        // Same result shape as `devcontainer up`, for editor integrations.
+        let result = serde_json::json!({
+            "outcome": "success",
+            "containerId": litebox.id().to_string(),
+            "remoteUser": devcontainer.remote_user(),
+            "remoteWorkspaceFolder": devcontainer.container_folder,
+        });
+        println!("{}", result);
+        Ok(())
+    }
+}
+
+fn find_config(workspace: &Path) -> Result<PathBuf> {
+    [
+        workspace.join(".devcontainer").join("devcontainer.json"),
+        workspace.join(".devcontainer.json"),
+    ]
+    .into_iter()
+    .find(|path| path.is_file())
+    .ok_or_else(|| anyhow::anyhow!("no devcontainer.json found in {}", workspace.display()))
+}
+
+fn default_box_name(workspace: &Path) -> String {
+    let base: String = basename(workspace)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("devcontainer-{}", base)
+}
+
+fn basename(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+// ============================================================================
+// devcontainer.json
+// ============================================================================
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DevContainerConfig {
+    image: Option<String>,
+    build: Option<BuildConfig>,
+    #[serde(default)]
+    mounts: Vec<MountConfig>,
+    workspace_mount: Option<String>,
+    #[serde(default)]
+    forward_ports: Vec<ForwardPort>,
+    app_port: Option<AppPort>,
+    #[serde(default)]
+    container_env: BTreeMap<String, String>,
+    #[serde(default)]
+    remote_env: BTreeMap<String, Option<String>>,
+    container_user: Option<String>,
+    remote_user: Option<String>,
+    #[serde(flatten)]
+    lifecycle: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct BuildConfig {
+    dockerfile: Option<String>,
+    context: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum MountConfig {
+    Spec(String),
+    Object {
+        source: Option<String>,
+        target: String,
+        #[serde(rename = "type")]
+        kind: Option<String>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ForwardPort {
+    Port(u16),
+    HostPort(String),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum AppPort {
+    One(ForwardPort),
+    Many(Vec<ForwardPort>),
+}
+
+/// A lifecycle command: a shell string, an argv array, or named commands.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum LifecycleCommand {
+    Shell(String),
+    Exec(Vec<String>),
+    Named(BTreeMap<String, LifecycleCommand>),
+}
+
+/// A loaded, variable-substituted dev container definition.
+struct DevContainer {
+    config: DevContainerConfig,
+    config_dir: PathBuf,
+    workspace: PathBuf,
+    container_folder: String,
+}
+
+impl DevContainer {
+    fn load(path: &Path, workspace: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let mut value: Value = serde_json::from_str(&strip_jsonc(&text))
+            .with_context(|| format!("invalid {}", path.display()))?;
+
+        let mut vars = Variables {
+            local_folder: workspace.to_string_lossy().into_owned(),
+            container_folder: None,
+        };
+        let container_folder = match value.get("workspaceFolder").and_then(Value::as_str) {
+            Some(folder) => vars.substitute(folder),
+            None => format!("/workspaces/{}", basename(workspace)),
+        };
+        vars.container_folder = Some(container_folder.clone());
+        vars.substitute_all(&mut value);
+
+        let config: DevContainerConfig =
+            serde_json::from_value(value).with_context(|| format!("invalid {}", path.display()))?;
+        if config.image.is_none() && config.build.is_none() {
+            anyhow::bail!(
+                "{}: only \"image\" and \"build\" dev containers are supported",
+                path.display()
+            );
+        }
+        Ok(Self {
+            config,
+            config_dir: path.parent().unwrap_or(Path::new(".")).to_path_buf(),
+            workspace: workspace.to_path_buf(),
+            container_folder,
+        })
+    }
+
+    /// The image reference, building `build.dockerfile` first if set.
+    async fn resolve_image(&self, runtime: &BoxliteRuntime, name: &str) -> Result<String> {
+        let Some(build) = &self.config.build else {
+            return Ok(self.config.image.clone().unwrap_or_default());
+        };
+        let dockerfile = self
+            .config_dir
+            .join(build.dockerfile.as_deref().unwrap_or("Dockerfile"));
+        let context = self
+            .config_dir
+            .join(build.context.as_deref().unwrap_or("."));
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let printer = tokio::spawn(async move {
+            while let Some(line) = rx.recv().await {
+                eprintln!("{}", line);
+            }
+        });
+        let result = runtime
+            .build(BuildOptions {
+                context,
+                dockerfile: Some(dockerfile),
+                tag: format!("boxlite-{}:latest", name),
+                no_cache: false,
+                progress: Some(tx),
+            })
+            .await;
+        let _ = printer.await;
+        Ok(result?.reference)
+    }
+
+    fn box_options(&self, image: String) -> Result<BoxOptions> {
+        let mut options = BoxOptions {
+            rootfs: RootfsSpec::Image(image),
+            working_dir: Some(self.container_folder.clone()),
+            env: self.config.container_env.clone().into_iter().collect(),
+            user: self.config.container_user.clone(),
+            detach: true,
+            auto_remove: false,
+            ..Default::default()
+        };
+
+        let workspace_mount = match &self.config.workspace_mount {
+            Some(spec) => parse_mount(spec, &self.workspace)?,
+            None => Some(VolumeSpec {
+                host_path: self.workspace.to_string_lossy().into_owned(),
+                guest_path: self.container_folder.clone(),
+                read_only: false,
+                ..Default::default()
+            }),
+        };
+        options.volumes.extend(workspace_mount);
+        for mount in &self.config.mounts {
+            let volume = match mount {
+                MountConfig::Spec(spec) => parse_mount(spec, &self.workspace)?,
+                MountConfig::Object {
+                    source,
+                    target,
+                    kind,
+                } => {
+                    let spec = format!(
+                        "type={},source={},target={}",
+                        kind.as_deref().unwrap_or("bind"),
+                        source.as_deref().unwrap_or_default(),
+                        target
+                    );
+                    parse_mount(&spec, &self.workspace)?
+                }
+            };
+            options.volumes.extend(volume);
+        }
+
+        let mut app_ports = Vec::new();
+        match &self.config.app_port {
+            Some(AppPort::One(port)) => app_ports.push(port),
+            Some(AppPort::Many(ports)) => app_ports.extend(ports),
+            None => {}
+        }
+        for port in app_ports {
+            let spec = match port {
+                ForwardPort::Port(port) => parse_publish_spec(&port.to_string())?,
+                ForwardPort::HostPort(spec) => parse_publish_spec(spec)?,
+            };
+            add_port(&mut options.ports, spec);
+        }
+        for port in &self.config.forward_ports {
+            if let Some(port) = forward_port(port) {
+                add_port(
+                    &mut options.ports,
+                    PortSpec {
+                        host_port: Some(port),
+                        guest_port: port,
+                        host_ip: Some("127.0.0.1".to_string()),
+                        ..Default::default()
+                    },
+                );
+            }
+        }
+        Ok(options)
+    }
+
+    fn remote_user(&self) -> String {
+        self.config
+            .remote_user
+            .clone()
+            .or_else(|| self.config.container_user.clone())
+            .unwrap_or_else(|| "root".to_string())
+    }
+
+    /// Run the given lifecycle hooks in order; a failing hook aborts.
+    async fn run_lifecycle(&self, litebox: &LiteBox, hooks: &[&str]) -> Result<()> {
+        for hook in hooks {
+            let Some(value) = self.config.lifecycle.get(*hook) else {
+                continue;
+            };
+            let command: LifecycleCommand = serde_json::from_value(value.clone())
+                .with_context(|| format!("invalid {}", hook))?;
+            for argv in command.argvs() {
+                eprintln!("Running {}: {}", hook, argv.join(" "));
+                let exit_code = self.exec(litebox, argv).await?;
+                if exit_code != 0 {
+                    anyhow::bail!("{} failed with exit code {}", hook, exit_code);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn exec(&self, litebox: &LiteBox, mut argv: Vec<String>) -> Result<i32> {
+        // BoxCommand has no user; switch with su when remoteUser differs.
+        let remote_user = self.remote_user();
+        if self.config.remote_user.is_some()
+            && self.config.container_user.as_deref() != Some(remote_user.as_str())
+        {
+            let script = argv
+                .iter()
+                .map(|arg| shell_quote(arg))
+                .collect::<Vec<_>>()
+                .join(" ");
+            argv = vec!["su".to_string(), remote_user, "-c".to_string(), script];
+        }
+
+        let mut command = BoxCommand::new(argv[0].clone())
+            .args(argv[1..].iter().cloned())
+            .working_dir(self.container_folder.clone());
+        for (key, value) in &self.config.remote_env {
+            if let Some(value) = value {
+                command = command.env(key.clone(), value.clone());
+            }
+        }
+
+        let mut execution = litebox.exec(command).await?;
+        let stdout = execution.stdout();
+        let stderr = execution.stderr();
+        // Hook output goes to stderr; stdout carries the JSON result.
+        let forward_stdout = async {
+            if let Some(mut stream) = stdout {
+                while let Some(chunk) = stream.next().await {
+                    let _ = std::io::stderr().write_all(&chunk);
+                }
+            }
+        };
+        let forward_stderr = async {
+            if let Some(mut stream) = stderr {
+                while let Some(chunk) = stream.next().await {
+                    let _ = std::io::stderr().write_all(&chunk);
+                }
+            }
+        };
+        let (_, _, result) = tokio::join!(forward_stdout, forward_stderr, execution.wait());
+        Ok(result?.exit_code)
+    }
+}
+
+impl LifecycleCommand {
+    fn argvs(&self) -> Vec<Vec<String>> {
+        match self {
+            LifecycleCommand::Shell(script) => vec![vec![
+                "/bin/sh".to_string(),
+                "-c".to_string(),
+                script.clone(),
+            ]],
+            LifecycleCommand::Exec(argv) if argv.is_empty() => Vec::new(),
+            LifecycleCommand::Exec(argv) => vec![argv.clone()],
+            LifecycleCommand::Named(commands) => {
+                commands.values().flat_map(|cmd| cmd.argvs()).collect()
+            }
+        }
+    }
+}
+
+/// Only `localhost:PORT` forwards map to the box; `service:PORT` entries
+/// refer to other compose services and are skipped.
+fn forward_port(port: &ForwardPort) -> Option<u16> {
+    match port {
+        ForwardPort::Port(port) => Some(*port),
+        ForwardPort::HostPort(spec) => {
+            let (host, port) = spec.rsplit_once(':')?;
+            if host != "localhost" && host != "127.0.0.1" {
+                eprintln!("Warning: skipping forwardPorts entry {}", spec);
+                return None;
+            }
+            port.parse().ok()
+        }
+    }
+}
+
+/// Ports already published on the same host port are left as configured.
+fn add_port(ports: &mut Vec<PortSpec>, spec: PortSpec) {
+    if !ports.iter().any(|p| p.host_port == spec.host_port) {
+        ports.push(spec);
+    }
+}
+
+/// Parse a Docker `--mount` style spec (`type=bind,source=..,target=..[,readonly]`).
+/// Non-bind mounts (named volumes, tmpfs) are skipped with a warning.
+fn parse_mount(spec: &str, workspace: &Path) -> Result<Option<VolumeSpec>> {
+    let mut kind = "volume";
+    let mut source = None;
+    let mut target = None;
+    let mut read_only = false;
+    for part in spec.split(',') {
+        let (key, value) = part.split_once('=').unwrap_or((part, "true"));
+        match key.trim() {
+            "type" => kind = value,
+            "source" | "src" => source = Some(value),
+            "target" | "destination" | "dst" => target = Some(value),
+            "readonly" | "ro" => read_only = value != "false",
+            _ => {}
+        }
+    }
+    let target = target.ok_or_else(|| anyhow::anyhow!("mount {}: missing target", spec))?;
+    if kind != "bind" {
+        eprintln!("Warning: skipping {} mount {}", kind, spec);
+        return Ok(None);
+    }
+    let source = source.ok_or_else(|| anyhow::anyhow!("mount {}: missing source", spec))?;
+    Ok(Some(VolumeSpec {
+        host_path: workspace.join(source).to_string_lossy().into_owned(),
+        guest_path: target.to_string(),
+        read_only,
+        ..Default::default()
+    }))
+}
+
+/// `${...}` variables of the Dev Container spec.
+struct Variables {
+    local_folder: String,
+    container_folder: Option<String>,
+}
+
+impl Variables {
+    fn substitute_all(&self, value: &mut Value) {
+        match value {
+            Value::String(s) => *s = self.substitute(s),
+            Value::Array(items) => items.iter_mut().for_each(|v| self.substitute_all(v)),
+            Value::Object(map) => map.values_mut().for_each(|v| self.substitute_all(v)),
+            _ => {}
+        }
+    }
+
+    fn substitute(&self, input: &str) -> String {
+        let mut out = String::with_capacity(input.len());
+        let mut rest = input;
+        while let Some(start) = rest.find("${") {
+            let Some(len) = rest[start..].find('}') else {
+                break;
+            };
+            out.push_str(&rest[..start]);
+            let name = &rest[start + 2..start + len];
+            match self.lookup(name) {
+                Some(value) => out.push_str(&value),
+                None => out.push_str(&rest[start..=start + len]),
+            }
+            rest = &rest[start + len + 1..];
+        }
+        out.push_str(rest);
+        out
+    }
+
+    fn lookup(&self, name: &str) -> Option<String> {
+        let folder_basename = |folder: &str| basename(Path::new(folder));
+        match name {
+            "localWorkspaceFolder" => Some(self.local_folder.clone()),
+            "localWorkspaceFolderBasename" => Some(folder_basename(&self.local_folder)),
+            "containerWorkspaceFolder" => self.container_folder.clone(),
+            "containerWorkspaceFolderBasename" => {
+                self.container_folder.as_deref().map(folder_basename)
+            }
+            _ => {
+                let var = name
+                    .strip_prefix("localEnv:")
+                    .or_else(|| name.strip_prefix("env:"))?;
+                let (var, default) = var.split_once(':').unwrap_or((var, ""));
+                Some(std::env::var(var).unwrap_or_else(|_| default.to_string()))
+            }
+        }
+    }
+}
+
+/// Strip `//` and `/* */` comments and trailing commas (JSON with Comments).
+fn strip_jsonc(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    let mut in_string = false;
+    let mut escaped = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = '\0';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            (']' | '}', _) => {
+                let trimmed = out.trim_end().len();
+                if out[..trimmed].ends_with(',') {
+                    out.truncate(trimmed - 1);
+                }
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_jsonc() {
+        let input = r#"{
+            // image
+            "image": "node:20", /* inline */
+            "url": "http://example.com/*x*/",
+            "forwardPorts": [3000,],
+        }"#;
+        let value: Value = serde_json::from_str(&strip_jsonc(input)).unwrap();
+        assert_eq!(value["image"], "node:20");
+        assert_eq!(value["url"], "http://example.com/*x*/");
+        assert_eq!(value["forwardPorts"], serde_json::json!([3000]));
+    }
+
+    #[test]
+    fn test_devcontainer_box_options() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path().join("app");
+        std::fs::create_dir_all(workspace.join(".devcontainer")).unwrap();
+        let config = workspace.join(".devcontainer/devcontainer.json");
+        std::fs::write(
+            &config,
+            r#"{
+                "image": "mcr.microsoft.com/devcontainers/rust:1",
+                "mounts": [
+                    "source=${localWorkspaceFolder}/.cache,target=/cache,type=bind,readonly",
+                    {"source": "vol", "target": "/data", "type": "volume"}
+                ],
+                "forwardPorts": [8080, "db:5432", "localhost:9000"],
+                "appPort": "3000:3001",
+                "containerEnv": {"APP_DIR": "${containerWorkspaceFolder}"},
+                "postCreateCommand": {"deps": "cargo fetch", "tools": ["rustup", "update"]}
+            }"#,
+        )
+        .unwrap();
+
+        let devcontainer = DevContainer::load(&config, &workspace).unwrap();
+        assert_eq!(devcontainer.container_folder, "/workspaces/app");
+        let options = devcontainer.box_options("rust:1".to_string()).unwrap();
+
+        assert_eq!(options.working_dir.as_deref(), Some("/workspaces/app"));
+        assert_eq!(
+            options.env,
+            vec![("APP_DIR".to_string(), "/workspaces/app".to_string())]
+        );
+        let volumes: Vec<_> = options
+            .volumes
+            .iter()
+            .map(|v| (v.host_path.clone(), v.guest_path.as_str(), v.read_only))
+            .collect();
+        let host = workspace.to_string_lossy().into_owned();
+        assert_eq!(
+            volumes,
+            vec![
+                (host.clone(), "/workspaces/app", false),
+                (format!("{}/.cache", host), "/cache", true),
+            ]
+        );
+        let ports: Vec<_> = options
+            .ports
+            .iter()
+            .map(|p| (p.host_port, p.guest_port))
+            .collect();
+        assert_eq!(
+            ports,
+            vec![(Some(3000), 3001), (Some(8080), 8080), (Some(9000), 9000)]
+        );
+
+        let command: LifecycleCommand =
+            serde_json::from_value(devcontainer.config.lifecycle["postCreateCommand"].clone())
+                .unwrap();
+        assert_eq!(
+            command.argvs(),
+            vec![
+                vec!["/bin/sh", "-c", "cargo fetch"],
+                vec!["rustup", "update"],
+            ]
+        );
+    }
+}
@@ -13,6 +13,18 @@ pub struct ExecArgs {
     #[arg(short = 'd', long)]
     pub detach: bool,
 
+    /// Run in the named sidecar instead of the main container
+    #[arg(long, value_name = "NAME")]
+    pub container: Option<String>,
+
+    /// Run as this user instead of the container's (uid, uid:gid, name or name:group)
+    #[arg(short = 'u', long, value_name = "USER")]
+    pub user: Option<String>,
+
+    /// Grant the command every Linux capability
+    #[arg(long)]
+    pub privileged: bool,
+
     /// Box ID or name
     #[arg(index = 1, value_name = "BOX")]
     pub target_box: String,
@@ -42,7 +54,7 @@ impl BoxExecutor {
     async fn execute(&mut self) -> anyhow::Result<()> {
         self.args.process.validate(self.args.detach)?;
         let litebox = self.get_box().await?;
-        let cmd = self.prepare_command();
+        let cmd = self.prepare_command()?;
         let mut execution = litebox.exec(cmd).await?;
 
         // Detach mode: Exit immediately without waiting
@@ -50,6 +62,13 @@ impl BoxExecutor {
             return Ok(());
         }
 
+        // Recordings under the box home get a fresh directory; say where
+        if self.args.process.output_dir.is_none()
+            && let Some(dir) = execution.recording_dir()
+        {
+            eprintln!("Recording output to {}", dir.display());
+        }
+
         // IO handle and signals
         let streamer = StreamManager::new(
             &mut execution,
@@ -73,8 +92,15 @@ impl BoxExecutor {
             .ok_or_else(|| anyhow::anyhow!("No such box: {}", self.args.target_box))
     }
 
-    fn prepare_command(&self) -> BoxCommand {
-        let cmd = BoxCommand::new(&self.args.command[0]).args(&self.args.command[1..]);
+    fn prepare_command(&self) -> anyhow::Result<BoxCommand> {
+        let mut cmd = BoxCommand::new(&self.args.command[0]).args(&self.args.command[1..]);
+        if let Some(name) = &self.args.container {
+            cmd = cmd.container(name);
+        }
+        if let Some(user) = &self.args.user {
+            cmd = cmd.user(user);
+        }
+        cmd = cmd.privileged(self.args.privileged);
         self.args.process.configure_command(cmd)
     }
 }
@@ -0,0 +1,22 @@
+use std::path::PathBuf;
+
+use clap::Args;
+
+/// Export a stopped box to a portable archive
+#[derive(Args, Debug)]
+pub struct ExportArgs {
+    /// Name or ID of the stopped box to export
+    pub target: String,
+
+    /// Write the archive to this file (e.g. box.tar.zst)
+    #[arg(short, long)]
+    pub output: PathBuf,
+}
+
+pub async fn execute(args: ExportArgs, global: &crate::cli::GlobalFlags) -> anyhow::Result<()> {
+    let runtime = global.create_runtime()?;
+
+    runtime.export_box(&args.target, &args.output).await?;
+    println!("{}", args.output.display());
+    Ok(())
+}
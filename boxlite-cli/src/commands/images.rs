@@ -1,13 +1,20 @@
 use crate::cli::GlobalFlags;
 use crate::formatter::{self, OutputFormat};
 use boxlite::runtime::types::ImageInfo;
-use clap::Args;
+use boxlite::{CorruptBlob, ImageInspect, ImageLayerInspect, ImagePullMetrics, ImageVerification};
+use clap::{Args, Subcommand};
 use serde::Serialize;
 use tabled::Tabled;
+use tabled::settings::Remove;
+use tabled::settings::location::ByColumnName;
 
 /// List images
 #[derive(Args, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct ImagesArgs {
+    #[command(subcommand)]
+    pub command: Option<ImagesCommand>,
+
     /// Show all images (default hides intermediate images)
     #[arg(short = 'a', long)]
     pub all: bool,
@@ -16,6 +23,46 @@ pub struct ImagesArgs {
     #[arg(short, long)]
     pub quiet: bool,
 
+    /// Show full manifest digests
+    #[arg(long)]
+    pub digests: bool,
+
+    /// Show statistics of the last registry pull (duration, bytes, layer cache hits)
+    #[arg(short, long)]
+    pub verbose: bool,
+
+    /// Output format (table, json, yaml)
+    #[arg(long, default_value = "table")]
+    pub format: String,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ImagesCommand {
+    /// Re-hash cached image blobs and report those that do not match their digest
+    ///
+    /// Exits with an error if any blob is corrupt or missing.
+    Verify(VerifyArgs),
+
+    /// Show the config, layers and platform of a cached image
+    Inspect(InspectArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct VerifyArgs {
+    /// Delete corrupt blobs so the next pull downloads them again
+    #[arg(long)]
+    pub remove: bool,
+
+    /// Output format (table, json, yaml)
+    #[arg(long, default_value = "table")]
+    pub format: String,
+}
+
+#[derive(Args, Debug)]
+pub struct InspectArgs {
+    /// Image reference, tag or short name
+    pub image: String,
+
     /// Output format (table, json, yaml)
     #[arg(long, default_value = "table")]
     pub format: String,
@@ -30,6 +77,9 @@ struct ImagePresenter {
     #[tabled(rename = "TAG")]
     #[serde(rename = "Tag")]
     tag: String,
+    #[tabled(rename = "DIGEST")]
+    #[serde(rename = "Digest")]
+    digest: String,
     #[tabled(rename = "IMAGE ID")]
     #[serde(rename = "ID")]
     id: String,
@@ -39,21 +89,103 @@ struct ImagePresenter {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[tabled(skip)]
     size: Option<String>,
+    #[tabled(rename = "PULL TIME")]
+    #[serde(skip)]
+    pull_time: String,
+    #[tabled(rename = "DOWNLOADED")]
+    #[serde(skip)]
+    downloaded: String,
+    #[tabled(rename = "CACHED LAYERS")]
+    #[serde(skip)]
+    cached_layers: String,
+    #[serde(rename = "LastPull", skip_serializing_if = "Option::is_none")]
+    #[tabled(skip)]
+    last_pull: Option<PullPresenter>,
 }
 
-impl From<&ImageInfo> for ImagePresenter {
-    fn from(info: &ImageInfo) -> Self {
+/// Last registry pull of an image (`--verbose` JSON/YAML output).
+#[derive(Serialize)]
+struct PullPresenter {
+    #[serde(rename = "PulledAt")]
+    pulled_at: String,
+    #[serde(rename = "DurationMs")]
+    duration_ms: u64,
+    #[serde(rename = "BytesDownloaded")]
+    bytes_downloaded: u64,
+    #[serde(rename = "LayersCached")]
+    layers_cached: usize,
+    #[serde(rename = "LayersFetched")]
+    layers_fetched: usize,
+    #[serde(rename = "Layers")]
+    layers: Vec<LayerPullPresenter>,
+}
+
+#[derive(Serialize)]
+struct LayerPullPresenter {
+    #[serde(rename = "Digest")]
+    digest: String,
+    #[serde(rename = "Size")]
+    size: u64,
+    #[serde(rename = "Cached")]
+    cached: bool,
+    #[serde(rename = "DurationMs")]
+    duration_ms: u64,
+}
+
+impl From<&ImagePullMetrics> for PullPresenter {
+    fn from(pull: &ImagePullMetrics) -> Self {
+        Self {
+            pulled_at: pull.pulled_at.to_rfc3339(),
+            duration_ms: pull.duration_ms as u64,
+            bytes_downloaded: pull.bytes_downloaded,
+            layers_cached: pull.layers_cached(),
+            layers_fetched: pull.layers_fetched(),
+            layers: pull
+                .layers
+                .iter()
+                .map(|layer| LayerPullPresenter {
+                    digest: layer.digest.clone(),
+                    size: layer.size_bytes,
+                    cached: layer.cached,
+                    duration_ms: layer.duration_ms as u64,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl ImagePresenter {
+    /// Build the presenter; pull statistics are only included when `verbose`.
+    fn new(info: &ImageInfo, verbose: bool) -> Self {
+        let last_pull = info.last_pull.as_ref().filter(|_| verbose);
         Self {
             repository: info.repository.clone(),
             tag: info.tag.clone(),
+            digest: info.id.clone(),
             id: get_short_id(&info.id),
             created: formatter::format_time(&info.cached_at),
             size: info.size.map(|s| s.to_string()),
+            pull_time: last_pull
+                .map(|p| format!("{:.1}s", p.duration_ms as f64 / 1000.0))
+                .unwrap_or_else(|| "-".to_string()),
+            downloaded: last_pull
+                .map(|p| format_size(p.bytes_downloaded))
+                .unwrap_or_else(|| "-".to_string()),
+            cached_layers: last_pull
+                .map(|p| format!("{}/{}", p.layers_cached(), p.layers.len()))
+                .unwrap_or_else(|| "-".to_string()),
+            last_pull: last_pull.map(Into::into),
         }
     }
 }
 
 pub async fn execute(args: ImagesArgs, global: &GlobalFlags) -> anyhow::Result<()> {
+    match args.command {
+        Some(ImagesCommand::Verify(verify_args)) => return verify(verify_args, global).await,
+        Some(ImagesCommand::Inspect(inspect_args)) => return inspect(inspect_args, global).await,
+        None => {}
+    }
+
     let rt = global.create_runtime()?;
     let images = rt.list_images().await?;
 
@@ -64,14 +196,17 @@ pub async fn execute(args: ImagesArgs, global: &GlobalFlags) -> anyhow::Result<(
         return Ok(());
     }
 
-    let presenters: Vec<ImagePresenter> = images.iter().map(Into::into).collect();
+    let presenters: Vec<ImagePresenter> = images
+        .iter()
+        .map(|info| ImagePresenter::new(info, args.verbose))
+        .collect();
     let format = OutputFormat::from_str(&args.format)?;
     formatter::print_output(
         &mut std::io::stdout().lock(),
         &presenters,
         format,
         |writer, data| {
-            print_images(writer, data)?;
+            print_images(writer, data, args.digests, args.verbose)?;
             Ok(())
         },
     )?;
@@ -79,12 +214,196 @@ pub async fn execute(args: ImagesArgs, global: &GlobalFlags) -> anyhow::Result<(
     Ok(())
 }
 
-fn print_images(writer: &mut impl std::io::Write, images: &[ImagePresenter]) -> anyhow::Result<()> {
-    let table = formatter::create_table(images).to_string();
+fn print_images(
+    writer: &mut impl std::io::Write,
+    images: &[ImagePresenter],
+    digests: bool,
+    verbose: bool,
+) -> anyhow::Result<()> {
+    let mut table = formatter::create_table(images);
+    if !digests {
+        table.with(Remove::column(ByColumnName::new("DIGEST")));
+    }
+    if !verbose {
+        for column in ["PULL TIME", "DOWNLOADED", "CACHED LAYERS"] {
+            table.with(Remove::column(ByColumnName::new(column)));
+        }
+    }
     writeln!(writer, "{}", table)?;
     Ok(())
 }
 
+/// One corrupt blob of `images verify`.
+#[derive(Tabled)]
+struct CorruptBlobPresenter {
+    #[tabled(rename = "KIND")]
+    kind: String,
+    #[tabled(rename = "DIGEST")]
+    digest: String,
+    #[tabled(rename = "ON DISK")]
+    actual: String,
+    #[tabled(rename = "IMAGES")]
+    images: String,
+    #[tabled(rename = "REMOVED")]
+    removed: String,
+}
+
+impl From<&CorruptBlob> for CorruptBlobPresenter {
+    fn from(blob: &CorruptBlob) -> Self {
+        Self {
+            kind: blob.kind.to_string(),
+            digest: blob.digest.clone(),
+            actual: blob.actual.clone().unwrap_or_else(|| "missing".to_string()),
+            images: blob.images.join(", "),
+            removed: if blob.removed { "yes" } else { "no" }.to_string(),
+        }
+    }
+}
+
+async fn verify(args: VerifyArgs, global: &GlobalFlags) -> anyhow::Result<()> {
+    let format = OutputFormat::from_str(&args.format)?;
+    let rt = global.create_runtime()?;
+    let report = rt.verify_images(args.remove).await?;
+
+    formatter::print_output(
+        &mut std::io::stdout().lock(),
+        &report,
+        format,
+        print_verification,
+    )?;
+    if !report.is_ok() {
+        anyhow::bail!(
+            "{} of {} blobs do not match their digest",
+            report.corrupt.len(),
+            report.blobs_checked
+        );
+    }
+    Ok(())
+}
+
+fn print_verification(
+    writer: &mut impl std::io::Write,
+    report: &ImageVerification,
+) -> anyhow::Result<()> {
+    if report.is_ok() {
+        writeln!(
+            writer,
+            "All {} blobs match their digest",
+            report.blobs_checked
+        )?;
+        return Ok(());
+    }
+    let rows: Vec<CorruptBlobPresenter> = report.corrupt.iter().map(Into::into).collect();
+    writeln!(writer, "{}", formatter::create_table(rows))?;
+    if report.corrupt.iter().any(|blob| !blob.removed) {
+        writeln!(
+            writer,
+            "Run `images verify --remove` to delete them; the next pull downloads them again"
+        )?;
+    }
+    Ok(())
+}
+
+/// One layer row of `images inspect`.
+#[derive(Tabled)]
+struct LayerPresenter {
+    #[tabled(rename = "DIGEST")]
+    digest: String,
+    #[tabled(rename = "SIZE")]
+    size: String,
+    #[tabled(rename = "DOWNLOADED")]
+    downloaded: String,
+    #[tabled(rename = "EXTRACTED")]
+    extracted: String,
+    #[tabled(rename = "CACHED ON PULL")]
+    cached_on_pull: String,
+}
+
+impl From<&ImageLayerInspect> for LayerPresenter {
+    fn from(layer: &ImageLayerInspect) -> Self {
+        let yes_no = |value: bool| if value { "yes" } else { "no" }.to_string();
+        Self {
+            digest: layer.digest.clone(),
+            size: format_size(layer.size.as_bytes()),
+            downloaded: yes_no(layer.downloaded),
+            extracted: yes_no(layer.extracted),
+            cached_on_pull: layer
+                .cached_on_pull
+                .map(yes_no)
+                .unwrap_or_else(|| "-".to_string()),
+        }
+    }
+}
+
+async fn inspect(args: InspectArgs, global: &GlobalFlags) -> anyhow::Result<()> {
+    let format = OutputFormat::from_str(&args.format)?;
+    let rt = global.create_runtime()?;
+    let image = rt.image_inspect(&args.image).await?;
+
+    formatter::print_output(&mut std::io::stdout().lock(), &image, format, print_inspect)?;
+    Ok(())
+}
+
+fn print_inspect(writer: &mut impl std::io::Write, image: &ImageInspect) -> anyhow::Result<()> {
+    let or_dash = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
+    let join = |values: &[String]| {
+        if values.is_empty() {
+            "-".to_string()
+        } else {
+            values.join(" ")
+        }
+    };
+    let platform = match &image.variant {
+        Some(variant) => format!("{}/{}/{}", image.os, image.architecture, variant),
+        None => format!("{}/{}", image.os, image.architecture),
+    };
+
+    writeln!(writer, "Reference:    {}", image.reference)?;
+    writeln!(writer, "ID:           {}", image.id)?;
+    writeln!(writer, "Config:       {}", image.config_digest)?;
+    writeln!(writer, "Platform:     {}", platform)?;
+    writeln!(writer, "Created:      {}", or_dash(&image.created))?;
+    writeln!(writer, "Author:       {}", or_dash(&image.author))?;
+    writeln!(
+        writer,
+        "Size:         {}",
+        format_size(image.size.as_bytes())
+    )?;
+    writeln!(writer, "Entrypoint:   {}", join(&image.entrypoint))?;
+    writeln!(writer, "Cmd:          {}", join(&image.cmd))?;
+    writeln!(writer, "WorkingDir:   {}", or_dash(&image.working_dir))?;
+    writeln!(writer, "User:         {}", or_dash(&image.user))?;
+    writeln!(writer, "ExposedPorts: {}", join(&image.exposed_ports))?;
+    writeln!(writer, "Env:")?;
+    for var in &image.env {
+        writeln!(writer, "  {}", var)?;
+    }
+    writeln!(writer, "Labels:")?;
+    for (key, value) in &image.labels {
+        writeln!(writer, "  {}={}", key, value)?;
+    }
+    writeln!(writer, "Layers:")?;
+    let rows: Vec<LayerPresenter> = image.layers.iter().map(Into::into).collect();
+    writeln!(writer, "{}", formatter::create_table(rows))?;
+    Ok(())
+}
+
+/// Human-readable decimal size, like `docker images` (e.g. "3.4MB").
+pub(crate) fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "kB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1000.0 && unit < UNITS.len() - 1 {
+        size /= 1000.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{}{}", bytes, UNITS[0])
+    } else {
+        format!("{:.1}{}", size, UNITS[unit])
+    }
+}
+
 fn get_short_id(id: &str) -> String {
     let clean_id = id.strip_prefix("sha256:").unwrap_or(id);
     if clean_id.len() > 12 {
@@ -105,4 +424,67 @@ mod tests {
         assert_eq!(get_short_id("short"), "short");
         assert_eq!(get_short_id("sha256:short"), "short");
     }
+
+    #[test]
+    fn test_digest_column_only_with_flag() {
+        let images = vec![ImagePresenter {
+            repository: "localhost/myapp".to_string(),
+            tag: "v1".to_string(),
+            digest: "sha256:1234567890abcdef1234".to_string(),
+            id: "1234567890ab".to_string(),
+            created: "now".to_string(),
+            size: None,
+            pull_time: "-".to_string(),
+            downloaded: "-".to_string(),
+            cached_layers: "-".to_string(),
+            last_pull: None,
+        }];
+
+        let mut out = Vec::new();
+        print_images(&mut out, &images, false, false).unwrap();
+        let plain = String::from_utf8(out).unwrap();
+        assert!(!plain.contains("DIGEST"));
+        assert!(!plain.contains("sha256:"));
+
+        let mut out = Vec::new();
+        print_images(&mut out, &images, true, false).unwrap();
+        let with_digests = String::from_utf8(out).unwrap();
+        assert!(with_digests.contains("DIGEST"));
+        assert!(with_digests.contains("sha256:1234567890abcdef1234"));
+    }
+
+    #[test]
+    fn test_print_verification() {
+        let mut report = ImageVerification {
+            blobs_checked: 3,
+            corrupt: Vec::new(),
+        };
+        let mut out = Vec::new();
+        print_verification(&mut out, &report).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "All 3 blobs match their digest\n"
+        );
+
+        report.corrupt.push(CorruptBlob {
+            kind: boxlite::ImageBlobKind::Layer,
+            digest: "sha256:aaaa".to_string(),
+            actual: None,
+            images: vec!["docker.io/library/alpine:3.20".to_string()],
+            removed: false,
+        });
+        let mut out = Vec::new();
+        print_verification(&mut out, &report).unwrap();
+        let table = String::from_utf8(out).unwrap();
+        assert!(table.contains("sha256:aaaa"));
+        assert!(table.contains("missing"));
+        assert!(table.contains("--remove"));
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512B");
+        assert_eq!(format_size(3_400_000), "3.4MB");
+        assert_eq!(format_size(1_500_000_000), "1.5GB");
+    }
 }
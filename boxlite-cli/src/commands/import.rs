@@ -0,0 +1,32 @@
+use std::path::PathBuf;
+
+use boxlite::ImportOptions;
+use clap::Args;
+
+/// Import a box from an archive created by `boxlite export`
+#[derive(Args, Debug)]
+pub struct ImportArgs {
+    /// Path to the box archive
+    pub input: PathBuf,
+
+    /// Assign a name to the imported box (defaults to the exported name)
+    #[arg(long)]
+    pub name: Option<String>,
+
+    /// Keep the archive's volumes, published ports, custom kernel, rootfs
+    /// path and MAC/IP addresses, which belong to the exporting host
+    #[arg(long)]
+    pub host_bindings: bool,
+}
+
+pub async fn execute(args: ImportArgs, global: &crate::cli::GlobalFlags) -> anyhow::Result<()> {
+    let runtime = global.create_runtime()?;
+
+    let options = ImportOptions {
+        name: args.name,
+        host_bindings: args.host_bindings,
+    };
+    let litebox = runtime.import_box(&args.input, options).await?;
+    println!("{}", litebox.id());
+    Ok(())
+}
@@ -2,9 +2,11 @@
 
 use crate::cli::GlobalFlags;
 use crate::formatter::{self, GtmplWithJson, OutputFormat, value_from_serde_json};
+use boxlite::runtime::options::{PortProtocol, PortSpec};
 use boxlite::{BoxInfo, BoxStateInfo};
 use clap::Args;
 use serde::Serialize;
+use std::collections::BTreeMap;
 
 /// Inspect one or more boxes
 #[derive(Args, Debug)]
@@ -41,6 +43,76 @@ struct InspectPresenter {
     cpus: u8,
     #[serde(rename = "Memory")]
     memory: u64,
+    #[serde(rename = "Hostname")]
+    hostname: String,
+    /// Empty when the box has no network device
+    #[serde(rename = "MacAddress")]
+    mac_address: String,
+    /// Empty when the box has no network device
+    #[serde(rename = "IPAddress")]
+    ip_address: String,
+    /// Docker-style port map: "80/tcp" -> host bindings
+    #[serde(rename = "Ports")]
+    ports: BTreeMap<String, Vec<InspectPortBindingPresenter>>,
+    /// Effective capabilities of the box process, e.g. "CAP_CHOWN"
+    #[serde(rename = "Capabilities")]
+    capabilities: Vec<String>,
+    /// "KEY=VALUE" entries; secret-looking values are redacted
+    #[serde(rename = "Env")]
+    env: Vec<String>,
+    /// Effective main process of the last start (Docker's `Config` subset)
+    #[serde(rename = "Config", skip_serializing_if = "Option::is_none")]
+    config: Option<InspectConfigPresenter>,
+    /// Init pipeline tasks of the last start, in execution order
+    #[serde(rename = "InitStages")]
+    init_stages: Vec<InspectInitStagePresenter>,
+    /// Guest-reported boot breakdown of the last start
+    #[serde(rename = "BootPhases", skip_serializing_if = "Option::is_none")]
+    boot_phases: Option<InspectBootPhasesPresenter>,
+}
+
+#[derive(Debug, Serialize)]
+struct InspectConfigPresenter {
+    #[serde(rename = "Entrypoint")]
+    entrypoint: Vec<String>,
+    #[serde(rename = "Cmd")]
+    cmd: Vec<String>,
+    #[serde(rename = "WorkingDir")]
+    working_dir: String,
+    #[serde(rename = "User")]
+    user: String,
+}
+
+#[derive(Debug, Serialize)]
+struct InspectBootPhasesPresenter {
+    #[serde(rename = "KernelMs")]
+    kernel_ms: u64,
+    #[serde(rename = "AgentStartupMs")]
+    agent_startup_ms: u64,
+    #[serde(rename = "MountMs")]
+    mount_ms: u64,
+    #[serde(rename = "NetworkMs")]
+    network_ms: u64,
+    #[serde(rename = "ContainerInitMs")]
+    container_init_ms: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct InspectInitStagePresenter {
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "DurationMs")]
+    duration_ms: u64,
+    #[serde(rename = "Outcome")]
+    outcome: String,
+}
+
+#[derive(Debug, Serialize)]
+struct InspectPortBindingPresenter {
+    #[serde(rename = "HostIp")]
+    host_ip: String,
+    #[serde(rename = "HostPort")]
+    host_port: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -49,8 +121,23 @@ struct InspectStatePresenter {
     status: String,
     #[serde(rename = "Running")]
     running: bool,
+    #[serde(rename = "OOMKilled")]
+    oom_killed: bool,
     #[serde(rename = "Pid")]
     pid: u32,
+    #[serde(rename = "Health", skip_serializing_if = "Option::is_none")]
+    health: Option<InspectHealthPresenter>,
+}
+
+#[derive(Debug, Serialize)]
+struct InspectHealthPresenter {
+    #[serde(rename = "Status")]
+    status: String,
+    #[serde(rename = "FailingStreak")]
+    failing_streak: u32,
+    /// RFC 3339 time of the last answered heartbeat, empty if none yet
+    #[serde(rename = "LastHeartbeat")]
+    last_heartbeat: String,
 }
 
 impl From<&BoxInfo> for InspectPresenter {
@@ -65,14 +152,76 @@ impl From<&BoxInfo> for InspectPresenter {
             state: InspectStatePresenter {
                 status: state.status.as_str().to_string(),
                 running: state.running,
+                oom_killed: state.oom_killed,
                 pid: state.pid.unwrap_or(0),
+                health: info.health.as_ref().map(|h| InspectHealthPresenter {
+                    status: h.status.as_str().to_string(),
+                    failing_streak: h.failing_streak,
+                    last_heartbeat: h.last_heartbeat.map(|t| t.to_rfc3339()).unwrap_or_default(),
+                }),
             },
             cpus: info.cpus,
             memory: info.memory_mib as u64 * 1024 * 1024,
+            hostname: info.hostname.clone(),
+            mac_address: info.mac_address.clone().unwrap_or_default(),
+            ip_address: info.ip_address.clone().unwrap_or_default(),
+            ports: port_bindings(&info.ports),
+            capabilities: info.capabilities.clone(),
+            env: info
+                .env
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect(),
+            config: info.process.as_ref().map(|process| InspectConfigPresenter {
+                entrypoint: process.entrypoint.clone(),
+                cmd: process.cmd.clone(),
+                working_dir: process.working_dir.clone(),
+                user: process.user.clone(),
+            }),
+            init_stages: info
+                .init_stages
+                .iter()
+                .map(|stage| InspectInitStagePresenter {
+                    name: stage.name.clone(),
+                    duration_ms: stage.duration_ms as u64,
+                    outcome: stage.outcome.as_str().to_string(),
+                })
+                .collect(),
+            boot_phases: info
+                .boot_phases
+                .as_ref()
+                .map(|boot| InspectBootPhasesPresenter {
+                    kernel_ms: boot.kernel_ms,
+                    agent_startup_ms: boot.agent_startup_ms,
+                    mount_ms: boot.mount_ms,
+                    network_ms: boot.network_ms,
+                    container_init_ms: boot.container_init_ms,
+                }),
         }
     }
 }
 
+fn port_bindings(ports: &[PortSpec]) -> BTreeMap<String, Vec<InspectPortBindingPresenter>> {
+    let mut bindings: BTreeMap<String, Vec<InspectPortBindingPresenter>> = BTreeMap::new();
+    for port in ports {
+        let protocol = match port.protocol {
+            PortProtocol::Tcp => "tcp",
+            PortProtocol::Udp => "udp",
+        };
+        bindings
+            .entry(format!("{}/{}", port.guest_port, protocol))
+            .or_default()
+            .push(InspectPortBindingPresenter {
+                host_ip: port
+                    .host_ip
+                    .clone()
+                    .unwrap_or_else(|| "0.0.0.0".to_string()),
+                host_port: port.host_port.unwrap_or(port.guest_port).to_string(),
+            });
+    }
+    bindings
+}
+
 pub async fn execute(args: InspectArgs, global: &GlobalFlags) -> anyhow::Result<()> {
     if !args.latest && args.boxes.is_empty() {
         return Err(anyhow::anyhow!("no names or ids specified"));
@@ -155,6 +304,7 @@ async fn resolve_inspect_infos(
 ) -> anyhow::Result<(Vec<boxlite::BoxInfo>, Vec<anyhow::Error>)> {
     if args.latest {
         let mut list = rt.list_info().await?;
+        #[allow(clippy::unnecessary_sort_by)]
         list.sort_by(|a, b| b.created_at.cmp(&a.created_at));
         match list.into_iter().next() {
             Some(info) => Ok((vec![info], Vec::new())),
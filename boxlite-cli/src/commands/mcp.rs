@@ -0,0 +1,544 @@
+//! `boxlite mcp serve`: a Model Context Protocol server on stdin/stdout.
+//!
+//! Speaks JSON-RPC 2.0 with one message per line, as MCP's stdio transport
+//! does, and offers tools to create boxes, run commands, read and write
+//! files, and destroy boxes. Tools only see boxes created in the same
+//! session, at most `--max-boxes` at a time; when the session ends (stdin
+//! closes, or the server gets SIGINT, SIGTERM or SIGHUP) its boxes are
+//! removed.
+//!
+//! Requests are handled one at a time, in order.
+
+use std::io::Write;
+use std::time::Duration;
+
+use anyhow::{Result, anyhow, bail};
+use boxlite::{BoxCommand, BoxOptions, BoxliteRuntime, LiteBox, RootfsSpec};
+use clap::{Args, Subcommand};
+use futures::StreamExt;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::signal::unix::{SignalKind, signal};
+use tokio::sync::mpsc;
+
+use crate::cli::GlobalFlags;
+
+/// Protocol revisions this server speaks, oldest first.
+const PROTOCOL_VERSIONS: &[&str] = &["2024-11-05", "2025-03-26", "2025-06-18"];
+
+/// Bytes of each output stream returned by `exec` and `read_file`.
+const OUTPUT_LIMIT: usize = 64 * 1024;
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Model Context Protocol server for LLM agents
+#[derive(Args, Debug)]
+pub struct McpArgs {
+    #[command(subcommand)]
+    pub command: McpCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum McpCommand {
+    /// Serve MCP on stdin/stdout
+    ///
+    /// Register this command as a stdio MCP server in an agent. Boxes the
+    /// agent creates are removed when the session ends.
+    Serve(ServeArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct ServeArgs {
+    /// Most boxes the session may have at once
+    #[arg(long, default_value_t = 5, value_name = "N")]
+    pub max_boxes: usize,
+
+    /// Image for create_box calls that do not name one
+    #[arg(long, default_value = "alpine:latest", value_name = "IMAGE")]
+    pub default_image: String,
+
+    /// Seconds an exec call may run before it is killed
+    #[arg(long, default_value_t = 300, value_name = "SECONDS")]
+    pub exec_timeout: u64,
+}
+
+pub async fn execute(args: McpArgs, global: &GlobalFlags) -> Result<()> {
+    match args.command {
+        McpCommand::Serve(args) => serve(args, global).await,
+    }
+}
+
+async fn serve(args: ServeArgs, global: &GlobalFlags) -> Result<()> {
+    let mut session = Session {
+        runtime: global.create_runtime()?,
+        args,
+        boxes: Vec::new(),
+    };
+
+    // MCP clients stop servers with SIGTERM, or close the terminal (SIGHUP)
+    let mut sigterm = signal(SignalKind::terminate())?;
+    let mut sighup = signal(SignalKind::hangup())?;
+
+    // Blocking reads on a thread, so a signal can end the session meanwhile
+    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lines() {
+            let Ok(line) = line else { break };
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    loop {
+        let line = tokio::select! {
+            line = rx.recv() => match line {
+                Some(line) => line,
+                None => break,
+            },
+            _ = tokio::signal::ctrl_c() => break,
+            _ = sigterm.recv() => break,
+            _ = sighup.recv() => break,
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = session.handle(&line).await {
+            let mut stdout = std::io::stdout().lock();
+            writeln!(stdout, "{}", response)?;
+            stdout.flush()?;
+        }
+    }
+
+    session.cleanup().await;
+    Ok(())
+}
+
+/// A box created in this session.
+///
+/// The handle is kept for the whole session, so tool calls reuse the
+/// running box instead of reattaching to it each time.
+struct OwnedBox {
+    id: String,
+    name: Option<String>,
+    litebox: LiteBox,
+}
+
+struct Session {
+    runtime: BoxliteRuntime,
+    args: ServeArgs,
+    boxes: Vec<OwnedBox>,
+}
+
+/// A JSON-RPC error answer.
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl Session {
+    /// Answer one message; notifications get no answer.
+    async fn handle(&mut self, line: &str) -> Option<Value> {
+        let message: Value = match serde_json::from_str(line) {
+            Ok(message) => message,
+            Err(e) => return Some(error_response(Value::Null, PARSE_ERROR, e.to_string())),
+        };
+        let id = message.get("id").cloned();
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            // Responses to requests we never send
+            return id.map(|id| error_response(id, INVALID_REQUEST, "missing method"));
+        };
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+        let result = match method {
+            "initialize" => Ok(initialize(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tools() })),
+            "tools/call" => self.call_tool(&params).await,
+            _ if id.is_none() => return None,
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("method not found: {}", method),
+            )),
+        };
+        let id = id?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(e) => error_response(id, e.code, e.message),
+        })
+    }
+
+    /// Run a tool. Failures of the tool itself are results with `isError`,
+    /// so the agent sees them; only unknown tools are protocol errors.
+    async fn call_tool(&mut self, params: &Value) -> Result<Value, RpcError> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, "missing tool name"))?;
+        let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+
+        let result = match name {
+            "create_box" => self.create_box(parse(arguments)?).await,
+            "exec" => self.exec(parse(arguments)?).await,
+            "read_file" => self.read_file(parse(arguments)?).await,
+            "write_file" => self.write_file(parse(arguments)?).await,
+            "destroy_box" => self.destroy_box(parse(arguments)?).await,
+            "list_boxes" => self.list_boxes().await,
+            _ => {
+                return Err(RpcError::new(
+                    INVALID_PARAMS,
+                    format!("unknown tool: {}", name),
+                ));
+            }
+        };
+        Ok(match result {
+            Ok(text) => json!({ "content": [{ "type": "text", "text": text }] }),
+            Err(e) => json!({
+                "content": [{ "type": "text", "text": format!("{:#}", e) }],
+                "isError": true,
+            }),
+        })
+    }
+
+    async fn create_box(&mut self, args: CreateBoxArgs) -> Result<String> {
+        if self.boxes.len() >= self.args.max_boxes {
+            bail!(
+                "box quota reached ({} per session); destroy a box first",
+                self.args.max_boxes
+            );
+        }
+        let image = args
+            .image
+            .unwrap_or_else(|| self.args.default_image.clone());
+        let options = BoxOptions {
+            rootfs: RootfsSpec::Image(image.clone()),
+            cpus: args.cpus,
+            memory_mib: args.memory_mib,
+            working_dir: args.working_dir,
+            env: args.env.into_iter().collect(),
+            ..Default::default()
+        };
+        let litebox = self.runtime.create(options, args.name.clone()).await?;
+        let id = litebox.id().to_string();
+        // Tracked before it starts, so a failed start is still cleaned up
+        self.boxes.push(OwnedBox {
+            id: id.clone(),
+            name: args.name,
+            litebox,
+        });
+        if let Some(owned) = self.boxes.last() {
+            owned.litebox.start().await?;
+        }
+        Ok(serde_json::to_string_pretty(
+            &json!({ "box": id, "image": image }),
+        )?)
+    }
+
+    async fn exec(&self, args: ExecToolArgs) -> Result<String> {
+        let litebox = self.owned_box(&args.r#box).await?;
+        let mut command = match (args.command, args.args) {
+            (Some(script), None) => BoxCommand::new("/bin/sh").args(["-c", script.as_str()]),
+            (None, Some(argv)) if !argv.is_empty() => BoxCommand::new(&argv[0]).args(&argv[1..]),
+            _ => bail!("give either command (a shell script) or args (a non-empty argv)"),
+        };
+        for (key, value) in args.env {
+            command = command.env(key, value);
+        }
+        if let Some(dir) = args.working_dir {
+            command = command.working_dir(dir);
+        }
+        let timeout = args.timeout_secs.unwrap_or(self.args.exec_timeout);
+        command = command.timeout(Duration::from_secs(timeout));
+
+        let output = run(litebox, command, args.stdin.as_deref()).await?;
+        Ok(serde_json::to_string_pretty(&json!({
+            "exit_code": output.exit_code,
+            "stdout": output.stdout,
+            "stderr": output.stderr,
+        }))?)
+    }
+
+    async fn read_file(&self, args: ReadFileArgs) -> Result<String> {
+        let litebox = self.owned_box(&args.r#box).await?;
+        let command = BoxCommand::new("cat").args(["--", args.path.as_str()]);
+        let output = run(litebox, command, None).await?;
+        if output.exit_code != 0 {
+            bail!("cannot read {}: {}", args.path, output.stderr.trim());
+        }
+        Ok(output.stdout)
+    }
+
+    async fn write_file(&self, args: WriteFileArgs) -> Result<String> {
+        let litebox = self.owned_box(&args.r#box).await?;
+        let command = BoxCommand::new("/bin/sh").args([
+            "-c",
+            r#"mkdir -p "$(dirname "$1")" && cat > "$1""#,
+            "sh",
+            args.path.as_str(),
+        ]);
+        let output = run(litebox, command, Some(&args.content)).await?;
+        if output.exit_code != 0 {
+            bail!("cannot write {}: {}", args.path, output.stderr.trim());
+        }
+        Ok(format!(
+            "wrote {} bytes to {}",
+            args.content.len(),
+            args.path
+        ))
+    }
+
+    async fn destroy_box(&mut self, args: BoxArgs) -> Result<String> {
+        let index = self.owned_index(&args.r#box)?;
+        let owned = self.boxes.remove(index);
+        self.runtime.remove(&owned.id, true).await?;
+        Ok(format!("destroyed {}", owned.id))
+    }
+
+    async fn list_boxes(&self) -> Result<String> {
+        let mut boxes = Vec::new();
+        for owned in &self.boxes {
+            let status = match self.runtime.get_info(&owned.id).await? {
+                Some(info) => info.status.as_str().to_string(),
+                None => "removed".to_string(),
+            };
+            boxes.push(json!({ "box": owned.id, "name": owned.name, "status": status }));
+        }
+        Ok(serde_json::to_string_pretty(&json!({
+            "boxes": boxes,
+            "quota": self.args.max_boxes,
+        }))?)
+    }
+
+    /// Index of a session box by ID or name.
+    fn owned_index(&self, target: &str) -> Result<usize> {
+        self.boxes
+            .iter()
+            .position(|owned| owned.id == target || owned.name.as_deref() == Some(target))
+            .ok_or_else(|| anyhow!("no box '{}' in this session (see list_boxes)", target))
+    }
+
+    async fn owned_box(&self, target: &str) -> Result<&LiteBox> {
+        let owned = &self.boxes[self.owned_index(target)?];
+        if self.runtime.get_info(&owned.id).await?.is_none() {
+            bail!("box '{}' no longer exists", target);
+        }
+        Ok(&owned.litebox)
+    }
+
+    /// Remove every box the session created.
+    async fn cleanup(&mut self) {
+        for owned in self.boxes.drain(..) {
+            if let Err(e) = self.runtime.remove(&owned.id, true).await {
+                eprintln!("Warning: failed to remove box {}: {}", owned.id, e);
+            }
+        }
+    }
+}
+
+fn initialize(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = requested
+        .filter(|v| PROTOCOL_VERSIONS.contains(v))
+        .unwrap_or(PROTOCOL_VERSIONS[PROTOCOL_VERSIONS.len() - 1]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": {} },
+        "serverInfo": { "name": "boxlite", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": "Create a sandbox box with create_box, run commands in it with exec, \
+                         and destroy it when done. Boxes are removed when the session ends.",
+    })
+}
+
+fn error_response(id: Value, code: i64, message: impl Into<String>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message.into() },
+    })
+}
+
+fn parse<T: serde::de::DeserializeOwned>(arguments: Value) -> Result<T, RpcError> {
+    serde_json::from_value(arguments)
+        .map_err(|e| RpcError::new(INVALID_PARAMS, format!("invalid arguments: {}", e)))
+}
+
+fn tools() -> Value {
+    let target =
+        json!({ "type": "string", "description": "ID or name of a box created in this session" });
+    let env = json!({ "type": "object", "additionalProperties": { "type": "string" } });
+    json!([
+        {
+            "name": "create_box",
+            "description": "Create and start an isolated Linux sandbox (a micro-VM running an OCI image). Returns its ID.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "image": { "type": "string", "description": "OCI image, e.g. python:3.12-slim" },
+                    "name": { "type": "string" },
+                    "cpus": { "type": "integer", "minimum": 1 },
+                    "memory_mib": { "type": "integer", "minimum": 128 },
+                    "working_dir": { "type": "string" },
+                    "env": env.clone(),
+                },
+            },
+        },
+        {
+            "name": "exec",
+            "description": "Run a command in a box and return its exit code, stdout and stderr.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "box": target.clone(),
+                    "command": { "type": "string", "description": "Shell script run with /bin/sh -c" },
+                    "args": { "type": "array", "items": { "type": "string" }, "description": "Program and arguments, instead of command" },
+                    "stdin": { "type": "string" },
+                    "env": env.clone(),
+                    "working_dir": { "type": "string" },
+                    "timeout_secs": { "type": "integer", "minimum": 1 },
+                },
+                "required": ["box"],
+            },
+        },
+        {
+            "name": "read_file",
+            "description": "Read a text file from a box.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "box": target.clone(), "path": { "type": "string" } },
+                "required": ["box", "path"],
+            },
+        },
+        {
+            "name": "write_file",
+            "description": "Write a text file in a box, creating parent directories.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "box": target.clone(),
+                    "path": { "type": "string" },
+                    "content": { "type": "string" },
+                },
+                "required": ["box", "path", "content"],
+            },
+        },
+        {
+            "name": "destroy_box",
+            "description": "Stop and remove a box.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "box": target.clone() },
+                "required": ["box"],
+            },
+        },
+        {
+            "name": "list_boxes",
+            "description": "List the boxes of this session and the session's box quota.",
+            "inputSchema": { "type": "object", "properties": {} },
+        },
+    ])
+}
+
+#[derive(Deserialize)]
+struct CreateBoxArgs {
+    image: Option<String>,
+    name: Option<String>,
+    cpus: Option<u8>,
+    memory_mib: Option<u32>,
+    working_dir: Option<String>,
+    #[serde(default)]
+    env: std::collections::BTreeMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct ExecToolArgs {
+    r#box: String,
+    command: Option<String>,
+    args: Option<Vec<String>>,
+    stdin: Option<String>,
+    #[serde(default)]
+    env: std::collections::BTreeMap<String, String>,
+    working_dir: Option<String>,
+    timeout_secs: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct ReadFileArgs {
+    r#box: String,
+    path: String,
+}
+
+#[derive(Deserialize)]
+struct WriteFileArgs {
+    r#box: String,
+    path: String,
+    content: String,
+}
+
+#[derive(Deserialize)]
+struct BoxArgs {
+    r#box: String,
+}
+
+struct Output {
+    exit_code: i32,
+    stdout: String,
+    stderr: String,
+}
+
+/// Run `command` to completion, feeding it `stdin`, and collect its output.
+async fn run(litebox: &LiteBox, command: BoxCommand, stdin: Option<&str>) -> Result<Output> {
+    let mut execution = litebox.exec(command).await?;
+    if let Some(mut input) = execution.stdin() {
+        if let Some(data) = stdin {
+            input.write_all(data.as_bytes()).await?;
+        }
+        input.close();
+    }
+
+    let stdout = execution.stdout();
+    let stderr = execution.stderr();
+    let (stdout, stderr) = tokio::join!(collect(stdout), collect(stderr));
+    let result = execution.wait().await?;
+    Ok(Output {
+        exit_code: result.exit_code,
+        stdout,
+        stderr,
+    })
+}
+
+/// Read a stream to the end, keeping the first [`OUTPUT_LIMIT`] bytes.
+async fn collect<S>(stream: Option<S>) -> String
+where
+    S: futures::Stream + Unpin,
+    S::Item: AsRef<[u8]>,
+{
+    let Some(mut stream) = stream else {
+        return String::new();
+    };
+    let mut kept = Vec::new();
+    let mut dropped = 0;
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.as_ref();
+        let room = OUTPUT_LIMIT.saturating_sub(kept.len());
+        kept.extend_from_slice(&chunk[..room.min(chunk.len())]);
+        dropped += chunk.len().saturating_sub(room);
+    }
+    let mut text = String::from_utf8_lossy(&kept).into_owned();
+    if dropped > 0 {
+        text.push_str(&format!("\n... ({} more bytes)", dropped));
+    }
+    text
+}
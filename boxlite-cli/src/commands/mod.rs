@@ -1,12 +1,36 @@
+pub mod build;
+pub mod clone;
+pub mod config;
 pub mod cp;
 pub mod create;
+pub mod dash;
+pub mod debug;
+pub mod devcontainer;
 pub mod exec;
+pub mod export;
 pub mod images;
+pub mod import;
 pub mod inspect;
 pub mod list;
+pub mod mcp;
+pub mod mount;
+pub mod net;
+pub mod pause;
+pub mod port;
 pub mod pull;
+pub mod push;
+pub mod registry_cache;
 pub mod restart;
 pub mod rm;
 pub mod run;
+pub mod ssh;
 pub mod start;
+pub mod stats;
 pub mod stop;
+pub mod sync;
+pub mod system;
+pub mod tag;
+pub mod top;
+pub mod umount;
+pub mod unpause;
+pub mod wait;
@@ -0,0 +1,42 @@
+use anyhow::{Context, Result};
+use clap::Args;
+use std::path::PathBuf;
+
+use crate::cli::GlobalFlags;
+
+/// Mount a host directory into a running box
+///
+/// The mount lasts until `boxlite umount` or until the box stops. Linux hosts
+/// only: libkrun cannot add virtiofs shares to a running VM.
+#[derive(Args, Debug)]
+pub struct MountArgs {
+    /// Name or ID of the box
+    pub target: String,
+
+    /// Host directory to mount
+    pub host_path: PathBuf,
+
+    /// Absolute path inside the box
+    pub guest_path: String,
+
+    /// Mount read-only
+    #[arg(long)]
+    pub read_only: bool,
+}
+
+pub async fn execute(args: MountArgs, global: &GlobalFlags) -> Result<()> {
+    let host_path = std::fs::canonicalize(&args.host_path)
+        .with_context(|| format!("Invalid host path: {}", args.host_path.display()))?;
+
+    let runtime = global.create_runtime()?;
+    let litebox = runtime
+        .get(&args.target)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("No such box: {}", args.target))?;
+
+    litebox
+        .mount(&host_path, &args.guest_path, args.read_only)
+        .await?;
+    println!("{}", args.guest_path);
+    Ok(())
+}
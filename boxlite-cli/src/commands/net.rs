@@ -0,0 +1,178 @@
+//! `boxlite net`: network observability for boxes.
+//!
+//! `net log` lists the connections a box created with `--log-connections`
+//! opened. The log lives on the host, so this works on stopped boxes too.
+
+use std::io::Write;
+
+use anyhow::{Result, anyhow};
+use boxlite::ConnectionRecord;
+use clap::{Args, Subcommand};
+use serde::Serialize;
+use tabled::Tabled;
+
+use crate::cli::GlobalFlags;
+use crate::commands::images::format_size;
+use crate::formatter::{self, OutputFormat};
+
+/// Network observability for boxes
+#[derive(Args, Debug)]
+pub struct NetArgs {
+    #[command(subcommand)]
+    pub command: NetCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum NetCommand {
+    /// List the connections a box opened
+    ///
+    /// Connections are logged for boxes created with --log-connections,
+    /// once they end.
+    Log(LogArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct LogArgs {
+    /// Name or ID of the box
+    pub target: String,
+
+    /// Show only the last N connections
+    #[arg(short = 'n', long, value_name = "N")]
+    pub tail: Option<usize>,
+
+    /// Show only connections dropped by the network policy
+    #[arg(long)]
+    pub blocked: bool,
+
+    /// Output format (table, json, yaml)
+    #[arg(long, default_value = "table")]
+    pub format: String,
+}
+
+#[derive(Tabled, Serialize)]
+struct ConnectionPresenter {
+    #[tabled(rename = "TIME")]
+    #[serde(rename = "Time")]
+    time: String,
+
+    #[tabled(rename = "PROTO")]
+    #[serde(rename = "Protocol")]
+    protocol: String,
+
+    #[tabled(rename = "DESTINATION")]
+    #[serde(rename = "Destination")]
+    destination: String,
+
+    #[tabled(rename = "HOSTNAME")]
+    #[serde(rename = "Hostname")]
+    hostname: String,
+
+    #[tabled(rename = "SENT")]
+    #[serde(skip)]
+    sent: String,
+
+    #[tabled(rename = "RECEIVED")]
+    #[serde(skip)]
+    received: String,
+
+    #[tabled(skip)]
+    #[serde(rename = "BytesSent")]
+    bytes_sent: u64,
+
+    #[tabled(skip)]
+    #[serde(rename = "BytesReceived")]
+    bytes_received: u64,
+
+    #[tabled(rename = "DURATION")]
+    #[serde(skip)]
+    duration: String,
+
+    #[tabled(skip)]
+    #[serde(rename = "DurationMs")]
+    duration_ms: u64,
+
+    #[tabled(rename = "STATUS")]
+    #[serde(rename = "Status")]
+    status: String,
+}
+
+impl From<ConnectionRecord> for ConnectionPresenter {
+    fn from(record: ConnectionRecord) -> Self {
+        let destination = if record.dst_ip.contains(':') {
+            format!("[{}]:{}", record.dst_ip, record.dst_port)
+        } else {
+            format!("{}:{}", record.dst_ip, record.dst_port)
+        };
+        Self {
+            time: formatter::format_time(&record.time),
+            protocol: record.protocol,
+            destination,
+            hostname: record.hostname.unwrap_or_default(),
+            sent: format_size(record.bytes_sent),
+            received: format_size(record.bytes_received),
+            bytes_sent: record.bytes_sent,
+            bytes_received: record.bytes_received,
+            duration: format_duration_ms(record.duration_ms),
+            duration_ms: record.duration_ms,
+            status: if record.blocked { "blocked" } else { "allowed" }.to_string(),
+        }
+    }
+}
+
+/// `830ms`, `4.2s`, `3m05s`.
+fn format_duration_ms(ms: u64) -> String {
+    if ms < 1000 {
+        format!("{}ms", ms)
+    } else if ms < 60_000 {
+        format!("{:.1}s", ms as f64 / 1000.0)
+    } else {
+        let secs = ms / 1000;
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    }
+}
+
+pub async fn execute(args: NetArgs, global: &GlobalFlags) -> Result<()> {
+    match args.command {
+        NetCommand::Log(args) => log(args, global).await,
+    }
+}
+
+async fn log(args: LogArgs, global: &GlobalFlags) -> Result<()> {
+    let format = OutputFormat::from_str(&args.format)?;
+    let runtime = global.create_runtime()?;
+    let litebox = runtime
+        .get(&args.target)
+        .await?
+        .ok_or_else(|| anyhow!("No such box: {}", args.target))?;
+    let mut records = litebox.connection_log()?;
+    if args.blocked {
+        records.retain(|r| r.blocked);
+    }
+    if let Some(tail) = args.tail {
+        records.drain(..records.len().saturating_sub(tail));
+    }
+
+    let rows: Vec<ConnectionPresenter> =
+        records.into_iter().map(ConnectionPresenter::from).collect();
+    formatter::print_output(
+        &mut std::io::stdout().lock(),
+        &rows,
+        format,
+        |writer, rows| {
+            writeln!(writer, "{}", formatter::create_table(rows))?;
+            Ok(())
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_duration_ms() {
+        assert_eq!(format_duration_ms(830), "830ms");
+        assert_eq!(format_duration_ms(4_200), "4.2s");
+        assert_eq!(format_duration_ms(185_000), "3m05s");
+    }
+}
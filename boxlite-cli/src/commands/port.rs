@@ -0,0 +1,160 @@
+use anyhow::Result;
+use boxlite::ListeningPort;
+use boxlite::runtime::options::{PortProtocol, PortSpec};
+use clap::{Args, Subcommand};
+
+use crate::cli::{GlobalFlags, parse_publish_spec};
+
+/// List or manage port mappings of a box
+///
+/// `boxlite port BOX` lists the mappings, including host ports assigned
+/// dynamically (`-p 80`). `boxlite port --listen BOX` lists the sockets
+/// listening inside the box instead, like `ss -ltnup`.
+#[derive(Args, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct PortArgs {
+    /// Name or ID of the box (lists its port mappings)
+    pub target: Option<String>,
+
+    /// List sockets listening inside the box instead of port mappings
+    #[arg(long)]
+    pub listen: bool,
+
+    #[command(subcommand)]
+    pub command: Option<PortCommand>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PortCommand {
+    /// List port mappings
+    #[command(visible_alias = "list")]
+    Ls(PortListArgs),
+
+    /// Publish ports on a running box without restarting it
+    Add(PortChangeArgs),
+
+    /// Stop publishing ports on a running box
+    #[command(visible_alias = "remove")]
+    Rm(PortChangeArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct PortListArgs {
+    /// Name or ID of the box
+    pub target: String,
+
+    /// List sockets listening inside the box instead of port mappings
+    #[arg(long)]
+    pub listen: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct PortChangeArgs {
+    /// Name or ID of the box
+    pub target: String,
+
+    /// Port specs (format: [[hostIp:]hostPort:]boxPort[/tcp|udp])
+    #[arg(required = true, num_args = 1..)]
+    pub ports: Vec<String>,
+}
+
+pub async fn execute(args: PortArgs, global: &GlobalFlags) -> Result<()> {
+    let command = match (args.command, args.target) {
+        (Some(command), _) => command,
+        (None, Some(target)) => PortCommand::Ls(PortListArgs {
+            target,
+            listen: args.listen,
+        }),
+        (None, None) => anyhow::bail!("box name or ID required"),
+    };
+    let runtime = global.create_runtime()?;
+    let target = match &command {
+        PortCommand::Ls(a) => &a.target,
+        PortCommand::Add(a) | PortCommand::Rm(a) => &a.target,
+    };
+    let litebox = runtime
+        .get(target)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("No such box: {}", target))?;
+
+    match command {
+        PortCommand::Ls(list) if list.listen => {
+            for port in &litebox.listening_ports().await? {
+                println!("{}", format_listening(port));
+            }
+        }
+        PortCommand::Ls(_) => {
+            let mut ports = litebox.ports().await?;
+            ports.sort_by_key(|p| (p.guest_port, p.host_port));
+            for port in &ports {
+                println!("{}", format_port(port));
+            }
+        }
+        PortCommand::Add(change) => {
+            for spec in &change.ports {
+                let port = litebox.publish_port(parse_publish_spec(spec)?).await?;
+                println!("{}", format_port(&port));
+            }
+        }
+        PortCommand::Rm(change) => {
+            for spec in &change.ports {
+                let port = parse_publish_spec(spec)?;
+                litebox.unpublish_port(port.clone()).await?;
+                println!("{}", format_port(&port));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Format like `docker port`: `80/tcp -> 0.0.0.0:8080`.
+fn format_port(port: &PortSpec) -> String {
+    let protocol = match port.protocol {
+        PortProtocol::Tcp => "tcp",
+        PortProtocol::Udp => "udp",
+    };
+    let host_ip = port.host_ip.as_deref().unwrap_or("0.0.0.0");
+    let host_port = port.host_port.unwrap_or(port.guest_port);
+    let host = if host_ip.contains(':') {
+        format!("[{}]:{}", host_ip, host_port)
+    } else {
+        format!("{}:{}", host_ip, host_port)
+    };
+    format!("{}/{} -> {}", port.guest_port, protocol, host)
+}
+
+/// Format like `ss`: `tcp 0.0.0.0:8080 12/python3`.
+fn format_listening(port: &ListeningPort) -> String {
+    let protocol = match port.protocol {
+        PortProtocol::Tcp => "tcp",
+        PortProtocol::Udp => "udp",
+    };
+    let local = std::net::SocketAddr::new(port.address, port.port);
+    format!("{} {} {}/{}", protocol, local, port.pid, port.command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_port() {
+        let port = parse_publish_spec("8080:80").unwrap();
+        assert_eq!(format_port(&port), "80/tcp -> 0.0.0.0:8080");
+
+        let port = parse_publish_spec("[::1]:5353:53/udp").unwrap();
+        assert_eq!(format_port(&port), "53/udp -> [::1]:5353");
+    }
+
+    #[test]
+    fn test_format_listening() {
+        let port = ListeningPort {
+            protocol: PortProtocol::Udp,
+            address: "::".parse().unwrap(),
+            port: 53,
+            pid: 7,
+            command: "dnsmasq".to_string(),
+        };
+        assert_eq!(format_listening(&port), "udp [::]:53 7/dnsmasq");
+    }
+}
@@ -1,12 +1,18 @@
-use anyhow::Result;
+use anyhow::{Result, bail};
 use clap::Args;
+use tokio::sync::mpsc;
 
 use crate::cli::GlobalFlags;
 
 #[derive(Args, Debug)]
 pub struct PullArgs {
     /// Image to pull
-    pub image: String,
+    #[arg(required_unless_present = "all_templates")]
+    pub image: Option<String>,
+
+    /// Pull and prepare the images of every template in --config
+    #[arg(long, conflicts_with = "image")]
+    pub all_templates: bool,
 
     /// Quiet mode - only show digest
     #[arg(short, long)]
@@ -14,9 +20,27 @@ pub struct PullArgs {
 }
 
 pub async fn execute(args: PullArgs, global: &GlobalFlags) -> Result<()> {
+    if args.all_templates {
+        return prefetch_templates(args.quiet, global).await;
+    }
+    let Some(image_ref) = args.image else {
+        bail!("an image is required");
+    };
     let runtime = global.create_runtime()?;
 
-    let image = runtime.pull_image(&args.image).await?;
+    let image = if args.quiet {
+        runtime.pull_image(&image_ref).await?
+    } else {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let printer = tokio::spawn(async move {
+            while let Some(line) = rx.recv().await {
+                println!("{}", line);
+            }
+        });
+        let result = runtime.pull_image_with_progress(&image_ref, tx).await;
+        let _ = printer.await;
+        result?
+    };
     if args.quiet {
         println!("{}", image.config_digest());
     } else {
@@ -27,3 +51,38 @@ pub async fn execute(args: PullArgs, global: &GlobalFlags) -> Result<()> {
 
     Ok(())
 }
+
+/// Warm the cache for every template image, reporting each one.
+async fn prefetch_templates(quiet: bool, global: &GlobalFlags) -> Result<()> {
+    let options = global.runtime_options()?;
+    let images = options.template_images();
+    if images.is_empty() {
+        bail!("no templates with an image in the configuration (see --config)");
+    }
+    let runtime = boxlite::BoxliteRuntime::new(options)?;
+
+    if !quiet {
+        println!("Prefetching {} template image(s)", images.len());
+    }
+    let results = runtime.prefetch(images).await?;
+
+    let mut failed = 0;
+    for prefetched in &results {
+        match &prefetched.result {
+            Ok(()) if !quiet => println!(
+                "Prepared: {} ({:.1}s)",
+                prefetched.image,
+                prefetched.elapsed.as_secs_f64()
+            ),
+            Ok(()) => {}
+            Err(e) => {
+                failed += 1;
+                eprintln!("Failed: {}: {}", prefetched.image, e);
+            }
+        }
+    }
+    if failed > 0 {
+        bail!("{} of {} template image(s) failed", failed, results.len());
+    }
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use anyhow::Result;
+use clap::Args;
+use tokio::sync::mpsc;
+
+use crate::cli::GlobalFlags;
+
+#[derive(Args, Debug)]
+pub struct PushArgs {
+    /// Image to push (must be in the local image store)
+    pub image: String,
+
+    /// Quiet mode - only show the pushed reference
+    #[arg(short, long)]
+    pub quiet: bool,
+}
+
+pub async fn execute(args: PushArgs, global: &GlobalFlags) -> Result<()> {
+    let runtime = global.create_runtime()?;
+
+    let reference = if args.quiet {
+        runtime.push_image(&args.image).await?
+    } else {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let printer = tokio::spawn(async move {
+            while let Some(line) = rx.recv().await {
+                println!("{}", line);
+            }
+        });
+        let result = runtime.push_image_with_progress(&args.image, tx).await;
+        let _ = printer.await;
+        result?
+    };
+
+    if args.quiet {
+        println!("{}", reference);
+    }
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use std::net::SocketAddr;
+
+use anyhow::Result;
+use boxlite::{BoxliteRuntime, RegistryCacheOptions};
+use clap::{Args, Subcommand};
+
+use crate::cli::GlobalFlags;
+
+/// Run a pull-through registry cache for other BoxLite hosts
+#[derive(Args, Debug)]
+pub struct RegistryCacheArgs {
+    #[command(subcommand)]
+    pub command: RegistryCacheCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum RegistryCacheCommand {
+    /// Serve cached images over the OCI distribution API (read-only)
+    ///
+    /// Point other hosts at it with `--registry-mirror docker.io=http://HOST:PORT`
+    /// (listen on a non-loopback address for that). Clients without --token
+    /// get anonymous pulls only. Send SIGHUP to re-read --config and apply
+    /// changed registry settings.
+    Serve(ServeArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct ServeArgs {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:5000")]
+    pub listen: SocketAddr,
+
+    /// Upstream registry for clients that do not name one
+    #[arg(long, default_value = "docker.io")]
+    pub upstream: String,
+
+    /// Access token that lets clients use this host's registry credentials
+    /// and its local image store (sent as a bearer token or basic-auth password)
+    #[arg(long, env = "BOXLITE_REGISTRY_CACHE_TOKEN", hide_env_values = true)]
+    pub token: Option<String>,
+}
+
+pub async fn execute(args: RegistryCacheArgs, global: &GlobalFlags) -> Result<()> {
+    match args.command {
+        RegistryCacheCommand::Serve(serve) => {
+            let runtime = global.create_runtime()?;
+            let options = RegistryCacheOptions {
+                listen: serve.listen,
+                upstream: serve.upstream,
+                token: serve.token,
+            };
+
+            println!("Serving registry cache on {}", options.listen);
+            tokio::select! {
+                result = runtime.serve_registry_cache(options) => result?,
+                result = reload_on_hangup(&runtime, global) => result?,
+                _ = tokio::signal::ctrl_c() => {}
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Re-read --config on SIGHUP and apply what can change without a restart.
+#[cfg(unix)]
+async fn reload_on_hangup(runtime: &BoxliteRuntime, global: &GlobalFlags) -> Result<()> {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut hangup = signal(SignalKind::hangup())?;
+    while hangup.recv().await.is_some() {
+        let reloaded = global
+            .runtime_options()
+            .and_then(|options| Ok(runtime.reload_options(options)?));
+        match reloaded {
+            Ok(changes) => println!("Reloaded configuration: {}", changes),
+            Err(e) => eprintln!("Warning: configuration not reloaded: {:#}", e),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+async fn reload_on_hangup(_runtime: &BoxliteRuntime, _global: &GlobalFlags) -> Result<()> {
+    std::future::pending().await
+}
@@ -1,3 +1,4 @@
+use crate::cli::BatchFlags;
 use clap::Args;
 
 #[derive(Args, Debug)]
@@ -10,8 +11,15 @@ pub struct RmArgs {
     #[arg(short, long)]
     pub all: bool,
 
+    #[command(flatten)]
+    pub batch: BatchFlags,
+
     /// Name or ID of the box(es) to remove
-    #[arg(required_unless_present = "all", num_args = 1..)]
+    #[arg(
+        num_args = 1..,
+        required_unless_present_any = ["all", "filter"],
+        conflicts_with_all = ["all", "filter"]
+    )]
     pub targets: Vec<String>,
 }
 
@@ -30,29 +38,15 @@ pub async fn execute(args: RmArgs, global: &crate::cli::GlobalFlags) -> anyhow::
         }
     }
 
-    let targets = if args.all {
-        runtime
-            .list_info()
-            .await?
-            .into_iter()
-            .map(|info| info.id.to_string())
-            .collect()
-    } else {
-        args.targets
-    };
-
-    let mut active_error = false;
-    for target in targets {
-        if let Err(e) = runtime.remove(&target, args.force).await {
-            eprintln!("Error removing box '{}': {}", target, e);
-            active_error = true;
-        } else {
-            println!("{}", target);
-        }
-    }
-
-    if active_error {
-        anyhow::bail!("Some boxes could not be removed");
-    }
-    Ok(())
+    let force = args.force;
+    let targets = args.batch.select(&runtime, args.targets, args.all).await?;
+    args.batch
+        .run(targets, "remove", |target| {
+            let runtime = &runtime;
+            async move {
+                runtime.remove(&target, force).await?;
+                Ok(())
+            }
+        })
+        .await
 }
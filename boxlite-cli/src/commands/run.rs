@@ -1,12 +1,17 @@
 use crate::cli::{
-    GlobalFlags, ManagementFlags, ProcessFlags, PublishFlags, ResourceFlags, VolumeFlags,
+    DebugFlags, GlobalFlags, KernelFlags, ManagementFlags, NetworkFlags, ProcessFlags,
+    PublishFlags, ResourceFlags, SecurityFlags, SshFlags, VolumeFlags,
 };
 use crate::terminal::StreamManager;
 use crate::util::to_shell_exit_code;
 use boxlite::BoxCommand;
-use boxlite::{BoxOptions, BoxliteRuntime, LiteBox, RootfsSpec};
+use boxlite::{BoxOptions, BoxPlan, BoxliteRuntime, LiteBox, RootfsSpec};
 use clap::Args;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
 use std::io::{self, IsTerminal};
+use std::path::Path;
+use tokio::sync::mpsc;
 
 #[derive(Args, Debug)]
 pub struct RunArgs {
@@ -22,11 +27,46 @@ pub struct RunArgs {
     #[command(flatten)]
     pub volume: VolumeFlags,
 
+    #[command(flatten)]
+    pub network: NetworkFlags,
+
+    #[command(flatten)]
+    pub ssh: SshFlags,
+
+    #[command(flatten)]
+    pub security: SecurityFlags,
+
+    #[command(flatten)]
+    pub kernel: KernelFlags,
+
+    #[command(flatten)]
+    pub debug: DebugFlags,
+
     #[command(flatten)]
     pub management: ManagementFlags,
 
-    #[arg(index = 1)]
-    pub image: String,
+    /// Install packages with the image's package manager (apk, apt-get or dnf)
+    /// before running; the result is cached as a local image for reuse
+    #[arg(long, value_name = "PKG,...", value_delimiter = ',')]
+    pub provision: Vec<String>,
+
+    /// Run in a warm box kept for this image and these options, creating it on
+    /// first use; each run gets a fresh copy of the box's filesystem
+    #[arg(long, conflicts_with_all = ["name", "detach"])]
+    pub reuse: bool,
+
+    /// Use this root filesystem directory instead of an image; the box writes
+    /// to a copy, never to the directory itself
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["provision", "reuse"])]
+    pub rootfs: Option<std::path::PathBuf>,
+
+    /// Pull the image and prepare its base disk, then exit without creating a
+    /// box; prints the image reference a later run starts from
+    #[arg(long, conflicts_with_all = ["rootfs", "reuse", "detach", "dry_run"])]
+    pub pull_only: bool,
+
+    #[arg(index = 1, required_unless_present = "rootfs")]
+    pub image: Option<String>,
 
     /// Command to run inside the image
     #[arg(index = 2, trailing_var_arg = true)]
@@ -39,24 +79,58 @@ pub async fn execute(args: RunArgs, global: &GlobalFlags) -> anyhow::Result<()>
     runner.run().await
 }
 
+/// Dry-run output of `run`: the box plan plus the command it would execute.
+#[derive(Serialize)]
+struct RunPlan<'a> {
+    #[serde(flatten)]
+    plan: BoxPlan,
+    command: Vec<&'a str>,
+}
+
 struct BoxRunner {
     args: RunArgs,
     rt: BoxliteRuntime,
-    home: Option<std::path::PathBuf>,
+    volumes_dir: std::path::PathBuf,
 }
 
 impl BoxRunner {
-    fn new(args: RunArgs, global: &GlobalFlags) -> anyhow::Result<Self> {
+    fn new(mut args: RunArgs, global: &GlobalFlags) -> anyhow::Result<Self> {
+        // Without an image the first positional argument starts the command
+        if args.rootfs.is_some()
+            && let Some(program) = args.image.take()
+        {
+            args.command.insert(0, program);
+        }
         let rt = global.create_runtime()?;
-        let home = global.home.clone();
+        let volumes_dir = global.volumes_dir()?;
 
-        Ok(Self { args, rt, home })
+        Ok(Self {
+            args,
+            rt,
+            volumes_dir,
+        })
     }
 
     async fn run(&mut self) -> anyhow::Result<()> {
         // Validate flags and environment
         self.validate_flags()?;
 
+        if self.args.management.dry_run {
+            return self.print_plan().await;
+        }
+
+        if !self.args.provision.is_empty() {
+            self.provision_image().await?;
+        }
+
+        if self.args.pull_only {
+            return self.pull_only().await;
+        }
+
+        if self.args.reuse {
+            return self.run_reused().await;
+        }
+
         let litebox = self.create_box().await?;
 
         // Start execution
@@ -69,6 +143,13 @@ impl BoxRunner {
             return Ok(());
         }
 
+        // Recordings under the box home get a fresh directory; say where
+        if self.args.process.output_dir.is_none()
+            && let Some(dir) = execution.recording_dir()
+        {
+            eprintln!("Recording output to {}", dir.display());
+        }
+
         // IO streaming and signal handling via shared StreamManager
         let streamer = StreamManager::new(
             &mut execution,
@@ -85,36 +166,146 @@ impl BoxRunner {
         Ok(())
     }
 
+    /// `--reuse`: run in the warm box, on a fresh copy of its filesystem.
+    ///
+    /// The guest gives the command a throwaway overlay of the box's rootfs,
+    /// so only writes to volumes reach the next run.
+    async fn run_reused(&self) -> anyhow::Result<()> {
+        let litebox = self.warm_box().await?;
+
+        let mut execution = litebox
+            .exec(self.prepare_command().fresh_rootfs(true))
+            .await?;
+        let exit_code = StreamManager::new(
+            &mut execution,
+            self.args.process.interactive,
+            self.args.process.tty,
+        )
+        .start()
+        .await?;
+        if exit_code != 0 {
+            std::process::exit(to_shell_exit_code(exit_code));
+        }
+        Ok(())
+    }
+
+    /// Get the warm box for `--reuse`, creating it if needed.
+    ///
+    /// Runs with the same image and options share a box, found by a name
+    /// derived from the options. The box is detached and never auto-removed,
+    /// so it stays up for the next run; remove it with `boxlite rm`.
+    async fn warm_box(&self) -> anyhow::Result<LiteBox> {
+        let options = self.box_options()?;
+        let name = reuse_box_name(&options)?;
+        let (litebox, created) = self.rt.get_or_create(options, Some(name.clone())).await?;
+        if created {
+            eprintln!("Created warm box {} for --reuse", name);
+        }
+        Ok(litebox)
+    }
+
+    /// `--pull-only`: warm the cache the way the first boot would, no box.
+    async fn pull_only(&self) -> anyhow::Result<()> {
+        let image = self.args.image.clone().unwrap_or_default();
+        for prefetched in self.rt.prefetch(vec![image]).await? {
+            prefetched.result?;
+            println!("{}", prefetched.image);
+        }
+        Ok(())
+    }
+
+    /// Install the `--provision` packages into a cached image and run from it.
+    async fn provision_image(&mut self) -> anyhow::Result<()> {
+        let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+        let printer = tokio::spawn(async move {
+            while let Some(line) = rx.recv().await {
+                eprintln!("{}", line.trim_end());
+            }
+        });
+        let result = self
+            .rt
+            .provision_image(
+                self.args.image.as_deref().unwrap_or_default(),
+                &self.args.provision,
+                Some(tx),
+            )
+            .await;
+        let _ = printer.await;
+
+        self.args.image = Some(result?.reference);
+        Ok(())
+    }
+
     async fn create_box(&self) -> anyhow::Result<LiteBox> {
+        let litebox = self
+            .rt
+            .create(self.box_options()?, self.args.management.name.clone())
+            .await?;
+
+        Ok(litebox)
+    }
+
+    /// Print what `run` would do (box plan plus command) as JSON.
+    async fn print_plan(&self) -> anyhow::Result<()> {
+        let options = self.box_options()?;
+        let name = if self.args.reuse {
+            Some(reuse_box_name(&options)?)
+        } else {
+            self.args.management.name.clone()
+        };
+        let plan = self.rt.plan(options, name).await?;
+        let (program, args) = parse_command_args(&self.args.command);
+        let plan = RunPlan {
+            plan,
+            command: std::iter::once(program)
+                .chain(args.iter().map(String::as_str))
+                .collect(),
+        };
+        println!("{}", serde_json::to_string_pretty(&plan)?);
+        Ok(())
+    }
+
+    fn box_options(&self) -> anyhow::Result<BoxOptions> {
         let mut options = BoxOptions::default();
         self.args.resource.apply_to(&mut options);
         self.args.management.apply_to(&mut options);
         self.args.publish.apply_to(&mut options)?;
-        self.args
-            .volume
-            .apply_to(&mut options, self.home.as_deref())?;
+        self.args.network.apply_to(&mut options)?;
+        self.args.ssh.apply_to(&mut options)?;
+        self.args.security.apply_to(&mut options)?;
+        self.args.kernel.apply_to(&mut options)?;
+        self.args.debug.apply_to(&mut options);
+        self.args.volume.apply_to(&mut options, &self.volumes_dir)?;
         self.args.process.apply_to(&mut options)?;
+        self.args.management.apply_profile(&mut options)?;
 
-        // Runtime requires detached boxes to have manual lifecycle control (auto_remove=false)
-        if self.args.management.detach {
+        // The warm box of `--reuse` outlives this run
+        if self.args.reuse {
+            options.detach = true;
             options.auto_remove = false;
         }
 
-        options.rootfs = RootfsSpec::Image(self.args.image.clone());
-
-        let litebox = self
-            .rt
-            .create(options, self.args.management.name.clone())
-            .await?;
-
-        Ok(litebox)
+        options.rootfs = match (&self.args.rootfs, &self.args.image) {
+            (Some(dir), _) => RootfsSpec::RootfsPath(rootfs_dir(dir)?),
+            (None, Some(image)) => RootfsSpec::Image(image.clone()),
+            (None, None) => anyhow::bail!("an image or --rootfs is required"),
+        };
+        Ok(options)
     }
 
     fn prepare_command(&self) -> BoxCommand {
         let (program, args) = parse_command_args(&self.args.command);
-        BoxCommand::new(program)
+        // With `--rm -d` nobody waits for the command, so the box stops (and
+        // is removed) by itself when it exits
+        let detached_rm = self.args.management.rm && self.args.management.detach;
+        let cmd = BoxCommand::new(program)
             .args(args)
             .tty(self.args.process.tty)
+            .stop_box_on_exit(detached_rm);
+        match self.args.process.recording() {
+            Some(recording) => cmd.record_output(recording),
+            None => cmd,
+        }
     }
 
     fn validate_flags(&self) -> anyhow::Result<()> {
@@ -123,10 +314,45 @@ impl BoxRunner {
             anyhow::bail!("the input device is not a TTY.");
         }
 
+        self.args
+            .process
+            .validate_recording(self.args.management.detach)?;
+        if self.args.management.rm
+            && self.args.process.output_dir.is_none()
+            && self.args.process.record
+        {
+            anyhow::bail!(
+                "--record keeps output in the box home, which --rm removes; use --output-dir"
+            );
+        }
+
         Ok(())
     }
 }
 
+/// Name of the `--reuse` warm box for `options` (image included).
+///
+/// A digest of the options, so any difference in resources, volumes, env
+/// or image gets its own box.
+fn reuse_box_name(options: &BoxOptions) -> anyhow::Result<String> {
+    let digest = Sha256::digest(serde_json::to_vec(options)?);
+    Ok(format!("reuse-{}", &format!("{:x}", digest)[..12]))
+}
+
+/// Absolute path of the `--rootfs` directory, checked to be one.
+fn rootfs_dir(path: &Path) -> anyhow::Result<String> {
+    let dir = path
+        .canonicalize()
+        .map_err(|e| anyhow::anyhow!("--rootfs {}: {}", path.display(), e))?;
+    if !dir.is_dir() {
+        anyhow::bail!("--rootfs {} is not a directory", path.display());
+    }
+    if dir.read_dir()?.next().is_none() {
+        anyhow::bail!("--rootfs {} is empty", path.display());
+    }
+    Ok(dir.to_string_lossy().into_owned())
+}
+
 fn parse_command_args(input: &[String]) -> (&str, &[String]) {
     if input.is_empty() {
         ("sh", &[])
@@ -153,4 +379,36 @@ mod tests {
             ("echo", &["hello".to_string()] as &[String])
         );
     }
+
+    #[test]
+    fn test_rootfs_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(rootfs_dir(dir.path()).is_err(), "empty directory");
+
+        std::fs::create_dir(dir.path().join("bin")).unwrap();
+        let path = rootfs_dir(dir.path()).unwrap();
+        assert!(Path::new(&path).is_absolute());
+
+        assert!(rootfs_dir(&dir.path().join("bin/sh")).is_err());
+        std::fs::write(dir.path().join("file"), "").unwrap();
+        assert!(rootfs_dir(&dir.path().join("file")).is_err());
+    }
+
+    #[test]
+    fn test_reuse_box_name_follows_options() {
+        let options = BoxOptions {
+            rootfs: RootfsSpec::Image("alpine:latest".into()),
+            ..Default::default()
+        };
+        let name = reuse_box_name(&options).unwrap();
+        assert!(name.starts_with("reuse-"));
+        assert_eq!(name.len(), "reuse-".len() + 12);
+        assert_eq!(reuse_box_name(&options.clone()).unwrap(), name);
+
+        let bigger = BoxOptions {
+            memory_mib: Some(2048),
+            ..options
+        };
+        assert_ne!(reuse_box_name(&bigger).unwrap(), name);
+    }
 }
@@ -0,0 +1,168 @@
+use anyhow::Result;
+use boxlite::runtime::options::{PortProtocol, PortSpec, SSH_GUEST_PORT};
+use clap::Args;
+
+use crate::cli::GlobalFlags;
+
+/// Open an SSH session to a box
+///
+/// The box must have been created with `--ssh` or `--ssh-key`. Runs the
+/// system `ssh` against the host port forwarded to the box's sshd.
+#[derive(Args, Debug)]
+pub struct SshArgs {
+    /// Name or ID of the box
+    pub target: String,
+
+    /// User to log in as
+    #[arg(short = 'l', long = "user", default_value = "root")]
+    pub user: String,
+
+    /// Private key to authenticate with
+    #[arg(short = 'i', long = "identity", value_name = "FILE")]
+    pub identity: Option<String>,
+
+    /// Print an ~/.ssh/config entry for the box instead of connecting
+    #[arg(long = "print-config")]
+    pub print_config: bool,
+
+    /// Extra arguments passed to ssh (after `--`), e.g. a remote command
+    #[arg(last = true)]
+    pub ssh_args: Vec<String>,
+}
+
+/// Where the box's sshd is reachable from the host.
+struct Endpoint {
+    host: String,
+    port: u16,
+}
+
+pub async fn execute(args: SshArgs, global: &GlobalFlags) -> Result<()> {
+    let runtime = global.create_runtime()?;
+    let litebox = runtime
+        .get(&args.target)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("No such box: {}", args.target))?;
+
+    let info = litebox.info();
+    if !info.status.is_running() {
+        anyhow::bail!("box {} is not running", args.target);
+    }
+    let endpoint = find_endpoint(&info.ports).ok_or_else(|| {
+        anyhow::anyhow!(
+            "box {} has no SSH port; create it with --ssh or --ssh-key",
+            args.target
+        )
+    })?;
+
+    if args.print_config {
+        print!("{}", ssh_config(&args, &endpoint));
+        return Ok(());
+    }
+    exec_ssh(ssh_command_args(&args, &endpoint))
+}
+
+fn find_endpoint(ports: &[PortSpec]) -> Option<Endpoint> {
+    let port = ports
+        .iter()
+        .find(|p| p.guest_port == SSH_GUEST_PORT && matches!(p.protocol, PortProtocol::Tcp))?;
+    let host = match port.host_ip.as_deref() {
+        None | Some("0.0.0.0") => "127.0.0.1".to_string(),
+        Some("::") => "::1".to_string(),
+        Some(ip) => ip.to_string(),
+    };
+    Some(Endpoint {
+        host,
+        port: port.host_port.unwrap_or(SSH_GUEST_PORT),
+    })
+}
+
+/// Host keys are regenerated per box, so known_hosts checking is disabled.
+const SSH_OPTIONS: [&str; 3] = [
+    "StrictHostKeyChecking=no",
+    "UserKnownHostsFile=/dev/null",
+    "LogLevel=ERROR",
+];
+
+fn ssh_command_args(args: &SshArgs, endpoint: &Endpoint) -> Vec<String> {
+    let mut cmd = vec!["-p".to_string(), endpoint.port.to_string()];
+    for option in SSH_OPTIONS {
+        cmd.push("-o".to_string());
+        cmd.push(option.to_string());
+    }
+    if let Some(identity) = &args.identity {
+        cmd.push("-i".to_string());
+        cmd.push(identity.clone());
+    }
+    cmd.push(format!("{}@{}", args.user, endpoint.host));
+    cmd.extend(args.ssh_args.iter().cloned());
+    cmd
+}
+
+fn ssh_config(args: &SshArgs, endpoint: &Endpoint) -> String {
+    let mut config = format!(
+        "Host boxlite-{}\n  HostName {}\n  Port {}\n  User {}\n  StrictHostKeyChecking no\n  UserKnownHostsFile /dev/null\n  LogLevel ERROR\n",
+        args.target, endpoint.host, endpoint.port, args.user
+    );
+    if let Some(identity) = &args.identity {
+        config.push_str(&format!("  IdentityFile {}\n", identity));
+    }
+    config
+}
+
+#[cfg(unix)]
+fn exec_ssh(args: Vec<String>) -> Result<()> {
+    use std::os::unix::process::CommandExt;
+
+    let error = std::process::Command::new("ssh").args(args).exec();
+    Err(anyhow::anyhow!("failed to run ssh: {}", error))
+}
+
+#[cfg(not(unix))]
+fn exec_ssh(args: Vec<String>) -> Result<()> {
+    let status = std::process::Command::new("ssh").args(args).status()?;
+    std::process::exit(status.code().unwrap_or(1));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::parse_publish_spec;
+
+    #[test]
+    fn test_ssh_command_args() {
+        let ports = vec![
+            parse_publish_spec("8080:80").unwrap(),
+            parse_publish_spec("127.0.0.1:2222:22").unwrap(),
+        ];
+        let endpoint = find_endpoint(&ports).unwrap();
+        let args = SshArgs {
+            target: "dev".to_string(),
+            user: "root".to_string(),
+            identity: Some("~/.ssh/id_ed25519".to_string()),
+            print_config: false,
+            ssh_args: vec!["uname".to_string()],
+        };
+        assert_eq!(
+            ssh_command_args(&args, &endpoint),
+            vec![
+                "-p",
+                "2222",
+                "-o",
+                "StrictHostKeyChecking=no",
+                "-o",
+                "UserKnownHostsFile=/dev/null",
+                "-o",
+                "LogLevel=ERROR",
+                "-i",
+                "~/.ssh/id_ed25519",
+                "root@127.0.0.1",
+                "uname",
+            ]
+        );
+        assert!(
+            ssh_config(&args, &endpoint)
+                .contains("Host boxlite-dev\n  HostName 127.0.0.1\n  Port 2222\n")
+        );
+        assert!(find_endpoint(&ports[..1]).is_none());
+    }
+}
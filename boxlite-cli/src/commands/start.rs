@@ -1,49 +1,61 @@
+use crate::cli::BatchFlags;
+use boxlite::WaitCondition;
 use clap::Args;
 
 #[derive(Args, Debug)]
 pub struct StartArgs {
     /// Name or ID of the box(es) to start
-    #[arg(required = true, num_args = 1..)]
+    #[arg(
+        num_args = 1..,
+        required_unless_present_any = ["all", "filter"],
+        conflicts_with_all = ["all", "filter"]
+    )]
     pub targets: Vec<String>,
+
+    /// Start all boxes
+    #[arg(long)]
+    pub all: bool,
+
+    #[command(flatten)]
+    pub batch: BatchFlags,
+
+    /// Stay in the foreground until the box stops; Ctrl-C stops the box
+    #[arg(short = 'a', long, conflicts_with_all = ["all", "filter"])]
+    pub attach: bool,
 }
 
 pub async fn execute(args: StartArgs, global: &crate::cli::GlobalFlags) -> anyhow::Result<()> {
     let runtime = global.create_runtime()?;
 
-    let mut errors = Vec::new();
-    let mut success_count = 0;
-
-    for target in args.targets {
-        let litebox = match runtime.get(&target).await? {
-            Some(b) => b,
-            None => {
-                eprintln!("Error: No such box: {}", target);
-                errors.push(format!("{}: not found", target));
-                continue;
-            }
+    if args.attach {
+        let [target] = args.targets.as_slice() else {
+            anyhow::bail!("--attach takes a single box");
         };
-
-        if let Err(e) = litebox.start().await {
-            eprintln!("Error starting box '{}': {}", target, e);
-            errors.push(format!("{}: {}", target, e));
-        } else {
-            println!("{}", target);
-            success_count += 1;
+        let litebox = runtime
+            .get(target)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("No such box: {}", target))?;
+        litebox.start().await?;
+        println!("{}", target);
+        tokio::select! {
+            result = litebox.wait_for(WaitCondition::Stopped, None) => result?,
+            _ = tokio::signal::ctrl_c() => litebox.stop().await?,
         }
+        return Ok(());
     }
 
-    if !errors.is_empty() {
-        let error_summary = if success_count > 0 {
-            format!(
-                "Failed to start {} of {} box(es)",
-                errors.len(),
-                errors.len() + success_count
-            )
-        } else {
-            format!("Failed to start all {} box(es)", errors.len())
-        };
-
-        anyhow::bail!("{}\nErrors:\n  {}", error_summary, errors.join("\n  "));
-    }
-    Ok(())
+    let targets = args.batch.select(&runtime, args.targets, args.all).await?;
+    args.batch
+        .run(targets, "start", |target| {
+            let runtime = &runtime;
+            async move {
+                let litebox = runtime
+                    .get(&target)
+                    .await?
+                    .ok_or_else(|| anyhow::anyhow!("not found"))?;
+                litebox.start().await?;
+                Ok(())
+            }
+        })
+        .await
 }
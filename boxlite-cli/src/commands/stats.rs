@@ -0,0 +1,282 @@
+//! `boxlite stats`: resource usage of a box, now or over a past period.
+//!
+//! `--history` reads the samples recorded while the box ran; the runtime
+//! config must set `metrics_history` for any to be recorded.
+//! `--format prometheus` prints the current usage in the Prometheus text
+//! format, e.g. for node_exporter's textfile collector.
+
+use std::io::Write;
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use boxlite::{BoxInfo, BoxMetrics, BoxStatus, LiteBox, MetricsSample};
+use clap::Args;
+use serde::Serialize;
+use tabled::Tabled;
+
+use crate::cli::GlobalFlags;
+use crate::commands::images::format_size;
+use crate::formatter::{self, OutputFormat};
+
+/// Display resource usage of a box
+#[derive(Args, Debug)]
+pub struct StatsArgs {
+    /// Name or ID of the box
+    pub target: String,
+
+    /// Show recorded samples from this period up to now, e.g. 30m, 1h, 2d
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    pub history: Option<Duration>,
+
+    /// Output format (table, json, yaml, prometheus)
+    #[arg(long, default_value = "table")]
+    pub format: String,
+}
+
+const PROMETHEUS_FORMAT: &str = "prometheus";
+
+#[derive(Tabled, Serialize)]
+struct SamplePresenter {
+    #[tabled(rename = "TIME")]
+    #[serde(rename = "Time")]
+    time: String,
+
+    #[tabled(rename = "CPU %")]
+    #[serde(skip)]
+    cpu: String,
+
+    #[tabled(skip)]
+    #[serde(rename = "CPUPercent")]
+    cpu_percent: Option<f32>,
+
+    #[tabled(rename = "MEM")]
+    #[serde(skip)]
+    memory: String,
+
+    #[tabled(skip)]
+    #[serde(rename = "MemoryBytes")]
+    memory_bytes: Option<u64>,
+
+    #[tabled(rename = "NET TX")]
+    #[serde(skip)]
+    net_sent: String,
+
+    #[tabled(skip)]
+    #[serde(rename = "NetworkBytesSent")]
+    network_bytes_sent: Option<u64>,
+
+    #[tabled(rename = "NET RX")]
+    #[serde(skip)]
+    net_received: String,
+
+    #[tabled(skip)]
+    #[serde(rename = "NetworkBytesReceived")]
+    network_bytes_received: Option<u64>,
+
+    #[tabled(rename = "NET CONNS")]
+    #[serde(skip)]
+    net_connections: String,
+
+    #[tabled(skip)]
+    #[serde(rename = "NetworkTCPConnections")]
+    network_tcp_connections: Option<u64>,
+}
+
+impl From<MetricsSample> for SamplePresenter {
+    fn from(sample: MetricsSample) -> Self {
+        let size = |bytes: Option<u64>| bytes.map(format_size).unwrap_or_else(|| "-".into());
+        Self {
+            time: formatter::format_time(&sample.sampled_at),
+            cpu: sample
+                .cpu_percent
+                .map(|cpu| format!("{:.1}%", cpu))
+                .unwrap_or_else(|| "-".into()),
+            cpu_percent: sample.cpu_percent,
+            memory: size(sample.memory_bytes),
+            memory_bytes: sample.memory_bytes,
+            net_sent: size(sample.network_bytes_sent),
+            network_bytes_sent: sample.network_bytes_sent,
+            net_received: size(sample.network_bytes_received),
+            network_bytes_received: sample.network_bytes_received,
+            net_connections: sample
+                .network_tcp_connections
+                .map(|conns| conns.to_string())
+                .unwrap_or_else(|| "-".into()),
+            network_tcp_connections: sample.network_tcp_connections,
+        }
+    }
+}
+
+pub async fn execute(args: StatsArgs, global: &GlobalFlags) -> Result<()> {
+    let prometheus = args.format == PROMETHEUS_FORMAT;
+    if prometheus && args.history.is_some() {
+        bail!("--format prometheus shows current usage and cannot be combined with --history");
+    }
+    let format = if prometheus {
+        None
+    } else {
+        Some(OutputFormat::from_str(&args.format)?)
+    };
+    let runtime = global.create_runtime()?;
+    let litebox = runtime
+        .get(&args.target)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("No such box: {}", args.target))?;
+
+    let Some(format) = format else {
+        let metrics = current_metrics(&litebox, &args.target).await?;
+        write!(
+            std::io::stdout().lock(),
+            "{}",
+            prometheus_series(&litebox.info(), &metrics)
+        )?;
+        return Ok(());
+    };
+
+    let samples = match args.history {
+        Some(range) => litebox.metrics_history(range)?,
+        None => {
+            let metrics = current_metrics(&litebox, &args.target).await?;
+            vec![MetricsSample {
+                sampled_at: chrono::Utc::now(),
+                cpu_percent: metrics.cpu_percent(),
+                memory_bytes: metrics.memory_bytes(),
+                network_bytes_sent: metrics.network_bytes_sent(),
+                network_bytes_received: metrics.network_bytes_received(),
+                network_tcp_connections: metrics.network_tcp_connections(),
+            }]
+        }
+    };
+    let rows: Vec<SamplePresenter> = samples.into_iter().map(SamplePresenter::from).collect();
+
+    formatter::print_output(
+        &mut std::io::stdout().lock(),
+        &rows,
+        format,
+        |writer, rows| {
+            writeln!(writer, "{}", formatter::create_table(rows))?;
+            Ok(())
+        },
+    )?;
+    Ok(())
+}
+
+async fn current_metrics(litebox: &LiteBox, target: &str) -> Result<BoxMetrics> {
+    if litebox.info().status != BoxStatus::Running {
+        bail!(
+            "box '{}' is not running (use --history for recorded usage)",
+            target
+        );
+    }
+    Ok(litebox.metrics().await?)
+}
+
+/// Render `metrics` as Prometheus text exposition, one series per known value.
+fn prometheus_series(info: &BoxInfo, metrics: &BoxMetrics) -> String {
+    let labels = format!(
+        "box_id=\"{}\",box_name=\"{}\"",
+        escape_label(info.id.as_str()),
+        escape_label(info.name.as_deref().unwrap_or_default())
+    );
+    let series: [(&str, &str, &str, Option<f64>); 6] = [
+        (
+            "boxlite_box_cpu_percent",
+            "gauge",
+            "CPU usage of the box in percent.",
+            metrics.cpu_percent().map(f64::from),
+        ),
+        (
+            "boxlite_box_memory_bytes",
+            "gauge",
+            "Memory used by the box.",
+            metrics.memory_bytes().map(|v| v as f64),
+        ),
+        (
+            "boxlite_box_network_sent_bytes_total",
+            "counter",
+            "Network bytes sent from the host to the box.",
+            metrics.network_bytes_sent().map(|v| v as f64),
+        ),
+        (
+            "boxlite_box_network_received_bytes_total",
+            "counter",
+            "Network bytes received by the host from the box.",
+            metrics.network_bytes_received().map(|v| v as f64),
+        ),
+        (
+            "boxlite_box_network_tcp_connections",
+            "gauge",
+            "TCP connections of the box currently established.",
+            metrics.network_tcp_connections().map(|v| v as f64),
+        ),
+        (
+            "boxlite_box_network_tcp_failed_connections_total",
+            "counter",
+            "Failed TCP connection attempts of the box.",
+            metrics.network_tcp_errors().map(|v| v as f64),
+        ),
+    ];
+
+    let mut out = String::new();
+    for (name, kind, help, value) in series {
+        if let Some(value) = value {
+            out.push_str(&format!(
+                "# HELP {name} {help}\n# TYPE {name} {kind}\n{name}{{{labels}}} {value}\n"
+            ));
+        }
+    }
+    out
+}
+
+/// Escape a Prometheus label value.
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Parse a duration such as `90s`, `30m`, `1h` or `2d`; a bare number is seconds.
+fn parse_duration(s: &str) -> Result<Duration, String> {
+    let (number, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+        Some(split) => s.split_at(split),
+        None => (s, "s"),
+    };
+    let secs_per_unit = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return Err(format!("invalid duration '{}': use s, m, h or d", s)),
+    };
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid duration '{}': expected e.g. 30m or 1h", s))?;
+    number
+        .checked_mul(secs_per_unit)
+        .map(Duration::from_secs)
+        .ok_or_else(|| format!("duration '{}' is too long", s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_label() {
+        assert_eq!(escape_label("web"), "web");
+        assert_eq!(escape_label("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_duration("30m").unwrap(), Duration::from_secs(1800));
+        assert_eq!(parse_duration("1h").unwrap(), Duration::from_secs(3600));
+        assert_eq!(parse_duration("2d").unwrap(), Duration::from_secs(172_800));
+        assert!(parse_duration("1w").is_err());
+        assert!(parse_duration("h").is_err());
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("1.5h").is_err());
+    }
+}
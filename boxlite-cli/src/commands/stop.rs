@@ -1,50 +1,39 @@
+use crate::cli::BatchFlags;
 use clap::Args;
 
 #[derive(Args, Debug)]
 pub struct StopArgs {
     /// Name or ID of the box(es) to stop
-    #[arg(required = true, num_args = 1..)]
+    #[arg(
+        num_args = 1..,
+        required_unless_present_any = ["all", "filter"],
+        conflicts_with_all = ["all", "filter"]
+    )]
     pub targets: Vec<String>,
+
+    /// Stop all boxes
+    #[arg(long)]
+    pub all: bool,
+
+    #[command(flatten)]
+    pub batch: BatchFlags,
 }
 
 pub async fn execute(args: StopArgs, global: &crate::cli::GlobalFlags) -> anyhow::Result<()> {
     let runtime = global.create_runtime()?;
+    let targets = args.batch.select(&runtime, args.targets, args.all).await?;
 
-    let mut errors = Vec::new();
-    let mut success_count = 0;
-
-    for target in args.targets {
-        // Get the box first
-        let litebox = match runtime.get(&target).await? {
-            Some(b) => b,
-            None => {
-                eprintln!("Error: No such box: {}", target);
-                errors.push(format!("{}: not found", target));
-                continue;
+    args.batch
+        .run(targets, "stop", |target| {
+            let runtime = &runtime;
+            async move {
+                let litebox = runtime
+                    .get(&target)
+                    .await?
+                    .ok_or_else(|| anyhow::anyhow!("not found"))?;
+                litebox.stop().await?;
+                Ok(())
             }
-        };
-
-        if let Err(e) = litebox.stop().await {
-            eprintln!("Error stopping box '{}': {}", target, e);
-            errors.push(format!("{}: {}", target, e));
-        } else {
-            println!("{}", target);
-            success_count += 1;
-        }
-    }
-
-    if !errors.is_empty() {
-        let error_summary = if success_count > 0 {
-            format!(
-                "Failed to stop {} of {} box(es)",
-                errors.len(),
-                errors.len() + success_count
-            )
-        } else {
-            format!("Failed to stop all {} box(es)", errors.len())
-        };
-
-        anyhow::bail!("{}\nErrors:\n  {}", error_summary, errors.join("\n  "));
-    }
-    Ok(())
+        })
+        .await
 }
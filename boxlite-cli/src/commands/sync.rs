@@ -0,0 +1,365 @@
+//! `boxlite sync`: copy a host directory into a box and keep it up to date.
+//!
+//! With `--watch`, file notifications (debounced, so a burst of writes is
+//! one round) trigger a round. A round compares snapshots of the host tree
+//! (entry type, size and mtime), so events only say when to look, and a
+//! dropped or coalesced event loses nothing. Each round uploads the changed
+//! entries as one archive and removes deleted ones with `rm -rf` in the box.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result, bail};
+use boxlite::{BoxCommand, LiteBox};
+use clap::Args;
+use notify_debouncer_mini::notify::RecursiveMode;
+use notify_debouncer_mini::{DebounceEventResult, new_debouncer};
+
+use crate::cli::GlobalFlags;
+use crate::commands::cp::{Direction, parse_direction};
+
+/// Copy a host directory into a box, then optionally keep pushing changes
+#[derive(Args, Debug)]
+pub struct SyncArgs {
+    /// Host directory to sync
+    #[arg(index = 1)]
+    pub src: String,
+
+    /// Destination directory in the box (BOX:PATH)
+    #[arg(index = 2)]
+    pub dst: String,
+
+    /// Keep running and push changes until interrupted (Ctrl-C)
+    #[arg(long)]
+    pub watch: bool,
+
+    /// How long --watch waits for changes to settle before pushing them
+    #[arg(long, value_name = "MS", default_value_t = 200, value_parser = clap::value_parser!(u64).range(1..))]
+    pub debounce: u64,
+
+    /// Skip files and directories with this name, e.g. .git (can be repeated)
+    #[arg(long, value_name = "NAME")]
+    pub exclude: Vec<String>,
+}
+
+pub async fn execute(args: SyncArgs, global: &GlobalFlags) -> Result<()> {
+    let Direction::HostToBox {
+        host,
+        box_name,
+        box_path,
+    } = parse_direction(&args.src, &args.dst)?
+    else {
+        bail!("sync copies from the host into a box: SRC is a host directory, DST is BOX:PATH");
+    };
+    if !host.is_dir() {
+        bail!("{} is not a directory", host.display());
+    }
+
+    let rt = global.create_runtime()?;
+    let litebox = rt
+        .get(&box_name)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("box '{}' not found", box_name))?;
+    let was_running = litebox.info().status == boxlite::BoxStatus::Running;
+    if !was_running {
+        litebox.start().await?;
+    }
+
+    let result = sync(&litebox, &host, &box_path, &args).await;
+    if !was_running {
+        litebox.stop().await?;
+    }
+    result
+}
+
+async fn sync(litebox: &LiteBox, host: &Path, box_path: &str, args: &SyncArgs) -> Result<()> {
+    let mut synced = tokio::task::block_in_place(|| snapshot(host, &args.exclude))?;
+    let initial = Changes {
+        updated: synced.keys().cloned().collect(),
+        removed: Vec::new(),
+    };
+    apply(litebox, host, box_path, &initial).await?;
+    println!("Synced {} entries to {}", initial.updated.len(), args.dst);
+    if !args.watch {
+        return Ok(());
+    }
+
+    let (tx, mut events) = tokio::sync::mpsc::unbounded_channel();
+    let mut debouncer = new_debouncer(
+        Duration::from_millis(args.debounce),
+        move |result: DebounceEventResult| {
+            let _ = tx.send(result);
+        },
+    )
+    .context("failed to start watching the host directory")?;
+    // Events name absolute paths
+    let root = host
+        .canonicalize()
+        .with_context(|| format!("failed to resolve {}", host.display()))?;
+    debouncer
+        .watcher()
+        .watch(&root, RecursiveMode::Recursive)
+        .with_context(|| format!("failed to watch {}", host.display()))?;
+
+    let mut retry = false;
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+            _ = tokio::time::sleep(RETRY_DELAY), if retry => {}
+            result = events.recv() => match result {
+                None => bail!("stopped watching {}", host.display()),
+                // Excluded entries change often (.git, build output)
+                Some(Ok(events)) if events
+                    .iter()
+                    .all(|event| is_excluded(&root, &event.path, &args.exclude)) => continue,
+                Some(Ok(_)) => {}
+                // A rescan finds whatever the lost events were about
+                Some(Err(e)) => eprintln!("Warning: file watch error, rescanning: {}", e),
+            },
+        }
+
+        let current = tokio::task::block_in_place(|| snapshot(host, &args.exclude))?;
+        let changes = diff(&synced, &current);
+        retry = false;
+        if changes.is_empty() {
+            continue;
+        }
+        // Entries can vanish between the scan and the upload; the retry
+        // diffs against the last successful sync
+        match apply(litebox, host, box_path, &changes).await {
+            Ok(()) => {
+                println!(
+                    "{} updated, {} removed",
+                    changes.updated.len(),
+                    changes.removed.len()
+                );
+                synced = current;
+            }
+            Err(e) => {
+                eprintln!("Warning: sync failed, retrying: {:#}", e);
+                retry = true;
+            }
+        }
+    }
+}
+
+/// Delay before a failed round is retried without a new change.
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Whether `path` lies below `root` under an excluded name.
+fn is_excluded(root: &Path, path: &Path, exclude: &[String]) -> bool {
+    let Ok(rel) = path.strip_prefix(root) else {
+        return false;
+    };
+    rel.components()
+        .any(|c| exclude.iter().any(|x| c.as_os_str() == x.as_str()))
+}
+
+/// Remove, then upload, so an entry whose type changed is replaced.
+async fn apply(litebox: &LiteBox, host: &Path, box_path: &str, changes: &Changes) -> Result<()> {
+    if !changes.removed.is_empty() {
+        let targets = changes
+            .removed
+            .iter()
+            .map(|rel| join_box_path(box_path, rel));
+        let mut execution = litebox
+            .exec(BoxCommand::new("rm").args(["-rf", "--"]).args(targets))
+            .await?;
+        let result = execution.wait().await?;
+        if !result.success() {
+            bail!("rm in box exited with code {}", result.exit_code);
+        }
+    }
+    litebox
+        .copy_paths_into(host, &changes.updated, box_path)
+        .await?;
+    Ok(())
+}
+
+fn join_box_path(box_path: &str, rel: &Path) -> String {
+    format!(
+        "{}/{}",
+        box_path.trim_end_matches('/'),
+        rel.to_string_lossy()
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EntryKind {
+    File,
+    Dir,
+    Symlink,
+}
+
+/// What a round compares. Directories compare by type only: their mtime
+/// changes with every child, which is reported on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Entry {
+    kind: EntryKind,
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+/// Entries below the sync root, keyed by relative path. Path order puts
+/// every directory right before its contents.
+type Snapshot = BTreeMap<PathBuf, Entry>;
+
+fn snapshot(root: &Path, exclude: &[String]) -> Result<Snapshot> {
+    let mut entries = Snapshot::new();
+    let mut stack = vec![PathBuf::new()];
+    while let Some(dir) = stack.pop() {
+        let listing = match std::fs::read_dir(root.join(&dir)) {
+            Ok(listing) => listing,
+            // Removed since its parent was listed
+            Err(_) if !dir.as_os_str().is_empty() => continue,
+            Err(e) => return Err(e).with_context(|| format!("failed to read {}", root.display())),
+        };
+        for item in listing.flatten() {
+            let name = item.file_name();
+            if exclude.iter().any(|x| name.as_os_str() == x.as_str()) {
+                continue;
+            }
+            let Ok(meta) = std::fs::symlink_metadata(item.path()) else {
+                continue;
+            };
+            let rel = dir.join(&name);
+            let file_type = meta.file_type();
+            let entry = if file_type.is_dir() {
+                stack.push(rel.clone());
+                Entry {
+                    kind: EntryKind::Dir,
+                    len: 0,
+                    modified: None,
+                }
+            } else {
+                let kind = if file_type.is_symlink() {
+                    EntryKind::Symlink
+                } else if file_type.is_file() {
+                    EntryKind::File
+                } else {
+                    // Sockets, FIFOs and devices are not synced
+                    continue;
+                };
+                Entry {
+                    kind,
+                    len: meta.len(),
+                    modified: meta.modified().ok(),
+                }
+            };
+            entries.insert(rel, entry);
+        }
+    }
+    Ok(entries)
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Changes {
+    /// New or modified entries, parents first
+    updated: Vec<PathBuf>,
+    /// Topmost removed entries; their contents go with them
+    removed: Vec<PathBuf>,
+}
+
+impl Changes {
+    fn is_empty(&self) -> bool {
+        self.updated.is_empty() && self.removed.is_empty()
+    }
+}
+
+fn diff(old: &Snapshot, new: &Snapshot) -> Changes {
+    let updated = new
+        .iter()
+        .filter(|(path, entry)| old.get(*path) != Some(entry))
+        .map(|(path, _)| path.clone())
+        .collect();
+
+    // Gone, or changed type and must be removed before it is re-created
+    let candidates: BTreeSet<&PathBuf> = old
+        .iter()
+        .filter(|(path, entry)| new.get(*path).is_none_or(|e| e.kind != entry.kind))
+        .map(|(path, _)| path)
+        .collect();
+    let mut removed: Vec<PathBuf> = Vec::new();
+    for path in candidates {
+        if removed
+            .last()
+            .is_some_and(|parent| path.starts_with(parent))
+        {
+            continue;
+        }
+        removed.push(path.clone());
+    }
+
+    Changes { updated, removed }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(list: &[&str]) -> Vec<PathBuf> {
+        list.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn test_snapshot_diff() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path();
+        std::fs::create_dir_all(dir.join("src/util")).unwrap();
+        std::fs::create_dir_all(dir.join(".git")).unwrap();
+        std::fs::write(dir.join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(dir.join("src/util/mod.rs"), "").unwrap();
+        std::fs::write(dir.join("README"), "hi").unwrap();
+        std::fs::write(dir.join(".git/HEAD"), "ref").unwrap();
+
+        let exclude = vec![".git".to_string()];
+        let before = snapshot(dir, &exclude).unwrap();
+        assert_eq!(
+            before.keys().cloned().collect::<Vec<_>>(),
+            paths(&[
+                "README",
+                "src",
+                "src/main.rs",
+                "src/util",
+                "src/util/mod.rs"
+            ])
+        );
+        assert!(diff(&before, &before).is_empty());
+
+        std::fs::write(dir.join("src/main.rs"), "fn main() { run() }").unwrap();
+        std::fs::remove_dir_all(dir.join("src/util")).unwrap();
+        std::fs::remove_file(dir.join("README")).unwrap();
+        std::fs::create_dir(dir.join("README")).unwrap();
+        std::fs::write(dir.join("new.txt"), "").unwrap();
+        std::fs::write(dir.join(".git/HEAD"), "ref2").unwrap();
+
+        let after = snapshot(dir, &exclude).unwrap();
+        assert_eq!(
+            diff(&before, &after),
+            Changes {
+                updated: paths(&["README", "new.txt", "src/main.rs"]),
+                // A removed directory is removed once, with its contents
+                removed: paths(&["README", "src/util"]),
+            }
+        );
+    }
+
+    #[test]
+    fn test_is_excluded() {
+        let root = Path::new("/src");
+        let exclude = vec![".git".to_string()];
+        assert!(is_excluded(root, Path::new("/src/.git/index"), &exclude));
+        assert!(is_excluded(root, Path::new("/src/sub/.git"), &exclude));
+        assert!(!is_excluded(root, Path::new("/src/main.rs"), &exclude));
+        assert!(!is_excluded(root, Path::new("/other/.gitignore"), &exclude));
+    }
+
+    #[test]
+    fn test_join_box_path() {
+        let rel = Path::new("src/main.rs");
+        assert_eq!(join_box_path("/app", rel), "/app/src/main.rs");
+        assert_eq!(join_box_path("/app/", rel), "/app/src/main.rs");
+        assert_eq!(join_box_path("/", rel), "/src/main.rs");
+    }
+}
@@ -0,0 +1,308 @@
+use std::io::Write;
+
+use anyhow::Result;
+use boxlite::vmm::host_check::{self, HugepageInfo};
+use boxlite::{DiskUsage, SystemPruneOptions, SystemPruneReport};
+use clap::{Args, Subcommand};
+use serde::Serialize;
+use tabled::Tabled;
+
+use crate::cli::GlobalFlags;
+use crate::commands::images::format_size;
+use crate::formatter::{self, OutputFormat};
+
+/// Manage BoxLite host resources
+#[derive(Args, Debug)]
+pub struct SystemArgs {
+    #[command(subcommand)]
+    pub command: SystemCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SystemCommand {
+    /// Display system-wide information (virtualization, huge pages)
+    Info(InfoArgs),
+
+    /// Show disk usage of images and boxes
+    ///
+    /// With --dedup-layers, extracted layers show both their size on disk
+    /// and their logical size (every file counted separately).
+    Df(DfArgs),
+
+    /// Remove stopped boxes, dangling image data, unused anonymous volumes
+    /// and runtime logs older than a week
+    Prune(PruneArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct InfoArgs {
+    /// Output format (table, json, yaml)
+    #[arg(long, default_value = "table")]
+    pub format: String,
+}
+
+#[derive(Args, Debug)]
+pub struct DfArgs {
+    /// Output format (table, json, yaml)
+    #[arg(long, default_value = "table")]
+    pub format: String,
+}
+
+#[derive(Args, Debug)]
+pub struct PruneArgs {
+    /// Do not prompt for confirmation
+    #[arg(short, long)]
+    pub force: bool,
+
+    /// Also remove every image no box uses, not just dangling data
+    #[arg(short, long)]
+    pub all: bool,
+}
+
+#[derive(Serialize)]
+struct SystemInfo {
+    #[serde(rename = "Version")]
+    version: String,
+    #[serde(rename = "OS")]
+    os: String,
+    #[serde(rename = "Arch")]
+    arch: String,
+    #[serde(rename = "HomeDir")]
+    home_dir: String,
+    #[serde(rename = "Profile")]
+    profile: Option<String>,
+    #[serde(rename = "Virtualization")]
+    virtualization: String,
+    #[serde(rename = "Hugepages")]
+    hugepages: Option<HugepageInfo>,
+}
+
+/// One row of `system df`, used by both table and JSON/YAML formats.
+#[derive(Tabled, Serialize)]
+struct UsagePresenter {
+    #[tabled(rename = "TYPE")]
+    #[serde(rename = "Type")]
+    kind: &'static str,
+    #[tabled(rename = "SIZE")]
+    #[serde(skip)]
+    size: String,
+    #[tabled(rename = "LOGICAL SIZE")]
+    #[serde(skip)]
+    logical_size: String,
+    #[tabled(skip)]
+    #[serde(rename = "Size")]
+    size_bytes: u64,
+    #[tabled(skip)]
+    #[serde(rename = "LogicalSize")]
+    logical_bytes: u64,
+}
+
+impl UsagePresenter {
+    fn new(kind: &'static str, size: u64, logical: u64) -> Self {
+        Self {
+            kind,
+            size: format_size(size),
+            logical_size: format_size(logical),
+            size_bytes: size,
+            logical_bytes: logical,
+        }
+    }
+
+    fn rows(usage: &DiskUsage) -> Vec<Self> {
+        vec![
+            Self::new("Layers", usage.layers_bytes, usage.layers_bytes),
+            Self::new(
+                "Extracted layers",
+                usage.extracted_bytes,
+                usage.extracted_logical_bytes,
+            ),
+            Self::new(
+                "Disk images",
+                usage.disk_images_bytes,
+                usage.disk_images_bytes,
+            ),
+            Self::new("Boxes", usage.boxes_bytes, usage.boxes_bytes),
+        ]
+    }
+}
+
+pub async fn execute(args: SystemArgs, global: &GlobalFlags) -> Result<()> {
+    match args.command {
+        SystemCommand::Info(info_args) => {
+            // Resolve through the config file so a profile's home_dir shows up
+            let home_dir = global.runtime_options()?.home_dir;
+            let virtualization = match host_check::check_virtualization_support() {
+                Ok(support) => support.reason,
+                Err(e) => format!(
+                    "unavailable: {}",
+                    e.to_string().lines().next().unwrap_or("")
+                ),
+            };
+
+            let info = SystemInfo {
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                os: std::env::consts::OS.to_string(),
+                arch: std::env::consts::ARCH.to_string(),
+                home_dir: home_dir.display().to_string(),
+                profile: global.selected_profile(),
+                virtualization,
+                hugepages: host_check::hugepage_info(),
+            };
+
+            let mut out = std::io::stdout().lock();
+            match OutputFormat::from_str(&info_args.format)? {
+                OutputFormat::Json => writeln!(out, "{}", formatter::format_json(&info)?)?,
+                OutputFormat::Yaml => write!(out, "{}", formatter::format_yaml(&info)?)?,
+                OutputFormat::Table => print_info(&mut out, &info)?,
+            }
+            Ok(())
+        }
+        SystemCommand::Df(df) => {
+            let runtime = global.create_runtime()?;
+            let usage = runtime.disk_usage().await?;
+            let rows = UsagePresenter::rows(&usage);
+
+            let format = OutputFormat::from_str(&df.format)?;
+            formatter::print_output(
+                &mut std::io::stdout().lock(),
+                &rows,
+                format,
+                |writer, rows| {
+                    writeln!(writer, "{}", formatter::create_table(rows))?;
+                    writeln!(
+                        writer,
+                        "Total: {}, dedup saved {} ({} files in content store)",
+                        format_size(usage.total_bytes()),
+                        format_size(usage.dedup_saved_bytes()),
+                        usage.dedup_objects
+                    )?;
+                    Ok(())
+                },
+            )?;
+            Ok(())
+        }
+        SystemCommand::Prune(prune) => {
+            if !prune.force && !confirm_prune(prune.all)? {
+                return Ok(());
+            }
+            let runtime = global.create_runtime()?;
+            let options = SystemPruneOptions {
+                all_images: prune.all,
+                ..Default::default()
+            };
+            let report = runtime.system_prune(&options).await?;
+            print_prune_report(&mut std::io::stdout().lock(), &report)?;
+            Ok(())
+        }
+    }
+}
+
+fn print_info(writer: &mut impl Write, info: &SystemInfo) -> Result<()> {
+    writeln!(writer, "Version:        {}", info.version)?;
+    writeln!(writer, "OS/Arch:        {}/{}", info.os, info.arch)?;
+    writeln!(writer, "Home Dir:       {}", info.home_dir)?;
+    writeln!(
+        writer,
+        "Profile:        {}",
+        info.profile.as_deref().unwrap_or("none")
+    )?;
+    writeln!(writer, "Virtualization: {}", info.virtualization)?;
+    match &info.hugepages {
+        Some(hp) => writeln!(
+            writer,
+            "Hugepages:      {} free / {} total ({} KiB pages, {} MiB free)",
+            hp.free,
+            hp.total,
+            hp.page_size_kib,
+            hp.free_mib()
+        )?,
+        None => writeln!(writer, "Hugepages:      unavailable")?,
+    }
+    Ok(())
+}
+
+fn confirm_prune(all_images: bool) -> Result<bool> {
+    let images = if all_images {
+        "all images not used by a box"
+    } else {
+        "dangling image data"
+    };
+    eprintln!("WARNING! This will remove:");
+    eprintln!("  - all stopped boxes");
+    eprintln!("  - {}", images);
+    eprintln!("  - anonymous volumes not used by a box");
+    eprintln!("  - runtime logs older than 7 days");
+    eprint!("Are you sure you want to continue? [y/N] ");
+    std::io::stderr().flush()?;
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    Ok(input.trim().eq_ignore_ascii_case("y"))
+}
+
+fn print_prune_report(writer: &mut impl Write, report: &SystemPruneReport) -> Result<()> {
+    if !report.boxes.is_empty() {
+        writeln!(writer, "Deleted boxes:")?;
+        for id in &report.boxes {
+            writeln!(writer, "{}", id)?;
+        }
+        writeln!(writer)?;
+    }
+    if !report.images.is_empty() {
+        writeln!(writer, "Deleted images:")?;
+        for image in &report.images {
+            writeln!(writer, "{}", image)?;
+        }
+        writeln!(writer)?;
+    }
+    if !report.volumes.is_empty() {
+        writeln!(writer, "Deleted volumes:")?;
+        for volume in &report.volumes {
+            writeln!(writer, "{}", volume.display())?;
+        }
+        writeln!(writer)?;
+    }
+    if report.dangling_blobs > 0 {
+        writeln!(
+            writer,
+            "Deleted {} dangling image files",
+            report.dangling_blobs
+        )?;
+    }
+    if !report.logs.is_empty() {
+        writeln!(writer, "Deleted {} log files", report.logs.len())?;
+    }
+    writeln!(
+        writer,
+        "Total reclaimed space: {}",
+        format_size(report.reclaimed_bytes)
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_print_prune_report() {
+        let report = SystemPruneReport {
+            images: vec!["alpine:3.19".to_string()],
+            dangling_blobs: 3,
+            volumes: vec!["/home/me/.boxlite/volumes/anonymous/01J".into()],
+            reclaimed_bytes: 5 * 1024 * 1024,
+            ..Default::default()
+        };
+        let mut out = Vec::new();
+        print_prune_report(&mut out, &report).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(!out.contains("Deleted boxes"));
+        assert!(out.contains("Deleted images:\nalpine:3.19\n"));
+        assert!(out.contains("/volumes/anonymous/01J"));
+        assert!(out.contains("Deleted 3 dangling image files"));
+        assert!(!out.contains("log files"));
+        assert!(out.contains(&format!(
+            "Total reclaimed space: {}",
+            format_size(5 * 1024 * 1024)
+        )));
+    }
+}
@@ -0,0 +1,21 @@
+use anyhow::Result;
+use clap::Args;
+
+use crate::cli::GlobalFlags;
+
+/// Create a tag TARGET_IMAGE that refers to SOURCE_IMAGE
+#[derive(Args, Debug)]
+pub struct TagArgs {
+    /// Existing local image or tag
+    pub source: String,
+
+    /// New tag (unqualified names are stored as localhost/<name>)
+    pub target: String,
+}
+
+pub async fn execute(args: TagArgs, global: &GlobalFlags) -> Result<()> {
+    let runtime = global.create_runtime()?;
+
+    runtime.tag_image(&args.source, &args.target).await?;
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use std::io::Write;
+
+use anyhow::Result;
+use boxlite::ProcessInfo;
+use clap::Args;
+use serde::Serialize;
+use tabled::Tabled;
+
+use crate::cli::GlobalFlags;
+use crate::commands::images::format_size;
+use crate::formatter::{self, OutputFormat};
+
+/// Display the processes running in a box
+#[derive(Args, Debug)]
+pub struct TopArgs {
+    /// Name or ID of the box
+    pub target: String,
+
+    /// Output format (table, json, yaml)
+    #[arg(long, default_value = "table")]
+    pub format: String,
+}
+
+#[derive(Tabled, Serialize)]
+struct ProcessPresenter {
+    #[tabled(rename = "PID")]
+    #[serde(rename = "PID")]
+    pid: u32,
+
+    #[tabled(rename = "PPID")]
+    #[serde(rename = "PPID")]
+    ppid: u32,
+
+    #[tabled(rename = "USER")]
+    #[serde(rename = "User")]
+    user: String,
+
+    #[tabled(rename = "RSS")]
+    #[serde(skip)]
+    rss: String,
+
+    #[tabled(skip)]
+    #[serde(rename = "RSS")]
+    rss_bytes: u64,
+
+    #[tabled(rename = "COMMAND")]
+    #[serde(rename = "Command")]
+    command: String,
+}
+
+impl From<ProcessInfo> for ProcessPresenter {
+    fn from(process: ProcessInfo) -> Self {
+        Self {
+            pid: process.pid,
+            ppid: process.ppid,
+            user: process.user,
+            rss: format_size(process.rss_bytes),
+            rss_bytes: process.rss_bytes,
+            command: process.command,
+        }
+    }
+}
+
+pub async fn execute(args: TopArgs, global: &GlobalFlags) -> Result<()> {
+    let format = OutputFormat::from_str(&args.format)?;
+    let runtime = global.create_runtime()?;
+    let litebox = runtime
+        .get(&args.target)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("No such box: {}", args.target))?;
+
+    let rows: Vec<ProcessPresenter> = litebox
+        .processes()
+        .await?
+        .into_iter()
+        .map(ProcessPresenter::from)
+        .collect();
+
+    formatter::print_output(
+        &mut std::io::stdout().lock(),
+        &rows,
+        format,
+        |writer, rows| {
+            writeln!(writer, "{}", formatter::create_table(rows))?;
+            Ok(())
+        },
+    )?;
+    Ok(())
+}
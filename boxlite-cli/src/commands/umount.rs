@@ -0,0 +1,26 @@
+use anyhow::Result;
+use clap::Args;
+
+use crate::cli::GlobalFlags;
+
+/// Remove a directory mounted with `boxlite mount`
+#[derive(Args, Debug)]
+pub struct UmountArgs {
+    /// Name or ID of the box
+    pub target: String,
+
+    /// Path inside the box passed to `boxlite mount`
+    pub guest_path: String,
+}
+
+pub async fn execute(args: UmountArgs, global: &GlobalFlags) -> Result<()> {
+    let runtime = global.create_runtime()?;
+    let litebox = runtime
+        .get(&args.target)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("No such box: {}", args.target))?;
+
+    litebox.unmount(&args.guest_path).await?;
+    println!("{}", args.guest_path);
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use clap::Args;
+
+#[derive(Args, Debug)]
+pub struct UnpauseArgs {
+    /// Name or ID of the box(es) to unpause
+    #[arg(required = true, num_args = 1..)]
+    pub targets: Vec<String>,
+}
+
+pub async fn execute(args: UnpauseArgs, global: &crate::cli::GlobalFlags) -> anyhow::Result<()> {
+    let runtime = global.create_runtime()?;
+
+    let mut errors = Vec::new();
+    let mut success_count = 0;
+
+    for target in args.targets {
+        let litebox = match runtime.get(&target).await? {
+            Some(b) => b,
+            None => {
+                eprintln!("Error: No such box: {}", target);
+                errors.push(format!("{}: not found", target));
+                continue;
+            }
+        };
+
+        if let Err(e) = litebox.resume().await {
+            eprintln!("Error unpausing box '{}': {}", target, e);
+            errors.push(format!("{}: {}", target, e));
+        } else {
+            println!("{}", target);
+            success_count += 1;
+        }
+    }
+
+    if !errors.is_empty() {
+        let error_summary = if success_count > 0 {
+            format!(
+                "Failed to unpause {} of {} box(es)",
+                errors.len(),
+                errors.len() + success_count
+            )
+        } else {
+            format!("Failed to unpause all {} box(es)", errors.len())
+        };
+
+        anyhow::bail!("{}\nErrors:\n  {}", error_summary, errors.join("\n  "));
+    }
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use boxlite::WaitCondition;
+use clap::Args;
+
+use crate::cli::GlobalFlags;
+
+/// Block until a box stops, becomes healthy, or starts listening on a port
+///
+/// Exits 0 once the condition holds and 1 on timeout or if the box stops
+/// before it could hold, so scripts can replace sleep loops with it.
+#[derive(Args, Debug)]
+pub struct WaitArgs {
+    /// Name or ID of the box
+    pub target: String,
+
+    /// Condition: stopped, healthy, or port:PORT (a socket in the box listens on PORT)
+    #[arg(long, default_value = "stopped", value_parser = parse_condition)]
+    pub condition: WaitCondition,
+
+    /// Give up after this many seconds (default: wait forever)
+    #[arg(long, value_name = "SECONDS")]
+    pub timeout: Option<u64>,
+}
+
+fn parse_condition(s: &str) -> Result<WaitCondition, String> {
+    s.parse().map_err(|e: boxlite::BoxliteError| e.to_string())
+}
+
+pub async fn execute(args: WaitArgs, global: &GlobalFlags) -> Result<()> {
+    let runtime = global.create_runtime()?;
+    let litebox = runtime
+        .get(&args.target)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("No such box: {}", args.target))?;
+
+    litebox
+        .wait_for(args.condition, args.timeout.map(Duration::from_secs))
+        .await?;
+    Ok(())
+}
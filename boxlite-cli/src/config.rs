@@ -1,33 +1,517 @@
 //! Configuration loading for BoxLite CLI.
 //!
-//! Handles loading configuration from JSON files.
+//! Handles loading configuration from JSON, TOML and YAML files; the format
+//! follows the file extension (`.toml`, `.yaml`/`.yml`, otherwise JSON).
 //! Uses `BoxliteOptions` directly to avoid maintaining duplicate config structures.
+//!
+//! A config file may also define named profiles, e.g. for dev, CI and prod.
+//! The selected profile is merged over the top-level options:
+//!
+//! ```toml
+//! image_registries = ["docker.io"]
+//!
+//! [profiles.ci]
+//! image_registries = ["mirror.internal"]
+//! max_running_boxes = 4
+//! log_level = "warn"
+//! ```
 
-use anyhow::{Context, Result, bail};
+use anyhow::{Context, Result, anyhow, bail};
 use boxlite::runtime::options::BoxliteOptions;
+use clap::ValueEnum;
+use serde_json::{Map, Value};
+use std::fmt;
 use std::path::Path;
 
-/// Load configuration from a JSON file.
-///
-/// # Arguments
+/// Config file key holding the named profiles.
+const PROFILES_KEY: &str = "profiles";
+
+/// Config file key for the CLI log level (not a `BoxliteOptions` field).
+const LOG_LEVEL_KEY: &str = "log_level";
+
+/// Runtime options and CLI settings from a config file, with a profile applied.
+#[derive(Debug)]
+pub struct Config {
+    pub options: BoxliteOptions,
+    /// Tracing filter (e.g. "warn", "debug") when the file sets `log_level`
+    pub log_level: Option<String>,
+}
+
+/// Config file syntax.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+#[value(rename_all = "lower")]
+pub enum ConfigFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl ConfigFormat {
+    /// The format for `path` by extension, JSON unless `.toml`, `.yaml` or `.yml`.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::Toml,
+            Some("yaml" | "yml") => Self::Yaml,
+            _ => Self::Json,
+        }
+    }
+
+    /// Parse `content` into the top-level mapping of options.
+    fn parse(self, content: &str) -> std::result::Result<Map<String, Value>, Diagnostic> {
+        let value = match self {
+            Self::Json => serde_json::from_str::<Value>(content).map_err(|e| Diagnostic {
+                line: e.line(),
+                column: e.column(),
+                message: strip_position(&e.to_string()),
+            })?,
+            Self::Toml => toml::from_str::<Value>(content).map_err(|e| match e.span() {
+                Some(span) => Diagnostic::at(content, span.start, e.message().to_string()),
+                None => Diagnostic {
+                    line: 0,
+                    column: 0,
+                    message: e.message().to_string(),
+                },
+            })?,
+            Self::Yaml => serde_yaml::from_str::<Value>(content).map_err(|e| {
+                let (line, column) = e.location().map_or((0, 0), |l| (l.line(), l.column()));
+                Diagnostic {
+                    line,
+                    column,
+                    message: strip_position(&e.to_string()),
+                }
+            })?,
+        };
+        match value {
+            Value::Object(root) => Ok(root),
+            // An empty YAML document
+            Value::Null => Ok(Map::new()),
+            _ => Err(Diagnostic::at(
+                content,
+                content.len() - content.trim_start().len(),
+                "expected a mapping of options at the top level".to_string(),
+            )),
+        }
+    }
+}
+
+/// A problem found by [`validate`], with its position when it is known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// 1-based line, or 0 when the problem cannot be located
+    pub line: usize,
+    /// 1-based column, or 0 when the problem cannot be located
+    pub column: usize,
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// A diagnostic at byte `offset` of `content`.
+    fn at(content: &str, offset: usize, message: String) -> Self {
+        let before = &content[..offset];
+        let line = before.matches('\n').count() + 1;
+        let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+        Self {
+            line,
+            column,
+            message,
+        }
+    }
+
+    /// A diagnostic at the first `"key":` at or after `start`, if there is one.
+    fn at_key(content: &str, start: usize, key: &str, message: String) -> Self {
+        match key_offset(content, start, key) {
+            Some(offset) => Self::at(content, offset, message),
+            None => Self {
+                line: 0,
+                column: 0,
+                message,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line > 0 {
+            write!(f, "line {}, column {}: ", self.line, self.column)?;
+        }
+        f.write_str(&self.message)
+    }
+}
+
+/// Load a configuration file with `profile` merged over its top-level options.
 ///
-/// * `path` - Path to the configuration file
+/// Profile objects are merged key by key (nested objects too); any other
+/// value, including a list, replaces the top-level one.
 ///
 /// # Errors
 ///
-/// Returns an error if:
-/// - The file does not exist
-/// - The file cannot be read or parsed
-pub fn load_config(path: &Path) -> Result<BoxliteOptions> {
+/// Returns an error if the file cannot be loaded or parsed, or if it does
+/// not define `profile`.
+pub fn load_profile(path: &Path, profile: Option<&str>) -> Result<Config> {
     if !path.exists() {
         bail!("Configuration file not found: {}", path.display());
     }
 
     let content = std::fs::read_to_string(path)
         .with_context(|| format!("Failed to read config file {}", path.display()))?;
+    let format = ConfigFormat::from_path(path);
+    let diagnostics = validate(&content, format);
+    if !diagnostics.is_empty() {
+        let details: Vec<_> = diagnostics.iter().map(|d| format!("  {}", d)).collect();
+        bail!(
+            "Failed to parse config file {}:\n{}",
+            path.display(),
+            details.join("\n")
+        );
+    }
+    let mut root = format
+        .parse(&content)
+        .map_err(|d| anyhow!("Failed to parse config file {}: {}", path.display(), d))?;
+
+    let mut profiles = match root.remove(PROFILES_KEY) {
+        None => Map::new(),
+        Some(Value::Object(profiles)) => profiles,
+        Some(_) => bail!(
+            "Failed to parse config file {}: \"{}\" must be an object",
+            path.display(),
+            PROFILES_KEY
+        ),
+    };
+    if let Some(name) = profile {
+        let Some(overrides) = profiles.remove(name) else {
+            let mut known: Vec<_> = profiles.keys().map(String::as_str).collect();
+            known.sort_unstable();
+            bail!(
+                "Profile '{}' is not defined in {} (available: {})",
+                name,
+                path.display(),
+                if known.is_empty() {
+                    "none".to_string()
+                } else {
+                    known.join(", ")
+                }
+            );
+        };
+        let Value::Object(overrides) = overrides else {
+            bail!(
+                "Failed to parse config file {}: profile '{}' must be an object",
+                path.display(),
+                name
+            );
+        };
+        merge(&mut root, overrides);
+    }
+
+    let log_level = match root.remove(LOG_LEVEL_KEY) {
+        None | Some(Value::Null) => None,
+        Some(Value::String(level)) => Some(level),
+        Some(_) => bail!(
+            "Failed to parse config file {}: \"{}\" must be a string",
+            path.display(),
+            LOG_LEVEL_KEY
+        ),
+    };
+    let options = serde_json::from_value::<BoxliteOptions>(Value::Object(root))
+        .with_context(|| format!("Failed to parse config file {}", path.display()))?;
+
+    Ok(Config { options, log_level })
+}
+
+/// Check a config file's contents against the options schema.
+///
+/// Reports JSON syntax errors, unknown keys (suggesting the closest known
+/// one), wrong value types and malformed profiles. Every profile is checked
+/// merged over the top-level options, whether or not it is selected. Serde
+/// stops at the first error in each, so fixing one may reveal the next.
+pub fn validate(content: &str, format: ConfigFormat) -> Vec<Diagnostic> {
+    let mut root = match format.parse(content) {
+        Ok(root) => root,
+        Err(diagnostic) => return vec![diagnostic],
+    };
+
+    let mut diagnostics = Vec::new();
+    let profiles = match root.remove(PROFILES_KEY) {
+        None => Map::new(),
+        Some(Value::Object(profiles)) => profiles,
+        Some(_) => {
+            diagnostics.push(Diagnostic::at_key(
+                content,
+                0,
+                PROFILES_KEY,
+                format!("\"{}\" must be an object", PROFILES_KEY),
+            ));
+            Map::new()
+        }
+    };
+
+    let base = check_options(content, 0, root.clone());
+    diagnostics.extend(base.clone());
+    let profiles_start = key_offset(content, 0, PROFILES_KEY).unwrap_or(0);
+    for (name, overrides) in profiles {
+        let start = key_offset(content, profiles_start, &name).unwrap_or(profiles_start);
+        let found = match overrides {
+            Value::Object(overrides) => {
+                let mut merged = root.clone();
+                merge(&mut merged, overrides);
+                // An error the top-level options already have is reported once
+                check_options(content, start, merged)
+                    .filter(|d| base.as_ref().is_none_or(|b| b.message != d.message))
+            }
+            _ => Some(Diagnostic::at(
+                content,
+                start,
+                "must be an object".to_string(),
+            )),
+        };
+        diagnostics.extend(found.map(|d| Diagnostic {
+            message: format!("profile '{}': {}", name, d.message),
+            ..d
+        }));
+    }
+    diagnostics
+}
+
+/// Deserialize one set of options, reporting the first problem.
+///
+/// `start` is where to look for an unknown key: the top of the file or the
+/// profile's own object.
+fn check_options(
+    content: &str,
+    start: usize,
+    mut options: Map<String, Value>,
+) -> Option<Diagnostic> {
+    if let Some(level) = options.remove(LOG_LEVEL_KEY)
+        && !matches!(level, Value::String(_) | Value::Null)
+    {
+        return Some(Diagnostic::at_key(
+            content,
+            start,
+            LOG_LEVEL_KEY,
+            format!("\"{}\" must be a string", LOG_LEVEL_KEY),
+        ));
+    }
+    let message = serde_json::from_value::<BoxliteOptions>(Value::Object(options))
+        .err()?
+        .to_string();
+
+    // serde reports: unknown field `x`, expected one of `a`, `b`
+    let Some((field, expected)) = message
+        .strip_prefix("unknown field `")
+        .and_then(|rest| rest.split_once('`'))
+    else {
+        return Some(Diagnostic {
+            line: 0,
+            column: 0,
+            message,
+        });
+    };
+    let mut candidates: Vec<&str> = expected.split('`').skip(1).step_by(2).collect();
+    if start == 0 {
+        candidates.extend([PROFILES_KEY, LOG_LEVEL_KEY]);
+    } else {
+        candidates.push(LOG_LEVEL_KEY);
+    }
+    let mut message = format!("unknown field `{}`", field);
+    if let Some(suggestion) = closest(field, &candidates) {
+        message.push_str(&format!(", did you mean `{}`?", suggestion));
+    }
+    Some(Diagnostic::at_key(content, start, field, message))
+}
+
+/// The candidate closest to `name` by edit distance, if it is close enough
+/// to be a likely typo.
+fn closest<'a>(name: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let limit = (name.chars().count() / 3).max(2);
+    candidates
+        .iter()
+        .map(|c| (edit_distance(name, c), *c))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, c)| c)
+}
+
+/// Levenshtein distance between `a` and `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// Byte offset of the first use of `key` as a key at or after `start`.
+///
+/// Matches `"key":` in JSON, `key:` in YAML and `key =`, `[key]` or
+/// `[parent.key]` in TOML, quoted or not.
+fn key_offset(content: &str, start: usize, key: &str) -> Option<usize> {
+    let mut from = start;
+    while let Some(found) = content[from..].find(key) {
+        let mut at = from + found;
+        let mut end = at + key.len();
+        from = end;
+        if let (Some(open @ ('"' | '\'')), Some(close)) = (
+            content[..at].chars().next_back(),
+            content[end..].chars().next(),
+        ) && open == close
+        {
+            at -= 1;
+            end += 1;
+        }
+        let left = content[..at].chars().next_back();
+        let right = content[end..]
+            .trim_start_matches([' ', '\t'])
+            .chars()
+            .next();
+        if left.is_none_or(|c| c.is_whitespace() || matches!(c, '{' | ',' | '[' | '.'))
+            && matches!(right, Some(':' | '=' | ']' | '.'))
+        {
+            return Some(at);
+        }
+    }
+    None
+}
+
+/// Drop each " at line L column C" that serde_json and serde_yaml add.
+fn strip_position(message: &str) -> String {
+    let mut stripped = String::new();
+    let mut rest = message;
+    while let Some(at) = rest.find(" at line ") {
+        stripped.push_str(&rest[..at]);
+        rest = rest[at + " at line ".len()..].trim_start_matches(|c: char| c.is_ascii_digit());
+        if let Some(after) = rest.strip_prefix(" column ") {
+            rest = after.trim_start_matches(|c: char| c.is_ascii_digit());
+        }
+    }
+    stripped.push_str(rest);
+    stripped
+}
+
+/// A commented starter config for `boxlite config init`.
+///
+/// JSON has no comments, so its starter only shows the layout.
+pub fn starter(format: ConfigFormat) -> &'static str {
+    match format {
+        ConfigFormat::Json => STARTER_JSON,
+        ConfigFormat::Toml => STARTER_TOML,
+        ConfigFormat::Yaml => STARTER_YAML,
+    }
+}
+
+const STARTER_JSON: &str = r#"{
+  "image_registries": ["docker.io"],
+  "profiles": {
+    "dev": {"log_level": "debug"},
+    "ci": {"log_level": "warn", "max_running_boxes": 4}
+  }
+}
+"#;
+
+const STARTER_TOML: &str = r#"# BoxLite configuration: boxlite --config boxlite.toml <command>
+# Check it with `boxlite config validate`. Uncomment a line to change a default.
+
+# Runtime data directory (default: ~/.boxlite)
+# home_dir = "/var/lib/boxlite"
+
+# Registries searched for unqualified image names, in order
+image_registries = ["docker.io"]
+
+# Bandwidth limit for image layer downloads, in Mbit/s
+# pull_rate_limit_mbps = 100
+
+# Retry transient image pull and guest boot failures this many times
+# create_retries = 2
+
+# Caps on the boxes this runtime runs at once
+# max_running_boxes = 8
+# max_total_memory_mib = 16384
+
+# Log verbosity unless --debug or RUST_LOG is given
+# log_level = "info"
+
+# Mirrors tried in order before the registry itself
+# [registry_mirrors]
+# "docker.io" = ["mirror.internal"]
+
+# Remove old stopped boxes and unused images automatically
+# [retention]
+# max_stopped_box_age_secs = 604800
+# max_image_cache_mib = 20480
+
+# Profiles merged over the options above, selected with --config-profile NAME
+# or BOXLITE_PROFILE=NAME
+[profiles.dev]
+log_level = "debug"
+
+[profiles.ci]
+log_level = "warn"
+max_running_boxes = 4
+"#;
+
+const STARTER_YAML: &str = r#"# BoxLite configuration: boxlite --config boxlite.yaml <command>
+# Check it with `boxlite config validate`. Uncomment a line to change a default.
+
+# Runtime data directory (default: ~/.boxlite)
+# home_dir: /var/lib/boxlite
 
-    serde_json::from_str::<BoxliteOptions>(&content)
-        .with_context(|| format!("Failed to parse config file {}", path.display()))
+# Registries searched for unqualified image names, in order
+image_registries:
+  - docker.io
+
+# Bandwidth limit for image layer downloads, in Mbit/s
+# pull_rate_limit_mbps: 100
+
+# Retry transient image pull and guest boot failures this many times
+# create_retries: 2
+
+# Caps on the boxes this runtime runs at once
+# max_running_boxes: 8
+# max_total_memory_mib: 16384
+
+# Log verbosity unless --debug or RUST_LOG is given
+# log_level: info
+
+# Mirrors tried in order before the registry itself
+# registry_mirrors:
+#   docker.io:
+#     - mirror.internal
+
+# Remove old stopped boxes and unused images automatically
+# retention:
+#   max_stopped_box_age_secs: 604800
+#   max_image_cache_mib: 20480
+
+# Profiles merged over the options above, selected with --config-profile NAME
+# or BOXLITE_PROFILE=NAME
+profiles:
+  dev:
+    log_level: debug
+  ci:
+    log_level: warn
+    max_running_boxes: 4
+"#;
+
+/// Merge `overrides` into `base`: objects recursively, other values replace.
+fn merge(base: &mut Map<String, Value>, overrides: Map<String, Value>) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Object(base)), Value::Object(value)) => merge(base, value),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -37,6 +521,11 @@ mod tests {
     use std::path::PathBuf;
     use tempfile::TempDir;
 
+    /// Load the top-level options of a config file, without a profile.
+    fn load_config(path: &Path) -> Result<BoxliteOptions> {
+        Ok(load_profile(path, None)?.options)
+    }
+
     #[test]
     fn test_load_config() {
         let temp_dir = TempDir::new().unwrap();
@@ -61,6 +550,37 @@ mod tests {
         assert_eq!(config.image_registries, vec!["docker.io"]);
     }
 
+    #[test]
+    fn test_load_config_with_storage_dirs() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.json");
+        let config_content =
+            r#"{"state_dir": "/nvme/boxlite", "image_cache_dir": "/hdd/boxlite-images"}"#;
+        fs::write(&config_path, config_content).unwrap();
+
+        let config = load_config(&config_path).unwrap();
+        assert_eq!(config.state_dir, Some(PathBuf::from("/nvme/boxlite")));
+        assert_eq!(
+            config.image_cache_dir,
+            Some(PathBuf::from("/hdd/boxlite-images"))
+        );
+        assert_eq!(config.volumes_dir, None);
+    }
+
+    #[test]
+    fn test_load_config_with_retention() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.json");
+        let config_content = r#"{"retention": {"max_image_cache_mib": 20480}}"#;
+        fs::write(&config_path, config_content).unwrap();
+
+        let config = load_config(&config_path).unwrap();
+        let retention = config.retention.unwrap();
+        assert_eq!(retention.max_image_cache_mib, Some(20480));
+        assert_eq!(retention.max_stopped_box_age_secs, None);
+        assert_eq!(retention.interval_secs, 300);
+    }
+
     #[test]
     fn test_load_empty_config() {
         let temp_dir = TempDir::new().unwrap();
@@ -83,6 +603,217 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("not found"));
     }
 
+    #[test]
+    fn test_load_profile_merges_over_top_level() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.json");
+        let config_content = r#"{
+            "image_registries": ["docker.io"],
+            "retention": {"max_image_cache_mib": 20480, "interval_secs": 60},
+            "log_level": "info",
+            "profiles": {
+                "ci": {
+                    "image_registries": ["mirror.internal"],
+                    "retention": {"max_stopped_box_age_secs": 3600},
+                    "max_running_boxes": 4,
+                    "log_level": "warn"
+                },
+                "dev": {}
+            }
+        }"#;
+        fs::write(&config_path, config_content).unwrap();
+
+        let config = load_profile(&config_path, Some("ci")).unwrap();
+        assert_eq!(config.options.image_registries, vec!["mirror.internal"]);
+        assert_eq!(config.options.max_running_boxes, Some(4));
+        let retention = config.options.retention.unwrap();
+        assert_eq!(retention.max_image_cache_mib, Some(20480));
+        assert_eq!(retention.max_stopped_box_age_secs, Some(3600));
+        assert_eq!(retention.interval_secs, 60);
+        assert_eq!(config.log_level.as_deref(), Some("warn"));
+
+        // No profile: top-level options only
+        let config = load_profile(&config_path, None).unwrap();
+        assert_eq!(config.options.image_registries, vec!["docker.io"]);
+        assert_eq!(config.options.max_running_boxes, None);
+        assert_eq!(config.log_level.as_deref(), Some("info"));
+    }
+
+    #[test]
+    fn test_load_profile_unknown_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.json");
+        fs::write(&config_path, r#"{"profiles": {"dev": {}, "ci": {}}}"#).unwrap();
+
+        let err = load_profile(&config_path, Some("prod"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Profile 'prod' is not defined"));
+        assert!(err.contains("available: ci, dev"));
+    }
+
+    #[test]
+    fn test_validate_unknown_field_suggests() {
+        let content = "{\n  \"image_registries\": [],\n  \"retention\": {\"intervl_secs\": 60}\n}";
+        let diagnostics = validate(content, ConfigFormat::Json);
+        assert_eq!(diagnostics.len(), 1);
+        let d = &diagnostics[0];
+        assert_eq!((d.line, d.column), (3, 17));
+        assert_eq!(
+            d.message,
+            "unknown field `intervl_secs`, did you mean `interval_secs`?"
+        );
+    }
+
+    #[test]
+    fn test_validate_checks_every_profile() {
+        let content = r#"{
+  "max_running_boxes": 2,
+  "profiles": {
+    "ci": {"image_registry": ["mirror.internal"]},
+    "dev": {"log_level": "debug"},
+    "prod": {"max_running_boxes": "many"}
+  }
+}"#;
+        let diagnostics = validate(content, ConfigFormat::Json);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (4, 12));
+        assert_eq!(
+            diagnostics[0].message,
+            "profile 'ci': unknown field `image_registry`, did you mean `image_registries`?"
+        );
+        assert_eq!(diagnostics[1].line, 0);
+        assert!(
+            diagnostics[1]
+                .message
+                .starts_with("profile 'prod': invalid type")
+        );
+    }
+
+    #[test]
+    fn test_validate_syntax_error_has_position() {
+        let diagnostics = validate("{\n  \"home_dir\": \"/x\",\n}", ConfigFormat::Json);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 3);
+        assert!(!diagnostics[0].message.contains(" at line "));
+        assert!(validate(r#"{"image_registries": ["docker.io"]}"#, ConfigFormat::Json).is_empty());
+    }
+
+    #[test]
+    fn test_load_config_rejects_unknown_field() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.json");
+        fs::write(&config_path, r#"{"image_registrys": ["ghcr.io"]}"#).unwrap();
+
+        let err = load_config(&config_path).unwrap_err().to_string();
+        assert!(err.contains("Failed to parse"));
+        assert!(err.contains("line 1, column 2: unknown field `image_registrys`"));
+        assert!(err.contains("did you mean `image_registries`?"));
+    }
+
+    #[test]
+    fn test_formats_load_the_same_options() {
+        let temp_dir = TempDir::new().unwrap();
+        let files = [
+            (
+                "config.json",
+                r#"{"image_registries": ["ghcr.io"], "retention": {"max_image_cache_mib": 1024},
+                    "profiles": {"ci": {"max_running_boxes": 4, "log_level": "warn"}}}"#,
+            ),
+            (
+                "config.toml",
+                "image_registries = [\"ghcr.io\"]\n\n[retention]\nmax_image_cache_mib = 1024\n\n\
+                 [profiles.ci]\nmax_running_boxes = 4\nlog_level = \"warn\"\n",
+            ),
+            (
+                "config.yml",
+                "image_registries: [ghcr.io]\nretention:\n  max_image_cache_mib: 1024\n\
+                 profiles:\n  ci:\n    max_running_boxes: 4\n    log_level: warn\n",
+            ),
+        ];
+
+        let mut loaded = Vec::new();
+        for (name, content) in files {
+            let path = temp_dir.path().join(name);
+            fs::write(&path, content).unwrap();
+            let config = load_profile(&path, Some("ci")).unwrap();
+            assert_eq!(config.options.max_running_boxes, Some(4), "{name}");
+            assert_eq!(config.log_level.as_deref(), Some("warn"), "{name}");
+            loaded.push(serde_json::to_value(&config.options).unwrap());
+        }
+        assert_eq!(loaded[0], loaded[1]);
+        assert_eq!(loaded[0], loaded[2]);
+    }
+
+    #[test]
+    fn test_round_trip_through_each_format() {
+        let temp_dir = TempDir::new().unwrap();
+        let json_path = temp_dir.path().join("config.json");
+        let config_content = r#"{
+            "image_registries": ["ghcr.io", "docker.io"],
+            "registry_mirrors": {"docker.io": ["mirror.internal"]},
+            "state_dir": "/nvme/boxlite",
+            "max_running_boxes": 8,
+            "retention": {"max_stopped_box_age_secs": 3600}
+        }"#;
+        fs::write(&json_path, config_content).unwrap();
+        let options = load_config(&json_path).unwrap();
+        let expected = serde_json::to_value(&options).unwrap();
+
+        let serialized = [
+            ("out.json", serde_json::to_string_pretty(&options).unwrap()),
+            ("out.toml", toml::to_string(&options).unwrap()),
+            ("out.yaml", serde_yaml::to_string(&options).unwrap()),
+        ];
+        for (name, content) in serialized {
+            let path = temp_dir.path().join(name);
+            fs::write(&path, content).unwrap();
+            let reloaded = load_config(&path).unwrap();
+            assert_eq!(serde_json::to_value(&reloaded).unwrap(), expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_starter_configs_are_valid() {
+        let temp_dir = TempDir::new().unwrap();
+        for (format, name) in [
+            (ConfigFormat::Json, "boxlite.json"),
+            (ConfigFormat::Toml, "boxlite.toml"),
+            (ConfigFormat::Yaml, "boxlite.yaml"),
+        ] {
+            assert!(validate(starter(format), format).is_empty(), "{name}");
+            let path = temp_dir.path().join(name);
+            fs::write(&path, starter(format)).unwrap();
+            let config = load_profile(&path, Some("ci")).unwrap();
+            assert_eq!(config.options.image_registries, vec!["docker.io"], "{name}");
+            assert_eq!(config.options.max_running_boxes, Some(4), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_validate_toml_and_yaml_positions() {
+        let toml = "image_registries = []\n\n[profiles.ci]\nmax_runing_boxes = 4\n";
+        let diagnostics = validate(toml, ConfigFormat::Toml);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (4, 1));
+        assert!(
+            diagnostics[0]
+                .message
+                .ends_with("did you mean `max_running_boxes`?")
+        );
+
+        let yaml = "profiles:\n  ci:\n    image_registrys: [ghcr.io]\n";
+        let diagnostics = validate(yaml, ConfigFormat::Yaml);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (3, 5));
+
+        let diagnostics = validate("a = \n", ConfigFormat::Toml);
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (1, 5));
+        let diagnostics = validate("a: [1\nb: 2\n", ConfigFormat::Yaml);
+        assert_eq!(diagnostics[0].line, 2);
+        assert!(!diagnostics[0].message.contains(" at line "));
+    }
+
     #[test]
     fn test_config_invalid_fails() {
         let temp_dir = TempDir::new().unwrap();
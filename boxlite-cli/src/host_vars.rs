@@ -0,0 +1,128 @@
+//! Host metadata variables in `--env` and `--volume` values.
+//!
+//! `{{uid}}`, `{{gid}}`, `{{user}}`, `{{home}}` and `{{cwd}}` expand to the
+//! invoking user's values before the flag is parsed, so
+//! `-e HOST_UID={{uid}} -v {{home}}/data:/data` needs no wrapper script.
+//! A backslash keeps the braces: `\{{uid}}` becomes `{{uid}}`.
+
+use anyhow::{Result, anyhow, bail};
+
+/// Variable names, as listed in errors.
+const NAMES: &str = "uid, gid, user, home, cwd";
+
+/// Expand host variables in `input`.
+pub fn expand(input: &str) -> Result<String> {
+    expand_with(input, host_var)
+}
+
+/// Value of a host variable; `None` for an unknown name.
+fn host_var(name: &str) -> Option<Result<String>> {
+    let value = match name {
+        "uid" => Ok(nix::unistd::getuid().to_string()),
+        "gid" => Ok(nix::unistd::getgid().to_string()),
+        "user" => nix::unistd::User::from_uid(nix::unistd::getuid())
+            .ok()
+            .flatten()
+            .map(|user| user.name)
+            .or_else(|| std::env::var("USER").ok())
+            .ok_or_else(|| anyhow!("cannot determine the host user name")),
+        "home" => dirs::home_dir()
+            .map(|home| home.to_string_lossy().into_owned())
+            .ok_or_else(|| anyhow!("cannot determine the host home directory")),
+        "cwd" => std::env::current_dir()
+            .map(|dir| dir.to_string_lossy().into_owned())
+            .map_err(|e| anyhow!("cannot determine the current directory: {}", e)),
+        _ => return None,
+    };
+    Some(value)
+}
+
+fn expand_with<F>(input: &str, lookup: F) -> Result<String>
+where
+    F: Fn(&str) -> Option<Result<String>>,
+{
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find("{{") {
+        if let Some(literal) = rest[..start].strip_suffix('\\') {
+            out.push_str(literal);
+            out.push_str("{{");
+            rest = &rest[start + 2..];
+            continue;
+        }
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            bail!(
+                "unclosed {{{{ in {:?} (write \\{{{{ for literal braces)",
+                input
+            );
+        };
+        let name = after[..end].trim();
+        let value = lookup(name).ok_or_else(|| {
+            anyhow!(
+                "unknown variable {{{{{}}}}} in {:?} (known: {}; write \\{{{{ for literal braces)",
+                name,
+                input,
+                NAMES
+            )
+        })??;
+        out.push_str(&value);
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<Result<String>> {
+        match name {
+            "uid" => Some(Ok("1000".to_string())),
+            "home" => Some(Ok("/home/dev".to_string())),
+            "user" => Some(Err(anyhow!("cannot determine the host user name"))),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_expand_host_vars() {
+        let expand = |s: &str| expand_with(s, lookup);
+        assert_eq!(expand("HOST_UID={{uid}}").unwrap(), "HOST_UID=1000");
+        assert_eq!(
+            expand("{{ home }}/data:/data:ro").unwrap(),
+            "/home/dev/data:/data:ro"
+        );
+        assert_eq!(expand("A={{uid}}-{{uid}}").unwrap(), "A=1000-1000");
+        assert_eq!(expand("PLAIN=}} {").unwrap(), "PLAIN=}} {");
+
+        // Escaped braces stay, and only the backslash is dropped
+        assert_eq!(expand(r"TPL=\{{uid}}").unwrap(), "TPL={{uid}}");
+        assert_eq!(expand(r"TPL=\{{uid}}:{{uid}}").unwrap(), "TPL={{uid}}:1000");
+
+        let err = expand("X={{nope}}").unwrap_err().to_string();
+        assert!(err.contains("unknown variable {{nope}}"), "{}", err);
+        assert!(err.contains("known: uid, gid"), "{}", err);
+        assert!(
+            expand("X={{uid")
+                .unwrap_err()
+                .to_string()
+                .contains("unclosed")
+        );
+        assert!(expand("X={{user}}").is_err());
+    }
+
+    #[test]
+    fn test_host_vars_resolve() {
+        assert_eq!(
+            expand("{{uid}}:{{gid}}").unwrap(),
+            format!("{}:{}", nix::unistd::getuid(), nix::unistd::getgid())
+        );
+        assert_eq!(
+            expand("{{cwd}}").unwrap(),
+            std::env::current_dir().unwrap().to_string_lossy()
+        );
+    }
+}
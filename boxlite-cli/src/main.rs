@@ -2,6 +2,7 @@ mod cli;
 mod commands;
 mod config;
 mod formatter;
+mod host_vars;
 pub mod terminal;
 pub mod util;
 
@@ -27,8 +28,12 @@ fn main() {
 
     // Handle shell completion before starting tokio or tracing
     if let cli::Commands::Completion(args) = &cli.command {
-        let mut cmd = Cli::command();
-        cli::generate_completion(&args.shell, &mut cmd, "boxlite", &mut std::io::stdout());
+        if let Some(kind) = args.dynamic {
+            cli::write_dynamic_candidates(kind, &cli.global, &mut std::io::stdout());
+        } else if let Some(shell) = &args.shell {
+            let mut cmd = Cli::command();
+            cli::generate_completion(shell, &mut cmd, "boxlite", &mut std::io::stdout());
+        }
         process::exit(0);
     }
 
@@ -42,11 +47,21 @@ fn main() {
 }
 
 async fn run_cli(cli: Cli) -> anyhow::Result<()> {
-    // Initialize tracing based on --debug flag
-    let level = if cli.global.debug { "debug" } else { "info" };
+    // Initialize tracing: RUST_LOG, then --debug, then the config file's
+    // log_level. Config errors are reported when the command loads it.
+    let level = if cli.global.debug {
+        "debug".to_string()
+    } else {
+        cli.global
+            .load_config()
+            .ok()
+            .flatten()
+            .and_then(|config| config.log_level)
+            .unwrap_or_else(|| "info".to_string())
+    };
     let env_filter = EnvFilter::try_from_default_env()
-        .or_else(|_| EnvFilter::try_new(level))
-        .unwrap_or_else(|_| EnvFilter::new(level));
+        .or_else(|_| EnvFilter::try_new(&level))
+        .unwrap_or_else(|_| EnvFilter::new("info"));
 
     tracing_subscriber::registry()
         .with(env_filter)
@@ -63,10 +78,36 @@ async fn run_cli(cli: Cli) -> anyhow::Result<()> {
         cli::Commands::Start(args) => commands::start::execute(args, &global).await,
         cli::Commands::Stop(args) => commands::stop::execute(args, &global).await,
         cli::Commands::Restart(args) => commands::restart::execute(args, &global).await,
+        cli::Commands::Pause(args) => commands::pause::execute(args, &global).await,
+        cli::Commands::Unpause(args) => commands::unpause::execute(args, &global).await,
+        cli::Commands::Clone(args) => commands::clone::execute(args, &global).await,
+        cli::Commands::Export(args) => commands::export::execute(args, &global).await,
+        cli::Commands::Import(args) => commands::import::execute(args, &global).await,
         cli::Commands::Pull(args) => commands::pull::execute(args, &global).await,
+        cli::Commands::Tag(args) => commands::tag::execute(args, &global).await,
+        cli::Commands::Push(args) => commands::push::execute(args, &global).await,
+        cli::Commands::RegistryCache(args) => {
+            commands::registry_cache::execute(args, &global).await
+        }
+        cli::Commands::Build(args) => commands::build::execute(args, &global).await,
         cli::Commands::Images(args) => commands::images::execute(args, &global).await,
+        cli::Commands::System(args) => commands::system::execute(args, &global).await,
+        cli::Commands::Config(args) => commands::config::execute(args, &global).await,
         cli::Commands::Inspect(args) => commands::inspect::execute(args, &global).await,
         cli::Commands::Cp(args) => commands::cp::execute(args, &global).await,
+        cli::Commands::Sync(args) => commands::sync::execute(args, &global).await,
+        cli::Commands::Port(args) => commands::port::execute(args, &global).await,
+        cli::Commands::Net(args) => commands::net::execute(args, &global).await,
+        cli::Commands::Top(args) => commands::top::execute(args, &global).await,
+        cli::Commands::Stats(args) => commands::stats::execute(args, &global).await,
+        cli::Commands::Wait(args) => commands::wait::execute(args, &global).await,
+        cli::Commands::Mount(args) => commands::mount::execute(args, &global).await,
+        cli::Commands::Umount(args) => commands::umount::execute(args, &global).await,
+        cli::Commands::Ssh(args) => commands::ssh::execute(args, &global).await,
+        cli::Commands::Devcontainer(args) => commands::devcontainer::execute(args, &global).await,
+        cli::Commands::Dash(args) => commands::dash::execute(args, &global).await,
+        cli::Commands::Debug(args) => commands::debug::execute(args, &global).await,
+        cli::Commands::Mcp(args) => commands::mcp::execute(args, &global).await,
         // Handled in main() before tokio; never reaches run_cli
         cli::Commands::Completion(_) => {
             unreachable!("completion subcommand is handled before tokio in main()")
@@ -102,7 +102,7 @@ impl<'a> StreamManager<'a> {
             if let Some(mut stream) = stdout_stream {
                 let mut stdout = tokio::io::stdout();
                 while let Some(chunk) = stream.next().await {
-                    if let Err(e) = stdout.write_all(chunk.as_bytes()).await {
+                    if let Err(e) = stdout.write_all(&chunk).await {
                         if e.kind() != std::io::ErrorKind::BrokenPipe {
                             tracing::debug!("stdout write error: {}", e);
                         }
@@ -123,9 +123,9 @@ impl<'a> StreamManager<'a> {
 
                 while let Some(chunk) = stream.next().await {
                     let res = if tty_mode {
-                        stdout.write_all(chunk.as_bytes()).await
+                        stdout.write_all(&chunk).await
                     } else {
-                        stderr.write_all(chunk.as_bytes()).await
+                        stderr.write_all(&chunk).await
                     };
 
                     if let Err(e) = res {
@@ -186,12 +186,13 @@ impl<'a> StreamManager<'a> {
                 res = self.execution.wait(), if exit_status.is_none() => {
                     match res {
                         Ok(status) => {
+                            let code = status.exit_code;
                             exit_status = Some(status);
                             if let Some(h) = stdin_handle.as_ref() {
                                 h.abort();
                             }
                             if io_done {
-                                break exit_status.unwrap().exit_code;
+                                break code;
                             }
                         }
                         Err(e) => {
@@ -232,6 +233,9 @@ impl<'a> StreamManager<'a> {
             }
         };
 
+        if exit_status.as_ref().is_some_and(|status| status.oom_killed) {
+            eprintln!("Warning: killed by the box kernel: out of memory (see --memory)");
+        }
         Ok(exit_code)
     }
 }
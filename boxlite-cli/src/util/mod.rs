@@ -2,8 +2,10 @@
 
 /// Convert boxlite exit code to shell exit code.
 ///
-/// Boxlite encodes signal termination as negative values (e.g., -9 for SIGKILL).
-/// Shell convention uses 128 + signal_number for signal termination.
+/// Boxlite already follows shell conventions: 127 for a missing program,
+/// 126 for one that cannot be executed and 128 + N for death by signal N.
+/// The only negative value, -1, means the exit status was lost (e.g. the
+/// guest connection dropped) and becomes a generic failure.
 ///
 /// # Examples
 ///
@@ -11,12 +13,12 @@
 /// # use boxlite_cli::utils::to_shell_exit_code;
 /// assert_eq!(to_shell_exit_code(0), 0);      // Normal success
 /// assert_eq!(to_shell_exit_code(1), 1);      // Normal failure
-/// assert_eq!(to_shell_exit_code(-9), 137);   // SIGKILL: 128 + 9
-/// assert_eq!(to_shell_exit_code(-15), 143);  // SIGTERM: 128 + 15
+/// assert_eq!(to_shell_exit_code(137), 137);  // SIGKILL: 128 + 9
+/// assert_eq!(to_shell_exit_code(-1), 1);     // Exit status lost
 /// ```
 pub fn to_shell_exit_code(boxlite_code: i32) -> i32 {
     match boxlite_code {
-        code if code < 0 => 128 + code.abs(),
+        code if code < 0 => 1,
         code => code,
     }
 }
@@ -34,15 +36,19 @@ mod tests {
     fn test_to_shell_exit_code_normal_failure() {
         assert_eq!(to_shell_exit_code(1), 1);
         assert_eq!(to_shell_exit_code(127), 127);
+        assert_eq!(to_shell_exit_code(126), 126);
     }
 
     #[test]
     fn test_to_shell_exit_code_signal_termination() {
         // SIGKILL (9)
-        assert_eq!(to_shell_exit_code(-9), 137);
+        assert_eq!(to_shell_exit_code(137), 137);
         // SIGTERM (15)
-        assert_eq!(to_shell_exit_code(-15), 143);
-        // SIGINT (2)
-        assert_eq!(to_shell_exit_code(-2), 130);
+        assert_eq!(to_shell_exit_code(143), 143);
+    }
+
+    #[test]
+    fn test_to_shell_exit_code_lost_status() {
+        assert_eq!(to_shell_exit_code(-1), 1);
     }
 }
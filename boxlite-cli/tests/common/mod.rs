@@ -49,10 +49,10 @@ pub fn boxlite() -> TestContext {
     let home = SHARED_HOME.get_or_init(|| {
         eprintln!("Initializing shared test environment...");
 
-        // Use a very short path in /tmp to avoid SUN_LEN limits for Unix sockets (104-108 chars)
-        // Project folders can be very deep, exceeding this limit when appended with /boxes/.../sockets/ready.sock
-        let test_home = PathBuf::from("/tmp/bl");
-        std::fs::create_dir_all(&test_home).expect("Failed to create /tmp/bl directory");
+        // Shared across runs so pulled images are reused. Deep target dirs
+        // are fine: box sockets move to a short path when needed.
+        let test_home = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("boxlite-home");
+        std::fs::create_dir_all(&test_home).expect("Failed to create test home directory");
 
         let home = test_home;
         let bin_path = env!("CARGO_BIN_EXE_boxlite");
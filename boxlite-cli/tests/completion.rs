@@ -115,3 +115,34 @@ fn completion_not_shown_in_help() {
         "completion subcommand should be hidden from --help"
     );
 }
+
+#[rstest]
+#[case("bash")]
+#[case("zsh")]
+#[case("fish")]
+fn completion_script_calls_dynamic_helper(#[case] shell: &str) {
+    boxlite_cmd()
+        .args(["completion", shell])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("boxlite completion --dynamic"));
+}
+
+#[test]
+fn completion_dynamic_empty_home() {
+    let home = tempfile::tempdir().unwrap();
+    for kind in ["boxes", "running", "stopped", "images"] {
+        boxlite_cmd()
+            .arg("--home")
+            .arg(home.path())
+            .args(["completion", "--dynamic", kind])
+            .assert()
+            .success()
+            .stdout("");
+    }
+}
+
+#[test]
+fn completion_requires_shell_or_dynamic() {
+    boxlite_cmd().arg("completion").assert().failure();
+}
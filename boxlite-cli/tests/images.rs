@@ -80,3 +80,24 @@ fn test_images_yaml_format() {
     assert!(stdout.contains("ID:") || stdout.trim() == "[]");
     assert!(stdout.contains("CreatedAt:") || stdout.trim() == "[]");
 }
+
+#[test]
+fn test_images_verify() {
+    let ctx = common::boxlite();
+    let _ = ctx.new_cmd().args(["pull", "alpine:latest"]).output();
+
+    ctx.new_cmd()
+        .args(["images", "verify"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("match their digest"));
+
+    let output = ctx
+        .new_cmd()
+        .args(["images", "verify", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["corrupt"], serde_json::json!([]));
+}
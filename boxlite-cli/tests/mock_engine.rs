@@ -0,0 +1,621 @@
+//! CLI tests on the in-process mock engine (`BOXLITE_ENGINE=mock`).
+//!
+//! No VM or image is needed, so these run anywhere and in milliseconds.
+//! Mock boxes only live as long as the CLI process, so each test is a
+//! single foreground invocation.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::time::Duration;
+
+fn boxlite_mock(home: &tempfile::TempDir) -> Command {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_boxlite"));
+    cmd.timeout(Duration::from_secs(30));
+    cmd.env("BOXLITE_ENGINE", "mock");
+    cmd.arg("--home").arg(home.path());
+    cmd
+}
+
+#[test]
+fn test_mock_run_echo() {
+    let home = tempfile::tempdir().unwrap();
+    boxlite_mock(&home)
+        .args(["run", "--rm", "alpine:latest", "echo", "hello", "mock"])
+        .assert()
+        .success()
+        .stdout("hello mock\n");
+}
+
+#[test]
+fn test_mock_run_exit_code() {
+    let home = tempfile::tempdir().unwrap();
+    boxlite_mock(&home)
+        .args(["run", "--rm", "alpine:latest", "false"])
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn test_mock_run_unknown_command() {
+    let home = tempfile::tempdir().unwrap();
+    boxlite_mock(&home)
+        .args(["run", "--rm", "alpine:latest", "does-not-exist"])
+        .assert()
+        .code(127)
+        .stderr(predicate::str::contains("command not found"));
+}
+
+#[test]
+fn test_mock_run_stdin() {
+    let home = tempfile::tempdir().unwrap();
+    boxlite_mock(&home)
+        .args(["run", "--rm", "-i", "alpine:latest", "cat"])
+        .write_stdin("from stdin")
+        .assert()
+        .success()
+        .stdout("from stdin");
+}
+
+#[test]
+fn test_mock_run_binary_output() {
+    let home = tempfile::tempdir().unwrap();
+    let data = b"50%\r100%\xff\x00no newline".to_vec();
+    boxlite_mock(&home)
+        .args(["run", "--rm", "-i", "alpine:latest", "cat"])
+        .write_stdin(data.clone())
+        .assert()
+        .success()
+        .stdout(data);
+}
+
+#[test]
+fn test_mock_run_output_dir() {
+    let home = tempfile::tempdir().unwrap();
+    let out = home.path().join("recording");
+    boxlite_mock(&home)
+        .args(["run", "--rm", "--output-dir"])
+        .arg(&out)
+        .args(["alpine:latest", "echo", "recorded"])
+        .assert()
+        .success()
+        .stdout("recorded\n");
+
+    assert_eq!(
+        std::fs::read_to_string(out.join("stdout.log")).unwrap(),
+        "recorded\n"
+    );
+    let manifest = std::fs::read_to_string(out.join("manifest.json")).unwrap();
+    assert!(manifest.contains("\"exit_code\": 0"), "{}", manifest);
+
+    boxlite_mock(&home)
+        .args(["run", "-d", "--record", "alpine:latest", "sleep", "1"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("--detach"));
+}
+
+#[test]
+fn test_mock_run_init_cmd_failure() {
+    let home = tempfile::tempdir().unwrap();
+    // The mock box has no /bin/sh, so the first setup command fails
+    boxlite_mock(&home)
+        .args([
+            "run",
+            "--rm",
+            "--init-cmd",
+            "apk add curl",
+            "alpine:latest",
+            "echo",
+            "unreachable",
+        ])
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(predicate::str::contains(
+            "init command 1 (apk add curl) failed with exit code 127",
+        ));
+}
+
+#[test]
+fn test_mock_run_unknown_host_var() {
+    let home = tempfile::tempdir().unwrap();
+    boxlite_mock(&home)
+        .args([
+            "run",
+            "--rm",
+            "-e",
+            "OWNER={{owner}}",
+            "alpine:latest",
+            "echo",
+            "unreachable",
+        ])
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(predicate::str::contains(
+            "unknown variable {{owner}} in \"OWNER={{owner}}\"",
+        ));
+}
+
+#[test]
+fn test_mock_dry_run_creates_nothing() {
+    let home = tempfile::tempdir().unwrap();
+    let output = boxlite_mock(&home)
+        .args([
+            "run",
+            "--dry-run",
+            "--name",
+            "planned",
+            "-p",
+            "8080:80",
+            "alpine:latest",
+            "echo",
+            "hi",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let plan: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(plan["name"], "planned");
+    assert_eq!(plan["engine"], "mock");
+    assert_eq!(plan["ports"][0]["host_port"], 8080);
+    assert_eq!(plan["command"], serde_json::json!(["echo", "hi"]));
+    assert!(plan["stages"].to_string().contains("mock_spawn"));
+
+    boxlite_mock(&home)
+        .args(["ls", "-a", "-q"])
+        .assert()
+        .success()
+        .stdout("");
+}
+
+#[test]
+fn test_mock_dry_run_browser_profile() {
+    let home = tempfile::tempdir().unwrap();
+    let output = boxlite_mock(&home)
+        .args([
+            "run",
+            "--dry-run",
+            "--profile",
+            "browser",
+            "--memory",
+            "4096",
+            "alpine:latest",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let plan: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    // Explicit flags win over the profile
+    assert_eq!(plan["memory_mib"], 4096);
+    assert_eq!(plan["ports"][0]["guest_port"], 9222);
+    assert!(
+        plan["env"]
+            .to_string()
+            .contains("--remote-debugging-port=9222")
+    );
+
+    boxlite_mock(&home)
+        .args(["run", "--dry-run", "--profile", "gpu", "alpine:latest"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown profile 'gpu'"));
+}
+
+#[test]
+fn test_mock_config_profile_with_run_profile() {
+    let home = tempfile::tempdir().unwrap();
+    let config = home.path().join("boxlite.json");
+    std::fs::write(&config, r#"{"profiles": {"ci": {"max_running_boxes": 4}}}"#).unwrap();
+    let config = config.to_str().unwrap();
+
+    // The config file profile and the box preset are separate flags
+    let output = boxlite_mock(&home)
+        .args(["--config", config, "--config-profile", "ci"])
+        .args(["run", "--dry-run", "--profile", "browser", "alpine:latest"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let plan: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(plan["ports"][0]["guest_port"], 9222);
+
+    boxlite_mock(&home)
+        .args(["--config", config, "--config-profile", "browser"])
+        .args(["run", "--dry-run", "alpine:latest"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Profile 'browser' is not defined"));
+
+    for var in ["BOXLITE_PROFILE", "BOXLITE_CONFIG_PROFILE"] {
+        boxlite_mock(&home)
+            .env(var, "ci")
+            .args(["run", "--dry-run", "--profile", "browser", "alpine:latest"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("no --config file given"));
+    }
+}
+
+#[test]
+fn test_mock_run_pull_only() {
+    let home = tempfile::tempdir().unwrap();
+    // Nothing listens on port 1, so the pull fails without a network
+    boxlite_mock(&home)
+        .args(["run", "--pull-only", "127.0.0.1:1/missing:latest"])
+        .assert()
+        .failure()
+        .stdout("")
+        .stderr(predicate::str::contains("127.0.0.1:1/missing:latest"));
+
+    boxlite_mock(&home)
+        .args(["ls", "-a", "-q"])
+        .assert()
+        .success()
+        .stdout("");
+
+    boxlite_mock(&home)
+        .args(["run", "--pull-only", "-d", "alpine:latest"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_mock_dry_run_priority() {
+    let home = tempfile::tempdir().unwrap();
+    let output = boxlite_mock(&home)
+        .args(["create", "--dry-run", "--priority", "low", "alpine:latest"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let plan: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(plan["priority"], "low");
+
+    boxlite_mock(&home)
+        .args([
+            "create",
+            "--dry-run",
+            "--priority",
+            "urgent",
+            "alpine:latest",
+        ])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_mock_network_disabled() {
+    let home = tempfile::tempdir().unwrap();
+    boxlite_mock(&home)
+        .args([
+            "run",
+            "--dry-run",
+            "--network",
+            "disabled",
+            "-p",
+            "8080:80",
+            "alpine:latest",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("requires networking"));
+
+    boxlite_mock(&home)
+        .args([
+            "run",
+            "-d",
+            "--network",
+            "disabled",
+            "--name",
+            "offline",
+            "alpine:latest",
+            "true",
+        ])
+        .assert()
+        .success();
+
+    let output = boxlite_mock(&home)
+        .args(["inspect", "offline"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let inspect: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(inspect[0]["Ports"], serde_json::json!({}));
+    assert!(inspect[0]["BootPhases"]["ContainerInitMs"].is_u64());
+}
+
+#[test]
+fn test_mock_run_reuse_names_warm_box() {
+    let home = tempfile::tempdir().unwrap();
+    let plan = |memory: &str| {
+        let output = boxlite_mock(&home)
+            .args([
+                "run",
+                "--dry-run",
+                "--reuse",
+                "--memory",
+                memory,
+                "alpine:latest",
+            ])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let plan: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        plan["name"].as_str().unwrap().to_string()
+    };
+
+    let name = plan("512");
+    assert!(name.starts_with("reuse-"));
+    assert_eq!(plan("512"), name);
+    assert_ne!(plan("1024"), name);
+
+    boxlite_mock(&home)
+        .args(["run", "--reuse", "--name", "web", "alpine:latest"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_mock_dynamic_completion_lists_boxes() {
+    let home = tempfile::tempdir().unwrap();
+    boxlite_mock(&home)
+        .args(["create", "--name", "completable", "alpine:latest"])
+        .assert()
+        .success();
+
+    boxlite_mock(&home)
+        .args(["completion", "--dynamic", "stopped"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("completable\n"));
+    boxlite_mock(&home)
+        .args(["completion", "--dynamic", "running"])
+        .assert()
+        .success()
+        .stdout("");
+}
+
+#[test]
+fn test_mock_top_requires_running_box() {
+    let home = tempfile::tempdir().unwrap();
+    boxlite_mock(&home)
+        .args(["create", "--name", "idle", "alpine:latest"])
+        .assert()
+        .success();
+
+    boxlite_mock(&home)
+        .args(["top", "idle"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not running"));
+    boxlite_mock(&home)
+        .args(["top", "missing"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No such box"));
+}
+
+#[test]
+fn test_mock_wait_conditions() {
+    let home = tempfile::tempdir().unwrap();
+    boxlite_mock(&home)
+        .args(["create", "--name", "idle", "alpine:latest"])
+        .assert()
+        .success();
+
+    // Never started, so already stopped
+    boxlite_mock(&home)
+        .args(["wait", "idle", "--timeout", "5"])
+        .assert()
+        .success();
+    boxlite_mock(&home)
+        .args(["wait", "idle", "--condition", "port:80", "--timeout", "5"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not running"));
+    boxlite_mock(&home)
+        .args(["wait", "idle", "--condition", "ready"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid wait condition"));
+}
+
+#[test]
+fn test_mock_port_listen_requires_running_box() {
+    let home = tempfile::tempdir().unwrap();
+    boxlite_mock(&home)
+        .args(["create", "--name", "idle", "alpine:latest"])
+        .assert()
+        .success();
+
+    boxlite_mock(&home)
+        .args(["port", "--listen", "idle"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not running"));
+    // Without --listen the saved mappings are listed
+    boxlite_mock(&home)
+        .args(["port", "idle"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_mock_stats_history() {
+    let home = tempfile::tempdir().unwrap();
+    boxlite_mock(&home)
+        .args(["create", "--name", "idle", "alpine:latest"])
+        .assert()
+        .success();
+
+    boxlite_mock(&home)
+        .args(["stats", "idle"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not running"));
+    // Nothing recorded yet
+    boxlite_mock(&home)
+        .args(["stats", "idle", "--history", "1h", "--format", "json"])
+        .assert()
+        .success()
+        .stdout("[]\n");
+    boxlite_mock(&home)
+        .args(["stats", "idle", "--history", "1w"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid duration"));
+}
+
+#[test]
+fn test_mock_debug_cores() {
+    let home = tempfile::tempdir().unwrap();
+    let output = boxlite_mock(&home)
+        .args([
+            "create",
+            "--name",
+            "crashy",
+            "--core-dumps",
+            "alpine:latest",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let id = String::from_utf8(output.stdout).unwrap();
+    let id = id.lines().next().unwrap();
+
+    boxlite_mock(&home)
+        .args(["debug", "cores", "crashy", "--format", "json"])
+        .assert()
+        .success()
+        .stdout("[]\n");
+
+    // Dumps are written by the guest kernel; plant one where it would land
+    let cores = home.path().join("boxes").join(id).join("cores");
+    std::fs::create_dir_all(&cores).unwrap();
+    std::fs::write(cores.join("core.1700000000.42.11.python3"), b"ELF").unwrap();
+
+    boxlite_mock(&home)
+        .args(["debug", "cores", "crashy"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("core.1700000000.42.11.python3"))
+        .stdout(predicate::str::contains("python3"));
+
+    let out = tempfile::tempdir().unwrap();
+    boxlite_mock(&home)
+        .args([
+            "debug",
+            "cores",
+            "crashy",
+            "core.1700000000.42.11.python3",
+            "-o",
+        ])
+        .arg(out.path())
+        .assert()
+        .success();
+    assert_eq!(
+        std::fs::read(out.path().join("core.1700000000.42.11.python3")).unwrap(),
+        b"ELF"
+    );
+
+    boxlite_mock(&home)
+        .args(["debug", "cores", "crashy", "core.1.2.3.nope"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no core dump"));
+}
+
+#[test]
+fn test_mock_mcp_serve() {
+    let home = tempfile::tempdir().unwrap();
+    let requests = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-03-26","capabilities":{},"clientInfo":{"name":"test","version":"0"}}}"#,
+        r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+        r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#,
+        r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"create_box","arguments":{"name":"agent-box"}}}"#,
+        r#"{"jsonrpc":"2.0","id":4,"method":"tools/call","params":{"name":"create_box","arguments":{}}}"#,
+        r#"{"jsonrpc":"2.0","id":5,"method":"tools/call","params":{"name":"exec","arguments":{"box":"agent-box","args":["echo","from-mcp"]}}}"#,
+        r#"{"jsonrpc":"2.0","id":6,"method":"tools/call","params":{"name":"exec","arguments":{"box":"someone-else","args":["true"]}}}"#,
+        r#"{"jsonrpc":"2.0","id":7,"method":"bogus"}"#,
+    ];
+    let output = boxlite_mock(&home)
+        .args(["mcp", "serve", "--max-boxes", "1"])
+        .write_stdin(requests.join("\n") + "\n")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let responses: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    // No answer to the notification
+    assert_eq!(responses.len(), 7);
+    let text = |i: usize| {
+        responses[i]["result"]["content"][0]["text"]
+            .as_str()
+            .unwrap()
+    };
+    let is_error = |i: usize| responses[i]["result"]["isError"] == true;
+
+    assert_eq!(responses[0]["result"]["protocolVersion"], "2025-03-26");
+    let tools = responses[1]["result"]["tools"].as_array().unwrap();
+    assert!(tools.iter().any(|tool| tool["name"] == "exec"));
+    assert!(!is_error(2));
+    assert!(is_error(3) && text(3).contains("box quota reached"));
+    assert!(!is_error(4) && text(4).contains("from-mcp"));
+    assert!(is_error(5) && text(5).contains("no box 'someone-else'"));
+    assert_eq!(responses[6]["error"]["code"], -32601);
+
+    // Session boxes are removed when stdin closes
+    boxlite_mock(&home)
+        .args(["list", "-a", "-q"])
+        .assert()
+        .success()
+        .stdout("");
+}
+
+#[test]
+fn test_mock_batch_filter_and_partial_failure() {
+    let home = tempfile::tempdir().unwrap();
+    for name in ["batch-a", "batch-b"] {
+        boxlite_mock(&home)
+            .args(["create", "--name", name, "alpine:latest"])
+            .assert()
+            .success();
+    }
+
+    // A missing box is reported; the others are still handled
+    boxlite_mock(&home)
+        .args(["start", "missing", "batch-a"])
+        .assert()
+        .failure()
+        .stdout("batch-a\n")
+        .stderr(predicate::str::contains("'missing': not found"))
+        .stderr(predicate::str::contains("Failed to start 1 of 2 box(es)"));
+
+    boxlite_mock(&home)
+        .args(["rm", "--filter", "status=configured"])
+        .assert()
+        .success()
+        .stdout("batch-b\n");
+    boxlite_mock(&home)
+        .args(["rm", "batch-a", "--filter", "status=stopped"])
+        .assert()
+        .failure();
+    boxlite_mock(&home)
+        .args(["stop", "--filter", "status=asleep"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid status 'asleep'"));
+    boxlite_mock(&home)
+        .args(["rm", "--all", "--force", "--parallel", "2"])
+        .assert()
+        .success()
+        .stdout("batch-a\n");
+}
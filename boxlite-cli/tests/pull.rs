@@ -73,3 +73,41 @@ fn test_pull_with_full_registry_name() {
     cmd2.args(["pull", "quay.io/libpod/alpine:latest"]);
     cmd2.assert().success();
 }
+
+#[test]
+fn test_pull_all_templates() {
+    let mut ctx = common::boxlite();
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    std::fs::write(
+        &config,
+        r#"{"templates": {"shell": {"rootfs": {"Image": "alpine:latest"}}}}"#,
+    )
+    .unwrap();
+
+    ctx.cmd
+        .arg("--config")
+        .arg(&config)
+        .args(["pull", "--all-templates"]);
+    ctx.cmd
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Prepared: alpine:latest"));
+}
+
+#[test]
+fn test_pull_all_templates_requires_templates() {
+    let mut ctx = common::boxlite();
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    std::fs::write(&config, "{}").unwrap();
+
+    ctx.cmd
+        .arg("--config")
+        .arg(&config)
+        .args(["pull", "--all-templates"]);
+    ctx.cmd
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no templates"));
+}
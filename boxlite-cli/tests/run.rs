@@ -39,28 +39,26 @@ fn test_run_command_not_found() {
     let mut ctx = common::boxlite();
     ctx.cmd
         .args(["run", "--rm", "alpine:latest", "nonexistent_command"]);
-    ctx.cmd.assert()
-        .failure() // Currently exits with 1, should be 127？
-        .stderr(
-            predicate::str::contains("not found")
-                .or(predicate::str::contains("No such file"))
-                .or(predicate::str::contains("executable"))
-        );
+    ctx.cmd.assert().code(127).stderr(
+        predicate::str::contains("not found")
+            .or(predicate::str::contains("No such file"))
+            .or(predicate::str::contains("executable")),
+    );
 }
 
 #[test]
 fn test_run_invalid_executable() {
     let mut ctx = common::boxlite();
     ctx.cmd.args(["run", "--rm", "alpine:latest", "/etc"]);
-    ctx.cmd.assert()
-        .failure() // Currently exits with 1, should be 126
-        .stderr(
-            predicate::str::contains("is a directory")
-                .or(predicate::str::contains("permission denied"))
-                .or(predicate::str::contains("cannot invoke"))
-                .or(predicate::str::contains("not a regular file"))
-                .or(predicate::str::contains("does not have correct permissions"))
-        );
+    ctx.cmd.assert().code(126).stderr(
+        predicate::str::contains("is a directory")
+            .or(predicate::str::contains("permission denied"))
+            .or(predicate::str::contains("cannot invoke"))
+            .or(predicate::str::contains("not a regular file"))
+            .or(predicate::str::contains(
+                "does not have correct permissions",
+            )),
+    );
 }
 
 // ============================================================================
@@ -0,0 +1,47 @@
+use predicates::prelude::*;
+
+mod common;
+
+#[test]
+fn test_system_df_table() {
+    let mut ctx = common::boxlite();
+    ctx.cmd
+        .args(["system", "df"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("TYPE"))
+        .stdout(predicate::str::contains("LOGICAL SIZE"))
+        .stdout(predicate::str::contains("Extracted layers"))
+        .stdout(predicate::str::contains("dedup saved"));
+}
+
+#[test]
+fn test_system_df_json() {
+    let mut ctx = common::boxlite();
+    let output = ctx
+        .cmd
+        .args(["system", "df", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let rows: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let rows = rows.as_array().unwrap();
+    assert_eq!(rows.len(), 4);
+    assert!(rows[1]["LogicalSize"].as_u64().unwrap() >= rows[1]["Size"].as_u64().unwrap());
+}
+
+#[test]
+fn test_system_info_json() {
+    let mut ctx = common::boxlite();
+    let output = ctx
+        .cmd
+        .args(["system", "info", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let info: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(info["Version"], env!("CARGO_PKG_VERSION"));
+    assert!(info["Virtualization"].is_string());
+}
@@ -0,0 +1,22 @@
+//! containerd runtime shim v2 for BoxLite (`io.containerd.boxlite.v2`).
+//!
+//! containerd finds the binary by runtime name, so install it on `PATH` as
+//! `containerd-shim-boxlite-v2` and run containers with
+//! `--runtime io.containerd.boxlite.v2`.
+
+#[cfg(target_os = "linux")]
+mod service;
+#[cfg(target_os = "linux")]
+mod spec;
+
+#[cfg(target_os = "linux")]
+#[tokio::main]
+async fn main() {
+    containerd_shim::asynchronous::run::<service::Service>("io.containerd.boxlite.v2", None).await;
+}
+
+#[cfg(not(target_os = "linux"))]
+fn main() {
+    eprintln!("containerd-shim-boxlite-v2 is only supported on Linux");
+    std::process::exit(1);
+}
@@ -0,0 +1,1008 @@
+//! Shim and Task service: one shim process per container, one box per container.
+//!
+//! The container's init process and every `Exec` run as BoxLite executions in
+//! the box. Guest processes have no host PIDs, so all of them report the PID
+//! of the box's VMM shim.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use boxlite::runtime::options::BoxliteOptions;
+use boxlite::{BoxCommand, BoxliteRuntime, Execution, LiteBox};
+use containerd_shim::asynchronous::{ExitSignal, Shim, spawn};
+use containerd_shim::protos::events::task::{
+    TaskCreate, TaskDelete, TaskExecAdded, TaskExecStarted, TaskExit, TaskIO, TaskStart,
+};
+use containerd_shim::protos::protobuf::well_known_types::timestamp::Timestamp;
+use containerd_shim::protos::protobuf::{EnumOrUnknown, MessageDyn, MessageField};
+use containerd_shim::protos::topics;
+use containerd_shim::protos::ttrpc::{self, Code};
+use containerd_shim::publisher::RemotePublisher;
+use containerd_shim::util::timestamp;
+use containerd_shim::{
+    Config, Context, DeleteResponse, Error, Flags, StartOpts, Task, TtrpcContext, TtrpcResult, api,
+};
+use futures::StreamExt;
+use log::{info, warn};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::{Mutex, Notify, watch};
+
+use crate::spec;
+
+/// Where per-container BoxLite homes live (`<root>/<namespace>/<id>`).
+///
+/// A BoxLite home is locked by one runtime at a time, so every shim
+/// gets its own.
+const DEFAULT_STATE_ROOT: &str = "/var/lib/boxlite-containerd";
+
+fn box_home(namespace: &str, id: &str) -> PathBuf {
+    let root = std::env::var_os("BOXLITE_CONTAINERD_ROOT")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_STATE_ROOT));
+    root.join(namespace).join(id)
+}
+
+// ============================================================================
+// SHIM
+// ============================================================================
+
+#[derive(Clone)]
+pub struct Service {
+    exit: Arc<ExitSignal>,
+    id: String,
+    namespace: String,
+}
+
+#[async_trait]
+impl Shim for Service {
+    type T = TaskService;
+
+    async fn new(_runtime_id: &str, args: &Flags, _config: &mut Config) -> Self {
+        Service {
+            exit: Arc::new(ExitSignal::default()),
+            id: args.id.clone(),
+            namespace: args.namespace.clone(),
+        }
+    }
+
+    async fn start_shim(&mut self, opts: StartOpts) -> Result<String, Error> {
+        let grouping = opts.id.clone();
+        spawn(opts, &grouping, Vec::new()).await
+    }
+
+    /// Cleanup after a shim that died without deleting its task.
+    async fn delete_shim(&mut self) -> Result<DeleteResponse, Error> {
+        let home = box_home(&self.namespace, &self.id);
+        if home.exists() {
+            match BoxliteRuntime::new(BoxliteOptions {
+                home_dir: home.clone(),
+                ..Default::default()
+            }) {
+                Ok(runtime) => {
+                    if let Err(e) = runtime.remove(&self.id, true).await {
+                        warn!("failed to remove box {}: {}", self.id, e);
+                    }
+                }
+                Err(e) => warn!("failed to open BoxLite home {}: {}", home.display(), e),
+            }
+            let _ = std::fs::remove_dir_all(&home);
+        }
+        Ok(DeleteResponse {
+            exit_status: 137,
+            exited_at: MessageField::some(timestamp()?),
+            ..Default::default()
+        })
+    }
+
+    async fn wait(&mut self) {
+        self.exit.wait().await;
+    }
+
+    async fn create_task_service(&self, publisher: RemotePublisher) -> Self::T {
+        TaskService {
+            namespace: self.namespace.clone(),
+            home: box_home(&self.namespace, &self.id),
+            publisher: Arc::new(publisher),
+            exit: Arc::clone(&self.exit),
+            container: Arc::new(Mutex::new(None)),
+        }
+    }
+}
+
+// ============================================================================
+// PROCESSES
+// ============================================================================
+
+/// FIFO paths created by containerd for a process.
+#[derive(Clone, Default)]
+struct Stdio {
+    stdin: String,
+    stdout: String,
+    stderr: String,
+    terminal: bool,
+}
+
+#[derive(Clone)]
+struct ExitStatus {
+    code: u32,
+    at: Timestamp,
+}
+
+struct Process {
+    command: BoxCommand,
+    stdio: Stdio,
+    execution: Option<Execution>,
+    close_stdin: Arc<Notify>,
+    exit: watch::Sender<Option<ExitStatus>>,
+}
+
+impl Process {
+    fn new(command: BoxCommand, stdio: Stdio) -> Self {
+        Self {
+            command,
+            stdio,
+            execution: None,
+            close_stdin: Arc::new(Notify::new()),
+            exit: watch::channel(None).0,
+        }
+    }
+
+    fn status(&self) -> api::Status {
+        match (&self.execution, self.exit.borrow().is_some()) {
+            (_, true) => api::Status::STOPPED,
+            (Some(_), false) => api::Status::RUNNING,
+            (None, false) => api::Status::CREATED,
+        }
+    }
+}
+
+struct Container {
+    id: String,
+    bundle: String,
+    /// Rootfs mounted by the shim (unmounted on delete).
+    mounted_rootfs: Option<PathBuf>,
+    runtime: BoxliteRuntime,
+    litebox: LiteBox,
+    init: Process,
+    execs: HashMap<String, Process>,
+}
+
+impl Container {
+    fn pid(&self) -> u32 {
+        self.litebox.info().pid.unwrap_or(0)
+    }
+
+    fn process(&self, exec_id: &str) -> TtrpcResult<&Process> {
+        if exec_id.is_empty() {
+            return Ok(&self.init);
+        }
+        self.execs
+            .get(exec_id)
+            .ok_or_else(|| status(Code::NOT_FOUND, format!("exec {} not found", exec_id)))
+    }
+
+    fn process_mut(&mut self, exec_id: &str) -> TtrpcResult<&mut Process> {
+        if exec_id.is_empty() {
+            return Ok(&mut self.init);
+        }
+        self.execs
+            .get_mut(exec_id)
+            .ok_or_else(|| status(Code::NOT_FOUND, format!("exec {} not found", exec_id)))
+    }
+}
+
+// ============================================================================
+// TASK SERVICE
+// ============================================================================
+
+#[derive(Clone)]
+pub struct TaskService {
+    namespace: String,
+    home: PathBuf,
+    publisher: Arc<RemotePublisher>,
+    exit: Arc<ExitSignal>,
+    container: Arc<Mutex<Option<Container>>>,
+}
+
+impl TaskService {
+    async fn publish(&self, topic: &str, event: Box<dyn MessageDyn>) {
+        if let Err(e) = self
+            .publisher
+            .publish(Context::default(), topic, &self.namespace, event)
+            .await
+        {
+            warn!("failed to publish {}: {}", topic, e);
+        }
+    }
+
+    async fn create_container(&self, req: &api::CreateTaskRequest) -> Result<Container, String> {
+        let bundle = Path::new(&req.bundle);
+        let spec = containerd_shim::util::read_spec(bundle)
+            .await
+            .map_err(|e| e.to_string())?;
+        let process = spec.process().as_ref().ok_or("spec has no process")?;
+        let command = spec::command(process).ok_or("spec process has no args")?;
+
+        let rootfs = bundle.join("rootfs");
+        let mut mounted_rootfs = None;
+        for mount in &req.rootfs {
+            containerd_shim::util::mount_rootfs(mount, &rootfs)
+                .await
+                .map_err(|e| format!("failed to mount rootfs: {}", e))?;
+            mounted_rootfs = Some(rootfs.clone());
+        }
+
+        let runtime = BoxliteRuntime::new(BoxliteOptions {
+            home_dir: self.home.clone(),
+            ..Default::default()
+        })
+        .map_err(|e| e.to_string())?;
+        let litebox = runtime
+            .create(spec::box_options(&spec, &rootfs), Some(req.id.clone()))
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let stdio = Stdio {
+            stdin: req.stdin.clone(),
+            stdout: req.stdout.clone(),
+            stderr: req.stderr.clone(),
+            terminal: req.terminal,
+        };
+        Ok(Container {
+            id: req.id.clone(),
+            bundle: req.bundle.clone(),
+            mounted_rootfs,
+            runtime,
+            litebox,
+            init: Process::new(command.tty(req.terminal), stdio),
+            execs: HashMap::new(),
+        })
+    }
+
+    /// Start a process's execution, wire its stdio and watch for its exit.
+    async fn start_process(&self, container: &mut Container, exec_id: &str) -> TtrpcResult<u32> {
+        if exec_id.is_empty() {
+            container.litebox.start().await.map_err(internal)?;
+        }
+        let pid = container.pid();
+        let container_id = container.id.clone();
+        let process = container.process(exec_id)?;
+        if process.execution.is_some() {
+            return Err(status(Code::FAILED_PRECONDITION, "process already started"));
+        }
+        let command = process.command.clone();
+
+        let mut execution = container.litebox.exec(command).await.map_err(internal)?;
+        let process = container.process_mut(exec_id)?;
+        process.execution = Some(execution.clone());
+
+        let stdio = process.stdio.clone();
+        if let Some(stdin) = execution.stdin()
+            && !stdio.stdin.is_empty()
+        {
+            tokio::spawn(copy_stdin(
+                stdio.stdin.clone(),
+                stdin,
+                Arc::clone(&process.close_stdin),
+            ));
+        }
+        let stdout = execution.stdout();
+        let stderr = execution.stderr();
+        let exit = process.exit.clone();
+        let service = self.clone();
+        let exec_id = exec_id.to_string();
+        tokio::spawn(async move {
+            let (_, _, result) = tokio::join!(
+                copy_output(stdio.stdout, stdout),
+                copy_output(stdio.stderr, stderr),
+                execution.wait()
+            );
+            let code = match result {
+                Ok(result) => u32::try_from(result.exit_code).unwrap_or(255),
+                Err(e) => {
+                    warn!("wait for {} failed: {}", container_id, e);
+                    255
+                }
+            };
+            let at = timestamp().unwrap_or_default();
+            exit.send_replace(Some(ExitStatus {
+                code,
+                at: at.clone(),
+            }));
+            service
+                .publish(
+                    topics::TASK_EXIT_EVENT_TOPIC,
+                    Box::new(TaskExit {
+                        container_id: container_id.clone(),
+                        id: if exec_id.is_empty() {
+                            container_id
+                        } else {
+                            exec_id
+                        },
+                        pid,
+                        exit_status: code,
+                        exited_at: MessageField::some(at),
+                        ..Default::default()
+                    }),
+                )
+                .await;
+        });
+        Ok(pid)
+    }
+}
+
+#[async_trait]
+impl Task for TaskService {
+    async fn create(
+        &self,
+        _ctx: &TtrpcContext,
+        req: api::CreateTaskRequest,
+    ) -> TtrpcResult<api::CreateTaskResponse> {
+        info!("create {}", req.id);
+        let mut guard = self.container.lock().await;
+        if guard.is_some() {
+            return Err(status(Code::ALREADY_EXISTS, "task already created"));
+        }
+        let container = self
+            .create_container(&req)
+            .await
+            .map_err(|e| status(Code::INTERNAL, e))?;
+        let pid = container.pid();
+        *guard = Some(container);
+        drop(guard);
+
+        self.publish(
+            topics::TASK_CREATE_EVENT_TOPIC,
+            Box::new(TaskCreate {
+                container_id: req.id.clone(),
+                bundle: req.bundle.clone(),
+                rootfs: req.rootfs.clone(),
+                io: MessageField::some(TaskIO {
+                    stdin: req.stdin.clone(),
+                    stdout: req.stdout.clone(),
+                    stderr: req.stderr.clone(),
+                    terminal: req.terminal,
+                    ..Default::default()
+                }),
+                pid,
+                ..Default::default()
+            }),
+        )
+        .await;
+        Ok(api::CreateTaskResponse {
+            pid,
+            ..Default::default()
+        })
+    }
+
+    async fn start(
+        &self,
+        _ctx: &TtrpcContext,
+        req: api::StartRequest,
+    ) -> TtrpcResult<api::StartResponse> {
+        let mut guard = self.container.lock().await;
+        let container = guard.as_mut().ok_or_else(not_created)?;
+        let pid = self.start_process(container, &req.exec_id).await?;
+        drop(guard);
+
+        if req.exec_id.is_empty() {
+            self.publish(
+                topics::TASK_START_EVENT_TOPIC,
+                Box::new(TaskStart {
+                    container_id: req.id.clone(),
+                    pid,
+                    ..Default::default()
+                }),
+            )
+            .await;
+        } else {
+            self.publish(
+                topics::TASK_EXEC_STARTED_EVENT_TOPIC,
+                Box::new(TaskExecStarted {
+                    container_id: req.id.clone(),
+                    exec_id: req.exec_id.clone(),
+                    pid,
+                    ..Default::default()
+                }),
+            )
+            .await;
+        }
+        Ok(api::StartResponse {
+            pid,
+            ..Default::default()
+        })
+    }
+
+    async fn state(
+        &self,
+        _ctx: &TtrpcContext,
+        req: api::StateRequest,
+    ) -> TtrpcResult<api::StateResponse> {
+        let guard = self.container.lock().await;
+        let container = guard.as_ref().ok_or_else(not_created)?;
+        let process = container.process(&req.exec_id)?;
+        let exit = process.exit.borrow().clone();
+        Ok(api::StateResponse {
+            id: req.id.clone(),
+            bundle: container.bundle.clone(),
+            pid: container.pid(),
+            status: EnumOrUnknown::new(process.status()),
+            stdin: process.stdio.stdin.clone(),
+            stdout: process.stdio.stdout.clone(),
+            stderr: process.stdio.stderr.clone(),
+            terminal: process.stdio.terminal,
+            exit_status: exit.as_ref().map(|e| e.code).unwrap_or_default(),
+            exited_at: exit.map(|e| e.at).into(),
+            exec_id: req.exec_id.clone(),
+            ..Default::default()
+        })
+    }
+
+    async fn exec(
+        &self,
+        _ctx: &TtrpcContext,
+        req: api::ExecProcessRequest,
+    ) -> TtrpcResult<api::Empty> {
+        let mut guard = self.container.lock().await;
+        let container = guard.as_mut().ok_or_else(not_created)?;
+        if container.execs.contains_key(&req.exec_id) {
+            return Err(status(
+                Code::ALREADY_EXISTS,
+                format!("exec {} already exists", req.exec_id),
+            ));
+        }
+        let process: oci_spec::runtime::Process =
+            serde_json::from_slice(&req.spec.value).map_err(|e| {
+                status(
+                    Code::INVALID_ARGUMENT,
+                    format!("invalid process spec: {}", e),
+                )
+            })?;
+        let command = spec::command(&process)
+            .ok_or_else(|| status(Code::INVALID_ARGUMENT, "process spec has no args"))?;
+        let stdio = Stdio {
+            stdin: req.stdin.clone(),
+            stdout: req.stdout.clone(),
+            stderr: req.stderr.clone(),
+            terminal: req.terminal,
+        };
+        container.execs.insert(
+            req.exec_id.clone(),
+            Process::new(command.tty(req.terminal), stdio),
+        );
+        drop(guard);
+
+        self.publish(
+            topics::TASK_EXEC_ADDED_EVENT_TOPIC,
+            Box::new(TaskExecAdded {
+                container_id: req.id.clone(),
+                exec_id: req.exec_id.clone(),
+                ..Default::default()
+            }),
+        )
+        .await;
+        Ok(api::Empty::default())
+    }
+
+    async fn kill(&self, _ctx: &TtrpcContext, req: api::KillRequest) -> TtrpcResult<api::Empty> {
+        let guard = self.container.lock().await;
+        let container = guard.as_ref().ok_or_else(not_created)?;
+        let process = container.process(&req.exec_id)?;
+        let execution = process
+            .execution
+            .as_ref()
+            .ok_or_else(|| status(Code::FAILED_PRECONDITION, "process not started"))?;
+        if process.exit.borrow().is_none() {
+            execution
+                .signal(req.signal as i32)
+                .await
+                .map_err(internal)?;
+        }
+        Ok(api::Empty::default())
+    }
+
+    async fn wait(
+        &self,
+        _ctx: &TtrpcContext,
+        req: api::WaitRequest,
+    ) -> TtrpcResult<api::WaitResponse> {
+        let mut exit = {
+            let guard = self.container.lock().await;
+            let container = guard.as_ref().ok_or_else(not_created)?;
+            container.process(&req.exec_id)?.exit.subscribe()
+        };
+        let exit = exit
+            .wait_for(Option::is_some)
+            .await
+            .map_err(internal)?
+            .clone()
+            .unwrap_or_else(|| unreachable!("waited for Some"));
+        Ok(api::WaitResponse {
+            exit_status: exit.code,
+            exited_at: MessageField::some(exit.at),
+            ..Default::default()
+        })
+    }
+
+    async fn delete(
+        &self,
+        _ctx: &TtrpcContext,
+        req: api::DeleteRequest,
+    ) -> TtrpcResult<api::DeleteResponse> {
+        let mut guard = self.container.lock().await;
+        let container = guard.as_mut().ok_or_else(not_created)?;
+        let pid = container.pid();
+
+        if !req.exec_id.is_empty() {
+            let process = container
+                .execs
+                .remove(&req.exec_id)
+                .ok_or_else(|| status(Code::NOT_FOUND, "exec not found"))?;
+            let exit = process.exit.borrow().clone();
+            return Ok(delete_response(pid, exit));
+        }
+
+        let container = guard.take().ok_or_else(not_created)?;
+        drop(guard);
+        let exit = container.init.exit.borrow().clone();
+        if let Err(e) = container.runtime.remove(&container.id, true).await {
+            warn!("failed to remove box {}: {}", container.id, e);
+        }
+        if let Some(rootfs) = &container.mounted_rootfs
+            && let Err(e) = nix::mount::umount2(rootfs, nix::mount::MntFlags::MNT_DETACH)
+        {
+            warn!("failed to unmount {}: {}", rootfs.display(), e);
+        }
+        let _ = std::fs::remove_dir_all(&self.home);
+
+        let response = delete_response(pid, exit);
+        self.publish(
+            topics::TASK_DELETE_EVENT_TOPIC,
+            Box::new(TaskDelete {
+                container_id: container.id.clone(),
+                id: container.id.clone(),
+                pid,
+                exit_status: response.exit_status,
+                exited_at: response.exited_at.clone(),
+                ..Default::default()
+            }),
+        )
+        .await;
+        Ok(response)
+    }
+
+    async fn pids(
+        &self,
+        _ctx: &TtrpcContext,
+        _req: api::PidsRequest,
+    ) -> TtrpcResult<api::PidsResponse> {
+        let guard = self.container.lock().await;
+        let container = guard.as_ref().ok_or_else(not_created)?;
+        Ok(api::PidsResponse {
+            processes: vec![containerd_shim::protos::api::ProcessInfo {
+                pid: container.pid(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        })
+    }
+
+    async fn resize_pty(
+        &self,
+        _ctx: &TtrpcContext,
+        req: api::ResizePtyRequest,
+    ) -> TtrpcResult<api::Empty> {
+        let guard = self.container.lock().await;
+        let container = guard.as_ref().ok_or_else(not_created)?;
+        if let Some(execution) = &container.process(&req.exec_id)?.execution {
+            execution
+                .resize_tty(req.height, req.width)
+                .await
+                .map_err(internal)?;
+        }
+        Ok(api::Empty::default())
+    }
+
+    async fn close_io(
+        &self,
+        _ctx: &TtrpcContext,
+        req: api::CloseIORequest,
+    ) -> TtrpcResult<api::Empty> {
+        let guard = self.container.lock().await;
+        let container = guard.as_ref().ok_or_else(not_created)?;
+        container.process(&req.exec_id)?.close_stdin.notify_one();
+        Ok(api::Empty::default())
+    }
+
+    async fn connect(
+        &self,
+        _ctx: &TtrpcContext,
+        _req: api::ConnectRequest,
+    ) -> TtrpcResult<api::ConnectResponse> {
+        let guard = self.container.lock().await;
+        Ok(api::ConnectResponse {
+            shim_pid: std::process::id(),
+            task_pid: guard.as_ref().map(Container::pid).unwrap_or_default(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            ..Default::default()
+        })
+    }
+
+    async fn shutdown(
+        &self,
+        _ctx: &TtrpcContext,
+        _req: api::ShutdownRequest,
+    ) -> TtrpcResult<api::Empty> {
+        if self.container.lock().await.is_none() {
+            self.exit.signal();
+        }
+        Ok(api::Empty::default())
+    }
+}
+
+fn delete_response(pid: u32, exit: Option<ExitStatus>) -> api::DeleteResponse {
+    api::DeleteResponse {
+        pid,
+        exit_status: exit.as_ref().map(|e| e.code).unwrap_or_default(),
+        exited_at: exit.map(|e| e.at).into(),
+        ..Default::default()
+    }
+}
+
+// ============================================================================
+// STDIO
+// ============================================================================
+
+async fn copy_stdin(path: String, mut stdin: boxlite::ExecStdin, close: Arc<Notify>) {
+    let mut fifo = match tokio::fs::File::open(&path).await {
+        Ok(fifo) => fifo,
+        Err(e) => {
+            warn!("failed to open stdin {}: {}", path, e);
+            return;
+        }
+    };
+    let mut buf = vec![0u8; 32 * 1024];
+    loop {
+        tokio::select! {
+            read = fifo.read(&mut buf) => match read {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    if stdin.write_all(&buf[..n]).await.is_err() {
+                        break;
+                    }
+                }
+            },
+            _ = close.notified() => break,
+        }
+    }
+    stdin.close();
+}
+
+async fn copy_output<S>(path: String, stream: Option<S>)
+where
+    S: futures::Stream + Unpin,
+    S::Item: AsRef<[u8]>,
+{
+    let Some(mut stream) = stream else {
+        return;
+    };
+    if path.is_empty() {
+        while stream.next().await.is_some() {}
+        return;
+    }
+    let mut fifo = match tokio::fs::OpenOptions::new().write(true).open(&path).await {
+        Ok(fifo) => fifo,
+        Err(e) => {
+            warn!("failed to open {}: {}", path, e);
+            return;
+        }
+    };
+    while let Some(chunk) = stream.next().await {
+        if fifo.write_all(chunk.as_ref()).await.is_err() {
+            break;
+        }
+    }
+}
+
+// ============================================================================
+// ERRORS
+// ============================================================================
+
+fn status(code: Code, message: impl Display) -> ttrpc::Error {
+    ttrpc::Error::RpcStatus(ttrpc::get_status(code, message.to_string()))
+}
+
+fn internal(error: impl Display) -> ttrpc::Error {
+    status(Code::INTERNAL, error)
+}
+
+fn not_created() -> ttrpc::Error {
+    status(Code::NOT_FOUND, "task not created")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::os::fd::AsRawFd;
+    use std::os::unix::net::UnixListener;
+    use std::sync::Once;
+
+    use containerd_shim::protos::shim_async::{Events, create_events};
+    use containerd_shim::protos::ttrpc::asynchronous::Server;
+    use tokio::sync::mpsc;
+
+    /// Stands in for containerd: records the topic of every published event.
+    struct EventSink {
+        topics: mpsc::UnboundedSender<String>,
+    }
+
+    #[async_trait]
+    impl Events for EventSink {
+        async fn forward(
+            &self,
+            _ctx: &TtrpcContext,
+            req: containerd_shim::protos::api::ForwardRequest,
+        ) -> TtrpcResult<api::Empty> {
+            let _ = self.topics.send(req.envelope().topic().to_string());
+            Ok(api::Empty::default())
+        }
+    }
+
+    /// A Task service on the mock engine, with its own BoxLite home and
+    /// event sink under `dir`.
+    async fn task_service(dir: &Path) -> (TaskService, mpsc::UnboundedReceiver<String>, Server) {
+        static MOCK_ENGINE: Once = Once::new();
+        // SAFETY: set once, before any test of this module reads it
+        MOCK_ENGINE.call_once(|| unsafe {
+            std::env::set_var(boxlite::runtime::constants::envs::BOXLITE_ENGINE, "mock")
+        });
+
+        let (tx, topics) = mpsc::unbounded_channel();
+        let socket = dir.join("events.sock");
+        let listener = UnixListener::bind(&socket).unwrap();
+        let mut server = Server::new()
+            .set_domain_unix()
+            .add_listener(listener.as_raw_fd())
+            .unwrap()
+            .register_service(create_events(Arc::new(Box::new(EventSink { topics: tx }))));
+        // The server owns the fd from here
+        std::mem::forget(listener);
+        server.start().await.unwrap();
+
+        let publisher = RemotePublisher::new(socket.to_string_lossy())
+            .await
+            .unwrap();
+        let service = TaskService {
+            namespace: "default".to_string(),
+            home: dir.join("home"),
+            publisher: Arc::new(publisher),
+            exit: Arc::new(ExitSignal::default()),
+            container: Arc::new(Mutex::new(None)),
+        };
+        (service, topics, server)
+    }
+
+    /// A bundle whose init process runs `args`, with stdout captured to a file.
+    fn create_request(dir: &Path, id: &str, args: &[&str]) -> api::CreateTaskRequest {
+        let bundle = dir.join("bundle");
+        std::fs::create_dir_all(bundle.join("rootfs")).unwrap();
+        let spec = serde_json::json!({
+            "ociVersion": "1.0.2",
+            "process": {
+                "args": args,
+                "cwd": "/",
+                "user": {"uid": 0, "gid": 0}
+            },
+            "root": {"path": "rootfs"}
+        });
+        std::fs::write(bundle.join("config.json"), spec.to_string()).unwrap();
+
+        let stdout = dir.join("stdout");
+        std::fs::write(&stdout, "").unwrap();
+        api::CreateTaskRequest {
+            id: id.to_string(),
+            bundle: bundle.to_string_lossy().into_owned(),
+            stdout: stdout.to_string_lossy().into_owned(),
+            ..Default::default()
+        }
+    }
+
+    fn exec_request(id: &str, exec_id: &str, args: &[&str]) -> api::ExecProcessRequest {
+        let process = serde_json::json!({
+            "args": args,
+            "cwd": "/",
+            "user": {"uid": 0, "gid": 0}
+        });
+        let mut request = api::ExecProcessRequest {
+            id: id.to_string(),
+            exec_id: exec_id.to_string(),
+            ..Default::default()
+        };
+        request.spec.mut_or_insert_default().value = process.to_string().into_bytes();
+        request
+    }
+
+    fn ctx() -> TtrpcContext {
+        TtrpcContext {
+            fd: -1,
+            mh: Default::default(),
+            metadata: Default::default(),
+            timeout_nano: 0,
+        }
+    }
+
+    fn start_request(id: &str, exec_id: &str) -> api::StartRequest {
+        api::StartRequest {
+            id: id.to_string(),
+            exec_id: exec_id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn wait_request(id: &str, exec_id: &str) -> api::WaitRequest {
+        api::WaitRequest {
+            id: id.to_string(),
+            exec_id: exec_id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn state_request(id: &str, exec_id: &str) -> api::StateRequest {
+        api::StateRequest {
+            id: id.to_string(),
+            exec_id: exec_id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn rpc_code(error: ttrpc::Error) -> Code {
+        match error {
+            ttrpc::Error::RpcStatus(status) => status.code(),
+            other => panic!("expected an RPC status, got {:?}", other),
+        }
+    }
+
+    async fn next_topic(topics: &mut mpsc::UnboundedReceiver<String>) -> String {
+        tokio::time::timeout(std::time::Duration::from_secs(10), topics.recv())
+            .await
+            .expect("event not published")
+            .expect("event sink closed")
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_task_lifecycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let (service, mut topics, _server) = task_service(dir.path()).await;
+        let create = create_request(dir.path(), "web", &["echo", "hello"]);
+
+        service.create(&ctx(), create.clone()).await.unwrap();
+        assert_eq!(
+            next_topic(&mut topics).await,
+            topics::TASK_CREATE_EVENT_TOPIC
+        );
+        let state = service
+            .state(&ctx(), state_request("web", ""))
+            .await
+            .unwrap();
+        assert_eq!(state.status.enum_value(), Ok(api::Status::CREATED));
+        assert_eq!(state.bundle, create.bundle);
+
+        service
+            .start(&ctx(), start_request("web", ""))
+            .await
+            .unwrap();
+        assert_eq!(
+            next_topic(&mut topics).await,
+            topics::TASK_START_EVENT_TOPIC
+        );
+        let exit = service.wait(&ctx(), wait_request("web", "")).await.unwrap();
+        assert_eq!(exit.exit_status, 0);
+        assert_eq!(next_topic(&mut topics).await, topics::TASK_EXIT_EVENT_TOPIC);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("stdout")).unwrap(),
+            "hello\n"
+        );
+
+        let state = service
+            .state(&ctx(), state_request("web", ""))
+            .await
+            .unwrap();
+        assert_eq!(state.status.enum_value(), Ok(api::Status::STOPPED));
+
+        let deleted = service
+            .delete(
+                &ctx(),
+                api::DeleteRequest {
+                    id: "web".to_string(),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(deleted.exit_status, 0);
+        assert_eq!(
+            next_topic(&mut topics).await,
+            topics::TASK_DELETE_EVENT_TOPIC
+        );
+        assert!(!service.home.exists());
+        let error = service
+            .state(&ctx(), state_request("web", ""))
+            .await
+            .unwrap_err();
+        assert_eq!(rpc_code(error), Code::NOT_FOUND);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_task_exec_and_kill() {
+        let dir = tempfile::tempdir().unwrap();
+        let (service, _topics, _server) = task_service(dir.path()).await;
+        service
+            .create(&ctx(), create_request(dir.path(), "db", &["sleep", "300"]))
+            .await
+            .unwrap();
+        service
+            .start(&ctx(), start_request("db", ""))
+            .await
+            .unwrap();
+
+        service
+            .exec(&ctx(), exec_request("db", "probe", &["false"]))
+            .await
+            .unwrap();
+        let error = service
+            .exec(&ctx(), exec_request("db", "probe", &["true"]))
+            .await
+            .unwrap_err();
+        assert_eq!(rpc_code(error), Code::ALREADY_EXISTS);
+        service
+            .start(&ctx(), start_request("db", "probe"))
+            .await
+            .unwrap();
+        let exit = service
+            .wait(&ctx(), wait_request("db", "probe"))
+            .await
+            .unwrap();
+        assert_eq!(exit.exit_status, 1);
+
+        service
+            .kill(
+                &ctx(),
+                api::KillRequest {
+                    id: "db".to_string(),
+                    signal: 9,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let exit = service.wait(&ctx(), wait_request("db", "")).await.unwrap();
+        assert_eq!(exit.exit_status, 137);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_task_requires_one_create() {
+        let dir = tempfile::tempdir().unwrap();
+        let (service, _topics, _server) = task_service(dir.path()).await;
+
+        let error = service
+            .start(&ctx(), start_request("app", ""))
+            .await
+            .unwrap_err();
+        assert_eq!(rpc_code(error), Code::NOT_FOUND);
+
+        let create = create_request(dir.path(), "app", &["true"]);
+        service.create(&ctx(), create.clone()).await.unwrap();
+        let error = service.create(&ctx(), create).await.unwrap_err();
+        assert_eq!(rpc_code(error), Code::ALREADY_EXISTS);
+
+        let error = service
+            .start(&ctx(), start_request("app", "missing"))
+            .await
+            .unwrap_err();
+        assert_eq!(rpc_code(error), Code::NOT_FOUND);
+    }
+}
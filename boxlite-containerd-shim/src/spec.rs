@@ -0,0 +1,141 @@
+//! Translation of OCI runtime specs (bundle `config.json`) into BoxLite options.
+
+use std::path::Path;
+
+use boxlite::runtime::options::VolumeSpec;
+use boxlite::{BoxCommand, BoxOptions, RootfsSpec};
+use oci_spec::runtime::{Mount, Process, Spec};
+
+/// Mount destinations the guest provides itself.
+const GUEST_MANAGED: &[&str] = &["/proc", "/dev", "/sys", "/run", "/etc/resolv.conf"];
+
+/// Box options for a container whose root filesystem is mounted at `rootfs`.
+pub fn box_options(spec: &Spec, rootfs: &Path) -> BoxOptions {
+    let mut options = BoxOptions {
+        rootfs: RootfsSpec::RootfsPath(rootfs.to_string_lossy().into_owned()),
+        // containerd owns the lifecycle: Delete removes the box.
+        auto_remove: false,
+        detach: true,
+        ..Default::default()
+    };
+
+    if let Some(process) = spec.process() {
+        options.env = env_pairs(process);
+        options.working_dir = Some(process.cwd().to_string_lossy().into_owned());
+        let user = process.user();
+        options.user = Some(format!("{}:{}", user.uid(), user.gid()));
+    }
+
+    if let Some(resources) = spec.linux().as_ref().and_then(|l| l.resources().as_ref()) {
+        if let Some(limit) = resources.memory().as_ref().and_then(|m| m.limit())
+            && limit > 0
+        {
+            options.memory_mib = Some(u32::try_from(limit / (1024 * 1024)).unwrap_or(u32::MAX));
+        }
+        if let Some(cpu) = resources.cpu()
+            && let (Some(quota), Some(period)) = (cpu.quota(), cpu.period())
+            && quota > 0
+            && period > 0
+        {
+            let cpus = (quota as u64).div_ceil(period);
+            options.cpus = Some(u8::try_from(cpus).unwrap_or(u8::MAX));
+        }
+    }
+
+    options.volumes = spec
+        .mounts()
+        .iter()
+        .flatten()
+        .filter_map(bind_volume)
+        .collect();
+    options
+}
+
+fn bind_volume(mount: &Mount) -> Option<VolumeSpec> {
+    let options = mount.options().clone().unwrap_or_default();
+    let is_bind = mount.typ().as_deref() == Some("bind")
+        || options.iter().any(|o| o == "bind" || o == "rbind");
+    let destination = mount.destination().to_string_lossy().into_owned();
+    if !is_bind
+        || GUEST_MANAGED.iter().any(|managed| {
+            destination == *managed || destination.starts_with(&format!("{}/", managed))
+        })
+    {
+        return None;
+    }
+    Some(VolumeSpec {
+        host_path: mount.source().as_ref()?.to_string_lossy().into_owned(),
+        guest_path: destination,
+        read_only: options.iter().any(|o| o == "ro"),
+        ..Default::default()
+    })
+}
+
+/// The command for an OCI process (the container's init or an exec).
+pub fn command(process: &Process) -> Option<BoxCommand> {
+    let args = process.args().as_ref()?;
+    let (program, rest) = args.split_first()?;
+    let mut command = BoxCommand::new(program.clone())
+        .args(rest.iter().cloned())
+        .working_dir(process.cwd().to_string_lossy().into_owned())
+        .tty(process.terminal().unwrap_or(false));
+    for (key, value) in env_pairs(process) {
+        command = command.env(key, value);
+    }
+    Some(command)
+}
+
+fn env_pairs(process: &Process) -> Vec<(String, String)> {
+    process
+        .env()
+        .iter()
+        .flatten()
+        .filter_map(|entry| entry.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_box_options_from_spec() {
+        let spec: Spec = serde_json::from_value(serde_json::json!({
+            "ociVersion": "1.0.2",
+            "process": {
+                "args": ["nginx", "-g", "daemon off;"],
+                "env": ["PATH=/usr/bin", "MODE=prod"],
+                "cwd": "/srv",
+                "user": {"uid": 101, "gid": 101}
+            },
+            "root": {"path": "rootfs"},
+            "mounts": [
+                {"destination": "/proc", "type": "proc", "source": "proc"},
+                {"destination": "/data", "type": "bind", "source": "/var/data", "options": ["rbind", "ro"]},
+                {"destination": "/tmp", "type": "tmpfs", "source": "tmpfs"}
+            ],
+            "linux": {
+                "resources": {
+                    "memory": {"limit": 536870912},
+                    "cpu": {"quota": 150000, "period": 100000}
+                }
+            }
+        }))
+        .unwrap();
+
+        let options = box_options(&spec, Path::new("/run/bundle/rootfs"));
+        assert!(matches!(&options.rootfs, RootfsSpec::RootfsPath(p) if p == "/run/bundle/rootfs"));
+        assert_eq!(options.memory_mib, Some(512));
+        assert_eq!(options.cpus, Some(2));
+        assert_eq!(options.user.as_deref(), Some("101:101"));
+        assert_eq!(options.working_dir.as_deref(), Some("/srv"));
+        assert_eq!(options.env.len(), 2);
+        assert_eq!(options.volumes.len(), 1);
+        assert_eq!(options.volumes[0].guest_path, "/data");
+        assert!(options.volumes[0].read_only);
+
+        let command = command(spec.process().as_ref().unwrap()).unwrap();
+        assert!(format!("{:?}", command).contains(r#"args: ["-g", "daemon off;"]"#));
+    }
+}
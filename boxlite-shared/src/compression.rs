@@ -0,0 +1,134 @@
+//! Exec output stream compression.
+//!
+//! Each compressed `Stdout`/`Stderr` chunk is one self-contained gzip or
+//! zstd frame, so the receiver can decode chunks independently and a chunk
+//! sent raw (too small, or incompressible) needs no special framing.
+
+use std::io::{self, Read, Write};
+
+use crate::StreamCompression;
+
+/// Chunks smaller than this are sent raw: frame overhead eats the savings.
+pub const MIN_COMPRESS_SIZE: usize = 512;
+
+/// Upper bound on a decompressed chunk, guarding against decompression bombs.
+pub const MAX_CHUNK_SIZE: u64 = 16 * 1024 * 1024;
+
+/// zstd level for exec output (fast; output streams are latency-sensitive)
+const ZSTD_LEVEL: i32 = 3;
+
+/// Compress `data` into a single frame.
+///
+/// Returns the input unchanged for `StreamCompression::None`.
+pub fn compress(codec: StreamCompression, data: &[u8]) -> io::Result<Vec<u8>> {
+    match codec {
+        StreamCompression::None => Ok(data.to_vec()),
+        StreamCompression::Gzip => {
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+            encoder.write_all(data)?;
+            encoder.finish()
+        }
+        StreamCompression::Zstd => zstd::bulk::compress(data, ZSTD_LEVEL),
+    }
+}
+
+/// Decompress one frame produced by [`compress`].
+///
+/// Fails with `InvalidData` if the output would exceed [`MAX_CHUNK_SIZE`].
+pub fn decompress(codec: StreamCompression, data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    match codec {
+        StreamCompression::None => return Ok(data.to_vec()),
+        StreamCompression::Gzip => {
+            flate2::read::GzDecoder::new(data)
+                .take(MAX_CHUNK_SIZE + 1)
+                .read_to_end(&mut out)?;
+        }
+        StreamCompression::Zstd => {
+            zstd::stream::read::Decoder::new(data)?
+                .take(MAX_CHUNK_SIZE + 1)
+                .read_to_end(&mut out)?;
+        }
+    }
+    if out.len() as u64 > MAX_CHUNK_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("decompressed chunk exceeds {} bytes", MAX_CHUNK_SIZE),
+        ));
+    }
+    Ok(out)
+}
+
+/// Encode an output chunk for the wire.
+///
+/// Returns the payload and whether it is compressed. Chunks below
+/// [`MIN_COMPRESS_SIZE`], and chunks that would not shrink, are sent raw.
+pub fn encode_chunk(codec: StreamCompression, data: Vec<u8>) -> (Vec<u8>, bool) {
+    if codec == StreamCompression::None || data.len() < MIN_COMPRESS_SIZE {
+        return (data, false);
+    }
+    match compress(codec, &data) {
+        Ok(frame) if frame.len() < data.len() => (frame, true),
+        _ => (data, false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<u8> {
+        (0..20_000)
+            .flat_map(|i| format!("line {} of build output\n", i % 100).into_bytes())
+            .collect()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let data = sample();
+        for codec in [StreamCompression::Gzip, StreamCompression::Zstd] {
+            let frame = compress(codec, &data).unwrap();
+            assert!(
+                frame.len() < data.len() / 10,
+                "{:?} barely compressed",
+                codec
+            );
+            assert_eq!(decompress(codec, &frame).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_encode_chunk_sends_small_and_incompressible_raw() {
+        let (payload, compressed) = encode_chunk(StreamCompression::Zstd, b"hello\n".to_vec());
+        assert!(!compressed);
+        assert_eq!(payload, b"hello\n");
+
+        // Pseudo-random bytes don't shrink
+        let mut state = 0x2545_f491_u32;
+        let noise: Vec<u8> = (0..4096)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect();
+        let (payload, compressed) = encode_chunk(StreamCompression::Gzip, noise.clone());
+        assert!(!compressed);
+        assert_eq!(payload, noise);
+
+        let (_, compressed) = encode_chunk(StreamCompression::Gzip, sample());
+        assert!(compressed);
+        let (_, compressed) = encode_chunk(StreamCompression::None, sample());
+        assert!(!compressed);
+    }
+
+    #[test]
+    fn test_decompress_rejects_bombs() {
+        let zeros = vec![0u8; MAX_CHUNK_SIZE as usize + 1];
+        let frame = compress(StreamCompression::Zstd, &zeros).unwrap();
+        let err = decompress(StreamCompression::Zstd, &frame).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
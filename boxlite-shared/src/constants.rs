@@ -7,6 +7,9 @@ pub mod container {
     /// Default container hostname
     pub const DEFAULT_HOSTNAME: &str = "boxlite";
 
+    /// Name of the host's loopback in the container's /etc/hosts
+    pub const HOST_LOOPBACK_HOSTNAME: &str = "host.boxlite.internal";
+
     /// Default RLIMIT_NOFILE soft limit
     pub const RLIMIT_NOFILE_SOFT: u64 = 1024;
 
@@ -40,6 +43,30 @@ pub mod executor {
     pub const CONTAINER_KEY: &str = "container";
 }
 
+/// `ExecError.reason` values
+///
+/// The host turns the first two into a finished execution with shell exit
+/// codes (127 and 126) instead of an error.
+pub mod exec_error {
+    /// The program is not in the container (not on `PATH`, or no such file)
+    pub const COMMAND_NOT_FOUND: &str = "command_not_found";
+
+    /// The program exists but cannot be executed (directory, no `x` bit)
+    pub const NOT_EXECUTABLE: &str = "not_executable";
+
+    /// Any other spawn failure
+    pub const SPAWN_FAILED: &str = "spawn_failed";
+
+    /// The box is already running as many executions as the guest allows
+    pub const TOO_MANY_EXECUTIONS: &str = "too_many_executions";
+
+    /// Exit code for `COMMAND_NOT_FOUND`, as in POSIX shells
+    pub const COMMAND_NOT_FOUND_EXIT_CODE: i32 = 127;
+
+    /// Exit code for `NOT_EXECUTABLE`, as in POSIX shells
+    pub const NOT_EXECUTABLE_EXIT_CODE: i32 = 126;
+}
+
 /// Virtiofs mount tags
 ///
 /// These tags identify shared filesystems mounted via virtiofs.
@@ -53,4 +80,46 @@ pub mod mount_tags {
 
     /// Tag for shared container directory (contains overlayfs/ and rootfs/)
     pub const SHARED: &str = "BoxLiteShared";
+
+    /// Tag for the core dump directory (writable by the guest)
+    pub const CORES: &str = "BoxLiteCores";
+}
+
+/// Host-guest agent protocol versioning
+///
+/// Checked by the host before the first init RPC so a custom guest rootfs
+/// with a stale or too-new agent fails with a clear error.
+pub mod protocol {
+    /// Protocol version implemented by this build.
+    /// Bump on changes an older peer cannot handle.
+    ///
+    /// - v2: `ImageRootfs` strategy (EROFS/SquashFS rootfs under overlayfs)
+    /// - v3: core dump collection in `GuestInitRequest`
+    /// - v4: `stop_box_on_exit` in `ExecRequest`
+    /// - v5: `user` and `privileged` in `ExecRequest`
+    /// - v6: `hostname` in `ContainerInitRequest`
+    /// - v7: `fresh_rootfs` in `ExecRequest`
+    pub const VERSION: u32 = 7;
+
+    /// Oldest guest agent protocol version the host still supports
+    pub const MIN_GUEST_VERSION: u32 = 1;
+
+    /// First protocol version with the `ImageRootfs` strategy
+    pub const IMAGE_ROOTFS: u32 = 2;
+
+    /// First protocol version that collects core dumps
+    pub const CORE_DUMPS: u32 = 3;
+
+    /// First protocol version that stops the box when an execution asks to
+    pub const STOP_BOX_ON_EXIT: u32 = 4;
+
+    /// First protocol version that runs an execution as another user or
+    /// privileged
+    pub const EXEC_USER: u32 = 5;
+
+    /// First protocol version that sets the container hostname
+    pub const CONTAINER_HOSTNAME: u32 = 6;
+
+    /// First protocol version that runs an execution on a fresh rootfs
+    pub const FRESH_ROOTFS: u32 = 7;
 }
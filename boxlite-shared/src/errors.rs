@@ -22,12 +22,33 @@ pub enum BoxliteError {
     #[error("images error: {0}")]
     Image(String),
 
+    /// Image reference did not resolve in any registry or local store.
+    #[error("image not found: {0}")]
+    ImageNotFound(String),
+
+    /// Registry rejected the pull credentials (or required some).
+    #[error("image pull unauthorized: {0}")]
+    ImagePullAuth(String),
+
+    /// Cached image content does not hash to its pinned digest.
+    #[error("image integrity check failed: {0}")]
+    Integrity(String),
+
     #[error("portal error: {0}")]
     Portal(String),
 
     #[error("network error: {0}")]
     Network(String),
 
+    /// Requested host port is already bound by another process or box.
+    #[error("port in use: host port {port} is already bound by {}", port_owner(.owner_box))]
+    PortInUse {
+        /// Host port that could not be bound
+        port: u16,
+        /// Name (or ID) of the boxlite box holding it, if it is one
+        owner_box: Option<String>,
+    },
+
     #[error("gRPC/tonic error: {0}")]
     Rpc(String),
 
@@ -43,6 +64,14 @@ pub enum BoxliteError {
     #[error("unsupported: {0}")]
     Unsupported(String),
 
+    /// Host lacks usable hardware virtualization (KVM, Hypervisor.framework).
+    #[error("virtualization unavailable: {0}")]
+    KvmUnavailable(String),
+
+    /// Guest agent did not become ready in time.
+    #[error("guest timeout: {0}")]
+    GuestTimeout(String),
+
     /// Box not found in registry or database.
     #[error("box not found: {0}")]
     NotFound(String),
@@ -51,6 +80,10 @@ pub enum BoxliteError {
     #[error("already exists: {0}")]
     AlreadyExists(String),
 
+    /// Box name is already taken by another box.
+    #[error("name conflict: {0}")]
+    NameConflict(String),
+
     /// Box is in wrong state for the requested operation.
     #[error("invalid state: {0}")]
     InvalidState(String),
@@ -70,11 +103,206 @@ pub enum BoxliteError {
     /// Resource (box or runtime) has been stopped/shutdown.
     #[error("stopped: {0}")]
     Stopped(String),
+
+    /// Runtime capacity limits (box count, memory) would be exceeded.
+    #[error("resource exhausted: {0}")]
+    ResourceExhausted(String),
+}
+
+/// Stable, machine-readable identifier for a [`BoxliteError`].
+///
+/// Codes survive the SDK boundaries: numeric in the C API, exception classes
+/// in Python, and the `code` field of Node errors. Specific codes roll up to
+/// a broader [`category`](ErrorCode::category) so callers can match coarsely.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorCode {
+    UnsupportedEngine,
+    Engine,
+    Config,
+    Storage,
+    Image,
+    ImageNotFound,
+    ImagePullAuth,
+    Integrity,
+    Portal,
+    Network,
+    PortInUse,
+    Rpc,
+    RpcTransport,
+    Internal,
+    Execution,
+    Unsupported,
+    KvmUnavailable,
+    GuestTimeout,
+    NotFound,
+    AlreadyExists,
+    NameConflict,
+    InvalidState,
+    Database,
+    Metadata,
+    InvalidArgument,
+    Stopped,
+    ResourceExhausted,
+}
+
+impl ErrorCode {
+    /// Every code, in declaration order.
+    pub const ALL: &'static [ErrorCode] = &[
+        ErrorCode::UnsupportedEngine,
+        ErrorCode::Engine,
+        ErrorCode::Config,
+        ErrorCode::Storage,
+        ErrorCode::Image,
+        ErrorCode::ImageNotFound,
+        ErrorCode::ImagePullAuth,
+        ErrorCode::Integrity,
+        ErrorCode::Portal,
+        ErrorCode::Network,
+        ErrorCode::PortInUse,
+        ErrorCode::Rpc,
+        ErrorCode::RpcTransport,
+        ErrorCode::Internal,
+        ErrorCode::Execution,
+        ErrorCode::Unsupported,
+        ErrorCode::KvmUnavailable,
+        ErrorCode::GuestTimeout,
+        ErrorCode::NotFound,
+        ErrorCode::AlreadyExists,
+        ErrorCode::NameConflict,
+        ErrorCode::InvalidState,
+        ErrorCode::Database,
+        ErrorCode::Metadata,
+        ErrorCode::InvalidArgument,
+        ErrorCode::Stopped,
+        ErrorCode::ResourceExhausted,
+    ];
+
+    /// Wire name, e.g. `IMAGE_NOT_FOUND`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::UnsupportedEngine => "UNSUPPORTED_ENGINE",
+            ErrorCode::Engine => "ENGINE",
+            ErrorCode::Config => "CONFIG",
+            ErrorCode::Storage => "STORAGE",
+            ErrorCode::Image => "IMAGE",
+            ErrorCode::ImageNotFound => "IMAGE_NOT_FOUND",
+            ErrorCode::ImagePullAuth => "IMAGE_PULL_AUTH",
+            ErrorCode::Integrity => "INTEGRITY",
+            ErrorCode::Portal => "PORTAL",
+            ErrorCode::Network => "NETWORK",
+            ErrorCode::PortInUse => "PORT_IN_USE",
+            ErrorCode::Rpc => "RPC",
+            ErrorCode::RpcTransport => "RPC_TRANSPORT",
+            ErrorCode::Internal => "INTERNAL",
+            ErrorCode::Execution => "EXECUTION",
+            ErrorCode::Unsupported => "UNSUPPORTED",
+            ErrorCode::KvmUnavailable => "KVM_UNAVAILABLE",
+            ErrorCode::GuestTimeout => "GUEST_TIMEOUT",
+            ErrorCode::NotFound => "NOT_FOUND",
+            ErrorCode::AlreadyExists => "ALREADY_EXISTS",
+            ErrorCode::NameConflict => "NAME_CONFLICT",
+            ErrorCode::InvalidState => "INVALID_STATE",
+            ErrorCode::Database => "DATABASE",
+            ErrorCode::Metadata => "METADATA",
+            ErrorCode::InvalidArgument => "INVALID_ARGUMENT",
+            ErrorCode::Stopped => "STOPPED",
+            ErrorCode::ResourceExhausted => "RESOURCE_EXHAUSTED",
+        }
+    }
+
+    /// Whether the failure may go away on its own (flaky network, slow boot),
+    /// so retrying the same operation can succeed.
+    pub fn is_transient(self) -> bool {
+        matches!(
+            self,
+            ErrorCode::Network | ErrorCode::RpcTransport | ErrorCode::GuestTimeout
+        )
+    }
+
+    /// Broader code this one refines; general codes are their own category.
+    pub fn category(self) -> ErrorCode {
+        match self {
+            ErrorCode::ImageNotFound | ErrorCode::ImagePullAuth | ErrorCode::Integrity => {
+                ErrorCode::Image
+            }
+            ErrorCode::PortInUse => ErrorCode::Network,
+            ErrorCode::KvmUnavailable => ErrorCode::Unsupported,
+            ErrorCode::GuestTimeout => ErrorCode::Engine,
+            ErrorCode::NameConflict => ErrorCode::AlreadyExists,
+            code => code,
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl BoxliteError {
+    /// Machine-readable code for this error.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            BoxliteError::UnsupportedEngine => ErrorCode::UnsupportedEngine,
+            BoxliteError::Engine(_) => ErrorCode::Engine,
+            BoxliteError::Config(_) => ErrorCode::Config,
+            BoxliteError::Storage(_) => ErrorCode::Storage,
+            BoxliteError::Image(_) => ErrorCode::Image,
+            BoxliteError::ImageNotFound(_) => ErrorCode::ImageNotFound,
+            BoxliteError::ImagePullAuth(_) => ErrorCode::ImagePullAuth,
+            BoxliteError::Integrity(_) => ErrorCode::Integrity,
+            BoxliteError::Portal(_) => ErrorCode::Portal,
+            BoxliteError::Network(_) => ErrorCode::Network,
+            BoxliteError::PortInUse { .. } => ErrorCode::PortInUse,
+            BoxliteError::Rpc(_) => ErrorCode::Rpc,
+            BoxliteError::RpcTransport(_) => ErrorCode::RpcTransport,
+            BoxliteError::Internal(_) => ErrorCode::Internal,
+            BoxliteError::Execution(_) => ErrorCode::Execution,
+            BoxliteError::Unsupported(_) => ErrorCode::Unsupported,
+            BoxliteError::KvmUnavailable(_) => ErrorCode::KvmUnavailable,
+            BoxliteError::GuestTimeout(_) => ErrorCode::GuestTimeout,
+            BoxliteError::NotFound(_) => ErrorCode::NotFound,
+            BoxliteError::AlreadyExists(_) => ErrorCode::AlreadyExists,
+            BoxliteError::NameConflict(_) => ErrorCode::NameConflict,
+            BoxliteError::InvalidState(_) => ErrorCode::InvalidState,
+            BoxliteError::Database(_) => ErrorCode::Database,
+            BoxliteError::MetadataError(_) => ErrorCode::Metadata,
+            BoxliteError::InvalidArgument(_) => ErrorCode::InvalidArgument,
+            BoxliteError::Stopped(_) => ErrorCode::Stopped,
+            BoxliteError::ResourceExhausted(_) => ErrorCode::ResourceExhausted,
+        }
+    }
+
+    /// Whether retrying the failed operation may succeed.
+    pub fn is_transient(&self) -> bool {
+        self.code().is_transient()
+    }
+}
+
+fn port_owner(owner_box: &Option<String>) -> String {
+    match owner_box {
+        Some(owner) => format!("box '{}'", owner),
+        None => "another process".to_string(),
+    }
 }
 
+/// `EMFILE` and `ENFILE`, the same on Linux and macOS.
+const TOO_MANY_OPEN_FILES: [i32; 2] = [24, 23];
+
 // Implement From for common error types to enable `?` operator
 impl From<std::io::Error> for BoxliteError {
     fn from(err: std::io::Error) -> Self {
+        if err
+            .raw_os_error()
+            .is_some_and(|code| TOO_MANY_OPEN_FILES.contains(&code))
+        {
+            return BoxliteError::ResourceExhausted(format!(
+                "{}: raise the open file limit (e.g. `ulimit -n 8192`) or stop some boxes",
+                err
+            ));
+        }
         BoxliteError::Internal(format!("I/O error: {}", err))
     }
 }
@@ -99,7 +327,12 @@ impl From<&str> for BoxliteError {
 
 impl From<tonic::Status> for BoxliteError {
     fn from(err: tonic::Status) -> Self {
-        BoxliteError::Rpc(err.to_string())
+        // `Unavailable` is how tonic reports a dropped or refused connection.
+        if err.code() == tonic::Code::Unavailable {
+            BoxliteError::RpcTransport(err.to_string())
+        } else {
+            BoxliteError::Rpc(err.to_string())
+        }
     }
 }
 
@@ -108,3 +341,75 @@ impl From<tonic::transport::Error> for BoxliteError {
         BoxliteError::RpcTransport(err.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_error_code_names_are_unique() {
+        let names: HashSet<_> = ErrorCode::ALL.iter().map(|code| code.as_str()).collect();
+        assert_eq!(names.len(), ErrorCode::ALL.len());
+    }
+
+    #[test]
+    fn test_error_code_categories() {
+        let err = BoxliteError::ImageNotFound("docker.io/library/nope:latest".into());
+        assert_eq!(err.code(), ErrorCode::ImageNotFound);
+        assert_eq!(err.code().category(), ErrorCode::Image);
+        assert_eq!(ErrorCode::NameConflict.category(), ErrorCode::AlreadyExists);
+        assert_eq!(ErrorCode::Storage.category(), ErrorCode::Storage);
+        for code in ErrorCode::ALL {
+            assert_eq!(code.category().category(), code.category());
+        }
+    }
+
+    #[test]
+    fn test_transient_errors() {
+        assert!(BoxliteError::GuestTimeout("30s".into()).is_transient());
+        assert!(BoxliteError::Network("connection reset".into()).is_transient());
+        assert!(!BoxliteError::ImageNotFound("nope".into()).is_transient());
+        assert!(!BoxliteError::Config("bad".into()).is_transient());
+    }
+
+    #[test]
+    fn test_port_in_use_names_owner() {
+        let err = BoxliteError::PortInUse {
+            port: 8080,
+            owner_box: Some("web".into()),
+        };
+        assert_eq!(err.code(), ErrorCode::PortInUse);
+        assert!(!err.is_transient());
+        assert_eq!(
+            err.to_string(),
+            "port in use: host port 8080 is already bound by box 'web'"
+        );
+
+        let err = BoxliteError::PortInUse {
+            port: 80,
+            owner_box: None,
+        };
+        assert!(err.to_string().ends_with("by another process"), "{}", err);
+    }
+
+    #[test]
+    fn test_unavailable_status_is_transport_error() {
+        let err = BoxliteError::from(tonic::Status::unavailable("connection reset"));
+        assert_eq!(err.code(), ErrorCode::RpcTransport);
+        assert!(err.is_transient());
+
+        let err = BoxliteError::from(tonic::Status::not_found("no such execution"));
+        assert_eq!(err.code(), ErrorCode::Rpc);
+    }
+
+    #[test]
+    fn test_fd_exhaustion_is_resource_exhausted() {
+        let err = BoxliteError::from(std::io::Error::from_raw_os_error(24));
+        assert_eq!(err.code(), ErrorCode::ResourceExhausted);
+        assert!(err.to_string().contains("ulimit -n"), "{}", err);
+
+        let err = BoxliteError::from(std::io::Error::from_raw_os_error(2));
+        assert_eq!(err.code(), ErrorCode::Internal);
+    }
+}
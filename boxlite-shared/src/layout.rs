@@ -35,6 +35,9 @@ pub mod dirs {
     /// Overlayfs work directory name
     pub const WORK: &str = "work";
 
+    /// Read-only rootfs image mount directory name (overlayfs lower dir)
+    pub const IMAGE: &str = "image";
+
     /// Overlayfs diff directory name (contains image layers)
     pub const DIFF: &str = "diff";
 
@@ -43,11 +46,17 @@ pub mod dirs {
 
     /// Volumes directory name (contains user volumes)
     pub const VOLUMES: &str = "volumes";
+
+    /// Core dumps directory name (written by the guest)
+    pub const CORES: &str = "cores";
 }
 
 /// Guest base path (FHS-compliant).
 pub const GUEST_BASE: &str = "/run/boxlite";
 
+/// Guest mount point of the core dump directory ({GUEST_BASE}/cores).
+pub const GUEST_CORES_DIR: &str = "/run/boxlite/cores";
+
 // ============================================================================
 // SHARED CONTAINER LAYOUT (per-container directories)
 // ============================================================================
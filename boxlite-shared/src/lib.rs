@@ -3,6 +3,7 @@
 //! This crate contains common types, protocols, and utilities
 //! used by both the host-side runtime (boxlite) and guest agent.
 
+pub mod compression;
 pub mod constants;
 pub mod errors;
 pub mod layout;
@@ -14,7 +15,7 @@ pub mod generated {
     tonic::include_proto!("boxlite.v1");
 }
 
-pub use errors::{BoxliteError, BoxliteResult};
+pub use errors::{BoxliteError, BoxliteResult, ErrorCode};
 pub use transport::Transport;
 
 // Container service
@@ -0,0 +1,52 @@
+//! Exec output compression throughput.
+//!
+//! Run with: `cargo bench -p boxlite --bench exec_compression`
+//!
+//! Targets (per core): encode ≥ 200 MiB/s and decode ≥ 500 MiB/s for zstd on
+//! typical text output, so compression never becomes the bottleneck on a
+//! vsock channel.
+
+use boxlite_shared::StreamCompression;
+use boxlite_shared::compression::{decompress, encode_chunk};
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+
+/// 64 KiB of build-log-like text, the size of a coalesced guest chunk.
+fn text_chunk() -> Vec<u8> {
+    (0..)
+        .flat_map(|i| {
+            format!("[{:>5}] Compiling crate-{} v0.{}.0\n", i, i % 37, i % 9).into_bytes()
+        })
+        .take(64 * 1024)
+        .collect()
+}
+
+fn bench_compression(c: &mut Criterion) {
+    let data = text_chunk();
+    let codecs = [
+        ("gzip", StreamCompression::Gzip),
+        ("zstd", StreamCompression::Zstd),
+    ];
+
+    let mut group = c.benchmark_group("exec_output_encode");
+    group.throughput(Throughput::Bytes(data.len() as u64));
+    for (name, codec) in codecs {
+        group.bench_with_input(BenchmarkId::from_parameter(name), &data, |b, data| {
+            b.iter(|| encode_chunk(codec, data.clone()));
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("exec_output_decode");
+    group.throughput(Throughput::Bytes(data.len() as u64));
+    for (name, codec) in codecs {
+        let (frame, compressed) = encode_chunk(codec, data.clone());
+        assert!(compressed);
+        group.bench_with_input(BenchmarkId::from_parameter(name), &frame, |b, frame| {
+            b.iter(|| decompress(codec, frame).unwrap());
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_compression);
+criterion_main!(benches);
@@ -0,0 +1,79 @@
+//! Exec round-trip latency under concurrency.
+//!
+//! Run with: `cargo bench -p boxlite --features testing --bench exec_latency`
+//!
+//! Runs `true` in a box on the mock engine, so this measures the host side
+//! of an exec (guest connection, bookkeeping, result delivery) without a VM.
+//! Concurrent execs should not queue behind each other: compare
+//! `exec_round_trip/32` with `exec_round_trip_sequential/32`, which runs the
+//! same batch one exec after another.
+
+use std::time::Duration;
+
+use boxlite::{BoxCommand, BoxOptions, BoxliteOptions, BoxliteRuntime, LiteBox, MockScript};
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use futures::future::join_all;
+
+/// Execs in the largest batch, run both concurrently and sequentially.
+const BATCH: u32 = 32;
+
+async fn round_trip(litebox: &LiteBox) {
+    let mut execution = litebox.exec(BoxCommand::new("true")).await.unwrap();
+    assert!(execution.wait().await.unwrap().success());
+}
+
+async fn concurrent(litebox: &LiteBox, n: u32) {
+    join_all((0..n).map(|_| round_trip(litebox))).await;
+}
+
+async fn sequential(litebox: &LiteBox, n: u32) {
+    for _ in 0..n {
+        round_trip(litebox).await;
+    }
+}
+
+fn bench_exec(c: &mut Criterion) {
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let home = tempfile::tempdir().unwrap();
+    let runtime = BoxliteRuntime::with_mock_engine(
+        BoxliteOptions {
+            home_dir: home.path().to_path_buf(),
+            ..Default::default()
+        },
+        MockScript::new(),
+    )
+    .unwrap();
+    let litebox = rt.block_on(async {
+        let litebox = runtime.create(BoxOptions::default(), None).await.unwrap();
+        litebox.start().await.unwrap();
+        // Warm up the guest connection
+        round_trip(&litebox).await;
+        litebox
+    });
+
+    let mut group = c.benchmark_group("exec_round_trip");
+    group.measurement_time(Duration::from_secs(5));
+    for n in [1, 8, BATCH] {
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, &n| {
+            b.iter(|| rt.block_on(concurrent(&litebox, n)));
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("exec_round_trip_sequential");
+    group.measurement_time(Duration::from_secs(5));
+    group.throughput(Throughput::Elements(BATCH as u64));
+    group.bench_with_input(BenchmarkId::from_parameter(BATCH), &BATCH, |b, &n| {
+        b.iter(|| rt.block_on(sequential(&litebox, n)));
+    });
+    group.finish();
+
+    rt.block_on(litebox.stop()).unwrap();
+}
+
+criterion_group!(benches, bench_exec);
+criterion_main!(benches);
@@ -0,0 +1,61 @@
+//! Layer extraction throughput.
+//!
+//! Run with: `cargo bench -p boxlite --features testing --bench layer_extract`
+
+use std::io::Write;
+use std::path::Path;
+
+use boxlite::extract_layer_tarball_streaming;
+use criterion::{BatchSize, BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+
+/// Write a gzip layer with `files` files of `file_size` bytes each.
+fn write_layer(path: &Path, files: usize, file_size: usize) -> u64 {
+    let mut builder = tar::Builder::new(Vec::new());
+    let content: Vec<u8> = (0..file_size).map(|i| (i * 31 % 251) as u8).collect();
+    for i in 0..files {
+        let mut header = tar::Header::new_gnu();
+        header
+            .set_path(format!("usr/lib/dir{}/file{}.bin", i % 16, i))
+            .unwrap();
+        header.set_size(file_size as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append(&header, &content[..]).unwrap();
+    }
+    let tar = builder.into_inner().unwrap();
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&tar).unwrap();
+    std::fs::write(path, encoder.finish().unwrap()).unwrap();
+    tar.len() as u64
+}
+
+fn bench_extract(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    let mut group = c.benchmark_group("extract_layer");
+    group.sample_size(10);
+
+    // Many small files (metadata-bound) vs few large files (decompression-bound)
+    for (name, files, file_size) in [("small_files", 4096, 4 * 1024), ("large_files", 8, 8 << 20)] {
+        let tarball = dir.path().join(format!("{}.tar.gz", name));
+        let bytes = write_layer(&tarball, files, file_size);
+        group.throughput(Throughput::Bytes(bytes));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &tarball, |b, tarball| {
+            b.iter_batched(
+                || tempfile::tempdir_in(dir.path()).unwrap(),
+                |dest| {
+                    extract_layer_tarball_streaming(tarball, dest.path()).unwrap();
+                    dest
+                },
+                BatchSize::PerIteration,
+            );
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_extract);
+criterion_main!(benches);
@@ -34,8 +34,8 @@ fn build_gvproxy(source_dir: &Path, output_path: &Path) {
     build_cmd.args([
         "-o",
         output_path.to_str().expect("Invalid output path"),
-        "main.go",
-        "stats.go",
+        // The whole package: main.go alone misses the other bridge sources
+        ".",
     ]);
 
     let build_status = build_cmd
@@ -99,9 +99,7 @@ fn get_library_name() -> &'static str {
 
 fn main() {
     // Rebuild if Go sources change
-    println!("cargo:rerun-if-changed=gvproxy-bridge/main.go");
-    println!("cargo:rerun-if-changed=gvproxy-bridge/stats.go");
-    println!("cargo:rerun-if-changed=gvproxy-bridge/go.mod");
+    println!("cargo:rerun-if-changed=gvproxy-bridge");
 
     // Check for stub mode (for CI linting without building)
     // Set BOXLITE_DEPS_STUB=1 to skip building and emit stub link directives
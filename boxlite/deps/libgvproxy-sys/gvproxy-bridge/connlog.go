@@ -0,0 +1,481 @@
+package main
+
+import (
+	"context"
+	"encoding/binary"
+	"encoding/json"
+	"net"
+	"net/netip"
+	"os"
+	"strconv"
+	"sync"
+	"time"
+
+	logrus "github.com/sirupsen/logrus"
+)
+
+const (
+	// connLogSweepInterval is how often idle flows are checked for
+	connLogSweepInterval = 10 * time.Second
+	// connLogIdleTimeout ends UDP flows and TCP flows that never connected
+	connLogIdleTimeout = time.Minute
+	// connLogTCPIdleTimeout ends established TCP flows that saw no FIN or RST
+	connLogTCPIdleTimeout = 10 * time.Minute
+	// connLogMaxFlows bounds the tracked flows; new ones beyond it are not logged
+	connLogMaxFlows = 65536
+	// connLogMaxBytes is the size past which the log is rotated
+	connLogMaxBytes = 16 << 20
+	// connLogRotatedFiles is how many rotated logs are kept, name.1 being the
+	// newest (must stay in sync with the Rust connection_log::ROTATED_FILES!)
+	connLogRotatedFiles = 4
+)
+
+const (
+	protoTCP = 6
+	protoUDP = 17
+
+	tcpFIN = 0x01
+	tcpSYN = 0x02
+	tcpRST = 0x04
+	tcpACK = 0x10
+)
+
+// connRecord is one line of the connection log (must stay in sync with the
+// Rust ConnectionRecord!)
+type connRecord struct {
+	Time          time.Time `json:"time"`
+	Protocol      string    `json:"protocol"`
+	SrcPort       uint16    `json:"src_port"`
+	DstIP         string    `json:"dst_ip"`
+	DstPort       uint16    `json:"dst_port"`
+	Hostname      string    `json:"hostname,omitempty"`
+	BytesSent     uint64    `json:"bytes_sent"`
+	BytesReceived uint64    `json:"bytes_received"`
+	DurationMs    int64     `json:"duration_ms"`
+	Blocked       bool      `json:"blocked,omitempty"`
+}
+
+// flowKey identifies a flow from the guest's side.
+type flowKey struct {
+	proto  uint8
+	guest  netip.AddrPort
+	remote netip.AddrPort
+}
+
+type connFlow struct {
+	start, last time.Time
+	sent        uint64
+	received    uint64
+	hostname    string
+	sniChecked  bool
+	blocked     bool
+	established bool
+	guestFin    bool
+	remoteFin   bool
+}
+
+// connLogger records the connections the guest opens to destinations other
+// than the gateway, one JSON line per connection once it ends. It watches
+// the raw frames on the VM connection, before the egress filter, so
+// connections the policy drops are logged as blocked. The log is rotated by
+// size, so a long-running box cannot fill the disk with it.
+type connLogger struct {
+	mu      sync.Mutex
+	path    string
+	out     *os.File
+	written int64
+	gateway netip.Addr
+	filter  *egressFilter
+	flows   map[flowKey]*connFlow
+	full    bool
+	closed  bool
+}
+
+func newConnLogger(path, gatewayIP string, filter *egressFilter) (*connLogger, error) {
+	gateway, err := netip.ParseAddr(gatewayIP)
+	if err != nil {
+		return nil, err
+	}
+	out, err := os.OpenFile(path, os.O_WRONLY|os.O_CREATE|os.O_APPEND, 0o600)
+	if err != nil {
+		return nil, err
+	}
+	// A restarted box appends to the log of its previous run
+	info, err := out.Stat()
+	if err != nil {
+		out.Close()
+		return nil, err
+	}
+	return &connLogger{
+		path:    path,
+		out:     out,
+		written: info.Size(),
+		gateway: gateway,
+		filter:  filter,
+		flows:   make(map[flowKey]*connFlow),
+	}, nil
+}
+
+// run ends idle flows until ctx is cancelled, then logs every open flow and
+// closes the log.
+func (l *connLogger) run(ctx context.Context) {
+	ticker := time.NewTicker(connLogSweepInterval)
+	defer ticker.Stop()
+	for {
+		select {
+		case <-ctx.Done():
+			l.mu.Lock()
+			for key, flow := range l.flows {
+				l.finish(key, flow)
+			}
+			l.out.Close()
+			l.closed = true
+			l.mu.Unlock()
+			return
+		case now := <-ticker.C:
+			l.mu.Lock()
+			for key, flow := range l.flows {
+				idle := connLogIdleTimeout
+				if key.proto == protoTCP && flow.established {
+					idle = connLogTCPIdleTimeout
+				}
+				if now.Sub(flow.last) > idle {
+					l.finish(key, flow)
+				}
+			}
+			l.mu.Unlock()
+		}
+	}
+}
+
+// finish logs flow and stops tracking it. Callers hold l.mu.
+func (l *connLogger) finish(key flowKey, flow *connFlow) {
+	delete(l.flows, key)
+	l.full = false
+	protocol := "tcp"
+	if key.proto == protoUDP {
+		protocol = "udp"
+	}
+	line, err := json.Marshal(connRecord{
+		Time:          flow.start.UTC(),
+		Protocol:      protocol,
+		SrcPort:       key.guest.Port(),
+		DstIP:         key.remote.Addr().String(),
+		DstPort:       key.remote.Port(),
+		Hostname:      flow.hostname,
+		BytesSent:     flow.sent,
+		BytesReceived: flow.received,
+		DurationMs:    flow.last.Sub(flow.start).Milliseconds(),
+		Blocked:       flow.blocked,
+	})
+	if err != nil {
+		return
+	}
+	if err := l.write(append(line, '\n')); err != nil {
+		logrus.WithError(err).Warn("Failed to write connection log")
+	}
+}
+
+// write appends line to the log, rotating it first when the line would take
+// it past connLogMaxBytes. Callers hold l.mu.
+func (l *connLogger) write(line []byte) error {
+	if l.written > 0 && l.written+int64(len(line)) > connLogMaxBytes {
+		if err := l.rotate(); err != nil {
+			return err
+		}
+	}
+	n, err := l.out.Write(line)
+	l.written += int64(n)
+	return err
+}
+
+// rotate shifts name.N to name.N+1, dropping the oldest, moves the log to
+// name.1 and starts a new one. Callers hold l.mu.
+func (l *connLogger) rotate() error {
+	for n := connLogRotatedFiles - 1; n >= 1; n-- {
+		if err := os.Rename(l.rotated(n), l.rotated(n+1)); err != nil && !os.IsNotExist(err) {
+			return err
+		}
+	}
+	// Renaming keeps the open file valid, so a failure below leaves the log
+	// as it was
+	if err := os.Rename(l.path, l.rotated(1)); err != nil {
+		return err
+	}
+	out, err := os.OpenFile(l.path, os.O_WRONLY|os.O_CREATE|os.O_TRUNC, 0o600)
+	if err != nil {
+		return err
+	}
+	l.out.Close()
+	l.out = out
+	l.written = 0
+	return nil
+}
+
+func (l *connLogger) rotated(n int) string {
+	return l.path + "." + strconv.Itoa(n)
+}
+
+// observe accounts an Ethernet frame sent by the guest (fromGuest) or to it.
+func (l *connLogger) observe(frame []byte, fromGuest bool) {
+	if len(frame) < 14 {
+		return
+	}
+	packet := frame[14:]
+	var proto uint8
+	var src, dst netip.Addr
+	var l4 []byte
+	switch binary.BigEndian.Uint16(frame[12:14]) {
+	case 0x0800: // IPv4
+		if len(packet) < 20 {
+			return
+		}
+		headerLen := int(packet[0]&0x0f) * 4
+		// Only the first fragment carries the ports
+		if headerLen < 20 || len(packet) < headerLen || binary.BigEndian.Uint16(packet[6:8])&0x1fff != 0 {
+			return
+		}
+		proto = packet[9]
+		src = netip.AddrFrom4([4]byte(packet[12:16]))
+		dst = netip.AddrFrom4([4]byte(packet[16:20]))
+		l4 = packet[headerLen:]
+	case 0x86DD: // IPv6 (extension headers are not followed)
+		if len(packet) < 40 {
+			return
+		}
+		proto = packet[6]
+		src = netip.AddrFrom16([16]byte(packet[8:24]))
+		dst = netip.AddrFrom16([16]byte(packet[24:40]))
+		l4 = packet[40:]
+	default:
+		return
+	}
+	if (proto != protoTCP && proto != protoUDP) || len(l4) < 8 {
+		return
+	}
+	srcPort := binary.BigEndian.Uint16(l4[0:2])
+	dstPort := binary.BigEndian.Uint16(l4[2:4])
+	var flags byte
+	var payload []byte
+	if proto == protoTCP {
+		if len(l4) < 20 {
+			return
+		}
+		flags = l4[13]
+		if offset := int(l4[12]>>4) * 4; offset >= 20 && offset <= len(l4) {
+			payload = l4[offset:]
+		}
+	} else {
+		payload = l4[8:]
+	}
+
+	key := flowKey{proto: proto}
+	if fromGuest {
+		key.guest = netip.AddrPortFrom(src, srcPort)
+		key.remote = netip.AddrPortFrom(dst, dstPort)
+	} else {
+		key.guest = netip.AddrPortFrom(dst, dstPort)
+		key.remote = netip.AddrPortFrom(src, srcPort)
+	}
+
+	now := time.Now()
+	l.mu.Lock()
+	defer l.mu.Unlock()
+	if l.closed {
+		return
+	}
+	flow := l.flows[key]
+	if flow == nil {
+		// Only connections the guest opens are logged
+		opens := proto == protoUDP || flags&(tcpSYN|tcpACK) == tcpSYN
+		if !fromGuest || !opens || !l.logs(key.remote.Addr()) {
+			return
+		}
+		if len(l.flows) >= connLogMaxFlows {
+			if !l.full {
+				logrus.WithField("flows", len(l.flows)).Warn("Too many open connections to log, skipping new ones")
+				l.full = true
+			}
+			return
+		}
+		flow = &connFlow{
+			start:   now,
+			blocked: l.filter != nil && !l.filter.allows(net.IP(key.remote.Addr().AsSlice())),
+		}
+		l.flows[key] = flow
+	}
+	flow.last = now
+
+	size := uint64(len(packet))
+	if fromGuest {
+		flow.sent += size
+		if len(payload) > 0 && !flow.sniChecked && proto == protoTCP {
+			flow.sniChecked = true
+			flow.hostname = parseSNI(payload)
+		}
+	} else {
+		flow.received += size
+		if flags&(tcpSYN|tcpACK) == tcpSYN|tcpACK {
+			flow.established = true
+		}
+	}
+
+	if proto == protoTCP {
+		if flags&tcpFIN != 0 {
+			if fromGuest {
+				flow.guestFin = true
+			} else {
+				flow.remoteFin = true
+			}
+		}
+		if flags&tcpRST != 0 || (flow.guestFin && flow.remoteFin) {
+			l.finish(key, flow)
+		}
+	}
+}
+
+// logs reports whether connections to dst are logged: everything but the
+// gateway's own services (DNS, DHCP) and broadcast or multicast traffic.
+func (l *connLogger) logs(dst netip.Addr) bool {
+	return dst != l.gateway && !dst.IsMulticast() && dst != netip.AddrFrom4([4]byte{255, 255, 255, 255})
+}
+
+// parseSNI returns the server name of a TLS ClientHello, or "" when payload
+// is not one (or the name is in a later segment).
+func parseSNI(payload []byte) string {
+	// Record header: type 22 (handshake), version, length
+	if len(payload) < 5 || payload[0] != 0x16 {
+		return ""
+	}
+	hello := payload[5:]
+	// Handshake header: type 1 (ClientHello), 3-byte length
+	if len(hello) < 4 || hello[0] != 0x01 {
+		return ""
+	}
+	body := hello[4:]
+	// Client version and random
+	pos := 2 + 32
+	skip := func(lenBytes int) bool {
+		if pos+lenBytes > len(body) {
+			return false
+		}
+		n := 0
+		for _, b := range body[pos : pos+lenBytes] {
+			n = n<<8 | int(b)
+		}
+		pos += lenBytes + n
+		return pos <= len(body)
+	}
+	// Session ID, cipher suites, compression methods
+	if !skip(1) || !skip(2) || !skip(1) {
+		return ""
+	}
+	if pos+2 > len(body) {
+		return ""
+	}
+	pos += 2 // extensions length
+	for pos+4 <= len(body) {
+		extType := binary.BigEndian.Uint16(body[pos : pos+2])
+		extLen := int(binary.BigEndian.Uint16(body[pos+2 : pos+4]))
+		pos += 4
+		if pos+extLen > len(body) {
+			return ""
+		}
+		if extType == 0 { // server_name
+			ext := body[pos : pos+extLen]
+			// List length, then entries of type, length, name
+			if len(ext) < 5 || ext[2] != 0 {
+				return ""
+			}
+			nameLen := int(binary.BigEndian.Uint16(ext[3:5]))
+			if 5+nameLen > len(ext) {
+				return ""
+			}
+			return string(ext[5 : 5+nameLen])
+		}
+		pos += extLen
+	}
+	return ""
+}
+
+// frameSplitter reassembles the frames of the Qemu stream protocol, where
+// every frame is preceded by a 4-byte big-endian length.
+type frameSplitter struct {
+	buf  []byte
+	lost bool
+}
+
+func (s *frameSplitter) feed(p []byte, frame func([]byte)) {
+	if s.lost {
+		return
+	}
+	s.buf = append(s.buf, p...)
+	for len(s.buf) >= 4 {
+		size := int(binary.BigEndian.Uint32(s.buf[:4]))
+		if size > maxFrameSize {
+			// Lost track of the framing; stop watching rather than misparse
+			s.buf = nil
+			s.lost = true
+			return
+		}
+		if len(s.buf) < 4+size {
+			return
+		}
+		frame(s.buf[4 : 4+size])
+		s.buf = s.buf[4+size:]
+	}
+}
+
+// loggedStreamConn watches the frames of the Qemu stream protocol.
+type loggedStreamConn struct {
+	net.Conn
+	fromGuest      frameSplitter
+	toGuest        frameSplitter
+	toGuestMu      sync.Mutex
+	fromGuestFrame func([]byte)
+	toGuestFrame   func([]byte)
+}
+
+func newLoggedStreamConn(conn net.Conn, logger *connLogger) *loggedStreamConn {
+	return &loggedStreamConn{
+		Conn:           conn,
+		fromGuestFrame: func(frame []byte) { logger.observe(frame, true) },
+		toGuestFrame:   func(frame []byte) { logger.observe(frame, false) },
+	}
+}
+
+func (c *loggedStreamConn) Read(p []byte) (int, error) {
+	n, err := c.Conn.Read(p)
+	c.fromGuest.feed(p[:n], c.fromGuestFrame)
+	return n, err
+}
+
+func (c *loggedStreamConn) Write(p []byte) (int, error) {
+	n, err := c.Conn.Write(p)
+	c.toGuestMu.Lock()
+	c.toGuest.feed(p[:n], c.toGuestFrame)
+	c.toGuestMu.Unlock()
+	return n, err
+}
+
+// loggedDatagramConn watches the VFKit protocol, one frame per datagram.
+type loggedDatagramConn struct {
+	net.Conn
+	logger *connLogger
+}
+
+func (c *loggedDatagramConn) Read(p []byte) (int, error) {
+	n, err := c.Conn.Read(p)
+	if n > 0 {
+		c.logger.observe(p[:n], true)
+	}
+	return n, err
+}
+
+func (c *loggedDatagramConn) Write(p []byte) (int, error) {
+	n, err := c.Conn.Write(p)
+	if n > 0 {
+		c.logger.observe(p[:n], false)
+	}
+	return n, err
+}
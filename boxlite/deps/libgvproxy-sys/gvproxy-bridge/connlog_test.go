@@ -0,0 +1,43 @@
+package main
+
+import (
+	"os"
+	"path/filepath"
+	"strconv"
+	"testing"
+)
+
+func TestConnLogRotatesBySize(t *testing.T) {
+	path := filepath.Join(t.TempDir(), "connections.log")
+	logger, err := newConnLogger(path, testGateway, nil)
+	if err != nil {
+		t.Fatalf("newConnLogger: %v", err)
+	}
+	defer logger.out.Close()
+
+	for i := 0; i < connLogRotatedFiles+2; i++ {
+		// Every line finds the log full
+		logger.written = connLogMaxBytes
+		if err := logger.write([]byte(strconv.Itoa(i) + "\n")); err != nil {
+			t.Fatalf("write %d: %v", i, err)
+		}
+	}
+
+	newest := connLogRotatedFiles + 1
+	for n := 0; n <= connLogRotatedFiles; n++ {
+		name := path
+		if n > 0 {
+			name = logger.rotated(n)
+		}
+		data, err := os.ReadFile(name)
+		if err != nil {
+			t.Fatalf("read %s: %v", name, err)
+		}
+		if want := strconv.Itoa(newest-n) + "\n"; string(data) != want {
+			t.Errorf("%s holds %q, want %q", name, data, want)
+		}
+	}
+	if _, err := os.Stat(logger.rotated(connLogRotatedFiles + 1)); !os.IsNotExist(err) {
+		t.Errorf("kept more than %d rotated logs", connLogRotatedFiles)
+	}
+}
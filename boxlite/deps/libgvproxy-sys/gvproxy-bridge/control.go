@@ -0,0 +1,52 @@
+package main
+
+import (
+	"context"
+	"errors"
+	"net"
+	"net/http"
+	"os"
+	"time"
+
+	"github.com/containers/gvisor-tap-vsock/pkg/virtualnetwork"
+	logrus "github.com/sirupsen/logrus"
+)
+
+// serveControl exposes the gvisor-tap-vsock services API on a Unix socket so
+// the runtime can change port forwards while the VM runs:
+//
+//	GET  /forwarder/all       list forwards
+//	POST /forwarder/expose    {"local": "0.0.0.0:8080", "remote": "192.168.127.2:80", "protocol": "tcp"}
+//	POST /forwarder/unexpose  {"local": "0.0.0.0:8080", "protocol": "tcp"}
+//
+// The socket is removed when ctx is cancelled.
+func serveControl(ctx context.Context, id int64, vn *virtualnetwork.VirtualNetwork, path string) {
+	if err := os.Remove(path); err != nil && !os.IsNotExist(err) {
+		logrus.WithFields(logrus.Fields{"error": err, "path": path}).Warn("Failed to remove existing control socket")
+	}
+
+	listener, err := net.Listen("unix", path)
+	if err != nil {
+		logrus.WithFields(logrus.Fields{"error": err, "path": path, "id": id}).Error("Failed to create control socket")
+		return
+	}
+	// Only the box owner may reconfigure forwards
+	if err := os.Chmod(path, 0o600); err != nil {
+		logrus.WithFields(logrus.Fields{"error": err, "path": path}).Warn("Failed to restrict control socket permissions")
+	}
+
+	server := &http.Server{
+		Handler:           vn.ServicesMux(),
+		ReadHeaderTimeout: 5 * time.Second,
+	}
+	go func() {
+		<-ctx.Done()
+		server.Close()
+		os.Remove(path)
+	}()
+
+	logrus.WithFields(logrus.Fields{"id": id, "path": path}).Info("Port forwarder control socket ready")
+	if err := server.Serve(listener); err != nil && !errors.Is(err, http.ErrServerClosed) {
+		logrus.WithFields(logrus.Fields{"error": err, "id": id}).Error("Control socket server failed")
+	}
+}
@@ -23,6 +23,7 @@ import (
 	"path/filepath"
 	"runtime"
 	"runtime/debug"
+	"strconv"
 	"sync"
 	"time"
 	"unsafe"
@@ -163,6 +164,7 @@ func gvproxy_set_log_callback(callback unsafe.Pointer) {
 type PortMapping struct {
 	HostPort  uint16 `json:"host_port"`
 	GuestPort uint16 `json:"guest_port"`
+	HostIP    string `json:"host_ip,omitempty"` // empty = all interfaces
 }
 
 // DNSZone represents a local DNS zone configuration
@@ -186,6 +188,18 @@ type GvproxyConfig struct {
 	DNSSearchDomains []string      `json:"dns_search_domains"`
 	Debug            bool          `json:"debug"`
 	CaptureFile      *string       `json:"capture_file,omitempty"`
+	// EgressLimitBytesPerSec caps traffic sent by the guest (0 = unlimited)
+	EgressLimitBytesPerSec uint64 `json:"egress_limit_bytes_per_sec,omitempty"`
+	// EgressPolicy filters traffic sent by the guest (nil = allow all)
+	EgressPolicy *EgressPolicy `json:"egress_policy,omitempty"`
+	// ControlSocket serves the port forwarder API when set (see control.go)
+	ControlSocket string `json:"control_socket,omitempty"`
+	// SocketPath is where the VM connects (empty = gvproxy-<id>.sock in TMPDIR)
+	SocketPath string `json:"socket_path,omitempty"`
+	// HostLoopbackIP is NATed to the host's 127.0.0.1 (empty = unreachable)
+	HostLoopbackIP string `json:"host_loopback_ip,omitempty"`
+	// ConnectionLog receives a JSON line per guest connection when set (see connlog.go)
+	ConnectionLog string `json:"connection_log,omitempty"`
 }
 
 // GvproxyInstance tracks a running gvisor-tap-vsock instance
@@ -221,8 +235,12 @@ func gvproxy_create(configJSON *C.char) C.longlong {
 	nextID++
 	instancesMu.Unlock()
 
-	// Create Unix socket path
-	socketPath := filepath.Join(os.TempDir(), fmt.Sprintf("gvproxy-%d.sock", id))
+	// Create Unix socket path. IDs restart at 1 in every process, so callers
+	// sharing a TMPDIR pass their own path.
+	socketPath := config.SocketPath
+	if socketPath == "" {
+		socketPath = filepath.Join(os.TempDir(), fmt.Sprintf("gvproxy-%d.sock", id))
+	}
 
 	// Remove existing socket if present
 	if err := os.Remove(socketPath); err != nil && !os.IsNotExist(err) {
@@ -267,6 +285,12 @@ func gvproxy_create(configJSON *C.char) C.longlong {
 		DNSSearchDomains:  config.DNSSearchDomains,
 	}
 
+	// Answer for the host loopback address and NAT it to the host's 127.0.0.1
+	if config.HostLoopbackIP != "" {
+		tapConfig.NAT[config.HostLoopbackIP] = "127.0.0.1"
+		tapConfig.GatewayVirtualIPs = append(tapConfig.GatewayVirtualIPs, config.HostLoopbackIP)
+	}
+
 	// Set CaptureFile if provided
 	if config.CaptureFile != nil && *config.CaptureFile != "" {
 		tapConfig.CaptureFile = *config.CaptureFile
@@ -274,17 +298,49 @@ func gvproxy_create(configJSON *C.char) C.longlong {
 	}
 
 	// Add port forwards from config
-	// Format: "0.0.0.0:PORT" for TCP (default), or "udp:0.0.0.0:PORT" for UDP
+	// Format: "IP:PORT" for TCP (default), or "udp:IP:PORT" for UDP; IP defaults to 0.0.0.0
 	// Do NOT use "tcp://" prefix - it causes "too many colons in address" error
 	// Forward to guest's DHCP IP, not localhost
 	// Containers bind to 0.0.0.0 inside the guest, accessible via guest IP
 	for _, pm := range config.PortMappings {
-		forwardKey := fmt.Sprintf("0.0.0.0:%d", pm.HostPort)
+		hostIP := pm.HostIP
+		if hostIP == "" {
+			hostIP = "0.0.0.0"
+		}
+		forwardKey := net.JoinHostPort(hostIP, strconv.Itoa(int(pm.HostPort)))
 		forwardVal := fmt.Sprintf("%s:%d", config.GuestIP, pm.GuestPort)
 		tapConfig.Forwards[forwardKey] = forwardVal
 		logrus.WithFields(logrus.Fields{"host": forwardKey, "guest": forwardVal}).Info("Added TCP port forward")
 	}
 
+	// Build egress filter before any socket is created so bad config fails early
+	var filter *egressFilter
+	if config.EgressPolicy != nil {
+		var err error
+		filter, err = newEgressFilter(config.EgressPolicy, config.Subnet, tapConfig.NAT)
+		if err != nil {
+			logrus.WithError(err).Error("Failed to build egress filter")
+			return -1
+		}
+		logrus.WithFields(logrus.Fields{
+			"default_allow": config.EgressPolicy.DefaultAllow,
+			"allow_rules":   len(filter.allow),
+			"deny_rules":    len(filter.deny),
+		}).Info("Egress network policy enabled")
+	}
+
+	// Open the connection log up front too; it sees frames before the filter
+	var connLog *connLogger
+	if config.ConnectionLog != "" {
+		var err error
+		connLog, err = newConnLogger(config.ConnectionLog, config.GatewayIP, filter)
+		if err != nil {
+			logrus.WithFields(logrus.Fields{"error": err, "path": config.ConnectionLog}).Error("Failed to open connection log")
+			return -1
+		}
+		logrus.WithField("path", config.ConnectionLog).Info("Connection logging enabled")
+	}
+
 	// Platform-specific socket creation
 	var conn net.Conn
 	var listener net.Listener
@@ -325,6 +381,10 @@ func gvproxy_create(configJSON *C.char) C.longlong {
 	instances[id] = instance
 	instancesMu.Unlock()
 
+	if connLog != nil {
+		go connLog.run(ctx)
+	}
+
 	// Start runtime metrics monitoring goroutine
 	go func() {
 		ticker := time.NewTicker(30 * time.Second)
@@ -364,6 +424,10 @@ func gvproxy_create(configJSON *C.char) C.longlong {
 		instance.vn = vn
 		instance.vnMu.Unlock()
 
+		if config.ControlSocket != "" {
+			go serveControl(ctx, id, vn, config.ControlSocket)
+		}
+
 		// Platform-specific packet handling
 		if runtime.GOOS == "darwin" {
 			// macOS: Handle VFKit datagram packets
@@ -383,6 +447,17 @@ func gvproxy_create(configJSON *C.char) C.longlong {
 
 				logrus.WithFields(logrus.Fields{"id": id, "remote": wrappedConn.RemoteAddr().String()}).Info("VFKit connection accepted")
 
+				if connLog != nil {
+					wrappedConn = &loggedDatagramConn{Conn: wrappedConn, logger: connLog}
+				}
+				if filter != nil {
+					wrappedConn = &filteredDatagramConn{Conn: wrappedConn, filter: filter}
+				}
+				if config.EgressLimitBytesPerSec > 0 {
+					wrappedConn = newRateLimitedConn(ctx, wrappedConn, config.EgressLimitBytesPerSec)
+					logrus.WithFields(logrus.Fields{"id": id, "bytes_per_sec": config.EgressLimitBytesPerSec}).Info("Egress rate limit enabled")
+				}
+
 				// Handle the VFKit protocol with the wrapped connection
 				if err := vn.AcceptVfkit(ctx, wrappedConn); err != nil {
 					if ctx.Err() == nil {
@@ -409,6 +484,17 @@ func gvproxy_create(configJSON *C.char) C.longlong {
 				// Close listener after first connection (one VM per gvproxy instance)
 				listener.Close()
 
+				if connLog != nil {
+					acceptedConn = newLoggedStreamConn(acceptedConn, connLog)
+				}
+				if filter != nil {
+					acceptedConn = &filteredStreamConn{Conn: acceptedConn, filter: filter}
+				}
+				if config.EgressLimitBytesPerSec > 0 {
+					acceptedConn = newRateLimitedConn(ctx, acceptedConn, config.EgressLimitBytesPerSec)
+					logrus.WithFields(logrus.Fields{"id": id, "bytes_per_sec": config.EgressLimitBytesPerSec}).Info("Egress rate limit enabled")
+				}
+
 				// Handle the Qemu protocol
 				if err := vn.AcceptQemu(ctx, acceptedConn); err != nil {
 					if ctx.Err() == nil {
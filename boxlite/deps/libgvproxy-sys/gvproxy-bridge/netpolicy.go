@@ -0,0 +1,201 @@
+package main
+
+import (
+	"encoding/binary"
+	"fmt"
+	"io"
+	"net"
+	"sync/atomic"
+
+	logrus "github.com/sirupsen/logrus"
+)
+
+// EgressPolicy matches the Rust NetworkPolicy structure (must stay in sync!)
+type EgressPolicy struct {
+	DefaultAllow bool     `json:"default_allow"`
+	Allow        []string `json:"allow"`
+	Deny         []string `json:"deny"`
+}
+
+// egressFilter decides whether a frame sent by the guest may leave the
+// virtual network. Traffic within the virtual subnet (gateway, DNS, DHCP,
+// port-forward replies) is always allowed; only IP destinations outside it
+// are matched against the rules. Virtual IPs that gvproxy NATs to a host
+// address (the host loopback address) are matched as that host address,
+// so a deny-all policy also keeps the guest off the host's loopback
+// services. Dropped TCP connections time out rather than being refused.
+type egressFilter struct {
+	subnet       *net.IPNet
+	nat          map[string]net.IP
+	defaultAllow bool
+	allow        []*net.IPNet
+	deny         []*net.IPNet
+	dropped      atomic.Uint64
+}
+
+// newEgressFilter builds the filter for a policy. nat maps virtual IPs in
+// the subnet to the host addresses gvproxy translates them to.
+func newEgressFilter(policy *EgressPolicy, subnet string, nat map[string]string) (*egressFilter, error) {
+	_, subnetNet, err := net.ParseCIDR(subnet)
+	if err != nil {
+		return nil, err
+	}
+	natIPs := make(map[string]net.IP, len(nat))
+	for virtual, target := range nat {
+		virtualIP, targetIP := net.ParseIP(virtual), net.ParseIP(target)
+		if virtualIP == nil || targetIP == nil {
+			return nil, fmt.Errorf("invalid NAT entry %s -> %s", virtual, target)
+		}
+		natIPs[virtualIP.String()] = targetIP
+	}
+	return &egressFilter{
+		subnet:       subnetNet,
+		nat:          natIPs,
+		defaultAllow: policy.DefaultAllow,
+		allow:        resolveRules(policy.Allow),
+		deny:         resolveRules(policy.Deny),
+	}, nil
+}
+
+// resolveRules turns IP, CIDR and hostname rules into networks.
+// Hostnames are resolved once; unresolvable hostnames are skipped.
+func resolveRules(rules []string) []*net.IPNet {
+	var nets []*net.IPNet
+	for _, rule := range rules {
+		if _, ipNet, err := net.ParseCIDR(rule); err == nil {
+			nets = append(nets, ipNet)
+			continue
+		}
+		ips := []net.IP{net.ParseIP(rule)}
+		if ips[0] == nil {
+			resolved, err := net.LookupIP(rule)
+			if err != nil {
+				logrus.WithFields(logrus.Fields{"rule": rule, "error": err}).Warn("Skipping unresolvable network policy rule")
+				continue
+			}
+			ips = resolved
+		}
+		for _, ip := range ips {
+			nets = append(nets, hostNet(ip))
+		}
+	}
+	return nets
+}
+
+func hostNet(ip net.IP) *net.IPNet {
+	if v4 := ip.To4(); v4 != nil {
+		return &net.IPNet{IP: v4, Mask: net.CIDRMask(32, 32)}
+	}
+	return &net.IPNet{IP: ip, Mask: net.CIDRMask(128, 128)}
+}
+
+func (f *egressFilter) allows(dst net.IP) bool {
+	if target, ok := f.nat[dst.String()]; ok {
+		return f.rulesAllow(target)
+	}
+	if f.subnet.Contains(dst) || dst.Equal(net.IPv4bcast) || dst.IsMulticast() {
+		return true
+	}
+	// IPv6 link-local is needed for neighbor discovery. IPv4 link-local is
+	// not exempt: it includes cloud metadata endpoints (169.254.169.254).
+	if dst.To4() == nil && dst.IsLinkLocalUnicast() {
+		return true
+	}
+	return f.rulesAllow(dst)
+}
+
+// rulesAllow matches dst against the deny, then allow rules.
+func (f *egressFilter) rulesAllow(dst net.IP) bool {
+	for _, n := range f.deny {
+		if n.Contains(dst) {
+			return false
+		}
+	}
+	for _, n := range f.allow {
+		if n.Contains(dst) {
+			return true
+		}
+	}
+	return f.defaultAllow
+}
+
+// frameAllowed inspects an Ethernet frame. Non-IP frames (ARP) pass.
+func (f *egressFilter) frameAllowed(frame []byte) bool {
+	if len(frame) < 14 {
+		return true
+	}
+	payload := frame[14:]
+	switch binary.BigEndian.Uint16(frame[12:14]) {
+	case 0x0800: // IPv4
+		if len(payload) < 20 {
+			return false
+		}
+		return f.allows(net.IP(payload[16:20]))
+	case 0x86DD: // IPv6
+		if len(payload) < 40 {
+			return false
+		}
+		return f.allows(net.IP(payload[24:40]))
+	default:
+		return true
+	}
+}
+
+func (f *egressFilter) drop() {
+	if f.dropped.Add(1)%100 == 1 {
+		logrus.WithField("dropped", f.dropped.Load()).Debug("Dropped egress frames by network policy")
+	}
+}
+
+// maxFrameSize bounds a single frame on the Qemu stream (jumbo MTU plus headers).
+const maxFrameSize = 65536
+
+// filteredStreamConn filters the Qemu stream protocol, where every frame
+// is preceded by a 4-byte big-endian length.
+type filteredStreamConn struct {
+	net.Conn
+	filter  *egressFilter
+	pending []byte
+}
+
+func (c *filteredStreamConn) Read(p []byte) (int, error) {
+	for len(c.pending) == 0 {
+		var header [4]byte
+		if _, err := io.ReadFull(c.Conn, header[:]); err != nil {
+			return 0, err
+		}
+		size := binary.BigEndian.Uint32(header[:])
+		if size > maxFrameSize {
+			return 0, fmt.Errorf("frame of %d bytes exceeds %d", size, maxFrameSize)
+		}
+		frame := make([]byte, 4+size)
+		copy(frame, header[:])
+		if _, err := io.ReadFull(c.Conn, frame[4:]); err != nil {
+			return 0, err
+		}
+		if c.filter.frameAllowed(frame[4:]) {
+			c.pending = frame
+		} else {
+			c.filter.drop()
+		}
+	}
+	n := copy(p, c.pending)
+	c.pending = c.pending[n:]
+	return n, nil
+}
+
+// filteredDatagramConn filters the VFKit protocol, one frame per datagram.
+type filteredDatagramConn struct {
+	net.Conn
+	filter *egressFilter
+}
+
+func (c *filteredDatagramConn) Read(p []byte) (int, error) {
+	for {
+		n, err := c.Conn.Read(p)
+		if err != nil || c.filter.frameAllowed(p[:n]) {
+			return n, err
+		}
+		c.filter.drop()
+	}
+}
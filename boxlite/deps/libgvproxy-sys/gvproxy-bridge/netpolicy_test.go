@@ -0,0 +1,63 @@
+package main
+
+import (
+	"net"
+	"testing"
+)
+
+const (
+	testSubnet     = "192.168.127.0/24"
+	testGateway    = "192.168.127.1"
+	testLoopbackIP = "192.168.127.254"
+)
+
+func newTestFilter(t *testing.T, policy *EgressPolicy) *egressFilter {
+	t.Helper()
+	filter, err := newEgressFilter(policy, testSubnet, map[string]string{testLoopbackIP: "127.0.0.1"})
+	if err != nil {
+		t.Fatalf("newEgressFilter: %v", err)
+	}
+	return filter
+}
+
+func TestDenyAllBlocksHostLoopback(t *testing.T) {
+	filter := newTestFilter(t, &EgressPolicy{DefaultAllow: false})
+
+	if filter.allows(net.ParseIP(testLoopbackIP)) {
+		t.Errorf("deny-all policy allowed the host loopback address")
+	}
+	if filter.allows(net.ParseIP("1.1.1.1")) {
+		t.Errorf("deny-all policy allowed an external address")
+	}
+	// The gateway serves DNS and DHCP and is never NATed to the host
+	if !filter.allows(net.ParseIP(testGateway)) {
+		t.Errorf("deny-all policy blocked the gateway")
+	}
+}
+
+func TestHostLoopbackFollowsRulesForTarget(t *testing.T) {
+	allowed := newTestFilter(t, &EgressPolicy{DefaultAllow: false, Allow: []string{"127.0.0.1"}})
+	if !allowed.allows(net.ParseIP(testLoopbackIP)) {
+		t.Errorf("policy allowing 127.0.0.1 blocked the host loopback address")
+	}
+
+	denied := newTestFilter(t, &EgressPolicy{DefaultAllow: true, Deny: []string{"127.0.0.0/8"}})
+	if denied.allows(net.ParseIP(testLoopbackIP)) {
+		t.Errorf("policy denying 127.0.0.0/8 allowed the host loopback address")
+	}
+	if !denied.allows(net.ParseIP("1.1.1.1")) {
+		t.Errorf("allow-by-default policy blocked an external address")
+	}
+
+	open := newTestFilter(t, &EgressPolicy{DefaultAllow: true})
+	if !open.allows(net.ParseIP(testLoopbackIP)) {
+		t.Errorf("allow-all policy blocked the host loopback address")
+	}
+}
+
+func TestInvalidNATEntryFails(t *testing.T) {
+	_, err := newEgressFilter(&EgressPolicy{}, testSubnet, map[string]string{"not-an-ip": "127.0.0.1"})
+	if err == nil {
+		t.Errorf("expected an error for an invalid NAT entry")
+	}
+}
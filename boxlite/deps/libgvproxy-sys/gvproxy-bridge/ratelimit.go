@@ -0,0 +1,49 @@
+package main
+
+import (
+	"context"
+	"net"
+
+	"golang.org/x/time/rate"
+)
+
+// minEgressBurst is the smallest token bucket used for egress shaping.
+// It must hold at least one full frame, otherwise WaitN could never succeed.
+const minEgressBurst = 64 * 1024
+
+// rateLimitedConn throttles reads from the VM connection, i.e. the frames the
+// guest sends into the virtual network (egress). Writes towards the guest are
+// not limited.
+type rateLimitedConn struct {
+	net.Conn
+	ctx     context.Context
+	limiter *rate.Limiter
+}
+
+func newRateLimitedConn(ctx context.Context, conn net.Conn, bytesPerSec uint64) net.Conn {
+	burst := int(bytesPerSec)
+	if burst < minEgressBurst {
+		burst = minEgressBurst
+	}
+	return &rateLimitedConn{
+		Conn:    conn,
+		ctx:     ctx,
+		limiter: rate.NewLimiter(rate.Limit(bytesPerSec), burst),
+	}
+}
+
+func (c *rateLimitedConn) Read(p []byte) (int, error) {
+	n, err := c.Conn.Read(p)
+	for remaining := n; remaining > 0; {
+		chunk := remaining
+		if burst := c.limiter.Burst(); chunk > burst {
+			chunk = burst
+		}
+		if waitErr := c.limiter.WaitN(c.ctx, chunk); waitErr != nil {
+			// Context cancelled: the instance is shutting down.
+			break
+		}
+		remaining -= chunk
+	}
+	return n, err
+}
@@ -11,8 +11,12 @@
 //! The shim creates the network backend (gvproxy) from network_config if present.
 //! This ensures networking survives detach operations - the gvproxy lives in the
 //! shim subprocess, not the main boxlite process.
+//!
+//! With a shared network daemon configured, the shim asks the daemon to host
+//! the backend instead and falls back to its own if the daemon is unavailable.
+//! `--network-daemon HOME` runs this binary as that daemon.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::thread;
 use std::time::Duration;
 
@@ -27,7 +31,10 @@ use clap::Parser;
 use tracing_subscriber::{EnvFilter, fmt, prelude::*};
 
 #[cfg(feature = "gvproxy-backend")]
-use boxlite::net::{ConnectionType, NetworkBackendEndpoint, gvproxy::GvproxyInstance};
+use boxlite::net::{
+    ConnectionType, NetworkBackendEndpoint,
+    gvproxy::{GvproxyConfig, GvproxyInstance, shared},
+};
 
 /// Universal Box runner binary - subprocess that executes isolated Boxes
 #[derive(Parser, Debug)]
@@ -40,29 +47,34 @@ struct ShimArgs {
     /// Engine type to use for Box execution
     ///
     /// Supported engines: libkrun, firecracker
-    #[arg(long)]
-    engine: VmmKind,
+    #[arg(long, required_unless_present = "network_daemon")]
+    engine: Option<VmmKind>,
 
     /// Box configuration as JSON string
     ///
     /// This contains the full InstanceSpec including rootfs path, volumes,
     /// networking, guest entrypoint, and other runtime configuration.
-    #[arg(long)]
-    config: String,
+    #[arg(long, required_unless_present = "network_daemon")]
+    config: Option<String>,
+
+    /// Serve the shared network daemon of this runtime home instead of
+    /// running a Box
+    #[arg(long, value_name = "HOME_DIR", conflicts_with_all = ["engine", "config"])]
+    network_daemon: Option<PathBuf>,
 }
 
 /// Initialize tracing with file logging.
 ///
-/// Logs are written to {home_dir}/logs/boxlite-shim.log with daily rotation.
+/// Logs are written to {home_dir}/logs/{file_name} with daily rotation.
 /// Returns WorkerGuard that must be kept alive to maintain the background writer thread.
-fn init_logging(home_dir: &Path) -> tracing_appender::non_blocking::WorkerGuard {
+fn init_logging(home_dir: &Path, file_name: &str) -> tracing_appender::non_blocking::WorkerGuard {
     let logs_dir = home_dir.join(layout::dirs::LOGS_DIR);
 
     // Create logs directory if it doesn't exist
     std::fs::create_dir_all(&logs_dir).expect("Failed to create logs directory");
 
     // Set up file appender with daily rotation
-    let file_appender = tracing_appender::rolling::daily(logs_dir, "boxlite-shim.log");
+    let file_appender = tracing_appender::rolling::daily(logs_dir, file_name);
 
     // Create non-blocking writer
     let (non_blocking, guard) = tracing_appender::non_blocking(file_appender);
@@ -83,17 +95,25 @@ fn main() -> BoxliteResult<()> {
     // VmmKind parsed via FromStr trait automatically
     let args = ShimArgs::parse();
 
+    if let Some(home_dir) = args.network_daemon {
+        return run_network_daemon(&home_dir);
+    }
+    // Both present unless --network-daemon (enforced by clap)
+    let (Some(engine_kind), Some(config_json)) = (args.engine, args.config) else {
+        unreachable!("clap requires --engine and --config");
+    };
+
     // Parse InstanceSpec from JSON
-    let mut config: InstanceSpec = serde_json::from_str(&args.config).map_err(|e| {
+    let mut config: InstanceSpec = serde_json::from_str(&config_json).map_err(|e| {
         boxlite_shared::errors::BoxliteError::Engine(format!("Failed to parse config JSON: {}", e))
     })?;
 
     // Initialize logging using home_dir from config
     // Keep guard alive until end of main to ensure logs are written
-    let _log_guard = init_logging(&config.home_dir);
+    let _log_guard = init_logging(&config.home_dir, "boxlite-shim.log");
 
     tracing::info!(
-        engine = ?args.engine,
+        engine = ?engine_kind,
         box_id = %config.box_id,
         "Box runner starting"
     );
@@ -166,15 +186,63 @@ fn main() -> BoxliteResult<()> {
     if let Some(ref net_config) = config.network_config {
         tracing::info!(
             port_mappings = ?net_config.port_mappings,
+            egress_limit_mbps = ?net_config.egress_limit_mbps,
+            egress_policy = ?net_config.egress_policy,
+            port_host_ips = ?net_config.port_host_ips,
             "Creating network backend (gvproxy) from config"
         );
 
-        // Create gvproxy instance
-        let gvproxy = GvproxyInstance::new(&net_config.port_mappings)?;
-        let socket_path = gvproxy.get_socket_path()?;
+        // Create gvproxy instance, in the shared daemon if there is one
+        let gvproxy_config = GvproxyConfig::from_backend_config(net_config);
+        let network_lost = config
+            .box_dir
+            .join(boxlite::runtime::constants::filenames::NETWORK_LOST);
+        // Left by a previous run of this box, whose network is gone anyway
+        let _ = std::fs::remove_file(&network_lost);
+        let lease = net_config.shared_daemon.as_deref().and_then(|daemon| {
+            shared::attach(daemon, config.box_id.as_str(), &gvproxy_config)
+                .inspect_err(|e| {
+                    tracing::warn!(
+                        error = %e,
+                        "Shared network daemon unavailable, creating a per-box backend"
+                    )
+                })
+                .ok()
+        });
+        let shared = lease.is_some();
+        let socket_path = match lease {
+            Some(lease) => {
+                let socket_path = lease.socket_path().to_path_buf();
+                // Held for the VM lifetime like the instance below: closing the
+                // connection tells the daemon to destroy the network, which
+                // happens when this process exits. If the daemon dies first,
+                // the marker tells the heartbeat monitor the box is offline.
+                lease.watch(move || {
+                    tracing::error!(
+                        event = "box_network_lost",
+                        "Shared network daemon exited, the box has no network until restarted"
+                    );
+                    if let Err(e) = std::fs::write(&network_lost, b"") {
+                        tracing::warn!(error = %e, "Failed to write network lost marker");
+                    }
+                })?;
+                socket_path
+            }
+            None => {
+                let gvproxy = GvproxyInstance::from_config(&gvproxy_config)?;
+                let socket_path = gvproxy.get_socket_path()?;
+                // Leak the gvproxy instance to keep it alive for VM lifetime.
+                // This is intentional - the VM needs networking for its entire life,
+                // and OS cleanup handles resources when process exits.
+                let _gvproxy_leaked = Box::leak(Box::new(gvproxy));
+                tracing::debug!("Leaked gvproxy instance for VM lifetime");
+                socket_path
+            }
+        };
 
         tracing::info!(
             socket_path = ?socket_path,
+            shared,
             "Network backend created"
         );
 
@@ -188,20 +256,12 @@ fn main() -> BoxliteResult<()> {
             ConnectionType::UnixStream
         };
 
-        // Use GUEST_MAC constant - must match DHCP static lease in gvproxy config
-        use boxlite::net::constants::GUEST_MAC;
-
+        // Must match the DHCP static lease in the gvproxy config
         config.network_backend_endpoint = Some(NetworkBackendEndpoint::UnixSocket {
             path: socket_path,
             connection_type,
-            mac_address: GUEST_MAC,
+            mac_address: net_config.guest_mac(),
         });
-
-        // Leak the gvproxy instance to keep it alive for VM lifetime.
-        // This is intentional - the VM needs networking for its entire life,
-        // and OS cleanup handles resources when process exits.
-        let _gvproxy_leaked = Box::leak(Box::new(gvproxy));
-        tracing::debug!("Leaked gvproxy instance for VM lifetime");
     }
 
     // Save detach/parent_pid/transport before config is moved into engine.create()
@@ -214,7 +274,7 @@ fn main() -> BoxliteResult<()> {
 
     // Create engine using inventory pattern (no match statement needed!)
     // Engines auto-register themselves at compile time
-    let mut engine = vmm::create_engine(args.engine, options)?;
+    let mut engine = vmm::create_engine(engine_kind, options)?;
 
     tracing::info!("Engine created, creating Box instance");
 
@@ -255,6 +315,24 @@ fn main() -> BoxliteResult<()> {
     }
 }
 
+/// Serve the shared network daemon of `home_dir` until killed.
+fn run_network_daemon(home_dir: &Path) -> BoxliteResult<()> {
+    let _log_guard = init_logging(home_dir, "boxlite-netd.log");
+
+    #[cfg(feature = "gvproxy-backend")]
+    {
+        tracing::info!(home_dir = %home_dir.display(), "Network daemon starting");
+        shared::serve(&home_dir.join(shared::SOCKET_NAME))
+    }
+
+    #[cfg(not(feature = "gvproxy-backend"))]
+    {
+        Err(boxlite_shared::errors::BoxliteError::Unsupported(
+            "the shared network daemon requires the gvproxy backend".into(),
+        ))
+    }
+}
+
 /// Timeout for graceful shutdown before force kill (in seconds).
 const GRACEFUL_SHUTDOWN_TIMEOUT_SECS: u64 = 5;
 
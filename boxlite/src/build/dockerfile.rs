@@ -0,0 +1,325 @@
+//! Parser for the Dockerfile subset supported by `boxlite build`.
+//!
+//! Supported instructions: `FROM`, `RUN`, `COPY`, `ENV`, `WORKDIR`, `CMD`.
+//! Comments, blank lines and `\` line continuations are handled. Build
+//! arguments, variable expansion, multi-stage builds and instruction flags
+//! (e.g. `COPY --chown`, or BuildKit's `RUN --mount`) are rejected with an
+//! error naming the line.
+
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+
+/// A command in shell form (`RUN echo hi`) or exec form (`RUN ["echo", "hi"]`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum CommandForm {
+    Shell(String),
+    Exec(Vec<String>),
+}
+
+impl CommandForm {
+    /// Argument vector to execute (shell form runs through `/bin/sh -c`).
+    pub(crate) fn argv(&self) -> Vec<String> {
+        match self {
+            CommandForm::Shell(cmd) => vec!["/bin/sh".into(), "-c".into(), cmd.clone()],
+            CommandForm::Exec(argv) => argv.clone(),
+        }
+    }
+}
+
+/// One parsed Dockerfile instruction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Instruction {
+    From(String),
+    Run(CommandForm),
+    Copy { sources: Vec<String>, dest: String },
+    Env(Vec<(String, String)>),
+    Workdir(String),
+    Cmd(CommandForm),
+}
+
+impl Instruction {
+    /// Canonical text of the instruction, used in cache keys and history.
+    pub(crate) fn canonical(&self) -> String {
+        match self {
+            Instruction::From(image) => format!("FROM {}", image),
+            Instruction::Run(cmd) => format!("RUN {:?}", cmd.argv()),
+            Instruction::Copy { sources, dest } => format!("COPY {:?} {}", sources, dest),
+            Instruction::Env(vars) => format!("ENV {:?}", vars),
+            Instruction::Workdir(dir) => format!("WORKDIR {}", dir),
+            Instruction::Cmd(cmd) => format!("CMD {:?}", cmd.argv()),
+        }
+    }
+}
+
+/// Parse a Dockerfile into instructions.
+///
+/// The first instruction must be `FROM`, and only one `FROM` is allowed.
+pub(crate) fn parse(content: &str) -> BoxliteResult<Vec<Instruction>> {
+    let mut instructions = Vec::new();
+
+    for (line_no, line) in logical_lines(content) {
+        let err =
+            |msg: String| BoxliteError::Config(format!("Dockerfile line {}: {}", line_no, msg));
+
+        let (keyword, rest) = match line.split_once(char::is_whitespace) {
+            Some((k, r)) => (k, r.trim()),
+            None => (line.as_str(), ""),
+        };
+        let keyword = keyword.to_ascii_uppercase();
+        if rest.is_empty() {
+            return Err(err(format!("{} requires arguments", keyword)));
+        }
+        // CMD has no flags: a leading `--` there is part of the command
+        if rest.starts_with("--") && keyword != "CMD" {
+            return Err(err(format!("{} flags are not supported", keyword)));
+        }
+
+        let instruction = match keyword.as_str() {
+            "FROM" => {
+                let words = split_words(rest).map_err(err)?;
+                if words.len() != 1 {
+                    return Err(err(
+                        "multi-stage builds (FROM ... AS) are not supported".into()
+                    ));
+                }
+                Instruction::From(words[0].clone())
+            }
+            "RUN" => Instruction::Run(parse_command(rest).map_err(err)?),
+            "CMD" => Instruction::Cmd(parse_command(rest).map_err(err)?),
+            "COPY" => {
+                let mut words = if rest.starts_with('[') {
+                    serde_json::from_str::<Vec<String>>(rest)
+                        .map_err(|e| err(format!("invalid JSON array: {}", e)))?
+                } else {
+                    split_words(rest).map_err(err)?
+                };
+                if words.len() < 2 {
+                    return Err(err(
+                        "COPY requires at least one source and a destination".into()
+                    ));
+                }
+                let dest = words.pop().unwrap_or_default();
+                Instruction::Copy {
+                    sources: words,
+                    dest,
+                }
+            }
+            "ENV" => Instruction::Env(parse_env(rest).map_err(err)?),
+            "WORKDIR" => {
+                let words = split_words(rest).map_err(err)?;
+                if words.len() != 1 {
+                    return Err(err("WORKDIR takes exactly one path".into()));
+                }
+                Instruction::Workdir(words[0].clone())
+            }
+            other => return Err(err(format!("unsupported instruction {}", other))),
+        };
+
+        let is_from = matches!(instruction, Instruction::From(_));
+        if instructions.is_empty() != is_from {
+            return Err(err(if is_from {
+                "only one FROM is supported".into()
+            } else {
+                "the first instruction must be FROM".into()
+            }));
+        }
+        instructions.push(instruction);
+    }
+
+    if instructions.is_empty() {
+        return Err(BoxliteError::Config(
+            "Dockerfile has no instructions".into(),
+        ));
+    }
+    Ok(instructions)
+}
+
+/// Join continuation lines and drop comments, keeping the starting line number.
+fn logical_lines(content: &str) -> Vec<(usize, String)> {
+    let mut lines = Vec::new();
+    let mut current: Option<(usize, String)> = None;
+
+    for (idx, raw) in content.lines().enumerate() {
+        let trimmed = raw.trim();
+        if trimmed.starts_with('#') || (trimmed.is_empty() && current.is_none()) {
+            continue;
+        }
+
+        let (text, continued) = match trimmed.strip_suffix('\\') {
+            Some(t) => (t.trim_end(), true),
+            None => (trimmed, false),
+        };
+
+        let entry = current.get_or_insert_with(|| (idx + 1, String::new()));
+        if !entry.1.is_empty() && !text.is_empty() {
+            entry.1.push(' ');
+        }
+        entry.1.push_str(text);
+
+        if !continued && let Some(done) = current.take() {
+            lines.push(done);
+        }
+    }
+    if let Some(done) = current.filter(|(_, text)| !text.is_empty()) {
+        lines.push(done);
+    }
+    lines
+}
+
+fn parse_command(rest: &str) -> Result<CommandForm, String> {
+    if rest.starts_with('[') {
+        let argv: Vec<String> =
+            serde_json::from_str(rest).map_err(|e| format!("invalid JSON array: {}", e))?;
+        if argv.is_empty() {
+            return Err("command must not be empty".into());
+        }
+        Ok(CommandForm::Exec(argv))
+    } else {
+        Ok(CommandForm::Shell(rest.to_string()))
+    }
+}
+
+/// Parse `ENV key=value ...` or the legacy `ENV key value` form.
+fn parse_env(rest: &str) -> Result<Vec<(String, String)>, String> {
+    let words = split_words(rest)?;
+    if !words[0].contains('=') {
+        let (key, value) = rest
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| "ENV requires a value".to_string())?;
+        return Ok(vec![(key.to_string(), value.trim().to_string())]);
+    }
+
+    words
+        .into_iter()
+        .map(|word| {
+            word.split_once('=')
+                .filter(|(k, _)| !k.is_empty())
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .ok_or_else(|| format!("expected key=value, got '{}'", word))
+        })
+        .collect()
+}
+
+/// Split on whitespace, honouring single/double quotes and backslash escapes.
+fn split_words(input: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') | (None, '\\') => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+                in_word = true;
+            }
+            (Some(_), c) => current.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+
+    if quote.is_some() {
+        return Err("unterminated quote".into());
+    }
+    if in_word {
+        words.push(current);
+    }
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_all_instructions() {
+        let df = r#"
+# build a tiny app
+FROM alpine:3.19
+ENV APP_HOME=/app MODE="prod build"
+WORKDIR /app
+COPY src/ config.toml ./
+RUN apk add --no-cache curl \
+    && echo done
+CMD ["./app", "--serve"]
+"#;
+        let parsed = parse(df).unwrap();
+        assert_eq!(
+            parsed,
+            vec![
+                Instruction::From("alpine:3.19".into()),
+                Instruction::Env(vec![
+                    ("APP_HOME".into(), "/app".into()),
+                    ("MODE".into(), "prod build".into()),
+                ]),
+                Instruction::Workdir("/app".into()),
+                Instruction::Copy {
+                    sources: vec!["src/".into(), "config.toml".into()],
+                    dest: "./".into(),
+                },
+                Instruction::Run(CommandForm::Shell(
+                    "apk add --no-cache curl && echo done".into()
+                )),
+                Instruction::Cmd(CommandForm::Exec(vec!["./app".into(), "--serve".into()])),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_legacy_env_form() {
+        let parsed = parse("FROM alpine\nENV GREETING hello world").unwrap();
+        assert_eq!(
+            parsed[1],
+            Instruction::Env(vec![("GREETING".into(), "hello world".into())])
+        );
+    }
+
+    #[test]
+    fn test_shell_form_argv() {
+        let cmd = CommandForm::Shell("echo $HOME".into());
+        assert_eq!(cmd.argv(), vec!["/bin/sh", "-c", "echo $HOME"]);
+    }
+
+    #[test]
+    fn test_first_instruction_must_be_from() {
+        let err = parse("RUN echo hi\nFROM alpine").unwrap_err();
+        assert!(err.to_string().contains("line 1"));
+    }
+
+    #[test]
+    fn test_rejects_unsupported() {
+        assert!(parse("FROM alpine\nEXPOSE 80").is_err());
+        assert!(parse("FROM alpine AS build").is_err());
+        assert!(parse("FROM alpine\nFROM busybox").is_err());
+        assert!(parse("FROM alpine\nCOPY --chown=1000 a /b").is_err());
+        assert!(parse("FROM alpine\nRUN --mount=type=cache,target=/root/.cache make").is_err());
+        assert!(parse("FROM alpine\nRUN --network=none true").is_err());
+        assert!(parse("FROM alpine\nCOPY onlyone").is_err());
+        assert!(parse("# nothing here\n").is_err());
+    }
+
+    #[test]
+    fn test_split_words_quotes() {
+        assert_eq!(
+            split_words(r#"a "b c" 'd e' f\ g"#).unwrap(),
+            vec!["a", "b c", "d e", "f g"]
+        );
+        assert!(split_words("\"open").is_err());
+    }
+}
@@ -0,0 +1,457 @@
+//! Image builds from a Dockerfile subset (`boxlite build`).
+//!
+//! The builder walks the Dockerfile one instruction at a time:
+//! - `FROM` pulls the base image into the local store
+//! - `ENV`, `WORKDIR` and `CMD` only edit the image config
+//! - `COPY` packs files from the build context into a layer on the host
+//! - `RUN` boots the previous state in a throwaway box, runs the command,
+//!   and captures every path whose ctime moved as a new layer
+//!
+//! Each step's result is stored as an OCI layout under
+//! `~/.boxlite/images/build-cache/<key>`, where the key hashes the parent key,
+//! the instruction, and (for `COPY`) the source files. Re-running a build
+//! reuses every step up to the first changed instruction. The final state is
+//! imported into the image store under the requested tag.
+//!
+//! `RUN` layers record additions and modifications only; files deleted by a
+//! `RUN` step remain visible in the built image.
+//!
+//! [`provision_image`] uses the same machinery for `--provision`: a `FROM`
+//! step followed by a package install step, captured like a `RUN` layer.
+
+mod dockerfile;
+mod oci;
+
+use std::path::{Path, PathBuf};
+
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+use futures::StreamExt;
+use sha2::{Digest, Sha256};
+use tokio::sync::mpsc;
+
+use crate::litebox::{BoxCommand, CopyOptions, LiteBox};
+use crate::runtime::options::{BoxOptions, RootfsSpec};
+use crate::runtime::rt_impl::SharedRuntimeImpl;
+use dockerfile::{CommandForm, Instruction};
+use oci::{BlobDir, BuildState};
+
+/// Records the guest clock before a `RUN` step; later ctimes form the layer.
+const RUN_START_FILE: &str = "/tmp/.boxlite-build-start";
+
+/// Where the guest writes the captured `RUN` layer before it is copied out.
+const RUN_LAYER_TAR: &str = "/tmp/.boxlite-build-layer.tar";
+
+/// Record the start time, then wait a second so every change made by the
+/// step has a strictly newer ctime (ctime cannot be forged by package
+/// managers that preserve mtimes, and `stat -c %Z` works in busybox too).
+const PREPARE_SCRIPT: &str = r#"date +%s > "$START" && sleep 1 && mkdir -p "$WORKDIR""#;
+
+/// Archive every path whose ctime moved since [`PREPARE_SCRIPT`]. `.` is
+/// always listed so the archive is never empty; if `tar` lacks
+/// `--no-recursion`, changed directories are archived whole instead.
+const CAPTURE_SCRIPT: &str = r#"cd / && start=$(cat "$START") && {
+    echo .
+    find . -xdev ! -path . -exec stat -c '%Z %n' {} + \
+        | awk -v s="$start" '$1 > s { sub(/^[0-9]+ /, ""); print }' \
+        | grep -v -e '^\./tmp$' -e '^\./tmp/\.boxlite-build-'
+} > /tmp/.boxlite-build-list
+tar -cf "$LAYER" --no-recursion -T /tmp/.boxlite-build-list 2>/dev/null \
+    || tar -cf "$LAYER" -T /tmp/.boxlite-build-list"#;
+
+/// Repository of images made by [`provision_image`].
+const PROVISIONED_REPOSITORY: &str = "boxlite-provisioned";
+
+/// Hex digits of the cache key used as the tag of a provisioned image.
+const PROVISIONED_TAG_LEN: usize = 16;
+
+/// Options for [`BoxliteRuntime::build`](crate::BoxliteRuntime::build).
+#[derive(Debug, Clone, Default)]
+pub struct BuildOptions {
+    /// Build context directory; `COPY` sources are resolved inside it.
+    pub context: PathBuf,
+    /// Dockerfile path (defaults to `<context>/Dockerfile`).
+    pub dockerfile: Option<PathBuf>,
+    /// Tag for the result. Unqualified names are stored as `localhost/<name>`.
+    pub tag: String,
+    /// Ignore cached steps and run every instruction.
+    pub no_cache: bool,
+    /// Receives progress lines and `RUN` output while the build runs.
+    pub progress: Option<mpsc::UnboundedSender<String>>,
+}
+
+/// Result of a successful build.
+#[derive(Debug, Clone)]
+pub struct BuildOutput {
+    /// Fully qualified reference the image was tagged as (usable with `run`).
+    pub reference: String,
+    /// Config digest of the built image.
+    pub image_id: String,
+    /// Number of instructions executed.
+    pub steps: usize,
+    /// Number of instructions served from the build cache.
+    pub cached_steps: usize,
+}
+
+/// Run a build against `runtime`.
+pub(crate) async fn build(
+    runtime: &SharedRuntimeImpl,
+    options: BuildOptions,
+) -> BoxliteResult<BuildOutput> {
+    let reference = crate::images::normalize_local_reference(&options.tag)?;
+    let dockerfile_path = options
+        .dockerfile
+        .clone()
+        .unwrap_or_else(|| options.context.join("Dockerfile"));
+    let content = std::fs::read_to_string(&dockerfile_path).map_err(|e| {
+        BoxliteError::InvalidArgument(format!(
+            "Failed to read Dockerfile {}: {}",
+            dockerfile_path.display(),
+            e
+        ))
+    })?;
+    let instructions = dockerfile::parse(&content)?;
+
+    let cache_root = runtime.layout.image_layout().build_cache_dir();
+    let blobs = BlobDir::new(cache_root.join("blobs").join("sha256"))?;
+    let progress = |line: String| {
+        if let Some(tx) = &options.progress {
+            let _ = tx.send(line);
+        }
+    };
+
+    let total = instructions.len();
+    let mut cached_steps = 0;
+    let mut key = String::new();
+    let mut state: Option<BuildState> = None;
+    let mut step_dir = PathBuf::new();
+
+    for (index, instruction) in instructions.iter().enumerate() {
+        progress(format!(
+            "Step {}/{} : {}",
+            index + 1,
+            total,
+            instruction.canonical()
+        ));
+
+        let inputs = match instruction {
+            Instruction::From(image_ref) => from_inputs(runtime, image_ref).await?,
+            Instruction::Copy { sources, .. } => oci::hash_context(&options.context, sources)?,
+            _ => String::new(),
+        };
+        key = step_key(&key, &instruction.canonical(), &inputs);
+        let dir = cache_root.join(&key);
+
+        if !options.no_cache
+            && let Some(cached) = read_cached(&dir, &key)
+        {
+            progress(" ---> Using cache".into());
+            cached_steps += 1;
+            state = Some(cached);
+            step_dir = dir;
+            continue;
+        }
+
+        let next = match (instruction, state.take()) {
+            (Instruction::From(image_ref), _) => from_image(runtime, &blobs, image_ref).await?,
+            (_, None) => {
+                return Err(BoxliteError::Internal("build step before FROM".into()));
+            }
+            (Instruction::Env(vars), Some(mut s)) => {
+                s.set_env(vars);
+                s.push_history(&instruction.canonical(), true);
+                s
+            }
+            (Instruction::Workdir(dir), Some(mut s)) => {
+                s.set_working_dir(dir);
+                s.push_history(&instruction.canonical(), true);
+                s
+            }
+            (Instruction::Cmd(cmd), Some(mut s)) => {
+                s.set_cmd(cmd.argv());
+                s.push_history(&instruction.canonical(), true);
+                s
+            }
+            (Instruction::Copy { sources, dest }, Some(mut s)) => {
+                let dest = s.resolve_path(dest);
+                let tar_path = cache_root.join(format!("{}.copy.tar", key));
+                let context = options.context.clone();
+                let sources = sources.clone();
+                let tar_clone = tar_path.clone();
+                tokio::task::spawn_blocking(move || {
+                    oci::write_copy_layer(&context, &sources, &dest, &tar_clone)
+                })
+                .await
+                .map_err(|e| BoxliteError::Internal(format!("spawn_blocking failed: {}", e)))??;
+                s.push_layer(blobs.add_layer(&tar_path, true)?);
+                s.push_history(&instruction.canonical(), false);
+                s
+            }
+            (Instruction::Run(cmd), Some(mut s)) => {
+                let step = BoxStep::Run(cmd);
+                let tar_path =
+                    run_step(runtime, &step_dir, &s, step, &cache_root, &progress).await?;
+                s.push_layer(blobs.add_layer(&tar_path, true)?);
+                s.push_history(&instruction.canonical(), false);
+                s
+            }
+        };
+
+        next.write_layout(&blobs, &dir)?;
+        state = Some(next);
+        step_dir = dir;
+    }
+
+    let image = runtime
+        .image_manager
+        .import_layout(&step_dir, &reference)
+        .await?;
+    progress(format!("Successfully tagged {}", reference));
+
+    Ok(BuildOutput {
+        reference,
+        image_id: image.config_digest().to_string(),
+        steps: total,
+        cached_steps,
+    })
+}
+
+/// Install `packages` on top of `image` and tag the result.
+///
+/// Works like a two-step build (`FROM image` plus an install step), sharing
+/// the build cache: provisioning the same packages on the same image again
+/// reuses the stored layer. The result is tagged
+/// `localhost/boxlite-provisioned:<key>`.
+pub(crate) async fn provision_image(
+    runtime: &SharedRuntimeImpl,
+    image: &str,
+    packages: &[String],
+    progress: Option<mpsc::UnboundedSender<String>>,
+) -> BoxliteResult<BuildOutput> {
+    crate::litebox::provision::validate_packages(packages)?;
+    let mut packages = packages.to_vec();
+    packages.sort();
+    packages.dedup();
+
+    let cache_root = runtime.layout.image_layout().build_cache_dir();
+    let blobs = BlobDir::new(cache_root.join("blobs").join("sha256"))?;
+    let progress = |line: String| {
+        if let Some(tx) = &progress {
+            let _ = tx.send(line);
+        }
+    };
+
+    let from = Instruction::From(image.to_string());
+    let from_key = step_key("", &from.canonical(), &from_inputs(runtime, image).await?);
+    let from_dir = cache_root.join(&from_key);
+    let mut cached_steps = 0;
+    let base = match read_cached(&from_dir, &from_key) {
+        Some(cached) => {
+            cached_steps += 1;
+            cached
+        }
+        None => {
+            let state = from_image(runtime, &blobs, image).await?;
+            state.write_layout(&blobs, &from_dir)?;
+            state
+        }
+    };
+
+    let canonical = format!("PROVISION {}", packages.join(" "));
+    let key = step_key(&from_key, &canonical, "");
+    let dir = cache_root.join(&key);
+    match read_cached(&dir, &key) {
+        Some(_) => {
+            progress(format!("Using cached packages: {}", packages.join(", ")));
+            cached_steps += 1;
+        }
+        None => {
+            progress(format!("Provisioning {}: {}", image, packages.join(", ")));
+            let mut state = base;
+            let step = BoxStep::Provision(&packages);
+            let tar_path =
+                run_step(runtime, &from_dir, &state, step, &cache_root, &progress).await?;
+            state.push_layer(blobs.add_layer(&tar_path, true)?);
+            state.push_history(&canonical, false);
+            state.write_layout(&blobs, &dir)?;
+        }
+    }
+
+    let reference = crate::images::normalize_local_reference(&format!(
+        "{}:{}",
+        PROVISIONED_REPOSITORY,
+        &key[..PROVISIONED_TAG_LEN]
+    ))?;
+    let image = runtime
+        .image_manager
+        .import_layout(&dir, &reference)
+        .await?;
+    Ok(BuildOutput {
+        reference,
+        image_id: image.config_digest().to_string(),
+        steps: 2,
+        cached_steps,
+    })
+}
+
+/// Cache key of a step from its parent's key, instruction and inputs.
+fn step_key(parent: &str, canonical: &str, inputs: &str) -> String {
+    let key_input = format!("{}\0{}{}", parent, canonical, inputs);
+    format!("{:x}", Sha256::digest(key_input.as_bytes()))
+}
+
+/// Cache inputs of `FROM image_ref`: the image's current content.
+async fn from_inputs(runtime: &SharedRuntimeImpl, image_ref: &str) -> BoxliteResult<String> {
+    let image = runtime.image_manager.pull(image_ref).await?;
+    Ok(format!(
+        "{}{}",
+        image.compute_image_digest(),
+        image.config_digest()
+    ))
+}
+
+/// The cached state of step `key` stored in `dir`, if any.
+fn read_cached(dir: &Path, key: &str) -> Option<BuildState> {
+    if !dir.join("index.json").exists() {
+        return None;
+    }
+    match BuildState::read_layout(dir) {
+        Ok(cached) => Some(cached),
+        Err(e) => {
+            tracing::warn!(step = %key, error = %e, "Ignoring corrupt build cache entry");
+            None
+        }
+    }
+}
+
+/// Start a build state from a base image in the local store.
+async fn from_image(
+    runtime: &SharedRuntimeImpl,
+    blobs: &BlobDir,
+    image_ref: &str,
+) -> BoxliteResult<BuildState> {
+    let image = runtime.image_manager.pull(image_ref).await?;
+    let config = serde_json::to_value(image.load_config().await?)
+        .map_err(|e| BoxliteError::Internal(format!("Failed to encode image config: {}", e)))?;
+
+    let mut layers = Vec::with_capacity(image.layer_count());
+    for tarball in image.layer_tarballs() {
+        layers.push(blobs.add_layer(&tarball, false)?);
+    }
+    Ok(BuildState { config, layers })
+}
+
+/// What a step does in its throwaway box.
+enum BoxStep<'a> {
+    /// A `RUN` instruction.
+    Run(&'a CommandForm),
+    /// Install packages with the image's package manager.
+    Provision(&'a [String]),
+}
+
+/// Run one box step in a throwaway box and return the captured layer.
+async fn run_step(
+    runtime: &SharedRuntimeImpl,
+    parent_dir: &Path,
+    state: &BuildState,
+    step: BoxStep<'_>,
+    cache_root: &Path,
+    progress: &impl Fn(String),
+) -> BoxliteResult<PathBuf> {
+    let options = BoxOptions {
+        rootfs: RootfsSpec::RootfsPath(parent_dir.to_string_lossy().into_owned()),
+        auto_remove: true,
+        ..Default::default()
+    };
+    let litebox = runtime.create(options, None).await?;
+
+    let result = run_in_box(&litebox, state, step, cache_root, progress).await;
+    if let Err(e) = litebox.stop().await {
+        tracing::warn!(box_id = %litebox.id(), error = %e, "Failed to stop build box");
+    }
+    result
+}
+
+async fn run_in_box(
+    litebox: &LiteBox,
+    state: &BuildState,
+    step: BoxStep<'_>,
+    cache_root: &Path,
+    progress: &impl Fn(String),
+) -> BoxliteResult<PathBuf> {
+    let working_dir = state.working_dir();
+
+    // Create the working directory after the start mark so it lands in the layer.
+    let prepare = BoxCommand::new("/bin/sh")
+        .args(["-c", PREPARE_SCRIPT])
+        .env("START", RUN_START_FILE)
+        .env("WORKDIR", &working_dir);
+    exec_checked(litebox, prepare, "prepare build step", &|_| {}).await?;
+
+    match step {
+        BoxStep::Run(cmd) => {
+            let argv = cmd.argv();
+            let mut command = BoxCommand::new(&argv[0])
+                .args(&argv[1..])
+                .working_dir(&working_dir);
+            for (key, value) in state.env() {
+                command = command.env(key, value);
+            }
+            exec_checked(litebox, command, "RUN", progress).await?;
+        }
+        BoxStep::Provision(packages) => {
+            crate::litebox::provision::install(litebox, packages, progress).await?;
+        }
+    }
+
+    let capture = BoxCommand::new("/bin/sh")
+        .args(["-c", CAPTURE_SCRIPT])
+        .env("START", RUN_START_FILE)
+        .env("LAYER", RUN_LAYER_TAR);
+    exec_checked(litebox, capture, "capture layer", &|_| {}).await?;
+
+    let out_dir = cache_root.join(format!("run-{}", litebox.id()));
+    std::fs::create_dir_all(&out_dir).map_err(|e| {
+        BoxliteError::Storage(format!("Failed to create {}: {}", out_dir.display(), e))
+    })?;
+    litebox
+        .copy_out(RUN_LAYER_TAR, &out_dir, CopyOptions::default())
+        .await?;
+
+    let layer_name = Path::new(RUN_LAYER_TAR).file_name().unwrap_or_default();
+    let tar_path = cache_root.join(format!("{}.run.tar", litebox.id()));
+    std::fs::rename(out_dir.join(layer_name), &tar_path)
+        .map_err(|e| BoxliteError::Storage(format!("Failed to collect RUN layer: {}", e)))?;
+    let _ = std::fs::remove_dir_all(&out_dir);
+    Ok(tar_path)
+}
+
+/// Execute a command, forwarding output, and fail on a non-zero exit.
+async fn exec_checked(
+    litebox: &LiteBox,
+    command: BoxCommand,
+    what: &str,
+    progress: &impl Fn(String),
+) -> BoxliteResult<()> {
+    let mut execution = litebox.exec(command).await?;
+
+    let mut streams = Vec::new();
+    if let Some(stdout) = execution.stdout() {
+        streams.push(stdout.lines().boxed());
+    }
+    if let Some(stderr) = execution.stderr() {
+        streams.push(stderr.lines().boxed());
+    }
+    let mut output = futures::stream::select_all(streams);
+    while let Some(line) = output.next().await {
+        progress(line);
+    }
+
+    let result = execution.wait().await?;
+    if result.exit_code != 0 {
+        return Err(BoxliteError::Execution(format!(
+            "{} failed with exit code {}",
+            what, result.exit_code
+        )));
+    }
+    Ok(())
+}
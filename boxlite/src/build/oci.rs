@@ -0,0 +1,534 @@
+//! OCI image layouts for intermediate build states.
+//!
+//! Every build step is materialized as a minimal OCI image layout
+//! (`oci-layout`, `index.json`, `blobs/sha256/*`) so that `RUN` steps can
+//! boot the previous state through `RootfsSpec::RootfsPath`, and so a cached
+//! step can be reloaded without re-running it. Blobs are hard-linked from a
+//! shared content-addressed directory to avoid duplicating layer data.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+const MEDIA_TYPE_MANIFEST: &str = "application/vnd.oci.image.manifest.v1+json";
+const MEDIA_TYPE_CONFIG: &str = "application/vnd.oci.image.config.v1+json";
+const MEDIA_TYPE_LAYER_TAR: &str = "application/vnd.oci.image.layer.v1.tar";
+const MEDIA_TYPE_LAYER_GZIP: &str = "application/vnd.oci.image.layer.v1.tar+gzip";
+
+/// A layer blob referenced by a build state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Layer {
+    pub digest: String,
+    pub size: u64,
+    pub media_type: String,
+}
+
+/// Content-addressed blob directory shared by all build steps.
+pub(crate) struct BlobDir {
+    root: PathBuf,
+}
+
+impl BlobDir {
+    pub(crate) fn new(root: PathBuf) -> BoxliteResult<Self> {
+        std::fs::create_dir_all(&root).map_err(|e| {
+            BoxliteError::Storage(format!("Failed to create {}: {}", root.display(), e))
+        })?;
+        Ok(Self { root })
+    }
+
+    pub(crate) fn path(&self, digest: &str) -> PathBuf {
+        self.root.join(digest.trim_start_matches("sha256:"))
+    }
+
+    /// Add a file as a layer blob, moving it when `take` is set.
+    pub(crate) fn add_layer(&self, src: &Path, take: bool) -> BoxliteResult<Layer> {
+        let (digest, size) = hash_file(src)?;
+        let media_type = if is_gzip(src)? {
+            MEDIA_TYPE_LAYER_GZIP
+        } else {
+            MEDIA_TYPE_LAYER_TAR
+        };
+
+        let dest = self.path(&digest);
+        if !dest.exists() {
+            if take {
+                std::fs::rename(src, &dest)
+            } else {
+                link_or_copy(src, &dest)
+            }
+            .map_err(|e| {
+                BoxliteError::Storage(format!(
+                    "Failed to store blob {} from {}: {}",
+                    digest,
+                    src.display(),
+                    e
+                ))
+            })?;
+        } else if take {
+            let _ = std::fs::remove_file(src);
+        }
+
+        Ok(Layer {
+            digest,
+            size,
+            media_type: media_type.to_string(),
+        })
+    }
+
+    /// Add an in-memory blob and return `(digest, size)`.
+    fn add_bytes(&self, bytes: &[u8]) -> BoxliteResult<(String, u64)> {
+        let digest = format!("sha256:{:x}", Sha256::digest(bytes));
+        let dest = self.path(&digest);
+        if !dest.exists() {
+            std::fs::write(&dest, bytes).map_err(|e| {
+                BoxliteError::Storage(format!("Failed to write blob {}: {}", digest, e))
+            })?;
+        }
+        Ok((digest, bytes.len() as u64))
+    }
+}
+
+/// Image config plus layers at one point in the build.
+#[derive(Debug, Clone)]
+pub(crate) struct BuildState {
+    pub config: Value,
+    pub layers: Vec<Layer>,
+}
+
+impl BuildState {
+    /// Environment as `(key, value)` pairs.
+    pub(crate) fn env(&self) -> Vec<(String, String)> {
+        self.config["config"]["Env"]
+            .as_array()
+            .map(|vars| {
+                vars.iter()
+                    .filter_map(|v| v.as_str()?.split_once('='))
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Working directory (defaults to `/`).
+    pub(crate) fn working_dir(&self) -> String {
+        match self.config["config"]["WorkingDir"].as_str() {
+            Some(dir) if !dir.is_empty() => dir.to_string(),
+            _ => "/".to_string(),
+        }
+    }
+
+    /// Resolve a container path against the working directory.
+    pub(crate) fn resolve_path(&self, path: &str) -> String {
+        if path.starts_with('/') {
+            path.to_string()
+        } else {
+            format!("{}/{}", self.working_dir().trim_end_matches('/'), path)
+        }
+    }
+
+    pub(crate) fn set_env(&mut self, vars: &[(String, String)]) {
+        let mut env = self.env();
+        for (key, value) in vars {
+            match env.iter_mut().find(|(k, _)| k == key) {
+                Some(entry) => entry.1 = value.clone(),
+                None => env.push((key.clone(), value.clone())),
+            }
+        }
+        self.config_section()["Env"] = env
+            .into_iter()
+            .map(|(k, v)| Value::String(format!("{}={}", k, v)))
+            .collect();
+    }
+
+    pub(crate) fn set_working_dir(&mut self, dir: &str) {
+        let dir = self.resolve_path(dir);
+        self.config_section()["WorkingDir"] = Value::String(dir);
+    }
+
+    pub(crate) fn set_cmd(&mut self, argv: Vec<String>) {
+        self.config_section()["Cmd"] = json!(argv);
+    }
+
+    /// Append a layer and its diff ID (layers built here are uncompressed,
+    /// so the diff ID equals the blob digest).
+    pub(crate) fn push_layer(&mut self, layer: Layer) {
+        if !self.config["rootfs"].is_object() {
+            self.config["rootfs"] = json!({ "type": "layers", "diff_ids": [] });
+        }
+        match self.config["rootfs"]["diff_ids"].as_array_mut() {
+            Some(ids) => ids.push(Value::String(layer.digest.clone())),
+            None => self.config["rootfs"]["diff_ids"] = json!([layer.digest]),
+        }
+        self.layers.push(layer);
+    }
+
+    /// Record a history entry for a step.
+    pub(crate) fn push_history(&mut self, created_by: &str, empty_layer: bool) {
+        let now = chrono::Utc::now().to_rfc3339();
+        self.config["created"] = Value::String(now.clone());
+        let mut entry = json!({ "created": now, "created_by": created_by });
+        if empty_layer {
+            entry["empty_layer"] = Value::Bool(true);
+        }
+        match self.config["history"].as_array_mut() {
+            Some(history) => history.push(entry),
+            None => self.config["history"] = json!([entry]),
+        }
+    }
+
+    fn config_section(&mut self) -> &mut Value {
+        if !self.config["config"].is_object() {
+            self.config["config"] = json!({});
+        }
+        &mut self.config["config"]
+    }
+
+    /// Write this state as an OCI layout at `dir`, returning `(manifest digest, config digest)`.
+    ///
+    /// The layout is staged next to `dir` and renamed into place, so a
+    /// partially written layout is never mistaken for a cache hit.
+    pub(crate) fn write_layout(
+        &self,
+        blobs: &BlobDir,
+        dir: &Path,
+    ) -> BoxliteResult<(String, String)> {
+        let config_bytes = serde_json::to_vec(&self.config)
+            .map_err(|e| BoxliteError::Internal(format!("Failed to encode config: {}", e)))?;
+        let (config_digest, config_size) = blobs.add_bytes(&config_bytes)?;
+
+        let manifest = json!({
+            "schemaVersion": 2,
+            "mediaType": MEDIA_TYPE_MANIFEST,
+            "config": {
+                "mediaType": MEDIA_TYPE_CONFIG,
+                "digest": config_digest,
+                "size": config_size,
+            },
+            "layers": self.layers.iter().map(|l| json!({
+                "mediaType": l.media_type,
+                "digest": l.digest,
+                "size": l.size,
+            })).collect::<Vec<_>>(),
+        });
+        let manifest_bytes = serde_json::to_vec(&manifest)
+            .map_err(|e| BoxliteError::Internal(format!("Failed to encode manifest: {}", e)))?;
+        let (manifest_digest, manifest_size) = blobs.add_bytes(&manifest_bytes)?;
+
+        let index = json!({
+            "schemaVersion": 2,
+            "manifests": [{
+                "mediaType": MEDIA_TYPE_MANIFEST,
+                "digest": manifest_digest,
+                "size": manifest_size,
+            }],
+        });
+
+        let staging = dir.with_extension(format!("tmp-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&staging);
+        let blob_dir = staging.join("blobs").join("sha256");
+        let io = |e: std::io::Error| {
+            BoxliteError::Storage(format!(
+                "Failed to write image layout {}: {}",
+                staging.display(),
+                e
+            ))
+        };
+
+        std::fs::create_dir_all(&blob_dir).map_err(io)?;
+        std::fs::write(
+            staging.join("oci-layout"),
+            br#"{"imageLayoutVersion":"1.0.0"}"#,
+        )
+        .map_err(io)?;
+        std::fs::write(staging.join("index.json"), index.to_string()).map_err(io)?;
+
+        let digests = [&config_digest, &manifest_digest]
+            .into_iter()
+            .chain(self.layers.iter().map(|l| &l.digest));
+        for digest in digests {
+            let name = digest.trim_start_matches("sha256:");
+            link_or_copy(&blobs.path(digest), &blob_dir.join(name)).map_err(io)?;
+        }
+
+        let _ = std::fs::remove_dir_all(dir);
+        std::fs::rename(&staging, dir).map_err(io)?;
+
+        Ok((manifest_digest, config_digest))
+    }
+
+    /// Load a state previously written by [`write_layout`](Self::write_layout).
+    pub(crate) fn read_layout(dir: &Path) -> BoxliteResult<Self> {
+        let read_json = |path: PathBuf| -> BoxliteResult<Value> {
+            let bytes = std::fs::read(&path).map_err(|e| {
+                BoxliteError::Storage(format!("Failed to read {}: {}", path.display(), e))
+            })?;
+            serde_json::from_slice(&bytes).map_err(|e| {
+                BoxliteError::Storage(format!("Failed to parse {}: {}", path.display(), e))
+            })
+        };
+        let blob = |digest: &str| {
+            dir.join("blobs")
+                .join("sha256")
+                .join(digest.trim_start_matches("sha256:"))
+        };
+
+        let index = read_json(dir.join("index.json"))?;
+        let manifest_digest = index["manifests"][0]["digest"]
+            .as_str()
+            .ok_or_else(|| BoxliteError::Storage(format!("{}: empty index", dir.display())))?;
+        let manifest = read_json(blob(manifest_digest))?;
+        let config_digest = manifest["config"]["digest"].as_str().unwrap_or_default();
+        let config = read_json(blob(config_digest))?;
+
+        let layers = manifest["layers"]
+            .as_array()
+            .map(|layers| {
+                layers
+                    .iter()
+                    .map(|l| Layer {
+                        digest: l["digest"].as_str().unwrap_or_default().to_string(),
+                        size: l["size"].as_u64().unwrap_or_default(),
+                        media_type: l["mediaType"].as_str().unwrap_or_default().to_string(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Self { config, layers })
+    }
+}
+
+/// Hash the files named by `COPY` sources so cache keys change with content.
+pub(crate) fn hash_context(context: &Path, sources: &[String]) -> BoxliteResult<String> {
+    let mut hasher = Sha256::new();
+    for source in sources {
+        let root = context_path(context, source)?;
+        hasher.update(source.as_bytes());
+        for entry in walkdir::WalkDir::new(&root).sort_by_file_name() {
+            let entry = entry.map_err(|e| {
+                BoxliteError::Storage(format!("Failed to walk {}: {}", root.display(), e))
+            })?;
+            let rel = entry.path().strip_prefix(&root).unwrap_or(entry.path());
+            hasher.update(rel.to_string_lossy().as_bytes());
+            let meta = entry.path().symlink_metadata().map_err(|e| {
+                BoxliteError::Storage(format!("Failed to stat {}: {}", entry.path().display(), e))
+            })?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                hasher.update(meta.permissions().mode().to_le_bytes());
+            }
+            if meta.is_file() {
+                let (digest, _) = hash_file(entry.path())?;
+                hasher.update(digest.as_bytes());
+            } else if meta.file_type().is_symlink()
+                && let Ok(target) = std::fs::read_link(entry.path())
+            {
+                hasher.update(target.to_string_lossy().as_bytes());
+            }
+        }
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Write a layer tarball for `COPY sources dest` (dest already resolved).
+///
+/// Follows Docker semantics: a directory source copies its contents, and a
+/// file is placed inside `dest` when `dest` ends in `/` or there are
+/// several sources.
+pub(crate) fn write_copy_layer(
+    context: &Path,
+    sources: &[String],
+    dest: &str,
+    output: &Path,
+) -> BoxliteResult<()> {
+    let io = |e: std::io::Error| {
+        BoxliteError::Storage(format!("Failed to write {}: {}", output.display(), e))
+    };
+    let file = File::create(output).map_err(io)?;
+    let mut builder = tar::Builder::new(file);
+    builder.follow_symlinks(false);
+    // Like Docker, copied files are owned by root regardless of the host owner.
+    builder.mode(tar::HeaderMode::Deterministic);
+
+    let dest = dest.trim_start_matches('/');
+    let dest_is_dir = dest.is_empty() || dest.ends_with('/') || sources.len() > 1;
+    let dest = dest.trim_end_matches('/');
+
+    for source in sources {
+        let src = context_path(context, source)?;
+        if src.is_dir() {
+            let archive_root = if dest.is_empty() { "." } else { dest };
+            builder.append_dir_all(archive_root, &src).map_err(io)?;
+        } else {
+            let name = src
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let target = match (dest_is_dir, dest.is_empty()) {
+                (true, true) => name,
+                (true, false) => format!("{}/{}", dest, name),
+                (false, _) => dest.to_string(),
+            };
+            builder.append_path_with_name(&src, target).map_err(io)?;
+        }
+    }
+
+    builder.into_inner().map_err(io)?;
+    Ok(())
+}
+
+/// Resolve a `COPY` source inside the build context, rejecting escapes.
+fn context_path(context: &Path, source: &str) -> BoxliteResult<PathBuf> {
+    let context = context.canonicalize().map_err(|e| {
+        BoxliteError::InvalidArgument(format!(
+            "Invalid build context {}: {}",
+            context.display(),
+            e
+        ))
+    })?;
+    let path = context
+        .join(source.trim_start_matches('/'))
+        .canonicalize()
+        .map_err(|e| {
+            BoxliteError::InvalidArgument(format!("COPY source '{}' not found: {}", source, e))
+        })?;
+    if !path.starts_with(&context) {
+        return Err(BoxliteError::InvalidArgument(format!(
+            "COPY source '{}' is outside the build context",
+            source
+        )));
+    }
+    Ok(path)
+}
+
+fn hash_file(path: &Path) -> BoxliteResult<(String, u64)> {
+    let mut file = File::open(path)
+        .map_err(|e| BoxliteError::Storage(format!("Failed to open {}: {}", path.display(), e)))?;
+    let mut hasher = Sha256::new();
+    let size = std::io::copy(&mut file, &mut hasher)
+        .map_err(|e| BoxliteError::Storage(format!("Failed to read {}: {}", path.display(), e)))?;
+    Ok((format!("sha256:{:x}", hasher.finalize()), size))
+}
+
+fn is_gzip(path: &Path) -> BoxliteResult<bool> {
+    let mut magic = [0u8; 2];
+    let mut file = File::open(path)
+        .map_err(|e| BoxliteError::Storage(format!("Failed to open {}: {}", path.display(), e)))?;
+    Ok(file.read_exact(&mut magic).is_ok() && magic == [0x1f, 0x8b])
+}
+
+fn link_or_copy(src: &Path, dest: &Path) -> std::io::Result<()> {
+    if dest.exists() {
+        return Ok(());
+    }
+    std::fs::hard_link(src, dest).or_else(|_| std::fs::copy(src, dest).map(|_| ()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn base_state() -> BuildState {
+        BuildState {
+            config: json!({
+                "architecture": "amd64",
+                "os": "linux",
+                "config": { "Env": ["PATH=/usr/bin"] },
+                "rootfs": { "type": "layers", "diff_ids": [] },
+            }),
+            layers: vec![],
+        }
+    }
+
+    #[test]
+    fn test_env_and_workdir() {
+        let mut state = base_state();
+        state.set_env(&[
+            ("PATH".into(), "/bin".into()),
+            ("HOME".into(), "/root".into()),
+        ]);
+        assert_eq!(
+            state.env(),
+            vec![
+                ("PATH".to_string(), "/bin".to_string()),
+                ("HOME".to_string(), "/root".to_string())
+            ]
+        );
+
+        assert_eq!(state.working_dir(), "/");
+        state.set_working_dir("/app");
+        state.set_working_dir("src");
+        assert_eq!(state.working_dir(), "/app/src");
+        assert_eq!(state.resolve_path("main.rs"), "/app/src/main.rs");
+        assert_eq!(state.resolve_path("/etc"), "/etc");
+    }
+
+    #[test]
+    fn test_layout_roundtrip() {
+        let tmp = TempDir::new().unwrap();
+        let blobs = BlobDir::new(tmp.path().join("blobs")).unwrap();
+
+        let layer_src = tmp.path().join("layer.tar");
+        std::fs::write(&layer_src, b"not really a tar").unwrap();
+        let layer = blobs.add_layer(&layer_src, true).unwrap();
+        assert!(!layer_src.exists());
+        assert_eq!(layer.media_type, MEDIA_TYPE_LAYER_TAR);
+
+        let mut state = base_state();
+        state.push_layer(layer.clone());
+        state.set_cmd(vec!["/bin/sh".into()]);
+        state.push_history("CMD", true);
+
+        let dir = tmp.path().join("step");
+        state.write_layout(&blobs, &dir).unwrap();
+        assert!(dir.join("oci-layout").exists());
+
+        let loaded = BuildState::read_layout(&dir).unwrap();
+        assert_eq!(loaded.layers, vec![layer.clone()]);
+        assert_eq!(loaded.config["config"]["Cmd"], json!(["/bin/sh"]));
+        assert_eq!(loaded.config["rootfs"]["diff_ids"], json!([layer.digest]));
+    }
+
+    #[test]
+    fn test_copy_layer_and_context_hash() {
+        let tmp = TempDir::new().unwrap();
+        let ctx = tmp.path().join("ctx");
+        std::fs::create_dir_all(ctx.join("src")).unwrap();
+        std::fs::write(ctx.join("src/main.py"), b"print(1)").unwrap();
+        std::fs::write(ctx.join("app.toml"), b"x = 1").unwrap();
+
+        let sources = vec!["src".to_string(), "app.toml".to_string()];
+        let before = hash_context(&ctx, &sources).unwrap();
+
+        let out = tmp.path().join("copy.tar");
+        write_copy_layer(&ctx, &sources, "/app/", &out).unwrap();
+        let mut names: Vec<String> = tar::Archive::new(File::open(&out).unwrap())
+            .entries()
+            .unwrap()
+            .map(|e| e.unwrap().path().unwrap().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        assert!(names.contains(&"app/main.py".to_string()));
+        assert!(names.contains(&"app/app.toml".to_string()));
+
+        std::fs::write(ctx.join("src/main.py"), b"print(2)").unwrap();
+        assert_ne!(before, hash_context(&ctx, &sources).unwrap());
+    }
+
+    #[test]
+    fn test_copy_source_outside_context() {
+        let tmp = TempDir::new().unwrap();
+        let ctx = tmp.path().join("ctx");
+        std::fs::create_dir_all(&ctx).unwrap();
+        std::fs::write(tmp.path().join("secret"), b"x").unwrap();
+
+        let err = hash_context(&ctx, &["../secret".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("outside the build context"));
+    }
+}
@@ -361,6 +361,7 @@ mod tests {
             container: ContainerRuntimeConfig {
                 id: ContainerID::new(),
             },
+            sidecars: Default::default(),
             options: BoxOptions {
                 rootfs: RootfsSpec::Image("test:latest".to_string()),
                 cpus: Some(2),
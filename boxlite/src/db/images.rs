@@ -8,6 +8,7 @@ use rusqlite::{OptionalExtension, params};
 use boxlite_shared::errors::{BoxliteError, BoxliteResult};
 
 use super::{Database, db_err};
+use crate::metrics::ImagePullMetrics;
 
 /// Metadata for a cached image.
 #[derive(Debug, Clone)]
@@ -106,6 +107,196 @@ impl ImageIndexStore {
         Ok(())
     }
 
+    /// Get a complete cached image by manifest digest.
+    ///
+    /// Used to resolve local tags, which point at a digest rather than a reference.
+    pub fn get_by_manifest_digest(
+        &self,
+        manifest_digest: &str,
+    ) -> BoxliteResult<Option<CachedImage>> {
+        let conn = self.db.conn();
+
+        let row: Option<(String, String, String)> = db_err!(
+            conn.query_row(
+                "SELECT config_digest, layers, cached_at FROM image_index WHERE manifest_digest = ?1 AND complete = 1 LIMIT 1",
+                params![manifest_digest],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()
+        )?;
+
+        match row {
+            Some((config_digest, layers_json, cached_at)) => {
+                let layers: Vec<String> = serde_json::from_str(&layers_json).map_err(|e| {
+                    BoxliteError::Database(format!("Failed to deserialize layers: {}", e))
+                })?;
+                Ok(Some(CachedImage {
+                    manifest_digest: manifest_digest.to_string(),
+                    config_digest,
+                    layers,
+                    cached_at,
+                    complete: true,
+                }))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Point a local tag at a manifest digest, replacing any previous target.
+    pub fn set_tag(&self, tag: &str, manifest_digest: &str) -> BoxliteResult<()> {
+        let conn = self.db.conn();
+        let now = chrono::Utc::now().to_rfc3339();
+
+        db_err!(conn.execute(
+            r#"
+            INSERT INTO image_tag (tag, manifest_digest, created_at)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT(tag) DO UPDATE SET
+                manifest_digest = excluded.manifest_digest,
+                created_at = excluded.created_at
+            "#,
+            params![tag, manifest_digest, now],
+        ))?;
+
+        Ok(())
+    }
+
+    /// Get the manifest digest a local tag points at.
+    pub fn get_tag(&self, tag: &str) -> BoxliteResult<Option<String>> {
+        let conn = self.db.conn();
+        db_err!(
+            conn.query_row(
+                "SELECT manifest_digest FROM image_tag WHERE tag = ?1",
+                params![tag],
+                |row| row.get(0),
+            )
+            .optional()
+        )
+    }
+
+    /// List all local tags as `(tag, manifest_digest, created_at)`, newest first.
+    pub fn list_tags(&self) -> BoxliteResult<Vec<(String, String, String)>> {
+        let conn = self.db.conn();
+        let mut stmt = db_err!(conn.prepare(
+            "SELECT tag, manifest_digest, created_at FROM image_tag ORDER BY created_at DESC"
+        ))?;
+
+        let rows = db_err!(stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?))))?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(db_err!(row)?);
+        }
+        Ok(result)
+    }
+
+    /// Record the statistics of a registry pull.
+    pub fn record_pull(&self, pull: &ImagePullMetrics) -> BoxliteResult<()> {
+        let conn = self.db.conn();
+
+        let json = serde_json::to_string(pull).map_err(|e| {
+            BoxliteError::Database(format!("Failed to serialize pull metrics: {}", e))
+        })?;
+
+        db_err!(conn.execute(
+            "INSERT INTO image_pull (reference, manifest_digest, pulled_at, json) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                pull.reference,
+                pull.manifest_digest,
+                pull.pulled_at.to_rfc3339(),
+                json
+            ],
+        ))?;
+
+        Ok(())
+    }
+
+    /// Get the most recent pull statistics for a reference.
+    pub fn last_pull(&self, reference: &str) -> BoxliteResult<Option<ImagePullMetrics>> {
+        let conn = self.db.conn();
+
+        let json: Option<String> = db_err!(
+            conn.query_row(
+                "SELECT json FROM image_pull WHERE reference = ?1 ORDER BY pulled_at DESC, id DESC LIMIT 1",
+                params![reference],
+                |row| row.get(0),
+            )
+            .optional()
+        )?;
+
+        json.map(|json| {
+            serde_json::from_str(&json).map_err(|e| {
+                BoxliteError::Database(format!("Failed to deserialize pull metrics: {}", e))
+            })
+        })
+        .transpose()
+    }
+
+    /// Record that the image with `manifest_digest` was just used.
+    pub fn touch(&self, manifest_digest: &str) -> BoxliteResult<()> {
+        let conn = self.db.conn();
+        let now = chrono::Utc::now().to_rfc3339();
+
+        db_err!(conn.execute(
+            r#"
+            INSERT INTO image_use (manifest_digest, used_at)
+            VALUES (?1, ?2)
+            ON CONFLICT(manifest_digest) DO UPDATE SET used_at = excluded.used_at
+            "#,
+            params![manifest_digest, now],
+        ))?;
+
+        Ok(())
+    }
+
+    /// List all cached images as `(reference, image, last_used)`, least
+    /// recently used first.
+    ///
+    /// Images never used since they were cached count as used at `cached_at`.
+    pub fn list_by_last_use(&self) -> BoxliteResult<Vec<(String, CachedImage, String)>> {
+        let uses: std::collections::HashMap<String, String> = {
+            let conn = self.db.conn();
+            let mut stmt = db_err!(conn.prepare("SELECT manifest_digest, used_at FROM image_use"))?;
+            let rows = db_err!(stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?))))?;
+            let mut uses = std::collections::HashMap::new();
+            for row in rows {
+                let (digest, used_at) = db_err!(row)?;
+                uses.insert(digest, used_at);
+            }
+            uses
+        };
+
+        let mut result: Vec<(String, CachedImage, String)> = self
+            .list_all()?
+            .into_iter()
+            .map(|(reference, image)| {
+                let last_used = uses
+                    .get(&image.manifest_digest)
+                    .cloned()
+                    .unwrap_or_else(|| image.cached_at.clone());
+                (reference, image, last_used)
+            })
+            .collect();
+        result.sort_by(|a, b| a.2.cmp(&b.2));
+        Ok(result)
+    }
+
+    /// Remove every reference to the image with `manifest_digest`, and its use record.
+    ///
+    /// Returns the number of references removed.
+    pub fn remove_manifest(&self, manifest_digest: &str) -> BoxliteResult<usize> {
+        let conn = self.db.conn();
+        let rows_affected = db_err!(conn.execute(
+            "DELETE FROM image_index WHERE manifest_digest = ?1",
+            params![manifest_digest]
+        ))?;
+        db_err!(conn.execute(
+            "DELETE FROM image_use WHERE manifest_digest = ?1",
+            params![manifest_digest]
+        ))?;
+        Ok(rows_affected)
+    }
+
     /// Remove cached image from index.
     #[allow(dead_code)]
     pub fn remove(&self, reference: &str) -> BoxliteResult<bool> {
@@ -299,6 +490,84 @@ mod tests {
         assert_eq!(store.len().unwrap(), 1);
     }
 
+    #[test]
+    fn test_tags_resolve_by_digest() {
+        let (store, _dir) = create_test_db();
+
+        let image = CachedImage {
+            manifest_digest: "sha256:abc123".to_string(),
+            config_digest: "sha256:config123".to_string(),
+            layers: vec!["sha256:layer1".to_string()],
+            cached_at: "2025-10-24T12:00:00Z".to_string(),
+            complete: true,
+        };
+        store
+            .upsert("docker.io/library/alpine:latest", &image)
+            .unwrap();
+
+        assert!(store.get_tag("localhost/base:v1").unwrap().is_none());
+        store.set_tag("localhost/base:v1", "sha256:abc123").unwrap();
+
+        let digest = store.get_tag("localhost/base:v1").unwrap().unwrap();
+        let resolved = store.get_by_manifest_digest(&digest).unwrap().unwrap();
+        assert_eq!(resolved.config_digest, "sha256:config123");
+        assert_eq!(resolved.layers, vec!["sha256:layer1".to_string()]);
+
+        // Retagging replaces the target
+        store.set_tag("localhost/base:v1", "sha256:def456").unwrap();
+        assert_eq!(
+            store.get_tag("localhost/base:v1").unwrap().as_deref(),
+            Some("sha256:def456")
+        );
+        assert!(
+            store
+                .get_by_manifest_digest("sha256:def456")
+                .unwrap()
+                .is_none()
+        );
+
+        let tags = store.list_tags().unwrap();
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].0, "localhost/base:v1");
+    }
+
+    #[test]
+    fn test_record_and_last_pull() {
+        use crate::metrics::LayerPullMetrics;
+
+        let (store, _dir) = create_test_db();
+        assert!(store.last_pull("alpine:latest").unwrap().is_none());
+
+        let pull = |digest: &str, pulled_at: &str, cached: bool| ImagePullMetrics {
+            reference: "alpine:latest".to_string(),
+            manifest_digest: digest.to_string(),
+            pulled_at: chrono::DateTime::parse_from_rfc3339(pulled_at)
+                .unwrap()
+                .with_timezone(&chrono::Utc),
+            duration_ms: 1200,
+            bytes_downloaded: if cached { 0 } else { 3_000_000 },
+            layers: vec![LayerPullMetrics {
+                digest: "sha256:layer1".to_string(),
+                size_bytes: 3_000_000,
+                cached,
+                duration_ms: 900,
+            }],
+        };
+
+        store
+            .record_pull(&pull("sha256:new", "2026-01-21T14:00:00Z", true))
+            .unwrap();
+        store
+            .record_pull(&pull("sha256:old", "2026-01-21T10:00:00Z", false))
+            .unwrap();
+
+        let last = store.last_pull("alpine:latest").unwrap().unwrap();
+        assert_eq!(last.manifest_digest, "sha256:new");
+        assert_eq!(last.layers_cached(), 1);
+        assert_eq!(last.layers_fetched(), 0);
+        assert!(store.last_pull("python:alpine").unwrap().is_none());
+    }
+
     #[test]
     fn test_list_all_empty() {
         let (store, _dir) = create_test_db();
@@ -346,4 +615,39 @@ mod tests {
         assert_eq!(images[1].0, "alpine:latest"); // 10:00
         assert_eq!(images[2].0, "nginx:latest"); // 08:00
     }
+
+    #[test]
+    fn test_list_by_last_use() {
+        let (store, _dir) = create_test_db();
+
+        let image = |digest: &str, cached_at: &str| CachedImage {
+            manifest_digest: digest.to_string(),
+            config_digest: "sha256:config".to_string(),
+            layers: vec!["sha256:layer".to_string()],
+            cached_at: cached_at.to_string(),
+            complete: true,
+        };
+        store
+            .upsert("alpine:latest", &image("sha256:a", "2026-01-21T10:00:00Z"))
+            .unwrap();
+        store
+            .upsert("python:alpine", &image("sha256:p", "2026-01-21T14:00:00Z"))
+            .unwrap();
+
+        // Never used: ordered by cache time
+        let images = store.list_by_last_use().unwrap();
+        assert_eq!(images[0].0, "alpine:latest");
+        assert_eq!(images[0].2, "2026-01-21T10:00:00Z");
+
+        // Using the older image moves it to the back
+        store.touch("sha256:a").unwrap();
+        let images = store.list_by_last_use().unwrap();
+        assert_eq!(images[0].0, "python:alpine");
+        assert_eq!(images[1].0, "alpine:latest");
+
+        assert_eq!(store.remove_manifest("sha256:a").unwrap(), 1);
+        let images = store.list_by_last_use().unwrap();
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].0, "python:alpine");
+    }
 }
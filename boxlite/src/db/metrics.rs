@@ -0,0 +1,141 @@
+//! Box metrics history storage operations.
+//!
+//! Each box keeps at most a fixed number of samples: recording a sample
+//! drops the oldest ones beyond that limit, like a ring buffer.
+
+use chrono::{DateTime, Utc};
+use rusqlite::params;
+
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+
+use super::{Database, db_err};
+use crate::metrics::MetricsSample;
+
+/// Box metrics sample storage wrapping Database.
+#[derive(Clone)]
+pub struct MetricsSampleStore {
+    db: Database,
+}
+
+impl MetricsSampleStore {
+    /// Create a new MetricsSampleStore from a Database.
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Record a sample, keeping only the newest `max_samples` of the box.
+    pub fn record(
+        &self,
+        box_id: &str,
+        sample: &MetricsSample,
+        max_samples: u32,
+    ) -> BoxliteResult<()> {
+        let conn = self.db.conn();
+
+        let json = serde_json::to_string(sample).map_err(|e| {
+            BoxliteError::Database(format!("Failed to serialize metrics sample: {}", e))
+        })?;
+
+        db_err!(conn.execute(
+            "INSERT INTO box_metrics_sample (box_id, sampled_at, json) VALUES (?1, ?2, ?3)",
+            params![box_id, sample.sampled_at.timestamp_millis(), json],
+        ))?;
+
+        // Row ids grow with insertion order; drop everything at or below the
+        // first one past the limit.
+        db_err!(conn.execute(
+            r#"
+            DELETE FROM box_metrics_sample
+            WHERE box_id = ?1 AND id <= (
+                SELECT id FROM box_metrics_sample WHERE box_id = ?1
+                ORDER BY id DESC LIMIT 1 OFFSET ?2
+            )
+            "#,
+            params![box_id, max_samples.max(1)],
+        ))?;
+
+        Ok(())
+    }
+
+    /// Samples of a box taken at or after `since`, oldest first.
+    pub fn since(&self, box_id: &str, since: DateTime<Utc>) -> BoxliteResult<Vec<MetricsSample>> {
+        let conn = self.db.conn();
+        let mut stmt = db_err!(conn.prepare(
+            "SELECT json FROM box_metrics_sample WHERE box_id = ?1 AND sampled_at >= ?2 ORDER BY sampled_at, id"
+        ))?;
+
+        let rows = db_err!(
+            stmt.query_map(params![box_id, since.timestamp_millis()], |row| {
+                row.get::<_, String>(0)
+            })
+        )?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            let json = db_err!(row)?;
+            result.push(serde_json::from_str(&json).map_err(|e| {
+                BoxliteError::Database(format!("Failed to deserialize metrics sample: {}", e))
+            })?);
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn sample(sampled_at: DateTime<Utc>, memory_bytes: u64) -> MetricsSample {
+        MetricsSample {
+            sampled_at,
+            cpu_percent: Some(12.5),
+            memory_bytes: Some(memory_bytes),
+            network_bytes_sent: None,
+            network_bytes_received: None,
+            network_tcp_connections: None,
+        }
+    }
+
+    #[test]
+    fn test_record_keeps_newest_samples() {
+        let dir = TempDir::new().unwrap();
+        let db = Database::open(&dir.path().join("test.db")).unwrap();
+        db.conn()
+            .execute_batch(
+                "INSERT INTO box_config (id, name, created_at, json) VALUES ('a', NULL, 0, '{}');
+                 INSERT INTO box_config (id, name, created_at, json) VALUES ('b', NULL, 0, '{}');",
+            )
+            .unwrap();
+        let store = MetricsSampleStore::new(db.clone());
+
+        let start = Utc::now() - chrono::Duration::minutes(10);
+        for minute in 0..5 {
+            let at = start + chrono::Duration::minutes(minute);
+            store.record("a", &sample(at, minute as u64), 3).unwrap();
+        }
+        store.record("b", &sample(start, 100), 3).unwrap();
+
+        // Only the newest three of box a remain; box b is untouched
+        let all = store.since("a", start).unwrap();
+        assert_eq!(
+            all.iter()
+                .map(|s| s.memory_bytes.unwrap())
+                .collect::<Vec<_>>(),
+            [2, 3, 4]
+        );
+        assert_eq!(all[0], sample(start + chrono::Duration::minutes(2), 2));
+        assert_eq!(store.since("b", start).unwrap().len(), 1);
+
+        let recent = store
+            .since("a", start + chrono::Duration::minutes(4))
+            .unwrap();
+        assert_eq!(recent.len(), 1);
+
+        // Samples are removed with their box
+        db.conn()
+            .execute("DELETE FROM box_config WHERE id = 'a'", [])
+            .unwrap();
+        assert!(store.since("a", start).unwrap().is_empty());
+    }
+}
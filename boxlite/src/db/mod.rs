@@ -8,6 +8,7 @@
 
 mod boxes;
 mod images;
+mod metrics;
 mod schema;
 
 use std::path::Path;
@@ -15,12 +16,13 @@ use std::sync::Arc;
 
 use chrono::Utc;
 use parking_lot::{Mutex, MutexGuard};
-use rusqlite::{Connection, OptionalExtension};
+use rusqlite::{Connection, OpenFlags, OptionalExtension};
 
 use boxlite_shared::errors::{BoxliteError, BoxliteResult};
 
 pub use boxes::BoxStore;
 pub use images::{CachedImage, ImageIndexStore};
+pub use metrics::MetricsSampleStore;
 
 /// Helper macro to convert rusqlite errors to BoxliteError.
 macro_rules! db_err {
@@ -70,6 +72,35 @@ impl Database {
         })
     }
 
+    /// Open an existing database read-only, without schema setup.
+    ///
+    /// Fails if the database is at a different schema version. Safe to use
+    /// while another process has it open for writing.
+    pub fn open_read_only(db_path: &Path) -> BoxliteResult<Self> {
+        let conn = db_err!(Connection::open_with_flags(
+            db_path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX
+        ))?;
+        db_err!(conn.busy_timeout(std::time::Duration::from_secs(1)))?;
+
+        let version: i32 = db_err!(conn.query_row(
+            "SELECT version FROM schema_version WHERE id = 1",
+            [],
+            |row| row.get(0),
+        ))?;
+        if version != schema::SCHEMA_VERSION {
+            return Err(BoxliteError::Database(format!(
+                "Schema version mismatch: database has v{}, process expects v{}",
+                version,
+                schema::SCHEMA_VERSION
+            )));
+        }
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
     /// Acquire the database connection.
     pub(crate) fn conn(&self) -> MutexGuard<'_, Connection> {
         self.conn.lock()
@@ -106,8 +137,12 @@ impl Database {
             Some(v) if v == schema::SCHEMA_VERSION => {
                 // Already at current version - nothing to do
             }
+            Some(v) if (schema::MIN_MIGRATABLE_VERSION..schema::SCHEMA_VERSION).contains(&v) => {
+                // Older database - upgrade in place
+                Self::run_migrations(conn, v)?;
+            }
             Some(v) => {
-                // Strict version check: any mismatch is an error
+                // Newer (or unknown) version: refuse to touch it
                 return Err(BoxliteError::Database(format!(
                     "Schema version mismatch: database has v{}, process expects v{}. \
                      Remove the database file in $BOXLITE_HOME/db to reset.",
@@ -140,9 +175,6 @@ impl Database {
     }
 
     /// Run migrations from `from_version` to current schema version.
-    ///
-    /// Called by explicit `boxlite migrate` command, not automatically.
-    #[allow(dead_code)] // Will be used by CLI migrate command
     fn run_migrations(conn: &Connection, from_version: i32) -> BoxliteResult<()> {
         let mut current = from_version;
 
@@ -175,6 +207,42 @@ impl Database {
             current = 4;
         }
 
+        // Migration 4 -> 5: Add image_tag table
+        if current == 4 {
+            tracing::info!("Running migration 4 -> 5: Adding image_tag table");
+
+            db_err!(conn.execute_batch(schema::IMAGE_TAG_TABLE))?;
+
+            current = 5;
+        }
+
+        // Migration 5 -> 6: Add image_pull table
+        if current == 5 {
+            tracing::info!("Running migration 5 -> 6: Adding image_pull table");
+
+            db_err!(conn.execute_batch(schema::IMAGE_PULL_TABLE))?;
+
+            current = 6;
+        }
+
+        // Migration 6 -> 7: Add box_metrics_sample table
+        if current == 6 {
+            tracing::info!("Running migration 6 -> 7: Adding box_metrics_sample table");
+
+            db_err!(conn.execute_batch(schema::BOX_METRICS_SAMPLE_TABLE))?;
+
+            current = 7;
+        }
+
+        // Migration 7 -> 8: Add image_use table
+        if current == 7 {
+            tracing::info!("Running migration 7 -> 8: Adding image_use table");
+
+            db_err!(conn.execute_batch(schema::IMAGE_USE_TABLE))?;
+
+            current = 8;
+        }
+
         // Update schema version
         let now = Utc::now().to_rfc3339();
         db_err!(conn.execute(
@@ -198,4 +266,36 @@ mod tests {
         let db_path = temp_dir.path().join("test.db");
         let _db = Database::open(&db_path).unwrap();
     }
+
+    #[test]
+    fn test_db_migrates_older_schema() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        {
+            let db = Database::open(&db_path).unwrap();
+            let conn = db.conn();
+            conn.execute_batch(
+                "DROP TABLE image_tag; DROP TABLE image_pull; DROP TABLE box_metrics_sample; \
+                 DROP TABLE image_use; \
+                 UPDATE schema_version SET version = 4 WHERE id = 1;",
+            )
+            .unwrap();
+        }
+
+        let db = Database::open(&db_path).unwrap();
+        let conn = db.conn();
+        let version: i32 = conn
+            .query_row(
+                "SELECT version FROM schema_version WHERE id = 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(version, schema::SCHEMA_VERSION);
+        conn.execute_batch("SELECT tag FROM image_tag").unwrap();
+        conn.execute_batch("SELECT json FROM image_pull").unwrap();
+        conn.execute_batch("SELECT json FROM box_metrics_sample")
+            .unwrap();
+        conn.execute_batch("SELECT used_at FROM image_use").unwrap();
+    }
 }
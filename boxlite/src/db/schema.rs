@@ -7,7 +7,10 @@
 //! Each table has queryable columns for efficient filtering + JSON blob for full data.
 
 /// Current schema version.
-pub const SCHEMA_VERSION: i32 = 4;
+pub const SCHEMA_VERSION: i32 = 8;
+
+/// Oldest schema version that can be upgraded in place on open.
+pub const MIN_MIGRATABLE_VERSION: i32 = 2;
 
 /// Schema version tracking table.
 pub const SCHEMA_VERSION_TABLE: &str = r#"
@@ -80,6 +83,65 @@ CREATE TABLE IF NOT EXISTS image_index (
 CREATE INDEX IF NOT EXISTS idx_image_index_manifest_digest ON image_index(manifest_digest);
 "#;
 
+/// Image tag table schema.
+///
+/// Maps local tags (e.g. from `boxlite tag` or `boxlite build`) to the
+/// manifest digest of an image in `image_index`.
+pub const IMAGE_TAG_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS image_tag (
+    tag TEXT PRIMARY KEY NOT NULL,
+    manifest_digest TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_image_tag_manifest_digest ON image_tag(manifest_digest);
+"#;
+
+/// Image pull table schema.
+///
+/// One row per registry pull. JSON blob contains full ImagePullMetrics struct.
+/// Queryable columns: reference, pulled_at (for latest pull per image).
+pub const IMAGE_PULL_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS image_pull (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    reference TEXT NOT NULL,
+    manifest_digest TEXT NOT NULL,
+    pulled_at TEXT NOT NULL,
+    json TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_image_pull_reference ON image_pull(reference, pulled_at);
+"#;
+
+/// Box metrics sample table schema.
+///
+/// Resource usage history, one row per sample. JSON blob contains full
+/// MetricsSample struct. Queryable columns: box_id, sampled_at (unix millis)
+/// for range queries and trimming. Rows go away with their box.
+pub const BOX_METRICS_SAMPLE_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS box_metrics_sample (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    box_id TEXT NOT NULL,
+    sampled_at INTEGER NOT NULL,
+    json TEXT NOT NULL,
+    FOREIGN KEY (box_id) REFERENCES box_config(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_box_metrics_sample_box ON box_metrics_sample(box_id, sampled_at);
+"#;
+
+/// Image use table schema.
+///
+/// When each cached image (by manifest digest) was last used by a pull or a
+/// box. Images without a row were last used when they were cached. Read by
+/// the retention reaper to evict the least recently used images first.
+pub const IMAGE_USE_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS image_use (
+    manifest_digest TEXT PRIMARY KEY NOT NULL,
+    used_at TEXT NOT NULL
+);
+"#;
+
 /// Get all schema creation statements.
 pub fn all_schemas() -> Vec<&'static str> {
     vec![
@@ -88,5 +150,9 @@ pub fn all_schemas() -> Vec<&'static str> {
         BOX_STATE_TABLE,
         ALIVE_TABLE,
         IMAGE_INDEX_TABLE,
+        IMAGE_TAG_TABLE,
+        IMAGE_PULL_TABLE,
+        BOX_METRICS_SAMPLE_TABLE,
+        IMAGE_USE_TABLE,
     ]
 }
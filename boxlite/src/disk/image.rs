@@ -11,6 +11,10 @@ pub enum DiskFormat {
     Ext4,
     /// QCOW2 (QEMU Copy-On-Write v2).
     Qcow2,
+    /// Compressed read-only EROFS image.
+    Erofs,
+    /// Compressed read-only SquashFS image.
+    Squashfs,
 }
 
 impl DiskFormat {
@@ -19,6 +23,8 @@ impl DiskFormat {
         match self {
             DiskFormat::Ext4 => "ext4",
             DiskFormat::Qcow2 => "qcow2",
+            DiskFormat::Erofs => "erofs",
+            DiskFormat::Squashfs => "squashfs",
         }
     }
 }
@@ -38,7 +44,7 @@ impl Disk {
     ///
     /// # Arguments
     /// * `path` - Path to the disk file
-    /// * `format` - Disk format (Ext4 or Qcow2)
+    /// * `format` - Disk format (Ext4, Qcow2, Erofs or Squashfs)
     /// * `persistent` - If true, disk won't be deleted on drop
     pub fn new(path: PathBuf, format: DiskFormat, persistent: bool) -> Self {
         Self {
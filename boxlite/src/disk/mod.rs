@@ -4,13 +4,16 @@
 //! - `Disk` - RAII wrapper for disk image files
 //! - `DiskFormat` - Disk format types (Ext4, Qcow2)
 //! - `create_ext4_from_dir` - Create ext4 filesystem from directory
+//! - `create_readonly_image_from_dir` - Create compressed erofs/squashfs image
 //! - `Qcow2Helper` - QCOW2 copy-on-write disk creation
 
 pub mod constants;
 pub(crate) mod ext4;
 mod image;
 mod qcow2;
+mod readonly;
 
 pub use ext4::create_ext4_from_dir;
 pub use image::{Disk, DiskFormat};
 pub use qcow2::{BackingFormat, Qcow2Helper};
+pub use readonly::{create_readonly_image_from_dir, detect_readonly_format};
@@ -4,7 +4,7 @@
 
 use std::fs::OpenOptions;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use boxlite_shared::errors::{BoxliteError, BoxliteResult};
@@ -20,10 +20,23 @@ struct Qcow2HeaderInfo {
     #[allow(dead_code)]
     version: u32,
     size: u64,
-    #[allow(dead_code)]
     cluster_bits: u32,
+    backing_file_offset: u64,
+    backing_file_size: u32,
 }
 
+/// qcow2 magic number ("QFI\xfb").
+const QCOW2_MAGIC: u32 = 0x514649fb;
+
+/// qcow2 incompatible feature bit: guest data lives in an external file.
+const QCOW2_INCOMPAT_DATA_FILE: u64 = 1 << 2;
+
+/// qcow2 header extension naming the external data file.
+const QCOW2_EXT_DATA_FILE: u32 = 0x4441_5441;
+
+/// Upper bound on backing chain depth, to stop on corrupt images.
+const MAX_BACKING_CHAIN: usize = 64;
+
 /// Helper for qcow2 disk operations.
 pub struct Qcow2Helper;
 
@@ -43,12 +56,29 @@ impl Qcow2Helper {
     /// * `persistent` - If true, disk won't be deleted on drop (used for base disks)
     #[allow(dead_code)]
     pub fn create_disk(&self, disk_path: &Path, persistent: bool) -> BoxliteResult<Disk> {
-        self.create_disk_native(disk_path, persistent)
+        let size_bytes = DEFAULT_DISK_SIZE_GB * 1024 * 1024 * 1024;
+        self.create_disk_native(disk_path, size_bytes, persistent)
+    }
+
+    /// Create an empty sparse qcow2 disk with the given virtual size in bytes.
+    ///
+    /// Used for overlay upper disks, which the guest formats on first boot.
+    pub fn create_disk_with_size(
+        &self,
+        disk_path: &Path,
+        virtual_size: u64,
+        persistent: bool,
+    ) -> BoxliteResult<Disk> {
+        self.create_disk_native(disk_path, virtual_size, persistent)
     }
 
     /// Create a qcow2 disk image using native Rust implementation (qcow2-rs).
-    #[allow(dead_code)]
-    fn create_disk_native(&self, disk_path: &Path, persistent: bool) -> BoxliteResult<Disk> {
+    fn create_disk_native(
+        &self,
+        disk_path: &Path,
+        size_bytes: u64,
+        persistent: bool,
+    ) -> BoxliteResult<Disk> {
         // Ensure parent directory exists
         if let Some(parent) = disk_path.parent() {
             std::fs::create_dir_all(parent).map_err(|e| {
@@ -70,13 +100,11 @@ impl Qcow2Helper {
         }
 
         tracing::info!(
-            "Creating qcow2 disk: {} ({}GB sparse)",
+            "Creating qcow2 disk: {} ({}MB sparse)",
             disk_path.display(),
-            DEFAULT_DISK_SIZE_GB
+            size_bytes / (1024 * 1024)
         );
 
-        let size_bytes = DEFAULT_DISK_SIZE_GB * 1024 * 1024 * 1024;
-
         // Calculate required metadata size
         let (rc_table, rc_block, _l1_table) = Qcow2Header::calculate_meta_params(
             size_bytes,
@@ -246,13 +274,315 @@ impl Qcow2Helper {
         ))
     }
 
+    /// Freeze a qcow2 disk into a shared snapshot and fork it.
+    ///
+    /// Moves `disk_path` to `snapshot_path`, then creates fresh COW overlays
+    /// backed by the snapshot at both `disk_path` and `fork_path`. The
+    /// original owner keeps its data; the fork starts from the same state.
+    pub fn fork_disk(
+        &self,
+        disk_path: &Path,
+        snapshot_path: &Path,
+        fork_path: &Path,
+    ) -> BoxliteResult<()> {
+        let virtual_size = Self::qcow2_virtual_size(disk_path)?;
+
+        if let Some(parent) = snapshot_path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                BoxliteError::Storage(format!(
+                    "Failed to create snapshot directory {}: {}",
+                    parent.display(),
+                    e
+                ))
+            })?;
+        }
+        std::fs::rename(disk_path, snapshot_path).map_err(|e| {
+            BoxliteError::Storage(format!(
+                "Failed to snapshot {} to {}: {}",
+                disk_path.display(),
+                snapshot_path.display(),
+                e
+            ))
+        })?;
+
+        // Re-create the owner's disk on top of the snapshot; on failure put
+        // the original file back so the owner is left untouched.
+        match self.create_cow_child_disk(
+            snapshot_path,
+            BackingFormat::Qcow2,
+            disk_path,
+            virtual_size,
+        ) {
+            Ok(disk) => {
+                disk.leak();
+            }
+            Err(e) => {
+                let _ = std::fs::rename(snapshot_path, disk_path);
+                return Err(e);
+            }
+        }
+
+        match self.create_cow_child_disk(
+            snapshot_path,
+            BackingFormat::Qcow2,
+            fork_path,
+            virtual_size,
+        ) {
+            Ok(fork) => {
+                fork.leak();
+                Ok(())
+            }
+            Err(e) => {
+                let _ = self.unfork_disk(disk_path, snapshot_path, fork_path);
+                Err(e)
+            }
+        }
+    }
+
+    /// Undo [`fork_disk`](Self::fork_disk) before either overlay was written.
+    ///
+    /// Drops the fork and moves the snapshot back over the owner's overlay.
+    pub fn unfork_disk(
+        &self,
+        disk_path: &Path,
+        snapshot_path: &Path,
+        fork_path: &Path,
+    ) -> BoxliteResult<()> {
+        match std::fs::remove_file(fork_path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                return Err(BoxliteError::Storage(format!(
+                    "Failed to remove {}: {}",
+                    fork_path.display(),
+                    e
+                )));
+            }
+            _ => {}
+        }
+        std::fs::rename(snapshot_path, disk_path).map_err(|e| {
+            BoxliteError::Storage(format!(
+                "Failed to restore {} from {}: {}",
+                disk_path.display(),
+                snapshot_path.display(),
+                e
+            ))
+        })
+    }
+
+    /// Collapse the backing chain of a qcow2 disk into the disk itself.
+    ///
+    /// Every layer above `base` is copied into `disk_path`, which is then
+    /// re-pointed at `base`; with no base the disk becomes standalone. Uses
+    /// `qemu-img rebase` in safe mode, which rewrites the disk in place.
+    pub fn flatten_disk(&self, disk_path: &Path, base: Option<&Path>) -> BoxliteResult<()> {
+        tracing::info!(
+            "Flattening disk: {} (base: {:?})",
+            disk_path.display(),
+            base.map(Path::display)
+        );
+
+        let mut cmd = Command::new("qemu-img");
+        cmd.args(["rebase", "-f", "qcow2", "-b"]);
+        match base {
+            Some(base) => {
+                let format = if Self::is_qcow2(base)? {
+                    BackingFormat::Qcow2
+                } else {
+                    BackingFormat::Raw
+                };
+                cmd.arg(base).arg("-F").arg(format.as_str());
+            }
+            None => {
+                cmd.arg("");
+            }
+        }
+        let output = cmd.arg(disk_path).output().map_err(|e| {
+            BoxliteError::Storage(format!("Failed to run qemu-img (is it installed?): {}", e))
+        })?;
+
+        if !output.status.success() {
+            return Err(BoxliteError::Storage(format!(
+                "Failed to flatten disk {}: {}",
+                disk_path.display(),
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+        Ok(())
+    }
+
     /// Get the virtual size of a qcow2 disk image.
-    #[allow(dead_code)]
     pub fn qcow2_virtual_size(path: &Path) -> BoxliteResult<u64> {
         let header = Self::read_qcow2_header(path)?;
         Ok(header.size)
     }
 
+    /// Check whether a file starts with the qcow2 magic.
+    pub fn is_qcow2(path: &Path) -> BoxliteResult<bool> {
+        use std::io::Read;
+
+        let mut file = std::fs::File::open(path).map_err(|e| {
+            BoxliteError::Storage(format!("Failed to open {}: {}", path.display(), e))
+        })?;
+        let mut magic = [0u8; 4];
+        match file.read_exact(&mut magic) {
+            Ok(()) => Ok(u32::from_be_bytes(magic) == QCOW2_MAGIC),
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+            Err(e) => Err(BoxliteError::Storage(format!(
+                "Failed to read {}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    /// Get the backing file of a qcow2 disk, if any.
+    ///
+    /// Relative backing paths are resolved against the disk's directory.
+    pub fn backing_file(path: &Path) -> BoxliteResult<Option<PathBuf>> {
+        use std::io::{Read, Seek, SeekFrom};
+
+        let header = Self::read_qcow2_header(path)?;
+        if header.backing_file_offset == 0 || header.backing_file_size == 0 {
+            return Ok(None);
+        }
+
+        let mut file = std::fs::File::open(path).map_err(|e| {
+            BoxliteError::Storage(format!("Failed to open {}: {}", path.display(), e))
+        })?;
+        let mut buf = vec![0u8; header.backing_file_size as usize];
+        file.seek(SeekFrom::Start(header.backing_file_offset))
+            .and_then(|_| file.read_exact(&mut buf))
+            .map_err(|e| {
+                BoxliteError::Storage(format!(
+                    "Failed to read backing file name from {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+
+        let backing = PathBuf::from(String::from_utf8_lossy(&buf).into_owned());
+        if backing.is_absolute() {
+            Ok(Some(backing))
+        } else {
+            Ok(Some(path.parent().unwrap_or(Path::new(".")).join(backing)))
+        }
+    }
+
+    /// Check whether a qcow2 disk keeps its data in an external file.
+    ///
+    /// Such a disk reads and writes whatever host file its header names, so
+    /// images from another host must not have one.
+    pub fn has_external_data_file(path: &Path) -> BoxliteResult<bool> {
+        use std::io::Read;
+
+        let header = Self::read_qcow2_header(path)?;
+        let cluster_size = 1usize << header.cluster_bits.min(21);
+        let file = std::fs::File::open(path).map_err(|e| {
+            BoxliteError::Storage(format!("Failed to open {}: {}", path.display(), e))
+        })?;
+        // Header extensions live in the first cluster
+        let mut buf = Vec::with_capacity(cluster_size);
+        file.take(cluster_size as u64)
+            .read_to_end(&mut buf)
+            .map_err(|e| {
+                BoxliteError::Storage(format!("Failed to read {}: {}", path.display(), e))
+            })?;
+        let be32 = |at: usize| {
+            buf.get(at..at + 4)
+                .map(|b| u32::from_be_bytes(b.try_into().unwrap()))
+        };
+
+        let mut pos = 72;
+        if header.version >= 3 {
+            let incompatible = u64::from_be_bytes(buf[72..80].try_into().unwrap());
+            if incompatible & QCOW2_INCOMPAT_DATA_FILE != 0 {
+                return Ok(true);
+            }
+            pos = be32(100).map_or(104, |len| len.max(104) as usize);
+        }
+        while let (Some(kind), Some(len)) = (be32(pos), be32(pos + 4)) {
+            match kind {
+                0 => break,
+                QCOW2_EXT_DATA_FILE => return Ok(true),
+                _ => pos += 8 + (len as usize).next_multiple_of(8),
+            }
+        }
+        Ok(false)
+    }
+
+    /// Walk the backing chain of a disk, starting with the disk itself.
+    ///
+    /// Stops at the first file that is not a qcow2 image (e.g. a raw base
+    /// disk) or that has no backing file.
+    pub fn backing_chain(path: &Path) -> BoxliteResult<Vec<PathBuf>> {
+        let mut chain = vec![path.to_path_buf()];
+        let mut current = path.to_path_buf();
+        while Self::is_qcow2(&current)? {
+            let Some(backing) = Self::backing_file(&current)? else {
+                break;
+            };
+            if chain.contains(&backing) || chain.len() > MAX_BACKING_CHAIN {
+                return Err(BoxliteError::Storage(format!(
+                    "Backing chain of {} is too deep or cyclic",
+                    path.display()
+                )));
+            }
+            chain.push(backing.clone());
+            current = backing;
+        }
+        Ok(chain)
+    }
+
+    /// Point a qcow2 disk at a different backing file.
+    ///
+    /// The new path is written in place of the old one, so it must fit in the
+    /// space between the backing file offset and the end of the header cluster.
+    pub fn set_backing_file(path: &Path, backing: &Path) -> BoxliteResult<()> {
+        use std::io::{Seek, SeekFrom};
+
+        let header = Self::read_qcow2_header(path)?;
+        if header.backing_file_offset == 0 {
+            return Err(BoxliteError::Storage(format!(
+                "{} has no backing file to replace",
+                path.display()
+            )));
+        }
+
+        let backing_str = backing.to_string_lossy();
+        let backing_bytes = backing_str.as_bytes();
+        let cluster_size = 1u64 << header.cluster_bits;
+        let old_len = header.backing_file_size as u64;
+        if header.backing_file_offset + backing_bytes.len() as u64 > cluster_size {
+            return Err(BoxliteError::Storage(format!(
+                "Backing path {} is too long for {}",
+                backing.display(),
+                path.display()
+            )));
+        }
+
+        let mut file = OpenOptions::new().write(true).open(path).map_err(|e| {
+            BoxliteError::Storage(format!("Failed to open {}: {}", path.display(), e))
+        })?;
+
+        let mut name = backing_bytes.to_vec();
+        // Clear any leftover bytes from a longer previous path.
+        name.resize(name.len().max(old_len as usize), 0);
+
+        let write = |file: &mut std::fs::File| -> std::io::Result<()> {
+            file.seek(SeekFrom::Start(header.backing_file_offset))?;
+            file.write_all(&name)?;
+            file.seek(SeekFrom::Start(16))?;
+            file.write_all(&(backing_bytes.len() as u32).to_be_bytes())?;
+            file.sync_all()
+        };
+        write(&mut file).map_err(|e| {
+            BoxliteError::Storage(format!(
+                "Failed to rewrite backing file of {}: {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+
     /// Read qcow2 header from disk file.
     #[allow(dead_code)]
     fn read_qcow2_header(path: &Path) -> BoxliteResult<Qcow2HeaderInfo> {
@@ -273,8 +603,7 @@ impl Qcow2Helper {
 
         // Parse qcow2 header (big-endian)
         let magic = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
-        if magic != 0x514649fb {
-            // "QFI\xfb"
+        if magic != QCOW2_MAGIC {
             return Err(BoxliteError::Storage(format!(
                 "Invalid qcow2 magic in {}: 0x{:08x}",
                 path.display(),
@@ -288,11 +617,19 @@ impl Qcow2Helper {
             header[31],
         ]);
         let cluster_bits = u32::from_be_bytes([header[20], header[21], header[22], header[23]]);
+        let backing_file_offset = u64::from_be_bytes([
+            header[8], header[9], header[10], header[11], header[12], header[13], header[14],
+            header[15],
+        ]);
+        let backing_file_size =
+            u32::from_be_bytes([header[16], header[17], header[18], header[19]]);
 
         Ok(Qcow2HeaderInfo {
             version,
             size,
             cluster_bits,
+            backing_file_offset,
+            backing_file_size,
         })
     }
 
@@ -388,7 +725,7 @@ impl Qcow2Helper {
 
         // Write qcow2 v3 header
         // Magic (QFI\xfb)
-        header[0..4].copy_from_slice(&0x514649fbu32.to_be_bytes());
+        header[0..4].copy_from_slice(&QCOW2_MAGIC.to_be_bytes());
         // Version 3
         header[4..8].copy_from_slice(&3u32.to_be_bytes());
         // Backing file offset
@@ -581,3 +918,58 @@ impl BackingFormat {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fork_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let disk = dir.path().join("box/disk.qcow2");
+        let snapshot = dir.path().join("snapshots/fork-disk.qcow2");
+        let fork = dir.path().join("fork/disk.qcow2");
+        std::fs::create_dir_all(disk.parent().unwrap()).unwrap();
+        std::fs::create_dir_all(fork.parent().unwrap()).unwrap();
+
+        let helper = Qcow2Helper::new();
+        helper
+            .create_disk_with_size(&disk, 64 * 1024 * 1024, true)
+            .unwrap();
+        helper.fork_disk(&disk, &snapshot, &fork).unwrap();
+
+        assert!(Qcow2Helper::is_qcow2(&snapshot).unwrap());
+        for overlay in [&disk, &fork] {
+            assert_eq!(
+                Qcow2Helper::backing_file(overlay).unwrap(),
+                Some(snapshot.clone())
+            );
+            assert_eq!(
+                Qcow2Helper::qcow2_virtual_size(overlay).unwrap(),
+                64 * 1024 * 1024
+            );
+        }
+        assert_eq!(Qcow2Helper::backing_file(&snapshot).unwrap(), None);
+
+        // Forking again stacks a second snapshot on the first
+        let second = dir.path().join("snapshots/fork2-disk.qcow2");
+        let fork2 = dir.path().join("fork/disk2.qcow2");
+        helper.fork_disk(&fork, &second, &fork2).unwrap();
+        assert_eq!(
+            Qcow2Helper::backing_chain(&fork2).unwrap(),
+            vec![fork2.clone(), second, snapshot]
+        );
+    }
+
+    #[test]
+    fn test_fork_disk_missing_source_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = Qcow2Helper::new().fork_disk(
+            &dir.path().join("missing.qcow2"),
+            &dir.path().join("snapshot.qcow2"),
+            &dir.path().join("fork.qcow2"),
+        );
+        assert!(result.is_err());
+        assert!(!dir.path().join("fork.qcow2").exists());
+    }
+}
@@ -0,0 +1,224 @@
+//! Compressed read-only filesystem images (EROFS, SquashFS).
+//!
+//! Built from a merged rootfs directory with the host's `mkfs.erofs` or
+//! `mksquashfs`. Boxes mount them read-only under overlayfs, so unlike ext4
+//! there is no free space to size for and no journal.
+
+use crate::util;
+use boxlite_shared::{BoxliteError, BoxliteResult};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::{Disk, DiskFormat};
+
+/// Locate a mkfs tool: runtime directories first, then `$PATH`.
+fn tool_path(name: &str) -> PathBuf {
+    util::find_binary(name).unwrap_or_else(|_| PathBuf::from(name))
+}
+
+/// Whether files must be forced to 0:0 (the source was extracted unprivileged).
+fn needs_root_owner() -> bool {
+    let uid = unsafe { libc::getuid() };
+    let gid = unsafe { libc::getgid() };
+    uid != 0 || gid != 0
+}
+
+/// Build the mkfs command line for `format`.
+fn mkfs_command(
+    format: DiskFormat,
+    source: &Path,
+    output: &Path,
+    all_root: bool,
+) -> BoxliteResult<Command> {
+    let mut cmd = match format {
+        DiskFormat::Erofs => {
+            // https://man7.org/linux/man-pages/man1/mkfs.erofs.1.html
+            // -zlz4hc: LZ4HC compression (fast to decompress, built into
+            //          every EROFS-enabled kernel)
+            let mut cmd = Command::new(tool_path("mkfs.erofs"));
+            cmd.arg("-zlz4hc").arg("--quiet");
+            if all_root {
+                cmd.arg("--all-root");
+            }
+            cmd.arg(output).arg(source);
+            cmd
+        }
+        DiskFormat::Squashfs => {
+            // gzip is the only compressor every SquashFS kernel supports
+            let mut cmd = Command::new(tool_path("mksquashfs"));
+            cmd.arg(source).arg(output).args([
+                "-comp",
+                "gzip",
+                "-noappend",
+                "-no-progress",
+                "-quiet",
+            ]);
+            if all_root {
+                cmd.arg("-all-root");
+            }
+            cmd
+        }
+        other => {
+            return Err(BoxliteError::Internal(format!(
+                "{} is not a read-only image format",
+                other.as_str()
+            )));
+        }
+    };
+    cmd.stdout(std::process::Stdio::null());
+    Ok(cmd)
+}
+
+/// Create a compressed read-only image (`Erofs` or `Squashfs`) from a directory.
+///
+/// Ownership is preserved when running as root; otherwise every file is
+/// owned by 0:0, matching `create_ext4_from_dir`.
+///
+/// Returns a non-persistent Disk (will be cleaned up on drop).
+pub fn create_readonly_image_from_dir(
+    source: &Path,
+    output_path: &Path,
+    format: DiskFormat,
+) -> BoxliteResult<Disk> {
+    let start = std::time::Instant::now();
+    let mut cmd = mkfs_command(format, source, output_path, needs_root_owner())?;
+
+    let output = cmd.output().map_err(|e| {
+        BoxliteError::Storage(format!(
+            "Failed to run {:?} (install {} to use the {} rootfs format): {}",
+            cmd.get_program(),
+            match format {
+                DiskFormat::Erofs => "erofs-utils",
+                _ => "squashfs-tools",
+            },
+            format.as_str(),
+            e
+        ))
+    })?;
+
+    // Disk owns the output from here so a failed build is cleaned up
+    let disk = Disk::new(output_path.to_path_buf(), format, false);
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(BoxliteError::Storage(format!(
+            "{:?} failed with exit code {:?}: {}",
+            cmd.get_program(),
+            output.status.code(),
+            stderr.trim()
+        )));
+    }
+
+    tracing::info!(
+        "Built {} image {} in {:?}",
+        format.as_str(),
+        output_path.display(),
+        start.elapsed()
+    );
+
+    Ok(disk)
+}
+
+/// EROFS superblock magic, little-endian at byte offset 1024.
+const EROFS_MAGIC: u32 = 0xE0F5_E1E2;
+/// SquashFS superblock magic (`hsqs`) at byte offset 0.
+const SQUASHFS_MAGIC: &[u8; 4] = b"hsqs";
+
+/// Identify a read-only image by its superblock magic.
+///
+/// Returns `None` for anything that is neither EROFS nor SquashFS.
+pub fn detect_readonly_format(path: &Path) -> BoxliteResult<Option<DiskFormat>> {
+    use std::io::Read;
+
+    let mut header = Vec::with_capacity(1028);
+    std::fs::File::open(path)
+        .and_then(|f| f.take(1028).read_to_end(&mut header))
+        .map_err(|e| {
+            BoxliteError::Storage(format!("Failed to read image {}: {}", path.display(), e))
+        })?;
+
+    if header.len() >= 4 && &header[..4] == SQUASHFS_MAGIC {
+        return Ok(Some(DiskFormat::Squashfs));
+    }
+    if header.len() >= 1028
+        && u32::from_le_bytes([header[1024], header[1025], header[1026], header[1027]])
+            == EROFS_MAGIC
+    {
+        return Ok(Some(DiskFormat::Erofs));
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(cmd: &Command) -> Vec<String> {
+        cmd.get_args()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_mkfs_command_erofs() {
+        let cmd = mkfs_command(
+            DiskFormat::Erofs,
+            Path::new("/src"),
+            Path::new("/out.erofs"),
+            true,
+        )
+        .unwrap();
+        assert_eq!(
+            args(&cmd),
+            ["-zlz4hc", "--quiet", "--all-root", "/out.erofs", "/src"]
+        );
+    }
+
+    #[test]
+    fn test_mkfs_command_squashfs() {
+        let cmd = mkfs_command(
+            DiskFormat::Squashfs,
+            Path::new("/src"),
+            Path::new("/out.squashfs"),
+            false,
+        )
+        .unwrap();
+        let args = args(&cmd);
+        assert_eq!(&args[..2], ["/src", "/out.squashfs"]);
+        assert!(!args.contains(&"-all-root".to_string()));
+    }
+
+    #[test]
+    fn test_detect_readonly_format() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let squashfs = dir.path().join("a.img");
+        let mut data = vec![0u8; 4096];
+        data[..4].copy_from_slice(SQUASHFS_MAGIC);
+        std::fs::write(&squashfs, &data).unwrap();
+        assert_eq!(
+            detect_readonly_format(&squashfs).unwrap(),
+            Some(DiskFormat::Squashfs)
+        );
+
+        let erofs = dir.path().join("b.img");
+        let mut data = vec![0u8; 4096];
+        data[1024..1028].copy_from_slice(&EROFS_MAGIC.to_le_bytes());
+        std::fs::write(&erofs, &data).unwrap();
+        assert_eq!(
+            detect_readonly_format(&erofs).unwrap(),
+            Some(DiskFormat::Erofs)
+        );
+
+        let other = dir.path().join("c.img");
+        std::fs::write(&other, [0u8; 16]).unwrap();
+        assert_eq!(detect_readonly_format(&other).unwrap(), None);
+    }
+
+    #[test]
+    fn test_mkfs_command_rejects_writable_formats() {
+        assert!(
+            mkfs_command(DiskFormat::Ext4, Path::new("/src"), Path::new("/out"), true).is_err()
+        );
+    }
+}
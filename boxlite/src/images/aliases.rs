@@ -0,0 +1,136 @@
+//! Short-name aliases.
+//!
+//! Maps an unqualified image name to a fully qualified repository, like the
+//! `[aliases]` table of containers-registries.conf:
+//!
+//! ```text
+//! ubuntu       → docker.io/library/ubuntu
+//! internal/app → registry.corp/app
+//! ```
+//!
+//! An alias replaces the registry search for that name: `ubuntu:24.04` is
+//! pulled as `docker.io/library/ubuntu:24.04` only. The tag or digest of the
+//! requested image is kept; names without an alias are searched as before.
+
+use std::collections::HashMap;
+
+use boxlite_shared::{BoxliteError, BoxliteResult};
+use oci_client::Reference;
+
+use super::is_fully_qualified;
+
+/// Aliases keyed by short name.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ShortNameAliases {
+    aliases: HashMap<String, String>,
+}
+
+impl ShortNameAliases {
+    /// Check and store `config` (short name → qualified repository).
+    ///
+    /// Short names must be unqualified and carry no tag or digest; targets
+    /// must name a registry and carry no tag or digest either.
+    pub(crate) fn new(config: &HashMap<String, String>) -> BoxliteResult<Self> {
+        for (name, target) in config {
+            let (bare, suffix) = split_name(name);
+            if bare.is_empty() || !suffix.is_empty() || is_fully_qualified(name) {
+                return Err(BoxliteError::Config(format!(
+                    "invalid short-name alias '{}': expected an unqualified name without tag or digest",
+                    name
+                )));
+            }
+            let (_, suffix) = split_name(target);
+            if !suffix.is_empty()
+                || !is_fully_qualified(target)
+                || target.parse::<Reference>().is_err()
+            {
+                return Err(BoxliteError::Config(format!(
+                    "invalid target '{}' for short-name alias '{}': expected a registry and repository without tag or digest",
+                    target, name
+                )));
+            }
+        }
+        Ok(Self {
+            aliases: config.clone(),
+        })
+    }
+
+    /// `image_ref` with its name replaced by the alias target, if one matches.
+    pub(crate) fn resolve(&self, image_ref: &str) -> Option<String> {
+        if is_fully_qualified(image_ref) {
+            return None;
+        }
+        let (name, suffix) = split_name(image_ref);
+        let target = self.aliases.get(name)?;
+        tracing::debug!(image_ref = %image_ref, alias = %target, "Resolved short-name alias");
+        Some(format!("{}{}", target, suffix))
+    }
+}
+
+/// Split `image_ref` into its name and the `:tag` / `@digest` suffix.
+fn split_name(image_ref: &str) -> (&str, &str) {
+    let name_end = image_ref.find('@').unwrap_or(image_ref.len());
+    let last_slash = image_ref[..name_end].rfind('/').map_or(0, |i| i + 1);
+    let end = image_ref[last_slash..name_end]
+        .find(':')
+        .map_or(name_end, |i| last_slash + i);
+    image_ref.split_at(end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aliases(pairs: &[(&str, &str)]) -> BoxliteResult<ShortNameAliases> {
+        let config = pairs
+            .iter()
+            .map(|(name, target)| (name.to_string(), target.to_string()))
+            .collect();
+        ShortNameAliases::new(&config)
+    }
+
+    #[test]
+    fn test_resolve_keeps_tag_and_digest() {
+        let a = aliases(&[
+            ("ubuntu", "docker.io/library/ubuntu"),
+            ("internal/app", "registry.corp:5000/app"),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            a.resolve("ubuntu").as_deref(),
+            Some("docker.io/library/ubuntu")
+        );
+        assert_eq!(
+            a.resolve("ubuntu:24.04").as_deref(),
+            Some("docker.io/library/ubuntu:24.04")
+        );
+        assert_eq!(
+            a.resolve("internal/app@sha256:abc").as_deref(),
+            Some("registry.corp:5000/app@sha256:abc")
+        );
+        assert_eq!(a.resolve("alpine"), None);
+        assert_eq!(a.resolve("library/ubuntu"), None);
+        assert_eq!(a.resolve("ghcr.io/ubuntu"), None);
+    }
+
+    #[test]
+    fn test_invalid_aliases_rejected() {
+        assert!(aliases(&[("ubuntu:24.04", "docker.io/library/ubuntu")]).is_err());
+        assert!(aliases(&[("docker.io/ubuntu", "docker.io/library/ubuntu")]).is_err());
+        assert!(aliases(&[("ubuntu", "library/ubuntu")]).is_err());
+        assert!(aliases(&[("ubuntu", "docker.io/library/ubuntu:24.04")]).is_err());
+        assert!(aliases(&[("", "docker.io/library/ubuntu")]).is_err());
+    }
+
+    #[test]
+    fn test_split_name() {
+        assert_eq!(split_name("alpine"), ("alpine", ""));
+        assert_eq!(split_name("alpine:3.19"), ("alpine", ":3.19"));
+        assert_eq!(
+            split_name("localhost:5000/app:v1"),
+            ("localhost:5000/app", ":v1")
+        );
+        assert_eq!(split_name("app@sha256:abc"), ("app", "@sha256:abc"));
+    }
+}
@@ -1,9 +1,11 @@
 //! Archive helpers (containerd-style apply).
 //!
 //! Mirrors containerd's layout: `tar` module contains the streaming layer apply,
-//! `time` provides time helpers, `override_stat` provides rootless container support.
+//! `time` provides time helpers, `override_stat` provides rootless container support,
+//! `readahead` decompresses on a background thread while entries are applied.
 
 mod override_stat;
+mod readahead;
 mod tar;
 mod time;
 
@@ -0,0 +1,150 @@
+//! Background read-ahead for layer decompression.
+//!
+//! Gzip decoding is CPU-bound and single-threaded, and so is applying tar
+//! entries (file writes, chown, xattrs). `ReadAhead` runs the decoder on its
+//! own thread and hands decompressed chunks over a bounded channel, so both
+//! halves of a layer apply progress concurrently.
+
+use std::io::{self, Read};
+use std::sync::mpsc::{Receiver, SyncSender, sync_channel};
+use std::thread::JoinHandle;
+
+/// Size of each decompressed chunk handed to the reader.
+const CHUNK_SIZE: usize = 1024 * 1024;
+/// Chunks buffered ahead of the reader (bounds memory to ~8 MiB).
+const CHUNKS_AHEAD: usize = 8;
+
+/// `Read` adapter that pulls from `inner` on a background thread.
+pub(super) struct ReadAhead {
+    chunks: Receiver<io::Result<Vec<u8>>>,
+    current: Vec<u8>,
+    pos: usize,
+    done: bool,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl ReadAhead {
+    /// Start reading `inner` on a background thread.
+    pub(super) fn spawn<R: Read + Send + 'static>(inner: R) -> io::Result<Self> {
+        let (tx, rx) = sync_channel(CHUNKS_AHEAD);
+        let worker = std::thread::Builder::new()
+            .name("layer-decompress".to_string())
+            .spawn(move || fill(inner, tx))?;
+
+        Ok(Self {
+            chunks: rx,
+            current: Vec::new(),
+            pos: 0,
+            done: false,
+            worker: Some(worker),
+        })
+    }
+}
+
+/// Read `inner` to EOF, sending chunks until the reader goes away.
+fn fill<R: Read>(mut inner: R, tx: SyncSender<io::Result<Vec<u8>>>) {
+    loop {
+        let mut chunk = vec![0u8; CHUNK_SIZE];
+        let mut filled = 0;
+        while filled < CHUNK_SIZE {
+            match inner.read(&mut chunk[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    let _ = tx.send(Err(e));
+                    return;
+                }
+            }
+        }
+        if filled == 0 {
+            return;
+        }
+        chunk.truncate(filled);
+        if tx.send(Ok(chunk)).is_err() {
+            // Reader dropped (extraction failed or finished early)
+            return;
+        }
+    }
+}
+
+impl Read for ReadAhead {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.current.len() {
+            if self.done {
+                return Ok(0);
+            }
+            match self.chunks.recv() {
+                Ok(Ok(chunk)) => {
+                    self.current = chunk;
+                    self.pos = 0;
+                }
+                Ok(Err(e)) => {
+                    self.done = true;
+                    return Err(e);
+                }
+                // Worker finished: EOF
+                Err(_) => {
+                    self.done = true;
+                    return Ok(0);
+                }
+            }
+        }
+
+        let n = buf.len().min(self.current.len() - self.pos);
+        buf[..n].copy_from_slice(&self.current[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+impl Drop for ReadAhead {
+    fn drop(&mut self) {
+        // Disconnect first: a worker blocked on a full channel then fails its
+        // send and exits, so the join can't hang.
+        let (_, rx) = sync_channel(0);
+        drop(std::mem::replace(&mut self.chunks, rx));
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_ahead_matches_source() {
+        let data: Vec<u8> = (0..CHUNK_SIZE * 3 + 17).map(|i| (i % 251) as u8).collect();
+        let mut reader = ReadAhead::spawn(io::Cursor::new(data.clone())).unwrap();
+
+        let mut out = Vec::new();
+        reader.read_to_end(&mut out).unwrap();
+        assert_eq!(out, data);
+    }
+
+    #[test]
+    fn test_read_ahead_propagates_errors() {
+        struct Failing;
+        impl Read for Failing {
+            fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::InvalidData, "corrupt gzip"))
+            }
+        }
+
+        let mut reader = ReadAhead::spawn(Failing).unwrap();
+        let err = reader.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_drop_before_eof_stops_worker() {
+        let data = vec![7u8; CHUNK_SIZE * (CHUNKS_AHEAD + 4)];
+        let mut reader = ReadAhead::spawn(io::Cursor::new(data)).unwrap();
+        let mut buf = [0u8; 16];
+        reader.read_exact(&mut buf).unwrap();
+        // Must not hang joining a worker blocked on the full channel
+        drop(reader);
+    }
+}
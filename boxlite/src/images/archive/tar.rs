@@ -18,6 +18,7 @@ use tracing::{debug, trace, warn};
 use walkdir::WalkDir;
 
 use super::override_stat::{OverrideFileType, OverrideStat};
+use super::readahead::ReadAhead;
 use super::time::{bound_time, latest_time};
 
 /// Apply a gzip-compressed OCI layer tarball into `dest`, preserving metadata.
@@ -52,7 +53,11 @@ pub fn extract_layer_tarball_streaming(tarball_path: &Path, dest: &Path) -> Boxl
                 e
             ))
         })?;
-        Box::new(GzDecoder::new(BufReader::new(file)))
+        // Decompress on a background thread while entries are applied here
+        let decoder = GzDecoder::new(BufReader::new(file));
+        Box::new(ReadAhead::spawn(decoder).map_err(|e| {
+            BoxliteError::Storage(format!("Failed to start layer decompression: {}", e))
+        })?)
     } else {
         // Uncompressed
         debug!(
@@ -0,0 +1,148 @@
+//! Registry credentials.
+//!
+//! BoxLite has no login command of its own; it reuses the credentials saved
+//! by `docker login` / `podman login` in a Docker-style `config.json`:
+//!
+//! 1. `$BOXLITE_REGISTRY_AUTH_FILE`
+//! 2. `$REGISTRY_AUTH_FILE` (podman)
+//! 3. `$DOCKER_CONFIG/config.json`
+//! 4. `~/.docker/config.json`
+//!
+//! Only inline `auths` entries are supported (not credential helpers).
+//! Registries without an entry are accessed anonymously.
+
+use std::path::PathBuf;
+
+use base64::Engine;
+use oci_client::secrets::RegistryAuth;
+use serde::Deserialize;
+
+/// Docker Hub stores its credentials under this legacy key.
+const DOCKER_HUB_AUTH_KEY: &str = "https://index.docker.io/v1/";
+
+#[derive(Debug, Default, Deserialize)]
+struct AuthFile {
+    #[serde(default)]
+    auths: std::collections::HashMap<String, AuthEntry>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct AuthEntry {
+    #[serde(default)]
+    auth: Option<String>,
+    #[serde(default)]
+    username: Option<String>,
+    #[serde(default)]
+    password: Option<String>,
+}
+
+/// Resolve credentials for `registry` (as returned by `Reference::resolve_registry`).
+pub(crate) fn registry_auth(registry: &str) -> RegistryAuth {
+    let Some(path) = auth_file_path() else {
+        return RegistryAuth::Anonymous;
+    };
+    let file = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(_) => return RegistryAuth::Anonymous,
+    };
+    match serde_json::from_str::<AuthFile>(&file) {
+        Ok(auth_file) => lookup(&auth_file, registry),
+        Err(e) => {
+            tracing::warn!(path = %path.display(), error = %e, "Ignoring unreadable registry auth file");
+            RegistryAuth::Anonymous
+        }
+    }
+}
+
+fn auth_file_path() -> Option<PathBuf> {
+    for var in ["BOXLITE_REGISTRY_AUTH_FILE", "REGISTRY_AUTH_FILE"] {
+        if let Some(path) = std::env::var_os(var) {
+            return Some(PathBuf::from(path));
+        }
+    }
+    if let Some(dir) = std::env::var_os("DOCKER_CONFIG") {
+        return Some(PathBuf::from(dir).join("config.json"));
+    }
+    dirs::home_dir().map(|home| home.join(".docker").join("config.json"))
+}
+
+fn lookup(auth_file: &AuthFile, registry: &str) -> RegistryAuth {
+    let wanted = normalize_registry(registry);
+    let entry = auth_file
+        .auths
+        .iter()
+        .find(|(key, _)| normalize_registry(key) == wanted)
+        .map(|(_, entry)| entry);
+
+    let Some(entry) = entry else {
+        return RegistryAuth::Anonymous;
+    };
+    if let (Some(username), Some(password)) = (&entry.username, &entry.password) {
+        return RegistryAuth::Basic(username.clone(), password.clone());
+    }
+    let decoded = entry
+        .auth
+        .as_deref()
+        .and_then(|auth| base64::engine::general_purpose::STANDARD.decode(auth).ok())
+        .and_then(|bytes| String::from_utf8(bytes).ok());
+    match decoded.as_deref().and_then(|s| s.split_once(':')) {
+        Some((username, password)) => RegistryAuth::Basic(username.into(), password.into()),
+        None => RegistryAuth::Anonymous,
+    }
+}
+
+/// Reduce an auth key or registry name to a bare host, folding Docker Hub aliases.
+pub(super) fn normalize_registry(key: &str) -> String {
+    if key == DOCKER_HUB_AUTH_KEY {
+        return "docker.io".to_string();
+    }
+    let host = key
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .split('/')
+        .next()
+        .unwrap_or_default();
+    match host {
+        "index.docker.io" | "registry-1.docker.io" => "docker.io".to_string(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auth_file(json: &str) -> AuthFile {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_lookup_encoded_auth() {
+        let file = auth_file(r#"{"auths": {"ghcr.io": {"auth": "dXNlcjpzM2NyZXQ="}}}"#);
+        assert_eq!(
+            lookup(&file, "ghcr.io"),
+            RegistryAuth::Basic("user".into(), "s3cret".into())
+        );
+        assert_eq!(lookup(&file, "quay.io"), RegistryAuth::Anonymous);
+    }
+
+    #[test]
+    fn test_lookup_docker_hub_aliases() {
+        let file = auth_file(
+            r#"{"auths": {"https://index.docker.io/v1/": {"username": "me", "password": "pw"}}}"#,
+        );
+        assert_eq!(
+            lookup(&file, "index.docker.io"),
+            RegistryAuth::Basic("me".into(), "pw".into())
+        );
+    }
+
+    #[test]
+    fn test_normalize_registry() {
+        assert_eq!(
+            normalize_registry("https://localhost:5000/v2/"),
+            "localhost:5000"
+        );
+        assert_eq!(normalize_registry("registry-1.docker.io"), "docker.io");
+    }
+}
@@ -86,6 +86,21 @@ impl BlobSource {
         .map_err(|e| BoxliteError::Internal(format!("Extract layers task failed: {}", e)))?
     }
 
+    /// Get a single extracted layer path, extracting if needed.
+    ///
+    /// Lets callers consume layers in order while later ones are still being
+    /// extracted (see `ImageObject::layer_extracted_pipelined`).
+    pub async fn extract_layer(&self, digest: &str) -> BoxliteResult<PathBuf> {
+        let source = self.clone();
+        let digest = digest.to_string();
+        tokio::task::spawn_blocking(move || match &source {
+            Self::Store(s) => s.extract_layer(&digest),
+            Self::LocalBundle(l) => l.extract_layer(&digest),
+        })
+        .await
+        .map_err(|e| BoxliteError::Internal(format!("Extract layer task failed: {}", e)))?
+    }
+
     /// Get cached disk image if available.
     pub fn disk_image(&self, image_digest: &str) -> Option<Disk> {
         match self {
@@ -94,6 +109,15 @@ impl BlobSource {
         }
     }
 
+    /// Get cached disk image of a specific format if available.
+    pub fn disk_image_with_format(&self, image_digest: &str, format: DiskFormat) -> Option<Disk> {
+        let path = match self {
+            Self::Store(s) => s.storage.disk_image_path(image_digest, format),
+            Self::LocalBundle(l) => l.disk_image_path(image_digest, format),
+        };
+        path.exists().then(|| Disk::new(path, format, true))
+    }
+
     /// Install disk image to source-specific cache.
     ///
     /// This method is async because `fs::rename` can block on network filesystems
@@ -149,24 +173,27 @@ impl StoreBlobSource {
 
         digests
             .par_iter()
-            .map(|digest| {
-                let tarball_path = self.storage.layer_tarball_path(digest);
-                let extracted_path = self.storage.layer_extracted_path(digest);
-
-                // Check if already extracted
-                if extracted_path.exists() {
-                    tracing::debug!("Using cached extracted layer: {}", digest);
-                    return Ok(extracted_path);
-                }
-
-                // Extract layer
-                tracing::debug!("Extracting layer: {}", digest);
-                self.storage.extract_layer(digest, &tarball_path)?;
-                Ok(extracted_path)
-            })
+            .map(|digest| self.extract_layer(digest))
             .collect()
     }
 
+    /// Get a single extracted layer path, extracting if needed.
+    pub fn extract_layer(&self, digest: &str) -> BoxliteResult<PathBuf> {
+        let tarball_path = self.storage.layer_tarball_path(digest);
+        let extracted_path = self.storage.layer_extracted_path(digest);
+
+        // Check if already extracted
+        if extracted_path.exists() {
+            tracing::debug!("Using cached extracted layer: {}", digest);
+            return Ok(extracted_path);
+        }
+
+        // Extract layer
+        tracing::debug!("Extracting layer: {}", digest);
+        self.storage.extract_layer(digest, &tarball_path)?;
+        Ok(extracted_path)
+    }
+
     /// Get cached disk image if available.
     pub fn disk_image(&self, image_digest: &str) -> Option<Disk> {
         self.storage
@@ -281,6 +308,14 @@ impl LocalBundleBlobSource {
     pub fn extract_layers(&self, digests: &[String]) -> BoxliteResult<Vec<PathBuf>> {
         use rayon::prelude::*;
 
+        digests
+            .par_iter()
+            .map(|digest| self.extract_layer(digest))
+            .collect()
+    }
+
+    /// Get a single extracted layer path, extracting if needed.
+    pub fn extract_layer(&self, digest: &str) -> BoxliteResult<PathBuf> {
         // Ensure cache directory exists
         let extracted_dir = self.cache_dir.join("extracted");
         std::fs::create_dir_all(&extracted_dir).map_err(|e| {
@@ -291,24 +326,19 @@ impl LocalBundleBlobSource {
             ))
         })?;
 
-        digests
-            .par_iter()
-            .map(|digest| {
-                let tarball_path = self.layer_tarball_path(digest);
-                let extracted_path = self.extracted_path(digest);
-
-                // Check if already extracted
-                if extracted_path.exists() {
-                    tracing::debug!("Using cached extracted layer (local): {}", digest);
-                    return Ok(extracted_path);
-                }
+        let tarball_path = self.layer_tarball_path(digest);
+        let extracted_path = self.extracted_path(digest);
 
-                // Extract layer using atomic temp directory pattern
-                tracing::debug!("Extracting layer (local bundle): {}", digest);
-                self.extract_layer_atomic(digest, &tarball_path, &extracted_path)?;
-                Ok(extracted_path)
-            })
-            .collect()
+        // Check if already extracted
+        if extracted_path.exists() {
+            tracing::debug!("Using cached extracted layer (local): {}", digest);
+            return Ok(extracted_path);
+        }
+
+        // Extract layer using atomic temp directory pattern
+        tracing::debug!("Extracting layer (local bundle): {}", digest);
+        self.extract_layer_atomic(digest, &tarball_path, &extracted_path)?;
+        Ok(extracted_path)
     }
 
     /// Extract layer with atomic temp directory pattern.
@@ -717,6 +747,26 @@ mod tests {
         assert_eq!(extracted1, extracted2);
     }
 
+    #[tokio::test]
+    async fn test_extract_single_layer_matches_batch() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let bundle_dir = temp_dir.path().join("bundle");
+        let cache_dir = temp_dir.path().join("images/local/test-cache");
+
+        let (layer_digest, _config_digest) = test_fixtures::create_test_oci_bundle(&bundle_dir);
+
+        let source = BlobSource::LocalBundle(LocalBundleBlobSource::new(bundle_dir, cache_dir));
+
+        let single = source.extract_layer(&layer_digest).await.unwrap();
+        assert!(single.join("test.txt").exists());
+
+        let batch = source
+            .extract_layers(std::slice::from_ref(&layer_digest))
+            .await
+            .unwrap();
+        assert_eq!(batch, vec![single]);
+    }
+
     #[test]
     fn test_store_and_local_cache_isolation() {
         let temp_dir = tempfile::tempdir().unwrap();
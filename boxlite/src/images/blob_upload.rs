@@ -0,0 +1,481 @@
+//! Streaming blob uploads for `push`.
+//!
+//! `oci_client::Client::push_blob` takes the whole blob as a byte slice, so
+//! pushing a layer that way holds all of it in memory. Layers are instead
+//! sent straight from the file with the OCI chunked upload protocol, one
+//! chunk in memory at a time:
+//!
+//! 1. `POST /v2/<name>/blobs/uploads/` opens an upload session
+//! 2. `PATCH <location>` sends each chunk with its `Content-Range`
+//! 3. `PUT <location>?digest=<digest>` closes the session
+//!
+//! Registries that do not take chunks get the file as one streamed `PUT`.
+//! Authentication follows the registry's `WWW-Authenticate` challenge, with
+//! the credentials from the auth file (see [`super::auth`]).
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use base64::Engine;
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+use bytes::Bytes;
+use oci_client::secrets::RegistryAuth;
+use reqwest::header::{AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, LOCATION, WWW_AUTHENTICATE};
+use reqwest::{Client, RequestBuilder, Response, StatusCode, Url};
+use tokio::io::AsyncReadExt;
+
+/// Bytes sent per `PATCH` request.
+const CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+/// Uploads blobs to one registry.
+pub(crate) struct BlobUploader {
+    client: Client,
+    base: Url,
+    auth: RegistryAuth,
+    chunk_size: usize,
+    authorization: tokio::sync::Mutex<Option<String>>,
+}
+
+impl BlobUploader {
+    /// `base` is the registry root, e.g. `https://ghcr.io/`.
+    pub(crate) fn new(client: Client, base: Url, auth: RegistryAuth) -> Self {
+        Self {
+            client,
+            base,
+            auth,
+            chunk_size: CHUNK_SIZE,
+            authorization: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    /// Upload the file at `path` as blob `digest` of `repository`.
+    pub(crate) async fn upload(
+        &self,
+        repository: &str,
+        digest: &str,
+        path: &Path,
+    ) -> BoxliteResult<()> {
+        let mut file = tokio::fs::File::open(path).await.map_err(|e| {
+            BoxliteError::Storage(format!("failed to open {}: {}", path.display(), e))
+        })?;
+        let len = file
+            .metadata()
+            .await
+            .map_err(|e| {
+                BoxliteError::Storage(format!("failed to stat {}: {}", path.display(), e))
+            })?
+            .len();
+
+        let mut location = self.begin(repository).await?;
+        let mut buf = vec![0u8; self.chunk_size];
+        let mut start = 0u64;
+        while start < len {
+            let filled = read_chunk(&mut file, &mut buf).await.map_err(|e| {
+                BoxliteError::Storage(format!("failed to read {}: {}", path.display(), e))
+            })?;
+            if filled == 0 {
+                break;
+            }
+            let chunk = Bytes::copy_from_slice(&buf[..filled]);
+            let end = start + filled as u64 - 1;
+            let response = self
+                .send(repository, || {
+                    self.client
+                        .patch(location.clone())
+                        .header("Content-Range", format!("{}-{}", start, end))
+                        .header(CONTENT_LENGTH, filled)
+                        .header(CONTENT_TYPE, "application/octet-stream")
+                        .body(chunk.clone())
+                })
+                .await?;
+
+            if response.status() != StatusCode::ACCEPTED {
+                if start == 0 && takes_no_chunks(response.status()) {
+                    tracing::warn!(
+                        status = %response.status(),
+                        "Registry does not accept chunked uploads, sending {} in one request",
+                        digest
+                    );
+                    return self.upload_monolithic(repository, digest, path, len).await;
+                }
+                return Err(upload_error(digest, response).await);
+            }
+            location = self.location(&response)?;
+            start = end + 1;
+        }
+
+        let mut url = location;
+        url.query_pairs_mut().append_pair("digest", digest);
+        let response = self
+            .send(repository, || {
+                self.client.put(url.clone()).header(CONTENT_LENGTH, 0)
+            })
+            .await?;
+        if response.status() != StatusCode::CREATED {
+            return Err(upload_error(digest, response).await);
+        }
+        Ok(())
+    }
+
+    /// Upload the whole file with a single streamed `PUT`.
+    async fn upload_monolithic(
+        &self,
+        repository: &str,
+        digest: &str,
+        path: &Path,
+        len: u64,
+    ) -> BoxliteResult<()> {
+        // Opening the session answers any auth challenge, which matters
+        // because a streamed body cannot be replayed after a 401.
+        let mut url = self.begin(repository).await?;
+        url.query_pairs_mut().append_pair("digest", digest);
+
+        let file = tokio::fs::File::open(path).await.map_err(|e| {
+            BoxliteError::Storage(format!("failed to open {}: {}", path.display(), e))
+        })?;
+        let mut request = self
+            .client
+            .put(url)
+            .header(CONTENT_LENGTH, len)
+            .header(CONTENT_TYPE, "application/octet-stream");
+        if let Some(authorization) = self.authorization.lock().await.clone() {
+            request = request.header(AUTHORIZATION, authorization);
+        }
+        let response =
+            request.body(file).send().await.map_err(|e| {
+                BoxliteError::Network(format!("failed to push blob {}: {}", digest, e))
+            })?;
+        if response.status() != StatusCode::CREATED {
+            return Err(upload_error(digest, response).await);
+        }
+        Ok(())
+    }
+
+    /// Open an upload session and return its location.
+    async fn begin(&self, repository: &str) -> BoxliteResult<Url> {
+        let url = self
+            .base
+            .join(&format!("v2/{}/blobs/uploads/", repository))
+            .map_err(|e| BoxliteError::InvalidArgument(format!("invalid repository: {}", e)))?;
+        let response = self
+            .send(repository, || {
+                self.client.post(url.clone()).header(CONTENT_LENGTH, 0)
+            })
+            .await?;
+        if response.status() != StatusCode::ACCEPTED {
+            return Err(upload_error(repository, response).await);
+        }
+        self.location(&response)
+    }
+
+    /// Resolve the `Location` header of an upload response.
+    fn location(&self, response: &Response) -> BoxliteResult<Url> {
+        let location = response
+            .headers()
+            .get(LOCATION)
+            .and_then(|value| value.to_str().ok())
+            .ok_or_else(|| BoxliteError::Network("registry sent no upload location".to_string()))?;
+        // Relative locations are relative to the URL that was requested.
+        response.url().join(location).map_err(|e| {
+            BoxliteError::Network(format!("invalid upload location '{}': {}", location, e))
+        })
+    }
+
+    /// Send a request, answering one authentication challenge if needed.
+    async fn send(
+        &self,
+        repository: &str,
+        build: impl Fn() -> RequestBuilder,
+    ) -> BoxliteResult<Response> {
+        let mut retried = false;
+        loop {
+            let mut request = build();
+            if let Some(authorization) = self.authorization.lock().await.clone() {
+                request = request.header(AUTHORIZATION, authorization);
+            }
+            let response = request
+                .send()
+                .await
+                .map_err(|e| BoxliteError::Network(format!("registry request failed: {}", e)))?;
+            if response.status() != StatusCode::UNAUTHORIZED || retried {
+                return Ok(response);
+            }
+            let challenge = response
+                .headers()
+                .get(WWW_AUTHENTICATE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_challenge)
+                .ok_or_else(|| {
+                    BoxliteError::Network("registry refused the request (401)".to_string())
+                })?;
+            let authorization = self.authenticate(repository, challenge).await?;
+            *self.authorization.lock().await = Some(authorization);
+            retried = true;
+        }
+    }
+
+    /// Turn a `WWW-Authenticate` challenge into an `Authorization` value.
+    async fn authenticate(
+        &self,
+        repository: &str,
+        (scheme, params): (String, HashMap<String, String>),
+    ) -> BoxliteResult<String> {
+        let credentials = match &self.auth {
+            RegistryAuth::Basic(username, password) => Some((username, password)),
+            _ => None,
+        };
+        if scheme.eq_ignore_ascii_case("basic") {
+            let (username, password) = credentials.ok_or_else(|| {
+                BoxliteError::Network("registry requires credentials to push".to_string())
+            })?;
+            let encoded = base64::engine::general_purpose::STANDARD
+                .encode(format!("{}:{}", username, password));
+            return Ok(format!("Basic {}", encoded));
+        }
+        if !scheme.eq_ignore_ascii_case("bearer") {
+            return Err(BoxliteError::Network(format!(
+                "unsupported registry auth scheme '{}'",
+                scheme
+            )));
+        }
+
+        let realm = params.get("realm").ok_or_else(|| {
+            BoxliteError::Network("registry auth challenge has no realm".to_string())
+        })?;
+        let mut url = Url::parse(realm)
+            .map_err(|e| BoxliteError::Network(format!("invalid auth realm '{}': {}", realm, e)))?;
+        {
+            let mut query = url.query_pairs_mut();
+            if let Some(service) = params.get("service") {
+                query.append_pair("service", service);
+            }
+            let scope = params
+                .get("scope")
+                .cloned()
+                .unwrap_or_else(|| format!("repository:{}:pull,push", repository));
+            query.append_pair("scope", &scope);
+        }
+        let mut request = self.client.get(url);
+        if let Some((username, password)) = credentials {
+            request = request.basic_auth(username, Some(password));
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| BoxliteError::Network(format!("registry token request failed: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(BoxliteError::Network(format!(
+                "registry token request failed: {}",
+                response.status()
+            )));
+        }
+
+        #[derive(serde::Deserialize)]
+        struct TokenResponse {
+            token: Option<String>,
+            access_token: Option<String>,
+        }
+        let body: TokenResponse = response
+            .json()
+            .await
+            .map_err(|e| BoxliteError::Network(format!("invalid registry token: {}", e)))?;
+        let token = body.token.or(body.access_token).ok_or_else(|| {
+            BoxliteError::Network("registry token response has no token".to_string())
+        })?;
+        Ok(format!("Bearer {}", token))
+    }
+}
+
+/// Fill `buf` from `file`, stopping early only at end of file.
+async fn read_chunk(file: &mut tokio::fs::File, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        let n = file.read(&mut buf[filled..]).await?;
+        if n == 0 {
+            break;
+        }
+        filled += n;
+    }
+    Ok(filled)
+}
+
+/// Whether the status a first chunk got means "send it whole instead".
+fn takes_no_chunks(status: StatusCode) -> bool {
+    status.is_success()
+        || status == StatusCode::METHOD_NOT_ALLOWED
+        || status == StatusCode::RANGE_NOT_SATISFIABLE
+}
+
+async fn upload_error(what: &str, response: Response) -> BoxliteError {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    BoxliteError::Network(format!(
+        "failed to push blob {}: {} {}",
+        what,
+        status,
+        body.trim()
+    ))
+}
+
+/// Split `Bearer realm="...",service="..."` into its scheme and parameters.
+fn parse_challenge(header: &str) -> Option<(String, HashMap<String, String>)> {
+    let (scheme, rest) = header.trim().split_once(' ').unwrap_or((header.trim(), ""));
+    if scheme.is_empty() {
+        return None;
+    }
+    let mut params = HashMap::new();
+    let mut rest = rest.trim();
+    while !rest.is_empty() {
+        let (key, after) = rest.split_once('=')?;
+        let after = after.trim_start();
+        let (value, remaining) = match after.strip_prefix('"') {
+            Some(quoted) => {
+                let end = quoted.find('"')?;
+                (&quoted[..end], &quoted[end + 1..])
+            }
+            None => after.split_once(',').unwrap_or((after, "")),
+        };
+        params.insert(key.trim().to_ascii_lowercase(), value.to_string());
+        rest = remaining.trim_start_matches([',', ' ']);
+    }
+    Some((scheme.to_string(), params))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::{BodyExt, Full};
+    use hyper::{Method, Request};
+    use hyper_util::rt::TokioIo;
+    use sha2::{Digest, Sha256};
+    use std::convert::Infallible;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_parse_challenge() {
+        let (scheme, params) = parse_challenge(
+            r#"Bearer realm="https://auth.docker.io/token",service="registry.docker.io",scope="repository:library/alpine:pull,push""#,
+        )
+        .unwrap();
+        assert_eq!(scheme, "Bearer");
+        assert_eq!(params["realm"], "https://auth.docker.io/token");
+        assert_eq!(params["service"], "registry.docker.io");
+        assert_eq!(params["scope"], "repository:library/alpine:pull,push");
+
+        let (scheme, params) = parse_challenge(r#"Basic realm=Registry"#).unwrap();
+        assert_eq!(scheme, "Basic");
+        assert_eq!(params["realm"], "Registry");
+        assert!(parse_challenge("").is_none());
+    }
+
+    /// A registry that wants a bearer token and takes chunked uploads.
+    #[derive(Default)]
+    struct FakeRegistry {
+        received: Vec<u8>,
+        patches: usize,
+        committed: Option<String>,
+    }
+
+    async fn serve(state: Arc<Mutex<FakeRegistry>>) -> Url {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = Url::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+        let realm = base.join("token").unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let state = Arc::clone(&state);
+                let realm = realm.clone();
+                tokio::spawn(async move {
+                    let service = hyper::service::service_fn(move |req: Request<_>| {
+                        let state = Arc::clone(&state);
+                        let realm = realm.clone();
+                        async move { Ok::<_, Infallible>(handle(&state, &realm, req).await) }
+                    });
+                    let _ = hyper::server::conn::http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service)
+                        .await;
+                });
+            }
+        });
+        base
+    }
+
+    async fn handle(
+        state: &Mutex<FakeRegistry>,
+        realm: &Url,
+        req: Request<hyper::body::Incoming>,
+    ) -> hyper::Response<Full<Bytes>> {
+        let response = |status: u16, location: Option<&str>, body: &str| {
+            let mut builder = hyper::Response::builder().status(status);
+            if let Some(location) = location {
+                builder = builder.header("Location", location);
+            }
+            builder
+                .body(Full::new(Bytes::from(body.to_string())))
+                .unwrap()
+        };
+        if req.uri().path() == "/token" {
+            return response(200, None, r#"{"token":"t0k3n"}"#);
+        }
+        let authorized = req
+            .headers()
+            .get("Authorization")
+            .is_some_and(|v| v == "Bearer t0k3n");
+        if !authorized {
+            let mut denied = response(401, None, "");
+            denied.headers_mut().insert(
+                "WWW-Authenticate",
+                format!(r#"Bearer realm="{}",service="fake""#, realm)
+                    .parse()
+                    .unwrap(),
+            );
+            return denied;
+        }
+
+        let method = req.method().clone();
+        let path = req.uri().path().to_string();
+        let query = req.uri().query().unwrap_or_default().to_string();
+        let range = req
+            .headers()
+            .get("Content-Range")
+            .map(|v| v.to_str().unwrap().to_string());
+        let body = req.into_body().collect().await.unwrap().to_bytes();
+        let mut state = state.lock().unwrap();
+        match (method, path.as_str()) {
+            (Method::POST, "/v2/acme/app/blobs/uploads/") => response(202, Some("/upload/1"), ""),
+            (Method::PATCH, "/upload/1") => {
+                let start: usize = range.unwrap().split('-').next().unwrap().parse().unwrap();
+                assert_eq!(start, state.received.len());
+                state.received.extend_from_slice(&body);
+                state.patches += 1;
+                response(202, Some("/upload/1"), "")
+            }
+            (Method::PUT, "/upload/1") => {
+                state.committed = query.strip_prefix("digest=").map(|d| d.replace("%3A", ":"));
+                response(201, Some("/v2/acme/app/blobs/done"), "")
+            }
+            _ => response(404, None, ""),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upload_streams_chunks_with_bearer_auth() {
+        let state = Arc::new(Mutex::new(FakeRegistry::default()));
+        let base = serve(Arc::clone(&state)).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("layer.tar.gz");
+        let data: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+        std::fs::write(&path, &data).unwrap();
+        let digest = format!("sha256:{:x}", Sha256::digest(&data));
+
+        let mut uploader = BlobUploader::new(Client::new(), base, RegistryAuth::Anonymous);
+        uploader.chunk_size = 4096;
+        uploader.upload("acme/app", &digest, &path).await.unwrap();
+
+        let state = state.lock().unwrap();
+        assert_eq!(state.received, data);
+        assert_eq!(state.patches, 3);
+        assert_eq!(state.committed.as_deref(), Some(digest.as_str()));
+    }
+}
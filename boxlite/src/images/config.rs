@@ -56,6 +56,51 @@ impl ContainerImageConfig {
         result
     }
 
+    /// Apply a box's overrides with Docker's precedence: image config, then
+    /// the box's options (the exec-time working directory is applied per
+    /// command).
+    ///
+    /// - A non-empty `entrypoint` replaces the image's ENTRYPOINT and drops
+    ///   its CMD, so the image's default arguments never reach a different
+    ///   program (`docker run --entrypoint`).
+    /// - An empty `entrypoint` clears the image's ENTRYPOINT and runs its CMD
+    ///   (`--entrypoint ""`).
+    /// - A non-empty `cmd` replaces the CMD; an empty one keeps the image's.
+    ///
+    /// Fails when nothing is left to run.
+    pub fn apply_overrides(
+        &mut self,
+        entrypoint: Option<&[String]>,
+        cmd: Option<&[String]>,
+        user: Option<&str>,
+        working_dir: Option<&str>,
+    ) -> boxlite_shared::errors::BoxliteResult<()> {
+        let cmd = cmd.filter(|c| !c.is_empty());
+        if let Some(entrypoint) = entrypoint {
+            if !entrypoint.is_empty() {
+                self.cmd.clear();
+            }
+            self.entrypoint = entrypoint.to_vec();
+        }
+        if let Some(cmd) = cmd {
+            self.cmd = cmd.to_vec();
+        }
+        if let Some(user) = user {
+            self.user = user.to_string();
+        }
+        if let Some(dir) = working_dir {
+            self.working_dir = dir.to_string();
+        }
+
+        if self.final_cmd().is_empty() {
+            return Err(boxlite_shared::errors::BoxliteError::Config(
+                "no command to run: the image has no ENTRYPOINT or CMD and the box sets neither"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+
     /// Parse port number and protocol from exposed port string
     ///
     /// # Examples
@@ -258,6 +303,85 @@ mod tests {
         assert_eq!(config.udp_ports(), vec![53, 123]);
     }
 
+    /// Docker's `run`/`create` results for an image with
+    /// `ENTRYPOINT ["/entry.sh"]`, `CMD ["serve"]` and `WORKDIR /app`.
+    #[test]
+    fn test_apply_overrides_matches_docker() {
+        let strings = |s: &[&str]| s.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let image = ContainerImageConfig {
+            entrypoint: strings(&["/entry.sh"]),
+            cmd: strings(&["serve"]),
+            working_dir: "/app".to_string(),
+            ..Default::default()
+        };
+        // (--entrypoint, command, effective process)
+        type Case<'a> = (Option<&'a [&'a str]>, Option<&'a [&'a str]>, &'a [&'a str]);
+        let cases: &[Case] = &[
+            // docker run image
+            (None, None, &["/entry.sh", "serve"]),
+            // docker run image migrate
+            (None, Some(&["migrate"]), &["/entry.sh", "migrate"]),
+            // docker run --entrypoint sh image: the image CMD is dropped
+            (Some(&["sh"]), None, &["sh"]),
+            // docker run --entrypoint sh image -c 'echo hi'
+            (
+                Some(&["sh"]),
+                Some(&["-c", "echo hi"]),
+                &["sh", "-c", "echo hi"],
+            ),
+            // docker run --entrypoint "" image: the image CMD runs alone
+            (Some(&[]), None, &["serve"]),
+            // docker run --entrypoint "" image ls /
+            (Some(&[]), Some(&["ls", "/"]), &["ls", "/"]),
+            // an empty command keeps the image CMD
+            (None, Some(&[]), &["/entry.sh", "serve"]),
+        ];
+        for (entrypoint, cmd, expected) in cases {
+            let mut config = image.clone();
+            let entrypoint = entrypoint.map(strings);
+            let cmd = cmd.map(strings);
+            config
+                .apply_overrides(entrypoint.as_deref(), cmd.as_deref(), None, None)
+                .unwrap();
+            assert_eq!(
+                config.final_cmd(),
+                strings(expected),
+                "entrypoint={:?} cmd={:?}",
+                entrypoint,
+                cmd
+            );
+            assert_eq!(config.working_dir, "/app");
+        }
+
+        // Shell-form ENTRYPOINT (stored as `sh -c`) keeps its form; CMD
+        // arguments only become positional parameters, as in Docker
+        let mut config = ContainerImageConfig {
+            entrypoint: strings(&["/bin/sh", "-c", "exec nginx"]),
+            cmd: Vec::new(),
+            ..Default::default()
+        };
+        config
+            .apply_overrides(None, Some(&strings(&["-g"])), None, Some("/srv"))
+            .unwrap();
+        assert_eq!(config.final_cmd(), ["/bin/sh", "-c", "exec nginx", "-g"]);
+        assert_eq!(config.working_dir, "/srv");
+    }
+
+    #[test]
+    fn test_apply_overrides_requires_a_command() {
+        let mut config = ContainerImageConfig {
+            entrypoint: Vec::new(),
+            cmd: Vec::new(),
+            ..Default::default()
+        };
+        assert!(config.apply_overrides(None, None, None, None).is_err());
+        assert!(
+            config
+                .apply_overrides(None, Some(&["true".to_string()]), None, None)
+                .is_ok()
+        );
+    }
+
     #[test]
     fn test_final_cmd() {
         let config = ContainerImageConfig {
@@ -0,0 +1,432 @@
+//! Content-addressed dedup of extracted layer files.
+//!
+//! Images built on the same base (e.g. several Python images) carry many
+//! byte-identical files in different layers. When enabled, each regular file
+//! of a freshly extracted layer is hashed and replaced by a link to a single
+//! object in `images/content/{aa}/{sha256}`:
+//!
+//! - same content and metadata: hardlink to the object (one inode on disk)
+//! - same content, different metadata: reflink (shared extents, own inode),
+//!   where the filesystem supports it; otherwise the file is left alone
+//!
+//! Extracted layers are immutable and rootfs materialization copies (or
+//! reflinks) out of them, so sharing inodes between layers is safe.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+use walkdir::WalkDir;
+
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+
+/// Files smaller than this are not worth an object (and a hash).
+const MIN_DEDUP_SIZE: u64 = 4096;
+
+/// Files deduplicated in one tree.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DedupStats {
+    /// Files replaced by a link to an existing object
+    pub files: u64,
+    /// Logical bytes of those files (no longer stored separately)
+    pub bytes: u64,
+}
+
+/// Objects held by the content store.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ContentUsage {
+    /// Number of unique objects
+    pub objects: u64,
+    /// Bytes allocated by the objects
+    pub bytes: u64,
+}
+
+/// Disk usage of a set of directory trees.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TreeUsage {
+    /// Allocated bytes counting every path separately
+    pub logical: u64,
+    /// Allocated bytes counting each inode once
+    pub physical: u64,
+}
+
+/// Measure `dirs` together, so inodes shared between them count once.
+///
+/// Sizes are allocated blocks, not file lengths: sparse disk images report
+/// what they actually occupy. Missing directories count as empty.
+pub fn tree_usage(dirs: &[PathBuf]) -> TreeUsage {
+    let mut usage = TreeUsage::default();
+    let mut seen = HashSet::new();
+
+    for dir in dirs {
+        for entry in WalkDir::new(dir)
+            .follow_links(false)
+            .into_iter()
+            .filter_map(Result::ok)
+        {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                continue;
+            }
+            let allocated = metadata.blocks() * 512;
+            usage.logical += allocated;
+            if metadata.nlink() == 1 || seen.insert((metadata.dev(), metadata.ino())) {
+                usage.physical += allocated;
+            }
+        }
+    }
+    usage
+}
+
+/// Content-addressed object store rooted at `images/content`.
+#[derive(Debug, Clone)]
+pub struct ContentStore {
+    root: PathBuf,
+}
+
+impl ContentStore {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// Path of the object holding content with the given hex sha256.
+    fn object_path(&self, hash: &str) -> PathBuf {
+        self.root.join(&hash[..2]).join(hash)
+    }
+
+    /// Replace regular files under `dir` with links into the store.
+    ///
+    /// Files that already have several links are skipped: they are hardlinks
+    /// within the layer, and relinking one of them would break the group.
+    /// For the same reason each object is linked at most once per tree.
+    pub fn dedup_tree(&self, dir: &Path) -> BoxliteResult<DedupStats> {
+        let mut stats = DedupStats::default();
+        let mut linked = HashSet::new();
+
+        for entry in WalkDir::new(dir).follow_links(false) {
+            let entry = entry.map_err(|e| {
+                BoxliteError::Storage(format!("Failed to walk {}: {}", dir.display(), e))
+            })?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let path = entry.path();
+            let metadata = match fs::symlink_metadata(path) {
+                Ok(m) => m,
+                Err(_) => continue,
+            };
+            if metadata.len() < MIN_DEDUP_SIZE || metadata.nlink() > 1 {
+                continue;
+            }
+
+            let hash = match hash_file(path) {
+                Ok(hash) => hash,
+                Err(e) => {
+                    tracing::trace!("Skipping dedup of {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            if !linked.insert(hash.clone()) {
+                continue;
+            }
+
+            match self.link_file(path, &metadata, &hash) {
+                Ok(true) => {
+                    stats.files += 1;
+                    stats.bytes += metadata.len();
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    tracing::trace!("Skipping dedup of {}: {}", path.display(), e);
+                }
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// Adopt `path` as the object for `hash`, or link it to the existing one.
+    ///
+    /// Returns whether the file now shares storage with an earlier copy.
+    fn link_file(&self, path: &Path, metadata: &fs::Metadata, hash: &str) -> io::Result<bool> {
+        let object = self.object_path(hash);
+        fs::create_dir_all(object.parent().expect("object has a parent"))?;
+
+        // First copy: the file itself becomes the object
+        match fs::hard_link(path, &object) {
+            Ok(()) => return Ok(false),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(e),
+        }
+
+        let object_metadata = fs::symlink_metadata(&object)?;
+        if object_metadata.ino() == metadata.ino() && object_metadata.dev() == metadata.dev() {
+            return Ok(false);
+        }
+
+        let temp = path.with_file_name(format!(".{}.dedup", uuid::Uuid::new_v4().simple()));
+        if same_metadata(path, metadata, &object, &object_metadata) {
+            fs::hard_link(&object, &temp)?;
+        } else if !reflink_with_metadata(&object, &temp, path, metadata)? {
+            return Ok(false);
+        }
+
+        if let Err(e) = fs::rename(&temp, path) {
+            let _ = fs::remove_file(&temp);
+            return Err(e);
+        }
+        Ok(true)
+    }
+
+    /// Remove objects that are no longer linked from any extracted layer.
+    ///
+    /// An object's own name is its only link once every layer sharing it
+    /// is gone. Returns the number of objects removed.
+    pub fn remove_unlinked(&self) -> BoxliteResult<u64> {
+        let mut removed = 0;
+        for entry in WalkDir::new(&self.root)
+            .min_depth(2)
+            .max_depth(2)
+            .into_iter()
+            .filter_map(Result::ok)
+        {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if !metadata.is_file() || metadata.nlink() > 1 {
+                continue;
+            }
+            match fs::remove_file(entry.path()) {
+                Ok(()) => removed += 1,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    return Err(BoxliteError::Storage(format!(
+                        "Failed to remove content object {}: {}",
+                        entry.path().display(),
+                        e
+                    )));
+                }
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Count objects and their allocated bytes.
+    pub fn usage(&self) -> ContentUsage {
+        let mut usage = ContentUsage::default();
+        for entry in WalkDir::new(&self.root)
+            .min_depth(2)
+            .max_depth(2)
+            .into_iter()
+            .filter_map(Result::ok)
+        {
+            if let Ok(metadata) = entry.metadata()
+                && metadata.is_file()
+            {
+                usage.objects += 1;
+                usage.bytes += metadata.blocks() * 512;
+            }
+        }
+        usage
+    }
+}
+
+/// Hex sha256 of a file's content.
+fn hash_file(path: &Path) -> io::Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Whether a hardlink to `object` is indistinguishable from `path`.
+fn same_metadata(
+    path: &Path,
+    metadata: &fs::Metadata,
+    object: &Path,
+    object_metadata: &fs::Metadata,
+) -> bool {
+    metadata.mode() == object_metadata.mode()
+        && metadata.uid() == object_metadata.uid()
+        && metadata.gid() == object_metadata.gid()
+        && metadata.mtime() == object_metadata.mtime()
+        && metadata.mtime_nsec() == object_metadata.mtime_nsec()
+        && read_xattrs(path) == read_xattrs(object)
+}
+
+fn read_xattrs(path: &Path) -> Vec<(std::ffi::OsString, Option<Vec<u8>>)> {
+    let mut attrs: Vec<_> = xattr::list(path)
+        .map(|names| {
+            names
+                .map(|name| {
+                    let value = xattr::get(path, &name).ok().flatten();
+                    (name, value)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    attrs.sort();
+    attrs
+}
+
+/// Reflink `object` to `temp` and give it the metadata of `path`.
+///
+/// Returns `false` when the filesystem can't share extents.
+#[cfg(target_os = "linux")]
+fn reflink_with_metadata(
+    object: &Path,
+    temp: &Path,
+    path: &Path,
+    metadata: &fs::Metadata,
+) -> io::Result<bool> {
+    use std::os::unix::fs::{PermissionsExt, lchown};
+
+    if crate::rootfs::reflink_file(object, temp).is_err() {
+        let _ = fs::remove_file(temp);
+        return Ok(false);
+    }
+
+    let result = (|| {
+        lchown(temp, Some(metadata.uid()), Some(metadata.gid()))?;
+        fs::set_permissions(temp, fs::Permissions::from_mode(metadata.mode()))?;
+        for (name, value) in read_xattrs(path) {
+            if let Some(value) = value {
+                xattr::set(temp, &name, &value)?;
+            }
+        }
+        filetime::set_file_times(
+            temp,
+            filetime::FileTime::from_last_access_time(metadata),
+            filetime::FileTime::from_last_modification_time(metadata),
+        )
+    })();
+
+    match result {
+        Ok(()) => Ok(true),
+        Err(e) => {
+            let _ = fs::remove_file(temp);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn reflink_with_metadata(
+    _object: &Path,
+    _temp: &Path,
+    _path: &Path,
+    _metadata: &fs::Metadata,
+) -> io::Result<bool> {
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, data: &[u8]) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, data).unwrap();
+    }
+
+    #[test]
+    fn test_dedup_links_identical_files_across_trees() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ContentStore::new(dir.path().join("content"));
+        let data = vec![42u8; 8192];
+
+        let a = dir.path().join("a/usr/lib/libpython.so");
+        let b = dir.path().join("b/usr/lib/libpython.so");
+        write(&a, &data);
+        write(&b, &data);
+        let mtime = filetime::FileTime::from_unix_time(1_700_000_000, 0);
+        filetime::set_file_mtime(&a, mtime).unwrap();
+        filetime::set_file_mtime(&b, mtime).unwrap();
+
+        assert_eq!(store.dedup_tree(&dir.path().join("a")).unwrap().files, 0);
+        let stats = store.dedup_tree(&dir.path().join("b")).unwrap();
+        assert_eq!(
+            stats,
+            DedupStats {
+                files: 1,
+                bytes: 8192
+            }
+        );
+
+        let a_meta = fs::metadata(&a).unwrap();
+        let b_meta = fs::metadata(&b).unwrap();
+        assert_eq!(a_meta.ino(), b_meta.ino());
+        assert_eq!(fs::read(&b).unwrap(), data);
+        assert_eq!(store.usage().objects, 1);
+
+        let usage = tree_usage(&[dir.path().join("a"), dir.path().join("b")]);
+        assert_eq!(usage.logical, 2 * usage.physical);
+    }
+
+    #[test]
+    fn test_dedup_skips_small_and_hardlinked_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ContentStore::new(dir.path().join("content"));
+
+        let small = dir.path().join("layer/small");
+        write(&small, b"tiny");
+        let big = dir.path().join("layer/big");
+        write(&big, &[1u8; 8192]);
+        fs::hard_link(&big, dir.path().join("layer/big-link")).unwrap();
+
+        let stats = store.dedup_tree(&dir.path().join("layer")).unwrap();
+        assert_eq!(stats, DedupStats::default());
+        assert_eq!(store.usage().objects, 0);
+        assert_eq!(fs::metadata(&big).unwrap().nlink(), 2);
+    }
+
+    #[test]
+    fn test_dedup_keeps_differing_metadata() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let store = ContentStore::new(dir.path().join("content"));
+        let data = vec![7u8; 8192];
+
+        let a = dir.path().join("a/bin/tool");
+        let b = dir.path().join("b/bin/tool");
+        write(&a, &data);
+        write(&b, &data);
+        fs::set_permissions(&a, fs::Permissions::from_mode(0o644)).unwrap();
+        fs::set_permissions(&b, fs::Permissions::from_mode(0o755)).unwrap();
+
+        store.dedup_tree(&dir.path().join("a")).unwrap();
+        store.dedup_tree(&dir.path().join("b")).unwrap();
+
+        // Either reflinked or left alone, never sharing an inode
+        let a_meta = fs::metadata(&a).unwrap();
+        let b_meta = fs::metadata(&b).unwrap();
+        assert_ne!(a_meta.ino(), b_meta.ino());
+        assert_eq!(b_meta.mode() & 0o777, 0o755);
+        assert_eq!(fs::read(&b).unwrap(), data);
+    }
+
+    #[test]
+    fn test_remove_unlinked_objects() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ContentStore::new(dir.path().join("content"));
+        let data = vec![9u8; 8192];
+
+        let a = dir.path().join("a/lib/libc.so");
+        write(&a, &data);
+        store.dedup_tree(&dir.path().join("a")).unwrap();
+        assert_eq!(store.usage().objects, 1);
+
+        // Still linked from the layer
+        assert_eq!(store.remove_unlinked().unwrap(), 0);
+
+        fs::remove_dir_all(dir.path().join("a")).unwrap();
+        assert_eq!(store.remove_unlinked().unwrap(), 1);
+        assert_eq!(store.usage().objects, 0);
+    }
+}
@@ -9,18 +9,32 @@
 //! - `ImageStore` handles all locking internally
 //! - `ImageObject` uses `BlobSource` for blob access
 
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+use std::time::SystemTime;
 
 use chrono::{DateTime, Utc};
 
 use super::blob_source::{BlobSource, LocalBundleBlobSource, StoreBlobSource};
 use super::object::ImageObject;
+use super::storage::ImageStorage;
 use crate::db::Database;
-use crate::images::store::{ImageStore, SharedImageStore};
-use crate::runtime::types::ImageInfo;
-use boxlite_shared::errors::BoxliteResult;
+use crate::images::aliases::ShortNameAliases;
+use crate::images::blob_cache::SharedBlobCache;
+use crate::images::mirrors::RegistryMirrors;
+use crate::images::registry_client::RegistryClients;
+use crate::images::store::{ImageStore, RegistrySettings, SharedImageStore};
+use crate::images::throttle::PullThrottle;
+use crate::runtime::options::{BlobCacheOptions, BoxliteOptions, ProxyOptions, RegistryConfig};
+use crate::runtime::types::{
+    Bytes, ImageHistoryEntry, ImageInfo, ImageInspect, ImageLayerInspect, ImageVerification,
+};
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
 use oci_client::Reference;
+use oci_client::manifest::OciManifest;
+use oci_spec::image::ImageConfiguration;
 use std::str::FromStr;
 
 // ============================================================================
@@ -62,7 +76,18 @@ pub(super) struct LayerInfo {
 ///
 /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
 /// let db = Database::open(&PathBuf::from("/tmp/boxlite.db"))?;
-/// let manager = ImageManager::new(PathBuf::from("/tmp/images"), db, vec![])?;
+/// let manager = ImageManager::new(
+///     PathBuf::from("/tmp/images"),
+///     db,
+///     vec![],
+///     &HashMap::new(),
+///     &HashMap::new(),
+///     &HashMap::new(),
+///     &Default::default(),
+///     None,
+///     false,
+///     None,
+/// )?;
 ///
 /// // Pull an image
 /// let image = manager.pull("python:alpine").await?;
@@ -91,11 +116,75 @@ impl ImageManager {
     /// * `images_dir` - Directory for image cache
     /// * `db` - Database for image index
     /// * `registries` - Registries to search for unqualified images (tried in order)
-    pub fn new(images_dir: PathBuf, db: Database, registries: Vec<String>) -> BoxliteResult<Self> {
-        let store = Arc::new(ImageStore::new(images_dir, db, registries)?);
+    /// * `short_name_aliases` - Short names resolved to a qualified repository instead
+    /// * `registry_mirrors` - Mirrors per upstream registry (tried before the upstream)
+    /// * `registry_config` - Per-registry TLS settings
+    /// * `proxy` - Proxy for registry requests (unset fields fall back to the environment)
+    /// * `pull_rate_limit_mbps` - Bandwidth limit for layer downloads (`None` = unlimited)
+    /// * `dedup_layers` - Dedup identical files across extracted layers
+    /// * `blob_cache` - Remote layer cache tried before registries
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        images_dir: PathBuf,
+        db: Database,
+        registries: Vec<String>,
+        short_name_aliases: &HashMap<String, String>,
+        registry_mirrors: &HashMap<String, Vec<String>>,
+        registry_config: &HashMap<String, RegistryConfig>,
+        proxy: &ProxyOptions,
+        pull_rate_limit_mbps: Option<u64>,
+        dedup_layers: bool,
+        blob_cache: Option<&BlobCacheOptions>,
+    ) -> BoxliteResult<Self> {
+        let RegistrySettings {
+            clients,
+            registries,
+            aliases,
+            mirrors,
+            blob_cache,
+        } = registry_settings(
+            registries,
+            short_name_aliases,
+            registry_mirrors,
+            registry_config,
+            proxy,
+            blob_cache,
+        )?;
+        let throttle = Arc::new(PullThrottle::new(pull_rate_limit_mbps));
+        let store = Arc::new(
+            ImageStore::new(
+                images_dir,
+                db,
+                registries,
+                mirrors,
+                clients,
+                throttle,
+                dedup_layers,
+            )?
+            .with_aliases(aliases)
+            .with_blob_cache(blob_cache),
+        );
         Ok(Self { store })
     }
 
+    /// Apply new registry, alias, mirror, TLS, proxy, bandwidth and blob
+    /// cache settings to pulls from now on.
+    ///
+    /// Nothing changes if the new settings are invalid.
+    pub(crate) fn reconfigure(&self, options: &BoxliteOptions) -> BoxliteResult<()> {
+        let settings = registry_settings(
+            options.image_registries.clone(),
+            &options.short_name_aliases,
+            &options.registry_mirrors,
+            &options.registries,
+            &options.proxy,
+            options.blob_cache.as_ref(),
+        )?;
+        self.store.set_registry(settings);
+        self.store.set_rate_limit(options.pull_rate_limit_mbps);
+        Ok(())
+    }
+
     /// Pull an OCI image from a registry.
     ///
     /// Checks local cache first. If the image is already cached and complete,
@@ -115,9 +204,264 @@ impl ImageManager {
         ))
     }
 
-    /// List all cached images.
+    /// Pull an image and warm its extracted-layer cache, reporting progress.
+    ///
+    /// Like [`pull`](Self::pull), then extracts every layer (concurrently,
+    /// cached layers are skipped) and sends a line per layer with its
+    /// extraction time, so the first box start doesn't pay for it.
+    pub async fn pull_with_progress(
+        &self,
+        image_ref: &str,
+        progress: &tokio::sync::mpsc::UnboundedSender<String>,
+    ) -> BoxliteResult<ImageObject> {
+        let report = |line: String| {
+            tracing::info!("{}", line);
+            let _ = progress.send(line);
+        };
+
+        report(format!("Pulling {}", image_ref));
+        let image = self.pull(image_ref).await?;
+        report(format!(
+            "Pulled {} ({} layers)",
+            image.reference(),
+            image.layer_count()
+        ));
+
+        let start = std::time::Instant::now();
+        let pending = image.layer_extracted_pipelined();
+        let count = pending.len();
+        for handle in pending {
+            let layer = handle.await.map_err(|e| {
+                BoxliteError::Internal(format!("Layer extraction task failed: {}", e))
+            })??;
+            report(format!(
+                "Extracted layer {} in {:.1}s",
+                layer.digest,
+                layer.elapsed.as_secs_f64()
+            ));
+        }
+        report(format!(
+            "Extracted {} layers in {:.1}s",
+            count,
+            start.elapsed().as_secs_f64()
+        ));
+
+        Ok(image)
+    }
+
+    /// Resolve an image reference without network access.
+    ///
+    /// Returns the references a pull would try, in order, and the cached image
+    /// if a local tag or one of those references is already in the store.
+    pub async fn resolve(
+        &self,
+        image_ref: &str,
+    ) -> BoxliteResult<(Vec<String>, Option<ImageObject>)> {
+        let candidates = self.store.candidates(image_ref)?;
+        let Some((reference, manifest)) = self.store.resolve_local(image_ref).await? else {
+            return Ok((candidates, None));
+        };
+        let storage = self.store.storage().await;
+        let blob_source = BlobSource::Store(StoreBlobSource::new(storage));
+
+        Ok((
+            candidates,
+            Some(ImageObject::new(reference.whole(), manifest, blob_source)),
+        ))
+    }
+
+    /// Config, layers and platform of the cached image `image_ref` resolves
+    /// to, without network access.
+    ///
+    /// Layer sizes come from the cached manifest and cache status from the
+    /// store and the last pull's metrics; the config blob is parsed on demand.
+    pub async fn inspect(&self, image_ref: &str) -> BoxliteResult<Option<ImageInspect>> {
+        let Some((reference, manifest)) = self.store.resolve_local(image_ref).await? else {
+            return Ok(None);
+        };
+        let reference = reference.whole();
+        let storage = self.store.storage().await;
+
+        let sizes: HashMap<String, u64> = match storage.load_manifest(&manifest.manifest_digest)? {
+            OciManifest::Image(image) => image
+                .layers
+                .iter()
+                .map(|layer| (layer.digest.clone(), layer.size.max(0) as u64))
+                .collect(),
+            OciManifest::ImageIndex(_) => HashMap::new(),
+        };
+        let pulled: HashMap<String, bool> = self
+            .store
+            .last_pull(&reference)
+            .await?
+            .filter(|metrics| metrics.manifest_digest == manifest.manifest_digest)
+            .map(|metrics| {
+                metrics
+                    .layers
+                    .into_iter()
+                    .map(|layer| (layer.digest, layer.cached))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let layers: Vec<ImageLayerInspect> = manifest
+            .layers
+            .iter()
+            .map(|layer| ImageLayerInspect {
+                digest: layer.digest.clone(),
+                media_type: layer.media_type.clone(),
+                size: Bytes(sizes.get(&layer.digest).copied().unwrap_or(0)),
+                downloaded: storage.has_layer(&layer.digest),
+                extracted: storage.layer_extracted_path(&layer.digest).exists(),
+                cached_on_pull: pulled.get(&layer.digest).copied(),
+            })
+            .collect();
+
+        let config: ImageConfiguration =
+            serde_json::from_str(&storage.load_config(&manifest.config_digest)?).map_err(|e| {
+                BoxliteError::Storage(format!("Failed to parse image config: {}", e))
+            })?;
+        let runtime = config.config().clone().unwrap_or_default();
+        let mut exposed_ports = runtime.exposed_ports().clone().unwrap_or_default();
+        exposed_ports.sort();
+        let history = config
+            .history()
+            .iter()
+            .flatten()
+            .map(|entry| ImageHistoryEntry {
+                created: entry.created().clone(),
+                created_by: entry.created_by().clone(),
+                comment: entry.comment().clone(),
+                empty_layer: entry.empty_layer().unwrap_or(false),
+            })
+            .collect();
+
+        Ok(Some(ImageInspect {
+            reference,
+            id: manifest.manifest_digest,
+            config_digest: manifest.config_digest,
+            os: config.os().to_string(),
+            architecture: config.architecture().to_string(),
+            variant: config.variant().clone(),
+            created: config.created().clone(),
+            author: config.author().clone(),
+            entrypoint: runtime.entrypoint().clone().unwrap_or_default(),
+            cmd: runtime.cmd().clone().unwrap_or_default(),
+            env: runtime.env().clone().unwrap_or_default(),
+            working_dir: runtime.working_dir().clone(),
+            user: runtime.user().clone(),
+            exposed_ports,
+            labels: runtime
+                .labels()
+                .clone()
+                .unwrap_or_default()
+                .into_iter()
+                .collect(),
+            size: Bytes(layers.iter().map(|layer| layer.size.as_bytes()).sum()),
+            layers,
+            history,
+        }))
+    }
+
+    /// Manifest digest of the cached image `image_ref` resolves to, if any.
+    pub(crate) async fn resolve_digest(&self, image_ref: &str) -> BoxliteResult<Option<String>> {
+        Ok(self
+            .store
+            .resolve_local(image_ref)
+            .await?
+            .map(|(_, manifest)| manifest.manifest_digest))
+    }
+
+    /// Evict least recently used images until the cache fits in `max_bytes`.
+    ///
+    /// See [`ImageStore::prune_lru`] for what is never evicted.
+    pub(crate) async fn prune_lru(
+        &self,
+        max_bytes: u64,
+        keep: HashSet<String>,
+        keep_disks: HashSet<PathBuf>,
+        used_before: DateTime<Utc>,
+    ) -> BoxliteResult<(Vec<String>, u64)> {
+        self.store
+            .prune_lru(max_bytes, keep, keep_disks, used_before)
+            .await
+    }
+
+    /// Delete cached blobs and disk images no image refers to any more.
+    ///
+    /// See [`ImageStore::prune_dangling`].
+    pub(crate) async fn prune_dangling(
+        &self,
+        keep_disks: HashSet<PathBuf>,
+        older_than: SystemTime,
+    ) -> BoxliteResult<u64> {
+        self.store.prune_dangling(keep_disks, older_than).await
+    }
+
+    /// Import a locally built OCI layout into the store and tag it as `reference`.
+    pub(crate) async fn import_layout(
+        &self,
+        path: &std::path::Path,
+        reference: &str,
+    ) -> BoxliteResult<ImageObject> {
+        let manifest = self.store.import_layout(path, reference).await?;
+        let storage = self.store.storage().await;
+        let blob_source = BlobSource::Store(StoreBlobSource::new(storage));
+
+        Ok(ImageObject::new(
+            reference.to_string(),
+            manifest,
+            blob_source,
+        ))
+    }
+
+    /// Push a locally stored image to its registry.
+    ///
+    /// Progress lines are sent to `progress` when provided. Returns the
+    /// fully qualified reference that was pushed.
+    pub async fn push(
+        &self,
+        image_ref: &str,
+        progress: Option<&tokio::sync::mpsc::UnboundedSender<String>>,
+    ) -> BoxliteResult<String> {
+        self.store.push(image_ref, progress).await
+    }
+
+    /// Shared image storage (for serving cached blobs).
+    pub(crate) async fn storage(&self) -> Arc<ImageStorage> {
+        self.store.storage().await
+    }
+
+    /// Counter of layer bytes downloaded by this manager.
+    pub(crate) fn pull_bytes_total(&self) -> Arc<AtomicU64> {
+        self.store.pull_bytes_total()
+    }
+
+    /// Counters of layers cached and fetched during registry pulls.
+    pub(crate) fn layer_counters(&self) -> (Arc<AtomicU64>, Arc<AtomicU64>) {
+        self.store.layer_counters()
+    }
+
+    /// Registry client for `registry`, sharing the store's TLS and proxy settings.
+    pub(crate) fn registry_client(&self, registry: &str) -> oci_client::Client {
+        self.store.client(registry)
+    }
+
+    /// Registry client with its own token cache (see `ImageStore::isolated_client`).
+    pub(crate) fn isolated_registry_client(
+        &self,
+        registry: &str,
+    ) -> BoxliteResult<oci_client::Client> {
+        self.store.isolated_client(registry)
+    }
+
+    /// List all cached images, including local tags.
     pub async fn list(&self) -> BoxliteResult<Vec<ImageInfo>> {
-        let raw_images = self.store.list().await?;
+        let mut raw_images = self.store.list().await?;
+        for (tag, cached) in self.store.list_tags().await? {
+            if !raw_images.iter().any(|(reference, _)| *reference == tag) {
+                raw_images.push((tag, cached));
+            }
+        }
 
         let mut images = Vec::with_capacity(raw_images.len());
         for (reference, cached) in raw_images {
@@ -130,15 +474,23 @@ impl ImageManager {
                 });
 
             let (repository, tag) = match Reference::from_str(&reference) {
-                Ok(r) => (
-                    r.repository().to_string(),
-                    r.tag().unwrap_or("latest").to_string(),
-                ),
+                Ok(r) => {
+                    let default_tag = if r.digest().is_some() {
+                        "<none>"
+                    } else {
+                        "latest"
+                    };
+                    (
+                        r.repository().to_string(),
+                        r.tag().unwrap_or(default_tag).to_string(),
+                    )
+                }
                 Err(_) => {
                     // Fallback if reference stored in DB is invalid
                     (reference.clone(), "<none>".to_string())
                 }
             };
+            let last_pull = self.store.last_pull(&reference).await?;
 
             images.push(ImageInfo {
                 reference,
@@ -147,12 +499,25 @@ impl ImageManager {
                 id: cached.manifest_digest,
                 cached_at,
                 size: None, // Size calculation is expensive now? omitted for list temporarily
+                last_pull,
             });
         }
 
         Ok(images)
     }
 
+    /// Re-hash the blobs of all cached images, optionally deleting corrupt ones.
+    pub async fn verify(&self, remove_corrupt: bool) -> BoxliteResult<ImageVerification> {
+        self.store.verify(remove_corrupt).await
+    }
+
+    /// Tag a local image under a new reference.
+    ///
+    /// Returns the normalized target (unqualified names get `localhost/`).
+    pub async fn tag(&self, source: &str, target: &str) -> BoxliteResult<String> {
+        self.store.tag(source, target).await
+    }
+
     /// Load an OCI/Docker image from a local directory.
     ///
     /// Reads image manifest from `manifest.json` and returns an `ImageObject`.
@@ -189,3 +554,27 @@ impl ImageManager {
         Ok(ImageObject::new(reference, manifest, blob_source))
     }
 }
+
+/// Build the registry-facing settings of the image store.
+fn registry_settings(
+    registries: Vec<String>,
+    short_name_aliases: &HashMap<String, String>,
+    registry_mirrors: &HashMap<String, Vec<String>>,
+    registry_config: &HashMap<String, RegistryConfig>,
+    proxy: &ProxyOptions,
+    blob_cache: Option<&BlobCacheOptions>,
+) -> BoxliteResult<RegistrySettings> {
+    let proxy = proxy.resolve();
+    let mirrors = RegistryMirrors::new(registry_mirrors);
+    let clients = RegistryClients::new(registry_config, &mirrors, &proxy)?;
+    let blob_cache = blob_cache
+        .map(|options| SharedBlobCache::new(options, &proxy))
+        .transpose()?;
+    Ok(RegistrySettings {
+        clients,
+        registries,
+        aliases: ShortNameAliases::new(short_name_aliases)?,
+        mirrors,
+        blob_cache,
+    })
+}
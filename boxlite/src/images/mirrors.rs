@@ -0,0 +1,171 @@
+//! Registry mirrors.
+//!
+//! Maps an upstream registry (e.g. `docker.io`) to an ordered list of mirror
+//! endpoints. Pulls try each mirror in order and fall back to the upstream
+//! registry when every mirror fails. A mirror endpoint is a registry host,
+//! optionally followed by a repository prefix and prefixed with `http://`
+//! for plain-HTTP mirrors such as `boxlite registry-cache serve`:
+//!
+//! ```text
+//! mirror.internal
+//! mirror.internal:5000/dockerhub
+//! http://10.0.0.5:5000
+//! ```
+
+use std::collections::HashMap;
+
+use oci_client::Reference;
+
+use super::auth::normalize_registry;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Mirror {
+    host: String,
+    prefix: Option<String>,
+    insecure: bool,
+}
+
+impl Mirror {
+    fn parse(endpoint: &str) -> Option<Self> {
+        let (rest, insecure) = match endpoint.strip_prefix("http://") {
+            Some(rest) => (rest, true),
+            None => (endpoint.strip_prefix("https://").unwrap_or(endpoint), false),
+        };
+        let rest = rest.trim_end_matches('/');
+        let (host, prefix) = match rest.split_once('/') {
+            Some((host, prefix)) => (host, Some(prefix.to_string())),
+            None => (rest, None),
+        };
+        if host.is_empty() {
+            return None;
+        }
+        Some(Self {
+            host: host.to_string(),
+            prefix,
+            insecure,
+        })
+    }
+
+    /// Rewrite `reference` to be fetched from this mirror.
+    fn rewrite(&self, reference: &Reference) -> Reference {
+        let repository = match &self.prefix {
+            Some(prefix) => format!("{}/{}", prefix, reference.repository()),
+            None => reference.repository().to_string(),
+        };
+        match (reference.tag(), reference.digest()) {
+            (Some(tag), Some(digest)) => Reference::with_tag_and_digest(
+                self.host.clone(),
+                repository,
+                tag.to_string(),
+                digest.to_string(),
+            ),
+            (None, Some(digest)) => {
+                Reference::with_digest(self.host.clone(), repository, digest.to_string())
+            }
+            (tag, None) => Reference::with_tag(
+                self.host.clone(),
+                repository,
+                tag.unwrap_or("latest").to_string(),
+            ),
+        }
+    }
+}
+
+/// Mirror configuration keyed by normalized upstream registry.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RegistryMirrors {
+    mirrors: HashMap<String, Vec<Mirror>>,
+}
+
+impl RegistryMirrors {
+    pub(crate) fn new(config: &HashMap<String, Vec<String>>) -> Self {
+        let mut mirrors = HashMap::new();
+        for (registry, endpoints) in config {
+            let parsed: Vec<Mirror> = endpoints
+                .iter()
+                .filter_map(|endpoint| {
+                    let mirror = Mirror::parse(endpoint);
+                    if mirror.is_none() {
+                        tracing::warn!(registry = %registry, endpoint = %endpoint, "Ignoring invalid registry mirror");
+                    }
+                    mirror
+                })
+                .collect();
+            if !parsed.is_empty() {
+                mirrors.insert(normalize_registry(registry), parsed);
+            }
+        }
+        Self { mirrors }
+    }
+
+    /// Sources to try for `reference`, in order: each mirror, then the upstream.
+    pub(crate) fn sources(&self, reference: &Reference) -> Vec<Reference> {
+        let mut sources: Vec<Reference> = self
+            .mirrors
+            .get(&normalize_registry(reference.registry()))
+            .map(|mirrors| mirrors.iter().map(|m| m.rewrite(reference)).collect())
+            .unwrap_or_default();
+        sources.push(reference.clone());
+        sources
+    }
+
+    /// Mirror hosts that must be reached over plain HTTP.
+    pub(crate) fn insecure_hosts(&self) -> Vec<String> {
+        self.mirrors
+            .values()
+            .flatten()
+            .filter(|m| m.insecure)
+            .map(|m| m.host.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mirrors(registry: &str, endpoints: &[&str]) -> RegistryMirrors {
+        let mut config = HashMap::new();
+        config.insert(
+            registry.to_string(),
+            endpoints.iter().map(|e| e.to_string()).collect(),
+        );
+        RegistryMirrors::new(&config)
+    }
+
+    #[test]
+    fn test_sources_mirrors_then_upstream() {
+        let m = mirrors(
+            "docker.io",
+            &["mirror.internal", "http://10.0.0.5:5000/hub"],
+        );
+        let reference: Reference = "alpine:3.19".parse().unwrap();
+
+        let sources: Vec<String> = m.sources(&reference).iter().map(|r| r.whole()).collect();
+        assert_eq!(
+            sources,
+            vec![
+                "mirror.internal/library/alpine:3.19",
+                "10.0.0.5:5000/hub/library/alpine:3.19",
+                "docker.io/library/alpine:3.19",
+            ]
+        );
+        assert_eq!(m.insecure_hosts(), vec!["10.0.0.5:5000"]);
+    }
+
+    #[test]
+    fn test_unmirrored_registry_uses_upstream_only() {
+        let m = mirrors("docker.io", &["mirror.internal"]);
+        let reference: Reference = "ghcr.io/acme/app@sha256:0000000000000000000000000000000000000000000000000000000000000000"
+            .parse()
+            .unwrap();
+        assert_eq!(m.sources(&reference), vec![reference]);
+    }
+
+    #[test]
+    fn test_registry_key_aliases() {
+        let m = mirrors("https://index.docker.io/v1/", &["mirror.internal"]);
+        let reference: Reference = "docker.io/library/redis".parse().unwrap();
+        assert_eq!(m.sources(&reference).len(), 2);
+    }
+}
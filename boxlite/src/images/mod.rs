@@ -1,16 +1,29 @@
+mod aliases;
 mod archive;
+mod auth;
+mod blob_cache;
 mod blob_source;
+mod blob_upload;
 mod config;
+mod dedup;
 mod manager;
+mod mirrors;
 mod object;
+mod registry_cache;
+mod registry_client;
 mod storage;
 mod store;
+mod throttle;
 
 pub use archive::extract_layer_tarball_streaming;
 pub use config::ContainerImageConfig;
+pub(crate) use dedup::{ContentStore, tree_usage};
 pub use manager::ImageManager;
 pub use object::ImageObject;
+pub use registry_cache::RegistryCacheOptions;
+pub(crate) use registry_cache::{CacheAccess, RegistryCache};
 
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
 use oci_client::Reference;
 
 // ============================================================================
@@ -139,6 +152,28 @@ fn is_fully_qualified(image_ref: &str) -> bool {
     }
 }
 
+/// Whether `image_ref` names an image by digest (`name@sha256:...`). Such
+/// references bypass local tags and are checked against the digest on use.
+pub(crate) fn is_digest_pinned(image_ref: &str) -> bool {
+    image_ref.contains('@')
+}
+
+/// Normalize a local tag: unqualified names go under `localhost/`.
+///
+/// Used for tags created by `boxlite tag` and `boxlite build`, which never
+/// live in a remote registry until pushed.
+pub(crate) fn normalize_local_reference(image_ref: &str) -> BoxliteResult<String> {
+    let qualified = if is_fully_qualified(image_ref) {
+        image_ref.to_string()
+    } else {
+        format!("localhost/{}", image_ref)
+    };
+    let reference: Reference = qualified.parse().map_err(|e| {
+        BoxliteError::InvalidArgument(format!("invalid image reference '{}': {}", image_ref, e))
+    })?;
+    Ok(reference.whole())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -250,4 +285,21 @@ mod tests {
         assert!(!is_fully_qualified("library/alpine"));
         assert!(!is_fully_qualified("myorg/myimage:v1"));
     }
+
+    #[test]
+    fn test_normalize_local_reference() {
+        assert_eq!(
+            normalize_local_reference("myapp").unwrap(),
+            "localhost/myapp:latest"
+        );
+        assert_eq!(
+            normalize_local_reference("myapp:v1").unwrap(),
+            "localhost/myapp:v1"
+        );
+        assert_eq!(
+            normalize_local_reference("ghcr.io/acme/app:v2").unwrap(),
+            "ghcr.io/acme/app:v2"
+        );
+        assert!(normalize_local_reference("Bad Tag").is_err());
+    }
 }
@@ -5,6 +5,11 @@
 //! layer access, inspection).
 
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
 
 use super::blob_source::BlobSource;
 use super::manager::ImageManifest;
@@ -14,6 +19,17 @@ use boxlite_shared::errors::{BoxliteError, BoxliteResult};
 // IMAGE OBJECT
 // ============================================================================
 
+/// A layer extracted to its cache directory.
+#[derive(Debug, Clone)]
+pub struct ExtractedLayer {
+    /// Layer digest (sha256:...)
+    pub digest: String,
+    /// Extracted layer directory
+    pub path: PathBuf,
+    /// Time spent extracting (near zero when the layer was cached)
+    pub elapsed: Duration,
+}
+
 /// A pulled OCI image with all associated operations.
 ///
 /// This object represents a complete pulled image and provides access to:
@@ -67,6 +83,11 @@ impl ImageObject {
             .collect()
     }
 
+    /// Get manifest digest
+    pub fn manifest_digest(&self) -> &str {
+        &self.manifest.manifest_digest
+    }
+
     /// Get config digest
     #[allow(dead_code)]
     pub fn config_digest(&self) -> &str {
@@ -165,18 +186,60 @@ impl ImageObject {
         self.blob_source.extract_layers(&digests).await
     }
 
+    /// Start extracting all layers concurrently, returning one handle per layer.
+    ///
+    /// Handles are ordered bottom to top, so callers can stack layer N as soon
+    /// as it is ready while layers above it are still extracting. Concurrency
+    /// is bounded by the number of available CPUs; cached layers resolve
+    /// immediately.
+    ///
+    /// # Example
+    /// ```ignore
+    /// for handle in image.layer_extracted_pipelined() {
+    ///     let layer = handle.await??;
+    ///     stack(&layer.path)?;
+    /// }
+    /// ```
+    pub fn layer_extracted_pipelined(&self) -> Vec<JoinHandle<BoxliteResult<ExtractedLayer>>> {
+        let limit = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(4);
+        let permits = Arc::new(Semaphore::new(limit));
+
+        self.manifest
+            .layers
+            .iter()
+            .map(|layer| {
+                let source = self.blob_source.clone();
+                let digest = layer.digest.clone();
+                let permits = Arc::clone(&permits);
+                tokio::spawn(async move {
+                    let _permit = permits.acquire_owned().await.map_err(|e| {
+                        BoxliteError::Internal(format!("Extraction semaphore closed: {}", e))
+                    })?;
+                    let start = Instant::now();
+                    let path = source.extract_layer(&digest).await?;
+                    Ok(ExtractedLayer {
+                        digest,
+                        path,
+                        elapsed: start.elapsed(),
+                    })
+                })
+            })
+            .collect()
+    }
+
     /// Compute a stable digest for this image based on its layers.
     ///
     /// This is used as a cache key for base disks - same layers = same base disk.
     /// Uses SHA256 hash of concatenated layer digests.
     pub(crate) fn compute_image_digest(&self) -> String {
-        use sha2::{Digest, Sha256};
-
-        let mut hasher = Sha256::new();
-        for layer in &self.manifest.layers {
-            hasher.update(layer.digest.as_bytes());
-        }
-        format!("sha256:{:x}", hasher.finalize())
+        super::storage::disk_image_digest(
+            self.manifest
+                .layers
+                .iter()
+                .map(|layer| layer.digest.as_str()),
+        )
     }
 
     /// Get existing disk image if available.
@@ -188,6 +251,19 @@ impl ImageObject {
         self.blob_source.disk_image(&image_digest)
     }
 
+    /// Get existing disk image of a specific format if available.
+    ///
+    /// Used for read-only rootfs images (erofs/squashfs), which are cached
+    /// alongside the ext4 image under their own extension.
+    pub fn disk_image_with_format(
+        &self,
+        format: crate::disk::DiskFormat,
+    ) -> Option<crate::disk::Disk> {
+        let image_digest = self.compute_image_digest();
+        self.blob_source
+            .disk_image_with_format(&image_digest, format)
+    }
+
     /// Install a disk as the cached disk image for this image.
     ///
     /// Atomically moves the source disk to the image store path.
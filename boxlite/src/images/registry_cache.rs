@@ -0,0 +1,649 @@
+//! Pull-through registry cache (`boxlite registry-cache serve`).
+//!
+//! Serves the read-only subset of the OCI distribution API so other hosts
+//! can use this one as a registry mirror:
+//!
+//! - `GET /v2/` - API version check
+//! - `GET|HEAD /v2/<name>/manifests/<reference>` - proxied to the upstream
+//!   registry byte-for-byte, so digests stay valid
+//! - `GET|HEAD /v2/<name>/blobs/<digest>` - served from the local image
+//!   store when present, otherwise fetched from upstream once and cached
+//!
+//! The upstream registry defaults to the one in [`RegistryCacheOptions`];
+//! clients that send the `ns` query parameter (containerd) select it per
+//! request instead, among the registries the runtime is configured for.
+//!
+//! Clients are anonymous unless they present the cache's access token.
+//! Anonymous requests reach upstream without this host's credentials, use a
+//! registry client that shares no tokens with the rest of the runtime, and
+//! are served only blobs fetched the same way - never the local image store,
+//! which may hold private images.
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+use futures::TryStreamExt;
+use http_body_util::combinators::BoxBody;
+use http_body_util::{BodyExt, Full, StreamBody};
+use hyper::body::{Bytes, Frame, Incoming};
+use hyper::header::{ACCEPT, AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, HeaderValue};
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use oci_client::manifest::{
+    IMAGE_MANIFEST_LIST_MEDIA_TYPE, IMAGE_MANIFEST_MEDIA_TYPE, OCI_IMAGE_INDEX_MEDIA_TYPE,
+    OCI_IMAGE_MEDIA_TYPE,
+};
+use oci_client::secrets::RegistryAuth;
+use oci_client::{Reference, RegistryOperation};
+use subtle::ConstantTimeEq;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+
+use super::auth::{normalize_registry, registry_auth};
+use super::storage::ImageStorage;
+
+type Body = BoxBody<Bytes, std::io::Error>;
+
+const DIGEST_HEADER: &str = "Docker-Content-Digest";
+
+/// Pause after a failed accept, so a persistent failure does not spin.
+const ACCEPT_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Options for [`BoxliteRuntime::serve_registry_cache`](crate::BoxliteRuntime::serve_registry_cache).
+#[derive(Debug, Clone)]
+pub struct RegistryCacheOptions {
+    /// Address to listen on (loopback only by default).
+    pub listen: SocketAddr,
+    /// Registry to proxy when the client does not name one (e.g. `docker.io`).
+    pub upstream: String,
+    /// Access token for trusted clients, sent as `Authorization: Bearer
+    /// <token>` or as the password of basic auth. Only trusted clients get
+    /// this host's registry credentials and blobs from its image store.
+    pub token: Option<String>,
+}
+
+impl Default for RegistryCacheOptions {
+    fn default() -> Self {
+        Self {
+            listen: SocketAddr::from(([127, 0, 0, 1], 5000)),
+            upstream: "docker.io".to_string(),
+            token: None,
+        }
+    }
+}
+
+/// Upstream access on behalf of one class of client.
+pub(crate) struct CacheAccess {
+    /// Registry client used for upstream requests.
+    pub client: oci_client::Client,
+    /// Directory of blobs fetched for these clients.
+    pub blobs_dir: PathBuf,
+}
+
+/// Shared state for all connections.
+pub(crate) struct RegistryCache {
+    storage: Arc<ImageStorage>,
+    upstream: String,
+    /// Normalized registries clients may select with `ns`.
+    upstreams: Vec<String>,
+    token: Option<String>,
+    trusted: CacheAccess,
+    anonymous: CacheAccess,
+}
+
+impl RegistryCache {
+    /// `upstreams` lists the registries besides `upstream` that clients may
+    /// select with `ns`.
+    pub(crate) fn new(
+        storage: Arc<ImageStorage>,
+        options: RegistryCacheOptions,
+        upstreams: &[String],
+        trusted: CacheAccess,
+        anonymous: CacheAccess,
+    ) -> BoxliteResult<Self> {
+        for dir in [&trusted.blobs_dir, &anonymous.blobs_dir] {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                BoxliteError::Storage(format!("Failed to create {}: {}", dir.display(), e))
+            })?;
+        }
+        let mut allowed = vec![normalize_registry(&options.upstream)];
+        for registry in upstreams {
+            let registry = normalize_registry(registry);
+            if !allowed.contains(&registry) {
+                allowed.push(registry);
+            }
+        }
+        Ok(Self {
+            storage,
+            upstream: options.upstream,
+            upstreams: allowed,
+            token: options.token.filter(|token| !token.is_empty()),
+            trusted,
+            anonymous,
+        })
+    }
+
+    /// The upstream a request's `ns` parameter selects, or the rejected `ns`.
+    fn select_upstream(&self, query: Option<&str>) -> Result<String, String> {
+        let Some(ns) = query.and_then(|q| q.split('&').find_map(|kv| kv.strip_prefix("ns=")))
+        else {
+            return Ok(self.upstream.clone());
+        };
+        if self.upstreams.contains(&normalize_registry(ns)) {
+            Ok(ns.to_string())
+        } else {
+            Err(ns.to_string())
+        }
+    }
+
+    /// Whether the request carries the access token.
+    fn is_trusted(&self, headers: &hyper::HeaderMap) -> bool {
+        let Some(token) = &self.token else {
+            return false;
+        };
+        let Some(value) = headers.get(AUTHORIZATION).and_then(|v| v.to_str().ok()) else {
+            return false;
+        };
+        if let Some(bearer) = value.strip_prefix("Bearer ") {
+            return token_matches(bearer.trim(), token);
+        }
+        value
+            .strip_prefix("Basic ")
+            .and_then(|encoded| {
+                use base64::Engine;
+                base64::engine::general_purpose::STANDARD
+                    .decode(encoded.trim())
+                    .ok()
+            })
+            .and_then(|decoded| String::from_utf8(decoded).ok())
+            .and_then(|pair| {
+                pair.split_once(':')
+                    .map(|(_, password)| token_matches(password, token))
+            })
+            .unwrap_or(false)
+    }
+
+    /// Accept connections until the task is dropped.
+    ///
+    /// Fails only if `listen` cannot be bound; a failed accept (e.g. out of
+    /// file descriptors) is logged and retried after a short pause.
+    pub(crate) async fn serve(self: Arc<Self>, listen: SocketAddr) -> BoxliteResult<()> {
+        let listener = TcpListener::bind(listen)
+            .await
+            .map_err(|e| BoxliteError::Network(format!("Failed to bind {}: {}", listen, e)))?;
+        tracing::info!(
+            %listen,
+            upstream = %self.upstream,
+            token = self.token.is_some(),
+            "Registry cache listening"
+        );
+
+        loop {
+            let (stream, peer) = match listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Registry cache accept failed");
+                    tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
+                    continue;
+                }
+            };
+            let cache = Arc::clone(&self);
+            tokio::spawn(async move {
+                let service = hyper::service::service_fn(move |req| {
+                    let cache = Arc::clone(&cache);
+                    async move { Ok::<_, Infallible>(cache.handle(req).await) }
+                });
+                if let Err(e) = hyper::server::conn::http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await
+                {
+                    tracing::debug!(%peer, error = %e, "Registry cache connection closed");
+                }
+            });
+        }
+    }
+
+    async fn handle(&self, req: Request<Incoming>) -> Response<Body> {
+        let head = req.method() == Method::HEAD;
+        if req.method() != Method::GET && !head {
+            return error_response(StatusCode::METHOD_NOT_ALLOWED, "UNSUPPORTED", "read-only");
+        }
+
+        let path = req.uri().path();
+        let upstream = match self.select_upstream(req.uri().query()) {
+            Ok(upstream) => upstream,
+            Err(ns) => {
+                return error_response(
+                    StatusCode::FORBIDDEN,
+                    "DENIED",
+                    &format!("upstream {} is not configured", ns),
+                );
+            }
+        };
+        let trusted = self.is_trusted(req.headers());
+
+        let Some(rest) = path.strip_prefix("/v2") else {
+            return error_response(StatusCode::NOT_FOUND, "NAME_UNKNOWN", "not found");
+        };
+        if rest.is_empty() || rest == "/" {
+            return json_response(StatusCode::OK, Bytes::from_static(b"{}"));
+        }
+
+        let response = match parse_route(rest) {
+            Some(Route::Manifest { name, reference }) => {
+                let accept: Vec<String> = req
+                    .headers()
+                    .get_all(ACCEPT)
+                    .iter()
+                    .filter_map(|v| v.to_str().ok())
+                    .flat_map(|v| v.split(',').map(|t| t.trim().to_string()))
+                    .collect();
+                self.manifest(&upstream, name, reference, &accept, trusted)
+                    .await
+            }
+            Some(Route::Blob { name, digest }) => self.blob(&upstream, name, digest, trusted).await,
+            None => return error_response(StatusCode::NOT_FOUND, "NAME_UNKNOWN", "not found"),
+        };
+
+        match response {
+            Ok(mut response) => {
+                if head {
+                    *response.body_mut() = empty();
+                }
+                response
+            }
+            Err((code, e)) => {
+                tracing::warn!(path = %path, error = %e, "Registry cache request failed");
+                error_response(StatusCode::NOT_FOUND, code, &e.to_string())
+            }
+        }
+    }
+
+    async fn manifest(
+        &self,
+        upstream: &str,
+        name: &str,
+        reference: &str,
+        accept: &[String],
+        trusted: bool,
+    ) -> Result<Response<Body>, (&'static str, BoxliteError)> {
+        let fail = |e: BoxliteError| ("MANIFEST_UNKNOWN", e);
+        let image = upstream_reference(upstream, name, reference).map_err(fail)?;
+
+        let default_types = [
+            OCI_IMAGE_MEDIA_TYPE,
+            OCI_IMAGE_INDEX_MEDIA_TYPE,
+            IMAGE_MANIFEST_MEDIA_TYPE,
+            IMAGE_MANIFEST_LIST_MEDIA_TYPE,
+        ];
+        let accept: Vec<&str> = if accept.is_empty() {
+            default_types.to_vec()
+        } else {
+            accept.iter().map(String::as_str).collect()
+        };
+
+        let (bytes, digest) = self
+            .access(trusted)
+            .client
+            .pull_manifest_raw(&image, &self.upstream_auth(&image, trusted), &accept)
+            .await
+            .map_err(|e| fail(BoxliteError::Image(format!("upstream manifest: {e}"))))?;
+
+        let media_type = serde_json::from_slice::<serde_json::Value>(&bytes)
+            .ok()
+            .and_then(|v| v["mediaType"].as_str().map(str::to_string))
+            .unwrap_or_else(|| OCI_IMAGE_MEDIA_TYPE.to_string());
+
+        let len = bytes.len();
+        let mut response = Response::new(full(Bytes::from(bytes)));
+        set_header(&mut response, CONTENT_TYPE.as_str(), &media_type);
+        set_header(&mut response, CONTENT_LENGTH.as_str(), &len.to_string());
+        set_header(&mut response, DIGEST_HEADER, &digest);
+        Ok(response)
+    }
+
+    async fn blob(
+        &self,
+        upstream: &str,
+        name: &str,
+        digest: &str,
+        trusted: bool,
+    ) -> Result<Response<Body>, (&'static str, BoxliteError)> {
+        let fail = |e: BoxliteError| ("BLOB_UNKNOWN", e);
+        let hex = digest
+            .strip_prefix("sha256:")
+            .filter(|h| h.len() == 64 && h.chars().all(|c| c.is_ascii_hexdigit()))
+            .ok_or_else(|| {
+                fail(BoxliteError::InvalidArgument(format!(
+                    "unsupported digest {digest}"
+                )))
+            })?;
+
+        let path = match self.local_blob(digest, hex, trusted) {
+            Some(path) => path,
+            None => {
+                let image = upstream_reference(upstream, name, digest).map_err(fail)?;
+                self.fetch_blob(&image, digest, hex, trusted)
+                    .await
+                    .map_err(fail)?
+            }
+        };
+
+        let file = tokio::fs::File::open(&path)
+            .await
+            .map_err(|e| fail(BoxliteError::Storage(format!("open blob: {e}"))))?;
+        let len = file
+            .metadata()
+            .await
+            .map_err(|e| fail(BoxliteError::Storage(format!("stat blob: {e}"))))?
+            .len();
+
+        let stream = tokio_util::io::ReaderStream::new(file).map_ok(Frame::data);
+        let mut response = Response::new(BodyExt::boxed(StreamBody::new(stream)));
+        set_header(
+            &mut response,
+            CONTENT_TYPE.as_str(),
+            "application/octet-stream",
+        );
+        set_header(&mut response, CONTENT_LENGTH.as_str(), &len.to_string());
+        set_header(&mut response, DIGEST_HEADER, digest);
+        Ok(response)
+    }
+
+    fn access(&self, trusted: bool) -> &CacheAccess {
+        if trusted {
+            &self.trusted
+        } else {
+            &self.anonymous
+        }
+    }
+
+    /// Host credentials for trusted clients; nothing for anonymous ones.
+    fn upstream_auth(&self, image: &Reference, trusted: bool) -> RegistryAuth {
+        if trusted {
+            registry_auth(image.resolve_registry())
+        } else {
+            RegistryAuth::Anonymous
+        }
+    }
+
+    /// Find a blob this client may be served without going upstream.
+    ///
+    /// Trusted clients see the image store and every cached blob; anonymous
+    /// clients only blobs that were fetched anonymously.
+    fn local_blob(&self, digest: &str, hex: &str, trusted: bool) -> Option<PathBuf> {
+        let mut candidates = vec![self.anonymous.blobs_dir.join(hex)];
+        if trusted {
+            candidates.extend([
+                self.storage.layer_tarball_path(digest),
+                self.storage.config_path(digest),
+                self.trusted.blobs_dir.join(hex),
+            ]);
+        }
+        candidates.into_iter().find(|p| p.is_file())
+    }
+
+    /// Download a blob from upstream into the client's blob directory.
+    async fn fetch_blob(
+        &self,
+        image: &Reference,
+        digest: &str,
+        hex: &str,
+        trusted: bool,
+    ) -> BoxliteResult<PathBuf> {
+        let access = self.access(trusted);
+        access
+            .client
+            .auth(
+                image,
+                &self.upstream_auth(image, trusted),
+                RegistryOperation::Pull,
+            )
+            .await
+            .map_err(|e| BoxliteError::Image(format!("upstream auth: {e}")))?;
+
+        let dest = access.blobs_dir.join(hex);
+        let staged = access
+            .blobs_dir
+            .join(format!("{}.partial-{}", hex, uuid::Uuid::new_v4()));
+        let result = async {
+            let mut file = tokio::fs::File::create(&staged)
+                .await
+                .map_err(|e| BoxliteError::Storage(format!("create {}: {e}", staged.display())))?;
+            access
+                .client
+                .pull_blob(image, digest, &mut file)
+                .await
+                .map_err(|e| BoxliteError::Image(format!("upstream blob {digest}: {e}")))?;
+            // Other clients are served the blob as soon as it is renamed
+            file.flush()
+                .await
+                .and(file.sync_all().await)
+                .map_err(|e| BoxliteError::Storage(format!("write blob {digest}: {e}")))?;
+            tokio::fs::rename(&staged, &dest)
+                .await
+                .map_err(|e| BoxliteError::Storage(format!("store blob {digest}: {e}")))
+        }
+        .await;
+
+        if let Err(e) = result {
+            let _ = tokio::fs::remove_file(&staged).await;
+            return Err(e);
+        }
+        tracing::info!(%digest, "Cached blob from {}", image.registry());
+        Ok(dest)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Route<'a> {
+    Manifest { name: &'a str, reference: &'a str },
+    Blob { name: &'a str, digest: &'a str },
+}
+
+/// Split `/<name>/manifests/<ref>` or `/<name>/blobs/<digest>`.
+fn parse_route(path: &str) -> Option<Route<'_>> {
+    let path = path.strip_prefix('/')?;
+    if let Some((name, reference)) = path.rsplit_once("/manifests/") {
+        return (!name.is_empty() && !reference.is_empty())
+            .then_some(Route::Manifest { name, reference });
+    }
+    if let Some((name, digest)) = path.rsplit_once("/blobs/") {
+        return (!name.is_empty() && !digest.is_empty()).then_some(Route::Blob { name, digest });
+    }
+    None
+}
+
+fn upstream_reference(upstream: &str, name: &str, reference: &str) -> BoxliteResult<Reference> {
+    let sep = if reference.contains(':') { '@' } else { ':' };
+    format!("{}/{}{}{}", upstream, name, sep, reference)
+        .parse()
+        .map_err(|e| BoxliteError::InvalidArgument(format!("invalid reference: {e}")))
+}
+
+/// Compare a presented token in constant time, so timing does not leak
+/// how much of it is right.
+fn token_matches(presented: &str, token: &str) -> bool {
+    presented.as_bytes().ct_eq(token.as_bytes()).into()
+}
+
+fn full(bytes: Bytes) -> Body {
+    Full::new(bytes).map_err(|never| match never {}).boxed()
+}
+
+fn empty() -> Body {
+    full(Bytes::new())
+}
+
+fn set_header(response: &mut Response<Body>, name: &'static str, value: &str) {
+    if let Ok(value) = HeaderValue::from_str(value) {
+        response.headers_mut().insert(name, value);
+    }
+}
+
+fn json_response(status: StatusCode, body: Bytes) -> Response<Body> {
+    let mut response = Response::new(full(body));
+    *response.status_mut() = status;
+    set_header(&mut response, CONTENT_TYPE.as_str(), "application/json");
+    set_header(
+        &mut response,
+        "Docker-Distribution-API-Version",
+        "registry/2.0",
+    );
+    response
+}
+
+fn error_response(status: StatusCode, code: &str, message: &str) -> Response<Body> {
+    let body = serde_json::json!({ "errors": [{ "code": code, "message": message }] });
+    json_response(status, Bytes::from(body.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_route() {
+        assert_eq!(
+            parse_route("/library/alpine/manifests/3.19"),
+            Some(Route::Manifest {
+                name: "library/alpine",
+                reference: "3.19"
+            })
+        );
+        assert_eq!(
+            parse_route("/acme/blobs/app/blobs/sha256:abc"),
+            Some(Route::Blob {
+                name: "acme/blobs/app",
+                digest: "sha256:abc"
+            })
+        );
+        assert_eq!(parse_route("/library/alpine/tags/list"), None);
+        assert_eq!(parse_route("/manifests/latest"), None);
+    }
+
+    #[test]
+    fn test_upstream_reference() {
+        let by_tag = upstream_reference("docker.io", "library/alpine", "3.19").unwrap();
+        assert_eq!(by_tag.whole(), "docker.io/library/alpine:3.19");
+
+        let digest = format!("sha256:{}", "a".repeat(64));
+        let by_digest = upstream_reference("ghcr.io", "acme/app", &digest).unwrap();
+        assert_eq!(by_digest.digest(), Some(digest.as_str()));
+    }
+
+    fn cache(dir: &std::path::Path, token: Option<&str>) -> RegistryCache {
+        let storage = Arc::new(ImageStorage::new(dir.join("images")).unwrap());
+        let access = |name: &str| CacheAccess {
+            client: Default::default(),
+            blobs_dir: dir.join(name),
+        };
+        RegistryCache::new(
+            storage,
+            RegistryCacheOptions {
+                token: token.map(str::to_string),
+                ..Default::default()
+            },
+            &["ghcr.io".to_string()],
+            access("trusted"),
+            access("anonymous"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_default_listen_is_loopback() {
+        assert!(RegistryCacheOptions::default().listen.ip().is_loopback());
+    }
+
+    #[test]
+    fn test_select_upstream_only_configured() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cache = cache(dir.path(), None);
+        assert_eq!(cache.select_upstream(None).unwrap(), "docker.io");
+        assert_eq!(
+            cache.select_upstream(Some("ns=ghcr.io")).unwrap(),
+            "ghcr.io"
+        );
+        assert_eq!(
+            cache
+                .select_upstream(Some("ns=registry-1.docker.io"))
+                .unwrap(),
+            "registry-1.docker.io"
+        );
+        assert_eq!(
+            cache
+                .select_upstream(Some("ns=169.254.169.254"))
+                .unwrap_err(),
+            "169.254.169.254"
+        );
+        assert!(cache.select_upstream(Some("ns=localhost:8080")).is_err());
+    }
+
+    #[test]
+    fn test_is_trusted() {
+        use base64::Engine;
+
+        let header = |value: &str| {
+            let mut headers = hyper::HeaderMap::new();
+            headers.insert(AUTHORIZATION, value.parse().unwrap());
+            headers
+        };
+        let basic = |pair: &str| {
+            header(&format!(
+                "Basic {}",
+                base64::engine::general_purpose::STANDARD.encode(pair)
+            ))
+        };
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let open = cache(dir.path(), None);
+        assert!(!open.is_trusted(&hyper::HeaderMap::new()));
+        assert!(!open.is_trusted(&header("Bearer ")));
+
+        let guarded = cache(dir.path(), Some("s3cret"));
+        assert!(guarded.is_trusted(&header("Bearer s3cret")));
+        assert!(guarded.is_trusted(&basic("mirror:s3cret")));
+        assert!(!guarded.is_trusted(&header("Bearer wrong")));
+        assert!(!guarded.is_trusted(&basic("s3cret:wrong")));
+        assert!(!guarded.is_trusted(&hyper::HeaderMap::new()));
+    }
+
+    #[test]
+    fn test_local_blob_lookup() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cache = cache(dir.path(), Some("s3cret"));
+
+        let hex = "b".repeat(64);
+        let digest = format!("sha256:{}", hex);
+        assert!(cache.local_blob(&digest, &hex, true).is_none());
+
+        // Blobs fetched with host credentials stay with trusted clients
+        let private = cache.trusted.blobs_dir.join(&hex);
+        std::fs::write(&private, b"blob").unwrap();
+        assert_eq!(cache.local_blob(&digest, &hex, true), Some(private));
+        assert!(cache.local_blob(&digest, &hex, false).is_none());
+
+        let public = cache.anonymous.blobs_dir.join(&hex);
+        std::fs::write(&public, b"blob").unwrap();
+        assert_eq!(cache.local_blob(&digest, &hex, false), Some(public));
+    }
+
+    #[test]
+    fn test_store_blobs_not_served_anonymously() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cache = cache(dir.path(), Some("s3cret"));
+
+        let hex = "c".repeat(64);
+        let digest = format!("sha256:{}", hex);
+        let layer = cache.storage.layer_tarball_path(&digest);
+        std::fs::create_dir_all(layer.parent().unwrap()).unwrap();
+        std::fs::write(&layer, b"private layer").unwrap();
+
+        assert_eq!(cache.local_blob(&digest, &hex, true), Some(layer));
+        assert!(cache.local_blob(&digest, &hex, false).is_none());
+    }
+}
@@ -0,0 +1,222 @@
+//! Registry HTTP clients.
+//!
+//! TLS settings are per registry (`BoxliteOptions::registries`), but an
+//! `oci_client::Client` applies its TLS configuration to every request. A
+//! dedicated client is therefore built for each configured registry, and all
+//! other registries share a default client. Every client carries the same
+//! proxy settings and plain-HTTP host list.
+//!
+//! Blob pushes stream layers through a plain `reqwest::Client` (see
+//! [`BlobUploader`]), built alongside each registry client with the same
+//! TLS and proxy settings.
+
+use std::collections::HashMap;
+
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+use oci_client::Client;
+use oci_client::client::{Certificate, CertificateEncoding, ClientConfig, ClientProtocol};
+
+use super::auth::{normalize_registry, registry_auth};
+use super::blob_cache::http_client_builder;
+use super::blob_upload::BlobUploader;
+use super::mirrors::RegistryMirrors;
+use crate::runtime::options::{ProxyOptions, RegistryConfig};
+
+/// Registry clients keyed by normalized registry host.
+#[derive(Clone)]
+pub(crate) struct RegistryClients {
+    default: Client,
+    per_registry: HashMap<String, Client>,
+    upload_default: reqwest::Client,
+    upload_per_registry: HashMap<String, reqwest::Client>,
+    plain_http_hosts: Vec<String>,
+    settings: (
+        HashMap<String, RegistryConfig>,
+        RegistryMirrors,
+        ProxyOptions,
+    ),
+}
+
+impl RegistryClients {
+    pub(crate) fn new(
+        registries: &HashMap<String, RegistryConfig>,
+        mirrors: &RegistryMirrors,
+        proxy: &ProxyOptions,
+    ) -> BoxliteResult<Self> {
+        let mut plain_http_hosts = mirrors.insecure_hosts();
+        plain_http_hosts.extend(
+            registries
+                .iter()
+                .filter(|(_, config)| config.plain_http)
+                .map(|(registry, _)| normalize_registry(registry)),
+        );
+        // ClientConfig is not Clone, so build a fresh base for each client.
+        let base = || ClientConfig {
+            protocol: if plain_http_hosts.is_empty() {
+                ClientProtocol::Https
+            } else {
+                ClientProtocol::HttpsExcept(plain_http_hosts.clone())
+            },
+            http_proxy: proxy.http_proxy.clone(),
+            https_proxy: proxy.https_proxy.clone(),
+            no_proxy: proxy.no_proxy.clone(),
+            ..Default::default()
+        };
+
+        let invalid_tls = |registry: &str, e: &dyn std::fmt::Display| {
+            BoxliteError::Config(format!(
+                "invalid TLS settings for registry '{}': {}",
+                registry, e
+            ))
+        };
+        let build_upload = |builder: reqwest::ClientBuilder, registry: &str| {
+            builder.build().map_err(|e| invalid_tls(registry, &e))
+        };
+
+        let mut per_registry = HashMap::new();
+        let mut upload_per_registry = HashMap::new();
+        for (registry, config) in registries {
+            if !config.insecure && config.ca_file.is_none() {
+                continue;
+            }
+            let certificates = load_ca_file(registry, config)?;
+            let mut upload =
+                http_client_builder(proxy)?.danger_accept_invalid_certs(config.insecure);
+            for certificate in &certificates {
+                let certificate = reqwest::Certificate::from_pem(&certificate.data)
+                    .map_err(|e| invalid_tls(registry, &e))?;
+                upload = upload.add_root_certificate(certificate);
+            }
+            let client_config = ClientConfig {
+                accept_invalid_certificates: config.insecure,
+                extra_root_certificates: certificates,
+                ..base()
+            };
+            let client = Client::try_from(client_config).map_err(|e| invalid_tls(registry, &e))?;
+            per_registry.insert(normalize_registry(registry), client);
+            upload_per_registry.insert(
+                normalize_registry(registry),
+                build_upload(upload, registry)?,
+            );
+        }
+
+        Ok(Self {
+            default: Client::new(base()),
+            per_registry,
+            upload_default: build_upload(http_client_builder(proxy)?, "default")?,
+            upload_per_registry,
+            plain_http_hosts,
+            settings: (registries.clone(), mirrors.clone(), proxy.clone()),
+        })
+    }
+
+    /// Client for `registry` with a token cache of its own.
+    ///
+    /// `oci_client::Client` clones share cached tokens, so requests that must
+    /// not reuse tokens obtained with host credentials get a fresh client.
+    pub(crate) fn isolated_client(&self, registry: &str) -> BoxliteResult<Client> {
+        let (registries, mirrors, proxy) = &self.settings;
+        Ok(Self::new(registries, mirrors, proxy)?
+            .for_registry(registry)
+            .clone())
+    }
+
+    /// Uploader for pushing blobs to `registry` (as in `Reference::resolve_registry`).
+    pub(crate) fn blob_uploader(&self, registry: &str) -> BoxliteResult<BlobUploader> {
+        let normalized = normalize_registry(registry);
+        let client = self
+            .upload_per_registry
+            .get(&normalized)
+            .unwrap_or(&self.upload_default);
+        let scheme = if self.plain_http_hosts.contains(&normalized)
+            || self.plain_http_hosts.iter().any(|host| host == registry)
+        {
+            "http"
+        } else {
+            "https"
+        };
+        let base = reqwest::Url::parse(&format!("{}://{}/", scheme, registry)).map_err(|e| {
+            BoxliteError::InvalidArgument(format!("invalid registry '{}': {}", registry, e))
+        })?;
+        Ok(BlobUploader::new(
+            client.clone(),
+            base,
+            registry_auth(registry),
+        ))
+    }
+
+    /// Client to use for requests to `registry` (as in `Reference::resolve_registry`).
+    pub(crate) fn for_registry(&self, registry: &str) -> &Client {
+        self.per_registry
+            .get(&normalize_registry(registry))
+            .unwrap_or(&self.default)
+    }
+}
+
+fn load_ca_file(registry: &str, config: &RegistryConfig) -> BoxliteResult<Vec<Certificate>> {
+    let Some(path) = &config.ca_file else {
+        return Ok(Vec::new());
+    };
+    let data = std::fs::read(path).map_err(|e| {
+        BoxliteError::Config(format!(
+            "failed to read CA file {} for registry '{}': {}",
+            path.display(),
+            registry,
+            e
+        ))
+    })?;
+    Ok(vec![Certificate {
+        encoding: CertificateEncoding::Pem,
+        data,
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_ca_file_is_config_error() {
+        let registries = HashMap::from([(
+            "registry.corp:5000".to_string(),
+            RegistryConfig {
+                ca_file: Some("/nonexistent/ca.pem".into()),
+                ..Default::default()
+            },
+        )]);
+        let result = RegistryClients::new(
+            &registries,
+            &RegistryMirrors::default(),
+            &ProxyOptions::default(),
+        );
+        assert!(matches!(result, Err(BoxliteError::Config(_))));
+    }
+
+    #[test]
+    fn test_only_tls_overrides_get_dedicated_clients() {
+        let registries = HashMap::from([
+            (
+                "https://index.docker.io/v1/".to_string(),
+                RegistryConfig {
+                    insecure: true,
+                    ..Default::default()
+                },
+            ),
+            (
+                "localhost:5000".to_string(),
+                RegistryConfig {
+                    plain_http: true,
+                    ..Default::default()
+                },
+            ),
+        ]);
+        let clients = RegistryClients::new(
+            &registries,
+            &RegistryMirrors::default(),
+            &ProxyOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(clients.per_registry.len(), 1);
+        assert!(clients.per_registry.contains_key("docker.io"));
+    }
+}
@@ -9,11 +9,14 @@
 //! - Registry communication (ImageManager's responsibility)
 //! - Cache lookup logic (ImageManager's responsibility)
 
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 use oci_client::manifest::OciManifest;
 
 use crate::images::archive;
+use crate::images::dedup::{ContentStore, tree_usage};
 use crate::runtime::layout::ImageFilesystemLayout;
 use boxlite_shared::errors::{BoxliteError, BoxliteResult};
 
@@ -28,12 +31,15 @@ use boxlite_shared::errors::{BoxliteError, BoxliteResult};
 /// verification.
 pub struct ImageStorage {
     layout: ImageFilesystemLayout,
+    /// Content store used to dedup extracted layers (None = disabled)
+    content_store: Option<ContentStore>,
 }
 
 impl std::fmt::Debug for ImageStorage {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ImageStorage")
             .field("images_dir", &self.layout.root())
+            .field("dedup", &self.content_store.is_some())
             .finish()
     }
 }
@@ -43,35 +49,51 @@ impl ImageStorage {
     pub fn new(images_dir: PathBuf) -> BoxliteResult<Self> {
         let layout = ImageFilesystemLayout::new(images_dir);
         layout.prepare()?;
-        Ok(Self { layout })
+        Ok(Self {
+            layout,
+            content_store: None,
+        })
+    }
+
+    /// Dedup identical files of newly extracted layers through the content
+    /// store (`images/content`).
+    pub fn with_dedup(mut self, enabled: bool) -> Self {
+        self.content_store = enabled.then(|| ContentStore::new(self.layout.content_dir()));
+        self
     }
 
     // ========================================================================
     // MANIFEST OPERATIONS [atomic, &self]
     // ========================================================================
 
-    /// Save manifest to disk using digest as filename.
+    /// Save manifest bytes, as served by the registry, using digest as filename.
     ///
-    /// **Mutability**: Atomic - writes file only if it doesn't exist, safe for
-    /// concurrent access (idempotent check-then-write).
-    pub fn save_manifest(&self, manifest: &OciManifest, digest: &str) -> BoxliteResult<()> {
+    /// The bytes are kept unchanged so the file keeps hashing to `digest`;
+    /// digest-pinned references re-check this on every use. An existing file
+    /// that no longer matches is replaced.
+    ///
+    /// **Mutability**: Atomic - writes a temp file and renames it into place,
+    /// safe for concurrent access.
+    pub fn save_manifest(&self, bytes: &[u8], digest: &str) -> BoxliteResult<()> {
         let manifest_path = self.manifest_path(digest);
 
-        if manifest_path.exists() {
+        if file_digest(&manifest_path)?.as_deref() == Some(digest) {
             tracing::debug!("Manifest already exists: {}", digest);
             return Ok(());
         }
 
-        let manifest_json = serde_json::to_string_pretty(manifest)
-            .map_err(|e| BoxliteError::Storage(format!("Failed to serialize manifest: {}", e)))?;
-
-        std::fs::write(&manifest_path, manifest_json).map_err(|e| {
-            BoxliteError::Storage(format!(
-                "Failed to write manifest to {}: {}",
-                manifest_path.display(),
-                e
-            ))
-        })?;
+        let staged_path =
+            manifest_path.with_extension(format!("{}.downloading", uuid::Uuid::new_v4().simple()));
+        std::fs::write(&staged_path, bytes)
+            .and_then(|_| std::fs::rename(&staged_path, &manifest_path))
+            .map_err(|e| {
+                let _ = std::fs::remove_file(&staged_path);
+                BoxliteError::Storage(format!(
+                    "Failed to write manifest to {}: {}",
+                    manifest_path.display(),
+                    e
+                ))
+            })?;
 
         tracing::debug!("Saved manifest: {}", digest);
         Ok(())
@@ -235,6 +257,20 @@ impl ImageStorage {
             return Err(e);
         }
 
+        // Dedup before publishing: the extracted dir is immutable afterwards
+        if let Some(store) = &self.content_store {
+            match store.dedup_tree(&temp_path) {
+                Ok(stats) if stats.files > 0 => tracing::debug!(
+                    "Deduplicated {} files ({} bytes) in layer {}",
+                    stats.files,
+                    stats.bytes,
+                    digest
+                ),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Failed to dedup layer {}: {}", digest, e),
+            }
+        }
+
         // Atomic rename: only one thread/process wins
         match std::fs::rename(&temp_path, &extracted_path) {
             Ok(()) => {
@@ -320,7 +356,6 @@ impl ImageStorage {
     /// Load config blob from disk.
     ///
     /// **Mutability**: Immutable - reads file only, no state changes.
-    #[allow(dead_code)]
     pub fn load_config(&self, digest: &str) -> BoxliteResult<String> {
         let config_path = self.config_path(digest);
 
@@ -406,6 +441,168 @@ impl ImageStorage {
         ))
     }
 
+    // ========================================================================
+    // PRUNE OPERATIONS [mutable, &self]
+    // ========================================================================
+
+    /// Bytes the cache occupies on disk: blobs, extracted layers, content
+    /// objects and disk images, with shared inodes counted once.
+    ///
+    /// **Mutability**: Immutable - walks the cache directories.
+    pub fn cache_usage(&self) -> u64 {
+        tree_usage(&[
+            self.layout.manifests_dir(),
+            self.layout.configs_dir(),
+            self.layout.layers_dir(),
+            self.layout.extracted_dir(),
+            self.layout.content_dir(),
+            self.layout.disk_images_dir(),
+        ])
+        .physical
+    }
+
+    /// Delete a layer's tarball and extracted tree.
+    ///
+    /// **Mutability**: Mutable - callers must make sure no image uses the layer.
+    pub fn remove_layer(&self, digest: &str) -> BoxliteResult<()> {
+        remove_path(&self.layer_tarball_path(digest))?;
+        remove_path(&self.layer_extracted_path(digest))
+    }
+
+    /// Delete a manifest and a config blob.
+    ///
+    /// **Mutability**: Mutable - callers must make sure no image uses them.
+    pub fn remove_manifest_and_config(
+        &self,
+        manifest_digest: &str,
+        config_digest: Option<&str>,
+    ) -> BoxliteResult<()> {
+        remove_path(&self.manifest_path(manifest_digest))?;
+        match config_digest {
+            Some(digest) => remove_path(&self.config_path(digest)),
+            None => Ok(()),
+        }
+    }
+
+    /// Delete the disk images built for `image_digest`, in every format,
+    /// except those in `keep`.
+    ///
+    /// **Mutability**: Mutable - callers must make sure no image uses them.
+    pub fn remove_disk_images(
+        &self,
+        image_digest: &str,
+        keep: &HashSet<PathBuf>,
+    ) -> BoxliteResult<()> {
+        use crate::disk::DiskFormat;
+
+        for format in [
+            DiskFormat::Ext4,
+            DiskFormat::Qcow2,
+            DiskFormat::Erofs,
+            DiskFormat::Squashfs,
+        ] {
+            let path = self.disk_image_path(image_digest, format);
+            let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+            if keep.contains(&canonical) {
+                tracing::debug!(path = %path.display(), "Keeping disk image backing a box");
+                continue;
+            }
+            remove_path(&path)?;
+        }
+        Ok(())
+    }
+
+    /// Delete blobs, extracted layers and disk images that `referenced` does
+    /// not name, e.g. left behind when a reference moved to a new digest.
+    ///
+    /// Only digest-named entries last modified before `older_than` are
+    /// touched, so files of a pull in progress and custom guest rootfs disks
+    /// stay. Disk images in `keep_disks` (backing files of box disks) stay
+    /// too. Returns the number of entries removed.
+    ///
+    /// **Mutability**: Mutable - callers must hold off pulls that rely on the index.
+    pub fn remove_unreferenced(
+        &self,
+        referenced: &ReferencedBlobs,
+        keep_disks: &HashSet<PathBuf>,
+        older_than: SystemTime,
+    ) -> BoxliteResult<u64> {
+        use crate::disk::DiskFormat;
+
+        let disk_suffixes = [
+            DiskFormat::Ext4,
+            DiskFormat::Qcow2,
+            DiskFormat::Erofs,
+            DiskFormat::Squashfs,
+        ]
+        .map(|format| format!(".{}", format.as_str()));
+        let dirs = [
+            (
+                self.layout.manifests_dir(),
+                vec![".json".to_string()],
+                &referenced.manifests,
+            ),
+            (
+                self.layout.configs_dir(),
+                vec![".json".to_string()],
+                &referenced.configs,
+            ),
+            (
+                self.layout.layers_dir(),
+                vec![".tar.gz".to_string()],
+                &referenced.layers,
+            ),
+            (
+                self.layout.extracted_dir(),
+                vec![String::new()],
+                &referenced.layers,
+            ),
+            (
+                self.layout.disk_images_dir(),
+                disk_suffixes.to_vec(),
+                &referenced.disk_images,
+            ),
+        ];
+
+        let mut removed = 0;
+        for (dir, suffixes, names) in dirs {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.filter_map(Result::ok) {
+                let file_name = entry.file_name();
+                let Some(digest) = file_name
+                    .to_str()
+                    .and_then(|name| suffixes.iter().find_map(|suffix| digest_of(name, suffix)))
+                else {
+                    continue;
+                };
+                let stale = entry
+                    .metadata()
+                    .and_then(|metadata| metadata.modified())
+                    .is_ok_and(|modified| modified < older_than);
+                let path = entry.path();
+                if names.contains(&digest)
+                    || !stale
+                    || keep_disks.contains(&path.canonicalize().unwrap_or_else(|_| path.clone()))
+                {
+                    continue;
+                }
+                tracing::debug!(path = %path.display(), "Removing unreferenced image data");
+                remove_path(&path)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Delete content store objects no extracted layer links to any more.
+    ///
+    /// **Mutability**: Mutable - returns the number of objects removed.
+    pub fn prune_content(&self) -> BoxliteResult<u64> {
+        ContentStore::new(self.layout.content_dir()).remove_unlinked()
+    }
+
     // ========================================================================
     // UTILITY OPERATIONS [immutable, &self]
     // ========================================================================
@@ -481,6 +678,56 @@ impl ImageStorage {
     }
 }
 
+/// Digests still in use, by kind of cache entry (see
+/// [`ImageStorage::remove_unreferenced`]).
+#[derive(Debug, Default)]
+pub(crate) struct ReferencedBlobs {
+    pub(crate) manifests: HashSet<String>,
+    pub(crate) configs: HashSet<String>,
+    pub(crate) layers: HashSet<String>,
+    /// Disk image digests (see [`disk_image_digest`])
+    pub(crate) disk_images: HashSet<String>,
+}
+
+/// Digest a cache entry named `sha256-{hex}{suffix}` stands for.
+fn digest_of(name: &str, suffix: &str) -> Option<String> {
+    let hex = name.strip_suffix(suffix)?.strip_prefix("sha256-")?;
+    (hex.len() == 64 && hex.bytes().all(|b| b.is_ascii_hexdigit()))
+        .then(|| format!("sha256:{}", hex))
+}
+
+/// Remove a file or directory tree, ignoring paths that do not exist.
+fn remove_path(path: &Path) -> BoxliteResult<()> {
+    let result = if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+    match result {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(BoxliteError::Storage(format!(
+            "Failed to remove {}: {}",
+            path.display(),
+            e
+        ))),
+    }
+}
+
+/// Digest of the disk image built from an image's layers.
+///
+/// Disk images are keyed by their layers rather than the manifest, so
+/// images sharing all layers share one disk.
+pub(crate) fn disk_image_digest<'a>(layers: impl IntoIterator<Item = &'a str>) -> String {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    for layer in layers {
+        hasher.update(layer.as_bytes());
+    }
+    format!("sha256:{:x}", hasher.finalize())
+}
+
 // ============================================================================
 // STAGED DOWNLOAD
 // ============================================================================
@@ -614,6 +861,37 @@ impl StagedDownload {
 // TESTS
 // ============================================================================
 
+/// SHA256 digest (`sha256:<hex>`) of the file at `path`, or None if it
+/// does not exist.
+pub(crate) fn file_digest(path: &Path) -> BoxliteResult<Option<String>> {
+    use sha2::{Digest, Sha256};
+    use std::io::Read;
+
+    let mut file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(BoxliteError::Storage(format!(
+                "Failed to open {}: {}",
+                path.display(),
+                e
+            )));
+        }
+    };
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf).map_err(|e| {
+            BoxliteError::Storage(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(Some(format!("sha256:{:x}", hasher.finalize())))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -671,6 +949,29 @@ mod tests {
         assert!(store.has_manifest("sha256:abc123"));
     }
 
+    #[test]
+    fn test_save_manifest_keeps_registry_bytes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store = ImageStorage::new(temp_dir.path().to_path_buf()).unwrap();
+
+        let bytes = br#"{"schemaVersion":2,  "layers":[]}"#;
+        let digest = {
+            use sha2::{Digest, Sha256};
+            format!("sha256:{:x}", Sha256::digest(bytes))
+        };
+        let path = store.manifest_path(&digest);
+        assert_eq!(file_digest(&path).unwrap(), None);
+
+        store.save_manifest(bytes, &digest).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), bytes);
+        assert_eq!(file_digest(&path).unwrap(), Some(digest.clone()));
+
+        // A tampered copy is replaced on the next save
+        std::fs::write(&path, "{}").unwrap();
+        store.save_manifest(bytes, &digest).unwrap();
+        assert_eq!(file_digest(&path).unwrap(), Some(digest));
+    }
+
     #[test]
     fn test_has_layer() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -730,4 +1031,44 @@ mod tests {
         std::fs::write(store.layer_tarball_path(&layer2), b"data2").unwrap();
         assert!(store.verify_blobs_exist(&[layer1, layer2]));
     }
+
+    #[test]
+    fn test_remove_unreferenced() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store = ImageStorage::new(temp_dir.path().to_path_buf()).unwrap();
+
+        let used = format!("sha256:{}", "a".repeat(64));
+        let unused = format!("sha256:{}", "b".repeat(64));
+        for digest in [&used, &unused] {
+            std::fs::write(store.manifest_path(digest), b"{}").unwrap();
+            std::fs::write(store.layer_tarball_path(digest), b"data").unwrap();
+        }
+        let custom_disk = store
+            .layout
+            .disk_images_dir()
+            .join("custom-guest-rootfs-1.ext4");
+        std::fs::write(&custom_disk, b"disk").unwrap();
+
+        let referenced = ReferencedBlobs {
+            manifests: HashSet::from([used.clone()]),
+            layers: HashSet::from([used.clone()]),
+            ..Default::default()
+        };
+        // Nothing is old enough yet
+        let removed = store
+            .remove_unreferenced(&referenced, &HashSet::new(), SystemTime::UNIX_EPOCH)
+            .unwrap();
+        assert_eq!(removed, 0);
+
+        let later = SystemTime::now() + std::time::Duration::from_secs(60);
+        let removed = store
+            .remove_unreferenced(&referenced, &HashSet::new(), later)
+            .unwrap();
+        assert_eq!(removed, 2);
+        assert!(store.manifest_path(&used).exists());
+        assert!(store.layer_tarball_path(&used).exists());
+        assert!(!store.manifest_path(&unused).exists());
+        assert!(!store.layer_tarball_path(&unused).exists());
+        assert!(custom_disk.exists());
+    }
 }
@@ -14,17 +14,31 @@
 //! - `layer_extracted()` - Get extracted layer path (extracts if needed)
 
 use crate::db::{CachedImage, Database, ImageIndexStore};
+use crate::images::aliases::ShortNameAliases;
+use crate::images::auth::registry_auth;
+use crate::images::blob_cache::SharedBlobCache;
 use crate::images::manager::{ImageManifest, LayerInfo};
-use crate::images::storage::ImageStorage;
+use crate::images::mirrors::RegistryMirrors;
+use crate::images::registry_client::RegistryClients;
+use crate::images::storage::{ImageStorage, ReferencedBlobs, disk_image_digest, file_digest};
+use crate::images::throttle::PullThrottle;
+use crate::metrics::{ImagePullMetrics, LayerPullMetrics};
+use crate::runtime::types::{CorruptBlob, ImageBlobKind, ImageVerification};
 use boxlite_shared::{BoxliteError, BoxliteResult};
-use oci_client::Reference;
+use chrono::{DateTime, Utc};
+use oci_client::errors::{OciDistributionError, OciErrorCode};
 use oci_client::manifest::{
-    ImageIndexEntry, OciDescriptor, OciImageIndex, OciImageManifest as ClientOciImageManifest,
+    IMAGE_MANIFEST_LIST_MEDIA_TYPE, IMAGE_MANIFEST_MEDIA_TYPE, ImageIndexEntry,
+    OCI_IMAGE_INDEX_MEDIA_TYPE, OCI_IMAGE_MEDIA_TYPE, OciDescriptor, OciImageIndex,
+    OciImageManifest as ClientOciImageManifest, OciManifest,
 };
-use oci_client::secrets::RegistryAuth;
+use oci_client::{Reference, RegistryOperation};
 use oci_spec::image::MediaType;
+use std::collections::{BTreeMap, HashSet};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Instant, SystemTime};
 use tokio::sync::RwLock;
 
 // ============================================================================
@@ -42,8 +56,8 @@ struct ImageStoreInner {
 }
 
 impl ImageStoreInner {
-    fn new(images_dir: PathBuf, db: Database) -> BoxliteResult<Self> {
-        let storage = Arc::new(ImageStorage::new(images_dir)?);
+    fn new(images_dir: PathBuf, db: Database, dedup_layers: bool) -> BoxliteResult<Self> {
+        let storage = Arc::new(ImageStorage::new(images_dir)?.with_dedup(dedup_layers));
         let index = ImageIndexStore::new(db);
         Ok(Self { index, storage })
     }
@@ -76,13 +90,49 @@ impl ImageStoreInner {
 /// let blob_source = BlobSource::Store(StoreBlobSource::new(storage));
 /// ```
 pub struct ImageStore {
-    /// OCI registry client (immutable, outside lock)
-    client: oci_client::Client,
+    /// Where images are pulled from (outside the store lock, replaced on reload)
+    registry: parking_lot::RwLock<Arc<RegistrySettings>>,
+    /// Layer download bandwidth limit (shared across concurrent pulls)
+    throttle: Arc<PullThrottle>,
     /// Mutable state protected by RwLock
     inner: RwLock<ImageStoreInner>,
+    /// Layers found in the local cache during registry pulls
+    layers_cached: Arc<AtomicU64>,
+    /// Layers downloaded during registry pulls
+    layers_fetched: Arc<AtomicU64>,
+}
+
+/// Registry-facing settings of an [`ImageStore`].
+///
+/// Replaced as a whole by [`ImageStore::set_registry`]; each step of a pull
+/// reads the settings current when it starts.
+#[derive(Clone)]
+pub(crate) struct RegistrySettings {
+    /// OCI registry clients (TLS and proxy settings)
+    pub(crate) clients: RegistryClients,
     /// Registries to search for unqualified image references.
     /// Tried in order; first successful pull wins.
-    registries: Vec<String>,
+    pub(crate) registries: Vec<String>,
+    /// Short names resolved to one qualified repository instead of searched.
+    pub(crate) aliases: ShortNameAliases,
+    /// Mirrors tried before each upstream registry.
+    pub(crate) mirrors: RegistryMirrors,
+    /// Remote layer cache tried before the registry
+    pub(crate) blob_cache: Option<SharedBlobCache>,
+}
+
+impl RegistrySettings {
+    /// References to try for `image_ref`: its alias target if it has one,
+    /// otherwise one per registry.
+    fn references(
+        &self,
+        image_ref: &str,
+    ) -> Result<super::ReferenceIter<'_>, oci_client::ParseError> {
+        match self.aliases.resolve(image_ref) {
+            Some(aliased) => super::ReferenceIter::new(&aliased, &self.registries),
+            None => super::ReferenceIter::new(image_ref, &self.registries),
+        }
+    }
 }
 
 impl std::fmt::Debug for ImageStore {
@@ -98,15 +148,95 @@ impl ImageStore {
     /// * `images_dir` - Directory for image cache
     /// * `db` - Database for image index
     /// * `registries` - Registries to search for unqualified images (tried in order)
-    pub fn new(images_dir: PathBuf, db: Database, registries: Vec<String>) -> BoxliteResult<Self> {
-        let inner = ImageStoreInner::new(images_dir, db)?;
+    /// * `mirrors` - Mirrors tried before each upstream registry
+    /// * `clients` - Registry clients (TLS and proxy settings)
+    /// * `throttle` - Bandwidth limit shared by layer downloads
+    /// * `dedup_layers` - Dedup identical files across extracted layers
+    pub fn new(
+        images_dir: PathBuf,
+        db: Database,
+        registries: Vec<String>,
+        mirrors: RegistryMirrors,
+        clients: RegistryClients,
+        throttle: Arc<PullThrottle>,
+        dedup_layers: bool,
+    ) -> BoxliteResult<Self> {
+        let inner = ImageStoreInner::new(images_dir, db, dedup_layers)?;
         Ok(Self {
-            client: oci_client::Client::new(Default::default()),
+            registry: parking_lot::RwLock::new(Arc::new(RegistrySettings {
+                clients,
+                registries,
+                aliases: ShortNameAliases::default(),
+                mirrors,
+                blob_cache: None,
+            })),
+            throttle,
             inner: RwLock::new(inner),
-            registries,
+            layers_cached: Arc::new(AtomicU64::new(0)),
+            layers_fetched: Arc::new(AtomicU64::new(0)),
         })
     }
 
+    /// Resolve short names through `aliases` before the registry search.
+    pub(crate) fn with_aliases(self, aliases: ShortNameAliases) -> Self {
+        let mut settings = RegistrySettings::clone(&self.registry());
+        settings.aliases = aliases;
+        self.set_registry(settings);
+        self
+    }
+
+    /// Look up missing layers in `cache` before the registry.
+    pub(crate) fn with_blob_cache(self, cache: Option<SharedBlobCache>) -> Self {
+        let mut settings = RegistrySettings::clone(&self.registry());
+        settings.blob_cache = cache;
+        self.set_registry(settings);
+        self
+    }
+
+    /// Current registry settings.
+    fn registry(&self) -> Arc<RegistrySettings> {
+        Arc::clone(&self.registry.read())
+    }
+
+    /// Replace the registry settings; pulls in progress pick them up at
+    /// their next manifest or blob request.
+    pub(crate) fn set_registry(&self, settings: RegistrySettings) {
+        *self.registry.write() = Arc::new(settings);
+    }
+
+    /// Change the layer download bandwidth limit.
+    pub(crate) fn set_rate_limit(&self, rate_limit_mbps: Option<u64>) {
+        self.throttle.set_rate_limit(rate_limit_mbps);
+    }
+
+    /// Counter of layer bytes downloaded through this store.
+    pub(crate) fn pull_bytes_total(&self) -> Arc<std::sync::atomic::AtomicU64> {
+        self.throttle.bytes_total()
+    }
+
+    /// Counters of layers cached and fetched during registry pulls.
+    pub(crate) fn layer_counters(&self) -> (Arc<AtomicU64>, Arc<AtomicU64>) {
+        (
+            Arc::clone(&self.layers_cached),
+            Arc::clone(&self.layers_fetched),
+        )
+    }
+
+    /// Most recent registry pull statistics for `reference`.
+    pub async fn last_pull(&self, reference: &str) -> BoxliteResult<Option<ImagePullMetrics>> {
+        self.inner.read().await.index.last_pull(reference)
+    }
+
+    /// Registry client for `registry`, with its TLS and proxy settings.
+    pub(crate) fn client(&self, registry: &str) -> oci_client::Client {
+        self.registry().clients.for_registry(registry).clone()
+    }
+
+    /// Registry client for `registry` that shares no tokens with [`client`](Self::client).
+    pub(crate) fn isolated_client(&self, registry: &str) -> BoxliteResult<oci_client::Client> {
+        self.registry().clients.isolated_client(registry)
+    }
+
     /// Get shared reference to image storage for BlobSource creation.
     ///
     /// This allows creating `StoreBlobSource` that can outlive the lock.
@@ -145,16 +275,26 @@ impl ImageStore {
     /// Thread-safe: Multiple concurrent pulls of the same image will only
     /// download once; others will get the cached result.
     pub async fn pull(&self, image_ref: &str) -> BoxliteResult<ImageManifest> {
-        use super::ReferenceIter;
-
+        let registry = self.registry();
         tracing::debug!(
             image_ref = %image_ref,
-            registries = ?self.registries,
+            registries = ?registry.registries,
             "Starting image pull with registry fallback"
         );
 
+        // Local tags take precedence over registry candidates
+        if !super::is_digest_pinned(image_ref) {
+            let inner = self.inner.read().await;
+            if let Some((tag, manifest)) = self.try_load_tagged(&inner, image_ref)? {
+                tracing::info!("Using locally tagged image: {}", tag.whole());
+                Self::touch(&inner, &manifest);
+                return Ok(manifest);
+            }
+        }
+
         // Parse image reference and create iterator over registry candidates
-        let candidates = ReferenceIter::new(image_ref, &self.registries)
+        let candidates = registry
+            .references(image_ref)
             .map_err(|e| BoxliteError::Storage(format!("invalid image reference: {e}")))?;
 
         let mut errors: Vec<(String, BoxliteError)> = Vec::new();
@@ -165,8 +305,9 @@ impl ImageStore {
             // Fast path: check cache with read lock
             {
                 let inner = self.inner.read().await;
-                if let Some(manifest) = self.try_load_cached(&inner, &ref_str)? {
+                if let Some(manifest) = self.try_load_cached(&inner, &reference)? {
                     tracing::info!("Using cached image: {}", ref_str);
+                    Self::touch(&inner, &manifest);
                     return Ok(manifest);
                 }
             } // Read lock released
@@ -208,7 +349,7 @@ impl ImageStore {
                 .map(|(registry, err)| format!("  - {}: {}", registry, err))
                 .collect();
 
-            Err(BoxliteError::Storage(format!(
+            let message = format!(
                 "Failed to pull image '{}' after trying {} {}:\n{}",
                 image_ref,
                 errors.len(),
@@ -218,7 +359,8 @@ impl ImageStore {
                     "registries"
                 },
                 details.join("\n")
-            )))
+            );
+            Err(pull_failed_error(message, &errors))
         }
     }
 
@@ -230,6 +372,56 @@ impl ImageStore {
         inner.index.list_all()
     }
 
+    /// Evict least recently used images until the cache fits in `max_bytes`.
+    ///
+    /// Never evicts images whose manifest digest is in `keep`, images with a
+    /// local tag (they cannot be pulled again) or images used at or after
+    /// `used_before`. Blobs, extracted layers and disk images go with the
+    /// last image using them, except disk images in `keep_disks` (backing
+    /// files of box disks). Pulls wait until the pass is done.
+    ///
+    /// Returns the evicted references and the cache size afterwards.
+    pub(crate) async fn prune_lru(
+        &self,
+        max_bytes: u64,
+        keep: HashSet<String>,
+        keep_disks: HashSet<PathBuf>,
+        used_before: DateTime<Utc>,
+    ) -> BoxliteResult<(Vec<String>, u64)> {
+        let inner = self.inner.write().await;
+        let index = inner.index.clone();
+        let storage = Arc::clone(&inner.storage);
+        let result = tokio::task::spawn_blocking(move || {
+            prune_images(&index, &storage, max_bytes, &keep, &keep_disks, used_before)
+        })
+        .await
+        .map_err(|e| BoxliteError::Internal(format!("image prune task failed: {e}")))?;
+        drop(inner);
+        result
+    }
+
+    /// Delete blobs, extracted layers and disk images no cached image refers
+    /// to any more, keeping disk images in `keep_disks` and anything modified
+    /// after `older_than`. Pulls wait until the pass is done.
+    ///
+    /// Returns the number of entries removed.
+    pub(crate) async fn prune_dangling(
+        &self,
+        keep_disks: HashSet<PathBuf>,
+        older_than: SystemTime,
+    ) -> BoxliteResult<u64> {
+        let inner = self.inner.write().await;
+        let index = inner.index.clone();
+        let storage = Arc::clone(&inner.storage);
+        let result = tokio::task::spawn_blocking(move || {
+            prune_dangling(&index, &storage, &keep_disks, older_than)
+        })
+        .await
+        .map_err(|e| BoxliteError::Internal(format!("image prune task failed: {e}")))?;
+        drop(inner);
+        result
+    }
+
     /// Load an OCI image from a local directory.
     ///
     /// Reads OCI layout files (index.json, manifest blob) using oci-spec types
@@ -313,6 +505,286 @@ impl ImageStore {
         })
     }
 
+    /// Push a locally stored image to its registry.
+    ///
+    /// `image_ref` must be a local tag or a cached reference (unqualified
+    /// references are resolved against the configured registries, as for
+    /// `pull`). Layers are streamed from disk with the OCI chunked upload
+    /// protocol, falling back to a single streamed upload for registries that
+    /// do not support it. Credentials
+    /// come from the same auth file used for pulls.
+    ///
+    /// Returns the fully qualified reference that was pushed.
+    pub async fn push(
+        &self,
+        image_ref: &str,
+        progress: Option<&tokio::sync::mpsc::UnboundedSender<String>>,
+    ) -> BoxliteResult<String> {
+        let report = |line: String| {
+            tracing::info!("{}", line);
+            if let Some(tx) = progress {
+                let _ = tx.send(line);
+            }
+        };
+
+        let (reference, image_manifest) =
+            self.resolve_local(image_ref).await?.ok_or_else(|| {
+                BoxliteError::NotFound(format!("image {} not found in local store", image_ref))
+            })?;
+
+        let storage = self.storage().await;
+        let oci_manifest = storage.load_manifest(&image_manifest.manifest_digest)?;
+
+        let auth = registry_auth(reference.resolve_registry());
+        let registry = self.registry();
+        let client = registry.clients.for_registry(reference.resolve_registry());
+        client
+            .auth(&reference, &auth, RegistryOperation::Push)
+            .await
+            .map_err(|e| {
+                BoxliteError::Storage(format!(
+                    "failed to authenticate to {}: {e}",
+                    reference.resolve_registry()
+                ))
+            })?;
+
+        // Layers stream from disk; only the small config goes through push_blob
+        let uploader = registry
+            .clients
+            .blob_uploader(reference.resolve_registry())?;
+        let total = image_manifest.layers.len();
+        for (index, layer) in image_manifest.layers.iter().enumerate() {
+            let path = storage.layer_tarball_path(&layer.digest);
+            let size = tokio::fs::metadata(&path)
+                .await
+                .map_err(|e| {
+                    BoxliteError::Storage(format!("failed to read layer {}: {e}", layer.digest))
+                })?
+                .len();
+            report(format!(
+                "Pushing layer {}/{} {} ({} bytes)",
+                index + 1,
+                total,
+                layer.digest,
+                size
+            ));
+            uploader
+                .upload(reference.repository(), &layer.digest, &path)
+                .await
+                .map_err(|e| {
+                    BoxliteError::Storage(format!("failed to push layer {}: {e}", layer.digest))
+                })?;
+        }
+
+        let config = tokio::fs::read(storage.config_path(&image_manifest.config_digest))
+            .await
+            .map_err(|e| BoxliteError::Storage(format!("failed to read image config: {e}")))?;
+        report(format!("Pushing config {}", image_manifest.config_digest));
+        client
+            .push_blob(&reference, &config, &image_manifest.config_digest)
+            .await
+            .map_err(|e| BoxliteError::Storage(format!("failed to push image config: {e}")))?;
+
+        client
+            .push_manifest(&reference, &oci_manifest)
+            .await
+            .map_err(|e| BoxliteError::Storage(format!("failed to push manifest: {e}")))?;
+        report(format!("Pushed {}", reference.whole()));
+
+        Ok(reference.whole())
+    }
+
+    /// Point the local tag `target` at the image `source` resolves to.
+    ///
+    /// `source` may be a local tag or any cached reference; it is never
+    /// pulled. Unqualified targets are stored under `localhost/`. Returns the
+    /// normalized target.
+    pub async fn tag(&self, source: &str, target: &str) -> BoxliteResult<String> {
+        let target = super::normalize_local_reference(target)?;
+        let (_, manifest) = self.resolve_local(source).await?.ok_or_else(|| {
+            BoxliteError::NotFound(format!("image {} not found in local store", source))
+        })?;
+
+        let inner = self.inner.read().await;
+        inner.index.set_tag(&target, &manifest.manifest_digest)?;
+        tracing::info!("Tagged {} as {}", manifest.manifest_digest, target);
+        Ok(target)
+    }
+
+    /// List local tags with the cached image each one points at.
+    ///
+    /// Tags whose image is no longer cached are skipped.
+    pub async fn list_tags(&self) -> BoxliteResult<Vec<(String, CachedImage)>> {
+        let inner = self.inner.read().await;
+        let mut tags = Vec::new();
+        for (tag, digest, created_at) in inner.index.list_tags()? {
+            if let Some(mut cached) = inner.index.get_by_manifest_digest(&digest)? {
+                cached.cached_at = created_at;
+                tags.push((tag, cached));
+            }
+        }
+        Ok(tags)
+    }
+
+    /// Re-hash every blob of the cached images against its digest.
+    ///
+    /// With `remove_corrupt`, blobs that fail are deleted, so the next pull
+    /// of an image using them downloads them again.
+    pub async fn verify(&self, remove_corrupt: bool) -> BoxliteResult<ImageVerification> {
+        let mut images = self.list().await?;
+        images.extend(self.list_tags().await?);
+
+        let mut blobs: BTreeMap<(ImageBlobKind, String), Vec<String>> = BTreeMap::new();
+        for (reference, cached) in images {
+            let mut add = |kind: ImageBlobKind, digest: &str| {
+                let users = blobs.entry((kind, digest.to_string())).or_default();
+                if !users.contains(&reference) {
+                    users.push(reference.clone());
+                }
+            };
+            // The image index a pinned reference names
+            if let Some((_, pinned)) = reference.split_once('@')
+                && pinned != cached.manifest_digest
+            {
+                add(ImageBlobKind::Manifest, pinned);
+            }
+            add(ImageBlobKind::Manifest, &cached.manifest_digest);
+            add(ImageBlobKind::Config, &cached.config_digest);
+            for layer in &cached.layers {
+                add(ImageBlobKind::Layer, layer);
+            }
+        }
+
+        let storage = self.storage().await;
+        tokio::task::spawn_blocking(move || -> BoxliteResult<ImageVerification> {
+            let mut report = ImageVerification::default();
+            for ((kind, digest), images) in blobs {
+                let path = match kind {
+                    ImageBlobKind::Manifest => storage.manifest_path(&digest),
+                    ImageBlobKind::Config => storage.config_path(&digest),
+                    ImageBlobKind::Layer => storage.layer_tarball_path(&digest),
+                };
+                report.blobs_checked += 1;
+                let actual = file_digest(&path)?;
+                if actual.as_deref() == Some(digest.as_str()) {
+                    continue;
+                }
+
+                tracing::warn!(
+                    kind = %kind,
+                    digest = %digest,
+                    actual = ?actual,
+                    "Cached image blob does not match its digest"
+                );
+                let removed = remove_corrupt && actual.is_some();
+                if removed {
+                    std::fs::remove_file(&path).map_err(|e| {
+                        BoxliteError::Storage(format!("Failed to remove {}: {}", path.display(), e))
+                    })?;
+                }
+                report.corrupt.push(CorruptBlob {
+                    kind,
+                    digest,
+                    actual,
+                    images,
+                    removed,
+                });
+            }
+            Ok(report)
+        })
+        .await
+        .map_err(|e| BoxliteError::Internal(format!("image verification task failed: {e}")))?
+    }
+
+    /// Import a locally built OCI layout into the store under `image_ref`.
+    ///
+    /// Unlike [`load_from_local`](Self::load_from_local), blobs are copied
+    /// (hard-linked when possible) into storage and the image is added to
+    /// the index, so it can later be resolved by reference like a pulled image.
+    pub async fn import_layout(
+        &self,
+        path: &std::path::Path,
+        image_ref: &str,
+    ) -> BoxliteResult<ImageManifest> {
+        let reference: Reference = image_ref
+            .parse()
+            .map_err(|e| BoxliteError::Storage(format!("invalid image reference: {e}")))?;
+
+        let index_json = std::fs::read_to_string(path.join("index.json"))
+            .map_err(|e| BoxliteError::Storage(format!("Failed to read index.json: {}", e)))?;
+        let index: OciImageIndex = serde_json::from_str(&index_json)
+            .map_err(|e| BoxliteError::Storage(format!("Failed to parse index.json: {}", e)))?;
+        let manifest_desc = index
+            .manifests
+            .first()
+            .ok_or_else(|| BoxliteError::Storage("No manifests found in index.json".into()))?;
+
+        let manifest_digest = self.get_image_manifest(path, manifest_desc)?;
+        let blob_path = |digest: &str| path.join("blobs").join(digest.replace(':', "/"));
+        let manifest_path = blob_path(&manifest_digest);
+        let (config_digest, layers) = self.parse_oci_manifest_from_path(
+            &manifest_path,
+            &format!("image manifest {}", manifest_digest),
+        )?;
+
+        {
+            let inner = self.inner.read().await;
+            let import_blob = |src: PathBuf, dest: PathBuf| -> BoxliteResult<()> {
+                if dest.exists() {
+                    return Ok(());
+                }
+                std::fs::hard_link(&src, &dest)
+                    .or_else(|_| std::fs::copy(&src, &dest).map(|_| ()))
+                    .map_err(|e| {
+                        BoxliteError::Storage(format!(
+                            "Failed to import blob {}: {}",
+                            src.display(),
+                            e
+                        ))
+                    })
+            };
+
+            for layer in &layers {
+                import_blob(
+                    blob_path(&layer.digest),
+                    inner.storage.layer_tarball_path(&layer.digest),
+                )?;
+            }
+            import_blob(
+                blob_path(&config_digest),
+                inner.storage.config_path(&config_digest),
+            )?;
+
+            let manifest_json = std::fs::read(&manifest_path)
+                .map_err(|e| BoxliteError::Storage(format!("Failed to read manifest: {}", e)))?;
+            serde_json::from_slice::<ClientOciImageManifest>(&manifest_json)
+                .map_err(|e| BoxliteError::Storage(format!("Failed to parse manifest: {}", e)))?;
+            inner
+                .storage
+                .save_manifest(&manifest_json, &manifest_digest)?;
+        }
+
+        let image_manifest = ImageManifest {
+            manifest_digest,
+            layers,
+            config_digest,
+        };
+        self.update_index(&reference.whole(), &image_manifest)
+            .await?;
+        self.inner
+            .read()
+            .await
+            .index
+            .set_tag(&reference.whole(), &image_manifest.manifest_digest)?;
+
+        tracing::info!(
+            "Imported image {} ({} layers)",
+            reference.whole(),
+            image_manifest.layers.len()
+        );
+        Ok(image_manifest)
+    }
+
     /// Get an ImageManifest digest from the descriptor.
     ///
     /// Handles at most two levels (like containerd):
@@ -412,12 +884,84 @@ impl ImageStore {
     // INTERNAL: Cache Operations
     // ========================================================================
 
-    /// Try to load image from local cache.
-    fn try_load_cached(
+    /// Resolve `image_ref` against local tags and the cache, without network access.
+    pub(super) async fn resolve_local(
+        &self,
+        image_ref: &str,
+    ) -> BoxliteResult<Option<(Reference, ImageManifest)>> {
+        let inner = self.inner.read().await;
+        if !super::is_digest_pinned(image_ref)
+            && let Some(found) = self.try_load_tagged(&inner, image_ref)?
+        {
+            return Ok(Some(found));
+        }
+
+        let registry = self.registry();
+        let candidates = registry
+            .references(image_ref)
+            .map_err(|e| BoxliteError::InvalidArgument(format!("invalid image reference: {e}")))?;
+        for reference in candidates {
+            if let Some(manifest) = self.try_load_cached(&inner, &reference)? {
+                return Ok(Some((reference, manifest)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// References a pull of `image_ref` would try, in order.
+    pub(super) fn candidates(&self, image_ref: &str) -> BoxliteResult<Vec<String>> {
+        let registry = self.registry();
+        let candidates = registry
+            .references(image_ref)
+            .map_err(|e| BoxliteError::InvalidArgument(format!("invalid image reference: {e}")))?;
+        Ok(candidates.map(|reference| reference.whole()).collect())
+    }
+
+    /// Record a cache hit, so the image counts as recently used.
+    fn touch(inner: &ImageStoreInner, manifest: &ImageManifest) {
+        if let Err(e) = inner.index.touch(&manifest.manifest_digest) {
+            tracing::warn!(
+                digest = %manifest.manifest_digest,
+                error = %e,
+                "Failed to record image use"
+            );
+        }
+    }
+
+    /// Try to resolve `image_ref` as a local tag.
+    fn try_load_tagged(
         &self,
         inner: &ImageStoreInner,
         image_ref: &str,
+    ) -> BoxliteResult<Option<(Reference, ImageManifest)>> {
+        let Ok(tag) = super::normalize_local_reference(image_ref) else {
+            return Ok(None);
+        };
+        let Some(digest) = inner.index.get_tag(&tag)? else {
+            return Ok(None);
+        };
+        let Some(cached) = inner.index.get_by_manifest_digest(&digest)? else {
+            tracing::debug!("Tag {} points at missing image {}", tag, digest);
+            return Ok(None);
+        };
+        if !self.verify_cached_image(inner, &cached)? {
+            return Ok(None);
+        }
+
+        let manifest = self.load_manifest_from_disk(inner, &cached)?;
+        let reference = tag
+            .parse()
+            .map_err(|e| BoxliteError::Storage(format!("invalid stored tag {}: {e}", tag)))?;
+        Ok(Some((reference, manifest)))
+    }
+
+    fn try_load_cached(
+        &self,
+        inner: &ImageStoreInner,
+        reference: &Reference,
     ) -> BoxliteResult<Option<ImageManifest>> {
+        let image_ref = &reference.whole();
+
         // Check if image exists in index
         let cached = match inner.index.get(image_ref)? {
             Some(c) if c.complete => c,
@@ -438,9 +982,65 @@ impl ImageStore {
 
         // Load manifest from disk
         let manifest = self.load_manifest_from_disk(inner, &cached)?;
+        if let Some(pinned) = reference.digest() {
+            Self::verify_pinned(&inner.storage, image_ref, pinned, &manifest)?;
+        }
         Ok(Some(manifest))
     }
 
+    /// Check a cached image against the digest its reference pins.
+    ///
+    /// The manifest stored under `pinned` must still hash to it; when it is
+    /// an image index, it must list the platform manifest in use, which is
+    /// checked the same way, as is the config. Layer tarballs were hashed
+    /// when downloaded and are re-hashed by [`verify`](Self::verify).
+    fn verify_pinned(
+        storage: &ImageStorage,
+        image_ref: &str,
+        pinned: &str,
+        manifest: &ImageManifest,
+    ) -> BoxliteResult<()> {
+        let check = |kind: &str, digest: &str, path: PathBuf| -> BoxliteResult<()> {
+            match file_digest(&path)? {
+                Some(actual) if actual == digest => Ok(()),
+                actual => Err(BoxliteError::Integrity(format!(
+                    "cached {} {} of {} hashes to {}",
+                    kind,
+                    digest,
+                    image_ref,
+                    actual.as_deref().unwrap_or("nothing (file missing)")
+                ))),
+            }
+        };
+
+        check("manifest", pinned, storage.manifest_path(pinned))?;
+        if manifest.manifest_digest != pinned {
+            let listed = match storage.load_manifest(pinned)? {
+                OciManifest::ImageIndex(index) => index
+                    .manifests
+                    .iter()
+                    .any(|entry| entry.digest == manifest.manifest_digest),
+                OciManifest::Image(_) => false,
+            };
+            if !listed {
+                return Err(BoxliteError::Integrity(format!(
+                    "{} does not list the cached manifest {}",
+                    image_ref, manifest.manifest_digest
+                )));
+            }
+            check(
+                "manifest",
+                &manifest.manifest_digest,
+                storage.manifest_path(&manifest.manifest_digest),
+            )?;
+        }
+        check(
+            "config",
+            &manifest.config_digest,
+            storage.config_path(&manifest.config_digest),
+        )
+    }
+
     fn verify_cached_image(
         &self,
         inner: &ImageStoreInner,
@@ -500,20 +1100,104 @@ impl ImageStore {
     /// This method handles the actual network I/O - manifest pull, layer download, etc.
     /// Lock is released during network I/O to allow other operations.
     async fn pull_from_registry(&self, reference: &Reference) -> BoxliteResult<ImageManifest> {
-        // Step 1: Pull manifest (no lock needed - uses self.client)
-        let (manifest, manifest_digest_str) = self
-            .client
-            .pull_manifest(reference, &RegistryAuth::Anonymous)
-            .await
-            .map_err(|e| BoxliteError::Storage(format!("failed to pull manifest: {e}")))?;
+        let start = Instant::now();
+        let (image_manifest, layers) = self.pull_via_mirrors(reference).await?;
 
-        // Step 2: Save manifest (quick write lock)
-        {
-            let inner = self.inner.read().await;
-            inner
-                .storage
-                .save_manifest(&manifest, &manifest_digest_str)?;
+        // Update index using the requested reference as the cache key,
+        // regardless of which mirror served it
+        self.update_index(&reference.whole(), &image_manifest)
+            .await?;
+
+        self.record_pull(reference, &image_manifest, layers, start)
+            .await;
+
+        Ok(image_manifest)
+    }
+
+    /// Persist pull statistics and update the layer cache counters.
+    ///
+    /// Best-effort: a failure to record never fails the pull.
+    async fn record_pull(
+        &self,
+        reference: &Reference,
+        manifest: &ImageManifest,
+        layers: Vec<LayerPullMetrics>,
+        start: Instant,
+    ) {
+        let pull = ImagePullMetrics {
+            reference: reference.whole(),
+            manifest_digest: manifest.manifest_digest.clone(),
+            pulled_at: chrono::Utc::now(),
+            duration_ms: start.elapsed().as_millis(),
+            bytes_downloaded: layers
+                .iter()
+                .filter(|layer| !layer.cached)
+                .map(|layer| layer.size_bytes)
+                .sum(),
+            layers,
+        };
+
+        self.layers_cached
+            .fetch_add(pull.layers_cached() as u64, Ordering::Relaxed);
+        self.layers_fetched
+            .fetch_add(pull.layers_fetched() as u64, Ordering::Relaxed);
+
+        tracing::info!(
+            reference = %pull.reference,
+            duration_ms = pull.duration_ms,
+            bytes_downloaded = pull.bytes_downloaded,
+            layers_cached = pull.layers_cached(),
+            layers_fetched = pull.layers_fetched(),
+            "Image pull completed"
+        );
+
+        let inner = self.inner.read().await;
+        if let Err(e) = inner.index.record_pull(&pull) {
+            tracing::warn!(
+                "Failed to record pull metrics for {}: {}",
+                pull.reference,
+                e
+            );
         }
+    }
+
+    /// Pull from each configured mirror in order, then from the upstream registry.
+    async fn pull_via_mirrors(
+        &self,
+        reference: &Reference,
+    ) -> BoxliteResult<(ImageManifest, Vec<LayerPullMetrics>)> {
+        let sources = self.registry().mirrors.sources(reference);
+        let (upstream, mirrors) = sources
+            .split_last()
+            .expect("mirror sources always end with the upstream reference");
+
+        for mirror in mirrors {
+            match self.pull_from_source(mirror).await {
+                Ok(pulled) => {
+                    tracing::info!("Pulled {} via mirror {}", reference.whole(), mirror.whole());
+                    return Ok(pulled);
+                }
+                Err(e) => tracing::warn!(
+                    mirror = %mirror.whole(),
+                    error = %e,
+                    "Mirror pull failed, trying next source"
+                ),
+            }
+        }
+        self.pull_from_source(upstream).await
+    }
+
+    /// Download manifest, layers and config from one registry source.
+    ///
+    /// Returns the image manifest and per-layer pull statistics.
+    async fn pull_from_source(
+        &self,
+        reference: &Reference,
+    ) -> BoxliteResult<(ImageManifest, Vec<LayerPullMetrics>)> {
+        // Steps 1-2: Pull and save manifest
+        let (manifest, manifest_digest_str) = self
+            .pull_manifest(reference, "failed to pull manifest")
+            .await?;
 
         // Step 3: Extract image manifest (may pull platform-specific manifest for multi-platform images)
         let image_manifest = self
@@ -521,18 +1205,49 @@ impl ImageStore {
             .await?;
 
         // Step 4: Download layers (no lock during download, atomic file writes)
-        self.download_layers(reference, &image_manifest.layers)
+        let layers = self
+            .download_layers(reference, &image_manifest.layers)
             .await?;
 
         // Step 5: Download config (no lock during download)
         self.download_config(reference, &image_manifest.config_digest)
             .await?;
 
-        // Step 6: Update index using reference.whole() as the cache key
-        self.update_index(&reference.whole(), &image_manifest)
-            .await?;
+        Ok((image_manifest, layers))
+    }
 
-        Ok(image_manifest)
+    /// Pull a manifest and save it as served, so the stored file keeps
+    /// hashing to its digest. The client checks the bytes against the digest
+    /// of a pinned reference.
+    async fn pull_manifest(
+        &self,
+        reference: &Reference,
+        context: &str,
+    ) -> BoxliteResult<(OciManifest, String)> {
+        const ACCEPTED: &[&str] = &[
+            OCI_IMAGE_MEDIA_TYPE,
+            OCI_IMAGE_INDEX_MEDIA_TYPE,
+            IMAGE_MANIFEST_MEDIA_TYPE,
+            IMAGE_MANIFEST_LIST_MEDIA_TYPE,
+        ];
+        let (bytes, digest) = self
+            .registry()
+            .clients
+            .for_registry(reference.resolve_registry())
+            .pull_manifest_raw(
+                reference,
+                &registry_auth(reference.resolve_registry()),
+                ACCEPTED,
+            )
+            .await
+            .map_err(|e| manifest_pull_error(context, e))?;
+        let manifest: OciManifest = serde_json::from_slice(&bytes).map_err(|e| {
+            BoxliteError::Image(format!("{}: invalid manifest {}: {}", context, digest, e))
+        })?;
+
+        let inner = self.inner.read().await;
+        inner.storage.save_manifest(&bytes, &digest)?;
+        Ok((manifest, digest))
     }
 
     /// Update index with newly pulled image.
@@ -606,28 +1321,16 @@ impl ImageStore {
 
         let platform_manifest = self.select_platform_manifest(index, platform_os, platform_arch)?;
 
-        let platform_ref = format!("{}@{}", reference.whole(), platform_manifest.digest);
-        let platform_reference: Reference = platform_ref
-            .parse()
-            .map_err(|e| BoxliteError::Storage(format!("invalid platform reference: {e}")))?;
+        // Replaces the tag, or the index digest of a pinned reference
+        let platform_reference = reference.clone_with_digest(platform_manifest.digest.clone());
 
         tracing::info!(
             "Pulling platform-specific manifest: {}",
             platform_manifest.digest
         );
         let (platform_image, platform_digest) = self
-            .client
-            .pull_manifest(&platform_reference, &RegistryAuth::Anonymous)
-            .await
-            .map_err(|e| BoxliteError::Storage(format!("failed to pull platform manifest: {e}")))?;
-
-        // Save platform manifest (quick lock)
-        {
-            let inner = self.inner.read().await;
-            inner
-                .storage
-                .save_manifest(&platform_image, &platform_digest)?;
-        }
+            .pull_manifest(&platform_reference, "failed to pull platform manifest")
+            .await?;
 
         match platform_image {
             oci_client::manifest::OciManifest::Image(img) => {
@@ -695,25 +1398,35 @@ impl ImageStore {
     // INTERNAL: Layer Download (no lock during I/O)
     // ========================================================================
 
+    /// Download missing layers, returning per-layer statistics in manifest order.
     async fn download_layers(
         &self,
         reference: &Reference,
         layers: &[LayerInfo],
-    ) -> BoxliteResult<()> {
+    ) -> BoxliteResult<Vec<LayerPullMetrics>> {
         use futures::future::join_all;
 
+        let mut metrics: Vec<Option<LayerPullMetrics>> = vec![None; layers.len()];
+
         // Check which layers need downloading (quick read lock)
         let layers_to_download: Vec<_> = {
             let inner = self.inner.read().await;
             let mut to_download = Vec::new();
-            for layer in layers {
+            for (i, layer) in layers.iter().enumerate() {
+                let verify_start = Instant::now();
                 if !inner.storage.has_layer(&layer.digest) {
-                    to_download.push(layer.clone());
+                    to_download.push((i, layer.clone()));
                 } else {
                     // Verify cached layer
                     match inner.storage.verify_layer(&layer.digest).await {
                         Ok(true) => {
                             tracing::debug!("Layer tarball cached and verified: {}", layer.digest);
+                            metrics[i] = Some(LayerPullMetrics {
+                                digest: layer.digest.clone(),
+                                size_bytes: Self::layer_size(&inner, &layer.digest),
+                                cached: true,
+                                duration_ms: verify_start.elapsed().as_millis(),
+                            });
                         }
                         _ => {
                             tracing::warn!(
@@ -723,7 +1436,7 @@ impl ImageStore {
                             let _ = std::fs::remove_file(
                                 inner.storage.layer_tarball_path(&layer.digest),
                             );
-                            to_download.push(layer.clone());
+                            to_download.push((i, layer.clone()));
                         }
                     }
                 }
@@ -731,35 +1444,56 @@ impl ImageStore {
             to_download
         }; // Read lock released
 
-        if layers_to_download.is_empty() {
-            return Ok(());
-        }
-
-        tracing::info!(
-            "Downloading {} layers in parallel",
-            layers_to_download.len()
-        );
+        if !layers_to_download.is_empty() {
+            tracing::info!(
+                "Downloading {} layers in parallel",
+                layers_to_download.len()
+            );
 
-        // Download in parallel (no lock held)
-        let download_futures = layers_to_download
-            .iter()
-            .map(|layer| self.download_layer(reference, layer));
+            // Download in parallel (no lock held)
+            let download_futures = layers_to_download.iter().map(|(i, layer)| async move {
+                let download_start = Instant::now();
+                self.download_layer(reference, layer).await?;
+                Ok::<_, BoxliteError>((*i, download_start.elapsed().as_millis()))
+            });
 
-        let results = join_all(download_futures).await;
+            let results = join_all(download_futures).await;
 
-        for result in results {
-            result?;
+            let inner = self.inner.read().await;
+            for result in results {
+                let (i, duration_ms) = result?;
+                metrics[i] = Some(LayerPullMetrics {
+                    digest: layers[i].digest.clone(),
+                    size_bytes: Self::layer_size(&inner, &layers[i].digest),
+                    cached: false,
+                    duration_ms,
+                });
+            }
         }
 
-        Ok(())
+        Ok(metrics.into_iter().flatten().collect())
+    }
+
+    /// Size of a stored layer tarball, 0 if it can't be read.
+    fn layer_size(inner: &ImageStoreInner, digest: &str) -> u64 {
+        std::fs::metadata(inner.storage.layer_tarball_path(digest))
+            .map(|meta| meta.len())
+            .unwrap_or(0)
     }
 
     async fn download_layer(&self, reference: &Reference, layer: &LayerInfo) -> BoxliteResult<()> {
         const MAX_RETRIES: u32 = 3;
 
+        let blob_cache = self.registry().blob_cache.clone();
+        if let Some(cache) = &blob_cache
+            && self.fetch_cached_layer(cache, layer).await
+        {
+            return Ok(());
+        }
+
         tracing::info!("Downloading layer: {}", layer.digest);
 
-        let mut last_error = None;
+        let mut last_error: Option<BoxliteError> = None;
 
         for attempt in 1..=MAX_RETRIES {
             if attempt > 1 {
@@ -777,10 +1511,10 @@ impl ImageStore {
                 match inner.storage.stage_layer_download(&layer.digest).await {
                     Ok(result) => result,
                     Err(e) => {
-                        last_error = Some(format!(
+                        last_error = Some(BoxliteError::Storage(format!(
                             "Failed to stage layer {} download: {e}",
                             layer.digest
-                        ));
+                        )));
                         continue;
                     }
                 }
@@ -788,7 +1522,9 @@ impl ImageStore {
 
             // Download (no lock)
             match self
-                .client
+                .registry()
+                .clients
+                .for_registry(reference.resolve_registry())
                 .pull_blob(
                     reference,
                     &OciDescriptor {
@@ -798,13 +1534,16 @@ impl ImageStore {
                         urls: None,
                         annotations: None,
                     },
-                    staged.file(),
+                    self.throttle.writer(staged.file()),
                 )
                 .await
             {
                 Ok(_) => match staged.commit().await {
                     Ok(true) => {
                         tracing::info!("Downloaded and verified layer: {}", layer.digest);
+                        if let Some(cache) = blob_cache.as_ref().filter(|c| c.write_back) {
+                            self.write_back_layer(cache, &layer.digest).await;
+                        }
                         return Ok(());
                     }
                     Ok(false) => {
@@ -813,25 +1552,76 @@ impl ImageStore {
                             attempt,
                             layer.digest
                         );
-                        last_error =
-                            Some("layer integrity verification failed: hash mismatch".to_string());
+                        last_error = Some(BoxliteError::Storage(
+                            "layer integrity verification failed: hash mismatch".to_string(),
+                        ));
                     }
                     Err(e) => {
                         tracing::warn!("Layer commit error (attempt {}): {}", attempt, e);
-                        last_error = Some(format!("layer commit error: {e}"));
+                        last_error =
+                            Some(BoxliteError::Storage(format!("layer commit error: {e}")));
                     }
                 },
                 Err(e) => {
                     tracing::warn!("Layer download failed (attempt {}): {}", attempt, e);
-                    last_error = Some(format!("failed to pull layer {}: {e}", layer.digest));
+                    last_error = Some(BoxliteError::Network(format!(
+                        "failed to pull layer {}: {e}",
+                        layer.digest
+                    )));
                     staged.abort().await;
                 }
             }
         }
 
-        Err(BoxliteError::Storage(last_error.unwrap_or_else(|| {
-            "download failed after retries".to_string()
-        })))
+        Err(last_error
+            .unwrap_or_else(|| BoxliteError::Storage("download failed after retries".to_string())))
+    }
+
+    /// Fill a layer from the blob cache. Misses, failures and blobs that
+    /// fail verification fall back to the registry.
+    async fn fetch_cached_layer(&self, cache: &SharedBlobCache, layer: &LayerInfo) -> bool {
+        let mut staged = {
+            let inner = self.inner.read().await;
+            match inner.storage.stage_layer_download(&layer.digest).await {
+                Ok(staged) => staged,
+                Err(_) => return false,
+            }
+        };
+
+        let fetched = {
+            let mut writer = self.throttle.writer(staged.file());
+            cache.cache.fetch(&layer.digest, &mut writer).await
+        };
+        match fetched {
+            Ok(true) => match staged.commit().await {
+                Ok(true) => {
+                    tracing::info!("Layer restored from blob cache: {}", layer.digest);
+                    return true;
+                }
+                Ok(false) => {
+                    tracing::warn!("Blob cache returned a corrupt layer: {}", layer.digest)
+                }
+                Err(e) => tracing::warn!("Layer commit error from blob cache: {}", e),
+            },
+            Ok(false) => {
+                tracing::debug!("Layer not in blob cache: {}", layer.digest);
+                staged.abort().await;
+            }
+            Err(e) => {
+                tracing::warn!("{}, pulling from registry", e);
+                staged.abort().await;
+            }
+        }
+        false
+    }
+
+    /// Upload a verified layer to the blob cache; failures only log.
+    async fn write_back_layer(&self, cache: &SharedBlobCache, digest: &str) {
+        let path = self.inner.read().await.storage.layer_tarball_path(digest);
+        match cache.cache.store(digest, &path).await {
+            Ok(()) => tracing::debug!("Layer written to blob cache: {}", digest),
+            Err(e) => tracing::warn!("{}", e),
+        }
     }
 
     async fn download_config(
@@ -858,7 +1648,9 @@ impl ImageStore {
 
         // Download to temp file (no lock)
         if let Err(e) = self
-            .client
+            .registry()
+            .clients
+            .for_registry(reference.resolve_registry())
             .pull_blob(
                 reference,
                 &OciDescriptor {
@@ -873,7 +1665,7 @@ impl ImageStore {
             .await
         {
             staged.abort().await;
-            return Err(BoxliteError::Storage(format!("failed to pull config: {e}")));
+            return Err(BoxliteError::Network(format!("failed to pull config: {e}")));
         }
 
         // Verify and commit (atomic move to final location)
@@ -924,6 +1716,216 @@ impl ImageStore {
     }
 }
 
+// ============================================================================
+// PRUNING
+// ============================================================================
+
+/// Evict images, least recently used first, until the cache fits in `max_bytes`.
+fn prune_images(
+    index: &ImageIndexStore,
+    storage: &ImageStorage,
+    max_bytes: u64,
+    keep: &HashSet<String>,
+    keep_disks: &HashSet<PathBuf>,
+    used_before: DateTime<Utc>,
+) -> BoxliteResult<(Vec<String>, u64)> {
+    let mut usage = storage.cache_usage();
+    if usage <= max_bytes {
+        return Ok((Vec::new(), usage));
+    }
+
+    let tagged: HashSet<String> = index
+        .list_tags()?
+        .into_iter()
+        .map(|(_, digest, _)| digest)
+        .collect();
+
+    // One candidate per image, placed by the last use of any of its references
+    let mut seen = HashSet::new();
+    let mut candidates = Vec::new();
+    for (_, image, last_used) in index.list_by_last_use()?.into_iter().rev() {
+        if !seen.insert(image.manifest_digest.clone())
+            || keep.contains(&image.manifest_digest)
+            || tagged.contains(&image.manifest_digest)
+        {
+            continue;
+        }
+        let recent = DateTime::parse_from_rfc3339(&last_used)
+            .is_ok_and(|used_at| used_at.with_timezone(&Utc) >= used_before);
+        if !recent {
+            candidates.push(image);
+        }
+    }
+    candidates.reverse();
+
+    let mut evicted = Vec::new();
+    for image in candidates {
+        if usage <= max_bytes {
+            break;
+        }
+
+        let references: Vec<String> = index
+            .list_all()?
+            .into_iter()
+            .filter(|(_, cached)| cached.manifest_digest == image.manifest_digest)
+            .map(|(reference, _)| reference)
+            .collect();
+        index.remove_manifest(&image.manifest_digest)?;
+
+        let remaining = index.list_all()?;
+        let used_layers: HashSet<&str> = remaining
+            .iter()
+            .flat_map(|(_, cached)| cached.layers.iter().map(String::as_str))
+            .collect();
+        for layer in &image.layers {
+            if !used_layers.contains(layer.as_str()) {
+                storage.remove_layer(layer)?;
+            }
+        }
+        let config_used = remaining
+            .iter()
+            .any(|(_, cached)| cached.config_digest == image.config_digest);
+        storage.remove_manifest_and_config(
+            &image.manifest_digest,
+            (!config_used).then_some(image.config_digest.as_str()),
+        )?;
+
+        let disk_digest = disk_image_digest(image.layers.iter().map(String::as_str));
+        let disk_used = remaining.iter().any(|(_, cached)| {
+            disk_image_digest(cached.layers.iter().map(String::as_str)) == disk_digest
+        });
+        if !disk_used {
+            storage.remove_disk_images(&disk_digest, keep_disks)?;
+        }
+        storage.prune_content()?;
+
+        let before = usage;
+        usage = storage.cache_usage();
+        tracing::info!(
+            digest = %image.manifest_digest,
+            references = ?references,
+            freed_bytes = before.saturating_sub(usage),
+            "Pruned least recently used image"
+        );
+        evicted.extend(references);
+    }
+
+    Ok((evicted, usage))
+}
+
+/// Delete cache entries no index entry refers to, see
+/// [`ImageStorage::remove_unreferenced`].
+fn prune_dangling(
+    index: &ImageIndexStore,
+    storage: &ImageStorage,
+    keep_disks: &HashSet<PathBuf>,
+    older_than: SystemTime,
+) -> BoxliteResult<u64> {
+    let mut referenced = ReferencedBlobs::default();
+    for (_, image) in index.list_all()? {
+        referenced
+            .disk_images
+            .insert(disk_image_digest(image.layers.iter().map(String::as_str)));
+        referenced.manifests.insert(image.manifest_digest);
+        referenced.configs.insert(image.config_digest);
+        referenced.layers.extend(image.layers);
+    }
+    referenced
+        .manifests
+        .extend(index.list_tags()?.into_iter().map(|(_, digest, _)| digest));
+
+    let removed = storage.remove_unreferenced(&referenced, keep_disks, older_than)?;
+    storage.prune_content()?;
+    if removed > 0 {
+        tracing::info!(removed, "Pruned dangling image data");
+    }
+    Ok(removed)
+}
+
+// ============================================================================
+// REGISTRY ERRORS
+// ============================================================================
+
+/// Classify a registry manifest error, so missing images and rejected
+/// credentials get their own error codes, and failures worth retrying
+/// (connection errors, 5xx, rate limiting) are reported as `Network`.
+fn manifest_pull_error(context: &str, err: OciDistributionError) -> BoxliteError {
+    let message = format!("{context}: {err}");
+    let codes: Vec<&OciErrorCode> = match &err {
+        OciDistributionError::RegistryError { envelope, .. } => {
+            envelope.errors.iter().map(|e| &e.code).collect()
+        }
+        _ => Vec::new(),
+    };
+    match &err {
+        OciDistributionError::UnauthorizedError { .. }
+        | OciDistributionError::AuthenticationFailure(_) => BoxliteError::ImagePullAuth(message),
+        OciDistributionError::ServerError {
+            code: 401 | 403, ..
+        } => BoxliteError::ImagePullAuth(message),
+        OciDistributionError::ImageManifestNotFoundError(_)
+        | OciDistributionError::ServerError { code: 404, .. } => {
+            BoxliteError::ImageNotFound(message)
+        }
+        OciDistributionError::DigestError(oci_client::errors::DigestError::VerificationError {
+            ..
+        }) => BoxliteError::Integrity(message),
+        OciDistributionError::RequestError(_)
+        | OciDistributionError::ServerError {
+            code: 429 | 500..=599,
+            ..
+        } => BoxliteError::Network(message),
+        _ if codes.contains(&&OciErrorCode::Toomanyrequests) => BoxliteError::Network(message),
+        _ if codes
+            .iter()
+            .any(|c| matches!(c, OciErrorCode::Unauthorized | OciErrorCode::Denied)) =>
+        {
+            BoxliteError::ImagePullAuth(message)
+        }
+        _ if codes.iter().any(|c| {
+            matches!(
+                c,
+                OciErrorCode::ManifestUnknown | OciErrorCode::NameUnknown | OciErrorCode::NotFound
+            )
+        }) =>
+        {
+            BoxliteError::ImageNotFound(message)
+        }
+        _ => BoxliteError::Storage(message),
+    }
+}
+
+/// Error for a pull where every registry candidate failed.
+///
+/// Content that did not match its digest makes the whole pull `Integrity`.
+/// Any transient failure makes the whole pull `Network` (worth retrying).
+/// Otherwise a specific code is kept when the candidates agree on it: all
+/// "not found" stays `ImageNotFound`, and auth failures mixed with "not
+/// found" become `ImagePullAuth` (registries answer unknown private repos
+/// with 401).
+fn pull_failed_error(message: String, errors: &[(String, BoxliteError)]) -> BoxliteError {
+    let all = |pred: fn(&BoxliteError) -> bool| errors.iter().all(|(_, e)| pred(e));
+    if errors
+        .iter()
+        .any(|(_, e)| matches!(e, BoxliteError::Integrity(_)))
+    {
+        BoxliteError::Integrity(message)
+    } else if errors.iter().any(|(_, e)| e.is_transient()) {
+        BoxliteError::Network(message)
+    } else if all(|e| matches!(e, BoxliteError::ImageNotFound(_))) {
+        BoxliteError::ImageNotFound(message)
+    } else if all(|e| {
+        matches!(
+            e,
+            BoxliteError::ImageNotFound(_) | BoxliteError::ImagePullAuth(_)
+        )
+    }) {
+        BoxliteError::ImagePullAuth(message)
+    } else {
+        BoxliteError::Storage(message)
+    }
+}
+
 // ============================================================================
 // SHARED TYPE ALIAS
 // ============================================================================
@@ -943,6 +1945,15 @@ mod tests {
     use crate::db::Database;
     use std::path::Path;
 
+    fn test_clients() -> RegistryClients {
+        RegistryClients::new(
+            &Default::default(),
+            &RegistryMirrors::default(),
+            &Default::default(),
+        )
+        .unwrap()
+    }
+
     /// Helper to create a minimal OCI bundle for testing
     fn create_test_oci_bundle(bundle_dir: &Path) -> String {
         use sha2::Digest;
@@ -1071,7 +2082,16 @@ mod tests {
 
         // Create store
         let db = Database::open(&db_path).unwrap();
-        let store = ImageStore::new(images_dir.clone(), db, vec![]).unwrap();
+        let store = ImageStore::new(
+            images_dir.clone(),
+            db,
+            vec![],
+            RegistryMirrors::default(),
+            test_clients(),
+            Arc::new(PullThrottle::new(None)),
+            false,
+        )
+        .unwrap();
 
         // Load from local
         let manifest = store.load_from_local(bundle_dir.clone()).await.unwrap();
@@ -1095,7 +2115,16 @@ mod tests {
 
         // Create store
         let db = Database::open(&db_path).unwrap();
-        let store = ImageStore::new(images_dir.clone(), db, vec![]).unwrap();
+        let store = ImageStore::new(
+            images_dir.clone(),
+            db,
+            vec![],
+            RegistryMirrors::default(),
+            test_clients(),
+            Arc::new(PullThrottle::new(None)),
+            false,
+        )
+        .unwrap();
 
         // Load from local
         let _manifest = store.load_from_local(bundle_dir.clone()).await.unwrap();
@@ -1130,7 +2159,16 @@ mod tests {
 
         // Create store
         let db = Database::open(&db_path).unwrap();
-        let store = ImageStore::new(images_dir.clone(), db, vec![]).unwrap();
+        let store = ImageStore::new(
+            images_dir.clone(),
+            db,
+            vec![],
+            RegistryMirrors::default(),
+            test_clients(),
+            Arc::new(PullThrottle::new(None)),
+            false,
+        )
+        .unwrap();
 
         // Load should fail
         let result = store.load_from_local(bundle_dir).await;
@@ -1156,7 +2194,16 @@ mod tests {
 
         // Create store
         let db = Database::open(&db_path).unwrap();
-        let store = ImageStore::new(images_dir.clone(), db, vec![]).unwrap();
+        let store = ImageStore::new(
+            images_dir.clone(),
+            db,
+            vec![],
+            RegistryMirrors::default(),
+            test_clients(),
+            Arc::new(PullThrottle::new(None)),
+            false,
+        )
+        .unwrap();
 
         // Load should fail
         let result = store.load_from_local(bundle_dir).await;
@@ -1164,4 +2211,189 @@ mod tests {
         let err = result.unwrap_err().to_string();
         assert!(err.contains("index.json"));
     }
+
+    #[tokio::test]
+    async fn test_pinned_reference_verified_on_use() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let bundle_dir = temp_dir.path().join("bundle");
+        let images_dir = temp_dir.path().join("images");
+        create_test_oci_bundle(&bundle_dir);
+
+        let db = Database::open(&temp_dir.path().join("test.db")).unwrap();
+        let store = ImageStore::new(
+            images_dir.clone(),
+            db,
+            vec![],
+            RegistryMirrors::default(),
+            test_clients(),
+            Arc::new(PullThrottle::new(None)),
+            false,
+        )
+        .unwrap();
+
+        let manifest = store
+            .import_layout(&bundle_dir, "localhost/app:v1")
+            .await
+            .unwrap();
+        let pinned = format!("localhost/app@{}", manifest.manifest_digest);
+        store.update_index(&pinned, &manifest).await.unwrap();
+
+        let resolved = store.pull(&pinned).await.unwrap();
+        assert_eq!(resolved.manifest_digest, manifest.manifest_digest);
+        let report = store.verify(false).await.unwrap();
+        assert!(report.is_ok(), "{:?}", report);
+        assert_eq!(report.blobs_checked, 3);
+
+        let config_path = images_dir
+            .join("configs")
+            .join(format!("{}.json", manifest.config_digest.replace(':', "-")));
+        std::fs::write(&config_path, "{}").unwrap();
+
+        let err = store.pull(&pinned).await.unwrap_err();
+        assert!(matches!(err, BoxliteError::Integrity(_)), "{}", err);
+        // The tag pins nothing and still resolves
+        store.pull("localhost/app:v1").await.unwrap();
+
+        let report = store.verify(true).await.unwrap();
+        assert_eq!(report.corrupt.len(), 1);
+        let corrupt = &report.corrupt[0];
+        assert_eq!(corrupt.kind, ImageBlobKind::Config);
+        assert_eq!(corrupt.digest, manifest.config_digest);
+        assert!(corrupt.images.contains(&pinned));
+        assert!(corrupt.removed);
+        assert!(!config_path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_inspect_imported_image() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let bundle_dir = temp_dir.path().join("bundle");
+        let layer_digest = create_test_oci_bundle(&bundle_dir);
+
+        let db = Database::open(&temp_dir.path().join("test.db")).unwrap();
+        let manager = crate::images::ImageManager::new(
+            temp_dir.path().join("images"),
+            db,
+            vec![],
+            &Default::default(),
+            &Default::default(),
+            &Default::default(),
+            &Default::default(),
+            None,
+            false,
+            None,
+        )
+        .unwrap();
+        assert!(manager.inspect("localhost/app:v1").await.unwrap().is_none());
+        manager
+            .import_layout(&bundle_dir, "localhost/app:v1")
+            .await
+            .unwrap();
+
+        let image = manager.inspect("localhost/app:v1").await.unwrap().unwrap();
+        assert_eq!(image.os, "linux");
+        assert_eq!(image.architecture, "amd64");
+        assert_eq!(image.env, vec!["PATH=/usr/local/bin:/usr/bin:/bin"]);
+        assert_eq!(image.working_dir.as_deref(), Some("/"));
+        assert!(image.entrypoint.is_empty());
+        assert_eq!(image.layers.len(), 1);
+        let layer = &image.layers[0];
+        assert_eq!(layer.digest, layer_digest);
+        assert!(layer.size.as_bytes() > 0);
+        assert_eq!(image.size, layer.size);
+        assert!(layer.downloaded);
+        assert_eq!(layer.cached_on_pull, None);
+    }
+
+    #[test]
+    fn test_manifest_pull_error_codes() {
+        let unauthorized = OciDistributionError::UnauthorizedError {
+            url: "https://registry.example/v2/private/app/manifests/latest".into(),
+        };
+        assert!(matches!(
+            manifest_pull_error("failed to pull manifest", unauthorized),
+            BoxliteError::ImagePullAuth(_)
+        ));
+
+        let envelope = serde_json::from_str(
+            r#"{"errors":[{"code":"MANIFEST_UNKNOWN","message":"manifest unknown"}]}"#,
+        )
+        .unwrap();
+        let unknown = OciDistributionError::RegistryError {
+            envelope,
+            url: "https://registry.example/v2/library/nope/manifests/latest".into(),
+        };
+        assert!(matches!(
+            manifest_pull_error("failed to pull manifest", unknown),
+            BoxliteError::ImageNotFound(_)
+        ));
+
+        assert!(matches!(
+            manifest_pull_error(
+                "failed to pull manifest",
+                OciDistributionError::ServerError {
+                    code: 503,
+                    url: "https://registry.example/v2/".into(),
+                    message: "unavailable".into(),
+                }
+            ),
+            BoxliteError::Network(_)
+        ));
+
+        assert!(matches!(
+            manifest_pull_error(
+                "failed to pull manifest",
+                OciDistributionError::GenericError(Some("bad digest".into()))
+            ),
+            BoxliteError::Storage(_)
+        ));
+
+        let tampered = oci_client::errors::DigestError::VerificationError {
+            expected: "sha256:aaaa".into(),
+            actual: "sha256:bbbb".into(),
+        };
+        assert!(matches!(
+            manifest_pull_error("failed to pull manifest", tampered.into()),
+            BoxliteError::Integrity(_)
+        ));
+    }
+
+    #[test]
+    fn test_pull_failed_error_keeps_shared_code() {
+        let not_found = || BoxliteError::ImageNotFound("missing".into());
+        let auth = || BoxliteError::ImagePullAuth("denied".into());
+
+        let errors = vec![
+            ("a".to_string(), not_found()),
+            ("b".to_string(), not_found()),
+        ];
+        assert!(matches!(
+            pull_failed_error(String::new(), &errors),
+            BoxliteError::ImageNotFound(_)
+        ));
+
+        let errors = vec![("a".to_string(), not_found()), ("b".to_string(), auth())];
+        assert!(matches!(
+            pull_failed_error(String::new(), &errors),
+            BoxliteError::ImagePullAuth(_)
+        ));
+
+        let errors = vec![
+            ("a".to_string(), not_found()),
+            ("b".to_string(), BoxliteError::Storage("disk full".into())),
+        ];
+        assert!(matches!(
+            pull_failed_error(String::new(), &errors),
+            BoxliteError::Storage(_)
+        ));
+
+        let errors = vec![
+            ("a".to_string(), not_found()),
+            ("b".to_string(), BoxliteError::Network("timeout".into())),
+        ];
+        assert!(matches!(
+            pull_failed_error(String::new(), &errors),
+            BoxliteError::Network(_)
+        ));
+    }
 }
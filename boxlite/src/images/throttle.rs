@@ -0,0 +1,156 @@
+//! Bandwidth limiting for layer downloads.
+//!
+//! All concurrent downloads share one byte budget (`pull_rate_limit_mbps`).
+//! Each write reserves its bytes against the budget; when the budget is
+//! ahead of real time, the next write waits until it catches up.
+
+use std::io;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use tokio::io::AsyncWrite;
+use tokio::time::{Instant, Sleep};
+
+/// Shared download budget and byte counter.
+#[derive(Debug)]
+pub(crate) struct PullThrottle {
+    /// Bytes per second, 0 = unlimited.
+    bytes_per_sec: AtomicU64,
+    /// Instant at which all bytes reserved so far have been "paid for".
+    next_free: Mutex<Instant>,
+    /// Layer bytes downloaded (monotonic).
+    bytes_total: Arc<AtomicU64>,
+}
+
+impl PullThrottle {
+    /// Create a throttle limited to `rate_limit_mbps` megabits per second.
+    pub(crate) fn new(rate_limit_mbps: Option<u64>) -> Self {
+        Self {
+            bytes_per_sec: AtomicU64::new(Self::bytes_per_sec(rate_limit_mbps)),
+            next_free: Mutex::new(Instant::now()),
+            bytes_total: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Change the limit; downloads in progress pick it up on their next write.
+    pub(crate) fn set_rate_limit(&self, rate_limit_mbps: Option<u64>) {
+        self.bytes_per_sec
+            .store(Self::bytes_per_sec(rate_limit_mbps), Ordering::Relaxed);
+    }
+
+    fn bytes_per_sec(rate_limit_mbps: Option<u64>) -> u64 {
+        rate_limit_mbps.map_or(0, |mbps| mbps * 1_000_000 / 8)
+    }
+
+    /// Counter of layer bytes downloaded, shared with runtime metrics.
+    pub(crate) fn bytes_total(&self) -> Arc<AtomicU64> {
+        Arc::clone(&self.bytes_total)
+    }
+
+    /// Wrap `inner` so that writes to it count against the budget.
+    pub(crate) fn writer<W>(self: &Arc<Self>, inner: W) -> ThrottledWriter<W> {
+        ThrottledWriter {
+            inner,
+            throttle: Arc::clone(self),
+            sleep: None,
+        }
+    }
+
+    /// Record `bytes` and return the instant the next write may start, if in the future.
+    fn reserve(&self, bytes: usize) -> Option<Instant> {
+        self.bytes_total.fetch_add(bytes as u64, Ordering::Relaxed);
+        let bytes_per_sec = self.bytes_per_sec.load(Ordering::Relaxed);
+        if bytes_per_sec == 0 {
+            return None;
+        }
+
+        let now = Instant::now();
+        let mut next_free = self.next_free.lock().unwrap_or_else(|e| e.into_inner());
+        let start = (*next_free).max(now);
+        *next_free = start + Duration::from_secs_f64(bytes as f64 / bytes_per_sec as f64);
+        (*next_free > now).then_some(*next_free)
+    }
+}
+
+/// `AsyncWrite` adapter returned by [`PullThrottle::writer`].
+pub(crate) struct ThrottledWriter<W> {
+    inner: W,
+    throttle: Arc<PullThrottle>,
+    sleep: Option<Pin<Box<Sleep>>>,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for ThrottledWriter<W> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        if let Some(sleep) = self.sleep.as_mut() {
+            if sleep.as_mut().poll(cx).is_pending() {
+                return Poll::Pending;
+            }
+            self.sleep = None;
+        }
+
+        let written = match Pin::new(&mut self.inner).poll_write(cx, buf) {
+            Poll::Ready(Ok(n)) => n,
+            other => return other,
+        };
+        if let Some(deadline) = self.throttle.reserve(written) {
+            self.sleep = Some(Box::pin(tokio::time::sleep_until(deadline)));
+        }
+        Poll::Ready(Ok(written))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncWriteExt;
+
+    #[tokio::test]
+    async fn test_writes_are_paced_to_the_limit() {
+        // 80 Mbit/s = 10,000,000 bytes per second
+        let throttle = Arc::new(PullThrottle::new(Some(80)));
+        let mut sink = Vec::new();
+        let start = Instant::now();
+
+        let mut writer = throttle.writer(&mut sink);
+        for _ in 0..4 {
+            writer.write_all(&[0u8; 500_000]).await.unwrap();
+        }
+        writer.flush().await.unwrap();
+
+        // The last reservation is not waited on until the next write.
+        assert!(start.elapsed() >= Duration::from_millis(150));
+        assert_eq!(throttle.bytes_total().load(Ordering::Relaxed), 2_000_000);
+        assert_eq!(sink.len(), 2_000_000);
+    }
+
+    #[test]
+    fn test_unlimited_never_reserves_time() {
+        let throttle = PullThrottle::new(None);
+        assert_eq!(throttle.reserve(4_000_000), None);
+        assert_eq!(throttle.bytes_total().load(Ordering::Relaxed), 4_000_000);
+    }
+
+    #[test]
+    fn test_rate_limit_can_be_changed() {
+        let throttle = PullThrottle::new(None);
+        throttle.set_rate_limit(Some(80));
+        assert!(throttle.reserve(1_000_000).is_some());
+        throttle.set_rate_limit(None);
+        assert_eq!(throttle.reserve(1_000_000), None);
+    }
+}
@@ -4,7 +4,7 @@
 //! along with a fluent `JailerBuilder` for configuration.
 
 use crate::jailer::config::{ResourceLimits, SecurityOptions};
-use crate::runtime::options::VolumeSpec;
+use crate::runtime::options::{BoxPriority, HostLimits, VolumeSpec};
 use std::path::{Path, PathBuf};
 
 // ============================================================================
@@ -47,6 +47,14 @@ pub struct Jailer {
     pub(crate) box_id: String,
     /// Box directory path
     pub(crate) box_dir: PathBuf,
+    /// Runtime-wide host limits (`BoxliteOptions::host_limits`)
+    pub(crate) host_limits: Option<HostLimits>,
+    /// Host scheduling priority (`BoxOptions::priority`)
+    pub(crate) priority: BoxPriority,
+    /// BoxLite home directory, if not two levels above `box_dir`
+    pub(crate) home_dir: Option<PathBuf>,
+    /// Image cache directory, if not `{home_dir}/images`
+    pub(crate) images_dir: Option<PathBuf>,
 }
 
 impl Jailer {
@@ -61,6 +69,10 @@ impl Jailer {
             volumes: Vec::new(),
             box_id: box_id.into(),
             box_dir: box_dir.into(),
+            host_limits: None,
+            priority: BoxPriority::default(),
+            home_dir: None,
+            images_dir: None,
         }
     }
 
@@ -96,6 +108,37 @@ impl Jailer {
         self
     }
 
+    /// Set runtime-wide host limits (consuming builder pattern - legacy API).
+    ///
+    /// Box processes join the host cgroup (Linux) or run at background
+    /// priority as the limits ask.
+    pub fn with_host_limits(mut self, host_limits: Option<HostLimits>) -> Self {
+        self.host_limits = host_limits;
+        self
+    }
+
+    /// Set the host scheduling priority (consuming builder pattern - legacy API).
+    pub fn with_priority(mut self, priority: BoxPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Set the runtime directories the shim uses outside its box directory
+    /// (consuming builder pattern - legacy API).
+    ///
+    /// Needed when box state lives outside the home directory
+    /// (`BoxliteOptions::state_dir`) or the image cache does
+    /// (`BoxliteOptions::image_cache_dir`).
+    pub fn with_runtime_dirs(
+        mut self,
+        home_dir: impl Into<PathBuf>,
+        images_dir: impl Into<PathBuf>,
+    ) -> Self {
+        self.home_dir = Some(home_dir.into());
+        self.images_dir = Some(images_dir.into());
+        self
+    }
+
     // ─────────────────────────────────────────────────────────────────────
     // Getters
     // ─────────────────────────────────────────────────────────────────────
@@ -125,6 +168,21 @@ impl Jailer {
         &self.box_dir
     }
 
+    /// Get the BoxLite home directory: as set, or two levels above the box
+    /// directory (`{home}/boxes/{box_id}`).
+    pub fn home_dir(&self) -> Option<PathBuf> {
+        self.home_dir
+            .clone()
+            .or_else(|| Some(self.box_dir.parent()?.parent()?.to_path_buf()))
+    }
+
+    /// Get the image cache directory: as set, or `{home}/images`.
+    pub fn images_dir(&self) -> Option<PathBuf> {
+        self.images_dir
+            .clone()
+            .or_else(|| Some(self.home_dir()?.join("images")))
+    }
+
     /// Get the resource limits.
     pub fn resource_limits(&self) -> &ResourceLimits {
         &self.security.resource_limits
@@ -185,6 +243,8 @@ pub struct JailerBuilder {
     volumes: Vec<VolumeSpec>,
     box_id: Option<String>,
     box_dir: Option<PathBuf>,
+    host_limits: Option<HostLimits>,
+    priority: BoxPriority,
 }
 
 impl Default for JailerBuilder {
@@ -201,6 +261,8 @@ impl JailerBuilder {
             volumes: Vec::new(),
             box_id: None,
             box_dir: None,
+            host_limits: None,
+            priority: BoxPriority::default(),
         }
     }
 
@@ -252,6 +314,24 @@ impl JailerBuilder {
         self
     }
 
+    /// Set runtime-wide host limits.
+    ///
+    /// # Arguments
+    /// * `limits` - `BoxliteOptions::host_limits` of the runtime
+    pub fn host_limits(&mut self, limits: HostLimits) -> &mut Self {
+        self.host_limits = Some(limits);
+        self
+    }
+
+    /// Set the host scheduling priority.
+    ///
+    /// # Arguments
+    /// * `priority` - `BoxOptions::priority` of the box
+    pub fn priority(&mut self, priority: BoxPriority) -> &mut Self {
+        self.priority = priority;
+        self
+    }
+
     /// Enable or disable jailer isolation.
     ///
     /// Shorthand for modifying `security.jailer_enabled`.
@@ -298,6 +378,10 @@ impl JailerBuilder {
             volumes: self.volumes.clone(),
             box_id,
             box_dir,
+            host_limits: self.host_limits.clone(),
+            priority: self.priority,
+            home_dir: None,
+            images_dir: None,
         })
     }
 }
@@ -391,11 +475,13 @@ mod tests {
                 host_path: "/data".to_string(),
                 guest_path: "/mnt/data".to_string(),
                 read_only: true,
+                ..Default::default()
             })
             .add_volume(VolumeSpec {
                 host_path: "/output".to_string(),
                 guest_path: "/mnt/output".to_string(),
                 read_only: false,
+                ..Default::default()
             })
             .build()
             .expect("Should build successfully");
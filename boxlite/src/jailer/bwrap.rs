@@ -416,6 +416,13 @@ pub fn build_shim_command(
     // This contains: boxes/, images/, db/, logs/, etc.
     bwrap.bind(layout.home_dir(), layout.home_dir());
 
+    // Box state and the image cache may be configured outside it
+    for dir in [layout.state_dir().to_path_buf(), layout.images_dir()] {
+        if !dir.starts_with(layout.home_dir()) {
+            bwrap.bind(&dir, &dir);
+        }
+    }
+
     // Mount the shim binary's directory (read-only)
     // This is CRITICAL: the shim binary and its bundled libraries (libkrun.so,
     // libgvproxy.so, libkrunfw.so) are in this directory. Without this mount,
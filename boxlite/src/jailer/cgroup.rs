@@ -20,7 +20,7 @@
 //!
 //! ```text
 //! {cgroup_base}/              # /sys/fs/cgroup (root) or user service path (rootless)
-//! └── boxlite/
+//! └── boxlite/               # BoxliteOptions::host_limits (cpu.max, cpu.weight, memory.max)
 //!     └── {box_id}/
 //!         ├── cpu.max           # CPU limit
 //!         ├── cpu.weight        # CPU shares
@@ -33,6 +33,7 @@
 use super::common;
 use super::config::ResourceLimits;
 use super::error::JailerError;
+use crate::runtime::options::HostLimits;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -166,19 +167,7 @@ pub fn setup_cgroup(box_id: &str, config: &CgroupConfig) -> Result<PathBuf, Jail
         "Using cgroup base path"
     );
 
-    // Create boxlite parent cgroup if needed
-    if !boxlite_cgroup.exists() {
-        fs::create_dir(&boxlite_cgroup).map_err(|e| {
-            JailerError::Cgroup(format!(
-                "Failed to create boxlite cgroup at {}: {}",
-                boxlite_cgroup.display(),
-                e
-            ))
-        })?;
-
-        // Enable controllers in parent
-        enable_controllers(&boxlite_cgroup)?;
-    }
+    ensure_boxlite_cgroup(&boxlite_cgroup)?;
 
     // Create box cgroup
     if !box_cgroup.exists() {
@@ -203,6 +192,64 @@ pub fn setup_cgroup(box_id: &str, config: &CgroupConfig) -> Result<PathBuf, Jail
     Ok(box_cgroup)
 }
 
+/// Apply runtime-wide host limits to the boxlite parent cgroup.
+///
+/// Every box cgroup is a child of it, so the limits bound all boxes
+/// together. Unset limits are reset to the kernel defaults, so lifting a
+/// limit takes effect too.
+///
+/// # Errors
+///
+/// Returns [`JailerError::Cgroup`] if cgroup v2 is not available or the
+/// cgroup cannot be created or written.
+pub fn setup_host_cgroup(config: &CgroupConfig) -> Result<PathBuf, JailerError> {
+    if !is_cgroup_v2_available() {
+        return Err(JailerError::Cgroup("Cgroup v2 not available".to_string()));
+    }
+
+    let boxlite_cgroup = get_cgroup_base().join(BOXLITE_CGROUP);
+    ensure_boxlite_cgroup(&boxlite_cgroup)?;
+
+    let memory_max = config
+        .memory_max
+        .map_or_else(|| "max".to_string(), |m| m.to_string());
+    write_file(&boxlite_cgroup.join("memory.max"), &memory_max)?;
+    let cpu_max = match config.cpu_max {
+        Some((quota, period)) => format!("{} {}", quota, period),
+        None => "max".to_string(),
+    };
+    write_file(&boxlite_cgroup.join("cpu.max"), &cpu_max)?;
+    let cpu_weight = config.cpu_weight.unwrap_or(100);
+    write_file(&boxlite_cgroup.join("cpu.weight"), &cpu_weight.to_string())?;
+
+    tracing::debug!(
+        path = %boxlite_cgroup.display(),
+        memory_max = %memory_max,
+        cpu_max = %cpu_max,
+        cpu_weight,
+        "Host cgroup limits applied"
+    );
+
+    Ok(boxlite_cgroup)
+}
+
+/// Create the boxlite parent cgroup if needed.
+fn ensure_boxlite_cgroup(boxlite_cgroup: &Path) -> Result<(), JailerError> {
+    if boxlite_cgroup.exists() {
+        return Ok(());
+    }
+    fs::create_dir(boxlite_cgroup).map_err(|e| {
+        JailerError::Cgroup(format!(
+            "Failed to create boxlite cgroup at {}: {}",
+            boxlite_cgroup.display(),
+            e
+        ))
+    })?;
+
+    // Enable controllers in parent
+    enable_controllers(boxlite_cgroup)
+}
+
 /// Enable controllers for child cgroups.
 fn enable_controllers(cgroup_path: &Path) -> Result<(), JailerError> {
     let subtree_control = cgroup_path.join("cgroup.subtree_control");
@@ -313,6 +360,23 @@ impl From<&ResourceLimits> for CgroupConfig {
     }
 }
 
+/// Convert HostLimits to CgroupConfig for the boxlite parent cgroup.
+impl From<&HostLimits> for CgroupConfig {
+    fn from(limits: &HostLimits) -> Self {
+        /// cpu.max period, in microseconds (the kernel default)
+        const PERIOD: u64 = 100_000;
+        Self {
+            memory_max: limits.memory_mib.map(|m| m * 1024 * 1024),
+            memory_high: None,
+            cpu_weight: limits.cpu_weight,
+            cpu_max: limits
+                .cpus
+                .map(|cpus| ((cpus * PERIOD as f64).round().max(1000.0) as u64, PERIOD)),
+            pids_max: None,
+        }
+    }
+}
+
 // ============================================================================
 // Async-Signal-Safe Cgroup (for pre_exec)
 // ============================================================================
@@ -440,4 +504,25 @@ mod tests {
         assert_eq!(config.pids_max, Some(100));
         assert!(config.cpu_max.is_some());
     }
+
+    #[test]
+    fn test_cgroup_config_from_host_limits() {
+        let limits = HostLimits {
+            cpus: Some(2.5),
+            memory_mib: Some(8192),
+            cpu_weight: Some(50),
+            background: false,
+        };
+
+        let config = CgroupConfig::from(&limits);
+
+        assert_eq!(config.cpu_max, Some((250_000, 100_000)));
+        assert_eq!(config.memory_max, Some(8192 * 1024 * 1024));
+        assert_eq!(config.cpu_weight, Some(50));
+        assert_eq!(config.memory_high, None);
+
+        let config = CgroupConfig::from(&HostLimits::default());
+        assert_eq!(config.cpu_max, None);
+        assert_eq!(config.memory_max, None);
+    }
 }
@@ -18,7 +18,10 @@
 //! - Cgroup membership (Linux only)
 
 use crate::jailer::builder::Jailer;
+use crate::jailer::common::priority::Scheduling;
 use crate::jailer::pre_exec;
+#[cfg(target_os = "linux")]
+use crate::runtime::options::BoxPriority;
 use std::path::Path;
 use std::process::Command;
 
@@ -68,7 +71,8 @@ impl Jailer {
                 )));
             }
 
-            let cgroup_config = CgroupConfig::from(&self.security.resource_limits);
+            let mut cgroup_config = CgroupConfig::from(&self.security.resource_limits);
+            cgroup_config.cpu_weight = Some(self.priority.cpu_weight());
 
             match setup_cgroup(&self.box_id, &cgroup_config) {
                 Ok(path) => {
@@ -157,7 +161,13 @@ impl Jailer {
         let cgroup_procs_path = cgroup::build_cgroup_procs_path(&self.box_id);
         let pid_file_path = self.build_pid_file_path();
 
-        pre_exec::add_pre_exec_hook(&mut cmd, resource_limits, cgroup_procs_path, pid_file_path);
+        pre_exec::add_pre_exec_hook(
+            &mut cmd,
+            resource_limits,
+            cgroup_procs_path,
+            self.scheduling(),
+            pid_file_path,
+        );
         cmd
     }
 
@@ -241,10 +251,16 @@ impl Jailer {
         bwrap.bind(&self.box_dir, &self.box_dir);
         tracing::debug!(box_dir = %self.box_dir.display(), "bwrap: mounted box directory");
 
+        //    Sockets live outside it when their paths would be too long, and
+        //    /tmp is a private tmpfs here
+        let sockets_dir = crate::runtime::layout::box_sockets_dir(&self.box_dir);
+        if !sockets_dir.starts_with(&self.box_dir) {
+            bwrap.bind(&sockets_dir, &sockets_dir);
+            tracing::debug!(sockets_dir = %sockets_dir.display(), "bwrap: mounted sockets directory");
+        }
+
         // Get boxlite home directory for other mounts
-        if let Some(boxes_dir) = self.box_dir.parent()
-            && let Some(home_dir) = boxes_dir.parent()
-        {
+        if let Some(home_dir) = self.home_dir() {
             // 2. Mount logs directory (read-write for shim logging + console output)
             let logs_dir = home_dir.join("logs");
             if logs_dir.exists() {
@@ -259,14 +275,15 @@ impl Jailer {
                 bwrap.bind(&tmp_dir, &tmp_dir);
                 tracing::debug!(tmp_dir = %tmp_dir.display(), "bwrap: mounted tmp directory");
             }
+        }
 
-            // 4. Mount images directory (read-only for extracted OCI layers)
-            //    Contains: extracted layer data used for rootfs
-            let images_dir = home_dir.join("images");
-            if images_dir.exists() {
-                bwrap.ro_bind(&images_dir, &images_dir);
-                tracing::debug!(images_dir = %images_dir.display(), "bwrap: mounted images directory (ro)");
-            }
+        // 4. Mount images directory (read-only for extracted OCI layers)
+        //    Contains: extracted layer data used for rootfs
+        if let Some(images_dir) = self.images_dir()
+            && images_dir.exists()
+        {
+            bwrap.ro_bind(&images_dir, &images_dir);
+            tracing::debug!(images_dir = %images_dir.display(), "bwrap: mounted images directory (ro)");
         }
 
         // NOTE: No external shim directory bind mount needed!
@@ -308,8 +325,9 @@ impl Jailer {
 
         let mut cmd = if macos::is_sandbox_available() {
             tracing::info!("Building sandbox-exec isolated command");
+            let volumes = self.sandbox_volumes();
             let (sandbox_cmd, sandbox_args) =
-                macos::get_sandbox_exec_args(&self.security, &self.box_dir, binary, &self.volumes);
+                macos::get_sandbox_exec_args(&self.security, &self.box_dir, binary, &volumes);
             let mut cmd = Command::new(sandbox_cmd);
             cmd.args(sandbox_args);
             cmd.arg(binary);
@@ -324,7 +342,13 @@ impl Jailer {
 
         let resource_limits = self.security.resource_limits.clone();
         let pid_file_path = self.build_pid_file_path();
-        pre_exec::add_pre_exec_hook(&mut cmd, resource_limits, None, pid_file_path);
+        pre_exec::add_pre_exec_hook(
+            &mut cmd,
+            resource_limits,
+            None,
+            self.scheduling(),
+            pid_file_path,
+        );
         cmd
     }
 
@@ -337,8 +361,23 @@ impl Jailer {
         cmd.args(args);
 
         let resource_limits = self.security.resource_limits.clone();
+        // Without the sandbox, boxes only join their cgroup when host limits
+        // or a CPU weight apply to them
+        #[cfg(target_os = "linux")]
+        let cgroup_procs_path = (self.host_limits.is_some()
+            || self.priority != BoxPriority::Normal)
+            .then(|| crate::jailer::cgroup::build_cgroup_procs_path(&self.box_id))
+            .flatten();
+        #[cfg(not(target_os = "linux"))]
+        let cgroup_procs_path = None;
         let pid_file_path = self.build_pid_file_path();
-        pre_exec::add_pre_exec_hook(&mut cmd, resource_limits, None, pid_file_path);
+        pre_exec::add_pre_exec_hook(
+            &mut cmd,
+            resource_limits,
+            cgroup_procs_path,
+            self.scheduling(),
+            pid_file_path,
+        );
         cmd
     }
 
@@ -346,6 +385,47 @@ impl Jailer {
     // Helper methods
     // ─────────────────────────────────────────────────────────────────────
 
+    /// Nice value and I/O priority for the shim.
+    ///
+    /// macOS has no cgroups for `HostLimits`, so any host limits imply
+    /// background priority there.
+    fn scheduling(&self) -> Scheduling {
+        Scheduling {
+            priority: self.priority,
+            background: self
+                .host_limits
+                .as_ref()
+                .is_some_and(|limits| limits.background || cfg!(target_os = "macos")),
+        }
+    }
+
+    /// Volumes plus the runtime directories set with `with_runtime_dirs`.
+    ///
+    /// The Seatbelt policy finds the home directory two levels above the
+    /// box directory; when box state or the image cache live elsewhere, the
+    /// shim still needs to write its logs and read the rootfs disks.
+    #[cfg(target_os = "macos")]
+    fn sandbox_volumes(&self) -> Vec<crate::runtime::options::VolumeSpec> {
+        use crate::runtime::options::VolumeSpec;
+
+        let mut volumes = self.volumes.clone();
+        if let Some(home_dir) = &self.home_dir {
+            volumes.push(VolumeSpec {
+                host_path: home_dir.join("logs").to_string_lossy().into_owned(),
+                read_only: false,
+                ..Default::default()
+            });
+        }
+        if let Some(images_dir) = &self.images_dir {
+            volumes.push(VolumeSpec {
+                host_path: images_dir.to_string_lossy().into_owned(),
+                read_only: true,
+                ..Default::default()
+            });
+        }
+        volumes
+    }
+
     /// Build the PID file path as a CString for use in pre_exec hook.
     ///
     /// Returns the path to `{box_dir}/shim.pid` as a CString, ready for
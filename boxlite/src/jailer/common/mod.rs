@@ -4,6 +4,7 @@
 //! - [`fd`]: File descriptor cleanup (async-signal-safe for pre_exec)
 //! - [`rlimit`]: Resource limit management (async-signal-safe for pre_exec)
 //! - [`pid`]: PID file writing (async-signal-safe for pre_exec)
+//! - [`priority`]: Nice value and I/O priority (async-signal-safe for pre_exec)
 //! - [`fs`]: Filesystem utilities (copy-if-newer, etc.)
 //!
 //! Note: Environment sanitization is handled by bwrap/sandbox-exec at spawn time.
@@ -11,6 +12,7 @@
 pub mod fd;
 pub mod fs;
 pub mod pid;
+pub mod priority;
 pub mod rlimit;
 
 /// Get errno in an async-signal-safe way.
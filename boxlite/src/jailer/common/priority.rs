@@ -0,0 +1,120 @@
+//! Host scheduling priority of the jailed process.
+//!
+//! Applied before exec(); the exec'd program and its children inherit it.
+//!
+//! - **Linux**: nice value (`BoxPriority::nice`, or 19 for
+//!   `HostLimits::background`) and I/O priority via `ioprio_set`
+//! - **macOS**: `PRIO_DARWIN_BG`, the background QoS `taskpolicy -b` sets,
+//!   which throttles CPU, disk I/O and network, for low priority and
+//!   background boxes; a nice value for high priority
+
+use crate::runtime::options::BoxPriority;
+
+/// Scheduling to apply to a box's shim process.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Scheduling {
+    /// The box's `BoxOptions::priority`
+    pub priority: BoxPriority,
+    /// Runtime-wide `HostLimits::background`; overrides `priority`
+    pub background: bool,
+}
+
+impl Scheduling {
+    /// Nice value to set, if not the default.
+    fn nice(&self) -> Option<i32> {
+        if self.background {
+            return Some(19);
+        }
+        Some(self.priority.nice()).filter(|&nice| nice != 0)
+    }
+}
+
+/// Apply `scheduling` to the current process - async-signal-safe version
+/// for pre_exec.
+///
+/// Only calls `setpriority()` and `ioprio_set()`. Do NOT add logging,
+/// allocation or locking. Raising priority without the privilege for it is
+/// skipped; I/O priority is best effort.
+///
+/// # Returns
+/// * `Ok(())` - Priority applied
+/// * `Err(errno)` - Failed (returns raw errno for io::Error conversion)
+pub fn apply_raw(scheduling: Scheduling) -> Result<(), i32> {
+    #[cfg(target_os = "linux")]
+    {
+        // ioprio_set(2) constants
+        const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+        const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+        const IOPRIO_CLASS_BE: libc::c_int = 2;
+        const IOPRIO_CLASS_IDLE: libc::c_int = 3;
+
+        if let Some(nice) = scheduling.nice() {
+            set_nice(nice)?;
+        }
+
+        let ioprio = if scheduling.background || scheduling.priority == BoxPriority::Low {
+            Some(IOPRIO_CLASS_IDLE << IOPRIO_CLASS_SHIFT)
+        } else if scheduling.priority == BoxPriority::High {
+            // Best-effort class, level 0 (the highest)
+            Some(IOPRIO_CLASS_BE << IOPRIO_CLASS_SHIFT)
+        } else {
+            None
+        };
+        if let Some(ioprio) = ioprio {
+            // Ignored: not every I/O scheduler honors priorities anyway
+            unsafe { libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, ioprio) };
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        if scheduling.background || scheduling.priority == BoxPriority::Low {
+            let result =
+                unsafe { libc::setpriority(libc::PRIO_DARWIN_PROCESS, 0, libc::PRIO_DARWIN_BG) };
+            if result != 0 {
+                return Err(super::get_errno());
+            }
+        } else if let Some(nice) = scheduling.nice() {
+            set_nice(nice)?;
+        }
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    let _ = scheduling;
+
+    Ok(())
+}
+
+/// Set the nice value; raising priority without privilege is skipped.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn set_nice(nice: i32) -> Result<(), i32> {
+    let result = unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, nice) };
+    if result != 0 {
+        let errno = super::get_errno();
+        if nice < 0 && (errno == libc::EPERM || errno == libc::EACCES) {
+            return Ok(());
+        }
+        return Err(errno);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scheduling_nice() {
+        assert_eq!(Scheduling::default().nice(), None);
+        let low = Scheduling {
+            priority: BoxPriority::Low,
+            background: false,
+        };
+        assert_eq!(low.nice(), Some(10));
+        let background = Scheduling {
+            priority: BoxPriority::High,
+            background: true,
+        };
+        assert_eq!(background.nice(), Some(19));
+    }
+}
@@ -370,6 +370,17 @@ fn build_dynamic_read_volumes(
     for vol in volumes {
         let path = canonicalize_or_original(Path::new(&vol.host_path));
         let ro_marker = if vol.read_only { " (ro)" } else { " (rw)" };
+        // Single-file volumes share the parent directory over virtiofs; allow
+        // listing it without exposing the sibling files.
+        if path.is_file()
+            && let Some(parent) = path.parent()
+        {
+            policy.push_str(&format!(
+                "    (literal \"{}\")  ; parent of {}\n",
+                parent.display(),
+                vol.guest_path
+            ));
+        }
         policy.push_str(&format!(
             "    (subpath \"{}\")  ; {}{}\n",
             path.display(),
@@ -593,11 +604,13 @@ mod tests {
                 host_path: "/data/input".to_string(),
                 guest_path: "/mnt/input".to_string(),
                 read_only: true,
+                ..Default::default()
             },
             VolumeSpec {
                 host_path: "/data/output".to_string(),
                 guest_path: "/mnt/output".to_string(),
                 read_only: false,
+                ..Default::default()
             },
         ];
 
@@ -620,11 +633,13 @@ mod tests {
                 host_path: "/data/input".to_string(),
                 guest_path: "/mnt/input".to_string(),
                 read_only: true, // Should NOT be in write policy
+                ..Default::default()
             },
             VolumeSpec {
                 host_path: "/data/output".to_string(),
                 guest_path: "/mnt/output".to_string(),
                 read_only: false, // Should be in write policy
+                ..Default::default()
             },
         ];
         let box_dir = PathBuf::from("/Users/test/.boxlite/boxes/test-box");
@@ -8,7 +8,8 @@
 //! 1. **Close inherited FDs** - Prevents information leakage
 //! 2. **Apply rlimits** - Resource limits (max files, memory, CPU time, etc.)
 //! 3. **Add to cgroup** - Linux only, for cgroup resource limits
-//! 4. **Write PID file** - Single source of truth for process tracking
+//! 4. **Set priority** - Nice value and I/O priority (`BoxOptions::priority`)
+//! 5. **Write PID file** - Single source of truth for process tracking
 //!
 //! # Safety
 //!
@@ -21,19 +22,22 @@
 //! See the [`common`](crate::jailer::common) module for async-signal-safe utilities.
 
 use crate::jailer::common;
+use crate::jailer::common::priority::Scheduling;
 use crate::jailer::config::ResourceLimits;
 use std::process::Command;
 
 /// Add pre-execution hook for process isolation (async-signal-safe).
 ///
 /// Runs after fork() but before the new program starts in the child process.
-/// Applies: FD cleanup, rlimits, cgroup membership (Linux), PID file writing.
+/// Applies: FD cleanup, rlimits, cgroup membership (Linux), scheduling
+/// priority, PID file writing.
 ///
 /// # Arguments
 ///
 /// * `cmd` - The Command to add the hook to
 /// * `resource_limits` - Resource limits to apply
 /// * `cgroup_procs_path` - Path to cgroup.procs file (Linux only, pre-computed)
+/// * `scheduling` - Nice value and I/O priority to set
 /// * `pid_file_path` - Path to PID file (pre-computed CString for async-signal-safety)
 ///
 /// # Safety
@@ -41,7 +45,7 @@ use std::process::Command;
 /// This function uses `unsafe` to set the hook. The hook itself
 /// only uses async-signal-safe operations:
 /// - `close()` / `close_range()` syscalls
-/// - `setrlimit()` syscall
+/// - `setrlimit()`, `setpriority()` and `ioprio_set()` syscalls
 /// - `open()` / `write()` / `close()` syscalls (for cgroup and PID file)
 /// - `getpid()` syscall
 ///
@@ -60,7 +64,7 @@ use std::process::Command;
 /// let mut cmd = Command::new("/path/to/binary");
 /// let limits = ResourceLimits::default();
 ///
-/// add_hook(&mut cmd, limits, None, None);
+/// add_hook(&mut cmd, limits, None, Scheduling::default(), None);
 ///
 /// cmd.spawn()?;
 /// ```
@@ -68,6 +72,7 @@ pub fn add_pre_exec_hook(
     cmd: &mut Command,
     resource_limits: ResourceLimits,
     #[allow(unused_variables)] cgroup_procs_path: Option<std::ffi::CString>,
+    scheduling: Scheduling,
     pid_file_path: Option<std::ffi::CString>,
 ) {
     use std::os::unix::process::CommandExt;
@@ -93,7 +98,11 @@ pub fn add_pre_exec_hook(
                 let _ = crate::jailer::cgroup::add_self_to_cgroup_raw(path);
             }
 
-            // 4. Write PID file (single source of truth for process tracking)
+            // 4. Set scheduling priority
+            // Lets interactive boxes stay responsive next to batch boxes
+            common::priority::apply_raw(scheduling).map_err(std::io::Error::from_raw_os_error)?;
+
+            // 5. Write PID file (single source of truth for process tracking)
             // This must happen after fork() - child has its own PID now
             if let Some(ref path) = pid_file_path {
                 common::pid::write_pid_file_raw(path).map_err(std::io::Error::from_raw_os_error)?;
@@ -114,7 +123,7 @@ mod tests {
         let mut cmd = Command::new("/bin/echo");
         let limits = ResourceLimits::default();
 
-        add_pre_exec_hook(&mut cmd, limits, None, None);
+        add_pre_exec_hook(&mut cmd, limits, None, Scheduling::default(), None);
 
         // We can't actually test the hook without forking
         // Integration tests should verify the actual behavior
@@ -129,7 +138,7 @@ mod tests {
         let limits = ResourceLimits::default();
         let cgroup_path = CString::new("/sys/fs/cgroup/boxlite/test/cgroup.procs").ok();
 
-        add_pre_exec_hook(&mut cmd, limits, cgroup_path, None);
+        add_pre_exec_hook(&mut cmd, limits, cgroup_path, Scheduling::default(), None);
     }
 
     #[test]
@@ -140,6 +149,6 @@ mod tests {
         let limits = ResourceLimits::default();
         let pid_file = CString::new("/tmp/test.pid").ok();
 
-        add_pre_exec_hook(&mut cmd, limits, None, pid_file);
+        add_pre_exec_hook(&mut cmd, limits, None, Scheduling::default(), pid_file);
     }
 }
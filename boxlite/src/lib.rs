@@ -18,6 +18,7 @@ pub mod runtime;
 pub mod util;
 pub mod vmm;
 
+mod build;
 mod db;
 mod disk;
 mod fs;
@@ -30,17 +31,41 @@ pub use litebox::LiteBox;
 pub use portal::GuestSession;
 pub use runtime::BoxliteRuntime;
 
-pub use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+pub use boxlite_shared::errors::{BoxliteError, BoxliteResult, ErrorCode};
+pub use build::{BuildOptions, BuildOutput};
+pub use images::RegistryCacheOptions;
+#[cfg(feature = "testing")]
+pub use images::extract_layer_tarball_streaming;
 pub use litebox::{
-    BoxCommand, CopyOptions, ExecResult, ExecStderr, ExecStdin, ExecStdout, Execution, ExecutionId,
+    BoxCommand, CellError, CellResult, ConnectionRecord, CopyOptions, CoreDump, ExecResult,
+    ExecStderr, ExecStdin, ExecStdout, Execution, ExecutionId, ExitReason, FsEvent, FsEventKind,
+    FsWatch, ListeningPort, OutputLines, OutputRecording, OutputText, PackageManager, ProcessInfo,
+    PythonSession, WaitCondition,
+};
+pub use metrics::{
+    BootPhases, BoxMetrics, ImagePullMetrics, LayerPullMetrics, MetricsSample, RuntimeMetrics,
+    StageOutcome, StageTiming,
 };
-pub use metrics::{BoxMetrics, RuntimeMetrics};
 use runtime::layout::FilesystemLayout;
 pub use runtime::options::{
-    BoxOptions, BoxliteOptions, ResourceLimits, RootfsSpec, SecurityOptions,
+    AdmissionPolicy, BlobCacheBackend, BlobCacheOptions, BoxOptions, BoxPriority, BoxTimeouts,
+    BoxliteOptions, CacheReclaimPolicy, CoreDumpOptions, ExecCompression, ExecLimit,
+    HeartbeatPolicy, HostLimits, MetricsHistoryPolicy, NetworkPolicy, ProxyOptions, RegistryConfig,
+    ResourceLimits, RetentionPolicy, RootfsFormat, RootfsSpec, SecurityOptions, SidecarSpec,
+    SshOptions,
 };
+pub use runtime::profile::{BoxProfile, BrowserProfile, builtin_profile, builtin_profiles};
+pub use runtime::reload::OptionsReload;
 pub use runtime::types::ContainerID;
-pub use runtime::types::{BoxID, BoxInfo, BoxState, BoxStateInfo, BoxStatus};
+pub use runtime::types::{
+    BoxHealth, BoxID, BoxInfo, BoxPlan, BoxShutdown, BoxShutdownOutcome, BoxState, BoxStateInfo,
+    BoxStatus, ContainerProcess, CorruptBlob, DiskUsage, HealthStatus, ImageBlobKind,
+    ImageHistoryEntry, ImageInspect, ImageLayerInspect, ImageVerification, ImportOptions,
+    PrefetchResult, PruneReport, ShutdownEvent, ShutdownReport, SystemPruneOptions,
+    SystemPruneReport,
+};
+#[cfg(any(test, feature = "testing"))]
+pub use vmm::mock::{MockExec, MockScript};
 
 /// Initialize tracing for Boxlite using the provided filesystem layout.
 ///
@@ -4,9 +4,13 @@
 // IMPORTS
 // ============================================================================
 
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::Ordering;
+use std::time::Duration;
 
+use futures::StreamExt;
 use parking_lot::RwLock;
 use tar;
 use tokio::sync::OnceCell;
@@ -14,19 +18,39 @@ use tokio_util::sync::CancellationToken;
 
 use boxlite_shared::errors::{BoxliteError, BoxliteResult};
 
+use super::cache_reclaim::{self, CacheReclaim};
 use super::config::BoxConfig;
+use super::connection_log::{self, ConnectionRecord};
+use super::core_dump::{self, CoreDump};
 use super::exec::{BoxCommand, ExecStderr, ExecStdin, ExecStdout, Execution};
-use super::state::BoxState;
+use super::exec_limit::{self, ExecSlots};
+use super::heartbeat;
+use super::init_commands;
+use super::metrics_sampler;
+use super::oom;
+use super::process::{ListeningPort, ProcessInfo};
+use super::recording::Recorder;
+use super::ssh;
+use super::state::{BoxHealth, BoxState, ContainerProcess, HealthStatus};
+use super::wait::{self, WaitCondition};
+use super::watch::{FsEvent, FsWatch};
 use crate::disk::Disk;
-#[cfg(target_os = "linux")]
 use crate::fs::BindMountHandle;
 use crate::litebox::copy::CopyOptions;
 use crate::lock::LockGuard;
-use crate::metrics::{BoxMetrics, BoxMetricsStorage};
+#[cfg(feature = "gvproxy-backend")]
+use crate::metrics::NetworkUsage;
+use crate::metrics::{BoxMetrics, BoxMetricsStorage, MetricsSample};
+use crate::net::forwarder::{PortForwarder, assign_host_port, check_host_port};
 use crate::portal::GuestSession;
+use crate::portal::interfaces::{
+    ContainerInterface, ExecutionInterface, FilesInterface, GuestInterface,
+};
+use crate::runtime::options::{NetworkSpec, PortSpec};
 use crate::runtime::rt_impl::SharedRuntimeImpl;
 use crate::runtime::types::BoxStatus;
 use crate::vmm::controller::VmmHandler;
+use crate::volumes::ContainerMount;
 use crate::{BoxID, BoxInfo};
 
 // ============================================================================
@@ -36,6 +60,10 @@ use crate::{BoxID, BoxInfo};
 /// Shared reference to BoxImpl.
 pub type SharedBoxImpl = Arc<BoxImpl>;
 
+/// How long a metrics read waits for the network backend's counters.
+#[cfg(feature = "gvproxy-backend")]
+const NETWORK_STATS_TIMEOUT: Duration = Duration::from_secs(2);
+
 // ============================================================================
 // LIVE STATE
 // ============================================================================
@@ -45,13 +73,26 @@ pub type SharedBoxImpl = Arc<BoxImpl>;
 /// Contains all resources related to a running VM instance.
 /// Separated from BoxImpl to allow operations like `info()` without initializing LiveState.
 pub(crate) struct LiveState {
-    // VM process control
-    handler: std::sync::Mutex<Box<dyn VmmHandler>>,
+    // VM process control: shared for status and metrics reads, exclusive
+    // only to stop
+    handler: RwLock<Box<dyn VmmHandler>>,
     guest_session: GuestSession,
 
     // Metrics
     metrics: BoxMetricsStorage,
 
+    // Idle memory reclaim (shared with its monitor task)
+    cache_reclaim: Arc<CacheReclaim>,
+
+    // User port mappings as assigned at spawn (None on reattach)
+    published_ports: Option<Vec<PortSpec>>,
+
+    // Main container process as started (None on reattach)
+    process: Option<ContainerProcess>,
+
+    // Volumes mounted after start, keyed by container path
+    hot_mounts: tokio::sync::Mutex<HotMounts>,
+
     // Disk resources (kept for lifecycle management)
     _container_rootfs_disk: Disk,
     #[allow(dead_code)]
@@ -74,15 +115,43 @@ impl LiveState {
         #[cfg(target_os = "linux")] bind_mount: Option<BindMountHandle>,
     ) -> Self {
         Self {
-            handler: std::sync::Mutex::new(handler),
+            handler: RwLock::new(handler),
             guest_session,
             metrics,
+            cache_reclaim: Arc::new(CacheReclaim::default()),
+            published_ports: None,
+            process: None,
+            hot_mounts: tokio::sync::Mutex::new(HotMounts::default()),
             _container_rootfs_disk: container_rootfs_disk,
             guest_rootfs_disk,
             #[cfg(target_os = "linux")]
             bind_mount,
         }
     }
+
+    /// Record the port mappings assigned when the VM was spawned.
+    pub(crate) fn with_published_ports(mut self, ports: Option<Vec<PortSpec>>) -> Self {
+        self.published_ports = ports;
+        self
+    }
+
+    /// Record the main container process the guest was initialized with.
+    pub(crate) fn with_process(mut self, process: Option<ContainerProcess>) -> Self {
+        self.process = process;
+        self
+    }
+}
+
+/// Volumes added to a running box with [`BoxImpl::mount`].
+#[derive(Default)]
+struct HotMounts {
+    next_id: u32,
+    mounts: HashMap<String, HotMount>,
+}
+
+struct HotMount {
+    // Unmounted from the shared directory on drop
+    bind_mount: BindMountHandle,
 }
 
 // ============================================================================
@@ -91,7 +160,9 @@ impl LiveState {
 
 /// Box implementation - created immediately, holds config and state.
 ///
-/// VM resources are held in LiveState and lazily initialized on first use.
+/// VM resources are held in LiveState and initialized by `start()` (which
+/// `exec()` calls for a box that is not running). Other operations attach
+/// to a running box via `running_live_state()` and never boot one.
 pub(crate) struct BoxImpl {
     // --- Always available ---
     pub(crate) config: BoxConfig,
@@ -100,6 +171,8 @@ pub(crate) struct BoxImpl {
     /// Cancellation token for this box (child of runtime's token).
     /// When cancelled (via stop() or runtime shutdown), all operations abort gracefully.
     pub(crate) shutdown_token: CancellationToken,
+    /// Slots for `BoxOptions::exec_limit`, if set.
+    exec_slots: Option<ExecSlots>,
 
     // --- Lazily initialized ---
     live: OnceCell<LiveState>,
@@ -112,7 +185,7 @@ impl BoxImpl {
 
     /// Create BoxImpl with config and state (LiveState not initialized yet).
     ///
-    /// LiveState will be lazily initialized when operations requiring it are called.
+    /// LiveState is initialized when the box is started.
     ///
     /// # Arguments
     /// * `config` - Box configuration
@@ -125,11 +198,13 @@ impl BoxImpl {
         runtime: SharedRuntimeImpl,
         shutdown_token: CancellationToken,
     ) -> Self {
+        let exec_slots = config.options.exec_limit.clone().map(ExecSlots::new);
         Self {
             config,
             state: RwLock::new(state),
             runtime,
             shutdown_token,
+            exec_slots,
             live: OnceCell::new(),
         }
     }
@@ -146,11 +221,83 @@ impl BoxImpl {
         self.config.container.id.as_str()
     }
 
+    /// Working directory of the main container's process, as last started.
+    fn default_working_dir(&self) -> Option<String> {
+        let state = self.state.read();
+        match &state.process {
+            Some(process) if !process.working_dir.is_empty() => Some(process.working_dir.clone()),
+            _ => self.config.options.working_dir.clone(),
+        }
+    }
+
+    /// Container ID of the sidecar `name`, or of the main container.
+    fn target_container_id(&self, name: Option<&str>) -> BoxliteResult<&str> {
+        let Some(name) = name else {
+            return Ok(self.container_id());
+        };
+        self.config
+            .sidecars
+            .get(name)
+            .map(|container| container.id.as_str())
+            .ok_or_else(|| {
+                BoxliteError::NotFound(format!(
+                    "box {} has no sidecar named '{}'",
+                    self.config.id, name
+                ))
+            })
+    }
+
     pub(crate) fn info(&self) -> BoxInfo {
         let state = self.state.read();
         BoxInfo::new(&self.config, &state)
     }
 
+    /// Record a heartbeat result in the box state and persist it.
+    ///
+    /// Returns the new health status on a transition.
+    pub(crate) fn record_heartbeat(
+        &self,
+        ok: bool,
+        failure_threshold: u32,
+    ) -> Option<HealthStatus> {
+        let mut state = self.state.write();
+        if state.status != BoxStatus::Running {
+            return None;
+        }
+        let transition = state.health.get_or_insert_with(BoxHealth::default).record(
+            ok,
+            chrono::Utc::now(),
+            failure_threshold,
+        );
+        if let Err(e) = self.runtime.box_manager.save_box(&self.config.id, &state) {
+            tracing::warn!(box_id = %self.config.id, "Failed to save box health: {}", e);
+        }
+        transition
+    }
+
+    /// Whether the shim reported the box's shared network daemon gone.
+    pub(crate) fn network_lost(&self) -> bool {
+        self.runtime
+            .layout
+            .box_layout(self.id().as_str(), self.config.options.isolate_mounts)
+            .is_ok_and(|layout| layout.network_lost_path().exists())
+    }
+
+    /// Record that a command of this box was killed out of memory.
+    pub(crate) fn record_oom_kill(&self, execution_id: &str) {
+        tracing::warn!(
+            box_id = %self.config.id,
+            execution_id,
+            event = "box_oom",
+            "Command killed by the guest kernel: out of memory"
+        );
+        let mut state = self.state.write();
+        state.oom_killed = true;
+        if let Err(e) = self.runtime.box_manager.save_box(&self.config.id, &state) {
+            tracing::warn!(box_id = %self.config.id, "Failed to save OOM kill: {}", e);
+        }
+    }
+
     // ========================================================================
     // OPERATIONS (require LiveState)
     // ========================================================================
@@ -191,7 +338,7 @@ impl BoxImpl {
         Ok(())
     }
 
-    pub(crate) async fn exec(&self, command: BoxCommand) -> BoxliteResult<Execution> {
+    pub(crate) async fn exec(self: &Arc<Self>, command: BoxCommand) -> BoxliteResult<Execution> {
         use boxlite_shared::constants::executor as executor_const;
 
         // Check if box is stopped before proceeding (via stop() or runtime shutdown)
@@ -201,7 +348,23 @@ impl BoxImpl {
             ));
         }
 
-        let live = self.live_state().await?;
+        let container_id = self
+            .target_container_id(command.container.as_deref())?
+            .to_string();
+
+        // exec() is, with start(), the only call that boots a box
+        self.start().await?;
+        let live = self.running_live_state().await?;
+        live.cache_reclaim.touch();
+
+        if let Some(health) = &self.state.read().health
+            && health.status == HealthStatus::Unhealthy
+        {
+            return Err(BoxliteError::Engine(format!(
+                "box {} is unhealthy: {} consecutive failed heartbeats",
+                self.config.id, health.failing_streak
+            )));
+        }
 
         // Inject container ID into environment if not already set
         let command = if command
@@ -214,19 +377,102 @@ impl BoxImpl {
         } else {
             command.env(
                 executor_const::ENV_VAR,
-                format!("{}={}", executor_const::CONTAINER_KEY, self.container_id()),
+                format!("{}={}", executor_const::CONTAINER_KEY, container_id),
             )
         };
 
-        // Set working directory from BoxOptions if not set in command
-        let command = match (&command.working_dir, &self.config.options.working_dir) {
-            (None, Some(dir)) => command.working_dir(dir),
+        // Like `docker exec`, commands default to the main container's
+        // working directory (image WORKDIR < BoxOptions::working_dir); a
+        // sidecar keeps its own image's.
+        let command = match (&command.working_dir, self.default_working_dir()) {
+            (None, Some(dir)) if command.container.is_none() => command.working_dir(dir),
             _ => command,
         };
 
-        let mut exec_interface = live.guest_session.execution().await?;
-        let result = exec_interface
-            .exec(command, self.shutdown_token.clone())
+        // Output compression defaults to the box's setting
+        let command = match command.compression {
+            Some(_) => command,
+            None => command.compression(self.config.options.exec_compression),
+        };
+
+        // Older agents ignore the flag and would leave the box running
+        if command.stop_box_on_exit {
+            live.guest_session
+                .guest()
+                .await?
+                .version()
+                .await?
+                .require_protocol(
+                    boxlite_shared::constants::protocol::STOP_BOX_ON_EXIT,
+                    "stopping the box when a command exits",
+                )?;
+        }
+        if command.user.is_some() || command.privileged {
+            live.guest_session
+                .guest()
+                .await?
+                .version()
+                .await?
+                .require_protocol(
+                    boxlite_shared::constants::protocol::EXEC_USER,
+                    "running a command as another user or privileged",
+                )?;
+        }
+        if command.fresh_rootfs {
+            live.guest_session
+                .guest()
+                .await?
+                .version()
+                .await?
+                .require_protocol(
+                    boxlite_shared::constants::protocol::FRESH_ROOTFS,
+                    "running a command on a fresh rootfs",
+                )?;
+        }
+
+        // Held until the command exits
+        let exec_slot = match &self.exec_slots {
+            Some(slots) => Some(slots.acquire(&self.shutdown_token).await?),
+            None => None,
+        };
+
+        // Recording files exist before the command runs, so it cannot start
+        // unrecorded
+        let recorder = match &command.recording {
+            Some(recording) => {
+                let dir = match &recording.dir {
+                    Some(dir) => dir.clone(),
+                    None => self
+                        .runtime
+                        .layout
+                        .box_layout(self.id().as_str(), self.config.options.isolate_mounts)?
+                        .recordings_dir()
+                        .join(ulid::Ulid::new().to_string()),
+                };
+                Some(Recorder::create(
+                    dir,
+                    recording,
+                    self.id().as_str(),
+                    &command,
+                )?)
+            }
+            None => None,
+        };
+
+        // Not retried: a connection that drops mid-request may already have
+        // spawned the process, and a retry would run the command again.
+        let result = live
+            .guest_session
+            .with_connection(|channel| {
+                let shutdown_token = self.shutdown_token.clone();
+                let output_counters = live.metrics.exec_output.clone();
+                async move {
+                    let mut exec_interface =
+                        ExecutionInterface::new(channel).with_output_counters(output_counters);
+                    let components = exec_interface.exec(command, shutdown_token).await?;
+                    Ok((exec_interface, components))
+                }
+            })
             .await;
 
         // Instrument metrics
@@ -244,7 +490,28 @@ impl BoxImpl {
                 .fetch_add(1, Ordering::Relaxed);
         }
 
-        let components = result?;
+        let result = result.map(|(exec_interface, components)| {
+            let components = match exec_slot {
+                Some(permit) => exec_limit::hold(permit, components),
+                None => components,
+            };
+            (exec_interface, oom::watch(Arc::downgrade(self), components))
+        });
+
+        let mut recording_dir = None;
+        let (exec_interface, components) = match (result, recorder) {
+            (Ok(started), None) => started,
+            (Ok((exec_interface, components)), Some(recorder)) => {
+                recording_dir = Some(recorder.dir().to_path_buf());
+                (exec_interface, recorder.attach(components))
+            }
+            (Err(e), recorder) => {
+                if let Some(recorder) = recorder {
+                    recorder.fail(&e);
+                }
+                return Err(e);
+            }
+        };
         Ok(Execution::new(
             components.execution_id,
             exec_interface,
@@ -252,7 +519,8 @@ impl BoxImpl {
             Some(ExecStdin::new(components.stdin_tx)),
             Some(ExecStdout::new(components.stdout_rx)),
             Some(ExecStderr::new(components.stderr_rx)),
-        ))
+        )
+        .with_recording_dir(recording_dir))
     }
 
     pub(crate) async fn metrics(&self) -> BoxliteResult<BoxMetrics> {
@@ -263,25 +531,168 @@ impl BoxImpl {
             ));
         }
 
-        let live = self.live_state().await?;
-        let handler = live
-            .handler
-            .lock()
-            .map_err(|e| BoxliteError::Internal(format!("handler lock poisoned: {}", e)))?;
-        let raw = handler.metrics()?;
+        let live = self.running_live_state().await?;
+        let raw = live.handler.read().metrics()?;
+        #[cfg(feature = "gvproxy-backend")]
+        self.refresh_network_usage(live).await;
+        let cache_reclaim_free_bytes = self
+            .runtime
+            .settings
+            .read()
+            .cache_reclaim_policy
+            .is_some()
+            .then(|| live.cache_reclaim.free_bytes());
 
         Ok(BoxMetrics::from_storage(
             &live.metrics,
             raw.cpu_percent,
             raw.memory_bytes,
-            None,
-            None,
-            None,
-            None,
+            cache_reclaim_free_bytes,
+            live.cache_reclaim.reclaimed_bytes_total(),
         ))
     }
 
+    /// Read the network backend's traffic counters into the box metrics.
+    ///
+    /// Keeps the last values read when the backend does not answer in time.
+    #[cfg(feature = "gvproxy-backend")]
+    async fn refresh_network_usage(&self, live: &LiveState) {
+        let Some(guest_ip) = self.config.ip_address() else {
+            return;
+        };
+        let Ok(layout) = self
+            .runtime
+            .layout
+            .box_layout(self.id().as_str(), self.config.options.isolate_mounts)
+        else {
+            return;
+        };
+        let forwarder = PortForwarder::new(layout.net_socket_path(), guest_ip);
+        match tokio::time::timeout(NETWORK_STATS_TIMEOUT, forwarder.stats()).await {
+            Ok(Ok(stats)) => live.metrics.record_network(NetworkUsage {
+                bytes_sent: stats.bytes_sent,
+                bytes_received: stats.bytes_received,
+                tcp_connections: stats.tcp.current_established,
+                tcp_errors: stats.tcp.failed_connection_attempts,
+            }),
+            Ok(Err(e)) => {
+                tracing::debug!(box_id = %self.config.id, error = %e, "Network counters unavailable")
+            }
+            Err(_) => {
+                tracing::debug!(box_id = %self.config.id, "Timed out reading network counters")
+            }
+        }
+    }
+
+    /// Recorded resource samples of the last `range`, oldest first.
+    ///
+    /// Reads the database, so it works whether or not the box is running.
+    pub(crate) fn metrics_history(&self, range: Duration) -> BoxliteResult<Vec<MetricsSample>> {
+        let range = chrono::Duration::from_std(range)
+            .map_err(|e| BoxliteError::InvalidArgument(format!("invalid range: {}", e)))?;
+        let since = chrono::Utc::now()
+            .checked_sub_signed(range)
+            .unwrap_or(chrono::DateTime::<chrono::Utc>::MIN_UTC);
+        self.runtime
+            .metrics_samples
+            .since(self.id().as_str(), since)
+    }
+
+    pub(crate) fn core_dumps(&self) -> BoxliteResult<Vec<CoreDump>> {
+        let layout = self
+            .runtime
+            .layout
+            .box_layout(self.id().as_str(), self.config.options.isolate_mounts)?;
+        core_dump::list(&layout.cores_dir())
+    }
+
+    pub(crate) fn connection_log(&self) -> BoxliteResult<Vec<ConnectionRecord>> {
+        let layout = self
+            .runtime
+            .layout
+            .box_layout(self.id().as_str(), self.config.options.isolate_mounts)?;
+        connection_log::read(&layout.connection_log_path())
+    }
+
+    /// Port forwarder of the running VM.
+    async fn port_forwarder(&self) -> BoxliteResult<PortForwarder> {
+        if self.shutdown_token.is_cancelled() {
+            return Err(BoxliteError::Stopped(
+                "Handle invalidated after stop(). Use runtime.get() to get a new handle.".into(),
+            ));
+        }
+        let Some(guest_ip) = self.config.ip_address() else {
+            return Err(BoxliteError::Unsupported(
+                "box has networking disabled".into(),
+            ));
+        };
+        // Ports can only change while the VM (and its network backend) runs
+        self.running_live_state().await?;
+
+        let layout = self
+            .runtime
+            .layout
+            .box_layout(self.id().as_str(), self.config.options.isolate_mounts)?;
+        Ok(PortForwarder::new(layout.net_socket_path(), guest_ip))
+    }
+
+    pub(crate) async fn ports(&self) -> BoxliteResult<Vec<PortSpec>> {
+        if self.state.read().status != BoxStatus::Running
+            || self.config.options.network == NetworkSpec::Disabled
+        {
+            return Ok(self.config.options.ports.clone());
+        }
+        self.port_forwarder().await?.list().await
+    }
+
+    pub(crate) async fn publish_port(&self, spec: PortSpec) -> BoxliteResult<PortSpec> {
+        let forwarder = self.port_forwarder().await?;
+        let owners = self.runtime.published_port_owners(None)?;
+        let spec = check_host_port(&spec, &owners, false)?;
+        let spec = assign_host_port(&spec, &[])?;
+        forwarder.expose(&spec).await?;
+        tracing::info!(
+            box_id = %self.config.id,
+            host_port = spec.host_port,
+            guest_port = spec.guest_port,
+            "Published port"
+        );
+
+        let mut state = self.state.write();
+        state.ports.push(spec.clone());
+        self.runtime.box_manager.save_box(&self.config.id, &state)?;
+        Ok(spec)
+    }
+
+    pub(crate) async fn unpublish_port(&self, spec: PortSpec) -> BoxliteResult<()> {
+        self.port_forwarder().await?.unexpose(&spec).await?;
+        tracing::info!(
+            box_id = %self.config.id,
+            host_port = spec.host_port.unwrap_or(spec.guest_port),
+            "Unpublished port"
+        );
+
+        let host_port = spec.host_port.unwrap_or(spec.guest_port);
+        let mut state = self.state.write();
+        state
+            .ports
+            .retain(|p| p.host_port != Some(host_port) || p.host_ip != spec.host_ip);
+        self.runtime.box_manager.save_box(&self.config.id, &state)?;
+        Ok(())
+    }
+
     pub(crate) async fn stop(&self) -> BoxliteResult<()> {
+        self.stop_vm(true).await
+    }
+
+    /// Stop the box without asking the guest to shut down, killing the VM.
+    ///
+    /// For boxes that did not stop in time; disks may lose unflushed writes.
+    pub(crate) async fn kill(&self) -> BoxliteResult<()> {
+        self.stop_vm(false).await
+    }
+
+    async fn stop_vm(&self, graceful: bool) -> BoxliteResult<()> {
         // Early exit if already stopped (idempotent, prevents double-counting)
         // Note: We check status, not shutdown_token, because the token may be cancelled
         // by runtime.shutdown() before stop() is called on each box.
@@ -289,20 +700,35 @@ impl BoxImpl {
             return Ok(());
         }
 
+        // A frozen VM cannot shut down gracefully
+        if self.state.read().status == BoxStatus::Paused {
+            self.set_paused(false)?;
+        }
+
         // Cancel the token - signals all in-flight operations to abort
         self.shutdown_token.cancel();
 
         // Only try to stop VM if LiveState exists
         if let Some(live) = self.live.get() {
-            // Gracefully shut down guest
-            if let Ok(mut guest) = live.guest_session.guest().await {
+            // Gracefully shut down guest, unless it stopped answering heartbeats
+            let unhealthy = self
+                .state
+                .read()
+                .health
+                .as_ref()
+                .is_some_and(|h| h.status == HealthStatus::Unhealthy);
+            if graceful
+                && !unhealthy
+                && let Ok(mut guest) = live.guest_session.guest().await
+            {
                 let _ = guest.shutdown().await;
             }
+            if !graceful && self.config.engine_kind.has_process() {
+                crate::util::kill_process(live.handler.read().pid());
+            }
 
             // Stop handler
-            if let Ok(mut handler) = live.handler.lock() {
-                handler.stop()?;
-            }
+            live.handler.write().stop()?;
         }
 
         // Clean up PID file (single source of truth)
@@ -331,6 +757,8 @@ impl BoxImpl {
             let mut state = self.state.write();
             state.set_status(BoxStatus::Stopped);
             state.set_pid(None);
+            state.ports.clear();
+            state.health = None;
 
             if was_persisted {
                 // Box was persisted - sync to DB
@@ -356,6 +784,7 @@ impl BoxImpl {
         // Invalidate cache so new handles get fresh BoxImpl
         self.runtime
             .invalidate_box_impl(self.id(), self.config.name.as_deref());
+        self.runtime.admission.release(self.id());
 
         tracing::info!("Stopped box {}", self.id());
 
@@ -372,6 +801,69 @@ impl BoxImpl {
         Ok(())
     }
 
+    /// Freeze the VM process; the box keeps its memory and connections.
+    pub(crate) async fn pause(&self) -> BoxliteResult<()> {
+        self.ensure_not_stopped()?;
+        self.set_paused(true)
+    }
+
+    /// Continue a VM frozen by [`pause`](Self::pause).
+    pub(crate) async fn resume(&self) -> BoxliteResult<()> {
+        self.ensure_not_stopped()?;
+        self.set_paused(false)
+    }
+
+    fn ensure_not_stopped(&self) -> BoxliteResult<()> {
+        if self.shutdown_token.is_cancelled() {
+            return Err(BoxliteError::Stopped(
+                "Handle invalidated after stop(). Use runtime.get() to get a new handle.".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Move between Running and Paused, signalling the VM process.
+    fn set_paused(&self, paused: bool) -> BoxliteResult<()> {
+        let (from, to, action) = if paused {
+            (BoxStatus::Running, BoxStatus::Paused, "pause")
+        } else {
+            (BoxStatus::Paused, BoxStatus::Running, "resume")
+        };
+        let mut state = self.state.write();
+        // Idempotent: already in the target state
+        if state.status == to {
+            return Ok(());
+        }
+        if state.status != from {
+            return Err(BoxliteError::InvalidState(format!(
+                "Cannot {} box in {} state",
+                action, state.status
+            )));
+        }
+
+        if self.config.engine_kind.has_process() {
+            let pid = state.pid.ok_or_else(|| {
+                BoxliteError::Internal(format!("box {} has no VM process", self.config.id))
+            })?;
+            let signalled = if paused {
+                crate::util::pause_process(pid)
+            } else {
+                crate::util::resume_process(pid)
+            };
+            if !signalled {
+                return Err(BoxliteError::Engine(format!(
+                    "Failed to {} box {} (pid {})",
+                    action, self.config.id, pid
+                )));
+            }
+        }
+
+        state.set_status(to);
+        self.runtime.box_manager.save_box(&self.config.id, &state)?;
+        tracing::info!(box_id = %self.config.id, "Box {}d", action);
+        Ok(())
+    }
+
     // ========================================================================
     // FILE COPY
     // ========================================================================
@@ -397,8 +889,8 @@ impl BoxImpl {
             ));
         }
 
-        // Ensure box is running
-        let live = self.live_state().await?;
+        let live = self.running_live_state().await?;
+        live.cache_reclaim.touch();
 
         if host_src.is_dir() {
             opts.validate_for_dir()?;
@@ -417,22 +909,357 @@ impl BoxImpl {
             .join(format!("cp-in-{}.tar", self.config.id.as_str()));
 
         build_tar_from_host(host_src, &temp_tar, &opts)?;
+        self.upload_tar(live, &temp_tar, container_dst, opts.overwrite)
+            .await
+    }
 
-        let mut files_iface = live.guest_session.files().await?;
-        files_iface
-            .upload_tar(
-                &temp_tar,
-                container_dst,
-                Some(self.container_id()),
-                true,
-                opts.overwrite,
-            )
+    /// Copy `paths` (relative to `host_root`) into `container_dst`, keeping
+    /// their relative layout, in a single upload.
+    ///
+    /// Directories are created but not descended into, and symlinks are
+    /// copied as links, so callers list exactly the entries to transfer.
+    pub(crate) async fn copy_paths_into(
+        &self,
+        host_root: &Path,
+        paths: &[PathBuf],
+        container_dst: &str,
+    ) -> BoxliteResult<()> {
+        if self.shutdown_token.is_cancelled() {
+            return Err(BoxliteError::Stopped(
+                "Handle invalidated after stop(). Use runtime.get() to get a new handle.".into(),
+            ));
+        }
+
+        let live = self.running_live_state().await?;
+        live.cache_reclaim.touch();
+
+        if container_dst.is_empty() {
+            return Err(BoxliteError::Config(
+                "destination path cannot be empty".into(),
+            ));
+        }
+        if let Some(path) = paths
+            .iter()
+            .find(|p| p.is_absolute() || p.components().any(|c| c == Component::ParentDir))
+        {
+            return Err(BoxliteError::InvalidArgument(format!(
+                "path must be relative to the copy root: {}",
+                path.display()
+            )));
+        }
+        if paths.is_empty() {
+            return Ok(());
+        }
+
+        let temp_tar = self.runtime.layout.temp_dir().join(format!(
+            "sync-in-{}-{}.tar",
+            self.config.id.as_str(),
+            uuid::Uuid::new_v4()
+        ));
+
+        build_tar_from_paths(host_root, paths, &temp_tar)?;
+        self.upload_tar(live, &temp_tar, container_dst, true).await
+    }
+
+    /// Upload and extract a tar built on the host, then remove it.
+    async fn upload_tar(
+        &self,
+        live: &LiveState,
+        temp_tar: &Path,
+        container_dst: &str,
+        overwrite: bool,
+    ) -> BoxliteResult<()> {
+        let result = live
+            .guest_session
+            .with_connection(|channel| async move {
+                FilesInterface::new(channel)
+                    .upload_tar(
+                        temp_tar,
+                        container_dst,
+                        Some(self.container_id()),
+                        true,
+                        overwrite,
+                    )
+                    .await
+            })
+            .await;
+
+        let _ = tokio::fs::remove_file(temp_tar).await;
+        result
+    }
+
+    /// Mount a host directory into the running container.
+    ///
+    /// The directory is bind-mounted under the box's shared directory, which
+    /// the guest already sees through the catch-all "shared" virtiofs tag,
+    /// then the guest binds it to `guest_path` inside the container. libkrun
+    /// cannot hot-plug new virtiofs devices, so platforms without bind mounts
+    /// are unsupported.
+    pub(crate) async fn mount(
+        &self,
+        host_path: &Path,
+        guest_path: &str,
+        read_only: bool,
+    ) -> BoxliteResult<()> {
+        if self.shutdown_token.is_cancelled() {
+            return Err(BoxliteError::Stopped(
+                "Handle invalidated after stop(). Use runtime.get() to get a new handle.".into(),
+            ));
+        }
+        validate_hot_mount(host_path, guest_path)?;
+
+        let live = self.running_live_state().await?;
+        live.cache_reclaim.touch();
+
+        let mut hot = live.hot_mounts.lock().await;
+        if hot.mounts.contains_key(guest_path) {
+            return Err(BoxliteError::AlreadyExists(format!(
+                "{} is already mounted in box {}",
+                guest_path, self.config.id
+            )));
+        }
+
+        let volume_name = format!("hot{}", hot.next_id);
+        hot.next_id += 1;
+        let bind_mount = self.share_hot_volume(host_path, &volume_name, read_only)?;
+
+        let mount = ContainerMount {
+            volume_name,
+            destination: guest_path.to_string(),
+            read_only,
+            sub_path: None,
+            chown: false,
+        };
+        live.guest_session
+            .with_connection(|channel| async move {
+                ContainerInterface::new(channel)
+                    .mount(self.container_id(), mount)
+                    .await
+            })
             .await?;
 
-        let _ = tokio::fs::remove_file(&temp_tar).await;
+        tracing::info!(
+            box_id = %self.config.id,
+            host_path = %host_path.display(),
+            guest_path,
+            read_only,
+            "Mounted volume"
+        );
+        hot.mounts
+            .insert(guest_path.to_string(), HotMount { bind_mount });
+        Ok(())
+    }
+
+    /// Remove a volume added with [`mount`](Self::mount).
+    pub(crate) async fn unmount(&self, guest_path: &str) -> BoxliteResult<()> {
+        if self.shutdown_token.is_cancelled() {
+            return Err(BoxliteError::Stopped(
+                "Handle invalidated after stop(). Use runtime.get() to get a new handle.".into(),
+            ));
+        }
+        let Some(live) = self.live.get() else {
+            return Err(BoxliteError::InvalidState(format!(
+                "box {} is not running",
+                self.config.id
+            )));
+        };
+
+        let mut hot = live.hot_mounts.lock().await;
+        if !hot.mounts.contains_key(guest_path) {
+            return Err(BoxliteError::NotFound(format!(
+                "no volume mounted at {} in box {}",
+                guest_path, self.config.id
+            )));
+        }
+
+        live.guest_session
+            .with_reconnect(|channel| async move {
+                ContainerInterface::new(channel)
+                    .unmount(self.container_id(), guest_path)
+                    .await
+            })
+            .await?;
+
+        if let Some(hot_mount) = hot.mounts.remove(guest_path) {
+            hot_mount.bind_mount.unmount()?;
+        }
+        tracing::info!(box_id = %self.config.id, guest_path, "Unmounted volume");
         Ok(())
     }
 
+    pub(crate) async fn processes(&self) -> BoxliteResult<Vec<ProcessInfo>> {
+        let live = self.running_live_state().await?;
+        live.guest_session
+            .with_reconnect(|channel| async move {
+                ContainerInterface::new(channel)
+                    .processes(self.container_id())
+                    .await
+            })
+            .await
+    }
+
+    pub(crate) async fn listening_ports(&self) -> BoxliteResult<Vec<ListeningPort>> {
+        let live = self.running_live_state().await?;
+        live.guest_session
+            .with_reconnect(|channel| async move {
+                ContainerInterface::new(channel)
+                    .listening_ports(self.container_id())
+                    .await
+            })
+            .await
+    }
+
+    /// Block until `condition` holds, or fail after `timeout`.
+    ///
+    /// `Healthy` and `Port` fail with `InvalidState` once the box is not
+    /// running; `Stopped` returns as soon as it isn't.
+    pub(crate) async fn wait_for(
+        &self,
+        condition: WaitCondition,
+        timeout: Option<Duration>,
+    ) -> BoxliteResult<()> {
+        let Some(timeout) = timeout else {
+            return self.poll_condition(condition).await;
+        };
+        tokio::time::timeout(timeout, self.poll_condition(condition))
+            .await
+            .map_err(|_| {
+                BoxliteError::GuestTimeout(format!(
+                    "box {} did not become {} within {}s",
+                    self.config.id,
+                    condition,
+                    timeout.as_secs_f64()
+                ))
+            })?
+    }
+
+    async fn poll_condition(&self, condition: WaitCondition) -> BoxliteResult<()> {
+        loop {
+            let met = match condition {
+                WaitCondition::Stopped => self.vm_exited(),
+                WaitCondition::Healthy => {
+                    let live = self.running_live_state().await?;
+                    let ping = live.guest_session.with_reconnect(|channel| async move {
+                        GuestInterface::new(channel).ping().await
+                    });
+                    matches!(
+                        tokio::time::timeout(wait::PING_TIMEOUT, ping).await,
+                        Ok(Ok(()))
+                    )
+                }
+                WaitCondition::Port(port) => self
+                    .listening_ports()
+                    .await?
+                    .iter()
+                    .any(|listening| listening.port == port),
+            };
+            if met {
+                return Ok(());
+            }
+            if condition != WaitCondition::Stopped && self.vm_exited() {
+                return Err(BoxliteError::InvalidState(format!(
+                    "box {} stopped before it became {}",
+                    self.config.id, condition
+                )));
+            }
+            tokio::time::sleep(wait::POLL_INTERVAL).await;
+        }
+    }
+
+    /// Whether the VM is gone, including when another process stopped it.
+    fn vm_exited(&self) -> bool {
+        if self.shutdown_token.is_cancelled() {
+            return true;
+        }
+        if let Some(live) = self.live.get() {
+            return !live.handler.read().is_running();
+        }
+        let state = self.state.read();
+        !state.status.is_active() || !state.pid.is_some_and(crate::util::is_process_alive)
+    }
+
+    /// Live state of a running box, attaching to it if another process
+    /// started it. Unlike [`live_state`](Self::live_state), never starts
+    /// the box.
+    async fn running_live_state(&self) -> BoxliteResult<&LiveState> {
+        if self.shutdown_token.is_cancelled() {
+            return Err(BoxliteError::Stopped(
+                "Handle invalidated after stop(). Use runtime.get() to get a new handle.".into(),
+            ));
+        }
+        if self.state.read().status == BoxStatus::Paused {
+            return Err(BoxliteError::InvalidState(format!(
+                "box {} is paused",
+                self.config.id
+            )));
+        }
+        if let Some(live) = self.live.get() {
+            return Ok(live);
+        }
+        if self.state.read().status != BoxStatus::Running {
+            return Err(BoxliteError::InvalidState(format!(
+                "box {} is not running",
+                self.config.id
+            )));
+        }
+        self.live_state().await
+    }
+
+    /// Expose `host_path` as volume `volume_name` in the shared directory.
+    #[cfg(target_os = "linux")]
+    fn share_hot_volume(
+        &self,
+        host_path: &Path,
+        volume_name: &str,
+        read_only: bool,
+    ) -> BoxliteResult<BindMountHandle> {
+        use crate::fs::{BindMountConfig, create_bind_mount};
+        use boxlite_shared::layout::SharedGuestLayout;
+
+        let layout = self
+            .runtime
+            .layout
+            .box_layout(self.id().as_str(), self.config.options.isolate_mounts)?;
+
+        // Create the mount point through the writable side of the share, then
+        // mount over the guest-visible path so it does not depend on mount
+        // propagation between mounts/ and shared/.
+        let mount_point = layout
+            .shared_layout()
+            .container(self.container_id())
+            .volume_dir(volume_name);
+        std::fs::create_dir_all(&mount_point).map_err(|e| {
+            BoxliteError::Storage(format!(
+                "Failed to create mount point {}: {}",
+                mount_point.display(),
+                e
+            ))
+        })?;
+        let target = SharedGuestLayout::new(layout.shared_dir())
+            .container(self.container_id())
+            .volume_dir(volume_name);
+
+        let mut config = BindMountConfig::new(host_path, &target);
+        if read_only {
+            config = config.read_only();
+        }
+        create_bind_mount(&config)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn share_hot_volume(
+        &self,
+        _host_path: &Path,
+        _volume_name: &str,
+        _read_only: bool,
+    ) -> BoxliteResult<BindMountHandle> {
+        Err(BoxliteError::Unsupported(
+            "Mounting volumes into a running box requires Linux: libkrun cannot hot-plug \
+             virtiofs shares, and the shared directory needs a bind mount"
+                .into(),
+        ))
+    }
+
     pub(crate) async fn copy_out(
         &self,
         container_src: &str,
@@ -446,8 +1273,8 @@ impl BoxImpl {
             ));
         }
 
-        // Ensure box is running
-        let live = self.live_state().await?;
+        let live = self.running_live_state().await?;
+        live.cache_reclaim.touch();
 
         if container_src.is_empty() {
             return Err(BoxliteError::Config("source path cannot be empty".into()));
@@ -459,15 +1286,22 @@ impl BoxImpl {
             .temp_dir()
             .join(format!("cp-out-{}.tar", self.config.id.as_str()));
 
-        let mut files_iface = live.guest_session.files().await?;
-        files_iface
-            .download_tar(
-                container_src,
-                Some(self.container_id()),
-                opts.include_parent,
-                opts.follow_symlinks,
-                &temp_tar,
-            )
+        live.guest_session
+            .with_reconnect(|channel| {
+                let temp_tar = &temp_tar;
+                let (include_parent, follow_symlinks) = (opts.include_parent, opts.follow_symlinks);
+                async move {
+                    FilesInterface::new(channel)
+                        .download_tar(
+                            container_src,
+                            Some(self.container_id()),
+                            include_parent,
+                            follow_symlinks,
+                            temp_tar,
+                        )
+                        .await
+                }
+            })
             .await?;
 
         extract_tar_to_host(&temp_tar, host_dst, opts.overwrite)?;
@@ -475,13 +1309,48 @@ impl BoxImpl {
         Ok(())
     }
 
+    pub(crate) async fn watch(&self, path: &str, recursive: bool) -> BoxliteResult<FsWatch> {
+        if self.shutdown_token.is_cancelled() {
+            return Err(BoxliteError::Stopped(
+                "Handle invalidated after stop(). Use runtime.get() to get a new handle.".into(),
+            ));
+        }
+
+        let live = self.running_live_state().await?;
+        live.cache_reclaim.touch();
+
+        if path.is_empty() {
+            return Err(BoxliteError::Config("watch path cannot be empty".into()));
+        }
+
+        let stream = live
+            .guest_session
+            .with_reconnect(|channel| async move {
+                FilesInterface::new(channel)
+                    .watch(path, Some(self.container_id()), recursive)
+                    .await
+            })
+            .await?;
+
+        // End the stream cleanly on stop rather than with a transport error
+        let events = stream
+            .filter_map(|event| async move { event.map(FsEvent::from_proto).transpose() })
+            .take_until(self.shutdown_token.clone().cancelled_owned());
+        Ok(FsWatch::new(events.boxed()))
+    }
+
     // ========================================================================
     // LIVE STATE INITIALIZATION (internal)
     // ========================================================================
 
     /// Get LiveState, lazily initializing it if needed.
     async fn live_state(&self) -> BoxliteResult<&LiveState> {
-        self.live.get_or_try_init(|| self.init_live_state()).await
+        let result = self.live.get_or_try_init(|| self.init_live_state()).await;
+        // A failed start leaves the box inactive; give back its reservation.
+        if result.is_err() && !self.state.read().status.is_active() {
+            self.runtime.admission.release(self.id());
+        }
+        result
     }
 
     /// Initialize LiveState via BoxBuilder.
@@ -500,6 +1369,7 @@ impl BoxImpl {
 
         let state = self.state.read().clone();
         let is_first_start = state.status == BoxStatus::Configured;
+        let is_reattach = state.status == BoxStatus::Running;
 
         // Retrieve the lock (allocated in create())
         let lock_id = state.lock_id.ok_or_else(|| {
@@ -508,6 +1378,15 @@ impl BoxImpl {
                 self.config.id, state.status
             ))
         })?;
+
+        // Starting a box reserves capacity; attaching to a running one
+        // reuses the reservation taken when it started.
+        if !is_reattach {
+            self.runtime
+                .admit_box(&self.config.id, &self.config.options)
+                .await?;
+        }
+
         let locker = self.runtime.lock_manager.retrieve(lock_id)?;
         tracing::debug!(
             box_id = %self.config.id,
@@ -548,6 +1427,27 @@ impl BoxImpl {
             let mut state = self.state.write();
             state.set_pid(Some(pid));
             state.set_status(BoxStatus::Running);
+            if let Some(ports) = &live_state.published_ports {
+                state.ports = ports.clone();
+            }
+            // Reattach only reconnects; keep the timings and OOM record of
+            // the actual start.
+            if !is_reattach {
+                state.init_stages = live_state.metrics.init_stages.clone();
+                state.boot_phases = live_state.metrics.boot_phases.clone();
+                state.oom_killed = false;
+                if let Some(process) = &live_state.process {
+                    state.process = Some(process.clone());
+                }
+            }
+            // The guest just answered init; a reattached box keeps its record.
+            let heartbeat = self.runtime.settings.read().heartbeat_policy.is_some();
+            if heartbeat && (!is_reattach || state.health.is_none()) {
+                state.health = Some(BoxHealth {
+                    last_heartbeat: Some(chrono::Utc::now()),
+                    ..BoxHealth::default()
+                });
+            }
 
             // Save to DB (cache for queries and recovery)
             self.runtime.box_manager.save_box(&self.config.id, &state)?;
@@ -559,9 +1459,90 @@ impl BoxImpl {
             );
         }
 
+        // A failing init command fails the start like any init step.
+        if is_first_start && !self.config.options.init_commands.is_empty() {
+            let recordings_dir = self
+                .runtime
+                .layout
+                .box_layout(self.id().as_str(), self.config.options.isolate_mounts)?
+                .recordings_dir();
+            init_commands::run(
+                &live_state.guest_session,
+                self.id().as_str(),
+                self.container_id(),
+                &self.config.options.init_commands,
+                self.default_working_dir().as_deref(),
+                &recordings_dir,
+                self.shutdown_token.clone(),
+            )
+            .await?;
+        }
+
         // All operations succeeded - disarm the cleanup guard
         cleanup_guard.disarm();
 
+        let (cache_reclaim_policy, heartbeat_policy, metrics_history_policy) = {
+            let settings = self.runtime.settings.read();
+            (
+                settings.cache_reclaim_policy.clone(),
+                settings.heartbeat_policy.clone(),
+                settings.metrics_history_policy.clone(),
+            )
+        };
+
+        if let Some(policy) = cache_reclaim_policy {
+            cache_reclaim::spawn_monitor(
+                self.config.id.clone(),
+                Arc::clone(&live_state.cache_reclaim),
+                live_state.guest_session.clone(),
+                policy,
+                self.shutdown_token.clone(),
+            );
+        }
+
+        if let Some(policy) = heartbeat_policy {
+            match self.runtime.cached_box_impl(self.id()) {
+                Some(box_impl) => heartbeat::spawn_monitor(
+                    Arc::downgrade(&box_impl),
+                    live_state.guest_session.clone(),
+                    policy,
+                    self.shutdown_token.clone(),
+                ),
+                None => tracing::debug!(
+                    box_id = %self.config.id,
+                    "Box handle not cached, skipping heartbeat monitor"
+                ),
+            }
+        }
+
+        if let Some(policy) = metrics_history_policy {
+            match self.runtime.cached_box_impl(self.id()) {
+                Some(box_impl) => metrics_sampler::spawn_sampler(
+                    Arc::downgrade(&box_impl),
+                    policy,
+                    self.shutdown_token.clone(),
+                ),
+                None => tracing::debug!(
+                    box_id = %self.config.id,
+                    "Box handle not cached, skipping metrics sampler"
+                ),
+            }
+        }
+
+        // sshd does not survive a reboot; a reattached box is already provisioned.
+        if let Some(ssh) = &self.config.options.ssh
+            && !is_reattach
+            && let Err(e) = ssh::provision(
+                &live_state.guest_session,
+                self.container_id(),
+                ssh,
+                self.shutdown_token.clone(),
+            )
+            .await
+        {
+            tracing::warn!(box_id = %self.config.id, "SSH provisioning failed: {}", e);
+        }
+
         tracing::info!(
             box_id = %self.config.id,
             "Box started successfully (first_start={})",
@@ -573,6 +1554,33 @@ impl BoxImpl {
     }
 }
 
+/// Check the arguments of [`BoxImpl::mount`] before touching the VM.
+fn validate_hot_mount(host_path: &Path, guest_path: &str) -> BoxliteResult<()> {
+    if !host_path.is_dir() {
+        return Err(BoxliteError::InvalidArgument(format!(
+            "host path {} is not a directory",
+            host_path.display()
+        )));
+    }
+    let guest = Path::new(guest_path);
+    if !guest.is_absolute() || guest == Path::new("/") {
+        return Err(BoxliteError::InvalidArgument(format!(
+            "guest path must be an absolute path other than '/': {}",
+            guest_path
+        )));
+    }
+    if guest
+        .components()
+        .any(|c| matches!(c, std::path::Component::ParentDir))
+    {
+        return Err(BoxliteError::InvalidArgument(format!(
+            "guest path must not contain '..': {}",
+            guest_path
+        )));
+    }
+    Ok(())
+}
+
 fn build_tar_from_host(
     src: &std::path::Path,
     tar_path: &std::path::Path,
@@ -620,6 +1628,40 @@ fn build_tar_from_host(
     })
 }
 
+fn build_tar_from_paths(
+    root: &std::path::Path,
+    paths: &[PathBuf],
+    tar_path: &std::path::Path,
+) -> BoxliteResult<()> {
+    tokio::task::block_in_place(|| {
+        let tar_file = std::fs::File::create(tar_path).map_err(|e| {
+            BoxliteError::Storage(format!(
+                "failed to create tar {}: {}",
+                tar_path.display(),
+                e
+            ))
+        })?;
+        let mut builder = tar::Builder::new(tar_file);
+        builder.follow_symlinks(false);
+
+        for path in paths {
+            builder
+                .append_path_with_name(root.join(path), path)
+                .map_err(|e| {
+                    BoxliteError::Storage(format!(
+                        "failed to archive {}: {}",
+                        root.join(path).display(),
+                        e
+                    ))
+                })?;
+        }
+
+        builder
+            .finish()
+            .map_err(|e| BoxliteError::Storage(format!("failed to finish tar: {}", e)))
+    })
+}
+
 fn extract_tar_to_host(
     tar_path: &std::path::Path,
     dest: &std::path::Path,
@@ -647,8 +1689,19 @@ fn extract_tar_to_host(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::vmm::mock::{MockScript, test_runtime};
     use tempfile::TempDir;
 
+    #[test]
+    fn validate_hot_mount_rejects_bad_paths() {
+        let tmp = TempDir::new().unwrap();
+        assert!(validate_hot_mount(tmp.path(), "/data").is_ok());
+        assert!(validate_hot_mount(&tmp.path().join("missing"), "/data").is_err());
+        assert!(validate_hot_mount(tmp.path(), "data").is_err());
+        assert!(validate_hot_mount(tmp.path(), "/").is_err());
+        assert!(validate_hot_mount(tmp.path(), "/data/../etc").is_err());
+    }
+
     #[test]
     fn tar_roundtrip_file() {
         // Multi-threaded runtime required for block_in_place
@@ -681,4 +1734,161 @@ mod tests {
             assert_eq!(data, "hello");
         });
     }
+
+    #[tokio::test]
+    async fn test_detached_auto_remove() {
+        use crate::{BoxCommand, BoxOptions, BoxliteOptions};
+
+        let (_home, runtime) = test_runtime(BoxliteOptions::default(), MockScript::new());
+        let litebox = runtime
+            .create(
+                BoxOptions {
+                    detach: true,
+                    auto_remove: true,
+                    ..Default::default()
+                },
+                Some("detached".to_string()),
+            )
+            .await
+            .unwrap();
+        let result = litebox
+            .exec(BoxCommand::new("true").stop_box_on_exit(true))
+            .await
+            .unwrap()
+            .wait()
+            .await
+            .unwrap();
+        assert!(result.success());
+
+        litebox.stop().await.unwrap();
+        assert!(!runtime.exists("detached").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_exec_targets_sidecar() {
+        use crate::{BoxCommand, BoxOptions, BoxliteOptions, SidecarSpec};
+
+        let (_home, runtime) = test_runtime(BoxliteOptions::default(), MockScript::new());
+        let litebox = runtime
+            .create(
+                BoxOptions {
+                    sidecars: vec![SidecarSpec::new("cache", "redis:7")],
+                    ..Default::default()
+                },
+                None,
+            )
+            .await
+            .unwrap();
+
+        let result = litebox
+            .exec(BoxCommand::new("true").container("cache"))
+            .await
+            .unwrap()
+            .wait()
+            .await
+            .unwrap();
+        assert!(result.success());
+
+        let err = litebox
+            .exec(BoxCommand::new("true").container("db"))
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(err, BoxliteError::NotFound(_)), "{}", err);
+        litebox.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle_create_is_lazy() {
+        use crate::{BoxCommand, BoxOptions, BoxStatus, BoxliteOptions, CopyOptions};
+
+        let (home, runtime) = test_runtime(BoxliteOptions::default(), MockScript::new());
+        // Kept on stop, so it can be looked up and started again
+        let options = BoxOptions {
+            auto_remove: false,
+            ..Default::default()
+        };
+        let litebox = runtime
+            .create(options, Some("lazy".to_string()))
+            .await
+            .unwrap();
+
+        // create never spawns a VM
+        let info = litebox.info();
+        assert_eq!(info.status, BoxStatus::Configured);
+        assert!(info.pid.is_none());
+
+        // Only start() and exec() boot a box
+        let host_file = home.path().join("file");
+        std::fs::write(&host_file, "x").unwrap();
+        assert!(matches!(
+            litebox.metrics().await,
+            Err(BoxliteError::InvalidState(_))
+        ));
+        assert!(matches!(
+            litebox.watch("/", false).await,
+            Err(BoxliteError::InvalidState(_))
+        ));
+        assert!(matches!(
+            litebox
+                .copy_into(&host_file, "/file", CopyOptions::default())
+                .await,
+            Err(BoxliteError::InvalidState(_))
+        ));
+        assert_eq!(litebox.info().status, BoxStatus::Configured);
+
+        litebox.start().await.unwrap();
+        assert_eq!(litebox.info().status, BoxStatus::Running);
+        litebox.metrics().await.unwrap();
+        litebox.stop().await.unwrap();
+        assert_eq!(litebox.info().status, BoxStatus::Stopped);
+
+        // exec restarts a stopped box
+        let litebox = runtime.get("lazy").await.unwrap().unwrap();
+        assert_eq!(litebox.info().status, BoxStatus::Stopped);
+        let mut execution = litebox.exec(BoxCommand::new("true")).await.unwrap();
+        execution.wait().await.unwrap();
+        assert_eq!(litebox.info().status, BoxStatus::Running);
+    }
+
+    #[tokio::test]
+    async fn test_pause_resume_restart() {
+        use crate::{BoxCommand, BoxOptions, BoxStatus, BoxliteOptions};
+
+        let (_home, runtime) = test_runtime(BoxliteOptions::default(), MockScript::new());
+        // auto_remove boxes cannot be restarted
+        let options = BoxOptions {
+            auto_remove: false,
+            ..Default::default()
+        };
+        let litebox = runtime
+            .create(options, Some("frozen".to_string()))
+            .await
+            .unwrap();
+        assert!(matches!(
+            litebox.pause().await,
+            Err(BoxliteError::InvalidState(_))
+        ));
+
+        litebox.start().await.unwrap();
+        litebox.pause().await.unwrap();
+        litebox.pause().await.unwrap();
+        assert_eq!(litebox.info().status, BoxStatus::Paused);
+        let err = litebox.exec(BoxCommand::new("true")).await.err().unwrap();
+        assert!(matches!(err, BoxliteError::InvalidState(_)), "{}", err);
+
+        litebox.resume().await.unwrap();
+        assert_eq!(litebox.info().status, BoxStatus::Running);
+        let mut execution = litebox.exec(BoxCommand::new("true")).await.unwrap();
+        execution.wait().await.unwrap();
+
+        // A paused box can be stopped and restarted
+        litebox.pause().await.unwrap();
+        let restarted = litebox.restart().await.unwrap();
+        assert_eq!(restarted.info().status, BoxStatus::Running);
+        assert!(matches!(
+            litebox.start().await,
+            Err(BoxliteError::Stopped(_))
+        ));
+    }
 }
@@ -0,0 +1,163 @@
+//! Guest cache reclaim on idle boxes.
+//!
+//! Once a box has been idle for `CacheReclaimPolicy::idle_threshold_secs`, the
+//! monitor asks the guest agent to drop its caches and compact free memory.
+//! Any exec or file copy marks the box active again.
+//!
+//! This frees memory inside the guest only. The VM keeps its guest RAM
+//! mapped; libkrun has no balloon device to hand pages back to the host.
+//! The numbers kept here are guest MemFree readings.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use tokio_util::sync::CancellationToken;
+
+use crate::BoxID;
+use crate::portal::GuestSession;
+use crate::portal::interfaces::MemoryReclaim;
+use crate::runtime::options::CacheReclaimPolicy;
+
+/// Per-box reclaim state, shared between the box and its monitor task.
+#[derive(Debug)]
+pub(crate) struct CacheReclaim {
+    last_activity: Mutex<Instant>,
+    /// Set after a reclaim; cleared by activity.
+    reclaimed: AtomicBool,
+    /// Guest MemFree after the last reclaim (bytes).
+    free_bytes: AtomicU64,
+    /// Total guest MemFree gained over the box lifetime (bytes).
+    reclaimed_bytes: AtomicU64,
+}
+
+impl Default for CacheReclaim {
+    fn default() -> Self {
+        Self {
+            last_activity: Mutex::new(Instant::now()),
+            reclaimed: AtomicBool::new(false),
+            free_bytes: AtomicU64::new(0),
+            reclaimed_bytes: AtomicU64::new(0),
+        }
+    }
+}
+
+impl CacheReclaim {
+    /// Mark the box active. The guest uses its free memory again, so the
+    /// last reclaim no longer says anything.
+    pub(crate) fn touch(&self) {
+        *self.last_activity.lock() = Instant::now();
+        self.reclaimed.store(false, Ordering::Relaxed);
+        self.free_bytes.store(0, Ordering::Relaxed);
+    }
+
+    /// Guest MemFree after the last reclaim, 0 once active again (bytes).
+    pub(crate) fn free_bytes(&self) -> u64 {
+        self.free_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Total guest MemFree gained over the box lifetime (bytes).
+    pub(crate) fn reclaimed_bytes_total(&self) -> u64 {
+        self.reclaimed_bytes.load(Ordering::Relaxed)
+    }
+
+    fn should_reclaim(&self, idle_threshold: Duration) -> bool {
+        !self.reclaimed.load(Ordering::Relaxed)
+            && self.last_activity.lock().elapsed() >= idle_threshold
+    }
+
+    fn record_reclaim(&self, reclaim: MemoryReclaim) {
+        self.reclaimed.store(true, Ordering::Relaxed);
+        self.free_bytes.store(reclaim.free_bytes, Ordering::Relaxed);
+        self.reclaimed_bytes
+            .fetch_add(reclaim.reclaimed_bytes, Ordering::Relaxed);
+    }
+}
+
+/// Spawn the cache reclaim task for a running box.
+///
+/// The task exits when `shutdown_token` is cancelled (box stop or runtime shutdown).
+pub(crate) fn spawn_monitor(
+    box_id: BoxID,
+    state: Arc<CacheReclaim>,
+    guest_session: GuestSession,
+    policy: CacheReclaimPolicy,
+    shutdown_token: CancellationToken,
+) {
+    let interval = Duration::from_secs(policy.check_interval_secs.max(1));
+    let idle_threshold = Duration::from_secs(policy.idle_threshold_secs);
+
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = shutdown_token.cancelled() => break,
+                _ = tokio::time::sleep(interval) => {}
+            }
+
+            if !state.should_reclaim(idle_threshold) {
+                continue;
+            }
+
+            let result = match guest_session.guest().await {
+                Ok(mut guest) => guest.reclaim_memory().await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(reclaim) => {
+                    tracing::debug!(
+                        box_id = %box_id,
+                        reclaimed_bytes = reclaim.reclaimed_bytes,
+                        free_bytes = reclaim.free_bytes,
+                        "Reclaimed guest caches on idle box"
+                    );
+                    state.record_reclaim(reclaim);
+                }
+                Err(e) => {
+                    tracing::warn!(box_id = %box_id, error = %e, "Guest cache reclaim failed");
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reclaim(reclaimed_bytes: u64, free_bytes: u64) -> MemoryReclaim {
+        MemoryReclaim {
+            reclaimed_bytes,
+            free_bytes,
+        }
+    }
+
+    #[test]
+    fn test_reclaims_once_until_activity() {
+        let state = CacheReclaim::default();
+        assert!(state.should_reclaim(Duration::ZERO));
+
+        state.record_reclaim(reclaim(100, 1000));
+        assert!(!state.should_reclaim(Duration::ZERO));
+        assert_eq!(state.free_bytes(), 1000);
+
+        state.touch();
+        assert!(state.should_reclaim(Duration::ZERO));
+        assert_eq!(state.free_bytes(), 0);
+    }
+
+    #[test]
+    fn test_not_idle_before_threshold() {
+        let state = CacheReclaim::default();
+        assert!(!state.should_reclaim(Duration::from_secs(3600)));
+    }
+
+    #[test]
+    fn test_reclaimed_bytes_accumulate() {
+        let state = CacheReclaim::default();
+        state.record_reclaim(reclaim(100, 1000));
+        state.touch();
+        state.record_reclaim(reclaim(50, 900));
+        assert_eq!(state.reclaimed_bytes_total(), 150);
+    }
+}
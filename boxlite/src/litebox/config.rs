@@ -3,12 +3,14 @@ use crate::runtime::types::ContainerID;
 use boxlite_shared::Transport;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::net::Ipv4Addr;
 use std::path::PathBuf;
 
 /// Container runtime configuration.
 ///
 /// Holds the container's identity.
-/// Owned by BoxConfig since each box runs exactly one container.
+/// Owned by BoxConfig, for the main container and each sidecar.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContainerRuntimeConfig {
     /// Container ID (64-char hex, generated at box creation).
@@ -32,6 +34,9 @@ pub struct BoxConfig {
     // === Container Configuration ===
     /// Container configuration (id).
     pub container: ContainerRuntimeConfig,
+    /// Sidecar containers by name, one per `BoxOptions::sidecars` entry.
+    #[serde(default)]
+    pub sidecars: BTreeMap<String, ContainerRuntimeConfig>,
 
     // === User Options (preserved for restart) ===
     /// User-provided options at creation time.
@@ -48,3 +53,58 @@ pub struct BoxConfig {
     /// Ready signal socket path.
     pub ready_socket_path: PathBuf,
 }
+
+impl BoxConfig {
+    /// Container hostname: `BoxOptions::hostname`, else the box name when it
+    /// is a valid hostname, else the lowercased box ID.
+    pub fn hostname(&self) -> String {
+        use crate::runtime::options::is_valid_hostname;
+
+        if let Some(hostname) = &self.options.hostname {
+            return hostname.clone();
+        }
+        match &self.name {
+            Some(name) if is_valid_hostname(name) => name.clone(),
+            _ => self.id.as_str().to_ascii_lowercase(),
+        }
+    }
+
+    /// Guest MAC address in `aa:bb:cc:dd:ee:ff` form (`None` without
+    /// networking).
+    pub fn mac_address(&self) -> Option<String> {
+        use crate::net::constants::{GUEST_MAC, mac_to_string, parse_mac};
+
+        if self.options.network == crate::runtime::options::NetworkSpec::Disabled {
+            return None;
+        }
+        let mac = match &self.options.mac_address {
+            Some(mac) => parse_mac(mac).unwrap_or(GUEST_MAC),
+            None => GUEST_MAC,
+        };
+        Some(mac_to_string(&mac))
+    }
+
+    /// Guest IPv4 address (`None` without networking).
+    pub fn ip_address(&self) -> Option<Ipv4Addr> {
+        use crate::net::constants::{GUEST_IP_ADDR, parse_guest_ip};
+
+        if self.options.network == crate::runtime::options::NetworkSpec::Disabled {
+            return None;
+        }
+        Some(match &self.options.ip_address {
+            Some(ip) => parse_guest_ip(ip).unwrap_or(GUEST_IP_ADDR),
+            None => GUEST_IP_ADDR,
+        })
+    }
+
+    /// Address the box reaches the host's loopback at (`None` without
+    /// networking or with `host_loopback` disabled).
+    pub fn host_loopback_ip(&self) -> Option<Ipv4Addr> {
+        if self.options.network == crate::runtime::options::NetworkSpec::Disabled
+            || !self.options.host_loopback
+        {
+            return None;
+        }
+        Some(crate::net::constants::HOST_LOOPBACK_IP_ADDR)
+    }
+}
@@ -0,0 +1,131 @@
+//! Connection log of a box (`BoxOptions::log_connections`).
+//!
+//! The gvproxy backend watches the frames the guest sends and appends one
+//! JSON line to the box's `connections.log` when a connection ends (FIN,
+//! RST, or idle for a minute; ten for established TCP connections). The log
+//! is rotated at 16 MiB into `connections.log.1` ... `connections.log.4`.
+
+use std::path::{Path, PathBuf};
+
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Rotated logs kept next to the log, `.1` being the newest (must stay in
+/// sync with connLogRotatedFiles in gvproxy-bridge/connlog.go!)
+const ROTATED_FILES: usize = 4;
+
+/// A connection opened by a box.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectionRecord {
+    /// When the guest opened the connection.
+    pub time: DateTime<Utc>,
+    /// `tcp` or `udp`.
+    pub protocol: String,
+    /// Source port in the box.
+    pub src_port: u16,
+    /// Destination address.
+    pub dst_ip: String,
+    /// Destination port.
+    pub dst_port: u16,
+    /// TLS server name (SNI) of the connection, if it opened with a
+    /// ClientHello.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+    /// IP bytes sent by the box.
+    pub bytes_sent: u64,
+    /// IP bytes received by the box.
+    pub bytes_received: u64,
+    /// Time from the first to the last packet, in milliseconds.
+    pub duration_ms: u64,
+    /// The network policy dropped the connection.
+    #[serde(default)]
+    pub blocked: bool,
+}
+
+/// Connections logged in `path` and its rotated files, oldest first. A
+/// missing log has none; lines that do not parse (e.g. one being written)
+/// are skipped.
+pub(crate) fn read(path: &Path) -> BoxliteResult<Vec<ConnectionRecord>> {
+    let mut records = Vec::new();
+    for n in (0..=ROTATED_FILES).rev() {
+        let file = if n == 0 {
+            path.to_path_buf()
+        } else {
+            rotated(path, n)
+        };
+        let content = match std::fs::read_to_string(&file) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => {
+                return Err(BoxliteError::Storage(format!(
+                    "Failed to read connection log {}: {}",
+                    file.display(),
+                    e
+                )));
+            }
+        };
+        records.extend(
+            content
+                .lines()
+                .filter_map(|line| serde_json::from_str::<ConnectionRecord>(line).ok()),
+        );
+    }
+    // Lines are written when connections end; order by when they began
+    records.sort_by_key(|r| r.time);
+    Ok(records)
+}
+
+fn rotated(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", n));
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_connection_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("connections.log");
+        assert!(read(&path).unwrap().is_empty());
+
+        std::fs::write(
+            &path,
+            concat!(
+                r#"{"time":"2026-10-17T10:00:05.5Z","protocol":"tcp","src_port":40000,"dst_ip":"140.82.112.3","dst_port":443,"hostname":"github.com","bytes_sent":1200,"bytes_received":5400,"duration_ms":830}"#,
+                "\n",
+                r#"{"time":"2026-10-17T10:00:01Z","protocol":"udp","src_port":50000,"dst_ip":"10.0.0.1","dst_port":123,"bytes_sent":76,"bytes_received":0,"duration_ms":0,"blocked":true}"#,
+                "\n",
+                r#"{"time":"2026-10-17T10:00:0"#,
+            ),
+        )
+        .unwrap();
+
+        let records = read(&path).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            (records[0].protocol.as_str(), records[0].dst_port),
+            ("udp", 123)
+        );
+        assert!(records[0].blocked);
+        assert_eq!(records[0].hostname, None);
+        assert_eq!(records[1].hostname.as_deref(), Some("github.com"));
+        assert!(!records[1].blocked);
+
+        // Rotated logs are read too
+        std::fs::write(
+            rotated(&path, 1),
+            concat!(
+                r#"{"time":"2026-10-17T09:59:00Z","protocol":"tcp","src_port":39000,"dst_ip":"1.1.1.1","dst_port":443,"bytes_sent":10,"bytes_received":20,"duration_ms":5}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+        let records = read(&path).unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].dst_ip, "1.1.1.1");
+    }
+}
@@ -0,0 +1,167 @@
+//! Core dumps collected from a box (`BoxOptions::core_dumps`).
+//!
+//! The guest agent receives each dump from the kernel and writes it to the
+//! box's `cores` directory as `core.{unix time}.{pid}.{signal}.{command}`,
+//! with a `.truncated` suffix when it hit the size cap.
+
+use std::path::{Path, PathBuf};
+
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+const TRUNCATED_SUFFIX: &str = ".truncated";
+
+/// A core dump written by a process that crashed in a box.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct CoreDump {
+    /// File name, unique within the box.
+    pub name: String,
+    /// Host path of the dump.
+    pub path: PathBuf,
+    /// Command name of the crashed process (at most 15 characters).
+    pub command: String,
+    /// PID of the crashed process, as seen in the box.
+    pub pid: u32,
+    /// Signal that caused the dump.
+    pub signal: i32,
+    /// When the process crashed.
+    pub created_at: DateTime<Utc>,
+    /// Size of the dump file.
+    pub size_bytes: u64,
+    /// The dump was cut off at `CoreDumpOptions::max_bytes`.
+    pub truncated: bool,
+}
+
+/// Dumps in `dir`, newest first. A missing directory has none.
+pub(crate) fn list(dir: &Path) -> BoxliteResult<Vec<CoreDump>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(BoxliteError::Storage(format!(
+                "Failed to read core dumps in {}: {}",
+                dir.display(),
+                e
+            )));
+        }
+    };
+
+    let mut dumps = Vec::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        // Skip files being written and anything else in the directory
+        let Some(mut dump) = parse_name(&name) else {
+            continue;
+        };
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if !metadata.is_file() {
+            continue;
+        }
+        dump.path = entry.path();
+        dump.size_bytes = metadata.len();
+        dumps.push(dump);
+    }
+    dumps.sort_by(|a, b| {
+        b.created_at
+            .cmp(&a.created_at)
+            .then_with(|| b.name.cmp(&a.name))
+    });
+    Ok(dumps)
+}
+
+/// Parse `core.{time}.{pid}.{signal}.{command}[.truncated]`.
+fn parse_name(name: &str) -> Option<CoreDump> {
+    let (rest, truncated) = match name.strip_suffix(TRUNCATED_SUFFIX) {
+        Some(rest) => (rest, true),
+        None => (name, false),
+    };
+    let mut parts = rest.strip_prefix("core.")?.splitn(4, '.');
+    let time = parts.next()?.parse::<i64>().ok()?;
+    let pid = parts.next()?.parse().ok()?;
+    let signal = parts.next()?.parse().ok()?;
+    let command = parts.next()?.to_string();
+    Some(CoreDump {
+        name: name.to_string(),
+        path: PathBuf::new(),
+        command,
+        pid,
+        signal,
+        created_at: DateTime::from_timestamp(time, 0)?,
+        size_bytes: 0,
+        truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vmm::mock::{MockScript, test_runtime};
+
+    #[test]
+    fn test_list_core_dumps() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(list(&dir.path().join("missing")).unwrap().is_empty());
+
+        std::fs::write(dir.path().join("core.1700000000.42.11.python3"), b"ELF").unwrap();
+        std::fs::write(
+            dir.path().join("core.1700000100.7.6.my.app.truncated"),
+            b"ELF.",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join(".core.partial"), b"").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), b"").unwrap();
+
+        let dumps = list(dir.path()).unwrap();
+        assert_eq!(dumps.len(), 2);
+        assert_eq!(
+            (dumps[0].command.as_str(), dumps[0].pid, dumps[0].signal),
+            ("my.app", 7, 6)
+        );
+        assert!(dumps[0].truncated);
+        assert_eq!(dumps[0].size_bytes, 4);
+        assert_eq!(dumps[1].name, "core.1700000000.42.11.python3");
+        assert_eq!(dumps[1].created_at.timestamp(), 1_700_000_000);
+        assert!(!dumps[1].truncated);
+        assert_eq!(dumps[1].path, dir.path().join(&dumps[1].name));
+    }
+
+    #[tokio::test]
+    async fn test_core_dumps() {
+        use crate::{BoxOptions, BoxliteOptions, CoreDumpOptions};
+
+        let (home, runtime) = test_runtime(BoxliteOptions::default(), MockScript::new());
+        let litebox = runtime
+            .create(
+                BoxOptions {
+                    auto_remove: false,
+                    core_dumps: Some(CoreDumpOptions::default()),
+                    ..Default::default()
+                },
+                Some("crashy".to_string()),
+            )
+            .await
+            .unwrap();
+        assert!(litebox.core_dumps().unwrap().is_empty());
+
+        // The guest agent of a mock box accepts core dump collection
+        litebox.start().await.unwrap();
+        let cores = home
+            .path()
+            .join("boxes")
+            .join(litebox.id().as_str())
+            .join("cores");
+        std::fs::create_dir_all(&cores).unwrap();
+        std::fs::write(cores.join("core.1700000000.42.11.python3"), b"ELF").unwrap();
+        litebox.stop().await.unwrap();
+
+        // Readable after stop
+        let litebox = runtime.get("crashy").await.unwrap().unwrap();
+        let dumps = litebox.core_dumps().unwrap();
+        assert_eq!(dumps.len(), 1);
+        assert_eq!((dumps[0].command.as_str(), dumps[0].pid), ("python3", 42));
+        assert_eq!(dumps[0].size_bytes, 3);
+    }
+}
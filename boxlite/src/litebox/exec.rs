@@ -3,11 +3,15 @@
 //! Type definitions for executing commands in a box.
 //! The actual execution logic is in BoxImpl::exec().
 
+use super::recording::OutputRecording;
 use crate::portal::interfaces::ExecutionInterface;
+use crate::runtime::options::ExecCompression;
 use boxlite_shared::errors::BoxliteResult;
+use bytes::Bytes;
 use futures::Stream;
+use std::path::{Path, PathBuf};
 use std::pin::Pin;
-use std::task::{Context, Poll};
+use std::task::{Context, Poll, ready};
 use std::time::Duration;
 use tokio::sync::mpsc;
 
@@ -34,6 +38,13 @@ pub struct BoxCommand {
     pub(crate) timeout: Option<Duration>,
     pub(crate) working_dir: Option<String>,
     pub(crate) tty: bool,
+    pub(crate) compression: Option<ExecCompression>,
+    pub(crate) recording: Option<OutputRecording>,
+    pub(crate) stop_box_on_exit: bool,
+    pub(crate) container: Option<String>,
+    pub(crate) user: Option<String>,
+    pub(crate) privileged: bool,
+    pub(crate) fresh_rootfs: bool,
 }
 
 impl BoxCommand {
@@ -46,6 +57,13 @@ impl BoxCommand {
             timeout: None,
             working_dir: None,
             tty: false,
+            compression: None,
+            recording: None,
+            stop_box_on_exit: false,
+            container: None,
+            user: None,
+            privileged: false,
+            fresh_rootfs: false,
         }
     }
 
@@ -92,6 +110,68 @@ impl BoxCommand {
         self.tty = enable;
         self
     }
+
+    /// Compress stdout/stderr on the host-guest channel.
+    ///
+    /// Overrides `BoxOptions::exec_compression` for this command.
+    pub fn compression(mut self, compression: ExecCompression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Save stdout and stderr to log files as the command runs.
+    ///
+    /// See [`Execution::recording_dir`] for where they end up.
+    pub fn record_output(mut self, recording: OutputRecording) -> Self {
+        self.recording = Some(recording);
+        self
+    }
+
+    /// Stop the box when this command exits, even if nobody is attached any
+    /// more.
+    ///
+    /// Makes the command the box's main process, like a container's
+    /// entrypoint. With `BoxOptions::auto_remove` the box is then removed as
+    /// well, which is how detached `auto_remove` boxes clean up after
+    /// themselves.
+    pub fn stop_box_on_exit(mut self, enable: bool) -> Self {
+        self.stop_box_on_exit = enable;
+        self
+    }
+
+    /// Run in the sidecar `name` (see `BoxOptions::sidecars`) instead of the
+    /// box's main container.
+    pub fn container(mut self, name: impl Into<String>) -> Self {
+        self.container = Some(name.into());
+        self
+    }
+
+    /// Run as `user` instead of the container's user.
+    ///
+    /// Accepts `uid`, `uid:gid`, `name` or `name:group`; names are looked up
+    /// in the container's `/etc/passwd` and `/etc/group`.
+    pub fn user(mut self, user: impl Into<String>) -> Self {
+        self.user = Some(user.into());
+        self
+    }
+
+    /// Grant the command every Linux capability, not just the container's.
+    pub fn privileged(mut self, enable: bool) -> Self {
+        self.privileged = enable;
+        self
+    }
+
+    /// Run on a throwaway copy of the container's root filesystem.
+    ///
+    /// The guest starts a container for this command alone whose root is an
+    /// overlay with an empty, in-memory upper layer over the container's
+    /// rootfs, sharing its volumes and the box's network. Nothing the
+    /// command writes outside volumes reaches the box or later commands; the
+    /// copy is removed when the command exits.
+    pub fn fresh_rootfs(mut self, enable: bool) -> Self {
+        self.fresh_rootfs = enable;
+        self
+    }
 }
 
 /// Handle to a running command execution.
@@ -108,10 +188,10 @@ impl BoxCommand {
 ///
 /// let mut execution = litebox.exec(BoxCommand::new("ls").arg("-la")).await?;
 ///
-/// // Read stdout
-/// let mut stdout = execution.stdout.take().unwrap();
+/// // Read stdout line by line
+/// let mut stdout = execution.stdout().unwrap().lines();
 /// while let Some(line) = stdout.next().await {
-///     println!("{}", line);
+///     print!("{}", line);
 /// }
 ///
 /// // Wait for completion
@@ -124,12 +204,14 @@ impl BoxCommand {
 pub struct Execution {
     id: ExecutionId,
     inner: std::sync::Arc<tokio::sync::Mutex<ExecutionInner>>,
+    /// Locked apart from `inner`, so a handle waiting for the exit does not
+    /// block signals sent through another one.
+    result: std::sync::Arc<tokio::sync::Mutex<ExecutionResult>>,
+    recording_dir: Option<PathBuf>,
 }
 
 pub(crate) struct ExecutionInner {
     interface: ExecutionInterface,
-    result_rx: mpsc::UnboundedReceiver<ExecResult>,
-    cached_result: Option<ExecResult>,
 
     /// Standard input stream (write-only).
     stdin: Option<ExecStdin>,
@@ -141,6 +223,11 @@ pub(crate) struct ExecutionInner {
     stderr: Option<ExecStderr>,
 }
 
+struct ExecutionResult {
+    rx: mpsc::UnboundedReceiver<ExecResult>,
+    cached: Option<ExecResult>,
+}
+
 /// Unique identifier for an execution.
 pub type ExecutionId = String;
 
@@ -156,8 +243,6 @@ impl Execution {
     ) -> Self {
         let inner = ExecutionInner {
             interface,
-            result_rx,
-            cached_result: None,
             stdin,
             stdout,
             stderr,
@@ -166,14 +251,30 @@ impl Execution {
         Self {
             id: execution_id,
             inner: std::sync::Arc::new(tokio::sync::Mutex::new(inner)),
+            result: std::sync::Arc::new(tokio::sync::Mutex::new(ExecutionResult {
+                rx: result_rx,
+                cached: None,
+            })),
+            recording_dir: None,
         }
     }
 
+    pub(crate) fn with_recording_dir(mut self, dir: Option<PathBuf>) -> Self {
+        self.recording_dir = dir;
+        self
+    }
+
     /// Get the execution ID.
     pub fn id(&self) -> &ExecutionId {
         &self.id
     }
 
+    /// Directory holding `stdout.log`, `stderr.log` and `manifest.json`
+    /// when the command was started with [`BoxCommand::record_output`].
+    pub fn recording_dir(&self) -> Option<&Path> {
+        self.recording_dir.as_deref()
+    }
+
     /// Take the stdin stream (can only be called once).
     pub fn stdin(&mut self) -> Option<ExecStdin> {
         futures::executor::block_on(async {
@@ -203,24 +304,24 @@ impl Execution {
     /// Returns the exit status once the execution finishes. If the result is
     /// already cached, returns immediately. Otherwise, waits for result from channel.
     pub async fn wait(&mut self) -> BoxliteResult<ExecResult> {
-        let mut inner = self.inner.lock().await;
+        let mut result = self.result.lock().await;
 
         // Check if result is already cached
-        if let Some(result) = &inner.cached_result {
-            return Ok(result.clone());
+        if let Some(cached) = &result.cached {
+            return Ok(cached.clone());
         }
 
         // Try to receive from result channel (non-blocking)
-        if let Ok(status) = inner.result_rx.try_recv() {
-            inner.cached_result = Some(status.clone());
+        if let Ok(status) = result.rx.try_recv() {
+            result.cached = Some(status.clone());
             return Ok(status);
         }
 
         // Await next result
-        let status = inner.result_rx.recv().await.ok_or_else(|| {
+        let status = result.rx.recv().await.ok_or_else(|| {
             boxlite_shared::BoxliteError::Internal("Result channel closed".into())
         })?;
-        inner.cached_result = Some(status.clone());
+        result.cached = Some(status.clone());
         Ok(status)
     }
 
@@ -244,15 +345,59 @@ impl Execution {
     }
 }
 
+/// Why a command did not exit on its own.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExitReason {
+    /// The program does not exist in the box (exit code 127).
+    CommandNotFound,
+    /// The program exists but cannot be executed, e.g. a directory or a
+    /// file without the execute bit (exit code 126).
+    NotExecutable,
+    /// Terminated by this signal (exit code 128 + signal).
+    Signaled(i32),
+}
+
+impl ExitReason {
+    /// Shell exit code for this reason.
+    pub fn exit_code(&self) -> i32 {
+        use boxlite_shared::constants::exec_error;
+        match self {
+            ExitReason::CommandNotFound => exec_error::COMMAND_NOT_FOUND_EXIT_CODE,
+            ExitReason::NotExecutable => exec_error::NOT_EXECUTABLE_EXIT_CODE,
+            ExitReason::Signaled(signal) => 128 + signal,
+        }
+    }
+
+    /// Stable name used by the SDKs: `command_not_found`, `not_executable`
+    /// or `signaled`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExitReason::CommandNotFound => "command_not_found",
+            ExitReason::NotExecutable => "not_executable",
+            ExitReason::Signaled(_) => "signaled",
+        }
+    }
+}
+
 /// Exit status of a process.
+///
+/// Exit codes follow shell conventions: 127 when the program was not
+/// found, 126 when it could not be executed, and 128 + N when signal N
+/// terminated it. `reason` tells these apart from a program that chose
+/// such a code itself.
 #[derive(Clone, Debug)]
 pub struct ExecResult {
-    /// Exit code (0 = success). If terminated by signal, code is negative signal number.
+    /// Exit code (0 = success).
     pub exit_code: i32,
+    /// Set when the program did not run or did not exit on its own.
+    pub reason: Option<ExitReason>,
     /// Diagnostic message when process died unexpectedly
-    /// (e.g., container init death causing PID namespace teardown).
-    /// None if the process exited normally.
+    /// (e.g., container init death causing PID namespace teardown)
+    /// or could not be started. None if the process exited normally.
     pub error_message: Option<String>,
+    /// The guest kernel killed a process out of memory while the command
+    /// ran, and the command died by SIGKILL or exited with code 137.
+    pub oom_killed: bool,
 }
 
 impl ExecResult {
@@ -264,6 +409,14 @@ impl ExecResult {
     pub fn code(&self) -> i32 {
         self.exit_code
     }
+
+    /// Signal that terminated the process, if any.
+    pub fn signal(&self) -> Option<i32> {
+        match self.reason {
+            Some(ExitReason::Signaled(signal)) => Some(signal),
+            _ => None,
+        }
+    }
 }
 
 /// Standard input stream (write-only).
@@ -307,18 +460,33 @@ impl ExecStdin {
 }
 
 /// Standard output stream (read-only).
+///
+/// Yields output as the process writes it: chunks may end mid-line or
+/// mid-character, and binary output passes through unchanged. Use
+/// [`lines`](Self::lines) to read text line by line, or [`text`](Self::text)
+/// to read it chunk by chunk.
 pub struct ExecStdout {
-    receiver: mpsc::UnboundedReceiver<String>,
+    receiver: mpsc::UnboundedReceiver<Bytes>,
 }
 
 impl ExecStdout {
-    pub(crate) fn new(receiver: mpsc::UnboundedReceiver<String>) -> Self {
+    pub(crate) fn new(receiver: mpsc::UnboundedReceiver<Bytes>) -> Self {
         Self { receiver }
     }
+
+    /// Decode the stream into lines.
+    pub fn lines(self) -> OutputLines<Self> {
+        OutputLines::new(self)
+    }
+
+    /// Decode the stream into text chunks as they arrive.
+    pub fn text(self) -> OutputText<Self> {
+        OutputText::new(self)
+    }
 }
 
 impl Stream for ExecStdout {
-    type Item = String;
+    type Item = Bytes;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         self.receiver.poll_recv(cx)
@@ -326,20 +494,267 @@ impl Stream for ExecStdout {
 }
 
 /// Standard error stream (read-only).
+///
+/// Raw chunks like [`ExecStdout`]; use [`lines`](Self::lines) or
+/// [`text`](Self::text) for text.
 pub struct ExecStderr {
-    receiver: mpsc::UnboundedReceiver<String>,
+    receiver: mpsc::UnboundedReceiver<Bytes>,
 }
 
 impl ExecStderr {
-    pub(crate) fn new(receiver: mpsc::UnboundedReceiver<String>) -> Self {
+    pub(crate) fn new(receiver: mpsc::UnboundedReceiver<Bytes>) -> Self {
         Self { receiver }
     }
+
+    /// Decode the stream into lines.
+    pub fn lines(self) -> OutputLines<Self> {
+        OutputLines::new(self)
+    }
+
+    /// Decode the stream into text chunks as they arrive.
+    pub fn text(self) -> OutputText<Self> {
+        OutputText::new(self)
+    }
 }
 
 impl Stream for ExecStderr {
-    type Item = String;
+    type Item = Bytes;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         self.receiver.poll_recv(cx)
     }
 }
+
+/// Lines of an output stream, from [`ExecStdout::lines`] or
+/// [`ExecStderr::lines`].
+///
+/// Each line keeps its trailing `\n`, so joining the lines reproduces the
+/// output. A last line without one is yielded when the stream ends.
+/// Invalid UTF-8 is replaced with U+FFFD.
+pub struct OutputLines<S> {
+    inner: S,
+    buf: Vec<u8>,
+    done: bool,
+}
+
+impl<S> OutputLines<S> {
+    fn new(inner: S) -> Self {
+        Self {
+            inner,
+            buf: Vec::new(),
+            done: false,
+        }
+    }
+}
+
+impl<S: Stream<Item = Bytes> + Unpin> Stream for OutputLines<S> {
+    type Item = String;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            if let Some(end) = this.buf.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = this.buf.drain(..=end).collect();
+                return Poll::Ready(Some(String::from_utf8_lossy(&line).into_owned()));
+            }
+            if this.done {
+                if this.buf.is_empty() {
+                    return Poll::Ready(None);
+                }
+                let line = std::mem::take(&mut this.buf);
+                return Poll::Ready(Some(String::from_utf8_lossy(&line).into_owned()));
+            }
+            match ready!(Pin::new(&mut this.inner).poll_next(cx)) {
+                Some(chunk) => this.buf.extend_from_slice(&chunk),
+                None => this.done = true,
+            }
+        }
+    }
+}
+
+/// Text of an output stream, from [`ExecStdout::text`] or
+/// [`ExecStderr::text`].
+///
+/// Yields each chunk decoded as soon as it arrives. A character split across
+/// chunks is held back until its remaining bytes arrive, so it is never
+/// replaced. Invalid UTF-8, including a character cut off by the end of the
+/// stream, is replaced with U+FFFD.
+pub struct OutputText<S> {
+    inner: S,
+    /// Bytes of a character whose remaining bytes have not arrived yet.
+    pending: Vec<u8>,
+}
+
+impl<S> OutputText<S> {
+    fn new(inner: S) -> Self {
+        Self {
+            inner,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Decode `pending`, keeping back an incomplete trailing character.
+    fn decode(&mut self) -> String {
+        let mut text = String::new();
+        let mut rest = self.pending.as_slice();
+        while let Err(e) = std::str::from_utf8(rest) {
+            let (valid, invalid) = rest.split_at(e.valid_up_to());
+            text.push_str(&String::from_utf8_lossy(valid));
+            let Some(len) = e.error_len() else {
+                // Cut off by the chunk end: wait for the next chunk
+                self.pending = invalid.to_vec();
+                return text;
+            };
+            text.push(char::REPLACEMENT_CHARACTER);
+            rest = &invalid[len..];
+        }
+        text.push_str(&String::from_utf8_lossy(rest));
+        self.pending.clear();
+        text
+    }
+}
+
+impl<S: Stream<Item = Bytes> + Unpin> Stream for OutputText<S> {
+    type Item = String;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            match ready!(Pin::new(&mut this.inner).poll_next(cx)) {
+                Some(chunk) => {
+                    this.pending.extend_from_slice(&chunk);
+                    let text = this.decode();
+                    // A chunk holding only part of a character yields nothing yet
+                    if !text.is_empty() {
+                        return Poll::Ready(Some(text));
+                    }
+                }
+                None if this.pending.is_empty() => return Poll::Ready(None),
+                None => {
+                    let tail = std::mem::take(&mut this.pending);
+                    return Poll::Ready(Some(String::from_utf8_lossy(&tail).into_owned()));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vmm::mock::{MockExec, MockScript, test_runtime};
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn test_output_lines_joins_partial_chunks() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        for chunk in [
+            &b"progress 1\rprogress 2\nhal"[..],
+            b"f \xe2\x9c",
+            b"\x93\nno newline",
+        ] {
+            tx.send(Bytes::copy_from_slice(chunk)).unwrap();
+        }
+        drop(tx);
+
+        let lines: Vec<String> = ExecStdout::new(rx).lines().collect().await;
+        assert_eq!(
+            lines,
+            ["progress 1\rprogress 2\n", "half \u{2713}\n", "no newline"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_output_text_keeps_split_characters() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        for chunk in [
+            &b"half \xe2"[..],
+            b"\x9c",
+            b"\x93 bad \xff ok",
+            b" cut \xe2\x9c",
+        ] {
+            tx.send(Bytes::copy_from_slice(chunk)).unwrap();
+        }
+        drop(tx);
+
+        let text: Vec<String> = ExecStdout::new(rx).text().collect().await;
+        assert_eq!(
+            text,
+            ["half ", "\u{2713} bad \u{FFFD} ok", " cut ", "\u{FFFD}"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_exec_compression() {
+        use crate::{BoxCommand, BoxOptions, BoxliteOptions, ExecCompression};
+
+        let log = "INFO compiling crate\n".repeat(500);
+        let script = MockScript::new().on(&["make"], MockExec::new().stdout(log.clone()));
+        let (_home, runtime) = test_runtime(BoxliteOptions::default(), script);
+        let litebox = runtime
+            .create(
+                BoxOptions {
+                    exec_compression: ExecCompression::Zstd,
+                    ..Default::default()
+                },
+                None,
+            )
+            .await
+            .unwrap();
+
+        let mut execution = litebox.exec(BoxCommand::new("make")).await.unwrap();
+        let stdout: Vec<String> = execution.stdout().unwrap().lines().collect().await;
+        assert_eq!(stdout.concat(), log);
+        assert!(execution.wait().await.unwrap().success());
+
+        let metrics = litebox.metrics().await.unwrap();
+        assert_eq!(metrics.bytes_received_total(), log.len() as u64);
+        assert!(metrics.bytes_received_wire_total() < metrics.bytes_received_total() / 10);
+
+        // Per-command override
+        let mut execution = litebox
+            .exec(BoxCommand::new("make").compression(ExecCompression::None))
+            .await
+            .unwrap();
+        let stdout: Vec<String> = execution.stdout().unwrap().lines().collect().await;
+        assert_eq!(stdout.concat(), log);
+        execution.wait().await.unwrap();
+        let after = litebox.metrics().await.unwrap();
+        assert_eq!(
+            after.bytes_received_wire_total() - metrics.bytes_received_wire_total(),
+            log.len() as u64
+        );
+        litebox.stop().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_concurrent_exec() {
+        use crate::{BoxCommand, BoxOptions, BoxliteOptions};
+
+        let script = MockScript::new();
+        let (_home, runtime) = test_runtime(BoxliteOptions::default(), script.clone());
+        let litebox = runtime.create(BoxOptions::default(), None).await.unwrap();
+
+        // The first execs race to start the box and connect to the guest
+        let results = futures::future::join_all((0..16).map(|i| {
+            let litebox = &litebox;
+            async move {
+                let mut execution = litebox
+                    .exec(BoxCommand::new("echo").arg(i.to_string()))
+                    .await
+                    .unwrap();
+                let stdout: Vec<String> = execution.stdout().unwrap().lines().collect().await;
+                assert!(execution.wait().await.unwrap().success());
+                stdout.concat()
+            }
+        }))
+        .await;
+
+        for (i, stdout) in results.iter().enumerate() {
+            assert_eq!(stdout, &format!("{}\n", i));
+        }
+        assert_eq!(script.calls().len(), 16);
+        assert_eq!(litebox.metrics().await.unwrap().commands_executed_total, 16);
+        litebox.stop().await.unwrap();
+    }
+}
@@ -0,0 +1,232 @@
+//! Per-box cap on commands running at once (`BoxOptions::exec_limit`).
+//!
+//! Each command takes a slot before it is started and gives it back when its
+//! result arrives, or when the result channel closes because the box went
+//! away. Commands over the cap wait for a slot, up to the configured queue
+//! timeout, instead of piling up in the guest.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore, mpsc};
+use tokio_util::sync::CancellationToken;
+
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+
+use super::exec::ExecResult;
+use crate::portal::interfaces::exec::ExecComponents;
+use crate::runtime::options::ExecLimit;
+
+/// Slots for the commands of one box.
+pub(crate) struct ExecSlots {
+    semaphore: Arc<Semaphore>,
+    limit: ExecLimit,
+}
+
+impl ExecSlots {
+    pub(crate) fn new(limit: ExecLimit) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(limit.max_in_flight as usize)),
+            limit,
+        }
+    }
+
+    /// Take a slot, waiting as long as the limit allows.
+    ///
+    /// Fails with `ResourceExhausted` when the queue timeout passes and with
+    /// `Stopped` when the box stops while waiting.
+    pub(crate) async fn acquire(
+        &self,
+        shutdown_token: &CancellationToken,
+    ) -> BoxliteResult<OwnedSemaphorePermit> {
+        if let Ok(permit) = Arc::clone(&self.semaphore).try_acquire_owned() {
+            return Ok(permit);
+        }
+        let exhausted = || {
+            BoxliteError::ResourceExhausted(format!(
+                "{} commands are already running in the box (exec_limit)",
+                self.limit.max_in_flight
+            ))
+        };
+        let wait = Arc::clone(&self.semaphore).acquire_owned();
+        let permit = match self.limit.queue_timeout_secs {
+            Some(0) => return Err(exhausted()),
+            Some(secs) => tokio::select! {
+                permit = tokio::time::timeout(Duration::from_secs(secs), wait) => {
+                    permit.map_err(|_| exhausted())?
+                }
+                _ = shutdown_token.cancelled() => return Err(stopped()),
+            },
+            None => tokio::select! {
+                permit = wait => permit,
+                _ = shutdown_token.cancelled() => return Err(stopped()),
+            },
+        };
+        permit.map_err(|_| stopped())
+    }
+}
+
+fn stopped() -> BoxliteError {
+    BoxliteError::Stopped("box stopped while the command waited for an exec slot".into())
+}
+
+/// Hold `permit` until the execution's result arrives.
+pub(crate) fn hold(permit: OwnedSemaphorePermit, components: ExecComponents) -> ExecComponents {
+    let ExecComponents {
+        execution_id,
+        stdin_tx,
+        stdout_rx,
+        stderr_rx,
+        result_rx: mut source_result,
+    } = components;
+
+    let (result_tx, result_rx) = mpsc::unbounded_channel::<ExecResult>();
+    tokio::spawn(async move {
+        let result = source_result.recv().await;
+        // Freed before the caller sees the result, so it can start the next
+        // command straight away
+        drop(permit);
+        if let Some(result) = result {
+            let _ = result_tx.send(result);
+        }
+    });
+
+    ExecComponents {
+        execution_id,
+        stdin_tx,
+        stdout_rx,
+        stderr_rx,
+        result_rx,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vmm::mock::{MockScript, test_runtime};
+    use futures::StreamExt;
+
+    fn slots(max_in_flight: u32, queue_timeout_secs: Option<u64>) -> ExecSlots {
+        ExecSlots::new(ExecLimit {
+            max_in_flight,
+            queue_timeout_secs,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_reject_when_full() {
+        let slots = slots(1, Some(0));
+        let token = CancellationToken::new();
+        let permit = slots.acquire(&token).await.unwrap();
+        assert!(matches!(
+            slots.acquire(&token).await,
+            Err(BoxliteError::ResourceExhausted(_))
+        ));
+        drop(permit);
+        assert!(slots.acquire(&token).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_queue_timeout() {
+        let slots = slots(1, Some(1));
+        let token = CancellationToken::new();
+        let _permit = slots.acquire(&token).await.unwrap();
+        assert!(matches!(
+            slots.acquire(&token).await,
+            Err(BoxliteError::ResourceExhausted(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_waiter_stops_with_box() {
+        let slots = slots(1, None);
+        let token = CancellationToken::new();
+        let _permit = slots.acquire(&token).await.unwrap();
+        token.cancel();
+        assert!(matches!(
+            slots.acquire(&token).await,
+            Err(BoxliteError::Stopped(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_exec_limit_queues_parallel_execs() {
+        use crate::{BoxCommand, BoxOptions, BoxliteOptions, ExecLimit};
+
+        let script = MockScript::new();
+        let (_home, runtime) = test_runtime(BoxliteOptions::default(), script.clone());
+        let litebox = runtime
+            .create(
+                BoxOptions {
+                    exec_limit: Some(ExecLimit {
+                        max_in_flight: 8,
+                        queue_timeout_secs: None,
+                    }),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await
+            .unwrap();
+
+        // Far more execs than slots: the rest queue and all of them finish
+        let results = futures::future::join_all((0..300).map(|i| {
+            let litebox = &litebox;
+            async move {
+                let mut execution = litebox
+                    .exec(BoxCommand::new("echo").arg(i.to_string()))
+                    .await
+                    .unwrap();
+                let stdout: Vec<String> = execution.stdout().unwrap().lines().collect().await;
+                assert!(execution.wait().await.unwrap().success());
+                stdout.concat()
+            }
+        }))
+        .await;
+
+        for (i, stdout) in results.iter().enumerate() {
+            assert_eq!(stdout, &format!("{}\n", i));
+        }
+        assert_eq!(script.calls().len(), 300);
+        litebox.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_exec_limit_rejects_when_full() {
+        use crate::{BoxCommand, BoxOptions, BoxliteOptions, ExecLimit};
+        use boxlite_shared::errors::BoxliteError;
+
+        let (_home, runtime) = test_runtime(BoxliteOptions::default(), MockScript::new());
+        let litebox = runtime
+            .create(
+                BoxOptions {
+                    exec_limit: Some(ExecLimit {
+                        max_in_flight: 2,
+                        queue_timeout_secs: Some(0),
+                    }),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await
+            .unwrap();
+
+        let mut first = litebox
+            .exec(BoxCommand::new("sleep").arg("300"))
+            .await
+            .unwrap();
+        let _second = litebox
+            .exec(BoxCommand::new("sleep").arg("300"))
+            .await
+            .unwrap();
+        let err = litebox.exec(BoxCommand::new("true")).await.err().unwrap();
+        assert!(matches!(err, BoxliteError::ResourceExhausted(_)), "{}", err);
+
+        // The slot is free again once the command has exited
+        first.kill().await.unwrap();
+        first.wait().await.unwrap();
+        let mut execution = litebox.exec(BoxCommand::new("true")).await.unwrap();
+        assert!(execution.wait().await.unwrap().success());
+        litebox.stop().await.unwrap();
+    }
+}
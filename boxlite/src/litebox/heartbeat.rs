@@ -0,0 +1,137 @@
+//! Guest agent liveness detection.
+//!
+//! If boxlite-guest dies inside a running VM, gRPC calls to it hang until the
+//! client gives up. The monitor pings the guest agent on a fixed interval and
+//! records the result in the box state; after `failure_threshold` consecutive
+//! misses the box is marked unhealthy, which new execs check to fail fast.
+//! A box whose shared network daemon died (see `net::gvproxy::shared`)
+//! fails its heartbeats too, since it is offline until restarted.
+//! With `HeartbeatPolicy::restart` the box is then stopped and started again.
+
+use std::sync::Weak;
+use std::time::Duration;
+
+use tokio_util::sync::CancellationToken;
+
+use super::LiteBox;
+use super::box_impl::{BoxImpl, SharedBoxImpl};
+use super::state::HealthStatus;
+use crate::portal::GuestSession;
+use crate::portal::interfaces::GuestInterface;
+use crate::runtime::options::HeartbeatPolicy;
+use boxlite_shared::errors::BoxliteResult;
+
+/// Spawn the heartbeat task for a running box.
+///
+/// The task exits when `shutdown_token` is cancelled (box stop or runtime
+/// shutdown) or when the last handle to the box is dropped.
+pub(crate) fn spawn_monitor(
+    box_impl: Weak<BoxImpl>,
+    guest_session: GuestSession,
+    policy: HeartbeatPolicy,
+    shutdown_token: CancellationToken,
+) {
+    let interval = Duration::from_secs(policy.interval_secs.max(1));
+    let timeout = Duration::from_secs(policy.timeout_secs.max(1));
+
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = shutdown_token.cancelled() => break,
+                _ = tokio::time::sleep(interval) => {}
+            }
+
+            let answered = match tokio::time::timeout(timeout, ping(&guest_session)).await {
+                Ok(Ok(())) => true,
+                Ok(Err(e)) => {
+                    tracing::debug!(error = %e, "Guest heartbeat failed");
+                    false
+                }
+                Err(_) => {
+                    tracing::debug!(
+                        timeout_secs = timeout.as_secs(),
+                        "Guest heartbeat timed out"
+                    );
+                    false
+                }
+            };
+
+            // Stop may have run while the ping was in flight.
+            if shutdown_token.is_cancelled() {
+                break;
+            }
+            let Some(box_impl) = box_impl.upgrade() else {
+                break;
+            };
+
+            let network_lost = box_impl.network_lost();
+            if network_lost {
+                tracing::debug!(box_id = %box_impl.id(), "Box lost its shared network");
+            }
+            let ok = answered && !network_lost;
+
+            match box_impl.record_heartbeat(ok, policy.failure_threshold) {
+                Some(HealthStatus::Unhealthy) => {
+                    tracing::error!(
+                        box_id = %box_impl.id(),
+                        event = "box_unhealthy",
+                        failure_threshold = policy.failure_threshold,
+                        "Guest agent stopped answering heartbeats"
+                    );
+                    if policy.restart && !box_impl.config.options.auto_remove {
+                        restart(box_impl).await;
+                        break;
+                    }
+                }
+                Some(HealthStatus::Healthy) => {
+                    tracing::info!(
+                        box_id = %box_impl.id(),
+                        event = "box_healthy",
+                        "Guest agent answering heartbeats again"
+                    );
+                }
+                None => {}
+            }
+        }
+    });
+}
+
+/// Ping the guest agent; a momentary connection drop is reconnected first.
+async fn ping(guest_session: &GuestSession) -> BoxliteResult<()> {
+    guest_session
+        .with_reconnect(|channel| async move { GuestInterface::new(channel).ping().await })
+        .await
+}
+
+/// Stop an unhealthy box and start it again.
+///
+/// The restarted box gets its own monitor. The new handle is held until that
+/// box stops so the monitor keeps running without a caller-held handle.
+async fn restart(box_impl: SharedBoxImpl) {
+    let box_id = box_impl.id().clone();
+    let runtime = box_impl.runtime.clone();
+    tracing::warn!(box_id = %box_id, event = "box_restart", "Restarting unhealthy box");
+
+    if let Err(e) = box_impl.stop().await {
+        tracing::error!(box_id = %box_id, "Failed to stop unhealthy box: {}", e);
+        return;
+    }
+    drop(box_impl);
+
+    let litebox: LiteBox = match runtime.get(box_id.as_str()).await {
+        Ok(Some(litebox)) => litebox,
+        Ok(None) => {
+            tracing::warn!(box_id = %box_id, "Box disappeared before restart");
+            return;
+        }
+        Err(e) => {
+            tracing::error!(box_id = %box_id, "Failed to restart unhealthy box: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = litebox.start().await {
+        tracing::error!(box_id = %box_id, "Failed to restart unhealthy box: {}", e);
+        return;
+    }
+    litebox.inner.shutdown_token.cancelled().await;
+}
@@ -8,7 +8,8 @@
 //! Starting (new box):
 //!   1. Filesystem           (create layout)
 //!   2. ContainerRootfs ─┬─  (pull image, create COW disk)
-//!      GuestRootfs     ─┘   (prepare guest, create COW disk)
+//!      GuestRootfs     ─┤   (prepare guest, create COW disk)
+//!      SidecarRootfs   ─┘   (sidecar images and COW disks, if any)
 //!   3. VmmSpawn             (build config + spawn VM)
 //!   4. GuestConnect         (wait for guest ready)
 //!   5. GuestInit            (initialize container)
@@ -21,33 +22,56 @@
 //!   4. GuestConnect         (wait for guest ready)
 //!   5. GuestInit            (re-initialize container in new VM)
 //!
+//! Cached rootfs (start or restart, image and base disk already local):
+//!   1. Filesystem
+//!   2. ContainerDisks  ─┬─  (create or reuse COW disks, no pull or build)
+//!      GuestRootfs     ─┘
+//!   3. VmmSpawn             (spawn VM; image ports not known yet)
+//!   4. GuestConnect    ─┬─  (VM boots...)
+//!      ContainerConfig ─┘   (...while the image config is loaded)
+//!   5. GuestInit            (forward image ports, initialize container)
+//!
 //! Running (reattach):
 //!   1. VmmAttach            (attach to running VM)
 //!   2. GuestConnect         (reconnect to guest)
+//!
+//! Mock engine (`testing` feature, start or restart):
+//!   1. Filesystem           (create layout)
+//!   2. MockSpawn            (start in-process guest, no image or VM)
+//!   3. GuestConnect
+//!   4. GuestInit
 //! ```
 //!
+//! SidecarRootfs joins the second stage of every start plan when the box
+//! has sidecars; GuestInit starts them after the main container.
+//!
 //! `CleanupGuard` provides RAII cleanup on failure.
 
+mod plan;
 mod tasks;
 mod types;
 
 pub(crate) use crate::litebox::box_impl::LiveState;
+pub(crate) use plan::plan_box;
+pub(crate) use tasks::{requested_ports, warm_image};
 
-use crate::litebox::BoxStatus;
 use crate::litebox::config::BoxConfig;
-use crate::metrics::BoxMetricsStorage;
+use crate::litebox::{BoxStatus, ContainerProcess};
+use crate::metrics::{BoxMetricsStorage, StageOutcome, StageTiming};
 use crate::pipeline::{
     BoxedTask, ExecutionPlan, PipelineBuilder, PipelineExecutor, PipelineMetrics, Stage,
+    TaskOutcome,
 };
 use crate::runtime::rt_impl::SharedRuntimeImpl;
 use crate::runtime::types::BoxState;
+use crate::vmm::VmmKind;
 use boxlite_shared::errors::{BoxliteError, BoxliteResult};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use tasks::{
-    ContainerRootfsTask, FilesystemTask, GuestConnectTask, GuestInitTask, GuestRootfsTask, InitCtx,
-    VmmAttachTask, VmmSpawnTask,
+    ContainerConfigTask, ContainerDisksTask, ContainerRootfsTask, FilesystemTask, GuestConnectTask,
+    GuestInitTask, GuestRootfsTask, InitCtx, SidecarRootfsTask, VmmAttachTask, VmmSpawnTask,
 };
 use types::InitPipelineContext;
 
@@ -55,18 +79,48 @@ use types::InitPipelineContext;
 // EXECUTION PLAN
 // ============================================================================
 
-/// Get execution plan based on BoxStatus.
-fn get_execution_plan(status: BoxStatus) -> ExecutionPlan<InitCtx> {
+/// Get execution plan based on BoxStatus and engine.
+///
+/// `rootfs_cached` (see [`tasks::rootfs_cached`]) selects the fast plan for a
+/// start or restart, which boots the VM while the image config is loaded.
+/// `sidecars` adds the sidecar rootfs task to the rootfs stage.
+#[cfg_attr(not(any(test, feature = "testing")), allow(unused_variables))]
+fn get_execution_plan(
+    status: BoxStatus,
+    engine_kind: VmmKind,
+    rootfs_cached: bool,
+    sidecars: bool,
+) -> ExecutionPlan<InitCtx> {
+    #[cfg(any(test, feature = "testing"))]
+    if engine_kind == VmmKind::Mock && matches!(status, BoxStatus::Configured | BoxStatus::Stopped)
+    {
+        return ExecutionPlan::new(vec![
+            Stage::sequential(vec![Box::new(FilesystemTask)]),
+            Stage::sequential(vec![Box::new(tasks::MockSpawnTask)]),
+            Stage::sequential(vec![Box::new(GuestConnectTask)]),
+            Stage::sequential(vec![Box::new(GuestInitTask)]),
+        ]);
+    }
+
     let stages: Vec<Stage<BoxedTask<InitCtx>>> = match status {
+        BoxStatus::Configured | BoxStatus::Stopped if rootfs_cached => vec![
+            // Nothing to pull or build: only the disks must exist before the
+            // VM is spawned. GuestInit waits for the config.
+            Stage::sequential(vec![Box::new(FilesystemTask)]),
+            Stage::parallel(rootfs_tasks(Box::new(ContainerDisksTask), sidecars)),
+            Stage::sequential(vec![Box::new(VmmSpawnTask)]),
+            Stage::parallel(vec![
+                Box::new(GuestConnectTask),
+                Box::new(ContainerConfigTask),
+            ]),
+            Stage::sequential(vec![Box::new(GuestInitTask)]),
+        ],
         BoxStatus::Configured => vec![
             // First start: Full pipeline
             // Phase 1: Setup filesystem layout first
             Stage::sequential(vec![Box::new(FilesystemTask)]),
             // Phase 2: Prepare rootfs (now has access to layout for disk paths)
-            Stage::parallel(vec![
-                Box::new(ContainerRootfsTask),
-                Box::new(GuestRootfsTask),
-            ]),
+            Stage::parallel(rootfs_tasks(Box::new(ContainerRootfsTask), sidecars)),
             // Phase 3: Build config and spawn VM
             Stage::sequential(vec![Box::new(VmmSpawnTask)]),
             // Phase 4: Connect to guest and initialize container
@@ -77,10 +131,7 @@ fn get_execution_plan(status: BoxStatus) -> ExecutionPlan<InitCtx> {
             // Restart: Same flow but rootfs tasks reuse existing COW disks
             // (preserves user modifications from previous run)
             Stage::sequential(vec![Box::new(FilesystemTask)]),
-            Stage::parallel(vec![
-                Box::new(ContainerRootfsTask),
-                Box::new(GuestRootfsTask),
-            ]),
+            Stage::parallel(rootfs_tasks(Box::new(ContainerRootfsTask), sidecars)),
             Stage::sequential(vec![Box::new(VmmSpawnTask)]),
             Stage::sequential(vec![Box::new(GuestConnectTask)]),
             // GuestInit must run - new VM process has fresh guest daemon
@@ -97,6 +148,16 @@ fn get_execution_plan(status: BoxStatus) -> ExecutionPlan<InitCtx> {
     ExecutionPlan::new(stages)
 }
 
+/// Tasks of the rootfs stage: `container`, the guest rootfs and, with
+/// `sidecars`, the sidecar rootfs task.
+fn rootfs_tasks(container: BoxedTask<InitCtx>, sidecars: bool) -> Vec<BoxedTask<InitCtx>> {
+    let mut tasks: Vec<BoxedTask<InitCtx>> = vec![container, Box::new(GuestRootfsTask)];
+    if sidecars {
+        tasks.push(Box::new(SidecarRootfsTask));
+    }
+    tasks
+}
+
 fn box_metrics_from_pipeline(pipeline_metrics: &PipelineMetrics) -> BoxMetricsStorage {
     let mut metrics = BoxMetricsStorage::new();
 
@@ -106,6 +167,12 @@ fn box_metrics_from_pipeline(pipeline_metrics: &PipelineMetrics) -> BoxMetricsSt
     if let Some(duration_ms) = pipeline_metrics.task_duration_ms("container_rootfs_prep") {
         metrics.set_stage_image_prepare(duration_ms);
     }
+    if let Some(duration_ms) = pipeline_metrics.task_duration_ms("container_disks") {
+        let config_ms = pipeline_metrics
+            .task_duration_ms("container_config")
+            .unwrap_or(0);
+        metrics.set_stage_image_prepare(duration_ms + config_ms);
+    }
     if let Some(duration_ms) = pipeline_metrics.task_duration_ms("guest_rootfs_init") {
         metrics.set_stage_guest_rootfs(duration_ms);
     }
@@ -115,17 +182,31 @@ fn box_metrics_from_pipeline(pipeline_metrics: &PipelineMetrics) -> BoxMetricsSt
     if let Some(duration_ms) = pipeline_metrics.task_duration_ms("vmm_attach") {
         metrics.set_stage_box_spawn(duration_ms);
     }
-    if let Some(_duration_ms) = pipeline_metrics.task_duration_ms("guest_connect") {
-        // Track guest connection time
-        // Could add a new metric field if needed
-    }
     if let Some(duration_ms) = pipeline_metrics.task_duration_ms("guest_init") {
         metrics.set_stage_container_init(duration_ms);
     }
+    metrics.set_init_stages(init_stages_from_pipeline(pipeline_metrics));
 
     metrics
 }
 
+/// Flatten pipeline metrics into per-task timings, in execution order.
+fn init_stages_from_pipeline(pipeline_metrics: &PipelineMetrics) -> Vec<StageTiming> {
+    pipeline_metrics
+        .tasks()
+        .map(|task| StageTiming {
+            name: task.name.clone(),
+            duration_ms: task.duration_ms,
+            outcome: match task.outcome {
+                TaskOutcome::Succeeded => StageOutcome::Succeeded,
+                TaskOutcome::Failed => StageOutcome::Failed,
+                TaskOutcome::Cancelled => StageOutcome::Cancelled,
+                TaskOutcome::Skipped => StageOutcome::Skipped,
+            },
+        })
+        .collect()
+}
+
 /// Builds and initializes box components.
 ///
 /// # Example
@@ -189,15 +270,31 @@ impl BoxBuilder {
         let reuse_rootfs = status == BoxStatus::Stopped;
         let skip_guest_wait = status == BoxStatus::Running;
 
+        let engine_kind = config.engine_kind;
+        let rootfs_cached = !skip_guest_wait
+            && tasks::rootfs_cached(&runtime, &config.options.rootfs, reuse_rootfs).await;
+        let sidecars = !config.options.sidecars.is_empty();
         let ctx = InitPipelineContext::new(config, runtime.clone(), reuse_rootfs, skip_guest_wait);
         let ctx = Arc::new(Mutex::new(ctx));
 
         // Note: Guard stays armed until caller disarms it after DB persist succeeds.
         // This ensures cleanup happens even if operations after build() fail.
 
-        let plan = get_execution_plan(status);
+        let plan = get_execution_plan(status, engine_kind, rootfs_cached, sidecars);
         let pipeline = PipelineBuilder::from_plan(plan);
-        let pipeline_metrics = PipelineExecutor::execute(pipeline, Arc::clone(&ctx)).await?;
+        let (pipeline_metrics, result) =
+            PipelineExecutor::execute(pipeline, Arc::clone(&ctx)).await;
+        if let Err(e) = result {
+            for stage in init_stages_from_pipeline(&pipeline_metrics) {
+                tracing::warn!(
+                    task = %stage.name,
+                    duration_ms = stage.duration_ms,
+                    outcome = ?stage.outcome,
+                    "Box initialization task"
+                );
+            }
+            return Err(e);
+        }
 
         let mut ctx = ctx.lock().await;
         let total_create_duration_ms = total_start.elapsed().as_millis();
@@ -208,6 +305,17 @@ impl BoxBuilder {
 
         let mut metrics = box_metrics_from_pipeline(&pipeline_metrics);
         metrics.set_total_create_duration(total_create_duration_ms);
+        metrics.set_create_retries(ctx.retries);
+        // Waiting for the ready signal covers kernel boot and agent startup;
+        // a reattach does not wait.
+        if !skip_guest_wait
+            && let Some(duration_ms) = pipeline_metrics.task_duration_ms("guest_connect")
+        {
+            metrics.set_guest_boot_duration(duration_ms);
+        }
+        if let Some(boot_phases) = ctx.boot_phases.take() {
+            metrics.set_boot_phases(boot_phases);
+        }
 
         metrics.log_init_stages();
 
@@ -241,6 +349,11 @@ impl BoxBuilder {
 
         #[cfg(target_os = "linux")]
         let bind_mount = ctx.bind_mount.take();
+        let published_ports = ctx.published_ports.take();
+        let process = ctx
+            .container_image_config
+            .as_ref()
+            .map(ContainerProcess::from);
 
         // Take the guard out of context, replacing with a disarmed placeholder.
         // The caller is responsible for disarming the returned guard after all
@@ -258,7 +371,9 @@ impl BoxBuilder {
             guest_disk,
             #[cfg(target_os = "linux")]
             bind_mount,
-        );
+        )
+        .with_published_ports(published_ports)
+        .with_process(process);
 
         Ok((live_state, guard))
     }
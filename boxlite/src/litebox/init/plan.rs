@@ -0,0 +1,212 @@
+//! Dry-run planning: what the init pipeline would do, without doing it.
+
+use super::get_execution_plan;
+use super::tasks::{image_port_mappings, requested_ports, rootfs_cached};
+use super::types::resolve_user_volumes;
+use crate::images::ContainerImageConfig;
+use crate::litebox::BoxStatus;
+use crate::runtime::constants::filenames;
+use crate::runtime::constants::vm_defaults::{DEFAULT_CPUS, DEFAULT_MEMORY_MIB};
+use crate::runtime::options::{BoxOptions, NetworkSpec, PortSpec, RootfsSpec};
+use crate::runtime::rt_impl::RuntimeImpl;
+use crate::runtime::types::{BoxPlan, ImagePlan, MountPlan, PlanPaths};
+use crate::vmm::VmmKind;
+use boxlite_shared::errors::BoxliteResult;
+
+/// Stands in for the box ID in planned paths.
+const BOX_ID_PLACEHOLDER: &str = "<box-id>";
+
+/// Plan a new box: validate options, resolve the image from the local cache
+/// and lay out ports, mounts, paths and pipeline stages.
+///
+/// Never touches the network, the database or the box directory.
+pub(crate) async fn plan_box(
+    runtime: &RuntimeImpl,
+    options: &BoxOptions,
+    name: Option<String>,
+    engine_kind: VmmKind,
+) -> BoxliteResult<BoxPlan> {
+    options.sanitize()?;
+
+    let mounts = resolve_user_volumes(&options.volumes)?
+        .into_iter()
+        .map(|volume| MountPlan {
+            tag: volume.tag,
+            host_path: volume.host_path.to_string_lossy().into_owned(),
+            guest_path: volume.guest_path,
+            read_only: volume.read_only,
+        })
+        .collect();
+
+    let (image, rootfs_path, image_config) = match &options.rootfs {
+        RootfsSpec::Image(reference) => {
+            let (candidates, cached) = runtime.image_manager.resolve(reference).await?;
+            let image_config = match &cached {
+                Some(image) => ContainerImageConfig::from_oci_config(&image.load_config().await?)?,
+                None => ContainerImageConfig::default(),
+            };
+            let image = ImagePlan {
+                reference: reference.clone(),
+                candidates,
+                cached: cached.as_ref().map(|image| image.reference().to_string()),
+                manifest_digest: cached.map(|image| image.manifest_digest().to_string()),
+            };
+            (Some(image), None, image_config)
+        }
+        RootfsSpec::RootfsPath(path) => (None, Some(path.clone()), ContainerImageConfig::default()),
+    };
+
+    let box_layout = runtime
+        .layout
+        .box_layout(BOX_ID_PLACEHOLDER, options.isolate_mounts)?;
+    let paths = PlanPaths {
+        box_home: box_layout.root().to_string_lossy().into_owned(),
+        disk: box_layout.disk_path().to_string_lossy().into_owned(),
+        socket: filenames::unix_socket_path(&runtime.layout.boxes_dir(), BOX_ID_PLACEHOLDER)
+            .to_string_lossy()
+            .into_owned(),
+        console_log: box_layout
+            .console_output_path()
+            .to_string_lossy()
+            .into_owned(),
+    };
+
+    let cached = rootfs_cached(runtime, &options.rootfs, false).await;
+    let stages = stage_names(
+        BoxStatus::Configured,
+        engine_kind,
+        cached,
+        !options.sidecars.is_empty(),
+    );
+
+    Ok(BoxPlan {
+        name,
+        engine: format!("{:?}", engine_kind).to_lowercase(),
+        image,
+        rootfs_path,
+        cpus: options.cpus.unwrap_or(DEFAULT_CPUS),
+        memory_mib: options.memory_mib.unwrap_or(DEFAULT_MEMORY_MIB),
+        disk_size_gb: options.disk_size_gb,
+        priority: options.priority,
+        env: crate::runtime::env::redact(&options.env),
+        ports: planned_ports(&image_config, options),
+        mounts,
+        paths,
+        stages,
+    })
+}
+
+/// Task names of each stage of the execution plan.
+fn stage_names(
+    status: BoxStatus,
+    engine_kind: VmmKind,
+    rootfs_cached: bool,
+    sidecars: bool,
+) -> Vec<Vec<String>> {
+    get_execution_plan(status, engine_kind, rootfs_cached, sidecars)
+        .stages()
+        .into_iter()
+        .map(|stage| {
+            stage
+                .tasks
+                .iter()
+                .map(|task| task.name().to_string())
+                .collect()
+        })
+        .collect()
+}
+
+/// Image exposed ports (1:1 or, with `publish_all`, dynamic, unless
+/// overridden) followed by requested ports.
+///
+/// Mirrors the merge done at spawn, minus dynamic host port assignment.
+fn planned_ports(image_config: &ContainerImageConfig, options: &BoxOptions) -> Vec<PortSpec> {
+    if options.network == NetworkSpec::Disabled {
+        return Vec::new();
+    }
+    let requested = requested_ports(options);
+    let mut ports = image_port_mappings(&image_config.tcp_ports(), &requested, options.publish_all);
+    ports.extend(requested);
+    ports
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_planned_ports_merges_image_and_user_ports() {
+        let image_config = ContainerImageConfig {
+            exposed_ports: vec!["8080/tcp".to_string(), "80/tcp".to_string()],
+            ..Default::default()
+        };
+        let options = BoxOptions {
+            ports: vec![PortSpec {
+                host_port: None,
+                guest_port: 8080,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let ports = planned_ports(&image_config, &options);
+        assert_eq!(ports.len(), 2);
+        assert_eq!((ports[0].host_port, ports[0].guest_port), (Some(80), 80));
+        assert_eq!((ports[1].host_port, ports[1].guest_port), (None, 8080));
+    }
+
+    #[test]
+    fn test_planned_ports_publish_all() {
+        let image_config = ContainerImageConfig {
+            exposed_ports: vec![
+                "8080/tcp".to_string(),
+                "80/tcp".to_string(),
+                "53/udp".to_string(),
+            ],
+            ..Default::default()
+        };
+        let options = BoxOptions {
+            ports: vec![PortSpec {
+                host_port: Some(9000),
+                guest_port: 8080,
+                ..Default::default()
+            }],
+            publish_all: true,
+            ..Default::default()
+        };
+
+        let ports = planned_ports(&image_config, &options);
+        assert_eq!(ports.len(), 2);
+        assert_eq!((ports[0].host_port, ports[0].guest_port), (None, 80));
+        assert_eq!(
+            (ports[1].host_port, ports[1].guest_port),
+            (Some(9000), 8080)
+        );
+    }
+
+    #[test]
+    fn test_cached_rootfs_boots_vm_alongside_container_config() {
+        let names = |stages: &[Vec<String>]| stages.concat();
+
+        let full = stage_names(BoxStatus::Configured, VmmKind::Libkrun, false, false);
+        assert_eq!(full[1], vec!["container_rootfs_prep", "guest_rootfs_init"]);
+
+        let fast = stage_names(BoxStatus::Configured, VmmKind::Libkrun, true, false);
+        assert_eq!(fast[1], vec!["container_disks", "guest_rootfs_init"]);
+        assert_eq!(fast[2], vec!["vmm_spawn"]);
+        assert_eq!(fast[3], vec!["guest_connect", "container_config"]);
+        assert_eq!(names(&fast).last().unwrap(), "guest_init");
+
+        let restart = stage_names(BoxStatus::Stopped, VmmKind::Libkrun, true, false);
+        assert_eq!(names(&restart), names(&fast));
+    }
+
+    #[test]
+    fn test_sidecars_prepared_with_rootfs() {
+        for cached in [false, true] {
+            let stages = stage_names(BoxStatus::Configured, VmmKind::Libkrun, cached, true);
+            assert_eq!(stages[1].len(), 3);
+            assert_eq!(stages[1][2], "sidecar_rootfs_prep");
+        }
+    }
+}
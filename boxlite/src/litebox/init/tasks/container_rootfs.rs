@@ -2,20 +2,44 @@
 //!
 //! Pulls container image and prepares container rootfs:
 //! - Disk-based: Creates ext4 disk image from merged layers (fast boot)
+//! - Read-only image: Creates erofs/squashfs image from merged layers, mounted
+//!   by the guest under overlayfs with a per-box upper disk
 //! - Overlayfs: Extracts layers for guest-side overlayfs (flexible)
 //!
 //! For restart (reuse_rootfs=true), opens existing COW disk instead of creating new.
-
-use super::{InitCtx, log_task_error, task_start};
-use crate::disk::{BackingFormat, Disk, DiskFormat, Qcow2Helper, create_ext4_from_dir};
-use crate::images::ContainerImageConfig;
-use crate::litebox::init::types::{ContainerRootfsPrepResult, USE_DISK_ROOTFS, USE_OVERLAYFS};
+//!
+//! A `RootfsPath` that is not an OCI layout is used as the container root as
+//! is: the directory is copied into a per-box base disk once, so the box
+//! never writes to it, and the image config defaults apply.
+//!
+//! Sidecars (`BoxOptions::sidecars`) get the same treatment from
+//! `SidecarRootfsTask`: an ext4 base disk of their image and a COW disk each.
+//!
+//! When the rootfs is already cached, the work is split in two:
+//! `ContainerDisksTask` makes the disks the VM needs at spawn, and
+//! `ContainerConfigTask` loads the image config while the VM boots.
+
+use super::{InitCtx, log_task_error, retry_transient, task_start};
+use crate::disk::constants::qcow2::DEFAULT_DISK_SIZE_GB;
+use crate::disk::{
+    BackingFormat, Disk, DiskFormat, Qcow2Helper, create_ext4_from_dir,
+    create_readonly_image_from_dir, detect_readonly_format,
+};
+use crate::images::{ContainerImageConfig, ImageObject};
+use crate::litebox::init::types::{
+    ContainerRootfsPrepResult, SidecarRootfs, USE_DISK_ROOTFS, USE_OVERLAYFS,
+};
 use crate::pipeline::PipelineTask;
 use crate::runtime::layout::BoxFilesystemLayout;
-use crate::runtime::options::RootfsSpec;
-use crate::runtime::rt_impl::SharedRuntimeImpl;
+use crate::runtime::options::{BoxOptions, RootfsFormat, RootfsSpec, SidecarSpec};
+use crate::runtime::rt_impl::{RuntimeImpl, SharedRuntimeImpl};
 use async_trait::async_trait;
 use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+use std::path::{Path, PathBuf};
+
+/// File that marks a `RootfsPath` as an OCI image layout rather than a raw
+/// root filesystem directory.
+const OCI_LAYOUT_FILE: &str = "oci-layout";
 
 pub struct ContainerRootfsTask;
 
@@ -24,130 +48,257 @@ impl PipelineTask<InitCtx> for ContainerRootfsTask {
     async fn run(self: Box<Self>, ctx: InitCtx) -> BoxliteResult<()> {
         let task_name = self.name();
         let box_id = task_start(&ctx, task_name).await;
+        let (options, runtime, layout, reuse_rootfs) = gather(&ctx).await?;
+
+        // Only the image pull fails transiently, and it fails before any disk
+        // is created, so the whole step can be re-run.
+        let (container_image_config, disk, image_disk) =
+            retry_transient(&ctx, &box_id, task_name, || {
+                run_container_rootfs(&options, &runtime, &layout, reuse_rootfs)
+            })
+            .await
+            .inspect_err(|e| log_task_error(&box_id, task_name, e))?;
 
-        let (
-            rootfs_spec,
-            env,
-            runtime,
-            layout,
-            reuse_rootfs,
-            disk_size_gb,
-            entrypoint_override,
-            cmd_override,
-            user_override,
-        ) = {
-            let ctx = ctx.lock().await;
-            let layout = ctx
-                .layout
-                .clone()
-                .ok_or_else(|| BoxliteError::Internal("filesystem task must run first".into()))?;
-            (
-                ctx.config.options.rootfs.clone(),
-                ctx.config.options.env.clone(),
-                ctx.runtime.clone(),
-                layout,
-                ctx.reuse_rootfs,
-                ctx.config.options.disk_size_gb,
-                ctx.config.options.entrypoint.clone(),
-                ctx.config.options.cmd.clone(),
-                ctx.config.options.user.clone(),
-            )
-        };
+        let mut ctx = ctx.lock().await;
+        ctx.container_image_config = Some(container_image_config);
+        ctx.container_disk = Some(disk);
+        ctx.container_image_disk = image_disk;
+
+        Ok(())
+    }
 
-        let (container_image_config, disk) = run_container_rootfs(
-            &rootfs_spec,
-            &env,
-            &runtime,
-            &layout,
-            reuse_rootfs,
-            disk_size_gb,
-            entrypoint_override.as_deref(),
-            cmd_override.as_deref(),
-            user_override.as_deref(),
-        )
+    fn name(&self) -> &str {
+        "container_rootfs_prep"
+    }
+}
+
+/// Disk half of [`ContainerRootfsTask`], for images whose rootfs is cached
+/// (see [`rootfs_cached`]).
+///
+/// Creates (or, on restart, reopens) the box's disks so the VM can be
+/// spawned; [`ContainerConfigTask`] loads the image config while it boots.
+pub struct ContainerDisksTask;
+
+#[async_trait]
+impl PipelineTask<InitCtx> for ContainerDisksTask {
+    async fn run(self: Box<Self>, ctx: InitCtx) -> BoxliteResult<()> {
+        let task_name = self.name();
+        let box_id = task_start(&ctx, task_name).await;
+        let (options, runtime, layout, reuse_rootfs) = gather(&ctx).await?;
+
+        let (options, runtime, layout) = (&options, &runtime, &layout);
+        let (image, disk, image_disk) = retry_transient(&ctx, &box_id, task_name, || async move {
+            let image = load_image(runtime, &options.rootfs).await?;
+            let (disk, image_disk) =
+                container_disks(runtime, &image, layout, reuse_rootfs, options.disk_size_gb)
+                    .await?;
+            Ok((image, disk, image_disk))
+        })
         .await
         .inspect_err(|e| log_task_error(&box_id, task_name, e))?;
 
         let mut ctx = ctx.lock().await;
-        ctx.container_image_config = Some(container_image_config);
+        ctx.container_image = Some(image);
         ctx.container_disk = Some(disk);
+        ctx.container_image_disk = image_disk;
 
         Ok(())
     }
 
     fn name(&self) -> &str {
-        "container_rootfs_prep"
+        "container_disks"
     }
 }
 
-/// Pull image and prepare rootfs, then create or reuse COW disk.
-#[allow(clippy::too_many_arguments)]
-async fn run_container_rootfs(
-    rootfs_spec: &RootfsSpec,
-    env: &[(String, String)],
+/// Config half of [`ContainerRootfsTask`]: the image config with the box's
+/// env and entrypoint, cmd and user overrides applied.
+pub struct ContainerConfigTask;
+
+#[async_trait]
+impl PipelineTask<InitCtx> for ContainerConfigTask {
+    async fn run(self: Box<Self>, ctx: InitCtx) -> BoxliteResult<()> {
+        let task_name = self.name();
+        let box_id = task_start(&ctx, task_name).await;
+        let (image, options) = {
+            let ctx = ctx.lock().await;
+            let image = ctx.container_image.clone().ok_or_else(|| {
+                BoxliteError::Internal("container_disks task must run first".into())
+            })?;
+            (image, ctx.config.options.clone())
+        };
+
+        let container_image_config = container_config(&image, &options)
+            .await
+            .inspect_err(|e| log_task_error(&box_id, task_name, e))?;
+
+        ctx.lock().await.container_image_config = Some(container_image_config);
+
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "container_config"
+    }
+}
+
+/// Rootfs disks and configs of the box's sidecars.
+///
+/// Runs next to the main container's rootfs task; on restart the sidecars'
+/// disks are reused, but their images are still loaded for the config.
+pub struct SidecarRootfsTask;
+
+#[async_trait]
+impl PipelineTask<InitCtx> for SidecarRootfsTask {
+    async fn run(self: Box<Self>, ctx: InitCtx) -> BoxliteResult<()> {
+        let task_name = self.name();
+        let box_id = task_start(&ctx, task_name).await;
+        let (options, runtime, layout, reuse_rootfs) = gather(&ctx).await?;
+        let ids = { ctx.lock().await.config.sidecars.clone() };
+
+        let mut sidecars = Vec::with_capacity(options.sidecars.len());
+        for spec in &options.sidecars {
+            let container_id = ids
+                .get(&spec.name)
+                .map(|container| container.id.clone())
+                .ok_or_else(|| {
+                    BoxliteError::Internal(format!("sidecar {} has no container id", spec.name))
+                })?;
+            let (config, disk) = retry_transient(&ctx, &box_id, task_name, || {
+                prepare_sidecar(&runtime, spec, &layout, reuse_rootfs)
+            })
+            .await
+            .inspect_err(|e| log_task_error(&box_id, task_name, e))?;
+            sidecars.push(SidecarRootfs {
+                name: spec.name.clone(),
+                container_id,
+                config,
+                disk,
+            });
+        }
+
+        ctx.lock().await.sidecars = sidecars;
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "sidecar_rootfs_prep"
+    }
+}
+
+/// Pull a sidecar's image and create (or reopen) its COW disk.
+///
+/// A box restarted after an export or an older clone may lack the disk; it
+/// then starts over from the image.
+async fn prepare_sidecar(
     runtime: &SharedRuntimeImpl,
+    spec: &SidecarSpec,
     layout: &BoxFilesystemLayout,
     reuse_rootfs: bool,
-    disk_size_gb: Option<u64>,
-    entrypoint_override: Option<&[String]>,
-    cmd_override: Option<&[String]>,
-    user_override: Option<&str>,
 ) -> BoxliteResult<(ContainerImageConfig, Disk)> {
-    let disk_path = layout.disk_path();
+    let image = pull_image(runtime, &spec.image).await?;
 
-    // For restart, reuse existing COW disk
-    if reuse_rootfs {
-        tracing::info!(
-            disk_path = %disk_path.display(),
-            "Restart mode: reusing existing container rootfs disk"
-        );
+    let image_config = image.load_config().await?;
+    let mut config = ContainerImageConfig::from_oci_config(&image_config)?;
+    if !spec.env.is_empty() {
+        config.merge_env(spec.env.clone());
+    }
+    config.apply_overrides(spec.entrypoint.as_deref(), spec.cmd.as_deref(), None, None)?;
 
-        if !disk_path.exists() {
-            return Err(BoxliteError::Storage(format!(
-                "Cannot restart: container rootfs disk not found at {}",
-                disk_path.display()
-            )));
-        }
+    let disk_path = layout.sidecar_disk_path(&spec.name);
+    if reuse_rootfs && disk_path.exists() {
+        return Ok((config, Disk::new(disk_path, DiskFormat::Qcow2, true)));
+    }
 
-        let disk = Disk::new(disk_path.clone(), DiskFormat::Qcow2, true);
+    let ContainerRootfsPrepResult::DiskImage {
+        base_disk_path,
+        disk_size,
+    } = prepare_disk_rootfs(runtime, &image, DiskFormat::Ext4).await?
+    else {
+        return Err(BoxliteError::Internal(
+            "ext4 rootfs preparation returned no disk image".into(),
+        ));
+    };
+    let disk = create_cow_child(&base_disk_path, disk_size, &disk_path, None)?;
+    tracing::info!(
+        sidecar = %spec.name,
+        image = %spec.image,
+        disk = %disk_path.display(),
+        "Created sidecar rootfs COW disk"
+    );
+    Ok((config, disk))
+}
 
-        // Load container config
-        let image = match rootfs_spec {
-            RootfsSpec::Image(r) => pull_image(runtime, r).await?,
-            RootfsSpec::RootfsPath(path) => {
-                let bundle_dir = std::path::Path::new(path);
-
-                if !bundle_dir.exists() {
-                    return Err(BoxliteError::Storage(format!(
-                        "Rootfs path does not exist: {}",
-                        path
-                    )));
-                }
-
-                runtime
-                    .image_manager
-                    .load_from_local(bundle_dir.to_path_buf(), format!("local:{}", path))
-                    .await?
-            }
-        };
-        let image_config = image.load_config().await?;
-        let mut container_image_config = ContainerImageConfig::from_oci_config(&image_config)?;
-        if !env.is_empty() {
-            container_image_config.merge_env(env.to_vec());
-        }
-        apply_user_overrides(
-            &mut container_image_config,
-            entrypoint_override,
-            cmd_override,
-            user_override,
-        );
+/// Inputs shared by the container rootfs tasks.
+async fn gather(
+    ctx: &InitCtx,
+) -> BoxliteResult<(BoxOptions, SharedRuntimeImpl, BoxFilesystemLayout, bool)> {
+    let ctx = ctx.lock().await;
+    let layout = ctx
+        .layout
+        .clone()
+        .ok_or_else(|| BoxliteError::Internal("filesystem task must run first".into()))?;
+    Ok((
+        ctx.config.options.clone(),
+        ctx.runtime.clone(),
+        layout,
+        ctx.reuse_rootfs,
+    ))
+}
 
-        return Ok((container_image_config, disk));
+/// Container disk plus, for read-only rootfs formats, the image disk and its format.
+type ContainerDisks = (Disk, Option<(Disk, DiskFormat)>);
+
+/// Whether the rootfs for `rootfs` is ready without pulling or building
+/// anything: the image is in the local store and, unless the box is
+/// restarting with its own disks, its base disk is cached.
+///
+/// Such boxes use the fast execution plan, which boots the VM while the
+/// image config is loaded. Only looks at the local store.
+pub(crate) async fn rootfs_cached(
+    runtime: &RuntimeImpl,
+    rootfs: &RootfsSpec,
+    reuse_rootfs: bool,
+) -> bool {
+    let RootfsSpec::Image(image_ref) = rootfs else {
+        return false;
+    };
+    if !USE_DISK_ROOTFS {
+        return false;
     }
+    let Ok((_, Some(image))) = runtime.image_manager.resolve(image_ref).await else {
+        return false;
+    };
+    reuse_rootfs || cached_base_disk(&image, base_disk_format(runtime)).is_some()
+}
+
+/// Pull image and prepare rootfs, then create or reuse COW disk.
+async fn run_container_rootfs(
+    options: &BoxOptions,
+    runtime: &SharedRuntimeImpl,
+    layout: &BoxFilesystemLayout,
+    reuse_rootfs: bool,
+) -> BoxliteResult<(ContainerImageConfig, Disk, Option<(Disk, DiskFormat)>)> {
+    if let RootfsSpec::RootfsPath(path) = &options.rootfs
+        && !Path::new(path).join(OCI_LAYOUT_FILE).exists()
+    {
+        return rootfs_dir_container(runtime, Path::new(path), options, layout, reuse_rootfs).await;
+    }
+
+    let image = load_image(runtime, &options.rootfs).await?;
+    let container_image_config = container_config(&image, options).await?;
+    let (disk, image_disk) =
+        container_disks(runtime, &image, layout, reuse_rootfs, options.disk_size_gb).await?;
+
+    Ok((container_image_config, disk, image_disk))
+}
 
-    // Fresh start: pull or load image
-    let image = match rootfs_spec {
-        RootfsSpec::Image(r) => pull_image(runtime, r).await?,
+/// Pull the image, or load the local rootfs bundle.
+async fn load_image(
+    runtime: &SharedRuntimeImpl,
+    rootfs_spec: &RootfsSpec,
+) -> BoxliteResult<ImageObject> {
+    match rootfs_spec {
+        RootfsSpec::Image(r) => pull_image(runtime, r).await,
         RootfsSpec::RootfsPath(path) => {
             let bundle_dir = std::path::Path::new(path);
 
@@ -161,41 +312,215 @@ async fn run_container_rootfs(
             runtime
                 .image_manager
                 .load_from_local(bundle_dir.to_path_buf(), format!("local:{}", path))
-                .await?
+                .await
         }
-    };
+    }
+}
+
+/// Create the box's COW disks from the image's base disk, or reopen them
+/// on restart.
+async fn container_disks(
+    runtime: &SharedRuntimeImpl,
+    image: &ImageObject,
+    layout: &BoxFilesystemLayout,
+    reuse_rootfs: bool,
+    disk_size_gb: Option<u64>,
+) -> BoxliteResult<ContainerDisks> {
+    let disk_path = layout.disk_path();
+
+    // For restart, reuse existing COW disk
+    if reuse_rootfs {
+        tracing::info!(
+            disk_path = %disk_path.display(),
+            "Restart mode: reusing existing container rootfs disk"
+        );
+
+        if !disk_path.exists() {
+            return Err(BoxliteError::Storage(format!(
+                "Cannot restart: container rootfs disk not found at {}",
+                disk_path.display()
+            )));
+        }
+
+        let disk = Disk::new(disk_path.clone(), DiskFormat::Qcow2, true);
+        let image_disk = existing_image_disk(layout)?;
+        return Ok((disk, image_disk));
+    }
 
     // Prepare rootfs from image
     let rootfs_result = if USE_DISK_ROOTFS {
-        prepare_disk_rootfs(runtime, &image).await?
+        prepare_disk_rootfs(runtime, image, base_disk_format(runtime)).await?
     } else if USE_OVERLAYFS {
-        prepare_overlayfs_layers(&image).await?
+        prepare_overlayfs_layers(image).await?
     } else {
         return Err(BoxliteError::Storage(
             "Merged rootfs not supported. Use overlayfs or disk rootfs.".into(),
         ));
     };
 
+    create_cow_disk(&rootfs_result, layout, disk_size_gb)
+}
+
+/// Disks and config for a raw root filesystem directory.
+///
+/// The directory is copied into a base disk under the snapshots directory
+/// (which, like a clone's snapshot, outlives the box) and the box gets a COW
+/// disk on top of it.
+async fn rootfs_dir_container(
+    runtime: &SharedRuntimeImpl,
+    rootfs_dir: &Path,
+    options: &BoxOptions,
+    layout: &BoxFilesystemLayout,
+    reuse_rootfs: bool,
+) -> BoxliteResult<(ContainerImageConfig, Disk, Option<(Disk, DiskFormat)>)> {
+    let mut container_image_config = ContainerImageConfig::default();
+    if !options.env.is_empty() {
+        container_image_config.merge_env(options.env.clone());
+    }
+    container_image_config.apply_overrides(
+        options.entrypoint.as_deref(),
+        options.cmd.as_deref(),
+        options.user.as_deref(),
+        options.working_dir.as_deref(),
+    )?;
+
+    if reuse_rootfs {
+        let disk_path = layout.disk_path();
+        if !disk_path.exists() {
+            return Err(BoxliteError::Storage(format!(
+                "Cannot restart: container rootfs disk not found at {}",
+                disk_path.display()
+            )));
+        }
+        let disk = Disk::new(disk_path, DiskFormat::Qcow2, true);
+        return Ok((container_image_config, disk, existing_image_disk(layout)?));
+    }
+
+    validate_rootfs_dir(rootfs_dir)?;
+
+    let format = base_disk_format(runtime);
+    let box_id = layout
+        .root()
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let base_path =
+        runtime
+            .layout
+            .snapshots_dir()
+            .join(format!("{}-rootfs.{}", box_id, format.as_str()));
+    std::fs::create_dir_all(runtime.layout.snapshots_dir())
+        .map_err(|e| BoxliteError::Storage(format!("Failed to create snapshots dir: {}", e)))?;
+
+    tracing::info!(
+        rootfs_dir = %rootfs_dir.display(),
+        base_disk = %base_path.display(),
+        "Creating {} disk image from rootfs directory",
+        format.as_str()
+    );
+    let source = rootfs_dir.to_path_buf();
+    let target = base_path.clone();
+    let base_disk = tokio::task::spawn_blocking(move || {
+        if format != DiskFormat::Ext4 {
+            create_readonly_image_from_dir(&source, &target, format)
+        } else {
+            create_ext4_from_dir(&source, &target)
+        }
+    })
+    .await
+    .map_err(|e| BoxliteError::Internal(format!("Disk creation task failed: {}", e)))??;
+    let base_path = base_disk.leak();
+
+    let disk_size = std::fs::metadata(&base_path)
+        .map(|m| m.len())
+        .unwrap_or(64 * 1024 * 1024);
+    let (disk, image_disk) = create_cow_disk(
+        &prep_result(base_path.clone(), format, disk_size),
+        layout,
+        options.disk_size_gb,
+    )
+    .inspect_err(|_| {
+        let _ = std::fs::remove_file(&base_path);
+    })?;
+
+    Ok((container_image_config, disk, image_disk))
+}
+
+/// Check that `rootfs_dir` can serve as a container root filesystem.
+fn validate_rootfs_dir(rootfs_dir: &Path) -> BoxliteResult<()> {
+    if !rootfs_dir.is_dir() {
+        return Err(BoxliteError::Config(format!(
+            "Rootfs path is not a directory: {}",
+            rootfs_dir.display()
+        )));
+    }
+    let mut entries = std::fs::read_dir(rootfs_dir).map_err(|e| {
+        BoxliteError::Config(format!(
+            "Cannot read rootfs directory {}: {}",
+            rootfs_dir.display(),
+            e
+        ))
+    })?;
+    if entries.next().is_none() {
+        return Err(BoxliteError::Config(format!(
+            "Rootfs directory is empty: {}",
+            rootfs_dir.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Image config with the box's env merged and its overrides applied.
+async fn container_config(
+    image: &ImageObject,
+    options: &BoxOptions,
+) -> BoxliteResult<ContainerImageConfig> {
     let image_config = image.load_config().await?;
     let mut container_image_config = ContainerImageConfig::from_oci_config(&image_config)?;
 
-    if !env.is_empty() {
-        container_image_config.merge_env(env.to_vec());
+    if !options.env.is_empty() {
+        container_image_config.merge_env(options.env.clone());
     }
-    apply_user_overrides(
-        &mut container_image_config,
-        entrypoint_override,
-        cmd_override,
-        user_override,
-    );
+    container_image_config.apply_overrides(
+        options.entrypoint.as_deref(),
+        options.cmd.as_deref(),
+        options.user.as_deref(),
+        options.working_dir.as_deref(),
+    )?;
+
+    Ok(container_image_config)
+}
 
-    let disk = create_cow_disk(&rootfs_result, layout, disk_size_gb)?;
+/// Image disk of a box created with a read-only rootfs format, if any.
+///
+/// The image format is read from the base of its backing chain, so it
+/// survives clones and archive imports that rename the backing files.
+fn existing_image_disk(layout: &BoxFilesystemLayout) -> BoxliteResult<Option<(Disk, DiskFormat)>> {
+    let image_path = layout.image_disk_path();
+    if !image_path.exists() {
+        return Ok(None);
+    }
 
-    Ok((container_image_config, disk))
+    let chain = Qcow2Helper::backing_chain(&image_path)?;
+    let base = chain.last().unwrap_or(&image_path);
+    let format = detect_readonly_format(base)?.ok_or_else(|| {
+        BoxliteError::Storage(format!(
+            "Rootfs image {} is neither erofs nor squashfs",
+            base.display()
+        ))
+    })?;
+
+    Ok(Some((
+        Disk::new(image_path, DiskFormat::Qcow2, true),
+        format,
+    )))
 }
 
 /// Create COW disk from base rootfs.
 ///
+/// For a read-only image, returns an empty upper disk (formatted by the guest)
+/// plus a COW overlay of the image, attached read-only.
+///
 /// # Arguments
 /// * `rootfs_result` - Result of rootfs preparation (disk image or layers)
 /// * `layout` - Box filesystem layout for disk paths
@@ -205,43 +530,58 @@ fn create_cow_disk(
     rootfs_result: &ContainerRootfsPrepResult,
     layout: &crate::runtime::layout::BoxFilesystemLayout,
     disk_size_gb: Option<u64>,
-) -> BoxliteResult<Disk> {
+) -> BoxliteResult<(Disk, Option<(Disk, DiskFormat)>)> {
     match rootfs_result {
         ContainerRootfsPrepResult::DiskImage {
             base_disk_path,
             disk_size: base_disk_size,
         } => {
-            // Calculate target disk size: use max of user-specified size and base disk size
-            let target_disk_size = if let Some(size_gb) = disk_size_gb {
-                let user_size_bytes = size_gb * 1024 * 1024 * 1024;
-                std::cmp::max(user_size_bytes, *base_disk_size)
-            } else {
-                *base_disk_size
-            };
-
-            let qcow2_helper = Qcow2Helper::new();
-            let cow_disk_path = layout.disk_path();
-            let temp_disk = qcow2_helper.create_cow_child_disk(
+            let disk = create_cow_child(
                 base_disk_path,
-                BackingFormat::Raw,
-                &cow_disk_path,
-                target_disk_size,
+                *base_disk_size,
+                &layout.disk_path(),
+                disk_size_gb,
             )?;
+            Ok((disk, None))
+        }
+        ContainerRootfsPrepResult::ReadOnlyImage {
+            image_path,
+            format,
+            image_size,
+        } => {
+            let qcow2_helper = Qcow2Helper::new();
 
-            // Make disk persistent so it survives stop/restart
-            // create_cow_child_disk returns non-persistent disk, but we want to preserve
-            // COW disks across box restarts (only delete on remove)
-            let disk_path = temp_disk.leak(); // Prevent cleanup
-            let disk = Disk::new(disk_path, DiskFormat::Qcow2, true); // persistent=true
+            // Per-box reference to the shared image (keeps the backing chain
+            // in one place for restart, clone and export)
+            let image_disk_path = layout.image_disk_path();
+            let image_disk = qcow2_helper
+                .create_cow_child_disk(
+                    image_path,
+                    BackingFormat::Raw,
+                    &image_disk_path,
+                    image_size.next_multiple_of(512),
+                )?
+                .leak();
+
+            let upper_size = disk_size_gb.unwrap_or(DEFAULT_DISK_SIZE_GB) * 1024 * 1024 * 1024;
+            let upper = qcow2_helper
+                .create_disk_with_size(&layout.disk_path(), upper_size, true)
+                .inspect_err(|_| {
+                    let _ = std::fs::remove_file(&image_disk);
+                })?;
 
             tracing::info!(
-                cow_disk = %cow_disk_path.display(),
-                base_disk = %base_disk_path.display(),
-                virtual_size_mb = target_disk_size / (1024 * 1024),
-                "Created container rootfs COW overlay (persistent)"
+                image = %image_path.display(),
+                format = format.as_str(),
+                upper_disk = %upper.path().display(),
+                upper_size_mb = upper_size / (1024 * 1024),
+                "Created container rootfs image overlay and upper disk (persistent)"
             );
 
-            Ok(disk)
+            Ok((
+                upper,
+                Some((Disk::new(image_disk, DiskFormat::Qcow2, true), *format)),
+            ))
         }
         ContainerRootfsPrepResult::Layers { .. } => Err(BoxliteError::Internal(
             "Layers mode requires overlayfs - disk creation not applicable".into(),
@@ -252,21 +592,65 @@ fn create_cow_disk(
     }
 }
 
-/// Apply user overrides to container image config (entrypoint, CMD, and user).
-fn apply_user_overrides(
-    config: &mut ContainerImageConfig,
-    entrypoint_override: Option<&[String]>,
-    cmd_override: Option<&[String]>,
-    user_override: Option<&str>,
-) {
-    if let Some(ep) = entrypoint_override {
-        config.entrypoint = ep.to_vec();
-    }
-    if let Some(cmd) = cmd_override {
-        config.cmd = cmd.to_vec();
+/// Create a persistent COW disk at `cow_disk_path` on a raw base disk.
+///
+/// Its virtual size is the larger of `disk_size_gb` and the base disk's.
+fn create_cow_child(
+    base_disk_path: &Path,
+    base_disk_size: u64,
+    cow_disk_path: &Path,
+    disk_size_gb: Option<u64>,
+) -> BoxliteResult<Disk> {
+    // Calculate target disk size: use max of user-specified size and base disk size
+    let target_disk_size = if let Some(size_gb) = disk_size_gb {
+        let user_size_bytes = size_gb * 1024 * 1024 * 1024;
+        std::cmp::max(user_size_bytes, base_disk_size)
+    } else {
+        base_disk_size
+    };
+
+    let qcow2_helper = Qcow2Helper::new();
+    let temp_disk = qcow2_helper.create_cow_child_disk(
+        base_disk_path,
+        BackingFormat::Raw,
+        cow_disk_path,
+        target_disk_size,
+    )?;
+
+    // Make disk persistent so it survives stop/restart
+    // create_cow_child_disk returns non-persistent disk, but we want to preserve
+    // COW disks across box restarts (only delete on remove)
+    let disk_path = temp_disk.leak(); // Prevent cleanup
+    let disk = Disk::new(disk_path, DiskFormat::Qcow2, true); // persistent=true
+
+    tracing::info!(
+        cow_disk = %cow_disk_path.display(),
+        base_disk = %base_disk_path.display(),
+        virtual_size_mb = target_disk_size / (1024 * 1024),
+        "Created container rootfs COW overlay (persistent)"
+    );
+
+    Ok(disk)
+}
+
+/// Pull `image_ref` and build its cached base disk, as the first box
+/// created from it would. Later boxes from the image skip both steps.
+pub(crate) async fn warm_image(runtime: &SharedRuntimeImpl, image_ref: &str) -> BoxliteResult<()> {
+    let image = pull_image(runtime, image_ref).await?;
+    if USE_DISK_ROOTFS {
+        prepare_disk_rootfs(runtime, &image, base_disk_format(runtime)).await?;
+    } else {
+        image.layer_extracted().await?;
     }
-    if let Some(user) = user_override {
-        config.user = user.to_string();
+    Ok(())
+}
+
+/// Format of the cached base disk, from the runtime's rootfs format.
+fn base_disk_format(runtime: &RuntimeImpl) -> DiskFormat {
+    match runtime.rootfs_format {
+        RootfsFormat::Ext4 => DiskFormat::Ext4,
+        RootfsFormat::Erofs => DiskFormat::Erofs,
+        RootfsFormat::Squashfs => DiskFormat::Squashfs,
     }
 }
 
@@ -318,37 +702,37 @@ async fn prepare_overlayfs_layers(
 /// Prepare disk-based rootfs from image layers.
 ///
 /// This function:
-/// 1. Checks if a cached base disk image exists for this image
-/// 2. If not, merges layers and creates an ext4 disk image
+/// 1. Checks if a cached base disk image of `format` exists for this image
+/// 2. If not, merges layers and creates an ext4 (or erofs/squashfs) image
 /// 3. Returns the path to the base disk for COW overlay creation
 async fn prepare_disk_rootfs(
     runtime: &crate::runtime::SharedRuntimeImpl,
     image: &crate::images::ImageObject,
+    format: DiskFormat,
 ) -> BoxliteResult<ContainerRootfsPrepResult> {
+    let read_only = format != DiskFormat::Ext4;
+
     // Check if we already have a cached disk image for this image
-    if let Some(disk) = image.disk_image() {
-        let disk_path = disk.path().to_path_buf();
+    if let Some(disk_path) = cached_base_disk(image, format) {
         let disk_size = std::fs::metadata(&disk_path)
             .map(|m| m.len())
             .unwrap_or(64 * 1024 * 1024);
 
         tracing::info!(
-            "Using cached disk image: {} ({}MB)",
+            "Using cached {} disk image: {} ({}MB)",
+            format.as_str(),
             disk_path.display(),
             disk_size / (1024 * 1024)
         );
 
-        // Leak the disk to prevent cleanup (it's a cached persistent disk)
-        let _ = disk.leak();
-
-        return Ok(ContainerRootfsPrepResult::DiskImage {
-            base_disk_path: disk_path,
-            disk_size,
-        });
+        return Ok(prep_result(disk_path, format, disk_size));
     }
 
     // No cached disk - we need to create one from layers
-    tracing::info!("Creating disk image from layers (first run for this image)");
+    tracing::info!(
+        "Creating {} disk image from layers (first run for this image)",
+        format.as_str()
+    );
 
     // Step 1: Extract and merge layers using RootfsBuilder
     let layer_paths = image.layer_extracted().await?;
@@ -359,8 +743,8 @@ async fn prepare_disk_rootfs(
         ));
     }
 
-    // Create a temporary directory for merged rootfs within boxlite home (same filesystem as destination)
-    let temp_base = runtime.layout.temp_dir();
+    // Create a temporary directory for merged rootfs within the image cache (same filesystem as destination)
+    let temp_base = runtime.layout.image_temp_dir();
     let temp_dir = tempfile::tempdir_in(&temp_base)
         .map_err(|e| BoxliteError::Storage(format!("Failed to create temp directory: {}", e)))?;
     let merged_path = temp_dir.path().join("merged");
@@ -374,23 +758,29 @@ async fn prepare_disk_rootfs(
         layer_paths.len()
     );
 
-    // Step 2: Create ext4 disk image from merged rootfs
-    let temp_disk_path = temp_dir.path().join("rootfs.ext4");
+    // Step 2: Create disk image from merged rootfs
+    let temp_disk_path = temp_dir.path().join(format!("rootfs.{}", format.as_str()));
 
     // Use blocking spawn for sync disk creation
     let merged_clone = merged_path.clone();
     let disk_path_clone = temp_disk_path.clone();
-    let temp_disk =
-        tokio::task::spawn_blocking(move || create_ext4_from_dir(&merged_clone, &disk_path_clone))
-            .await
-            .map_err(|e| BoxliteError::Internal(format!("Disk creation task failed: {}", e)))??;
+    let temp_disk = tokio::task::spawn_blocking(move || {
+        if read_only {
+            create_readonly_image_from_dir(&merged_clone, &disk_path_clone, format)
+        } else {
+            create_ext4_from_dir(&merged_clone, &disk_path_clone)
+        }
+    })
+    .await
+    .map_err(|e| BoxliteError::Internal(format!("Disk creation task failed: {}", e)))??;
 
     let disk_size = std::fs::metadata(temp_disk.path())
         .map(|m| m.len())
         .unwrap_or(64 * 1024 * 1024);
 
     tracing::info!(
-        "Created ext4 disk image: {} ({}MB)",
+        "Created {} disk image: {} ({}MB)",
+        format.as_str(),
         temp_disk.path().display(),
         disk_size / (1024 * 1024)
     );
@@ -406,8 +796,52 @@ async fn prepare_disk_rootfs(
 
     // Cleanup: temp_dir is dropped automatically
 
-    Ok(ContainerRootfsPrepResult::DiskImage {
-        base_disk_path: final_path,
-        disk_size,
-    })
+    Ok(prep_result(final_path, format, disk_size))
+}
+
+/// Path of the cached base disk of `format` for `image`, if there is one.
+fn cached_base_disk(image: &ImageObject, format: DiskFormat) -> Option<PathBuf> {
+    let cached = if format != DiskFormat::Ext4 {
+        image.disk_image_with_format(format)
+    } else {
+        image.disk_image()
+    };
+    // Leak the disk to prevent cleanup (it's a cached persistent disk)
+    cached.map(|disk| disk.leak())
+}
+
+/// Wrap a cached base image as the matching rootfs preparation result.
+fn prep_result(path: PathBuf, format: DiskFormat, size: u64) -> ContainerRootfsPrepResult {
+    match format {
+        DiskFormat::Erofs | DiskFormat::Squashfs => ContainerRootfsPrepResult::ReadOnlyImage {
+            image_path: path,
+            format,
+            image_size: size,
+        },
+        DiskFormat::Ext4 | DiskFormat::Qcow2 => ContainerRootfsPrepResult::DiskImage {
+            base_disk_path: path,
+            disk_size: size,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_rootfs_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            validate_rootfs_dir(dir.path()),
+            Err(BoxliteError::Config(_))
+        ));
+
+        std::fs::create_dir(dir.path().join("bin")).unwrap();
+        assert!(validate_rootfs_dir(dir.path()).is_ok());
+
+        assert!(validate_rootfs_dir(&dir.path().join("missing")).is_err());
+        std::fs::write(dir.path().join("file"), "").unwrap();
+        assert!(validate_rootfs_dir(&dir.path().join("file")).is_err());
+    }
 }
@@ -6,13 +6,14 @@
 //! IMPORTANT: Must wait for guest to be ready before creating session.
 //! Races guest readiness against shim process death for fast failure detection.
 
-use super::{InitCtx, log_task_error, task_start};
+use super::{InitCtx, guest_timeout_error, log_task_error, retry_transient, task_start};
 use crate::pipeline::PipelineTask;
 use crate::portal::GuestSession;
 use async_trait::async_trait;
 use boxlite_shared::Transport;
 use boxlite_shared::errors::{BoxliteError, BoxliteResult};
-use std::time::Duration;
+use std::path::Path;
+use std::time::{Duration, Instant};
 
 pub struct GuestConnectTask;
 
@@ -22,13 +23,15 @@ impl PipelineTask<InitCtx> for GuestConnectTask {
         let task_name = self.name();
         let box_id = task_start(&ctx, task_name).await;
 
-        let (transport, ready_transport, skip_guest_wait, shim_pid) = {
+        let (transport, ready_transport, skip_guest_wait, shim_pid, timeouts, console_path) = {
             let ctx = ctx.lock().await;
             (
                 ctx.config.transport.clone(),
                 Transport::unix(ctx.config.ready_socket_path.clone()),
                 ctx.skip_guest_wait,
                 ctx.guard.handler_pid(),
+                ctx.config.options.timeouts.clone(),
+                ctx.layout.as_ref().map(|l| l.console_output_path()),
             )
         };
 
@@ -38,13 +41,24 @@ impl PipelineTask<InitCtx> for GuestConnectTask {
             tracing::debug!(box_id = %box_id, "Skipping guest ready wait (reattach)");
         } else {
             tracing::debug!(box_id = %box_id, "Waiting for guest to be ready");
-            wait_for_guest_ready(&ready_transport, shim_pid)
-                .await
+            // Bind once: a slow guest that connects between attempts still
+            // lands in the listener backlog.
+            let listener = bind_ready_listener(&ready_transport)
                 .inspect_err(|e| log_task_error(&box_id, task_name, e))?;
+            retry_transient(&ctx, &box_id, task_name, || {
+                await_guest_ready(
+                    &listener,
+                    shim_pid,
+                    timeouts.guest_ready(),
+                    console_path.as_deref(),
+                )
+            })
+            .await
+            .inspect_err(|e| log_task_error(&box_id, task_name, e))?;
         }
 
         tracing::debug!(box_id = %box_id, "Guest is ready, creating session");
-        let guest_session = GuestSession::new(transport);
+        let guest_session = GuestSession::with_connect_timeout(transport, timeouts.connect());
 
         let mut ctx = ctx.lock().await;
         ctx.guest_session = Some(guest_session);
@@ -57,16 +71,8 @@ impl PipelineTask<InitCtx> for GuestConnectTask {
     }
 }
 
-/// Wait for guest to signal readiness, racing against shim process death.
-///
-/// Uses `tokio::select!` to detect three conditions:
-/// 1. Guest connects to ready socket (success)
-/// 2. Shim process exits unexpectedly (fast failure with diagnostic)
-/// 3. 30s timeout expires (slow failure fallback)
-async fn wait_for_guest_ready(
-    ready_transport: &Transport,
-    shim_pid: Option<u32>,
-) -> BoxliteResult<()> {
+/// Bind the Unix socket the guest connects to once it is ready.
+fn bind_ready_listener(ready_transport: &Transport) -> BoxliteResult<tokio::net::UnixListener> {
     let ready_socket_path = match ready_transport {
         Transport::Unix { socket_path } => socket_path,
         _ => {
@@ -94,9 +100,24 @@ async fn wait_for_guest_ready(
         socket = %ready_socket_path.display(),
         "Listening for guest ready notification"
     );
+    Ok(listener)
+}
 
+/// Wait for guest to signal readiness, racing against shim process death.
+///
+/// Uses `tokio::select!` to detect three conditions:
+/// 1. Guest connects to ready socket (success)
+/// 2. Shim process exits unexpectedly (fast failure with diagnostic)
+/// 3. `timeout` expires (slow failure fallback, transient); the error
+///    carries the elapsed wait and the tail of `console_path`
+async fn await_guest_ready(
+    listener: &tokio::net::UnixListener,
+    shim_pid: Option<u32>,
+    timeout: Duration,
+    console_path: Option<&Path>,
+) -> BoxliteResult<()> {
     // Race: guest ready signal vs shim death vs timeout
-    let timeout = Duration::from_secs(30);
+    let started = Instant::now();
 
     tokio::select! {
         result = tokio::time::timeout(timeout, listener.accept()) => {
@@ -108,12 +129,12 @@ async fn wait_for_guest_ready(
                 Ok(Err(e)) => Err(BoxliteError::Engine(format!(
                     "Ready socket accept failed: {}", e
                 ))),
-                Err(_) => Err(BoxliteError::Engine(format!(
-                    "Timeout waiting for guest ready ({}s). \
-                     Check logs: ~/.boxlite/logs/boxlite-shim.log, \
-                     and system: dmesg | grep -i 'apparmor\\|kvm'",
-                    timeout.as_secs()
-                ))),
+                Err(_) => Err(guest_timeout_error(
+                    "waiting for guest ready",
+                    started.elapsed(),
+                    timeout,
+                    console_path,
+                )),
             }
         }
         _ = wait_for_process_exit(shim_pid) => {
@@ -153,6 +174,14 @@ async fn wait_for_process_exit(pid: Option<u32>) {
 mod tests {
     use super::*;
 
+    async fn wait_for_guest_ready(
+        ready_transport: &Transport,
+        shim_pid: Option<u32>,
+    ) -> BoxliteResult<()> {
+        let listener = bind_ready_listener(ready_transport)?;
+        await_guest_ready(&listener, shim_pid, Duration::from_secs(30), None).await
+    }
+
     // ─────────────────────────────────────────────────────────────────────
     // wait_for_guest_ready tests
     // ─────────────────────────────────────────────────────────────────────
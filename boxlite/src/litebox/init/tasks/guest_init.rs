@@ -1,17 +1,27 @@
 //! Task: Guest initialization.
 //!
-//! Sends init configuration to guest and starts container.
+//! Sends init configuration to guest and starts the container, then its
+//! sidecars.
 //! Builds guest volumes from volume manager, uses rootfs config from vmm_config stage.
 
-use super::{InitCtx, log_task_error, task_start};
+use super::{InitCtx, guest_timeout_error, image_port_mappings, log_task_error, task_start};
 use crate::images::ContainerImageConfig;
+use crate::litebox::init::types::SidecarInit;
+use crate::metrics::BootPhases;
+use crate::net::constants::{GATEWAY_IP, SUBNET_PREFIX_LEN};
+use crate::net::forwarder::{PortForwarder, assign_host_port};
 use crate::pipeline::PipelineTask;
 use crate::portal::GuestSession;
 use crate::portal::interfaces::{ContainerRootfsInitConfig, GuestInitConfig, NetworkInitConfig};
+use crate::runtime::options::{CoreDumpOptions, PortSpec, TmpfsSpec};
 use crate::runtime::types::ContainerID;
 use crate::volumes::{ContainerMount, GuestVolumeManager};
 use async_trait::async_trait;
 use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+use std::future::Future;
+use std::net::Ipv4Addr;
+use std::path::Path;
+use std::time::{Duration, Instant};
 
 pub struct GuestInitTask;
 
@@ -28,6 +38,19 @@ impl PipelineTask<InitCtx> for GuestInitTask {
             volume_mgr,
             rootfs_init,
             container_mounts,
+            tmpfs,
+            allow_fuse,
+            capabilities,
+            init_timeout,
+            console_path,
+            core_dumps,
+            guest_ip,
+            deferred_ports,
+            sidecars,
+            hostname,
+            explicit_hostname,
+            timezone,
+            host_loopback_ip,
         ) =
             {
                 let mut ctx = ctx.lock().await;
@@ -55,25 +78,90 @@ impl PipelineTask<InitCtx> for GuestInitTask {
                     volume_mgr,
                     rootfs_init,
                     container_mounts,
+                    ctx.config.options.container_tmpfs(),
+                    ctx.config.options.security.allow_fuse,
+                    ctx.config.options.security.container_capabilities()?,
+                    ctx.config.options.timeouts.init(),
+                    ctx.layout.as_ref().map(|l| l.console_output_path()),
+                    ctx.config.options.core_dumps.clone(),
+                    ctx.config.ip_address(),
+                    ctx.defer_image_ports.then(|| {
+                        (
+                            ctx.layout.as_ref().map(|l| l.net_socket_path()),
+                            ctx.published_ports.clone().unwrap_or_default(),
+                            ctx.config.options.publish_all,
+                        )
+                    }),
+                    ctx.sidecar_inits.clone(),
+                    ctx.config.hostname(),
+                    ctx.config.options.hostname.is_some(),
+                    ctx.config.options.timezone.clone(),
+                    ctx.config.host_loopback_ip(),
                 )
             };
 
-        run_guest_init(
+        // Sync point of the fast plan: the container config task ran alongside
+        // the VM boot, so the image's ports are only known now
+        let mut image_published = Vec::new();
+        if let Some((Some(socket), published_ports, publish_all)) = deferred_ports
+            && let Some(guest_ip) = guest_ip
+        {
+            image_published = expose_image_ports(
+                PortForwarder::new(socket, guest_ip),
+                &container_image_config,
+                &published_ports,
+                publish_all,
+            )
+            .await;
+        }
+
+        let mut boot_phases = run_guest_init(
             guest_session.clone(),
             &container_image_config,
             &container_id,
             &volume_mgr,
             &rootfs_init,
             &container_mounts,
+            &tmpfs,
+            allow_fuse,
+            &capabilities,
+            init_timeout,
+            console_path.as_deref(),
+            core_dumps,
+            guest_ip,
+            &hostname,
+            explicit_hostname,
+            timezone.as_deref(),
+            host_loopback_ip,
+        )
+        .await
+        .inspect_err(|e| log_task_error(&box_id, task_name, e))?;
+
+        let started = Instant::now();
+        init_sidecars(
+            &guest_session,
+            &sidecars,
+            &capabilities,
+            &hostname,
+            host_loopback_ip,
+            init_timeout,
+            console_path.as_deref(),
         )
         .await
         .inspect_err(|e| log_task_error(&box_id, task_name, e))?;
+        if let Some(boot_phases) = &mut boot_phases {
+            boot_phases.container_init_ms += started.elapsed().as_millis() as u64;
+        }
 
         let mut ctx = ctx.lock().await;
+        ctx.boot_phases = boot_phases;
         ctx.guest_session = Some(guest_session);
         ctx.volume_mgr = Some(volume_mgr);
         ctx.rootfs_init = Some(rootfs_init);
         ctx.container_mounts = Some(container_mounts);
+        if let Some(published_ports) = &mut ctx.published_ports {
+            published_ports.extend(image_published);
+        }
 
         Ok(())
     }
@@ -83,7 +171,58 @@ impl PipelineTask<InitCtx> for GuestInitTask {
     }
 }
 
+/// Forward the image's exposed ports on a box spawned before its image
+/// config was loaded, like the network config would have: 1:1, or with
+/// `publish_all` to free host ports.
+///
+/// Ports the user mapped, or whose host port is already published, are
+/// left alone. Best effort: a forward that fails is logged and skipped.
+/// Returns the ports published with `publish_all`, to record on the box.
+async fn expose_image_ports(
+    forwarder: PortForwarder,
+    container_image_config: &ContainerImageConfig,
+    published_ports: &[PortSpec],
+    publish_all: bool,
+) -> Vec<PortSpec> {
+    let mut taken: Vec<u16> = published_ports.iter().filter_map(|p| p.host_port).collect();
+    let mut published = Vec::new();
+    let image_ports = container_image_config.tcp_ports();
+    for spec in image_port_mappings(&image_ports, published_ports, publish_all) {
+        if spec.host_port.is_some_and(|port| taken.contains(&port)) {
+            continue;
+        }
+        let spec = match assign_host_port(&spec, &taken) {
+            Ok(spec) => spec,
+            Err(e) => {
+                tracing::warn!(
+                    port = spec.guest_port,
+                    "Failed to publish image exposed port: {}",
+                    e
+                );
+                continue;
+            }
+        };
+        if let Err(e) = forwarder.expose(&spec).await {
+            tracing::warn!(
+                port = spec.guest_port,
+                "Failed to forward image exposed port: {}",
+                e
+            );
+            continue;
+        }
+        taken.extend(spec.host_port);
+        if publish_all {
+            published.push(spec);
+        }
+    }
+    published
+}
+
 /// Initialize guest and start container.
+///
+/// Each RPC (including the lazy connect before it) is bounded by `timeout`.
+/// Returns the guest's boot phases, if the agent reports them.
+#[allow(clippy::too_many_arguments)]
 async fn run_guest_init(
     guest_session: GuestSession,
     container_image_config: &ContainerImageConfig,
@@ -91,7 +230,18 @@ async fn run_guest_init(
     volume_mgr: &GuestVolumeManager,
     rootfs_init: &ContainerRootfsInitConfig,
     container_mounts: &[ContainerMount],
-) -> BoxliteResult<()> {
+    tmpfs: &[TmpfsSpec],
+    allow_fuse: bool,
+    capabilities: &[String],
+    timeout: Duration,
+    console_path: Option<&Path>,
+    core_dumps: Option<CoreDumpOptions>,
+    guest_ip: Option<Ipv4Addr>,
+    hostname: &str,
+    explicit_hostname: bool,
+    timezone: Option<&str>,
+    host_loopback_ip: Option<Ipv4Addr>,
+) -> BoxliteResult<Option<BootPhases>> {
     let container_id_str = container_id.as_str();
 
     // Build guest volumes from volume manager
@@ -99,31 +249,134 @@ async fn run_guest_init(
 
     let guest_init_config = GuestInitConfig {
         volumes: guest_volumes,
-        network: Some(NetworkInitConfig {
+        network: guest_ip.map(|ip| NetworkInitConfig {
             interface: "eth0".to_string(),
-            ip: Some("192.168.127.2/24".to_string()),
-            gateway: Some("192.168.127.1".to_string()),
+            ip: Some(format!("{}/{}", ip, SUBNET_PREFIX_LEN)),
+            gateway: Some(GATEWAY_IP.to_string()),
         }),
+        core_dumps,
     };
+    let wants_core_dumps = guest_init_config.core_dumps.is_some();
 
-    // Step 1: Guest Init (volumes + network)
+    // Step 1: Guest Init (protocol check, then volumes + network)
     tracing::info!("Sending guest initialization request");
-    let mut guest_interface = guest_session.guest().await?;
-    guest_interface.init(guest_init_config).await?;
+    let mut boot_phases = with_init_timeout("initializing guest", timeout, console_path, async {
+        let mut guest_interface = guest_session.guest().await?;
+        let version = guest_interface.version().await?;
+        tracing::debug!(
+            agent = %version.agent,
+            protocol = version.protocol,
+            "Guest agent version"
+        );
+        version.check_compatible()?;
+        if let Some((required, feature)) = rootfs_init.required_protocol() {
+            version.require_protocol(required, feature)?;
+        }
+        if wants_core_dumps {
+            version.require_protocol(
+                boxlite_shared::constants::protocol::CORE_DUMPS,
+                "core dump collection",
+            )?;
+        }
+        // Older guests name every container "boxlite"; only an explicit
+        // hostname is worth refusing the start for
+        if explicit_hostname {
+            version.require_protocol(
+                boxlite_shared::constants::protocol::CONTAINER_HOSTNAME,
+                "container hostname",
+            )?;
+        }
+        guest_interface.init(guest_init_config).await
+    })
+    .await?;
     tracing::info!("Guest initialized successfully");
 
     // Step 2: Container Init (rootfs + container image config + user volume mounts)
     tracing::info!("Sending container configuration to guest");
-    let mut container_interface = guest_session.container().await?;
-    let returned_id = container_interface
-        .init(
-            container_id_str,
-            container_image_config.clone(),
-            rootfs_init.clone(),
-            container_mounts.to_vec(),
-        )
-        .await?;
+    let started = Instant::now();
+    let returned_id = with_init_timeout("initializing container", timeout, console_path, async {
+        let mut container_interface = guest_session.container().await?;
+        container_interface
+            .init(
+                container_id_str,
+                container_image_config.clone(),
+                rootfs_init.clone(),
+                container_mounts.to_vec(),
+                tmpfs,
+                allow_fuse,
+                capabilities.to_vec(),
+                hostname,
+                timezone,
+                host_loopback_ip,
+            )
+            .await
+    })
+    .await?;
     tracing::info!(container_id = %returned_id, "Container initialized");
+    if let Some(boot_phases) = &mut boot_phases {
+        boot_phases.container_init_ms = started.elapsed().as_millis() as u64;
+    }
+
+    Ok(boot_phases)
+}
 
+/// Start the sidecar containers, in order, once the main one is running.
+///
+/// Sidecars get no volumes or tmpfs mounts but the same capabilities,
+/// hostname and host loopback name as the main container.
+async fn init_sidecars(
+    guest_session: &GuestSession,
+    sidecars: &[SidecarInit],
+    capabilities: &[String],
+    hostname: &str,
+    host_loopback_ip: Option<Ipv4Addr>,
+    timeout: Duration,
+    console_path: Option<&Path>,
+) -> BoxliteResult<()> {
+    for sidecar in sidecars {
+        let what = format!("initializing sidecar {}", sidecar.name);
+        with_init_timeout(&what, timeout, console_path, async {
+            let mut container_interface = guest_session.container().await?;
+            container_interface
+                .init(
+                    sidecar.container_id.as_str(),
+                    sidecar.config.clone(),
+                    sidecar.rootfs_init.clone(),
+                    Vec::new(),
+                    &[],
+                    false,
+                    capabilities.to_vec(),
+                    hostname,
+                    None,
+                    host_loopback_ip,
+                )
+                .await
+        })
+        .await
+        .map_err(|e| match e {
+            BoxliteError::Internal(reason) => {
+                BoxliteError::Internal(format!("sidecar {}: {}", sidecar.name, reason))
+            }
+            other => other,
+        })?;
+        tracing::info!(
+            sidecar = %sidecar.name,
+            container_id = %sidecar.container_id,
+            "Sidecar initialized"
+        );
+    }
     Ok(())
 }
+
+/// Run an init RPC, failing with `GuestTimeout` once `timeout` elapses.
+async fn with_init_timeout<T>(
+    what: &str,
+    timeout: Duration,
+    console_path: Option<&Path>,
+    rpc: impl Future<Output = BoxliteResult<T>>,
+) -> BoxliteResult<T> {
+    let started = Instant::now();
+    tokio::time::timeout(timeout, rpc)
+        .await
+        .map_err(|_| guest_timeout_error(what, started.elapsed(), timeout, console_path))?
+}
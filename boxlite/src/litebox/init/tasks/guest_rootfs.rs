@@ -1,6 +1,7 @@
 //! Task: Guest rootfs preparation.
 //!
-//! Lazily initializes the bootstrap guest rootfs as a disk image (shared across all boxes).
+//! Lazily initializes the bootstrap guest rootfs as a disk image (shared across all boxes),
+//! either from the bundled init image or from `BoxliteOptions::guest_rootfs_path`.
 //! Then creates or reuses per-box COW overlay disk.
 
 use super::{InitCtx, log_task_error, task_start};
@@ -14,6 +15,13 @@ use crate::runtime::rt_impl::SharedRuntimeImpl;
 use crate::util;
 use async_trait::async_trait;
 use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+use std::path::{Path, PathBuf};
+
+/// Guest agent location inside a guest rootfs
+const GUEST_AGENT_PATH: &str = "boxlite/bin/boxlite-guest";
+
+/// PATH for the guest agent when the rootfs has no image config to take it from
+const DEFAULT_GUEST_PATH: &str = "/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin";
 
 pub struct GuestRootfsTask;
 
@@ -57,6 +65,10 @@ async fn run_guest_rootfs(
     let guest_rootfs = runtime
         .guest_rootfs
         .get_or_try_init(|| async {
+            if let Some(path) = &runtime.guest_rootfs_path {
+                return prepare_custom_guest_rootfs(runtime, path).await;
+            }
+
             tracing::info!(
                 "Initializing bootstrap guest rootfs {} (first time only)",
                 images::INIT_ROOTFS
@@ -86,7 +98,9 @@ fn create_or_reuse_cow_disk(
     layout: &BoxFilesystemLayout,
     reuse_rootfs: bool,
 ) -> BoxliteResult<(GuestRootfs, Option<Disk>)> {
-    let guest_rootfs_disk_path = layout.root().join("guest-rootfs.qcow2");
+    let guest_rootfs_disk_path = layout
+        .root()
+        .join(crate::runtime::constants::filenames::GUEST_ROOTFS_DISK);
 
     if reuse_rootfs {
         // Restart: reuse existing COW disk
@@ -201,8 +215,8 @@ async fn prepare_guest_rootfs(
     // No cached disk - create from layers
     tracing::info!("Creating guest rootfs disk image from layers (first run)");
 
-    // Extract layers to temp directory within boxlite home (same filesystem as destination)
-    let temp_base = runtime.layout.temp_dir();
+    // Extract layers to temp directory within the image cache (same filesystem as destination)
+    let temp_base = runtime.layout.image_temp_dir();
     let temp_dir = tempfile::tempdir_in(&temp_base)
         .map_err(|e| BoxliteError::Storage(format!("Failed to create temp directory: {}", e)))?;
     let merged_path = temp_dir.path().join("merged");
@@ -276,6 +290,120 @@ async fn prepare_guest_rootfs(
     )
 }
 
+/// Prepare a user-supplied guest rootfs (ext4 disk image or directory).
+///
+/// Disk images are used in place as the read-only base of each box's COW
+/// disk. Directories are packed into a cached disk image under
+/// `images/disk-images`; the guest agent they ship is used as-is.
+async fn prepare_custom_guest_rootfs(
+    runtime: &SharedRuntimeImpl,
+    path: &Path,
+) -> BoxliteResult<GuestRootfs> {
+    let metadata = std::fs::metadata(path).map_err(|e| {
+        BoxliteError::Config(format!(
+            "guest rootfs {} is not accessible: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    let env = vec![("PATH".to_string(), DEFAULT_GUEST_PATH.to_string())];
+
+    let disk_path = if metadata.is_dir() {
+        let agent = path.join(GUEST_AGENT_PATH);
+        if !agent.is_file() {
+            return Err(BoxliteError::Config(format!(
+                "guest rootfs {} has no guest agent at /{}",
+                path.display(),
+                GUEST_AGENT_PATH
+            )));
+        }
+        let disk_path = custom_disk_path(&runtime.layout.image_layout().disk_images_dir(), path);
+        if is_custom_disk_fresh(&disk_path, path, &agent) {
+            tracing::info!(
+                "Using cached custom guest rootfs disk: {}",
+                disk_path.display()
+            );
+        } else {
+            build_custom_disk(runtime, path, &disk_path).await?;
+        }
+        disk_path
+    } else {
+        tracing::info!("Using custom guest rootfs disk image: {}", path.display());
+        path.to_path_buf()
+    };
+
+    GuestRootfs::new(
+        disk_path.clone(),
+        Strategy::Disk {
+            disk_path,
+            device_path: None, // Set later in build_disk_attachments
+        },
+        None,
+        None,
+        env,
+    )
+}
+
+/// Cached disk location for a custom rootfs directory, keyed by its path.
+fn custom_disk_path(disk_images_dir: &Path, source: &Path) -> PathBuf {
+    use sha2::{Digest, Sha256};
+
+    let hash = format!("{:x}", Sha256::digest(source.to_string_lossy().as_bytes()));
+    disk_images_dir.join(format!("custom-guest-rootfs-{}.ext4", &hash[..16]))
+}
+
+/// Whether the cached disk is newer than both the source directory and its agent.
+fn is_custom_disk_fresh(disk_path: &Path, source: &Path, agent: &Path) -> bool {
+    let mtime = |p: &Path| std::fs::metadata(p).and_then(|m| m.modified()).ok();
+    match (mtime(disk_path), mtime(source), mtime(agent)) {
+        (Some(disk), Some(dir), Some(agent)) => disk >= dir && disk >= agent,
+        _ => false,
+    }
+}
+
+/// Pack a custom rootfs directory into an ext4 disk at `disk_path`.
+async fn build_custom_disk(
+    runtime: &SharedRuntimeImpl,
+    source: &Path,
+    disk_path: &Path,
+) -> BoxliteResult<()> {
+    tracing::info!(
+        "Creating custom guest rootfs disk from {}",
+        source.display()
+    );
+
+    let temp_dir = tempfile::tempdir_in(runtime.layout.image_temp_dir())
+        .map_err(|e| BoxliteError::Storage(format!("Failed to create temp directory: {}", e)))?;
+    let temp_disk_path = temp_dir.path().join("guest-rootfs.ext4");
+    let source_owned = source.to_path_buf();
+    let temp_clone = temp_disk_path.clone();
+    let temp_disk =
+        tokio::task::spawn_blocking(move || create_ext4_from_dir(&source_owned, &temp_clone))
+            .await
+            .map_err(|e| BoxliteError::Internal(format!("Disk creation task failed: {}", e)))??;
+
+    if let Some(parent) = disk_path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| {
+            BoxliteError::Storage(format!("Failed to create {}: {}", parent.display(), e))
+        })?;
+    }
+    std::fs::rename(temp_disk.path(), disk_path).map_err(|e| {
+        BoxliteError::Storage(format!(
+            "Failed to install custom guest rootfs disk {}: {}",
+            disk_path.display(),
+            e
+        ))
+    })?;
+    // Renamed into place; nothing left for the temp disk to clean up
+    let _ = temp_disk.leak();
+
+    tracing::info!(
+        "Installed custom guest rootfs disk: {}",
+        disk_path.display()
+    );
+    Ok(())
+}
+
 async fn pull_guest_rootfs_image(
     runtime: &crate::runtime::SharedRuntimeImpl,
 ) -> BoxliteResult<crate::images::ImageObject> {
@@ -0,0 +1,77 @@
+//! Task: Mock Spawn - Start the in-process guest of a mock box.
+//!
+//! Replaces ContainerRootfs, GuestRootfs and VmmSpawn for `VmmKind::Mock`:
+//! no image is pulled and no VM is started. Outputs the same context fields
+//! as VmmSpawn so GuestConnect and GuestInit run unchanged.
+
+use super::{InitCtx, log_task_error, task_start};
+use crate::disk::{Disk, DiskFormat};
+use crate::images::ContainerImageConfig;
+use crate::pipeline::PipelineTask;
+use crate::portal::interfaces::ContainerRootfsInitConfig;
+use crate::vmm::mock;
+use crate::volumes::GuestVolumeManager;
+use async_trait::async_trait;
+use boxlite_shared::Transport;
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+
+pub struct MockSpawnTask;
+
+#[async_trait]
+impl PipelineTask<InitCtx> for MockSpawnTask {
+    async fn run(self: Box<Self>, ctx: InitCtx) -> BoxliteResult<()> {
+        let task_name = self.name();
+        let box_id = task_start(&ctx, task_name).await;
+
+        let (script, socket_path, ready_socket_path, layout, ports) =
+            {
+                let ctx = ctx.lock().await;
+                let script =
+                    ctx.runtime.mock_script.clone().ok_or_else(|| {
+                        BoxliteError::Internal("mock engine not configured".into())
+                    })?;
+                let socket_path = match &ctx.config.transport {
+                    Transport::Unix { socket_path } => socket_path.clone(),
+                    _ => {
+                        return Err(BoxliteError::Engine(
+                            "mock engine requires a Unix socket transport".into(),
+                        ));
+                    }
+                };
+                let layout = ctx.layout.clone().ok_or_else(|| {
+                    BoxliteError::Internal("filesystem task must run first".into())
+                })?;
+                (
+                    script,
+                    socket_path,
+                    ctx.config.ready_socket_path.clone(),
+                    layout,
+                    ctx.config.options.ports.clone(),
+                )
+            };
+
+        let handler = mock::spawn(script, &socket_path, &ready_socket_path)
+            .await
+            .inspect_err(|e| log_task_error(&box_id, task_name, e))?;
+
+        // The runtime reads the box PID from the PID file, as for shim boxes
+        std::fs::write(layout.pid_file_path(), std::process::id().to_string())
+            .map_err(|e| BoxliteError::Storage(format!("Failed to write PID file: {}", e)))
+            .inspect_err(|e| log_task_error(&box_id, task_name, e))?;
+
+        let mut ctx = ctx.lock().await;
+        ctx.guard.set_handler(Box::new(handler));
+        // No disk is created; the reference only satisfies LiveState
+        ctx.container_disk = Some(Disk::new(layout.disk_path(), DiskFormat::Qcow2, true));
+        ctx.container_image_config = Some(ContainerImageConfig::default());
+        ctx.volume_mgr = Some(GuestVolumeManager::new());
+        ctx.rootfs_init = Some(ContainerRootfsInitConfig::Merged);
+        ctx.container_mounts = Some(Vec::new());
+        ctx.published_ports = Some(ports);
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "mock_spawn"
+    }
+}
@@ -11,7 +11,7 @@
 //!
 //! Starting (new box):
 //! - Stage 1 (sequential): [Filesystem]
-//! - Stage 2 (parallel):   [ContainerRootfs, GuestRootfs]
+//! - Stage 2 (parallel):   [ContainerRootfs, GuestRootfs, SidecarRootfs?]
 //! - Stage 3 (sequential): [VmmSpawn, GuestConnect, GuestInit]
 //!
 //! Stopped (restart):
@@ -19,8 +19,21 @@
 //! - Stage 2 (parallel):   [ContainerRootfs, GuestRootfs]
 //! - Stage 3 (sequential): [VmmSpawn, GuestConnect]
 //!
+//! Cached rootfs (start or restart, see `rootfs_cached`):
+//! - Stage 1 (sequential): [Filesystem]
+//! - Stage 2 (parallel):   [ContainerDisks, GuestRootfs]
+//! - Stage 3 (sequential): [VmmSpawn]
+//! - Stage 4 (parallel):   [GuestConnect, ContainerConfig]
+//! - Stage 5 (sequential): [GuestInit]
+//!
+//! On this plan the VM boots without the image config; GuestInit is the
+//! sync point that waits for it and forwards the image's exposed ports.
+//!
 //! Running (reattach):
 //! - Stage 1 (sequential): [VmmAttach, GuestConnect]
+//!
+//! Mock engine (start or restart):
+//! - Stage 1 (sequential): [Filesystem, MockSpawn, GuestConnect, GuestInit]
 //! ```
 
 mod container_rootfs;
@@ -28,13 +41,20 @@ mod filesystem;
 mod guest_connect;
 mod guest_init;
 mod guest_rootfs;
+#[cfg(any(test, feature = "testing"))]
+mod mock_spawn;
 mod vmm_attach;
 mod vmm_spawn;
 
 use super::types::InitPipelineContext;
 use crate::runtime::types::BoxID;
-use boxlite_shared::errors::BoxliteError;
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+use std::future::Future;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
 use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
 use tokio::sync::Mutex;
 
 pub type InitCtx = Arc<Mutex<InitPipelineContext>>;
@@ -49,10 +69,197 @@ fn log_task_error(box_id: &BoxID, task_name: &str, err: &BoxliteError) {
     tracing::error!(box_id = %box_id, task = %task_name, "Task failed: {}", err);
 }
 
-pub use container_rootfs::ContainerRootfsTask;
+/// First retry delay; doubles per attempt up to `RETRY_MAX_DELAY`.
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Backoff before retry number `attempt` (1-based).
+fn retry_delay(attempt: u32) -> Duration {
+    RETRY_BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+        .min(RETRY_MAX_DELAY)
+}
+
+/// Run `step`, retrying transient failures up to the runtime's `create_retries`.
+///
+/// Permanent errors are returned immediately. Each retry is logged and counted
+/// in the box and runtime metrics; shutdown interrupts the backoff.
+async fn retry_transient<T, F, Fut>(
+    ctx: &InitCtx,
+    box_id: &BoxID,
+    task_name: &str,
+    mut step: F,
+) -> BoxliteResult<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = BoxliteResult<T>>,
+{
+    let runtime = { ctx.lock().await.runtime.clone() };
+    let max_retries = runtime.settings.read().create_retries;
+    let mut attempt = 0;
+    loop {
+        let err = match step().await {
+            Err(e) if e.is_transient() && attempt < max_retries => e,
+            result => return result,
+        };
+        attempt += 1;
+        let delay = retry_delay(attempt);
+        tracing::warn!(
+            box_id = %box_id,
+            task = %task_name,
+            attempt,
+            max_retries,
+            delay_ms = delay.as_millis() as u64,
+            code = %err.code(),
+            "Transient failure, retrying: {}",
+            err
+        );
+        ctx.lock().await.retries += 1;
+        runtime
+            .runtime_metrics
+            .create_retries
+            .fetch_add(1, Ordering::Relaxed);
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = runtime.shutdown_token.cancelled() => {
+                return Err(BoxliteError::Stopped(format!(
+                    "runtime shut down while retrying {}: {}",
+                    task_name, err
+                )));
+            }
+        }
+    }
+}
+
+/// Lines of guest console output attached to startup timeout errors.
+const CONSOLE_TAIL_LINES: usize = 20;
+/// Bytes read from the end of the console log to find those lines.
+const CONSOLE_TAIL_BYTES: u64 = 16 * 1024;
+
+/// Build a `GuestTimeout` error reporting the elapsed wait and, when
+/// available, the last lines of the guest console.
+fn guest_timeout_error(
+    what: &str,
+    elapsed: Duration,
+    limit: Duration,
+    console_path: Option<&Path>,
+) -> BoxliteError {
+    let mut message = format!(
+        "Timeout {} after {:.1}s (limit {}s)",
+        what,
+        elapsed.as_secs_f64(),
+        limit.as_secs()
+    );
+    match console_path.and_then(|path| console_tail(path, CONSOLE_TAIL_LINES)) {
+        Some(tail) => {
+            message.push_str("\nLast console output:\n");
+            message.push_str(&tail);
+        }
+        None => message.push_str(
+            ". Check logs: ~/.boxlite/logs/boxlite-shim.log, \
+             and system: dmesg | grep -i 'apparmor\\|kvm'",
+        ),
+    }
+    BoxliteError::GuestTimeout(message)
+}
+
+/// Last `max_lines` non-empty lines of a console log, or `None` if the file
+/// is missing or empty.
+fn console_tail(path: &Path, max_lines: usize) -> Option<String> {
+    let mut file = std::fs::File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    let start = len.saturating_sub(CONSOLE_TAIL_BYTES);
+    file.seek(SeekFrom::Start(start)).ok()?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf).ok()?;
+
+    let text = String::from_utf8_lossy(&buf);
+    let mut lines: Vec<&str> = text
+        .lines()
+        // The first line may be cut mid-way when reading from an offset.
+        .skip(usize::from(start > 0))
+        .map(|line| line.trim_end())
+        .filter(|line| !line.is_empty())
+        .collect();
+    if lines.is_empty() {
+        return None;
+    }
+    let keep = lines.len().saturating_sub(max_lines);
+    lines.drain(..keep);
+    Some(lines.join("\n"))
+}
+
+pub use container_rootfs::{
+    ContainerConfigTask, ContainerDisksTask, ContainerRootfsTask, SidecarRootfsTask,
+};
+pub(crate) use container_rootfs::{rootfs_cached, warm_image};
 pub use filesystem::FilesystemTask;
 pub use guest_connect::GuestConnectTask;
 pub use guest_init::GuestInitTask;
 pub use guest_rootfs::GuestRootfsTask;
+#[cfg(any(test, feature = "testing"))]
+pub use mock_spawn::MockSpawnTask;
 pub use vmm_attach::VmmAttachTask;
-pub use vmm_spawn::VmmSpawnTask;
+pub use vmm_spawn::{VmmSpawnTask, image_port_mappings, requested_ports};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_backs_off_exponentially() {
+        assert_eq!(retry_delay(1), Duration::from_secs(1));
+        assert_eq!(retry_delay(2), Duration::from_secs(2));
+        assert_eq!(retry_delay(4), Duration::from_secs(8));
+        assert_eq!(retry_delay(6), RETRY_MAX_DELAY);
+        assert_eq!(retry_delay(u32::MAX), RETRY_MAX_DELAY);
+    }
+
+    #[test]
+    fn test_console_tail_keeps_last_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("console.log");
+        assert_eq!(console_tail(&path, 2), None);
+
+        std::fs::write(&path, "").unwrap();
+        assert_eq!(console_tail(&path, 2), None);
+
+        std::fs::write(&path, "boot\r\n\nmount root\nkernel panic\n").unwrap();
+        assert_eq!(
+            console_tail(&path, 2).as_deref(),
+            Some("mount root\nkernel panic")
+        );
+    }
+
+    #[test]
+    fn test_console_tail_drops_partial_first_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("console.log");
+        let mut content = "x".repeat(CONSOLE_TAIL_BYTES as usize);
+        content.push_str("\nlast\n");
+        std::fs::write(&path, content).unwrap();
+        assert_eq!(console_tail(&path, 5).as_deref(), Some("last"));
+    }
+
+    #[test]
+    fn test_guest_timeout_error_includes_elapsed_and_console() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("console.log");
+        std::fs::write(&path, "init: waiting for /dev/vda\n").unwrap();
+
+        let err = guest_timeout_error(
+            "waiting for guest ready",
+            Duration::from_millis(30_400),
+            Duration::from_secs(30),
+            Some(&path),
+        );
+        assert_eq!(err.code(), boxlite_shared::ErrorCode::GuestTimeout);
+        let msg = err.to_string();
+        assert!(msg.contains("after 30.4s (limit 30s)"), "{}", msg);
+        assert!(msg.contains("init: waiting for /dev/vda"), "{}", msg);
+
+        let msg = guest_timeout_error("connecting", Duration::ZERO, Duration::from_secs(1), None)
+            .to_string();
+        assert!(msg.contains("boxlite-shim.log"), "{}", msg);
+    }
+}
@@ -6,27 +6,35 @@
 use super::{InitCtx, log_task_error, task_start};
 use crate::disk::DiskFormat;
 use crate::images::ContainerImageConfig;
-use crate::litebox::init::types::resolve_user_volumes;
+use crate::litebox::init::types::{SidecarInit, resolve_user_volumes};
 use crate::net::NetworkBackendConfig;
+use crate::net::forwarder::{assign_host_port, check_host_port};
 use crate::pipeline::PipelineTask;
 use crate::runtime::constants::{guest_paths, mount_tags};
 use crate::runtime::guest_rootfs::{GuestRootfs, Strategy};
 use crate::runtime::layout::BoxFilesystemLayout;
-use crate::runtime::options::BoxOptions;
+use crate::runtime::options::{BoxOptions, HostLimits, NetworkSpec, PortSpec, SSH_GUEST_PORT};
 use crate::runtime::rt_impl::SharedRuntimeImpl;
 use crate::runtime::types::{BoxID, ContainerID};
 use crate::util::find_binary;
 use crate::vmm::controller::{ShimController, VmmController, VmmHandler};
 use crate::vmm::{Entrypoint, InstanceSpec, VmmKind};
-use crate::volumes::{ContainerMount, ContainerVolumeManager, GuestVolumeManager};
+use crate::volumes::{
+    ContainerMount, ContainerVolumeManager, GuestVolumeManager, VolumeMountOptions,
+};
 use async_trait::async_trait;
 use boxlite_shared::Transport;
 use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+use boxlite_shared::layout::GUEST_CORES_DIR;
 use std::collections::{HashMap, HashSet};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 pub struct VmmSpawnTask;
 
+/// A prepared sidecar as the VM config needs it: name, container id, config
+/// and rootfs disk.
+type SidecarDisk = (String, ContainerID, ContainerImageConfig, PathBuf);
+
 #[async_trait]
 impl PipelineTask<InitCtx> for VmmSpawnTask {
     async fn run(self: Box<Self>, ctx: InitCtx) -> BoxliteResult<()> {
@@ -39,8 +47,10 @@ impl PipelineTask<InitCtx> for VmmSpawnTask {
             layout,
             container_image_config,
             container_disk_path,
+            container_image_disk,
             guest_disk_path,
             container_id,
+            sidecars,
             runtime,
             reuse_rootfs,
         ) = {
@@ -49,38 +59,66 @@ impl PipelineTask<InitCtx> for VmmSpawnTask {
                 .layout
                 .clone()
                 .ok_or_else(|| BoxliteError::Internal("filesystem task must run first".into()))?;
-            let container_image_config = ctx
-                .container_image_config
-                .clone()
-                .ok_or_else(|| BoxliteError::Internal("rootfs task must run first".into()))?;
+            // Not loaded yet on the fast plan, where the VM boots while the
+            // container config task runs
+            let container_image_config = ctx.container_image_config.clone();
             let container_disk_path = ctx
                 .container_disk
                 .as_ref()
                 .ok_or_else(|| BoxliteError::Internal("rootfs task must run first".into()))?
                 .path()
                 .to_path_buf();
+            let container_image_disk = ctx
+                .container_image_disk
+                .as_ref()
+                .map(|(disk, format)| (disk.path().to_path_buf(), *format));
             let guest_disk_path = ctx.guest_disk.as_ref().map(|d| d.path().to_path_buf());
+            let sidecars: Vec<SidecarDisk> = ctx
+                .sidecars
+                .iter()
+                .map(|sidecar| {
+                    (
+                        sidecar.name.clone(),
+                        sidecar.container_id.clone(),
+                        sidecar.config.clone(),
+                        sidecar.disk.path().to_path_buf(),
+                    )
+                })
+                .collect();
             (
                 ctx.config.options.clone(),
                 layout,
                 container_image_config,
                 container_disk_path,
+                container_image_disk,
                 guest_disk_path,
                 ctx.config.container.id.clone(),
+                sidecars,
                 ctx.runtime.clone(),
                 ctx.reuse_rootfs,
             )
         };
 
         // Build config and get outputs
-        let (instance_spec, volume_mgr, rootfs_init, container_mounts) = build_config(
+        let (
+            instance_spec,
+            volume_mgr,
+            rootfs_init,
+            container_mounts,
+            published_ports,
+            sidecar_inits,
+        ) = build_config(
             &box_id,
             &options,
             &layout,
-            &container_image_config,
+            container_image_config.as_ref(),
             &container_disk_path,
+            container_image_disk
+                .as_ref()
+                .map(|(path, format)| (path.as_path(), *format)),
             guest_disk_path.as_deref(),
             &container_id,
+            &sidecars,
             &runtime,
             reuse_rootfs,
         )
@@ -88,7 +126,8 @@ impl PipelineTask<InitCtx> for VmmSpawnTask {
         .inspect_err(|e| log_task_error(&box_id, task_name, e))?;
 
         // Spawn VM
-        let handler = spawn_vm(&box_id, &instance_spec, &options)
+        let host_limits = runtime.settings.read().host_limits.clone();
+        let handler = spawn_vm(&box_id, &instance_spec, &options, host_limits)
             .await
             .inspect_err(|e| log_task_error(&box_id, task_name, e))?;
 
@@ -97,6 +136,10 @@ impl PipelineTask<InitCtx> for VmmSpawnTask {
         ctx.volume_mgr = Some(volume_mgr);
         ctx.rootfs_init = Some(rootfs_init);
         ctx.container_mounts = Some(container_mounts);
+        ctx.sidecar_inits = sidecar_inits;
+        ctx.published_ports = Some(published_ports);
+        ctx.defer_image_ports =
+            container_image_config.is_none() && options.network != NetworkSpec::Disabled;
         Ok(())
     }
 
@@ -111,10 +154,12 @@ async fn build_config(
     box_id: &BoxID,
     options: &BoxOptions,
     layout: &BoxFilesystemLayout,
-    container_image_config: &ContainerImageConfig,
+    container_image_config: Option<&ContainerImageConfig>,
     container_disk_path: &Path,
+    container_image_disk: Option<(&Path, DiskFormat)>,
     guest_disk_path: Option<&Path>,
     container_id: &ContainerID,
+    sidecars: &[SidecarDisk],
     runtime: &SharedRuntimeImpl,
     reuse_rootfs: bool,
 ) -> BoxliteResult<(
@@ -122,6 +167,8 @@ async fn build_config(
     GuestVolumeManager,
     crate::portal::interfaces::ContainerRootfsInitConfig,
     Vec<ContainerMount>,
+    Vec<PortSpec>,
+    Vec<SidecarInit>,
 )> {
     // Transport setup
     let transport = Transport::unix(layout.socket_path());
@@ -139,29 +186,82 @@ async fn build_config(
     // SHARED virtiofs - needed by all strategies
     volume_mgr.add_fs_share(mount_tags::SHARED, layout.shared_dir(), None, false, None);
 
-    // Add container rootfs disk (COW overlay workflow):
-    // 1. Base disk: Pre-built ext4 image with container layers merged
-    // 2. COW disk: QCOW2 overlay with copy-on-write semantics
-    //    - Inherits formatted ext4 from base (need_format=false)
-    //    - May have larger virtual size if disk_size_gb specified
-    // 3. Guest mount: Only resize on fresh start, not restart
-    //    - Fresh start with custom size: resize2fs expands filesystem
-    //    - Restart: filesystem already at correct size, skip resize
-    let need_resize = options.disk_size_gb.is_some() && !reuse_rootfs;
-    let rootfs_device = volume_mgr.add_block_device(
-        container_disk_path,
-        DiskFormat::Qcow2,
-        false,
-        None,
-        false,       // need_format: COW child inherits formatted base
-        need_resize, // need_resize: only on fresh start with custom disk size
-    );
+    // Core dumps are written by the guest straight to the host
+    if options.core_dumps.is_some() {
+        let cores_dir = layout.cores_dir();
+        std::fs::create_dir_all(&cores_dir).map_err(|e| {
+            BoxliteError::Storage(format!(
+                "Failed to create core dump directory {}: {}",
+                cores_dir.display(),
+                e
+            ))
+        })?;
+        volume_mgr.add_fs_share(
+            mount_tags::CORES,
+            cores_dir,
+            Some(GUEST_CORES_DIR),
+            false,
+            None,
+        );
+    }
 
-    // Update rootfs_init with actual device path and resize flag
-    let rootfs_init = crate::portal::interfaces::ContainerRootfsInitConfig::DiskImage {
-        device: rootfs_device,
-        need_format: false, // COW child uses pre-formatted base
-        need_resize,        // Only on fresh start with custom disk size
+    let rootfs_init = match container_image_disk {
+        // Read-only image workflow:
+        // 1. Image disk: QCOW2 reference to the shared EROFS/SquashFS image,
+        //    attached read-only and used as the overlay lower dir
+        // 2. Upper disk: Per-box ext4 disk with the overlay upper/work dirs
+        //    - Formatted by the guest on fresh start, kept on restart
+        Some((image_disk_path, image_format)) => {
+            let image_device = volume_mgr.add_block_device(
+                image_disk_path,
+                DiskFormat::Qcow2,
+                true, // read_only: shared image, never written
+                None,
+                false,
+                false,
+            );
+            let need_format = !reuse_rootfs;
+            let upper_device = volume_mgr.add_block_device(
+                container_disk_path,
+                DiskFormat::Qcow2,
+                false,
+                None,
+                need_format,
+                false,
+            );
+            crate::portal::interfaces::ContainerRootfsInitConfig::ReadOnlyImage {
+                image_device,
+                image_format,
+                upper_device,
+                need_format,
+            }
+        }
+        // Add container rootfs disk (COW overlay workflow):
+        // 1. Base disk: Pre-built ext4 image with container layers merged
+        // 2. COW disk: QCOW2 overlay with copy-on-write semantics
+        //    - Inherits formatted ext4 from base (need_format=false)
+        //    - May have larger virtual size if disk_size_gb specified
+        // 3. Guest mount: Only resize on fresh start, not restart
+        //    - Fresh start with custom size: resize2fs expands filesystem
+        //    - Restart: filesystem already at correct size, skip resize
+        None => {
+            let need_resize = options.disk_size_gb.is_some() && !reuse_rootfs;
+            let rootfs_device = volume_mgr.add_block_device(
+                container_disk_path,
+                DiskFormat::Qcow2,
+                false,
+                None,
+                false,       // need_format: COW child inherits formatted base
+                need_resize, // need_resize: only on fresh start with custom disk size
+            );
+
+            // Update rootfs_init with actual device path and resize flag
+            crate::portal::interfaces::ContainerRootfsInitConfig::DiskImage {
+                device: rootfs_device,
+                need_format: false, // COW child uses pre-formatted base
+                need_resize,        // Only on fresh start with custom disk size
+            }
+        }
     };
 
     // Add user volumes via ContainerVolumeManager
@@ -173,7 +273,11 @@ async fn build_config(
             &vol.tag,
             vol.host_path.clone(),
             &vol.guest_path,
-            vol.read_only,
+            VolumeMountOptions {
+                read_only: vol.read_only,
+                chown: vol.chown,
+                id_shift: vol.id_shift,
+            },
         );
     }
     let container_mounts = container_mgr.build_container_mounts();
@@ -187,6 +291,28 @@ async fn build_config(
 
     let guest_rootfs = configure_guest_rootfs(guest_rootfs, guest_disk_path, &mut volume_mgr)?;
 
+    // Sidecar rootfs disks go after the guest rootfs so the devices of the
+    // main container and guest keep their usual names
+    let mut sidecar_inits = Vec::with_capacity(sidecars.len());
+    for (name, sidecar_id, config, disk_path) in sidecars {
+        layout
+            .shared_layout()
+            .container(sidecar_id.as_str())
+            .prepare()?;
+        let device =
+            volume_mgr.add_block_device(disk_path, DiskFormat::Qcow2, false, None, false, false);
+        sidecar_inits.push(SidecarInit {
+            name: name.clone(),
+            container_id: sidecar_id.clone(),
+            config: config.clone(),
+            rootfs_init: crate::portal::interfaces::ContainerRootfsInitConfig::DiskImage {
+                device,
+                need_format: false,
+                need_resize: false,
+            },
+        });
+    }
+
     // Build VMM config from volume manager
     let vmm_config = volume_mgr.build_vmm_config();
 
@@ -194,8 +320,27 @@ async fn build_config(
     let guest_entrypoint =
         build_guest_entrypoint(&transport, &ready_transport, &guest_rootfs, options)?;
 
-    // Network configuration
-    let network_config = build_network_config(container_image_config, options);
+    // Network configuration (none at all when the box's network is disabled)
+    let (network_config, published_ports) = if options.network == NetworkSpec::Disabled {
+        (None, Vec::new())
+    } else {
+        let owners = runtime.published_port_owners(Some(box_id))?;
+        let (config, ports) = build_network_config(container_image_config, options, &owners)?;
+        (
+            Some(
+                config
+                    .with_control_socket(layout.net_socket_path())
+                    .with_tap_socket(layout.tap_socket_path())
+                    .with_connection_log(
+                        options
+                            .log_connections
+                            .then(|| layout.connection_log_path()),
+                    )
+                    .with_shared_daemon(runtime.shared_network_socket()),
+            ),
+            ports,
+        )
+    };
 
     // Use runtime home for logs (not box_home)
     let runtime_home = runtime.layout.home_dir();
@@ -209,6 +354,7 @@ async fn build_config(
         // VM resources
         cpus: options.cpus,
         memory_mib: options.memory_mib,
+        kernel: options.kernel.clone(),
         // Filesystem and devices
         fs_shares: vmm_config.fs_shares,
         block_devices: vmm_config.block_devices,
@@ -219,12 +365,21 @@ async fn build_config(
         network_config,
         network_backend_endpoint: None,
         home_dir: runtime_home.to_path_buf(),
+        box_dir: layout.root().to_path_buf(),
+        images_dir: runtime.layout.images_dir(),
         console_output: Some(logs_dir.join(format!("{}-console.log", box_id))),
         detach: options.detach,
         parent_pid: std::process::id(),
     };
 
-    Ok((instance_spec, volume_mgr, rootfs_init, container_mounts))
+    Ok((
+        instance_spec,
+        volume_mgr,
+        rootfs_init,
+        container_mounts,
+        published_ports,
+        sidecar_inits,
+    ))
 }
 
 /// Configure guest rootfs with device path from volume manager.
@@ -303,26 +458,50 @@ fn build_guest_entrypoint(
 }
 
 /// Build network configuration from container image config and options.
+///
+/// Without an image config, image exposed ports are left to guest init.
+/// Also returns the user port mappings with dynamic host ports (`None`/`0`)
+/// replaced by the ports assigned on the host.
 fn build_network_config(
-    container_image_config: &crate::images::ContainerImageConfig,
+    container_image_config: Option<&ContainerImageConfig>,
     options: &crate::runtime::options::BoxOptions,
-) -> Option<NetworkBackendConfig> {
+    port_owners: &[(String, PortSpec)],
+) -> BoxliteResult<(NetworkBackendConfig, Vec<PortSpec>)> {
     let mut port_map: HashMap<u16, u16> = HashMap::new();
-
-    // Step 1: Collect guest ports that user wants to customize
-    let user_guest_ports: HashSet<u16> = options.ports.iter().map(|p| p.guest_port).collect();
-
-    // Step 2: Image exposed ports (only add default 1:1 mapping if user didn't override)
-    for port in container_image_config.tcp_ports() {
-        if !user_guest_ports.contains(&port) {
-            port_map.insert(port, port);
+    let mut host_ips: HashMap<u16, String> = HashMap::new();
+
+    // Step 1: Collect guest ports that user wants to customize.
+    let mut user_ports = requested_ports(options);
+    let user_guest_ports: HashSet<u16> = user_ports.iter().map(|p| p.guest_port).collect();
+    let requested_count = user_ports.len();
+
+    // Step 2: Image exposed ports the user didn't override: a default 1:1
+    // mapping, or with publish_all a published port like the user's
+    let image_ports = container_image_config
+        .map(|config| config.tcp_ports())
+        .unwrap_or_default();
+    let image_mappings = image_port_mappings(&image_ports, &user_ports, options.publish_all);
+    if options.publish_all {
+        user_ports.extend(image_mappings);
+    } else {
+        for port in &image_mappings {
+            port_map.insert(port.guest_port, port.guest_port);
         }
     }
 
-    // Step 3: User-provided mappings (always applied)
-    for port in &options.ports {
+    // Step 3: User-provided mappings (always applied), fixed host ports
+    // checked for conflicts, dynamic host ports assigned
+    let mut published_ports = Vec::with_capacity(user_ports.len());
+    for port in &user_ports {
+        let taken: Vec<u16> = port_map.keys().copied().collect();
+        let port = check_host_port(port, port_owners, options.publish_retry)?;
+        let port = assign_host_port(&port, &taken)?;
         let host_port = port.host_port.unwrap_or(port.guest_port);
         port_map.insert(host_port, port.guest_port);
+        if let Some(host_ip) = &port.host_ip {
+            host_ips.insert(host_port, host_ip.clone());
+        }
+        published_ports.push(port);
     }
 
     let final_mappings: Vec<(u16, u16)> = port_map.into_iter().collect();
@@ -330,15 +509,77 @@ fn build_network_config(
     tracing::info!(
         "Port mappings: {} (image: {}, user: {}, overridden: {})",
         final_mappings.len(),
-        container_image_config.exposed_ports.len(),
-        options.ports.len(),
-        user_guest_ports
-            .intersection(&container_image_config.tcp_ports().into_iter().collect())
+        image_ports.len(),
+        requested_count,
+        image_ports
+            .iter()
+            .filter(|port| user_guest_ports.contains(port))
             .count()
     );
 
-    // Always return Some - gvproxy provides virtio-net (eth0) even without port mappings
-    Some(NetworkBackendConfig::new(final_mappings))
+    // gvproxy provides virtio-net (eth0) even without port mappings
+    let config = NetworkBackendConfig::new(final_mappings)
+        .with_egress_limit_mbps(options.network_limit_mbps)
+        .with_egress_policy(options.network_policy.clone())
+        .with_port_host_ips(host_ips)
+        .with_guest_mac(
+            options
+                .mac_address
+                .as_deref()
+                .map(crate::net::constants::parse_mac)
+                .transpose()?,
+        )
+        .with_guest_ip(
+            options
+                .ip_address
+                .as_deref()
+                .map(crate::net::constants::parse_guest_ip)
+                .transpose()?,
+        )
+        .with_host_loopback(options.host_loopback);
+    Ok((config, published_ports))
+}
+
+/// Port mappings requested by the options, before host port assignment.
+///
+/// SSH access adds a loopback-only mapping for sshd unless the user maps it.
+pub fn requested_ports(options: &BoxOptions) -> Vec<PortSpec> {
+    let mut ports = options.ports.clone();
+    if options.ssh.is_some() && !ports.iter().any(|p| p.guest_port == SSH_GUEST_PORT) {
+        ports.push(PortSpec {
+            host_port: None,
+            guest_port: SSH_GUEST_PORT,
+            host_ip: Some("127.0.0.1".to_string()),
+            ..Default::default()
+        });
+    }
+    ports
+}
+
+/// Mappings for the image's exposed TCP ports that `requested` leaves alone,
+/// sorted by port.
+///
+/// Each maps 1:1, or with `publish_all` to a free host port assigned at start.
+pub fn image_port_mappings(
+    image_ports: &[u16],
+    requested: &[PortSpec],
+    publish_all: bool,
+) -> Vec<PortSpec> {
+    let mut ports: Vec<u16> = image_ports
+        .iter()
+        .copied()
+        .filter(|port| !requested.iter().any(|p| p.guest_port == *port))
+        .collect();
+    ports.sort_unstable();
+    ports.dedup();
+    ports
+        .into_iter()
+        .map(|port| PortSpec {
+            host_port: (!publish_all).then_some(port),
+            guest_port: port,
+            ..Default::default()
+        })
+        .collect()
 }
 
 /// Spawn VM subprocess and return handler.
@@ -346,13 +587,15 @@ async fn spawn_vm(
     box_id: &BoxID,
     config: &InstanceSpec,
     options: &BoxOptions,
+    host_limits: Option<HostLimits>,
 ) -> BoxliteResult<Box<dyn VmmHandler>> {
     let mut controller = ShimController::new(
         find_binary("boxlite-shim")?,
         VmmKind::Libkrun,
         box_id.clone(),
         options.clone(),
-    )?;
+    )?
+    .with_host_limits(host_limits);
 
     controller.start(config).await
 }
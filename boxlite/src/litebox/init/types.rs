@@ -1,16 +1,18 @@
 //! Type definitions for initialization pipeline.
 
 use crate::BoxID;
-use crate::disk::Disk;
+use crate::disk::{Disk, DiskFormat};
 #[cfg(target_os = "linux")]
 use crate::fs::BindMountHandle;
-use crate::images::ContainerImageConfig;
+use crate::images::{ContainerImageConfig, ImageObject};
 use crate::litebox::config::BoxConfig;
+use crate::metrics::BootPhases;
 use crate::portal::GuestSession;
 use crate::portal::interfaces::ContainerRootfsInitConfig;
 use crate::runtime::layout::BoxFilesystemLayout;
-use crate::runtime::options::VolumeSpec;
+use crate::runtime::options::{PortSpec, VolumeIdShift, VolumeSpec};
 use crate::runtime::rt_impl::SharedRuntimeImpl;
+use crate::runtime::types::ContainerID;
 use crate::vmm::controller::VmmHandler;
 use crate::volumes::{ContainerMount, GuestVolumeManager};
 use boxlite_shared::errors::{BoxliteError, BoxliteResult};
@@ -30,13 +32,33 @@ pub const USE_OVERLAYFS: bool = true;
 /// When enabled, USE_OVERLAYFS is ignored.
 pub const USE_DISK_ROOTFS: bool = true;
 
+/// A sidecar's rootfs disk and config, from the sidecar rootfs task.
+pub struct SidecarRootfs {
+    pub name: String,
+    pub container_id: ContainerID,
+    pub config: ContainerImageConfig,
+    pub disk: Disk,
+}
+
+/// What Container.Init needs for a sidecar, once its disk is attached.
+#[derive(Debug, Clone)]
+pub struct SidecarInit {
+    pub name: String,
+    pub container_id: ContainerID,
+    pub config: ContainerImageConfig,
+    pub rootfs_init: ContainerRootfsInitConfig,
+}
+
 /// User-specified volume with resolved paths and generated tag.
 #[derive(Debug, Clone)]
 pub struct ResolvedVolume {
     pub tag: String,
+    /// Directory or regular file to mount.
     pub host_path: PathBuf,
     pub guest_path: String,
     pub read_only: bool,
+    pub chown: bool,
+    pub id_shift: Option<VolumeIdShift>,
 }
 
 pub fn resolve_user_volumes(volumes: &[VolumeSpec]) -> BoxliteResult<Vec<ResolvedVolume>> {
@@ -59,9 +81,9 @@ pub fn resolve_user_volumes(volumes: &[VolumeSpec]) -> BoxliteResult<Vec<Resolve
             ))
         })?;
 
-        if !resolved_path.is_dir() {
+        if !resolved_path.is_dir() && !resolved_path.is_file() {
             return Err(BoxliteError::Config(format!(
-                "Volume host path is not a directory: {}",
+                "Volume host path is not a directory or regular file: {}",
                 vol.host_path
             )));
         }
@@ -81,13 +103,15 @@ pub fn resolve_user_volumes(volumes: &[VolumeSpec]) -> BoxliteResult<Vec<Resolve
             host_path: resolved_path,
             guest_path: vol.guest_path.clone(),
             read_only: vol.read_only,
+            chown: vol.chown,
+            id_shift: vol.id_shift,
         });
     }
 
     Ok(resolved)
 }
 
-/// Result of rootfs preparation - either merged, separate layers, or disk image.
+/// Result of rootfs preparation - merged, separate layers, or a disk image.
 #[derive(Debug)]
 pub enum ContainerRootfsPrepResult {
     /// Single merged directory (all layers merged on host)
@@ -109,6 +133,16 @@ pub enum ContainerRootfsPrepResult {
         /// Size of the disk in bytes (for creating COW overlay)
         disk_size: u64,
     },
+    /// Compressed read-only image (erofs/squashfs) mounted under overlayfs,
+    /// with a per-box writable ext4 upper disk
+    ReadOnlyImage {
+        /// Path to the cached image (shared across boxes)
+        image_path: PathBuf,
+        /// Image filesystem (Erofs or Squashfs)
+        format: DiskFormat,
+        /// Size of the image in bytes
+        image_size: u64,
+    },
 }
 
 /// RAII guard for cleanup on initialization failure.
@@ -214,15 +248,32 @@ pub struct InitPipelineContext {
     pub reuse_rootfs: bool,
     /// Skip waiting for guest ready signal (for reattach to running box).
     pub skip_guest_wait: bool,
+    /// Steps retried after transient failures (see `tasks::retry_transient`).
+    pub retries: u32,
 
     pub layout: Option<BoxFilesystemLayout>,
+    /// Image resolved by the container disks task, for the config task.
+    pub container_image: Option<ImageObject>,
     pub container_image_config: Option<ContainerImageConfig>,
     pub container_disk: Option<Disk>,
+    /// Read-only rootfs image; `container_disk` is then its overlayfs upper disk.
+    pub container_image_disk: Option<(Disk, DiskFormat)>,
     pub guest_disk: Option<Disk>,
     pub volume_mgr: Option<GuestVolumeManager>,
     pub rootfs_init: Option<ContainerRootfsInitConfig>,
     pub container_mounts: Option<Vec<ContainerMount>>,
+    /// Prepared sidecars, in `BoxOptions::sidecars` order.
+    pub sidecars: Vec<SidecarRootfs>,
+    /// Sidecars to initialize after the main container, set by vmm_spawn.
+    pub sidecar_inits: Vec<SidecarInit>,
     pub guest_session: Option<GuestSession>,
+    /// User port mappings with dynamically assigned host ports resolved.
+    pub published_ports: Option<Vec<PortSpec>>,
+    /// Image exposed ports left out of the network config because the VM was
+    /// spawned before the image config was loaded; guest init exposes them.
+    pub defer_image_ports: bool,
+    /// Guest-reported boot breakdown, set by the guest init task.
+    pub boot_phases: Option<BootPhases>,
 
     #[cfg(target_os = "linux")]
     pub bind_mount: Option<BindMountHandle>,
@@ -242,14 +293,22 @@ impl InitPipelineContext {
             guard,
             reuse_rootfs,
             skip_guest_wait,
+            retries: 0,
             layout: None,
+            container_image: None,
             container_image_config: None,
             container_disk: None,
+            container_image_disk: None,
             guest_disk: None,
             volume_mgr: None,
             rootfs_init: None,
             container_mounts: None,
+            sidecars: Vec::new(),
+            sidecar_inits: Vec::new(),
             guest_session: None,
+            published_ports: None,
+            defer_image_ports: false,
+            boot_phases: None,
             #[cfg(target_os = "linux")]
             bind_mount: None,
         }
@@ -0,0 +1,147 @@
+//! Setup commands run on first start (`BoxOptions::init_commands`).
+//!
+//! Each command runs through `/bin/sh -c` after the container is initialized
+//! and before the box is handed to the caller. Output of every command is
+//! recorded under the box's `recordings` directory, like
+//! [`BoxCommand::record_output`].
+
+use std::path::Path;
+
+use futures::StreamExt;
+use tokio_util::sync::CancellationToken;
+
+use boxlite_shared::constants::executor as executor_const;
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+
+use super::exec::{BoxCommand, ExecStderr, Execution};
+use super::recording::{OutputRecording, Recorder};
+use crate::portal::GuestSession;
+
+/// Bytes of stderr kept for the error of a failing command.
+const STDERR_TAIL_BYTES: usize = 2048;
+
+/// Run `commands` in order, stopping at the first failure.
+pub(crate) async fn run(
+    session: &GuestSession,
+    box_id: &str,
+    container_id: &str,
+    commands: &[String],
+    working_dir: Option<&str>,
+    recordings_dir: &Path,
+    shutdown: CancellationToken,
+) -> BoxliteResult<()> {
+    for (index, script) in commands.iter().enumerate() {
+        let mut command = BoxCommand::new("/bin/sh")
+            .args(["-c", script.as_str()])
+            .env(
+                executor_const::ENV_VAR,
+                format!("{}={}", executor_const::CONTAINER_KEY, container_id),
+            );
+        if let Some(dir) = working_dir {
+            command = command.working_dir(dir);
+        }
+
+        tracing::info!(box_id = %box_id, command = %script, "Running init command");
+        let recorder = Recorder::create(
+            recordings_dir.join(ulid::Ulid::new().to_string()),
+            &OutputRecording::default(),
+            box_id,
+            &command,
+        )?;
+        let result = async {
+            let mut exec_interface = session.execution().await?;
+            let components = exec_interface.exec(command, shutdown.clone()).await?;
+            Ok::<_, BoxliteError>((exec_interface, components))
+        }
+        .await;
+        let (exec_interface, components) = match result {
+            Ok(started) => started,
+            Err(e) => {
+                recorder.fail(&e);
+                return Err(e);
+            }
+        };
+        let components = recorder.attach(components);
+        let mut execution = Execution::new(
+            components.execution_id,
+            exec_interface,
+            components.result_rx,
+            None,
+            None,
+            Some(ExecStderr::new(components.stderr_rx)),
+        );
+
+        let mut stderr = execution.stderr();
+        let result = execution.wait().await?;
+        if result.success() {
+            continue;
+        }
+
+        let mut tail = Vec::new();
+        if let Some(stderr) = stderr.as_mut() {
+            while let Some(chunk) = stderr.next().await {
+                tail.extend_from_slice(&chunk);
+                let excess = tail.len().saturating_sub(STDERR_TAIL_BYTES);
+                tail.drain(..excess);
+            }
+        }
+        return Err(BoxliteError::Execution(format!(
+            "init command {} ({}) failed with exit code {}: {}",
+            index + 1,
+            script,
+            result.exit_code,
+            String::from_utf8_lossy(&tail).trim()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vmm::mock::{MockExec, MockScript, test_runtime};
+
+    #[tokio::test]
+    async fn test_init_commands() {
+        use crate::{BoxCommand, BoxOptions, BoxliteOptions};
+
+        let script = MockScript::new()
+            .on(&["/bin/sh", "-c"], MockExec::new())
+            .on(
+                &["/bin/sh", "-c", "pip install requests"],
+                MockExec::new().stderr("no network\n").exit_code(3),
+            );
+        let (home, runtime) = test_runtime(BoxliteOptions::default(), script.clone());
+        let options = |commands: &[&str]| BoxOptions {
+            init_commands: commands.iter().map(|c| c.to_string()).collect(),
+            ..Default::default()
+        };
+
+        let litebox = runtime
+            .create(options(&["apk add curl", "touch /ready"]), None)
+            .await
+            .unwrap();
+        litebox.exec(BoxCommand::new("true")).await.unwrap();
+        let calls: Vec<String> = script.calls().iter().map(|c| c.join(" ")).collect();
+        assert_eq!(
+            calls,
+            ["/bin/sh -c apk add curl", "/bin/sh -c touch /ready", "true"]
+        );
+        let recordings = home
+            .path()
+            .join("boxes")
+            .join(litebox.id().as_str())
+            .join("recordings");
+        assert_eq!(std::fs::read_dir(recordings).unwrap().count(), 2);
+        litebox.stop().await.unwrap();
+
+        // A failing command fails the start; later commands never run
+        let litebox = runtime
+            .create(options(&["pip install requests", "touch /ready"]), None)
+            .await
+            .unwrap();
+        let err = litebox.start().await.unwrap_err().to_string();
+        assert!(err.contains("init command 1"), "{}", err);
+        assert!(err.contains("exit code 3: no network"), "{}", err);
+        assert_eq!(script.calls().len(), 4);
+    }
+}
@@ -44,7 +44,7 @@ impl BoxManager {
         if let Some(ref name) = config.name
             && self.lookup_box_id(name)?.is_some()
         {
-            return Err(BoxliteError::InvalidState(format!(
+            return Err(BoxliteError::NameConflict(format!(
                 "box with name '{}' already exists",
                 name
             )));
@@ -218,6 +218,7 @@ mod tests {
             container: ContainerRuntimeConfig {
                 id: ContainerID::new(),
             },
+            sidecars: Default::default(),
             options: BoxOptions {
                 rootfs: RootfsSpec::Image("test:latest".to_string()),
                 cpus: Some(2),
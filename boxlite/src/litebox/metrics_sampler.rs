@@ -0,0 +1,100 @@
+//! Resource usage history (`BoxliteOptions::metrics_history`).
+//!
+//! The sampler reads the metrics of a running box on a fixed interval and
+//! stores CPU, memory and network usage in the database, where it outlives
+//! the box run: after a crash or an out-of-memory kill the usage leading up
+//! to it can still be read with `LiteBox::metrics_history`.
+
+use std::sync::Weak;
+use std::time::Duration;
+
+use tokio_util::sync::CancellationToken;
+
+use super::box_impl::BoxImpl;
+use crate::metrics::MetricsSample;
+use crate::runtime::options::MetricsHistoryPolicy;
+
+/// Spawn the metrics sampler for a running box.
+///
+/// The task exits when `shutdown_token` is cancelled (box stop or runtime
+/// shutdown) or when the last handle to the box is dropped.
+pub(crate) fn spawn_sampler(
+    box_impl: Weak<BoxImpl>,
+    policy: MetricsHistoryPolicy,
+    shutdown_token: CancellationToken,
+) {
+    let interval = Duration::from_secs(policy.interval_secs.max(1));
+
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = shutdown_token.cancelled() => break,
+                _ = tokio::time::sleep(interval) => {}
+            }
+            let Some(box_impl) = box_impl.upgrade() else {
+                break;
+            };
+
+            let metrics = match box_impl.metrics().await {
+                Ok(metrics) => metrics,
+                Err(e) => {
+                    tracing::debug!(box_id = %box_impl.id(), error = %e, "Metrics sample skipped");
+                    continue;
+                }
+            };
+            let sample = MetricsSample::from_metrics(&metrics, chrono::Utc::now());
+            if let Err(e) = box_impl.runtime.metrics_samples.record(
+                box_impl.id().as_str(),
+                &sample,
+                policy.max_samples,
+            ) {
+                tracing::warn!(box_id = %box_impl.id(), "Failed to record metrics sample: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vmm::mock::{MockScript, test_runtime};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_metrics_history() {
+        use crate::{BoxOptions, BoxliteOptions, MetricsHistoryPolicy};
+
+        let (_home, runtime) = test_runtime(
+            BoxliteOptions {
+                metrics_history: Some(MetricsHistoryPolicy {
+                    interval_secs: 1,
+                    max_samples: 2,
+                }),
+                ..Default::default()
+            },
+            MockScript::new(),
+        );
+        let litebox = runtime
+            .create(
+                BoxOptions {
+                    auto_remove: false,
+                    ..Default::default()
+                },
+                Some("sampled".to_string()),
+            )
+            .await
+            .unwrap();
+        let hour = Duration::from_secs(3600);
+        assert!(litebox.metrics_history(hour).unwrap().is_empty());
+
+        litebox.start().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(3500)).await;
+        litebox.stop().await.unwrap();
+
+        // Readable after stop; only the newest max_samples are kept
+        let litebox = runtime.get("sampled").await.unwrap().unwrap();
+        let history = litebox.metrics_history(hour).unwrap();
+        assert_eq!(history.len(), 2);
+        assert!(history[0].sampled_at < history[1].sampled_at);
+        assert!(litebox.metrics_history(Duration::ZERO).unwrap().is_empty());
+    }
+}
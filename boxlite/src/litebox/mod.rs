@@ -3,31 +3,59 @@
 //! Provides lazy initialization and execution capabilities for isolated boxes.
 
 pub(crate) mod box_impl;
+mod cache_reclaim;
 pub(crate) mod config;
+mod connection_log;
 pub mod copy;
+mod core_dump;
 mod exec;
+mod exec_limit;
+mod heartbeat;
 mod init;
+mod init_commands;
 mod manager;
+mod metrics_sampler;
+mod oom;
+mod process;
+pub(crate) mod provision;
+mod python;
+mod recording;
+mod ssh;
 mod state;
+mod wait;
+mod watch;
 
+pub use connection_log::ConnectionRecord;
 pub use copy::CopyOptions;
-pub use exec::{BoxCommand, ExecResult, ExecStderr, ExecStdin, ExecStdout, Execution, ExecutionId};
+pub use core_dump::CoreDump;
+pub use exec::{
+    BoxCommand, ExecResult, ExecStderr, ExecStdin, ExecStdout, Execution, ExecutionId, ExitReason,
+    OutputLines, OutputText,
+};
 pub(crate) use manager::BoxManager;
-pub use state::{BoxState, BoxStatus};
+pub use process::{ListeningPort, ProcessInfo};
+pub use provision::PackageManager;
+pub use python::{CellError, CellResult, PythonSession};
+pub use recording::OutputRecording;
+pub use state::{BoxHealth, BoxState, BoxStatus, ContainerProcess, HealthStatus};
+pub use wait::WaitCondition;
+pub use watch::{FsEvent, FsEventKind, FsWatch};
 
 pub(crate) use box_impl::SharedBoxImpl;
-pub(crate) use init::BoxBuilder;
+pub(crate) use init::{BoxBuilder, plan_box, requested_ports, warm_image};
 
-use crate::metrics::BoxMetrics;
+use crate::metrics::{BoxMetrics, MetricsSample};
+use crate::runtime::options::PortSpec;
 use crate::{BoxID, BoxInfo};
-use boxlite_shared::errors::BoxliteResult;
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
 pub use config::BoxConfig;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// LiteBox - Handle to a box.
 ///
 /// Thin wrapper around BoxImpl. BoxImpl is created immediately with config,
-/// but VM resources (LiveState) are lazily initialized on first use.
+/// but VM resources (LiveState) are only initialized by `start()` or `exec()`.
 ///
 /// Following the same pattern as BoxliteRuntime wrapping RuntimeImpl.
 pub struct LiteBox {
@@ -69,7 +97,9 @@ impl LiteBox {
     /// For Stopped boxes: restarts the VM.
     ///
     /// This is idempotent - calling start() on a Running box is a no-op.
-    /// Also called implicitly by exec() if the box is not running.
+    /// Also called implicitly by exec() if the box is not running. These are
+    /// the only two calls that boot a VM; `create()` never does, and the
+    /// other operations fail with `InvalidState` until the box is running.
     pub async fn start(&self) -> BoxliteResult<()> {
         self.inner.start().await
     }
@@ -78,15 +108,198 @@ impl LiteBox {
         self.inner.exec(command).await
     }
 
+    /// Live resource usage of the box.
+    ///
+    /// Fails with `InvalidState` if the box is not running.
     pub async fn metrics(&self) -> BoxliteResult<BoxMetrics> {
         self.inner.metrics().await
     }
 
+    /// Resource usage samples recorded over the last `range`, oldest first.
+    ///
+    /// Samples are only recorded when `BoxliteOptions::metrics_history` is
+    /// set; they stay readable after the box stops, until it is removed.
+    pub fn metrics_history(&self, range: Duration) -> BoxliteResult<Vec<MetricsSample>> {
+        self.inner.metrics_history(range)
+    }
+
+    /// Core dumps collected from the box, newest first.
+    ///
+    /// Dumps are only collected when `BoxOptions::core_dumps` is set; they
+    /// stay readable after the box stops, until it is removed.
+    pub fn core_dumps(&self) -> BoxliteResult<Vec<CoreDump>> {
+        self.inner.core_dumps()
+    }
+
+    /// Connections the box opened, oldest first.
+    ///
+    /// Connections are only logged when `BoxOptions::log_connections` is
+    /// set, and appear once they end; the log stays readable after the box
+    /// stops, until it is removed.
+    pub fn connection_log(&self) -> BoxliteResult<Vec<ConnectionRecord>> {
+        self.inner.connection_log()
+    }
+
+    /// Install `packages` with the box's package manager (`apk`, `apt-get`
+    /// or `dnf`, whichever is found first) and return the manager used.
+    ///
+    /// Starts the box if needed. If the install fails, the error lists
+    /// every package that could not be installed. Changes last as long as
+    /// the box; use [`BoxliteRuntime::provision_image`](crate::BoxliteRuntime::provision_image)
+    /// to keep them in a reusable image.
+    pub async fn provision(&self, packages: &[String]) -> BoxliteResult<PackageManager> {
+        provision::install(self, packages, &|_| {}).await
+    }
+
+    /// Stop the box (Running or Paused → Stopped).
+    ///
+    /// Idempotent on a Stopped box. The handle is invalidated afterwards;
+    /// use `BoxliteRuntime::get` for a new one.
     pub async fn stop(&self) -> BoxliteResult<()> {
         self.inner.stop().await
     }
 
+    /// Stop the box and start it again, returning the new handle.
+    ///
+    /// A Configured or Stopped box is just started. This handle is
+    /// invalidated, like after [`stop`](Self::stop). Boxes with
+    /// `auto_remove` cannot be restarted, since stopping removes them.
+    pub async fn restart(&self) -> BoxliteResult<LiteBox> {
+        if self.inner.config.options.auto_remove {
+            return Err(BoxliteError::InvalidState(format!(
+                "cannot restart auto_remove box {}",
+                self.id
+            )));
+        }
+        self.inner.stop().await?;
+        let litebox = self
+            .inner
+            .runtime
+            .get(self.id.as_str())
+            .await?
+            .ok_or_else(|| BoxliteError::NotFound(self.id.to_string()))?;
+        litebox.start().await?;
+        Ok(litebox)
+    }
+
+    /// Freeze the box's VM (Running → Paused).
+    ///
+    /// Memory, open connections and running commands are kept; nothing in
+    /// the box runs until [`resume`](Self::resume). Other operations fail
+    /// with `InvalidState` meanwhile; [`stop`](Self::stop) resumes the VM
+    /// before shutting it down. Idempotent on a Paused box.
+    pub async fn pause(&self) -> BoxliteResult<()> {
+        self.inner.pause().await
+    }
+
+    /// Continue a box frozen by [`pause`](Self::pause) (Paused → Running).
+    ///
+    /// Idempotent on a Running box.
+    pub async fn resume(&self) -> BoxliteResult<()> {
+        self.inner.resume().await
+    }
+
+    /// Port mappings of the box.
+    ///
+    /// For a running box these are the live forwards, including ports
+    /// published with [`publish_port`](Self::publish_port). Otherwise the
+    /// ports configured at create time.
+    pub async fn ports(&self) -> BoxliteResult<Vec<PortSpec>> {
+        self.inner.ports().await
+    }
+
+    /// Forward a host port to the running box without restarting it.
+    ///
+    /// Fails with `InvalidState` if the box is not running. A `host_port` of
+    /// `None` or `0` is assigned a free host port; the returned spec carries
+    /// the assigned port. The mapping lasts until the box stops; it is not
+    /// added to the box's saved options.
+    pub async fn publish_port(&self, spec: PortSpec) -> BoxliteResult<PortSpec> {
+        self.inner.publish_port(spec).await
+    }
+
+    /// Stop forwarding the host side (`host_ip`, `host_port`, protocol) of `spec`.
+    pub async fn unpublish_port(&self, spec: PortSpec) -> BoxliteResult<()> {
+        self.inner.unpublish_port(spec).await
+    }
+
+    /// Mount a host directory into the running container at `guest_path`.
+    ///
+    /// Fails with `InvalidState` if the box is not running. The mount lasts
+    /// until it is removed with [`unmount`](Self::unmount) or the box stops;
+    /// it is not added to the box's saved options. Returns `Unsupported` on platforms where volumes
+    /// cannot be added to a live VM (currently everything but Linux).
+    pub async fn mount(
+        &self,
+        host_path: impl AsRef<Path>,
+        guest_path: &str,
+        read_only: bool,
+    ) -> BoxliteResult<()> {
+        self.inner
+            .mount(host_path.as_ref(), guest_path, read_only)
+            .await
+    }
+
+    /// Remove a volume added with [`mount`](Self::mount).
+    pub async fn unmount(&self, guest_path: &str) -> BoxliteResult<()> {
+        self.inner.unmount(guest_path).await
+    }
+
+    /// Processes running in the box's container, sorted by PID.
+    ///
+    /// Fails with `InvalidState` if the box is not running.
+    pub async fn processes(&self) -> BoxliteResult<Vec<ProcessInfo>> {
+        self.inner.processes().await
+    }
+
+    /// Sockets listening inside the box's container, sorted by port.
+    ///
+    /// Poll this to wait for a service to come up instead of sleeping.
+    /// Fails with `InvalidState` if the box is not running.
+    pub async fn listening_ports(&self) -> BoxliteResult<Vec<ListeningPort>> {
+        self.inner.listening_ports().await
+    }
+
+    /// Wait until the box stops, its guest agent answers, or a port in the
+    /// container starts listening.
+    ///
+    /// Polls the condition; with a `timeout`, fails with `GuestTimeout` if
+    /// it does not hold in time. Waiting for `Healthy` or `Port` fails with
+    /// `InvalidState` if the box is not running or stops meanwhile.
+    ///
+    /// ```ignore
+    /// litebox.exec(BoxCommand::new("nginx")).await?;
+    /// litebox
+    ///     .wait_for(WaitCondition::Port(80), Some(Duration::from_secs(30)))
+    ///     .await?;
+    /// ```
+    pub async fn wait_for(
+        &self,
+        condition: WaitCondition,
+        timeout: Option<Duration>,
+    ) -> BoxliteResult<()> {
+        self.inner.wait_for(condition, timeout).await
+    }
+
+    /// Start a persistent Python interpreter in the box.
+    ///
+    /// Cells submitted with [`PythonSession::run`] share one namespace and
+    /// one process, so imports and variables carry over without per-exec
+    /// startup costs. Requires `python3` in the image.
+    ///
+    /// ```ignore
+    /// let session = litebox.python_session().await?;
+    /// session.run("import math; r = 2").await?;
+    /// let cell = session.run("{'area': math.pi * r ** 2}").await?;
+    /// println!("{:?}", cell.value);
+    /// ```
+    pub async fn python_session(&self) -> BoxliteResult<PythonSession> {
+        PythonSession::start(self).await
+    }
+
     /// Copy files/directories from host into the container rootfs.
+    ///
+    /// Fails with `InvalidState` if the box is not running.
     pub async fn copy_into(
         &self,
         host_src: impl AsRef<Path>,
@@ -98,7 +311,26 @@ impl LiteBox {
             .await
     }
 
+    /// Copy selected entries under `host_root` into `container_dst` in one
+    /// upload, keeping their paths relative to `host_root`.
+    ///
+    /// Directories are created without their contents and symlinks are
+    /// copied as links. Used for incremental syncs, where only changed
+    /// entries are sent.
+    pub async fn copy_paths_into(
+        &self,
+        host_root: impl AsRef<Path>,
+        paths: &[PathBuf],
+        container_dst: impl AsRef<str>,
+    ) -> BoxliteResult<()> {
+        self.inner
+            .copy_paths_into(host_root.as_ref(), paths, container_dst.as_ref())
+            .await
+    }
+
     /// Copy files/directories from container rootfs to host.
+    ///
+    /// Fails with `InvalidState` if the box is not running.
     pub async fn copy_out(
         &self,
         container_src: impl AsRef<str>,
@@ -109,6 +341,24 @@ impl LiteBox {
             .copy_out(container_src.as_ref(), host_dst.as_ref(), opts)
             .await
     }
+
+    /// Watch a container path for changes.
+    ///
+    /// Fails with `InvalidState` if the box is not running. With `recursive`,
+    /// directories below `path` are watched too, including ones created
+    /// later. Like `copy_out`, this sees the container rootfs, not tmpfs
+    /// mounts such as `/tmp`.
+    ///
+    /// ```ignore
+    /// let mut events = litebox.watch("/app/src", true).await?;
+    /// while let Some(event) = events.next().await {
+    ///     let event = event?;
+    ///     println!("{:?} {}", event.kind, event.path);
+    /// }
+    /// ```
+    pub async fn watch(&self, path: impl AsRef<str>, recursive: bool) -> BoxliteResult<FsWatch> {
+        self.inner.watch(path.as_ref(), recursive).await
+    }
 }
 
 // ============================================================================
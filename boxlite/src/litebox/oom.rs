@@ -0,0 +1,93 @@
+//! Recording of out-of-memory kills on the box.
+//!
+//! The guest marks a command's result when the kernel killed it out of
+//! memory. The result is passed on unchanged; the box only notes the kill
+//! so [`BoxInfo::oom_killed`](crate::BoxInfo::oom_killed) reports it.
+
+use std::sync::Weak;
+
+use tokio::sync::mpsc;
+
+use super::box_impl::BoxImpl;
+use super::exec::ExecResult;
+use crate::portal::interfaces::exec::ExecComponents;
+
+/// Route the execution's result through the box so OOM kills are recorded.
+///
+/// Holds the box weakly: a kill after the box is gone is not recorded.
+pub(crate) fn watch(box_impl: Weak<BoxImpl>, components: ExecComponents) -> ExecComponents {
+    let ExecComponents {
+        execution_id,
+        stdin_tx,
+        stdout_rx,
+        stderr_rx,
+        result_rx: mut source_result,
+    } = components;
+
+    let (result_tx, result_rx) = mpsc::unbounded_channel::<ExecResult>();
+    let id = execution_id.clone();
+    tokio::spawn(async move {
+        if let Some(result) = source_result.recv().await {
+            // Recorded before the caller sees the result
+            if result.oom_killed
+                && let Some(box_impl) = box_impl.upgrade()
+            {
+                box_impl.record_oom_kill(&id);
+            }
+            let _ = result_tx.send(result);
+        }
+    });
+
+    ExecComponents {
+        execution_id,
+        stdin_tx,
+        stdout_rx,
+        stderr_rx,
+        result_rx,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vmm::mock::{MockExec, MockScript, test_runtime};
+
+    #[tokio::test]
+    async fn test_oom_killed() {
+        use crate::{BoxCommand, BoxOptions, BoxliteOptions};
+
+        let script = MockScript::new().on(&["hog"], MockExec::new().oom_killed());
+        let (_home, runtime) = test_runtime(BoxliteOptions::default(), script);
+        let litebox = runtime
+            .create(
+                BoxOptions {
+                    auto_remove: false,
+                    ..Default::default()
+                },
+                Some("oom".to_string()),
+            )
+            .await
+            .unwrap();
+
+        // A plain kill is not an OOM kill
+        let mut execution = litebox
+            .exec(BoxCommand::new("sleep").arg("300"))
+            .await
+            .unwrap();
+        execution.kill().await.unwrap();
+        assert!(!execution.wait().await.unwrap().oom_killed);
+        assert!(!litebox.info().oom_killed);
+
+        let mut execution = litebox.exec(BoxCommand::new("hog")).await.unwrap();
+        let result = execution.wait().await.unwrap();
+        assert_eq!((result.exit_code, result.oom_killed), (137, true));
+        assert!(litebox.info().oom_killed);
+
+        // Kept after stop, cleared by the next start
+        litebox.stop().await.unwrap();
+        let litebox = runtime.get("oom").await.unwrap().unwrap();
+        assert!(litebox.info().oom_killed);
+        litebox.start().await.unwrap();
+        assert!(!litebox.info().oom_killed);
+        litebox.stop().await.unwrap();
+    }
+}
@@ -0,0 +1,131 @@
+//! Processes running inside a box and the sockets they listen on.
+
+use std::net::IpAddr;
+
+use crate::runtime::options::PortProtocol;
+
+/// A process in the box's container.
+///
+/// PIDs are as seen inside the container, so they can be passed to `kill`
+/// run through [`LiteBox::exec`](super::LiteBox::exec).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    /// Parent PID (0 when the parent is outside the container)
+    pub ppid: u32,
+    pub uid: u32,
+    /// User name from the container's `/etc/passwd`, or the uid when unknown
+    pub user: String,
+    /// Resident set size
+    pub rss_bytes: u64,
+    /// Full command line, or `[name]` for processes without one
+    pub command: String,
+}
+
+impl From<boxlite_shared::ProcessInfo> for ProcessInfo {
+    fn from(process: boxlite_shared::ProcessInfo) -> Self {
+        Self {
+            pid: process.pid,
+            ppid: process.ppid,
+            uid: process.uid,
+            user: process.user,
+            rss_bytes: process.rss_bytes,
+            command: process.command,
+        }
+    }
+}
+
+/// A socket listening inside the box's container: a TCP socket in LISTEN
+/// state or an unconnected UDP socket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListeningPort {
+    pub protocol: PortProtocol,
+    /// Local address, e.g. `0.0.0.0`, `127.0.0.1` or `::`
+    pub address: IpAddr,
+    pub port: u16,
+    /// Owning process, as seen inside the container
+    pub pid: u32,
+    /// Process name of the owner
+    pub command: String,
+}
+
+impl ListeningPort {
+    pub(crate) fn from_proto(socket: boxlite_shared::ListeningSocket) -> Option<Self> {
+        let protocol = match socket.protocol() {
+            boxlite_shared::SocketProtocol::Tcp => PortProtocol::Tcp,
+            boxlite_shared::SocketProtocol::Udp => PortProtocol::Udp,
+        };
+        Some(Self {
+            protocol,
+            address: socket.address.parse().ok()?,
+            port: u16::try_from(socket.port).ok()?,
+            pid: socket.pid,
+            command: socket.command,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vmm::mock::{MockExec, MockScript, test_runtime};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_processes() {
+        use crate::{BoxCommand, BoxOptions, BoxliteOptions};
+
+        let (_home, runtime) = test_runtime(BoxliteOptions::default(), MockScript::new());
+        let litebox = runtime.create(BoxOptions::default(), None).await.unwrap();
+        assert!(litebox.processes().await.is_err());
+
+        let mut execution = litebox
+            .exec(BoxCommand::new("sleep").arg("300"))
+            .await
+            .unwrap();
+        litebox.exec(BoxCommand::new("true")).await.unwrap();
+        let processes = litebox.processes().await.unwrap();
+        assert_eq!(processes.len(), 1);
+        assert_eq!(processes[0].command, "sleep 300");
+        assert_eq!(processes[0].user, "root");
+
+        execution.kill().await.unwrap();
+        execution.wait().await.unwrap();
+        assert!(litebox.processes().await.unwrap().is_empty());
+        litebox.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_listening_ports() {
+        use crate::runtime::options::PortProtocol;
+        use crate::{BoxCommand, BoxOptions, BoxliteOptions};
+
+        let (_home, runtime) = test_runtime(
+            BoxliteOptions::default(),
+            MockScript::new().on(
+                &["/usr/bin/server"],
+                MockExec::new().delay(Duration::from_secs(300)).listen(8080),
+            ),
+        );
+        let litebox = runtime.create(BoxOptions::default(), None).await.unwrap();
+        assert!(litebox.listening_ports().await.is_err());
+
+        litebox.exec(BoxCommand::new("true")).await.unwrap();
+        assert!(litebox.listening_ports().await.unwrap().is_empty());
+
+        let mut execution = litebox
+            .exec(BoxCommand::new("/usr/bin/server"))
+            .await
+            .unwrap();
+        let ports = litebox.listening_ports().await.unwrap();
+        assert_eq!(ports.len(), 1);
+        assert_eq!(ports[0].protocol, PortProtocol::Tcp);
+        assert_eq!(ports[0].port, 8080);
+        assert!(ports[0].address.is_unspecified());
+        assert_eq!(ports[0].command, "server");
+
+        execution.kill().await.unwrap();
+        execution.wait().await.unwrap();
+        assert!(litebox.listening_ports().await.unwrap().is_empty());
+        litebox.stop().await.unwrap();
+    }
+}
@@ -0,0 +1,272 @@
+//! Package installation (`LiteBox::provision`).
+//!
+//! The box's package manager is found by probing `apk`, `apt-get` and `dnf`
+//! in that order. All packages are installed with one command; if that
+//! fails, each package is retried on its own so the error names every
+//! package that could not be installed, not just the first.
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use futures::StreamExt;
+
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+
+use super::LiteBox;
+use super::exec::{BoxCommand, ExecResult};
+
+/// Lines of stderr kept for the error of a failing install.
+const STDERR_TAIL_LINES: usize = 3;
+
+/// A package manager found in a box.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PackageManager {
+    /// Alpine's `apk`.
+    Apk,
+    /// Debian and Ubuntu's `apt-get`.
+    Apt,
+    /// Fedora and RHEL's `dnf`.
+    Dnf,
+}
+
+impl PackageManager {
+    /// Probe order; the first one present wins.
+    const ALL: [PackageManager; 3] = [Self::Apk, Self::Apt, Self::Dnf];
+
+    /// Program name in the box.
+    pub fn program(&self) -> &'static str {
+        match self {
+            Self::Apk => "apk",
+            Self::Apt => "apt-get",
+            Self::Dnf => "dnf",
+        }
+    }
+
+    /// Command that refreshes the package index before installing, if needed.
+    fn update_command(&self) -> Option<BoxCommand> {
+        match self {
+            // apt images ship without package lists
+            Self::Apt => Some(self.command().arg("update").arg("-q")),
+            // apk --no-cache and dnf fetch the index themselves
+            Self::Apk | Self::Dnf => None,
+        }
+    }
+
+    fn install_command(&self, packages: &[String]) -> BoxCommand {
+        let command = match self {
+            Self::Apk => self.command().args(["add", "--no-cache"]),
+            Self::Apt => self
+                .command()
+                .args(["install", "-y", "-q", "--no-install-recommends"]),
+            Self::Dnf => self.command().args(["install", "-y", "-q"]),
+        };
+        command.args(packages)
+    }
+
+    fn command(&self) -> BoxCommand {
+        BoxCommand::new(self.program()).env("DEBIAN_FRONTEND", "noninteractive")
+    }
+}
+
+impl fmt::Display for PackageManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.program())
+    }
+}
+
+/// Check that `packages` can be passed to a package manager as names.
+pub(crate) fn validate_packages(packages: &[String]) -> BoxliteResult<()> {
+    if packages.is_empty() {
+        return Err(BoxliteError::InvalidArgument(
+            "no packages to provision".into(),
+        ));
+    }
+    for package in packages {
+        // Option-like or multi-word names would change the install command
+        if package.is_empty()
+            || package.starts_with('-')
+            || package.chars().any(|c| c.is_whitespace() || c.is_control())
+        {
+            return Err(BoxliteError::InvalidArgument(format!(
+                "invalid package name '{}'",
+                package
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Install `packages` in the box, forwarding command output to `progress`.
+pub(crate) async fn install(
+    litebox: &LiteBox,
+    packages: &[String],
+    progress: &impl Fn(String),
+) -> BoxliteResult<PackageManager> {
+    validate_packages(packages)?;
+    let manager = detect(litebox).await?;
+    tracing::info!(box_id = %litebox.id(), %manager, ?packages, "Provisioning packages");
+
+    if let Some(update) = manager.update_command() {
+        let (result, stderr) = run(litebox, update, progress).await?;
+        if !result.success() {
+            return Err(BoxliteError::Execution(format!(
+                "{} update failed with exit code {}: {}",
+                manager, result.exit_code, stderr
+            )));
+        }
+    }
+
+    let (result, _) = run(litebox, manager.install_command(packages), progress).await?;
+    if result.success() {
+        return Ok(manager);
+    }
+
+    // Find every package that fails, not just the one the manager stopped at
+    let mut failures = Vec::new();
+    for package in packages {
+        let (result, stderr) = run(
+            litebox,
+            manager.install_command(std::slice::from_ref(package)),
+            progress,
+        )
+        .await?;
+        if !result.success() {
+            failures.push(format!(
+                "{} (exit code {}: {})",
+                package, result.exit_code, stderr
+            ));
+        }
+    }
+    if failures.is_empty() {
+        return Ok(manager);
+    }
+    Err(BoxliteError::Execution(format!(
+        "failed to install {} of {} packages with {}: {}",
+        failures.len(),
+        packages.len(),
+        manager,
+        failures.join("; ")
+    )))
+}
+
+/// Find the box's package manager.
+async fn detect(litebox: &LiteBox) -> BoxliteResult<PackageManager> {
+    for manager in PackageManager::ALL {
+        let probe = BoxCommand::new(manager.program()).arg("--version");
+        let (result, _) = run(litebox, probe, &|_| {}).await?;
+        if result.success() {
+            return Ok(manager);
+        }
+    }
+    Err(BoxliteError::Unsupported(
+        "no supported package manager (apk, apt-get, dnf) found in the box".into(),
+    ))
+}
+
+/// Run `command` to completion, returning its result and the last lines of
+/// its stderr.
+async fn run(
+    litebox: &LiteBox,
+    command: BoxCommand,
+    progress: &impl Fn(String),
+) -> BoxliteResult<(ExecResult, String)> {
+    let mut execution = litebox.exec(command).await?;
+
+    let mut streams = Vec::new();
+    if let Some(stdout) = execution.stdout() {
+        streams.push(stdout.lines().map(|line| (false, line)).boxed());
+    }
+    if let Some(stderr) = execution.stderr() {
+        streams.push(stderr.lines().map(|line| (true, line)).boxed());
+    }
+    let mut output = futures::stream::select_all(streams);
+    let mut tail = VecDeque::new();
+    while let Some((is_stderr, line)) = output.next().await {
+        if is_stderr {
+            tail.push_back(line.trim_end().to_string());
+            if tail.len() > STDERR_TAIL_LINES {
+                tail.pop_front();
+            }
+        }
+        progress(line);
+    }
+
+    let result = execution.wait().await?;
+    let stderr = Vec::from(tail).join(" ").trim().to_string();
+    Ok((result, stderr))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vmm::mock::{MockExec, MockScript, test_runtime};
+
+    #[test]
+    fn test_validate_packages() {
+        let names = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(validate_packages(&names(&["curl", "py3-pip", "libssl3=3.1.4-r5"])).is_ok());
+        assert!(validate_packages(&[]).is_err());
+        assert!(validate_packages(&names(&["curl", "--allow-untrusted"])).is_err());
+        assert!(validate_packages(&names(&["curl git"])).is_err());
+        assert!(validate_packages(&names(&[""])).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_provision() {
+        use crate::{BoxOptions, BoxliteError, BoxliteOptions, PackageManager};
+
+        let script = MockScript::new()
+            .on(&["apk", "--version"], MockExec::new())
+            .on(&["apk", "add"], MockExec::new())
+            .on(
+                &["apk", "add", "--no-cache", "curl", "nosuch", "gone"],
+                MockExec::new().exit_code(1),
+            )
+            .on(
+                &["apk", "add", "--no-cache", "nosuch"],
+                MockExec::new()
+                    .stderr("ERROR: unable to select packages:\n  nosuch (no such package)\n")
+                    .exit_code(1),
+            )
+            .on(
+                &["apk", "add", "--no-cache", "gone"],
+                MockExec::new().exit_code(2),
+            );
+        let (_home, runtime) = test_runtime(BoxliteOptions::default(), script.clone());
+        let litebox = runtime.create(BoxOptions::default(), None).await.unwrap();
+        let packages = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        let manager = litebox
+            .provision(&packages(&["curl", "git"]))
+            .await
+            .unwrap();
+        assert_eq!(manager, PackageManager::Apk);
+        assert!(
+            script
+                .calls()
+                .contains(&packages(&["apk", "add", "--no-cache", "curl", "git"]))
+        );
+
+        // Every failing package is reported, not just the first
+        let err = litebox
+            .provision(&packages(&["curl", "nosuch", "gone"]))
+            .await
+            .unwrap_err();
+        let message = match err {
+            BoxliteError::Execution(message) => message,
+            other => panic!("unexpected error: {other}"),
+        };
+        assert!(message.starts_with("failed to install 2 of 3 packages with apk"));
+        assert!(message.contains("nosuch (exit code 1: ERROR: unable to select packages:"));
+        assert!(message.contains("gone (exit code 2"));
+        assert!(!message.contains("curl"));
+
+        // No package manager in the box
+        let (_bare_home, bare) = test_runtime(BoxliteOptions::default(), MockScript::new());
+        let litebox = bare.create(BoxOptions::default(), None).await.unwrap();
+        assert!(matches!(
+            litebox.provision(&packages(&["curl"])).await,
+            Err(BoxliteError::Unsupported(_))
+        ));
+    }
+}
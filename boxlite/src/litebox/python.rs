@@ -0,0 +1,309 @@
+//! Persistent Python sessions (`LiteBox::python_session`).
+//!
+//! A session is one long-lived `python3` process running a small driver. The
+//! host sends each cell as a JSON line on stdin; the driver runs it in a
+//! namespace kept across cells, like a notebook kernel, and answers with one
+//! JSON line on a private copy of stdout. The driver points the process's own
+//! stdout at stderr first, so output written straight to the file
+//! descriptors (e.g. by subprocesses) cannot corrupt the replies.
+
+use std::sync::Arc;
+
+use futures::StreamExt;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+
+use super::LiteBox;
+use super::exec::{BoxCommand, ExecStdin, ExecStdout, Execution, ExitReason, OutputLines};
+
+/// Runs cells read from stdin. Print output of a cell is captured per cell;
+/// the value of a trailing expression is returned as JSON when it can be
+/// encoded, and always as its `repr()`. SIGINT interrupts the running cell.
+const DRIVER: &str = r#"
+import ast, contextlib, io, json, os, sys, traceback
+proto = os.fdopen(os.dup(1), "w")
+os.dup2(2, 1)
+ns = {"__name__": "__main__"}
+
+def reply(msg):
+    proto.write(json.dumps(msg) + "\n")
+    proto.flush()
+
+def run(code):
+    msg = {}
+    out, err = io.StringIO(), io.StringIO()
+    try:
+        with contextlib.redirect_stdout(out), contextlib.redirect_stderr(err):
+            tree = ast.parse(code, "<cell>", "exec")
+            last = None
+            if tree.body and isinstance(tree.body[-1], ast.Expr):
+                last = ast.Expression(tree.body.pop().value)
+            exec(compile(tree, "<cell>", "exec"), ns)
+            if last is not None:
+                value = eval(compile(last, "<cell>", "eval"), ns)
+                if value is not None:
+                    ns["_"] = value
+                    msg["repr"] = repr(value)
+                    try:
+                        msg["value"] = json.loads(json.dumps(value, allow_nan=False))
+                    except (TypeError, ValueError):
+                        pass
+    except BaseException as e:
+        tb = e.__traceback__.tb_next if e.__traceback__ else None
+        msg["error"] = {
+            "name": type(e).__name__,
+            "value": str(e),
+            "traceback": traceback.format_exception(type(e), e, tb),
+        }
+    msg["stdout"] = out.getvalue()
+    msg["stderr"] = err.getvalue()
+    return msg
+
+reply({"ready": sys.version.split()[0]})
+while True:
+    try:
+        line = sys.stdin.readline()
+    except KeyboardInterrupt:
+        continue
+    if not line:
+        break
+    request = json.loads(line)
+    msg = run(request["code"])
+    msg["id"] = request["id"]
+    reply(msg)
+"#;
+
+/// Result of a code cell run in a [`PythonSession`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CellResult {
+    /// Text the cell printed to `sys.stdout`.
+    #[serde(default)]
+    pub stdout: String,
+    /// Text the cell printed to `sys.stderr`, followed by anything the
+    /// process wrote straight to its stdout or stderr file descriptors.
+    #[serde(default)]
+    pub stderr: String,
+    /// Value of the cell's trailing expression, if it is not `None` and can
+    /// be encoded as JSON.
+    #[serde(default)]
+    pub value: Option<serde_json::Value>,
+    /// `repr()` of the trailing expression's value, if it is not `None`.
+    #[serde(default)]
+    pub repr: Option<String>,
+    /// The exception the cell raised, if any.
+    #[serde(default)]
+    pub error: Option<CellError>,
+}
+
+impl CellResult {
+    /// Whether the cell ran without raising.
+    pub fn success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// An exception raised by a code cell.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CellError {
+    /// Exception class, e.g. `ZeroDivisionError`.
+    pub name: String,
+    /// `str()` of the exception.
+    pub value: String,
+    /// Formatted traceback lines, as `traceback.format_exception` returns them.
+    #[serde(default)]
+    pub traceback: Vec<String>,
+}
+
+/// One reply line from the driver.
+#[derive(Deserialize)]
+struct Reply {
+    #[serde(default)]
+    id: Option<u64>,
+    #[serde(flatten)]
+    cell: CellResult,
+}
+
+/// A long-lived Python interpreter in a box, created by
+/// [`LiteBox::python_session`].
+///
+/// Variables, imports and functions defined by one cell stay visible to the
+/// next, and cells run without the startup cost of a new process. Cells run
+/// one at a time; concurrent [`run`](Self::run) calls wait their turn.
+/// Dropping the session closes the interpreter's stdin, which ends it.
+pub struct PythonSession {
+    execution: Execution,
+    io: tokio::sync::Mutex<SessionIo>,
+    /// Output written straight to the process's file descriptors.
+    stray: Arc<Mutex<Vec<u8>>>,
+    version: String,
+}
+
+struct SessionIo {
+    stdin: ExecStdin,
+    replies: OutputLines<ExecStdout>,
+    next_id: u64,
+}
+
+impl PythonSession {
+    /// Start `python3` in the box and wait until it is ready.
+    pub(crate) async fn start(litebox: &LiteBox) -> BoxliteResult<Self> {
+        let command = BoxCommand::new("python3").args(["-c", DRIVER]);
+        let mut execution = litebox.exec(command).await?;
+        let (Some(stdin), Some(stdout), Some(stderr)) =
+            (execution.stdin(), execution.stdout(), execution.stderr())
+        else {
+            return Err(BoxliteError::Internal(
+                "python session has no stdio streams".into(),
+            ));
+        };
+
+        let stray = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&stray);
+        tokio::spawn(async move {
+            let mut stderr = stderr;
+            while let Some(chunk) = stderr.next().await {
+                sink.lock().extend_from_slice(&chunk);
+            }
+        });
+
+        let mut replies = stdout.lines();
+        let Some(line) = replies.next().await else {
+            let result = execution.wait().await?;
+            let stderr = String::from_utf8_lossy(&stray.lock()).trim().to_string();
+            return Err(BoxliteError::Execution(
+                if matches!(result.reason, Some(ExitReason::CommandNotFound)) {
+                    "python3 is not installed in the box".to_string()
+                } else {
+                    format!(
+                        "python session exited with code {} before it was ready: {}",
+                        result.exit_code, stderr
+                    )
+                },
+            ));
+        };
+        let version = serde_json::from_str::<serde_json::Value>(&line)
+            .ok()
+            .and_then(|ready| ready.get("ready")?.as_str().map(str::to_string))
+            .ok_or_else(|| {
+                BoxliteError::Internal(format!("unexpected python session greeting: {}", line))
+            })?;
+
+        Ok(Self {
+            execution,
+            io: tokio::sync::Mutex::new(SessionIo {
+                stdin,
+                replies,
+                next_id: 1,
+            }),
+            stray,
+            version,
+        })
+    }
+
+    /// Version of the interpreter, e.g. `3.12.1`.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Run a code cell and return what it printed, returned and raised.
+    ///
+    /// An exception in the cell is reported in [`CellResult::error`], not as
+    /// an `Err`; `Err` means the session itself failed, e.g. the
+    /// interpreter exited.
+    pub async fn run(&self, code: &str) -> BoxliteResult<CellResult> {
+        let mut io = self.io.lock().await;
+        let id = io.next_id;
+        io.next_id += 1;
+
+        let mut request = serde_json::json!({ "id": id, "code": code }).to_string();
+        request.push('\n');
+        io.stdin.write_all(request.as_bytes()).await?;
+
+        loop {
+            let Some(line) = io.replies.next().await else {
+                return Err(BoxliteError::Execution(
+                    "python session ended while running a cell".into(),
+                ));
+            };
+            let reply = parse_reply(&line)?;
+            // Replies to earlier cells abandoned by a cancelled run()
+            if reply.id != Some(id) {
+                continue;
+            }
+            let mut cell = reply.cell;
+            let stray = std::mem::take(&mut *self.stray.lock());
+            cell.stderr.push_str(&String::from_utf8_lossy(&stray));
+            return Ok(cell);
+        }
+    }
+
+    /// Interrupt the running cell, which then fails with `KeyboardInterrupt`.
+    ///
+    /// The session stays usable.
+    pub async fn interrupt(&self) -> BoxliteResult<()> {
+        self.execution.signal(libc::SIGINT).await
+    }
+
+    /// End the interpreter and wait for it to exit.
+    pub async fn close(mut self) -> BoxliteResult<()> {
+        self.io.get_mut().stdin.close();
+        self.execution.wait().await?;
+        Ok(())
+    }
+}
+
+fn parse_reply(line: &str) -> BoxliteResult<Reply> {
+    serde_json::from_str(line).map_err(|e| {
+        BoxliteError::Internal(format!("invalid python session reply '{}': {}", line, e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vmm::mock::{MockScript, test_runtime};
+
+    #[test]
+    fn test_parse_reply() {
+        let reply = parse_reply(
+            r#"{"id": 2, "repr": "{'a': 1}", "value": {"a": 1}, "stdout": "hi\n", "stderr": ""}"#,
+        )
+        .unwrap();
+        assert_eq!(reply.id, Some(2));
+        assert_eq!(reply.cell.stdout, "hi\n");
+        assert_eq!(reply.cell.value, Some(serde_json::json!({"a": 1})));
+        assert_eq!(reply.cell.repr.as_deref(), Some("{'a': 1}"));
+        assert!(reply.cell.success());
+
+        let reply = parse_reply(
+            r#"{"id": 3, "error": {"name": "ZeroDivisionError", "value": "division by zero",
+                "traceback": ["Traceback (most recent call last):\n", "ZeroDivisionError: division by zero\n"]},
+                "stdout": "", "stderr": ""}"#,
+        )
+        .unwrap();
+        let error = reply.cell.error.unwrap();
+        assert_eq!(error.name, "ZeroDivisionError");
+        assert_eq!(error.traceback.len(), 2);
+        assert_eq!(reply.cell.value, None);
+
+        assert!(parse_reply("not json").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_python_session_without_python() {
+        use crate::{BoxOptions, BoxliteError, BoxliteOptions};
+
+        let (_home, runtime) = test_runtime(BoxliteOptions::default(), MockScript::new());
+        let litebox = runtime.create(BoxOptions::default(), None).await.unwrap();
+
+        match litebox.python_session().await {
+            Err(BoxliteError::Execution(message)) => {
+                assert_eq!(message, "python3 is not installed in the box")
+            }
+            Err(e) => panic!("unexpected error: {e}"),
+            Ok(_) => panic!("session started without python3"),
+        }
+    }
+}
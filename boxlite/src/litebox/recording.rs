@@ -0,0 +1,377 @@
+//! Recording of execution output to files.
+//!
+//! A recorder tees stdout and stderr into `stdout.log` and `stderr.log`,
+//! rotating them by size, and keeps a `manifest.json` describing the run.
+//! It records whether or not anyone reads the streams.
+
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::mpsc;
+
+use super::exec::{BoxCommand, ExecResult};
+use crate::portal::interfaces::exec::ExecComponents;
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Where and how [`BoxCommand::record_output`] saves output.
+#[derive(Debug, Clone)]
+pub struct OutputRecording {
+    /// Directory for the recording. Defaults to a new directory under
+    /// `~/.boxlite/boxes/{box_id}/recordings`, removed with the box.
+    pub dir: Option<PathBuf>,
+    /// A log is rotated once writing to it would exceed this size.
+    pub max_file_bytes: u64,
+    /// Rotated files kept per stream (`stdout.log.1` is the newest).
+    pub max_rotated_files: usize,
+}
+
+impl Default for OutputRecording {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            max_file_bytes: 16 * 1024 * 1024,
+            max_rotated_files: 4,
+        }
+    }
+}
+
+impl OutputRecording {
+    /// Record into `dir` instead of the box home.
+    pub fn dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.dir = Some(dir.into());
+        self
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Manifest {
+    box_id: String,
+    execution_id: Option<String>,
+    command: Vec<String>,
+    started_at: DateTime<Utc>,
+    finished_at: Option<DateTime<Utc>>,
+    exit_code: Option<i32>,
+    reason: Option<&'static str>,
+    error_message: Option<String>,
+    stdout: StreamManifest,
+    stderr: StreamManifest,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct StreamManifest {
+    /// Files holding this stream, oldest first.
+    files: Vec<String>,
+    /// Bytes written, including rotated-away data.
+    bytes: u64,
+    rotations: u32,
+}
+
+/// A log file rotated by size: `name`, `name.1`, ... `name.{keep}`.
+struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    written: u64,
+    max_bytes: u64,
+    keep: usize,
+}
+
+impl RotatingFile {
+    fn create(path: PathBuf, max_bytes: u64, keep: usize) -> io::Result<Self> {
+        let file = File::create(&path)?;
+        Ok(Self {
+            path,
+            file,
+            written: 0,
+            max_bytes,
+            keep,
+        })
+    }
+
+    fn write(&mut self, data: &[u8], stats: &mut StreamManifest) -> io::Result<()> {
+        if self.written > 0 && self.written + data.len() as u64 > self.max_bytes {
+            self.rotate()?;
+            stats.rotations += 1;
+        }
+        self.file.write_all(data)?;
+        self.written += data.len() as u64;
+        stats.bytes += data.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        for n in (1..self.keep).rev() {
+            match std::fs::rename(self.rotated(n), self.rotated(n + 1)) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+        if self.keep > 0 {
+            std::fs::rename(&self.path, self.rotated(1))?;
+        }
+        self.file = File::create(&self.path)?;
+        self.written = 0;
+        Ok(())
+    }
+
+    fn rotated(&self, n: usize) -> PathBuf {
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    }
+
+    /// File names present on disk, oldest first.
+    fn files(&self) -> Vec<String> {
+        let mut paths: Vec<PathBuf> = (1..=self.keep).rev().map(|n| self.rotated(n)).collect();
+        paths.push(self.path.clone());
+        paths
+            .into_iter()
+            .filter(|path| path.exists())
+            .filter_map(|path| Some(path.file_name()?.to_string_lossy().into_owned()))
+            .collect()
+    }
+}
+
+/// Tees one execution's output into a recording directory.
+pub(crate) struct Recorder {
+    dir: PathBuf,
+    manifest: Manifest,
+    stdout: RotatingFile,
+    stderr: RotatingFile,
+}
+
+impl Recorder {
+    /// Create the recording directory and its files before the command runs.
+    pub(crate) fn create(
+        dir: PathBuf,
+        options: &OutputRecording,
+        box_id: &str,
+        command: &BoxCommand,
+    ) -> BoxliteResult<Self> {
+        let open = |name: &str| {
+            RotatingFile::create(
+                dir.join(name),
+                options.max_file_bytes,
+                options.max_rotated_files,
+            )
+        };
+        let (stdout, stderr) = std::fs::create_dir_all(&dir)
+            .and_then(|_| Ok((open("stdout.log")?, open("stderr.log")?)))
+            .map_err(|e| {
+                BoxliteError::Storage(format!(
+                    "Failed to create output recording in {}: {}",
+                    dir.display(),
+                    e
+                ))
+            })?;
+
+        let mut recorder = Self {
+            dir,
+            manifest: Manifest {
+                box_id: box_id.to_string(),
+                execution_id: None,
+                command: std::iter::once(command.command.clone())
+                    .chain(command.args.iter().cloned())
+                    .collect(),
+                started_at: Utc::now(),
+                finished_at: None,
+                exit_code: None,
+                reason: None,
+                error_message: None,
+                stdout: StreamManifest::default(),
+                stderr: StreamManifest::default(),
+            },
+            stdout,
+            stderr,
+        };
+        recorder.save_manifest();
+        Ok(recorder)
+    }
+
+    pub(crate) fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Record a command that failed to start.
+    pub(crate) fn fail(mut self, err: &BoxliteError) {
+        self.manifest.finished_at = Some(Utc::now());
+        self.manifest.error_message = Some(err.to_string());
+        self.save_manifest();
+    }
+
+    /// Route the execution's output and result through the recorder.
+    ///
+    /// Recording continues after the returned streams are dropped.
+    pub(crate) fn attach(mut self, components: ExecComponents) -> ExecComponents {
+        let ExecComponents {
+            execution_id,
+            stdin_tx,
+            stdout_rx: mut source_stdout,
+            stderr_rx: mut source_stderr,
+            result_rx: mut source_result,
+        } = components;
+        self.manifest.execution_id = Some(execution_id.clone());
+        self.save_manifest();
+
+        let (stdout_tx, stdout_rx) = mpsc::unbounded_channel::<Bytes>();
+        let (stderr_tx, stderr_rx) = mpsc::unbounded_channel::<Bytes>();
+        let (result_tx, result_rx) = mpsc::unbounded_channel::<ExecResult>();
+
+        tokio::spawn(async move {
+            let (mut stdout_open, mut stderr_open, mut result_open) = (true, true, true);
+            loop {
+                tokio::select! {
+                    chunk = source_stdout.recv(), if stdout_open => match chunk {
+                        Some(chunk) => {
+                            self.write_stdout(&chunk);
+                            let _ = stdout_tx.send(chunk);
+                        }
+                        None => stdout_open = false,
+                    },
+                    chunk = source_stderr.recv(), if stderr_open => match chunk {
+                        Some(chunk) => {
+                            self.write_stderr(&chunk);
+                            let _ = stderr_tx.send(chunk);
+                        }
+                        None => stderr_open = false,
+                    },
+                    result = source_result.recv(), if result_open => {
+                        result_open = false;
+                        if let Some(result) = result {
+                            self.manifest.exit_code = Some(result.exit_code);
+                            self.manifest.reason = result.reason.map(|r| r.as_str());
+                            self.manifest.error_message = result.error_message.clone();
+                            self.manifest.finished_at = Some(Utc::now());
+                            // Saved before the caller sees the result
+                            self.save_manifest();
+                            let _ = result_tx.send(result);
+                        }
+                    },
+                    else => break,
+                }
+            }
+            self.manifest.finished_at.get_or_insert_with(Utc::now);
+            self.save_manifest();
+            tracing::debug!(dir = %self.dir.display(), "Output recording finished");
+        });
+
+        ExecComponents {
+            execution_id,
+            stdin_tx,
+            stdout_rx,
+            stderr_rx,
+            result_rx,
+        }
+    }
+
+    fn write_stdout(&mut self, data: &[u8]) {
+        let rotations = self.manifest.stdout.rotations;
+        if let Err(e) = self.stdout.write(data, &mut self.manifest.stdout) {
+            tracing::warn!(error = %e, "Failed to record stdout");
+        }
+        if self.manifest.stdout.rotations != rotations {
+            self.save_manifest();
+        }
+    }
+
+    fn write_stderr(&mut self, data: &[u8]) {
+        let rotations = self.manifest.stderr.rotations;
+        if let Err(e) = self.stderr.write(data, &mut self.manifest.stderr) {
+            tracing::warn!(error = %e, "Failed to record stderr");
+        }
+        if self.manifest.stderr.rotations != rotations {
+            self.save_manifest();
+        }
+    }
+
+    /// Write the manifest atomically so readers never see a partial file.
+    fn save_manifest(&mut self) {
+        self.manifest.stdout.files = self.stdout.files();
+        self.manifest.stderr.files = self.stderr.files();
+        let path = self.dir.join(MANIFEST_FILE);
+        let tmp = self.dir.join(format!("{}.tmp", MANIFEST_FILE));
+        let result = serde_json::to_vec_pretty(&self.manifest)
+            .map_err(io::Error::other)
+            .and_then(|json| std::fs::write(&tmp, json))
+            .and_then(|_| std::fs::rename(&tmp, &path));
+        if let Err(e) = result {
+            tracing::warn!(path = %path.display(), error = %e, "Failed to write recording manifest");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vmm::mock::{MockExec, MockScript, test_runtime};
+    use futures::StreamExt;
+
+    #[test]
+    fn test_rotating_file_keeps_newest() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut stats = StreamManifest::default();
+        let mut file = RotatingFile::create(dir.path().join("out.log"), 4, 2).unwrap();
+        for chunk in ["aaaa", "bbbb", "cc", "dd", "eeeee"] {
+            file.write(chunk.as_bytes(), &mut stats).unwrap();
+        }
+
+        let read = |name: &str| std::fs::read_to_string(dir.path().join(name)).unwrap();
+        assert_eq!(read("out.log"), "eeeee");
+        assert_eq!(read("out.log.1"), "ccdd");
+        assert_eq!(read("out.log.2"), "bbbb");
+        assert!(!dir.path().join("out.log.3").exists());
+        assert_eq!(file.files(), ["out.log.2", "out.log.1", "out.log"]);
+        assert_eq!((stats.bytes, stats.rotations), (17, 3));
+    }
+
+    #[tokio::test]
+    async fn test_record_output() {
+        use crate::{BoxCommand, BoxOptions, BoxliteOptions, OutputRecording};
+
+        let script = MockScript::new().on(
+            &["build"],
+            MockExec::new()
+                .stdout("step 1\nstep 2\n")
+                .stderr("warn\n")
+                .exit_code(2),
+        );
+        let (home, runtime) = test_runtime(BoxliteOptions::default(), script);
+        let litebox = runtime
+            .create(BoxOptions::default(), Some("mock".to_string()))
+            .await
+            .unwrap();
+
+        // Nobody reads the streams; output is recorded anyway
+        let mut execution = litebox
+            .exec(BoxCommand::new("build").record_output(OutputRecording::default()))
+            .await
+            .unwrap();
+        assert_eq!(execution.wait().await.unwrap().exit_code, 2);
+
+        let dir = execution.recording_dir().unwrap().to_path_buf();
+        assert!(dir.starts_with(home.path().join("boxes")));
+        let manifest: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(dir.join("manifest.json")).unwrap()).unwrap();
+        assert_eq!(manifest["exit_code"], 2);
+        assert_eq!(manifest["execution_id"], execution.id().as_str());
+        assert_eq!(manifest["command"], serde_json::json!(["build"]));
+        assert!(manifest["finished_at"].is_string());
+
+        let stdout: Vec<String> = execution.stdout().unwrap().lines().collect().await;
+        assert_eq!(stdout.concat(), "step 1\nstep 2\n");
+        assert_eq!(
+            std::fs::read_to_string(dir.join("stdout.log")).unwrap(),
+            "step 1\nstep 2\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("stderr.log")).unwrap(),
+            "warn\n"
+        );
+        litebox.stop().await.unwrap();
+    }
+}
@@ -0,0 +1,97 @@
+//! SSH endpoint provisioning (`BoxOptions::ssh`).
+//!
+//! Runs after each cold start or restart: installs the authorized keys,
+//! generates missing host keys and starts the image's `sshd`. The host side
+//! is a regular port forward to [`SSH_GUEST_PORT`] set up by the VMM spawn
+//! task.
+
+use futures::StreamExt;
+use tokio_util::sync::CancellationToken;
+
+use boxlite_shared::constants::executor as executor_const;
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+
+use super::exec::{BoxCommand, ExecStderr, Execution};
+use crate::portal::GuestSession;
+use crate::runtime::options::{SSH_GUEST_PORT, SshOptions};
+
+/// Idempotent: keys are appended once and a running sshd is left alone.
+const PROVISION_SCRIPT: &str = r#"set -e
+user="$BOXLITE_SSH_USER"
+sshd=$(command -v sshd || true)
+if [ -z "$sshd" ] && [ -x /usr/sbin/sshd ]; then sshd=/usr/sbin/sshd; fi
+if [ -z "$sshd" ]; then
+  echo "sshd not found in image (install openssh-server)" >&2
+  exit 127
+fi
+home=$(awk -F: -v u="$user" '$1 == u { print $6 }' /etc/passwd)
+if [ -z "$home" ]; then
+  echo "user $user not found in /etc/passwd" >&2
+  exit 1
+fi
+keys="$home/.ssh/authorized_keys"
+mkdir -p "$home/.ssh"
+touch "$keys"
+printf '%s\n' "$BOXLITE_SSH_KEYS" | while IFS= read -r key; do
+  if [ -n "$key" ] && ! grep -qxF -- "$key" "$keys"; then
+    printf '%s\n' "$key" >> "$keys"
+  fi
+done
+chmod 700 "$home/.ssh"
+chmod 600 "$keys"
+chown -R "$user" "$home/.ssh" 2>/dev/null || true
+ssh-keygen -A >/dev/null
+mkdir -p /run/sshd /var/empty
+if [ -f /run/sshd.pid ] && kill -0 "$(cat /run/sshd.pid)" 2>/dev/null; then
+  exit 0
+fi
+"$sshd" -o PidFile=/run/sshd.pid -o Port="$BOXLITE_SSH_PORT"
+"#;
+
+/// Provision SSH access in the box's container.
+pub(crate) async fn provision(
+    session: &GuestSession,
+    container_id: &str,
+    ssh: &SshOptions,
+    shutdown: CancellationToken,
+) -> BoxliteResult<()> {
+    let command = BoxCommand::new("/bin/sh")
+        .args(["-c", PROVISION_SCRIPT])
+        .env(
+            executor_const::ENV_VAR,
+            format!("{}={}", executor_const::CONTAINER_KEY, container_id),
+        )
+        .env("BOXLITE_SSH_USER", ssh.user.clone())
+        .env("BOXLITE_SSH_KEYS", ssh.authorized_keys.join("\n"))
+        .env("BOXLITE_SSH_PORT", SSH_GUEST_PORT.to_string());
+
+    let mut exec_interface = session.execution().await?;
+    let components = exec_interface.exec(command, shutdown).await?;
+    let mut execution = Execution::new(
+        components.execution_id,
+        exec_interface,
+        components.result_rx,
+        None,
+        None,
+        Some(ExecStderr::new(components.stderr_rx)),
+    );
+
+    let mut stderr = execution.stderr();
+    let result = execution.wait().await?;
+    if result.success() {
+        return Ok(());
+    }
+
+    let mut message = Vec::new();
+    if let Some(stderr) = stderr.as_mut() {
+        while let Some(chunk) = stderr.next().await {
+            message.extend_from_slice(&chunk);
+        }
+    }
+    let message = String::from_utf8_lossy(&message);
+    Err(BoxliteError::Config(format!(
+        "failed to provision ssh (exit {}): {}",
+        result.exit_code,
+        message.trim()
+    )))
+}
@@ -4,6 +4,8 @@
 
 use crate::ContainerID;
 use crate::lock::LockId;
+use crate::metrics::{BootPhases, StageTiming};
+use crate::runtime::options::PortSpec;
 use boxlite_shared::errors::{BoxliteError, BoxliteResult};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -32,6 +34,10 @@ pub enum BoxStatus {
     /// Box is running and guest server is accepting commands.
     Running,
 
+    /// Box VM is frozen by pause(); its memory is kept and resume()
+    /// continues it where it left off.
+    Paused,
+
     /// Box is shutting down gracefully (transient state).
     Stopping,
 
@@ -41,9 +47,9 @@ pub enum BoxStatus {
 }
 
 impl BoxStatus {
-    /// Check if this status represents an active VM (process is running).
+    /// Check if this status represents an active VM (process exists).
     pub fn is_active(&self) -> bool {
-        matches!(self, BoxStatus::Running)
+        matches!(self, BoxStatus::Running | BoxStatus::Paused)
     }
 
     pub fn is_running(&self) -> bool {
@@ -58,6 +64,10 @@ impl BoxStatus {
         matches!(self, BoxStatus::Stopped)
     }
 
+    pub fn is_paused(&self) -> bool {
+        matches!(self, BoxStatus::Paused)
+    }
+
     /// Check if this status represents a transient state.
     /// Only Stopping is transient - Configured is a stable state.
     pub fn is_transient(&self) -> bool {
@@ -71,9 +81,9 @@ impl BoxStatus {
     }
 
     /// Check if stop() can be called from this state.
-    /// Only running boxes can be stopped.
+    /// Running and Paused boxes can be stopped.
     pub fn can_stop(&self) -> bool {
-        matches!(self, BoxStatus::Running)
+        matches!(self, BoxStatus::Running | BoxStatus::Paused)
     }
 
     /// Check if remove() can be called from this state.
@@ -109,6 +119,13 @@ impl BoxStatus {
             (Running, Stopping) |
             (Running, Stopped) |
             (Running, Unknown) |
+            // Running ⇄ Paused (pause/resume)
+            (Running, Paused) |
+            (Paused, Running) |
+            // Paused → Stopping/Stopped (stop resumes the VM first)
+            (Paused, Stopping) |
+            (Paused, Stopped) |
+            (Paused, Unknown) |
             // Stopping → Stopped (complete) or Unknown (error)
             (Stopping, Stopped) |
             (Stopping, Unknown) |
@@ -124,6 +141,7 @@ impl BoxStatus {
             BoxStatus::Unknown => "unknown",
             BoxStatus::Configured => "configured",
             BoxStatus::Running => "running",
+            BoxStatus::Paused => "paused",
             BoxStatus::Stopping => "stopping",
             BoxStatus::Stopped => "stopped",
         }
@@ -140,6 +158,7 @@ impl std::str::FromStr for BoxStatus {
             // Legacy: support "starting" for backward compatibility with existing databases
             "starting" => Ok(BoxStatus::Configured),
             "running" => Ok(BoxStatus::Running),
+            "paused" => Ok(BoxStatus::Paused),
             "stopping" => Ok(BoxStatus::Stopping),
             "stopped" => Ok(BoxStatus::Stopped),
             _ => Err(()),
@@ -153,6 +172,99 @@ impl std::fmt::Display for BoxStatus {
     }
 }
 
+/// Guest agent liveness as seen by the heartbeat monitor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    /// The guest agent answers heartbeats.
+    Healthy,
+    /// `failure_threshold` consecutive heartbeats failed: the guest agent
+    /// did not answer, or the box lost its shared network.
+    Unhealthy,
+}
+
+impl HealthStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HealthStatus::Healthy => "healthy",
+            HealthStatus::Unhealthy => "unhealthy",
+        }
+    }
+}
+
+impl std::fmt::Display for HealthStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Heartbeat results for a running box.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BoxHealth {
+    pub status: HealthStatus,
+    /// Consecutive failed heartbeats.
+    pub failing_streak: u32,
+    /// Last answered heartbeat (UTC).
+    pub last_heartbeat: Option<DateTime<Utc>>,
+}
+
+impl BoxHealth {
+    /// Record one heartbeat result.
+    ///
+    /// Returns the new status if this result changed it.
+    pub(crate) fn record(
+        &mut self,
+        ok: bool,
+        now: DateTime<Utc>,
+        failure_threshold: u32,
+    ) -> Option<HealthStatus> {
+        let previous = self.status;
+        if ok {
+            self.failing_streak = 0;
+            self.last_heartbeat = Some(now);
+            self.status = HealthStatus::Healthy;
+        } else {
+            self.failing_streak = self.failing_streak.saturating_add(1);
+            if self.failing_streak >= failure_threshold.max(1) {
+                self.status = HealthStatus::Unhealthy;
+            }
+        }
+        (self.status != previous).then_some(self.status)
+    }
+}
+
+impl Default for BoxHealth {
+    fn default() -> Self {
+        Self {
+            status: HealthStatus::Healthy,
+            failing_streak: 0,
+            last_heartbeat: None,
+        }
+    }
+}
+
+/// Main process of a box's container as last started: the image config
+/// with the box's `entrypoint`, `cmd`, `user` and `working_dir` applied.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContainerProcess {
+    pub entrypoint: Vec<String>,
+    pub cmd: Vec<String>,
+    /// Default working directory of commands run in the box.
+    pub working_dir: String,
+    pub user: String,
+}
+
+impl From<&crate::images::ContainerImageConfig> for ContainerProcess {
+    fn from(config: &crate::images::ContainerImageConfig) -> Self {
+        Self {
+            entrypoint: config.entrypoint.clone(),
+            cmd: config.cmd.clone(),
+            working_dir: config.working_dir.clone(),
+            user: config.user.clone(),
+        }
+    }
+}
+
 /// Dynamic box state (changes during lifecycle).
 ///
 /// This is updated frequently and persisted to database.
@@ -170,6 +282,25 @@ pub struct BoxState {
     /// Allocated when the box is first initialized (not at creation time).
     /// Used to retrieve the lock across process restarts.
     pub lock_id: Option<LockId>,
+    /// Port mappings of the running box, with dynamically assigned host ports.
+    #[serde(default)]
+    pub ports: Vec<PortSpec>,
+    /// Heartbeat results while running (`None` when stopped or unmonitored).
+    #[serde(default)]
+    pub health: Option<BoxHealth>,
+    /// Per-task init pipeline timings of the last start.
+    #[serde(default)]
+    pub init_stages: Vec<StageTiming>,
+    /// Guest-reported boot breakdown of the last start.
+    #[serde(default)]
+    pub boot_phases: Option<BootPhases>,
+    /// A command was killed by the guest kernel out of memory since the
+    /// last start.
+    #[serde(default)]
+    pub oom_killed: bool,
+    /// Main container process of the last start (`None` before the first).
+    #[serde(default)]
+    pub process: Option<ContainerProcess>,
 }
 
 impl BoxState {
@@ -182,6 +313,12 @@ impl BoxState {
             container_id: None,
             last_updated: Utc::now(),
             lock_id: None,
+            ports: Vec::new(),
+            health: None,
+            init_stages: Vec::new(),
+            boot_phases: None,
+            oom_killed: false,
+            process: None,
         }
     }
 
@@ -228,10 +365,12 @@ impl BoxState {
     ///
     /// In our simplified state model, crashed VMs become Stopped
     /// since the rootfs is preserved and can be restarted.
-    /// PID is cleared since the process is no longer alive.
+    /// PID, published ports and health are cleared since the process is no longer alive.
     pub fn mark_stop(&mut self) {
         self.status = BoxStatus::Stopped;
         self.pid = None;
+        self.ports.clear();
+        self.health = None;
         self.last_updated = Utc::now();
     }
 
@@ -244,6 +383,8 @@ impl BoxState {
             self.status = BoxStatus::Stopped;
         }
         self.pid = None;
+        self.ports.clear();
+        self.health = None;
         self.last_updated = Utc::now();
     }
 }
@@ -258,11 +399,58 @@ impl Default for BoxState {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_health_unhealthy_after_threshold_and_recovers() {
+        let now = Utc::now();
+        let mut health = BoxHealth::default();
+
+        assert_eq!(health.record(false, now, 3), None);
+        assert_eq!(health.record(false, now, 3), None);
+        assert_eq!(health.record(false, now, 3), Some(HealthStatus::Unhealthy));
+        assert_eq!(health.record(false, now, 3), None);
+        assert_eq!(health.failing_streak, 4);
+        assert_eq!(health.last_heartbeat, None);
+
+        assert_eq!(health.record(true, now, 3), Some(HealthStatus::Healthy));
+        assert_eq!(health.failing_streak, 0);
+        assert_eq!(health.last_heartbeat, Some(now));
+    }
+
+    #[test]
+    fn test_state_without_health_deserializes() {
+        let mut json = serde_json::to_value(BoxState::new()).unwrap();
+        json.as_object_mut().unwrap().remove("health");
+        let state: BoxState = serde_json::from_value(json).unwrap();
+        assert_eq!(state.health, None);
+    }
+
+    #[test]
+    fn test_state_init_stages_roundtrip() {
+        use crate::metrics::StageOutcome;
+
+        let mut json = serde_json::to_value(BoxState::new()).unwrap();
+        json.as_object_mut().unwrap().remove("init_stages");
+        let state: BoxState = serde_json::from_value(json).unwrap();
+        assert!(state.init_stages.is_empty());
+
+        let mut state = BoxState::new();
+        state.init_stages = vec![StageTiming {
+            name: "vmm_spawn".to_string(),
+            duration_ms: 42,
+            outcome: StageOutcome::Succeeded,
+        }];
+        let json = serde_json::to_value(&state).unwrap();
+        assert_eq!(json["init_stages"][0]["outcome"], "succeeded");
+        let back: BoxState = serde_json::from_value(json).unwrap();
+        assert_eq!(back.init_stages, state.init_stages);
+    }
+
     #[test]
     fn test_status_is_active() {
-        // Only Running is active (VM process running)
+        // Running and Paused are active (VM process exists)
         assert!(!BoxStatus::Configured.is_active());
         assert!(BoxStatus::Running.is_active());
+        assert!(BoxStatus::Paused.is_active());
         assert!(!BoxStatus::Stopping.is_active());
         assert!(!BoxStatus::Stopped.is_active());
         assert!(!BoxStatus::Unknown.is_active());
@@ -289,9 +477,10 @@ mod tests {
 
     #[test]
     fn test_status_can_stop() {
-        // Only Running boxes can be stopped
+        // Running and Paused boxes can be stopped
         assert!(!BoxStatus::Configured.can_stop());
         assert!(BoxStatus::Running.can_stop());
+        assert!(BoxStatus::Paused.can_stop());
         assert!(!BoxStatus::Stopping.can_stop());
         assert!(!BoxStatus::Stopped.can_stop());
         assert!(!BoxStatus::Unknown.can_stop());
@@ -319,6 +508,13 @@ mod tests {
         assert!(BoxStatus::Running.can_transition_to(BoxStatus::Stopped));
         assert!(!BoxStatus::Running.can_transition_to(BoxStatus::Configured));
 
+        // Paused transitions
+        assert!(BoxStatus::Running.can_transition_to(BoxStatus::Paused));
+        assert!(BoxStatus::Paused.can_transition_to(BoxStatus::Running));
+        assert!(BoxStatus::Paused.can_transition_to(BoxStatus::Stopped));
+        assert!(!BoxStatus::Paused.can_transition_to(BoxStatus::Configured));
+        assert!(!BoxStatus::Stopped.can_transition_to(BoxStatus::Paused));
+
         // Stopping transitions
         assert!(BoxStatus::Stopping.can_transition_to(BoxStatus::Stopped));
         assert!(!BoxStatus::Stopping.can_transition_to(BoxStatus::Running));
@@ -0,0 +1,135 @@
+//! Conditions for [`LiteBox::wait_for`](super::LiteBox::wait_for).
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use boxlite_shared::errors::BoxliteError;
+
+/// How often `wait_for` re-checks its condition.
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Guest ping deadline while waiting for `Healthy`.
+pub(crate) const PING_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// What [`LiteBox::wait_for`](super::LiteBox::wait_for) waits for.
+///
+/// Parses from the CLI form: `stopped`, `healthy` or `port:8080`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitCondition {
+    /// The VM has exited: stopped by any process, or crashed.
+    Stopped,
+    /// The guest agent answers a ping.
+    Healthy,
+    /// A TCP or UDP socket in the container listens on this port.
+    Port(u16),
+}
+
+impl fmt::Display for WaitCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaitCondition::Stopped => write!(f, "stopped"),
+            WaitCondition::Healthy => write!(f, "healthy"),
+            WaitCondition::Port(port) => write!(f, "port:{}", port),
+        }
+    }
+}
+
+impl FromStr for WaitCondition {
+    type Err = BoxliteError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "stopped" => Ok(WaitCondition::Stopped),
+            "healthy" => Ok(WaitCondition::Healthy),
+            _ => s
+                .strip_prefix("port:")
+                .and_then(|port| port.parse().ok())
+                .filter(|&port| port != 0)
+                .map(WaitCondition::Port)
+                .ok_or_else(|| {
+                    BoxliteError::InvalidArgument(format!(
+                        "invalid wait condition '{}' (expected stopped, healthy or port:N)",
+                        s
+                    ))
+                }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vmm::mock::{MockExec, MockScript, test_runtime};
+
+    #[test]
+    fn test_parse_wait_condition() {
+        for condition in [
+            WaitCondition::Stopped,
+            WaitCondition::Healthy,
+            WaitCondition::Port(8080),
+        ] {
+            assert_eq!(
+                condition.to_string().parse::<WaitCondition>().unwrap(),
+                condition
+            );
+        }
+        for bad in ["running", "port:", "port:0", "port:70000", "port:http"] {
+            assert!(bad.parse::<WaitCondition>().is_err(), "{}", bad);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wait_for() {
+        use crate::{BoxCommand, BoxOptions, BoxliteError, BoxliteOptions, WaitCondition};
+
+        let (_home, runtime) = test_runtime(
+            BoxliteOptions::default(),
+            MockScript::new().on(
+                &["server"],
+                MockExec::new().delay(Duration::from_secs(300)).listen(8080),
+            ),
+        );
+        let litebox = runtime.create(BoxOptions::default(), None).await.unwrap();
+        let short = Some(Duration::from_millis(300));
+
+        // Not started yet
+        litebox
+            .wait_for(WaitCondition::Stopped, short)
+            .await
+            .unwrap();
+        let err = litebox
+            .wait_for(WaitCondition::Port(8080), short)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BoxliteError::InvalidState(_)));
+
+        litebox.start().await.unwrap();
+        litebox
+            .wait_for(WaitCondition::Healthy, short)
+            .await
+            .unwrap();
+        let err = litebox
+            .wait_for(WaitCondition::Port(8080), short)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BoxliteError::GuestTimeout(_)));
+        let err = litebox
+            .wait_for(WaitCondition::Stopped, short)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BoxliteError::GuestTimeout(_)));
+
+        let _server = litebox.exec(BoxCommand::new("server")).await.unwrap();
+        litebox
+            .wait_for(WaitCondition::Port(8080), short)
+            .await
+            .unwrap();
+
+        litebox.stop().await.unwrap();
+        litebox
+            .wait_for(WaitCondition::Stopped, None)
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,110 @@
+//! File change events from inside a box.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use boxlite_shared::errors::BoxliteResult;
+use futures::Stream;
+use futures::stream::BoxStream;
+
+/// What happened to a watched path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsEventKind {
+    Created,
+    /// A file opened for writing was closed.
+    Modified,
+    Removed,
+    /// First half of a rename; pair with `MovedTo` by `cookie`.
+    MovedFrom,
+    MovedTo,
+    /// The guest dropped events; rescan the watched path.
+    Overflow,
+}
+
+/// One change to a file or directory inside the container.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FsEvent {
+    pub kind: FsEventKind,
+    /// Absolute path inside the container (empty for `Overflow`).
+    pub path: String,
+    pub is_dir: bool,
+    /// Shared by the `MovedFrom`/`MovedTo` events of one rename, 0 otherwise.
+    pub cookie: u32,
+}
+
+impl FsEvent {
+    pub(crate) fn from_proto(event: boxlite_shared::FsEvent) -> Option<Self> {
+        use boxlite_shared::FsEventKind as Kind;
+        let kind = match event.kind() {
+            Kind::Created => FsEventKind::Created,
+            Kind::Modified => FsEventKind::Modified,
+            Kind::Removed => FsEventKind::Removed,
+            Kind::MovedFrom => FsEventKind::MovedFrom,
+            Kind::MovedTo => FsEventKind::MovedTo,
+            Kind::Overflow => FsEventKind::Overflow,
+            Kind::Unspecified => return None,
+        };
+        Some(Self {
+            kind,
+            path: event.path,
+            is_dir: event.is_dir,
+            cookie: event.cookie,
+        })
+    }
+}
+
+/// Stream of [`FsEvent`]s returned by [`LiteBox::watch`](super::LiteBox::watch).
+///
+/// Ends when the watched path is removed or the box stops. Dropping the
+/// stream cancels the watch in the guest.
+pub struct FsWatch {
+    inner: BoxStream<'static, BoxliteResult<FsEvent>>,
+}
+
+impl FsWatch {
+    pub(crate) fn new(inner: BoxStream<'static, BoxliteResult<FsEvent>>) -> Self {
+        Self { inner }
+    }
+}
+
+impl Stream for FsWatch {
+    type Item = BoxliteResult<FsEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vmm::mock::{MockScript, test_runtime};
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn test_watch() {
+        use crate::{BoxOptions, BoxliteOptions, FsEventKind};
+
+        let script = MockScript::new()
+            .fs_event(FsEventKind::Created, "/app/main.py")
+            .fs_event(FsEventKind::Modified, "/app/lib/util.py")
+            .fs_event(FsEventKind::Removed, "/etc/hosts");
+        let (_home, runtime) = test_runtime(BoxliteOptions::default(), script);
+        let litebox = runtime.create(BoxOptions::default(), None).await.unwrap();
+        litebox.start().await.unwrap();
+
+        let mut events = litebox.watch("/app", true).await.unwrap();
+        let first = events.next().await.unwrap().unwrap();
+        assert_eq!(first.kind, FsEventKind::Created);
+        assert_eq!(first.path, "/app/main.py");
+        let second = events.next().await.unwrap().unwrap();
+        assert_eq!(second.path, "/app/lib/util.py");
+
+        let mut shallow = litebox.watch("/app", false).await.unwrap();
+        assert_eq!(shallow.next().await.unwrap().unwrap().path, "/app/main.py");
+
+        // Stopping the box ends open watches
+        litebox.stop().await.unwrap();
+        assert!(events.next().await.is_none());
+        assert!(shallow.next().await.is_none());
+    }
+}
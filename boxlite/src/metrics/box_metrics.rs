@@ -1,7 +1,95 @@
 //! Per-box metrics (individual LiteBox statistics).
 
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
 
+/// How an initialization task ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StageOutcome {
+    /// Task ran to completion.
+    Succeeded,
+    /// Task returned an error, aborting initialization.
+    Failed,
+    /// Task was interrupted because a parallel task failed.
+    Cancelled,
+    /// Task never ran because an earlier stage failed.
+    Skipped,
+}
+
+impl StageOutcome {
+    /// Lowercase name, matching the serialized form.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StageOutcome::Succeeded => "succeeded",
+            StageOutcome::Failed => "failed",
+            StageOutcome::Cancelled => "cancelled",
+            StageOutcome::Skipped => "skipped",
+        }
+    }
+}
+
+/// Timing of a single initialization pipeline task.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageTiming {
+    /// Task name (e.g. `container_rootfs_prep`, `vmm_spawn`)
+    pub name: String,
+    /// Wall-clock task duration (milliseconds)
+    pub duration_ms: u128,
+    /// How the task ended
+    pub outcome: StageOutcome,
+}
+
+/// Where a box spent its boot, as reported by the guest.
+///
+/// Complements the host-side [`StageTiming`]s: `guest_connect` covers
+/// `kernel_ms` and `agent_startup_ms`, `guest_init` covers the rest.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BootPhases {
+    /// Guest kernel start until the guest agent started (milliseconds)
+    pub kernel_ms: u64,
+    /// Guest agent start until it served requests (milliseconds)
+    pub agent_startup_ms: u64,
+    /// Mounting the box's volumes in the guest (milliseconds)
+    pub mount_ms: u64,
+    /// Configuring the guest network; 0 with networking disabled (milliseconds)
+    pub network_ms: u64,
+    /// Preparing the rootfs and starting the container (milliseconds)
+    pub container_init_ms: u64,
+}
+
+/// Exec output byte counters, shared with the tasks that stream output.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ExecOutputCounters {
+    /// Output bytes delivered to callers (after decompression)
+    pub(crate) logical: Arc<AtomicU64>,
+    /// Output payload bytes received from the guest (before decompression)
+    pub(crate) wire: Arc<AtomicU64>,
+}
+
+impl ExecOutputCounters {
+    /// Record one output chunk.
+    pub(crate) fn add(&self, wire: u64, logical: u64) {
+        self.wire.fetch_add(wire, Ordering::Relaxed);
+        self.logical.fetch_add(logical, Ordering::Relaxed);
+    }
+}
+
+/// Network counters of a box as last read from its network backend.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct NetworkUsage {
+    /// Bytes sent from host to guest
+    pub(crate) bytes_sent: u64,
+    /// Bytes received from guest to host
+    pub(crate) bytes_received: u64,
+    /// TCP connections currently established
+    pub(crate) tcp_connections: u64,
+    /// Failed TCP connection attempts
+    pub(crate) tcp_errors: u64,
+}
+
 /// Storage for per-box metrics.
 ///
 /// Stored in `BoxMetadata`, one instance per box.
@@ -14,14 +102,18 @@ pub struct BoxMetricsStorage {
     pub(crate) exec_errors: AtomicU64,
     /// Bytes sent to this box (via stdin)
     pub(crate) bytes_sent: AtomicU64,
-    /// Bytes received from this box (via stdout/stderr)
-    pub(crate) bytes_received: AtomicU64,
+    /// Bytes received from this box (via stdout/stderr), logical and on the wire
+    pub(crate) exec_output: ExecOutputCounters,
+    /// Network counters from the backend (None until first read)
+    pub(crate) network: Mutex<Option<NetworkUsage>>,
 
     // Timing metrics (set once, never change)
     /// Total time from create() call to LiteBox ready (includes all stages)
     pub(crate) total_create_duration_ms: Option<u128>,
     /// Time from box subprocess spawn to guest agent ready
     pub(crate) guest_boot_duration_ms: Option<u128>,
+    /// Init steps retried after a transient failure during the last start
+    pub(crate) create_retries: u32,
 
     // Stage-level timing breakdown (set once during initialization)
     /// Time to create box directory structure (Stage 1)
@@ -36,6 +128,10 @@ pub struct BoxMetricsStorage {
     pub(crate) stage_box_spawn_ms: Option<u128>,
     /// Time to initialize container inside guest (Stage 6)
     pub(crate) stage_container_init_ms: Option<u128>,
+    /// Every init pipeline task, in execution order
+    pub(crate) init_stages: Vec<StageTiming>,
+    /// Guest boot breakdown (None for reattached boxes and older agents)
+    pub(crate) boot_phases: Option<BootPhases>,
 }
 
 impl Clone for BoxMetricsStorage {
@@ -44,15 +140,26 @@ impl Clone for BoxMetricsStorage {
             commands_executed: AtomicU64::new(self.commands_executed.load(Ordering::Relaxed)),
             exec_errors: AtomicU64::new(self.exec_errors.load(Ordering::Relaxed)),
             bytes_sent: AtomicU64::new(self.bytes_sent.load(Ordering::Relaxed)),
-            bytes_received: AtomicU64::new(self.bytes_received.load(Ordering::Relaxed)),
+            exec_output: ExecOutputCounters {
+                logical: Arc::new(AtomicU64::new(
+                    self.exec_output.logical.load(Ordering::Relaxed),
+                )),
+                wire: Arc::new(AtomicU64::new(
+                    self.exec_output.wire.load(Ordering::Relaxed),
+                )),
+            },
+            network: Mutex::new(*self.network.lock()),
             total_create_duration_ms: self.total_create_duration_ms,
             guest_boot_duration_ms: self.guest_boot_duration_ms,
+            create_retries: self.create_retries,
             stage_filesystem_setup_ms: self.stage_filesystem_setup_ms,
             stage_image_prepare_ms: self.stage_image_prepare_ms,
             stage_guest_rootfs_ms: self.stage_guest_rootfs_ms,
             stage_box_config_ms: self.stage_box_config_ms,
             stage_box_spawn_ms: self.stage_box_spawn_ms,
             stage_container_init_ms: self.stage_container_init_ms,
+            init_stages: self.init_stages.clone(),
+            boot_phases: self.boot_phases.clone(),
         }
     }
 }
@@ -69,11 +176,15 @@ impl BoxMetricsStorage {
     }
 
     /// Set guest boot duration (called once after guest is ready).
-    #[allow(dead_code)] // API designed but not yet wired up
     pub(crate) fn set_guest_boot_duration(&mut self, duration_ms: u128) {
         self.guest_boot_duration_ms = Some(duration_ms);
     }
 
+    /// Set the number of init steps retried (called once per start).
+    pub(crate) fn set_create_retries(&mut self, retries: u32) {
+        self.create_retries = retries;
+    }
+
     /// Set filesystem setup stage duration.
     pub(crate) fn set_stage_filesystem_setup(&mut self, duration_ms: u128) {
         self.stage_filesystem_setup_ms = Some(duration_ms);
@@ -105,10 +216,32 @@ impl BoxMetricsStorage {
         self.stage_container_init_ms = Some(duration_ms);
     }
 
+    /// Set per-task init pipeline timings.
+    pub(crate) fn set_init_stages(&mut self, init_stages: Vec<StageTiming>) {
+        self.init_stages = init_stages;
+    }
+
+    /// Set the guest boot breakdown.
+    pub(crate) fn set_boot_phases(&mut self, boot_phases: BootPhases) {
+        self.boot_phases = Some(boot_phases);
+    }
+
     /// Log init stage durations for debugging.
     pub(crate) fn log_init_stages(&self) {
+        if let Some(boot) = &self.boot_phases {
+            tracing::debug!(
+                kernel_ms = boot.kernel_ms,
+                agent_startup_ms = boot.agent_startup_ms,
+                mount_ms = boot.mount_ms,
+                network_ms = boot.network_ms,
+                container_init_ms = boot.container_init_ms,
+                "Box boot phases"
+            );
+        }
         tracing::debug!(
             total_create_duration_ms = self.total_create_duration_ms.unwrap_or(0),
+            guest_boot_duration_ms = self.guest_boot_duration_ms.unwrap_or(0),
+            create_retries = self.create_retries,
             stage_filesystem_setup_ms = self.stage_filesystem_setup_ms.unwrap_or(0),
             stage_image_prepare_ms = self.stage_image_prepare_ms.unwrap_or(0),
             stage_guest_rootfs_ms = self.stage_guest_rootfs_ms.unwrap_or(0),
@@ -129,17 +262,16 @@ impl BoxMetricsStorage {
         self.exec_errors.fetch_add(1, Ordering::Relaxed);
     }
 
+    /// Store the network counters just read from the backend.
+    pub(crate) fn record_network(&self, usage: NetworkUsage) {
+        *self.network.lock() = Some(usage);
+    }
+
     /// Add bytes sent to counter.
     #[allow(dead_code)]
     pub(crate) fn add_bytes_sent(&self, bytes: u64) {
         self.bytes_sent.fetch_add(bytes, Ordering::Relaxed);
     }
-
-    /// Add bytes received to counter.
-    #[allow(dead_code)]
-    pub(crate) fn add_bytes_received(&self, bytes: u64) {
-        self.bytes_received.fetch_add(bytes, Ordering::Relaxed);
-    }
 }
 
 /// Handle for querying per-box metrics.
@@ -156,10 +288,14 @@ pub struct BoxMetrics {
     pub bytes_sent_total: u64,
     /// Bytes received from this box (via stdout/stderr)
     pub bytes_received_total: u64,
+    /// Bytes of stdout/stderr on the host-guest channel (compressed size)
+    pub bytes_received_wire_total: u64,
     /// Total time from create() call to LiteBox ready (milliseconds)
     pub total_create_duration_ms: Option<u128>,
     /// Time from box subprocess spawn to guest agent ready (milliseconds)
     pub guest_boot_duration_ms: Option<u128>,
+    /// Init steps retried after a transient failure during the last start
+    pub create_retries: u32,
     /// CPU usage percent (0.0-100.0)
     pub cpu_percent: Option<f32>,
     /// Memory usage in bytes
@@ -172,6 +308,10 @@ pub struct BoxMetrics {
     pub network_tcp_connections: Option<u64>,
     /// Total TCP connection errors
     pub network_tcp_errors: Option<u64>,
+    /// Guest free memory (MemFree) after the last cache reclaim (bytes)
+    pub cache_reclaim_free_bytes: Option<u64>,
+    /// Guest free memory gained by cache reclaims over the box lifetime (bytes)
+    pub cache_reclaimed_bytes_total: u64,
 
     // Stage-level timing breakdown
     /// Time to create box directory structure (milliseconds)
@@ -186,6 +326,10 @@ pub struct BoxMetrics {
     pub stage_box_spawn_ms: Option<u128>,
     /// Time to initialize container inside guest (milliseconds)
     pub stage_container_init_ms: Option<u128>,
+    /// Every init pipeline task with its duration and outcome
+    pub init_stages: Vec<StageTiming>,
+    /// Guest-reported boot breakdown of the last start
+    pub boot_phases: Option<BootPhases>,
 }
 
 impl BoxMetrics {
@@ -194,30 +338,35 @@ impl BoxMetrics {
         storage: &BoxMetricsStorage,
         cpu_percent: Option<f32>,
         memory_bytes: Option<u64>,
-        network_bytes_sent: Option<u64>,
-        network_bytes_received: Option<u64>,
-        network_tcp_connections: Option<u64>,
-        network_tcp_errors: Option<u64>,
+        cache_reclaim_free_bytes: Option<u64>,
+        cache_reclaimed_bytes_total: u64,
     ) -> Self {
+        let network = *storage.network.lock();
         Self {
             commands_executed_total: storage.commands_executed.load(Ordering::Relaxed),
             exec_errors_total: storage.exec_errors.load(Ordering::Relaxed),
             bytes_sent_total: storage.bytes_sent.load(Ordering::Relaxed),
-            bytes_received_total: storage.bytes_received.load(Ordering::Relaxed),
+            bytes_received_total: storage.exec_output.logical.load(Ordering::Relaxed),
+            bytes_received_wire_total: storage.exec_output.wire.load(Ordering::Relaxed),
             total_create_duration_ms: storage.total_create_duration_ms,
             guest_boot_duration_ms: storage.guest_boot_duration_ms,
+            create_retries: storage.create_retries,
             cpu_percent,
             memory_bytes,
-            network_bytes_sent,
-            network_bytes_received,
-            network_tcp_connections,
-            network_tcp_errors,
+            network_bytes_sent: network.map(|n| n.bytes_sent),
+            network_bytes_received: network.map(|n| n.bytes_received),
+            network_tcp_connections: network.map(|n| n.tcp_connections),
+            network_tcp_errors: network.map(|n| n.tcp_errors),
+            cache_reclaim_free_bytes,
+            cache_reclaimed_bytes_total,
             stage_filesystem_setup_ms: storage.stage_filesystem_setup_ms,
             stage_image_prepare_ms: storage.stage_image_prepare_ms,
             stage_guest_rootfs_ms: storage.stage_guest_rootfs_ms,
             stage_box_config_ms: storage.stage_box_config_ms,
             stage_box_spawn_ms: storage.stage_box_spawn_ms,
             stage_container_init_ms: storage.stage_container_init_ms,
+            init_stages: storage.init_stages.clone(),
+            boot_phases: storage.boot_phases.clone(),
         }
     }
 
@@ -251,6 +400,15 @@ impl BoxMetrics {
         self.bytes_received_total
     }
 
+    /// Total stdout/stderr bytes on the host-guest channel.
+    ///
+    /// Equals `bytes_received_total` without exec compression; the ratio of
+    /// the two is the compression ratio achieved.
+    /// Never decreases (monotonic counter).
+    pub fn bytes_received_wire_total(&self) -> u64 {
+        self.bytes_received_wire_total
+    }
+
     /// Total time from create() call to box ready (milliseconds).
     ///
     /// Includes all initialization stages: filesystem setup, image pull,
@@ -268,6 +426,12 @@ impl BoxMetrics {
         self.guest_boot_duration_ms
     }
 
+    /// Init steps (image pull, guest connect) retried after a transient
+    /// failure during the last start of this box.
+    pub fn create_retries(&self) -> u32 {
+        self.create_retries
+    }
+
     /// CPU usage percent (0.0-100.0).
     ///
     /// Returns None if box not started or process not found.
@@ -310,6 +474,23 @@ impl BoxMetrics {
         self.network_tcp_errors
     }
 
+    /// Guest free memory (MemFree) after the last cache reclaim (bytes).
+    ///
+    /// Measured inside the guest; host memory use does not change (see
+    /// `BoxliteOptions::cache_reclaim`). Drops back to 0 when the box
+    /// becomes active again.
+    /// Returns None if cache reclaim is disabled (`BoxliteOptions::cache_reclaim`).
+    pub fn cache_reclaim_free_bytes(&self) -> Option<u64> {
+        self.cache_reclaim_free_bytes
+    }
+
+    /// Total guest free memory gained by cache reclaims (bytes).
+    ///
+    /// Never decreases (monotonic counter).
+    pub fn cache_reclaimed_bytes_total(&self) -> u64 {
+        self.cache_reclaimed_bytes_total
+    }
+
     // Stage-level timing getters
 
     /// Time to create box directory structure (milliseconds).
@@ -364,4 +545,20 @@ impl BoxMetrics {
     pub fn stage_container_init_ms(&self) -> Option<u128> {
         self.stage_container_init_ms
     }
+
+    /// Per-task timings of the initialization pipeline (execution order).
+    ///
+    /// Finer-grained than the `stage_*` getters: one entry per pipeline
+    /// task, including tasks that don't map to a named stage.
+    pub fn init_stages(&self) -> &[StageTiming] {
+        &self.init_stages
+    }
+
+    /// Where the guest spent its boot during the last start.
+    ///
+    /// Returns None for a reattached box or a guest agent too old to
+    /// report it.
+    pub fn boot_phases(&self) -> Option<&BootPhases> {
+        self.boot_phases.as_ref()
+    }
 }
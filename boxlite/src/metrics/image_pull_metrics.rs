@@ -0,0 +1,49 @@
+//! Per-pull image metrics (registry downloads and layer cache hits).
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Statistics of one image pull from a registry.
+///
+/// Recorded when an image is pulled over the network (fully cached images
+/// return without a pull) and persisted in the database.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImagePullMetrics {
+    /// Requested image reference (cache key, regardless of serving mirror)
+    pub reference: String,
+    /// Manifest digest of the pulled image
+    pub manifest_digest: String,
+    /// When the pull completed (UTC)
+    pub pulled_at: DateTime<Utc>,
+    /// Wall-clock pull duration, manifest to config (milliseconds)
+    pub duration_ms: u128,
+    /// Layer bytes downloaded (sum of fetched layer sizes)
+    pub bytes_downloaded: u64,
+    /// Per-layer statistics, in manifest order
+    pub layers: Vec<LayerPullMetrics>,
+}
+
+/// Statistics of one layer within an image pull.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayerPullMetrics {
+    /// Layer digest (sha256:...)
+    pub digest: String,
+    /// Compressed layer size in bytes
+    pub size_bytes: u64,
+    /// Whether the layer was already in the local cache
+    pub cached: bool,
+    /// Time to download (fetched) or verify (cached) the layer (milliseconds)
+    pub duration_ms: u128,
+}
+
+impl ImagePullMetrics {
+    /// Number of layers served from the local cache.
+    pub fn layers_cached(&self) -> usize {
+        self.layers.iter().filter(|layer| layer.cached).count()
+    }
+
+    /// Number of layers downloaded from the registry.
+    pub fn layers_fetched(&self) -> usize {
+        self.layers.len() - self.layers_cached()
+    }
+}
@@ -0,0 +1,41 @@
+//! Persisted per-box resource samples (see `BoxliteOptions::metrics_history`).
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::BoxMetrics;
+
+/// Resource usage of a box at one point in time.
+///
+/// Recorded by the metrics history sampler while a box runs and kept in the
+/// database after it stops, see `LiteBox::metrics_history`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricsSample {
+    /// When the sample was taken (UTC)
+    pub sampled_at: DateTime<Utc>,
+    /// CPU usage percent (0.0-100.0)
+    pub cpu_percent: Option<f32>,
+    /// Memory usage in bytes
+    pub memory_bytes: Option<u64>,
+    /// Network bytes sent from host to guest (monotonic)
+    pub network_bytes_sent: Option<u64>,
+    /// Network bytes received from guest to host (monotonic)
+    pub network_bytes_received: Option<u64>,
+    /// TCP connections established at sample time
+    #[serde(default)]
+    pub network_tcp_connections: Option<u64>,
+}
+
+impl MetricsSample {
+    /// Sample the resource fields of a metrics snapshot.
+    pub(crate) fn from_metrics(metrics: &BoxMetrics, sampled_at: DateTime<Utc>) -> Self {
+        Self {
+            sampled_at,
+            cpu_percent: metrics.cpu_percent,
+            memory_bytes: metrics.memory_bytes,
+            network_bytes_sent: metrics.network_bytes_sent,
+            network_bytes_received: metrics.network_bytes_received,
+            network_tcp_connections: metrics.network_tcp_connections,
+        }
+    }
+}
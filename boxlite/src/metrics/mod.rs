@@ -4,6 +4,10 @@
 //! - **RuntimeMetrics**: Aggregate metrics across all boxes (like Tokio's RuntimeMetrics)
 //! - **BoxMetrics**: Per-box metrics for individual LiteBox instances (like Tokio's TaskMetrics)
 //!
+//! `ImagePullMetrics` records each registry pull separately and is persisted
+//! in the database, see `ImageInfo::last_pull`. `MetricsSample` rows are the
+//! per-box resource history, see `LiteBox::metrics_history`.
+//!
 //! # Design
 //!
 //! All counters are monotonic (never decrease). Delta calculation is the caller's
@@ -31,7 +35,12 @@
 //! ```
 
 mod box_metrics;
+mod image_pull_metrics;
+mod metrics_sample;
 mod runtime_metrics;
 
-pub use box_metrics::{BoxMetrics, BoxMetricsStorage};
+pub use box_metrics::{BootPhases, BoxMetrics, BoxMetricsStorage, StageOutcome, StageTiming};
+pub(crate) use box_metrics::{ExecOutputCounters, NetworkUsage};
+pub use image_pull_metrics::{ImagePullMetrics, LayerPullMetrics};
+pub use metrics_sample::MetricsSample;
 pub use runtime_metrics::{RuntimeMetrics, RuntimeMetricsStorage};
@@ -19,6 +19,16 @@ pub struct RuntimeMetricsStorage {
     pub(crate) total_commands: Arc<AtomicU64>,
     /// Total command execution errors across all boxes
     pub(crate) total_exec_errors: Arc<AtomicU64>,
+    /// Total box creations rejected by admission control
+    pub(crate) admission_rejections: Arc<AtomicU64>,
+    /// Total image layer bytes downloaded from registries
+    pub(crate) image_pull_bytes: Arc<AtomicU64>,
+    /// Total image layers found in the local cache during registry pulls
+    pub(crate) image_layers_cached: Arc<AtomicU64>,
+    /// Total image layers downloaded during registry pulls
+    pub(crate) image_layers_fetched: Arc<AtomicU64>,
+    /// Total init steps retried after a transient failure
+    pub(crate) create_retries: Arc<AtomicU64>,
 }
 
 impl RuntimeMetricsStorage {
@@ -92,6 +102,46 @@ impl RuntimeMetrics {
     pub fn total_exec_errors(&self) -> u64 {
         self.storage.total_exec_errors.load(Ordering::Relaxed)
     }
+
+    /// Total box creations/starts rejected by admission control.
+    ///
+    /// Incremented when `max_running_boxes` or `max_total_memory_mib`
+    /// would be exceeded. Never decreases (monotonic counter).
+    pub fn admission_rejections_total(&self) -> u64 {
+        self.storage.admission_rejections.load(Ordering::Relaxed)
+    }
+
+    /// Total image layer bytes downloaded from registries.
+    ///
+    /// Sample twice and divide the delta by the interval for current pull
+    /// throughput. Never decreases (monotonic counter).
+    pub fn image_pull_bytes_total(&self) -> u64 {
+        self.storage.image_pull_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Total image layers already in the local cache during registry pulls.
+    ///
+    /// Together with `image_layers_fetched_total()` this gives the layer
+    /// cache hit ratio. Never decreases (monotonic counter).
+    pub fn image_layers_cached_total(&self) -> u64 {
+        self.storage.image_layers_cached.load(Ordering::Relaxed)
+    }
+
+    /// Total image layers downloaded from registries.
+    ///
+    /// Never decreases (monotonic counter).
+    pub fn image_layers_fetched_total(&self) -> u64 {
+        self.storage.image_layers_fetched.load(Ordering::Relaxed)
+    }
+
+    /// Total init steps (image pull, guest connect) retried after a
+    /// transient failure, across all boxes.
+    ///
+    /// Incremented once per retry, see `BoxliteOptions::create_retries`.
+    /// Never decreases (monotonic counter).
+    pub fn create_retries_total(&self) -> u64 {
+        self.storage.create_retries.load(Ordering::Relaxed)
+    }
 }
 
 #[cfg(test)]
@@ -152,4 +202,15 @@ mod tests {
         storage.boxes_stopped.fetch_add(3, Ordering::Relaxed);
         assert_eq!(metrics.boxes_stopped_total(), 3);
     }
+
+    #[test]
+    fn test_admission_rejections_total() {
+        let storage = RuntimeMetricsStorage::new();
+        let metrics = RuntimeMetrics::new(storage.clone());
+
+        assert_eq!(metrics.admission_rejections_total(), 0);
+
+        storage.admission_rejections.fetch_add(2, Ordering::Relaxed);
+        assert_eq!(metrics.admission_rejections_total(), 2);
+    }
 }
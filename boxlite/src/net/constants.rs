@@ -3,9 +3,19 @@
 //! These constants define the virtual network topology and must
 //! remain consistent across the host runtime and network backend.
 
+use std::net::Ipv4Addr;
+
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+
 /// Virtual network subnet configuration
 pub const SUBNET: &str = "192.168.127.0/24";
 
+/// Network address of [`SUBNET`]
+pub const SUBNET_ADDR: Ipv4Addr = Ipv4Addr::new(192, 168, 127, 0);
+
+/// Prefix length of [`SUBNET`]
+pub const SUBNET_PREFIX_LEN: u8 = 24;
+
 /// Gateway IP address (gvproxy listens here)
 /// Also serves as DNS server for guest containers
 pub const GATEWAY_IP: &str = "192.168.127.1";
@@ -13,6 +23,22 @@ pub const GATEWAY_IP: &str = "192.168.127.1";
 /// Guest IP address (assigned via DHCP static lease)
 pub const GUEST_IP: &str = "192.168.127.2";
 
+/// [`GUEST_IP`] as an address
+pub const GUEST_IP_ADDR: Ipv4Addr = Ipv4Addr::new(192, 168, 127, 2);
+
+/// Address the guest reaches the host's loopback interface at, as
+/// [`HOST_LOOPBACK_NAME`]
+///
+/// gvproxy answers for it next to the gateway and NATs connections to the
+/// host's 127.0.0.1.
+pub const HOST_LOOPBACK_IP: &str = "192.168.127.254";
+
+/// [`HOST_LOOPBACK_IP`] as an address
+pub const HOST_LOOPBACK_IP_ADDR: Ipv4Addr = Ipv4Addr::new(192, 168, 127, 254);
+
+/// Hostname of the host inside a box (like `host.docker.internal`)
+pub const HOST_LOOPBACK_NAME: &str = boxlite_shared::constants::container::HOST_LOOPBACK_HOSTNAME;
+
 /// Gateway MAC address
 ///
 /// This MAC is used by gvproxy's virtual network interface.
@@ -50,10 +76,88 @@ pub fn mac_to_string(mac: &[u8; 6]) -> String {
     )
 }
 
+/// Parse a guest MAC address in `aa:bb:cc:dd:ee:ff` form (`-` separators
+/// are accepted too).
+///
+/// Rejects multicast addresses, the all-zero address and the gateway's.
+pub fn parse_mac(s: &str) -> BoxliteResult<[u8; 6]> {
+    let invalid = |why: &str| BoxliteError::Config(format!("invalid MAC address '{}': {}", s, why));
+    let parts: Vec<&str> = s.split([':', '-']).collect();
+    if parts.len() != 6 {
+        return Err(invalid("expected six hex bytes, e.g. 02:42:ac:11:00:02"));
+    }
+    let mut mac = [0u8; 6];
+    for (byte, part) in mac.iter_mut().zip(&parts) {
+        if part.len() != 2 {
+            return Err(invalid("expected six hex bytes, e.g. 02:42:ac:11:00:02"));
+        }
+        *byte = u8::from_str_radix(part, 16).map_err(|_| invalid("not a hex byte"))?;
+    }
+    if mac[0] & 0x01 != 0 {
+        return Err(invalid("multicast addresses cannot be assigned"));
+    }
+    if mac == [0; 6] || mac == GATEWAY_MAC {
+        return Err(invalid("address is reserved"));
+    }
+    Ok(mac)
+}
+
+/// Parse a static guest IP, e.g. `192.168.127.10`.
+///
+/// Must lie inside [`SUBNET`]; its network, broadcast, gateway and host
+/// loopback addresses are rejected.
+pub fn parse_guest_ip(s: &str) -> BoxliteResult<Ipv4Addr> {
+    let invalid = |why: &str| BoxliteError::Config(format!("invalid IP address '{}': {}", s, why));
+    let ip: Ipv4Addr = s
+        .parse()
+        .map_err(|_| invalid("expected an IPv4 address, e.g. 192.168.127.10"))?;
+    let mask = u32::MAX << (32 - SUBNET_PREFIX_LEN);
+    if u32::from(ip) & mask != u32::from(SUBNET_ADDR) {
+        return Err(invalid(&format!(
+            "must be inside the box subnet {}",
+            SUBNET
+        )));
+    }
+    let host = u32::from(ip) & !mask;
+    if host == 0 || host == !mask || ip.to_string() == GATEWAY_IP || ip == HOST_LOOPBACK_IP_ADDR {
+        return Err(invalid("address is reserved"));
+    }
+    Ok(ip)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_subnet_constants_agree() {
+        assert_eq!(format!("{}/{}", SUBNET_ADDR, SUBNET_PREFIX_LEN), SUBNET);
+        assert_eq!(GUEST_IP_ADDR.to_string(), GUEST_IP);
+        assert_eq!(HOST_LOOPBACK_IP_ADDR.to_string(), HOST_LOOPBACK_IP);
+    }
+
+    #[test]
+    fn test_parse_guest_ip() {
+        assert_eq!(
+            parse_guest_ip("192.168.127.10").unwrap(),
+            Ipv4Addr::new(192, 168, 127, 10)
+        );
+        assert_eq!(parse_guest_ip(GUEST_IP).unwrap().to_string(), GUEST_IP);
+        for bad in [
+            "",
+            "10.0.0.5",
+            "192.168.128.2",
+            "192.168.127.0",
+            "192.168.127.255",
+            GATEWAY_IP,
+            HOST_LOOPBACK_IP,
+            "::1",
+            "192.168.127.10/24",
+        ] {
+            assert!(parse_guest_ip(bad).is_err(), "{}", bad);
+        }
+    }
+
     #[test]
     fn test_mac_to_string() {
         assert_eq!(mac_to_string(&GUEST_MAC), GUEST_MAC_STRING);
@@ -69,4 +173,24 @@ mod tests {
         assert_eq!(GUEST_MAC[5], 0xee);
         assert_eq!(GATEWAY_MAC[5], 0xdd);
     }
+
+    #[test]
+    fn test_parse_mac() {
+        assert_eq!(
+            parse_mac("02:42:AC:11:00:02").unwrap(),
+            [0x02, 0x42, 0xac, 0x11, 0x00, 0x02]
+        );
+        assert_eq!(parse_mac("02-42-ac-11-00-02").unwrap()[5], 0x02);
+        assert_eq!(parse_mac(GUEST_MAC_STRING).unwrap(), GUEST_MAC);
+        for bad in [
+            "02:42:ac:11:00",
+            "02:42:ac:11:00:2",
+            "02:42:ac:11:00:zz",
+            "01:00:5e:00:00:01",
+            "00:00:00:00:00:00",
+            GATEWAY_MAC_STRING,
+        ] {
+            assert!(parse_mac(bad).is_err(), "{}", bad);
+        }
+    }
 }
@@ -0,0 +1,415 @@
+//! Client for the network backend's port forwarder API.
+//!
+//! The gvproxy backend runs inside the shim process and serves its forwarder
+//! API on the box's `net.sock` (see [`BoxFilesystemLayout::net_socket_path`]).
+//! The runtime uses it to list, add and remove port forwards without
+//! restarting the VM, and to read the traffic counters of the box's network.
+//!
+//! [`BoxFilesystemLayout::net_socket_path`]: crate::runtime::layout::BoxFilesystemLayout::net_socket_path
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+use http_body_util::{BodyExt, Full};
+use hyper::body::Bytes;
+use hyper::header::{CONTENT_TYPE, HOST};
+use hyper::{Method, Request};
+use hyper_util::rt::TokioIo;
+use serde::{Deserialize, Serialize};
+use tokio::net::UnixStream;
+
+#[cfg(feature = "gvproxy-backend")]
+use crate::net::gvproxy::NetworkStats;
+use crate::runtime::options::{PortProtocol, PortSpec};
+
+/// Wire format of gvisor-tap-vsock's `ExposeRequest` / `UnexposeRequest`.
+#[derive(Debug, Serialize, Deserialize)]
+struct ForwardRequest {
+    local: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    remote: String,
+    protocol: String,
+}
+
+/// Ports above a taken one tried with `publish_retry`.
+const PUBLISH_RETRY_LIMIT: u16 = 100;
+
+/// Port forwarder of one running box.
+pub(crate) struct PortForwarder {
+    socket: PathBuf,
+    guest_ip: Ipv4Addr,
+}
+
+impl PortForwarder {
+    /// Forwarder serving `socket`, forwarding to the guest at `guest_ip`.
+    pub(crate) fn new(socket: PathBuf, guest_ip: Ipv4Addr) -> Self {
+        Self { socket, guest_ip }
+    }
+
+    /// Current forwards, including those configured at create time.
+    pub(crate) async fn list(&self) -> BoxliteResult<Vec<PortSpec>> {
+        let body = self.request(Method::GET, "/forwarder/all", None).await?;
+        let forwards: Vec<ForwardRequest> = serde_json::from_slice(&body).map_err(|e| {
+            BoxliteError::Network(format!("invalid port forwarder response: {}", e))
+        })?;
+        Ok(forwards.iter().filter_map(port_spec_from_forward).collect())
+    }
+
+    /// Start forwarding `spec` (host side) to the guest.
+    pub(crate) async fn expose(&self, spec: &PortSpec) -> BoxliteResult<()> {
+        let local = local_addr(spec)?;
+        let request = ForwardRequest {
+            local: local.to_string(),
+            remote: format!("{}:{}", self.guest_ip, spec.guest_port),
+            protocol: protocol_name(&spec.protocol).to_string(),
+        };
+        self.post("/forwarder/expose", &request)
+            .await
+            .map_err(|e| match e {
+                // gvproxy surfaces the host bind error verbatim
+                BoxliteError::Network(message) if message.contains("address already in use") => {
+                    BoxliteError::PortInUse {
+                        port: local.port(),
+                        owner_box: None,
+                    }
+                }
+                e => e,
+            })
+    }
+
+    /// Stop forwarding the host side of `spec`.
+    pub(crate) async fn unexpose(&self, spec: &PortSpec) -> BoxliteResult<()> {
+        let request = ForwardRequest {
+            local: local_addr(spec)?.to_string(),
+            remote: String::new(),
+            protocol: protocol_name(&spec.protocol).to_string(),
+        };
+        self.post("/forwarder/unexpose", &request).await
+    }
+
+    /// Traffic counters of the box's virtual network since it started.
+    #[cfg(feature = "gvproxy-backend")]
+    pub(crate) async fn stats(&self) -> BoxliteResult<NetworkStats> {
+        let body = self.request(Method::GET, "/stats", None).await?;
+        serde_json::from_slice(&body)
+            .map_err(|e| BoxliteError::Network(format!("invalid network stats response: {}", e)))
+    }
+
+    async fn post(&self, path: &str, request: &ForwardRequest) -> BoxliteResult<()> {
+        let body = serde_json::to_vec(request)
+            .map_err(|e| BoxliteError::Internal(format!("serialize forward request: {}", e)))?;
+        self.request(Method::POST, path, Some(body)).await?;
+        Ok(())
+    }
+
+    async fn request(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<Vec<u8>>,
+    ) -> BoxliteResult<Bytes> {
+        let stream = UnixStream::connect(&self.socket).await.map_err(|e| {
+            BoxliteError::Network(format!(
+                "port forwarder is not reachable at {} (box not running, or started \
+                 without a gvproxy network backend): {}",
+                self.socket.display(),
+                e
+            ))
+        })?;
+        let (mut sender, connection) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+            .await
+            .map_err(|e| BoxliteError::Network(format!("port forwarder handshake: {}", e)))?;
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                tracing::debug!("port forwarder connection closed: {}", e);
+            }
+        });
+
+        let request = Request::builder()
+            .method(method)
+            .uri(path)
+            .header(HOST, "gvproxy")
+            .header(CONTENT_TYPE, "application/json")
+            .body(Full::new(Bytes::from(body.unwrap_or_default())))
+            .map_err(|e| BoxliteError::Internal(format!("build forwarder request: {}", e)))?;
+        let response = sender
+            .send_request(request)
+            .await
+            .map_err(|e| BoxliteError::Network(format!("port forwarder request failed: {}", e)))?;
+        let status = response.status();
+        let bytes = response
+            .into_body()
+            .collect()
+            .await
+            .map_err(|e| BoxliteError::Network(format!("port forwarder response: {}", e)))?
+            .to_bytes();
+        if !status.is_success() {
+            let reason = String::from_utf8_lossy(&bytes);
+            return Err(BoxliteError::Network(format!(
+                "port forwarder rejected {}: {}",
+                path,
+                reason.trim()
+            )));
+        }
+        Ok(bytes)
+    }
+}
+
+/// Resolve a dynamic host port (`None` or `0`) to a free port on the host.
+///
+/// The port is found by binding port 0 and releasing it, so another process
+/// may still take it before the forwarder binds it; `taken` ports are skipped.
+pub(crate) fn assign_host_port(spec: &PortSpec, taken: &[u16]) -> BoxliteResult<PortSpec> {
+    if spec.host_port.is_some_and(|port| port != 0) {
+        return Ok(spec.clone());
+    }
+    let ip = local_addr(spec)?.ip();
+    for _ in 0..8 {
+        let port = match spec.protocol {
+            PortProtocol::Tcp => std::net::TcpListener::bind((ip, 0))?.local_addr()?.port(),
+            PortProtocol::Udp => std::net::UdpSocket::bind((ip, 0))?.local_addr()?.port(),
+        };
+        if !taken.contains(&port) {
+            return Ok(PortSpec {
+                host_port: Some(port),
+                ..spec.clone()
+            });
+        }
+    }
+    Err(BoxliteError::Network(format!(
+        "no free host port for box port {}",
+        spec.guest_port
+    )))
+}
+
+/// Check the fixed host port of `spec` against running boxes and the host.
+///
+/// `owners` are the published ports of other running boxes, each with the
+/// name (or ID) of its box. A taken port fails with `PortInUse`, or with
+/// `retry` moves to the next free port above it. Dynamic ports are left to
+/// [`assign_host_port`].
+pub(crate) fn check_host_port(
+    spec: &PortSpec,
+    owners: &[(String, PortSpec)],
+    retry: bool,
+) -> BoxliteResult<PortSpec> {
+    let Some(requested) = spec.host_port.filter(|&port| port != 0) else {
+        return Ok(spec.clone());
+    };
+    let last = requested.saturating_add(PUBLISH_RETRY_LIMIT);
+    for port in requested..=last {
+        let candidate = PortSpec {
+            host_port: Some(port),
+            ..spec.clone()
+        };
+        match host_port_conflict(&candidate, owners)? {
+            None => {
+                if port != requested {
+                    tracing::info!(
+                        requested,
+                        host_port = port,
+                        guest_port = spec.guest_port,
+                        "Requested host port is taken, published on the next free one"
+                    );
+                }
+                return Ok(candidate);
+            }
+            Some(conflict) if !retry => return Err(conflict),
+            Some(_) => {}
+        }
+    }
+    Err(BoxliteError::Network(format!(
+        "no free host port in {}-{} for box port {}",
+        requested, last, spec.guest_port
+    )))
+}
+
+/// `PortInUse` if another running box or a host process holds the host
+/// side of `spec`.
+fn host_port_conflict(
+    spec: &PortSpec,
+    owners: &[(String, PortSpec)],
+) -> BoxliteResult<Option<BoxliteError>> {
+    let local = local_addr(spec)?;
+    if let Some((owner, _)) = owners.iter().find(|(_, owned)| {
+        owned.protocol == spec.protocol
+            && owned.host_port.unwrap_or(owned.guest_port) == local.port()
+            && host_ips_overlap(owned.host_ip.as_deref(), spec.host_ip.as_deref())
+    }) {
+        return Ok(Some(BoxliteError::PortInUse {
+            port: local.port(),
+            owner_box: Some(owner.clone()),
+        }));
+    }
+
+    // Bind and release: only a port someone else holds is a conflict; other
+    // bind errors (e.g. privileged ports) are left to the forwarder
+    let bound = match spec.protocol {
+        PortProtocol::Tcp => std::net::TcpListener::bind(local).map(drop),
+        PortProtocol::Udp => std::net::UdpSocket::bind(local).map(drop),
+    };
+    Ok(match bound {
+        Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => Some(BoxliteError::PortInUse {
+            port: local.port(),
+            owner_box: None,
+        }),
+        _ => None,
+    })
+}
+
+/// Whether two host IPs (None = all interfaces) can bind the same port.
+fn host_ips_overlap(a: Option<&str>, b: Option<&str>) -> bool {
+    let is_wildcard = |ip: Option<&str>| {
+        ip.is_none_or(|ip| ip.parse::<IpAddr>().is_ok_and(|ip| ip.is_unspecified()))
+    };
+    is_wildcard(a) || is_wildcard(b) || a == b
+}
+
+/// Host address a spec binds: `host_ip` (default all interfaces) and
+/// `host_port` (default the guest port).
+fn local_addr(spec: &PortSpec) -> BoxliteResult<SocketAddr> {
+    let ip = match &spec.host_ip {
+        Some(ip) => ip
+            .parse::<IpAddr>()
+            .map_err(|_| BoxliteError::InvalidArgument(format!("invalid host IP '{}'", ip)))?,
+        None => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+    };
+    Ok(SocketAddr::new(
+        ip,
+        spec.host_port.unwrap_or(spec.guest_port),
+    ))
+}
+
+fn protocol_name(protocol: &PortProtocol) -> &'static str {
+    match protocol {
+        PortProtocol::Tcp => "tcp",
+        PortProtocol::Udp => "udp",
+    }
+}
+
+fn port_spec_from_forward(forward: &ForwardRequest) -> Option<PortSpec> {
+    let local: SocketAddr = forward.local.parse().ok()?;
+    let remote: SocketAddr = forward.remote.parse().ok()?;
+    let protocol = match forward.protocol.as_str() {
+        "tcp" => PortProtocol::Tcp,
+        "udp" => PortProtocol::Udp,
+        _ => return None,
+    };
+    Some(PortSpec {
+        host_port: Some(local.port()),
+        guest_port: remote.port(),
+        protocol,
+        host_ip: (!local.ip().is_unspecified()).then(|| local.ip().to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::constants::GUEST_IP;
+
+    #[test]
+    fn test_forward_round_trip() {
+        let spec = PortSpec {
+            host_port: Some(8080),
+            guest_port: 80,
+            protocol: PortProtocol::Tcp,
+            host_ip: Some("::1".to_string()),
+        };
+        let forward = ForwardRequest {
+            local: local_addr(&spec).unwrap().to_string(),
+            remote: format!("{}:{}", GUEST_IP, spec.guest_port),
+            protocol: protocol_name(&spec.protocol).to_string(),
+        };
+        assert_eq!(forward.local, "[::1]:8080");
+
+        let parsed = port_spec_from_forward(&forward).unwrap();
+        assert_eq!(parsed.host_port, Some(8080));
+        assert_eq!(parsed.guest_port, 80);
+        assert_eq!(parsed.host_ip.as_deref(), Some("::1"));
+
+        let wildcard = ForwardRequest {
+            local: "0.0.0.0:9000".to_string(),
+            remote: format!("{}:90", GUEST_IP),
+            protocol: "tcp".to_string(),
+        };
+        assert_eq!(port_spec_from_forward(&wildcard).unwrap().host_ip, None);
+    }
+
+    #[test]
+    fn test_assign_host_port() {
+        let fixed = PortSpec {
+            host_port: Some(8080),
+            guest_port: 80,
+            ..Default::default()
+        };
+        assert_eq!(assign_host_port(&fixed, &[]).unwrap().host_port, Some(8080));
+
+        let dynamic = PortSpec {
+            host_port: Some(0),
+            guest_port: 80,
+            host_ip: Some("127.0.0.1".to_string()),
+            ..Default::default()
+        };
+        let assigned = assign_host_port(&dynamic, &[]).unwrap();
+        assert!(assigned.host_port.is_some_and(|p| p != 0));
+        assert_eq!(assigned.host_ip.as_deref(), Some("127.0.0.1"));
+    }
+
+    #[test]
+    fn test_check_host_port() {
+        let held = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = held.local_addr().unwrap().port();
+        let spec = PortSpec {
+            host_port: Some(port),
+            guest_port: 80,
+            host_ip: Some("127.0.0.1".to_string()),
+            ..Default::default()
+        };
+
+        let err = check_host_port(&spec, &[], false).unwrap_err();
+        assert!(matches!(
+            err,
+            BoxliteError::PortInUse {
+                owner_box: None,
+                ..
+            }
+        ));
+
+        let owners = vec![(
+            "web".to_string(),
+            PortSpec {
+                host_port: Some(port),
+                guest_port: 8080,
+                ..Default::default()
+            },
+        )];
+        match check_host_port(&spec, &owners, false).unwrap_err() {
+            BoxliteError::PortInUse { port: p, owner_box } => {
+                assert_eq!(p, port);
+                assert_eq!(owner_box.as_deref(), Some("web"));
+            }
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let moved = check_host_port(&spec, &owners, true).unwrap();
+        assert!(moved.host_port.is_some_and(|p| p > port));
+
+        let dynamic = PortSpec {
+            host_port: None,
+            ..spec.clone()
+        };
+        let checked = check_host_port(&dynamic, &owners, false).unwrap();
+        assert_eq!(checked.host_port, None);
+        assert_eq!(checked.guest_port, dynamic.guest_port);
+    }
+
+    #[test]
+    fn test_host_ips_overlap() {
+        assert!(host_ips_overlap(None, Some("127.0.0.1")));
+        assert!(host_ips_overlap(Some("0.0.0.0"), Some("10.0.0.1")));
+        assert!(host_ips_overlap(Some("::1"), Some("::1")));
+        assert!(!host_ips_overlap(Some("127.0.0.1"), Some("10.0.0.1")));
+    }
+}
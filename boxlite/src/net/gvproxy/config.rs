@@ -1,7 +1,11 @@
 //! Gvproxy configuration structures
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
+use crate::runtime::options::NetworkPolicy;
+
 /// Local DNS zone configuration
 ///
 /// Defines local DNS records served by the gateway's embedded DNS server.
@@ -21,6 +25,9 @@ pub struct PortMapping {
     pub host_port: u16,
     /// Guest port to forward to
     pub guest_port: u16,
+    /// Host interface to bind (None = all interfaces)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub host_ip: Option<String>,
 }
 
 /// Network configuration for gvproxy instance
@@ -64,6 +71,32 @@ pub struct GvproxyConfig {
     /// Set via config or BOXLITE_NET_CAPTURE_FILE environment variable
     #[serde(skip_serializing_if = "Option::is_none")]
     pub capture_file: Option<String>,
+
+    /// Optional cap on traffic sent by the guest, in bytes per second
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub egress_limit_bytes_per_sec: Option<u64>,
+
+    /// Optional egress allow/deny rules for traffic sent by the guest
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub egress_policy: Option<NetworkPolicy>,
+
+    /// Optional Unix socket serving the port forwarder API
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub control_socket: Option<String>,
+
+    /// Optional Unix socket the VM connects to (default: `gvproxy-<id>.sock`
+    /// in TMPDIR, which collides across processes sharing a TMPDIR)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub socket_path: Option<String>,
+
+    /// Optional address the guest reaches the host's 127.0.0.1 at (None =
+    /// the host's loopback is unreachable)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host_loopback_ip: Option<String>,
+
+    /// Optional file receiving a JSON line per connection the guest opens
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connection_log: Option<String>,
 }
 
 impl Default for GvproxyConfig {
@@ -82,6 +115,12 @@ impl Default for GvproxyConfig {
             dns_search_domains: DNS_SEARCH_DOMAINS.iter().map(|s| s.to_string()).collect(),
             debug: false,
             capture_file: None,
+            egress_limit_bytes_per_sec: None,
+            egress_policy: None,
+            control_socket: None,
+            socket_path: None,
+            host_loopback_ip: Some(HOST_LOOPBACK_IP.to_string()),
+            connection_log: None,
         }
     }
 }
@@ -109,6 +148,7 @@ impl GvproxyConfig {
                 .map(|(host_port, guest_port)| PortMapping {
                     host_port,
                     guest_port,
+                    host_ip: None,
                 })
                 .collect(),
             ..Default::default()
@@ -134,6 +174,20 @@ impl GvproxyConfig {
         config
     }
 
+    /// Configuration for the backend described by `config`
+    pub fn from_backend_config(config: &crate::net::NetworkBackendConfig) -> Self {
+        Self::new(config.port_mappings.clone())
+            .with_egress_limit(config.egress_limit_bytes_per_sec())
+            .with_egress_policy(config.egress_policy.clone())
+            .with_port_host_ips(&config.port_host_ips)
+            .with_control_socket(config.control_socket.as_deref())
+            .with_socket_path(config.tap_socket.as_deref())
+            .with_guest_mac(&config.guest_mac())
+            .with_guest_ip(config.guest_ip())
+            .with_host_loopback(config.host_loopback)
+            .with_connection_log(config.connection_log.as_deref())
+    }
+
     /// Enable debug logging
     pub fn with_debug(mut self, debug: bool) -> Self {
         self.debug = debug;
@@ -169,6 +223,64 @@ impl GvproxyConfig {
         self.capture_file = Some(capture_file);
         self
     }
+
+    /// Limit guest egress to `bytes_per_sec`
+    pub fn with_egress_limit(mut self, bytes_per_sec: Option<u64>) -> Self {
+        self.egress_limit_bytes_per_sec = bytes_per_sec;
+        self
+    }
+
+    /// Filter guest egress with `policy`
+    pub fn with_egress_policy(mut self, policy: Option<NetworkPolicy>) -> Self {
+        self.egress_policy = policy;
+        self
+    }
+
+    /// Serve the port forwarder API on `path`
+    pub fn with_control_socket(mut self, path: Option<&std::path::Path>) -> Self {
+        self.control_socket = path.map(|p| p.to_string_lossy().into_owned());
+        self
+    }
+
+    /// Create the VM's socket at `path`
+    pub fn with_socket_path(mut self, path: Option<&std::path::Path>) -> Self {
+        self.socket_path = path.map(|p| p.to_string_lossy().into_owned());
+        self
+    }
+
+    /// Assign `mac` to the guest in the DHCP static lease
+    pub fn with_guest_mac(mut self, mac: &[u8; 6]) -> Self {
+        self.guest_mac = crate::net::constants::mac_to_string(mac);
+        self
+    }
+
+    /// Lease `ip` to the guest and forward ports to it
+    pub fn with_guest_ip(mut self, ip: std::net::Ipv4Addr) -> Self {
+        self.guest_ip = ip.to_string();
+        self
+    }
+
+    /// Let the guest reach the host's loopback at
+    /// [`HOST_LOOPBACK_IP`](crate::net::constants::HOST_LOOPBACK_IP), or not
+    pub fn with_host_loopback(mut self, enabled: bool) -> Self {
+        self.host_loopback_ip =
+            enabled.then(|| crate::net::constants::HOST_LOOPBACK_IP.to_string());
+        self
+    }
+
+    /// Log the connections the guest opens to `path`
+    pub fn with_connection_log(mut self, path: Option<&std::path::Path>) -> Self {
+        self.connection_log = path.map(|p| p.to_string_lossy().into_owned());
+        self
+    }
+
+    /// Bind the listed host ports to specific interfaces
+    pub fn with_port_host_ips(mut self, host_ips: &HashMap<u16, String>) -> Self {
+        for mapping in &mut self.port_mappings {
+            mapping.host_ip = host_ips.get(&mapping.host_port).cloned();
+        }
+        self
+    }
 }
 
 #[cfg(test)]
@@ -204,6 +316,30 @@ mod tests {
         assert_eq!(config.mtu, 9000);
     }
 
+    #[test]
+    fn test_from_backend_config_guest_ip() {
+        let backend = crate::net::NetworkBackendConfig::new(vec![(8080, 80)])
+            .with_guest_ip(Some(std::net::Ipv4Addr::new(192, 168, 127, 10)));
+        let config = GvproxyConfig::from_backend_config(&backend);
+        assert_eq!(config.guest_ip, "192.168.127.10");
+
+        let config =
+            GvproxyConfig::from_backend_config(&crate::net::NetworkBackendConfig::new(vec![]));
+        assert_eq!(config.guest_ip, "192.168.127.2");
+    }
+
+    #[test]
+    fn test_from_backend_config_host_loopback() {
+        let backend = crate::net::NetworkBackendConfig::new(vec![]);
+        let config = GvproxyConfig::from_backend_config(&backend);
+        assert_eq!(config.host_loopback_ip.as_deref(), Some("192.168.127.254"));
+
+        let config = GvproxyConfig::from_backend_config(&backend.with_host_loopback(false));
+        assert_eq!(config.host_loopback_ip, None);
+        let json = serde_json::to_value(&config).unwrap();
+        assert!(json.get("host_loopback_ip").is_none());
+    }
+
     #[test]
     fn test_serialization() {
         let config = GvproxyConfig::new(vec![(8080, 80)]);
@@ -242,6 +378,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_egress_limit_serialization() {
+        let config = GvproxyConfig::new(vec![]);
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(!json.contains("egress_limit_bytes_per_sec"));
+
+        let json = serde_json::to_string(&config.with_egress_limit(Some(1_250_000))).unwrap();
+        assert!(json.contains(r#""egress_limit_bytes_per_sec":1250000"#));
+    }
+
+    #[test]
+    fn test_egress_policy_serialization() {
+        let config = GvproxyConfig::new(vec![])
+            .with_egress_policy(Some(NetworkPolicy::restricted(vec!["10.0.0.0/8".into()])));
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains(
+            r#""egress_policy":{"default_allow":false,"allow":["10.0.0.0/8"],"deny":[]}"#
+        ));
+    }
+
+    #[test]
+    fn test_port_host_ip_serialization() {
+        let host_ips = HashMap::from([(8080, "127.0.0.1".to_string())]);
+        let config = GvproxyConfig::new(vec![(8080, 80), (9090, 90)]).with_port_host_ips(&host_ips);
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains(r#"{"host_port":8080,"guest_port":80,"host_ip":"127.0.0.1"}"#));
+        assert!(json.contains(r#"{"host_port":9090,"guest_port":90}"#));
+    }
+
     #[test]
     fn test_capture_file_default() {
         let config = GvproxyConfig::default();
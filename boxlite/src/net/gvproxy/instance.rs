@@ -78,15 +78,21 @@ impl GvproxyInstance {
     /// # Ok::<(), boxlite_shared::errors::BoxliteError>(())
     /// ```
     pub fn new(port_mappings: &[(u16, u16)]) -> BoxliteResult<Self> {
+        // Create config with defaults + port mappings
+        Self::from_config(&super::config::GvproxyConfig::new(port_mappings.to_vec()))
+    }
+
+    /// Create a new gvproxy instance from a full configuration
+    ///
+    /// Use this instead of [`GvproxyInstance::new`] to set options beyond port
+    /// mappings, such as an egress rate limit.
+    pub fn from_config(config: &super::config::GvproxyConfig) -> BoxliteResult<Self> {
         // Initialize logging callback (one-time setup)
         // This ensures all gvproxy logs are routed to Rust's tracing system
         logging::init_logging();
 
-        // Create config with defaults + port mappings
-        let config = super::config::GvproxyConfig::new(port_mappings.to_vec());
-
         // Create instance via FFI with full config
-        let id = ffi::create_instance(&config)?;
+        let id = ffi::create_instance(config)?;
 
         tracing::info!(id, "Created GvproxyInstance");
 
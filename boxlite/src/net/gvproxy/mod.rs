@@ -8,6 +8,7 @@
 //! - `logging` - Logging bridge between Go's slog and Rust's tracing
 //! - `ffi` - Safe wrappers around raw FFI functions from libgvproxy-sys
 //! - `instance` - High-level `GvproxyInstance` with RAII resource management
+//! - `shared` - Daemon hosting the instances of all boxes in one process
 //! - `GvisorTapBackend` - Network backend implementation (this file)
 //!
 //! ## Logging Integration
@@ -56,9 +57,7 @@
 //! ```no_run
 //! use boxlite::net::{NetworkBackendConfig, GvisorTapBackend, NetworkBackend};
 //!
-//! let config = NetworkBackendConfig {
-//!     port_mappings: vec![(8080, 80), (8443, 443)],
-//! };
+//! let config = NetworkBackendConfig::new(vec![(8080, 80), (8443, 443)]);
 //!
 //! // Create backend - logs from gvproxy will appear in tracing
 //! let backend = GvisorTapBackend::new(config)?;
@@ -72,6 +71,7 @@ mod config;
 mod ffi;
 mod instance;
 mod logging;
+pub mod shared;
 mod stats;
 
 use super::{ConnectionType, NetworkBackend, NetworkBackendConfig, NetworkBackendEndpoint};
@@ -106,6 +106,8 @@ pub struct GvisorTapBackend {
     instance: Arc<GvproxyInstance>,
     /// Socket path for cross-process communication
     socket_path: PathBuf,
+    /// Guest MAC address, as leased by gvproxy
+    guest_mac: [u8; 6],
 }
 
 impl GvisorTapBackend {
@@ -127,9 +129,7 @@ impl GvisorTapBackend {
     /// ```no_run
     /// use boxlite::net::{NetworkBackendConfig, GvisorTapBackend};
     ///
-    /// let config = NetworkBackendConfig {
-    ///     port_mappings: vec![(8080, 80), (8443, 443)],
-    /// };
+    /// let config = NetworkBackendConfig::new(vec![(8080, 80), (8443, 443)]);
     ///
     /// let backend = GvisorTapBackend::new(config)?;
     /// # Ok::<(), boxlite_shared::errors::BoxliteError>(())
@@ -140,8 +140,9 @@ impl GvisorTapBackend {
             config.port_mappings
         );
 
-        // Create gvproxy instance with port mappings
-        let instance = Arc::new(GvproxyInstance::new(&config.port_mappings)?);
+        // Create gvproxy instance with port mappings and egress limit
+        let gvproxy_config = GvproxyConfig::from_backend_config(&config);
+        let instance = Arc::new(GvproxyInstance::from_config(&gvproxy_config)?);
 
         // Start background stats logging thread
         instance::start_stats_logging(Arc::downgrade(&instance));
@@ -158,6 +159,7 @@ impl GvisorTapBackend {
         Ok(Self {
             instance,
             socket_path,
+            guest_mac: config.guest_mac(),
         })
     }
 
@@ -175,9 +177,7 @@ impl GvisorTapBackend {
     /// ```no_run
     /// use boxlite::net::{NetworkBackendConfig, GvisorTapBackend};
     ///
-    /// let config = NetworkBackendConfig {
-    ///     port_mappings: vec![(8080, 80)],
-    /// };
+    /// let config = NetworkBackendConfig::new(vec![(8080, 80)]);
     /// let backend = GvisorTapBackend::new(config)?;
     ///
     /// // Get stats
@@ -202,13 +202,11 @@ impl NetworkBackend for GvisorTapBackend {
             ConnectionType::UnixStream
         };
 
-        // Use GUEST_MAC constant - this must match the DHCP static lease in gvproxy config
-        use crate::net::constants::GUEST_MAC;
-
+        // Must match the DHCP static lease in the gvproxy config
         Ok(NetworkBackendEndpoint::UnixSocket {
             path: self.socket_path.clone(),
             connection_type,
-            mac_address: GUEST_MAC,
+            mac_address: self.guest_mac,
         })
     }
 
@@ -0,0 +1,541 @@
+//! Shared network daemon.
+//!
+//! With `BoxliteOptions::shared_network`, one long-lived process
+//! (`boxlite-shim --network-daemon HOME`) hosts the gvproxy instances of all
+//! boxes, instead of every shim loading its own copy of the Go runtime.
+//!
+//! ```text
+//! runtime ──spawn, ping──▶ daemon (HOME/netd.sock)
+//! shim ────attach(box)───▶ daemon ──creates──▶ sockets/tap.sock ◀── VM
+//! ```
+//!
+//! Each box still gets its own virtual network (gVisor stack, DHCP lease and
+//! port forwarder), so boxes stay as isolated from each other as with a
+//! per-box backend. An instance lives as long as the shim's attach
+//! connection: when the shim exits or crashes, the daemon sees EOF and
+//! destroys it. A shim that cannot attach creates its own instance instead.
+//!
+//! All instances use the same subnet and guest address, as per-box backends
+//! do. They cannot collide: every instance is a separate gVisor stack keyed
+//! by box ID, and guest traffic leaves it only through host sockets, whose
+//! ports the host kernel keeps apart.
+//!
+//! Only one daemon serves a home: it holds `netd.lock` while it runs, and a
+//! second daemon started for the same home exits instead of taking over the
+//! socket of the first.
+//!
+//! If the daemon dies, the shim sees EOF in turn. Re-attaching would not
+//! help: the VM's virtio-net device stays connected to the dead tap socket
+//! and cannot be pointed at a new one. The shim writes the box's
+//! `network-lost` marker instead, the heartbeat monitor then reports the box
+//! unhealthy, and with `HeartbeatPolicy::restart` restarts it onto the
+//! restarted daemon.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+
+use super::{GvproxyConfig, GvproxyInstance};
+
+/// Daemon socket file name, under the runtime home directory.
+pub const SOCKET_NAME: &str = "netd.sock";
+
+/// Lock held by the serving daemon, next to its socket.
+const LOCK_NAME: &str = "netd.lock";
+
+/// How long a client waits for the daemon to answer.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How often the runtime checks that the daemon answers.
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Longest wait between restarts of a daemon that keeps dying.
+const MAX_RESTART_BACKOFF: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum Request {
+    /// Create the network of `box_id`; it lives until the connection closes
+    Attach {
+        box_id: String,
+        config: Box<GvproxyConfig>,
+    },
+    Ping,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum Response {
+    Attached { socket_path: PathBuf },
+    Pong { boxes: usize },
+    Error { message: String },
+}
+
+/// Instances by box ID, with the connection that owns each.
+type Instances = Arc<Mutex<HashMap<String, (u64, GvproxyInstance)>>>;
+
+// ============================================================================
+// DAEMON
+// ============================================================================
+
+/// Serve the daemon on `socket_path` until the process is killed.
+///
+/// Returns at once if another daemon already serves `socket_path`.
+pub fn serve(socket_path: &Path) -> BoxliteResult<()> {
+    let lock_path = socket_path.with_file_name(LOCK_NAME);
+    let Some(_lock) = try_lock(&lock_path)? else {
+        tracing::info!(socket = %socket_path.display(), "Network daemon already running");
+        return Ok(());
+    };
+
+    // Left by a daemon that died; no live daemon holds the lock
+    let _ = std::fs::remove_file(socket_path);
+    let listener = UnixListener::bind(socket_path).map_err(|e| {
+        BoxliteError::Network(format!(
+            "Failed to bind network daemon socket {}: {}",
+            socket_path.display(),
+            e
+        ))
+    })?;
+    std::fs::set_permissions(socket_path, std::fs::Permissions::from_mode(0o600))?;
+    tracing::info!(socket = %socket_path.display(), "Network daemon listening");
+
+    let instances: Instances = Arc::default();
+    for (connection, stream) in (1u64..).zip(listener.incoming()) {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to accept network daemon connection");
+                continue;
+            }
+        };
+        let instances = Arc::clone(&instances);
+        std::thread::spawn(move || {
+            if let Err(e) = handle(stream, &instances, connection) {
+                tracing::warn!(error = %e, "Network daemon connection failed");
+            }
+        });
+    }
+    Ok(())
+}
+
+/// Open and lock `lock_path`, or None if another process holds the lock.
+fn try_lock(lock_path: &Path) -> BoxliteResult<Option<std::fs::File>> {
+    use std::os::unix::io::AsRawFd;
+
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(false)
+        .open(lock_path)
+        .map_err(|e| {
+            BoxliteError::Network(format!(
+                "Failed to open network daemon lock {}: {}",
+                lock_path.display(),
+                e
+            ))
+        })?;
+    // Released by the OS when the daemon exits, however it exits
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } != 0 {
+        let err = std::io::Error::last_os_error();
+        if err.kind() == std::io::ErrorKind::WouldBlock {
+            return Ok(None);
+        }
+        return Err(BoxliteError::Network(format!(
+            "Failed to lock {}: {}",
+            lock_path.display(),
+            err
+        )));
+    }
+    Ok(Some(file))
+}
+
+/// Answer one request; an attach holds the connection until the shim closes it.
+fn handle(stream: UnixStream, instances: &Instances, connection: u64) -> BoxliteResult<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let request: Request = serde_json::from_str(&line)
+        .map_err(|e| BoxliteError::Network(format!("invalid network daemon request: {}", e)))?;
+
+    match request {
+        Request::Ping => {
+            let boxes = instances.lock().len();
+            send(&mut writer, &Response::Pong { boxes })
+        }
+        Request::Attach { box_id, config } => {
+            let socket_path = match create(instances, &box_id, &config, connection) {
+                Ok(socket_path) => socket_path,
+                Err(e) => {
+                    return send(
+                        &mut writer,
+                        &Response::Error {
+                            message: e.to_string(),
+                        },
+                    );
+                }
+            };
+            send(&mut writer, &Response::Attached { socket_path })?;
+
+            // Blocks until the shim exits, however it exits
+            let _ = reader.read_to_end(&mut Vec::new());
+            let mut instances = instances.lock();
+            if instances
+                .get(&box_id)
+                .is_some_and(|(owner, _)| *owner == connection)
+            {
+                instances.remove(&box_id);
+                tracing::info!(box_id = %box_id, "Destroyed network of detached box");
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Create the instance of `box_id`, replacing one left by a previous start.
+fn create(
+    instances: &Instances,
+    box_id: &str,
+    config: &GvproxyConfig,
+    connection: u64,
+) -> BoxliteResult<PathBuf> {
+    let mut instances = instances.lock();
+    // Dropped first: the new instance reuses its socket paths
+    if instances.remove(box_id).is_some() {
+        tracing::info!(box_id = %box_id, "Replaced stale network of restarted box");
+    }
+    let instance = GvproxyInstance::from_config(config)?;
+    let socket_path = instance.get_socket_path()?;
+    tracing::info!(box_id = %box_id, socket_path = %socket_path.display(), "Created box network");
+    instances.insert(box_id.to_string(), (connection, instance));
+    Ok(socket_path)
+}
+
+// ============================================================================
+// CLIENT
+// ============================================================================
+
+/// A box network hosted by the daemon, destroyed when this is dropped or the
+/// process exits.
+#[derive(Debug)]
+pub struct SharedNetworkLease {
+    _stream: UnixStream,
+    socket_path: PathBuf,
+}
+
+impl SharedNetworkLease {
+    /// Unix socket the VM connects to.
+    pub fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+
+    /// Hold the lease on a thread of its own and call `on_lost` if the
+    /// daemon closes the connection, i.e. exits and takes the network with it.
+    pub fn watch(self, on_lost: impl FnOnce() + Send + 'static) -> BoxliteResult<()> {
+        let mut stream = self._stream;
+        std::thread::Builder::new()
+            .name("netd-lease".to_string())
+            .spawn(move || {
+                // The daemon never writes after attaching, so this returns
+                // only at EOF or on an error
+                let _ = stream.read_to_end(&mut Vec::new());
+                on_lost();
+            })?;
+        Ok(())
+    }
+}
+
+/// Ask the daemon at `daemon_socket` to host the network of `box_id`.
+pub fn attach(
+    daemon_socket: &Path,
+    box_id: &str,
+    config: &GvproxyConfig,
+) -> BoxliteResult<SharedNetworkLease> {
+    let request = Request::Attach {
+        box_id: box_id.to_string(),
+        config: Box::new(config.clone()),
+    };
+    let (stream, response) = roundtrip(daemon_socket, &request)?;
+    match response {
+        Response::Attached { socket_path } => {
+            // The daemon never writes again; the connection only has to stay open
+            stream.set_read_timeout(None)?;
+            Ok(SharedNetworkLease {
+                _stream: stream,
+                socket_path,
+            })
+        }
+        Response::Error { message } => Err(BoxliteError::Network(format!(
+            "network daemon could not create the box network: {}",
+            message
+        ))),
+        other => Err(BoxliteError::Network(format!(
+            "unexpected network daemon response: {:?}",
+            other
+        ))),
+    }
+}
+
+/// Number of boxes the daemon at `daemon_socket` hosts, if it answers.
+pub fn ping(daemon_socket: &Path) -> BoxliteResult<usize> {
+    match roundtrip(daemon_socket, &Request::Ping)?.1 {
+        Response::Pong { boxes } => Ok(boxes),
+        other => Err(BoxliteError::Network(format!(
+            "unexpected network daemon response: {:?}",
+            other
+        ))),
+    }
+}
+
+fn roundtrip(daemon_socket: &Path, request: &Request) -> BoxliteResult<(UnixStream, Response)> {
+    let mut stream = UnixStream::connect(daemon_socket).map_err(|e| {
+        BoxliteError::Network(format!(
+            "network daemon at {} is unavailable: {}",
+            daemon_socket.display(),
+            e
+        ))
+    })?;
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+
+    let mut line = serde_json::to_string(request)
+        .map_err(|e| BoxliteError::Internal(format!("Failed to encode request: {}", e)))?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+
+    let mut reply = String::new();
+    BufReader::new(stream.try_clone()?).read_line(&mut reply)?;
+    let response = serde_json::from_str(&reply)
+        .map_err(|e| BoxliteError::Network(format!("invalid network daemon response: {}", e)))?;
+    Ok((stream, response))
+}
+
+fn send(writer: &mut UnixStream, response: &Response) -> BoxliteResult<()> {
+    let mut line = serde_json::to_string(response)
+        .map_err(|e| BoxliteError::Internal(format!("Failed to encode response: {}", e)))?;
+    line.push('\n');
+    writer.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+// ============================================================================
+// SUPERVISOR (runtime side)
+// ============================================================================
+
+/// Starts the daemon for a runtime and restarts it when it stops answering.
+///
+/// The daemon is not stopped with the runtime: detached boxes keep using it.
+/// Boxes attached to a daemon that died lose networking until restarted and
+/// are reported unhealthy (see the module docs); boxes started while it is
+/// down use a per-box backend.
+#[derive(Debug)]
+pub(crate) struct SharedNetworkDaemon {
+    home_dir: PathBuf,
+    socket_path: PathBuf,
+    available: AtomicBool,
+    /// Daemon started by this runtime, reaped when it exits
+    child: Mutex<Option<Child>>,
+}
+
+impl SharedNetworkDaemon {
+    pub(crate) fn new(home_dir: &Path) -> Self {
+        Self {
+            home_dir: home_dir.to_path_buf(),
+            socket_path: home_dir.join(SOCKET_NAME),
+            available: AtomicBool::new(false),
+            child: Mutex::new(None),
+        }
+    }
+
+    /// Daemon socket for a box about to start, or None to use a per-box backend.
+    pub(crate) fn endpoint(&self) -> Option<PathBuf> {
+        self.available
+            .load(Ordering::Acquire)
+            .then(|| self.socket_path.clone())
+    }
+
+    /// Ping the daemon, starting it if it does not answer.
+    pub(crate) fn ensure_running(&self) -> BoxliteResult<()> {
+        self.reap();
+        if ping(&self.socket_path).is_ok() {
+            self.available.store(true, Ordering::Release);
+            return Ok(());
+        }
+        self.available.store(false, Ordering::Release);
+        self.spawn()?;
+
+        let deadline = Instant::now() + REQUEST_TIMEOUT;
+        loop {
+            match ping(&self.socket_path) {
+                Ok(_) => break,
+                Err(e) if Instant::now() >= deadline => return Err(e),
+                Err(_) => std::thread::sleep(Duration::from_millis(100)),
+            }
+        }
+        self.available.store(true, Ordering::Release);
+        tracing::info!(socket = %self.socket_path.display(), "Network daemon started");
+        Ok(())
+    }
+
+    fn spawn(&self) -> BoxliteResult<()> {
+        let binary = crate::util::find_binary("boxlite-shim")?;
+        let mut cmd = Command::new(&binary);
+        cmd.arg("--network-daemon").arg(&self.home_dir);
+        if let Ok(rust_log) = std::env::var("RUST_LOG") {
+            cmd.env("RUST_LOG", rust_log);
+        }
+        crate::util::configure_library_env(
+            &mut cmd,
+            libkrun_sys::krun_create_ctx as *const libc::c_void,
+        );
+        // Own process group: a Ctrl-C aimed at the runtime leaves it running
+        std::os::unix::process::CommandExt::process_group(&mut cmd, 0);
+        cmd.stdin(Stdio::null());
+        cmd.stdout(Stdio::null());
+        cmd.stderr(Stdio::null());
+
+        let child = cmd.spawn().map_err(|e| {
+            BoxliteError::Network(format!(
+                "Failed to spawn network daemon {}: {}",
+                binary.display(),
+                e
+            ))
+        })?;
+        *self.child.lock() = Some(child);
+        Ok(())
+    }
+
+    /// Collect the exit status of a daemon this runtime started, if it exited.
+    fn reap(&self) {
+        let mut child = self.child.lock();
+        if let Some(process) = child.as_mut()
+            && let Ok(Some(status)) = process.try_wait()
+        {
+            tracing::warn!(%status, "Network daemon exited");
+            *child = None;
+        }
+    }
+}
+
+/// Keep `daemon` running until `shutdown`, restarting it with backoff.
+pub(crate) fn spawn_supervisor(daemon: Arc<SharedNetworkDaemon>, shutdown: CancellationToken) {
+    let Ok(handle) = tokio::runtime::Handle::try_current() else {
+        tracing::debug!("No tokio runtime, network daemon supervisor not started");
+        return;
+    };
+
+    handle.spawn(async move {
+        let mut backoff = Duration::ZERO;
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => return,
+                _ = tokio::time::sleep(CHECK_INTERVAL + backoff) => {}
+            }
+            let check = Arc::clone(&daemon);
+            match tokio::task::spawn_blocking(move || check.ensure_running()).await {
+                Ok(Ok(())) => backoff = Duration::ZERO,
+                Ok(Err(e)) => {
+                    backoff = (backoff * 2)
+                        .max(Duration::from_secs(1))
+                        .min(MAX_RESTART_BACKOFF);
+                    tracing::warn!(
+                        error = %e,
+                        retry_in = ?CHECK_INTERVAL + backoff,
+                        "Network daemon unavailable, new boxes use a per-box backend"
+                    );
+                }
+                Err(e) => tracing::warn!(error = %e, "Network daemon check panicked"),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ping_counts_hosted_boxes() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join(SOCKET_NAME);
+        assert!(ping(&socket).is_err());
+
+        let served = socket.clone();
+        std::thread::spawn(move || serve(&served));
+        let deadline = Instant::now() + REQUEST_TIMEOUT;
+        while ping(&socket).is_err() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(ping(&socket).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_second_daemon_leaves_socket_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join(SOCKET_NAME);
+        let served = socket.clone();
+        std::thread::spawn(move || serve(&served));
+        let deadline = Instant::now() + REQUEST_TIMEOUT;
+        while ping(&socket).is_err() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        serve(&socket).unwrap();
+        assert_eq!(ping(&socket).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_lease_reports_daemon_exit() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join(SOCKET_NAME);
+        let listener = UnixListener::bind(&socket).unwrap();
+
+        // A daemon that attaches one box, then dies
+        let daemon = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut line = String::new();
+            BufReader::new(stream.try_clone().unwrap())
+                .read_line(&mut line)
+                .unwrap();
+            let socket_path = PathBuf::from("/tmp/tap.sock");
+            send(&mut stream, &Response::Attached { socket_path }).unwrap();
+        });
+
+        let config = GvproxyConfig::new(Vec::new());
+        let lease = attach(&socket, "box", &config).unwrap();
+        let (lost_tx, lost_rx) = std::sync::mpsc::channel();
+        lease.watch(move || lost_tx.send(()).unwrap()).unwrap();
+
+        daemon.join().unwrap();
+        lost_rx.recv_timeout(REQUEST_TIMEOUT).unwrap();
+    }
+
+    #[test]
+    fn test_supervisor_endpoint_needs_running_daemon() {
+        let dir = tempfile::tempdir().unwrap();
+        let daemon = SharedNetworkDaemon::new(dir.path());
+        assert_eq!(daemon.endpoint(), None);
+
+        let socket = dir.path().join(SOCKET_NAME);
+        let served = socket.clone();
+        std::thread::spawn(move || serve(&served));
+        let deadline = Instant::now() + REQUEST_TIMEOUT;
+        while ping(&socket).is_err() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        daemon.ensure_running().unwrap();
+        assert_eq!(daemon.endpoint(), Some(dir.path().join(SOCKET_NAME)));
+    }
+}
@@ -76,7 +76,12 @@ impl LibslirpBackend {
 
         // Add port forwarding configuration
         for (host_port, guest_port) in &config.port_mappings {
-            let forward_spec = format!("tcp:127.0.0.1:{}::{}:tcp", host_port, guest_port);
+            let host_ip = config
+                .port_host_ips
+                .get(host_port)
+                .map(String::as_str)
+                .unwrap_or("127.0.0.1");
+            let forward_spec = format!("tcp:{}:{}::{}:tcp", host_ip, host_port, guest_port);
             helper_args.push(format!("--forward={}", forward_spec));
 
             tracing::info!(
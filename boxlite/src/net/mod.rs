@@ -7,10 +7,14 @@
 //! When no backend is configured (None), the engine uses its default net
 //! implementation.
 
+use crate::runtime::options::NetworkPolicy;
 use boxlite_shared::errors::BoxliteResult;
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
 use std::path::PathBuf;
 
 pub mod constants;
+pub(crate) mod forwarder;
 
 #[cfg(feature = "libslirp-backend")]
 mod libslirp;
@@ -50,12 +54,137 @@ pub enum NetworkBackendEndpoint {
 pub struct NetworkBackendConfig {
     /// Port mappings: (host_port, guest_port)
     pub port_mappings: Vec<(u16, u16)>,
+    /// Egress limit for traffic sent by the guest, in megabits per second
+    #[serde(default)]
+    pub egress_limit_mbps: Option<u64>,
+    /// Egress allow/deny rules (None = allow all)
+    #[serde(default)]
+    pub egress_policy: Option<NetworkPolicy>,
+    /// Host interface to bind, keyed by host port (missing = all interfaces)
+    #[serde(default)]
+    pub port_host_ips: HashMap<u16, String>,
+    /// Unix socket on which the backend accepts port forward changes
+    #[serde(default)]
+    pub control_socket: Option<PathBuf>,
+    /// MAC address of the guest interface (None = `constants::GUEST_MAC`)
+    #[serde(default)]
+    pub guest_mac: Option<[u8; 6]>,
+    /// IPv4 address of the guest (None = `constants::GUEST_IP`)
+    #[serde(default)]
+    pub guest_ip: Option<Ipv4Addr>,
+    /// Unix socket the VM connects to (None = chosen by the backend)
+    #[serde(default)]
+    pub tap_socket: Option<PathBuf>,
+    /// Let the guest reach the host's loopback at
+    /// `constants::HOST_LOOPBACK_IP`
+    #[serde(default = "default_host_loopback")]
+    pub host_loopback: bool,
+    /// File to log the connections the guest opens to (None = not logged)
+    #[serde(default)]
+    pub connection_log: Option<PathBuf>,
+    /// Socket of the shared network daemon to host the backend (None = the
+    /// shim hosts it)
+    #[serde(default)]
+    pub shared_daemon: Option<PathBuf>,
 }
 
 impl NetworkBackendConfig {
     pub fn new(port_mappings: Vec<(u16, u16)>) -> Self {
-        Self { port_mappings }
+        Self {
+            port_mappings,
+            egress_limit_mbps: None,
+            egress_policy: None,
+            port_host_ips: HashMap::new(),
+            control_socket: None,
+            guest_mac: None,
+            guest_ip: None,
+            tap_socket: None,
+            host_loopback: default_host_loopback(),
+            connection_log: None,
+            shared_daemon: None,
+        }
+    }
+
+    /// Limit guest egress to `mbps` megabits per second (`None` = unlimited).
+    pub fn with_egress_limit_mbps(mut self, mbps: Option<u64>) -> Self {
+        self.egress_limit_mbps = mbps;
+        self
+    }
+
+    /// Restrict guest egress to `policy` (`None` = allow all).
+    pub fn with_egress_policy(mut self, policy: Option<NetworkPolicy>) -> Self {
+        self.egress_policy = policy;
+        self
+    }
+
+    /// Bind the listed host ports to specific interfaces instead of all of them.
+    pub fn with_port_host_ips(mut self, host_ips: HashMap<u16, String>) -> Self {
+        self.port_host_ips = host_ips;
+        self
+    }
+
+    /// Accept port forward changes on `path` while the box runs.
+    pub fn with_control_socket(mut self, path: PathBuf) -> Self {
+        self.control_socket = Some(path);
+        self
+    }
+
+    /// Create the socket the VM connects to at `path`.
+    pub fn with_tap_socket(mut self, path: PathBuf) -> Self {
+        self.tap_socket = Some(path);
+        self
+    }
+
+    /// Host the backend in the shared network daemon at `socket`, if any.
+    pub fn with_shared_daemon(mut self, socket: Option<PathBuf>) -> Self {
+        self.shared_daemon = socket;
+        self
+    }
+
+    /// Give the guest interface `mac` instead of the default address.
+    pub fn with_guest_mac(mut self, mac: Option<[u8; 6]>) -> Self {
+        self.guest_mac = mac;
+        self
     }
+
+    /// MAC address of the guest interface.
+    pub fn guest_mac(&self) -> [u8; 6] {
+        self.guest_mac.unwrap_or(constants::GUEST_MAC)
+    }
+
+    /// Give the guest `ip` instead of the default address.
+    pub fn with_guest_ip(mut self, ip: Option<Ipv4Addr>) -> Self {
+        self.guest_ip = ip;
+        self
+    }
+
+    /// IPv4 address of the guest.
+    pub fn guest_ip(&self) -> Ipv4Addr {
+        self.guest_ip.unwrap_or(constants::GUEST_IP_ADDR)
+    }
+
+    /// Allow or block guest connections to the host's loopback.
+    pub fn with_host_loopback(mut self, enabled: bool) -> Self {
+        self.host_loopback = enabled;
+        self
+    }
+
+    /// Log the connections the guest opens to `path`, if any.
+    pub fn with_connection_log(mut self, path: Option<PathBuf>) -> Self {
+        self.connection_log = path;
+        self
+    }
+
+    /// Egress limit in bytes per second, if any.
+    pub fn egress_limit_bytes_per_sec(&self) -> Option<u64> {
+        self.egress_limit_mbps
+            .filter(|mbps| *mbps > 0)
+            .map(|mbps| mbps * 1_000_000 / 8)
+    }
+}
+
+fn default_host_loopback() -> bool {
+    true
 }
 
 /// Network metrics from a network backend.
@@ -1,9 +1,23 @@
 use crate::pipeline::ExecutionMode;
 
+/// How a task ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskOutcome {
+    /// Task ran to completion.
+    Succeeded,
+    /// Task returned an error, aborting the pipeline.
+    Failed,
+    /// Task was dropped mid-run because a parallel sibling failed.
+    Cancelled,
+    /// Task never started because an earlier stage failed.
+    Skipped,
+}
+
 #[derive(Debug, Clone)]
 pub struct TaskMetrics {
     pub name: String,
     pub duration_ms: u128,
+    pub outcome: TaskOutcome,
 }
 
 #[derive(Debug, Clone)]
@@ -22,10 +36,13 @@ pub struct PipelineMetrics {
 
 impl PipelineMetrics {
     pub fn task_duration_ms(&self, name: &str) -> Option<u128> {
-        self.stages
-            .iter()
-            .flat_map(|stage| stage.tasks.iter())
+        self.tasks()
             .find(|task| task.name == name)
             .map(|task| task.duration_ms)
     }
+
+    /// All tasks in execution order, including failed and skipped ones.
+    pub fn tasks(&self) -> impl Iterator<Item = &TaskMetrics> {
+        self.stages.iter().flat_map(|stage| stage.tasks.iter())
+    }
 }
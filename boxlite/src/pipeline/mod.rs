@@ -33,8 +33,9 @@
 //!
 //! let ctx = Arc::new(Mutex::new(Context));
 //! let pipeline = PipelineBuilder::from_plan(plan);
-//! let metrics = PipelineExecutor::execute(pipeline, ctx).await?;
+//! let (metrics, result) = PipelineExecutor::execute(pipeline, ctx).await;
 //! println!("pipeline took {}ms", metrics.total_duration_ms);
+//! result?;
 //! ```
 
 mod metrics;
@@ -43,7 +44,7 @@ mod pipeline;
 mod stage;
 mod task;
 
-pub use metrics::{PipelineMetrics, StageMetrics, TaskMetrics};
+pub use metrics::{PipelineMetrics, StageMetrics, TaskMetrics, TaskOutcome};
 pub use pipeline::{ExecutionPlan, Pipeline, PipelineBuilder, PipelineExecutor};
 pub use stage::{ExecutionMode, Stage};
 pub use task::{BoxedTask, PipelineTask};
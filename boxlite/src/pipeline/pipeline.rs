@@ -3,11 +3,12 @@
 //! Provides a table-driven pipeline executor that can run stages containing tasks
 //! in parallel or sequential mode.
 
-use super::metrics::{PipelineMetrics, StageMetrics, TaskMetrics};
+use super::metrics::{PipelineMetrics, StageMetrics, TaskMetrics, TaskOutcome};
 use super::stage::{ExecutionMode, Stage};
 use super::task::BoxedTask;
 use boxlite_shared::errors::BoxliteResult;
-use futures::future::try_join_all;
+use futures::StreamExt;
+use futures::stream::FuturesUnordered;
 use std::time::Instant;
 
 pub struct ExecutionPlan<Ctx> {
@@ -54,44 +55,120 @@ impl PipelineExecutor {
     /// This is the core pipeline execution loop. It iterates through stages
     /// and executes their tasks according to the stage's execution mode.
     ///
+    /// Metrics are returned even when a task fails: the failed task is
+    /// recorded as `Failed`, parallel siblings still running as `Cancelled`,
+    /// and tasks of later stages as `Skipped`.
+    ///
     /// Generic over:
     /// - `Ctx`: Shared pipeline context (use interior mutability for writes)
-    pub async fn execute<Ctx>(pipeline: Pipeline<Ctx>, ctx: Ctx) -> BoxliteResult<PipelineMetrics>
+    pub async fn execute<Ctx>(
+        pipeline: Pipeline<Ctx>,
+        ctx: Ctx,
+    ) -> (PipelineMetrics, BoxliteResult<()>)
     where
         Ctx: Clone,
     {
         let total_start = Instant::now();
         let mut stage_metrics = Vec::new();
+        let mut result = Ok(());
 
         for (index, stage) in pipeline.stages.into_iter().enumerate() {
             let execution = stage.execution;
             let stage_start = Instant::now();
 
+            if result.is_err() {
+                stage_metrics.push(StageMetrics {
+                    index,
+                    execution,
+                    duration_ms: 0,
+                    tasks: stage
+                        .tasks
+                        .iter()
+                        .map(|task| TaskMetrics {
+                            name: task.name().to_string(),
+                            duration_ms: 0,
+                            outcome: TaskOutcome::Skipped,
+                        })
+                        .collect(),
+                });
+                continue;
+            }
+
             let task_metrics = match execution {
                 ExecutionMode::Parallel => {
-                    let futures = stage.tasks.into_iter().map(|task| {
-                        let ctx = ctx.clone();
-                        async move {
-                            let name = task.name().to_string();
-                            let task_start = Instant::now();
-                            task.run(ctx).await?;
-                            Ok::<TaskMetrics, boxlite_shared::errors::BoxliteError>(TaskMetrics {
+                    let names: Vec<String> =
+                        stage.tasks.iter().map(|t| t.name().to_string()).collect();
+                    let mut finished: Vec<Option<TaskMetrics>> = vec![None; names.len()];
+                    let mut running: FuturesUnordered<_> = stage
+                        .tasks
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, task)| {
+                            let ctx = ctx.clone();
+                            async move {
+                                let task_start = Instant::now();
+                                let task_result = task.run(ctx).await;
+                                (i, task_start.elapsed().as_millis(), task_result)
+                            }
+                        })
+                        .collect();
+
+                    while let Some((i, duration_ms, task_result)) = running.next().await {
+                        let outcome = match task_result {
+                            Ok(()) => TaskOutcome::Succeeded,
+                            Err(e) => {
+                                result = Err(e);
+                                TaskOutcome::Failed
+                            }
+                        };
+                        finished[i] = Some(TaskMetrics {
+                            name: names[i].clone(),
+                            duration_ms,
+                            outcome,
+                        });
+                        if result.is_err() {
+                            break;
+                        }
+                    }
+                    // Dropping the remaining futures cancels them
+                    drop(running);
+
+                    names
+                        .into_iter()
+                        .zip(finished)
+                        .map(|(name, metrics)| {
+                            metrics.unwrap_or_else(|| TaskMetrics {
                                 name,
-                                duration_ms: task_start.elapsed().as_millis(),
+                                duration_ms: stage_start.elapsed().as_millis(),
+                                outcome: TaskOutcome::Cancelled,
                             })
-                        }
-                    });
-                    try_join_all(futures).await?
+                        })
+                        .collect()
                 }
                 ExecutionMode::Sequential => {
                     let mut task_metrics = Vec::new();
                     for task in stage.tasks {
                         let name = task.name().to_string();
+                        if result.is_err() {
+                            task_metrics.push(TaskMetrics {
+                                name,
+                                duration_ms: 0,
+                                outcome: TaskOutcome::Skipped,
+                            });
+                            continue;
+                        }
                         let task_start = Instant::now();
-                        task.run(ctx.clone()).await?;
+                        let outcome = match task.run(ctx.clone()).await {
+                            Ok(()) => TaskOutcome::Succeeded,
+                            Err(e) => {
+                                result = Err(e);
+                                TaskOutcome::Failed
+                            }
+                        };
                         task_metrics.push(TaskMetrics {
                             name,
                             duration_ms: task_start.elapsed().as_millis(),
+                            outcome,
                         });
                     }
                     task_metrics
@@ -106,9 +183,68 @@ impl PipelineExecutor {
             });
         }
 
-        Ok(PipelineMetrics {
+        let metrics = PipelineMetrics {
             total_duration_ms: total_start.elapsed().as_millis(),
             stages: stage_metrics,
-        })
+        };
+        (metrics, result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::PipelineTask;
+    use async_trait::async_trait;
+    use boxlite_shared::errors::BoxliteError;
+
+    struct TestTask {
+        name: &'static str,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl PipelineTask<()> for TestTask {
+        async fn run(self: Box<Self>, _ctx: ()) -> BoxliteResult<()> {
+            if self.fail {
+                return Err(BoxliteError::Internal(format!("{} failed", self.name)));
+            }
+            Ok(())
+        }
+
+        fn name(&self) -> &str {
+            self.name
+        }
+    }
+
+    fn task(name: &'static str, fail: bool) -> BoxedTask<()> {
+        Box::new(TestTask { name, fail })
+    }
+
+    #[tokio::test]
+    async fn test_execute_records_outcomes_on_failure() {
+        let plan = ExecutionPlan::new(vec![
+            Stage::sequential(vec![task("setup", false)]),
+            Stage::sequential(vec![task("spawn", true), task("connect", false)]),
+            Stage::parallel(vec![task("init", false)]),
+        ]);
+
+        let (metrics, result) =
+            PipelineExecutor::execute(PipelineBuilder::from_plan(plan), ()).await;
+
+        assert!(result.is_err());
+        let outcomes: Vec<_> = metrics
+            .tasks()
+            .map(|t| (t.name.as_str(), t.outcome))
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                ("setup", TaskOutcome::Succeeded),
+                ("spawn", TaskOutcome::Failed),
+                ("connect", TaskOutcome::Skipped),
+                ("init", TaskOutcome::Skipped),
+            ]
+        );
     }
 }
@@ -1,21 +1,43 @@
 //! Connection management.
 //!
 //! Converts Transport to tonic Channel with lazy initialization.
+//!
+//! A dropped connection is re-established on demand: transient connect
+//! failures are retried a few times with backoff, and a channel whose call
+//! failed at the transport level is discarded so the next call reconnects.
+//! Only [`Connection::call`] repeats the call itself; calls that must not
+//! run twice use [`Connection::call_once`].
+//!
+//! Every guest call goes through [`Connection::channel`], so the cached
+//! channel is read without waiting on other calls; only connecting is
+//! serialized.
 
 use boxlite_shared::{BoxliteError, BoxliteResult, Transport};
 use hyper_util::rt::TokioIo;
+use parking_lot::RwLock;
+use std::future::Future;
 use std::sync::Arc;
-use tokio::sync::OnceCell;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
 use tonic::transport::{Channel, Endpoint, Uri};
 use tower::service_fn;
 
+/// Connect attempts per (re)connect before giving up.
+const CONNECT_ATTEMPTS: u32 = 3;
+/// Delay before the second connect attempt; doubles per attempt.
+const CONNECT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
 /// Lazy connection to guest.
 ///
 /// Connects on first use to ensure connection happens in the correct async runtime.
 #[derive(Clone)]
 pub struct Connection {
     transport: Transport,
-    channel: Arc<OnceCell<Channel>>,
+    /// Cached channel. Never held across an await.
+    channel: Arc<RwLock<Option<Channel>>>,
+    /// Held while connecting, so concurrent callers share one connect.
+    connecting: Arc<Mutex<()>>,
+    connect_timeout: Option<Duration>,
 }
 
 impl Connection {
@@ -23,18 +45,123 @@ impl Connection {
     pub fn new(transport: Transport) -> Self {
         Self {
             transport,
-            channel: Arc::new(OnceCell::new()),
+            channel: Arc::new(RwLock::new(None)),
+            connecting: Arc::new(Mutex::new(())),
+            connect_timeout: None,
         }
     }
 
+    /// Fail a connect with `GuestTimeout` if it takes longer than `timeout`.
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
     /// Get or establish the channel.
     pub async fn channel(&self) -> BoxliteResult<Channel> {
-        let channel = self
-            .channel
-            .get_or_try_init(|| async { connect_transport(&self.transport).await })
-            .await?;
+        if let Some(channel) = self.cached() {
+            return Ok(channel);
+        }
+
+        let _connecting = self.connecting.lock().await;
+        // Another caller may have connected while this one waited
+        if let Some(channel) = self.cached() {
+            return Ok(channel);
+        }
+
+        let channel = match self.connect_timeout {
+            None => connect_with_retries(&self.transport).await?,
+            Some(timeout) => {
+                let started = Instant::now();
+                tokio::time::timeout(timeout, connect_with_retries(&self.transport))
+                    .await
+                    .map_err(|_| {
+                        BoxliteError::GuestTimeout(format!(
+                            "Timeout connecting to guest after {:.1}s (limit {}s)",
+                            started.elapsed().as_secs_f64(),
+                            timeout.as_secs()
+                        ))
+                    })??
+            }
+        };
+        *self.channel.write() = Some(channel.clone());
+        Ok(channel)
+    }
+
+    fn cached(&self) -> Option<Channel> {
+        self.channel.read().clone()
+    }
+
+    /// Discard the cached channel so the next call reconnects.
+    pub async fn reset(&self) {
+        self.channel.write().take();
+    }
+
+    /// Run `call` on the channel, reconnecting after transport failures.
+    ///
+    /// A call that fails with `RpcTransport` is retried on a fresh channel,
+    /// up to `CONNECT_ATTEMPTS` calls in total. Other errors are returned as-is.
+    pub async fn call<T, F, Fut>(&self, mut call: F) -> BoxliteResult<T>
+    where
+        F: FnMut(Channel) -> Fut,
+        Fut: Future<Output = BoxliteResult<T>>,
+    {
+        let mut attempt = 1;
+        loop {
+            let channel = self.channel().await?;
+            match call(channel).await {
+                Err(e) if is_transport_error(&e) && attempt < CONNECT_ATTEMPTS => {
+                    tracing::debug!(attempt, "Guest connection lost, reconnecting: {}", e);
+                    self.reset().await;
+                    tokio::time::sleep(retry_delay(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
 
-        Ok(channel.clone())
+    /// Run `call` once; only opening the channel is retried.
+    ///
+    /// For calls the guest must not run twice. A transport error once the
+    /// channel is up may have come after the request reached the guest, so
+    /// it is returned rather than retried. The channel is still discarded
+    /// so the next call reconnects.
+    pub async fn call_once<T, F, Fut>(&self, call: F) -> BoxliteResult<T>
+    where
+        F: FnOnce(Channel) -> Fut,
+        Fut: Future<Output = BoxliteResult<T>>,
+    {
+        let channel = self.channel().await?;
+        let result = call(channel).await;
+        if matches!(&result, Err(e) if is_transport_error(e)) {
+            self.reset().await;
+        }
+        result
+    }
+}
+
+fn is_transport_error(err: &BoxliteError) -> bool {
+    matches!(err, BoxliteError::RpcTransport(_))
+}
+
+/// Backoff after failed attempt number `attempt` (1-based).
+fn retry_delay(attempt: u32) -> Duration {
+    CONNECT_RETRY_DELAY.saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+}
+
+/// Connect, retrying transport errors up to `CONNECT_ATTEMPTS` times.
+async fn connect_with_retries(transport: &Transport) -> BoxliteResult<Channel> {
+    let mut attempt = 1;
+    loop {
+        match connect_transport(transport).await {
+            Err(e) if is_transport_error(&e) && attempt < CONNECT_ATTEMPTS => {
+                tracing::debug!(attempt, "Guest connect failed, retrying: {}", e);
+                tokio::time::sleep(retry_delay(attempt)).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
     }
 }
 
@@ -80,3 +207,44 @@ async fn connect_tcp(port: u16) -> BoxliteResult<Channel> {
     tracing::debug!("Connected via TCP");
     Ok(channel)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_doubles() {
+        assert_eq!(retry_delay(1), Duration::from_millis(100));
+        assert_eq!(retry_delay(2), Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_only_transport_errors_reconnect() {
+        assert!(is_transport_error(&BoxliteError::from(
+            tonic::Status::unavailable("connection reset")
+        )));
+        assert!(!is_transport_error(&BoxliteError::GuestTimeout(
+            "10s".into()
+        )));
+        assert!(!is_transport_error(&BoxliteError::Rpc("not found".into())));
+    }
+
+    #[tokio::test]
+    async fn test_call_once_does_not_retry_after_send() {
+        let connection = Connection::new(Transport::Tcp { port: 1 });
+        *connection.channel.write() =
+            Some(Endpoint::from_static("http://127.0.0.1:1").connect_lazy());
+
+        let mut calls = 0;
+        let result: BoxliteResult<()> = connection
+            .call_once(|_| {
+                calls += 1;
+                async { Err(BoxliteError::RpcTransport("connection reset".into())) }
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+        // The dead channel is dropped so the next call reconnects
+        assert!(connection.cached().is_none());
+    }
+}
@@ -1,12 +1,18 @@
 //! Container service interface.
 
 use boxlite_shared::{
-    BindMount, BoxliteError, BoxliteResult, ContainerClient,
-    ContainerConfig as ProtoContainerConfig, ContainerInitRequest, DiskRootfs, MergedRootfs,
-    OverlayRootfs, RootfsInit, container_init_response,
+    BindMount, BoxliteError, BoxliteResult, CapabilitySet, ContainerClient,
+    ContainerConfig as ProtoContainerConfig, ContainerInitRequest, ContainerMountRequest,
+    ContainerProcessesRequest, ContainerUnmountRequest, DiskRootfs, Filesystem, ImageRootfs,
+    ListeningPortsRequest, MergedRootfs, OverlayRootfs, RootfsInit, TmpfsMount,
+    container_init_response,
 };
+use std::net::Ipv4Addr;
 use tonic::transport::Channel;
 
+use crate::disk::DiskFormat;
+use crate::litebox::{ListeningPort, ProcessInfo};
+use crate::runtime::options::TmpfsSpec;
 use crate::volumes::ContainerMount;
 
 /// Container rootfs initialization strategy.
@@ -33,9 +39,32 @@ pub enum ContainerRootfsInitConfig {
         /// Whether to resize filesystem after mounting to fill disk
         need_resize: bool,
     },
+    /// Read-only image (EROFS/SquashFS) under an overlay with a writable upper disk
+    ReadOnlyImage {
+        /// Read-only image block device path (e.g., "/dev/vda")
+        image_device: String,
+        /// Image filesystem (`Erofs` or `Squashfs`)
+        image_format: DiskFormat,
+        /// Writable upper disk block device path (e.g., "/dev/vdb")
+        upper_device: String,
+        /// Whether to format the upper disk before mounting
+        need_format: bool,
+    },
 }
 
 impl ContainerRootfsInitConfig {
+    /// Guest protocol version this strategy needs beyond the baseline, with
+    /// a feature name for the error message.
+    pub(crate) fn required_protocol(&self) -> Option<(u32, &'static str)> {
+        match self {
+            ContainerRootfsInitConfig::ReadOnlyImage { .. } => Some((
+                boxlite_shared::constants::protocol::IMAGE_ROOTFS,
+                "read-only image rootfs",
+            )),
+            _ => None,
+        }
+    }
+
     pub(crate) fn into_proto(self) -> RootfsInit {
         match self {
             ContainerRootfsInitConfig::Merged => RootfsInit {
@@ -65,6 +94,22 @@ impl ContainerRootfsInitConfig {
                     need_resize,
                 })),
             },
+            ContainerRootfsInitConfig::ReadOnlyImage {
+                image_device,
+                image_format,
+                upper_device,
+                need_format,
+            } => RootfsInit {
+                strategy: Some(boxlite_shared::rootfs_init::Strategy::Image(ImageRootfs {
+                    image_device,
+                    image_filesystem: match image_format {
+                        DiskFormat::Squashfs => Filesystem::Squashfs,
+                        _ => Filesystem::Erofs,
+                    } as i32,
+                    upper_device,
+                    need_format,
+                })),
+            },
         }
     }
 }
@@ -89,15 +134,28 @@ impl ContainerInterface {
     /// * `image_config` - Image-derived container config (entrypoint, env, workdir)
     /// * `rootfs` - Rootfs initialization strategy
     /// * `mounts` - Bind mounts from guest VM paths into container
+    /// * `tmpfs` - In-memory mounts inside the container
+    /// * `allow_fuse` - Expose /dev/fuse to the container
+    /// * `capabilities` - Capabilities of the container process (`CAP_NAME` form)
+    /// * `hostname` - Container hostname
+    /// * `timezone` - Timezone to bind at /etc/localtime (None = image default)
+    /// * `host_loopback_ip` - Host loopback address to name `host.boxlite.internal`
     ///
     /// # Returns
     /// Container ID on success
+    #[allow(clippy::too_many_arguments)]
     pub async fn init(
         &mut self,
         container_id: &str,
         image_config: crate::images::ContainerImageConfig,
         rootfs: ContainerRootfsInitConfig,
         mounts: Vec<ContainerMount>,
+        tmpfs: &[TmpfsSpec],
+        allow_fuse: bool,
+        capabilities: Vec<String>,
+        hostname: &str,
+        timezone: Option<&str>,
+        host_loopback_ip: Option<Ipv4Addr>,
     ) -> BoxliteResult<String> {
         let proto_config = ProtoContainerConfig {
             entrypoint: image_config.final_cmd(),
@@ -114,6 +172,8 @@ impl ContainerInterface {
                 volume_name: m.volume_name,
                 destination: m.destination,
                 read_only: m.read_only,
+                sub_path: m.sub_path.unwrap_or_default(),
+                chown: m.chown,
             })
             .collect();
 
@@ -127,6 +187,11 @@ impl ContainerInterface {
             env_count = image_config.env.len(),
             rootfs = ?rootfs,
             mounts_count = proto_mounts.len(),
+            allow_fuse,
+            capabilities = ?capabilities,
+            hostname,
+            timezone,
+            host_loopback_ip = ?host_loopback_ip,
             "Container configuration"
         );
 
@@ -135,6 +200,23 @@ impl ContainerInterface {
             container_config: Some(proto_config),
             rootfs: Some(rootfs.into_proto()),
             mounts: proto_mounts,
+            tmpfs: tmpfs
+                .iter()
+                .map(|t| TmpfsMount {
+                    destination: t.path.clone(),
+                    size_bytes: t.size_bytes.unwrap_or(0),
+                    mode: t.mode.unwrap_or(0),
+                })
+                .collect(),
+            allow_fuse,
+            capabilities: Some(CapabilitySet {
+                names: capabilities,
+            }),
+            hostname: hostname.to_string(),
+            timezone: timezone.unwrap_or_default().to_string(),
+            host_loopback_ip: host_loopback_ip
+                .map(|ip| ip.to_string())
+                .unwrap_or_default(),
         };
 
         let response = self.client.init(request).await?.into_inner();
@@ -156,4 +238,71 @@ impl ContainerInterface {
             )),
         }
     }
+
+    /// Bind-mount a volume into the running container.
+    ///
+    /// The volume must already exist at its convention path in the guest.
+    pub async fn mount(&mut self, container_id: &str, mount: ContainerMount) -> BoxliteResult<()> {
+        tracing::debug!(
+            container_id = %container_id,
+            volume = %mount.volume_name,
+            destination = %mount.destination,
+            "Sending ContainerMount request"
+        );
+        self.client
+            .mount(ContainerMountRequest {
+                container_id: container_id.to_string(),
+                mount: Some(BindMount {
+                    volume_name: mount.volume_name,
+                    destination: mount.destination,
+                    read_only: mount.read_only,
+                    sub_path: mount.sub_path.unwrap_or_default(),
+                    chown: mount.chown,
+                }),
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Remove a mount added by [`mount`](Self::mount).
+    pub async fn unmount(&mut self, container_id: &str, destination: &str) -> BoxliteResult<()> {
+        self.client
+            .unmount(ContainerUnmountRequest {
+                container_id: container_id.to_string(),
+                destination: destination.to_string(),
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// List the container's processes, sorted by PID.
+    pub async fn processes(&mut self, container_id: &str) -> BoxliteResult<Vec<ProcessInfo>> {
+        let response = self
+            .client
+            .processes(ContainerProcessesRequest {
+                container_id: container_id.to_string(),
+            })
+            .await?
+            .into_inner();
+        Ok(response.processes.into_iter().map(Into::into).collect())
+    }
+
+    /// List sockets listening in the container, sorted by port.
+    pub async fn listening_ports(
+        &mut self,
+        container_id: &str,
+    ) -> BoxliteResult<Vec<ListeningPort>> {
+        let response = self
+            .client
+            .listening_ports(ListeningPortsRequest {
+                container_id: container_id.to_string(),
+            })
+            .await?
+            .into_inner();
+        Ok(response
+            .sockets
+            .into_iter()
+            .filter_map(ListeningPort::from_proto)
+            .collect())
+    }
 }
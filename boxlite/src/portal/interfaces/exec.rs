@@ -3,11 +3,15 @@
 //! High-level API for execution operations (unary Exec + output-only Attach +
 //! blocking Wait).
 
-use crate::litebox::{BoxCommand, ExecResult};
+use crate::litebox::{BoxCommand, ExecResult, ExitReason};
+use crate::metrics::ExecOutputCounters;
+use crate::runtime::options::ExecCompression;
 use boxlite_shared::{
     AttachRequest, BoxliteError, BoxliteResult, ExecOutput, ExecRequest, ExecStdin,
-    ExecutionClient, KillRequest, WaitRequest, WaitResponse, exec_output,
+    ExecutionClient, KillRequest, StreamCompression, WaitRequest, WaitResponse, compression,
+    constants::exec_error, exec_output,
 };
+use bytes::Bytes;
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
 use tokio_util::sync::CancellationToken;
@@ -17,14 +21,15 @@ use tonic::transport::Channel;
 #[derive(Clone)]
 pub struct ExecutionInterface {
     client: ExecutionClient<Channel>,
+    output_counters: ExecOutputCounters,
 }
 
 /// Components for building an Execution.
 pub struct ExecComponents {
     pub execution_id: String,
     pub stdin_tx: mpsc::UnboundedSender<Vec<u8>>,
-    pub stdout_rx: mpsc::UnboundedReceiver<String>,
-    pub stderr_rx: mpsc::UnboundedReceiver<String>,
+    pub stdout_rx: mpsc::UnboundedReceiver<Bytes>,
+    pub stderr_rx: mpsc::UnboundedReceiver<Bytes>,
     pub result_rx: mpsc::UnboundedReceiver<ExecResult>,
 }
 
@@ -33,9 +38,16 @@ impl ExecutionInterface {
     pub fn new(channel: Channel) -> Self {
         Self {
             client: ExecutionClient::new(channel),
+            output_counters: ExecOutputCounters::default(),
         }
     }
 
+    /// Count stdout/stderr bytes (logical and on the wire) into `counters`.
+    pub(crate) fn with_output_counters(mut self, counters: ExecOutputCounters) -> Self {
+        self.output_counters = counters;
+        self
+    }
+
     /// Execute a command and return execution components.
     ///
     /// # Arguments
@@ -48,8 +60,8 @@ impl ExecutionInterface {
     ) -> BoxliteResult<ExecComponents> {
         // Create channels
         let (stdin_tx, stdin_rx) = mpsc::unbounded_channel::<Vec<u8>>();
-        let (stdout_tx, stdout_rx) = mpsc::unbounded_channel::<String>();
-        let (stderr_tx, stderr_rx) = mpsc::unbounded_channel::<String>();
+        let (stdout_tx, stdout_rx) = mpsc::unbounded_channel::<Bytes>();
+        let (stderr_tx, stderr_rx) = mpsc::unbounded_channel::<Bytes>();
         let (result_tx, result_rx) = mpsc::unbounded_channel();
 
         // Build request
@@ -60,13 +72,38 @@ impl ExecutionInterface {
         // Start execution
         let exec_response = self.client.exec(request).await?.into_inner();
         if let Some(err) = exec_response.error {
-            return Err(BoxliteError::Internal(format!(
-                "{}: {}",
-                err.reason, err.detail
-            )));
+            // A missing or non-executable program finishes like a shell
+            // would run it: message on stderr, exit code 127 or 126
+            let Some(reason) = spawn_failure_reason(&err.reason) else {
+                if err.reason == exec_error::TOO_MANY_EXECUTIONS {
+                    return Err(BoxliteError::ResourceExhausted(err.detail));
+                }
+                return Err(BoxliteError::Internal(format!(
+                    "{}: {}",
+                    err.reason, err.detail
+                )));
+            };
+            tracing::debug!(reason = reason.as_str(), detail = %err.detail, "Exec failed to start");
+            let _ = stderr_tx.send(Bytes::from(format!("{}\n", err.detail)));
+            let _ = result_tx.send(ExecResult {
+                exit_code: reason.exit_code(),
+                reason: Some(reason),
+                error_message: Some(err.detail),
+                oom_killed: false,
+            });
+            return Ok(ExecComponents {
+                execution_id: exec_response.execution_id,
+                stdin_tx,
+                stdout_rx,
+                stderr_rx,
+                result_rx,
+            });
         }
 
         let execution_id = exec_response.execution_id.clone();
+        // Older guests leave this unset and send raw output
+        let compression = exec_response.compression();
+        tracing::debug!(execution_id = %execution_id, ?compression, "Exec output codec");
 
         // Spawn stdin pump (no cancellation needed - closes when stdin_tx is dropped)
         ExecProtocol::spawn_stdin(self.client.clone(), execution_id.clone(), stdin_rx);
@@ -75,8 +112,12 @@ impl ExecutionInterface {
         ExecProtocol::spawn_attach(
             self.client.clone(),
             execution_id.clone(),
-            stdout_tx,
-            stderr_tx,
+            OutputSink {
+                stdout_tx,
+                stderr_tx,
+                compression,
+                counters: self.output_counters.clone(),
+            },
             shutdown_token.clone(),
         );
 
@@ -181,6 +222,11 @@ impl ExecProtocol {
                 .collect(),
             workdir: command.working_dir.clone().unwrap_or_default(),
             timeout_ms: command.timeout.map(|d| d.as_millis() as u64).unwrap_or(0),
+            compression: match command.compression.unwrap_or_default() {
+                ExecCompression::None => StreamCompression::None,
+                ExecCompression::Gzip => StreamCompression::Gzip,
+                ExecCompression::Zstd => StreamCompression::Zstd,
+            } as i32,
             tty: if command.tty {
                 let (rows, cols) = crate::util::get_terminal_size();
                 Some(TtyConfig {
@@ -192,31 +238,32 @@ impl ExecProtocol {
             } else {
                 None
             },
+            stop_box_on_exit: command.stop_box_on_exit,
+            user: command.user.clone().unwrap_or_default(),
+            privileged: command.privileged,
+            fresh_rootfs: command.fresh_rootfs,
         }
     }
 
     fn map_wait_response(resp: WaitResponse) -> ExecResult {
-        let code = if resp.signal != 0 {
-            -resp.signal
-        } else {
-            resp.exit_code
-        };
+        let reason = (resp.signal != 0).then_some(ExitReason::Signaled(resp.signal));
         let error_message = if resp.error_message.is_empty() {
             None
         } else {
             Some(resp.error_message)
         };
         ExecResult {
-            exit_code: code,
+            exit_code: reason.map_or(resp.exit_code, |reason| reason.exit_code()),
+            reason,
             error_message,
+            oom_killed: resp.oom_killed,
         }
     }
 
     fn spawn_attach(
         mut client: ExecutionClient<Channel>,
         execution_id: String,
-        stdout_tx: mpsc::UnboundedSender<String>,
-        stderr_tx: mpsc::UnboundedSender<String>,
+        sink: OutputSink,
         shutdown_token: CancellationToken,
     ) {
         tokio::spawn(async move {
@@ -258,7 +305,7 @@ impl ExecProtocol {
                         match output.transpose() {
                             Some(Ok(output)) => {
                                 message_count += 1;
-                                Self::route_output(output, &stdout_tx, &stderr_tx);
+                                sink.route(output);
                             }
                             Some(Err(e)) => {
                                 tracing::debug!(
@@ -267,7 +314,9 @@ impl ExecProtocol {
                                     message_count,
                                     "Attach stream error, breaking"
                                 );
-                                let _ = stderr_tx.send(format!("Attach stream error: {}", e));
+                                let _ = sink
+                                    .stderr_tx
+                                    .send(Bytes::from(format!("Attach stream error: {}", e)));
                                 break;
                             }
                             None => {
@@ -285,32 +334,14 @@ impl ExecProtocol {
                 }
                 Err(e) => {
                     tracing::debug!(execution_id = %execution_id, error = %e, "Attach failed");
-                    let _ = stderr_tx.send(format!("Attach failed: {}", e));
+                    let _ = sink
+                        .stderr_tx
+                        .send(Bytes::from(format!("Attach failed: {}\n", e)));
                 }
             }
         });
     }
 
-    fn route_output(
-        output: ExecOutput,
-        stdout_tx: &mpsc::UnboundedSender<String>,
-        stderr_tx: &mpsc::UnboundedSender<String>,
-    ) {
-        match output.event {
-            Some(exec_output::Event::Stdout(chunk)) => {
-                let stdout_data = String::from_utf8_lossy(&chunk.data).to_string();
-                tracing::trace!(?stdout_data, "Received exec stdout");
-                let _ = stdout_tx.send(stdout_data);
-            }
-            Some(exec_output::Event::Stderr(chunk)) => {
-                let stderr_data = String::from_utf8_lossy(&chunk.data).to_string();
-                tracing::trace!(?stderr_data, "Received exec stderr");
-                let _ = stderr_tx.send(stderr_data);
-            }
-            None => {}
-        }
-    }
-
     fn spawn_wait(
         mut client: ExecutionClient<Channel>,
         execution_id: String,
@@ -329,7 +360,12 @@ impl ExecProtocol {
                     tracing::debug!(execution_id = %execution_id, "Wait cancelled during shutdown");
                     // Send a special result indicating cancellation
                     // Using exit code -1 to indicate abnormal termination
-                    let _ = result_tx.send(ExecResult { exit_code: -1, error_message: None });
+                    let _ = result_tx.send(ExecResult {
+                        exit_code: -1,
+                        reason: None,
+                        error_message: None,
+                        oom_killed: false,
+                    });
                     return;
                 }
                 result = client.wait(request) => result,
@@ -346,9 +382,13 @@ impl ExecProtocol {
                         error = %e,
                         "Wait failed"
                     );
+                    // The process may still run in the guest, but its exit
+                    // status is lost with the connection; report why.
                     let _ = result_tx.send(ExecResult {
                         exit_code: -1,
-                        error_message: None,
+                        reason: None,
+                        error_message: Some(wait_error_message(&e)),
+                        oom_killed: false,
                     });
                 }
             }
@@ -399,6 +439,74 @@ impl ExecProtocol {
     }
 }
 
+/// Destination of an execution's output, decoding compressed chunks.
+struct OutputSink {
+    stdout_tx: mpsc::UnboundedSender<Bytes>,
+    stderr_tx: mpsc::UnboundedSender<Bytes>,
+    compression: StreamCompression,
+    counters: ExecOutputCounters,
+}
+
+impl OutputSink {
+    fn route(&self, output: ExecOutput) {
+        match output.event {
+            Some(exec_output::Event::Stdout(chunk)) => {
+                if let Some(stdout_data) = self.decode(chunk.data, chunk.compressed) {
+                    tracing::trace!(?stdout_data, "Received exec stdout");
+                    let _ = self.stdout_tx.send(stdout_data);
+                }
+            }
+            Some(exec_output::Event::Stderr(chunk)) => {
+                if let Some(stderr_data) = self.decode(chunk.data, chunk.compressed) {
+                    tracing::trace!(?stderr_data, "Received exec stderr");
+                    let _ = self.stderr_tx.send(stderr_data);
+                }
+            }
+            None => {}
+        }
+    }
+
+    /// Decompress (if needed) and count a chunk; None if it can't be decoded.
+    fn decode(&self, data: Vec<u8>, compressed: bool) -> Option<Bytes> {
+        let wire = data.len() as u64;
+        let data = if compressed {
+            match compression::decompress(self.compression, &data) {
+                Ok(data) => data,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Dropping undecodable exec output chunk");
+                    let _ = self.stderr_tx.send(Bytes::from(format!(
+                        "Failed to decompress exec output: {}",
+                        e
+                    )));
+                    return None;
+                }
+            }
+        } else {
+            data
+        };
+        self.counters.add(wire, data.len() as u64);
+        Some(Bytes::from(data))
+    }
+}
+
+/// Typed reason for an `ExecError` the caller sees as an exit code.
+fn spawn_failure_reason(reason: &str) -> Option<ExitReason> {
+    match reason {
+        exec_error::COMMAND_NOT_FOUND => Some(ExitReason::CommandNotFound),
+        exec_error::NOT_EXECUTABLE => Some(ExitReason::NotExecutable),
+        _ => None,
+    }
+}
+
+/// Error reported for an execution whose Wait call failed.
+fn wait_error_message(status: &tonic::Status) -> String {
+    if status.code() == tonic::Code::Unavailable {
+        format!("lost connection to guest: {}", status.message())
+    } else {
+        format!("wait failed: {}", status.message())
+    }
+}
+
 // ============================================================================
 // UNIT TESTS
 // ============================================================================
@@ -408,6 +516,130 @@ mod tests {
     use super::*;
     use std::time::Duration;
 
+    fn test_sink(
+        compression: StreamCompression,
+    ) -> (
+        OutputSink,
+        mpsc::UnboundedReceiver<Bytes>,
+        mpsc::UnboundedReceiver<Bytes>,
+    ) {
+        let (stdout_tx, stdout_rx) = mpsc::unbounded_channel();
+        let (stderr_tx, stderr_rx) = mpsc::unbounded_channel();
+        let sink = OutputSink {
+            stdout_tx,
+            stderr_tx,
+            compression,
+            counters: ExecOutputCounters::default(),
+        };
+        (sink, stdout_rx, stderr_rx)
+    }
+
+    #[test]
+    fn test_output_sink_decodes_and_counts() {
+        use std::sync::atomic::Ordering;
+
+        let (sink, mut stdout_rx, _stderr_rx) = test_sink(StreamCompression::Zstd);
+        let text = "hello from the box\n".repeat(200);
+        let frame = compression::compress(StreamCompression::Zstd, text.as_bytes()).unwrap();
+        let wire = frame.len() as u64;
+
+        sink.route(ExecOutput {
+            event: Some(exec_output::Event::Stdout(boxlite_shared::Stdout {
+                data: frame,
+                compressed: true,
+            })),
+        });
+        sink.route(ExecOutput {
+            event: Some(exec_output::Event::Stdout(boxlite_shared::Stdout {
+                data: b"raw\xff".to_vec(),
+                compressed: false,
+            })),
+        });
+
+        assert_eq!(stdout_rx.try_recv().unwrap(), text);
+        // Binary output passes through unchanged
+        assert_eq!(stdout_rx.try_recv().unwrap(), &b"raw\xff"[..]);
+        assert_eq!(
+            sink.counters.logical.load(Ordering::Relaxed),
+            text.len() as u64 + 4
+        );
+        assert_eq!(sink.counters.wire.load(Ordering::Relaxed), wire + 4);
+    }
+
+    #[test]
+    fn test_output_sink_reports_corrupt_chunks() {
+        let (sink, mut stdout_rx, mut stderr_rx) = test_sink(StreamCompression::Gzip);
+        sink.route(ExecOutput {
+            event: Some(exec_output::Event::Stdout(boxlite_shared::Stdout {
+                data: b"not gzip".to_vec(),
+                compressed: true,
+            })),
+        });
+
+        assert!(stdout_rx.try_recv().is_err());
+        let message = stderr_rx.try_recv().unwrap();
+        assert!(String::from_utf8_lossy(&message).contains("decompress"));
+    }
+
+    #[test]
+    fn test_exec_request_carries_user() {
+        let request = ExecProtocol::build_exec_request(&BoxCommand::new("id"));
+        assert_eq!(request.user, "");
+        assert!(!request.privileged);
+
+        let command = BoxCommand::new("id").user("1000:100").privileged(true);
+        let request = ExecProtocol::build_exec_request(&command);
+        assert_eq!(request.user, "1000:100");
+        assert!(request.privileged);
+        assert!(!request.fresh_rootfs);
+
+        let request = ExecProtocol::build_exec_request(&BoxCommand::new("id").fresh_rootfs(true));
+        assert!(request.fresh_rootfs);
+    }
+
+    #[test]
+    fn test_wait_error_message_reports_lost_connection() {
+        let msg = wait_error_message(&tonic::Status::unavailable("broken pipe"));
+        assert_eq!(msg, "lost connection to guest: broken pipe");
+
+        let msg = wait_error_message(&tonic::Status::not_found("unknown execution"));
+        assert_eq!(msg, "wait failed: unknown execution");
+    }
+
+    #[test]
+    fn test_exit_codes_follow_shell_conventions() {
+        let result = ExecProtocol::map_wait_response(WaitResponse {
+            signal: 15,
+            ..Default::default()
+        });
+        assert_eq!(result.exit_code, 143);
+        assert_eq!(result.signal(), Some(15));
+        assert!(!result.oom_killed);
+
+        let result = ExecProtocol::map_wait_response(WaitResponse {
+            signal: 9,
+            oom_killed: true,
+            ..Default::default()
+        });
+        assert_eq!((result.exit_code, result.oom_killed), (137, true));
+
+        let result = ExecProtocol::map_wait_response(WaitResponse {
+            exit_code: 3,
+            ..Default::default()
+        });
+        assert_eq!((result.exit_code, result.reason), (3, None));
+
+        assert_eq!(
+            spawn_failure_reason(exec_error::COMMAND_NOT_FOUND).map(|r| r.exit_code()),
+            Some(127)
+        );
+        assert_eq!(
+            spawn_failure_reason(exec_error::NOT_EXECUTABLE).map(|r| r.exit_code()),
+            Some(126)
+        );
+        assert!(spawn_failure_reason(exec_error::SPAWN_FAILED).is_none());
+    }
+
     /// Test that CancellationToken correctly signals cancelled state.
     #[tokio::test]
     async fn test_cancellation_token_basic() {
@@ -537,7 +769,12 @@ mod tests {
             tokio::select! {
                 biased;
                 _ = token_clone.cancelled() => {
-                    let _ = result_tx.send(ExecResult { exit_code: -1, error_message: None });
+                    let _ = result_tx.send(ExecResult {
+                        exit_code: -1,
+                        reason: None,
+                        error_message: None,
+                        oom_killed: false,
+                    });
                 }
                 _ = tokio::time::sleep(Duration::from_secs(3600)) => {
                     // Would normally wait for gRPC response
@@ -563,8 +800,8 @@ mod tests {
     #[tokio::test]
     async fn test_spawn_attach_cancellation_exits() {
         let token = CancellationToken::new();
-        let (stdout_tx, _stdout_rx) = mpsc::unbounded_channel::<String>();
-        let (_stderr_tx, _stderr_rx) = mpsc::unbounded_channel::<String>();
+        let (stdout_tx, _stdout_rx) = mpsc::unbounded_channel::<Bytes>();
+        let (_stderr_tx, _stderr_rx) = mpsc::unbounded_channel::<Bytes>();
 
         // Simulate spawn_attach's cancellation handling in streaming loop
         let token_clone = token.clone();
@@ -578,7 +815,7 @@ mod tests {
                     }
                     _ = tokio::time::sleep(Duration::from_millis(10)) => {
                         // Simulate receiving output
-                        let _ = stdout_tx.send("output".to_string());
+                        let _ = stdout_tx.send(Bytes::from_static(b"output"));
                         iterations += 1;
                     }
                 }
@@ -1,8 +1,13 @@
 //! Files service interface.
 //!
-//! Provides tar-based upload/download to the guest container rootfs.
-
-use boxlite_shared::{BoxliteError, BoxliteResult, DownloadRequest, FilesClient, UploadChunk};
+//! Provides tar-based upload/download to the guest container rootfs, and
+//! change notifications for container paths.
+
+use boxlite_shared::{
+    BoxliteError, BoxliteResult, DownloadRequest, FilesClient, FsEvent, UploadChunk,
+    WatchPathRequest,
+};
+use futures::{Stream, StreamExt};
 use tokio::fs::File;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tonic::transport::Channel;
@@ -132,6 +137,40 @@ impl FilesInterface {
 
         Ok(())
     }
+
+    /// Watch a container path, streaming change events until the stream is dropped.
+    pub async fn watch(
+        &mut self,
+        path: &str,
+        container_id: Option<&str>,
+        recursive: bool,
+    ) -> BoxliteResult<impl Stream<Item = BoxliteResult<FsEvent>> + Send + 'static + use<>> {
+        let request = WatchPathRequest {
+            path: path.to_string(),
+            container_id: container_id.unwrap_or_default().to_string(),
+            recursive,
+        };
+
+        let stream = self
+            .client
+            .watch_path(request)
+            .await
+            .map_err(map_watch_err)?
+            .into_inner();
+
+        Ok(stream.map(|event| event.map_err(map_watch_err)))
+    }
+}
+
+/// Watch errors carry actionable codes (missing path, inotify limits).
+fn map_watch_err(err: tonic::Status) -> BoxliteError {
+    let message = err.message().to_string();
+    match err.code() {
+        tonic::Code::NotFound => BoxliteError::NotFound(message),
+        tonic::Code::InvalidArgument => BoxliteError::InvalidArgument(message),
+        tonic::Code::ResourceExhausted => BoxliteError::ResourceExhausted(message),
+        _ => map_tonic_err(err),
+    }
 }
 
 fn map_tonic_err(err: tonic::Status) -> BoxliteError {
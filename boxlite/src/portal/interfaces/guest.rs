@@ -1,11 +1,15 @@
 //! Guest service interface.
 
 use boxlite_shared::{
-    BlockDeviceSource, BoxliteError, BoxliteResult, Filesystem, GuestClient, GuestInitRequest,
-    NetworkInit, PingRequest, ShutdownRequest, VirtiofsSource, Volume, guest_init_response,
+    BlockDeviceSource, BoxliteError, BoxliteResult, CoreDumpInit, Filesystem, GuestClient,
+    GuestInitRequest, IdShift, NetworkInit, PingRequest, ReclaimMemoryRequest, ShutdownRequest,
+    VirtiofsSource, Volume, guest_init_response,
 };
 use tonic::transport::Channel;
 
+use crate::metrics::BootPhases;
+use crate::runtime::options::{CoreDumpOptions, VolumeIdShift};
+
 /// Guest service interface.
 pub struct GuestInterface {
     client: GuestClient<Channel>,
@@ -23,7 +27,10 @@ impl GuestInterface {
     ///
     /// This must be called first after connection, before Container.Init.
     /// Sets up volumes (virtiofs + block devices) and network.
-    pub async fn init(&mut self, config: GuestInitConfig) -> BoxliteResult<()> {
+    ///
+    /// Returns the guest's boot phases so far (`container_init_ms` unset),
+    /// or None if the agent does not report them.
+    pub async fn init(&mut self, config: GuestInitConfig) -> BoxliteResult<Option<BootPhases>> {
         tracing::debug!("Sending GuestInit request");
         tracing::trace!(
             volumes = config.volumes.len(),
@@ -38,14 +45,24 @@ impl GuestInterface {
                 ip: n.ip,
                 gateway: n.gateway,
             }),
+            core_dumps: config.core_dumps.map(|c| CoreDumpInit {
+                max_bytes: c.max_bytes,
+                max_files: c.max_files,
+            }),
         };
 
         let response = self.client.init(request).await?.into_inner();
 
         match response.result {
-            Some(guest_init_response::Result::Success(_)) => {
+            Some(guest_init_response::Result::Success(success)) => {
                 tracing::debug!("Guest initialized");
-                Ok(())
+                Ok(success.boot_phases.map(|boot| BootPhases {
+                    kernel_ms: boot.kernel_ms,
+                    agent_startup_ms: boot.agent_startup_ms,
+                    mount_ms: boot.mount_ms,
+                    network_ms: boot.network_ms,
+                    container_init_ms: 0,
+                }))
             }
             Some(guest_init_response::Result::Error(err)) => {
                 tracing::error!("Guest init failed: {}", err.reason);
@@ -61,17 +78,98 @@ impl GuestInterface {
     }
 
     /// Ping the guest (health check).
-    #[allow(dead_code)] // API method for future health checks
     pub async fn ping(&mut self) -> BoxliteResult<()> {
         let _response = self.client.ping(PingRequest {}).await?;
         Ok(())
     }
 
+    /// Query the guest agent's version and protocol version.
+    pub async fn version(&mut self) -> BoxliteResult<GuestVersion> {
+        let response = self.client.ping(PingRequest {}).await?.into_inner();
+        Ok(GuestVersion {
+            agent: response.version,
+            protocol: response.protocol_version,
+        })
+    }
+
     /// Shutdown the guest agent.
     pub async fn shutdown(&mut self) -> BoxliteResult<()> {
         let _response = self.client.shutdown(ShutdownRequest {}).await?;
         Ok(())
     }
+
+    /// Ask the guest to drop its caches and compact free memory.
+    pub async fn reclaim_memory(&mut self) -> BoxliteResult<MemoryReclaim> {
+        let response = self
+            .client
+            .reclaim_memory(ReclaimMemoryRequest {})
+            .await?
+            .into_inner();
+        Ok(MemoryReclaim {
+            reclaimed_bytes: response.reclaimed_bytes,
+            free_bytes: response.free_bytes,
+        })
+    }
+}
+
+/// Guest agent version reported by Ping.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuestVersion {
+    /// Guest agent crate version (e.g. "0.4.2")
+    pub agent: String,
+    /// Host-guest protocol version (0 = agent predates versioning)
+    pub protocol: u32,
+}
+
+impl GuestVersion {
+    /// Check that this host can talk to the guest agent.
+    ///
+    /// Fails when the agent speaks a protocol older than
+    /// `protocol::MIN_GUEST_VERSION` or newer than this host's `protocol::VERSION`.
+    pub fn check_compatible(&self) -> BoxliteResult<()> {
+        use boxlite_shared::constants::protocol;
+
+        if self.protocol < protocol::MIN_GUEST_VERSION {
+            return Err(BoxliteError::Unsupported(format!(
+                "guest agent {} speaks protocol v{}, host requires at least v{}; \
+                 rebuild the guest rootfs with a newer boxlite-guest",
+                self.agent,
+                self.protocol,
+                protocol::MIN_GUEST_VERSION
+            )));
+        }
+        if self.protocol > protocol::VERSION {
+            return Err(BoxliteError::Unsupported(format!(
+                "guest agent {} speaks protocol v{}, newer than host v{}; \
+                 upgrade boxlite or use a matching boxlite-guest",
+                self.agent,
+                self.protocol,
+                protocol::VERSION
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check that the guest agent speaks at least `required` for `feature`.
+    pub fn require_protocol(&self, required: u32, feature: &str) -> BoxliteResult<()> {
+        if self.protocol < required {
+            return Err(BoxliteError::Unsupported(format!(
+                "{} requires guest protocol v{}, guest agent {} speaks v{}; \
+                 rebuild the guest rootfs with a newer boxlite-guest",
+                feature, required, self.agent, self.protocol
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Result of a guest memory reclaim.
+#[derive(Debug, Clone, Copy)]
+pub struct MemoryReclaim {
+    /// Guest free memory gained by the reclaim (bytes)
+    pub reclaimed_bytes: u64,
+    /// Guest free memory after the reclaim (bytes)
+    pub free_bytes: u64,
 }
 
 /// Configuration for guest initialization.
@@ -81,6 +179,8 @@ pub struct GuestInitConfig {
     pub volumes: Vec<VolumeConfig>,
     /// Network configuration (optional)
     pub network: Option<NetworkInitConfig>,
+    /// Core dump collection (optional; needs the cores share mounted)
+    pub core_dumps: Option<CoreDumpOptions>,
 }
 
 /// Volume configuration.
@@ -95,6 +195,8 @@ pub enum VolumeConfig {
         read_only: bool,
         /// Optional container_id for convention-based paths
         container_id: Option<String>,
+        /// Swap root with these host ids via an idmapped mount
+        id_shift: Option<VolumeIdShift>,
     },
     /// Block device mount
     BlockDevice {
@@ -124,7 +226,16 @@ impl VolumeConfig {
             mount_point: mount_point.into(),
             read_only,
             container_id,
+            id_shift: None,
+        }
+    }
+
+    /// Set the id shift of a virtiofs volume; no-op for block devices.
+    pub fn with_id_shift(mut self, shift: Option<VolumeIdShift>) -> Self {
+        if let Self::Virtiofs { id_shift, .. } = &mut self {
+            *id_shift = shift;
         }
+        self
     }
 
     /// Create block device volume config.
@@ -151,11 +262,16 @@ impl VolumeConfig {
                 mount_point,
                 read_only,
                 container_id,
+                id_shift,
             } => Volume {
                 mount_point,
                 source: Some(boxlite_shared::volume::Source::Virtiofs(VirtiofsSource {
                     tag,
                     read_only,
+                    id_shift: id_shift.map(|s| IdShift {
+                        uid: s.uid,
+                        gid: s.gid,
+                    }),
                 })),
                 container_id: container_id.unwrap_or_default(),
             },
@@ -191,3 +307,50 @@ pub struct NetworkInitConfig {
     /// Gateway address (e.g., "192.168.127.1")
     pub gateway: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use boxlite_shared::constants::protocol;
+
+    fn version(protocol: u32) -> GuestVersion {
+        GuestVersion {
+            agent: "0.0.0".to_string(),
+            protocol,
+        }
+    }
+
+    #[test]
+    fn test_guest_version_compatibility() {
+        assert!(version(protocol::VERSION).check_compatible().is_ok());
+        assert!(
+            version(protocol::MIN_GUEST_VERSION)
+                .check_compatible()
+                .is_ok()
+        );
+
+        // Agents built before protocol versioning report 0
+        let err = version(0).check_compatible().unwrap_err().to_string();
+        assert!(err.contains("at least"), "{}", err);
+
+        let err = version(protocol::VERSION + 1)
+            .check_compatible()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("newer than host"), "{}", err);
+    }
+
+    #[test]
+    fn test_guest_version_require_protocol() {
+        assert!(version(2).require_protocol(2, "feature").is_ok());
+        let err = version(1)
+            .require_protocol(2, "erofs rootfs")
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("erofs rootfs requires guest protocol v2"),
+            "{}",
+            err
+        );
+    }
+}
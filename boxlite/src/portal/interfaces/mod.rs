@@ -10,4 +10,4 @@ pub mod guest;
 pub use container::{ContainerInterface, ContainerRootfsInitConfig};
 pub use exec::ExecutionInterface;
 pub use files::FilesInterface;
-pub use guest::{GuestInitConfig, GuestInterface, NetworkInitConfig, VolumeConfig};
+pub use guest::{GuestInitConfig, GuestInterface, MemoryReclaim, NetworkInitConfig, VolumeConfig};
@@ -6,6 +6,9 @@ use crate::portal::connection::Connection;
 use crate::portal::interfaces::FilesInterface;
 use crate::portal::interfaces::{ContainerInterface, ExecutionInterface, GuestInterface};
 use boxlite_shared::{BoxliteResult, Transport};
+use std::future::Future;
+use std::time::Duration;
+use tonic::transport::Channel;
 
 /// High-level guest session.
 ///
@@ -23,6 +26,42 @@ impl GuestSession {
         }
     }
 
+    /// Create a session whose first connect fails after `connect_timeout`.
+    pub fn with_connect_timeout(transport: Transport, connect_timeout: Duration) -> Self {
+        Self {
+            connection: Connection::new(transport).with_connect_timeout(connect_timeout),
+        }
+    }
+
+    /// Run `call` on the guest channel, reconnecting after transport failures.
+    ///
+    /// Use for calls that are safe to repeat when the connection dropped
+    /// before a reply arrived.
+    pub async fn with_reconnect<T, F, Fut>(&self, call: F) -> BoxliteResult<T>
+    where
+        F: FnMut(Channel) -> Fut,
+        Fut: Future<Output = BoxliteResult<T>>,
+    {
+        self.connection.call(call).await
+    }
+
+    /// Run `call` on the guest channel once, reconnecting only to open it.
+    ///
+    /// Use for calls that must not run twice, such as starting a process:
+    /// a connection that drops mid-call fails the call.
+    pub async fn with_connection<T, F, Fut>(&self, call: F) -> BoxliteResult<T>
+    where
+        F: FnOnce(Channel) -> Fut,
+        Fut: Future<Output = BoxliteResult<T>>,
+    {
+        self.connection.call_once(call).await
+    }
+
+    /// Discard the current connection; the next call reconnects.
+    pub async fn reset(&self) {
+        self.connection.reset().await;
+    }
+
     /// Get execution interface.
     pub async fn execution(&self) -> BoxliteResult<ExecutionInterface> {
         let channel = self.connection.channel().await?;
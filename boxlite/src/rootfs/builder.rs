@@ -4,6 +4,7 @@ use crate::images::{ImageObject, extract_layer_tarball_streaming};
 use crate::rootfs::{CopyMode, CopyMountOptions, copy_based_mount};
 use boxlite_shared::errors::{BoxliteError, BoxliteResult};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 /// Unified builder for all rootfs preparation needs
 pub struct RootfsBuilder;
@@ -83,6 +84,8 @@ impl RootfsBuilder {
     /// Prepare rootfs using VFS-style copy-based mount with layer caching
     ///
     /// This is the preferred method as it caches extracted layers for reuse.
+    /// Layers are extracted concurrently and stacked in order as each one
+    /// becomes ready, so stacking overlaps with extraction of upper layers.
     async fn prepare_copy_based(
         &self,
         dest: &Path,
@@ -90,64 +93,59 @@ impl RootfsBuilder {
     ) -> BoxliteResult<PreparedRootfs> {
         tracing::info!("Attempting copy-based mount with layer caching");
 
-        // Get extracted layer directories (with caching)
-        let extracted_layers = image.layer_extracted().await?;
+        // Start extracting all layers (with caching)
+        let pending_layers = image.layer_extracted_pipelined();
 
-        if extracted_layers.is_empty() {
+        if pending_layers.is_empty() {
             return Err(BoxliteError::Storage(
                 "Cannot prepare rootfs with no layers".into(),
             ));
         }
 
+        let layer_count = pending_layers.len();
         tracing::info!(
             "Stacking {} cached layers directly to destination",
-            extracted_layers.len()
+            layer_count
         );
 
-        // Stack layers directly to destination
+        let start = Instant::now();
+        let mut extract_total = Duration::ZERO;
+
+        // Stack layers directly to destination, bottom to top
         // IMPORTANT: Whiteouts are processed INLINE during copy (not as separate phase)
         // When copying a layer, .wh.* files delete corresponding files from destination
-        for (idx, layer_dir) in extracted_layers.iter().enumerate() {
-            if idx == 0 {
-                // First layer: copy to dest
-                tracing::debug!(
-                    "Copying base layer {}/{}: {} -> {}",
-                    idx + 1,
-                    extracted_layers.len(),
-                    layer_dir.display(),
-                    dest.display()
-                );
-
-                let mount = copy_based_mount(
-                    layer_dir,
-                    dest,
-                    CopyMountOptions {
-                        copy_xattrs: true,
-                        copy_mode: CopyMode::Content,
-                        ignore_chown_errors: false,
-                    },
-                )?;
-
-                // Unmount (no-op)
-                mount.unmount()?;
-            } else {
-                // Subsequent layers: copy on top, processing whiteouts inline
-                tracing::debug!(
-                    "Overlaying layer {}/{}: {} (whiteouts processed inline)",
-                    idx + 1,
-                    extracted_layers.len(),
-                    layer_dir.display()
-                );
+        for (idx, pending) in pending_layers.into_iter().enumerate() {
+            let layer = pending.await.map_err(|e| {
+                BoxliteError::Internal(format!("Layer extraction task failed: {}", e))
+            })??;
+            extract_total += layer.elapsed;
+            tracing::debug!(
+                "Layer {}/{} ready: {} (extracted in {:?})",
+                idx + 1,
+                layer_count,
+                layer.digest,
+                layer.elapsed
+            );
 
-                // Copy this layer on top, whiteouts handled during copy
-                copy_directory_overlay(layer_dir, dest)?;
-            }
+            let layer_dir = layer.path;
+            let dest = dest.to_path_buf();
+            tokio::task::spawn_blocking(move || stack_layer(idx, layer_count, &layer_dir, &dest))
+                .await
+                .map_err(|e| {
+                    BoxliteError::Internal(format!("Layer stacking task failed: {}", e))
+                })??;
         }
 
         // Fix rootfs permissions for container compatibility
         // crate::util::fix_rootfs_permissions(dest)?;
 
-        tracing::info!("✅ Rootfs prepared at {}", dest.display());
+        tracing::info!(
+            "✅ Rootfs prepared at {} in {:?} ({} layers, {:?} cumulative extraction)",
+            dest.display(),
+            start.elapsed(),
+            layer_count,
+            extract_total
+        );
         Ok(PreparedRootfs {
             path: dest.to_path_buf(),
         })
@@ -203,6 +201,46 @@ impl Default for RootfsBuilder {
     }
 }
 
+/// Stack one extracted layer onto `dest`.
+///
+/// The base layer is copied; upper layers are overlaid with whiteouts applied.
+fn stack_layer(idx: usize, count: usize, layer_dir: &Path, dest: &Path) -> BoxliteResult<()> {
+    if idx == 0 {
+        // First layer: copy to dest
+        tracing::debug!(
+            "Copying base layer {}/{}: {} -> {}",
+            idx + 1,
+            count,
+            layer_dir.display(),
+            dest.display()
+        );
+
+        let mount = copy_based_mount(
+            layer_dir,
+            dest,
+            CopyMountOptions {
+                copy_xattrs: true,
+                copy_mode: CopyMode::Content,
+                ignore_chown_errors: false,
+            },
+        )?;
+
+        // Unmount (no-op)
+        mount.unmount()
+    } else {
+        // Subsequent layers: copy on top, processing whiteouts inline
+        tracing::debug!(
+            "Overlaying layer {}/{}: {} (whiteouts processed inline)",
+            idx + 1,
+            count,
+            layer_dir.display()
+        );
+
+        // Copy this layer on top, whiteouts handled during copy
+        copy_directory_overlay(layer_dir, dest)
+    }
+}
+
 /// Simple data holder for prepared rootfs path (no cleanup responsibility)
 pub struct PreparedRootfs {
     pub path: PathBuf,
@@ -335,7 +373,6 @@ fn execute_copy_with_metadata(src: &Path, dst: &Path) -> BoxliteResult<()> {
 /// - Circular symlinks in dst are handled specially to avoid ELOOP errors
 fn copy_directory_overlay(src: &Path, dst: &Path) -> BoxliteResult<()> {
     use std::collections::HashSet;
-    use std::time::Instant;
     use walkdir::WalkDir;
 
     let total_start = Instant::now();
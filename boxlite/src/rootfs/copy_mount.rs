@@ -8,7 +8,8 @@
 //! - Preserves all file types: regular, directory, symlink, fifo, socket, device
 //! - Preserves permissions, ownership, timestamps, xattrs
 //! - Hardlink detection and preservation
-//! - Platform-specific optimizations (Linux: copy_file_range, macOS: standard copy)
+//! - Zero-copy where possible (Linux: FICLONE reflink, then copy_file_range;
+//!   macOS: clonefile)
 //! - Proper error handling with cleanup on failure
 
 use boxlite_shared::errors::{BoxliteError, BoxliteResult};
@@ -113,8 +114,9 @@ impl CopyMount {
 /// * `CopyMount` - Handle to the mounted directory
 ///
 /// # Platform Notes
-/// * **Linux**: Uses optimized copy with copy_file_range when available
-/// * **macOS**: Uses standard copy operations
+/// * **Linux**: Reflinks files (FICLONE) when the filesystem supports it,
+///   otherwise copies in-kernel with copy_file_range
+/// * **macOS**: Clones files on APFS, otherwise copies
 ///
 /// # Example
 /// ```ignore
@@ -418,9 +420,21 @@ fn dir_copy(src_dir: &Path, dst_dir: &Path, options: CopyMountOptions) -> Boxlit
 }
 
 /// Copy a regular file's content from src to dst
+///
+/// Follows VFS: FICLONE ioctl -> copy_file_range -> legacy copy.
+/// - Linux: try a reflink (btrfs, XFS, bcachefs) so the copy shares extents;
+///   otherwise `fs::copy`, which uses copy_file_range (in-kernel, no
+///   userspace buffer) and falls back to read/write itself
+/// - macOS: `fs::copy` clones on APFS via fclonefileat
 fn copy_regular_file(src: &Path, dst: &Path, _metadata: &fs::Metadata) -> BoxliteResult<()> {
-    // VFS tries: FICLONE ioctl -> copy_file_range -> legacy copy
-    // We'll use Rust's standard copy which is efficient enough
+    #[cfg(target_os = "linux")]
+    match reflink_file(src, dst) {
+        Ok(()) => return Ok(()),
+        Err(e) => {
+            tracing::trace!("Reflink {} failed ({}), copying", src.display(), e);
+            // A partially created dst is truncated by fs::copy below
+        }
+    }
 
     fs::copy(src, dst).map_err(|e| {
         BoxliteError::Storage(format!(
@@ -434,6 +448,30 @@ fn copy_regular_file(src: &Path, dst: &Path, _metadata: &fs::Metadata) -> Boxlit
     Ok(())
 }
 
+/// Clone `src` into a new `dst` sharing the same extents (FICLONE).
+///
+/// Fails with EOPNOTSUPP/EXDEV/EINVAL when the filesystem can't reflink or
+/// src and dst are on different filesystems.
+#[cfg(target_os = "linux")]
+pub(crate) fn reflink_file(src: &Path, dst: &Path) -> std::io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    let src_file = fs::File::open(src)?;
+    let dst_file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(dst)?;
+
+    // SAFETY: both descriptors are valid for the duration of the call
+    let ret = unsafe { libc::ioctl(dst_file.as_raw_fd(), libc::FICLONE, src_file.as_raw_fd()) };
+    if ret == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
 /// Copy metadata (permissions, ownership, timestamps, xattrs) from src to dst
 fn copy_metadata(
     src: &Path,
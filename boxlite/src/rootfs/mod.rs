@@ -8,5 +8,7 @@ mod dns;
 pub(crate) mod operations;
 
 pub use builder::RootfsBuilder;
+#[cfg(target_os = "linux")]
+pub(crate) use copy_mount::reflink_file;
 pub use copy_mount::{CopyMode, CopyMountOptions, copy_based_mount};
 pub use dns::configure_container_dns;
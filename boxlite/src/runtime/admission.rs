@@ -0,0 +1,352 @@
+//! Runtime-level admission control.
+//!
+//! Limits how many boxes (and how much guest memory) a single runtime may
+//! hold reservations for at once. A reservation is taken when a box starts
+//! and released when it stops or is removed.
+//!
+//! Reservations also carry each box's estimated file descriptors, which are
+//! checked against the runtime process's [`FdBudget`] whatever the limits.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+use parking_lot::Mutex;
+use tokio::sync::Notify;
+
+use crate::runtime::fd_budget::FdBudget;
+use crate::runtime::options::{AdmissionPolicy, BoxliteOptions};
+use crate::runtime::types::BoxID;
+
+/// Host-wide limits enforced by [`AdmissionController`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct AdmissionLimits {
+    /// Maximum number of boxes holding a reservation.
+    pub max_running_boxes: Option<usize>,
+    /// Maximum sum of `memory_mib` across reserved boxes.
+    pub max_total_memory_mib: Option<u64>,
+    /// What to do when a request does not fit.
+    pub policy: AdmissionPolicy,
+}
+
+impl AdmissionLimits {
+    pub(crate) fn from_options(options: &BoxliteOptions) -> Self {
+        Self {
+            max_running_boxes: options.max_running_boxes,
+            max_total_memory_mib: options.max_total_memory_mib,
+            policy: options.admission_policy.clone(),
+        }
+    }
+
+    fn is_unlimited(&self) -> bool {
+        self.max_running_boxes.is_none() && self.max_total_memory_mib.is_none()
+    }
+}
+
+/// What a box holds while admitted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Reservation {
+    /// Guest memory, in MiB.
+    pub memory_mib: u64,
+    /// Estimated file descriptors open in the runtime process.
+    pub fds: u64,
+}
+
+/// Tracks per-box reservations against [`AdmissionLimits`].
+pub(crate) struct AdmissionController {
+    /// Replaced by `set_limits` on reload.
+    limits: Mutex<AdmissionLimits>,
+    /// Descriptor limit of the runtime process.
+    fd_budget: FdBudget,
+    /// Reservations keyed by box.
+    reservations: Mutex<HashMap<BoxID, Reservation>>,
+    /// Woken whenever a reservation is released.
+    released: Notify,
+}
+
+impl AdmissionController {
+    pub(crate) fn new(limits: AdmissionLimits) -> Self {
+        Self {
+            limits: Mutex::new(limits),
+            fd_budget: FdBudget::unlimited(),
+            reservations: Mutex::new(HashMap::new()),
+            released: Notify::new(),
+        }
+    }
+
+    /// Also admit boxes only while their descriptors fit in `fd_budget`.
+    pub(crate) fn with_fd_budget(mut self, fd_budget: FdBudget) -> Self {
+        self.fd_budget = fd_budget;
+        self
+    }
+
+    /// Replace the limits. Existing reservations are kept even if they no
+    /// longer fit; queued requests are re-checked against the new limits.
+    pub(crate) fn set_limits(&self, limits: AdmissionLimits) {
+        *self.limits.lock() = limits;
+        self.released.notify_waiters();
+    }
+
+    /// Reserve capacity for a box, honoring the configured policy.
+    ///
+    /// Idempotent: a box that already holds a reservation is admitted again
+    /// without consuming extra capacity.
+    pub(crate) async fn admit(
+        &self,
+        box_id: &BoxID,
+        reservation: Reservation,
+    ) -> BoxliteResult<()> {
+        let policy = self.limits.lock().policy.clone();
+        let deadline = match policy {
+            AdmissionPolicy::FailFast => return self.try_admit(box_id, reservation),
+            AdmissionPolicy::Queue { timeout_secs } => {
+                timeout_secs.map(|secs| tokio::time::Instant::now() + Duration::from_secs(secs))
+            }
+        };
+
+        loop {
+            // Register interest before checking so a release between the
+            // check and the await is not missed.
+            let notified = self.released.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            match self.try_admit(box_id, reservation) {
+                Ok(()) => return Ok(()),
+                Err(e) if !self.fits_when_empty(reservation) => return Err(e),
+                Err(e) => match deadline {
+                    Some(deadline) => {
+                        if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                            return Err(e);
+                        }
+                    }
+                    None => notified.await,
+                },
+            }
+        }
+    }
+
+    /// Reserve capacity without waiting.
+    pub(crate) fn try_admit(&self, box_id: &BoxID, reservation: Reservation) -> BoxliteResult<()> {
+        let limits = self.limits.lock().clone();
+        let mut reservations = self.reservations.lock();
+        if reservations.contains_key(box_id) {
+            reservations.insert(box_id.clone(), reservation);
+            return Ok(());
+        }
+
+        let reserved_fds: u64 = reservations.values().map(|r| r.fds).sum();
+        self.fd_budget.check(reserved_fds, reservation.fds)?;
+        if limits.is_unlimited() {
+            reservations.insert(box_id.clone(), reservation);
+            return Ok(());
+        }
+        let memory_mib = reservation.memory_mib;
+
+        if let Some(max) = limits.max_running_boxes
+            && reservations.len() >= max
+        {
+            return Err(BoxliteError::ResourceExhausted(format!(
+                "max_running_boxes limit reached ({} of {})",
+                reservations.len(),
+                max
+            )));
+        }
+
+        if let Some(max) = limits.max_total_memory_mib {
+            let used: u64 = reservations.values().map(|r| r.memory_mib).sum();
+            if used + memory_mib > max {
+                return Err(BoxliteError::ResourceExhausted(format!(
+                    "max_total_memory_mib limit reached (requested {} MiB, {} of {} MiB in use)",
+                    memory_mib, used, max
+                )));
+            }
+        }
+
+        reservations.insert(box_id.clone(), reservation);
+        Ok(())
+    }
+
+    /// Record a reservation without checking limits.
+    ///
+    /// Used during recovery for boxes that are already running.
+    pub(crate) fn force_admit(&self, box_id: &BoxID, reservation: Reservation) {
+        self.reservations.lock().insert(box_id.clone(), reservation);
+    }
+
+    /// Release a box's reservation (no-op if it holds none).
+    pub(crate) fn release(&self, box_id: &BoxID) {
+        if self.reservations.lock().remove(box_id).is_some() {
+            self.released.notify_waiters();
+        }
+    }
+
+    /// Number of boxes currently holding a reservation.
+    #[cfg(test)]
+    pub(crate) fn reserved_boxes(&self) -> usize {
+        self.reservations.lock().len()
+    }
+
+    /// Whether a request could ever be admitted, even with no other reservations.
+    fn fits_when_empty(&self, reservation: Reservation) -> bool {
+        let memory_mib = reservation.memory_mib;
+        let limits = self.limits.lock();
+        let boxes_ok = limits.max_running_boxes.is_none_or(|max| max > 0);
+        let memory_ok = limits
+            .max_total_memory_mib
+            .is_none_or(|max| memory_mib <= max);
+        boxes_ok && memory_ok && self.fd_budget.fits_alone(reservation.fds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mem(memory_mib: u64) -> Reservation {
+        Reservation { memory_mib, fds: 0 }
+    }
+
+    fn limits(
+        boxes: Option<usize>,
+        memory: Option<u64>,
+        policy: AdmissionPolicy,
+    ) -> AdmissionLimits {
+        AdmissionLimits {
+            max_running_boxes: boxes,
+            max_total_memory_mib: memory,
+            policy,
+        }
+    }
+
+    #[test]
+    fn test_unlimited_admits_everything() {
+        let ctl = AdmissionController::new(AdmissionLimits::default());
+        for _ in 0..10 {
+            ctl.try_admit(&BoxID::new(), mem(4096)).unwrap();
+        }
+        assert_eq!(ctl.reserved_boxes(), 10);
+    }
+
+    #[test]
+    fn test_box_limit_rejects_with_resource_exhausted() {
+        let ctl = AdmissionController::new(limits(Some(1), None, AdmissionPolicy::FailFast));
+        ctl.try_admit(&BoxID::new(), mem(512)).unwrap();
+        let err = ctl.try_admit(&BoxID::new(), mem(512)).unwrap_err();
+        assert!(matches!(err, BoxliteError::ResourceExhausted(_)));
+    }
+
+    #[test]
+    fn test_memory_limit() {
+        let ctl = AdmissionController::new(limits(None, Some(1024), AdmissionPolicy::FailFast));
+        ctl.try_admit(&BoxID::new(), mem(512)).unwrap();
+        ctl.try_admit(&BoxID::new(), mem(512)).unwrap();
+        assert!(ctl.try_admit(&BoxID::new(), mem(1)).is_err());
+    }
+
+    #[test]
+    fn test_readmit_same_box_is_idempotent() {
+        let ctl = AdmissionController::new(limits(Some(1), None, AdmissionPolicy::FailFast));
+        let id = BoxID::new();
+        ctl.try_admit(&id, mem(512)).unwrap();
+        ctl.try_admit(&id, mem(512)).unwrap();
+        assert_eq!(ctl.reserved_boxes(), 1);
+    }
+
+    #[test]
+    fn test_release_frees_capacity() {
+        let ctl = AdmissionController::new(limits(Some(1), None, AdmissionPolicy::FailFast));
+        let id = BoxID::new();
+        ctl.try_admit(&id, mem(512)).unwrap();
+        ctl.release(&id);
+        ctl.try_admit(&BoxID::new(), mem(512)).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_queue_waits_for_release() {
+        let ctl = std::sync::Arc::new(AdmissionController::new(limits(
+            Some(1),
+            None,
+            AdmissionPolicy::Queue {
+                timeout_secs: Some(5),
+            },
+        )));
+        let first = BoxID::new();
+        ctl.admit(&first, mem(512)).await.unwrap();
+
+        let waiter = {
+            let ctl = ctl.clone();
+            tokio::spawn(async move { ctl.admit(&BoxID::new(), mem(512)).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+
+        ctl.release(&first);
+        waiter.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_raised_limit_admits_queued_request() {
+        let queue = AdmissionPolicy::Queue {
+            timeout_secs: Some(5),
+        };
+        let ctl = std::sync::Arc::new(AdmissionController::new(limits(
+            Some(1),
+            None,
+            queue.clone(),
+        )));
+        ctl.admit(&BoxID::new(), mem(512)).await.unwrap();
+
+        let waiter = {
+            let ctl = ctl.clone();
+            tokio::spawn(async move { ctl.admit(&BoxID::new(), mem(512)).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+
+        ctl.set_limits(limits(Some(2), None, queue));
+        waiter.await.unwrap().unwrap();
+        assert_eq!(ctl.reserved_boxes(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_queue_times_out() {
+        let ctl = AdmissionController::new(limits(
+            Some(1),
+            None,
+            AdmissionPolicy::Queue {
+                timeout_secs: Some(0),
+            },
+        ));
+        ctl.admit(&BoxID::new(), mem(512)).await.unwrap();
+        let err = ctl.admit(&BoxID::new(), mem(512)).await.unwrap_err();
+        assert!(matches!(err, BoxliteError::ResourceExhausted(_)));
+    }
+
+    #[tokio::test]
+    async fn test_queue_fails_fast_when_request_can_never_fit() {
+        let ctl = AdmissionController::new(limits(
+            None,
+            Some(256),
+            AdmissionPolicy::Queue { timeout_secs: None },
+        ));
+        let err = ctl.admit(&BoxID::new(), mem(512)).await.unwrap_err();
+        assert!(matches!(err, BoxliteError::ResourceExhausted(_)));
+    }
+
+    #[test]
+    fn test_fd_budget_rejects_even_without_limits() {
+        let ctl = AdmissionController::new(AdmissionLimits::default())
+            .with_fd_budget(FdBudget::with_limit(10_000));
+        let reservation = Reservation {
+            memory_mib: 512,
+            fds: 4000,
+        };
+        ctl.try_admit(&BoxID::new(), reservation).unwrap();
+        ctl.try_admit(&BoxID::new(), reservation).unwrap();
+        let err = ctl.try_admit(&BoxID::new(), reservation).unwrap_err();
+        assert!(matches!(err, BoxliteError::ResourceExhausted(_)));
+        assert!(err.to_string().contains("ulimit -n"), "{}", err);
+        assert_eq!(ctl.reserved_boxes(), 2);
+    }
+}
@@ -0,0 +1,594 @@
+//! Portable box archives (`boxlite export` / `boxlite import`).
+//!
+//! An archive is a zstd-compressed tarball containing:
+//! - `manifest.json` - box identity and creation options
+//! - `layers/<disk>.<n>` - each rootfs disk plus its full backing chain,
+//!   from the box's own overlay (`n = 0`) down to the base image
+//!
+//! Backing file paths inside qcow2 headers are host-specific, so import
+//! places every layer on the new host and rewrites each header to point at
+//! the layer below it.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::disk::Qcow2Helper;
+use crate::litebox::config::BoxConfig;
+use crate::runtime::constants::filenames;
+use crate::runtime::options::{BoxOptions, RootfsSpec};
+
+/// Archive format version written by this build.
+const ARCHIVE_VERSION: u32 = 1;
+
+/// Name of the manifest entry (always first in the archive).
+const MANIFEST_ENTRY: &str = "manifest.json";
+
+/// Directory holding disk layers inside the archive.
+const LAYERS_DIR: &str = "layers";
+
+/// zstd compression level (favour speed; disk images are mostly sparse).
+const ZSTD_LEVEL: i32 = 3;
+
+/// Disks carried by an archive, in the order they are written.
+pub(crate) const ARCHIVE_DISKS: [&str; 2] =
+    [filenames::CONTAINER_DISK, filenames::GUEST_ROOTFS_DISK];
+
+/// Disks carried only when the box has them (read-only image rootfs).
+pub(crate) const OPTIONAL_ARCHIVE_DISKS: [&str; 1] = [filenames::CONTAINER_IMAGE_DISK];
+
+/// Clear the options that refer to resources of the exporting host.
+///
+/// An imported box boots from the disks in the archive, so a rootfs path is
+/// never read again; it is emptied rather than left naming a directory of
+/// the exporting host. MAC and IP addresses must be unique on a network.
+///
+/// Returns the names of the options that were set, for a warning.
+pub(crate) fn strip_host_bindings(options: &mut BoxOptions) -> Vec<&'static str> {
+    let mut dropped = Vec::new();
+    if !options.volumes.is_empty() {
+        options.volumes.clear();
+        dropped.push("volumes");
+    }
+    if !options.ports.is_empty() {
+        options.ports.clear();
+        dropped.push("ports");
+    }
+    if options.kernel.take().is_some() {
+        dropped.push("kernel");
+    }
+    if let RootfsSpec::RootfsPath(path) = &mut options.rootfs
+        && !path.is_empty()
+    {
+        path.clear();
+        dropped.push("rootfs");
+    }
+    if options.mac_address.take().is_some() {
+        dropped.push("mac_address");
+    }
+    if options.ip_address.take().is_some() {
+        dropped.push("ip_address");
+    }
+    dropped
+}
+
+/// Metadata stored at the head of a box archive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ArchiveManifest {
+    /// Archive format version.
+    pub version: u32,
+    /// ID of the box on the exporting host (informational).
+    pub source_id: String,
+    /// Box name at export time.
+    pub name: Option<String>,
+    /// Creation time of the original box.
+    pub created_at: DateTime<Utc>,
+    /// Options the box was created with.
+    pub options: BoxOptions,
+    /// Disks and their layer chains.
+    pub disks: Vec<ArchiveDisk>,
+}
+
+/// One rootfs disk and its backing chain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ArchiveDisk {
+    /// Disk filename inside the box home (e.g. `disk.qcow2`).
+    pub name: String,
+    /// Number of layers, top overlay first.
+    pub layers: usize,
+}
+
+fn layer_entry(disk: &str, index: usize) -> String {
+    format!("{}/{}.{}", LAYERS_DIR, disk, index)
+}
+
+/// Write a stopped box's config and disks to `output`.
+pub(crate) fn export_box(config: &BoxConfig, output: &Path) -> BoxliteResult<()> {
+    let mut disks = Vec::new();
+    let mut chains = Vec::new();
+    for disk_name in ARCHIVE_DISKS {
+        let disk_path = config.box_home.join(disk_name);
+        if !disk_path.exists() {
+            return Err(BoxliteError::InvalidState(format!(
+                "box {} has no {} (it must have been started at least once)",
+                config.id, disk_name
+            )));
+        }
+        let chain = Qcow2Helper::backing_chain(&disk_path)?;
+        disks.push(ArchiveDisk {
+            name: disk_name.to_string(),
+            layers: chain.len(),
+        });
+        chains.push((disk_name, chain));
+    }
+    for disk_name in OPTIONAL_ARCHIVE_DISKS {
+        let disk_path = config.box_home.join(disk_name);
+        if !disk_path.exists() {
+            continue;
+        }
+        let chain = Qcow2Helper::backing_chain(&disk_path)?;
+        disks.push(ArchiveDisk {
+            name: disk_name.to_string(),
+            layers: chain.len(),
+        });
+        chains.push((disk_name, chain));
+    }
+
+    let manifest = ArchiveManifest {
+        version: ARCHIVE_VERSION,
+        source_id: config.id.to_string(),
+        name: config.name.clone(),
+        created_at: config.created_at,
+        options: config.options.clone(),
+        disks,
+    };
+    write_archive(&manifest, &chains, output)?;
+
+    tracing::info!(
+        box_id = %config.id,
+        output = %output.display(),
+        "Exported box"
+    );
+    Ok(())
+}
+
+/// Write `manifest` followed by every layer in `chains` to `output`.
+fn write_archive(
+    manifest: &ArchiveManifest,
+    chains: &[(&str, Vec<PathBuf>)],
+    output: &Path,
+) -> BoxliteResult<()> {
+    let manifest_json = serde_json::to_vec_pretty(manifest)
+        .map_err(|e| BoxliteError::Internal(format!("Failed to encode manifest: {}", e)))?;
+
+    let file = File::create(output).map_err(|e| {
+        BoxliteError::Storage(format!("Failed to create {}: {}", output.display(), e))
+    })?;
+    let write_err = |e: std::io::Error| {
+        BoxliteError::Storage(format!("Failed to write {}: {}", output.display(), e))
+    };
+
+    let encoder = zstd::Encoder::new(file, ZSTD_LEVEL).map_err(write_err)?;
+    let mut builder = tar::Builder::new(encoder);
+
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest_json.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, MANIFEST_ENTRY, manifest_json.as_slice())
+        .map_err(write_err)?;
+
+    for (disk_name, chain) in chains {
+        for (index, layer) in chain.iter().enumerate() {
+            let mut f = File::open(layer).map_err(|e| {
+                BoxliteError::Storage(format!("Failed to open {}: {}", layer.display(), e))
+            })?;
+            builder
+                .append_file(layer_entry(disk_name, index), &mut f)
+                .map_err(write_err)?;
+        }
+    }
+
+    let encoder = builder.into_inner().map_err(write_err)?;
+    encoder.finish().map_err(write_err)?;
+
+    Ok(())
+}
+
+fn open_archive(
+    input: &Path,
+) -> BoxliteResult<tar::Archive<zstd::Decoder<'static, std::io::BufReader<File>>>> {
+    let file = File::open(input)
+        .map_err(|e| BoxliteError::Storage(format!("Failed to open {}: {}", input.display(), e)))?;
+    let decoder = zstd::Decoder::new(file)
+        .map_err(|e| BoxliteError::Storage(format!("Failed to read {}: {}", input.display(), e)))?;
+    Ok(tar::Archive::new(decoder))
+}
+
+/// Read only the manifest from the head of an archive.
+pub(crate) fn read_manifest(input: &Path) -> BoxliteResult<ArchiveManifest> {
+    let mut archive = open_archive(input)?;
+    let invalid = |msg: String| {
+        BoxliteError::InvalidArgument(format!("{} is not a box archive: {}", input.display(), msg))
+    };
+
+    let mut entries = archive.entries().map_err(|e| invalid(e.to_string()))?;
+    let mut entry = entries
+        .next()
+        .ok_or_else(|| invalid("archive is empty".into()))?
+        .map_err(|e| invalid(e.to_string()))?;
+
+    let path = entry.path().map_err(|e| invalid(e.to_string()))?;
+    if path != Path::new(MANIFEST_ENTRY) {
+        return Err(invalid(format!("expected {}", MANIFEST_ENTRY)));
+    }
+
+    let mut json = Vec::new();
+    entry
+        .read_to_end(&mut json)
+        .map_err(|e| invalid(e.to_string()))?;
+    let manifest: ArchiveManifest =
+        serde_json::from_slice(&json).map_err(|e| invalid(e.to_string()))?;
+
+    if manifest.version != ARCHIVE_VERSION {
+        return Err(BoxliteError::Unsupported(format!(
+            "box archive version {} (this build supports {})",
+            manifest.version, ARCHIVE_VERSION
+        )));
+    }
+    for disk in &manifest.disks {
+        let known = ARCHIVE_DISKS.contains(&disk.name.as_str())
+            || OPTIONAL_ARCHIVE_DISKS.contains(&disk.name.as_str());
+        if !known || disk.layers == 0 {
+            return Err(invalid(format!("unexpected disk entry {}", disk.name)));
+        }
+    }
+    for name in ARCHIVE_DISKS {
+        if !manifest.disks.iter().any(|d| d.name == name) {
+            return Err(invalid(format!("missing disk {}", name)));
+        }
+    }
+
+    Ok(manifest)
+}
+
+/// Unpack disk layers and relink their backing chains.
+///
+/// `place(disk, index)` chooses where each layer lives on this host. Layer 0
+/// of each disk is the box's own overlay; lower layers are its backing files.
+/// Every file created is pushed to `written` so the caller can clean up
+/// on failure.
+///
+/// A qcow2 header names other host files, so only the links this function
+/// writes are trusted: the base layer must not have a backing file, and no
+/// layer may keep its data in an external file.
+pub(crate) fn unpack_layers(
+    input: &Path,
+    manifest: &ArchiveManifest,
+    place: impl Fn(&str, usize) -> PathBuf,
+    written: &mut Vec<PathBuf>,
+) -> BoxliteResult<()> {
+    let mut archive = open_archive(input)?;
+    let read_err = |e: std::io::Error| {
+        BoxliteError::Storage(format!("Failed to read {}: {}", input.display(), e))
+    };
+
+    let mut expected = Vec::new();
+    for disk in &manifest.disks {
+        for index in 0..disk.layers {
+            expected.push((layer_entry(&disk.name, index), place(&disk.name, index)));
+        }
+    }
+
+    for entry in archive.entries().map_err(read_err)? {
+        let mut entry = entry.map_err(read_err)?;
+        let entry_path = entry
+            .path()
+            .map_err(read_err)?
+            .to_string_lossy()
+            .into_owned();
+        if entry_path == MANIFEST_ENTRY {
+            continue;
+        }
+        let Some((_, dest)) = expected.iter().find(|(name, _)| *name == entry_path) else {
+            tracing::warn!(entry = %entry_path, "Skipping unknown archive entry");
+            continue;
+        };
+
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                BoxliteError::Storage(format!("Failed to create {}: {}", parent.display(), e))
+            })?;
+        }
+        written.push(dest.clone());
+        let mut out = File::create(dest).map_err(|e| {
+            BoxliteError::Storage(format!("Failed to create {}: {}", dest.display(), e))
+        })?;
+        std::io::copy(&mut entry, &mut out).map_err(read_err)?;
+    }
+
+    for (name, dest) in &expected {
+        if !dest.exists() {
+            return Err(BoxliteError::InvalidArgument(format!(
+                "{} is missing {}",
+                input.display(),
+                name
+            )));
+        }
+    }
+
+    for disk in &manifest.disks {
+        check_layers(input, disk, &place)?;
+    }
+    for disk in &manifest.disks {
+        for index in 0..disk.layers - 1 {
+            Qcow2Helper::set_backing_file(
+                &place(&disk.name, index),
+                &place(&disk.name, index + 1),
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Reject layers whose headers reach outside the unpacked chain.
+fn check_layers(
+    input: &Path,
+    disk: &ArchiveDisk,
+    place: &impl Fn(&str, usize) -> PathBuf,
+) -> BoxliteResult<()> {
+    let invalid = |index: usize, what: &str| {
+        BoxliteError::InvalidArgument(format!(
+            "{}: layer {} of {} {}",
+            input.display(),
+            index,
+            disk.name,
+            what
+        ))
+    };
+    let base = disk.layers - 1;
+    for index in 0..disk.layers {
+        let layer = place(&disk.name, index);
+        if !Qcow2Helper::is_qcow2(&layer)? {
+            // Raw images hold no references; only the base may be one
+            if index < base {
+                return Err(invalid(index, "is not a qcow2 image"));
+            }
+            continue;
+        }
+        if Qcow2Helper::has_external_data_file(&layer)? {
+            return Err(invalid(index, "uses an external data file"));
+        }
+        if index == base && Qcow2Helper::backing_file(&layer)?.is_some() {
+            return Err(invalid(index, "has a backing file outside the archive"));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disk::BackingFormat;
+    use tempfile::TempDir;
+
+    const SIZE: u64 = 64 * 1024 * 1024;
+
+    /// Build a raw base disk with a two-level qcow2 chain on top.
+    fn make_chain(dir: &Path, disk: &str) -> Vec<PathBuf> {
+        let base = dir.join(format!("{}.base.raw", disk));
+        std::fs::write(&base, vec![0xAB; 4096]).unwrap();
+
+        let helper = Qcow2Helper::new();
+        let mid = dir.join(format!("{}.mid.qcow2", disk));
+        helper
+            .create_cow_child_disk(&base, BackingFormat::Raw, &mid, SIZE)
+            .unwrap()
+            .leak();
+        let top = dir.join(disk);
+        helper
+            .create_cow_child_disk(&mid, BackingFormat::Qcow2, &top, SIZE)
+            .unwrap()
+            .leak();
+
+        vec![
+            top,
+            mid.canonicalize().unwrap(),
+            base.canonicalize().unwrap(),
+        ]
+    }
+
+    fn manifest(layers: usize) -> ArchiveManifest {
+        ArchiveManifest {
+            version: ARCHIVE_VERSION,
+            source_id: "01HSOURCE".into(),
+            name: Some("prepared".into()),
+            created_at: Utc::now(),
+            options: BoxOptions::default(),
+            disks: ARCHIVE_DISKS
+                .iter()
+                .map(|name| ArchiveDisk {
+                    name: name.to_string(),
+                    layers,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_backing_chain_follows_to_raw_base() {
+        let dir = TempDir::new().unwrap();
+        let chain = make_chain(dir.path(), "disk.qcow2");
+        assert_eq!(Qcow2Helper::backing_chain(&chain[0]).unwrap(), chain);
+    }
+
+    #[test]
+    fn test_roundtrip_relinks_backing_files() {
+        let src = TempDir::new().unwrap();
+        let chains: Vec<(&str, Vec<PathBuf>)> = ARCHIVE_DISKS
+            .iter()
+            .map(|name| (*name, make_chain(src.path(), name)))
+            .collect();
+
+        let archive_path = src.path().join("box.tar.zst");
+        write_archive(&manifest(3), &chains, &archive_path).unwrap();
+
+        let read = read_manifest(&archive_path).unwrap();
+        assert_eq!(read.name.as_deref(), Some("prepared"));
+        assert_eq!(read.disks.len(), ARCHIVE_DISKS.len());
+
+        let dst = TempDir::new().unwrap();
+        let root = dst.path().to_path_buf();
+        let place = |disk: &str, index: usize| root.join(format!("{}.{}", disk, index));
+        let mut written = Vec::new();
+        unpack_layers(&archive_path, &read, place, &mut written).unwrap();
+        assert_eq!(written.len(), 6);
+
+        for name in ARCHIVE_DISKS {
+            let chain = Qcow2Helper::backing_chain(&place(name, 0)).unwrap();
+            assert_eq!(chain, vec![place(name, 0), place(name, 1), place(name, 2)]);
+            assert_eq!(std::fs::read(place(name, 2)).unwrap(), vec![0xAB; 4096]);
+        }
+    }
+
+    #[test]
+    fn test_unpack_rejects_base_with_backing_file() {
+        let src = TempDir::new().unwrap();
+        // Export only the top two layers, so the base in the archive still
+        // points at a file of this host
+        let chains: Vec<(&str, Vec<PathBuf>)> = ARCHIVE_DISKS
+            .iter()
+            .map(|name| (*name, make_chain(src.path(), name)[..2].to_vec()))
+            .collect();
+        let archive_path = src.path().join("box.tar.zst");
+        write_archive(&manifest(2), &chains, &archive_path).unwrap();
+
+        let dst = TempDir::new().unwrap();
+        let root = dst.path().to_path_buf();
+        let place = |disk: &str, index: usize| root.join(format!("{}.{}", disk, index));
+        let read = read_manifest(&archive_path).unwrap();
+        let err = unpack_layers(&archive_path, &read, place, &mut Vec::new()).unwrap_err();
+        assert!(
+            err.to_string().contains("backing file outside the archive"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_unpack_rejects_external_data_file() {
+        use std::io::{Seek, SeekFrom, Write};
+
+        let src = TempDir::new().unwrap();
+        let chains: Vec<(&str, Vec<PathBuf>)> = ARCHIVE_DISKS
+            .iter()
+            .map(|name| (*name, make_chain(src.path(), name)))
+            .collect();
+        // Set the external data file feature bit of one overlay
+        let mut top = std::fs::OpenOptions::new()
+            .write(true)
+            .open(&chains[0].1[0])
+            .unwrap();
+        top.seek(SeekFrom::Start(72)).unwrap();
+        top.write_all(&(1u64 << 2).to_be_bytes()).unwrap();
+        drop(top);
+        let archive_path = src.path().join("box.tar.zst");
+        write_archive(&manifest(3), &chains, &archive_path).unwrap();
+
+        let dst = TempDir::new().unwrap();
+        let root = dst.path().to_path_buf();
+        let place = |disk: &str, index: usize| root.join(format!("{}.{}", disk, index));
+        let read = read_manifest(&archive_path).unwrap();
+        let err = unpack_layers(&archive_path, &read, place, &mut Vec::new()).unwrap_err();
+        assert!(err.to_string().contains("external data file"), "{}", err);
+    }
+
+    #[test]
+    fn test_strip_host_bindings() {
+        use crate::runtime::options::{KernelSpec, PortSpec, VolumeSpec};
+
+        let mut options = BoxOptions {
+            volumes: vec![VolumeSpec {
+                host_path: "/etc".into(),
+                guest_path: "/mnt/etc".into(),
+                ..Default::default()
+            }],
+            ports: vec![PortSpec {
+                host_port: Some(8080),
+                guest_port: 80,
+                ..Default::default()
+            }],
+            kernel: Some(KernelSpec {
+                path: "/boot/vmlinuz".into(),
+                initrd: None,
+                cmdline: None,
+                format: Default::default(),
+            }),
+            env: vec![("KEEP".into(), "1".into())],
+            ..Default::default()
+        };
+        options.rootfs = RootfsSpec::RootfsPath("/srv/rootfs".into());
+        options.mac_address = Some("52:54:00:12:34:56".into());
+        options.ip_address = Some("192.168.127.10".into());
+        assert_eq!(
+            strip_host_bindings(&mut options),
+            vec![
+                "volumes",
+                "ports",
+                "kernel",
+                "rootfs",
+                "mac_address",
+                "ip_address"
+            ]
+        );
+        assert!(matches!(options.rootfs, RootfsSpec::RootfsPath(ref p) if p.is_empty()));
+        assert!(options.mac_address.is_none() && options.ip_address.is_none());
+        assert!(options.volumes.is_empty());
+        assert!(options.ports.is_empty());
+        assert!(options.kernel.is_none());
+        assert_eq!(options.env.len(), 1);
+
+        assert!(strip_host_bindings(&mut options).is_empty());
+    }
+
+    #[test]
+    fn test_read_manifest_rejects_non_archive() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("not-an-archive");
+        std::fs::write(&path, b"hello").unwrap();
+        assert!(read_manifest(&path).is_err());
+    }
+
+    #[test]
+    fn test_read_manifest_rejects_missing_disk() {
+        let dir = TempDir::new().unwrap();
+        let mut m = manifest(1);
+        m.disks.pop();
+        let path = dir.path().join("box.tar.zst");
+        write_archive(&m, &[], &path).unwrap();
+
+        let err = read_manifest(&path).unwrap_err();
+        assert!(err.to_string().contains("missing disk"));
+    }
+
+    #[test]
+    fn test_read_manifest_accepts_optional_disk() {
+        let dir = TempDir::new().unwrap();
+        let mut m = manifest(1);
+        m.disks.push(ArchiveDisk {
+            name: OPTIONAL_ARCHIVE_DISKS[0].to_string(),
+            layers: 1,
+        });
+        let path = dir.path().join("box.tar.zst");
+        write_archive(&m, &[], &path).unwrap();
+
+        assert_eq!(read_manifest(&path).unwrap().disks.len(), 3);
+    }
+}
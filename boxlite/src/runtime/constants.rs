@@ -17,6 +17,9 @@ pub mod guest_paths {
 
 pub mod envs {
     pub const BOXLITE_HOME: &str = "BOXLITE_HOME";
+    /// Engine override for test builds (`mock` selects the in-process mock engine).
+    #[cfg(feature = "testing")]
+    pub const BOXLITE_ENGINE: &str = "BOXLITE_ENGINE";
 }
 
 /// Container images used by the runtime
@@ -26,6 +29,9 @@ pub mod images {
 
     /// Base image for VM init rootfs (must include mkfs.ext4 for disk formatting)
     pub const INIT_ROOTFS: &str = "debian:bookworm-slim";
+
+    /// Images pulled and prepared at once by `BoxliteRuntime::prefetch()`
+    pub const PREFETCH_CONCURRENCY: usize = 2;
 }
 
 /// Filesystem and mount options
@@ -48,24 +54,43 @@ pub mod vm_defaults {
 
     /// Default disk size in GB for the container rootfs (sparse, grows as needed)
     pub const DEFAULT_DISK_SIZE_GB: u64 = 10;
+
+    /// Snapshots a disk may be stacked on before a clone flattens it.
+    pub const MAX_SNAPSHOT_DEPTH: usize = 8;
 }
 
 /// File naming patterns
 pub mod filenames {
-    use crate::runtime::layout::dirs;
+    use crate::runtime::layout::box_sockets_dir;
     use std::path::{Path, PathBuf};
 
     /// Lock file name
     pub const LOCK_FILE: &str = ".lock";
 
-    pub fn box_home(home_dir: &Path, box_id: &str) -> PathBuf {
-        home_dir.join(dirs::BOXES_DIR).join(box_id)
+    /// Per-box container rootfs COW disk
+    pub const CONTAINER_DISK: &str = "disk.qcow2";
+
+    /// Per-box read-only rootfs image overlay (erofs/squashfs rootfs format only)
+    pub const CONTAINER_IMAGE_DISK: &str = "image.qcow2";
+
+    /// Per-box guest rootfs COW disk
+    pub const GUEST_ROOTFS_DISK: &str = "guest-rootfs.qcow2";
+
+    /// Per-box marker of a network lost with the shared network daemon
+    pub const NETWORK_LOST: &str = "network-lost";
+
+    /// Per-box rootfs COW disk of the sidecar `name`
+    pub fn sidecar_disk(name: &str) -> String {
+        format!("sidecar-{}.qcow2", name)
+    }
+
+    /// Get full path for Unix socket of a box under `boxes_dir`
+    pub fn unix_socket_path(boxes_dir: &Path, box_id: &str) -> PathBuf {
+        box_sockets_dir(&boxes_dir.join(box_id)).join("box.sock")
     }
 
-    /// Get full path for Unix socket
-    pub fn unix_socket_path(home_dir: &Path, box_id: &str) -> PathBuf {
-        box_home(home_dir, box_id)
-            .join(dirs::SOCKETS_DIR)
-            .join("box.sock")
+    /// Get full path for the ready notification socket of a box under `boxes_dir`
+    pub fn ready_socket_path(boxes_dir: &Path, box_id: &str) -> PathBuf {
+        box_sockets_dir(&boxes_dir.join(box_id)).join("ready.sock")
     }
 }
@@ -1,13 +1,22 @@
 //! High-level sandbox runtime structures.
 
-use std::sync::OnceLock;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
 
+use crate::build::{BuildOptions, BuildOutput};
+use crate::db::{BoxStore, Database, ImageIndexStore};
+use crate::images::{CacheAccess, RegistryCache, RegistryCacheOptions};
 use crate::litebox::LiteBox;
 use crate::metrics::RuntimeMetrics;
-use crate::runtime::options::{BoxOptions, BoxliteOptions};
+use crate::runtime::layout::{FilesystemLayout, FsLayoutConfig};
+use crate::runtime::options::{BoxOptions, BoxliteOptions, RetentionPolicy};
+use crate::runtime::reload::OptionsReload;
 use crate::runtime::rt_impl::{RuntimeImpl, SharedRuntimeImpl};
 use crate::runtime::signal_handler::install_signal_handler;
-use crate::runtime::types::BoxInfo;
+use crate::runtime::types::{
+    BoxInfo, BoxPlan, DiskUsage, ImportOptions, PrefetchResult, PruneReport, ShutdownEvent,
+    ShutdownReport, SystemPruneOptions, SystemPruneReport,
+};
 use boxlite_shared::errors::{BoxliteError, BoxliteResult};
 // ============================================================================
 // GLOBAL DEFAULT RUNTIME
@@ -58,6 +67,29 @@ impl BoxliteRuntime {
         })
     }
 
+    /// Create a runtime whose boxes run on the in-process mock engine.
+    ///
+    /// No VM is started and no image is pulled: commands are answered from
+    /// `script`. Intended for unit tests of code built on BoxLite.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use boxlite::{BoxliteRuntime, MockExec, MockScript};
+    ///
+    /// let script = MockScript::new().on(&["uname"], MockExec::new().stdout("Linux\n"));
+    /// let runtime = BoxliteRuntime::with_mock_engine(options, script.clone())?;
+    /// ```
+    #[cfg(any(test, feature = "testing"))]
+    pub fn with_mock_engine(
+        options: BoxliteOptions,
+        script: crate::vmm::mock::MockScript,
+    ) -> BoxliteResult<Self> {
+        Ok(Self {
+            rt_impl: RuntimeImpl::with_mock_engine(options, script)?,
+        })
+    }
+
     /// Create a new runtime with default options.
     ///
     /// This is equivalent to `BoxliteRuntime::new(BoxliteOptions::default())`
@@ -116,7 +148,7 @@ impl BoxliteRuntime {
         // When signal is received, the shutdown callback stops all boxes gracefully.
         let rt_impl = rt.rt_impl.clone();
         install_signal_handler(move || async move {
-            let _ = rt_impl.shutdown(None).await;
+            let _ = rt_impl.shutdown(None, &|_| {}).await;
         });
 
         rt
@@ -179,6 +211,47 @@ impl BoxliteRuntime {
             ))
     }
 
+    // ========================================================================
+    // READ-ONLY LOOKUPS (no runtime instance)
+    // ========================================================================
+
+    /// List boxes under `home_dir` without opening a runtime.
+    ///
+    /// Reads the database read-only and skips the runtime lock, recovery and
+    /// virtualization checks, so it works while another process owns
+    /// `home_dir`. Statuses are as last recorded and may be stale; use this
+    /// for best-effort lookups such as shell completion.
+    pub fn peek_boxes(home_dir: &Path) -> BoxliteResult<Vec<BoxInfo>> {
+        let Some(db) = open_db_read_only(home_dir)? else {
+            return Ok(Vec::new());
+        };
+        Ok(BoxStore::new(db)
+            .list_all()?
+            .iter()
+            .map(|(config, state)| BoxInfo::new(config, state))
+            .collect())
+    }
+
+    /// References of images cached under `home_dir`, local tags first,
+    /// without opening a runtime. See [`peek_boxes`](Self::peek_boxes).
+    pub fn peek_images(home_dir: &Path) -> BoxliteResult<Vec<String>> {
+        let Some(db) = open_db_read_only(home_dir)? else {
+            return Ok(Vec::new());
+        };
+        let index = ImageIndexStore::new(db);
+        let mut references: Vec<String> = index
+            .list_tags()?
+            .into_iter()
+            .map(|(tag, _, _)| tag)
+            .collect();
+        for (reference, cached) in index.list_all()? {
+            if cached.complete && !references.contains(&reference) {
+                references.push(reference);
+            }
+        }
+        Ok(references)
+    }
+
     // ========================================================================
     // BOX LIFECYCLE OPERATIONS (delegate to RuntimeInnerImpl)
     // ========================================================================
@@ -198,6 +271,16 @@ impl BoxliteRuntime {
         self.rt_impl.create(options, name).await
     }
 
+    /// Plan a box without creating it (dry run).
+    ///
+    /// Validates `options`, resolves the image from the local cache and
+    /// returns the ports, mounts, paths and initialization stages that
+    /// `create` followed by `start` would use. Nothing is persisted, pulled
+    /// or spawned, and the result contains no generated IDs.
+    pub async fn plan(&self, options: BoxOptions, name: Option<String>) -> BoxliteResult<BoxPlan> {
+        self.rt_impl.plan(options, name).await
+    }
+
     /// Get an existing box by name, or create a new one if it doesn't exist.
     ///
     /// Returns `(LiteBox, true)` if a new box was created, or `(LiteBox, false)`
@@ -211,6 +294,61 @@ impl BoxliteRuntime {
         self.rt_impl.get_or_create(options, name).await
     }
 
+    /// Clone a stopped box into a new box with the same options and disk state.
+    ///
+    /// Disks are shared copy-on-write through a snapshot, so cloning is cheap
+    /// and the source box is left unchanged. The clone starts `Stopped`.
+    /// It does not take over the source's MAC or IP address, and its
+    /// published ports get dynamically assigned host ports.
+    pub async fn clone_box(
+        &self,
+        src_id_or_name: &str,
+        name: Option<String>,
+    ) -> BoxliteResult<LiteBox> {
+        self.rt_impl.clone_box(src_id_or_name, name).await
+    }
+
+    /// Export a stopped box to a portable `.tar.zst` archive.
+    ///
+    /// The archive bundles the box's options and rootfs disks (including their
+    /// backing layers) so it can be imported on another host.
+    pub async fn export_box(&self, id_or_name: &str, output: &Path) -> BoxliteResult<()> {
+        self.rt_impl.export_box(id_or_name, output).await
+    }
+
+    /// Import a box from an archive created by [`export_box`](Self::export_box).
+    ///
+    /// The box gets a new ID and starts `Stopped`. Options that bind the box
+    /// to the exporting host (volumes, ports, a custom kernel) are dropped
+    /// unless [`ImportOptions::host_bindings`] is set.
+    pub async fn import_box(&self, input: &Path, options: ImportOptions) -> BoxliteResult<LiteBox> {
+        self.rt_impl.import_box(input, options).await
+    }
+
+    /// Build an image from a Dockerfile and tag it in the local image store.
+    ///
+    /// `RUN` steps execute inside throwaway boxes. Each step is cached by its
+    /// instruction and inputs, so unchanged prefixes of the Dockerfile are
+    /// reused on the next build. The resulting tag can be passed to `create`.
+    pub async fn build(&self, options: BuildOptions) -> BoxliteResult<BuildOutput> {
+        crate::build::build(&self.rt_impl, options).await
+    }
+
+    /// Install `packages` on top of `image` and tag the result in the local
+    /// image store, like [`LiteBox::provision`] run once and committed.
+    ///
+    /// The install runs in a throwaway box and its layer is kept in the
+    /// build cache, so provisioning the same packages on the same image
+    /// again returns at once. Pass the returned reference to `create`.
+    pub async fn provision_image(
+        &self,
+        image: &str,
+        packages: &[String],
+        progress: Option<tokio::sync::mpsc::UnboundedSender<String>>,
+    ) -> BoxliteResult<BuildOutput> {
+        crate::build::provision_image(&self.rt_impl, image, packages, progress).await
+    }
+
     /// Get a handle to an existing box by ID or name.
     ///
     /// The `id_or_name` parameter can be either:
@@ -240,19 +378,87 @@ impl BoxliteRuntime {
         self.rt_impl.metrics().await
     }
 
+    /// Disk space used by cached images and boxes.
+    ///
+    /// Walks the image cache and box directories, so it takes a moment on
+    /// large caches. With `dedup_layers`, `extracted_bytes` is below
+    /// `extracted_logical_bytes` by the space the content store saves.
+    pub async fn disk_usage(&self) -> BoxliteResult<DiskUsage> {
+        self.rt_impl.disk_usage().await
+    }
+
+    /// Run one pruning pass with `policy` now.
+    ///
+    /// Does what the background reaper of `BoxliteOptions::retention` does
+    /// on each pass: removes boxes stopped for longer than
+    /// `max_stopped_box_age_secs`, then evicts least recently used images
+    /// until the cache fits in `max_image_cache_mib`.
+    pub async fn prune(&self, policy: &RetentionPolicy) -> BoxliteResult<PruneReport> {
+        self.rt_impl.prune(policy).await
+    }
+
+    /// Remove what the runtime no longer needs, in one pass.
+    ///
+    /// Removes every stopped box nobody holds a handle to, cached image data
+    /// no image refers to (every image no box uses with `all_images`),
+    /// anonymous volumes no box mounts and runtime logs older than
+    /// `logs_max_age`. Files changed within the last hour are kept, so a pull
+    /// or box creation running meanwhile is not disturbed.
+    pub async fn system_prune(
+        &self,
+        options: &SystemPruneOptions,
+    ) -> BoxliteResult<SystemPruneReport> {
+        self.rt_impl.system_prune(options).await
+    }
+
     /// Remove a box completely by ID or name.
     pub async fn remove(&self, id_or_name: &str, force: bool) -> BoxliteResult<()> {
         self.rt_impl.remove(id_or_name, force)
     }
 
+    // ========================================================================
+    // CONFIGURATION
+    // ========================================================================
+
+    /// Apply changed options without restarting the runtime.
+    ///
+    /// Registries, mirrors, TLS, proxy, `pull_rate_limit_mbps` and
+    /// `blob_cache` apply to the next pull; admission limits to the next
+    /// admission, including requests already queued. Cache reclaim, heartbeat,
+    /// `metrics_history`, `create_retries` and proxy propagation apply to
+    /// boxes started afterwards. `home_dir`, `dedup_layers`, `guest_rootfs_path` and
+    /// `rootfs_format` need a new runtime: they are reported in
+    /// [`OptionsReload::restart_required`] and keep their old values.
+    ///
+    /// The changes are also logged as an info event. If the new options are
+    /// invalid (e.g. a bad proxy URL), nothing is applied.
+    ///
+    /// ```no_run
+    /// # use boxlite::runtime::{BoxliteRuntime, options::BoxliteOptions};
+    /// # fn example(runtime: &BoxliteRuntime) -> Result<(), Box<dyn std::error::Error>> {
+    /// let options = BoxliteOptions {
+    ///     max_running_boxes: Some(8),
+    ///     ..Default::default()
+    /// };
+    /// let changes = runtime.reload_options(options)?;
+    /// println!("{}", changes); // "applied max_running_boxes"
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn reload_options(&self, options: BoxliteOptions) -> BoxliteResult<OptionsReload> {
+        self.rt_impl.reload_options(options)
+    }
+
     // ========================================================================
     // SHUTDOWN OPERATIONS
     // ========================================================================
 
     /// Gracefully shutdown all boxes in this runtime.
     ///
-    /// This method stops all running boxes, waiting up to `timeout` seconds
-    /// for each box to stop gracefully before force-killing it.
+    /// This method stops all running boxes concurrently, waiting up to
+    /// `timeout` seconds for each box to stop gracefully before force-killing
+    /// it. The returned report says which boxes stopped, which were killed
+    /// and which could not be stopped; check [`ShutdownReport::failed`].
     ///
     /// After calling this method, the runtime is permanently shut down and
     /// will return errors for any new operations (like `create()`).
@@ -285,8 +491,32 @@ impl BoxliteRuntime {
     ///     Ok(())
     /// }
     /// ```
-    pub async fn shutdown(&self, timeout: Option<i32>) -> BoxliteResult<()> {
-        self.rt_impl.shutdown(timeout).await
+    pub async fn shutdown(&self, timeout: Option<i32>) -> BoxliteResult<ShutdownReport> {
+        self.rt_impl.shutdown(timeout, &|_| {}).await
+    }
+
+    /// Like [`shutdown`](Self::shutdown), calling `progress` as each box
+    /// starts stopping, is killed after the timeout and finishes.
+    ///
+    /// ```no_run
+    /// # use boxlite::{BoxliteRuntime, ShutdownEvent};
+    /// # async fn f(runtime: BoxliteRuntime) -> boxlite::BoxliteResult<()> {
+    /// runtime
+    ///     .shutdown_with_progress(None, &|event| {
+    ///         if let ShutdownEvent::Finished(done) = event {
+    ///             println!("{}: {:?}", done.box_id, done.outcome);
+    ///         }
+    ///     })
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn shutdown_with_progress(
+        &self,
+        timeout: Option<i32>,
+        progress: &(dyn Fn(&ShutdownEvent) + Send + Sync),
+    ) -> BoxliteResult<ShutdownReport> {
+        self.rt_impl.shutdown(timeout, progress).await
     }
 
     // ========================================================================
@@ -311,6 +541,139 @@ impl BoxliteRuntime {
         self.rt_impl.image_manager.pull(image_ref).await
     }
 
+    /// Like [`pull_image`](Self::pull_image), then extracts the image's layers
+    /// into the local cache, sending progress lines (including per-layer
+    /// extraction time) to `progress`.
+    pub async fn pull_image_with_progress(
+        &self,
+        image_ref: &str,
+        progress: tokio::sync::mpsc::UnboundedSender<String>,
+    ) -> BoxliteResult<crate::images::ImageObject> {
+        self.rt_impl
+            .image_manager
+            .pull_with_progress(image_ref, &progress)
+            .await
+    }
+
+    /// Warm the cache for `images` in the background.
+    ///
+    /// Each image is pulled and its base rootfs disk is built in the
+    /// runtime's `rootfs_format`, so the first box created from it skips
+    /// both. At most `PREFETCH_CONCURRENCY` images are prepared at once and
+    /// downloads share `pull_rate_limit_mbps` with other pulls. The handle
+    /// resolves to one result per image, in input order; a failed image does
+    /// not stop the others.
+    pub fn prefetch(&self, images: Vec<String>) -> tokio::task::JoinHandle<Vec<PrefetchResult>> {
+        let rt_impl = Arc::clone(&self.rt_impl);
+        tokio::spawn(async move {
+            let permits = Arc::new(tokio::sync::Semaphore::new(
+                crate::runtime::constants::images::PREFETCH_CONCURRENCY,
+            ));
+            let tasks: Vec<_> = images
+                .into_iter()
+                .map(|image| {
+                    let rt_impl = Arc::clone(&rt_impl);
+                    let permits = Arc::clone(&permits);
+                    let task_image = image.clone();
+                    let task = tokio::spawn(async move {
+                        let image = task_image;
+                        let _permit = permits.acquire_owned().await;
+                        let start = std::time::Instant::now();
+                        let result = crate::litebox::warm_image(&rt_impl, &image).await;
+                        let elapsed = start.elapsed();
+                        if let Err(e) = &result {
+                            tracing::warn!(image = %image, error = %e, "Failed to prefetch image");
+                        } else {
+                            tracing::info!(image = %image, ?elapsed, "Prefetched image");
+                        }
+                        PrefetchResult {
+                            image,
+                            result,
+                            elapsed,
+                        }
+                    });
+                    (image, task)
+                })
+                .collect();
+
+            let mut results = Vec::with_capacity(tasks.len());
+            for (image, task) in tasks {
+                results.push(task.await.unwrap_or_else(|e| PrefetchResult {
+                    image,
+                    result: Err(BoxliteError::Internal(format!(
+                        "prefetch task failed: {}",
+                        e
+                    ))),
+                    elapsed: std::time::Duration::ZERO,
+                }));
+            }
+            results
+        })
+    }
+
+    /// Push an image from the local store to its registry.
+    ///
+    /// Credentials are taken from the Docker-style auth file written by
+    /// `docker login` (see `$DOCKER_CONFIG`). Returns the fully qualified
+    /// reference that was pushed.
+    pub async fn push_image(&self, image_ref: &str) -> BoxliteResult<String> {
+        self.rt_impl.image_manager.push(image_ref, None).await
+    }
+
+    /// Like [`push_image`](Self::push_image), sending progress lines to `progress`.
+    pub async fn push_image_with_progress(
+        &self,
+        image_ref: &str,
+        progress: tokio::sync::mpsc::UnboundedSender<String>,
+    ) -> BoxliteResult<String> {
+        self.rt_impl
+            .image_manager
+            .push(image_ref, Some(&progress))
+            .await
+    }
+
+    /// Tag a local image under a new reference.
+    ///
+    /// `source` may be a pulled reference or an existing local tag; it is
+    /// never fetched from a registry. Tags are resolved before registries
+    /// when creating boxes. Returns the normalized target (unqualified names
+    /// are stored as `localhost/<name>`).
+    pub async fn tag_image(&self, source: &str, target: &str) -> BoxliteResult<String> {
+        self.rt_impl.image_manager.tag(source, target).await
+    }
+
+    /// Run a pull-through registry cache until the listener fails.
+    ///
+    /// Other hosts can list this address (with `http://`) in
+    /// `BoxliteOptions::registry_mirrors`. Blobs are fetched from upstream
+    /// once and kept. Clients that present `options.token` also get blobs
+    /// already in this runtime's image store and this host's registry
+    /// credentials; others are served as anonymous pulls. Clients may pick
+    /// the upstream (`ns`) only among `options.upstream` and the registries
+    /// named in this runtime's options.
+    pub async fn serve_registry_cache(&self, options: RegistryCacheOptions) -> BoxliteResult<()> {
+        let upstreams = self.rt_impl.configured_registries();
+        let image_manager = &self.rt_impl.image_manager;
+        let image_layout = self.rt_impl.layout.image_layout();
+        let trusted = CacheAccess {
+            client: image_manager.registry_client(&options.upstream),
+            blobs_dir: image_layout.registry_cache_dir(),
+        };
+        let anonymous = CacheAccess {
+            client: image_manager.isolated_registry_client(&options.upstream)?,
+            blobs_dir: image_layout.registry_cache_public_dir(),
+        };
+        let listen = options.listen;
+        let cache = RegistryCache::new(
+            image_manager.storage().await,
+            options,
+            &upstreams,
+            trusted,
+            anonymous,
+        )?;
+        Arc::new(cache).serve(listen).await
+    }
+
     /// List all cached images.
     ///
     /// Returns a list of images available in the local content store.
@@ -323,6 +686,49 @@ impl BoxliteRuntime {
     pub async fn list_images(&self) -> BoxliteResult<Vec<crate::runtime::types::ImageInfo>> {
         self.rt_impl.image_manager.list().await
     }
+
+    /// Re-hash every cached manifest, config and layer tarball against its
+    /// digest.
+    ///
+    /// Digest-pinned references (`name@sha256:...`) check their manifest
+    /// chain and config on every use and fail with
+    /// [`BoxliteError::Integrity`]; this checks everything, layers included.
+    /// With `remove_corrupt`, blobs that fail are deleted so the next pull
+    /// downloads them again.
+    pub async fn verify_images(
+        &self,
+        remove_corrupt: bool,
+    ) -> BoxliteResult<crate::runtime::types::ImageVerification> {
+        self.rt_impl.image_manager.verify(remove_corrupt).await
+    }
+
+    /// OCI config, layers and platform of a cached image.
+    ///
+    /// `image_ref` resolves like a pull would (local tags, short-name aliases,
+    /// then the search registries) but without network access. Fails with
+    /// [`BoxliteError::NotFound`] if the image is not cached.
+    pub async fn image_inspect(
+        &self,
+        image_ref: &str,
+    ) -> BoxliteResult<crate::runtime::types::ImageInspect> {
+        self.rt_impl
+            .image_manager
+            .inspect(image_ref)
+            .await?
+            .ok_or_else(|| {
+                BoxliteError::NotFound(format!("image {} not found in local store", image_ref))
+            })
+    }
+}
+
+/// Open the database under `home_dir` read-only, or None if there is none yet.
+fn open_db_read_only(home_dir: &Path) -> BoxliteResult<Option<Database>> {
+    let layout = FilesystemLayout::new(home_dir.to_path_buf(), FsLayoutConfig::default());
+    let db_path = layout.db_dir().join("boxlite.db");
+    if !db_path.exists() {
+        return Ok(None);
+    }
+    Database::open_read_only(&db_path).map(Some)
 }
 
 // ============================================================================
@@ -0,0 +1,307 @@
+//! Box environment helpers: host passthrough, timezone and locale, and
+//! redaction of secrets.
+//!
+//! `BoxOptions::env_passthrough` patterns copy matching host variables into a
+//! box when it is created. Values of secret-looking keys are replaced with
+//! [`REDACTED`] wherever boxlite shows an environment (dry-run plans,
+//! `BoxInfo::env`, logs); the box itself always gets the real values.
+//!
+//! `BoxOptions::timezone` and `BoxOptions::locale` become `TZ` and `LANG`;
+//! a timezone of [`HOST_TIMEZONE`] is resolved to the host's zone at create.
+
+/// Placeholder shown instead of a sensitive value.
+pub const REDACTED: &str = "<redacted>";
+
+/// `BoxOptions::timezone` value that copies the host's timezone.
+pub const HOST_TIMEZONE: &str = "host";
+
+/// Key fragments that mark a variable as sensitive (matched on the uppercased key).
+const SENSITIVE_FRAGMENTS: &[&str] = &[
+    "PASSWORD",
+    "PASSWD",
+    "SECRET",
+    "TOKEN",
+    "API_KEY",
+    "APIKEY",
+    "ACCESS_KEY",
+    "PRIVATE_KEY",
+    "CREDENTIAL",
+    "AUTH",
+];
+
+/// Whether `key` matches a passthrough pattern.
+///
+/// Patterns are variable names in which `*` matches any run of characters,
+/// e.g. `AWS_*`, `*_TOKEN` or `HOME`. Matching is case-sensitive.
+pub fn pattern_matches(pattern: &str, key: &str) -> bool {
+    let mut parts = pattern.split('*');
+    // split always yields at least one part
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = key.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        // No '*': the pattern is an exact name
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+/// Host variables in `vars` matching any of `patterns`, sorted by key.
+pub fn passthrough(
+    patterns: &[String],
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> Vec<(String, String)> {
+    let mut matched: Vec<(String, String)> = vars
+        .into_iter()
+        .filter(|(key, _)| patterns.iter().any(|p| pattern_matches(p, key)))
+        .collect();
+    matched.sort();
+    matched
+}
+
+/// Whether `name` is an IANA timezone name such as `Europe/Berlin`,
+/// `UTC` or `Etc/GMT+5` (a relative path under `/usr/share/zoneinfo`).
+pub fn is_valid_timezone(name: &str) -> bool {
+    !name.is_empty()
+        && name.split('/').all(|part| {
+            !part.is_empty()
+                && part != "."
+                && part != ".."
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '-'))
+        })
+}
+
+/// Whether `name` looks like a locale such as `C.UTF-8`, `en_US.UTF-8` or
+/// `de_DE@euro`.
+pub fn is_valid_locale(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-' | '@'))
+}
+
+/// The host's timezone name, from `TZ`, `/etc/timezone` or the
+/// `/etc/localtime` symlink, in that order.
+pub fn host_timezone() -> Option<String> {
+    if let Ok(tz) = std::env::var("TZ") {
+        let tz = tz.trim_start_matches(':');
+        if is_valid_timezone(tz) {
+            return Some(tz.to_string());
+        }
+    }
+    if let Ok(tz) = std::fs::read_to_string("/etc/timezone") {
+        let tz = tz.trim();
+        if is_valid_timezone(tz) {
+            return Some(tz.to_string());
+        }
+    }
+    zone_from_localtime_link(&std::fs::read_link("/etc/localtime").ok()?)
+}
+
+/// Zone name of a `/etc/localtime` symlink target, e.g.
+/// `/usr/share/zoneinfo/Europe/Berlin` or, on macOS,
+/// `/var/db/timezone/zoneinfo/Europe/Berlin`.
+fn zone_from_localtime_link(target: &std::path::Path) -> Option<String> {
+    let (_, name) = target.to_str()?.rsplit_once("zoneinfo/")?;
+    is_valid_timezone(name).then(|| name.to_string())
+}
+
+/// Whether `key` looks like it holds a secret (`*_TOKEN`, `DB_PASSWORD`, ...).
+pub fn is_sensitive_key(key: &str) -> bool {
+    let key = key.to_ascii_uppercase();
+    SENSITIVE_FRAGMENTS.iter().any(|f| key.contains(f))
+}
+
+/// `env` with the values of sensitive keys replaced by [`REDACTED`].
+pub fn redact(env: &[(String, String)]) -> Vec<(String, String)> {
+    env.iter()
+        .map(|(key, value)| {
+            let value = if is_sensitive_key(key) {
+                REDACTED.to_string()
+            } else {
+                value.clone()
+            };
+            (key.clone(), value)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vmm::mock::{MockScript, test_runtime};
+
+    #[test]
+    fn test_pattern_matches() {
+        assert!(pattern_matches("HOME", "HOME"));
+        assert!(!pattern_matches("HOME", "HOMEDIR"));
+        assert!(pattern_matches("AWS_*", "AWS_REGION"));
+        assert!(pattern_matches("AWS_*", "AWS_"));
+        assert!(!pattern_matches("AWS_*", "MY_AWS_REGION"));
+        assert!(pattern_matches("*_TOKEN", "GITHUB_TOKEN"));
+        assert!(pattern_matches("*", "ANYTHING"));
+        assert!(pattern_matches("A*B*C", "AxxBC"));
+        assert!(!pattern_matches("A*B*C", "AxxC"));
+        assert!(!pattern_matches("AB*B", "AB"));
+        assert!(!pattern_matches("aws_*", "AWS_REGION"));
+    }
+
+    #[test]
+    fn test_passthrough_sorted() {
+        let vars = [
+            ("AWS_SECRET_ACCESS_KEY", "s"),
+            ("PATH", "/bin"),
+            ("AWS_REGION", "eu-west-1"),
+        ]
+        .map(|(k, v)| (k.to_string(), v.to_string()));
+        let env = passthrough(&["AWS_*".to_string()], vars);
+        assert_eq!(
+            env,
+            vec![
+                ("AWS_REGION".to_string(), "eu-west-1".to_string()),
+                ("AWS_SECRET_ACCESS_KEY".to_string(), "s".to_string()),
+            ]
+        );
+        assert!(passthrough(&[], [("PATH".to_string(), "/bin".to_string())]).is_empty());
+    }
+
+    #[test]
+    fn test_timezone_and_locale_names() {
+        for good in [
+            "UTC",
+            "Europe/Berlin",
+            "America/Argentina/Buenos_Aires",
+            "Etc/GMT+5",
+        ] {
+            assert!(is_valid_timezone(good), "{}", good);
+        }
+        for bad in [
+            "",
+            "/etc/passwd",
+            "../etc/passwd",
+            "Europe//Berlin",
+            "Europe/Ber lin",
+        ] {
+            assert!(!is_valid_timezone(bad), "{}", bad);
+        }
+        for good in ["C", "C.UTF-8", "en_US.UTF-8", "de_DE@euro"] {
+            assert!(is_valid_locale(good), "{}", good);
+        }
+        assert!(!is_valid_locale(""));
+        assert!(!is_valid_locale("en US"));
+    }
+
+    #[test]
+    fn test_zone_from_localtime_link() {
+        use std::path::Path;
+        assert_eq!(
+            zone_from_localtime_link(Path::new("/usr/share/zoneinfo/Europe/Berlin")).as_deref(),
+            Some("Europe/Berlin")
+        );
+        assert_eq!(
+            zone_from_localtime_link(Path::new("/var/db/timezone/zoneinfo/Asia/Tokyo")).as_deref(),
+            Some("Asia/Tokyo")
+        );
+        assert_eq!(
+            zone_from_localtime_link(Path::new("/etc/localtime.bak")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_redact() {
+        let env = [
+            ("GITHUB_TOKEN", "ghp_x"),
+            ("db_password", "hunter2"),
+            ("AWS_SECRET_ACCESS_KEY", "s"),
+            ("PATH", "/bin"),
+        ]
+        .map(|(k, v)| (k.to_string(), v.to_string()));
+        let values: Vec<String> = redact(&env).into_iter().map(|(_, v)| v).collect();
+        assert_eq!(values, vec![REDACTED, REDACTED, REDACTED, "/bin"]);
+    }
+
+    #[tokio::test]
+    async fn test_info_redacts_secrets() {
+        use crate::{BoxOptions, BoxliteOptions};
+
+        let (_home, runtime) = test_runtime(BoxliteOptions::default(), MockScript::new());
+        let env = vec![
+            ("GITHUB_TOKEN".to_string(), "ghp_secret".to_string()),
+            ("LANG".to_string(), "C.UTF-8".to_string()),
+        ];
+        let litebox = runtime
+            .create(
+                BoxOptions {
+                    env,
+                    ..Default::default()
+                },
+                None,
+            )
+            .await
+            .unwrap();
+
+        // Proxy variables of the runtime may follow
+        let env = litebox.info().env;
+        assert_eq!(
+            env[..2],
+            [
+                ("GITHUB_TOKEN".to_string(), REDACTED.to_string()),
+                ("LANG".to_string(), "C.UTF-8".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_timezone_and_locale_env() {
+        use crate::{BoxOptions, BoxliteOptions};
+
+        let (_home, runtime) = test_runtime(BoxliteOptions::default(), MockScript::new());
+        let litebox = runtime
+            .create(
+                BoxOptions {
+                    env: vec![("LANG".to_string(), "C".to_string())],
+                    timezone: Some("Europe/Berlin".to_string()),
+                    locale: Some("en_US.UTF-8".to_string()),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await
+            .unwrap();
+
+        // LANG from env wins over the locale
+        let env = litebox.info().env;
+        assert_eq!(
+            env[..2],
+            [
+                ("LANG".to_string(), "C".to_string()),
+                ("TZ".to_string(), "Europe/Berlin".to_string()),
+            ]
+        );
+
+        // "host" is resolved at create, never stored as is
+        let litebox = runtime
+            .create(
+                BoxOptions {
+                    timezone: Some("host".to_string()),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        let tz = litebox.info().env.into_iter().find(|(k, _)| k == "TZ");
+        assert!(tz.is_some_and(|(_, zone)| zone != "host"));
+    }
+}
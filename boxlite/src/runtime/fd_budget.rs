@@ -0,0 +1,185 @@
+//! File descriptor budget of the runtime process.
+//!
+//! Every box keeps descriptors open in the runtime process for as long as it
+//! runs: the shim's stdio pipes, gRPC connections, console and log files,
+//! lock files and bind mounts. With the common default soft limit of 1024
+//! (256 on macOS) a few dozen boxes are enough to make some unrelated
+//! `open()` fail with `EMFILE` deep in the stack.
+//!
+//! The runtime raises its soft `RLIMIT_NOFILE` to the hard limit when it
+//! starts, and admits a box only if its estimated descriptors fit in what is
+//! left. A box that does not fit fails with `ResourceExhausted` and a
+//! suggested `ulimit -n`.
+
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+
+use crate::runtime::options::BoxOptions;
+
+/// Descriptors a box keeps open in the runtime process, before volumes.
+pub(crate) const FDS_PER_BOX: u64 = 32;
+
+/// Descriptors kept free for everything that is not a box: the database,
+/// image pulls, copies and the caller's own files.
+const HEADROOM: u64 = 128;
+
+/// `ulimit -n` is suggested in steps of this size.
+const SUGGESTION_STEP: u64 = 1024;
+
+/// Estimated descriptors `options` keep open in the runtime process.
+pub(crate) fn box_fds(options: &BoxOptions) -> u64 {
+    // One bind mount handle per volume
+    FDS_PER_BOX + options.volumes.len() as u64
+}
+
+/// Descriptor limit of the runtime process.
+pub(crate) struct FdBudget {
+    /// Soft `RLIMIT_NOFILE`, if known.
+    limit: Option<u64>,
+    /// Descriptors open before any box was admitted.
+    baseline: u64,
+}
+
+impl FdBudget {
+    /// Raise the process's descriptor limit and record the current usage.
+    pub(crate) fn for_process() -> Self {
+        let limit = raise_nofile_limit();
+        let baseline = open_fds().unwrap_or(0);
+        tracing::debug!(?limit, baseline, "file descriptor budget");
+        Self { limit, baseline }
+    }
+
+    /// A budget that admits every box.
+    pub(crate) fn unlimited() -> Self {
+        Self {
+            limit: None,
+            baseline: 0,
+        }
+    }
+
+    /// A budget of `limit` descriptors, none of them open yet.
+    #[cfg(test)]
+    pub(crate) fn with_limit(limit: u64) -> Self {
+        Self {
+            limit: Some(limit),
+            baseline: 0,
+        }
+    }
+
+    /// Whether a box needing `needed` descriptors fits when it is alone.
+    pub(crate) fn fits_alone(&self, needed: u64) -> bool {
+        self.limit
+            .is_none_or(|limit| self.baseline + needed + HEADROOM <= limit)
+    }
+
+    /// Check that a box needing `needed` descriptors fits next to boxes that
+    /// have `reserved` descriptors between them.
+    ///
+    /// Usage is the larger of what is open now and the baseline plus the
+    /// reservations, so boxes that are still starting count in full.
+    pub(crate) fn check(&self, reserved: u64, needed: u64) -> BoxliteResult<()> {
+        let Some(limit) = self.limit else {
+            return Ok(());
+        };
+        let in_use = open_fds().unwrap_or(0).max(self.baseline + reserved);
+        check_fit(limit, in_use, needed)
+    }
+}
+
+fn check_fit(limit: u64, in_use: u64, needed: u64) -> BoxliteResult<()> {
+    let required = in_use + needed + HEADROOM;
+    if required <= limit {
+        return Ok(());
+    }
+    let suggested = required.div_ceil(SUGGESTION_STEP).max(2) * SUGGESTION_STEP;
+    Err(BoxliteError::ResourceExhausted(format!(
+        "not enough file descriptors for another box: about {} open, the box needs about {}, \
+         and the limit is {}. Raise it (e.g. `ulimit -n {}`) or stop some boxes",
+        in_use, needed, limit, suggested
+    )))
+}
+
+/// Raise the soft `RLIMIT_NOFILE` as far as allowed and return it.
+fn raise_nofile_limit() -> Option<u64> {
+    let mut rlim = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    // SAFETY: getrlimit only writes to the struct passed in
+    if unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut rlim) } != 0 {
+        return None;
+    }
+    let soft = rlim.rlim_cur;
+    // The kernel rejects soft limits above its own cap (OPEN_MAX on macOS,
+    // fs.nr_open on Linux) even when the hard limit is unlimited
+    #[cfg(target_os = "macos")]
+    let cap = libc::OPEN_MAX as libc::rlim_t;
+    #[cfg(not(target_os = "macos"))]
+    let cap: libc::rlim_t = 1 << 20;
+    let targets = [rlim.rlim_max, rlim.rlim_max.min(cap)];
+
+    for target in targets {
+        if target <= soft {
+            break;
+        }
+        let raised = libc::rlimit {
+            rlim_cur: target,
+            rlim_max: rlim.rlim_max,
+        };
+        // SAFETY: setrlimit only reads the struct passed in
+        if unsafe { libc::setrlimit(libc::RLIMIT_NOFILE, &raised) } == 0 {
+            tracing::debug!(from = soft, to = target, "raised RLIMIT_NOFILE");
+            return Some(target);
+        }
+    }
+    Some(soft)
+}
+
+/// Descriptors open in this process.
+fn open_fds() -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    let dir = "/proc/self/fd";
+    #[cfg(not(target_os = "linux"))]
+    let dir = "/dev/fd";
+    // The directory handle itself is listed too
+    let count = std::fs::read_dir(dir).ok()?.count() as u64;
+    Some(count.saturating_sub(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_fit() {
+        assert!(check_fit(1024, 100, FDS_PER_BOX).is_ok());
+
+        let err = check_fit(1024, 900, FDS_PER_BOX).unwrap_err();
+        assert!(matches!(err, BoxliteError::ResourceExhausted(_)));
+        assert!(err.to_string().contains("ulimit -n 2048"), "{}", err);
+
+        let err = check_fit(4096, 4000, FDS_PER_BOX).unwrap_err();
+        assert!(err.to_string().contains("ulimit -n 5120"), "{}", err);
+    }
+
+    #[test]
+    fn test_box_fds_counts_volumes() {
+        let volume = crate::runtime::options::VolumeSpec {
+            host_path: "/data".into(),
+            guest_path: "/data".into(),
+            read_only: false,
+            chown: false,
+            id_shift: None,
+        };
+        let options = BoxOptions {
+            volumes: vec![volume.clone(), volume],
+            ..Default::default()
+        };
+        assert_eq!(box_fds(&BoxOptions::default()), FDS_PER_BOX);
+        assert_eq!(box_fds(&options), FDS_PER_BOX + 2);
+    }
+
+    #[test]
+    fn test_unlimited_budget() {
+        assert!(FdBudget::unlimited().check(u64::MAX / 2, 1).is_ok());
+    }
+}
@@ -2,6 +2,8 @@ use boxlite_shared::errors::{BoxliteError, BoxliteResult};
 use boxlite_shared::layout::{SharedGuestLayout, dirs as shared_dirs};
 use std::path::{Path, PathBuf};
 
+use crate::runtime::options::BoxliteOptions;
+
 /// Directory structure constants
 pub mod dirs {
     /// Base directory name for BoxLite data
@@ -41,6 +43,18 @@ pub mod dirs {
 
     /// Subdirectory for per-entity locks
     pub const LOCKS_DIR: &str = "locks";
+
+    /// Subdirectory for immutable disk snapshots shared by cloned boxes
+    pub const SNAPSHOTS_DIR: &str = "snapshots";
+
+    /// Subdirectory for volume data
+    pub const VOLUMES_DIR: &str = "volumes";
+
+    /// Subdirectory of the volumes dir for anonymous volumes
+    pub const ANONYMOUS_VOLUMES_DIR: &str = "anonymous";
+
+    /// Subdirectory for transient files
+    pub const TMP_DIR: &str = "tmp";
 }
 
 /// Configuration for filesystem layout behavior.
@@ -80,27 +94,74 @@ impl FsLayoutConfig {
 // FILESYSTEM LAYOUT (home directory)
 // ============================================================================
 
+/// Runtime directory structure.
+///
+/// Everything lives under `home_dir` unless `BoxliteOptions` moves box state
+/// (`state_dir`), the image cache (`image_cache_dir`) or volumes
+/// (`volumes_dir`) elsewhere, e.g. box disks onto a fast local disk and the
+/// image cache onto a large slow one.
 #[derive(Clone, Debug)]
 pub struct FilesystemLayout {
     home_dir: PathBuf,
+    state_dir: PathBuf,
+    image_cache_dir: PathBuf,
+    volumes_dir: PathBuf,
     config: FsLayoutConfig,
 }
 
 impl FilesystemLayout {
+    /// Layout with everything under `home_dir`.
     pub fn new(home_dir: PathBuf, config: FsLayoutConfig) -> Self {
-        Self { home_dir, config }
+        Self {
+            state_dir: home_dir.clone(),
+            image_cache_dir: home_dir.join(dirs::IMAGES_DIR),
+            volumes_dir: home_dir.join(dirs::VOLUMES_DIR),
+            home_dir,
+            config,
+        }
+    }
+
+    /// Layout of the runtime configured by `options`.
+    pub fn from_options(options: &BoxliteOptions, config: FsLayoutConfig) -> Self {
+        let mut layout = Self::new(options.home_dir.clone(), config);
+        if let Some(dir) = &options.state_dir {
+            layout.state_dir = dir.clone();
+        }
+        if let Some(dir) = &options.image_cache_dir {
+            layout.image_cache_dir = dir.clone();
+        }
+        if let Some(dir) = &options.volumes_dir {
+            layout.volumes_dir = dir.clone();
+        }
+        layout
     }
 
     pub fn home_dir(&self) -> &Path {
         &self.home_dir
     }
 
+    /// Root of per-box state (boxes and snapshots): `home_dir` by default
+    pub fn state_dir(&self) -> &Path {
+        &self.state_dir
+    }
+
+    /// Root of named and anonymous volumes: ~/.boxlite/volumes by default
+    pub fn volumes_dir(&self) -> &Path {
+        &self.volumes_dir
+    }
+
+    /// Anonymous volumes, one directory each: ~/.boxlite/volumes/anonymous
+    pub fn anonymous_volumes_dir(&self) -> PathBuf {
+        self.volumes_dir.join(dirs::ANONYMOUS_VOLUMES_DIR)
+    }
+
     pub fn db_dir(&self) -> PathBuf {
         self.home_dir.join(dirs::DB_DIR)
     }
 
+    /// Image cache: ~/.boxlite/images by default
     pub fn images_dir(&self) -> PathBuf {
-        self.home_dir.join(dirs::IMAGES_DIR)
+        self.image_cache_dir.clone()
     }
 
     pub fn logs_dir(&self) -> PathBuf {
@@ -117,10 +178,10 @@ impl FilesystemLayout {
         self.images_dir().join(dirs::MANIFESTS_DIR)
     }
 
-    /// Root directory for all box workspaces: ~/.boxlite/boxes
+    /// Root directory for all box workspaces: {state_dir}/boxes
     /// Each box gets a subdirectory containing upper/work dirs for overlayfs
     pub fn boxes_dir(&self) -> PathBuf {
-        self.home_dir.join(dirs::BOXES_DIR)
+        self.state_dir.join(dirs::BOXES_DIR)
     }
 
     /// Per-entity locks directory: ~/.boxlite/locks
@@ -131,11 +192,25 @@ impl FilesystemLayout {
         self.home_dir.join(dirs::LOCKS_DIR)
     }
 
+    /// Disk snapshots shared by cloned boxes: {state_dir}/snapshots
+    ///
+    /// Snapshots are read-only qcow2 backing files; the source box and its
+    /// clones each write to their own overlay on top.
+    pub fn snapshots_dir(&self) -> PathBuf {
+        self.state_dir.join(dirs::SNAPSHOTS_DIR)
+    }
+
     /// Temporary directory for transient files: ~/.boxlite/tmp
-    /// Used for disk image creation and other operations that need
-    /// temp files on the same filesystem as the final destination.
+    /// Used for copies in and out of boxes and other scratch files.
     pub fn temp_dir(&self) -> PathBuf {
-        self.home_dir.join("tmp")
+        self.home_dir.join(dirs::TMP_DIR)
+    }
+
+    /// Temporary directory for building rootfs disks: {images}/tmp
+    /// On the same filesystem as the image cache, so finished disks are
+    /// renamed into place rather than copied.
+    pub fn image_temp_dir(&self) -> PathBuf {
+        self.images_dir().join(dirs::TMP_DIR)
     }
 
     /// Initialize the filesystem structure.
@@ -151,6 +226,9 @@ impl FilesystemLayout {
         std::fs::create_dir_all(self.temp_dir())
             .map_err(|e| BoxliteError::Storage(format!("failed to create temp dir: {e}")))?;
 
+        std::fs::create_dir_all(self.image_temp_dir())
+            .map_err(|e| BoxliteError::Storage(format!("failed to create image temp dir: {e}")))?;
+
         std::fs::create_dir_all(self.image_layers_dir())
             .map_err(|e| BoxliteError::Storage(format!("failed to create layers dir: {e}")))?;
 
@@ -188,6 +266,86 @@ impl FilesystemLayout {
     }
 }
 
+// ============================================================================
+// SOCKET PATHS
+// ============================================================================
+
+/// Longest path a Unix socket can be bound or connected at (`sun_path`
+/// without its trailing NUL).
+#[cfg(target_os = "linux")]
+pub const MAX_SOCKET_PATH: usize = 107;
+/// Longest path a Unix socket can be bound or connected at (`sun_path`
+/// without its trailing NUL).
+#[cfg(not(target_os = "linux"))]
+pub const MAX_SOCKET_PATH: usize = 103;
+
+/// Longest socket file name in a box's sockets directory.
+const LONGEST_SOCKET_NAME: &str = "ready.sock";
+
+/// Sockets directory of the box at `box_dir`.
+///
+/// `{box_dir}/sockets`, unless a socket in it would be longer than
+/// [`MAX_SOCKET_PATH`] (deep home directories, macOS temp directories). Then
+/// it is a short directory under `/tmp/boxlite-{uid}`, named by a hash of
+/// `box_dir` so every process derives the same path, and `{box_dir}/sockets`
+/// links to it. Abstract sockets would avoid the filesystem altogether, but
+/// the VMM and the network backend bind their sockets by path.
+pub fn box_sockets_dir(box_dir: &Path) -> PathBuf {
+    let dir = box_dir.join(dirs::SOCKETS_DIR);
+    if dir.join(LONGEST_SOCKET_NAME).as_os_str().len() <= MAX_SOCKET_PATH {
+        return dir;
+    }
+    short_sockets_dir(box_dir)
+}
+
+/// Per-user parent of the short sockets directories.
+fn short_sockets_root() -> PathBuf {
+    // SAFETY: getuid cannot fail
+    let uid = unsafe { libc::getuid() };
+    PathBuf::from(format!("/tmp/boxlite-{}", uid))
+}
+
+fn short_sockets_dir(box_dir: &Path) -> PathBuf {
+    use sha2::{Digest, Sha256};
+
+    let hash = Sha256::digest(box_dir.as_os_str().as_encoded_bytes());
+    short_sockets_root().join(&hex::encode(hash)[..16])
+}
+
+/// Create the short sockets root, or check that an existing one is a
+/// private directory of this user: anyone who could write to it could
+/// swap a box's sockets.
+fn prepare_short_sockets_root(root: &Path) -> BoxliteResult<()> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+
+    match std::fs::DirBuilder::new().mode(0o700).create(root) {
+        Ok(()) => return Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+        Err(e) => {
+            return Err(BoxliteError::Storage(format!(
+                "failed to create sockets dir {}: {e}",
+                root.display()
+            )));
+        }
+    }
+    let meta = std::fs::symlink_metadata(root).map_err(|e| {
+        BoxliteError::Storage(format!(
+            "failed to stat sockets dir {}: {e}",
+            root.display()
+        ))
+    })?;
+    // SAFETY: getuid cannot fail
+    let uid = unsafe { libc::getuid() };
+    if !meta.is_dir() || meta.uid() != uid || meta.mode() & 0o077 != 0 {
+        return Err(BoxliteError::Storage(format!(
+            "{} must be a directory owned by uid {} with mode 0700",
+            root.display(),
+            uid
+        )));
+    }
+    Ok(())
+}
+
 // ============================================================================
 // BOX FILESYSTEM LAYOUT (per-box directory)
 // ============================================================================
@@ -210,8 +368,9 @@ impl FilesystemLayout {
 ///
 /// ```text
 /// ~/.boxlite/boxes/{box_id}/
-/// ├── sockets/
+/// ├── sockets/            # May link to a short dir (see box_sockets_dir)
 /// │   ├── box.sock        # gRPC communication
+/// │   ├── net.sock        # Port forwarder control
 /// │   └── ready.sock      # Ready notification
 /// ├── mounts/             # Host preparation (SharedGuestLayout)
 /// │   └── containers/
@@ -257,9 +416,10 @@ impl BoxFilesystemLayout {
     // SOCKETS
     // ========================================================================
 
-    /// Sockets directory: ~/.boxlite/boxes/{box_id}/sockets
+    /// Sockets directory: ~/.boxlite/boxes/{box_id}/sockets, or a short
+    /// directory it links to (see [`box_sockets_dir`]).
     pub fn sockets_dir(&self) -> PathBuf {
-        self.box_dir.join(dirs::SOCKETS_DIR)
+        box_sockets_dir(&self.box_dir)
     }
 
     /// Unix socket path: ~/.boxlite/boxes/{box_id}/sockets/box.sock
@@ -267,6 +427,20 @@ impl BoxFilesystemLayout {
         self.sockets_dir().join("box.sock")
     }
 
+    /// Port forwarder control socket: ~/.boxlite/boxes/{box_id}/sockets/net.sock
+    ///
+    /// The network backend accepts port forward changes here while the box runs.
+    pub fn net_socket_path(&self) -> PathBuf {
+        self.sockets_dir().join("net.sock")
+    }
+
+    /// Network tap socket: ~/.boxlite/boxes/{box_id}/sockets/tap.sock
+    ///
+    /// The network backend creates it and the VM connects to it.
+    pub fn tap_socket_path(&self) -> PathBuf {
+        self.sockets_dir().join("tap.sock")
+    }
+
     /// Ready notification socket: ~/.boxlite/boxes/{box_id}/sockets/ready.sock
     ///
     /// Guest connects to this socket to signal it's ready to serve.
@@ -322,7 +496,56 @@ impl BoxFilesystemLayout {
 
     /// Virtual disk path: ~/.boxlite/boxes/{box_id}/disk.qcow2
     pub fn disk_path(&self) -> PathBuf {
-        self.box_dir.join("disk.qcow2")
+        self.box_dir
+            .join(crate::runtime::constants::filenames::CONTAINER_DISK)
+    }
+
+    /// Read-only rootfs image path: ~/.boxlite/boxes/{box_id}/image.qcow2
+    ///
+    /// Only present for boxes created with an erofs/squashfs rootfs format;
+    /// `disk_path()` is then the overlayfs upper disk.
+    pub fn image_disk_path(&self) -> PathBuf {
+        self.box_dir
+            .join(crate::runtime::constants::filenames::CONTAINER_IMAGE_DISK)
+    }
+
+    /// Rootfs disk of a sidecar: ~/.boxlite/boxes/{box_id}/sidecar-{name}.qcow2
+    pub fn sidecar_disk_path(&self, name: &str) -> PathBuf {
+        self.box_dir
+            .join(crate::runtime::constants::filenames::sidecar_disk(name))
+    }
+
+    /// Output recordings: ~/.boxlite/boxes/{box_id}/recordings
+    ///
+    /// One directory per recorded execution, see `BoxCommand::record_output`.
+    pub fn recordings_dir(&self) -> PathBuf {
+        self.box_dir.join("recordings")
+    }
+
+    /// Core dumps: ~/.boxlite/boxes/{box_id}/cores
+    ///
+    /// Shared read-write with the guest when `BoxOptions::core_dumps` is set.
+    pub fn cores_dir(&self) -> PathBuf {
+        self.box_dir.join(shared_dirs::CORES)
+    }
+
+    /// Connection log path: ~/.boxlite/boxes/{box_id}/connections.log
+    ///
+    /// One JSON line per connection the guest opens, written by the network
+    /// backend when `BoxOptions::log_connections` is set, and rotated by size
+    /// into `connections.log.{1..4}`.
+    pub fn connection_log_path(&self) -> PathBuf {
+        self.box_dir.join("connections.log")
+    }
+
+    /// Network lost marker: ~/.boxlite/boxes/{box_id}/network-lost
+    ///
+    /// Written by the shim when the shared network daemon hosting the box's
+    /// network exits. The VM stays connected to the dead backend, so the box
+    /// has no network until it is restarted.
+    pub fn network_lost_path(&self) -> PathBuf {
+        self.box_dir
+            .join(crate::runtime::constants::filenames::NETWORK_LOST)
     }
 
     /// Console output path: ~/.boxlite/boxes/{box_id}/console.log
@@ -357,8 +580,17 @@ impl BoxFilesystemLayout {
         std::fs::create_dir_all(&self.box_dir)
             .map_err(|e| BoxliteError::Storage(format!("failed to create box dir: {e}")))?;
 
-        std::fs::create_dir_all(self.sockets_dir())
+        let sockets_dir = self.sockets_dir();
+        let link = self.box_dir.join(dirs::SOCKETS_DIR);
+        if sockets_dir != link {
+            prepare_short_sockets_root(&short_sockets_root())?;
+        }
+        std::fs::create_dir_all(&sockets_dir)
             .map_err(|e| BoxliteError::Storage(format!("failed to create sockets dir: {e}")))?;
+        if sockets_dir != link && std::fs::symlink_metadata(&link).is_err() {
+            std::os::unix::fs::symlink(&sockets_dir, &link)
+                .map_err(|e| BoxliteError::Storage(format!("failed to link sockets dir: {e}")))?;
+        }
 
         std::fs::create_dir_all(self.mounts_dir())
             .map_err(|e| BoxliteError::Storage(format!("failed to create mounts dir: {e}")))?;
@@ -372,6 +604,12 @@ impl BoxFilesystemLayout {
 
     /// Cleanup the box directory.
     pub fn cleanup(&self) -> BoxliteResult<()> {
+        let sockets_dir = self.sockets_dir();
+        if !sockets_dir.starts_with(&self.box_dir) && sockets_dir.exists() {
+            std::fs::remove_dir_all(&sockets_dir).map_err(|e| {
+                BoxliteError::Storage(format!("failed to cleanup sockets dir: {e}"))
+            })?;
+        }
         if self.box_dir.exists() {
             std::fs::remove_dir_all(&self.box_dir)
                 .map_err(|e| BoxliteError::Storage(format!("failed to cleanup box dir: {e}")))?;
@@ -390,6 +628,7 @@ impl BoxFilesystemLayout {
 /// - layers/: Downloaded layer tarballs
 /// - extracted/: Extracted layer directories
 /// - disk-images/: Cached disk images for COW
+/// - content/: Content-addressed file objects (layer dedup)
 /// - manifests/: Image manifests
 /// - configs/: Image configs
 #[derive(Clone, Debug)]
@@ -453,6 +692,15 @@ impl ImageFilesystemLayout {
         self.images_dir.join("disk-images")
     }
 
+    /// Content store directory: ~/.boxlite/images/content
+    ///
+    /// Holds one object per unique file content, hardlinked into extracted
+    /// layers when layer dedup is enabled. Must share a filesystem with
+    /// `extracted/`.
+    pub fn content_dir(&self) -> PathBuf {
+        self.images_dir.join("content")
+    }
+
     /// Manifests directory: ~/.boxlite/images/manifests
     pub fn manifests_dir(&self) -> PathBuf {
         self.images_dir.join(dirs::MANIFESTS_DIR)
@@ -463,6 +711,22 @@ impl ImageFilesystemLayout {
         self.images_dir.join("configs")
     }
 
+    /// Build cache directory: ~/.boxlite/images/build-cache
+    pub fn build_cache_dir(&self) -> PathBuf {
+        self.images_dir.join("build-cache")
+    }
+
+    /// Registry cache blobs directory: ~/.boxlite/images/registry-cache
+    pub fn registry_cache_dir(&self) -> PathBuf {
+        self.images_dir.join("registry-cache")
+    }
+
+    /// Registry cache blobs fetched for anonymous clients:
+    /// ~/.boxlite/images/registry-cache/public
+    pub fn registry_cache_public_dir(&self) -> PathBuf {
+        self.registry_cache_dir().join("public")
+    }
+
     /// Prepare the images directory structure.
     pub fn prepare(&self) -> BoxliteResult<()> {
         std::fs::create_dir_all(self.layers_dir())
@@ -491,6 +755,7 @@ impl ImageFilesystemLayout {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::vmm::mock::{MockScript, test_runtime};
 
     #[test]
     fn test_local_bundle_cache_dir_format() {
@@ -591,4 +856,134 @@ mod tests {
             "Different paths should have different cache dirs"
         );
     }
+
+    #[test]
+    fn test_layout_defaults_to_home_dir() {
+        let layout = FilesystemLayout::new(PathBuf::from("/h"), FsLayoutConfig::default());
+        assert_eq!(layout.boxes_dir(), PathBuf::from("/h/boxes"));
+        assert_eq!(layout.snapshots_dir(), PathBuf::from("/h/snapshots"));
+        assert_eq!(layout.images_dir(), PathBuf::from("/h/images"));
+        assert_eq!(layout.image_temp_dir(), PathBuf::from("/h/images/tmp"));
+        assert_eq!(layout.volumes_dir(), Path::new("/h/volumes"));
+    }
+
+    #[test]
+    fn test_layout_from_options_overrides() {
+        let options = BoxliteOptions {
+            home_dir: PathBuf::from("/h"),
+            state_dir: Some(PathBuf::from("/nvme/boxlite")),
+            image_cache_dir: Some(PathBuf::from("/hdd/images")),
+            volumes_dir: Some(PathBuf::from("/data/volumes")),
+            ..Default::default()
+        };
+        let layout = FilesystemLayout::from_options(&options, FsLayoutConfig::default());
+        assert_eq!(layout.boxes_dir(), PathBuf::from("/nvme/boxlite/boxes"));
+        assert_eq!(
+            layout.snapshots_dir(),
+            PathBuf::from("/nvme/boxlite/snapshots")
+        );
+        assert_eq!(layout.images_dir(), PathBuf::from("/hdd/images"));
+        assert_eq!(
+            layout.image_layers_dir(),
+            PathBuf::from("/hdd/images/layers")
+        );
+        assert_eq!(layout.volumes_dir(), Path::new("/data/volumes"));
+        // Runtime bookkeeping stays in home_dir
+        assert_eq!(layout.db_dir(), PathBuf::from("/h/db"));
+        assert_eq!(layout.logs_dir(), PathBuf::from("/h/logs"));
+        assert_eq!(layout.temp_dir(), PathBuf::from("/h/tmp"));
+    }
+
+    #[test]
+    fn test_sockets_dir_stays_in_short_box_dir() {
+        let layout = BoxFilesystemLayout::new(
+            PathBuf::from("/home/u/.boxlite/boxes/01JBOX"),
+            FsLayoutConfig::default(),
+            false,
+        );
+        assert_eq!(
+            layout.socket_path(),
+            PathBuf::from("/home/u/.boxlite/boxes/01JBOX/sockets/box.sock")
+        );
+    }
+
+    #[test]
+    fn test_sockets_dir_shortened_for_deep_home() {
+        let deep = PathBuf::from("/Users/someone/Library/Application Support")
+            .join("a".repeat(80))
+            .join(".boxlite/boxes/01JBXQ5N8W3Z2Y7V4T6R1P0M9K");
+        let layout = BoxFilesystemLayout::new(deep.clone(), FsLayoutConfig::default(), false);
+
+        let sockets_dir = layout.sockets_dir();
+        assert!(sockets_dir.starts_with(short_sockets_root()));
+        for socket in [
+            layout.socket_path(),
+            layout.net_socket_path(),
+            layout.ready_socket_path(),
+        ] {
+            assert!(socket.as_os_str().len() <= MAX_SOCKET_PATH, "{:?}", socket);
+        }
+
+        // Every process derives the same directory, and boxes get their own
+        assert_eq!(box_sockets_dir(&deep), sockets_dir);
+        assert_ne!(box_sockets_dir(&deep.with_file_name("other")), sockets_dir);
+        assert_eq!(
+            crate::runtime::constants::filenames::unix_socket_path(
+                &PathBuf::from("/Users/someone/Library/Application Support")
+                    .join("a".repeat(80))
+                    .join(".boxlite/boxes"),
+                "01JBXQ5N8W3Z2Y7V4T6R1P0M9K"
+            ),
+            layout.socket_path()
+        );
+    }
+
+    #[test]
+    fn test_prepare_links_short_sockets_dir() {
+        let home = tempfile::tempdir().unwrap();
+        let box_dir = home.path().join("d".repeat(120)).join("box");
+        let layout = BoxFilesystemLayout::new(box_dir.clone(), FsLayoutConfig::default(), false);
+        layout.prepare().unwrap();
+
+        let sockets_dir = layout.sockets_dir();
+        assert!(sockets_dir.is_dir());
+        assert_eq!(
+            std::fs::read_link(box_dir.join(dirs::SOCKETS_DIR)).unwrap(),
+            sockets_dir
+        );
+        let listener = std::os::unix::net::UnixListener::bind(layout.ready_socket_path());
+        assert!(listener.is_ok(), "{:?}", listener.err());
+
+        layout.cleanup().unwrap();
+        assert!(!sockets_dir.exists());
+        assert!(!box_dir.exists());
+    }
+
+    #[tokio::test]
+    async fn test_state_dir_holds_box_directories() {
+        use crate::{BoxCommand, BoxOptions, BoxliteOptions};
+
+        let state = tempfile::TempDir::new().unwrap();
+        let (home, runtime) = test_runtime(
+            BoxliteOptions {
+                state_dir: Some(state.path().to_path_buf()),
+                ..Default::default()
+            },
+            MockScript::new(),
+        );
+        let litebox = runtime.create(BoxOptions::default(), None).await.unwrap();
+        let mut execution = litebox.exec(BoxCommand::new("true")).await.unwrap();
+        assert!(execution.wait().await.unwrap().success());
+
+        let box_dir = state.path().join("boxes").join(litebox.id().as_str());
+        assert!(box_dir.is_dir());
+        assert!(
+            !home
+                .path()
+                .join("boxes")
+                .join(litebox.id().as_str())
+                .exists()
+        );
+        litebox.stop().await.unwrap();
+    }
 }
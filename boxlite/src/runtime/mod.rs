@@ -1,8 +1,15 @@
+pub(crate) mod admission;
+pub(crate) mod archive;
 pub mod constants;
+pub mod env;
+pub(crate) mod fd_budget;
 pub(crate) mod guest_rootfs;
 pub mod layout;
 pub(crate) mod lock;
 pub mod options;
+pub mod profile;
+pub(crate) mod reload;
+pub(crate) mod retention;
 pub(crate) mod signal_handler;
 pub mod types;
 
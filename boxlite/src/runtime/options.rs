@@ -5,7 +5,9 @@ use crate::runtime::layout::dirs as const_dirs;
 use boxlite_shared::errors::BoxliteResult;
 use dirs::home_dir;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
+use std::time::Duration;
 
 // ============================================================================
 // Security Options
@@ -114,6 +116,21 @@ pub struct SecurityOptions {
     /// Default: true (needed for gvproxy VM networking)
     #[serde(default = "default_network_enabled")]
     pub network_enabled: bool,
+
+    /// Allow FUSE filesystems inside the container.
+    ///
+    /// When true, the guest checks that its kernel supports FUSE, creates
+    /// `/dev/fuse`, and exposes it to the container together with
+    /// `CAP_SYS_ADMIN` so tools like sshfs and AppImages can mount.
+    /// Default: false
+    #[serde(default)]
+    pub allow_fuse: bool,
+
+    /// Linux capabilities of the container process.
+    ///
+    /// Default: Docker's default set (see [`DEFAULT_CAPABILITIES`])
+    #[serde(default)]
+    pub capabilities: CapabilityOptions,
 }
 
 /// Resource limits for the jailed process.
@@ -140,6 +157,153 @@ pub struct ResourceLimits {
     pub max_cpu_time: Option<u64>,
 }
 
+/// Capabilities granted to the container process by default.
+///
+/// Matches Docker's default set: enough for package managers, sudo and
+/// privileged ports, without kernel-level admin rights.
+pub const DEFAULT_CAPABILITIES: &[&str] = &[
+    "CAP_AUDIT_WRITE",
+    "CAP_CHOWN",
+    "CAP_DAC_OVERRIDE",
+    "CAP_FOWNER",
+    "CAP_FSETID",
+    "CAP_KILL",
+    "CAP_MKNOD",
+    "CAP_NET_BIND_SERVICE",
+    "CAP_NET_RAW",
+    "CAP_SETFCAP",
+    "CAP_SETGID",
+    "CAP_SETPCAP",
+    "CAP_SETUID",
+    "CAP_SYS_CHROOT",
+];
+
+/// Every capability the guest kernel knows about (CAP 0-40).
+pub const ALL_CAPABILITIES: &[&str] = &[
+    "CAP_CHOWN",
+    "CAP_DAC_OVERRIDE",
+    "CAP_DAC_READ_SEARCH",
+    "CAP_FOWNER",
+    "CAP_FSETID",
+    "CAP_KILL",
+    "CAP_SETGID",
+    "CAP_SETUID",
+    "CAP_SETPCAP",
+    "CAP_LINUX_IMMUTABLE",
+    "CAP_NET_BIND_SERVICE",
+    "CAP_NET_BROADCAST",
+    "CAP_NET_ADMIN",
+    "CAP_NET_RAW",
+    "CAP_IPC_LOCK",
+    "CAP_IPC_OWNER",
+    "CAP_SYS_MODULE",
+    "CAP_SYS_RAWIO",
+    "CAP_SYS_CHROOT",
+    "CAP_SYS_PTRACE",
+    "CAP_SYS_PACCT",
+    "CAP_SYS_ADMIN",
+    "CAP_SYS_BOOT",
+    "CAP_SYS_NICE",
+    "CAP_SYS_RESOURCE",
+    "CAP_SYS_TIME",
+    "CAP_SYS_TTY_CONFIG",
+    "CAP_MKNOD",
+    "CAP_LEASE",
+    "CAP_AUDIT_WRITE",
+    "CAP_AUDIT_CONTROL",
+    "CAP_SETFCAP",
+    "CAP_MAC_OVERRIDE",
+    "CAP_MAC_ADMIN",
+    "CAP_SYSLOG",
+    "CAP_WAKE_ALARM",
+    "CAP_BLOCK_SUSPEND",
+    "CAP_AUDIT_READ",
+    "CAP_PERFMON",
+    "CAP_BPF",
+    "CAP_CHECKPOINT_RESTORE",
+];
+
+/// Capability changes relative to [`DEFAULT_CAPABILITIES`].
+///
+/// Names are case-insensitive, with or without the `CAP_` prefix
+/// (`net_admin`, `CAP_NET_ADMIN`). `ALL` in `add` starts from every
+/// capability; `ALL` in `drop` starts from none.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapabilityOptions {
+    /// Capabilities to grant on top of the default set.
+    #[serde(default)]
+    pub add: Vec<String>,
+
+    /// Capabilities to remove from the default set.
+    #[serde(default)]
+    pub drop: Vec<String>,
+}
+
+impl CapabilityOptions {
+    /// Resolve to the effective capability set, sorted, in `CAP_NAME` form.
+    ///
+    /// Fails on unknown names, on `ALL` in both lists, and on a capability
+    /// that is both added and dropped.
+    pub fn resolve(&self) -> BoxliteResult<Vec<String>> {
+        let add = normalize_capabilities(&self.add)?;
+        let drop = normalize_capabilities(&self.drop)?;
+        let add_all = add.iter().any(|c| c == "ALL");
+        let drop_all = drop.iter().any(|c| c == "ALL");
+        if add_all && drop_all {
+            return Err(boxlite_shared::errors::BoxliteError::Config(
+                "capabilities: ALL cannot be both added and dropped".to_string(),
+            ));
+        }
+        if let Some(cap) = add.iter().find(|c| *c != "ALL" && drop.contains(c)) {
+            return Err(boxlite_shared::errors::BoxliteError::Config(format!(
+                "capabilities: {} is both added and dropped",
+                cap
+            )));
+        }
+
+        let base: &[&str] = if add_all {
+            ALL_CAPABILITIES
+        } else if drop_all {
+            &[]
+        } else {
+            DEFAULT_CAPABILITIES
+        };
+        let mut caps: std::collections::BTreeSet<String> = base
+            .iter()
+            .map(|c| c.to_string())
+            .filter(|c| !drop.contains(c))
+            .collect();
+        caps.extend(add.into_iter().filter(|c| c != "ALL"));
+        Ok(caps.into_iter().collect())
+    }
+}
+
+/// Canonicalize capability names to `CAP_NAME` (or `ALL`), rejecting unknown ones.
+fn normalize_capabilities(names: &[String]) -> BoxliteResult<Vec<String>> {
+    names
+        .iter()
+        .map(|name| {
+            let upper = name.trim().to_ascii_uppercase();
+            if upper == "ALL" {
+                return Ok(upper);
+            }
+            let cap = if upper.starts_with("CAP_") {
+                upper
+            } else {
+                format!("CAP_{}", upper)
+            };
+            if ALL_CAPABILITIES.contains(&cap.as_str()) {
+                Ok(cap)
+            } else {
+                Err(boxlite_shared::errors::BoxliteError::Config(format!(
+                    "unknown capability {:?}",
+                    name
+                )))
+            }
+        })
+        .collect()
+}
+
 // Default value functions for SecurityOptions
 
 fn default_jailer_enabled() -> bool {
@@ -198,6 +362,8 @@ impl Default for SecurityOptions {
             resource_limits: ResourceLimits::default(),
             sandbox_profile: None,
             network_enabled: default_network_enabled(),
+            allow_fuse: false,
+            capabilities: CapabilityOptions::default(),
         }
     }
 }
@@ -255,6 +421,19 @@ impl SecurityOptions {
         }
     }
 
+    /// Effective capabilities of the container process.
+    ///
+    /// Resolves [`CapabilityOptions`] and adds `CAP_SYS_ADMIN` when
+    /// `allow_fuse` is set, since mounting a FUSE filesystem needs it.
+    pub fn container_capabilities(&self) -> BoxliteResult<Vec<String>> {
+        let mut caps = self.capabilities.resolve()?;
+        if self.allow_fuse && !caps.iter().any(|c| c == "CAP_SYS_ADMIN") {
+            caps.push("CAP_SYS_ADMIN".to_string());
+            caps.sort();
+        }
+        Ok(caps)
+    }
+
     /// Check if current platform supports full jailer features.
     pub fn is_full_isolation_available() -> bool {
         cfg!(target_os = "linux")
@@ -484,6 +663,28 @@ impl SecurityOptionsBuilder {
         self
     }
 
+    // ─────────────────────────────────────────────────────────────────────
+    // Container settings
+    // ─────────────────────────────────────────────────────────────────────
+
+    /// Allow or disallow FUSE filesystems inside the container.
+    pub fn allow_fuse(&mut self, enabled: bool) -> &mut Self {
+        self.inner.allow_fuse = enabled;
+        self
+    }
+
+    /// Grant a capability to the container process (`ALL` for every one).
+    pub fn cap_add(&mut self, cap: impl Into<String>) -> &mut Self {
+        self.inner.capabilities.add.push(cap.into());
+        self
+    }
+
+    /// Remove a capability from the container process (`ALL` for every one).
+    pub fn cap_drop(&mut self, cap: impl Into<String>) -> &mut Self {
+        self.inner.capabilities.drop.push(cap.into());
+        self
+    }
+
     // ─────────────────────────────────────────────────────────────────────
     // Build
     // ─────────────────────────────────────────────────────────────────────
@@ -500,10 +701,32 @@ impl SecurityOptionsBuilder {
 /// Configuration options for BoxliteRuntime.
 ///
 /// Users can create it with defaults and modify fields as needed.
+/// Deserializing rejects unknown keys, so a misspelled config option is an
+/// error rather than silently ignored. `templates` are the exception: box
+/// options are also read back from stored boxes and stay permissive.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct BoxliteOptions {
     #[serde(default = "default_home_dir")]
     pub home_dir: PathBuf,
+
+    /// Where box state lives: each box's directory with its COW disks, and
+    /// the snapshots clones share.
+    ///
+    /// `None` (default) keeps it in `home_dir` (`{home_dir}/boxes`,
+    /// `{home_dir}/snapshots`). Boxes already created keep their directory.
+    #[serde(default)]
+    pub state_dir: Option<PathBuf>,
+
+    /// Where pulled image layers and the rootfs disks built from them are
+    /// cached. `None` (default) uses `{home_dir}/images`.
+    #[serde(default)]
+    pub image_cache_dir: Option<PathBuf>,
+
+    /// Where volumes created by boxlite (e.g. the CLI's anonymous volumes)
+    /// are stored. `None` (default) uses `{home_dir}/volumes`.
+    #[serde(default)]
+    pub volumes_dir: Option<PathBuf>,
     /// Registries to search for unqualified image references.
     ///
     /// When pulling an image without a registry prefix (e.g., `"alpine"`),
@@ -527,207 +750,1734 @@ pub struct BoxliteOptions {
     /// ```
     #[serde(default)]
     pub image_registries: Vec<String>,
-}
 
-fn default_home_dir() -> PathBuf {
-    std::env::var(const_envs::BOXLITE_HOME)
-        .map(PathBuf::from)
-        .unwrap_or_else(|_| {
-            let mut path = home_dir().unwrap_or_else(|| PathBuf::from("."));
-            path.push(const_dirs::BOXLITE_DIR);
-            path
-        })
-}
+    /// Short names pulled from one fully qualified repository instead of
+    /// being searched in `image_registries`, like the `[aliases]` table of
+    /// containers-registries.conf.
+    ///
+    /// Keys are unqualified names (`"ubuntu"`, `"internal/app"`); values
+    /// are repositories with a registry and no tag or digest. The requested
+    /// tag or digest is kept, and local tags still take precedence.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// BoxliteOptions {
+    ///     short_name_aliases: HashMap::from([(
+    ///         "internal/app".to_string(),
+    ///         "registry.corp/app".to_string(),
+    ///     )]),
+    ///     ..Default::default()
+    /// }
+    /// // "internal/app:v2" → registry.corp/app:v2 only
+    /// ```
+    #[serde(default)]
+    pub short_name_aliases: HashMap<String, String>,
 
-impl Default for BoxliteOptions {
-    fn default() -> Self {
-        Self {
-            home_dir: default_home_dir(),
-            image_registries: Vec::new(),
-        }
-    }
-}
+    /// Mirrors for upstream registries, tried in order before the upstream.
+    ///
+    /// Keys are upstream registries (e.g. `"docker.io"`); values are mirror
+    /// endpoints (`host[:port][/prefix]`, with `http://` for plain-HTTP
+    /// mirrors such as `boxlite registry-cache serve`). If every mirror
+    /// fails, the image is pulled from the upstream registry.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// BoxliteOptions {
+    ///     registry_mirrors: HashMap::from([(
+    ///         "docker.io".to_string(),
+    ///         vec!["mirror.internal".to_string()],
+    ///     )]),
+    ///     ..Default::default()
+    /// }
+    /// // "alpine" → tries mirror.internal/library/alpine, then docker.io
+    /// ```
+    #[serde(default)]
+    pub registry_mirrors: HashMap<String, Vec<String>>,
 
-/// Options used when constructing a box.
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
-pub struct BoxOptions {
-    pub cpus: Option<u8>,
-    pub memory_mib: Option<u32>,
-    /// Disk size in GB for the container rootfs (sparse, grows as needed).
+    /// Per-registry connection settings, keyed by registry host (`host[:port]`).
     ///
-    /// The actual disk will be at least as large as the base image.
-    /// If set, the COW overlay will have this virtual size, allowing
-    /// the container to write more data than the base image size.
-    pub disk_size_gb: Option<u64>,
-    pub working_dir: Option<String>,
-    pub env: Vec<(String, String)>,
-    pub rootfs: RootfsSpec,
-    pub volumes: Vec<VolumeSpec>,
-    pub network: NetworkSpec,
-    pub ports: Vec<PortSpec>,
-    /// Enable bind mount isolation for the shared mounts directory.
+    /// Applies to pulls, pushes and mirrors alike, e.g. to trust a private CA:
     ///
-    /// When true, creates a read-only bind mount from `mounts/` to `shared/`,
-    /// preventing the guest from modifying host-prepared files.
+    /// ```ignore
+    /// BoxliteOptions {
+    ///     registries: HashMap::from([(
+    ///         "registry.corp:5000".to_string(),
+    ///         RegistryConfig {
+    ///             ca_file: Some("/etc/ssl/corp-ca.pem".into()),
+    ///             ..Default::default()
+    ///         },
+    ///     )]),
+    ///     ..Default::default()
+    /// }
+    /// ```
+    #[serde(default)]
+    pub registries: HashMap<String, RegistryConfig>,
+
+    /// Bandwidth limit for image layer downloads, in megabits per second.
     ///
-    /// Requires CAP_SYS_ADMIN (privileged) or FUSE (rootless) on Linux.
-    /// Defaults to false.
+    /// Shared by all concurrent pulls of this runtime. `None` means unlimited.
     #[serde(default)]
-    pub isolate_mounts: bool,
+    pub pull_rate_limit_mbps: Option<u64>,
 
-    /// Automatically remove box when stopped.
+    /// Shared cache for image layers, e.g. one bucket for all CI runners.
     ///
-    /// When true (default), the box is removed from the database and its
-    /// files are deleted when `stop()` is called. This is similar to
-    /// Docker's `--rm` flag.
+    /// Layers missing locally are looked up in the cache before the
+    /// registry; with `write_back`, layers pulled from a registry are
+    /// uploaded to it. Cached layers are verified against their digest like
+    /// registry downloads. `None` (default) disables it.
+    #[serde(default)]
+    pub blob_cache: Option<BlobCacheOptions>,
+
+    /// Dedup identical files across extracted image layers.
     ///
-    /// When false, the box is preserved after stop and can be restarted
-    /// with `runtime.get(box_id)`.
-    #[serde(default = "default_auto_remove")]
-    pub auto_remove: bool,
+    /// Each newly extracted layer is hashed file by file; content already seen
+    /// in another layer is hardlinked (or reflinked, when metadata differs)
+    /// to a single object under `images/content`. Saves disk when many
+    /// images share a base, at the cost of hashing on first extraction.
+    /// Layers extracted earlier are not rewritten. `false` (default) disables it.
+    #[serde(default)]
+    pub dedup_layers: bool,
 
-    /// Whether the box should continue running when the parent process exits.
+    /// Maximum number of boxes that may hold a runtime reservation at once.
     ///
-    /// When false (default), the box will automatically stop when the process
-    /// that created it exits. This ensures orphan boxes don't accumulate.
-    /// Similar to running a process in the foreground.
+    /// A reservation is taken when a box starts and released when it stops
+    /// or is removed. `None` means unlimited.
+    #[serde(default)]
+    pub max_running_boxes: Option<usize>,
+
+    /// Maximum total guest memory (MiB) across reserved boxes.
     ///
-    /// When true, the box runs independently and survives parent process exit.
-    /// The box can be reattached using `runtime.get(box_id)`. Similar to
-    /// Docker's `-d` (detach) flag.
-    #[serde(default = "default_detach")]
-    pub detach: bool,
+    /// Each box counts its `memory_mib` (or the engine default). `None` means unlimited.
+    #[serde(default)]
+    pub max_total_memory_mib: Option<u64>,
 
-    /// Security isolation options for the box.
+    /// Behavior when a request exceeds `max_running_boxes` or `max_total_memory_mib`.
+    #[serde(default)]
+    pub admission_policy: AdmissionPolicy,
+
+    /// Drop guest caches in idle boxes.
     ///
-    /// Controls how the boxlite-shim process is isolated from the host.
-    /// Different presets are available: `SecurityOptions::development()`,
-    /// `SecurityOptions::standard()`, `SecurityOptions::maximum()`.
+    /// The guest drops its page cache and compacts free memory, which
+    /// leaves room for its next workload. This does not shrink the VM:
+    /// host memory use stays the same.
+    ///
+    /// `None` (default) disables cache reclaim.
     #[serde(default)]
-    pub security: SecurityOptions,
+    pub cache_reclaim: Option<CacheReclaimPolicy>,
 
-    /// Override the image's ENTRYPOINT directive.
+    /// HTTP(S) proxy for registry traffic, optionally propagated into boxes.
     ///
-    /// When set, completely replaces the image's ENTRYPOINT.
-    /// Use with `cmd` to build the full command:
-    ///   Final execution = entrypoint + cmd
+    /// Unset fields fall back to `HTTP_PROXY`, `HTTPS_PROXY` and `NO_PROXY`
+    /// (upper- or lowercase) from the runtime's environment.
+    #[serde(default)]
+    pub proxy: ProxyOptions,
+
+    /// Extra attempts for transient failures while creating or starting a box.
     ///
-    /// Example: For `docker:dind`, bypass the failing entrypoint script:
-    ///   `entrypoint = vec!["dockerd"]`, `cmd = vec!["--iptables=false"]`
+    /// Applies only to the image pull and the wait for the guest agent; a
+    /// failure is retried when `BoxliteError::is_transient()` holds (registry
+    /// connection errors, 5xx, guest boot timeouts). Attempts back off
+    /// exponentially from 1s up to 30s. `0` (default) disables retries.
     #[serde(default)]
-    pub entrypoint: Option<Vec<String>>,
+    pub create_retries: u32,
 
-    /// Override the image's CMD directive.
+    /// Guest agent liveness checks for running boxes.
     ///
-    /// The image ENTRYPOINT is preserved; these args replace the image's CMD.
-    /// Final execution = image_entrypoint + cmd.
+    /// Enabled by default; `None` disables heartbeats.
+    #[serde(default = "default_heartbeat")]
+    pub heartbeat: Option<HeartbeatPolicy>,
+
+    /// Record CPU, memory and network usage of running boxes in the database.
     ///
-    /// Example: For `docker:dind` (ENTRYPOINT=["dockerd-entrypoint.sh"]),
-    /// setting `cmd = vec!["--iptables=false"]` produces:
-    /// `["dockerd-entrypoint.sh", "--iptables=false"]`
+    /// Read back with `LiteBox::metrics_history`, also after the box stopped.
+    /// `None` (default) disables sampling.
     #[serde(default)]
-    pub cmd: Option<Vec<String>>,
+    pub metrics_history: Option<MetricsHistoryPolicy>,
 
-    /// Username or UID (format: <name|uid>[:<group|gid>]).
-    /// If None, uses the image's USER directive (defaults to root).
+    /// Custom guest rootfs to boot boxes from instead of the bundled init image.
+    ///
+    /// Either a directory or an ext4 disk image. It must contain the guest
+    /// agent at `/boxlite/bin/boxlite-guest`; its protocol version is checked
+    /// against the host when each box starts. A directory is packed into a
+    /// cached disk image, rebuilt when the directory or the agent changes.
+    /// `None` (default) uses the bundled init image.
     #[serde(default)]
-    pub user: Option<String>,
-}
+    pub guest_rootfs_path: Option<PathBuf>,
 
-fn default_auto_remove() -> bool {
-    true
-}
+    /// Filesystem of the cached per-image disk that container rootfs use.
+    ///
+    /// `Ext4` (default) builds a writable image that each box clones.
+    /// `Erofs` and `Squashfs` build a compressed read-only image instead
+    /// (needs `mkfs.erofs` or `mksquashfs` on the host); each box mounts it
+    /// under overlayfs with its own writable ext4 upper disk, which is faster
+    /// to prepare and much smaller on disk. Only affects boxes created
+    /// afterwards; existing boxes keep the format they were created with.
+    #[serde(default)]
+    pub rootfs_format: RootfsFormat,
 
-fn default_detach() -> bool {
-    false
+    /// Named box option presets, e.g. one per service a host runs.
+    ///
+    /// Their images can be warmed ahead of the first `create()` with
+    /// `BoxliteRuntime::prefetch(options.template_images())`.
+    #[serde(default)]
+    pub templates: BTreeMap<String, BoxOptions>,
+
+    /// Limits on the host resources all boxes may use together.
+    ///
+    /// For shared machines (e.g. CI runners) where boxlite must not starve
+    /// other work. `None` (default) leaves the host cgroup as it is.
+    #[serde(default)]
+    pub host_limits: Option<HostLimits>,
+
+    /// Automatic pruning of stopped boxes and unused images.
+    ///
+    /// A background task applies the policy every `interval_secs`: boxes
+    /// stopped for longer than `max_stopped_box_age_secs` are removed, then
+    /// the least recently used images are evicted while the image cache is
+    /// over `max_image_cache_mib`. Images used by a box or carrying a local
+    /// tag are never evicted. `None` (default) disables pruning.
+    #[serde(default)]
+    pub retention: Option<RetentionPolicy>,
+
+    /// Host the network backends of all boxes in one long-lived daemon
+    /// instead of one per box, which saves the memory of a network stack
+    /// per box.
+    ///
+    /// The runtime starts the daemon (`boxlite-shim --network-daemon`),
+    /// restarts it if it dies, and leaves it running on exit for detached
+    /// boxes. Each box keeps its own isolated virtual network. Boxes started
+    /// while the daemon is unavailable get a per-box backend. Boxes attached
+    /// to a daemon that dies stay offline and fail their heartbeats until
+    /// restarted, by `HeartbeatPolicy::restart` or by hand. Unlike a
+    /// per-box backend, the daemon runs outside the box sandbox.
+    /// `false` (default) disables it.
+    #[serde(default)]
+    pub shared_network: bool,
 }
 
-impl Default for BoxOptions {
-    fn default() -> Self {
-        Self {
-            cpus: None,
-            memory_mib: None,
-            disk_size_gb: None,
-            working_dir: None,
-            env: Vec::new(),
-            rootfs: RootfsSpec::default(),
-            volumes: Vec::new(),
-            network: NetworkSpec::default(),
-            ports: Vec::new(),
-            isolate_mounts: false,
-            auto_remove: default_auto_remove(),
-            detach: default_detach(),
-            security: SecurityOptions::default(),
-            entrypoint: None,
-            cmd: None,
-            user: None,
+impl BoxliteOptions {
+    /// Image references used by `templates`, deduplicated, in template name order.
+    pub fn template_images(&self) -> Vec<String> {
+        let mut images: Vec<String> = Vec::new();
+        for options in self.templates.values() {
+            if let RootfsSpec::Image(image) = &options.rootfs
+                && !images.contains(image)
+            {
+                images.push(image.clone());
+            }
         }
+        images
     }
 }
 
-impl BoxOptions {
-    /// Sanitize and validate options.
+fn default_heartbeat() -> Option<HeartbeatPolicy> {
+    Some(HeartbeatPolicy::default())
+}
+
+/// What starting a box does when runtime limits are exceeded.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdmissionPolicy {
+    /// Return `BoxliteError::ResourceExhausted` immediately.
+    #[default]
+    FailFast,
+    /// Wait for capacity to be released, up to `timeout_secs` (None = forever).
+    Queue {
+        #[serde(default)]
+        timeout_secs: Option<u64>,
+    },
+}
+
+/// Limits on boxlite's aggregate host footprint (see `BoxliteOptions::host_limits`).
+///
+/// On Linux every box's shim and VMM processes run in a cgroup v2 under a
+/// common `boxlite` cgroup; `cpus`, `memory_mib` and `cpu_weight` are set on
+/// that parent, so they bound all boxes of all runtimes of the user
+/// together. The limits stay on the cgroup after the runtime exits; set
+/// `HostLimits::default()` to lift them. macOS has no cgroups: there any
+/// `host_limits` runs box processes at background priority instead, the
+/// QoS `taskpolicy -b` uses, which throttles their CPU and disk I/O.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HostLimits {
+    /// CPU time for all boxes together, in CPUs (e.g. `2.5`). Linux only.
+    pub cpus: Option<f64>,
+    /// Memory for all box processes together, in MiB. Linux only.
     ///
-    /// Validates option combinations:
-    /// - `auto_remove=true` with `detach=true` is invalid (detached boxes need manual lifecycle control)
-    /// - `isolate_mounts=true` is only supported on Linux
-    pub fn sanitize(&self) -> BoxliteResult<()> {
-        // Validate auto_remove + detach combination
-        // A detached box that auto-removes doesn't make practical sense:
-        // - detach=true: box survives parent exit
-        // - auto_remove=true: box removed on stop
-        // This combination is confusing - detached boxes should have manual lifecycle control
-        if self.auto_remove && self.detach {
+    /// Guest RAM counts as it is touched, so a box that needs more than is
+    /// left is OOM-killed on the host.
+    pub memory_mib: Option<u64>,
+    /// CPU share against the host's other cgroups (1-10000, kernel default
+    /// 100). Linux only.
+    pub cpu_weight: Option<u32>,
+    /// Run box processes at the lowest CPU priority (nice 19 on Linux;
+    /// always on for macOS).
+    pub background: bool,
+}
+
+impl HostLimits {
+    /// Whether a limit needs a host cgroup (Linux).
+    pub fn needs_cgroup(&self) -> bool {
+        self.cpus.is_some() || self.memory_mib.is_some() || self.cpu_weight.is_some()
+    }
+
+    /// Check the limits are in range.
+    pub fn validate(&self) -> BoxliteResult<()> {
+        if let Some(cpus) = self.cpus
+            && !(cpus.is_finite() && cpus > 0.0)
+        {
+            return Err(boxlite_shared::errors::BoxliteError::Config(format!(
+                "host_limits.cpus must be positive, got {}",
+                cpus
+            )));
+        }
+        if self.memory_mib == Some(0) {
             return Err(boxlite_shared::errors::BoxliteError::Config(
-                "auto_remove=true is incompatible with detach=true. \
-                 Detached boxes should use auto_remove=false for manual lifecycle control."
-                    .to_string(),
+                "host_limits.memory_mib must be positive".into(),
             ));
         }
-
-        #[cfg(not(target_os = "linux"))]
-        if self.isolate_mounts {
-            return Err(boxlite_shared::errors::BoxliteError::Unsupported(
-                "isolate_mounts is only supported on Linux".to_string(),
-            ));
+        if let Some(weight) = self.cpu_weight
+            && !(1..=10000).contains(&weight)
+        {
+            return Err(boxlite_shared::errors::BoxliteError::Config(format!(
+                "host_limits.cpu_weight must be between 1 and 10000, got {}",
+                weight
+            )));
         }
         Ok(())
     }
 }
 
-/// How to populate the box root filesystem.
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
-pub enum RootfsSpec {
-    /// Pull/resolve this registry image reference.
-    Image(String),
-    /// Use an already prepared rootfs at the given host path.
-    RootfsPath(String),
+/// Host scheduling priority of a box (see `BoxOptions::priority`).
+///
+/// Each level sets the `cpu.weight` of the box's cgroup (Linux), which
+/// splits CPU time between the vCPU threads of competing boxes, the nice
+/// value of its shim and VMM process, and its I/O priority.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BoxPriority {
+    /// Batch work: a quarter of the CPU weight, nice 10, idle I/O class.
+    Low,
+    /// The kernel defaults.
+    #[default]
+    Normal,
+    /// Interactive work: four times the CPU weight, nice -5, top
+    /// best-effort I/O priority.
+    High,
 }
 
-impl Default for RootfsSpec {
-    fn default() -> Self {
-        Self::Image("alpine:latest".into())
+impl BoxPriority {
+    /// cgroup v2 `cpu.weight` of the box (kernel default 100).
+    pub fn cpu_weight(self) -> u32 {
+        match self {
+            Self::Low => 25,
+            Self::Normal => 100,
+            Self::High => 400,
+        }
     }
-}
 
-/// Filesystem mount specification.
-#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
-pub struct VolumeSpec {
-    pub host_path: String,
-    pub guest_path: String,
-    pub read_only: bool,
+    /// Nice value of the box's shim and VMM process.
+    pub fn nice(self) -> i32 {
+        match self {
+            Self::Low => 10,
+            Self::Normal => 0,
+            Self::High => -5,
+        }
+    }
 }
 
-/// Network isolation options.
-#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
-pub enum NetworkSpec {
+/// Filesystem of the per-image rootfs disk (see `BoxliteOptions::rootfs_format`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RootfsFormat {
+    /// Writable ext4 image, cloned per box as a qcow2 overlay.
     #[default]
-    Isolated,
-    // Host,
-    // Custom(String),
+    Ext4,
+    /// Compressed read-only EROFS image with a per-box overlayfs upper disk.
+    Erofs,
+    /// Compressed read-only SquashFS image with a per-box overlayfs upper disk.
+    Squashfs,
 }
 
+impl RootfsFormat {
+    /// Whether the image is read-only and needs a separate upper disk.
+    pub fn is_read_only(&self) -> bool {
+        !matches!(self, Self::Ext4)
+    }
+}
+
+/// Compression of exec stdout/stderr on the host-guest channel.
+///
+/// Negotiated per execution: a guest without compression support answers
+/// with `None` and output is sent raw.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecCompression {
+    /// Send output uncompressed.
+    #[default]
+    None,
+    /// gzip (fast level); smaller CPU footprint in the guest than ratio.
+    Gzip,
+    /// zstd (level 3); better ratio and throughput than gzip.
+    Zstd,
+}
+
+/// When to drop the guest caches of an idle box.
+///
+/// A box is idle when no exec or file copy has started for `idle_threshold_secs`.
+/// Reclaiming asks the guest to free its page cache once per idle period;
+/// see `BoxliteOptions::cache_reclaim`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CacheReclaimPolicy {
+    /// Seconds without activity before a box is considered idle.
+    #[serde(default = "default_cache_reclaim_idle_threshold_secs")]
+    pub idle_threshold_secs: u64,
+    /// How often idle boxes are checked, in seconds.
+    #[serde(default = "default_cache_reclaim_check_interval_secs")]
+    pub check_interval_secs: u64,
+}
+
+fn default_cache_reclaim_idle_threshold_secs() -> u64 {
+    300
+}
+
+fn default_cache_reclaim_check_interval_secs() -> u64 {
+    30
+}
+
+impl Default for CacheReclaimPolicy {
+    fn default() -> Self {
+        Self {
+            idle_threshold_secs: default_cache_reclaim_idle_threshold_secs(),
+            check_interval_secs: default_cache_reclaim_check_interval_secs(),
+        }
+    }
+}
+
+/// How running boxes are checked for a live guest agent.
+///
+/// The host pings the guest agent every `interval_secs`. After
+/// `failure_threshold` consecutive missed pings the box is marked unhealthy
+/// (see [`BoxInfo::health`](crate::BoxInfo::health)) and new execs fail fast
+/// instead of hanging.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HeartbeatPolicy {
+    /// Seconds between pings.
+    #[serde(default = "default_heartbeat_interval_secs")]
+    pub interval_secs: u64,
+    /// Seconds to wait for a ping reply before counting it as missed.
+    #[serde(default = "default_heartbeat_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Consecutive missed pings before the box is unhealthy.
+    #[serde(default = "default_heartbeat_failure_threshold")]
+    pub failure_threshold: u32,
+    /// Stop and start an unhealthy box. Ignored for `auto_remove` boxes.
+    #[serde(default)]
+    pub restart: bool,
+}
+
+fn default_heartbeat_interval_secs() -> u64 {
+    10
+}
+
+fn default_heartbeat_timeout_secs() -> u64 {
+    5
+}
+
+fn default_heartbeat_failure_threshold() -> u32 {
+    3
+}
+
+impl Default for HeartbeatPolicy {
+    fn default() -> Self {
+        Self {
+            interval_secs: default_heartbeat_interval_secs(),
+            timeout_secs: default_heartbeat_timeout_secs(),
+            failure_threshold: default_heartbeat_failure_threshold(),
+            restart: false,
+        }
+    }
+}
+
+/// How often running boxes are sampled for `BoxliteOptions::metrics_history`.
+///
+/// Each box keeps its newest `max_samples` samples; older ones are dropped
+/// as new ones are recorded. The defaults keep 24 hours at one sample every
+/// 10 seconds. Samples are removed with their box.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MetricsHistoryPolicy {
+    /// Seconds between samples.
+    #[serde(default = "default_metrics_history_interval_secs")]
+    pub interval_secs: u64,
+    /// Samples kept per box.
+    #[serde(default = "default_metrics_history_max_samples")]
+    pub max_samples: u32,
+}
+
+fn default_metrics_history_interval_secs() -> u64 {
+    10
+}
+
+fn default_metrics_history_max_samples() -> u32 {
+    8640
+}
+
+impl Default for MetricsHistoryPolicy {
+    fn default() -> Self {
+        Self {
+            interval_secs: default_metrics_history_interval_secs(),
+            max_samples: default_metrics_history_max_samples(),
+        }
+    }
+}
+
+/// Limits enforced by the background reaper (see `BoxliteOptions::retention`).
+///
+/// Each limit is off when unset; a policy with neither only runs empty passes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RetentionPolicy {
+    /// Largest size of the image cache (blobs, extracted layers and disk
+    /// images), in MiB.
+    #[serde(default)]
+    pub max_image_cache_mib: Option<u64>,
+    /// Seconds a box may stay stopped before it is removed.
+    #[serde(default)]
+    pub max_stopped_box_age_secs: Option<u64>,
+    /// Seconds between pruning passes.
+    #[serde(default = "default_retention_interval_secs")]
+    pub interval_secs: u64,
+}
+
+fn default_retention_interval_secs() -> u64 {
+    300
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            max_image_cache_mib: None,
+            max_stopped_box_age_secs: None,
+            interval_secs: default_retention_interval_secs(),
+        }
+    }
+}
+
+impl RetentionPolicy {
+    /// Check the policy is usable.
+    pub fn validate(&self) -> BoxliteResult<()> {
+        if self.interval_secs == 0 {
+            return Err(boxlite_shared::errors::BoxliteError::Config(
+                "retention.interval_secs must be positive".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Connection settings for a single registry.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RegistryConfig {
+    /// Skip TLS certificate verification.
+    #[serde(default)]
+    pub insecure: bool,
+    /// Use plain HTTP instead of HTTPS.
+    #[serde(default)]
+    pub plain_http: bool,
+    /// PEM file with additional CA certificates to trust for this registry.
+    #[serde(default)]
+    pub ca_file: Option<PathBuf>,
+}
+
+/// Remote layer cache shared between hosts (see `BoxliteOptions::blob_cache`).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BlobCacheOptions {
+    pub backend: BlobCacheBackend,
+    /// Upload layers pulled from a registry to the cache.
+    #[serde(default)]
+    pub write_back: bool,
+}
+
+/// Where a blob cache keeps layers, at `blobs/sha256/{hex}` under its root.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BlobCacheBackend {
+    /// HTTP(S) server answering `GET`, `HEAD` and (for write-back) `PUT`.
+    Http {
+        /// Root URL, e.g. `https://cache.corp/boxlite`.
+        url: String,
+        /// Headers sent with every request, e.g. `Authorization`.
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+    },
+    /// Amazon S3 or an S3-compatible store, addressed path-style.
+    ///
+    /// Credentials come from `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and
+    /// `AWS_SESSION_TOKEN`; without them requests are anonymous.
+    S3 {
+        bucket: String,
+        /// Key prefix inside the bucket, e.g. `ci/`.
+        #[serde(default)]
+        prefix: String,
+        #[serde(default = "default_s3_region")]
+        region: String,
+        /// Endpoint for S3-compatible stores (MinIO, R2, ...).
+        /// Defaults to `https://s3.{region}.amazonaws.com`.
+        #[serde(default)]
+        endpoint: Option<String>,
+    },
+}
+
+fn default_s3_region() -> String {
+    "us-east-1".to_string()
+}
+
+impl BlobCacheOptions {
+    /// Check URLs and names before the runtime uses them.
+    pub fn validate(&self) -> BoxliteResult<()> {
+        let url = match &self.backend {
+            BlobCacheBackend::Http { url, .. } => Some(url),
+            BlobCacheBackend::S3 {
+                bucket, endpoint, ..
+            } => {
+                if bucket.is_empty() || bucket.contains('/') {
+                    return Err(boxlite_shared::errors::BoxliteError::Config(format!(
+                        "invalid blob cache bucket '{}'",
+                        bucket
+                    )));
+                }
+                endpoint.as_ref()
+            }
+        };
+        if let Some(url) = url
+            && !(url.starts_with("http://") || url.starts_with("https://"))
+        {
+            return Err(boxlite_shared::errors::BoxliteError::Config(format!(
+                "blob cache URL must start with http:// or https://: {}",
+                url
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Proxy settings for outbound HTTP(S) traffic.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProxyOptions {
+    /// Proxy URL for plain-HTTP requests (e.g. `http://proxy.corp:3128`).
+    #[serde(default)]
+    pub http_proxy: Option<String>,
+    /// Proxy URL for HTTPS requests.
+    #[serde(default)]
+    pub https_proxy: Option<String>,
+    /// Comma-separated hosts, domains or CIDRs that bypass the proxy.
+    #[serde(default)]
+    pub no_proxy: Option<String>,
+    /// Set the proxy variables in the environment of newly created boxes.
+    ///
+    /// Variables the box sets explicitly are left untouched. The proxy must be
+    /// reachable from the guest network; loopback addresses are not.
+    #[serde(default)]
+    pub propagate_to_boxes: bool,
+}
+
+impl ProxyOptions {
+    /// Fill unset fields from the process environment.
+    pub fn resolve(&self) -> Self {
+        self.resolve_with(|name| std::env::var(name).ok())
+    }
+
+    fn resolve_with(&self, lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let from_env = |name: &str| {
+            lookup(name)
+                .or_else(|| lookup(&name.to_lowercase()))
+                .filter(|value| !value.is_empty())
+        };
+        Self {
+            http_proxy: self.http_proxy.clone().or_else(|| from_env("HTTP_PROXY")),
+            https_proxy: self.https_proxy.clone().or_else(|| from_env("HTTPS_PROXY")),
+            no_proxy: self.no_proxy.clone().or_else(|| from_env("NO_PROXY")),
+            propagate_to_boxes: self.propagate_to_boxes,
+        }
+    }
+
+    /// Environment variables describing this proxy, in both upper- and lowercase.
+    pub fn env_vars(&self) -> Vec<(String, String)> {
+        let mut vars = Vec::new();
+        for (name, value) in [
+            ("HTTP_PROXY", &self.http_proxy),
+            ("HTTPS_PROXY", &self.https_proxy),
+            ("NO_PROXY", &self.no_proxy),
+        ] {
+            if let Some(value) = value {
+                vars.push((name.to_string(), value.clone()));
+                vars.push((name.to_lowercase(), value.clone()));
+            }
+        }
+        vars
+    }
+}
+
+fn default_home_dir() -> PathBuf {
+    std::env::var(const_envs::BOXLITE_HOME)
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| {
+            let mut path = home_dir().unwrap_or_else(|| PathBuf::from("."));
+            path.push(const_dirs::BOXLITE_DIR);
+            path
+        })
+}
+
+impl Default for BoxliteOptions {
+    fn default() -> Self {
+        Self {
+            home_dir: default_home_dir(),
+            state_dir: None,
+            image_cache_dir: None,
+            volumes_dir: None,
+            image_registries: Vec::new(),
+            short_name_aliases: HashMap::new(),
+            registry_mirrors: HashMap::new(),
+            registries: HashMap::new(),
+            pull_rate_limit_mbps: None,
+            blob_cache: None,
+            dedup_layers: false,
+            max_running_boxes: None,
+            max_total_memory_mib: None,
+            admission_policy: AdmissionPolicy::default(),
+            cache_reclaim: None,
+            proxy: ProxyOptions::default(),
+            create_retries: 0,
+            heartbeat: default_heartbeat(),
+            metrics_history: None,
+            guest_rootfs_path: None,
+            rootfs_format: RootfsFormat::default(),
+            templates: BTreeMap::new(),
+            host_limits: None,
+            retention: None,
+            shared_network: false,
+        }
+    }
+}
+
+/// Options used when constructing a box.
+///
+/// Fields missing when deserializing take their `Default` values, so config
+/// templates only need to list what they change.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct BoxOptions {
+    pub cpus: Option<u8>,
+    pub memory_mib: Option<u32>,
+    /// Disk size in GB for the container rootfs (sparse, grows as needed).
+    ///
+    /// The actual disk will be at least as large as the base image.
+    /// If set, the COW overlay will have this virtual size, allowing
+    /// the container to write more data than the base image size.
+    pub disk_size_gb: Option<u64>,
+    /// Working directory of the container (absolute), replacing the image's
+    /// WORKDIR; created if missing. Commands run here unless
+    /// `BoxCommand::working_dir` says otherwise.
+    pub working_dir: Option<String>,
+    pub env: Vec<(String, String)>,
+    /// Host variables to copy into the box when it is created, as names in
+    /// which `*` matches any run of characters (e.g. `AWS_*`).
+    ///
+    /// Variables set in `env` take precedence. Values are captured once, at
+    /// creation; restarts keep them.
+    #[serde(default)]
+    pub env_passthrough: Vec<String>,
+    /// Timezone of the box: `"host"` for the host's zone, or an IANA name
+    /// such as `"Europe/Berlin"`.
+    ///
+    /// Sets `TZ` (unless `env` does) and mounts the zone's file from the
+    /// container image, or the guest's, at `/etc/localtime`. `"host"` is
+    /// resolved once, at creation. `None` leaves the image default (UTC).
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// Locale of the box, e.g. `"en_US.UTF-8"`; sets `LANG` unless `env` does.
+    #[serde(default)]
+    pub locale: Option<String>,
+    pub rootfs: RootfsSpec,
+    pub volumes: Vec<VolumeSpec>,
+    /// In-memory tmpfs mounts inside the container. Contents live in guest
+    /// RAM and are lost when the box stops.
+    #[serde(default)]
+    pub tmpfs: Vec<TmpfsSpec>,
+    /// Size of the container's /dev/shm in MiB (`None` = 64 MiB).
+    #[serde(default)]
+    pub shm_size_mib: Option<u32>,
+    pub network: NetworkSpec,
+    pub ports: Vec<PortSpec>,
+    /// Publish every TCP port the image exposes to a free host port, like
+    /// `docker run -P`. Without it, exposed ports are forwarded 1:1 and not
+    /// recorded as published ports.
+    #[serde(default)]
+    pub publish_all: bool,
+    /// Move a requested host port that is already taken to the next free
+    /// port above it at start, instead of failing with `PortInUse`.
+    #[serde(default)]
+    pub publish_retry: bool,
+    /// Enable bind mount isolation for the shared mounts directory.
+    ///
+    /// When true, creates a read-only bind mount from `mounts/` to `shared/`,
+    /// preventing the guest from modifying host-prepared files.
+    ///
+    /// Requires CAP_SYS_ADMIN (privileged) or FUSE (rootless) on Linux.
+    /// Defaults to false.
+    #[serde(default)]
+    pub isolate_mounts: bool,
+
+    /// Automatically remove box when stopped.
+    ///
+    /// When true (default), the box is removed from the database and its
+    /// files are deleted when `stop()` is called. This is similar to
+    /// Docker's `--rm` flag.
+    ///
+    /// When false, the box is preserved after stop and can be restarted
+    /// with `runtime.get(box_id)`.
+    ///
+    /// A detached box may stop while no process holds it, e.g. when its main
+    /// command (see `BoxCommand::stop_box_on_exit`) exits. It is then
+    /// removed by the next runtime that opens the same home directory.
+    #[serde(default = "default_auto_remove")]
+    pub auto_remove: bool,
+
+    /// Whether the box should continue running when the parent process exits.
+    ///
+    /// When false (default), the box will automatically stop when the process
+    /// that created it exits. This ensures orphan boxes don't accumulate.
+    /// Similar to running a process in the foreground.
+    ///
+    /// When true, the box runs independently and survives parent process exit.
+    /// The box can be reattached using `runtime.get(box_id)`. Similar to
+    /// Docker's `-d` (detach) flag.
+    #[serde(default = "default_detach")]
+    pub detach: bool,
+
+    /// Security isolation options for the box.
+    ///
+    /// Controls how the boxlite-shim process is isolated from the host.
+    /// Different presets are available: `SecurityOptions::development()`,
+    /// `SecurityOptions::standard()`, `SecurityOptions::maximum()`.
+    #[serde(default)]
+    pub security: SecurityOptions,
+
+    /// Override the image's ENTRYPOINT directive.
+    ///
+    /// When set, completely replaces the image's ENTRYPOINT and, as with
+    /// `docker run --entrypoint`, drops the image's CMD. Use with `cmd` to
+    /// build the full command:
+    ///   Final execution = entrypoint + cmd
+    ///
+    /// An empty vector clears the image's ENTRYPOINT and runs its CMD.
+    ///
+    /// Example: For `docker:dind`, bypass the failing entrypoint script:
+    ///   `entrypoint = vec!["dockerd"]`, `cmd = vec!["--iptables=false"]`
+    #[serde(default)]
+    pub entrypoint: Option<Vec<String>>,
+
+    /// Override the image's CMD directive.
+    ///
+    /// The image ENTRYPOINT is preserved; these args replace the image's CMD.
+    /// Final execution = image_entrypoint + cmd.
+    ///
+    /// Example: For `docker:dind` (ENTRYPOINT=["dockerd-entrypoint.sh"]),
+    /// setting `cmd = vec!["--iptables=false"]` produces:
+    /// `["dockerd-entrypoint.sh", "--iptables=false"]`
+    #[serde(default)]
+    pub cmd: Option<Vec<String>>,
+
+    /// Username or UID (format: <name|uid>[:<group|gid>]).
+    /// If None, uses the image's USER directive (defaults to root).
+    #[serde(default)]
+    pub user: Option<String>,
+
+    /// Boot the box with a custom guest kernel instead of the bundled one.
+    ///
+    /// Intended for testing against other kernel versions. The kernel must
+    /// provide virtio-fs, virtio-blk and vsock for the guest agent to start.
+    #[serde(default)]
+    pub kernel: Option<KernelSpec>,
+
+    /// Egress bandwidth limit for the box, in megabits per second.
+    ///
+    /// Enforced by the network backend on traffic sent by the guest.
+    /// `None` means unlimited.
+    #[serde(default)]
+    pub network_limit_mbps: Option<u64>,
+
+    /// Host CPU and I/O priority of the box against other boxes.
+    ///
+    /// `Low` for batch boxes that should yield to interactive ones. Raising
+    /// the nice value for `High` needs `CAP_SYS_NICE` (root on macOS); without
+    /// it only the CPU weight and I/O priority are raised.
+    #[serde(default)]
+    pub priority: BoxPriority,
+
+    /// Outbound traffic policy, enforced by the host-side network backend.
+    ///
+    /// `None` allows all egress.
+    #[serde(default)]
+    pub network_policy: Option<NetworkPolicy>,
+
+    /// Hostname of the container (and its sidecars), like `docker run
+    /// --hostname`.
+    ///
+    /// `None` uses the box name when it is a valid hostname, else the
+    /// lowercased box ID.
+    #[serde(default)]
+    pub hostname: Option<String>,
+
+    /// MAC address of the box's network interface, e.g. `"02:42:ac:11:00:02"`.
+    ///
+    /// `None` keeps the default address. Must be a unicast address.
+    #[serde(default)]
+    pub mac_address: Option<String>,
+
+    /// Static IPv4 address of the box inside its subnet (192.168.127.0/24),
+    /// e.g. `"192.168.127.10"`.
+    ///
+    /// `None` keeps the default address (192.168.127.2). Each box has its own
+    /// virtual network, so boxes may share an address.
+    #[serde(default)]
+    pub ip_address: Option<String>,
+
+    /// Let the box reach services on the host's loopback interface at
+    /// `host.boxlite.internal` (192.168.127.254), like
+    /// `host.docker.internal`.
+    ///
+    /// Disable for stricter isolation: the name is left out of the box's
+    /// `/etc/hosts` and the address no longer reaches the host. With a
+    /// `network_policy`, the address is matched against the rules as
+    /// `127.0.0.1`, so a deny-all policy blocks it too unless `127.0.0.1`
+    /// is allowed.
+    #[serde(default = "default_host_loopback")]
+    pub host_loopback: bool,
+
+    /// Log every connection the box opens (destination, TLS server name,
+    /// bytes and duration) for auditing. See
+    /// [`LiteBox::connection_log`](crate::LiteBox::connection_log).
+    ///
+    /// Needs the gvproxy network backend; connections to the gateway's DNS
+    /// and DHCP services are not logged.
+    #[serde(default)]
+    pub log_connections: bool,
+
+    /// Provision an SSH endpoint on each start (`None` = disabled).
+    #[serde(default)]
+    pub ssh: Option<SshOptions>,
+
+    /// Collect core dumps of processes that crash in the box (`None` =
+    /// disabled). See [`LiteBox::core_dumps`](crate::LiteBox::core_dumps).
+    #[serde(default)]
+    pub core_dumps: Option<CoreDumpOptions>,
+
+    /// Startup timeouts for guest readiness, connection and init RPCs.
+    #[serde(default)]
+    pub timeouts: BoxTimeouts,
+
+    /// Default stdout/stderr compression for commands run in this box.
+    ///
+    /// Worth enabling for commands with large, compressible output (logs,
+    /// build output, text dumps). `BoxCommand::compression` overrides it.
+    #[serde(default)]
+    pub exec_compression: ExecCompression,
+
+    /// Cap on commands running at once in this box (`None` = no cap
+    /// beyond the guest's own).
+    ///
+    /// Counts commands started through this runtime; without a cap, the
+    /// guest refuses new commands with `ResourceExhausted` once several
+    /// hundred are running.
+    #[serde(default)]
+    pub exec_limit: Option<ExecLimit>,
+
+    /// Setup commands run on the box's first start, in order.
+    ///
+    /// Each runs through `/bin/sh -c` once the container is initialized,
+    /// before the box is handed back and before `ssh` is provisioned. A
+    /// failing command fails the start. Output is recorded under the box's
+    /// `recordings` directory.
+    #[serde(default)]
+    pub init_commands: Vec<String>,
+
+    /// Containers run next to the main one in the same VM, e.g. a database
+    /// for an app.
+    ///
+    /// Target one with `BoxCommand::container`. Sidecars share the box's
+    /// network, so the main container reaches them on `localhost`.
+    #[serde(default)]
+    pub sidecars: Vec<SidecarSpec>,
+}
+
+fn default_auto_remove() -> bool {
+    true
+}
+
+fn default_detach() -> bool {
+    false
+}
+
+fn default_host_loopback() -> bool {
+    true
+}
+
+/// Whether `name` is a valid container hostname: at most 63 characters of
+/// dot-separated labels made of letters, digits and inner `-`.
+pub(crate) fn is_valid_hostname(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 63
+        && name.split('.').all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+impl Default for BoxOptions {
+    fn default() -> Self {
+        Self {
+            cpus: None,
+            memory_mib: None,
+            disk_size_gb: None,
+            working_dir: None,
+            env: Vec::new(),
+            env_passthrough: Vec::new(),
+            timezone: None,
+            locale: None,
+            rootfs: RootfsSpec::default(),
+            volumes: Vec::new(),
+            tmpfs: Vec::new(),
+            shm_size_mib: None,
+            network: NetworkSpec::default(),
+            ports: Vec::new(),
+            publish_all: false,
+            publish_retry: false,
+            isolate_mounts: false,
+            auto_remove: default_auto_remove(),
+            detach: default_detach(),
+            security: SecurityOptions::default(),
+            entrypoint: None,
+            cmd: None,
+            user: None,
+            kernel: None,
+            network_limit_mbps: None,
+            priority: BoxPriority::default(),
+            network_policy: None,
+            hostname: None,
+            mac_address: None,
+            ip_address: None,
+            host_loopback: default_host_loopback(),
+            log_connections: false,
+            ssh: None,
+            core_dumps: None,
+            timeouts: BoxTimeouts::default(),
+            exec_compression: ExecCompression::default(),
+            exec_limit: None,
+            init_commands: Vec::new(),
+            sidecars: Vec::new(),
+        }
+    }
+}
+
+impl BoxOptions {
+    /// Sanitize and validate options.
+    ///
+    /// Validates option combinations:
+    /// - `isolate_mounts=true` is only supported on Linux
+    /// - custom kernel and initrd paths must be absolute
+    /// - startup timeouts must be non-zero
+    /// - tmpfs paths must be absolute, with a non-zero size and a valid mode
+    /// - `shm_size_mib` must be non-zero
+    /// - capability names must be known, and not both added and dropped
+    /// - `working_dir` must be absolute
+    /// - `hostname` must be a valid hostname, `mac_address` a unicast MAC,
+    ///   `ip_address` a free address in the box subnet
+    /// - init commands must not be empty
+    /// - env passthrough patterns must not be empty or contain `=`
+    /// - `timezone` must be `host` or a zone name, `locale` a locale name
+    /// - sidecars need an image and a unique, valid name
+    /// - core dump limits must be non-zero
+    /// - `exec_limit` must allow at least one command
+    /// - a box with networking disabled has no ports, SSH or network limits
+    pub fn sanitize(&self) -> BoxliteResult<()> {
+        #[cfg(not(target_os = "linux"))]
+        if self.isolate_mounts {
+            return Err(boxlite_shared::errors::BoxliteError::Unsupported(
+                "isolate_mounts is only supported on Linux".to_string(),
+            ));
+        }
+
+        if let Some(kernel) = &self.kernel {
+            kernel.validate()?;
+        }
+
+        if let Some(policy) = &self.network_policy {
+            policy.validate()?;
+        }
+
+        if self.network == NetworkSpec::Disabled {
+            let needs_network = [
+                (!self.ports.is_empty(), "port mappings"),
+                (self.publish_all, "publish_all"),
+                (self.ssh.is_some(), "ssh"),
+                (self.network_limit_mbps.is_some(), "network_limit_mbps"),
+                (self.network_policy.is_some(), "network_policy"),
+                (self.mac_address.is_some(), "mac_address"),
+                (self.ip_address.is_some(), "ip_address"),
+                (self.log_connections, "log_connections"),
+            ];
+            if let Some((_, what)) = needs_network.iter().find(|(set, _)| *set) {
+                return Err(boxlite_shared::errors::BoxliteError::Config(format!(
+                    "{} requires networking, but the box's network is disabled",
+                    what
+                )));
+            }
+        }
+
+        if let Some(ssh) = &self.ssh
+            && ssh.authorized_keys.is_empty()
+        {
+            return Err(boxlite_shared::errors::BoxliteError::Config(
+                "ssh requires at least one authorized key".to_string(),
+            ));
+        }
+
+        if let Some(core_dumps) = &self.core_dumps {
+            core_dumps.validate()?;
+        }
+
+        if let Some(exec_limit) = &self.exec_limit {
+            exec_limit.validate()?;
+        }
+
+        self.timeouts.validate()?;
+
+        for tmpfs in &self.tmpfs {
+            tmpfs.validate()?;
+        }
+        self.security.container_capabilities()?;
+        if self.init_commands.iter().any(|c| c.trim().is_empty()) {
+            return Err(boxlite_shared::errors::BoxliteError::Config(
+                "init commands must not be empty".to_string(),
+            ));
+        }
+        if let Some(dir) = &self.working_dir
+            && !dir.starts_with('/')
+        {
+            return Err(boxlite_shared::errors::BoxliteError::Config(format!(
+                "working_dir must be an absolute path: {}",
+                dir
+            )));
+        }
+        if let Some(hostname) = &self.hostname
+            && !is_valid_hostname(hostname)
+        {
+            return Err(boxlite_shared::errors::BoxliteError::Config(format!(
+                "invalid hostname '{}': expected up to 63 letters, digits, '-' and '.'",
+                hostname
+            )));
+        }
+        if let Some(mac) = &self.mac_address {
+            crate::net::constants::parse_mac(mac)?;
+        }
+        if let Some(ip) = &self.ip_address {
+            crate::net::constants::parse_guest_ip(ip)?;
+        }
+        if let Some(pattern) = self
+            .env_passthrough
+            .iter()
+            .find(|p| p.is_empty() || p.contains('='))
+        {
+            return Err(boxlite_shared::errors::BoxliteError::Config(format!(
+                "invalid env passthrough pattern '{}'",
+                pattern
+            )));
+        }
+        if let Some(timezone) = &self.timezone
+            && timezone != crate::runtime::env::HOST_TIMEZONE
+            && !crate::runtime::env::is_valid_timezone(timezone)
+        {
+            return Err(boxlite_shared::errors::BoxliteError::Config(format!(
+                "invalid timezone '{}': expected 'host' or a name like Europe/Berlin",
+                timezone
+            )));
+        }
+        if let Some(locale) = &self.locale
+            && !crate::runtime::env::is_valid_locale(locale)
+        {
+            return Err(boxlite_shared::errors::BoxliteError::Config(format!(
+                "invalid locale '{}': expected a name like en_US.UTF-8",
+                locale
+            )));
+        }
+        let mut sidecar_names = HashSet::new();
+        for sidecar in &self.sidecars {
+            sidecar.validate()?;
+            if !sidecar_names.insert(sidecar.name.as_str()) {
+                return Err(boxlite_shared::errors::BoxliteError::Config(format!(
+                    "sidecar name '{}' is used more than once",
+                    sidecar.name
+                )));
+            }
+        }
+        if self.shm_size_mib == Some(0) {
+            return Err(boxlite_shared::errors::BoxliteError::Config(
+                "shm_size_mib must be greater than zero".to_string(),
+            ));
+        }
+
+        self.validate_ports()
+    }
+
+    /// tmpfs mounts for the container, including /dev/shm when
+    /// `shm_size_mib` is set and no explicit /dev/shm tmpfs is given.
+    pub(crate) fn container_tmpfs(&self) -> Vec<TmpfsSpec> {
+        let mut tmpfs = self.tmpfs.clone();
+        if let Some(mib) = self.shm_size_mib
+            && !tmpfs.iter().any(|t| t.path == "/dev/shm")
+        {
+            tmpfs.push(TmpfsSpec {
+                path: "/dev/shm".to_string(),
+                size_bytes: Some(u64::from(mib) << 20),
+                mode: Some(0o1777),
+            });
+        }
+        tmpfs
+    }
+
+    /// Reject unparseable host IPs and host ports published more than once.
+    ///
+    /// Forwarding is keyed by host port, so one host port cannot be bound on
+    /// two interfaces. Dynamic host ports are assigned at start and never
+    /// conflict.
+    fn validate_ports(&self) -> BoxliteResult<()> {
+        let mut seen: HashMap<u16, Option<&str>> = HashMap::new();
+        for port in &self.ports {
+            if let Some(host_ip) = &port.host_ip
+                && host_ip.parse::<std::net::IpAddr>().is_err()
+            {
+                return Err(boxlite_shared::errors::BoxliteError::Config(format!(
+                    "invalid host IP '{}' for port {}",
+                    host_ip, port.guest_port
+                )));
+            }
+            let Some(host_port) = port.host_port.filter(|p| *p != 0) else {
+                continue;
+            };
+            if let Some(previous) = seen.insert(host_port, port.host_ip.as_deref()) {
+                return Err(boxlite_shared::errors::BoxliteError::Config(format!(
+                    "host port {} is published more than once ({} and {})",
+                    host_port,
+                    previous.unwrap_or("0.0.0.0"),
+                    port.host_ip.as_deref().unwrap_or("0.0.0.0")
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// SSH access to a box.
+///
+/// On every start the runtime installs `authorized_keys` for `user`,
+/// generates missing host keys and starts the image's `sshd`, which must be
+/// installed (e.g. `openssh-server`). Port [`SSH_GUEST_PORT`] is published
+/// on `127.0.0.1` with a dynamically assigned host port unless `ports`
+/// already maps it; see [`BoxInfo::ports`](crate::BoxInfo::ports).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SshOptions {
+    /// Public keys in `authorized_keys` format.
+    pub authorized_keys: Vec<String>,
+    /// Login user (must exist in the image).
+    #[serde(default = "default_ssh_user")]
+    pub user: String,
+}
+
+/// Port `sshd` listens on inside the box.
+pub const SSH_GUEST_PORT: u16 = 22;
+
+fn default_ssh_user() -> String {
+    "root".to_string()
+}
+
+impl SshOptions {
+    /// SSH access as `root` with the given public keys.
+    pub fn new(authorized_keys: Vec<String>) -> Self {
+        Self {
+            authorized_keys,
+            user: default_ssh_user(),
+        }
+    }
+}
+
+/// Core dump collection for a box.
+///
+/// The guest kernel pipes each dump into `~/.boxlite/boxes/{box_id}/cores`
+/// on the host, so dumps survive the box stopping and are removed with it.
+/// Processes get a dump even when their `RLIMIT_CORE` is 0; a soft limit
+/// of 1 still opts a process out.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoreDumpOptions {
+    /// Dumps are truncated at this size.
+    #[serde(default = "default_core_dump_max_bytes")]
+    pub max_bytes: u64,
+    /// Dumps kept; the oldest are removed when a new one is written.
+    #[serde(default = "default_core_dump_max_files")]
+    pub max_files: u32,
+}
+
+fn default_core_dump_max_bytes() -> u64 {
+    512 << 20
+}
+
+fn default_core_dump_max_files() -> u32 {
+    5
+}
+
+impl Default for CoreDumpOptions {
+    fn default() -> Self {
+        Self {
+            max_bytes: default_core_dump_max_bytes(),
+            max_files: default_core_dump_max_files(),
+        }
+    }
+}
+
+impl CoreDumpOptions {
+    fn validate(&self) -> BoxliteResult<()> {
+        if self.max_bytes == 0 || self.max_files == 0 {
+            return Err(boxlite_shared::errors::BoxliteError::Config(
+                "core dump max_bytes and max_files must be greater than zero".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Cap on commands running at once in a box (see `BoxOptions::exec_limit`).
+///
+/// A command holds a slot from `exec()` until it exits. When all slots are
+/// taken, `exec()` waits for one for up to `queue_timeout_secs` and then
+/// fails with [`BoxliteError::ResourceExhausted`](boxlite_shared::errors::BoxliteError::ResourceExhausted).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecLimit {
+    /// Commands that may run at once.
+    pub max_in_flight: u32,
+    /// Seconds `exec()` waits for a free slot: `None` waits as long as it
+    /// takes, `Some(0)` fails at once.
+    #[serde(default)]
+    pub queue_timeout_secs: Option<u64>,
+}
+
+impl ExecLimit {
+    fn validate(&self) -> BoxliteResult<()> {
+        if self.max_in_flight == 0 {
+            return Err(boxlite_shared::errors::BoxliteError::Config(
+                "exec_limit max_in_flight must be greater than zero".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// How long box startup waits on the guest, in seconds.
+///
+/// A timeout fails the step with
+/// [`ErrorCode::GuestTimeout`](crate::ErrorCode::GuestTimeout), which is
+/// retried per [`BoxliteOptions::create_retries`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BoxTimeouts {
+    /// Wait for the guest agent to signal ready after the VM starts.
+    #[serde(default = "default_guest_ready_secs")]
+    pub guest_ready_secs: u64,
+    /// Establish the gRPC connection to the guest agent.
+    #[serde(default = "default_connect_secs")]
+    pub connect_secs: u64,
+    /// Each guest and container init RPC.
+    #[serde(default = "default_init_secs")]
+    pub init_secs: u64,
+}
+
+fn default_guest_ready_secs() -> u64 {
+    30
+}
+
+fn default_connect_secs() -> u64 {
+    10
+}
+
+fn default_init_secs() -> u64 {
+    60
+}
+
+impl Default for BoxTimeouts {
+    fn default() -> Self {
+        Self {
+            guest_ready_secs: default_guest_ready_secs(),
+            connect_secs: default_connect_secs(),
+            init_secs: default_init_secs(),
+        }
+    }
+}
+
+impl BoxTimeouts {
+    /// Guest-ready wait.
+    pub fn guest_ready(&self) -> Duration {
+        Duration::from_secs(self.guest_ready_secs)
+    }
+
+    /// gRPC connect.
+    pub fn connect(&self) -> Duration {
+        Duration::from_secs(self.connect_secs)
+    }
+
+    /// Per init RPC.
+    pub fn init(&self) -> Duration {
+        Duration::from_secs(self.init_secs)
+    }
+
+    fn validate(&self) -> BoxliteResult<()> {
+        for (name, secs) in [
+            ("guest_ready_secs", self.guest_ready_secs),
+            ("connect_secs", self.connect_secs),
+            ("init_secs", self.init_secs),
+        ] {
+            if secs == 0 {
+                return Err(boxlite_shared::errors::BoxliteError::Config(format!(
+                    "timeouts.{} must be greater than zero",
+                    name
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Egress allow/deny rules for a box.
+///
+/// Each rule is an IP address, a CIDR block or a hostname; hostnames are
+/// resolved once when the box's network starts. Deny rules win over allow
+/// rules, and destinations matching neither get `default_allow`. Traffic to
+/// the box's virtual subnet (gateway, DNS, port forwards) is always allowed,
+/// except the host loopback address, which is matched as `127.0.0.1`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkPolicy {
+    /// Whether destinations that match no rule are reachable.
+    #[serde(default = "default_policy_allow")]
+    pub default_allow: bool,
+    /// Destinations that are always reachable (unless denied).
+    #[serde(default)]
+    pub allow: Vec<String>,
+    /// Destinations that are never reachable.
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+fn default_policy_allow() -> bool {
+    true
+}
+
+impl Default for NetworkPolicy {
+    fn default() -> Self {
+        Self {
+            default_allow: default_policy_allow(),
+            allow: Vec::new(),
+            deny: Vec::new(),
+        }
+    }
+}
+
+impl NetworkPolicy {
+    /// Block all outbound traffic.
+    pub fn none() -> Self {
+        Self::restricted(Vec::new())
+    }
+
+    /// Block outbound traffic except to `allow`.
+    pub fn restricted(allow: Vec<String>) -> Self {
+        Self {
+            default_allow: false,
+            allow,
+            deny: Vec::new(),
+        }
+    }
+
+    /// Check that every rule is an IP, a CIDR block or a hostname.
+    pub fn validate(&self) -> BoxliteResult<()> {
+        for rule in self.allow.iter().chain(&self.deny) {
+            if !is_valid_policy_rule(rule) {
+                return Err(boxlite_shared::errors::BoxliteError::Config(format!(
+                    "invalid network policy rule '{}': expected an IP, CIDR or hostname",
+                    rule
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_valid_policy_rule(rule: &str) -> bool {
+    if let Some((ip, prefix)) = rule.split_once('/') {
+        let max_prefix = match ip.parse::<std::net::IpAddr>() {
+            Ok(std::net::IpAddr::V4(_)) => 32,
+            Ok(std::net::IpAddr::V6(_)) => 128,
+            Err(_) => return false,
+        };
+        return prefix.parse::<u8>().is_ok_and(|p| p <= max_prefix);
+    }
+    if rule.parse::<std::net::IpAddr>().is_ok() {
+        return true;
+    }
+    !rule.is_empty()
+        && rule.len() <= 253
+        && rule.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+/// Custom guest kernel to boot instead of the bundled one.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KernelSpec {
+    /// Host path to the kernel image.
+    pub path: PathBuf,
+    /// Host path to an initramfs to load alongside the kernel.
+    #[serde(default)]
+    pub initrd: Option<PathBuf>,
+    /// Extra arguments appended to the kernel command line.
+    #[serde(default)]
+    pub cmdline: Option<String>,
+    /// Image format of `path`.
+    #[serde(default)]
+    pub format: KernelFormat,
+}
+
+impl KernelSpec {
+    fn validate(&self) -> BoxliteResult<()> {
+        for path in self.host_paths() {
+            if !path.is_absolute() {
+                return Err(boxlite_shared::errors::BoxliteError::Config(format!(
+                    "kernel path must be absolute: {}",
+                    path.display()
+                )));
+            }
+        }
+        if let Some(cmdline) = &self.cmdline
+            && cmdline.contains('\0')
+        {
+            return Err(boxlite_shared::errors::BoxliteError::Config(
+                "kernel cmdline must not contain NUL bytes".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Host files the VMM reads at boot (kernel, then initrd if set).
+    pub(crate) fn host_paths(&self) -> impl Iterator<Item = &PathBuf> {
+        std::iter::once(&self.path).chain(self.initrd.as_ref())
+    }
+}
+
+/// Image format of a custom guest kernel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KernelFormat {
+    /// Uncompressed raw image (`Image` on aarch64).
+    Raw,
+    /// ELF binary (`vmlinux`).
+    Elf,
+    /// gzip-compressed PE image.
+    PeGz,
+    /// bzip2-compressed `Image`.
+    ImageBz2,
+    /// gzip-compressed `Image`.
+    ImageGz,
+    /// zstd-compressed `Image`.
+    ImageZstd,
+}
+
+impl Default for KernelFormat {
+    /// `vmlinux` on x86_64, raw `Image` elsewhere.
+    fn default() -> Self {
+        if cfg!(target_arch = "x86_64") {
+            Self::Elf
+        } else {
+            Self::Raw
+        }
+    }
+}
+
+/// How to populate the box root filesystem.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub enum RootfsSpec {
+    /// Pull/resolve this registry image reference.
+    Image(String),
+    /// Use an already prepared rootfs at the given host path.
+    RootfsPath(String),
+}
+
+impl Default for RootfsSpec {
+    fn default() -> Self {
+        Self::Image("alpine:latest".into())
+    }
+}
+
+/// Filesystem mount specification.
+///
+/// `host_path` may be a directory or a single file.
 #[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct VolumeSpec {
+    pub host_path: String,
+    pub guest_path: String,
+    pub read_only: bool,
+    /// Chown the volume to the container user when the box starts (like
+    /// Podman's `:U`). Changes ownership on the host too.
+    #[serde(default)]
+    pub chown: bool,
+    /// Map container root to these host ids inside the volume, so files the
+    /// box creates as root are owned by `uid:gid` on the host. Needs a guest
+    /// kernel with idmapped virtiofs mounts.
+    #[serde(default)]
+    pub id_shift: Option<VolumeIdShift>,
+}
+
+/// Host ids that container root is mapped to within a volume.
+///
+/// The mapping is a swap: container root appears as `uid:gid` on the host,
+/// and files owned by host root appear as `uid:gid` in the container. All
+/// other ids pass through unchanged.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct VolumeIdShift {
+    pub uid: u32,
+    pub gid: u32,
+}
+
+/// tmpfs mount inside the container.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TmpfsSpec {
+    /// Absolute path in the container.
+    pub path: String,
+    /// Size limit in bytes (`None` = kernel default, half of guest RAM).
+    #[serde(default)]
+    pub size_bytes: Option<u64>,
+    /// Permission bits of the mount root (`None` = `0o1777`).
+    #[serde(default)]
+    pub mode: Option<u32>,
+}
+
+impl TmpfsSpec {
+    fn validate(&self) -> BoxliteResult<()> {
+        if !self.path.starts_with('/') {
+            return Err(boxlite_shared::errors::BoxliteError::Config(format!(
+                "tmpfs path must be absolute: {}",
+                self.path
+            )));
+        }
+        if self.size_bytes == Some(0) {
+            return Err(boxlite_shared::errors::BoxliteError::Config(format!(
+                "tmpfs size for {} must be greater than zero",
+                self.path
+            )));
+        }
+        if let Some(mode) = self.mode
+            && mode > 0o7777
+        {
+            return Err(boxlite_shared::errors::BoxliteError::Config(format!(
+                "tmpfs mode for {} must be at most 7777 (octal), got {:o}",
+                self.path, mode
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A sidecar container, run from its own image next to the box's main
+/// container.
+///
+/// It gets its own rootfs disk (always ext4) and runs the image's entrypoint
+/// and cmd as its main process. Volumes, tmpfs mounts and the box's `env`,
+/// `user` and `working_dir` only apply to the main container.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SidecarSpec {
+    /// Name to target the sidecar by, e.g. "db".
+    pub name: String,
+    /// Image reference to run.
+    pub image: String,
+    /// Environment variables merged over the image's.
+    #[serde(default)]
+    pub env: Vec<(String, String)>,
+    /// Override the image's ENTRYPOINT.
+    #[serde(default)]
+    pub entrypoint: Option<Vec<String>>,
+    /// Override the image's CMD.
+    #[serde(default)]
+    pub cmd: Option<Vec<String>>,
+}
+
+impl SidecarSpec {
+    /// Sidecar `name` running `image` with the image's defaults.
+    pub fn new(name: impl Into<String>, image: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            image: image.into(),
+            ..Default::default()
+        }
+    }
+
+    fn validate(&self) -> BoxliteResult<()> {
+        let valid_name = !self.name.is_empty()
+            && self.name.len() <= 63
+            && self
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            && !self.name.starts_with('-');
+        if !valid_name {
+            return Err(boxlite_shared::errors::BoxliteError::Config(format!(
+                "invalid sidecar name '{}': use up to 63 letters, digits, '-' or '_'",
+                self.name
+            )));
+        }
+        if self.image.trim().is_empty() {
+            return Err(boxlite_shared::errors::BoxliteError::Config(format!(
+                "sidecar '{}' needs an image",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Network isolation options.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum NetworkSpec {
+    /// Private network behind the host-side network backend.
+    #[default]
+    Isolated,
+    /// No network device: the box boots without a network stack or backend.
+    ///
+    /// Starts faster for boxes that never talk to the network. Port
+    /// mappings, SSH and network limits or policies are rejected.
+    Disabled,
+    // Host,
+    // Custom(String),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum PortProtocol {
     #[default]
     Tcp,
@@ -742,7 +2492,7 @@ fn default_protocol() -> PortProtocol {
 /// Port mapping specification (host -> guest).
 #[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
 pub struct PortSpec {
-    pub host_port: Option<u16>, // None/0 => dynamically assigned
+    pub host_port: Option<u16>, // None/0 => dynamically assigned at start
     pub guest_port: u16,
     #[serde(default = "default_protocol")]
     pub protocol: PortProtocol,
@@ -777,6 +2527,10 @@ mod tests {
             "auto_remove should default to true via serde"
         );
         assert!(!opts.detach, "detach should default to false via serde");
+        assert!(
+            opts.host_loopback,
+            "host_loopback should default to true via serde"
+        );
     }
 
     #[test]
@@ -813,26 +2567,6 @@ mod tests {
         assert_eq!(opts.detach, opts2.detach);
     }
 
-    #[test]
-    fn test_sanitize_auto_remove_detach_incompatible() {
-        // auto_remove=true + detach=true is invalid
-        let opts = BoxOptions {
-            auto_remove: true,
-            detach: true,
-            ..Default::default()
-        };
-        let result = opts.sanitize();
-        assert!(
-            result.is_err(),
-            "auto_remove=true + detach=true should fail"
-        );
-        let err_msg = result.unwrap_err().to_string();
-        assert!(
-            err_msg.contains("incompatible"),
-            "Error should mention incompatibility"
-        );
-    }
-
     #[test]
     fn test_sanitize_valid_combinations() {
         // auto_remove=true, detach=false (default) - valid
@@ -858,6 +2592,14 @@ mod tests {
             ..Default::default()
         };
         assert!(opts3.sanitize().is_ok());
+
+        // auto_remove=true, detach=true - valid (removed once it stops)
+        let opts4 = BoxOptions {
+            auto_remove: true,
+            detach: true,
+            ..Default::default()
+        };
+        assert!(opts4.sanitize().is_ok());
     }
 
     // ========================================================================
@@ -941,6 +2683,92 @@ mod tests {
         assert!(opts.jailer_enabled);
     }
 
+    #[test]
+    fn test_security_allow_fuse_is_opt_in() {
+        assert!(!SecurityOptions::default().allow_fuse);
+        assert!(!SecurityOptions::maximum().allow_fuse);
+
+        let opts = SecurityOptionsBuilder::standard().allow_fuse(true).build();
+        assert!(opts.allow_fuse);
+
+        // Older serialized options without the field still deserialize
+        let json = serde_json::to_value(SecurityOptions::default()).unwrap();
+        let mut map = json.as_object().unwrap().clone();
+        map.remove("allow_fuse");
+        let opts: SecurityOptions = serde_json::from_value(map.into()).unwrap();
+        assert!(!opts.allow_fuse);
+    }
+
+    #[test]
+    fn test_capabilities_default_set() {
+        let caps = CapabilityOptions::default().resolve().unwrap();
+        assert_eq!(caps.len(), DEFAULT_CAPABILITIES.len());
+        assert!(caps.contains(&"CAP_CHOWN".to_string()));
+        assert!(!caps.contains(&"CAP_SYS_ADMIN".to_string()));
+    }
+
+    #[test]
+    fn test_capabilities_add_and_drop() {
+        let caps = CapabilityOptions {
+            add: vec!["net_admin".into(), "CAP_SYS_PTRACE".into()],
+            drop: vec!["MKNOD".into()],
+        }
+        .resolve()
+        .unwrap();
+        assert!(caps.contains(&"CAP_NET_ADMIN".to_string()));
+        assert!(caps.contains(&"CAP_SYS_PTRACE".to_string()));
+        assert!(!caps.contains(&"CAP_MKNOD".to_string()));
+
+        let only = CapabilityOptions {
+            add: vec!["NET_BIND_SERVICE".into()],
+            drop: vec!["all".into()],
+        }
+        .resolve()
+        .unwrap();
+        assert_eq!(only, vec!["CAP_NET_BIND_SERVICE"]);
+
+        let all_but_one = CapabilityOptions {
+            add: vec!["ALL".into()],
+            drop: vec!["SYS_MODULE".into()],
+        }
+        .resolve()
+        .unwrap();
+        assert_eq!(all_but_one.len(), ALL_CAPABILITIES.len() - 1);
+    }
+
+    #[test]
+    fn test_capabilities_rejects_invalid() {
+        let unknown = CapabilityOptions {
+            add: vec!["FLY".into()],
+            ..Default::default()
+        };
+        assert!(unknown.resolve().is_err());
+
+        let conflict = CapabilityOptions {
+            add: vec!["NET_ADMIN".into()],
+            drop: vec!["cap_net_admin".into()],
+        };
+        assert!(conflict.resolve().is_err());
+
+        let opts = BoxOptions {
+            security: SecurityOptionsBuilder::new().cap_add("FLY").build(),
+            ..Default::default()
+        };
+        assert!(opts.sanitize().is_err());
+    }
+
+    #[test]
+    fn test_container_capabilities_with_fuse() {
+        let opts = SecurityOptionsBuilder::new()
+            .cap_drop("ALL")
+            .allow_fuse(true)
+            .build();
+        assert_eq!(
+            opts.container_capabilities().unwrap(),
+            vec!["CAP_SYS_ADMIN"]
+        );
+    }
+
     // ========================================================================
     // cmd/user option tests
     // ========================================================================
@@ -1081,4 +2909,399 @@ mod tests {
         assert!(opts1.resource_limits.max_processes.is_none());
         assert_eq!(opts2.resource_limits.max_processes, Some(50));
     }
+
+    #[test]
+    fn test_stored_memory_backing_is_ignored() {
+        // Configs written while the option existed still load
+        let json = r#"{"rootfs":{"Image":"alpine"},"memory_backing":"anonymous"}"#;
+        let opts: BoxOptions = serde_json::from_str(json).unwrap();
+        assert!(matches!(opts.rootfs, RootfsSpec::Image(ref image) if image == "alpine"));
+    }
+
+    #[test]
+    fn test_sanitize_validates_kernel() {
+        let with_kernel = |kernel: KernelSpec| BoxOptions {
+            auto_remove: false,
+            kernel: Some(kernel),
+            ..Default::default()
+        };
+        assert!(
+            with_kernel(KernelSpec {
+                path: PathBuf::from("/opt/kernels/vmlinux-6.12"),
+                initrd: Some(PathBuf::from("/opt/kernels/initrd.img")),
+                cmdline: Some("loglevel=7".to_string()),
+                ..Default::default()
+            })
+            .sanitize()
+            .is_ok()
+        );
+        assert!(
+            with_kernel(KernelSpec {
+                path: PathBuf::from("vmlinux"),
+                ..Default::default()
+            })
+            .sanitize()
+            .is_err()
+        );
+        assert!(
+            with_kernel(KernelSpec {
+                path: PathBuf::from("/opt/kernels/vmlinux"),
+                initrd: Some(PathBuf::from("initrd.img")),
+                ..Default::default()
+            })
+            .sanitize()
+            .is_err()
+        );
+
+        let parsed: KernelSpec =
+            serde_json::from_str(r#"{"path":"/boot/Image.gz","format":"image_gz"}"#).unwrap();
+        assert_eq!(parsed.format, KernelFormat::ImageGz);
+        assert!(parsed.cmdline.is_none());
+    }
+
+    #[test]
+    fn test_box_timeouts_defaults_and_partial_deserialize() {
+        let timeouts = BoxOptions::default().timeouts;
+        assert_eq!(timeouts.guest_ready(), Duration::from_secs(30));
+        assert_eq!(timeouts.connect(), Duration::from_secs(10));
+        assert_eq!(timeouts.init(), Duration::from_secs(60));
+
+        let parsed: BoxTimeouts = serde_json::from_str(r#"{"guest_ready_secs": 90}"#).unwrap();
+        assert_eq!(parsed.guest_ready_secs, 90);
+        assert_eq!(parsed.connect_secs, 10);
+        assert_eq!(parsed.init_secs, 60);
+    }
+
+    #[test]
+    fn test_sanitize_rejects_zero_timeout() {
+        let opts = BoxOptions {
+            auto_remove: false,
+            timeouts: BoxTimeouts {
+                init_secs: 0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let err = opts.sanitize().unwrap_err().to_string();
+        assert!(err.contains("timeouts.init_secs"), "{}", err);
+    }
+
+    #[test]
+    fn test_sanitize_rejects_zero_exec_limit() {
+        let opts = BoxOptions {
+            exec_limit: Some(ExecLimit {
+                max_in_flight: 0,
+                queue_timeout_secs: None,
+            }),
+            ..Default::default()
+        };
+        let err = opts.sanitize().unwrap_err().to_string();
+        assert!(err.contains("max_in_flight"), "{}", err);
+    }
+
+    #[test]
+    fn test_sanitize_rejects_ports_without_network() {
+        let mut opts = BoxOptions {
+            auto_remove: false,
+            network: NetworkSpec::Disabled,
+            ..Default::default()
+        };
+        assert!(opts.sanitize().is_ok());
+
+        opts.ports.push(PortSpec {
+            guest_port: 80,
+            ..Default::default()
+        });
+        let err = opts.sanitize().unwrap_err().to_string();
+        assert!(err.contains("port mappings requires networking"), "{}", err);
+    }
+
+    #[test]
+    fn test_sanitize_validates_tmpfs() {
+        let with_tmpfs = |tmpfs: TmpfsSpec| BoxOptions {
+            auto_remove: false,
+            tmpfs: vec![tmpfs],
+            ..Default::default()
+        };
+        assert!(
+            with_tmpfs(TmpfsSpec {
+                path: "/scratch".into(),
+                size_bytes: Some(64 << 20),
+                mode: Some(0o1777),
+            })
+            .sanitize()
+            .is_ok()
+        );
+        for bad in [
+            TmpfsSpec {
+                path: "scratch".into(),
+                ..Default::default()
+            },
+            TmpfsSpec {
+                path: "/scratch".into(),
+                size_bytes: Some(0),
+                ..Default::default()
+            },
+            TmpfsSpec {
+                path: "/scratch".into(),
+                mode: Some(0o17777),
+                ..Default::default()
+            },
+        ] {
+            assert!(with_tmpfs(bad).sanitize().is_err());
+        }
+    }
+
+    #[test]
+    fn test_sanitize_requires_absolute_working_dir() {
+        let with_dir = |dir: &str| BoxOptions {
+            working_dir: Some(dir.to_string()),
+            ..Default::default()
+        };
+        assert!(with_dir("/app").sanitize().is_ok());
+        assert!(with_dir("app").sanitize().is_err());
+    }
+
+    #[test]
+    fn test_sanitize_validates_hostname_and_mac() {
+        let with_hostname = |hostname: &str| BoxOptions {
+            hostname: Some(hostname.to_string()),
+            ..Default::default()
+        };
+        for good in ["web", "web-1", "db.internal", "A1"] {
+            assert!(with_hostname(good).sanitize().is_ok(), "{}", good);
+        }
+        for bad in ["", "-web", "web-", "a..b", "web_1", &"a".repeat(64)] {
+            assert!(with_hostname(bad).sanitize().is_err(), "{}", bad);
+        }
+
+        let with_mac = |mac: &str| BoxOptions {
+            mac_address: Some(mac.to_string()),
+            ..Default::default()
+        };
+        assert!(with_mac("02:42:ac:11:00:02").sanitize().is_ok());
+        assert!(with_mac("01:00:5e:00:00:01").sanitize().is_err());
+        let offline = BoxOptions {
+            network: NetworkSpec::Disabled,
+            ..with_mac("02:42:ac:11:00:02")
+        };
+        assert!(offline.sanitize().is_err());
+
+        let with_ip = |ip: &str| BoxOptions {
+            ip_address: Some(ip.to_string()),
+            ..Default::default()
+        };
+        assert!(with_ip("192.168.127.10").sanitize().is_ok());
+        assert!(with_ip("10.0.0.10").sanitize().is_err());
+        let offline = BoxOptions {
+            network: NetworkSpec::Disabled,
+            ..with_ip("192.168.127.10")
+        };
+        assert!(offline.sanitize().is_err());
+    }
+
+    #[test]
+    fn test_sanitize_validates_timezone_and_locale() {
+        let with = |timezone: &str, locale: &str| BoxOptions {
+            timezone: Some(timezone.to_string()),
+            locale: Some(locale.to_string()),
+            ..Default::default()
+        };
+        assert!(with("host", "C.UTF-8").sanitize().is_ok());
+        assert!(with("Europe/Berlin", "en_US.UTF-8").sanitize().is_ok());
+        assert!(with("../etc/passwd", "C").sanitize().is_err());
+        assert!(with("UTC", "en US").sanitize().is_err());
+    }
+
+    #[test]
+    fn test_sanitize_validates_env_passthrough() {
+        let with_patterns = |patterns: &[&str]| BoxOptions {
+            env_passthrough: patterns.iter().map(|p| p.to_string()).collect(),
+            ..Default::default()
+        };
+        assert!(with_patterns(&["AWS_*", "HOME"]).sanitize().is_ok());
+        assert!(with_patterns(&[""]).sanitize().is_err());
+        assert!(with_patterns(&["FOO=bar"]).sanitize().is_err());
+    }
+
+    #[test]
+    fn test_sanitize_validates_sidecars() {
+        let with_sidecars = |sidecars: Vec<SidecarSpec>| BoxOptions {
+            sidecars,
+            ..Default::default()
+        };
+        assert!(
+            with_sidecars(vec![
+                SidecarSpec::new("db", "postgres:16-alpine"),
+                SidecarSpec::new("cache_1", "redis:7"),
+            ])
+            .sanitize()
+            .is_ok()
+        );
+        for bad in [
+            vec![SidecarSpec::new("", "redis:7")],
+            vec![SidecarSpec::new("my db", "redis:7")],
+            vec![SidecarSpec::new("-db", "redis:7")],
+            vec![SidecarSpec::new("db", " ")],
+            vec![
+                SidecarSpec::new("db", "redis:7"),
+                SidecarSpec::new("db", "postgres:16"),
+            ],
+        ] {
+            assert!(with_sidecars(bad).sanitize().is_err());
+        }
+    }
+
+    #[test]
+    fn test_container_tmpfs_adds_shm() {
+        let mut opts = BoxOptions {
+            shm_size_mib: Some(512),
+            ..Default::default()
+        };
+        assert_eq!(
+            opts.container_tmpfs(),
+            vec![TmpfsSpec {
+                path: "/dev/shm".into(),
+                size_bytes: Some(512 << 20),
+                mode: Some(0o1777),
+            }]
+        );
+
+        // An explicit /dev/shm tmpfs wins over shm_size_mib
+        opts.tmpfs = vec![TmpfsSpec {
+            path: "/dev/shm".into(),
+            size_bytes: Some(1 << 20),
+            mode: None,
+        }];
+        assert_eq!(opts.container_tmpfs(), opts.tmpfs);
+    }
+
+    #[test]
+    fn test_proxy_resolve_prefers_explicit_then_env() {
+        let proxy = ProxyOptions {
+            https_proxy: Some("http://explicit:3128".to_string()),
+            ..Default::default()
+        };
+        let resolved = proxy.resolve_with(|name| match name {
+            "HTTPS_PROXY" => Some("http://env:3128".to_string()),
+            "http_proxy" => Some("http://lower:3128".to_string()),
+            "NO_PROXY" => Some(String::new()),
+            _ => None,
+        });
+        assert_eq!(
+            resolved.https_proxy.as_deref(),
+            Some("http://explicit:3128")
+        );
+        assert_eq!(resolved.http_proxy.as_deref(), Some("http://lower:3128"));
+        assert_eq!(resolved.no_proxy, None);
+
+        let names: Vec<String> = resolved.env_vars().into_iter().map(|(k, _)| k).collect();
+        assert_eq!(
+            names,
+            ["HTTP_PROXY", "http_proxy", "HTTPS_PROXY", "https_proxy"]
+        );
+    }
+
+    #[test]
+    fn test_network_policy_rules_validate() {
+        let policy = NetworkPolicy::restricted(vec![
+            "10.0.0.0/8".to_string(),
+            "2001:db8::/32".to_string(),
+            "1.1.1.1".to_string(),
+            "pypi.org".to_string(),
+        ]);
+        assert!(policy.validate().is_ok());
+
+        for bad in ["10.0.0.0/33", "not a host", "-bad.example", "", "host/8"] {
+            let policy = NetworkPolicy {
+                deny: vec![bad.to_string()],
+                ..Default::default()
+            };
+            assert!(policy.validate().is_err(), "{bad} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_sanitize_rejects_port_conflicts() {
+        let port = |host_ip: Option<&str>, host_port: u16| PortSpec {
+            host_port: Some(host_port),
+            guest_port: 80,
+            host_ip: host_ip.map(str::to_string),
+            ..Default::default()
+        };
+        let mut opts = BoxOptions {
+            auto_remove: false,
+            ports: vec![port(Some("127.0.0.1"), 8080), port(None, 8081)],
+            ..Default::default()
+        };
+        assert!(opts.sanitize().is_ok());
+
+        opts.ports.push(port(Some("::1"), 8080));
+        assert!(opts.sanitize().is_err());
+
+        opts.ports = vec![port(Some("localhost"), 8080)];
+        assert!(opts.sanitize().is_err());
+    }
+
+    #[test]
+    fn test_template_images_deduplicated() {
+        let options: BoxliteOptions = serde_json::from_str(
+            r#"{"templates": {
+                "web": {"rootfs": {"Image": "nginx:1.27"}, "cpus": 2},
+                "api": {"rootfs": {"Image": "python:3.12"}},
+                "worker": {"rootfs": {"Image": "python:3.12"}},
+                "local": {"rootfs": {"RootfsPath": "/srv/rootfs"}}
+            }}"#,
+        )
+        .unwrap();
+        assert_eq!(options.templates["web"].cpus, Some(2));
+        assert_eq!(options.template_images(), ["python:3.12", "nginx:1.27"]);
+    }
+
+    #[test]
+    fn test_unknown_config_fields_rejected() {
+        let err = serde_json::from_str::<BoxliteOptions>(r#"{"image_registry": ["ghcr.io"]}"#)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("unknown field `image_registry`"), "{err}");
+
+        let err = serde_json::from_str::<BoxliteOptions>(r#"{"retention": {"interval": 60}}"#)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("unknown field `interval`"), "{err}");
+    }
+
+    #[test]
+    fn test_host_limits_from_config() {
+        let options: BoxliteOptions =
+            serde_json::from_str(r#"{"host_limits": {"cpus": 2.5, "background": true}}"#).unwrap();
+        let limits = options.host_limits.unwrap();
+        assert_eq!(limits.cpus, Some(2.5));
+        assert!(limits.background);
+        assert!(limits.needs_cgroup());
+        assert!(limits.validate().is_ok());
+
+        let background_only = HostLimits {
+            background: true,
+            ..Default::default()
+        };
+        assert!(!background_only.needs_cgroup());
+
+        for invalid in [
+            HostLimits {
+                cpus: Some(0.0),
+                ..Default::default()
+            },
+            HostLimits {
+                memory_mib: Some(0),
+                ..Default::default()
+            },
+            HostLimits {
+                cpu_weight: Some(20_000),
+                ..Default::default()
+            },
+        ] {
+            assert!(invalid.validate().is_err());
+        }
+    }
 }
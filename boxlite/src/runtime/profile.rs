@@ -0,0 +1,196 @@
+//! Box profiles: presets that prepare [`BoxOptions`] for a kind of workload.
+//!
+//! A profile fills in what a workload needs to run reliably in a micro-VM
+//! (memory, mounts, ports, setup commands). It only adds to what the caller
+//! set: explicit settings always win. Built-in profiles are looked up by name
+//! with [`builtin_profile`]; other crates implement [`BoxProfile`] for their
+//! own presets.
+
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+
+use super::options::{BoxOptions, PortProtocol, PortSpec};
+
+/// A named preset for [`BoxOptions`].
+pub trait BoxProfile: Send + Sync {
+    /// Name the profile is selected by, e.g. `browser`.
+    fn name(&self) -> &str;
+
+    /// One-line description for listings and errors.
+    fn description(&self) -> &str;
+
+    /// Fill in the settings the workload needs.
+    ///
+    /// Must keep every setting the caller already made.
+    fn apply(&self, options: &mut BoxOptions);
+}
+
+/// Headless Chromium.
+///
+/// - `/dev/shm` of 1 GiB: Chromium's renderers share memory through it and
+///   crash with the 64 MiB default
+/// - at least 2 GiB of memory
+/// - the DevTools port published on a free host port
+/// - `BOXLITE_CHROMIUM_FLAGS` with the flags Chromium needs in a box:
+///   headless, no setuid sandbox (the VM is the sandbox), no GPU, and
+///   DevTools listening on all addresses so the port forward reaches it
+/// - an init command that installs fonts with the image's package manager
+///   when none are present, so pages do not render as empty boxes
+///
+/// ```bash
+/// chromium $BOXLITE_CHROMIUM_FLAGS https://example.com
+/// ```
+#[derive(Clone, Debug)]
+pub struct BrowserProfile {
+    /// Port Chromium's DevTools listens on in the box.
+    pub devtools_port: u16,
+}
+
+impl BrowserProfile {
+    /// `/dev/shm` size, in MiB.
+    pub const SHM_MIB: u32 = 1024;
+    /// Memory given to boxes that do not set any, in MiB.
+    pub const MEMORY_MIB: u32 = 2048;
+    /// Environment variable holding the Chromium flags.
+    pub const FLAGS_ENV: &'static str = "BOXLITE_CHROMIUM_FLAGS";
+
+    /// Best effort: a box without fonts still works, pages just render badly.
+    const FONTS_SCRIPT: &'static str = r#"if command -v fc-list >/dev/null 2>&1 && [ -n "$(fc-list)" ]; then :
+elif command -v apk >/dev/null 2>&1; then apk add --no-cache fontconfig font-noto font-noto-emoji
+elif command -v apt-get >/dev/null 2>&1; then apt-get update -q && DEBIAN_FRONTEND=noninteractive apt-get install -y -q --no-install-recommends fontconfig fonts-liberation fonts-noto-color-emoji
+elif command -v dnf >/dev/null 2>&1; then dnf install -y -q fontconfig liberation-fonts google-noto-emoji-color-fonts
+fi || echo "browser profile: could not install fonts" >&2"#;
+
+    fn chromium_flags(&self) -> String {
+        format!(
+            "--headless=new --no-sandbox --disable-gpu --no-first-run \
+             --no-default-browser-check --remote-debugging-address=0.0.0.0 \
+             --remote-debugging-port={}",
+            self.devtools_port
+        )
+    }
+}
+
+impl Default for BrowserProfile {
+    fn default() -> Self {
+        Self {
+            devtools_port: 9222,
+        }
+    }
+}
+
+impl BoxProfile for BrowserProfile {
+    fn name(&self) -> &str {
+        "browser"
+    }
+
+    fn description(&self) -> &str {
+        "headless Chromium: large /dev/shm, fonts, DevTools port forward"
+    }
+
+    fn apply(&self, options: &mut BoxOptions) {
+        if options.shm_size_mib.is_none() && !options.tmpfs.iter().any(|t| t.path == "/dev/shm") {
+            options.shm_size_mib = Some(Self::SHM_MIB);
+        }
+        options.memory_mib.get_or_insert(Self::MEMORY_MIB);
+        if !options.env.iter().any(|(key, _)| key == Self::FLAGS_ENV) {
+            options
+                .env
+                .push((Self::FLAGS_ENV.to_string(), self.chromium_flags()));
+        }
+        if !options
+            .ports
+            .iter()
+            .any(|port| port.guest_port == self.devtools_port)
+        {
+            options.ports.push(PortSpec {
+                host_port: None,
+                guest_port: self.devtools_port,
+                protocol: PortProtocol::Tcp,
+                host_ip: None,
+            });
+        }
+        // Fonts first, so the caller's own init commands can use them
+        options
+            .init_commands
+            .insert(0, Self::FONTS_SCRIPT.to_string());
+    }
+}
+
+/// The profiles that ship with boxlite.
+pub fn builtin_profiles() -> Vec<Box<dyn BoxProfile>> {
+    vec![Box::new(BrowserProfile::default())]
+}
+
+/// Look up a built-in profile by name.
+pub fn builtin_profile(name: &str) -> BoxliteResult<Box<dyn BoxProfile>> {
+    let profiles = builtin_profiles();
+    let names: Vec<String> = profiles.iter().map(|p| p.name().to_string()).collect();
+    profiles
+        .into_iter()
+        .find(|profile| profile.name() == name)
+        .ok_or_else(|| {
+            BoxliteError::InvalidArgument(format!(
+                "unknown profile '{}' (available: {})",
+                name,
+                names.join(", ")
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_browser_profile_fills_defaults() {
+        let mut options = BoxOptions::default();
+        builtin_profile("browser").unwrap().apply(&mut options);
+
+        assert_eq!(options.shm_size_mib, Some(BrowserProfile::SHM_MIB));
+        assert_eq!(options.memory_mib, Some(BrowserProfile::MEMORY_MIB));
+        assert_eq!(options.ports.len(), 1);
+        assert_eq!(options.ports[0].guest_port, 9222);
+        assert_eq!(options.ports[0].host_port, None);
+        let flags = &options
+            .env
+            .iter()
+            .find(|(key, _)| key == BrowserProfile::FLAGS_ENV)
+            .unwrap()
+            .1;
+        assert!(flags.contains("--remote-debugging-port=9222"));
+        assert_eq!(options.init_commands.len(), 1);
+        assert!(options.sanitize().is_ok());
+    }
+
+    #[test]
+    fn test_browser_profile_keeps_explicit_settings() {
+        let mut options = BoxOptions {
+            memory_mib: Some(4096),
+            shm_size_mib: Some(256),
+            env: vec![(BrowserProfile::FLAGS_ENV.to_string(), "--headless".into())],
+            ports: vec![PortSpec {
+                host_port: Some(9000),
+                guest_port: 9222,
+                protocol: PortProtocol::Tcp,
+                host_ip: None,
+            }],
+            init_commands: vec!["npm ci".into()],
+            ..Default::default()
+        };
+        BrowserProfile::default().apply(&mut options);
+
+        assert_eq!(options.memory_mib, Some(4096));
+        assert_eq!(options.shm_size_mib, Some(256));
+        assert_eq!(options.env.len(), 1);
+        assert_eq!(options.ports.len(), 1);
+        assert_eq!(options.ports[0].host_port, Some(9000));
+        assert_eq!(options.init_commands.len(), 2);
+        assert_eq!(options.init_commands[1], "npm ci");
+    }
+
+    #[test]
+    fn test_unknown_profile() {
+        let err = builtin_profile("gpu").err().unwrap();
+        assert!(err.to_string().contains("available: browser"));
+    }
+}
@@ -0,0 +1,260 @@
+//! Applying new [`BoxliteOptions`] to a running runtime.
+//!
+//! Registry, proxy, bandwidth, blob cache and admission settings take effect
+//! for the next pull or box start; cache reclaim, heartbeat, metrics history,
+//! retry and proxy propagation settings for boxes started afterwards. Host
+//! cgroup limits are rewritten at once; background priority applies to
+//! boxes started afterwards, and the retention policy to the next pruning
+//! pass. Options that shape on-disk state (home
+//! directory, layer dedup, guest rootfs, rootfs format) or runtime processes
+//! (shared network daemon) need a new runtime, and a reload keeps their old
+//! values.
+
+use std::fmt;
+
+use crate::runtime::options::BoxliteOptions;
+
+/// What [`BoxliteRuntime::reload_options`](crate::BoxliteRuntime::reload_options)
+/// changed, by `BoxliteOptions` field name.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OptionsReload {
+    /// Changed options that are now in effect.
+    pub applied: Vec<&'static str>,
+    /// Changed options that only take effect in a new runtime; their old
+    /// values stay in use.
+    pub restart_required: Vec<&'static str>,
+}
+
+impl OptionsReload {
+    /// Whether no option changed.
+    pub fn is_empty(&self) -> bool {
+        self.applied.is_empty() && self.restart_required.is_empty()
+    }
+}
+
+impl fmt::Display for OptionsReload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no changes");
+        }
+        let mut parts = Vec::new();
+        if !self.applied.is_empty() {
+            parts.push(format!("applied {}", self.applied.join(", ")));
+        }
+        if !self.restart_required.is_empty() {
+            parts.push(format!(
+                "restart required for {}",
+                self.restart_required.join(", ")
+            ));
+        }
+        write!(f, "{}", parts.join("; "))
+    }
+}
+
+/// Compare the options in effect with `new`, field by field.
+pub(crate) fn diff(old: &BoxliteOptions, new: &BoxliteOptions) -> OptionsReload {
+    // Destructured so a new field cannot be added without deciding whether
+    // it can be reloaded.
+    let BoxliteOptions {
+        home_dir,
+        state_dir,
+        image_cache_dir,
+        volumes_dir,
+        image_registries,
+        short_name_aliases,
+        registry_mirrors,
+        registries,
+        pull_rate_limit_mbps,
+        blob_cache,
+        dedup_layers,
+        max_running_boxes,
+        max_total_memory_mib,
+        admission_policy,
+        cache_reclaim,
+        proxy,
+        create_retries,
+        heartbeat,
+        metrics_history,
+        guest_rootfs_path,
+        rootfs_format,
+        templates,
+        host_limits,
+        retention,
+        shared_network,
+    } = new;
+
+    let mut reload = OptionsReload::default();
+    let mut live = |name: &'static str, changed: bool| {
+        if changed {
+            reload.applied.push(name);
+        }
+    };
+    live(
+        "image_registries",
+        *image_registries != old.image_registries,
+    );
+    live(
+        "short_name_aliases",
+        *short_name_aliases != old.short_name_aliases,
+    );
+    live(
+        "registry_mirrors",
+        *registry_mirrors != old.registry_mirrors,
+    );
+    live("registries", *registries != old.registries);
+    live(
+        "pull_rate_limit_mbps",
+        *pull_rate_limit_mbps != old.pull_rate_limit_mbps,
+    );
+    live("blob_cache", *blob_cache != old.blob_cache);
+    live(
+        "max_running_boxes",
+        *max_running_boxes != old.max_running_boxes,
+    );
+    live(
+        "max_total_memory_mib",
+        *max_total_memory_mib != old.max_total_memory_mib,
+    );
+    live(
+        "admission_policy",
+        *admission_policy != old.admission_policy,
+    );
+    live("cache_reclaim", *cache_reclaim != old.cache_reclaim);
+    live("proxy", *proxy != old.proxy);
+    live("create_retries", *create_retries != old.create_retries);
+    live("heartbeat", *heartbeat != old.heartbeat);
+    live("metrics_history", *metrics_history != old.metrics_history);
+    live("host_limits", *host_limits != old.host_limits);
+    live("retention", *retention != old.retention);
+    // BoxOptions has no PartialEq; compare the serialized form.
+    live(
+        "templates",
+        serde_json::to_value(templates).ok() != serde_json::to_value(&old.templates).ok(),
+    );
+
+    let mut restart = |name: &'static str, changed: bool| {
+        if changed {
+            reload.restart_required.push(name);
+        }
+    };
+    restart("home_dir", *home_dir != old.home_dir);
+    restart("state_dir", *state_dir != old.state_dir);
+    restart("image_cache_dir", *image_cache_dir != old.image_cache_dir);
+    restart("volumes_dir", *volumes_dir != old.volumes_dir);
+    restart("dedup_layers", *dedup_layers != old.dedup_layers);
+    restart(
+        "guest_rootfs_path",
+        *guest_rootfs_path != old.guest_rootfs_path,
+    );
+    restart("rootfs_format", *rootfs_format != old.rootfs_format);
+    restart("shared_network", *shared_network != old.shared_network);
+    reload
+}
+
+/// `new` with the restart-only options kept at their `old` values: the
+/// options in effect after a reload.
+pub(crate) fn effective(old: &BoxliteOptions, new: BoxliteOptions) -> BoxliteOptions {
+    BoxliteOptions {
+        home_dir: old.home_dir.clone(),
+        state_dir: old.state_dir.clone(),
+        image_cache_dir: old.image_cache_dir.clone(),
+        volumes_dir: old.volumes_dir.clone(),
+        dedup_layers: old.dedup_layers,
+        guest_rootfs_path: old.guest_rootfs_path.clone(),
+        rootfs_format: old.rootfs_format,
+        shared_network: old.shared_network,
+        ..new
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::options::{AdmissionPolicy, RootfsFormat};
+    use crate::vmm::mock::{MockScript, test_runtime};
+
+    #[test]
+    fn test_diff_sorts_changes() {
+        let old = BoxliteOptions::default();
+        assert!(diff(&old, &old.clone()).is_empty());
+        assert_eq!(diff(&old, &old.clone()).to_string(), "no changes");
+
+        let new = BoxliteOptions {
+            image_registries: vec!["ghcr.io".to_string()],
+            max_running_boxes: Some(4),
+            admission_policy: AdmissionPolicy::Queue { timeout_secs: None },
+            rootfs_format: RootfsFormat::Erofs,
+            dedup_layers: !old.dedup_layers,
+            ..old.clone()
+        };
+        let reload = diff(&old, &new);
+        assert_eq!(
+            reload.applied,
+            ["image_registries", "max_running_boxes", "admission_policy"]
+        );
+        assert_eq!(reload.restart_required, ["dedup_layers", "rootfs_format"]);
+        assert_eq!(
+            reload.to_string(),
+            "applied image_registries, max_running_boxes, admission_policy; \
+             restart required for dedup_layers, rootfs_format"
+        );
+
+        // A reload keeps restart-only options, so reloading the same file
+        // again still reports them.
+        let kept = effective(&old, new.clone());
+        assert_eq!(kept.rootfs_format, old.rootfs_format);
+        assert_eq!(kept.max_running_boxes, Some(4));
+        assert_eq!(
+            diff(&kept, &new).restart_required,
+            ["dedup_layers", "rootfs_format"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reload_options() {
+        use crate::{
+            BlobCacheBackend, BlobCacheOptions, BoxOptions, BoxliteError, BoxliteOptions,
+            RootfsFormat,
+        };
+
+        let (home, runtime) = test_runtime(BoxliteOptions::default(), MockScript::new());
+        let base = BoxliteOptions {
+            home_dir: home.path().to_path_buf(),
+            ..Default::default()
+        };
+        let running = runtime.create(BoxOptions::default(), None).await.unwrap();
+        running.start().await.unwrap();
+
+        let limited = BoxliteOptions {
+            max_running_boxes: Some(1),
+            rootfs_format: RootfsFormat::Squashfs,
+            ..base.clone()
+        };
+        let changes = runtime.reload_options(limited.clone()).unwrap();
+        assert_eq!(changes.applied, ["max_running_boxes"]);
+        assert_eq!(changes.restart_required, ["rootfs_format"]);
+        let queued = runtime.create(BoxOptions::default(), None).await.unwrap();
+        let err = queued.start().await.unwrap_err();
+        assert!(matches!(err, BoxliteError::ResourceExhausted(_)), "{}", err);
+
+        // Invalid options are rejected as a whole
+        let invalid = BoxliteOptions {
+            max_running_boxes: None,
+            blob_cache: Some(BlobCacheOptions {
+                backend: BlobCacheBackend::Http {
+                    url: "cache.internal".to_string(),
+                    headers: Default::default(),
+                },
+                write_back: false,
+            }),
+            ..base.clone()
+        };
+        assert!(runtime.reload_options(invalid).is_err());
+        assert!(queued.start().await.is_err());
+
+        let changes = runtime.reload_options(base).unwrap();
+        assert_eq!(changes.applied, ["max_running_boxes"]);
+        assert!(changes.restart_required.is_empty());
+        queued.start().await.unwrap();
+    }
+}
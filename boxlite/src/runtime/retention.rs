@@ -0,0 +1,450 @@
+//! Automatic pruning of stopped boxes and unused images
+//! (`BoxliteOptions::retention`).
+//!
+//! A background task runs a pass every `interval_secs`. A pass first removes
+//! boxes that have been stopped for longer than `max_stopped_box_age_secs`,
+//! then evicts the least recently used images while the image cache is over
+//! `max_image_cache_mib`. Every removal is logged as an info event.
+//!
+//! An image is kept while any box uses it, while it carries a local tag, and
+//! when it was used after the pass started. Cached disk images that box or
+//! snapshot disks are layered on stay even when their image goes.
+//!
+//! [`system_prune`] is the one-off cleanup behind `boxlite system prune`: it
+//! removes every stopped box, image data no image refers to, anonymous
+//! volumes no box mounts and old runtime logs. Files changed within
+//! [`GRACE_PERIOD`] are left alone, since a pull or `boxlite run` may be
+//! about to use them.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use chrono::{TimeDelta, Utc};
+use walkdir::WalkDir;
+
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+
+use crate::disk::Qcow2Helper;
+use crate::images::tree_usage;
+use crate::runtime::constants::images::INIT_ROOTFS;
+use crate::runtime::options::RetentionPolicy;
+use crate::runtime::rt_impl::RuntimeImpl;
+use crate::runtime::types::{BoxStatus, PruneReport, SystemPruneOptions, SystemPruneReport};
+
+/// Seconds between checks while no retention policy is set, so one added by
+/// `reload_options` is picked up.
+const IDLE_CHECK_SECS: u64 = 300;
+
+/// Files changed more recently than this are never pruned by
+/// [`system_prune`].
+const GRACE_PERIOD: Duration = Duration::from_secs(60 * 60);
+
+/// Start the background reaper of `runtime`.
+///
+/// The policy is read before each pass, so a reload applies from the next
+/// one. The task holds no strong reference between passes and ends with the
+/// runtime.
+pub(crate) fn spawn_reaper(runtime: &Arc<RuntimeImpl>) {
+    let Ok(handle) = tokio::runtime::Handle::try_current() else {
+        tracing::debug!("No tokio runtime, retention reaper not started");
+        return;
+    };
+    let weak = Arc::downgrade(runtime);
+    let shutdown = runtime.shutdown_token.clone();
+
+    handle.spawn(async move {
+        loop {
+            let interval = match weak.upgrade() {
+                Some(runtime) => runtime
+                    .retention_policy()
+                    .map_or(IDLE_CHECK_SECS, |policy| policy.interval_secs),
+                None => return,
+            };
+            tokio::select! {
+                _ = shutdown.cancelled() => return,
+                _ = tokio::time::sleep(Duration::from_secs(interval)) => {}
+            }
+
+            let Some(runtime) = weak.upgrade() else {
+                return;
+            };
+            let Some(policy) = runtime.retention_policy() else {
+                continue;
+            };
+            if let Err(e) = prune(&runtime, &policy).await {
+                tracing::warn!(error = %e, "Retention pass failed");
+            }
+        }
+    });
+}
+
+/// Run one pruning pass with `policy`.
+pub(crate) async fn prune(
+    runtime: &Arc<RuntimeImpl>,
+    policy: &RetentionPolicy,
+) -> BoxliteResult<PruneReport> {
+    let started_at = Utc::now();
+    let mut report = PruneReport::default();
+
+    if let Some(cutoff) = policy
+        .max_stopped_box_age_secs
+        .and_then(|secs| TimeDelta::try_seconds(i64::try_from(secs).ok()?))
+        .and_then(|age| started_at.checked_sub_signed(age))
+    {
+        for info in runtime.list_info().await? {
+            // A box someone holds a handle to may be started again any moment
+            if info.status != BoxStatus::Stopped
+                || info.last_updated > cutoff
+                || runtime.has_handle(&info.id)
+            {
+                continue;
+            }
+            match runtime.remove(info.id.as_str(), false) {
+                Ok(()) => {
+                    tracing::info!(
+                        box_id = %info.id,
+                        name = ?info.name,
+                        stopped_at = %info.last_updated,
+                        "Pruned stopped box"
+                    );
+                    report.boxes.push(info.id);
+                }
+                Err(e) => {
+                    tracing::warn!(box_id = %info.id, error = %e, "Failed to prune stopped box");
+                }
+            }
+        }
+    }
+
+    if let Some(max_mib) = policy.max_image_cache_mib {
+        let max_bytes = max_mib.saturating_mul(1024 * 1024);
+
+        let (keep, keep_disks) = images_in_use(runtime).await?;
+        let (images, cache_bytes) = runtime
+            .image_manager
+            .prune_lru(max_bytes, keep, keep_disks, started_at)
+            .await?;
+        if cache_bytes > max_bytes {
+            tracing::warn!(
+                cache_bytes,
+                max_bytes,
+                "Image cache is over max_image_cache_mib but the remaining images are in use"
+            );
+        }
+        report.images = images;
+        report.image_cache_bytes = Some(cache_bytes);
+    }
+
+    Ok(report)
+}
+
+/// Remove stopped boxes, dangling image data, unused anonymous volumes and
+/// old logs in one pass.
+pub(crate) async fn system_prune(
+    runtime: &Arc<RuntimeImpl>,
+    options: &SystemPruneOptions,
+) -> BoxliteResult<SystemPruneReport> {
+    let started_at = Utc::now();
+    let older_than = SystemTime::now() - GRACE_PERIOD;
+    let mut report = SystemPruneReport::default();
+    let usage_before = runtime.disk_usage().await?.total_bytes();
+
+    for info in runtime.list_info().await? {
+        if info.status != BoxStatus::Stopped || runtime.has_handle(&info.id) {
+            continue;
+        }
+        match runtime.remove(info.id.as_str(), false) {
+            Ok(()) => {
+                tracing::info!(box_id = %info.id, name = ?info.name, "Pruned stopped box");
+                report.boxes.push(info.id);
+            }
+            Err(e) => {
+                tracing::warn!(box_id = %info.id, error = %e, "Failed to prune stopped box");
+            }
+        }
+    }
+
+    let (keep, keep_disks) = images_in_use(runtime).await?;
+    if options.all_images {
+        let (images, _) = runtime
+            .image_manager
+            .prune_lru(0, keep, keep_disks.clone(), started_at)
+            .await?;
+        report.images = images;
+    }
+    report.dangling_blobs = runtime
+        .image_manager
+        .prune_dangling(keep_disks, older_than)
+        .await?;
+
+    let mounted: HashSet<PathBuf> = runtime
+        .box_manager
+        .all_boxes(true)?
+        .into_iter()
+        .flat_map(|(config, _)| config.options.volumes)
+        .map(|volume| canonical(PathBuf::from(volume.host_path)))
+        .collect();
+    let anonymous_dir = runtime.layout.anonymous_volumes_dir();
+    let logs_dir = runtime.layout.logs_dir();
+    let logs_before = SystemTime::now()
+        .checked_sub(options.logs_max_age)
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+    let (volumes, logs, freed) = tokio::task::spawn_blocking(move || {
+        let volumes = remove_entries(&anonymous_dir, older_than, |path| {
+            path.is_dir() && !mounted.contains(&canonical(path.to_path_buf()))
+        });
+        let logs = remove_entries(&logs_dir, logs_before, |path| path.is_file());
+        let freed = volumes
+            .iter()
+            .chain(&logs)
+            .map(|(_, bytes)| bytes)
+            .sum::<u64>();
+        let paths = |entries: Vec<(PathBuf, u64)>| -> Vec<PathBuf> {
+            entries.into_iter().map(|(path, _)| path).collect()
+        };
+        (paths(volumes), paths(logs), freed)
+    })
+    .await
+    .map_err(|e| BoxliteError::Internal(format!("spawn_blocking failed: {}", e)))?;
+    report.volumes = volumes;
+    report.logs = logs;
+
+    let usage_after = runtime.disk_usage().await?.total_bytes();
+    report.reclaimed_bytes = usage_before.saturating_sub(usage_after) + freed;
+    Ok(report)
+}
+
+/// Manifest digests of the images boxes use (and the guest rootfs), and the
+/// cached disk images box and snapshot disks are layered on.
+async fn images_in_use(
+    runtime: &Arc<RuntimeImpl>,
+) -> BoxliteResult<(HashSet<String>, HashSet<PathBuf>)> {
+    // The guest rootfs image is needed by every box start
+    let mut image_refs: Vec<String> = runtime
+        .list_info()
+        .await?
+        .into_iter()
+        .map(|info| info.image)
+        .collect();
+    for (config, _) in runtime.box_manager.all_boxes(false)? {
+        image_refs.extend(config.options.sidecars.into_iter().map(|s| s.image));
+    }
+    image_refs.push(INIT_ROOTFS.to_string());
+    let mut keep = HashSet::new();
+    for image_ref in image_refs {
+        if let Ok(Some(digest)) = runtime.image_manager.resolve_digest(&image_ref).await {
+            keep.insert(digest);
+        }
+    }
+
+    let disk_dirs = vec![runtime.layout.boxes_dir(), runtime.layout.snapshots_dir()];
+    let keep_disks = tokio::task::spawn_blocking(move || backing_files(&disk_dirs))
+        .await
+        .map_err(|e| BoxliteError::Internal(format!("spawn_blocking failed: {}", e)))?;
+    Ok((keep, keep_disks))
+}
+
+/// Delete the entries of `dir` last modified before `before` that `prunable`
+/// accepts, returning each with the bytes it took.
+fn remove_entries(
+    dir: &Path,
+    before: SystemTime,
+    prunable: impl Fn(&Path) -> bool,
+) -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut removed = Vec::new();
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        let stale = entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .is_ok_and(|modified| modified < before);
+        if !stale || !prunable(&path) {
+            continue;
+        }
+        let bytes = tree_usage(std::slice::from_ref(&path)).physical;
+        let result = if path.is_dir() {
+            std::fs::remove_dir_all(&path)
+        } else {
+            std::fs::remove_file(&path)
+        };
+        match result {
+            Ok(()) => {
+                tracing::info!(path = %path.display(), bytes, "Pruned unused file");
+                removed.push((path, bytes));
+            }
+            Err(e) => {
+                tracing::warn!(path = %path.display(), error = %e, "Failed to prune file");
+            }
+        }
+    }
+    removed
+}
+
+pub(crate) fn canonical(path: PathBuf) -> PathBuf {
+    path.canonicalize().unwrap_or(path)
+}
+
+/// Files that the qcow2 disks under `dirs` are layered on, canonicalized.
+pub(crate) fn backing_files(dirs: &[PathBuf]) -> HashSet<PathBuf> {
+    let mut files = HashSet::new();
+    for dir in dirs {
+        for entry in WalkDir::new(dir)
+            .follow_links(false)
+            .into_iter()
+            .filter_map(Result::ok)
+        {
+            let path = entry.path();
+            if !entry.file_type().is_file() || path.extension().is_none_or(|ext| ext != "qcow2") {
+                continue;
+            }
+            match Qcow2Helper::backing_chain(path) {
+                Ok(chain) => {
+                    files.extend(
+                        chain
+                            .into_iter()
+                            .skip(1)
+                            .map(|file| file.canonicalize().unwrap_or(file)),
+                    );
+                }
+                Err(e) => {
+                    tracing::debug!(path = %path.display(), error = %e, "Skipping unreadable disk");
+                }
+            }
+        }
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vmm::mock::{MockScript, test_runtime};
+
+    #[tokio::test]
+    async fn test_prune_stopped_boxes() {
+        use crate::{BoxOptions, BoxliteOptions, RetentionPolicy};
+
+        let (_home, runtime) = test_runtime(BoxliteOptions::default(), MockScript::new());
+        let kept_options = || BoxOptions {
+            auto_remove: false,
+            ..Default::default()
+        };
+        let old = runtime
+            .create(kept_options(), Some("old".to_string()))
+            .await
+            .unwrap();
+        let held = runtime
+            .create(kept_options(), Some("held".to_string()))
+            .await
+            .unwrap();
+        let running = runtime
+            .create(kept_options(), Some("running".to_string()))
+            .await
+            .unwrap();
+        for litebox in [&old, &held, &running] {
+            litebox.start().await.unwrap();
+        }
+        old.stop().await.unwrap();
+        held.stop().await.unwrap();
+        let old_id = old.id().clone();
+        drop(old);
+        // stop() retires the cached handle; a fresh one marks the box as held
+        let held = runtime.get("held").await.unwrap().unwrap();
+
+        let policy = RetentionPolicy {
+            max_stopped_box_age_secs: Some(0),
+            ..Default::default()
+        };
+        let report = runtime.prune(&policy).await.unwrap();
+        assert_eq!(report.boxes, vec![old_id]);
+        assert!(report.images.is_empty());
+        assert_eq!(report.image_cache_bytes, None);
+        assert!(!runtime.exists("old").await.unwrap());
+        assert!(runtime.exists("held").await.unwrap());
+        assert!(runtime.exists("running").await.unwrap());
+
+        // Nothing is old enough under a day
+        drop(held);
+        let policy = RetentionPolicy {
+            max_stopped_box_age_secs: Some(86400),
+            ..Default::default()
+        };
+        assert!(runtime.prune(&policy).await.unwrap().boxes.is_empty());
+        running.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_system_prune() {
+        use crate::runtime::options::VolumeSpec;
+        use crate::{BoxOptions, BoxliteOptions, SystemPruneOptions};
+
+        let (home, runtime) = test_runtime(BoxliteOptions::default(), MockScript::new());
+        let anonymous = home.path().join("volumes").join("anonymous");
+        let logs = home.path().join("logs");
+        let mounted = anonymous.join("mounted");
+        let unused = anonymous.join("unused");
+        let old_log = logs.join("boxlite.log.2020-01-01");
+        let new_log = logs.join("boxlite.log");
+        for dir in [&mounted, &unused, &logs] {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        std::fs::write(unused.join("data"), vec![1u8; 8192]).unwrap();
+        std::fs::write(&old_log, b"old").unwrap();
+        std::fs::write(&new_log, b"new").unwrap();
+        let long_ago = filetime::FileTime::from_unix_time(1_577_836_800, 0);
+        for path in [&mounted, &unused, &old_log] {
+            filetime::set_file_mtime(path, long_ago).unwrap();
+        }
+
+        let stopped = runtime
+            .create(
+                BoxOptions {
+                    auto_remove: false,
+                    ..Default::default()
+                },
+                Some("stopped".to_string()),
+            )
+            .await
+            .unwrap();
+        let running = runtime
+            .create(
+                BoxOptions {
+                    auto_remove: false,
+                    volumes: vec![VolumeSpec {
+                        host_path: mounted.to_string_lossy().into_owned(),
+                        guest_path: "/data".into(),
+                        read_only: false,
+                        chown: false,
+                        id_shift: None,
+                    }],
+                    ..Default::default()
+                },
+                Some("running".to_string()),
+            )
+            .await
+            .unwrap();
+        stopped.start().await.unwrap();
+        running.start().await.unwrap();
+        stopped.stop().await.unwrap();
+        let stopped_id = stopped.id().clone();
+        drop(stopped);
+
+        let report = runtime
+            .system_prune(&SystemPruneOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(report.boxes, vec![stopped_id]);
+        assert_eq!(report.volumes, vec![unused.clone()]);
+        assert_eq!(report.logs, vec![old_log.clone()]);
+        assert!(report.reclaimed_bytes >= 8192);
+        assert!(!runtime.exists("stopped").await.unwrap());
+        assert!(runtime.exists("running").await.unwrap());
+        assert!(mounted.exists() && !unused.exists());
+        assert!(new_log.exists() && !old_log.exists());
+        running.stop().await.unwrap();
+    }
+}
@@ -1,21 +1,39 @@
-use crate::db::{BoxStore, Database};
-use crate::images::ImageManager;
+use crate::db::{BoxStore, Database, MetricsSampleStore};
+use crate::disk::Qcow2Helper;
+use crate::images::{ContentStore, ImageManager, tree_usage};
 use crate::init_logging_for;
 use crate::litebox::config::BoxConfig;
-use crate::litebox::{BoxManager, LiteBox, SharedBoxImpl};
-use crate::lock::{FileLockManager, LockManager};
+use crate::litebox::{BoxManager, LiteBox, SharedBoxImpl, plan_box, requested_ports};
+use crate::lock::{FileLockManager, LockGuard, LockManager};
 use crate::metrics::{RuntimeMetrics, RuntimeMetricsStorage};
+use crate::net::forwarder::check_host_port;
+#[cfg(feature = "gvproxy-backend")]
+use crate::net::gvproxy::shared::{self, SharedNetworkDaemon};
+use crate::runtime::admission::{AdmissionController, AdmissionLimits, Reservation};
+use crate::runtime::archive;
 use crate::runtime::constants::filenames;
+use crate::runtime::constants::vm_defaults::{DEFAULT_MEMORY_MIB, MAX_SNAPSHOT_DEPTH};
+use crate::runtime::fd_budget::{self, FdBudget};
 use crate::runtime::guest_rootfs::GuestRootfs;
 use crate::runtime::layout::{FilesystemLayout, FsLayoutConfig};
 use crate::runtime::lock::RuntimeLock;
-use crate::runtime::options::{BoxOptions, BoxliteOptions};
+use crate::runtime::options::{
+    BoxOptions, BoxliteOptions, CacheReclaimPolicy, HeartbeatPolicy, HostLimits,
+    MetricsHistoryPolicy, NetworkSpec, PortSpec, RetentionPolicy, RootfsFormat,
+};
+use crate::runtime::reload::{self, OptionsReload};
+use crate::runtime::retention::{self, canonical};
 use crate::runtime::signal_handler::timeout_to_duration;
-use crate::runtime::types::{BoxID, BoxInfo, BoxState, BoxStatus, ContainerID};
+use crate::runtime::types::{
+    BoxID, BoxInfo, BoxPlan, BoxShutdown, BoxShutdownOutcome, BoxState, BoxStatus, ContainerID,
+    DiskUsage, ImportOptions, PruneReport, ShutdownEvent, ShutdownReport, SystemPruneOptions,
+    SystemPruneReport,
+};
 use crate::vmm::VmmKind;
 use boxlite_shared::{BoxliteError, BoxliteResult, Transport};
 use chrono::Utc;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock, Weak};
 use tokio::sync::OnceCell;
 use tokio_util::sync::CancellationToken;
@@ -54,8 +72,26 @@ pub struct RuntimeImpl {
     pub(crate) layout: FilesystemLayout,
     /// Guest rootfs lazy initialization (Arc<OnceCell>)
     pub(crate) guest_rootfs: Arc<OnceCell<GuestRootfs>>,
+    /// Custom guest rootfs (None = bundled init image)
+    pub(crate) guest_rootfs_path: Option<PathBuf>,
+    /// Filesystem for per-image container rootfs disks
+    pub(crate) rootfs_format: RootfsFormat,
     /// Runtime-wide metrics (AtomicU64 based, lock-free)
     pub(crate) runtime_metrics: RuntimeMetricsStorage,
+    /// Per-box resource usage history (SQLite, internally synchronized)
+    pub(crate) metrics_samples: MetricsSampleStore,
+    /// Admission control for box count / memory limits (internal Mutex)
+    pub(crate) admission: AdmissionController,
+    /// Settings read when a box starts (replaced by `reload_options`)
+    pub(crate) settings: parking_lot::RwLock<RuntimeSettings>,
+    /// Options in effect, diffed against by `reload_options`
+    options: parking_lot::Mutex<BoxliteOptions>,
+    /// Script for the in-process mock engine (Some = boxes use `VmmKind::Mock`)
+    #[cfg(any(test, feature = "testing"))]
+    pub(crate) mock_script: Option<crate::vmm::mock::MockScript>,
+    /// Shared network daemon (None = each box hosts its own network backend)
+    #[cfg(feature = "gvproxy-backend")]
+    network_daemon: Option<Arc<SharedNetworkDaemon>>,
 
     /// Per-entity lock manager for multiprocess-safe locking.
     ///
@@ -89,6 +125,41 @@ pub struct SynchronizedState {
     active_boxes_by_name: HashMap<String, Weak<crate::litebox::box_impl::BoxImpl>>,
 }
 
+/// Per-box settings taken from `BoxliteOptions`, read as each box starts.
+#[derive(Clone, Debug)]
+pub(crate) struct RuntimeSettings {
+    /// Idle memory reclaim policy (None = disabled)
+    pub(crate) cache_reclaim_policy: Option<CacheReclaimPolicy>,
+    /// Guest agent liveness checks (None = disabled)
+    pub(crate) heartbeat_policy: Option<HeartbeatPolicy>,
+    /// Resource usage sampling (None = disabled)
+    pub(crate) metrics_history_policy: Option<MetricsHistoryPolicy>,
+    /// Proxy variables added to new boxes (empty = propagation disabled)
+    pub(crate) box_proxy_env: Vec<(String, String)>,
+    /// Extra attempts for transient init failures (pull, guest connect)
+    pub(crate) create_retries: u32,
+    /// Host cgroup and priority of box processes (None = unlimited)
+    pub(crate) host_limits: Option<HostLimits>,
+}
+
+impl RuntimeSettings {
+    fn from_options(options: &BoxliteOptions) -> Self {
+        let proxy = options.proxy.resolve();
+        Self {
+            cache_reclaim_policy: options.cache_reclaim.clone(),
+            heartbeat_policy: options.heartbeat.clone(),
+            metrics_history_policy: options.metrics_history.clone(),
+            box_proxy_env: if proxy.propagate_to_boxes {
+                proxy.env_vars()
+            } else {
+                Vec::new()
+            },
+            create_retries: options.create_retries,
+            host_limits: options.host_limits.clone(),
+        }
+    }
+}
+
 impl RuntimeImpl {
     // ========================================================================
     // CONSTRUCTION
@@ -98,15 +169,42 @@ impl RuntimeImpl {
     ///
     /// Performs all initialization: filesystem setup, locks, managers, and box recovery.
     pub fn new(options: BoxliteOptions) -> BoxliteResult<SharedRuntimeImpl> {
-        let vmm_support = crate::vmm::host_check::check_virtualization_support().map_err(|e| {
-            BoxliteError::Internal(format!("Failed to check virtualization support: {}", e))
-        })?;
+        #[cfg(feature = "testing")]
+        if std::env::var(crate::runtime::constants::envs::BOXLITE_ENGINE)
+            .is_ok_and(|engine| engine.parse::<VmmKind>().ok() == Some(VmmKind::Mock))
+        {
+            return Self::with_mock_engine(options, crate::vmm::mock::MockScript::default());
+        }
+
+        let vmm_support = crate::vmm::host_check::check_virtualization_support()?;
 
         tracing::info!(
             reason = %vmm_support.reason,
             "Virtualization support verified"
         );
 
+        Self::init(
+            options,
+            #[cfg(any(test, feature = "testing"))]
+            None,
+        )
+    }
+
+    /// Create a runtime whose boxes use the in-process mock engine.
+    ///
+    /// Skips the virtualization check: mock boxes never start a VM.
+    #[cfg(any(test, feature = "testing"))]
+    pub fn with_mock_engine(
+        options: BoxliteOptions,
+        script: crate::vmm::mock::MockScript,
+    ) -> BoxliteResult<SharedRuntimeImpl> {
+        Self::init(options, Some(script))
+    }
+
+    fn init(
+        options: BoxliteOptions,
+        #[cfg(any(test, feature = "testing"))] mock_script: Option<crate::vmm::mock::MockScript>,
+    ) -> BoxliteResult<SharedRuntimeImpl> {
         // Validate Early: Check preconditions before expensive work
         if !options.home_dir.is_absolute() {
             return Err(BoxliteError::Internal(format!(
@@ -115,13 +213,42 @@ impl RuntimeImpl {
             )));
         }
 
+        for (name, dir) in [
+            ("state_dir", &options.state_dir),
+            ("image_cache_dir", &options.image_cache_dir),
+            ("volumes_dir", &options.volumes_dir),
+        ] {
+            if let Some(dir) = dir
+                && !dir.is_absolute()
+            {
+                return Err(BoxliteError::Config(format!(
+                    "{} must be absolute path, got: {}",
+                    name,
+                    dir.display()
+                )));
+            }
+        }
+
+        if let Some(path) = &options.guest_rootfs_path
+            && !path.is_absolute()
+        {
+            return Err(BoxliteError::Config(format!(
+                "guest_rootfs_path must be absolute path, got: {}",
+                path.display()
+            )));
+        }
+
+        if let Some(policy) = &options.retention {
+            policy.validate()?;
+        }
+
         // Configure bind mount support based on platform
         #[cfg(target_os = "linux")]
         let fs_config = FsLayoutConfig::with_bind_mount();
         #[cfg(not(target_os = "linux"))]
         let fs_config = FsLayoutConfig::without_bind_mount();
 
-        let layout = FilesystemLayout::new(options.home_dir.clone(), fs_config);
+        let layout = FilesystemLayout::from_options(&options, fs_config);
 
         layout.prepare().map_err(|e| {
             BoxliteError::Storage(format!(
@@ -133,6 +260,10 @@ impl RuntimeImpl {
 
         init_logging_for(&layout)?;
 
+        if let Some(limits) = &options.host_limits {
+            apply_host_limits(limits)?;
+        }
+
         let runtime_lock = RuntimeLock::acquire(layout.home_dir()).map_err(|e| {
             BoxliteError::Internal(format!(
                 "Failed to acquire runtime lock at {}: {}",
@@ -142,7 +273,10 @@ impl RuntimeImpl {
         })?;
 
         // Clean temp dir contents to avoid stale files from previous runs
-        if let Ok(entries) = std::fs::read_dir(layout.temp_dir()) {
+        for temp_dir in [layout.temp_dir(), layout.image_temp_dir()] {
+            let Ok(entries) = std::fs::read_dir(temp_dir) else {
+                continue;
+            };
             for entry in entries.flatten() {
                 let path = entry.path();
                 if path.is_dir() {
@@ -161,17 +295,51 @@ impl RuntimeImpl {
             ))
         })?;
 
-        let image_manager =
-            ImageManager::new(layout.images_dir(), db.clone(), options.image_registries).map_err(
-                |e| {
-                    BoxliteError::Storage(format!(
-                        "Failed to initialize image manager at {}: {}",
-                        layout.images_dir().display(),
-                        e
-                    ))
-                },
-            )?;
+        let admission_limits = AdmissionLimits::from_options(&options);
+        let settings = RuntimeSettings::from_options(&options);
+        let guest_rootfs_path = options.guest_rootfs_path.clone();
+        let rootfs_format = options.rootfs_format;
+        #[cfg(feature = "gvproxy-backend")]
+        let network_daemon = options.shared_network.then(|| {
+            let daemon = Arc::new(SharedNetworkDaemon::new(layout.home_dir()));
+            if let Err(e) = daemon.ensure_running() {
+                tracing::warn!(
+                    error = %e,
+                    "Network daemon unavailable, boxes use a per-box backend until it starts"
+                );
+            }
+            daemon
+        });
 
+        let image_manager = ImageManager::new(
+            layout.images_dir(),
+            db.clone(),
+            options.image_registries.clone(),
+            &options.short_name_aliases,
+            &options.registry_mirrors,
+            &options.registries,
+            &options.proxy,
+            options.pull_rate_limit_mbps,
+            options.dedup_layers,
+            options.blob_cache.as_ref(),
+        )
+        .map_err(|e| {
+            BoxliteError::Storage(format!(
+                "Failed to initialize image manager at {}: {}",
+                layout.images_dir().display(),
+                e
+            ))
+        })?;
+
+        let (image_layers_cached, image_layers_fetched) = image_manager.layer_counters();
+        let runtime_metrics = RuntimeMetricsStorage {
+            image_pull_bytes: image_manager.pull_bytes_total(),
+            image_layers_cached,
+            image_layers_fetched,
+            ..RuntimeMetricsStorage::new()
+        };
+
+        let metrics_samples = MetricsSampleStore::new(db.clone());
         let box_store = BoxStore::new(db);
 
         // Initialize lock manager for per-entity multiprocess-safe locking
@@ -198,7 +366,18 @@ impl RuntimeImpl {
             image_manager,
             layout,
             guest_rootfs: Arc::new(OnceCell::new()),
-            runtime_metrics: RuntimeMetricsStorage::new(),
+            guest_rootfs_path,
+            rootfs_format,
+            runtime_metrics,
+            metrics_samples,
+            admission: AdmissionController::new(admission_limits)
+                .with_fd_budget(FdBudget::for_process()),
+            settings: parking_lot::RwLock::new(settings),
+            options: parking_lot::Mutex::new(options),
+            #[cfg(any(test, feature = "testing"))]
+            mock_script,
+            #[cfg(feature = "gvproxy-backend")]
+            network_daemon,
             lock_manager,
             _runtime_lock: runtime_lock,
             shutdown_token: CancellationToken::new(),
@@ -209,6 +388,12 @@ impl RuntimeImpl {
         // Recover boxes from database
         inner.recover_boxes()?;
 
+        retention::spawn_reaper(&inner);
+        #[cfg(feature = "gvproxy-backend")]
+        if let Some(daemon) = &inner.network_daemon {
+            shared::spawn_supervisor(Arc::clone(daemon), inner.shutdown_token.clone());
+        }
+
         Ok(inner)
     }
 
@@ -231,6 +416,34 @@ impl RuntimeImpl {
         Ok(litebox)
     }
 
+    /// Plan a box without creating it (dry run).
+    ///
+    /// Runs the same validation as `create` and resolves the image from the
+    /// local cache only. Nothing is persisted, pulled or spawned.
+    pub async fn plan(
+        self: &Arc<Self>,
+        options: BoxOptions,
+        name: Option<String>,
+    ) -> BoxliteResult<BoxPlan> {
+        if self.shutdown_token.is_cancelled() {
+            return Err(BoxliteError::Stopped(
+                "Cannot plan box: runtime has been shut down".into(),
+            ));
+        }
+
+        if let Some(ref name) = name
+            && self.box_manager.lookup_box(name)?.is_some()
+        {
+            return Err(BoxliteError::NameConflict(format!(
+                "box with name '{}' already exists",
+                name
+            )));
+        }
+
+        let options = self.with_runtime_env(&options);
+        plan_box(self, &options, name, self.engine_kind()).await
+    }
+
     /// Get an existing box by name, or create a new one if it doesn't exist.
     ///
     /// Returns `(LiteBox, true)` if a new box was created, or `(LiteBox, false)`
@@ -244,6 +457,411 @@ impl RuntimeImpl {
         self.create_inner(options, name, true).await
     }
 
+    /// Clone a stopped box into a new box that starts from the same disk state.
+    ///
+    /// The source's rootfs disks are frozen into read-only snapshots under
+    /// `~/.boxlite/snapshots`; the source and the clone each get a fresh
+    /// copy-on-write overlay on top, so cloning is cheap regardless of disk
+    /// size. The clone is created in `Stopped` status with the source's
+    /// options and starts through the restart pipeline.
+    pub async fn clone_box(
+        self: &Arc<Self>,
+        src_id_or_name: &str,
+        name: Option<String>,
+    ) -> BoxliteResult<LiteBox> {
+        if self.shutdown_token.is_cancelled() {
+            return Err(BoxliteError::Stopped(
+                "Cannot clone box: runtime has been shut down".into(),
+            ));
+        }
+
+        let (src_config, src_state) = self
+            .box_manager
+            .lookup_box(src_id_or_name)?
+            .ok_or_else(|| BoxliteError::NotFound(src_id_or_name.to_string()))?;
+
+        if let Some(ref name) = name
+            && self.box_manager.lookup_box(name)?.is_some()
+        {
+            return Err(BoxliteError::NameConflict(format!(
+                "box with name '{}' already exists",
+                name
+            )));
+        }
+
+        let (config, mut state) =
+            self.init_box_variables(&clone_options(&src_config.options), name);
+
+        // Hold the source's lock so it cannot start while its disks move.
+        {
+            let src_locker = src_state
+                .lock_id
+                .map(|lock_id| self.lock_manager.retrieve(lock_id))
+                .transpose()?;
+            let _guard = src_locker.as_deref().map(LockGuard::new);
+
+            // Starting saves the new status under the same lock, so only a
+            // status read now is current
+            let src_state = self
+                .box_manager
+                .box_by_id(&src_config.id)?
+                .map(|(_, state)| state)
+                .ok_or_else(|| BoxliteError::NotFound(src_id_or_name.to_string()))?;
+            if src_state.status != BoxStatus::Stopped {
+                return Err(BoxliteError::InvalidState(format!(
+                    "Cannot clone box in {} state (stop it first)",
+                    src_state.status
+                )));
+            }
+
+            if let Err(e) = self.fork_box_disks(&src_config, &config) {
+                let _ = std::fs::remove_dir_all(&config.box_home);
+                return Err(e);
+            }
+        }
+
+        state.set_status(BoxStatus::Stopped);
+        let lock_id = match self.lock_manager.allocate() {
+            Ok(lock_id) => lock_id,
+            Err(e) => {
+                self.discard_clone(&config.box_home);
+                return Err(e);
+            }
+        };
+        state.set_lock_id(lock_id);
+
+        if let Err(e) = self.box_manager.add_box(&config, &state) {
+            let _ = self.lock_manager.free(lock_id);
+            self.discard_clone(&config.box_home);
+            return Err(e);
+        }
+
+        let box_id = config.id.clone();
+        let box_home = config.box_home.clone();
+        let (box_impl, inserted) = self.get_or_create_box_impl(config, state);
+        if !inserted {
+            // Another box took the name since the check above; undo the clone
+            if let Err(e) = self.box_manager.remove_box(&box_id) {
+                tracing::error!(box_id = %box_id, error = %e, "Failed to remove cloned box record");
+            }
+            let _ = self.lock_manager.free(lock_id);
+            self.discard_clone(&box_home);
+            return Err(BoxliteError::NameConflict(
+                "box with this name already exists".into(),
+            ));
+        }
+
+        tracing::info!(
+            box_id = %box_id,
+            source = %src_config.id,
+            "Cloned box"
+        );
+
+        self.runtime_metrics
+            .boxes_created
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+
+        Ok(LiteBox::new(box_impl))
+    }
+
+    /// Snapshot the source box's disks and create overlays for the clone.
+    ///
+    /// A source disk already stacked on `MAX_SNAPSHOT_DEPTH` snapshots is
+    /// flattened first, so clones of clones do not grow the chain forever.
+    fn fork_box_disks(&self, src: &BoxConfig, dst: &BoxConfig) -> BoxliteResult<()> {
+        let mut disk_names = Vec::new();
+        for disk_name in [filenames::CONTAINER_DISK, filenames::GUEST_ROOTFS_DISK] {
+            if !src.box_home.join(disk_name).exists() {
+                return Err(BoxliteError::InvalidState(format!(
+                    "box {} has no {} (it must have been started at least once)",
+                    src.id, disk_name
+                )));
+            }
+            disk_names.push(disk_name.to_string());
+        }
+        // Read-only image rootfs and sidecar rootfs disks fork like any disk
+        disk_names.push(filenames::CONTAINER_IMAGE_DISK.to_string());
+        disk_names.extend(
+            src.options
+                .sidecars
+                .iter()
+                .map(|sidecar| filenames::sidecar_disk(&sidecar.name)),
+        );
+
+        let helper = Qcow2Helper::new();
+        let mut released = Vec::new();
+        let mut forked = Vec::new();
+        let fork_all = || -> BoxliteResult<()> {
+            for disk_name in disk_names {
+                let src_disk = src.box_home.join(&disk_name);
+                if !src_disk.exists() {
+                    continue;
+                }
+
+                let snapshots = self.disk_snapshots(&src_disk);
+                if snapshots.len() >= MAX_SNAPSHOT_DEPTH {
+                    let chain = Qcow2Helper::backing_chain(&src_disk)?;
+                    let base = chain
+                        .last()
+                        .filter(|last| !snapshots.contains(&canonical(last.to_path_buf())));
+                    helper.flatten_disk(&src_disk, base.map(PathBuf::as_path))?;
+                    released.extend(snapshots);
+                }
+
+                let snapshot = self
+                    .layout
+                    .snapshots_dir()
+                    .join(format!("{}-{}", dst.id, disk_name));
+                let fork = dst.box_home.join(&disk_name);
+                helper.fork_disk(&src_disk, &snapshot, &fork)?;
+                forked.push((src_disk, snapshot, fork));
+            }
+            Ok(())
+        };
+        let result = fork_all();
+
+        // Put the source's disks back as they were; its new overlays are
+        // still empty, since it cannot start while its lock is held
+        if result.is_err() {
+            for (src_disk, snapshot, fork) in forked.iter().rev() {
+                if let Err(e) = helper.unfork_disk(src_disk, snapshot, fork) {
+                    tracing::warn!(
+                        disk = %src_disk.display(),
+                        error = %e,
+                        "Failed to undo disk fork"
+                    );
+                }
+            }
+        }
+
+        self.release_snapshots(released);
+        result
+    }
+
+    /// Remove a clone that never made it into the runtime, with the
+    /// snapshots only it used.
+    fn discard_clone(&self, box_home: &Path) {
+        let snapshots = self.box_snapshots(box_home);
+        let _ = std::fs::remove_dir_all(box_home);
+        self.release_snapshots(snapshots);
+    }
+
+    /// Snapshots under `~/.boxlite/snapshots` that `disk` is stacked on,
+    /// canonicalized.
+    fn disk_snapshots(&self, disk: &Path) -> Vec<PathBuf> {
+        let snapshots_dir = canonical(self.layout.snapshots_dir());
+        let Ok(chain) = Qcow2Helper::backing_chain(disk) else {
+            return Vec::new();
+        };
+        chain
+            .into_iter()
+            .skip(1)
+            .map(canonical)
+            .filter(|file| file.starts_with(&snapshots_dir))
+            .collect()
+    }
+
+    /// Snapshots the disks in `box_home` are stacked on.
+    fn box_snapshots(&self, box_home: &Path) -> Vec<PathBuf> {
+        let Ok(entries) = std::fs::read_dir(box_home) else {
+            return Vec::new();
+        };
+        let mut snapshots = Vec::new();
+        for entry in entries.filter_map(Result::ok) {
+            if !entry.file_type().is_ok_and(|t| t.is_file()) {
+                continue;
+            }
+            for snapshot in self.disk_snapshots(&entry.path()) {
+                if !snapshots.contains(&snapshot) {
+                    snapshots.push(snapshot);
+                }
+            }
+        }
+        snapshots
+    }
+
+    /// Delete the given snapshots once no disk is stacked on them any more.
+    ///
+    /// Snapshots are shared between a box and its clones, so each one is
+    /// only removed when neither a box disk nor another snapshot still uses
+    /// it as a backing file. Deleting one can free the one beneath it, so
+    /// this repeats until nothing more is released.
+    fn release_snapshots(&self, mut snapshots: Vec<PathBuf>) {
+        let dirs = [self.layout.boxes_dir(), self.layout.snapshots_dir()];
+        while !snapshots.is_empty() {
+            let in_use = retention::backing_files(&dirs);
+            let (unused, kept): (Vec<_>, Vec<_>) = snapshots
+                .into_iter()
+                .partition(|snapshot| !in_use.contains(snapshot));
+            if unused.is_empty() {
+                break;
+            }
+            for snapshot in unused {
+                match std::fs::remove_file(&snapshot) {
+                    Ok(()) => {
+                        tracing::debug!(path = %snapshot.display(), "Removed unused snapshot")
+                    }
+                    Err(e) => tracing::warn!(
+                        path = %snapshot.display(),
+                        error = %e,
+                        "Failed to remove unused snapshot"
+                    ),
+                }
+            }
+            snapshots = kept;
+        }
+    }
+
+    /// Export a stopped box to a portable archive at `output`.
+    ///
+    /// The archive carries the box's options and both rootfs disks with their
+    /// full backing chains, so it can be imported on a host that has never
+    /// pulled the image.
+    pub async fn export_box(&self, id_or_name: &str, output: &Path) -> BoxliteResult<()> {
+        let (config, state) = self
+            .box_manager
+            .lookup_box(id_or_name)?
+            .ok_or_else(|| BoxliteError::NotFound(id_or_name.to_string()))?;
+
+        if state.status != BoxStatus::Stopped {
+            return Err(BoxliteError::InvalidState(format!(
+                "Cannot export box in {} state (stop it first)",
+                state.status
+            )));
+        }
+
+        // Hold the box lock so it cannot start while its disks are read.
+        let locker = state
+            .lock_id
+            .map(|lock_id| self.lock_manager.retrieve(lock_id))
+            .transpose()?;
+        let output = output.to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            let _guard = locker.as_deref().map(LockGuard::new);
+            archive::export_box(&config, &output)
+        })
+        .await
+        .map_err(|e| BoxliteError::Internal(format!("spawn_blocking failed: {}", e)))?
+    }
+
+    /// Recreate a box from an archive written by [`export_box`](Self::export_box).
+    ///
+    /// The box gets a new ID, home directory and transport. Backing layers
+    /// are placed under `~/.boxlite/snapshots` and the qcow2 headers are
+    /// relinked to them. Volumes, ports and a custom kernel in the archive's
+    /// options are dropped unless `options.host_bindings` is set.
+    pub async fn import_box(
+        self: &Arc<Self>,
+        input: &Path,
+        options: ImportOptions,
+    ) -> BoxliteResult<LiteBox> {
+        if self.shutdown_token.is_cancelled() {
+            return Err(BoxliteError::Stopped(
+                "Cannot import box: runtime has been shut down".into(),
+            ));
+        }
+
+        let input_owned = input.to_path_buf();
+        let manifest = tokio::task::spawn_blocking(move || archive::read_manifest(&input_owned))
+            .await
+            .map_err(|e| BoxliteError::Internal(format!("spawn_blocking failed: {}", e)))??;
+
+        let name = options.name.or_else(|| manifest.name.clone());
+        if let Some(ref name) = name
+            && self.box_manager.lookup_box(name)?.is_some()
+        {
+            return Err(BoxliteError::NameConflict(format!(
+                "box with name '{}' already exists",
+                name
+            )));
+        }
+
+        let mut box_options = manifest.options.clone();
+        if !options.host_bindings {
+            let dropped = archive::strip_host_bindings(&mut box_options);
+            if !dropped.is_empty() {
+                tracing::warn!(
+                    input = %input.display(),
+                    dropped = ?dropped,
+                    "Dropped host-bound options from imported box"
+                );
+            }
+        }
+        // The archive may come from anywhere; hold it to the rules of create
+        box_options.sanitize()?;
+        let (config, mut state) = self.init_box_variables(&box_options, name);
+
+        let box_home = config.box_home.clone();
+        let snapshots_dir = self.layout.snapshots_dir();
+        let box_id = config.id.to_string();
+        let input_owned = input.to_path_buf();
+        let unpacked = tokio::task::spawn_blocking(move || {
+            let mut written = Vec::new();
+            let result = archive::unpack_layers(
+                &input_owned,
+                &manifest,
+                |disk, index| {
+                    if index == 0 {
+                        box_home.join(disk)
+                    } else {
+                        snapshots_dir.join(format!("{}-{}.{}", box_id, disk, index))
+                    }
+                },
+                &mut written,
+            );
+            if result.is_err() {
+                remove_imported_files(&written, &box_home);
+            }
+            result.map(|()| written)
+        })
+        .await
+        .map_err(|e| BoxliteError::Internal(format!("spawn_blocking failed: {}", e)))?;
+        let written = unpacked?;
+
+        state.set_status(BoxStatus::Stopped);
+        let lock_id = match self.lock_manager.allocate() {
+            Ok(lock_id) => lock_id,
+            Err(e) => {
+                remove_imported_files(&written, &config.box_home);
+                return Err(e);
+            }
+        };
+        state.set_lock_id(lock_id);
+
+        if let Err(e) = self.box_manager.add_box(&config, &state) {
+            let _ = self.lock_manager.free(lock_id);
+            remove_imported_files(&written, &config.box_home);
+            return Err(e);
+        }
+
+        let box_id = config.id.clone();
+        let box_home = config.box_home.clone();
+        let (box_impl, inserted) = self.get_or_create_box_impl(config, state);
+        if !inserted {
+            // Another box took the name since the check above; undo the import
+            if let Err(e) = self.box_manager.remove_box(&box_id) {
+                tracing::error!(box_id = %box_id, error = %e, "Failed to remove imported box record");
+            }
+            let _ = self.lock_manager.free(lock_id);
+            remove_imported_files(&written, &box_home);
+            return Err(BoxliteError::NameConflict(
+                "box with this name already exists".into(),
+            ));
+        }
+
+        tracing::info!(
+            box_id = %box_id,
+            input = %input.display(),
+            "Imported box"
+        );
+
+        self.runtime_metrics
+            .boxes_created
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+
+        Ok(LiteBox::new(box_impl))
+    }
+
     /// Inner create logic shared by `create()` and `get_or_create()`.
     ///
     /// When `reuse_existing` is false, returns an error if a box with the same
@@ -271,13 +889,25 @@ impl RuntimeImpl {
                 let (box_impl, _) = self.get_or_create_box_impl(config, state);
                 return Ok((LiteBox::new(box_impl), false));
             } else {
-                return Err(BoxliteError::InvalidArgument(format!(
+                return Err(BoxliteError::NameConflict(format!(
                     "box with name '{}' already exists",
                     name
                 )));
             }
         }
 
+        // Reject invalid options now rather than at the first start
+        options.sanitize()?;
+
+        // Fail fast on requested host ports that are taken; with
+        // publish_retry they move to a free port at start instead
+        if options.network != NetworkSpec::Disabled && !options.publish_retry {
+            let owners = self.published_port_owners(None)?;
+            for port in requested_ports(&options) {
+                check_host_port(&port, &owners, false)?;
+            }
+        }
+
         // Initialize box variables with defaults
         let (config, mut state) = self.init_box_variables(&options, name.clone());
 
@@ -325,7 +955,7 @@ impl RuntimeImpl {
         // This also checks in-memory cache for duplicate names
         let (box_impl, inserted) = self.get_or_create_box_impl(config, state);
         if !inserted {
-            return Err(BoxliteError::InvalidArgument(
+            return Err(BoxliteError::NameConflict(
                 "box with this name already exists".into(),
             ));
         }
@@ -475,6 +1105,7 @@ impl RuntimeImpl {
         }
 
         // Sort by creation time (newest first)
+        #[allow(clippy::unnecessary_sort_by)]
         infos.sort_by(|a, b| b.created_at.cmp(&a.created_at));
         Ok(infos)
     }
@@ -523,6 +1154,159 @@ impl RuntimeImpl {
         RuntimeMetrics::new(self.runtime_metrics.clone())
     }
 
+    /// Measure disk space used by images and boxes.
+    pub async fn disk_usage(&self) -> BoxliteResult<DiskUsage> {
+        let layout = self.layout.clone();
+        tokio::task::spawn_blocking(move || {
+            let images = layout.image_layout();
+            let content = ContentStore::new(images.content_dir()).usage();
+            // Content objects are hardlinks into extracted layers: walk both
+            // so each shared inode counts once, then drop the objects from
+            // the logical total.
+            let extracted = tree_usage(&[images.extracted_dir(), images.content_dir()]);
+
+            DiskUsage {
+                layers_bytes: tree_usage(&[images.layers_dir()]).physical,
+                extracted_logical_bytes: extracted.logical.saturating_sub(content.bytes),
+                extracted_bytes: extracted.physical,
+                dedup_objects: content.objects,
+                disk_images_bytes: tree_usage(&[images.disk_images_dir()]).physical,
+                boxes_bytes: tree_usage(&[layout.boxes_dir()]).physical,
+            }
+        })
+        .await
+        .map_err(|e| BoxliteError::Internal(format!("spawn_blocking failed: {}", e)))
+    }
+
+    /// Run one pruning pass with `policy` now.
+    pub async fn prune(self: &Arc<Self>, policy: &RetentionPolicy) -> BoxliteResult<PruneReport> {
+        if self.shutdown_token.is_cancelled() {
+            return Err(BoxliteError::Stopped(
+                "Cannot prune: runtime has been shut down".into(),
+            ));
+        }
+        policy.validate()?;
+        retention::prune(self, policy).await
+    }
+
+    /// Remove stopped boxes, dangling image data, unused anonymous volumes
+    /// and old logs.
+    pub async fn system_prune(
+        self: &Arc<Self>,
+        options: &SystemPruneOptions,
+    ) -> BoxliteResult<SystemPruneReport> {
+        if self.shutdown_token.is_cancelled() {
+            return Err(BoxliteError::Stopped(
+                "Cannot prune: runtime has been shut down".into(),
+            ));
+        }
+        retention::system_prune(self, options).await
+    }
+
+    /// Socket of the shared network daemon for a box about to start, or None
+    /// if the box should host its own network backend.
+    pub(crate) fn shared_network_socket(&self) -> Option<PathBuf> {
+        #[cfg(feature = "gvproxy-backend")]
+        {
+            self.network_daemon
+                .as_ref()
+                .and_then(|daemon| daemon.endpoint())
+        }
+        #[cfg(not(feature = "gvproxy-backend"))]
+        {
+            None
+        }
+    }
+
+    /// Published host ports of active boxes other than `exclude`, each with
+    /// the name (or ID) of its box.
+    ///
+    /// Read from the database, so boxes of other runtime processes count too.
+    pub(crate) fn published_port_owners(
+        &self,
+        exclude: Option<&BoxID>,
+    ) -> BoxliteResult<Vec<(String, PortSpec)>> {
+        let mut owners = Vec::new();
+        for (config, state) in self.box_manager.all_boxes(true)? {
+            if Some(&config.id) == exclude || !state.status.is_active() {
+                continue;
+            }
+            let owner = config.name.unwrap_or_else(|| config.id.to_string());
+            owners.extend(state.ports.into_iter().map(|port| (owner.clone(), port)));
+        }
+        Ok(owners)
+    }
+
+    /// Retention policy in effect (None = pruning disabled).
+    pub(crate) fn retention_policy(&self) -> Option<RetentionPolicy> {
+        self.options.lock().retention.clone()
+    }
+
+    /// Registries named in the options in effect (search list, mirrors and
+    /// per-registry settings).
+    pub(crate) fn configured_registries(&self) -> Vec<String> {
+        let options = self.options.lock();
+        options
+            .image_registries
+            .iter()
+            .chain(options.registry_mirrors.keys())
+            .chain(options.registries.keys())
+            .cloned()
+            .collect()
+    }
+
+    /// Whether some caller still holds a handle to the box.
+    pub(crate) fn has_handle(&self, id: &BoxID) -> bool {
+        let sync = self.sync_state.read().unwrap();
+        sync.active_boxes_by_id
+            .get(id)
+            .is_some_and(|weak| weak.upgrade().is_some())
+    }
+
+    // ========================================================================
+    // PUBLIC API - CONFIGURATION
+    // ========================================================================
+
+    /// Apply changed options to the running runtime.
+    ///
+    /// The image manager is reconfigured first since it is the only step
+    /// that can fail; on error nothing is applied.
+    pub fn reload_options(&self, options: BoxliteOptions) -> BoxliteResult<OptionsReload> {
+        if self.shutdown_token.is_cancelled() {
+            return Err(BoxliteError::Stopped(
+                "Cannot reload options: runtime has been shut down".into(),
+            ));
+        }
+
+        let mut current = self.options.lock();
+        let changes = reload::diff(&current, &options);
+        if changes.is_empty() {
+            return Ok(changes);
+        }
+
+        let options = reload::effective(&current, options);
+        if let Some(policy) = &options.retention {
+            policy.validate()?;
+        }
+        if let Some(limits) = &options.host_limits
+            && options.host_limits != current.host_limits
+        {
+            apply_host_limits(limits)?;
+        }
+        self.image_manager.reconfigure(&options)?;
+        self.admission
+            .set_limits(AdmissionLimits::from_options(&options));
+        *self.settings.write() = RuntimeSettings::from_options(&options);
+        *current = options;
+
+        tracing::info!(
+            applied = ?changes.applied,
+            restart_required = ?changes.restart_required,
+            "Runtime options reloaded"
+        );
+        Ok(changes)
+    }
+
     // ========================================================================
     // PUBLIC API - SHUTDOWN
     // ========================================================================
@@ -532,17 +1316,23 @@ impl RuntimeImpl {
     /// This method:
     /// 1. Marks the runtime as shut down (no new operations allowed)
     /// 2. Cancels the shutdown token (signals in-flight operations)
-    /// 3. Stops all active boxes with the given timeout
+    /// 3. Stops all active boxes concurrently, each with the given timeout;
+    ///    a box that does not stop in time is killed
     ///
     /// # Arguments
     /// * `timeout` - Seconds before force-kill. None=10s, Some(-1)=infinite
+    /// * `progress` - Called as each box starts stopping, is killed and finishes
     ///
     /// # Returns
-    /// Ok(()) if all boxes stopped successfully, Err if any box failed to stop.
-    pub async fn shutdown(&self, timeout: Option<i32>) -> BoxliteResult<()> {
+    /// A report of how each box ended, including boxes that failed to stop.
+    pub async fn shutdown(
+        &self,
+        timeout: Option<i32>,
+        progress: &(dyn Fn(&ShutdownEvent) + Send + Sync),
+    ) -> BoxliteResult<ShutdownReport> {
         // Check if already shut down (idempotent)
         if self.shutdown_token.is_cancelled() {
-            return Ok(());
+            return Ok(ShutdownReport::default());
         }
 
         tracing::info!("Initiating runtime shutdown");
@@ -561,7 +1351,7 @@ impl RuntimeImpl {
 
         if active_boxes.is_empty() {
             tracing::info!("No active boxes to shutdown");
-            return Ok(());
+            return Ok(ShutdownReport::default());
         }
 
         tracing::info!(count = active_boxes.len(), "Stopping active boxes");
@@ -569,55 +1359,50 @@ impl RuntimeImpl {
         // Convert timeout to duration
         let timeout_duration = timeout_to_duration(timeout);
 
-        // Stop all boxes concurrently
-        let stop_futures = active_boxes.iter().map(|box_impl| {
-            let box_id = box_impl.id().to_string();
-            async move {
-                let result = if let Some(duration) = timeout_duration {
-                    tokio::time::timeout(duration, box_impl.stop()).await
-                } else {
-                    // Infinite timeout
-                    Ok(box_impl.stop().await)
-                };
-                (box_id, result)
-            }
-        });
-
-        let results = futures::future::join_all(stop_futures).await;
-
-        // Check for errors
-        let mut errors = Vec::new();
-        for (box_id, result) in results {
-            match result {
-                Ok(Ok(())) => {
-                    tracing::debug!(box_id = %box_id, "Box stopped gracefully");
-                }
-                Ok(Err(e)) => {
-                    tracing::warn!(box_id = %box_id, error = %e, "Box stop failed");
-                    errors.push(format!("{}: {}", box_id, e));
-                }
-                Err(_) => {
-                    tracing::warn!(box_id = %box_id, "Box stop timed out");
-                    errors.push(format!("{}: timeout", box_id));
-                }
-            }
+        // Stop all boxes concurrently, each with its own timeout; reports
+        // are collected in the order boxes finish
+        let mut stops: futures::stream::FuturesUnordered<_> = active_boxes
+            .iter()
+            .map(|box_impl| shutdown_box(box_impl, timeout_duration, progress))
+            .collect();
+        let mut report = ShutdownReport::default();
+        while let Some(done) = futures::StreamExt::next(&mut stops).await {
+            progress(&ShutdownEvent::Finished(done.clone()));
+            report.boxes.push(done);
         }
 
-        if errors.is_empty() {
-            tracing::info!("Runtime shutdown complete");
-            Ok(())
-        } else {
-            Err(BoxliteError::Internal(format!(
-                "Shutdown completed with errors: {}",
-                errors.join(", ")
-            )))
-        }
+        tracing::info!(
+            stopped = report.stopped().count(),
+            killed = report.killed().count(),
+            failed = report.failed().count(),
+            "Runtime shutdown complete"
+        );
+        Ok(report)
     }
 
     // ========================================================================
     // INTERNAL - BOX OPERATIONS
     // ========================================================================
 
+    /// Reserve runtime capacity for a box (see `BoxliteOptions::max_running_boxes`).
+    ///
+    /// Rejections are counted in `RuntimeMetrics::admission_rejections_total()`.
+    pub(crate) async fn admit_box(
+        &self,
+        box_id: &BoxID,
+        options: &BoxOptions,
+    ) -> BoxliteResult<()> {
+        let result = self.admission.admit(box_id, box_reservation(options)).await;
+
+        if let Err(BoxliteError::ResourceExhausted(ref reason)) = result {
+            self.runtime_metrics
+                .admission_rejections
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            tracing::warn!(box_id = %box_id, reason = %reason, "Box admission rejected");
+        }
+        result
+    }
+
     /// Resolve an ID or name to the actual box ID.
     ///
     /// Checks in-memory cache first (for boxes not yet persisted), then database.
@@ -673,7 +1458,9 @@ impl RuntimeImpl {
             if state.status.is_active() {
                 if force {
                     // Force mode: kill the process directly
-                    if let Some(pid) = state.pid {
+                    if let Some(pid) = state.pid
+                        && config.engine_kind.has_process()
+                    {
                         tracing::info!(box_id = %id, pid = pid, "Force killing active box");
                         crate::util::kill_process(pid);
                     }
@@ -711,8 +1498,9 @@ impl RuntimeImpl {
                 }
             }
 
-            // Delete box directory
+            // Delete box directory, then the snapshots only it still used
             let box_home = config.box_home;
+            let snapshots = self.box_snapshots(&box_home);
             if box_home.exists()
                 && let Err(e) = std::fs::remove_dir_all(&box_home)
             {
@@ -723,9 +1511,11 @@ impl RuntimeImpl {
                     "Failed to cleanup box directory"
                 );
             }
+            self.release_snapshots(snapshots);
 
             // Invalidate cache
             self.invalidate_box_impl(id, config.name.as_deref());
+            self.admission.release(id);
 
             tracing::info!(box_id = %id, "Removed box");
             return Ok(());
@@ -752,6 +1542,7 @@ impl RuntimeImpl {
 
             // Invalidate cache (removes from in-memory maps)
             self.invalidate_box_impl(id, box_impl.config.name.as_deref());
+            self.admission.release(id);
 
             // Delete box directory if it exists
             let box_home = &box_impl.config.box_home;
@@ -799,12 +1590,25 @@ impl RuntimeImpl {
         let now = Utc::now();
 
         // Derive paths from ID (computed from layout + ID)
-        let box_home = self.layout.boxes_dir().join(box_id.as_str());
-        let socket_path = filenames::unix_socket_path(self.layout.home_dir(), box_id.as_str());
-        let ready_socket_path = box_home.join("sockets").join("ready.sock");
+        let boxes_dir = self.layout.boxes_dir();
+        let box_home = boxes_dir.join(box_id.as_str());
+        let socket_path = filenames::unix_socket_path(&boxes_dir, box_id.as_str());
+        let ready_socket_path = filenames::ready_socket_path(&boxes_dir, box_id.as_str());
 
         // Create container runtime config
         let container = ContainerRuntimeConfig { id: container_id };
+        let sidecars = options
+            .sidecars
+            .iter()
+            .map(|sidecar| {
+                let config = ContainerRuntimeConfig {
+                    id: ContainerID::new(),
+                };
+                (sidecar.name.clone(), config)
+            })
+            .collect();
+
+        let options = self.with_runtime_env(options);
 
         // Create config with defaults + user options
         let config = BoxConfig {
@@ -812,8 +1616,9 @@ impl RuntimeImpl {
             name,
             created_at: now,
             container,
-            options: options.clone(),
-            engine_kind: VmmKind::Libkrun,
+            sidecars,
+            options,
+            engine_kind: self.engine_kind(),
             transport: Transport::unix(socket_path),
             box_home,
             ready_socket_path,
@@ -825,6 +1630,59 @@ impl RuntimeImpl {
         (config, state)
     }
 
+    /// Options with runtime-level environment merged in.
+    ///
+    /// Passthrough host variables, `TZ`/`LANG` from the timezone and locale,
+    /// and proxy variables never override ones the box sets itself. A
+    /// `"host"` timezone is resolved here, so restarts keep the zone the box
+    /// was created with.
+    fn with_runtime_env(&self, options: &BoxOptions) -> BoxOptions {
+        use crate::runtime::env::{HOST_TIMEZONE, host_timezone};
+
+        let mut options = options.clone();
+        if options.timezone.as_deref() == Some(HOST_TIMEZONE) {
+            options.timezone = Some(host_timezone().unwrap_or_else(|| {
+                tracing::warn!("Cannot determine the host timezone, using UTC");
+                "UTC".to_string()
+            }));
+        }
+        let zone_vars = [
+            ("TZ", options.timezone.clone()),
+            ("LANG", options.locale.clone()),
+        ];
+        for (key, value) in zone_vars {
+            if let Some(value) = value
+                && !options.env.iter().any(|(k, _)| k == key)
+            {
+                options.env.push((key.to_string(), value));
+            }
+        }
+        // Variables that are not valid UTF-8 cannot be passed on
+        let host_vars = std::env::vars_os()
+            .filter_map(|(k, v)| Some((k.into_string().ok()?, v.into_string().ok()?)));
+        let passthrough = crate::runtime::env::passthrough(&options.env_passthrough, host_vars);
+        for (key, value) in passthrough {
+            if !options.env.iter().any(|(k, _)| *k == key) {
+                options.env.push((key, value));
+            }
+        }
+        for (key, value) in &self.settings.read().box_proxy_env {
+            if !options.env.iter().any(|(k, _)| k == key) {
+                options.env.push((key.clone(), value.clone()));
+            }
+        }
+        options
+    }
+
+    /// Engine used for boxes created by this runtime.
+    fn engine_kind(&self) -> VmmKind {
+        #[cfg(any(test, feature = "testing"))]
+        if self.mock_script.is_some() {
+            return VmmKind::Mock;
+        }
+        VmmKind::Libkrun
+    }
+
     /// Recover boxes from persistent storage on runtime startup.
     fn recover_boxes(&self) -> BoxliteResult<()> {
         use crate::util::{is_process_alive, is_same_process};
@@ -846,7 +1704,8 @@ impl RuntimeImpl {
         let persisted = self.box_manager.all_boxes(true)?;
 
         // Phase 1: Clean up boxes that shouldn't persist
-        // - auto_remove=true boxes: these are ephemeral and shouldn't survive restarts
+        // - auto_remove=true boxes: these are ephemeral and shouldn't survive restarts,
+        //   except detached ones whose VM still runs (removed once it has stopped)
         // - Orphaned active boxes: was Running but directory is missing (crashed mid-operation)
         //
         // Note: We don't remove Configured or Stopped boxes without directories because:
@@ -856,11 +1715,19 @@ impl RuntimeImpl {
         let mut boxes_to_remove = Vec::new();
         for (config, state) in &persisted {
             let should_remove = if config.options.auto_remove {
-                tracing::info!(
-                    box_id = %config.id,
-                    "Removing auto_remove=true box during recovery"
-                );
-                true
+                if self.shim_running(&config.id) {
+                    tracing::debug!(
+                        box_id = %config.id,
+                        "Keeping running detached auto_remove=true box during recovery"
+                    );
+                    false
+                } else {
+                    tracing::info!(
+                        box_id = %config.id,
+                        "Removing auto_remove=true box during recovery"
+                    );
+                    true
+                }
             } else if state.status.is_active() && !config.box_home.exists() {
                 // Only remove orphaned boxes that were in an active state
                 // Stopped boxes might not have a directory if never started
@@ -955,9 +1822,12 @@ impl RuntimeImpl {
                 match crate::util::read_pid_file(&pid_file) {
                     Ok(pid) => {
                         if is_process_alive(pid) && is_same_process(pid, box_id.as_str()) {
-                            // Process is alive and it's our boxlite-shim - box stays Running
+                            // Process is alive and it's our boxlite-shim - box stays
+                            // Running, or Paused if it was frozen
                             state.set_pid(Some(pid));
-                            state.set_status(BoxStatus::Running);
+                            if state.status != BoxStatus::Paused {
+                                state.set_status(BoxStatus::Running);
+                            }
                             tracing::info!(
                                 box_id = %box_id,
                                 pid = pid,
@@ -988,7 +1858,7 @@ impl RuntimeImpl {
             } else {
                 // No PID file - box was stopped gracefully or never started
                 // Note: Configured boxes won't have a PID file (this is expected)
-                if state.status == BoxStatus::Running {
+                if state.status.is_active() {
                     state.set_status(BoxStatus::Stopped);
                     tracing::warn!(
                         box_id = %box_id,
@@ -997,6 +1867,13 @@ impl RuntimeImpl {
                 }
             }
 
+            // Boxes that are still running keep their reservation across
+            // restarts (limits are not re-checked).
+            if state.status.is_active() {
+                self.admission
+                    .force_admit(box_id, box_reservation(&config.options));
+            }
+
             // Save updated state to database if changed
             if state.status != original_status {
                 self.box_manager.save_box(box_id, &state)?;
@@ -1007,6 +1884,18 @@ impl RuntimeImpl {
         Ok(())
     }
 
+    /// Whether the box's shim process, according to its PID file, is alive.
+    fn shim_running(&self, box_id: &BoxID) -> bool {
+        let pid_file = self
+            .layout
+            .boxes_dir()
+            .join(box_id.as_str())
+            .join("shim.pid");
+        crate::util::read_pid_file(&pid_file).is_ok_and(|pid| {
+            crate::util::is_process_alive(pid) && crate::util::is_same_process(pid, box_id.as_str())
+        })
+    }
+
     /// Scan filesystem for orphaned box directories and remove them.
     ///
     /// Orphaned directories are those that exist in ~/.boxlite/boxes/
@@ -1146,6 +2035,14 @@ impl RuntimeImpl {
         (box_impl, true)
     }
 
+    /// Cached BoxImpl of a box, if a handle to it is still alive.
+    pub(crate) fn cached_box_impl(&self, box_id: &BoxID) -> Option<SharedBoxImpl> {
+        let sync = self.sync_state.read().unwrap();
+        sync.active_boxes_by_id
+            .get(box_id)
+            .and_then(|weak| weak.upgrade())
+    }
+
     /// Remove BoxImpl from cache.
     ///
     /// Called when box is stopped or removed. Existing handles become stale;
@@ -1173,6 +2070,118 @@ impl RuntimeImpl {
     }
 }
 
+/// Set `host_limits` on the host cgroup that holds every box (Linux).
+///
+/// Background priority is applied per box as it spawns. Fails when a
+/// cgroup limit is asked for but cannot be set, rather than run unlimited.
+fn apply_host_limits(limits: &HostLimits) -> BoxliteResult<()> {
+    limits.validate()?;
+
+    #[cfg(target_os = "linux")]
+    {
+        use crate::jailer::cgroup::{CgroupConfig, setup_host_cgroup};
+
+        match setup_host_cgroup(&CgroupConfig::from(limits)) {
+            Ok(path) => {
+                tracing::info!(path = %path.display(), ?limits, "Host limits applied");
+            }
+            Err(e) if limits.needs_cgroup() => {
+                return Err(BoxliteError::Config(format!(
+                    "Cannot apply host_limits: {}",
+                    e
+                )));
+            }
+            Err(e) => {
+                tracing::debug!(error = %e, "No host cgroup; host_limits only lower priority");
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    if limits.needs_cgroup() {
+        tracing::warn!(
+            ?limits,
+            "host_limits cpus, memory_mib and cpu_weight need cgroups (Linux); \
+             box processes run at background priority instead"
+        );
+    }
+
+    Ok(())
+}
+
+/// Stop one box for runtime shutdown, killing it if it misses `timeout`.
+async fn shutdown_box(
+    box_impl: &SharedBoxImpl,
+    timeout: Option<std::time::Duration>,
+    progress: &(dyn Fn(&ShutdownEvent) + Send + Sync),
+) -> BoxShutdown {
+    let box_id = box_impl.id().clone();
+    let started = std::time::Instant::now();
+    progress(&ShutdownEvent::Stopping(box_id.clone()));
+
+    let stopped = match timeout {
+        Some(duration) => tokio::time::timeout(duration, box_impl.stop()).await,
+        // Infinite timeout
+        None => Ok(box_impl.stop().await),
+    };
+    let outcome = match stopped {
+        Ok(Ok(())) => {
+            tracing::debug!(box_id = %box_id, "Box stopped gracefully");
+            BoxShutdownOutcome::Stopped
+        }
+        Ok(Err(e)) => {
+            tracing::warn!(box_id = %box_id, error = %e, "Box stop failed");
+            BoxShutdownOutcome::Failed(e.to_string())
+        }
+        Err(_) => {
+            tracing::warn!(box_id = %box_id, "Box stop timed out, killing it");
+            progress(&ShutdownEvent::Killing(box_id.clone()));
+            match box_impl.kill().await {
+                Ok(()) => BoxShutdownOutcome::Killed,
+                Err(e) => BoxShutdownOutcome::Failed(format!("timeout, then kill failed: {}", e)),
+            }
+        }
+    };
+
+    BoxShutdown {
+        name: box_impl.config.name.clone(),
+        box_id,
+        outcome,
+        elapsed: started.elapsed(),
+    }
+}
+
+/// What a box reserves: guest memory against `max_total_memory_mib`, and
+/// its estimated file descriptors.
+fn box_reservation(options: &BoxOptions) -> Reservation {
+    Reservation {
+        memory_mib: u64::from(options.memory_mib.unwrap_or(DEFAULT_MEMORY_MIB)),
+        fds: fd_budget::box_fds(options),
+    }
+}
+
+/// Options for a clone of a box created with `options`.
+///
+/// Addresses and fixed host ports must stay unique to the source, so the
+/// clone gets its own: published ports move to dynamically assigned ones.
+fn clone_options(options: &BoxOptions) -> BoxOptions {
+    let mut options = options.clone();
+    options.mac_address = None;
+    options.ip_address = None;
+    for port in &mut options.ports {
+        port.host_port = None;
+    }
+    options
+}
+
+/// Remove the layers an import unpacked, and the new box's home.
+fn remove_imported_files(written: &[PathBuf], box_home: &Path) {
+    for path in written {
+        let _ = std::fs::remove_file(path);
+    }
+    let _ = std::fs::remove_dir_all(box_home);
+}
+
 impl std::fmt::Debug for RuntimeImpl {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RuntimeInner")
@@ -1180,3 +2189,213 @@ impl std::fmt::Debug for RuntimeImpl {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::vmm::mock::{MockScript, test_runtime};
+
+    #[tokio::test]
+    async fn test_clone_failure_restores_source() {
+        use crate::disk::Qcow2Helper;
+        use crate::runtime::constants::filenames;
+        use crate::runtime::options::PortSpec;
+        use crate::{BoxOptions, BoxliteOptions};
+
+        let (home, runtime) = test_runtime(BoxliteOptions::default(), MockScript::new());
+        let options = BoxOptions {
+            auto_remove: false,
+            mac_address: Some("02:00:00:00:00:01".to_string()),
+            ports: vec![PortSpec {
+                host_port: Some(18080),
+                guest_port: 80,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let source = runtime
+            .create(options, Some("source".to_string()))
+            .await
+            .unwrap();
+        source.start().await.unwrap();
+        source.stop().await.unwrap();
+        let source_home = home.path().join("boxes").join(source.id().as_str());
+        let container_disk = source_home.join(filenames::CONTAINER_DISK);
+        Qcow2Helper::new()
+            .create_disk_with_size(&container_disk, 1024 * 1024, true)
+            .unwrap();
+        // The first disk forks, the second is unreadable
+        std::fs::write(source_home.join(filenames::GUEST_ROOTFS_DISK), b"garbage").unwrap();
+
+        assert!(
+            runtime
+                .clone_box("source", Some("clone".to_string()))
+                .await
+                .is_err()
+        );
+        assert_eq!(
+            Qcow2Helper::backing_chain(&container_disk).unwrap().len(),
+            1
+        );
+        let snapshots = home.path().join("snapshots");
+        assert!(!snapshots.exists() || std::fs::read_dir(&snapshots).unwrap().count() == 0);
+        assert_eq!(runtime.list_info().await.unwrap().len(), 1);
+
+        // Host-unique settings stay with the source
+        let rootfs_disk = source_home.join(filenames::GUEST_ROOTFS_DISK);
+        std::fs::remove_file(&rootfs_disk).unwrap();
+        Qcow2Helper::new()
+            .create_disk_with_size(&rootfs_disk, 1024 * 1024, true)
+            .unwrap();
+        let clone = runtime
+            .clone_box("source", Some("clone".to_string()))
+            .await
+            .unwrap();
+        let ports = clone.ports().await.unwrap();
+        assert_eq!(ports[0].host_port, None);
+        assert_eq!(ports[0].guest_port, 80);
+    }
+
+    #[tokio::test]
+    async fn test_admission_reserves_at_start() {
+        use crate::{BoxOptions, BoxliteError, BoxliteOptions};
+
+        let options = BoxliteOptions {
+            max_running_boxes: Some(1),
+            ..Default::default()
+        };
+        let (_home, runtime) = test_runtime(options, MockScript::new());
+
+        // Configured boxes hold no reservation
+        let first = runtime.create(BoxOptions::default(), None).await.unwrap();
+        let second = runtime.create(BoxOptions::default(), None).await.unwrap();
+
+        first.start().await.unwrap();
+        let err = second.start().await.unwrap_err();
+        assert!(matches!(err, BoxliteError::ResourceExhausted(_)), "{}", err);
+
+        first.stop().await.unwrap();
+        second.start().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_clone_round_trip() {
+        use crate::disk::Qcow2Helper;
+        use crate::runtime::constants::filenames;
+        use crate::{BoxOptions, BoxStatus, BoxliteOptions};
+
+        let (home, runtime) = test_runtime(BoxliteOptions::default(), MockScript::new());
+        let options = BoxOptions {
+            auto_remove: false,
+            ..Default::default()
+        };
+        let source = runtime
+            .create(options, Some("source".to_string()))
+            .await
+            .unwrap();
+        source.start().await.unwrap();
+        source.stop().await.unwrap();
+
+        // Mock boxes boot without disks; give the source the ones a VM would have
+        let boxes = home.path().join("boxes");
+        let source_home = boxes.join(source.id().as_str());
+        let helper = Qcow2Helper::new();
+        for disk in [filenames::CONTAINER_DISK, filenames::GUEST_ROOTFS_DISK] {
+            helper
+                .create_disk_with_size(&source_home.join(disk), 1024 * 1024, true)
+                .unwrap();
+        }
+
+        let snapshots = home.path().join("snapshots");
+        let snapshot_count = || std::fs::read_dir(&snapshots).unwrap().count();
+
+        // Each level of cloning stacks one snapshot on the last
+        let clone = runtime
+            .clone_box("source", Some("clone".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(clone.info().status, BoxStatus::Stopped);
+        let clone_home = boxes.join(clone.id().as_str());
+        let grandchild = runtime
+            .clone_box("clone", Some("grandchild".to_string()))
+            .await
+            .unwrap();
+        let grandchild_home = boxes.join(grandchild.id().as_str());
+        assert_eq!(snapshot_count(), 4);
+        for disk in [filenames::CONTAINER_DISK, filenames::GUEST_ROOTFS_DISK] {
+            assert_eq!(
+                Qcow2Helper::backing_chain(&source_home.join(disk))
+                    .unwrap()
+                    .len(),
+                2
+            );
+            assert_eq!(
+                Qcow2Helper::backing_chain(&clone_home.join(disk))
+                    .unwrap()
+                    .len(),
+                3
+            );
+            assert_eq!(
+                Qcow2Helper::backing_chain(&grandchild_home.join(disk))
+                    .unwrap()
+                    .len(),
+                3
+            );
+        }
+
+        // The source still needs the first snapshots; only the second level goes
+        let (clone_id, grandchild_id) = (clone.id().clone(), grandchild.id().clone());
+        drop((clone, grandchild));
+        runtime.remove(clone_id.as_str(), false).await.unwrap();
+        assert_eq!(snapshot_count(), 4);
+        runtime.remove(grandchild_id.as_str(), false).await.unwrap();
+        assert_eq!(snapshot_count(), 2);
+        assert!(!grandchild_home.exists());
+
+        drop(source);
+        runtime.remove("source", false).await.unwrap();
+        assert_eq!(snapshot_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_report() {
+        use crate::{BoxOptions, BoxShutdownOutcome, BoxliteOptions, ShutdownEvent};
+
+        let (_home, runtime) = test_runtime(BoxliteOptions::default(), MockScript::new());
+        let mut boxes = Vec::new();
+        for name in ["one", "two"] {
+            let litebox = runtime
+                .create(BoxOptions::default(), Some(name.to_string()))
+                .await
+                .unwrap();
+            litebox.start().await.unwrap();
+            boxes.push(litebox);
+        }
+
+        let events = std::sync::Mutex::new(Vec::new());
+        let report = runtime
+            .shutdown_with_progress(None, &|event| events.lock().unwrap().push(event.clone()))
+            .await
+            .unwrap();
+        assert_eq!(report.boxes.len(), 2);
+        assert_eq!(report.stopped().count(), 2);
+        assert!(
+            report
+                .boxes
+                .iter()
+                .all(|b| b.outcome == BoxShutdownOutcome::Stopped)
+        );
+        let events = events.into_inner().unwrap();
+        let stopping = events
+            .iter()
+            .filter(|e| matches!(e, ShutdownEvent::Stopping(_)))
+            .count();
+        let finished = events
+            .iter()
+            .filter(|e| matches!(e, ShutdownEvent::Finished(_)))
+            .count();
+        assert_eq!((stopping, finished), (2, 2));
+
+        // Idempotent: nothing left to stop
+        assert!(runtime.shutdown(None).await.unwrap().boxes.is_empty());
+    }
+}
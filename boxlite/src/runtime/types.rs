@@ -1,17 +1,20 @@
 //! Core data types for box lifecycle management.
 
+use crate::metrics::{BootPhases, ImagePullMetrics, StageTiming};
+use crate::runtime::options::{BoxPriority, PortSpec};
+use boxlite_shared::errors::BoxliteResult;
 use chrono::{DateTime, Utc};
 use rand::RngCore;
 use rusqlite::ToSql;
 use rusqlite::types::{ToSqlOutput, ValueRef};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::hash::Hash;
 
 // Re-export status types from litebox module
-pub use crate::litebox::{BoxState, BoxStatus};
+pub use crate::litebox::{BoxHealth, BoxState, BoxStatus, ContainerProcess, HealthStatus};
 
 // ============================================================================
 // RESOURCE LIMIT TYPES (C-NEWTYPE: Semantic newtypes for distinct concepts)
@@ -417,6 +420,41 @@ pub struct BoxInfo {
 
     /// User-defined labels for filtering and organization.
     pub labels: HashMap<String, String>,
+
+    /// Published ports of the running box, with assigned host ports.
+    pub ports: Vec<PortSpec>,
+
+    /// Guest agent heartbeat results (None if stopped or unmonitored).
+    pub health: Option<BoxHealth>,
+
+    /// Hostname of the container.
+    pub hostname: String,
+
+    /// MAC address of the box's network interface (None without networking).
+    pub mac_address: Option<String>,
+
+    /// IPv4 address of the box inside its subnet (None without networking).
+    pub ip_address: Option<String>,
+
+    /// Effective Linux capabilities of the container process.
+    pub capabilities: Vec<String>,
+
+    /// Environment of the main container, with sensitive values redacted.
+    pub env: Vec<(String, String)>,
+
+    /// Effective entrypoint, command, working directory and user of the
+    /// main container as last started (None before the first start).
+    pub process: Option<ContainerProcess>,
+
+    /// Per-task init pipeline timings of the last start.
+    pub init_stages: Vec<StageTiming>,
+
+    /// Guest-reported boot breakdown of the last start.
+    pub boot_phases: Option<BootPhases>,
+
+    /// A command was killed by the guest kernel out of memory since the
+    /// last start.
+    pub oom_killed: bool,
 }
 
 impl BoxInfo {
@@ -438,6 +476,21 @@ impl BoxInfo {
             cpus: config.options.cpus.unwrap_or(2),
             memory_mib: config.options.memory_mib.unwrap_or(512),
             labels: HashMap::new(),
+            ports: state.ports.clone(),
+            health: state.health.clone(),
+            hostname: config.hostname(),
+            mac_address: config.mac_address(),
+            ip_address: config.ip_address().map(|ip| ip.to_string()),
+            capabilities: config
+                .options
+                .security
+                .container_capabilities()
+                .unwrap_or_default(),
+            env: crate::runtime::env::redact(&config.options.env),
+            process: state.process.clone(),
+            init_stages: state.init_stages.clone(),
+            boot_phases: state.boot_phases.clone(),
+            oom_killed: state.oom_killed,
         }
     }
 }
@@ -473,6 +526,11 @@ pub struct BoxStateInfo {
 
     /// Process ID of the VMM subprocess (None if not running).
     pub pid: Option<u32>,
+
+    /// A command was killed by the guest kernel out of memory since the
+    /// last start (Docker's `OOMKilled`).
+    #[serde(default)]
+    pub oom_killed: bool,
 }
 
 impl BoxStateInfo {
@@ -482,6 +540,7 @@ impl BoxStateInfo {
             status: state.status,
             running: state.status.is_running(),
             pid: state.pid,
+            oom_killed: state.oom_killed,
         }
     }
 }
@@ -494,6 +553,7 @@ impl From<&BoxInfo> for BoxStateInfo {
             status: info.status,
             running: info.status.is_running(),
             pid: info.pid,
+            oom_killed: info.oom_killed,
         }
     }
 }
@@ -525,6 +585,395 @@ pub struct ImageInfo {
 
     /// Image size in bytes (if available)
     pub size: Option<Bytes>,
+
+    /// Statistics of the most recent registry pull (None for local tags,
+    /// imports, or images cached before pull metrics were recorded)
+    pub last_pull: Option<ImagePullMetrics>,
+}
+
+/// Kind of a cached image blob.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageBlobKind {
+    /// Image manifest or image index
+    Manifest,
+    /// Image config
+    Config,
+    /// Layer tarball
+    Layer,
+}
+
+impl ImageBlobKind {
+    /// Lowercase name, e.g. `layer`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ImageBlobKind::Manifest => "manifest",
+            ImageBlobKind::Config => "config",
+            ImageBlobKind::Layer => "layer",
+        }
+    }
+}
+
+impl std::fmt::Display for ImageBlobKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A cached blob whose content does not hash to its digest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CorruptBlob {
+    pub kind: ImageBlobKind,
+    /// Digest the blob is stored under
+    pub digest: String,
+    /// Digest of the content on disk (None if the file is missing)
+    pub actual: Option<String>,
+    /// Cached image references that use the blob
+    pub images: Vec<String>,
+    /// Whether the file was deleted, so the next pull downloads it again
+    pub removed: bool,
+}
+
+/// Result of `BoxliteRuntime::verify_images()`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageVerification {
+    /// Distinct blobs re-hashed
+    pub blobs_checked: usize,
+    /// Blobs that failed, ordered by kind and digest
+    pub corrupt: Vec<CorruptBlob>,
+}
+
+impl ImageVerification {
+    /// Whether every blob matched its digest.
+    pub fn is_ok(&self) -> bool {
+        self.corrupt.is_empty()
+    }
+}
+
+/// Configuration, layers and platform of a cached image, from
+/// `BoxliteRuntime::image_inspect()`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageInspect {
+    /// Reference the image is cached under
+    pub reference: String,
+    /// Image ID (manifest digest)
+    pub id: String,
+    /// Digest of the config blob
+    pub config_digest: String,
+    /// Target OS (e.g. "linux")
+    pub os: String,
+    /// Target CPU architecture (e.g. "amd64")
+    pub architecture: String,
+    /// CPU variant (e.g. "v8"), if any
+    pub variant: Option<String>,
+    /// Image build time, as recorded in the config
+    pub created: Option<String>,
+    pub author: Option<String>,
+    pub entrypoint: Vec<String>,
+    pub cmd: Vec<String>,
+    /// Environment in `KEY=value` form
+    pub env: Vec<String>,
+    pub working_dir: Option<String>,
+    pub user: Option<String>,
+    /// Exposed ports in `port/proto` form, sorted
+    pub exposed_ports: Vec<String>,
+    pub labels: BTreeMap<String, String>,
+    /// Layers in manifest order, base layer first
+    pub layers: Vec<ImageLayerInspect>,
+    /// Build history, oldest first
+    pub history: Vec<ImageHistoryEntry>,
+    /// Sum of compressed layer sizes
+    pub size: Bytes,
+}
+
+/// One layer of [`ImageInspect`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageLayerInspect {
+    pub digest: String,
+    pub media_type: String,
+    /// Compressed size from the manifest
+    pub size: Bytes,
+    /// Whether the layer tarball is in the local cache
+    pub downloaded: bool,
+    /// Whether the layer is extracted in the local cache
+    pub extracted: bool,
+    /// Whether the most recent pull found the layer already cached
+    /// (None for local tags, imports, or images pulled before pull metrics
+    /// were recorded)
+    pub cached_on_pull: Option<bool>,
+}
+
+/// One build step of [`ImageInspect`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageHistoryEntry {
+    pub created: Option<String>,
+    pub created_by: Option<String>,
+    pub comment: Option<String>,
+    /// Whether the step left the filesystem unchanged (no layer)
+    pub empty_layer: bool,
+}
+
+/// Outcome of prefetching one image with `BoxliteRuntime::prefetch()`.
+#[derive(Debug)]
+pub struct PrefetchResult {
+    /// Image reference as requested
+    pub image: String,
+    /// Pull and base disk preparation result
+    pub result: BoxliteResult<()>,
+    /// Wall-clock time spent on this image
+    pub elapsed: std::time::Duration,
+}
+
+/// Disk space used by the runtime, from `BoxliteRuntime::disk_usage()`.
+///
+/// All sizes are allocated bytes, so sparse disk images count what they
+/// occupy rather than their virtual size.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiskUsage {
+    /// Downloaded layer tarballs
+    pub layers_bytes: u64,
+    /// Extracted layers as if every file were stored separately
+    pub extracted_logical_bytes: u64,
+    /// Extracted layers on disk (files shared through dedup count once)
+    pub extracted_bytes: u64,
+    /// Unique files in the content store (`dedup_layers`)
+    pub dedup_objects: u64,
+    /// Cached base rootfs disk images
+    pub disk_images_bytes: u64,
+    /// Box directories (disks, logs, sockets)
+    pub boxes_bytes: u64,
+}
+
+impl DiskUsage {
+    /// Bytes saved by sharing files between extracted layers.
+    pub fn dedup_saved_bytes(&self) -> u64 {
+        self.extracted_logical_bytes
+            .saturating_sub(self.extracted_bytes)
+    }
+
+    /// Total bytes on disk.
+    pub fn total_bytes(&self) -> u64 {
+        self.layers_bytes + self.extracted_bytes + self.disk_images_bytes + self.boxes_bytes
+    }
+}
+
+/// What one pruning pass removed, from `BoxliteRuntime::prune()` or the
+/// background reaper (`BoxliteOptions::retention`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PruneReport {
+    /// Boxes removed for being stopped longer than `max_stopped_box_age_secs`
+    pub boxes: Vec<BoxID>,
+    /// References of the images evicted from the cache
+    pub images: Vec<String>,
+    /// Image cache size after the pass (None = no cache limit in the policy)
+    pub image_cache_bytes: Option<u64>,
+}
+
+/// What `BoxliteRuntime::system_prune()` removes besides stopped boxes and
+/// dangling image data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemPruneOptions {
+    /// Also remove every cached image no box uses, not just dangling data
+    pub all_images: bool,
+    /// Remove runtime log files last written longer ago than this
+    pub logs_max_age: std::time::Duration,
+}
+
+impl Default for SystemPruneOptions {
+    fn default() -> Self {
+        Self {
+            all_images: false,
+            logs_max_age: std::time::Duration::from_secs(7 * 24 * 60 * 60),
+        }
+    }
+}
+
+/// How `BoxliteRuntime::import_box()` recreates a box from an archive.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportOptions {
+    /// Name of the imported box (None = the name stored in the archive)
+    pub name: Option<String>,
+    /// Keep the options that bind the box to the exporting host: volumes,
+    /// published ports, a custom kernel, a rootfs path, and MAC and IP
+    /// addresses. Off by default, since these name host paths and ports
+    /// chosen by whoever wrote the archive.
+    pub host_bindings: bool,
+}
+
+/// What `BoxliteRuntime::system_prune()` removed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SystemPruneReport {
+    /// Stopped boxes
+    pub boxes: Vec<BoxID>,
+    /// References of the cached images removed (`all_images` only)
+    pub images: Vec<String>,
+    /// Blobs, extracted layers and disk images no image referred to
+    pub dangling_blobs: u64,
+    /// Anonymous volume directories no box mounted
+    pub volumes: Vec<std::path::PathBuf>,
+    /// Runtime log files
+    pub logs: Vec<std::path::PathBuf>,
+    /// Bytes freed on disk
+    pub reclaimed_bytes: u64,
+}
+
+// ============================================================================
+// SHUTDOWN REPORT
+// ============================================================================
+
+/// How one box ended during `BoxliteRuntime::shutdown()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BoxShutdownOutcome {
+    /// Stopped gracefully within the timeout
+    Stopped,
+    /// Did not stop within the timeout and was killed
+    Killed,
+    /// Could not be stopped
+    Failed(String),
+}
+
+/// Result of stopping one box during shutdown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoxShutdown {
+    pub box_id: BoxID,
+    pub name: Option<String>,
+    pub outcome: BoxShutdownOutcome,
+    /// Time from the start of the stop to its outcome
+    pub elapsed: std::time::Duration,
+}
+
+/// Progress of `BoxliteRuntime::shutdown_with_progress()`, reported per box.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShutdownEvent {
+    /// Graceful stop of the box began
+    Stopping(BoxID),
+    /// The box missed the timeout and is being killed
+    Killing(BoxID),
+    /// The box is done
+    Finished(BoxShutdown),
+}
+
+/// What `BoxliteRuntime::shutdown()` did to each box the runtime held.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShutdownReport {
+    /// One entry per box, in the order they finished
+    pub boxes: Vec<BoxShutdown>,
+}
+
+impl ShutdownReport {
+    /// Boxes that stopped gracefully.
+    pub fn stopped(&self) -> impl Iterator<Item = &BoxShutdown> {
+        self.with_outcome(|o| *o == BoxShutdownOutcome::Stopped)
+    }
+
+    /// Boxes that were killed after the timeout.
+    pub fn killed(&self) -> impl Iterator<Item = &BoxShutdown> {
+        self.with_outcome(|o| *o == BoxShutdownOutcome::Killed)
+    }
+
+    /// Boxes that could not be stopped.
+    pub fn failed(&self) -> impl Iterator<Item = &BoxShutdown> {
+        self.with_outcome(|o| matches!(o, BoxShutdownOutcome::Failed(_)))
+    }
+
+    /// Whether every box stopped, gracefully or by being killed.
+    pub fn is_clean(&self) -> bool {
+        self.failed().next().is_none()
+    }
+
+    fn with_outcome(
+        &self,
+        pred: impl Fn(&BoxShutdownOutcome) -> bool,
+    ) -> impl Iterator<Item = &BoxShutdown> {
+        self.boxes.iter().filter(move |b| pred(&b.outcome))
+    }
+}
+
+// ============================================================================
+// BOX PLAN (dry run)
+// ============================================================================
+
+/// What creating and starting a box would do, computed without side effects.
+///
+/// Returned by `BoxliteRuntime::plan()`. Contains no generated IDs or
+/// timestamps, so the same options against the same runtime state always
+/// produce the same plan; per-box paths use a `<box-id>` placeholder.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoxPlan {
+    /// User-defined name (optional).
+    pub name: Option<String>,
+
+    /// Engine that would run the box (e.g. "libkrun").
+    pub engine: String,
+
+    /// Image resolution, or None for a `RootfsPath` rootfs.
+    pub image: Option<ImagePlan>,
+
+    /// Pre-built rootfs directory, when not using an image.
+    pub rootfs_path: Option<String>,
+
+    /// CPU count after defaults.
+    pub cpus: u8,
+
+    /// Memory in MiB after defaults.
+    pub memory_mib: u32,
+
+    /// Requested container disk size in GB (None = image size).
+    pub disk_size_gb: Option<u64>,
+
+    /// Host CPU and I/O priority.
+    pub priority: BoxPriority,
+
+    /// Environment after runtime-level merges (e.g. proxy variables), with
+    /// sensitive values redacted.
+    pub env: Vec<(String, String)>,
+
+    /// Ports that would be published. `host_port` is None when assigned at start.
+    pub ports: Vec<PortSpec>,
+
+    /// User volumes with resolved host paths.
+    pub mounts: Vec<MountPlan>,
+
+    /// Files the box would create.
+    pub paths: PlanPaths,
+
+    /// Initialization pipeline: stages in order, each with its task names.
+    pub stages: Vec<Vec<String>>,
+}
+
+/// Image resolution result of a dry run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImagePlan {
+    /// Reference as given by the user.
+    pub reference: String,
+
+    /// References a pull would try, in order.
+    pub candidates: Vec<String>,
+
+    /// Reference found in the local cache, if any (no pull needed).
+    pub cached: Option<String>,
+
+    /// Manifest digest of the cached image.
+    pub manifest_digest: Option<String>,
+}
+
+/// User volume as it would be mounted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MountPlan {
+    /// virtiofs tag shared with the guest.
+    pub tag: String,
+    pub host_path: String,
+    pub guest_path: String,
+    pub read_only: bool,
+}
+
+/// Per-box paths, with `<box-id>` standing in for the generated ID.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanPaths {
+    pub box_home: String,
+    pub disk: String,
+    pub socket: String,
+    pub console_log: String,
 }
 
 // ============================================================================
@@ -607,6 +1056,7 @@ mod tests {
             container: ContainerRuntimeConfig {
                 id: ContainerID::new(),
             },
+            sidecars: Default::default(),
             options: BoxOptions {
                 rootfs: RootfsSpec::Image("python:3.11".to_string()),
                 cpus: Some(4),
@@ -632,6 +1082,36 @@ mod tests {
         assert_eq!(info.image, "python:3.11");
         assert_eq!(info.cpus, 4);
         assert_eq!(info.memory_mib, 1024);
+        assert_eq!(info.hostname, "01hjk4tnrpqsxyz8wm6ncvt9r5");
+        assert_eq!(
+            info.mac_address.as_deref(),
+            Some(crate::net::constants::GUEST_MAC_STRING)
+        );
+        assert_eq!(
+            info.ip_address.as_deref(),
+            Some(crate::net::constants::GUEST_IP)
+        );
+
+        let named = BoxConfig {
+            name: Some("web".to_string()),
+            ..config.clone()
+        };
+        assert_eq!(BoxInfo::new(&named, &state).hostname, "web");
+        let mut explicit = BoxConfig {
+            name: Some("my_box".to_string()),
+            ..config
+        };
+        assert_eq!(
+            BoxInfo::new(&explicit, &state).hostname,
+            "01hjk4tnrpqsxyz8wm6ncvt9r5"
+        );
+        explicit.options.hostname = Some("db.internal".to_string());
+        explicit.options.mac_address = Some("02:42:AC:11:00:02".to_string());
+        explicit.options.ip_address = Some("192.168.127.10".to_string());
+        let info = BoxInfo::new(&explicit, &state);
+        assert_eq!(info.hostname, "db.internal");
+        assert_eq!(info.mac_address.as_deref(), Some("02:42:ac:11:00:02"));
+        assert_eq!(info.ip_address.as_deref(), Some("192.168.127.10"));
     }
 
     #[test]
@@ -38,6 +38,24 @@ pub fn kill_process(pid: u32) -> bool {
     unsafe { libc::kill(pid as i32, libc::SIGKILL) == 0 || !is_process_alive(pid) }
 }
 
+/// Freeze a process with SIGSTOP.
+///
+/// # Returns
+/// * `true` - Signal was delivered
+/// * `false` - Process does not exist or permission denied
+pub fn pause_process(pid: u32) -> bool {
+    unsafe { libc::kill(pid as i32, libc::SIGSTOP) == 0 }
+}
+
+/// Continue a process frozen by [`pause_process`] with SIGCONT.
+///
+/// # Returns
+/// * `true` - Signal was delivered
+/// * `false` - Process does not exist or permission denied
+pub fn resume_process(pid: u32) -> bool {
+    unsafe { libc::kill(pid as i32, libc::SIGCONT) == 0 }
+}
+
 /// Check if a process with the given PID exists.
 ///
 /// Uses `libc::kill(pid, 0)` which sends a null signal to check existence.
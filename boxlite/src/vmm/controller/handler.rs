@@ -15,7 +15,9 @@ use boxlite_shared::BoxliteResult;
 /// - Get process ID
 ///
 /// Other metadata (transport, boot duration) is stored in BoxConfig/BoxMetrics.
-pub trait VmmHandler: Send {
+///
+/// Handlers are `Sync` so status and metrics reads can run concurrently.
+pub trait VmmHandler: Send + Sync {
     /// Stop the VM.
     fn stop(&mut self) -> BoxliteResult<()>;
 
@@ -203,6 +203,8 @@ pub struct ShimController {
     box_id: BoxID,
     /// Box options (includes security and volumes for jailer isolation)
     options: crate::runtime::options::BoxOptions,
+    /// Runtime-wide limits on all boxes' host resources
+    host_limits: Option<crate::runtime::options::HostLimits>,
 }
 
 impl ShimController {
@@ -236,8 +238,18 @@ impl ShimController {
             engine_type,
             box_id,
             options,
+            host_limits: None,
         })
     }
+
+    /// Apply the runtime's `BoxliteOptions::host_limits` to the subprocess.
+    pub fn with_host_limits(
+        mut self,
+        host_limits: Option<crate::runtime::options::HostLimits>,
+    ) -> Self {
+        self.host_limits = host_limits;
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -268,6 +280,7 @@ impl VmmController for ShimController {
             // VM configuration
             cpus: config.cpus,
             memory_mib: config.memory_mib,
+            kernel: config.kernel.clone(),
             fs_shares: config.fs_shares.clone(),
             block_devices: config.block_devices.clone(),
             guest_entrypoint,
@@ -277,6 +290,8 @@ impl VmmController for ShimController {
             network_config: config.network_config.clone(), // Pass port mappings to subprocess (shim creates gvproxy)
             network_backend_endpoint: None, // Will be populated by shim (not serialized)
             home_dir: config.home_dir.clone(),
+            box_dir: config.box_dir.clone(),
+            images_dir: config.images_dir.clone(),
             console_output: config.console_output.clone(),
             detach: config.detach,
             parent_pid: config.parent_pid,
@@ -310,9 +325,9 @@ impl VmmController for ShimController {
             &self.binary_path,
             self.engine_type,
             &config_json,
-            &config.home_dir,
-            self.box_id.as_str(),
+            &serializable_config,
             &self.options,
+            self.host_limits.as_ref(),
         )?;
         // spawn_duration: time to create Box subprocess
         let shim_spawn_duration = shim_spawn_start.elapsed();
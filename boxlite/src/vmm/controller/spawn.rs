@@ -6,10 +6,9 @@ use std::{
 };
 
 use crate::jailer::Jailer;
-use crate::runtime::layout::FilesystemLayout;
-use crate::runtime::options::BoxOptions;
+use crate::runtime::options::{BoxOptions, HostLimits, VolumeSpec};
 use crate::util::configure_library_env;
-use crate::vmm::VmmKind;
+use crate::vmm::{InstanceSpec, VmmKind};
 use boxlite_shared::errors::{BoxliteError, BoxliteResult};
 use libkrun_sys::krun_create_ctx;
 
@@ -19,9 +18,9 @@ use libkrun_sys::krun_create_ctx;
 /// * `binary_path` - Path to the boxlite-shim binary
 /// * `engine_type` - Type of VM engine to use
 /// * `config_json` - Serialized BoxConfig
-/// * `home_dir` - BoxLite home directory
-/// * `box_id` - Unique box identifier
+/// * `spec` - Instance spec (box identifier and the directories it uses)
 /// * `options` - Box options (includes security and volumes)
+/// * `host_limits` - Runtime-wide host limits, if any
 ///
 /// # Returns
 /// * `Ok(Child)` - Successfully spawned subprocess
@@ -30,9 +29,9 @@ pub(crate) fn spawn_subprocess(
     binary_path: &Path,
     engine_type: VmmKind,
     config_json: &str,
-    home_dir: &Path,
-    box_id: &str,
+    spec: &InstanceSpec,
     options: &BoxOptions,
+    host_limits: Option<&HostLimits>,
 ) -> BoxliteResult<Child> {
     // Build shim arguments
     let shim_args = vec![
@@ -42,15 +41,23 @@ pub(crate) fn spawn_subprocess(
         config_json.to_string(),
     ];
 
-    // Create filesystem layout and box directory
-    use crate::runtime::layout::FsLayoutConfig;
-    let layout = FilesystemLayout::new(home_dir.to_path_buf(), FsLayoutConfig::default());
-    let box_dir = layout.boxes_dir().join(box_id);
+    // The shim reads a custom kernel/initrd at boot, so expose them read-only
+    let mut volumes = options.volumes.clone();
+    if let Some(kernel) = &options.kernel {
+        volumes.extend(kernel.host_paths().map(|path| VolumeSpec {
+            host_path: path.to_string_lossy().into_owned(),
+            read_only: true,
+            ..Default::default()
+        }));
+    }
 
     // Create Jailer with security options and volumes
-    let jailer = Jailer::new(box_id, &box_dir)
+    let jailer = Jailer::new(&spec.box_id, &spec.box_dir)
+        .with_runtime_dirs(&spec.home_dir, &spec.images_dir)
         .with_security(options.security.clone())
-        .with_volumes(options.volumes.clone());
+        .with_volumes(volumes)
+        .with_host_limits(host_limits.cloned())
+        .with_priority(options.priority);
 
     // Setup pre-spawn isolation (cgroups on Linux, no-op on macOS)
     jailer.setup_pre_spawn()?;
@@ -28,7 +28,7 @@ pub struct VirtualizationSupport {
 ///
 /// # Errors
 ///
-/// Returns `BoxliteError::Unsupported` with diagnostic information if:
+/// Returns `BoxliteError::KvmUnavailable` with diagnostic information if:
 /// - Linux: `/dev/kvm` doesn't exist or isn't accessible
 /// - macOS: Wrong architecture (only ARM64 supported) or Hypervisor.framework unavailable
 ///
@@ -71,7 +71,7 @@ pub fn check_virtualization_support() -> BoxliteResult<VirtualizationSupport> {
 ///
 /// # Errors
 ///
-/// Returns `BoxliteError::Unsupported` if KVM is not available or accessible.
+/// Returns `BoxliteError::KvmUnavailable` if KVM is not available or accessible.
 #[cfg(target_os = "linux")]
 fn check_linux_kvm() -> BoxliteResult<VirtualizationSupport> {
     use std::path::Path;
@@ -102,7 +102,7 @@ fn check_linux_kvm() -> BoxliteResult<VirtualizationSupport> {
                   );
         }
 
-        return Err(BoxliteError::Unsupported(suggestions));
+        return Err(BoxliteError::KvmUnavailable(suggestions));
     }
 
     // Check if /dev/kvm is accessible
@@ -115,7 +115,7 @@ fn check_linux_kvm() -> BoxliteResult<VirtualizationSupport> {
             reason: "KVM is available and accessible".to_string(),
         }),
         Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
-            Err(BoxliteError::Unsupported(format!(
+            Err(BoxliteError::KvmUnavailable(format!(
                 "{} exists but access denied (permissions)\n\n\
                  Suggestions:\n\
                  - Add your user to the kvm group: sudo usermod -aG kvm $USER\n\
@@ -125,7 +125,7 @@ fn check_linux_kvm() -> BoxliteResult<VirtualizationSupport> {
                 KVM_DEVICE, KVM_DEVICE
             )))
         }
-        Err(e) => Err(BoxliteError::Unsupported(format!(
+        Err(e) => Err(BoxliteError::KvmUnavailable(format!(
             "{} exists but couldn't be accessed: {}\n\n\
              Suggestions:\n\
              - Check if another VM process is locking the device\n\
@@ -144,7 +144,7 @@ fn check_linux_kvm() -> BoxliteResult<VirtualizationSupport> {
 ///
 /// # Errors
 ///
-/// Returns `BoxliteError::Unsupported` if architecture is not ARM64 or
+/// Returns `BoxliteError::KvmUnavailable` if architecture is not ARM64 or
 /// Hypervisor.framework is not available.
 #[cfg(target_os = "macos")]
 fn check_macos_hypervisor() -> BoxliteResult<VirtualizationSupport> {
@@ -157,7 +157,7 @@ fn check_macos_hypervisor() -> BoxliteResult<VirtualizationSupport> {
             .arg("kern.hv_support")
             .output()
             .map_err(|e| {
-                BoxliteError::Unsupported(format!(
+                BoxliteError::KvmUnavailable(format!(
                     "Failed to check Hypervisor.framework support: {}\n\n\
                      Suggestions:\n\
                      - Verify macOS version and system integrity\n\
@@ -167,7 +167,7 @@ fn check_macos_hypervisor() -> BoxliteResult<VirtualizationSupport> {
             })?;
 
         if !output.status.success() {
-            return Err(BoxliteError::Unsupported(
+            return Err(BoxliteError::KvmUnavailable(
                 "sysctl command failed\n\n\
                  Suggestions:\n\
                  - Verify macOS version and system integrity\n\
@@ -186,7 +186,7 @@ fn check_macos_hypervisor() -> BoxliteResult<VirtualizationSupport> {
                 reason: "Hypervisor.framework is available (Apple Silicon)".to_string(),
             })
         } else {
-            Err(BoxliteError::Unsupported(
+            Err(BoxliteError::KvmUnavailable(
                 "Hypervisor.framework is not available\n\n\
                  Suggestions:\n\
                  - Verify you're on macOS 10.10 or later\n\
@@ -199,7 +199,7 @@ fn check_macos_hypervisor() -> BoxliteResult<VirtualizationSupport> {
 
     #[cfg(not(target_arch = "aarch64"))]
     {
-        Err(BoxliteError::Unsupported(format!(
+        Err(BoxliteError::KvmUnavailable(format!(
             "Unsupported architecture: {}\n\n\
              Suggestions:\n\
              - Boxlite on macOS requires Apple Silicon (ARM64)\n\
@@ -209,10 +209,78 @@ fn check_macos_hypervisor() -> BoxliteResult<VirtualizationSupport> {
     }
 }
 
+/// Host huge page pool, as reported by `/proc/meminfo`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct HugepageInfo {
+    /// Pages in the pool (`HugePages_Total`)
+    pub total: u64,
+    /// Unallocated pages (`HugePages_Free`)
+    pub free: u64,
+    /// Default huge page size in KiB (`Hugepagesize`)
+    pub page_size_kib: u64,
+}
+
+impl HugepageInfo {
+    /// Unallocated huge page memory in MiB.
+    pub fn free_mib(&self) -> u64 {
+        self.free * self.page_size_kib / 1024
+    }
+}
+
+/// Read the host huge page pool.
+///
+/// Returns `None` on non-Linux hosts or when the kernel has no hugetlbfs support.
+pub fn hugepage_info() -> Option<HugepageInfo> {
+    #[cfg(target_os = "linux")]
+    {
+        let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+        parse_hugepage_info(&meminfo)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_hugepage_info(meminfo: &str) -> Option<HugepageInfo> {
+    let field = |name: &str| -> Option<u64> {
+        meminfo.lines().find_map(|line| {
+            let rest = line.strip_prefix(name)?.strip_prefix(':')?;
+            rest.split_whitespace().next()?.parse().ok()
+        })
+    };
+
+    Some(HugepageInfo {
+        total: field("HugePages_Total")?,
+        free: field("HugePages_Free")?,
+        page_size_kib: field("Hugepagesize")?,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_hugepage_info() {
+        let meminfo = "MemTotal:       16318480 kB\n\
+                       HugePages_Total:     512\n\
+                       HugePages_Free:      256\n\
+                       Hugepagesize:       2048 kB\n";
+        let info = parse_hugepage_info(meminfo).unwrap();
+        assert_eq!(info.total, 512);
+        assert_eq!(info.free, 256);
+        assert_eq!(info.page_size_kib, 2048);
+        assert_eq!(info.free_mib(), 512);
+    }
+
+    #[test]
+    fn test_parse_hugepage_info_missing() {
+        assert_eq!(parse_hugepage_info("MemTotal: 1 kB\n"), None);
+    }
+
     #[test]
     #[cfg(target_os = "linux")]
     fn test_linux_kvm_check_runs() {
@@ -15,3 +15,13 @@ pub mod network_features {
     // This is required by gvproxy when using VFKit protocol with unixgram sockets
     pub const NET_FLAG_VFKIT: u32 = 1 << 0;
 }
+
+/// Kernel image formats accepted by `krun_set_kernel`
+pub mod kernel_format {
+    pub const RAW: u32 = 0;
+    pub const ELF: u32 = 1;
+    pub const PE_GZ: u32 = 2;
+    pub const IMAGE_BZ2: u32 = 3;
+    pub const IMAGE_GZ: u32 = 4;
+    pub const IMAGE_ZSTD: u32 = 5;
+}
@@ -233,7 +233,7 @@ impl KrunContext {
         arg_ptrs.push(ptr::null());
 
         tracing::trace!("Building env array with {} elements:", env.len());
-        for (k, v) in env.iter() {
+        for (k, v) in crate::runtime::env::redact(env) {
             tracing::trace!("  {}={}", k, v);
         }
 
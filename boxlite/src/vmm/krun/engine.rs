@@ -2,6 +2,7 @@
 
 use super::context::KrunContext;
 use crate::runtime::constants::network;
+use crate::runtime::options::KernelFormat;
 use crate::vmm::{InstanceSpec, Vmm, VmmConfig, VmmInstance, engine::VmmInstanceImpl};
 use boxlite_shared::errors::{BoxliteError, BoxliteResult};
 
@@ -167,6 +168,18 @@ impl Krun {
         guest_args
     }
 
+    fn kernel_format(format: KernelFormat) -> u32 {
+        use crate::vmm::krun::constants::kernel_format::*;
+        match format {
+            KernelFormat::Raw => RAW,
+            KernelFormat::Elf => ELF,
+            KernelFormat::PeGz => PE_GZ,
+            KernelFormat::ImageBz2 => IMAGE_BZ2,
+            KernelFormat::ImageGz => IMAGE_GZ,
+            KernelFormat::ImageZstd => IMAGE_ZSTD,
+        }
+    }
+
     fn set_entrypoint(config: &InstanceSpec, ctx: &mut KrunContext) -> Result<(), BoxliteError> {
         // Prepare entrypoint - the VM runs the guest agent which will:
         // 1. Mount virtiofs shares
@@ -229,6 +242,23 @@ impl Vmm for Krun {
             );
         }
 
+        // Validate custom kernel files exist
+        if let Some(kernel) = &config.kernel {
+            for path in kernel.host_paths() {
+                if !path.exists() {
+                    return Err(BoxliteError::Engine(format!(
+                        "Kernel file not found: {}",
+                        path.display()
+                    )));
+                }
+            }
+            tracing::debug!(
+                kernel = %kernel.path.display(),
+                format = ?kernel.format,
+                "Validated custom kernel"
+            );
+        }
+
         // Create and configure libkrun context
         let ctx = unsafe {
             tracing::debug!("Initializing libkrun logging system");
@@ -399,6 +429,27 @@ impl Vmm for Krun {
                 ctx.set_rootfs(rootfs_str)?;
             }
 
+            // Replace the libkrunfw kernel when a custom one is given
+            if let Some(kernel) = &config.kernel {
+                fn path_str(path: &std::path::Path) -> BoxliteResult<&str> {
+                    path.to_str().ok_or_else(|| {
+                        BoxliteError::Engine(format!("Invalid kernel path: {}", path.display()))
+                    })
+                }
+                let initrd = kernel.initrd.as_deref().map(path_str).transpose()?;
+                tracing::info!(
+                    kernel = %kernel.path.display(),
+                    cmdline = ?kernel.cmdline,
+                    "Configuring custom guest kernel"
+                );
+                ctx.set_kernel(
+                    path_str(&kernel.path)?,
+                    Self::kernel_format(kernel.format),
+                    initrd,
+                    kernel.cmdline.as_deref(),
+                )?;
+            }
+
             tracing::debug!("Setting working directory to /");
             // Set working directory (default to root if not specified)
             ctx.set_workdir("/boxlite")?;
@@ -0,0 +1,866 @@
+//! In-process mock engine (`VmmKind::Mock`) for tests.
+//!
+//! Mock boxes have no VM and no image: an in-process gRPC server stands in
+//! for the guest agent and answers exec and watch requests from a
+//! [`MockScript`].
+//! A mock box lives as long as the runtime process that started it.
+//!
+//! ```ignore
+//! let script = MockScript::new()
+//!     .on(&["python3", "--version"], MockExec::new().stdout("Python 3.12.0\n"));
+//! let runtime = BoxliteRuntime::with_mock_engine(options, script.clone())?;
+//! ```
+
+use crate::vmm::controller::{VmmHandler, VmmMetrics};
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+use boxlite_shared::{
+    AttachRequest, Container, ContainerInitRequest, ContainerInitResponse, ContainerInitSuccess,
+    ContainerMountRequest, ContainerMountResponse, ContainerProcessesRequest,
+    ContainerProcessesResponse, ContainerServer, ContainerUnmountRequest, ContainerUnmountResponse,
+    DownloadChunk, DownloadRequest, ExecError, ExecOutput, ExecRequest, ExecResponse, ExecStdin,
+    Execution, ExecutionServer, Files, FilesServer, FsEvent, Guest, GuestBootPhases,
+    GuestInitRequest, GuestInitResponse, GuestInitSuccess, GuestServer, KillRequest, KillResponse,
+    ListeningPortsRequest, ListeningPortsResponse, ListeningSocket, PingRequest, PingResponse,
+    ProcessInfo, ReclaimMemoryRequest, ReclaimMemoryResponse, ResizeTtyRequest, ResizeTtyResponse,
+    SendInputAck, ShutdownRequest, ShutdownResponse, SocketProtocol, StreamCompression,
+    UploadChunk, UploadResponse, WaitRequest, WaitResponse, WatchPathRequest, compression,
+    constants::exec_error, container_init_response, exec_output, guest_init_response,
+};
+use futures::Stream;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, watch};
+use tokio_stream::StreamExt;
+use tokio_stream::wrappers::{UnboundedReceiverStream, UnixListenerStream};
+use tokio_util::sync::CancellationToken;
+use tonic::transport::Server;
+use tonic::{Request, Response, Status, Streaming};
+
+/// How long the mock guest keeps trying to signal readiness.
+const READY_TIMEOUT: Duration = Duration::from_secs(30);
+
+// ============================================================================
+// SCRIPT
+// ============================================================================
+
+/// Scripted outcome of a command run in a mock box.
+#[derive(Clone, Debug, Default)]
+pub struct MockExec {
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+    exit_code: i32,
+    delay: Option<Duration>,
+    echo_stdin: bool,
+    listen: Vec<u16>,
+    not_found: bool,
+    oom_killed: bool,
+}
+
+impl MockExec {
+    /// A command that succeeds without output.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A program missing from the box: exec fails in the guest with
+    /// `command_not_found`, which the host reports as exit code 127.
+    pub fn not_found() -> Self {
+        Self {
+            exit_code: exec_error::COMMAND_NOT_FOUND_EXIT_CODE,
+            not_found: true,
+            ..Self::default()
+        }
+    }
+
+    /// Data written to stdout.
+    pub fn stdout(mut self, data: impl Into<Vec<u8>>) -> Self {
+        self.stdout = data.into();
+        self
+    }
+
+    /// Data written to stderr.
+    pub fn stderr(mut self, data: impl Into<Vec<u8>>) -> Self {
+        self.stderr = data.into();
+        self
+    }
+
+    /// Exit code reported by `wait()`.
+    pub fn exit_code(mut self, exit_code: i32) -> Self {
+        self.exit_code = exit_code;
+        self
+    }
+
+    /// Run for `delay` before producing output and exiting.
+    ///
+    /// The command can be killed while it runs.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    /// Copy stdin to stdout and exit once stdin is closed.
+    pub fn echo_stdin(mut self) -> Self {
+        self.echo_stdin = true;
+        self
+    }
+
+    /// Listen on TCP `port` (all addresses) while the command runs.
+    pub fn listen(mut self, port: u16) -> Self {
+        self.listen.push(port);
+        self
+    }
+
+    /// End by the guest kernel's OOM killer: SIGKILL, reported as an OOM
+    /// kill.
+    pub fn oom_killed(mut self) -> Self {
+        self.oom_killed = true;
+        self
+    }
+}
+
+/// Maps commands to scripted results and records the commands executed.
+///
+/// Rules match when their words are a prefix of the command line (program
+/// followed by arguments); the most recently added matching rule wins.
+/// Unmatched commands fall back to built-ins: `echo`, `true`, `false`,
+/// `cat` (echoes stdin) and `sleep`. Anything else is [`MockExec::not_found`].
+///
+/// Clones share rules and history, so a test can keep a clone to inspect
+/// [`MockScript::calls`] after handing the script to the runtime.
+#[derive(Clone, Default)]
+pub struct MockScript {
+    inner: Arc<ScriptInner>,
+}
+
+#[derive(Default)]
+struct ScriptInner {
+    rules: Mutex<Vec<(Vec<String>, MockExec)>>,
+    calls: Mutex<Vec<Vec<String>>>,
+    fs_events: Mutex<Vec<crate::FsEvent>>,
+}
+
+impl MockScript {
+    /// An empty script (built-ins only).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer commands starting with `command` with `result`.
+    pub fn on<S: AsRef<str>>(self, command: &[S], result: MockExec) -> Self {
+        let words = command.iter().map(|w| w.as_ref().to_string()).collect();
+        self.inner.rules.lock().push((words, result));
+        self
+    }
+
+    /// Report a file change to watches covering `path`.
+    ///
+    /// Each watch replays the matching events in order, then stays open.
+    pub fn fs_event(self, kind: crate::FsEventKind, path: impl Into<String>) -> Self {
+        self.inner.fs_events.lock().push(crate::FsEvent {
+            kind,
+            path: path.into(),
+            is_dir: false,
+            cookie: 0,
+        });
+        self
+    }
+
+    /// Command lines executed so far, in order.
+    pub fn calls(&self) -> Vec<Vec<String>> {
+        self.inner.calls.lock().clone()
+    }
+
+    fn resolve(&self, argv: &[String]) -> MockExec {
+        self.inner.calls.lock().push(argv.to_vec());
+        let rules = self.inner.rules.lock();
+        if let Some((_, result)) = rules
+            .iter()
+            .rev()
+            .find(|(words, _)| argv.starts_with(words))
+        {
+            return result.clone();
+        }
+        builtin(argv)
+    }
+}
+
+impl std::fmt::Debug for MockScript {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MockScript")
+            .field("rules", &self.inner.rules.lock().len())
+            .field("calls", &self.inner.calls.lock().len())
+            .finish()
+    }
+}
+
+fn builtin(argv: &[String]) -> MockExec {
+    let (program, args) = match argv.split_first() {
+        Some((program, args)) => (program.as_str(), args),
+        None => return MockExec::not_found(),
+    };
+    match program.rsplit('/').next().unwrap_or(program) {
+        "echo" => MockExec::new().stdout(format!("{}\n", args.join(" "))),
+        "true" => MockExec::new(),
+        "false" => MockExec::new().exit_code(1),
+        "cat" if args.is_empty() => MockExec::new().echo_stdin(),
+        "sleep" => {
+            let secs = args
+                .first()
+                .and_then(|s| s.parse::<f64>().ok())
+                .unwrap_or(0.0);
+            MockExec::new().delay(Duration::from_secs_f64(secs.max(0.0)))
+        }
+        _ => MockExec::not_found(),
+    }
+}
+
+// ============================================================================
+// MOCK GUEST (gRPC services)
+// ============================================================================
+
+/// One execution: buffered output plus its terminal status.
+struct MockExecution {
+    output_tx: Mutex<Option<mpsc::UnboundedSender<ExecOutput>>>,
+    output_rx: Mutex<Option<mpsc::UnboundedReceiver<ExecOutput>>>,
+    status: watch::Sender<Option<WaitResponse>>,
+    started: Instant,
+    /// Output codec, echoed from the request like the real guest
+    compression: StreamCompression,
+    /// Process table entry while the command runs
+    pid: u32,
+    command: String,
+}
+
+impl MockExecution {
+    fn new(pid: u32, argv: &[String], compression: StreamCompression) -> Self {
+        let (output_tx, output_rx) = mpsc::unbounded_channel();
+        Self {
+            output_tx: Mutex::new(Some(output_tx)),
+            output_rx: Mutex::new(Some(output_rx)),
+            status: watch::Sender::new(None),
+            started: Instant::now(),
+            compression,
+            pid,
+            command: argv.join(" "),
+        }
+    }
+
+    fn send_stdout(&self, data: Vec<u8>) {
+        let (data, compressed) = compression::encode_chunk(self.compression, data);
+        self.send(exec_output::Event::Stdout(boxlite_shared::Stdout {
+            data,
+            compressed,
+        }));
+    }
+
+    fn send_stderr(&self, data: Vec<u8>) {
+        let (data, compressed) = compression::encode_chunk(self.compression, data);
+        self.send(exec_output::Event::Stderr(boxlite_shared::Stderr {
+            data,
+            compressed,
+        }));
+    }
+
+    fn send(&self, event: exec_output::Event) {
+        if let Some(tx) = self.output_tx.lock().as_ref() {
+            let _ = tx.send(ExecOutput { event: Some(event) });
+        }
+    }
+
+    fn complete(&self, result: &MockExec) {
+        if self.status.borrow().is_some() {
+            return;
+        }
+        if !result.stdout.is_empty() {
+            self.send_stdout(result.stdout.clone());
+        }
+        if !result.stderr.is_empty() {
+            self.send_stderr(result.stderr.clone());
+        }
+        if result.oom_killed {
+            self.finish(0, libc::SIGKILL, true);
+        } else {
+            self.finish(result.exit_code, 0, false);
+        }
+    }
+
+    /// Close output and publish the exit status (first call wins).
+    fn finish(&self, exit_code: i32, signal: i32, oom_killed: bool) {
+        self.output_tx.lock().take();
+        let duration_ms = self.started.elapsed().as_millis() as u64;
+        self.status.send_if_modified(|status| {
+            if status.is_some() {
+                return false;
+            }
+            *status = Some(WaitResponse {
+                exit_code,
+                signal,
+                timed_out: false,
+                duration_ms,
+                error_message: String::new(),
+                oom_killed,
+            });
+            true
+        });
+    }
+}
+
+struct MockGuest {
+    script: MockScript,
+    executions: Mutex<HashMap<String, (Arc<MockExecution>, MockExec)>>,
+    next_pid: AtomicU32,
+}
+
+impl MockGuest {
+    #[allow(clippy::result_large_err)]
+    fn execution(&self, execution_id: &str) -> Result<(Arc<MockExecution>, MockExec), Status> {
+        self.executions
+            .lock()
+            .get(execution_id)
+            .cloned()
+            .ok_or_else(|| Status::not_found(format!("Execution not found: {}", execution_id)))
+    }
+}
+
+#[tonic::async_trait]
+impl Guest for MockGuest {
+    async fn init(
+        &self,
+        _request: Request<GuestInitRequest>,
+    ) -> Result<Response<GuestInitResponse>, Status> {
+        Ok(Response::new(GuestInitResponse {
+            result: Some(guest_init_response::Result::Success(GuestInitSuccess {
+                boot_phases: Some(GuestBootPhases::default()),
+            })),
+        }))
+    }
+
+    async fn ping(&self, _request: Request<PingRequest>) -> Result<Response<PingResponse>, Status> {
+        Ok(Response::new(PingResponse {
+            version: "mock".to_string(),
+            protocol_version: boxlite_shared::constants::protocol::VERSION,
+        }))
+    }
+
+    async fn shutdown(
+        &self,
+        _request: Request<ShutdownRequest>,
+    ) -> Result<Response<ShutdownResponse>, Status> {
+        Ok(Response::new(ShutdownResponse {}))
+    }
+
+    async fn reclaim_memory(
+        &self,
+        _request: Request<ReclaimMemoryRequest>,
+    ) -> Result<Response<ReclaimMemoryResponse>, Status> {
+        Ok(Response::new(ReclaimMemoryResponse {
+            reclaimed_bytes: 0,
+            free_bytes: 0,
+        }))
+    }
+}
+
+#[tonic::async_trait]
+impl Container for MockGuest {
+    async fn init(
+        &self,
+        request: Request<ContainerInitRequest>,
+    ) -> Result<Response<ContainerInitResponse>, Status> {
+        let container_id = request.into_inner().container_id;
+        Ok(Response::new(ContainerInitResponse {
+            result: Some(container_init_response::Result::Success(
+                ContainerInitSuccess { container_id },
+            )),
+        }))
+    }
+
+    async fn mount(
+        &self,
+        _request: Request<ContainerMountRequest>,
+    ) -> Result<Response<ContainerMountResponse>, Status> {
+        Ok(Response::new(ContainerMountResponse {}))
+    }
+
+    async fn unmount(
+        &self,
+        _request: Request<ContainerUnmountRequest>,
+    ) -> Result<Response<ContainerUnmountResponse>, Status> {
+        Ok(Response::new(ContainerUnmountResponse {}))
+    }
+
+    /// Commands still running, as root children of a phantom init.
+    async fn processes(
+        &self,
+        _request: Request<ContainerProcessesRequest>,
+    ) -> Result<Response<ContainerProcessesResponse>, Status> {
+        let mut processes: Vec<ProcessInfo> = self
+            .executions
+            .lock()
+            .values()
+            .filter(|(execution, _)| execution.status.borrow().is_none())
+            .map(|(execution, _)| ProcessInfo {
+                pid: execution.pid,
+                ppid: 0,
+                uid: 0,
+                user: "root".to_string(),
+                rss_bytes: 0,
+                command: execution.command.clone(),
+            })
+            .collect();
+        processes.sort_by_key(|process| process.pid);
+        Ok(Response::new(ContainerProcessesResponse { processes }))
+    }
+
+    /// Ports of running commands scripted with [`MockExec::listen`].
+    async fn listening_ports(
+        &self,
+        _request: Request<ListeningPortsRequest>,
+    ) -> Result<Response<ListeningPortsResponse>, Status> {
+        let mut sockets: Vec<ListeningSocket> = self
+            .executions
+            .lock()
+            .values()
+            .filter(|(execution, _)| execution.status.borrow().is_none())
+            .flat_map(|(execution, result)| {
+                let program = execution.command.split(' ').next().unwrap_or_default();
+                let command = program.rsplit('/').next().unwrap_or_default().to_string();
+                result.listen.iter().map(move |&port| ListeningSocket {
+                    protocol: SocketProtocol::Tcp as i32,
+                    address: "0.0.0.0".to_string(),
+                    port: port as u32,
+                    pid: execution.pid,
+                    command: command.clone(),
+                })
+            })
+            .collect();
+        sockets.sort_by_key(|socket| socket.port);
+        Ok(Response::new(ListeningPortsResponse { sockets }))
+    }
+}
+
+#[tonic::async_trait]
+impl Execution for MockGuest {
+    async fn exec(&self, request: Request<ExecRequest>) -> Result<Response<ExecResponse>, Status> {
+        let req = request.into_inner();
+        let pid = self.next_pid.fetch_add(1, Ordering::Relaxed);
+        let execution_id = req
+            .execution_id
+            .clone()
+            .unwrap_or_else(|| format!("mock-{}", pid));
+
+        let compression = req.compression();
+        let argv: Vec<String> = std::iter::once(req.program).chain(req.args).collect();
+        let result = self.script.resolve(&argv);
+        tracing::debug!(execution_id = %execution_id, ?argv, "Mock exec");
+
+        if result.not_found {
+            return Ok(Response::new(ExecResponse {
+                execution_id,
+                error: Some(ExecError {
+                    reason: exec_error::COMMAND_NOT_FOUND.to_string(),
+                    detail: format!("{}: command not found", argv[0]),
+                }),
+                ..Default::default()
+            }));
+        }
+
+        let execution = Arc::new(MockExecution::new(pid, &argv, compression));
+        self.executions.lock().insert(
+            execution_id.clone(),
+            (Arc::clone(&execution), result.clone()),
+        );
+
+        if !result.echo_stdin {
+            match result.delay {
+                None => execution.complete(&result),
+                Some(delay) => {
+                    tokio::spawn(async move {
+                        tokio::time::sleep(delay).await;
+                        execution.complete(&result);
+                    });
+                }
+            }
+        }
+
+        Ok(Response::new(ExecResponse {
+            execution_id,
+            pid,
+            started_at_ms: chrono::Utc::now().timestamp_millis() as u64,
+            error: None,
+            compression: compression as i32,
+        }))
+    }
+
+    type AttachStream = Pin<Box<dyn Stream<Item = Result<ExecOutput, Status>> + Send + 'static>>;
+
+    async fn attach(
+        &self,
+        request: Request<AttachRequest>,
+    ) -> Result<Response<Self::AttachStream>, Status> {
+        let execution_id = request.into_inner().execution_id;
+        let (execution, _) = self.execution(&execution_id)?;
+        let rx = execution
+            .output_rx
+            .lock()
+            .take()
+            .ok_or_else(|| Status::failed_precondition("Execution already attached"))?;
+        Ok(Response::new(
+            Box::pin(UnboundedReceiverStream::new(rx).map(Ok)) as Self::AttachStream,
+        ))
+    }
+
+    async fn send_input(
+        &self,
+        request: Request<Streaming<ExecStdin>>,
+    ) -> Result<Response<SendInputAck>, Status> {
+        let mut stream = request.into_inner();
+        while let Some(chunk) = stream.message().await? {
+            let (execution, result) = self.execution(&chunk.execution_id)?;
+            if !result.echo_stdin {
+                continue;
+            }
+            if !chunk.data.is_empty() {
+                execution.send_stdout(chunk.data);
+            }
+            if chunk.close {
+                execution.complete(&result);
+            }
+        }
+        Ok(Response::new(SendInputAck {}))
+    }
+
+    async fn wait(&self, request: Request<WaitRequest>) -> Result<Response<WaitResponse>, Status> {
+        let execution_id = request.into_inner().execution_id;
+        let (execution, _) = self.execution(&execution_id)?;
+        let mut status = execution.status.subscribe();
+        let response = status
+            .wait_for(Option::is_some)
+            .await
+            .map_err(|_| Status::internal("Execution dropped"))?
+            .clone()
+            .unwrap_or_default();
+        Ok(Response::new(response))
+    }
+
+    async fn kill(&self, request: Request<KillRequest>) -> Result<Response<KillResponse>, Status> {
+        let req = request.into_inner();
+        let (execution, _) = self.execution(&req.execution_id)?;
+        let signal = if req.signal == 0 { 9 } else { req.signal };
+        execution.finish(0, signal, false);
+        Ok(Response::new(KillResponse {
+            success: true,
+            error: None,
+        }))
+    }
+
+    async fn resize_tty(
+        &self,
+        request: Request<ResizeTtyRequest>,
+    ) -> Result<Response<ResizeTtyResponse>, Status> {
+        self.execution(&request.into_inner().execution_id)?;
+        Ok(Response::new(ResizeTtyResponse {
+            success: true,
+            error: None,
+        }))
+    }
+}
+
+#[tonic::async_trait]
+impl Files for MockGuest {
+    async fn upload(
+        &self,
+        request: Request<Streaming<UploadChunk>>,
+    ) -> Result<Response<UploadResponse>, Status> {
+        let mut stream = request.into_inner();
+        while stream.message().await?.is_some() {}
+        Ok(Response::new(UploadResponse {
+            success: true,
+            error: None,
+        }))
+    }
+
+    type DownloadStream =
+        Pin<Box<dyn Stream<Item = Result<DownloadChunk, Status>> + Send + 'static>>;
+
+    async fn download(
+        &self,
+        _request: Request<DownloadRequest>,
+    ) -> Result<Response<Self::DownloadStream>, Status> {
+        Err(Status::unimplemented(
+            "file download is not supported by the mock engine",
+        ))
+    }
+
+    type WatchPathStream = Pin<Box<dyn Stream<Item = Result<FsEvent, Status>> + Send + 'static>>;
+
+    #[allow(clippy::result_large_err)]
+    async fn watch_path(
+        &self,
+        request: Request<WatchPathRequest>,
+    ) -> Result<Response<Self::WatchPathStream>, Status> {
+        let req = request.into_inner();
+        let root = Path::new(&req.path);
+        let events: Vec<_> = self
+            .script
+            .inner
+            .fs_events
+            .lock()
+            .iter()
+            .filter(|event| {
+                let path = Path::new(&event.path);
+                match path.strip_prefix(root) {
+                    Ok(rel) => req.recursive || rel.components().count() <= 1,
+                    Err(_) => false,
+                }
+            })
+            .map(|event| Ok(proto_fs_event(event)))
+            .collect();
+        Ok(Response::new(Box::pin(
+            tokio_stream::iter(events).chain(futures::stream::pending()),
+        )))
+    }
+}
+
+fn proto_fs_event(event: &crate::FsEvent) -> FsEvent {
+    use crate::FsEventKind as Kind;
+    use boxlite_shared::FsEventKind as Proto;
+    let kind = match event.kind {
+        Kind::Created => Proto::Created,
+        Kind::Modified => Proto::Modified,
+        Kind::Removed => Proto::Removed,
+        Kind::MovedFrom => Proto::MovedFrom,
+        Kind::MovedTo => Proto::MovedTo,
+        Kind::Overflow => Proto::Overflow,
+    };
+    FsEvent {
+        kind: kind as i32,
+        path: event.path.clone(),
+        is_dir: event.is_dir,
+        cookie: event.cookie,
+    }
+}
+
+// ============================================================================
+// HANDLER
+// ============================================================================
+
+/// Handler for a mock box; stopping it shuts down the in-process guest.
+pub(crate) struct MockHandler {
+    shutdown: CancellationToken,
+    socket_path: PathBuf,
+}
+
+impl VmmHandler for MockHandler {
+    fn stop(&mut self) -> BoxliteResult<()> {
+        self.shutdown.cancel();
+        let _ = std::fs::remove_file(&self.socket_path);
+        Ok(())
+    }
+
+    fn metrics(&self) -> BoxliteResult<VmmMetrics> {
+        Ok(VmmMetrics::default())
+    }
+
+    fn is_running(&self) -> bool {
+        !self.shutdown.is_cancelled()
+    }
+
+    fn pid(&self) -> u32 {
+        std::process::id()
+    }
+}
+
+impl Drop for MockHandler {
+    fn drop(&mut self) {
+        self.shutdown.cancel();
+    }
+}
+
+/// Serve a mock guest on `socket_path` and signal readiness on `ready_socket_path`.
+pub(crate) async fn spawn(
+    script: MockScript,
+    socket_path: &Path,
+    ready_socket_path: &Path,
+) -> BoxliteResult<MockHandler> {
+    if socket_path.exists() {
+        let _ = std::fs::remove_file(socket_path);
+    }
+    if let Some(parent) = socket_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let listener = tokio::net::UnixListener::bind(socket_path).map_err(|e| {
+        BoxliteError::Engine(format!(
+            "Failed to bind mock guest socket {}: {}",
+            socket_path.display(),
+            e
+        ))
+    })?;
+
+    let guest = Arc::new(MockGuest {
+        script,
+        executions: Mutex::new(HashMap::new()),
+        next_pid: AtomicU32::new(1),
+    });
+    let shutdown = CancellationToken::new();
+
+    let server = Server::builder()
+        .add_service(ContainerServer::from_arc(guest.clone()))
+        .add_service(GuestServer::from_arc(guest.clone()))
+        .add_service(ExecutionServer::from_arc(guest.clone()))
+        .add_service(FilesServer::from_arc(guest));
+    let token = shutdown.clone();
+    tokio::spawn(async move {
+        let incoming = UnixListenerStream::new(listener);
+        if let Err(e) = server
+            .serve_with_incoming_shutdown(incoming, token.cancelled())
+            .await
+        {
+            tracing::warn!("Mock guest server error: {}", e);
+        }
+    });
+
+    tokio::spawn(notify_ready(
+        ready_socket_path.to_path_buf(),
+        shutdown.clone(),
+    ));
+
+    Ok(MockHandler {
+        shutdown,
+        socket_path: socket_path.to_path_buf(),
+    })
+}
+
+/// Connect to the ready socket once the host listens on it, like the guest agent.
+async fn notify_ready(ready_socket_path: PathBuf, shutdown: CancellationToken) {
+    let deadline = Instant::now() + READY_TIMEOUT;
+    while !shutdown.is_cancelled() && Instant::now() < deadline {
+        if tokio::net::UnixStream::connect(&ready_socket_path)
+            .await
+            .is_ok()
+        {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+/// A runtime on the mock engine, homed in a fresh temp dir.
+///
+/// Replaces `options.home_dir`; the returned dir must outlive the runtime.
+#[cfg(test)]
+pub(crate) fn test_runtime(
+    options: crate::BoxliteOptions,
+    script: MockScript,
+) -> (tempfile::TempDir, crate::BoxliteRuntime) {
+    let home = tempfile::tempdir().unwrap();
+    let options = crate::BoxliteOptions {
+        home_dir: home.path().to_path_buf(),
+        ..options
+    };
+    let runtime = crate::BoxliteRuntime::with_mock_engine(options, script).unwrap();
+    (home, runtime)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argv(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn test_script_resolution() {
+        let script = MockScript::new()
+            .on(&["git"], MockExec::new().exit_code(2))
+            .on(&["git", "status"], MockExec::new().stdout("clean\n"));
+
+        assert_eq!(
+            script.resolve(&argv(&["git", "status", "-s"])).stdout,
+            b"clean\n"
+        );
+        assert_eq!(script.resolve(&argv(&["git", "log"])).exit_code, 2);
+        assert_eq!(
+            script.resolve(&argv(&["/bin/echo", "a", "b"])).stdout,
+            b"a b\n"
+        );
+        assert!(script.resolve(&argv(&["cat"])).echo_stdin);
+        assert!(script.resolve(&argv(&["nope"])).not_found);
+        assert_eq!(script.calls().len(), 5);
+        assert_eq!(script.calls()[0], argv(&["git", "status", "-s"]));
+    }
+
+    #[tokio::test]
+    async fn test_mock_box_exec() {
+        use crate::{BoxCommand, BoxOptions, BoxliteOptions};
+
+        let script = MockScript::new().on(
+            &["python3", "-c"],
+            MockExec::new().stderr("boom\n").exit_code(3),
+        );
+        let (_home, runtime) = test_runtime(BoxliteOptions::default(), script.clone());
+        let litebox = runtime
+            .create(BoxOptions::default(), Some("mock".to_string()))
+            .await
+            .unwrap();
+
+        let mut execution = litebox
+            .exec(BoxCommand::new("echo").args(["hello", "mock"]))
+            .await
+            .unwrap();
+        let stdout: Vec<String> = execution.stdout().unwrap().lines().collect().await;
+        assert_eq!(stdout.concat(), "hello mock\n");
+        assert!(execution.wait().await.unwrap().success());
+
+        let mut execution = litebox
+            .exec(BoxCommand::new("python3").args(["-c", "raise"]))
+            .await
+            .unwrap();
+        let stderr: Vec<String> = execution.stderr().unwrap().lines().collect().await;
+        assert_eq!(stderr.concat(), "boom\n");
+        assert_eq!(execution.wait().await.unwrap().exit_code, 3);
+
+        let mut execution = litebox.exec(BoxCommand::new("cat")).await.unwrap();
+        let mut stdin = execution.stdin().unwrap();
+        stdin.write_all(b"piped\xff").await.unwrap();
+        stdin.close();
+        let stdout: Vec<bytes::Bytes> = execution.stdout().unwrap().collect().await;
+        assert_eq!(stdout.concat(), b"piped\xff");
+        assert!(execution.wait().await.unwrap().success());
+
+        let mut execution = litebox
+            .exec(BoxCommand::new("sleep").arg("300"))
+            .await
+            .unwrap();
+        // A handle waiting for the exit must not hold off the kill
+        let mut waiting = execution.clone();
+        let waiter = tokio::spawn(async move { waiting.wait().await });
+        tokio::task::yield_now().await;
+        tokio::time::timeout(Duration::from_secs(5), execution.kill())
+            .await
+            .expect("kill blocked by a waiting handle")
+            .unwrap();
+        let result = waiter.await.unwrap().unwrap();
+        assert_eq!(result.exit_code, 137);
+        assert_eq!(result.signal(), Some(9));
+        assert_eq!(execution.wait().await.unwrap().exit_code, 137);
+
+        let mut execution = litebox.exec(BoxCommand::new("nope")).await.unwrap();
+        let stderr: Vec<String> = execution.stderr().unwrap().lines().collect().await;
+        assert_eq!(stderr.concat(), "nope: command not found\n");
+        let result = execution.wait().await.unwrap();
+        assert_eq!(result.exit_code, 127);
+        assert_eq!(result.reason, Some(crate::ExitReason::CommandNotFound));
+
+        assert_eq!(script.calls().len(), 5);
+        assert!(litebox.info().status.is_running());
+        litebox.stop().await.unwrap();
+        // auto_remove: stopping the box removes it
+        assert!(runtime.get("mock").await.unwrap().is_none());
+    }
+}
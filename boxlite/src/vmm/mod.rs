@@ -10,10 +10,13 @@ pub mod engine;
 pub mod factory;
 pub mod host_check;
 pub mod krun;
+#[cfg(any(test, feature = "testing"))]
+pub mod mock;
 pub mod registry;
 
 use crate::jailer::SecurityOptions;
 use crate::runtime::guest_rootfs::GuestRootfs;
+use crate::runtime::options::KernelSpec;
 pub use engine::{Vmm, VmmConfig, VmmInstance};
 pub use factory::VmmFactory;
 pub use registry::create_engine;
@@ -23,6 +26,20 @@ pub use registry::create_engine;
 pub enum VmmKind {
     Libkrun,
     Firecracker,
+    /// In-process fake guest for tests (see [`mock`]).
+    #[cfg(any(test, feature = "testing"))]
+    Mock,
+}
+
+impl VmmKind {
+    /// Whether boxes of this kind run in a separate (shim) process.
+    pub fn has_process(&self) -> bool {
+        match self {
+            VmmKind::Libkrun | VmmKind::Firecracker => true,
+            #[cfg(any(test, feature = "testing"))]
+            VmmKind::Mock => false,
+        }
+    }
 }
 
 impl FromStr for VmmKind {
@@ -32,6 +49,8 @@ impl FromStr for VmmKind {
         match s.to_lowercase().as_str() {
             "libkrun" => Ok(VmmKind::Libkrun),
             "firecracker" => Ok(VmmKind::Firecracker),
+            #[cfg(any(test, feature = "testing"))]
+            "mock" => Ok(VmmKind::Mock),
             _ => Err(BoxliteError::Engine(format!(
                 "Unknown engine type: '{}'. Supported: libkrun, firecracker",
                 s
@@ -150,6 +169,9 @@ pub struct InstanceSpec {
     pub security: SecurityOptions,
     pub cpus: Option<u8>,
     pub memory_mib: Option<u32>,
+    /// Custom guest kernel (None = engine's bundled kernel)
+    #[serde(default)]
+    pub kernel: Option<KernelSpec>,
     /// Filesystem shares from host to guest
     pub fs_shares: FsShares,
     /// Block device attachments via virtio-blk
@@ -172,6 +194,10 @@ pub struct InstanceSpec {
     pub network_backend_endpoint: Option<crate::net::NetworkBackendEndpoint>,
     /// Home directory for boxlite runtime (~/.boxlite or BOXLITE_HOME)
     pub home_dir: PathBuf,
+    /// Directory of this box (`{state_dir}/boxes/{box_id}`)
+    pub box_dir: PathBuf,
+    /// Image cache directory the box's rootfs disks live in
+    pub images_dir: PathBuf,
     /// Optional file path to redirect console output (kernel/init messages)
     pub console_output: Option<PathBuf>,
     /// Whether the box should continue running when the parent process exits.
@@ -6,10 +6,14 @@
 //! Uses convention-based paths following Kata pattern:
 //! - Host: Only tracks volume_name, doesn't know guest paths
 //! - Guest: Constructs paths from `/run/boxlite/shared/containers/{container_id}/volumes/{volume_name}`
+//!
+//! virtiofs can only share directories, so a single-file volume shares the
+//! file's parent directory and the container mount selects the file by name.
 
 use std::path::PathBuf;
 
 use super::guest_volume::GuestVolumeManager;
+use crate::runtime::options::VolumeIdShift;
 
 /// Container bind mount entry.
 ///
@@ -23,6 +27,21 @@ pub struct ContainerMount {
     pub destination: String,
     /// Read-only mount
     pub read_only: bool,
+    /// File within the volume to mount (single-file volumes), `None` for the
+    /// whole volume.
+    pub sub_path: Option<String>,
+    /// Chown the source to the container user before start
+    pub chown: bool,
+}
+
+/// Per-volume mount options.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VolumeMountOptions {
+    pub read_only: bool,
+    /// Chown the volume to the container user when the container starts
+    pub chown: bool,
+    /// Swap container root with these host ids on the virtiofs mount
+    pub id_shift: Option<VolumeIdShift>,
 }
 
 /// Manages container-level volume configuration.
@@ -58,9 +77,10 @@ impl<'a> ContainerVolumeManager<'a> {
     /// * `container_id` - Container ID for path construction
     /// * `volume_name` - Volume identifier (e.g., "data", "config")
     /// * `tag` - Virtiofs tag name (e.g., "uservol0")
-    /// * `host_path` - Path on host to share
+    /// * `host_path` - Path on host to share (a directory, or a file whose
+    ///   parent directory is shared)
     /// * `container_path` - Mount point in container (user-specified)
-    /// * `read_only` - Whether the mount is read-only
+    /// * `options` - Read-only and ownership options
     pub fn add_volume(
         &mut self,
         container_id: &str,
@@ -68,23 +88,30 @@ impl<'a> ContainerVolumeManager<'a> {
         tag: &str,
         host_path: PathBuf,
         container_path: &str,
-        read_only: bool,
+        options: VolumeMountOptions,
     ) {
+        let (share_path, sub_path) = split_file_volume(host_path);
+
         // Add virtiofs share to guest with container_id
         // Guest will mount at convention path: /run/boxlite/shared/containers/{container_id}/volumes/{tag}
         self.guest.add_fs_share(
             tag,
-            host_path,
+            share_path,
             None,
-            read_only,
+            options.read_only,
             Some(container_id.to_string()),
         );
+        if let Some(shift) = options.id_shift {
+            self.guest.set_id_shift(tag, shift);
+        }
 
         // Record container bind mount - guest constructs source path from convention
         self.container_mounts.push(ContainerMount {
             volume_name: volume_name.to_string(),
             destination: container_path.to_string(),
-            read_only,
+            read_only: options.read_only,
+            sub_path,
+            chown: options.chown,
         });
     }
 
@@ -97,6 +124,8 @@ impl<'a> ContainerVolumeManager<'a> {
             volume_name: volume_name.to_string(),
             destination: container_path.to_string(),
             read_only,
+            sub_path: None,
+            chown: false,
         });
     }
 
@@ -105,3 +134,39 @@ impl<'a> ContainerVolumeManager<'a> {
         self.container_mounts.clone()
     }
 }
+
+/// Split a volume host path into the directory to share and the file to
+/// mount from it. Directories are shared as-is.
+fn split_file_volume(host_path: PathBuf) -> (PathBuf, Option<String>) {
+    if host_path.is_dir() {
+        return (host_path, None);
+    }
+    match (host_path.parent(), host_path.file_name()) {
+        (Some(parent), Some(name)) => (
+            parent.to_path_buf(),
+            Some(name.to_string_lossy().into_owned()),
+        ),
+        _ => (host_path, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_file_volume() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let file = tmp.path().join("config.yaml");
+        std::fs::write(&file, "a: 1").unwrap();
+
+        assert_eq!(
+            split_file_volume(tmp.path().to_path_buf()),
+            (tmp.path().to_path_buf(), None)
+        );
+        assert_eq!(
+            split_file_volume(file),
+            (tmp.path().to_path_buf(), Some("config.yaml".to_string()))
+        );
+    }
+}
@@ -10,6 +10,7 @@ use std::path::{Path, PathBuf};
 
 use crate::disk::DiskFormat;
 use crate::portal::interfaces::VolumeConfig;
+use crate::runtime::options::VolumeIdShift;
 use crate::vmm::{BlockDevice, BlockDevices, FsShares};
 
 /// Tracked virtiofs share entry.
@@ -23,6 +24,8 @@ pub struct FsShareEntry {
     pub read_only: bool,
     /// Optional container_id for convention-based paths.
     pub container_id: Option<String>,
+    /// Root id swap applied by the guest when mounting.
+    pub id_shift: Option<VolumeIdShift>,
 }
 
 /// Tracked block device entry.
@@ -91,9 +94,18 @@ impl GuestVolumeManager {
             guest_path: guest_path.map(String::from),
             read_only,
             container_id,
+            id_shift: None,
         });
     }
 
+    /// Have the guest mount share `tag` with container root swapped for
+    /// the given host ids.
+    pub fn set_id_shift(&mut self, tag: &str, shift: VolumeIdShift) {
+        if let Some(entry) = self.fs_shares.iter_mut().find(|e| e.tag == tag) {
+            entry.id_shift = Some(shift);
+        }
+    }
+
     /// Add a block device.
     ///
     /// Returns the device path in guest (e.g., "/dev/vda").
@@ -160,10 +172,12 @@ impl GuestVolumeManager {
         let mut block_devices = BlockDevices::new();
         for entry in &self.block_devices {
             // Map disk format to VMM block format:
-            // - Ext4 filesystem → Raw block image
+            // - Filesystem images (ext4, erofs, squashfs) → Raw block image
             // - Qcow2 → Qcow2 (COW format)
             let vmm_format = match entry.format {
-                DiskFormat::Ext4 => crate::vmm::DiskFormat::Raw,
+                DiskFormat::Ext4 | DiskFormat::Erofs | DiskFormat::Squashfs => {
+                    crate::vmm::DiskFormat::Raw
+                }
                 DiskFormat::Qcow2 => crate::vmm::DiskFormat::Qcow2,
             };
             block_devices.add(BlockDevice {
@@ -187,12 +201,15 @@ impl GuestVolumeManager {
         for entry in &self.fs_shares {
             // Empty mount_point = guest determines from tag
             let mount_point = entry.guest_path.as_deref().unwrap_or("");
-            volumes.push(VolumeConfig::virtiofs(
-                &entry.tag,
-                mount_point,
-                entry.read_only,
-                entry.container_id.clone(),
-            ));
+            volumes.push(
+                VolumeConfig::virtiofs(
+                    &entry.tag,
+                    mount_point,
+                    entry.read_only,
+                    entry.container_id.clone(),
+                )
+                .with_id_shift(entry.id_shift),
+            );
         }
 
         for entry in &self.block_devices {
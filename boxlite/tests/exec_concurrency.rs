@@ -0,0 +1,146 @@
+//! Tests for many executions running at once in one box.
+//!
+//! Hammers a single box with hundreds of parallel execs, with and without
+//! `BoxOptions::exec_limit`, and checks every command gets its own output
+//! and exit code back.
+
+use boxlite::BoxCommand;
+use boxlite::BoxliteRuntime;
+use boxlite::runtime::options::{BoxOptions, BoxliteOptions, ExecLimit, RootfsSpec};
+use boxlite_shared::BoxliteError;
+use futures::StreamExt;
+use tempfile::TempDir;
+
+// ============================================================================
+// TEST FIXTURES
+// ============================================================================
+
+/// Test context with isolated runtime and automatic cleanup.
+struct TestContext {
+    runtime: BoxliteRuntime,
+    _temp_dir: TempDir,
+}
+
+impl TestContext {
+    fn new() -> Self {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let options = BoxliteOptions {
+            home_dir: temp_dir.path().to_path_buf(),
+            image_registries: vec![],
+            ..Default::default()
+        };
+        let runtime = BoxliteRuntime::new(options).expect("Failed to create runtime");
+        Self {
+            runtime,
+            _temp_dir: temp_dir,
+        }
+    }
+}
+
+fn box_options(exec_limit: Option<ExecLimit>) -> BoxOptions {
+    BoxOptions {
+        rootfs: RootfsSpec::Image("alpine:latest".into()),
+        exec_limit,
+        ..Default::default()
+    }
+}
+
+/// Run `echo i; exit i % 7` for every `i` in parallel and check each result.
+async fn hammer(handle: &boxlite::LiteBox, count: usize) {
+    let results = futures::future::join_all((0..count).map(|i| async move {
+        let script = format!("echo {}; exit {}", i, i % 7);
+        let mut execution = handle
+            .exec(BoxCommand::new("sh").arg("-c").arg(script))
+            .await
+            .unwrap_or_else(|e| panic!("exec {} failed to start: {}", i, e));
+        let stdout: Vec<String> = execution.stdout().unwrap().lines().collect().await;
+        let result = execution.wait().await.unwrap();
+        (stdout.concat(), result.exit_code)
+    }))
+    .await;
+
+    for (i, (stdout, exit_code)) in results.into_iter().enumerate() {
+        assert_eq!(stdout, format!("{}\n", i), "output of exec {}", i);
+        assert_eq!(exit_code, (i % 7) as i32, "exit code of exec {}", i);
+    }
+}
+
+// ============================================================================
+// CONCURRENCY TESTS
+// ============================================================================
+
+/// Hundreds of parallel execs without a limit all complete correctly.
+#[tokio::test]
+async fn test_parallel_execs_without_limit() {
+    let ctx = TestContext::new();
+    let handle = ctx.runtime.create(box_options(None), None).await.unwrap();
+    handle.start().await.unwrap();
+
+    hammer(&handle, 300).await;
+
+    handle.stop().await.unwrap();
+}
+
+/// With a limit, excess execs queue for a slot and all complete correctly.
+#[tokio::test]
+async fn test_parallel_execs_with_queueing_limit() {
+    let ctx = TestContext::new();
+    let limit = ExecLimit {
+        max_in_flight: 16,
+        queue_timeout_secs: None,
+    };
+    let handle = ctx
+        .runtime
+        .create(box_options(Some(limit)), None)
+        .await
+        .unwrap();
+    handle.start().await.unwrap();
+
+    hammer(&handle, 500).await;
+
+    handle.stop().await.unwrap();
+}
+
+/// With a rejecting limit, execs beyond it fail with a typed error and the
+/// box keeps serving the ones that fit.
+#[tokio::test]
+async fn test_parallel_execs_with_rejecting_limit() {
+    let ctx = TestContext::new();
+    let limit = ExecLimit {
+        max_in_flight: 4,
+        queue_timeout_secs: Some(0),
+    };
+    let handle = ctx
+        .runtime
+        .create(box_options(Some(limit)), None)
+        .await
+        .unwrap();
+    handle.start().await.unwrap();
+
+    let mut sleepers = Vec::new();
+    for _ in 0..4 {
+        sleepers.push(
+            handle
+                .exec(BoxCommand::new("sleep").arg("3600"))
+                .await
+                .unwrap(),
+        );
+    }
+    let rejected =
+        futures::future::join_all((0..100).map(|_| handle.exec(BoxCommand::new("true")))).await;
+    for result in rejected {
+        match result {
+            Err(BoxliteError::ResourceExhausted(_)) => {}
+            Err(e) => panic!("unexpected error: {}", e),
+            Ok(_) => panic!("exec started beyond the limit"),
+        }
+    }
+
+    for sleeper in &mut sleepers {
+        sleeper.kill().await.unwrap();
+        sleeper.wait().await.unwrap();
+    }
+    hammer(&handle, 50).await;
+
+    handle.stop().await.unwrap();
+}
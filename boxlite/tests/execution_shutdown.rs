@@ -22,12 +22,11 @@ struct TestContext {
 
 impl TestContext {
     fn new() -> Self {
-        // Use /tmp directly to avoid macOS's long temp paths that exceed SUN_LEN
-        // for Unix socket paths (limited to ~104 chars)
-        let temp_dir = TempDir::new_in("/tmp").expect("Failed to create temp dir");
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
         let options = BoxliteOptions {
             home_dir: temp_dir.path().to_path_buf(),
             image_registries: vec![],
+            ..Default::default()
         };
         let runtime = BoxliteRuntime::new(options).expect("Failed to create runtime");
         Self {
@@ -214,7 +213,7 @@ async fn test_stdout_stream_on_box_stop() {
         .await
         .unwrap();
 
-    let mut stdout = execution.stdout().unwrap();
+    let mut stdout = execution.stdout().unwrap().lines();
 
     // Read a few lines in background
     let read_handle = tokio::spawn(async move {
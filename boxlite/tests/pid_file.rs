@@ -35,6 +35,7 @@ impl TestContext {
         let options = BoxliteOptions {
             home_dir: home_dir.clone(),
             image_registries: vec![],
+            ..Default::default()
         };
         let runtime = BoxliteRuntime::new(options).expect("Failed to create runtime");
         Self {
@@ -331,6 +332,7 @@ async fn detached_box_survives_runtime_drop() {
         let runtime = BoxliteRuntime::new(BoxliteOptions {
             home_dir: home_dir.clone(),
             image_registries: vec![],
+            ..Default::default()
         })
         .unwrap();
 
@@ -370,6 +372,7 @@ async fn detached_box_survives_runtime_drop() {
     let runtime = BoxliteRuntime::new(BoxliteOptions {
         home_dir,
         image_registries: vec![],
+        ..Default::default()
     })
     .unwrap();
     runtime.remove(&box_id, true).await.unwrap();
@@ -386,6 +389,7 @@ async fn detached_box_recoverable_after_restart() {
         let runtime = BoxliteRuntime::new(BoxliteOptions {
             home_dir: home_dir.clone(),
             image_registries: vec![],
+            ..Default::default()
         })
         .unwrap();
 
@@ -411,6 +415,7 @@ async fn detached_box_recoverable_after_restart() {
         let runtime = BoxliteRuntime::new(BoxliteOptions {
             home_dir,
             image_registries: vec![],
+            ..Default::default()
         })
         .unwrap();
 
@@ -504,6 +509,7 @@ async fn recovery_with_live_process() {
         let runtime = BoxliteRuntime::new(BoxliteOptions {
             home_dir: home_dir.clone(),
             image_registries: vec![],
+            ..Default::default()
         })
         .unwrap();
 
@@ -532,6 +538,7 @@ async fn recovery_with_live_process() {
         let runtime = BoxliteRuntime::new(BoxliteOptions {
             home_dir,
             image_registries: vec![],
+            ..Default::default()
         })
         .unwrap();
 
@@ -561,6 +568,7 @@ async fn recovery_with_dead_process() {
         let runtime = BoxliteRuntime::new(BoxliteOptions {
             home_dir: home_dir.clone(),
             image_registries: vec![],
+            ..Default::default()
         })
         .unwrap();
 
@@ -597,6 +605,7 @@ async fn recovery_with_dead_process() {
         let runtime = BoxliteRuntime::new(BoxliteOptions {
             home_dir: home_dir.clone(),
             image_registries: vec![],
+            ..Default::default()
         })
         .unwrap();
 
@@ -636,6 +645,7 @@ async fn recovery_with_missing_pid_file() {
         let runtime = BoxliteRuntime::new(BoxliteOptions {
             home_dir: home_dir.clone(),
             image_registries: vec![],
+            ..Default::default()
         })
         .unwrap();
 
@@ -665,6 +675,7 @@ async fn recovery_with_missing_pid_file() {
         let runtime = BoxliteRuntime::new(BoxliteOptions {
             home_dir,
             image_registries: vec![],
+            ..Default::default()
         })
         .unwrap();
 
@@ -696,6 +707,7 @@ async fn recovery_with_corrupted_pid_file() {
         let runtime = BoxliteRuntime::new(BoxliteOptions {
             home_dir: home_dir.clone(),
             image_registries: vec![],
+            ..Default::default()
         })
         .unwrap();
 
@@ -725,6 +737,7 @@ async fn recovery_with_corrupted_pid_file() {
         let runtime = BoxliteRuntime::new(BoxliteOptions {
             home_dir: home_dir.clone(),
             image_registries: vec![],
+            ..Default::default()
         })
         .unwrap();
 
@@ -760,6 +773,7 @@ async fn recovery_preserves_stopped_boxes() {
         let runtime = BoxliteRuntime::new(BoxliteOptions {
             home_dir: home_dir.clone(),
             image_registries: vec![],
+            ..Default::default()
         })
         .unwrap();
 
@@ -791,6 +805,7 @@ async fn recovery_preserves_stopped_boxes() {
         let runtime = BoxliteRuntime::new(BoxliteOptions {
             home_dir,
             image_registries: vec![],
+            ..Default::default()
         })
         .unwrap();
 
@@ -0,0 +1,62 @@
+//! Boot phase timing.
+//!
+//! The kernel's boot clock starts at zero when the guest kernel starts, so
+//! reading it as the agent starts tells how long the kernel took to hand
+//! over, and reading it again once the server is up tells how long the agent
+//! took to start. Guest.Init adds its own steps and reports all of them to
+//! the host.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use boxlite_shared::GuestBootPhases;
+use nix::time::{clock_gettime, ClockId};
+
+/// Boot clock when the agent started (milliseconds).
+static AGENT_START_MS: AtomicU64 = AtomicU64::new(0);
+/// Boot clock when the agent started serving (milliseconds).
+static AGENT_READY_MS: AtomicU64 = AtomicU64::new(0);
+
+/// Record that the agent process started.
+pub fn mark_agent_start() {
+    AGENT_START_MS.store(boot_clock_ms(), Ordering::Relaxed);
+}
+
+/// Record that the agent is serving requests.
+pub fn mark_agent_ready() {
+    AGENT_READY_MS.store(boot_clock_ms(), Ordering::Relaxed);
+}
+
+/// Boot phases so far, plus the Guest.Init steps.
+pub fn phases(mount: Duration, network: Duration) -> GuestBootPhases {
+    let start = AGENT_START_MS.load(Ordering::Relaxed);
+    let ready = AGENT_READY_MS.load(Ordering::Relaxed);
+    GuestBootPhases {
+        kernel_ms: start,
+        agent_startup_ms: ready.saturating_sub(start),
+        mount_ms: mount.as_millis() as u64,
+        network_ms: network.as_millis() as u64,
+    }
+}
+
+/// Milliseconds since the kernel started, including any time suspended.
+fn boot_clock_ms() -> u64 {
+    clock_gettime(ClockId::CLOCK_BOOTTIME)
+        .map(|t| Duration::from(t).as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phases() {
+        mark_agent_start();
+        mark_agent_ready();
+        let phases = phases(Duration::from_millis(12), Duration::ZERO);
+        assert!(phases.kernel_ms > 0);
+        assert_eq!(phases.mount_ms, 12);
+        assert_eq!(phases.network_ms, 0);
+    }
+}
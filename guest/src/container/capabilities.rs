@@ -1,10 +1,10 @@
 //! Linux capabilities
 //!
-//! Single source of truth for all 41 Linux capabilities.
-//! Used by:
-//! - OCI spec builder (process.capabilities)
-//! - Tenant process spawning (exec capabilities)
+//! Single source of truth for all 41 Linux capabilities, used by the OCI
+//! spec builder (process.capabilities) when the host sends no explicit set.
+//! Exec processes inherit the container's set from the spec.
 
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
 use oci_spec::runtime::Capability;
 use std::collections::HashSet;
 
@@ -75,62 +75,52 @@ pub fn all_capabilities() -> HashSet<Capability> {
     .collect()
 }
 
-/// Convert capabilities to string names for libcontainer API
+/// Parse capability names (`CAP_NAME` form) sent by the host
 ///
-/// Returns capability names in "CAP_NAME" format (e.g., "CAP_CHOWN").
-pub fn capability_names() -> Vec<String> {
-    vec![
-        // File operations (CAP 0-4)
-        "CAP_CHOWN".to_string(),
-        "CAP_DAC_OVERRIDE".to_string(),
-        "CAP_DAC_READ_SEARCH".to_string(),
-        "CAP_FOWNER".to_string(),
-        "CAP_FSETID".to_string(),
-        // Process capabilities (CAP 5-9)
-        "CAP_KILL".to_string(),
-        "CAP_SETGID".to_string(),
-        "CAP_SETUID".to_string(),
-        "CAP_SETPCAP".to_string(),
-        "CAP_LINUX_IMMUTABLE".to_string(),
-        // Network capabilities (CAP 10-13)
-        "CAP_NET_BIND_SERVICE".to_string(),
-        "CAP_NET_BROADCAST".to_string(),
-        "CAP_NET_ADMIN".to_string(),
-        "CAP_NET_RAW".to_string(),
-        // IPC capabilities (CAP 14-15)
-        "CAP_IPC_LOCK".to_string(),
-        "CAP_IPC_OWNER".to_string(),
-        // System operations (CAP 16-26)
-        "CAP_SYS_MODULE".to_string(),
-        "CAP_SYS_RAWIO".to_string(),
-        "CAP_SYS_CHROOT".to_string(),
-        "CAP_SYS_PTRACE".to_string(),
-        "CAP_SYS_PACCT".to_string(),
-        "CAP_SYS_ADMIN".to_string(),
-        "CAP_SYS_BOOT".to_string(),
-        "CAP_SYS_NICE".to_string(),
-        "CAP_SYS_RESOURCE".to_string(),
-        "CAP_SYS_TIME".to_string(),
-        "CAP_SYS_TTY_CONFIG".to_string(),
-        // Device operations (CAP 27)
-        "CAP_MKNOD".to_string(),
-        // File leases (CAP 28)
-        "CAP_LEASE".to_string(),
-        // Audit capabilities (CAP 29-30)
-        "CAP_AUDIT_WRITE".to_string(),
-        "CAP_AUDIT_CONTROL".to_string(),
-        // Filesystem capabilities (CAP 31)
-        "CAP_SETFCAP".to_string(),
-        // MAC (CAP 32-33)
-        "CAP_MAC_OVERRIDE".to_string(),
-        "CAP_MAC_ADMIN".to_string(),
-        // Modern capabilities (CAP 34-40)
-        "CAP_SYSLOG".to_string(),
-        "CAP_WAKE_ALARM".to_string(),
-        "CAP_BLOCK_SUSPEND".to_string(),
-        "CAP_AUDIT_READ".to_string(),
-        "CAP_PERFMON".to_string(),
-        "CAP_BPF".to_string(),
-        "CAP_CHECKPOINT_RESTORE".to_string(),
-    ]
+/// Exec processes inherit the resulting set from the container's OCI spec.
+pub fn parse_capabilities(names: &[String]) -> BoxliteResult<HashSet<Capability>> {
+    names
+        .iter()
+        .map(|name| {
+            serde_json::from_value(serde_json::Value::String(name.clone())).map_err(|_| {
+                BoxliteError::InvalidArgument(format!("Unknown capability '{}'", name))
+            })
+        })
+        .collect()
+}
+
+/// Capability names in `CAP_NAME` form, as libcontainer's tenant builder
+/// takes them
+pub fn capability_names(caps: &HashSet<Capability>) -> Vec<String> {
+    caps.iter()
+        .filter_map(|cap| match serde_json::to_value(cap) {
+            Ok(serde_json::Value::String(name)) => Some(name),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_capabilities() {
+        let caps =
+            parse_capabilities(&["CAP_CHOWN".to_string(), "CAP_NET_ADMIN".to_string()]).unwrap();
+        assert_eq!(
+            caps,
+            HashSet::from([Capability::Chown, Capability::NetAdmin])
+        );
+        assert!(parse_capabilities(&["CAP_FLY".to_string()]).is_err());
+        assert!(parse_capabilities(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_capability_names_round_trip() {
+        let names = capability_names(&all_capabilities());
+        assert_eq!(names.len(), all_capabilities().len());
+        assert!(names.contains(&"CAP_SYS_ADMIN".to_string()));
+        assert_eq!(parse_capabilities(&names).unwrap(), all_capabilities());
+    }
 }
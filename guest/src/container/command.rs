@@ -3,10 +3,15 @@
 //! Provides a builder pattern for spawning processes inside containers,
 //! following the `std::process::Command` pattern.
 
-use super::capabilities::capability_names;
+use super::capabilities::{all_capabilities, capability_names};
 use crate::service::exec::exec_handle::{ExecHandle, PtyConfig};
+use crate::service::exec::executor::SpawnError;
+use boxlite_shared::constants::exec_error;
 use boxlite_shared::errors::{BoxliteError, BoxliteResult};
 use libcontainer::container::builder::ContainerBuilder;
+use libcontainer::error::LibcontainerError;
+use libcontainer::process::channel::ChannelError;
+use libcontainer::process::container_main_process::ProcessError;
 use libcontainer::syscall::syscall::SyscallType;
 use nix::unistd::Pid;
 use std::collections::HashMap;
@@ -60,6 +65,9 @@ pub struct ContainerCommand {
 
     /// PTY configuration (set via with_pty())
     pty_config: Option<PtyConfig>,
+
+    /// Grant every capability instead of the container's set
+    privileged: bool,
 }
 
 impl ContainerCommand {
@@ -81,6 +89,7 @@ impl ContainerCommand {
             cwd: None,
             console_socket: None,
             pty_config: None,
+            privileged: false,
             id,
             state_root,
         }
@@ -207,6 +216,18 @@ impl ContainerCommand {
         self
     }
 
+    /// Run as `(uid, gid)` instead of the container's user
+    pub fn user(mut self, user: (u32, u32)) -> Self {
+        self.user = user;
+        self
+    }
+
+    /// Grant the process every capability, not just the container's
+    pub fn privileged(mut self, enable: bool) -> Self {
+        self.privileged = enable;
+        self
+    }
+
     /// Spawn the process
     ///
     /// Creates a tenant process in the container with stdin/stdout/stderr pipes.
@@ -237,7 +258,7 @@ impl ContainerCommand {
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn spawn(self) -> BoxliteResult<ExecHandle> {
+    pub async fn spawn(self) -> Result<ExecHandle, SpawnError> {
         if let Some(pty_config) = self.pty_config.clone() {
             self.spawn_with_pty(pty_config).await
         } else {
@@ -246,7 +267,7 @@ impl ContainerCommand {
     }
 
     /// Spawn process with pipes (standard mode).
-    async fn spawn_with_pipes(self) -> BoxliteResult<ExecHandle> {
+    async fn spawn_with_pipes(self) -> Result<ExecHandle, SpawnError> {
         use nix::unistd::pipe;
 
         // Create pipes for I/O
@@ -273,7 +294,7 @@ impl ContainerCommand {
     }
 
     /// Spawn process with PTY (interactive mode).
-    async fn spawn_with_pty(mut self, config: PtyConfig) -> BoxliteResult<ExecHandle> {
+    async fn spawn_with_pty(mut self, config: PtyConfig) -> Result<ExecHandle, SpawnError> {
         use super::console_socket::ConsoleSocket;
 
         // Setup console socket
@@ -294,14 +315,14 @@ impl ContainerCommand {
         let pty_master = socket.receive_pty_master()?;
 
         // Create child with PTY
-        create_pty_child(pid, pty_master, config)
+        Ok(create_pty_child(pid, pty_master, config)?)
     }
 
     /// Build and spawn process using libcontainer.
     async fn build_and_spawn(
         &self,
         pipes: Option<(OwnedFd, OwnedFd, OwnedFd)>,
-    ) -> BoxliteResult<Pid> {
+    ) -> Result<Pid, SpawnError> {
         // Build command arguments
         let program = self.program.clone().unwrap_or("".into());
         let mut container_args = vec![program.clone()];
@@ -355,9 +376,17 @@ impl ContainerCommand {
 
         let (uid, gid) = self.user;
 
+        // No extra capabilities unless privileged: exec processes get the
+        // container's set
+        let capabilities = if self.privileged {
+            capability_names(&all_capabilities())
+        } else {
+            Vec::new()
+        };
+
         let pid = builder
             .as_tenant()
-            .with_capabilities(capability_names())
+            .with_capabilities(capabilities)
             .with_no_new_privs(false)
             .with_detach(false)
             .with_cwd(self.cwd.clone().or(Some("/".parse().unwrap())))
@@ -388,10 +417,13 @@ impl ContainerCommand {
                     );
                 }
 
-                BoxliteError::Internal(format!(
-                    "Failed to spawn '{}' with args {:?}: {}",
-                    program, container_args, e
-                ))
+                SpawnError::new(
+                    spawn_failure_reason(&e),
+                    format!(
+                        "Failed to spawn '{}' with args {:?}: {}",
+                        program, container_args, e
+                    ),
+                )
             })?;
 
         tracing::debug!(
@@ -404,6 +436,27 @@ impl ContainerCommand {
     }
 }
 
+/// Classify a failed tenant build.
+///
+/// The container's init process validates the program before exec and
+/// reports failures across a process boundary, so only its message is
+/// left. Only libcontainer's executable validation errors name the program;
+/// anything else (a missing working directory, for one) is a spawn failure.
+fn spawn_failure_reason(err: &LibcontainerError) -> &'static str {
+    let LibcontainerError::MainProcess(ProcessError::Channel(ChannelError::ExecError(message))) =
+        err
+    else {
+        return exec_error::SPAWN_FAILED;
+    };
+    if message.contains("not found in $PATH") {
+        exec_error::COMMAND_NOT_FOUND
+    } else if message.contains("does not have correct permissions") {
+        exec_error::NOT_EXECUTABLE
+    } else {
+        exec_error::SPAWN_FAILED
+    }
+}
+
 /// Create ExecHandle with PTY.
 ///
 /// Sets terminal window size, reconciles PTY master FD as stdin/stdout,
@@ -481,3 +534,38 @@ fn pty_master_to_file(pty_master: OwnedFd) -> std::fs::File {
     std::mem::forget(pty_master); // Transfer ownership, don't close
     unsafe { std::fs::File::from_raw_fd(fd) }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn init_failure(message: &str) -> LibcontainerError {
+        LibcontainerError::MainProcess(ProcessError::Channel(ChannelError::ExecError(format!(
+            "error in executing process : {}",
+            message
+        ))))
+    }
+
+    #[test]
+    fn test_spawn_failure_reason() {
+        assert_eq!(
+            spawn_failure_reason(&init_failure("executable 'nope' not found in $PATH")),
+            exec_error::COMMAND_NOT_FOUND
+        );
+        assert_eq!(
+            spawn_failure_reason(&init_failure(
+                "executable '/etc' at path '\"/etc\"' does not have correct permissions"
+            )),
+            exec_error::NOT_EXECUTABLE
+        );
+        // A failed chdir is not a missing program
+        assert_eq!(
+            spawn_failure_reason(&init_failure("ENOENT: No such file or directory")),
+            exec_error::SPAWN_FAILED
+        );
+        assert_eq!(
+            spawn_failure_reason(&LibcontainerError::IncorrectStatus),
+            exec_error::SPAWN_FAILED
+        );
+    }
+}
@@ -0,0 +1,86 @@
+//! FUSE device setup
+//!
+//! Makes `/dev/fuse` available in the guest VM so a container created with
+//! `allow_fuse` can mount FUSE filesystems (sshfs, AppImages, rclone).
+
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+use nix::sys::stat::{makedev, mknod, Mode, SFlag};
+use std::path::Path;
+use std::process::Command;
+
+/// FUSE character device path
+pub(super) const FUSE_DEVICE: &str = "/dev/fuse";
+/// FUSE device major number (misc devices)
+pub(super) const FUSE_MAJOR: u64 = 10;
+/// FUSE device minor number
+pub(super) const FUSE_MINOR: u64 = 229;
+
+/// Ensure the guest kernel supports FUSE and `/dev/fuse` exists.
+///
+/// Tries to load the `fuse` module when the filesystem is not registered
+/// (built-in kernels need no module), then creates the device node if
+/// devtmpfs did not.
+pub fn ensure_fuse_device() -> BoxliteResult<()> {
+    if !kernel_supports_fuse() {
+        // Best-effort: guest kernels built with CONFIG_FUSE_FS=y have no module
+        match Command::new("modprobe").arg("fuse").status() {
+            Ok(status) if !status.success() => {
+                tracing::debug!(?status, "modprobe fuse failed");
+            }
+            Err(e) => tracing::debug!("modprobe unavailable: {}", e),
+            Ok(_) => {}
+        }
+        if !kernel_supports_fuse() {
+            return Err(BoxliteError::Unsupported(
+                "FUSE requested but the guest kernel has no FUSE support (CONFIG_FUSE_FS)"
+                    .to_string(),
+            ));
+        }
+    }
+
+    if Path::new(FUSE_DEVICE).exists() {
+        tracing::debug!("{} already exists", FUSE_DEVICE);
+        return Ok(());
+    }
+
+    mknod(
+        FUSE_DEVICE,
+        SFlag::S_IFCHR,
+        Mode::from_bits_truncate(0o666),
+        makedev(FUSE_MAJOR, FUSE_MINOR),
+    )
+    .map_err(|e| BoxliteError::Internal(format!("Failed to create {}: {}", FUSE_DEVICE, e)))?;
+
+    tracing::info!("Created {}", FUSE_DEVICE);
+    Ok(())
+}
+
+/// Whether the running kernel has the `fuse` filesystem registered.
+fn kernel_supports_fuse() -> bool {
+    std::fs::read_to_string("/proc/filesystems")
+        .map(|content| has_fuse_filesystem(&content))
+        .unwrap_or(false)
+}
+
+/// Check /proc/filesystems content for the `fuse` entry.
+///
+/// Lines look like `nodev\tfuse`; `fuseblk` and `fusectl` don't count.
+fn has_fuse_filesystem(filesystems: &str) -> bool {
+    filesystems
+        .lines()
+        .any(|line| line.split_whitespace().last() == Some("fuse"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_has_fuse_filesystem() {
+        assert!(has_fuse_filesystem("nodev\tsysfs\nnodev\tfuse\n\text4\n"));
+        assert!(!has_fuse_filesystem(
+            "nodev\tsysfs\n\tfuseblk\nnodev\tfusectl\n"
+        ));
+        assert!(!has_fuse_filesystem(""));
+    }
+}
@@ -3,8 +3,9 @@
 //! Provides container creation, startup, and status checking using libcontainer.
 //! Follows the OCI Runtime Specification.
 
+use super::capabilities::{all_capabilities, parse_capabilities};
 use super::command::ContainerCommand;
-use super::spec::UserMount;
+use super::spec::{TmpfsMount, UserMount};
 use super::stdio::ContainerStdio;
 use super::{kill, spec, start};
 use crate::layout::GuestLayout;
@@ -45,6 +46,8 @@ pub struct Container {
     id: String,
     state_root: PathBuf,
     bundle_path: PathBuf,
+    /// Root filesystem, used to resolve exec users
+    rootfs: PathBuf,
     env: HashMap<String, String>,
     /// Resolved (uid, gid) from image USER directive, propagated to exec commands.
     user: (u32, u32),
@@ -70,11 +73,17 @@ impl Container {
     /// # Arguments
     ///
     /// - `container_id`: Unique container identifier
+    /// - `hostname`: UTS hostname, also in /etc/hostname and /etc/hosts
     /// - `rootfs`: Path to container root filesystem
     /// - `entrypoint`: Command and arguments for container init process
     /// - `env`: Environment variables in "KEY=VALUE" format
     /// - `workdir`: Working directory inside container
     /// - `user_mounts`: Bind mounts from guest VM paths into container
+    /// - `tmpfs_mounts`: In-memory mounts inside the container
+    /// - `allow_fuse`: Expose /dev/fuse (caller must run `ensure_fuse_device` first)
+    /// - `capabilities`: Capability names of the container process (None = all)
+    /// - `host_loopback_ip`: Address named `host.boxlite.internal` in /etc/hosts
+    ///   (None = no entry)
     ///
     /// # Errors
     ///
@@ -82,14 +91,20 @@ impl Container {
     /// - Failed to create container directory
     /// - Failed to create or start container
     /// - Init process exited immediately
+    #[allow(clippy::too_many_arguments)]
     pub fn start(
         container_id: &str,
+        hostname: &str,
         rootfs: impl AsRef<Path>,
         entrypoint: Vec<String>,
         env: Vec<String>,
         workdir: impl AsRef<Path>,
         user: &str,
         user_mounts: Vec<UserMount>,
+        tmpfs_mounts: Vec<TmpfsMount>,
+        allow_fuse: bool,
+        capabilities: Option<&[String]>,
+        host_loopback_ip: Option<&str>,
     ) -> BoxliteResult<Self> {
         let rootfs = rootfs.as_ref();
         let workdir = workdir.as_ref();
@@ -99,6 +114,7 @@ impl Container {
 
         // Validate inputs early
         start::validate_container_inputs(rootfs, &entrypoint, workdir)?;
+        start::ensure_workdir(rootfs, workdir)?;
 
         // Parse existing env into map (KEY=VALUE)
         let mut env_map: HashMap<String, String> = HashMap::new();
@@ -120,10 +136,20 @@ impl Container {
             .ok_or_else(|| BoxliteError::Internal("Invalid rootfs path".to_string()))?;
         let (uid, gid) = spec::resolve_user(rootfs_str, user)?;
 
+        let capabilities = match capabilities {
+            Some(names) => parse_capabilities(names)?,
+            None => all_capabilities(),
+        };
+
+        for mount in user_mounts.iter().filter(|m| m.chown) {
+            crate::storage::OwnershipFixer::chown_if_needed(Path::new(&mount.source), uid, gid)?;
+        }
+
         // Create OCI bundle at /run/boxlite/containers/{cid}/
         // create_oci_bundle creates bundle_root/{cid}/, so pass containers_dir
         let bundle_path = start::create_oci_bundle(
             container_id,
+            hostname,
             rootfs,
             &entrypoint,
             &env,
@@ -132,6 +158,10 @@ impl Container {
             gid,
             &layout.containers_dir(),
             &user_mounts,
+            &tmpfs_mounts,
+            allow_fuse,
+            capabilities,
+            host_loopback_ip,
         )?;
 
         // Create stdio pipes before container creation.
@@ -146,6 +176,7 @@ impl Container {
             id: container_id.to_string(),
             state_root,
             bundle_path,
+            rootfs: rootfs.to_path_buf(),
             env: env_map,
             user: (uid, gid),
             stdio,
@@ -209,6 +240,17 @@ impl Container {
         &self.id
     }
 
+    /// PID of the container init process, in the guest's PID namespace
+    ///
+    /// Returns `None` if the container state cannot be loaded or init never
+    /// started.
+    pub fn init_pid(&self) -> Option<i32> {
+        LibContainer::load(self.container_state_path())
+            .ok()?
+            .pid()
+            .map(|pid| pid.as_raw())
+    }
+
     /// Create a command builder for executing processes in this container
     ///
     /// Returns a Command builder. Use `.cmd()` to set the program to execute.
@@ -237,6 +279,16 @@ impl Container {
         )
     }
 
+    /// Resolve an exec user (`uid`, `uid:gid`, `name` or `name:group`)
+    /// against the container's `/etc/passwd` and `/etc/group`.
+    pub fn resolve_user(&self, user: &str) -> BoxliteResult<(u32, u32)> {
+        let rootfs = self
+            .rootfs
+            .to_str()
+            .ok_or_else(|| BoxliteError::Internal("Invalid rootfs path".to_string()))?;
+        spec::resolve_user(rootfs, user)
+    }
+
     /// Drain init process stdout and stderr.
     ///
     /// Reads all available data from the init process pipes using non-blocking I/O.
@@ -6,6 +6,7 @@
 //!
 //! - [`Container`]: OCI container lifecycle (create, start, check status)
 //! - [`ContainerCommand`]: Builder for executing commands inside container
+//! - [`list_processes`]: Process table of a running container
 //! - [`crate::service::exec::exec_handle::ExecHandle`]: Handle to a running process
 //!
 //! # Example
@@ -68,17 +69,33 @@ mod command;
 #[cfg(target_os = "linux")]
 mod console_socket;
 #[cfg(target_os = "linux")]
+mod fuse;
+#[cfg(target_os = "linux")]
 mod kill;
 #[cfg(target_os = "linux")]
 mod lifecycle;
 #[cfg(target_os = "linux")]
+mod pause;
+#[cfg(target_os = "linux")]
+mod processes;
+#[cfg(target_os = "linux")]
+mod sockets;
+#[cfg(target_os = "linux")]
 mod spec;
 #[cfg(target_os = "linux")]
 mod start;
 #[cfg(target_os = "linux")]
 mod stdio;
 
+#[cfg(target_os = "linux")]
+pub use fuse::ensure_fuse_device;
 #[cfg(target_os = "linux")]
 pub use lifecycle::Container;
 #[cfg(target_os = "linux")]
-pub use spec::UserMount;
+pub use pause::{run_pause, PAUSE_ARG, PAUSE_PATH};
+#[cfg(target_os = "linux")]
+pub use processes::list_processes;
+#[cfg(target_os = "linux")]
+pub use sockets::{listening_sockets, SocketProtocol};
+#[cfg(target_os = "linux")]
+pub use spec::{TmpfsMount, UserMount};
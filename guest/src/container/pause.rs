@@ -0,0 +1,24 @@
+//! Idle init process for containers that only host exec'd processes.
+//!
+//! A container needs an init process, but the image's own command would
+//! start a second copy of its service (binding the same ports on the shared
+//! network). The agent binary is static, so it is bound into the container
+//! and runs as an init that does nothing.
+
+use nix::unistd::pause;
+
+/// First argument that runs the binary as an idle container init.
+pub const PAUSE_ARG: &str = "pause";
+
+/// Where the agent binary is bound inside a container it idles in.
+pub const PAUSE_PATH: &str = "/.boxlite-pause";
+
+/// Sleep until killed.
+///
+/// As PID 1 of its namespace the process ignores signals it has no handler
+/// for, so only SIGKILL stops it.
+pub fn run_pause() -> ! {
+    loop {
+        pause();
+    }
+}
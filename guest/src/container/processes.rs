@@ -0,0 +1,142 @@
+//! Process table of a running container.
+//!
+//! Scans `/proc` for processes in the container's PID namespace and reports
+//! them with PIDs as seen from inside the container.
+
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+use procfs::process::{Process, Status};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// One container process.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessEntry {
+    /// PID inside the container
+    pub pid: u32,
+    /// Parent PID inside the container (0 when the parent is outside it)
+    pub ppid: u32,
+    pub uid: u32,
+    /// User name from the container's `/etc/passwd`, or the uid
+    pub user: String,
+    pub rss_bytes: u64,
+    /// Full command line, or `[name]` when it is unavailable
+    pub command: String,
+}
+
+/// List processes sharing the PID namespace of `init_pid`, sorted by PID.
+///
+/// `rootfs` is the container rootfs, used to resolve user names.
+pub fn list_processes(init_pid: i32, rootfs: &Path) -> BoxliteResult<Vec<ProcessEntry>> {
+    let users = std::fs::read_to_string(rootfs.join("etc/passwd"))
+        .map(|content| parse_passwd(&content))
+        .unwrap_or_default();
+
+    let processes = namespace_processes(init_pid)?;
+    let container_pids: HashMap<i32, u32> = processes
+        .iter()
+        .map(|(_, status, pid)| (status.pid, *pid))
+        .collect();
+
+    let mut entries: Vec<ProcessEntry> = processes
+        .into_iter()
+        .map(|(process, status, pid)| {
+            let command = match process.cmdline() {
+                Ok(args) if !args.is_empty() => args.join(" "),
+                _ => format!("[{}]", status.name),
+            };
+            let user = users
+                .get(&status.ruid)
+                .cloned()
+                .unwrap_or_else(|| status.ruid.to_string());
+            ProcessEntry {
+                pid,
+                ppid: container_pids.get(&status.ppid).copied().unwrap_or(0),
+                uid: status.ruid,
+                user,
+                rss_bytes: status.vmrss.unwrap_or(0) * 1024,
+                command,
+            }
+        })
+        .collect();
+    entries.sort_by_key(|entry| entry.pid);
+    Ok(entries)
+}
+
+/// Processes in the PID namespace of `init_pid`, with their status and
+/// their PID inside that namespace.
+pub(super) fn namespace_processes(init_pid: i32) -> BoxliteResult<Vec<(Process, Status, u32)>> {
+    let namespace = pid_namespace(init_pid).ok_or_else(|| {
+        BoxliteError::NotFound(format!("container init process {} has exited", init_pid))
+    })?;
+    let all = procfs::process::all_processes()
+        .map_err(|e| BoxliteError::Internal(format!("Failed to read /proc: {}", e)))?;
+
+    let mut found = Vec::new();
+    for process in all.flatten() {
+        if pid_namespace(process.pid).as_ref() != Some(&namespace) {
+            continue;
+        }
+        // Processes can exit mid-scan
+        let Ok(status) = process.status() else {
+            continue;
+        };
+        let pid = status
+            .nspid
+            .as_ref()
+            .and_then(|pids| pids.last().copied())
+            .unwrap_or(status.pid);
+        found.push((process, status, pid as u32));
+    }
+    Ok(found)
+}
+
+/// PID namespace of `pid` (e.g. `pid:[4026532201]`), if the process exists.
+fn pid_namespace(pid: i32) -> Option<String> {
+    std::fs::read_link(format!("/proc/{}/ns/pid", pid))
+        .ok()
+        .map(|link| link.to_string_lossy().into_owned())
+}
+
+/// Map uids to user names from passwd(5) content; the first entry wins.
+fn parse_passwd(content: &str) -> HashMap<u32, String> {
+    let mut users = HashMap::new();
+    for line in content.lines() {
+        let mut fields = line.split(':');
+        let (Some(name), Some(uid)) = (fields.next(), fields.nth(1)) else {
+            continue;
+        };
+        if let Ok(uid) = uid.parse() {
+            users.entry(uid).or_insert_with(|| name.to_string());
+        }
+    }
+    users
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_passwd() {
+        let users = parse_passwd(
+            "root:x:0:0:root:/root:/bin/sh\n\
+             # comment\n\
+             app:x:1000:1000::/home/app:/bin/sh\n\
+             toor:x:0:0::/root:/bin/sh\n",
+        );
+        assert_eq!(users.get(&0).map(String::as_str), Some("root"));
+        assert_eq!(users.get(&1000).map(String::as_str), Some("app"));
+        assert_eq!(users.len(), 2);
+    }
+
+    #[test]
+    fn test_list_processes_includes_self() {
+        let rootfs = tempfile::tempdir().unwrap();
+        let me = std::process::id();
+        let entries = list_processes(me as i32, rootfs.path()).unwrap();
+        let entry = entries.iter().find(|e| e.pid == me).unwrap();
+        assert!(entry.rss_bytes > 0);
+        assert!(!entry.command.is_empty());
+        assert!(entries.windows(2).all(|w| w[0].pid < w[1].pid));
+    }
+}
@@ -0,0 +1,108 @@
+//! Listening sockets of a running container.
+//!
+//! Reads the socket tables of the container's network namespace and keeps
+//! the listening ones owned by processes in the container's PID namespace.
+
+use super::processes::namespace_processes;
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+use procfs::net::{TcpState, UdpState};
+use procfs::process::{FDTarget, Process};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SocketProtocol {
+    Tcp,
+    Udp,
+}
+
+/// One listening socket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListeningSocket {
+    pub protocol: SocketProtocol,
+    pub address: SocketAddr,
+    /// Owning process, as seen inside the container
+    pub pid: u32,
+    /// Process name (comm) of the owner
+    pub command: String,
+}
+
+/// List TCP sockets in LISTEN state and unconnected UDP sockets owned by
+/// processes sharing the PID namespace of `init_pid`, sorted by port.
+pub fn listening_sockets(init_pid: i32) -> BoxliteResult<Vec<ListeningSocket>> {
+    // Socket inode -> owning (container pid, name); the first owner wins
+    // when a socket is shared across fork
+    let mut owners: HashMap<u64, (u32, String)> = HashMap::new();
+    for (process, status, pid) in namespace_processes(init_pid)? {
+        let Ok(fds) = process.fd() else {
+            continue;
+        };
+        for fd in fds.flatten() {
+            if let FDTarget::Socket(inode) = fd.target {
+                owners
+                    .entry(inode)
+                    .or_insert_with(|| (pid, status.name.clone()));
+            }
+        }
+    }
+
+    let init = Process::new(init_pid).map_err(|_| {
+        BoxliteError::NotFound(format!("container init process {} has exited", init_pid))
+    })?;
+
+    let mut found = Vec::new();
+    // tcp6/udp6 are missing when IPv6 is disabled
+    let tcp = init.tcp().unwrap_or_default().into_iter();
+    for entry in tcp.chain(init.tcp6().unwrap_or_default()) {
+        if entry.state == TcpState::Listen {
+            found.push((SocketProtocol::Tcp, entry.local_address, entry.inode));
+        }
+    }
+    let udp = init.udp().unwrap_or_default().into_iter();
+    for entry in udp.chain(init.udp6().unwrap_or_default()) {
+        // Unconnected UDP sockets report CLOSE
+        if entry.state == UdpState::Close {
+            found.push((SocketProtocol::Udp, entry.local_address, entry.inode));
+        }
+    }
+
+    let mut sockets: Vec<ListeningSocket> = found
+        .into_iter()
+        .filter_map(|(protocol, address, inode)| {
+            let (pid, command) = owners.get(&inode)?.clone();
+            Some(ListeningSocket {
+                protocol,
+                address,
+                pid,
+                command,
+            })
+        })
+        .collect();
+    sockets.sort_by_key(|socket| (socket.address.port(), socket.protocol, socket.address));
+    Ok(sockets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_listening_sockets_includes_own_listener() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        // A connected socket is not listening
+        let _client = std::net::TcpStream::connect(addr).unwrap();
+
+        let me = std::process::id();
+        let sockets = listening_sockets(me as i32).unwrap();
+        let matching: Vec<_> = sockets
+            .iter()
+            .filter(|s| s.address.port() == addr.port())
+            .collect();
+        assert_eq!(matching.len(), 1);
+        assert_eq!(matching[0].protocol, SocketProtocol::Tcp);
+        assert_eq!(matching[0].address, addr);
+        assert_eq!(matching[0].pid, me);
+        assert!(!matching[0].command.is_empty());
+    }
+}
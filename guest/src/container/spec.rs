@@ -2,14 +2,16 @@
 //!
 //! Creates OCI-compliant runtime specifications following the runtime-spec standard.
 
-use super::capabilities::all_capabilities;
+use super::fuse::{FUSE_DEVICE, FUSE_MAJOR, FUSE_MINOR};
 use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+use std::collections::HashSet;
 use std::path::Path;
 
 use oci_spec::runtime::{
-    LinuxBuilder, LinuxCapabilitiesBuilder, LinuxIdMappingBuilder, LinuxNamespaceBuilder,
-    LinuxNamespaceType, Mount, MountBuilder, PosixRlimitBuilder, PosixRlimitType, ProcessBuilder,
-    RootBuilder, Spec, SpecBuilder, UserBuilder,
+    Capability, LinuxBuilder, LinuxCapabilitiesBuilder, LinuxDevice, LinuxDeviceBuilder,
+    LinuxDeviceType, LinuxIdMappingBuilder, LinuxNamespaceBuilder, LinuxNamespaceType, Mount,
+    MountBuilder, PosixRlimitBuilder, PosixRlimitType, ProcessBuilder, RootBuilder, Spec,
+    SpecBuilder, UserBuilder,
 };
 
 /// User-specified bind mount for container
@@ -21,14 +23,43 @@ pub struct UserMount {
     pub destination: String,
     /// Read-only mount
     pub read_only: bool,
+    /// Chown the source to the container user before start
+    pub chown: bool,
+}
+
+/// tmpfs mount inside the container
+#[derive(Debug, Clone)]
+pub struct TmpfsMount {
+    /// Destination path in container
+    pub destination: String,
+    /// Size limit in bytes (None = kernel default)
+    pub size_bytes: Option<u64>,
+    /// Permission bits of the mount root (None = 1777)
+    pub mode: Option<u32>,
+}
+
+impl TmpfsMount {
+    /// Mount options for the OCI spec.
+    fn options(&self) -> Vec<String> {
+        let mut options = vec![
+            "nosuid".to_string(),
+            "nodev".to_string(),
+            format!("mode={:o}", self.mode.unwrap_or(0o1777)),
+        ];
+        if let Some(size) = self.size_bytes {
+            options.push(format!("size={}", size));
+        }
+        options
+    }
 }
 
 /// Create OCI runtime specification with default configuration
 ///
 /// Builds an OCI spec with:
 /// - Standard mounts (/proc, /dev, /sys, etc.)
-/// - User-specified bind mounts (volumes)
-/// - Default capabilities (matching runc defaults)
+/// - User-specified bind mounts (volumes) and tmpfs mounts
+/// - The host-selected capability set
+/// - /dev/fuse and CAP_SYS_ADMIN when `allow_fuse` is set
 /// - Standard namespaces (pid, ipc, uts, mount)
 /// - UID/GID mappings for user namespace
 /// - Configurable user (resolved uid/gid)
@@ -42,6 +73,7 @@ pub struct UserMount {
 #[allow(clippy::too_many_arguments)]
 pub fn create_oci_spec(
     container_id: &str,
+    hostname: &str,
     rootfs: &str,
     entrypoint: &[String],
     env: &[String],
@@ -50,8 +82,17 @@ pub fn create_oci_spec(
     gid: u32,
     bundle_path: &Path,
     user_mounts: &[UserMount],
+    tmpfs_mounts: &[TmpfsMount],
+    allow_fuse: bool,
+    mut cap_set: HashSet<Capability>,
 ) -> BoxliteResult<Spec> {
-    let caps = build_default_capabilities()?;
+    let mut devices = Vec::new();
+    if allow_fuse {
+        // Mounting a FUSE filesystem needs the device and CAP_SYS_ADMIN
+        cap_set.insert(Capability::SysAdmin);
+        devices.push(build_fuse_device()?);
+    }
+    let caps = build_capabilities(cap_set)?;
     let namespaces = build_default_namespaces()?;
     let mut mounts = build_standard_mounts(bundle_path)?;
 
@@ -86,13 +127,33 @@ pub fn create_oci_spec(
         );
     }
 
+    for tmpfs in tmpfs_mounts {
+        // A tmpfs on a standard mount point (e.g. a larger /dev/shm) replaces it
+        mounts.retain(|m| m.destination() != Path::new(&tmpfs.destination));
+        mounts.push(
+            MountBuilder::default()
+                .destination(&tmpfs.destination)
+                .typ("tmpfs")
+                .source("tmpfs")
+                .options(tmpfs.options())
+                .build()
+                .map_err(|e| {
+                    BoxliteError::Internal(format!(
+                        "Failed to build tmpfs mount {}: {}",
+                        tmpfs.destination, e
+                    ))
+                })?,
+        );
+        tracing::debug!(destination = %tmpfs.destination, "Added tmpfs mount to OCI spec");
+    }
+
     let process = build_process_spec(entrypoint, env, workdir, uid, gid, caps)?;
     let root = build_root_spec(rootfs)?;
-    let linux = build_linux_spec(container_id, namespaces)?;
+    let linux = build_linux_spec(container_id, namespaces, devices)?;
 
     SpecBuilder::default()
         .version("1.0.2")
-        .hostname("boxlite")
+        .hostname(hostname)
         .root(root)
         .mounts(mounts)
         .process(process)
@@ -260,13 +321,12 @@ fn find_group_in_group_file(rootfs: &str, name: &str) -> BoxliteResult<u32> {
 // Spec Component Builders
 // ====================
 
-/// Build default Linux capabilities
+/// Build Linux capabilities for the container process
 ///
-/// Uses all 41 capabilities from the shared capabilities module.
-/// This provides maximum compatibility but reduced security isolation.
-fn build_default_capabilities() -> BoxliteResult<oci_spec::runtime::LinuxCapabilities> {
-    let caps = all_capabilities();
-
+/// All five sets get the same capabilities, so the process keeps them across exec.
+fn build_capabilities(
+    caps: HashSet<Capability>,
+) -> BoxliteResult<oci_spec::runtime::LinuxCapabilities> {
     LinuxCapabilitiesBuilder::default()
         .bounding(caps.clone())
         .effective(caps.clone())
@@ -293,6 +353,20 @@ fn build_default_namespaces() -> BoxliteResult<Vec<oci_spec::runtime::LinuxNames
     ])
 }
 
+/// Build the /dev/fuse device entry (runtime creates the node in the container)
+fn build_fuse_device() -> BoxliteResult<LinuxDevice> {
+    LinuxDeviceBuilder::default()
+        .path(FUSE_DEVICE)
+        .typ(LinuxDeviceType::C)
+        .major(FUSE_MAJOR as i64)
+        .minor(FUSE_MINOR as i64)
+        .file_mode(0o666u32)
+        .uid(0u32)
+        .gid(0u32)
+        .build()
+        .map_err(|e| BoxliteError::Internal(format!("Failed to build /dev/fuse device: {}", e)))
+}
+
 /// Build a single namespace specification
 fn build_namespace(typ: LinuxNamespaceType) -> BoxliteResult<oci_spec::runtime::LinuxNamespace> {
     LinuxNamespaceBuilder::default()
@@ -353,6 +427,7 @@ fn build_root_spec(rootfs: &str) -> BoxliteResult<oci_spec::runtime::Root> {
 fn build_linux_spec(
     container_id: &str,
     namespaces: Vec<oci_spec::runtime::LinuxNamespace>,
+    devices: Vec<LinuxDevice>,
 ) -> BoxliteResult<oci_spec::runtime::Linux> {
     // UID/GID mappings for user namespace
     // Map full range of UIDs/GIDs to allow non-root users (nginx=33, etc.)
@@ -400,13 +475,18 @@ fn build_linux_spec(
     // let cgroups_path = format!("/boxlite/{}", container_id);
     let _ = container_id; // Suppress unused warning
 
-    LinuxBuilder::default()
+    let mut builder = LinuxBuilder::default()
         .namespaces(namespaces)
         .uid_mappings(uid_mappings)
-        .gid_mappings(gid_mappings)
-        // .masked_paths(masked_paths)
-        // .readonly_paths(readonly_paths)
-        // .cgroups_path(cgroups_path)
+        .gid_mappings(gid_mappings);
+    // .masked_paths(masked_paths)
+    // .readonly_paths(readonly_paths)
+    // .cgroups_path(cgroups_path)
+    if !devices.is_empty() {
+        builder = builder.devices(devices);
+    }
+
+    builder
         .build()
         .map_err(|e| BoxliteError::Internal(format!("Failed to build linux spec: {}", e)))
 }
@@ -960,4 +1040,78 @@ mod tests {
         let err = resolve_user(r, "short").unwrap_err().to_string();
         assert!(err.contains("User 'short' not found"), "got: {}", err);
     }
+
+    #[test]
+    fn test_tmpfs_mount_options() {
+        let tmpfs = TmpfsMount {
+            destination: "/scratch".to_string(),
+            size_bytes: Some(64 << 20),
+            mode: Some(0o700),
+        };
+        assert_eq!(
+            tmpfs.options(),
+            vec!["nosuid", "nodev", "mode=700", "size=67108864"]
+        );
+
+        let default = TmpfsMount {
+            destination: "/tmp".to_string(),
+            size_bytes: None,
+            mode: None,
+        };
+        assert_eq!(default.options(), vec!["nosuid", "nodev", "mode=1777"]);
+    }
+
+    #[test]
+    fn test_allow_fuse_adds_device() {
+        let build = |allow_fuse| {
+            create_oci_spec(
+                "c1",
+                "boxlite",
+                "/rootfs",
+                &["sh".to_string()],
+                &[],
+                "/",
+                0,
+                0,
+                Path::new("/run/boxlite/containers/c1"),
+                &[],
+                &[],
+                allow_fuse,
+                HashSet::from([Capability::Chown]),
+            )
+            .unwrap()
+        };
+
+        let spec = build(true);
+        let devices = spec.linux().as_ref().unwrap().devices().clone().unwrap();
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].path(), Path::new("/dev/fuse"));
+        assert_eq!((devices[0].major(), devices[0].minor()), (10, 229));
+        let caps = spec
+            .process()
+            .as_ref()
+            .unwrap()
+            .capabilities()
+            .clone()
+            .unwrap();
+        assert!(caps
+            .bounding()
+            .as_ref()
+            .unwrap()
+            .contains(&Capability::SysAdmin));
+
+        let spec = build(false);
+        assert!(spec.linux().as_ref().unwrap().devices().is_none());
+        let caps = spec
+            .process()
+            .as_ref()
+            .unwrap()
+            .capabilities()
+            .clone()
+            .unwrap();
+        assert_eq!(
+            caps.bounding().as_ref().unwrap(),
+            &HashSet::from([Capability::Chown])
+        );
+    }
 }
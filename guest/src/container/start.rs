@@ -8,6 +8,8 @@ use boxlite_shared::errors::{BoxliteError, BoxliteResult};
 use libcontainer::container::builder::ContainerBuilder;
 use libcontainer::container::Container as LibContainer;
 use libcontainer::syscall::syscall::SyscallType;
+use oci_spec::runtime::Capability;
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -40,21 +42,53 @@ pub(crate) fn validate_container_inputs(
     Ok(())
 }
 
+/// Create the working directory in the rootfs if the image lacks it
+///
+/// Matches Docker, which creates a missing `WORKDIR` / `--workdir` rather
+/// than failing the container start.
+pub(crate) fn ensure_workdir(rootfs: &Path, workdir: &Path) -> BoxliteResult<()> {
+    use std::path::Component;
+
+    if workdir.components().any(|c| c == Component::ParentDir) {
+        return Err(BoxliteError::Internal(format!(
+            "Container working directory must not contain '..': {}",
+            workdir.display()
+        )));
+    }
+    let relative = workdir.strip_prefix("/").unwrap_or(workdir);
+    if relative.as_os_str().is_empty() {
+        return Ok(());
+    }
+    let path = rootfs.join(relative);
+    if !path.exists() {
+        tracing::debug!("Creating missing working directory {}", workdir.display());
+        fs::create_dir_all(&path).map_err(|e| {
+            BoxliteError::Internal(format!(
+                "Failed to create working directory {}: {}",
+                workdir.display(),
+                e
+            ))
+        })?;
+    }
+    Ok(())
+}
+
 /// Create /etc/hosts, /etc/hostname and /etc/resolv.conf files for the container
+///
+/// With `host_loopback_ip`, /etc/hosts also names it `host.boxlite.internal`.
 pub(crate) fn create_container_etc_files(
     bundle_path: &Path,
-    _container_id: &str,
+    hostname: &str,
+    host_loopback_ip: Option<&str>,
 ) -> BoxliteResult<()> {
-    const DEFAULT_HOSTNAME: &str = "boxlite";
-
     // Create /etc/hostname
     let hostname_path = bundle_path.join("hostname");
-    fs::write(&hostname_path, format!("{}\n", DEFAULT_HOSTNAME))
+    fs::write(&hostname_path, format!("{}\n", hostname))
         .map_err(|e| BoxliteError::Internal(format!("Failed to create hostname file: {}", e)))?;
 
     // Create /etc/hosts with localhost and hostname entries
     let hosts_path = bundle_path.join("hosts");
-    let hosts_content = format!(
+    let mut hosts_content = format!(
         "127.0.0.1\tlocalhost\n\
          ::1\t\tlocalhost ip6-localhost ip6-loopback\n\
          fe00::0\t\tip6-localnet\n\
@@ -62,8 +96,15 @@ pub(crate) fn create_container_etc_files(
          ff02::1\t\tip6-allnodes\n\
          ff02::2\t\tip6-allrouters\n\
          127.0.1.1\t{}\n",
-        DEFAULT_HOSTNAME
+        hostname
     );
+    if let Some(ip) = host_loopback_ip {
+        hosts_content.push_str(&format!(
+            "{}\t{}\n",
+            ip,
+            boxlite_shared::constants::container::HOST_LOOPBACK_HOSTNAME
+        ));
+    }
     fs::write(&hosts_path, hosts_content)
         .map_err(|e| BoxliteError::Internal(format!("Failed to create hosts file: {}", e)))?;
 
@@ -90,6 +131,7 @@ pub(crate) fn create_container_etc_files(
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn create_oci_bundle(
     container_id: &str,
+    hostname: &str,
     rootfs: &Path,
     entrypoint: &[String],
     env: &[String],
@@ -98,6 +140,10 @@ pub(crate) fn create_oci_bundle(
     gid: u32,
     bundle_root: &Path,
     user_mounts: &[spec::UserMount],
+    tmpfs_mounts: &[spec::TmpfsMount],
+    allow_fuse: bool,
+    capabilities: HashSet<Capability>,
+    host_loopback_ip: Option<&str>,
 ) -> BoxliteResult<PathBuf> {
     let bundle_path = bundle_root.join(container_id);
 
@@ -111,10 +157,11 @@ pub(crate) fn create_oci_bundle(
 
     // Create /etc/hosts, /etc/hostname and /etc/resolv.conf files
     // These will be bind-mounted into the container to provide hostname and DNS resolution
-    create_container_etc_files(&bundle_path, container_id)?;
+    create_container_etc_files(&bundle_path, hostname, host_loopback_ip)?;
 
     let spec = spec::create_oci_spec(
         container_id,
+        hostname,
         rootfs
             .to_str()
             .ok_or_else(|| BoxliteError::Internal("Invalid rootfs path".to_string()))?,
@@ -127,6 +174,9 @@ pub(crate) fn create_oci_bundle(
         gid,
         &bundle_path,
         user_mounts,
+        tmpfs_mounts,
+        allow_fuse,
+        capabilities,
     )?;
     let config_path = bundle_path.join("config.json");
 
@@ -278,3 +328,22 @@ pub(crate) fn load_container_status(
 
     Ok(container.status())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hosts_names_host_loopback() {
+        let bundle = tempfile::tempdir().unwrap();
+
+        create_container_etc_files(bundle.path(), "web", Some("192.168.127.254")).unwrap();
+        let hosts = fs::read_to_string(bundle.path().join("hosts")).unwrap();
+        assert!(hosts.contains("127.0.1.1\tweb\n"));
+        assert!(hosts.ends_with("192.168.127.254\thost.boxlite.internal\n"));
+
+        create_container_etc_files(bundle.path(), "web", None).unwrap();
+        let hosts = fs::read_to_string(bundle.path().join("hosts")).unwrap();
+        assert!(!hosts.contains("host.boxlite.internal"));
+    }
+}
@@ -0,0 +1,180 @@
+//! Core dump collection.
+//!
+//! When the host asks for core dumps, the kernel's `core_pattern` pipes
+//! each dump into this binary (`boxlite-guest core-dump ...`), which writes
+//! it to the host-shared cores directory as
+//! `core.{unix time}.{pid}.{signal}.{command}`, truncated at a size cap, and
+//! removes the oldest dumps beyond a count cap. A pipe runs the helper in
+//! the guest's own namespaces, so dumps of container processes land in the
+//! shared directory too.
+
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::Path;
+
+use boxlite_shared::layout::GUEST_CORES_DIR;
+use boxlite_shared::CoreDumpInit;
+
+/// First argument that runs the binary as the core dump helper.
+pub const HELPER_ARG: &str = "core-dump";
+
+/// The kernel ignores longer core patterns (CORENAME_MAX_SIZE).
+const CORE_PATTERN_MAX: usize = 127;
+
+const TRUNCATED_SUFFIX: &str = ".truncated";
+
+/// Point the kernel's core pattern at the helper.
+pub fn configure(config: &CoreDumpInit) -> io::Result<()> {
+    let exe = std::env::current_exe()?;
+    // %e goes last: older kernels split the expanded pattern on spaces
+    let pattern = format!(
+        "|{} {} {} {} %p %s %t %e",
+        exe.display(),
+        HELPER_ARG,
+        config.max_bytes,
+        config.max_files
+    );
+    if pattern.len() > CORE_PATTERN_MAX {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("core pattern too long: {}", pattern),
+        ));
+    }
+    fs::write("/proc/sys/kernel/core_pattern", pattern)
+}
+
+/// Run as the kernel's core dump helper; returns the exit code.
+///
+/// The kernel discards the helper's output, so errors go nowhere.
+pub fn run_helper(args: &[String]) -> i32 {
+    match write_dump(Path::new(GUEST_CORES_DIR), &mut io::stdin().lock(), args) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("core dump failed: {}", e);
+            1
+        }
+    }
+}
+
+/// Write the dump read from `input` into `dir`.
+///
+/// `args` are `max_bytes max_files pid signal time command...`.
+fn write_dump(dir: &Path, input: &mut impl Read, args: &[String]) -> io::Result<()> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidInput, format!("bad args {:?}", args));
+    let (limits, command) = args.split_at(args.len().min(5));
+    let [max_bytes, max_files, pid, signal, time] = limits else {
+        return Err(invalid());
+    };
+    let max_bytes: u64 = max_bytes.parse().map_err(|_| invalid())?;
+    let max_files: usize = max_files.parse().map_err(|_| invalid())?;
+    let command: String = command
+        .join(" ")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || "._-".contains(c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    let name = format!("core.{}.{}.{}.{}", time, pid, signal, command);
+    // Hidden until complete, so the host never lists a partial dump
+    let partial = dir.join(format!(".{}.partial", name));
+    let mut file = File::create(&partial)?;
+    let written = io::copy(&mut input.by_ref().take(max_bytes), &mut file)?;
+    // Anything left means the dump was cut off
+    let truncated = written == max_bytes && input.read(&mut [0u8; 1])? > 0;
+    drop(file);
+
+    let name = if truncated {
+        format!("{}{}", name, TRUNCATED_SUFFIX)
+    } else {
+        name
+    };
+    fs::rename(&partial, dir.join(name))?;
+    prune(dir, max_files)
+}
+
+/// Remove the oldest dumps beyond `max_files`.
+fn prune(dir: &Path, max_files: usize) -> io::Result<()> {
+    let mut dumps: Vec<(u64, String)> = fs::read_dir(dir)?
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let time = name
+                .strip_prefix("core.")?
+                .split('.')
+                .next()?
+                .parse()
+                .ok()?;
+            Some((time, name))
+        })
+        .collect();
+    dumps.sort_unstable_by(|a, b| b.cmp(a));
+    for (_, name) in dumps.iter().skip(max_files) {
+        fs::remove_file(dir.join(name))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_write_dump() {
+        let dir = tempfile::tempdir().unwrap();
+
+        write_dump(
+            dir.path(),
+            &mut &b"ELF"[..],
+            &args(&["8", "2", "42", "11", "100", "python3"]),
+        )
+        .unwrap();
+        assert_eq!(
+            fs::read(dir.path().join("core.100.42.11.python3")).unwrap(),
+            b"ELF"
+        );
+
+        // Cut at max_bytes; odd command names are made file-safe
+        write_dump(
+            dir.path(),
+            &mut &b"0123456789"[..],
+            &args(&["8", "2", "7", "6", "200", "my", "app/1"]),
+        )
+        .unwrap();
+        assert_eq!(
+            fs::read(dir.path().join("core.200.7.6.my_app_1.truncated")).unwrap(),
+            b"01234567"
+        );
+
+        // The oldest dump goes once there are more than max_files
+        write_dump(
+            dir.path(),
+            &mut &b""[..],
+            &args(&["8", "2", "9", "11", "300", "sh"]),
+        )
+        .unwrap();
+        assert_eq!(
+            names(dir.path()),
+            ["core.200.7.6.my_app_1.truncated", "core.300.9.11.sh"]
+        );
+
+        assert!(write_dump(dir.path(), &mut &b""[..], &args(&["8", "2"])).is_err());
+    }
+}
@@ -15,6 +15,10 @@ use std::path::{Path, PathBuf};
 /// Represents the OCI bundle directory for a single container:
 /// ```text
 /// /run/boxlite/containers/{cid}/
+/// ├── image/             # Read-only rootfs image (image strategy only)
+/// ├── overlayfs/         # Upper disk mount (image strategy only)
+/// │   ├── upper/
+/// │   └── work/
 /// ├── rootfs/            # Container rootfs (bind → shared/containers/{cid}/rootfs)
 /// └── state/             # libcontainer state directory
 /// ```
@@ -42,6 +46,30 @@ impl ContainerLayout {
         self.root.join(dirs::ROOTFS)
     }
 
+    /// Image directory: /run/boxlite/containers/{cid}/image
+    ///
+    /// Mount point of the read-only rootfs image (overlayfs lower dir).
+    pub fn image_dir(&self) -> PathBuf {
+        self.root.join(dirs::IMAGE)
+    }
+
+    /// Overlayfs directory: /run/boxlite/containers/{cid}/overlayfs
+    ///
+    /// Mount point of the writable upper disk.
+    pub fn overlayfs_dir(&self) -> PathBuf {
+        self.root.join(dirs::OVERLAYFS)
+    }
+
+    /// Upper directory: /run/boxlite/containers/{cid}/overlayfs/upper
+    pub fn upper_dir(&self) -> PathBuf {
+        self.overlayfs_dir().join(dirs::UPPER)
+    }
+
+    /// Work directory: /run/boxlite/containers/{cid}/overlayfs/work
+    pub fn work_dir(&self) -> PathBuf {
+        self.overlayfs_dir().join(dirs::WORK)
+    }
+
     /// Prepare container directory.
     pub fn prepare(&self) -> std::io::Result<()> {
         std::fs::create_dir_all(self.rootfs_dir())
@@ -3,15 +3,23 @@
 #[cfg(not(target_os = "linux"))]
 compile_error!("BoxLite guest is Linux-only; build with a Linux target");
 
+#[cfg(target_os = "linux")]
+mod boot;
 #[cfg(target_os = "linux")]
 mod container;
 #[cfg(target_os = "linux")]
+mod coredump;
+#[cfg(target_os = "linux")]
 mod layout;
 #[cfg(target_os = "linux")]
+mod memory;
+#[cfg(target_os = "linux")]
 mod mounts;
 #[cfg(target_os = "linux")]
 mod network;
 #[cfg(target_os = "linux")]
+mod oom;
+#[cfg(target_os = "linux")]
 mod overlayfs;
 #[cfg(target_os = "linux")]
 mod service;
@@ -54,6 +62,18 @@ struct GuestArgs {
 #[cfg(target_os = "linux")]
 #[tokio::main]
 async fn main() -> BoxliteResult<()> {
+    // The kernel runs this binary to collect core dumps
+    let raw_args: Vec<String> = std::env::args().collect();
+    if raw_args.get(1).map(String::as_str) == Some(coredump::HELPER_ARG) {
+        std::process::exit(coredump::run_helper(&raw_args[2..]));
+    }
+    // Init of the throwaway containers of fresh-rootfs executions
+    if raw_args.get(1).map(String::as_str) == Some(container::PAUSE_ARG) {
+        container::run_pause();
+    }
+
+    boot::mark_agent_start();
+
     // Early diagnostic - visible even if tracing fails
     eprintln!("[BOOT] BoxLite guest agent starting");
 
@@ -98,6 +118,9 @@ async fn main() -> BoxliteResult<()> {
     info!("Preparing guest layout at {}", layout.base().display());
     layout.prepare_base()?;
 
+    // Count OOM kills so executions can report them
+    oom::start_watcher();
+
     // Start server in uninitialized state
     // All initialization (mounts, rootfs, network) will happen via Guest.Init RPC
     info!("Starting guest server on: {}", args.listen);
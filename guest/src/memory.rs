@@ -0,0 +1,74 @@
+//! Guest memory reclaim.
+//!
+//! Makes the guest free as much memory as it can: drop the page cache and
+//! compact free memory into large blocks. Whether the host gets those pages
+//! back depends on the VMM reporting free guest pages; this module only
+//! frees them and measures MemFree before and after.
+
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+use tracing::{debug, warn};
+
+/// Result of a reclaim pass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReclaimStats {
+    /// MemFree gained by this pass (bytes).
+    pub reclaimed_bytes: u64,
+    /// MemFree after this pass (bytes).
+    pub free_bytes: u64,
+}
+
+/// Drop caches and compact memory, returning the MemFree change.
+pub fn reclaim() -> BoxliteResult<ReclaimStats> {
+    let before = read_mem_free()?;
+
+    // Flush dirty pages first so drop_caches can release them.
+    nix::unistd::sync();
+    for (knob, value) in [
+        ("/proc/sys/vm/drop_caches", "3"),
+        ("/proc/sys/vm/compact_memory", "1"),
+    ] {
+        if let Err(e) = std::fs::write(knob, value) {
+            warn!(knob, error = %e, "Failed to write memory reclaim knob");
+        }
+    }
+
+    let after = read_mem_free()?;
+    let stats = ReclaimStats {
+        reclaimed_bytes: after.saturating_sub(before),
+        free_bytes: after,
+    };
+    debug!(?stats, "Memory reclaim complete");
+    Ok(stats)
+}
+
+fn read_mem_free() -> BoxliteResult<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo")
+        .map_err(|e| BoxliteError::Internal(format!("Failed to read /proc/meminfo: {}", e)))?;
+    parse_mem_free(&meminfo)
+        .ok_or_else(|| BoxliteError::Internal("MemFree missing from /proc/meminfo".to_string()))
+}
+
+/// Parse `MemFree` (reported in kB) from /proc/meminfo into bytes.
+fn parse_mem_free(meminfo: &str) -> Option<u64> {
+    meminfo.lines().find_map(|line| {
+        let rest = line.strip_prefix("MemFree:")?;
+        let kb: u64 = rest.split_whitespace().next()?.parse().ok()?;
+        Some(kb * 1024)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mem_free() {
+        let meminfo = "MemTotal:        2030404 kB\nMemFree:         1745320 kB\nMemAvailable:    1820000 kB\n";
+        assert_eq!(parse_mem_free(meminfo), Some(1745320 * 1024));
+    }
+
+    #[test]
+    fn test_parse_mem_free_missing() {
+        assert_eq!(parse_mem_free("MemTotal: 1 kB\n"), None);
+    }
+}
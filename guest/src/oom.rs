@@ -0,0 +1,129 @@
+//! Out-of-memory kill detection.
+//!
+//! The kernel logs every OOM kill to /dev/kmsg, e.g. `Out of memory: Killed
+//! process 1234 (python3) ...` or `Memory cgroup out of memory: Killed process
+//! 1234 ...`. A watcher thread counts these records; an execution compares
+//! the count at its start and exit to tell whether the kernel killed a
+//! process while it ran.
+
+use std::fs::File;
+use std::io::{ErrorKind, Read, Seek, SeekFrom};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use tracing::{info, warn};
+
+/// OOM kills logged since the watcher started.
+static OOM_KILLS: AtomicU64 = AtomicU64::new(0);
+
+/// How long to wait for the kill record: the kernel signals the victim
+/// before logging it, so the exit can be seen first.
+const LOG_SETTLE: Duration = Duration::from_millis(100);
+
+/// Start counting OOM kills from the kernel log.
+///
+/// Records already in the log are skipped. If /dev/kmsg cannot be read, no
+/// kills are ever reported.
+pub fn start_watcher() {
+    let mut kmsg = match File::open("/dev/kmsg") {
+        Ok(kmsg) => kmsg,
+        Err(e) => {
+            warn!(error = %e, "Cannot open /dev/kmsg, OOM kills will not be reported");
+            return;
+        }
+    };
+    if let Err(e) = kmsg.seek(SeekFrom::End(0)) {
+        warn!(error = %e, "Cannot skip old /dev/kmsg records");
+    }
+
+    std::thread::spawn(move || {
+        // Each read returns exactly one record
+        let mut buf = vec![0u8; 8192];
+        loop {
+            let len = match kmsg.read(&mut buf) {
+                Ok(0) => break,
+                Ok(len) => len,
+                // Records were overwritten before we read them
+                Err(e) if e.kind() == ErrorKind::BrokenPipe => continue,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    warn!(error = %e, "Stopped reading /dev/kmsg");
+                    break;
+                }
+            };
+            let record = String::from_utf8_lossy(&buf[..len]);
+            if let Some((pid, command)) = parse_oom_kill(&record) {
+                OOM_KILLS.fetch_add(1, Ordering::Relaxed);
+                warn!(pid, command, "Kernel killed a process: out of memory");
+            }
+        }
+        info!("OOM watcher stopped");
+    });
+}
+
+/// OOM kills logged so far.
+pub fn kill_count() -> u64 {
+    OOM_KILLS.load(Ordering::Relaxed)
+}
+
+/// Whether an OOM kill was logged after the count was `start`.
+pub async fn killed_since(start: u64) -> bool {
+    if kill_count() > start {
+        return true;
+    }
+    tokio::time::sleep(LOG_SETTLE).await;
+    kill_count() > start
+}
+
+/// Victim pid and command name of a kmsg OOM kill record.
+///
+/// Records look like `3,1234,5678,-;Out of memory: Killed process 42 (app) ...`.
+fn parse_oom_kill(record: &str) -> Option<(u32, &str)> {
+    let message = record
+        .split_once(';')
+        .map_or(record, |(_, message)| message);
+    if !message.contains("out of memory") && !message.contains("Out of memory") {
+        return None;
+    }
+    let rest = message.split_once("Killed process ")?.1;
+    let (pid, rest) = rest.split_once(' ')?;
+    let command = rest
+        .strip_prefix('(')
+        .and_then(|rest| rest.split_once(')'))
+        .map_or("", |(command, _)| command);
+    Some((pid.parse().ok()?, command))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_oom_kill() {
+        assert_eq!(
+            parse_oom_kill(
+                "3,812,73482911,-;Out of memory: Killed process 1234 (python3) \
+                 total-vm:2203944kB, anon-rss:1030568kB, file-rss:0kB, shmem-rss:0kB, UID:0"
+            ),
+            Some((1234, "python3"))
+        );
+        assert_eq!(
+            parse_oom_kill(
+                "3,900,80000000,-;Memory cgroup out of memory: Killed process 77 (node) \
+                 total-vm:100kB"
+            ),
+            Some((77, "node"))
+        );
+        // The summary line before the kill is not a kill
+        assert_eq!(
+            parse_oom_kill(
+                "6,811,73482900,-;oom-kill:constraint=CONSTRAINT_NONE,task=python3,pid=1234,uid=0"
+            ),
+            None
+        );
+        assert_eq!(
+            parse_oom_kill("6,1,2,-;Killed process 5 (sh) by request"),
+            None
+        );
+    }
+}
@@ -1,29 +1,39 @@
 #![cfg(target_os = "linux")]
 //! Container service implementation.
 //!
-//! Handles OCI container lifecycle (Init RPC).
+//! Handles OCI container lifecycle (Init RPC), volumes mounted into a
+//! running container (Mount/Unmount RPCs), its process table (Processes RPC)
+//! and its listening sockets (ListeningPorts RPC).
 
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 
 use crate::service::server::GuestServer;
 use boxlite_shared::{
-    container_init_response, rootfs_init, Container as ContainerService, ContainerInitError,
-    ContainerInitRequest, ContainerInitResponse, ContainerInitSuccess, Filesystem, RootfsInit,
+    container_init_response, rootfs_init, Container as ContainerService, ContainerConfig,
+    ContainerInitError, ContainerInitRequest, ContainerInitResponse, ContainerInitSuccess,
+    ContainerMountRequest, ContainerMountResponse, ContainerProcessesRequest,
+    ContainerProcessesResponse, ContainerUnmountRequest, ContainerUnmountResponse, Filesystem,
+    ListeningPortsRequest, ListeningPortsResponse, ListeningSocket, ProcessInfo, RootfsInit,
+    SocketProtocol as ProtoSocketProtocol,
 };
-use nix::mount::{mount, MsFlags};
+use nix::mount::{mount, umount2, MntFlags, MsFlags};
 use tonic::{Request, Response, Status};
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
-use crate::container::{Container, UserMount};
+use crate::container::{
+    ensure_fuse_device, list_processes, listening_sockets, Container, SocketProtocol, TmpfsMount,
+    UserMount,
+};
 use crate::layout::GuestLayout;
 use crate::storage::block_device::BlockDeviceMount;
 
 /// Prepare container rootfs based on the initialization strategy.
 ///
-/// Handles three strategies:
+/// Handles four strategies:
 /// - Merged: Shared rootfs already exists (no-op)
 /// - Overlay: Bind-mount layers to diff dir, create overlayfs
 /// - Disk: Mount block device to shared rootfs
+/// - Image: Read-only image + upper disk, overlayfs on shared rootfs
 fn prepare_rootfs(
     rootfs_init: &RootfsInit,
     container_id: &str,
@@ -81,10 +91,348 @@ fn prepare_rootfs(
 
             Ok(())
         }
+        Some(rootfs_init::Strategy::Image(image)) => {
+            let filesystem = Filesystem::try_from(image.image_filesystem)
+                .ok()
+                .filter(|fs| matches!(fs, Filesystem::Erofs | Filesystem::Squashfs))
+                .ok_or_else(|| {
+                    format!(
+                        "Unsupported rootfs image filesystem: {}",
+                        image.image_filesystem
+                    )
+                })?;
+            info!(
+                "Rootfs strategy: image (image={}, filesystem={:?}, upper={})",
+                image.image_device, filesystem, image.upper_device
+            );
+
+            let container_layout = layout.container(container_id);
+            let lower_dir = container_layout.image_dir();
+            let upper_dir = container_layout.upper_dir();
+            let work_dir = container_layout.work_dir();
+
+            BlockDeviceMount::mount_read_only(
+                Path::new(&image.image_device),
+                &lower_dir,
+                filesystem,
+            )
+            .map_err(|e| format!("Failed to mount rootfs image: {}", e))?;
+
+            // Upper and work dirs must share a filesystem, so both live on
+            // the upper disk
+            BlockDeviceMount::mount(
+                Path::new(&image.upper_device),
+                &container_layout.overlayfs_dir(),
+                Filesystem::Ext4,
+                image.need_format,
+                false,
+            )
+            .map_err(|e| format!("Failed to mount rootfs upper disk: {}", e))?;
+
+            for dir in [&upper_dir, &work_dir] {
+                std::fs::create_dir_all(dir)
+                    .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+            }
+            std::fs::create_dir_all(shared_rootfs)
+                .map_err(|e| format!("Failed to create shared rootfs directory: {}", e))?;
+
+            let options = format!(
+                "lowerdir={},upperdir={},workdir={}",
+                lower_dir.display(),
+                upper_dir.display(),
+                work_dir.display()
+            );
+            mount(
+                Some("overlay"),
+                shared_rootfs,
+                Some("overlay"),
+                MsFlags::empty(),
+                Some(options.as_str()),
+            )
+            .map_err(|e| format!("Failed to mount rootfs overlay: {}", e))?;
+
+            Ok(())
+        }
         None => Err("Missing rootfs strategy in Container.Init request".to_string()),
     }
 }
 
+/// Guest path of a bind mount's source: the volume directory, or the file
+/// named by `sub_path` inside it (single-file volumes).
+fn volume_source(volume_dir: PathBuf, sub_path: &str) -> Result<PathBuf, String> {
+    if sub_path.is_empty() {
+        return Ok(volume_dir);
+    }
+    let mut components = Path::new(sub_path).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(name)), None) => Ok(volume_dir.join(name)),
+        _ => Err(format!("invalid volume sub_path: {}", sub_path)),
+    }
+}
+
+/// Resolve a container path under the bundle rootfs.
+///
+/// Rejects relative paths and `..` so a mount cannot land outside the rootfs.
+fn container_target(bundle_rootfs: &Path, destination: &str) -> Result<PathBuf, String> {
+    let path = Path::new(destination);
+    if !path.is_absolute() {
+        return Err(format!(
+            "mount destination must be absolute: {}",
+            destination
+        ));
+    }
+    let mut target = bundle_rootfs.to_path_buf();
+    for component in path.components() {
+        match component {
+            Component::RootDir | Component::CurDir => {}
+            Component::Normal(part) => target.push(part),
+            Component::ParentDir | Component::Prefix(_) => {
+                return Err(format!(
+                    "mount destination must not contain '..': {}",
+                    destination
+                ));
+            }
+        }
+    }
+    if target == bundle_rootfs {
+        return Err("mount destination cannot be the container root".to_string());
+    }
+    Ok(target)
+}
+
+/// Guest path of the zoneinfo file for `timezone`, to bind at
+/// /etc/localtime.
+///
+/// Prefers the container image's own tzdata, then the guest's. Returns
+/// `None` for an invalid name or when neither has the zone.
+fn zoneinfo_source(bundle_rootfs: &Path, timezone: &str) -> Option<PathBuf> {
+    let zone = Path::new(timezone);
+    if !zone.components().all(|c| matches!(c, Component::Normal(_))) {
+        return None;
+    }
+    let relative = Path::new("usr/share/zoneinfo").join(zone);
+
+    // Zone files may be symlinks; one that leads out of the image is not the
+    // image's tzdata
+    if let (Ok(root), Ok(path)) = (
+        bundle_rootfs.canonicalize(),
+        bundle_rootfs.join(&relative).canonicalize(),
+    ) {
+        if path.starts_with(root) && path.is_file() {
+            return Some(path);
+        }
+    }
+    let guest = Path::new("/").join(&relative);
+    guest.is_file().then_some(guest)
+}
+
+impl GuestServer {
+    /// Host PID of a running container's init process.
+    #[allow(clippy::result_large_err)]
+    async fn container_init_pid(&self, container_id: &str) -> Result<i32, Status> {
+        let container = self
+            .containers
+            .lock()
+            .await
+            .get(container_id)
+            .cloned()
+            .ok_or_else(|| {
+                Status::not_found(format!("container {} is not running", container_id))
+            })?;
+        let init_pid = container.lock().await.init_pid();
+        init_pid.ok_or_else(|| {
+            Status::failed_precondition(format!("container {} has no init process", container_id))
+        })
+    }
+
+    /// Mount a prepared shared rootfs into the container's bundle and start it.
+    ///
+    /// Volumes resolve under `volumes_of`, which differs from `container_id`
+    /// for a fresh-rootfs container borrowing its source's volumes.
+    /// `extra_mounts` are appended after the request's own mounts.
+    pub(crate) async fn launch_container(
+        &self,
+        container_id: &str,
+        init_req: &ContainerInitRequest,
+        config: ContainerConfig,
+        volumes_of: &str,
+        extra_mounts: Vec<UserMount>,
+    ) -> Result<(), String> {
+        let shared_rootfs = self.layout.shared().container(container_id).rootfs_dir();
+        let bundle_rootfs = self
+            .layout
+            .container_bundle_dir(container_id)
+            .join("rootfs");
+
+        // Bind mount shared rootfs to bundle rootfs
+        if let Err(e) = mount(
+            Some(shared_rootfs.as_path()),
+            &bundle_rootfs,
+            None::<&str>,
+            MsFlags::MS_BIND,
+            None::<&str>,
+        ) {
+            error!("Failed to bind mount rootfs: {}", e);
+            return Err(format!("Failed to bind mount rootfs: {}", e));
+        }
+
+        // Shared propagation lets Container.Mount reach the container's
+        // (slave) mount namespace after it has started.
+        if let Err(e) = mount(
+            None::<&str>,
+            &bundle_rootfs,
+            None::<&str>,
+            MsFlags::MS_SHARED,
+            None::<&str>,
+        ) {
+            warn!("Failed to make rootfs shared, hot mounts disabled: {}", e);
+        }
+
+        // Convert proto BindMount to UserMount for OCI spec
+        // Construct full source path from convention: /run/boxlite/shared/containers/{id}/volumes/{name}
+        let guest_layout = boxlite_shared::layout::SharedGuestLayout::new("/run/boxlite/shared");
+        let container_layout = guest_layout.container(volumes_of);
+
+        let user_mounts: Result<Vec<UserMount>, String> = init_req
+            .mounts
+            .iter()
+            .map(|m| {
+                let source =
+                    volume_source(container_layout.volume_dir(&m.volume_name), &m.sub_path)?;
+                Ok(UserMount {
+                    source: source.to_string_lossy().to_string(),
+                    destination: m.destination.clone(),
+                    read_only: m.read_only,
+                    chown: m.chown,
+                })
+            })
+            .collect();
+        let mut user_mounts = match user_mounts {
+            Ok(mut mounts) => {
+                mounts.extend(extra_mounts);
+                mounts
+            }
+            Err(reason) => {
+                error!("Invalid container mount: {}", reason);
+                return Err(reason);
+            }
+        };
+
+        // The user's own /etc/localtime mount wins over the timezone
+        if !init_req.timezone.is_empty()
+            && !user_mounts
+                .iter()
+                .any(|m| m.destination == "/etc/localtime")
+        {
+            match zoneinfo_source(&bundle_rootfs, &init_req.timezone) {
+                Some(source) => user_mounts.push(UserMount {
+                    source: source.to_string_lossy().to_string(),
+                    destination: "/etc/localtime".to_string(),
+                    read_only: true,
+                    chown: false,
+                }),
+                None => warn!(
+                    timezone = %init_req.timezone,
+                    "No zoneinfo for timezone in the image or guest, only TZ is set"
+                ),
+            }
+        }
+
+        let tmpfs_mounts: Vec<TmpfsMount> = init_req
+            .tmpfs
+            .iter()
+            .map(|t| TmpfsMount {
+                destination: t.destination.clone(),
+                size_bytes: (t.size_bytes > 0).then_some(t.size_bytes),
+                mode: (t.mode > 0).then_some(t.mode),
+            })
+            .collect();
+
+        if init_req.allow_fuse {
+            if let Err(e) = ensure_fuse_device() {
+                error!("FUSE setup failed: {}", e);
+                return Err(format!("FUSE setup failed: {}", e));
+            }
+        }
+
+        debug!(
+            entrypoint = ?config.entrypoint,
+            workdir = %config.workdir,
+            env_count = config.env.len(),
+            shared_rootfs = %shared_rootfs.display(),
+            bundle_rootfs = %bundle_rootfs.display(),
+            container_id = %container_id,
+            user_mounts_count = user_mounts.len(),
+            "Container configuration"
+        );
+
+        // Start container using OCI bundle rootfs
+        // Container init process uses pipe-based stdio to stay alive indefinitely.
+        // boxlite-guest holds the write-end of stdin pipe open, so init blocks on read() forever.
+        debug!(
+            container_id = %container_id,
+            entrypoint = ?config.entrypoint,
+            "Starting OCI container with pipe-based stdio"
+        );
+        let hostname = if init_req.hostname.is_empty() {
+            boxlite_shared::constants::container::DEFAULT_HOSTNAME
+        } else {
+            init_req.hostname.as_str()
+        };
+
+        match Container::start(
+            container_id,
+            hostname,
+            &bundle_rootfs,
+            config.entrypoint,
+            config.env,
+            &config.workdir,
+            &config.user,
+            user_mounts,
+            tmpfs_mounts,
+            init_req.allow_fuse,
+            init_req.capabilities.as_ref().map(|c| c.names.as_slice()),
+            Some(init_req.host_loopback_ip.as_str()).filter(|ip| !ip.is_empty()),
+        ) {
+            Ok(mut container) => {
+                debug!(container_id = %container_id, "Container started, checking if init process is running");
+                // Verify container init process is running
+                if !container.is_running() {
+                    // Gather diagnostic information (includes init stdout/stderr)
+                    let diagnostics = container.diagnose_exit();
+
+                    error!(
+                        "Container init process exited immediately after start. Diagnostics: {}",
+                        diagnostics
+                    );
+
+                    return Err(format!(
+                        "Container init process exited immediately. {}",
+                        diagnostics
+                    ));
+                }
+
+                info!(
+                    container_id = %container_id,
+                    "✅ Container started successfully and ready for exec"
+                );
+
+                // Store container in registry
+                self.containers.lock().await.insert(
+                    container_id.to_string(),
+                    std::sync::Arc::new(tokio::sync::Mutex::new(container)),
+                );
+
+                Ok(())
+            }
+            Err(e) => {
+                error!("Failed to start container: {}", e);
+                Err(format!("Failed to start container: {}", e))
+            }
+        }
+    }
+}
+
 #[tonic::async_trait]
 impl ContainerService for GuestServer {
     async fn init(
@@ -105,6 +453,16 @@ impl ContainerService for GuestServer {
             }));
         }
 
+        // Sidecars share the VM, so IDs must not collide
+        if self.containers.lock().await.contains_key(&container_id) {
+            error!("Container {} already initialized", container_id);
+            return Ok(Response::new(ContainerInitResponse {
+                result: Some(container_init_response::Result::Error(ContainerInitError {
+                    reason: format!("Container {} already initialized", container_id),
+                })),
+            }));
+        }
+
         // Check if guest is initialized
         {
             let init_state = self.init_state.lock().await;
@@ -122,6 +480,7 @@ impl ContainerService for GuestServer {
         // Extract container config
         let config = init_req
             .container_config
+            .clone()
             .ok_or_else(|| Status::invalid_argument("Missing container_config in Init request"))?;
 
         // Validate configuration
@@ -158,10 +517,11 @@ impl ContainerService for GuestServer {
         // Handle rootfs initialization based on strategy
         let rootfs_init = init_req
             .rootfs
+            .as_ref()
             .ok_or_else(|| Status::invalid_argument("Missing rootfs in Container.Init request"))?;
 
         if let Err(reason) =
-            prepare_rootfs(&rootfs_init, &container_id, &shared_rootfs, &self.layout)
+            prepare_rootfs(rootfs_init, &container_id, &shared_rootfs, &self.layout)
         {
             error!("{}", reason);
             return Ok(Response::new(ContainerInitResponse {
@@ -171,115 +531,256 @@ impl ContainerService for GuestServer {
             }));
         }
 
-        // Bind mount shared rootfs to bundle rootfs
-        if let Err(e) = mount(
-            Some(shared_rootfs.as_path()),
-            &bundle_rootfs,
-            None::<&str>,
-            MsFlags::MS_BIND,
-            None::<&str>,
-        ) {
-            error!("Failed to bind mount rootfs: {}", e);
-            return Ok(Response::new(ContainerInitResponse {
+        match self
+            .launch_container(&container_id, &init_req, config, &container_id, Vec::new())
+            .await
+        {
+            Ok(()) => {
+                self.container_requests
+                    .lock()
+                    .await
+                    .insert(container_id.clone(), init_req);
+                Ok(Response::new(ContainerInitResponse {
+                    result: Some(container_init_response::Result::Success(
+                        ContainerInitSuccess { container_id },
+                    )),
+                }))
+            }
+            Err(reason) => Ok(Response::new(ContainerInitResponse {
                 result: Some(container_init_response::Result::Error(ContainerInitError {
-                    reason: format!("Failed to bind mount rootfs: {}", e),
+                    reason,
                 })),
-            }));
+            })),
         }
+    }
 
-        // Convert proto BindMount to UserMount for OCI spec
-        // Construct full source path from convention: /run/boxlite/shared/containers/{id}/volumes/{name}
-        let guest_layout = boxlite_shared::layout::SharedGuestLayout::new("/run/boxlite/shared");
-        let container_layout = guest_layout.container(&container_id);
+    async fn mount(
+        &self,
+        request: Request<ContainerMountRequest>,
+    ) -> Result<Response<ContainerMountResponse>, Status> {
+        let req = request.into_inner();
+        let bind = req
+            .mount
+            .ok_or_else(|| Status::invalid_argument("Missing mount in Mount request"))?;
+        if !self.containers.lock().await.contains_key(&req.container_id) {
+            return Err(Status::not_found(format!(
+                "container {} is not running",
+                req.container_id
+            )));
+        }
 
-        let user_mounts: Vec<UserMount> = init_req
-            .mounts
-            .iter()
-            .map(|m| {
-                let source = container_layout.volume_dir(&m.volume_name);
-                UserMount {
-                    source: source.to_string_lossy().to_string(),
-                    destination: m.destination.clone(),
-                    read_only: m.read_only,
-                }
-            })
-            .collect();
+        let source = volume_source(
+            self.layout
+                .shared()
+                .container(&req.container_id)
+                .volume_dir(&bind.volume_name),
+            &bind.sub_path,
+        )
+        .map_err(Status::invalid_argument)?;
+        if !source.exists() {
+            return Err(Status::failed_precondition(format!(
+                "volume {} is not visible in the guest at {}",
+                bind.volume_name,
+                source.display()
+            )));
+        }
+        let bundle_rootfs = self
+            .layout
+            .container_bundle_dir(&req.container_id)
+            .join("rootfs");
+        let target = container_target(&bundle_rootfs, &bind.destination)
+            .map_err(Status::invalid_argument)?;
+
+        let created = if source.is_dir() {
+            std::fs::create_dir_all(&target)
+        } else {
+            target
+                .parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .and_then(|_| {
+                    std::fs::OpenOptions::new()
+                        .create(true)
+                        .truncate(false)
+                        .write(true)
+                        .open(&target)
+                        .map(|_| ())
+                })
+        };
+        created.map_err(|e| {
+            Status::internal(format!("Failed to create {}: {}", bind.destination, e))
+        })?;
+        mount(
+            Some(source.as_path()),
+            &target,
+            None::<&str>,
+            MsFlags::MS_BIND | MsFlags::MS_REC,
+            None::<&str>,
+        )
+        .map_err(|e| Status::internal(format!("Failed to mount {}: {}", bind.destination, e)))?;
+
+        if bind.read_only {
+            if let Err(e) = mount(
+                None::<&str>,
+                &target,
+                None::<&str>,
+                MsFlags::MS_BIND | MsFlags::MS_REMOUNT | MsFlags::MS_RDONLY,
+                None::<&str>,
+            ) {
+                let _ = umount2(&target, MntFlags::MNT_DETACH);
+                return Err(Status::internal(format!(
+                    "Failed to make {} read-only: {}",
+                    bind.destination, e
+                )));
+            }
+        }
+
+        info!(
+            container_id = %req.container_id,
+            volume = %bind.volume_name,
+            destination = %bind.destination,
+            read_only = bind.read_only,
+            "Mounted volume into running container"
+        );
+        Ok(Response::new(ContainerMountResponse {}))
+    }
+
+    async fn unmount(
+        &self,
+        request: Request<ContainerUnmountRequest>,
+    ) -> Result<Response<ContainerUnmountResponse>, Status> {
+        let req = request.into_inner();
+        let bundle_rootfs = self
+            .layout
+            .container_bundle_dir(&req.container_id)
+            .join("rootfs");
+        let target =
+            container_target(&bundle_rootfs, &req.destination).map_err(Status::invalid_argument)?;
+
+        umount2(&target, MntFlags::MNT_DETACH).map_err(|e| {
+            Status::internal(format!("Failed to unmount {}: {}", req.destination, e))
+        })?;
+
+        info!(
+            container_id = %req.container_id,
+            destination = %req.destination,
+            "Unmounted volume from running container"
+        );
+        Ok(Response::new(ContainerUnmountResponse {}))
+    }
+
+    async fn processes(
+        &self,
+        request: Request<ContainerProcessesRequest>,
+    ) -> Result<Response<ContainerProcessesResponse>, Status> {
+        let req = request.into_inner();
+        let init_pid = self.container_init_pid(&req.container_id).await?;
+
+        let rootfs = self
+            .layout
+            .shared()
+            .container(&req.container_id)
+            .rootfs_dir();
+        let entries = tokio::task::spawn_blocking(move || list_processes(init_pid, &rootfs))
+            .await
+            .map_err(|e| Status::internal(format!("task join error: {}", e)))?
+            .map_err(|e| Status::internal(e.to_string()))?;
 
         debug!(
-            entrypoint = ?config.entrypoint,
-            workdir = %config.workdir,
-            env_count = config.env.len(),
-            shared_rootfs = %shared_rootfs.display(),
-            bundle_rootfs = %bundle_rootfs.display(),
-            container_id = %container_id,
-            user_mounts_count = user_mounts.len(),
-            "Container configuration"
+            container_id = %req.container_id,
+            count = entries.len(),
+            "Listed container processes"
         );
+        Ok(Response::new(ContainerProcessesResponse {
+            processes: entries
+                .into_iter()
+                .map(|entry| ProcessInfo {
+                    pid: entry.pid,
+                    ppid: entry.ppid,
+                    uid: entry.uid,
+                    user: entry.user,
+                    rss_bytes: entry.rss_bytes,
+                    command: entry.command,
+                })
+                .collect(),
+        }))
+    }
+
+    async fn listening_ports(
+        &self,
+        request: Request<ListeningPortsRequest>,
+    ) -> Result<Response<ListeningPortsResponse>, Status> {
+        let req = request.into_inner();
+        let init_pid = self.container_init_pid(&req.container_id).await?;
+
+        let sockets = tokio::task::spawn_blocking(move || listening_sockets(init_pid))
+            .await
+            .map_err(|e| Status::internal(format!("task join error: {}", e)))?
+            .map_err(|e| Status::internal(e.to_string()))?;
 
-        // Start container using OCI bundle rootfs
-        // Container init process uses pipe-based stdio to stay alive indefinitely.
-        // boxlite-guest holds the write-end of stdin pipe open, so init blocks on read() forever.
         debug!(
-            container_id = %container_id,
-            entrypoint = ?config.entrypoint,
-            "Starting OCI container with pipe-based stdio"
+            container_id = %req.container_id,
+            count = sockets.len(),
+            "Listed listening sockets"
         );
-        match Container::start(
-            &container_id,
-            &bundle_rootfs,
-            config.entrypoint,
-            config.env,
-            &config.workdir,
-            &config.user,
-            user_mounts,
-        ) {
-            Ok(mut container) => {
-                debug!(container_id = %container_id, "Container started, checking if init process is running");
-                // Verify container init process is running
-                if !container.is_running() {
-                    // Gather diagnostic information (includes init stdout/stderr)
-                    let diagnostics = container.diagnose_exit();
+        Ok(Response::new(ListeningPortsResponse {
+            sockets: sockets
+                .into_iter()
+                .map(|socket| ListeningSocket {
+                    protocol: match socket.protocol {
+                        SocketProtocol::Tcp => ProtoSocketProtocol::Tcp,
+                        SocketProtocol::Udp => ProtoSocketProtocol::Udp,
+                    } as i32,
+                    address: socket.address.ip().to_string(),
+                    port: socket.address.port() as u32,
+                    pid: socket.pid,
+                    command: socket.command,
+                })
+                .collect(),
+        }))
+    }
+}
 
-                    error!(
-                        "Container init process exited immediately after start. Diagnostics: {}",
-                        diagnostics
-                    );
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-                    return Ok(Response::new(ContainerInitResponse {
-                        result: Some(container_init_response::Result::Error(ContainerInitError {
-                            reason: format!(
-                                "Container init process exited immediately. {}",
-                                diagnostics
-                            ),
-                        })),
-                    }));
-                }
+    #[test]
+    fn test_container_target_stays_under_rootfs() {
+        let rootfs = Path::new("/run/boxlite/containers/c1/rootfs");
+        assert_eq!(
+            container_target(rootfs, "/data/./in").unwrap(),
+            rootfs.join("data/in")
+        );
+        assert!(container_target(rootfs, "data").is_err());
+        assert!(container_target(rootfs, "/data/../../etc").is_err());
+        assert!(container_target(rootfs, "/").is_err());
+    }
 
-                info!(
-                    container_id = %container_id,
-                    "✅ Container started successfully and ready for exec"
-                );
+    #[test]
+    fn test_zoneinfo_source_prefers_image() {
+        let rootfs = tempfile::tempdir().unwrap();
+        let zone_dir = rootfs.path().join("usr/share/zoneinfo/Europe");
+        std::fs::create_dir_all(&zone_dir).unwrap();
+        std::fs::write(zone_dir.join("Berlin"), b"TZif").unwrap();
 
-                // Store container in registry
-                self.containers.lock().await.insert(
-                    container_id.clone(),
-                    std::sync::Arc::new(tokio::sync::Mutex::new(container)),
-                );
+        assert_eq!(
+            zoneinfo_source(rootfs.path(), "Europe/Berlin"),
+            Some(zone_dir.join("Berlin").canonicalize().unwrap())
+        );
+        assert_eq!(zoneinfo_source(rootfs.path(), "../../etc/passwd"), None);
+        assert_eq!(zoneinfo_source(rootfs.path(), "/etc/passwd"), None);
+    }
 
-                Ok(Response::new(ContainerInitResponse {
-                    result: Some(container_init_response::Result::Success(
-                        ContainerInitSuccess { container_id },
-                    )),
-                }))
-            }
-            Err(e) => {
-                error!("Failed to start container: {}", e);
-                Ok(Response::new(ContainerInitResponse {
-                    result: Some(container_init_response::Result::Error(ContainerInitError {
-                        reason: format!("Failed to start container: {}", e),
-                    })),
-                }))
-            }
-        }
+    #[test]
+    fn test_volume_source_sub_path() {
+        let dir = PathBuf::from("/run/boxlite/shared/containers/c1/volumes/uservol0");
+        assert_eq!(volume_source(dir.clone(), "").unwrap(), dir);
+        assert_eq!(
+            volume_source(dir.clone(), "config.yaml").unwrap(),
+            dir.join("config.yaml")
+        );
+        assert!(volume_source(dir.clone(), "../secret").is_err());
+        assert!(volume_source(dir.clone(), "a/b").is_err());
+        assert!(volume_source(dir, "/etc/passwd").is_err());
     }
 }
@@ -7,7 +7,8 @@
 use crate::container::Container;
 use crate::service::exec::exec_handle::{ExecHandle, PtyConfig};
 use async_trait::async_trait;
-use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+use boxlite_shared::constants::exec_error;
+use boxlite_shared::errors::BoxliteError;
 use boxlite_shared::ExecRequest;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -16,17 +17,83 @@ use tokio::sync::Mutex;
 #[async_trait]
 pub trait Executor: Send + Sync {
     /// Spawn process from ExecRequest.
-    async fn spawn(&self, req: &ExecRequest) -> BoxliteResult<ExecHandle>;
+    async fn spawn(&self, req: &ExecRequest) -> Result<ExecHandle, SpawnError>;
+}
+
+/// A process that could not be started, and why.
+#[derive(Debug)]
+pub struct SpawnError {
+    /// `exec_error` reason reported to the host
+    pub reason: &'static str,
+    pub message: String,
+}
+
+impl SpawnError {
+    pub fn new(reason: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            reason,
+            message: message.into(),
+        }
+    }
+
+    /// `program` could not be executed; `err` is the errno of the exec.
+    ///
+    /// Only meaningful once the working directory is known to exist: a
+    /// failed chdir reports the same `ENOENT` as a missing program.
+    fn exec(program: &str, err: std::io::Error) -> Self {
+        let reason = match err.raw_os_error().map(nix::errno::Errno::from_raw) {
+            Some(nix::errno::Errno::ENOENT) => exec_error::COMMAND_NOT_FOUND,
+            Some(
+                nix::errno::Errno::EACCES | nix::errno::Errno::EISDIR | nix::errno::Errno::ENOEXEC,
+            ) => exec_error::NOT_EXECUTABLE,
+            _ => exec_error::SPAWN_FAILED,
+        };
+        Self::new(reason, format!("Failed to spawn '{}': {}", program, err))
+    }
+}
+
+impl std::fmt::Display for SpawnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<BoxliteError> for SpawnError {
+    fn from(err: BoxliteError) -> Self {
+        Self::new(exec_error::SPAWN_FAILED, err.to_string())
+    }
+}
+
+/// Fail unless `workdir` is empty (inherit) or an existing directory.
+fn check_workdir(workdir: &str) -> Result<(), SpawnError> {
+    if workdir.is_empty() || std::path::Path::new(workdir).is_dir() {
+        return Ok(());
+    }
+    Err(SpawnError::new(
+        exec_error::SPAWN_FAILED,
+        format!("working directory '{}' does not exist", workdir),
+    ))
 }
 
 /// Executes commands inside OCI container.
 pub struct ContainerExecutor {
     container: Arc<Mutex<Container>>,
+    /// Resolved `ExecRequest::user`; None keeps the container's user
+    user: Option<(u32, u32)>,
 }
 
 impl ContainerExecutor {
     pub fn new(container: Arc<Mutex<Container>>) -> Self {
-        Self { container }
+        Self {
+            container,
+            user: None,
+        }
+    }
+
+    /// Run as `(uid, gid)` instead of the container's user.
+    pub fn with_user(mut self, user: (u32, u32)) -> Self {
+        self.user = Some(user);
+        self
     }
 
     /// Get a clone of the container reference for status checking.
@@ -37,7 +104,7 @@ impl ContainerExecutor {
 
 #[async_trait]
 impl Executor for ContainerExecutor {
-    async fn spawn(&self, req: &ExecRequest) -> BoxliteResult<ExecHandle> {
+    async fn spawn(&self, req: &ExecRequest) -> Result<ExecHandle, SpawnError> {
         // Build the command while holding the lock
         let cmd = {
             let container = self.container.lock().await;
@@ -46,7 +113,12 @@ impl Executor for ContainerExecutor {
                 .cmd()
                 .program(&req.program)
                 .args(&req.args)
-                .envs(req.env.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+                .envs(req.env.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+                .privileged(req.privileged);
+
+            if let Some(user) = self.user {
+                cmd = cmd.user(user);
+            }
 
             if !req.workdir.is_empty() {
                 cmd = cmd.current_dir(&req.workdir);
@@ -73,7 +145,8 @@ pub struct GuestExecutor;
 
 #[async_trait]
 impl Executor for GuestExecutor {
-    async fn spawn(&self, req: &ExecRequest) -> BoxliteResult<ExecHandle> {
+    async fn spawn(&self, req: &ExecRequest) -> Result<ExecHandle, SpawnError> {
+        check_workdir(&req.workdir)?;
         if let Some(tty) = &req.tty {
             let config = PtyConfig {
                 rows: tty.rows as u16,
@@ -89,7 +162,7 @@ impl Executor for GuestExecutor {
 }
 
 /// Spawn process with pipes (standard mode).
-fn spawn_with_pipes(req: &ExecRequest) -> BoxliteResult<ExecHandle> {
+fn spawn_with_pipes(req: &ExecRequest) -> Result<ExecHandle, SpawnError> {
     use nix::unistd::Pid;
     use std::os::unix::io::{AsRawFd, FromRawFd};
     use tokio::process::Command;
@@ -120,9 +193,7 @@ fn spawn_with_pipes(req: &ExecRequest) -> BoxliteResult<ExecHandle> {
         cmd.stderr(std::process::Stdio::from_raw_fd(stderr_write.as_raw_fd()));
     }
 
-    let child = cmd
-        .spawn()
-        .map_err(|e| BoxliteError::Internal(format!("Failed to spawn '{}': {}", req.program, e)))?;
+    let child = cmd.spawn().map_err(|e| SpawnError::exec(&req.program, e))?;
 
     let pid = child
         .id()
@@ -143,7 +214,7 @@ fn spawn_with_pipes(req: &ExecRequest) -> BoxliteResult<ExecHandle> {
 }
 
 /// Spawn process with PTY (interactive mode).
-fn spawn_with_pty(req: &ExecRequest, config: PtyConfig) -> BoxliteResult<ExecHandle> {
+fn spawn_with_pty(req: &ExecRequest, config: PtyConfig) -> Result<ExecHandle, SpawnError> {
     use nix::pty::{openpty, OpenptyResult, Winsize};
     use nix::unistd::{dup, Pid};
     use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
@@ -207,9 +278,7 @@ fn spawn_with_pty(req: &ExecRequest, config: PtyConfig) -> BoxliteResult<ExecHan
         });
     }
 
-    let child = cmd
-        .spawn()
-        .map_err(|e| BoxliteError::Internal(format!("Failed to spawn '{}': {}", req.program, e)))?;
+    let child = cmd.spawn().map_err(|e| SpawnError::exec(&req.program, e))?;
 
     let pid = child.id();
 
@@ -242,3 +311,35 @@ fn spawn_with_pty(req: &ExecRequest, config: PtyConfig) -> BoxliteResult<ExecHan
 
     Ok(handle)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errno(errno: nix::errno::Errno) -> std::io::Error {
+        std::io::Error::from_raw_os_error(errno as i32)
+    }
+
+    #[test]
+    fn test_exec_errors_name_the_program() {
+        use nix::errno::Errno;
+
+        let reason = |err| SpawnError::exec("prog", errno(err)).reason;
+        assert_eq!(reason(Errno::ENOENT), exec_error::COMMAND_NOT_FOUND);
+        assert_eq!(reason(Errno::EACCES), exec_error::NOT_EXECUTABLE);
+        assert_eq!(reason(Errno::EISDIR), exec_error::NOT_EXECUTABLE);
+        assert_eq!(reason(Errno::EMFILE), exec_error::SPAWN_FAILED);
+    }
+
+    #[tokio::test]
+    async fn test_missing_workdir_is_not_a_missing_command() {
+        let req = ExecRequest {
+            program: "true".to_string(),
+            workdir: "/nonexistent/workdir".to_string(),
+            ..Default::default()
+        };
+        let err = GuestExecutor.spawn(&req).await.err().unwrap();
+        assert_eq!(err.reason, exec_error::SPAWN_FAILED);
+        assert!(err.message.contains("/nonexistent/workdir"));
+    }
+}
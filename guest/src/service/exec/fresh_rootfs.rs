@@ -0,0 +1,186 @@
+//! Executions on a throwaway copy of a container's rootfs.
+//!
+//! An execution started with `fresh_rootfs` does not run in its container
+//! but in a sibling started for it from the same init request: same
+//! volumes, network and settings, on an overlay of the container's rootfs
+//! whose upper layer lives in a tmpfs. Writes outside volumes go away with
+//! the sibling when the execution exits, so one run of a reused box leaves
+//! nothing behind for the next.
+//!
+//! The sibling's init is the agent binary parked in `pause()`, because the
+//! image need not have anything that idles.
+
+use crate::container::{UserMount, PAUSE_ARG, PAUSE_PATH};
+use crate::service::exec::state::ExecutionState;
+use crate::service::server::GuestServer;
+use boxlite_shared::{constants::executor as executor_const, ExecRequest};
+use nix::mount::{mount, umount2, MntFlags, MsFlags};
+use tracing::{info, warn};
+
+/// Start a fresh-rootfs sibling of the request's container and point the
+/// request at it.
+///
+/// Returns the sibling's ID, which [`start_cleanup_watcher`] or [`remove`]
+/// must be given once the execution is over or failed to spawn.
+pub(super) async fn create(server: &GuestServer, req: &mut ExecRequest) -> Result<String, String> {
+    let source_id = req
+        .env
+        .get(executor_const::ENV_VAR)
+        .and_then(|s| s.strip_prefix(executor_const::CONTAINER_KEY))
+        .and_then(|rest| rest.strip_prefix('='))
+        .filter(|id| !id.is_empty())
+        .ok_or("fresh_rootfs is only supported for container executions")?
+        .to_string();
+
+    let mut init_req = server
+        .container_requests
+        .lock()
+        .await
+        .get(&source_id)
+        .cloned()
+        .ok_or_else(|| format!("Container not found: {}", source_id))?;
+    let mut config = init_req
+        .container_config
+        .clone()
+        .ok_or("Missing container_config in Init request")?;
+    config.entrypoint = vec![PAUSE_PATH.to_string(), PAUSE_ARG.to_string()];
+
+    let short_id = uuid::Uuid::new_v4().simple().to_string();
+    let container_id = format!("{}-run-{}", source_id, &short_id[..12]);
+    init_req.container_id = container_id.clone();
+
+    let agent =
+        std::env::current_exe().map_err(|e| format!("Failed to locate the agent binary: {}", e))?;
+    let pause_mount = UserMount {
+        source: agent.to_string_lossy().to_string(),
+        destination: PAUSE_PATH.to_string(),
+        read_only: true,
+        chown: false,
+    };
+
+    let launched = match mount_rootfs(server, &source_id, &container_id) {
+        Ok(()) => {
+            server
+                .launch_container(
+                    &container_id,
+                    &init_req,
+                    config,
+                    &source_id,
+                    vec![pause_mount],
+                )
+                .await
+        }
+        Err(reason) => Err(reason),
+    };
+    if let Err(reason) = launched {
+        unmount_rootfs(server, &container_id);
+        return Err(reason);
+    }
+
+    info!(
+        source_id = %source_id,
+        container_id = %container_id,
+        "Started fresh-rootfs container"
+    );
+    req.env.insert(
+        executor_const::ENV_VAR.to_string(),
+        format!("{}={}", executor_const::CONTAINER_KEY, container_id),
+    );
+    Ok(container_id)
+}
+
+/// Remove the sibling once the execution's process has exited.
+pub(super) fn start_cleanup_watcher(
+    server: GuestServer,
+    exec_state: ExecutionState,
+    container_id: String,
+) {
+    tokio::spawn(async move {
+        if let Err(e) = exec_state.wait_process().await {
+            warn!(container_id = %container_id, error = %e, "lost the fresh-rootfs process");
+        }
+        remove(&server, &container_id).await;
+    });
+}
+
+/// Stop a sibling and drop its rootfs.
+pub(super) async fn remove(server: &GuestServer, container_id: &str) {
+    const SHUTDOWN_TIMEOUT_MS: u64 = 1000;
+
+    let container = server.containers.lock().await.remove(container_id);
+    let Some(container) = container else {
+        return;
+    };
+    if let Err(e) = container.lock().await.shutdown(SHUTDOWN_TIMEOUT_MS) {
+        warn!(container_id = %container_id, error = %e, "Failed to shut down fresh-rootfs container");
+    }
+
+    // Dropping the container removes its bundle, so nothing may still be
+    // mounted in there
+    let bundle_rootfs = server.layout.container(container_id).rootfs_dir();
+    if let Err(e) = umount2(&bundle_rootfs, MntFlags::MNT_DETACH) {
+        warn!(container_id = %container_id, error = %e, "Failed to unmount bundle rootfs");
+    }
+    unmount_rootfs(server, container_id);
+    drop(container);
+    info!(container_id = %container_id, "Removed fresh-rootfs container");
+}
+
+/// Overlay the source container's rootfs with an upper layer in a tmpfs.
+fn mount_rootfs(server: &GuestServer, source_id: &str, container_id: &str) -> Result<(), String> {
+    let lower_dir = server.layout.shared().container(source_id).rootfs_dir();
+    let shared_rootfs = server.layout.shared().container(container_id).rootfs_dir();
+    let container_layout = server.layout.container(container_id);
+    let upper_dir = container_layout.upper_dir();
+    let work_dir = container_layout.work_dir();
+
+    let overlayfs_dir = container_layout.overlayfs_dir();
+    std::fs::create_dir_all(&overlayfs_dir)
+        .map_err(|e| format!("Failed to create {}: {}", overlayfs_dir.display(), e))?;
+    // Upper and work dirs must share a filesystem, so both live on the tmpfs
+    mount(
+        Some("tmpfs"),
+        &overlayfs_dir,
+        Some("tmpfs"),
+        MsFlags::empty(),
+        Some("mode=0755"),
+    )
+    .map_err(|e| format!("Failed to mount fresh rootfs upper tmpfs: {}", e))?;
+
+    for dir in [&upper_dir, &work_dir, &shared_rootfs] {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    std::fs::create_dir_all(container_layout.rootfs_dir())
+        .map_err(|e| format!("Failed to create bundle rootfs directory: {}", e))?;
+
+    let options = format!(
+        "lowerdir={},upperdir={},workdir={}",
+        lower_dir.display(),
+        upper_dir.display(),
+        work_dir.display()
+    );
+    mount(
+        Some("overlay"),
+        &shared_rootfs,
+        Some("overlay"),
+        MsFlags::empty(),
+        Some(options.as_str()),
+    )
+    .map_err(|e| format!("Failed to mount fresh rootfs overlay: {}", e))
+}
+
+/// Undo [`mount_rootfs`], ignoring whatever was never mounted.
+fn unmount_rootfs(server: &GuestServer, container_id: &str) {
+    let shared = server.layout.shared().container(container_id);
+    let _ = umount2(&shared.rootfs_dir(), MntFlags::MNT_DETACH);
+    let _ = umount2(
+        &server.layout.container(container_id).overlayfs_dir(),
+        MntFlags::MNT_DETACH,
+    );
+    if let Err(e) = std::fs::remove_dir_all(shared.root()) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            warn!(container_id = %container_id, error = %e, "Failed to remove fresh rootfs dir");
+        }
+    }
+}
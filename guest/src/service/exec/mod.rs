@@ -10,7 +10,8 @@
 //!
 //! - **Protocol Layer** (mod.rs): gRPC service implementation
 //! - **Executor Layer** (executor.rs): Process spawning abstraction
-//! - **Lifecycle Layer** (timeout.rs): Process management
+//! - **Lifecycle Layer** (timeout.rs, stop_on_exit.rs, fresh_rootfs.rs):
+//!   Process management
 //! - **State Layer** (registry.rs, state.rs): Execution state
 //! - **Types** (types.rs): Shared types
 //!
@@ -18,20 +19,22 @@
 
 #[cfg(target_os = "linux")]
 pub mod exec_handle;
-pub(in crate::service) mod executor;
+pub(crate) mod executor;
+mod fresh_rootfs;
 pub(in crate::service) mod registry;
 mod state;
+mod stop_on_exit;
 mod timeout;
 
 // Re-export trait so container module can implement it
 pub(crate) use state::InitHealthCheck;
 
-use crate::service::exec::executor::{ContainerExecutor, GuestExecutor};
+use crate::service::exec::executor::{ContainerExecutor, GuestExecutor, SpawnError};
 use crate::service::server::GuestServer;
 use boxlite_shared::{
-    constants::executor as executor_const, AttachRequest, ExecError, ExecOutput, ExecRequest,
-    ExecResponse, ExecStdin, Execution, KillRequest, KillResponse, ResizeTtyRequest,
-    ResizeTtyResponse, SendInputAck, WaitRequest, WaitResponse,
+    constants::exec_error, constants::executor as executor_const, AttachRequest, ExecError,
+    ExecOutput, ExecRequest, ExecResponse, ExecStdin, Execution, KillRequest, KillResponse,
+    ResizeTtyRequest, ResizeTtyResponse, SendInputAck, WaitRequest, WaitResponse,
 };
 use futures::stream::Stream;
 use std::pin::Pin;
@@ -63,6 +66,18 @@ impl Execution for GuestServer {
             )));
         }
 
+        if !self.registry.has_room().await {
+            warn!(execution_id = %execution_id, "too many executions running");
+            return Ok(Response::new(error_response(
+                execution_id,
+                exec_error::TOO_MANY_EXECUTIONS,
+                &format!(
+                    "{} executions are already running in the box",
+                    registry::MAX_RUNNING
+                ),
+            )));
+        }
+
         // Spawn execution
         match spawn_execution(self, execution_id, req).await {
             Ok(resp) => Ok(Response::new(resp)),
@@ -144,6 +159,7 @@ impl Execution for GuestServer {
 
         // Wait for process to exit
         let exit_status = state.wait_process().await?;
+        self.registry.finish(&exec_id).await;
 
         let (exit_code, signal, error_message) = match exit_status {
             ExitStatus::Code(code) => {
@@ -180,12 +196,23 @@ impl Execution for GuestServer {
             }
         };
 
+        // OOM victims die by SIGKILL; a shell whose child was the victim
+        // exits with 128 + SIGKILL. The kill is attributed to this execution
+        // if one was logged while it ran.
+        let sigkill = nix::sys::signal::Signal::SIGKILL as i32;
+        let oom_killed =
+            (signal == sigkill || exit_code == 128 + sigkill) && state.oom_kill_seen().await;
+        if oom_killed {
+            warn!(execution_id = %exec_id, "Process killed by the kernel: out of memory");
+        }
+
         Ok(Response::new(WaitResponse {
             exit_code,
             signal,
             timed_out: false,
             duration_ms: 0,
             error_message,
+            oom_killed,
         }))
     }
 
@@ -294,16 +321,36 @@ impl Execution for GuestServer {
 async fn spawn_execution(
     server: &GuestServer,
     execution_id: String,
-    req: ExecRequest,
+    mut req: ExecRequest,
 ) -> Result<ExecResponse, ExecResponse> {
     let started_at_ms = now_ms();
+    // Every codec is supported, so the requested one is used as-is
+    let compression = req.compression();
 
-    // Step 1: Spawn process using executor selected by BOXLITE_EXECUTOR env var
-    let (child, container_ref) = spawn_with_executor(server, &req, &execution_id).await?;
+    // Step 1: Move to a throwaway copy of the container (if requested)
+    let fresh_container = if req.fresh_rootfs {
+        let id = fresh_rootfs::create(server, &mut req)
+            .await
+            .map_err(|e| spawn_error(&execution_id, e))?;
+        Some(id)
+    } else {
+        None
+    };
+
+    // Step 2: Spawn process using executor selected by BOXLITE_EXECUTOR env var
+    let (child, container_ref) = match spawn_with_executor(server, &req, &execution_id).await {
+        Ok(spawned) => spawned,
+        Err(response) => {
+            if let Some(id) = &fresh_container {
+                fresh_rootfs::remove(server, id).await;
+            }
+            return Err(response);
+        }
+    };
 
     let pid = child.pid().as_raw() as u32;
 
-    // Step 2: Create execution state and register
+    // Step 3: Create execution state and register
     // If running inside a container, pass the init health checker for death detection
     let state = match container_ref {
         Some(container) => {
@@ -311,26 +358,38 @@ async fn spawn_execution(
             state::ExecutionState::new_with_init_health(child, health)
         }
         None => state::ExecutionState::new(child),
-    };
+    }
+    .with_compression(compression);
     server
         .registry
         .register(execution_id.clone(), state.clone())
         .await;
 
-    // Step 3: Start timeout watcher (if requested)
+    // Step 4: Start timeout watcher (if requested)
     if req.timeout_ms > 0 {
         timeout::start_timeout_watcher(
-            state,
+            state.clone(),
             execution_id.clone(),
             std::time::Duration::from_millis(req.timeout_ms),
         );
     }
 
+    // Step 5: Drop the throwaway container with its process
+    if let Some(id) = fresh_container {
+        fresh_rootfs::start_cleanup_watcher(server.clone(), state.clone(), id);
+    }
+
+    // Step 6: Stop the box with its main process (if requested)
+    if req.stop_box_on_exit {
+        stop_on_exit::start_exit_watcher(server.clone(), state, execution_id.clone());
+    }
+
     Ok(ExecResponse {
         execution_id,
         pid,
         started_at_ms,
         error: None,
+        compression: compression as i32,
     })
 }
 
@@ -343,19 +402,18 @@ fn error_response(id: String, reason: &str, detail: &str) -> ExecResponse {
             reason: reason.to_string(),
             detail: detail.to_string(),
         }),
+        compression: 0,
     }
 }
 
 fn spawn_error(exec_id: &str, err: String) -> ExecResponse {
-    ExecResponse {
-        execution_id: exec_id.to_string(),
-        pid: 0,
-        started_at_ms: 0,
-        error: Some(ExecError {
-            reason: "spawn_failed".to_string(),
-            detail: err,
-        }),
-    }
+    error_response(exec_id.to_string(), exec_error::SPAWN_FAILED, &err)
+}
+
+/// Like [`spawn_error`], with the reason the executor found, so the host
+/// can report exit code 127 or 126 for a missing or non-executable program.
+fn program_spawn_error(exec_id: &str, err: SpawnError) -> ExecResponse {
+    error_response(exec_id.to_string(), err.reason, &err.message)
 }
 
 fn now_ms() -> u64 {
@@ -394,10 +452,16 @@ async fn spawn_with_executor(
         Some(executor_const::GUEST) | None | Some("") => {
             // Guest executor (explicit or default)
             debug!(execution_id = %execution_id, "Using GuestExecutor");
+            if !req.user.is_empty() {
+                return Err(spawn_error(
+                    execution_id,
+                    "user is only supported for container executions".to_string(),
+                ));
+            }
             let handle = GuestExecutor
                 .spawn(req)
                 .await
-                .map_err(|e| spawn_error(execution_id, e.to_string()))?;
+                .map_err(|e| program_spawn_error(execution_id, e))?;
             Ok((handle, None))
         }
         Some(s) if s.starts_with(executor_const::CONTAINER_KEY) => {
@@ -427,7 +491,17 @@ async fn spawn_with_executor(
                     )
                 })?
             };
-            let executor = ContainerExecutor::new(container_arc);
+            // Resolved here so an unknown user is not taken for a missing
+            // program
+            let mut executor = ContainerExecutor::new(container_arc.clone());
+            if !req.user.is_empty() {
+                let user = container_arc
+                    .lock()
+                    .await
+                    .resolve_user(&req.user)
+                    .map_err(|e| spawn_error(execution_id, e.to_string()))?;
+                executor = executor.with_user(user);
+            }
             let container_ref = executor.container_ref();
             let handle = match executor.spawn(req).await {
                 Ok(h) => h,
@@ -448,7 +522,7 @@ async fn spawn_with_executor(
                         }
                         return Err(spawn_error(execution_id, msg));
                     }
-                    return Err(spawn_error(execution_id, e.to_string()));
+                    return Err(program_spawn_error(execution_id, e));
                 }
             };
             Ok((handle, Some(container_ref)))
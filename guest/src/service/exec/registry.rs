@@ -2,45 +2,95 @@
 //!
 //! Manages the state of all active executions, providing thread-safe access
 //! to execution metadata, I/O channels, and completion status.
+//!
+//! The registry is bounded. Every running execution has a waiter blocked in
+//! `waitpid` on tokio's blocking pool (512 threads), so at most
+//! [`MAX_RUNNING`] may run at once; past that, new executions are refused
+//! instead of queueing behind waiters that may never return. Finished
+//! executions are kept for late `Attach`/`Kill` calls and evicted oldest first
+//! beyond [`MAX_FINISHED`].
 
 use crate::service::exec::state::ExecutionState;
 use nix::sys::signal::{kill, Signal};
 use nix::unistd::Pid;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tracing::{info, warn};
 
+/// Executions that may run at once, leaving blocking threads for other work.
+pub(crate) const MAX_RUNNING: usize = 384;
+
+/// Finished executions kept for lookup.
+pub(crate) const MAX_FINISHED: usize = 1024;
+
 /// Registry of active executions.
 ///
 /// Thread-safe registry that stores execution state and provides
 /// methods for registration, lookup, and lifecycle management.
 #[derive(Clone)]
 pub(crate) struct ExecutionRegistry {
-    executions: Arc<Mutex<HashMap<String, ExecutionState>>>,
+    inner: Arc<Mutex<Inner>>,
+}
+
+struct Inner {
+    executions: HashMap<String, ExecutionState>,
+    /// Finished execution IDs, oldest first
+    finished: VecDeque<String>,
+}
+
+impl Inner {
+    fn running(&self) -> usize {
+        self.executions.len() - self.finished.len()
+    }
 }
 
 impl ExecutionRegistry {
     /// Create new registry.
     pub fn new() -> Self {
         Self {
-            executions: Arc::new(Mutex::new(HashMap::new())),
+            inner: Arc::new(Mutex::new(Inner {
+                executions: HashMap::new(),
+                finished: VecDeque::new(),
+            })),
         }
     }
 
     /// Check if execution exists.
     pub async fn exists(&self, exec_id: &str) -> bool {
-        self.executions.lock().await.contains_key(exec_id)
+        self.inner.lock().await.executions.contains_key(exec_id)
+    }
+
+    /// Whether another execution may start.
+    pub async fn has_room(&self) -> bool {
+        self.inner.lock().await.running() < MAX_RUNNING
     }
 
     /// Get execution state.
     pub async fn get(&self, exec_id: &str) -> Option<ExecutionState> {
-        self.executions.lock().await.get(exec_id).cloned()
+        self.inner.lock().await.executions.get(exec_id).cloned()
     }
 
     /// Register new execution state.
     pub async fn register(&self, exec_id: String, state: ExecutionState) {
-        self.executions.lock().await.insert(exec_id, state);
+        self.inner.lock().await.executions.insert(exec_id, state);
+    }
+
+    /// Mark an execution finished once its process has been reaped.
+    ///
+    /// Evicts the oldest finished executions beyond [`MAX_FINISHED`].
+    pub async fn finish(&self, exec_id: &str) {
+        let mut inner = self.inner.lock().await;
+        if !inner.executions.contains_key(exec_id) || inner.finished.iter().any(|id| id == exec_id)
+        {
+            return;
+        }
+        inner.finished.push_back(exec_id.to_string());
+        while inner.finished.len() > MAX_FINISHED {
+            if let Some(oldest) = inner.finished.pop_front() {
+                inner.executions.remove(&oldest);
+            }
+        }
     }
 
     /// Gracefully shutdown all running executions.
@@ -51,8 +101,8 @@ impl ExecutionRegistry {
         let mut pids_to_wait: Vec<(String, i32)> = Vec::new();
 
         {
-            let executions = self.executions.lock().await;
-            for (exec_id, state) in executions.iter() {
+            let inner = self.inner.lock().await;
+            for (exec_id, state) in inner.executions.iter() {
                 if let Some(pid) = state.get_pid().await {
                     let pid_i32 = pid as i32;
                     // Check if process is still alive (signal 0 doesn't send anything)
@@ -1,8 +1,8 @@
-use crate::service::exec::exec_handle::ExecHandle;
-use boxlite_shared::ExecOutput;
+use crate::service::exec::exec_handle::{ExecHandle, ExitStatus};
+use boxlite_shared::{ExecOutput, StreamCompression};
 use std::os::unix::io::AsRawFd;
 use std::sync::Arc;
-use tokio::sync::{mpsc, Mutex};
+use tokio::sync::{mpsc, Mutex, OnceCell};
 use tokio::task::JoinHandle;
 use tonic::Status;
 use tracing::info;
@@ -42,6 +42,12 @@ struct Inner {
 #[derive(Clone)]
 pub(crate) struct ExecutionState {
     inner: Arc<Mutex<Inner>>,
+    /// Codec for stdout/stderr chunks, negotiated in the Exec request
+    compression: StreamCompression,
+    /// OOM kill count when the execution started
+    oom_kills_at_start: u64,
+    /// Exit status, once reaped (shared by every waiter)
+    exit: Arc<OnceCell<ExitStatus>>,
 }
 
 impl ExecutionState {
@@ -56,6 +62,9 @@ impl ExecutionState {
 
         Self {
             inner: Arc::new(Mutex::new(inner)),
+            compression: StreamCompression::None,
+            oom_kills_at_start: crate::oom::kill_count(),
+            exit: Arc::new(OnceCell::new()),
         }
     }
 
@@ -76,9 +85,18 @@ impl ExecutionState {
 
         Self {
             inner: Arc::new(Mutex::new(inner)),
+            compression: StreamCompression::None,
+            oom_kills_at_start: crate::oom::kill_count(),
+            exit: Arc::new(OnceCell::new()),
         }
     }
 
+    /// Compress output chunks with `compression`.
+    pub(super) fn with_compression(mut self, compression: StreamCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
     /// Check if the container init process died.
     ///
     /// Returns `Some(diagnosis)` if init is dead, `None` if alive or no health checker.
@@ -92,6 +110,11 @@ impl ExecutionState {
         Some(health.diagnose_exit())
     }
 
+    /// Whether the kernel OOM-killed a process since the execution started.
+    pub(super) async fn oom_kill_seen(&self) -> bool {
+        crate::oom::killed_since(self.oom_kills_at_start).await
+    }
+
     /// Get PID for execution.
     #[allow(dead_code)] // API completeness
     pub async fn get_pid(&self) -> Option<u32> {
@@ -154,11 +177,13 @@ impl ExecutionState {
 
     /// Wait for process to exit.
     ///
-    /// Gets pid from handle and waits using waitpid.
-    pub async fn wait_process(
-        &self,
-    ) -> Result<crate::service::exec::exec_handle::ExitStatus, Status> {
-        use crate::service::exec::exec_handle::ExitStatus;
+    /// Gets pid from handle and waits using waitpid. The process is reaped
+    /// once; concurrent and later callers get the same status.
+    pub async fn wait_process(&self) -> Result<ExitStatus, Status> {
+        self.exit.get_or_try_init(|| self.reap()).await.copied()
+    }
+
+    async fn reap(&self) -> Result<ExitStatus, Status> {
         use nix::sys::wait::{waitpid, WaitStatus};
 
         // Get pid from handle
@@ -195,7 +220,7 @@ impl ExecutionState {
         &self,
         exec_id: &str,
     ) -> Result<mpsc::Receiver<Result<ExecOutput, Status>>, Status> {
-        use boxlite_shared::{exec_output, Stderr, Stdout};
+        use boxlite_shared::{compression, exec_output, Stderr, Stdout};
         use futures::StreamExt;
 
         let (tx, rx) = mpsc::channel(100);
@@ -221,15 +246,18 @@ impl ExecutionState {
 
         // Spawn forwarding tasks
         let mut tasks = Vec::new();
+        let compression = self.compression;
 
         // Spawn stdout forwarding task
         let exec_id_string = exec_id.to_string();
-        if let Some(mut stdout) = stdout {
+        if let Some(stdout) = stdout {
             let tx = tx.clone();
             let handle = tokio::spawn(async move {
+                let mut stdout = coalesce(stdout, compression);
                 while let Some(chunk) = stdout.next().await {
+                    let (data, compressed) = compression::encode_chunk(compression, chunk);
                     let msg = ExecOutput {
-                        event: Some(exec_output::Event::Stdout(Stdout { data: chunk })),
+                        event: Some(exec_output::Event::Stdout(Stdout { data, compressed })),
                     };
                     if tx.send(Ok(msg)).await.is_err() {
                         break;
@@ -242,12 +270,14 @@ impl ExecutionState {
 
         // Spawn stderr forwarding task
         let exec_id_string = exec_id.to_string();
-        if let Some(mut stderr) = stderr {
+        if let Some(stderr) = stderr {
             let tx = tx.clone();
             let handle = tokio::spawn(async move {
+                let mut stderr = coalesce(stderr, compression);
                 while let Some(chunk) = stderr.next().await {
+                    let (data, compressed) = compression::encode_chunk(compression, chunk);
                     let msg = ExecOutput {
-                        event: Some(exec_output::Event::Stderr(Stderr { data: chunk })),
+                        event: Some(exec_output::Event::Stderr(Stderr { data, compressed })),
                     };
                     if tx.send(Ok(msg)).await.is_err() {
                         break;
@@ -319,3 +349,27 @@ impl ExecutionState {
         Ok(())
     }
 }
+
+/// Output reads merged into one chunk when already buffered (64 KiB of 1 KiB reads).
+const COALESCE_CHUNKS: usize = 64;
+
+/// Merge output chunks that are already available, so each compressed frame
+/// covers more than one pipe read. Never waits for more output.
+fn coalesce<S>(
+    stream: S,
+    compression: StreamCompression,
+) -> std::pin::Pin<Box<dyn futures::Stream<Item = Vec<u8>> + Send>>
+where
+    S: futures::Stream<Item = Vec<u8>> + Send + 'static,
+{
+    use futures::StreamExt;
+
+    if compression == StreamCompression::None {
+        return Box::pin(stream);
+    }
+    Box::pin(
+        stream
+            .ready_chunks(COALESCE_CHUNKS)
+            .map(|chunks| chunks.concat()),
+    )
+}
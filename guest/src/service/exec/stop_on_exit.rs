@@ -0,0 +1,32 @@
+//! Box lifetime bound to one execution.
+//!
+//! An execution started with `stop_box_on_exit` is the box's main process:
+//! once it exits, the agent stops everything else, syncs filesystems and
+//! exits, which ends the VM. The host needs no attached client for this,
+//! so detached `auto_remove` boxes go away on their own.
+
+use crate::service::exec::state::ExecutionState;
+use crate::service::server::GuestServer;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Time an attached client gets to collect the exit status and the last
+/// output before the agent exits.
+const EXIT_GRACE: Duration = Duration::from_secs(1);
+
+/// Start watching the main process of the box.
+pub(super) fn start_exit_watcher(server: GuestServer, exec_state: ExecutionState, exec_id: String) {
+    tokio::spawn(async move {
+        match exec_state.wait_process().await {
+            Ok(status) => {
+                info!(execution_id = %exec_id, ?status, "main process exited, stopping the box")
+            }
+            Err(e) => {
+                warn!(execution_id = %exec_id, error = %e, "lost the main process, stopping the box")
+            }
+        }
+        tokio::time::sleep(EXIT_GRACE).await;
+        server.stop_workloads().await;
+        std::process::exit(0);
+    });
+}
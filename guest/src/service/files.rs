@@ -2,11 +2,13 @@
 //! Files service implementation.
 //!
 //! Provides tar-based upload/download between host and the single container
-//! running inside the guest.
+//! running inside the guest, and change notifications for container paths.
 
 use crate::service::server::GuestServer;
+use crate::service::watch;
 use boxlite_shared::{
     files_server::Files, DownloadChunk, DownloadRequest, UploadChunk, UploadResponse,
+    WatchPathRequest,
 };
 use std::path::{Path, PathBuf};
 use tokio::fs::File;
@@ -255,6 +257,35 @@ impl Files for GuestServer {
 
         Ok(Response::new(ReceiverStream::new(rx)))
     }
+
+    type WatchPathStream = watch::WatchStream;
+
+    async fn watch_path(
+        &self,
+        request: Request<WatchPathRequest>,
+    ) -> Result<Response<Self::WatchPathStream>, Status> {
+        let req = request.into_inner();
+        if req.path.is_empty() {
+            return Err(Status::invalid_argument("path is required"));
+        }
+        let container_id = self
+            .resolve_container_id(req.container_id.as_str())
+            .await
+            .map_err(Status::failed_precondition)?;
+
+        let rootfs = self.layout.shared().container(&container_id).rootfs_dir();
+        let target = self.container_rootfs(&container_id, &req.path)?;
+        let stream = watch::watch(rootfs, target, req.recursive)?;
+
+        info!(
+            path = %req.path,
+            recursive = req.recursive,
+            container_id = %container_id,
+            "watch started"
+        );
+
+        Ok(Response::new(stream))
+    }
 }
 
 impl GuestServer {
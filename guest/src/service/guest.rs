@@ -1,13 +1,15 @@
 //! Guest service implementation.
 //!
-//! Handles guest initialization and management (Init, Ping, Shutdown RPCs).
+//! Handles guest initialization and management (Init, Ping, Shutdown,
+//! ReclaimMemory RPCs).
 
 use crate::service::server::GuestServer;
 use boxlite_shared::{
     guest_init_response, Guest as GuestService, GuestInitError, GuestInitRequest,
-    GuestInitResponse, GuestInitSuccess, PingRequest, PingResponse, ShutdownRequest,
-    ShutdownResponse,
+    GuestInitResponse, GuestInitSuccess, PingRequest, PingResponse, ReclaimMemoryRequest,
+    ReclaimMemoryResponse, ShutdownRequest, ShutdownResponse,
 };
+use std::time::Instant;
 use tonic::{Request, Response, Status};
 use tracing::{debug, error, info};
 
@@ -18,6 +20,9 @@ impl GuestService for GuestServer {
     /// This must be called first after connection. It:
     /// 1. Mounts all volumes (virtiofs + block devices)
     /// 2. Configures network (if specified)
+    /// 3. Points core dumps at the shared cores directory (if specified)
+    ///
+    /// Replies with the guest's boot phases, including steps 1 and 2.
     ///
     /// Note: Rootfs setup is handled by Container.Init.
     async fn init(
@@ -41,6 +46,7 @@ impl GuestService for GuestServer {
         // Step 1: Mount all volumes (virtiofs + block devices)
         // Empty mount_point = guest determines path from tag
         info!("Mounting {} volumes", req.volumes.len());
+        let started = Instant::now();
         if let Err(e) = crate::storage::mount_volumes(&req.volumes) {
             error!("Failed to mount volumes: {}", e);
             return Ok(Response::new(GuestInitResponse {
@@ -50,7 +56,10 @@ impl GuestService for GuestServer {
             }));
         }
 
+        let mount_time = started.elapsed();
+
         // Step 2: Configure network (if specified)
+        let started = Instant::now();
         if let Some(network) = req.network {
             info!("Configuring network interface: {}", network.interface);
             if let Err(e) = crate::network::configure_network_from_config(
@@ -69,12 +78,40 @@ impl GuestService for GuestServer {
             }
         }
 
+        let network_time = started.elapsed();
+
+        // Step 3: Collect core dumps (if requested)
+        if let Some(core_dumps) = req.core_dumps {
+            info!(
+                max_bytes = core_dumps.max_bytes,
+                max_files = core_dumps.max_files,
+                "Enabling core dump collection"
+            );
+            if let Err(e) = crate::coredump::configure(&core_dumps) {
+                error!("Failed to enable core dumps: {}", e);
+                return Ok(Response::new(GuestInitResponse {
+                    result: Some(guest_init_response::Result::Error(GuestInitError {
+                        reason: format!("Failed to enable core dumps: {}", e),
+                    })),
+                }));
+            }
+        }
+
         // Mark as initialized
         init_state.initialized = true;
 
-        info!("✅ Guest initialized successfully");
+        let boot_phases = crate::boot::phases(mount_time, network_time);
+        info!(
+            kernel_ms = boot_phases.kernel_ms,
+            agent_startup_ms = boot_phases.agent_startup_ms,
+            mount_ms = boot_phases.mount_ms,
+            network_ms = boot_phases.network_ms,
+            "✅ Guest initialized successfully"
+        );
         Ok(Response::new(GuestInitResponse {
-            result: Some(guest_init_response::Result::Success(GuestInitSuccess {})),
+            result: Some(guest_init_response::Result::Success(GuestInitSuccess {
+                boot_phases: Some(boot_phases),
+            })),
         }))
     }
 
@@ -82,6 +119,7 @@ impl GuestService for GuestServer {
         debug!("Received ping request");
         Ok(Response::new(PingResponse {
             version: env!("CARGO_PKG_VERSION").to_string(),
+            protocol_version: boxlite_shared::constants::protocol::VERSION,
         }))
     }
 
@@ -90,7 +128,34 @@ impl GuestService for GuestServer {
         _request: Request<ShutdownRequest>,
     ) -> Result<Response<ShutdownResponse>, Status> {
         info!("Received shutdown request - graceful shutdown starting");
+        self.stop_workloads().await;
+        info!("Graceful shutdown complete");
+        Ok(Response::new(ShutdownResponse {}))
+    }
+
+    /// Free as much guest memory as possible by dropping caches and
+    /// compacting free memory.
+    async fn reclaim_memory(
+        &self,
+        _request: Request<ReclaimMemoryRequest>,
+    ) -> Result<Response<ReclaimMemoryResponse>, Status> {
+        debug!("Received reclaim memory request");
+        let stats = tokio::task::spawn_blocking(crate::memory::reclaim)
+            .await
+            .map_err(|e| Status::internal(format!("reclaim task failed: {}", e)))?
+            .map_err(|e| Status::internal(e.to_string()))?;
 
+        Ok(Response::new(ReclaimMemoryResponse {
+            reclaimed_bytes: stats.reclaimed_bytes,
+            free_bytes: stats.free_bytes,
+        }))
+    }
+}
+
+impl GuestServer {
+    /// Stop every execution and container and flush filesystems, so the VM
+    /// can go away without losing data.
+    pub(crate) async fn stop_workloads(&self) {
         // Step 1: Gracefully shutdown all running executions
         const EXEC_SHUTDOWN_TIMEOUT_MS: u64 = 1000;
         info!("Stopping running executions...");
@@ -115,8 +180,5 @@ impl GuestService for GuestServer {
         unsafe {
             nix::libc::sync();
         }
-
-        info!("Graceful shutdown complete");
-        Ok(Response::new(ShutdownResponse {}))
     }
 }
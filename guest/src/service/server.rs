@@ -1,7 +1,7 @@
 use crate::container::Container;
 use crate::layout::GuestLayout;
 use crate::service::exec::registry::ExecutionRegistry;
-use boxlite_shared::{BoxliteResult, Transport};
+use boxlite_shared::{BoxliteResult, ContainerInitRequest, Transport};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -23,6 +23,7 @@ pub(crate) struct GuestInitState {
 /// - Guest: Agent initialization and management
 /// - Container: OCI container lifecycle
 /// - Execution: Command execution with bidirectional streaming
+#[derive(Clone)]
 pub(crate) struct GuestServer {
     /// Guest filesystem layout
     pub layout: GuestLayout,
@@ -33,6 +34,9 @@ pub(crate) struct GuestServer {
     /// Container registry: container_id -> Container
     pub containers: Arc<Mutex<HashMap<String, Arc<Mutex<Container>>>>>,
 
+    /// Init requests of started containers, replayed for fresh-rootfs execs
+    pub container_requests: Arc<Mutex<HashMap<String, ContainerInitRequest>>>,
+
     /// Execution registry for tracking running executions
     pub registry: ExecutionRegistry,
 }
@@ -47,6 +51,7 @@ impl GuestServer {
             layout,
             init_state: Arc::new(Mutex::new(GuestInitState::default())),
             containers: Arc::new(Mutex::new(HashMap::new())),
+            container_requests: Arc::new(Mutex::new(HashMap::new())),
             registry: ExecutionRegistry::new(),
         }
     }
@@ -185,6 +190,7 @@ impl GuestServer {
 ///
 /// The connection itself is the signal - no data needs to be sent.
 async fn notify_host_ready(notify_uri: Option<String>) -> BoxliteResult<()> {
+    crate::boot::mark_agent_ready();
     let uri = match notify_uri {
         Some(uri) => uri,
         None => {
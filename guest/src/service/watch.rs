@@ -0,0 +1,327 @@
+#![cfg(target_os = "linux")]
+//! Path watching for the Files service.
+//!
+//! Translates inotify events under a container rootfs path into `FsEvent`s.
+//! Recursive watches add one inotify watch per directory, including
+//! directories created after the watch started.
+
+use boxlite_shared::{FsEvent, FsEventKind};
+use nix::sys::inotify::{AddWatchFlags, InitFlags, Inotify, InotifyEvent, WatchDescriptor};
+use std::collections::HashMap;
+use std::io;
+use std::os::fd::{AsFd, AsRawFd, RawFd};
+use std::path::{Path, PathBuf};
+use tokio::io::unix::AsyncFd;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::Status;
+use tracing::debug;
+
+/// Events buffered for a slow client before the kernel queue takes over
+/// (and eventually overflows, which the client sees as `OVERFLOW`).
+const CHANNEL_CAPACITY: usize = 256;
+
+pub(crate) type WatchStream = ReceiverStream<Result<FsEvent, Status>>;
+
+fn dir_flags() -> AddWatchFlags {
+    AddWatchFlags::IN_CREATE
+        | AddWatchFlags::IN_CLOSE_WRITE
+        | AddWatchFlags::IN_DELETE
+        | AddWatchFlags::IN_MOVED_FROM
+        | AddWatchFlags::IN_MOVED_TO
+        | AddWatchFlags::IN_DELETE_SELF
+        | AddWatchFlags::IN_MOVE_SELF
+        | AddWatchFlags::IN_ONLYDIR
+}
+
+fn file_flags() -> AddWatchFlags {
+    AddWatchFlags::IN_CLOSE_WRITE | AddWatchFlags::IN_DELETE_SELF | AddWatchFlags::IN_MOVE_SELF
+}
+
+/// `AsyncFd` needs `AsRawFd`; nix's `Inotify` only implements `AsFd`.
+struct InotifyFd(Inotify);
+
+impl AsRawFd for InotifyFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0.as_fd().as_raw_fd()
+    }
+}
+
+/// Maps watch descriptors back to the paths they watch.
+struct Watcher {
+    rootfs: PathBuf,
+    recursive: bool,
+    root: WatchDescriptor,
+    root_is_dir: bool,
+    paths: HashMap<WatchDescriptor, PathBuf>,
+}
+
+impl Watcher {
+    /// Path as seen from inside the container.
+    fn container_path(&self, path: &Path) -> String {
+        let rel = path.strip_prefix(&self.rootfs).unwrap_or(path);
+        Path::new("/").join(rel).to_string_lossy().into_owned()
+    }
+
+    fn event(&self, kind: FsEventKind, path: &Path, is_dir: bool, cookie: u32) -> FsEvent {
+        FsEvent {
+            kind: kind as i32,
+            path: self.container_path(path),
+            is_dir,
+            cookie,
+        }
+    }
+
+    fn watch_dir(&mut self, inotify: &Inotify, dir: &Path) -> nix::Result<()> {
+        let wd = inotify.add_watch(dir, dir_flags())?;
+        // A directory moved within the tree keeps its descriptor; re-adding
+        // it updates the path
+        self.paths.insert(wd, dir.to_path_buf());
+        Ok(())
+    }
+
+    /// Watch every directory below `dir`.
+    ///
+    /// With `report`, entries found are reported as created: they appeared
+    /// before the watch on their parent existed. Entries created while the
+    /// scan runs may be reported twice.
+    fn watch_subtree(
+        &mut self,
+        inotify: &Inotify,
+        dir: &Path,
+        report: bool,
+        out: &mut Vec<FsEvent>,
+    ) {
+        let mut stack = vec![dir.to_path_buf()];
+        while let Some(dir) = stack.pop() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+                if report {
+                    out.push(self.event(FsEventKind::Created, &path, is_dir, 0));
+                }
+                if is_dir {
+                    match self.watch_dir(inotify, &path) {
+                        Ok(()) => stack.push(path),
+                        Err(e) => debug!(path = %path.display(), "skipping watch: {}", e),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Translate one inotify event. Returns true once the watch is over.
+    fn translate(
+        &mut self,
+        inotify: &Inotify,
+        event: InotifyEvent,
+        out: &mut Vec<FsEvent>,
+    ) -> bool {
+        let mask = event.mask;
+        if mask.contains(AddWatchFlags::IN_Q_OVERFLOW) {
+            out.push(FsEvent {
+                kind: FsEventKind::Overflow as i32,
+                ..Default::default()
+            });
+            return false;
+        }
+        if mask.contains(AddWatchFlags::IN_IGNORED) {
+            self.paths.remove(&event.wd);
+            return event.wd == self.root;
+        }
+
+        let Some(dir) = self.paths.get(&event.wd).cloned() else {
+            return false;
+        };
+        let path = match &event.name {
+            Some(name) => dir.join(name),
+            None => dir,
+        };
+        let is_dir = mask.contains(AddWatchFlags::IN_ISDIR);
+
+        if mask.intersects(AddWatchFlags::IN_DELETE_SELF | AddWatchFlags::IN_MOVE_SELF) {
+            if event.wd != self.root {
+                // The parent reports the removal or move
+                return false;
+            }
+            if mask.contains(AddWatchFlags::IN_DELETE_SELF) {
+                out.push(self.event(FsEventKind::Removed, &path, self.root_is_dir, 0));
+            }
+            return true;
+        }
+
+        if mask.contains(AddWatchFlags::IN_CREATE) {
+            out.push(self.event(FsEventKind::Created, &path, is_dir, 0));
+            if is_dir && self.recursive && self.watch_dir(inotify, &path).is_ok() {
+                self.watch_subtree(inotify, &path, true, out);
+            }
+        } else if mask.contains(AddWatchFlags::IN_CLOSE_WRITE) {
+            out.push(self.event(FsEventKind::Modified, &path, false, 0));
+        } else if mask.contains(AddWatchFlags::IN_DELETE) {
+            out.push(self.event(FsEventKind::Removed, &path, is_dir, 0));
+        } else if mask.contains(AddWatchFlags::IN_MOVED_FROM) {
+            out.push(self.event(FsEventKind::MovedFrom, &path, is_dir, event.cookie));
+        } else if mask.contains(AddWatchFlags::IN_MOVED_TO) {
+            out.push(self.event(FsEventKind::MovedTo, &path, is_dir, event.cookie));
+            if is_dir && self.recursive && self.watch_dir(inotify, &path).is_ok() {
+                self.watch_subtree(inotify, &path, false, out);
+            }
+        }
+        false
+    }
+}
+
+/// Start watching `target` (a path under `rootfs`).
+///
+/// Events are streamed until the client drops the stream or `target` is
+/// removed or moved. A file replaced by rename (as editors save) ends its
+/// watch; watch the parent directory to follow such files.
+#[allow(clippy::result_large_err)]
+pub(crate) fn watch(
+    rootfs: PathBuf,
+    target: PathBuf,
+    recursive: bool,
+) -> Result<WatchStream, Status> {
+    let inotify = Inotify::init(InitFlags::IN_NONBLOCK | InitFlags::IN_CLOEXEC)
+        .map_err(|e| Status::internal(format!("inotify init failed: {}", e)))?;
+
+    let is_dir = target.is_dir();
+    let flags = if is_dir { dir_flags() } else { file_flags() };
+    let root = inotify.add_watch(&target, flags).map_err(|e| match e {
+        nix::errno::Errno::ENOENT => Status::not_found("watch path does not exist"),
+        nix::errno::Errno::ENOSPC => {
+            Status::resource_exhausted("inotify watch limit reached (fs.inotify.max_user_watches)")
+        }
+        e => Status::internal(format!("failed to watch path: {}", e)),
+    })?;
+
+    let mut watcher = Watcher {
+        rootfs,
+        recursive: recursive && is_dir,
+        root,
+        root_is_dir: is_dir,
+        paths: HashMap::from([(root, target.clone())]),
+    };
+    if watcher.recursive {
+        watcher.watch_subtree(&inotify, &target, false, &mut Vec::new());
+    }
+
+    let fd = AsyncFd::new(InotifyFd(inotify))
+        .map_err(|e| Status::internal(format!("failed to register inotify fd: {}", e)))?;
+
+    let (tx, rx) = mpsc::channel::<Result<FsEvent, Status>>(CHANNEL_CAPACITY);
+    tokio::spawn(async move {
+        let mut out = Vec::new();
+        loop {
+            let mut guard = tokio::select! {
+                _ = tx.closed() => break,
+                guard = fd.readable() => match guard {
+                    Ok(guard) => guard,
+                    Err(e) => {
+                        let _ = tx.send(Err(Status::internal(format!("inotify poll failed: {}", e)))).await;
+                        break;
+                    }
+                },
+            };
+            let events =
+                match guard.try_io(|fd| fd.get_ref().0.read_events().map_err(io::Error::from)) {
+                    Ok(Ok(events)) => events,
+                    Ok(Err(e)) => {
+                        let _ = tx
+                            .send(Err(Status::internal(format!("inotify read failed: {}", e))))
+                            .await;
+                        break;
+                    }
+                    Err(_would_block) => continue,
+                };
+
+            let mut done = false;
+            for event in events {
+                done |= watcher.translate(&fd.get_ref().0, event, &mut out);
+            }
+            for event in out.drain(..) {
+                if tx.send(Ok(event)).await.is_err() {
+                    return;
+                }
+            }
+            if done {
+                break;
+            }
+        }
+        debug!(path = %target.display(), "watch ended");
+    });
+
+    Ok(ReceiverStream::new(rx))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio_stream::StreamExt;
+
+    async fn next(stream: &mut WatchStream) -> FsEvent {
+        tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .expect("timed out waiting for event")
+            .expect("stream ended")
+            .expect("watch error")
+    }
+
+    #[tokio::test]
+    async fn test_watch_recursive() {
+        let rootfs = tempfile::tempdir().unwrap();
+        let app = rootfs.path().join("app");
+        std::fs::create_dir_all(app.join("existing")).unwrap();
+
+        let mut stream = watch(rootfs.path().to_path_buf(), app.clone(), true).unwrap();
+
+        std::fs::write(app.join("existing/a.txt"), b"hi").unwrap();
+        let event = next(&mut stream).await;
+        assert_eq!(event.kind(), FsEventKind::Created);
+        assert_eq!(event.path, "/app/existing/a.txt");
+        let event = next(&mut stream).await;
+        assert_eq!(event.kind(), FsEventKind::Modified);
+        assert_eq!(event.path, "/app/existing/a.txt");
+
+        // Directories created later are watched too
+        std::fs::create_dir(app.join("new")).unwrap();
+        let event = next(&mut stream).await;
+        assert_eq!(event.kind(), FsEventKind::Created);
+        assert!(event.is_dir);
+        std::fs::remove_file(app.join("existing/a.txt")).unwrap();
+        std::fs::File::create(app.join("new/b.txt")).unwrap();
+        let mut seen = Vec::new();
+        while !seen.iter().any(|e: &FsEvent| e.path == "/app/new/b.txt") {
+            seen.push(next(&mut stream).await);
+        }
+        assert!(seen
+            .iter()
+            .any(|e| e.kind() == FsEventKind::Removed && e.path == "/app/existing/a.txt"));
+    }
+
+    #[tokio::test]
+    async fn test_watch_ends_when_path_removed() {
+        let rootfs = tempfile::tempdir().unwrap();
+        let file = rootfs.path().join("config.json");
+        std::fs::write(&file, b"{}").unwrap();
+
+        let mut stream = watch(rootfs.path().to_path_buf(), file.clone(), false).unwrap();
+        std::fs::write(&file, b"{\"a\":1}").unwrap();
+        assert_eq!(next(&mut stream).await.kind(), FsEventKind::Modified);
+
+        std::fs::remove_file(&file).unwrap();
+        let event = next(&mut stream).await;
+        assert_eq!(event.kind(), FsEventKind::Removed);
+        assert_eq!(event.path, "/config.json");
+        let end = tokio::time::timeout(Duration::from_secs(5), stream.next()).await;
+        assert!(end.unwrap().is_none());
+
+        let missing = rootfs.path().join("missing");
+        let err = watch(rootfs.path().to_path_buf(), missing, false).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::NotFound);
+    }
+}
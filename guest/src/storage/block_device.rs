@@ -110,6 +110,64 @@ impl BlockDeviceMount {
         Ok(())
     }
 
+    /// Mount a read-only image filesystem (EROFS, SquashFS).
+    ///
+    /// No format, resize or ownership fix: the image is immutable and its
+    /// ownership was set when it was built on the host.
+    pub fn mount_read_only(
+        device: &Path,
+        mount_point: &Path,
+        filesystem: Filesystem,
+    ) -> BoxliteResult<()> {
+        let fs_name = filesystem_to_str(filesystem);
+
+        tracing::info!(
+            "Mounting read-only block device: {} → {} (filesystem={:?})",
+            device.display(),
+            mount_point.display(),
+            filesystem
+        );
+
+        if !device.exists() {
+            return Err(BoxliteError::Storage(format!(
+                "Block device not found: {}",
+                device.display()
+            )));
+        }
+
+        std::fs::create_dir_all(mount_point).map_err(|e| {
+            BoxliteError::Storage(format!(
+                "Failed to create mount point {}: {}",
+                mount_point.display(),
+                e
+            ))
+        })?;
+
+        mount(
+            Some(device),
+            mount_point,
+            Some(fs_name),
+            MsFlags::MS_RDONLY | MsFlags::MS_NOATIME | MsFlags::MS_NODIRATIME,
+            None::<&str>,
+        )
+        .map_err(|e| {
+            BoxliteError::Storage(format!(
+                "Failed to mount {} ({}) read-only to {}: {}",
+                device.display(),
+                fs_name,
+                mount_point.display(),
+                e
+            ))
+        })?;
+
+        tracing::info!(
+            "Mounted read-only block device: {} → {}",
+            device.display(),
+            mount_point.display()
+        );
+        Ok(())
+    }
+
     /// Log a glance view of mounted filesystem contents (2 levels deep).
     fn log_filesystem_contents(mount_point: &Path) -> BoxliteResult<()> {
         tracing::trace!("Filesystem structure at {}:", mount_point.display());
@@ -260,10 +318,12 @@ impl BlockDeviceMount {
     }
 }
 
-/// Convert Filesystem enum to string for mkfs command.
+/// Convert Filesystem enum to string for mkfs and mount.
 fn filesystem_to_str(fs: Filesystem) -> &'static str {
     match fs {
         Filesystem::Ext4 => "ext4",
+        Filesystem::Erofs => "erofs",
+        Filesystem::Squashfs => "squashfs",
         Filesystem::Unspecified => "ext4", // Default to ext4
     }
 }
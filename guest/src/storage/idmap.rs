@@ -0,0 +1,176 @@
+//! Idmapped mounts for virtiofs volumes.
+//!
+//! With a root-run VMM, virtiofs creates files with the guest caller's ids,
+//! so anything container root writes to a volume is owned by root on the
+//! host. An idmapped mount swaps root with a host uid/gid: files created by
+//! container root land as `uid:gid` on the host, and host-root-owned files
+//! appear as `uid:gid` in the container. Other ids pass through unchanged.
+//!
+//! Needs a guest kernel that supports idmapped virtiofs mounts (6.12+).
+
+use std::fs::File;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::path::Path;
+
+use boxlite_shared::errors::{BoxliteError, BoxliteResult};
+use nix::libc;
+use nix::sched::CloneFlags;
+use nix::sys::signal::{kill, Signal};
+use nix::sys::wait::waitpid;
+
+// Not exported by libc yet (linux/mount.h).
+const OPEN_TREE_CLONE: libc::c_uint = 1;
+const MOVE_MOUNT_F_EMPTY_PATH: libc::c_uint = 0x4;
+const MOUNT_ATTR_IDMAP: u64 = 0x0010_0000;
+
+#[repr(C)]
+struct MountAttr {
+    attr_set: u64,
+    attr_clr: u64,
+    propagation: u64,
+    userns_fd: u64,
+}
+
+/// Highest id a user namespace mapping can cover (`(u32)-1` is invalid).
+const ID_LIMIT: u64 = u32::MAX as u64;
+
+/// Build a `uid_map`/`gid_map` that swaps 0 and `host_id` and maps every
+/// other id to itself.
+fn swap_id_map(host_id: u32) -> String {
+    let host_id = host_id as u64;
+    if host_id == 0 {
+        return format!("0 0 {}\n", ID_LIMIT);
+    }
+    let mut map = format!("0 {host_id} 1\n{host_id} 0 1\n");
+    if host_id > 1 {
+        map.push_str(&format!("1 1 {}\n", host_id - 1));
+    }
+    if host_id + 1 < ID_LIMIT {
+        map.push_str(&format!(
+            "{0} {0} {1}\n",
+            host_id + 1,
+            ID_LIMIT - host_id - 1
+        ));
+    }
+    map
+}
+
+/// Replace the mount at `mount_point` with an idmapped clone of itself that
+/// swaps root with `uid:gid`.
+pub fn shift_mount(mount_point: &Path, uid: u32, gid: u32) -> BoxliteResult<()> {
+    let userns = swapped_user_namespace(uid, gid)?;
+    let path = std::ffi::CString::new(mount_point.as_os_str().as_encoded_bytes())
+        .map_err(|e| BoxliteError::Storage(format!("Invalid mount point: {}", e)))?;
+    let empty = c"";
+
+    // SAFETY: plain syscalls on valid C strings and fds we own.
+    let tree = unsafe {
+        libc::syscall(
+            libc::SYS_open_tree,
+            libc::AT_FDCWD,
+            path.as_ptr(),
+            OPEN_TREE_CLONE | libc::O_CLOEXEC as libc::c_uint,
+        )
+    };
+    if tree < 0 {
+        return Err(syscall_error("open_tree", mount_point));
+    }
+    // SAFETY: open_tree returned a new fd.
+    let tree = unsafe { OwnedFd::from_raw_fd(tree as i32) };
+
+    let attr = MountAttr {
+        attr_set: MOUNT_ATTR_IDMAP,
+        attr_clr: 0,
+        propagation: 0,
+        userns_fd: userns.as_raw_fd() as u64,
+    };
+    let ret = unsafe {
+        libc::syscall(
+            libc::SYS_mount_setattr,
+            tree.as_raw_fd(),
+            empty.as_ptr(),
+            libc::AT_EMPTY_PATH,
+            &attr as *const MountAttr,
+            std::mem::size_of::<MountAttr>(),
+        )
+    };
+    if ret < 0 {
+        return Err(syscall_error("mount_setattr", mount_point));
+    }
+
+    let ret = unsafe {
+        libc::syscall(
+            libc::SYS_move_mount,
+            tree.as_raw_fd(),
+            empty.as_ptr(),
+            libc::AT_FDCWD,
+            path.as_ptr(),
+            MOVE_MOUNT_F_EMPTY_PATH,
+        )
+    };
+    if ret < 0 {
+        return Err(syscall_error("move_mount", mount_point));
+    }
+
+    tracing::info!(
+        mount_point = %mount_point.display(),
+        uid,
+        gid,
+        "Applied id shift to volume"
+    );
+    Ok(())
+}
+
+/// Create a user namespace with root swapped for `uid:gid` and return an fd
+/// to it. A child parked in the namespace holds it until the fd is opened.
+fn swapped_user_namespace(uid: u32, gid: u32) -> BoxliteResult<File> {
+    let mut stack = vec![0u8; 64 * 1024];
+    // SAFETY: the child only blocks in pause(2) until it is killed.
+    let child = unsafe {
+        nix::sched::clone(
+            Box::new(|| loop {
+                nix::unistd::pause();
+            }),
+            &mut stack,
+            CloneFlags::CLONE_NEWUSER,
+            Some(libc::SIGCHLD),
+        )
+    }
+    .map_err(|e| BoxliteError::Storage(format!("Failed to create user namespace: {}", e)))?;
+
+    let result = (|| {
+        let proc_dir = Path::new("/proc").join(child.as_raw().to_string());
+        std::fs::write(proc_dir.join("uid_map"), swap_id_map(uid))?;
+        std::fs::write(proc_dir.join("gid_map"), swap_id_map(gid))?;
+        File::open(proc_dir.join("ns/user"))
+    })()
+    .map_err(|e| BoxliteError::Storage(format!("Failed to set up id mapping: {}", e)));
+
+    let _ = kill(child, Signal::SIGKILL);
+    let _ = waitpid(child, None);
+    result
+}
+
+fn syscall_error(call: &str, mount_point: &Path) -> BoxliteError {
+    BoxliteError::Storage(format!(
+        "{} on {} failed (guest kernel may not support idmapped virtiofs mounts): {}",
+        call,
+        mount_point.display(),
+        std::io::Error::last_os_error()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_swap_id_map() {
+        assert_eq!(swap_id_map(0), "0 0 4294967295\n");
+        assert_eq!(swap_id_map(1), "0 1 1\n1 0 1\n2 2 4294967293\n");
+        assert_eq!(
+            swap_id_map(1000),
+            "0 1000 1\n1000 0 1\n1 1 999\n1001 1001 4294966294\n"
+        );
+    }
+}
@@ -7,8 +7,10 @@
 pub mod block_device;
 #[allow(dead_code)]
 mod copy;
+mod idmap;
 mod perms;
 mod virtiofs;
 mod volume;
 
+pub use perms::OwnershipFixer;
 pub use volume::mount_volumes;
@@ -17,19 +17,23 @@ impl OwnershipFixer {
     pub fn fix_if_needed(path: &Path) -> BoxliteResult<()> {
         let current_uid = unsafe { libc::getuid() };
         let current_gid = unsafe { libc::getgid() };
+        Self::chown_if_needed(path, current_uid, current_gid)
+    }
 
+    /// Recursively chown `path` to `uid:gid` unless a sample already matches.
+    pub fn chown_if_needed(path: &Path, uid: u32, gid: u32) -> BoxliteResult<()> {
         // Check if ownership fix is needed by sampling root and subdirectories
-        if Self::ownership_matches(path, current_uid, current_gid) {
+        if Self::ownership_matches(path, uid, gid) {
             tracing::debug!(
                 "Ownership of {} already matches {}:{}",
                 path.display(),
-                current_uid,
-                current_gid
+                uid,
+                gid
             );
             return Ok(());
         }
 
-        let owner = format!("{}:{}", current_uid, current_gid);
+        let owner = format!("{}:{}", uid, gid);
 
         tracing::info!("Fixing ownership of {} to {}", path.display(), owner);
 
@@ -83,6 +83,38 @@ typedef enum BoxliteErrorCode {
    * RPC error
    */
   Rpc = 16,
+  /**
+   * Runtime capacity limits exceeded
+   */
+  ResourceExhausted = 17,
+  /**
+   * Image reference not found in any registry
+   */
+  ImageNotFound = 18,
+  /**
+   * Registry rejected the pull credentials
+   */
+  ImagePullAuth = 19,
+  /**
+   * Guest agent did not become ready in time
+   */
+  GuestTimeout = 20,
+  /**
+   * Hardware virtualization (KVM, Hypervisor.framework) unavailable
+   */
+  KvmUnavailable = 21,
+  /**
+   * Host port already in use
+   */
+  PortInUse = 22,
+  /**
+   * Box name already taken
+   */
+  NameConflict = 23,
+  /**
+   * Cached image content does not match its pinned digest
+   */
+  Integrity = 24,
 } BoxliteErrorCode;
 
 /**
@@ -192,6 +224,25 @@ enum BoxliteErrorCode boxlite_create_box(struct CBoxliteRuntime *runtime,
                                          struct CBoxHandle **out_box,
                                          struct CBoxliteError *out_error);
 
+/**
+ * Clone a stopped box (copy-on-write disks)
+ *
+ * # Arguments
+ * * `runtime` - BoxLite runtime instance
+ * * `source` - ID or name of the stopped box to clone
+ * * `name` - Name for the new box (NULL for none)
+ * * `out_box` - Output parameter for the new box handle
+ * * `out_error` - Output parameter for error information
+ *
+ * # Returns
+ * BoxliteErrorCode::Ok on success, error code on failure
+ */
+enum BoxliteErrorCode boxlite_clone_box(struct CBoxliteRuntime *runtime,
+                                        const char *source,
+                                        const char *name,
+                                        struct CBoxHandle **out_box,
+                                        struct CBoxliteError *out_error);
+
 /**
  * Execute a command in a box
  *
@@ -201,7 +252,8 @@ enum BoxliteErrorCode boxlite_create_box(struct CBoxliteRuntime *runtime,
  * * `args_json` - JSON array of arguments, e.g.: `["arg1", "arg2"]`
  * * `callback` - Optional callback for streaming output (chunk_text, is_stderr, user_data)
  * * `user_data` - User data passed to callback
- * * `out_exit_code` - Output parameter for command exit code
+ * * `out_exit_code` - Output parameter for command exit code (127: command
+ *   not found, 126: not executable, 128 + N: killed by signal N)
  * * `out_error` - Output parameter for error information
  *
  * # Returns
@@ -450,6 +502,14 @@ void boxlite_runtime_free(struct CBoxliteRuntime *runtime);
  */
 void boxlite_free_string(char *str);
 
+/**
+ * Most specific code for an error, e.g. ImageNotFound where `code` is Image
+ *
+ * Returns `error->code` when no more specific code applies, and Ok for NULL.
+ * Valid until the error is passed to `boxlite_error_free`.
+ */
+enum BoxliteErrorCode boxlite_error_detail_code(const struct CBoxliteError *error);
+
 /**
  * Free error struct
  */
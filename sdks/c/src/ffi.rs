@@ -14,19 +14,20 @@
 #![allow(clippy::missing_safety_doc)]
 #![allow(clippy::doc_overindented_list_items)]
 
+use std::collections::HashMap;
 use std::ffi::{CStr, CString};
 use std::os::raw::{c_char, c_int, c_void};
 use std::ptr;
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock, Mutex};
 
 use tokio::runtime::Runtime as TokioRuntime;
 
 use boxlite::BoxID;
-use boxlite::BoxliteError;
 use boxlite::litebox::LiteBox;
 use boxlite::runtime::BoxliteRuntime;
 use boxlite::runtime::options::{BoxOptions, BoxliteOptions, RootfsSpec};
-use boxlite::runtime::types::{BoxInfo, BoxStatus};
+use boxlite::runtime::types::{BoxInfo, BoxShutdownOutcome, BoxStatus};
+use boxlite::{BoxliteError, ErrorCode};
 
 // ============================================================================
 // Error Code Enum - Maps to BoxliteError variants
@@ -73,6 +74,24 @@ pub enum BoxliteErrorCode {
     Portal = 15,
     /// RPC error
     Rpc = 16,
+    /// Runtime capacity limits exceeded
+    ResourceExhausted = 17,
+    // Detail codes below refine a category above. They are only returned by
+    // boxlite_error_detail_code(), never as a result or CBoxliteError.code.
+    /// Image reference not found in any registry
+    ImageNotFound = 18,
+    /// Registry rejected the pull credentials
+    ImagePullAuth = 19,
+    /// Guest agent did not become ready in time
+    GuestTimeout = 20,
+    /// Hardware virtualization (KVM, Hypervisor.framework) unavailable
+    KvmUnavailable = 21,
+    /// Host port already in use
+    PortInUse = 22,
+    /// Box name already taken
+    NameConflict = 23,
+    /// Cached image content does not match its pinned digest
+    Integrity = 24,
 }
 
 /// Extended error information for C API.
@@ -122,34 +141,70 @@ pub struct CBoxliteSimple {
 // Error Conversion Helpers
 // ============================================================================
 
-/// Map BoxliteError to BoxliteErrorCode
+/// Map BoxliteError to the BoxliteErrorCode category it rolls up to.
+///
+/// Only codes 0-17 are returned here, so callers matching on the codes of
+/// earlier releases see the same values; the specific code is available
+/// from `boxlite_error_detail_code`.
 fn error_to_code(err: &BoxliteError) -> BoxliteErrorCode {
-    match err {
-        BoxliteError::Internal(_) => BoxliteErrorCode::Internal,
-        BoxliteError::NotFound(_) => BoxliteErrorCode::NotFound,
-        BoxliteError::AlreadyExists(_) => BoxliteErrorCode::AlreadyExists,
-        BoxliteError::InvalidState(_) => BoxliteErrorCode::InvalidState,
-        BoxliteError::InvalidArgument(_) => BoxliteErrorCode::InvalidArgument,
-        BoxliteError::Config(_) => BoxliteErrorCode::Config,
-        BoxliteError::Storage(_) => BoxliteErrorCode::Storage,
-        BoxliteError::Image(_) => BoxliteErrorCode::Image,
-        BoxliteError::Network(_) => BoxliteErrorCode::Network,
-        BoxliteError::Execution(_) => BoxliteErrorCode::Execution,
-        BoxliteError::Stopped(_) => BoxliteErrorCode::Stopped,
-        BoxliteError::Engine(_) => BoxliteErrorCode::Engine,
-        BoxliteError::Unsupported(_) => BoxliteErrorCode::Unsupported,
-        BoxliteError::UnsupportedEngine => BoxliteErrorCode::Unsupported,
-        BoxliteError::Database(_) => BoxliteErrorCode::Database,
-        BoxliteError::Portal(_) => BoxliteErrorCode::Portal,
-        BoxliteError::Rpc(_) | BoxliteError::RpcTransport(_) => BoxliteErrorCode::Rpc,
-        BoxliteError::MetadataError(_) => BoxliteErrorCode::Internal,
+    code_to_c(err.code().category())
+}
+
+/// Map BoxliteError to its most specific BoxliteErrorCode
+fn error_to_detail_code(err: &BoxliteError) -> BoxliteErrorCode {
+    code_to_c(err.code())
+}
+
+fn code_to_c(code: ErrorCode) -> BoxliteErrorCode {
+    match code {
+        ErrorCode::Internal | ErrorCode::Metadata => BoxliteErrorCode::Internal,
+        ErrorCode::NotFound => BoxliteErrorCode::NotFound,
+        ErrorCode::AlreadyExists => BoxliteErrorCode::AlreadyExists,
+        ErrorCode::InvalidState => BoxliteErrorCode::InvalidState,
+        ErrorCode::InvalidArgument => BoxliteErrorCode::InvalidArgument,
+        ErrorCode::Config => BoxliteErrorCode::Config,
+        ErrorCode::Storage => BoxliteErrorCode::Storage,
+        ErrorCode::Image => BoxliteErrorCode::Image,
+        ErrorCode::Network => BoxliteErrorCode::Network,
+        ErrorCode::Execution => BoxliteErrorCode::Execution,
+        ErrorCode::Stopped => BoxliteErrorCode::Stopped,
+        ErrorCode::Engine => BoxliteErrorCode::Engine,
+        ErrorCode::Unsupported | ErrorCode::UnsupportedEngine => BoxliteErrorCode::Unsupported,
+        ErrorCode::Database => BoxliteErrorCode::Database,
+        ErrorCode::Portal => BoxliteErrorCode::Portal,
+        ErrorCode::Rpc | ErrorCode::RpcTransport => BoxliteErrorCode::Rpc,
+        ErrorCode::ResourceExhausted => BoxliteErrorCode::ResourceExhausted,
+        ErrorCode::ImageNotFound => BoxliteErrorCode::ImageNotFound,
+        ErrorCode::ImagePullAuth => BoxliteErrorCode::ImagePullAuth,
+        ErrorCode::GuestTimeout => BoxliteErrorCode::GuestTimeout,
+        ErrorCode::KvmUnavailable => BoxliteErrorCode::KvmUnavailable,
+        ErrorCode::PortInUse => BoxliteErrorCode::PortInUse,
+        ErrorCode::NameConflict => BoxliteErrorCode::NameConflict,
+        ErrorCode::Integrity => BoxliteErrorCode::Integrity,
+        _ => BoxliteErrorCode::Internal,
     }
 }
 
+/// Detail codes of live errors, keyed by their message pointer.
+///
+/// CBoxliteError keeps its two-field layout, so the detail lives here until
+/// `boxlite_error_free` releases the message.
+static ERROR_DETAILS: LazyLock<Mutex<HashMap<usize, BoxliteErrorCode>>> =
+    LazyLock::new(Default::default);
+
 /// Convert Rust error to C error struct
 fn error_to_c_error(err: BoxliteError) -> CBoxliteError {
     let code = error_to_code(&err);
+    let detail = error_to_detail_code(&err);
     let message = error_to_c_string(err);
+    let mut details = ERROR_DETAILS.lock().unwrap();
+    // A message freed with boxlite_free_string can leave a stale entry at
+    // a reused address, so errors without a detail clear it
+    if detail == code {
+        details.remove(&(message as usize));
+    } else {
+        details.insert(message as usize, detail);
+    }
     CBoxliteError { code, message }
 }
 
@@ -198,6 +253,7 @@ fn status_to_string(status: BoxStatus) -> &'static str {
         BoxStatus::Unknown => "unknown",
         BoxStatus::Configured => "configured",
         BoxStatus::Running => "running",
+        BoxStatus::Paused => "paused",
         BoxStatus::Stopping => "stopping",
         BoxStatus::Stopped => "stopped",
     }
@@ -396,6 +452,77 @@ pub unsafe extern "C" fn boxlite_create_box(
     }
 }
 
+/// Clone a stopped box (copy-on-write disks)
+///
+/// # Arguments
+/// * `runtime` - BoxLite runtime instance
+/// * `source` - ID or name of the stopped box to clone
+/// * `name` - Name for the new box (NULL for none)
+/// * `out_box` - Output parameter for the new box handle
+/// * `out_error` - Output parameter for error information
+///
+/// # Returns
+/// BoxliteErrorCode::Ok on success, error code on failure
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn boxlite_clone_box(
+    runtime: *mut CBoxliteRuntime,
+    source: *const c_char,
+    name: *const c_char,
+    out_box: *mut *mut CBoxHandle,
+    out_error: *mut CBoxliteError,
+) -> BoxliteErrorCode {
+    if runtime.is_null() {
+        write_error(out_error, null_pointer_error("runtime"));
+        return BoxliteErrorCode::InvalidArgument;
+    }
+    if out_box.is_null() {
+        write_error(out_error, null_pointer_error("out_box"));
+        return BoxliteErrorCode::InvalidArgument;
+    }
+
+    let runtime_ref = &*runtime;
+
+    let source_str = match c_str_to_string(source) {
+        Ok(s) => s,
+        Err(e) => {
+            write_error(out_error, e);
+            return BoxliteErrorCode::InvalidArgument;
+        }
+    };
+    let name_opt = if name.is_null() {
+        None
+    } else {
+        match c_str_to_string(name) {
+            Ok(s) => Some(s),
+            Err(e) => {
+                write_error(out_error, e);
+                return BoxliteErrorCode::InvalidArgument;
+            }
+        }
+    };
+
+    let result = runtime_ref
+        .tokio_rt
+        .block_on(runtime_ref.runtime.clone_box(&source_str, name_opt));
+
+    match result {
+        Ok(handle) => {
+            let box_id = handle.id().clone();
+            *out_box = Box::into_raw(Box::new(CBoxHandle {
+                handle,
+                box_id,
+                tokio_rt: runtime_ref.tokio_rt.clone(),
+            }));
+            BoxliteErrorCode::Ok
+        }
+        Err(e) => {
+            let code = error_to_code(&e);
+            write_error(out_error, e);
+            code
+        }
+    }
+}
+
 /// Execute a command in a box
 ///
 /// # Arguments
@@ -404,7 +531,8 @@ pub unsafe extern "C" fn boxlite_create_box(
 /// * `args_json` - JSON array of arguments, e.g.: `["arg1", "arg2"]`
 /// * `callback` - Optional callback for streaming output (chunk_text, is_stderr, user_data)
 /// * `user_data` - User data passed to callback
-/// * `out_exit_code` - Output parameter for command exit code
+/// * `out_exit_code` - Output parameter for command exit code (127: command
+///   not found, 126: not executable, 128 + N: killed by signal N)
 /// * `out_error` - Output parameter for error information
 ///
 /// # Returns
@@ -485,28 +613,28 @@ pub unsafe extern "C" fn boxlite_execute(
             use futures::StreamExt;
 
             // Take stdout and stderr
-            let mut stdout = execution.stdout();
-            let mut stderr = execution.stderr();
+            let mut stdout = execution.stdout().map(|s| s.text());
+            let mut stderr = execution.stderr().map(|s| s.text());
 
             // Read both streams
             loop {
                 tokio::select! {
-                    Some(line) = async {
+                    Some(text) = async {
                         match &mut stdout {
                             Some(s) => s.next().await,
                             None => None,
                         }
                     } => {
-                        let c_text = CString::new(line).unwrap_or_default();
+                        let c_text = CString::new(text).unwrap_or_default();
                         cb(c_text.as_ptr(), 0, user_data); // 0 = stdout
                     }
-                    Some(line) = async {
+                    Some(text) = async {
                         match &mut stderr {
                             Some(s) => s.next().await,
                             None => None,
                         }
                     } => {
-                        let c_text = CString::new(line).unwrap_or_default();
+                        let c_text = CString::new(text).unwrap_or_default();
                         cb(c_text.as_ptr(), 1, user_data); // 1 = stderr
                     }
                     else => break,
@@ -925,7 +1053,26 @@ pub unsafe extern "C" fn boxlite_runtime_shutdown(
 
     let result = runtime_ref
         .tokio_rt
-        .block_on(runtime_ref.runtime.shutdown(timeout_opt));
+        .block_on(runtime_ref.runtime.shutdown(timeout_opt))
+        .and_then(|report| {
+            // Boxes that could not be stopped fail the call
+            let errors: Vec<String> = report
+                .boxes
+                .iter()
+                .filter_map(|b| match &b.outcome {
+                    BoxShutdownOutcome::Failed(e) => Some(format!("{}: {}", b.box_id, e)),
+                    _ => None,
+                })
+                .collect();
+            if errors.is_empty() {
+                Ok(())
+            } else {
+                Err(BoxliteError::Internal(format!(
+                    "Shutdown completed with errors: {}",
+                    errors.join(", ")
+                )))
+            }
+        });
 
     match result {
         Ok(()) => BoxliteErrorCode::Ok,
@@ -1023,12 +1170,15 @@ pub unsafe extern "C" fn boxlite_box_metrics(
                 "exec_errors_total": metrics.exec_errors_total,
                 "bytes_sent_total": metrics.bytes_sent_total,
                 "bytes_received_total": metrics.bytes_received_total,
+                "bytes_received_wire_total": metrics.bytes_received_wire_total,
                 "total_create_duration_ms": metrics.total_create_duration_ms,
                 "guest_boot_duration_ms": metrics.guest_boot_duration_ms,
                 "network_bytes_sent": metrics.network_bytes_sent,
                 "network_bytes_received": metrics.network_bytes_received,
                 "network_tcp_connections": metrics.network_tcp_connections,
-                "network_tcp_errors": metrics.network_tcp_errors
+                "network_tcp_errors": metrics.network_tcp_errors,
+                "cache_reclaim_free_bytes": metrics.cache_reclaim_free_bytes,
+                "cache_reclaimed_bytes_total": metrics.cache_reclaimed_bytes_total
             });
 
             let json_str = match serde_json::to_string(&json) {
@@ -1273,29 +1423,29 @@ pub unsafe extern "C" fn boxlite_simple_run(
         let mut execution = handle.exec(cmd).await?;
 
         use futures::StreamExt;
-        let mut stdout_lines = Vec::new();
-        let mut stderr_lines = Vec::new();
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
 
         let mut stdout_stream = execution.stdout();
         let mut stderr_stream = execution.stderr();
 
         loop {
             tokio::select! {
-                Some(line) = async {
+                Some(chunk) = async {
                     match &mut stdout_stream {
                         Some(s) => s.next().await,
                         None => None,
                     }
                 } => {
-                    stdout_lines.push(line);
+                    stdout.extend_from_slice(&chunk);
                 }
-                Some(line) = async {
+                Some(chunk) = async {
                     match &mut stderr_stream {
                         Some(s) => s.next().await,
                         None => None,
                     }
                 } => {
-                    stderr_lines.push(line);
+                    stderr.extend_from_slice(&chunk);
                 }
                 else => break,
             }
@@ -1305,8 +1455,8 @@ pub unsafe extern "C" fn boxlite_simple_run(
 
         Ok::<(i32, String, String), BoxliteError>((
             status.exit_code,
-            stdout_lines.join("\n"),
-            stderr_lines.join("\n"),
+            String::from_utf8_lossy(&stdout).into_owned(),
+            String::from_utf8_lossy(&stderr).into_owned(),
         ))
     });
 
@@ -1395,12 +1545,39 @@ pub unsafe extern "C" fn boxlite_free_string(str: *mut c_char) {
     }
 }
 
+/// Most specific code for an error, e.g. ImageNotFound where `code` is Image
+///
+/// Returns `error->code` when no more specific code applies, and Ok for NULL.
+/// Valid until the error is passed to `boxlite_error_free`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn boxlite_error_detail_code(
+    error: *const CBoxliteError,
+) -> BoxliteErrorCode {
+    if error.is_null() {
+        return BoxliteErrorCode::Ok;
+    }
+    let err = &*error;
+    if err.message.is_null() {
+        return err.code;
+    }
+    ERROR_DETAILS
+        .lock()
+        .unwrap()
+        .get(&(err.message as usize))
+        .copied()
+        .unwrap_or(err.code)
+}
+
 /// Free error struct
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn boxlite_error_free(error: *mut CBoxliteError) {
     if !error.is_null() {
         let err = &mut *error;
         if !err.message.is_null() {
+            ERROR_DETAILS
+                .lock()
+                .unwrap()
+                .remove(&(err.message as usize));
             drop(CString::from_raw(err.message));
             err.message = ptr::null_mut();
         }
@@ -1467,6 +1644,39 @@ mod tests {
             error_to_code(&BoxliteError::Execution("test".into())),
             BoxliteErrorCode::Execution
         );
+        // Specific codes report their category
+        assert_eq!(
+            error_to_code(&BoxliteError::ImageNotFound("test".into())),
+            BoxliteErrorCode::Image
+        );
+        assert_eq!(
+            error_to_code(&BoxliteError::NameConflict("test".into())),
+            BoxliteErrorCode::AlreadyExists
+        );
+    }
+
+    #[test]
+    fn test_error_detail_code() {
+        let mut error = error_to_c_error(BoxliteError::ImageNotFound("alpine:nope".into()));
+        assert_eq!(error.code, BoxliteErrorCode::Image);
+        unsafe {
+            assert_eq!(
+                boxlite_error_detail_code(&error),
+                BoxliteErrorCode::ImageNotFound
+            );
+            boxlite_error_free(&mut error);
+            assert_eq!(boxlite_error_detail_code(&error), BoxliteErrorCode::Ok);
+        }
+
+        let mut error = error_to_c_error(BoxliteError::NotFound("box123".into()));
+        unsafe {
+            assert_eq!(
+                boxlite_error_detail_code(&error),
+                BoxliteErrorCode::NotFound
+            );
+            boxlite_error_free(&mut error);
+            assert_eq!(boxlite_error_detail_code(ptr::null()), BoxliteErrorCode::Ok);
+        }
     }
 
     #[test]
@@ -1517,6 +1727,7 @@ mod tests {
         assert_eq!(status_to_string(BoxStatus::Unknown), "unknown");
         assert_eq!(status_to_string(BoxStatus::Configured), "configured");
         assert_eq!(status_to_string(BoxStatus::Running), "running");
+        assert_eq!(status_to_string(BoxStatus::Paused), "paused");
         assert_eq!(status_to_string(BoxStatus::Stopping), "stopping");
         assert_eq!(status_to_string(BoxStatus::Stopped), "stopped");
     }
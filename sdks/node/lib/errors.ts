@@ -13,9 +13,16 @@
  * ```
  */
 export class BoxliteError extends Error {
-  constructor(message: string) {
+  /**
+   * Machine-readable error code from the native runtime (e.g. `IMAGE_NOT_FOUND`),
+   * or undefined for errors raised by the TypeScript layer.
+   */
+  public code?: string;
+
+  constructor(message: string, code?: string) {
     super(message);
     this.name = 'BoxliteError';
+    this.code = code;
     // Maintain proper stack trace for where our error was thrown (V8 only)
     if (Error.captureStackTrace) {
       Error.captureStackTrace(this, BoxliteError);
@@ -108,3 +115,230 @@ export class ParseError extends BoxliteError {
     }
   }
 }
+
+// ============================================================================
+// Native runtime errors
+// ============================================================================
+//
+// Errors from the native runtime arrive as `[CODE] message`. `fromNativeError`
+// strips the prefix and rethrows them as the most specific class below, with
+// the code on `err.code`.
+
+/** Invalid runtime or box configuration. */
+export class ConfigError extends BoxliteError {
+  constructor(message: string, code: string = 'CONFIG') {
+    super(message, code);
+    this.name = 'ConfigError';
+  }
+}
+
+/** Reading or writing BoxLite storage failed. */
+export class StorageError extends BoxliteError {
+  constructor(message: string, code: string = 'STORAGE') {
+    super(message, code);
+    this.name = 'StorageError';
+  }
+}
+
+/** An image could not be pulled, unpacked or used. */
+export class ImageError extends BoxliteError {
+  constructor(message: string, code: string = 'IMAGE') {
+    super(message, code);
+    this.name = 'ImageError';
+  }
+}
+
+/** The image reference does not exist in any registry. */
+export class ImageNotFoundError extends ImageError {
+  constructor(message: string, code: string = 'IMAGE_NOT_FOUND') {
+    super(message, code);
+    this.name = 'ImageNotFoundError';
+  }
+}
+
+/** The registry rejected (or required) pull credentials. */
+export class ImagePullAuthError extends ImageError {
+  constructor(message: string, code: string = 'IMAGE_PULL_AUTH') {
+    super(message, code);
+    this.name = 'ImagePullAuthError';
+  }
+}
+
+/** Cached image content does not match its pinned digest. */
+export class IntegrityError extends ImageError {
+  constructor(message: string, code: string = 'INTEGRITY') {
+    super(message, code);
+    this.name = 'IntegrityError';
+  }
+}
+
+/** Box networking failed. */
+export class NetworkError extends BoxliteError {
+  constructor(message: string, code: string = 'NETWORK') {
+    super(message, code);
+    this.name = 'NetworkError';
+  }
+}
+
+/** A requested host port is already bound. */
+export class PortInUseError extends NetworkError {
+  constructor(message: string, code: string = 'PORT_IN_USE') {
+    super(message, code);
+    this.name = 'PortInUseError';
+  }
+}
+
+/** The VM engine failed. */
+export class EngineError extends BoxliteError {
+  constructor(message: string, code: string = 'ENGINE') {
+    super(message, code);
+    this.name = 'EngineError';
+  }
+}
+
+/** The guest agent did not become ready in time. */
+export class GuestTimeoutError extends EngineError {
+  constructor(message: string, code: string = 'GUEST_TIMEOUT') {
+    super(message, code);
+    this.name = 'GuestTimeoutError';
+  }
+}
+
+/** The operation is not supported on this host or box. */
+export class UnsupportedError extends BoxliteError {
+  constructor(message: string, code: string = 'UNSUPPORTED') {
+    super(message, code);
+    this.name = 'UnsupportedError';
+  }
+}
+
+/** Hardware virtualization (KVM, Hypervisor.framework) is unavailable. */
+export class KvmUnavailableError extends UnsupportedError {
+  constructor(message: string, code: string = 'KVM_UNAVAILABLE') {
+    super(message, code);
+    this.name = 'KvmUnavailableError';
+  }
+}
+
+/** The box or resource does not exist. */
+export class NotFoundError extends BoxliteError {
+  constructor(message: string, code: string = 'NOT_FOUND') {
+    super(message, code);
+    this.name = 'NotFoundError';
+  }
+}
+
+/** The box or resource already exists. */
+export class AlreadyExistsError extends BoxliteError {
+  constructor(message: string, code: string = 'ALREADY_EXISTS') {
+    super(message, code);
+    this.name = 'AlreadyExistsError';
+  }
+}
+
+/** The box name is already taken. */
+export class NameConflictError extends AlreadyExistsError {
+  constructor(message: string, code: string = 'NAME_CONFLICT') {
+    super(message, code);
+    this.name = 'NameConflictError';
+  }
+}
+
+/** The box is in the wrong state for the operation. */
+export class InvalidStateError extends BoxliteError {
+  constructor(message: string, code: string = 'INVALID_STATE') {
+    super(message, code);
+    this.name = 'InvalidStateError';
+  }
+}
+
+/** An argument is invalid. */
+export class InvalidArgumentError extends BoxliteError {
+  constructor(message: string, code: string = 'INVALID_ARGUMENT') {
+    super(message, code);
+    this.name = 'InvalidArgumentError';
+  }
+}
+
+/** The box or runtime has been stopped. */
+export class StoppedError extends BoxliteError {
+  constructor(message: string, code: string = 'STOPPED') {
+    super(message, code);
+    this.name = 'StoppedError';
+  }
+}
+
+/** Runtime capacity limits would be exceeded. */
+export class ResourceExhaustedError extends BoxliteError {
+  constructor(message: string, code: string = 'RESOURCE_EXHAUSTED') {
+    super(message, code);
+    this.name = 'ResourceExhaustedError';
+  }
+}
+
+const ERRORS_BY_CODE: Record<string, new (message: string, code?: string) => BoxliteError> = {
+  CONFIG: ConfigError,
+  STORAGE: StorageError,
+  IMAGE: ImageError,
+  IMAGE_NOT_FOUND: ImageNotFoundError,
+  IMAGE_PULL_AUTH: ImagePullAuthError,
+  INTEGRITY: IntegrityError,
+  NETWORK: NetworkError,
+  PORT_IN_USE: PortInUseError,
+  ENGINE: EngineError,
+  GUEST_TIMEOUT: GuestTimeoutError,
+  UNSUPPORTED: UnsupportedError,
+  KVM_UNAVAILABLE: KvmUnavailableError,
+  NOT_FOUND: NotFoundError,
+  ALREADY_EXISTS: AlreadyExistsError,
+  NAME_CONFLICT: NameConflictError,
+  INVALID_STATE: InvalidStateError,
+  INVALID_ARGUMENT: InvalidArgumentError,
+  STOPPED: StoppedError,
+  RESOURCE_EXHAUSTED: ResourceExhaustedError,
+};
+
+const NATIVE_CODE_PREFIX = /^\[([A-Z_]+)\] /;
+
+/**
+ * Convert an error thrown by the native bindings into a typed BoxliteError.
+ *
+ * Anything that doesn't carry a native error code is returned unchanged.
+ *
+ * @example
+ * ```typescript
+ * try {
+ *   await runtime.create({ image: 'does-not-exist' });
+ * } catch (err) {
+ *   const e = fromNativeError(err);
+ *   if (e instanceof ImageNotFoundError) { ... }
+ * }
+ * ```
+ */
+export function fromNativeError(err: unknown): unknown {
+  if (!(err instanceof Error) || err instanceof BoxliteError) {
+    return err;
+  }
+  const match = NATIVE_CODE_PREFIX.exec(err.message);
+  if (!match) {
+    return err;
+  }
+  const code = match[1];
+  const message = err.message.slice(match[0].length);
+  const ErrorClass = ERRORS_BY_CODE[code] ?? BoxliteError;
+  const typed = new ErrorClass(message, code);
+  typed.stack = err.stack;
+  return typed;
+}
+
+/**
+ * Await a native call, rethrowing its errors through {@link fromNativeError}.
+ * @internal
+ */
+export async function withNativeErrors<T>(promise: Promise<T>): Promise<T> {
+  try {
+    return await promise;
+  } catch (err) {
+    throw fromNativeError(err);
+  }
+}
@@ -17,10 +17,22 @@
  */
 export interface ExecResult {
   /**
-   * Process exit code (0 = success, non-zero = error)
+   * Process exit code (0 = success, non-zero = error).
+   * 127: command not found, 126: not executable, 128 + N: killed by signal N.
    */
   exitCode: number;
 
+  /**
+   * Why the command did not exit on its own: `command_not_found`,
+   * `not_executable` or `signaled`. Undefined for a normal exit.
+   */
+  reason?: string;
+
+  /**
+   * Signal that terminated the process, if any
+   */
+  signal?: number;
+
   /**
    * Standard output (stdout) as a string
    */
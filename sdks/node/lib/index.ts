@@ -33,7 +33,32 @@ export { getNativeModule, getJsBoxlite };
 // Re-export TypeScript wrappers
 export { SimpleBox, type SimpleBoxOptions } from './simplebox.js';
 export { type ExecResult } from './exec.js';
-export { BoxliteError, ExecError, TimeoutError, ParseError } from './errors.js';
+export {
+  BoxliteError,
+  ExecError,
+  TimeoutError,
+  ParseError,
+  ConfigError,
+  StorageError,
+  ImageError,
+  ImageNotFoundError,
+  ImagePullAuthError,
+  IntegrityError,
+  NetworkError,
+  PortInUseError,
+  EngineError,
+  GuestTimeoutError,
+  UnsupportedError,
+  KvmUnavailableError,
+  NotFoundError,
+  AlreadyExistsError,
+  NameConflictError,
+  InvalidStateError,
+  InvalidArgumentError,
+  StoppedError,
+  ResourceExhaustedError,
+  fromNativeError,
+} from './errors.js';
 export * from './constants.js';
 
 // Specialized boxes
@@ -10,6 +10,7 @@
 
 import type { ExecResult } from './exec.js';
 import { getJsBoxlite } from './native.js';
+import { withNativeErrors } from './errors.js';
 
 // Import types from native module (will be available after build)
 type Boxlite = any;
@@ -215,12 +216,14 @@ export class SimpleBox {
     if (!this._boxPromise) {
       this._boxPromise = (async () => {
         if (this._reuseExisting) {
-          const result = await this._runtime.getOrCreate(this._boxOpts, this._name);
+          const result = await withNativeErrors(
+            this._runtime.getOrCreate(this._boxOpts, this._name)
+          );
           this._created = result.created;
           return result.box;
         } else {
           this._created = true;
-          return this._runtime.create(this._boxOpts, this._name);
+          return withNativeErrors(this._runtime.create(this._boxOpts, this._name));
         }
       })();
     }
@@ -357,7 +360,7 @@ export class SimpleBox {
 
     // Ensure box is created, then execute via Rust (returns Execution)
     const box = await this._ensureBox();
-    const execution: Execution = await box.exec(cmd, args, envArray, false);
+    const execution: Execution = await withNativeErrors(box.exec(cmd, args, envArray, false));
 
     // Collect stdout and stderr
     const stdoutLines: string[] = [];
@@ -408,10 +411,12 @@ export class SimpleBox {
     }
 
     // Wait for completion
-    const result = await execution.wait();
+    const result = await withNativeErrors(execution.wait());
 
     return {
       exitCode: result.exitCode,
+      reason: result.reason ?? undefined,
+      signal: result.signal ?? undefined,
       stdout: stdoutLines.join(''),
       stderr: stderrLines.join(''),
     };
@@ -436,7 +441,7 @@ export class SimpleBox {
       // Box was never created, nothing to stop
       return;
     }
-    await this._box.stop();
+    await withNativeErrors(this._box.stop());
   }
 
   /**
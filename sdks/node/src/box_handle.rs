@@ -8,7 +8,9 @@ use crate::copy::{JsCopyOptions, into_copy_options};
 use crate::exec::JsExecution;
 use crate::info::JsBoxInfo;
 use crate::metrics::JsBoxMetrics;
+use crate::python::JsPythonSession;
 use crate::util::map_err;
+use crate::watch::JsFsWatch;
 
 /// Box handle for interacting with a running container.
 ///
@@ -179,6 +181,47 @@ impl JsBox {
         Ok(JsBoxMetrics::from(metrics))
     }
 
+    /// Install packages with the box's package manager.
+    ///
+    /// Detects `apk`, `apt-get` or `dnf` and installs all packages with it,
+    /// starting the box if needed. If the install fails, the error lists
+    /// every package that could not be installed.
+    ///
+    /// # Returns
+    /// A `Promise<string>` with the package manager used (e.g. `"apk"`)
+    ///
+    /// # Example
+    /// ```javascript
+    /// await box.provision(['curl', 'git']);
+    /// ```
+    #[napi]
+    pub async fn provision(&self, packages: Vec<String>) -> Result<String> {
+        let manager = self.handle.provision(&packages).await.map_err(map_err)?;
+        Ok(manager.program().to_string())
+    }
+
+    /// Start a persistent Python interpreter in the box.
+    ///
+    /// Code cells run in one long-lived `python3` process, so imports and
+    /// variables carry over between cells without per-exec startup costs.
+    /// Requires `python3` in the image.
+    ///
+    /// # Example
+    /// ```javascript
+    /// const session = await box.pythonSession();
+    /// await session.run('x = 21');
+    /// const cell = await session.run('x * 2');
+    /// console.log(cell.repr); // "42"
+    /// await session.close();
+    /// ```
+    #[napi(js_name = "pythonSession")]
+    pub async fn python_session(&self) -> Result<JsPythonSession> {
+        let session = self.handle.python_session().await.map_err(map_err)?;
+        Ok(JsPythonSession {
+            session: Arc::new(tokio::sync::RwLock::new(Some(session))),
+        })
+    }
+
     /// Copy files from host into the box's container rootfs.
     ///
     /// **Note:** Destinations under tmpfs mounts (e.g. `/tmp`, `/dev/shm`) will
@@ -216,4 +259,27 @@ impl JsBox {
             .await
             .map_err(map_err)
     }
+
+    /// Watch a path inside the box for file changes.
+    ///
+    /// With `recursive`, subdirectories are watched too, including ones
+    /// created later. Like `copyOut`, this sees the container rootfs, not
+    /// tmpfs mounts such as `/tmp`.
+    ///
+    /// # Example
+    /// ```javascript
+    /// const watch = await box.watch('/app/src', true);
+    /// const event = await watch.next();
+    /// ```
+    #[napi]
+    pub async fn watch(&self, path: String, recursive: Option<bool>) -> Result<JsFsWatch> {
+        let stream = self
+            .handle
+            .watch(&path, recursive.unwrap_or(false))
+            .await
+            .map_err(map_err)?;
+        Ok(JsFsWatch {
+            stream: Arc::new(tokio::sync::Mutex::new(stream)),
+        })
+    }
 }
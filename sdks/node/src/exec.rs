@@ -16,8 +16,16 @@ const ERR_STDERR_UNAVAILABLE: &str = "stderr stream not available";
 #[napi(object)]
 #[derive(Clone, Debug)]
 pub struct JsExecResult {
-    /// Process exit code (0 = success, non-zero = error)
+    /// Process exit code (0 = success, non-zero = error).
+    /// 127: command not found, 126: not executable, 128 + N: killed by signal N.
     pub exit_code: i32,
+    /// Why the command did not exit on its own: `command_not_found`,
+    /// `not_executable` or `signaled`. Undefined for a normal exit.
+    pub reason: Option<String>,
+    /// Signal that terminated the process, if any.
+    pub signal: Option<i32>,
+    /// The guest kernel killed the command out of memory.
+    pub oom_killed: bool,
     /// Diagnostic error message when process died unexpectedly.
     /// Undefined if the process exited normally.
     pub error_message: Option<String>,
@@ -25,15 +33,16 @@ pub struct JsExecResult {
 
 /// Stdout stream for reading command output.
 ///
-/// Provides line-by-line access to stdout via async iteration.
+/// Provides text chunks of stdout, as the process writes them, via async
+/// iteration. Characters split across chunks are decoded whole.
 #[napi]
 pub struct JsExecStdout {
-    pub(crate) stream: Arc<Mutex<boxlite::ExecStdout>>,
+    pub(crate) stream: Arc<Mutex<boxlite::OutputText<boxlite::ExecStdout>>>,
 }
 
 #[napi]
 impl JsExecStdout {
-    /// Read the next line from stdout.
+    /// Read the next chunk of text from stdout.
     ///
     /// Returns null when the stream is closed (EOF).
     ///
@@ -56,15 +65,16 @@ impl JsExecStdout {
 
 /// Stderr stream for reading command error output.
 ///
-/// Provides line-by-line access to stderr via async iteration.
+/// Provides text chunks of stderr, as the process writes them, via async
+/// iteration. Characters split across chunks are decoded whole.
 #[napi]
 pub struct JsExecStderr {
-    pub(crate) stream: Arc<Mutex<boxlite::ExecStderr>>,
+    pub(crate) stream: Arc<Mutex<boxlite::OutputText<boxlite::ExecStderr>>>,
 }
 
 #[napi]
 impl JsExecStderr {
-    /// Read the next line from stderr.
+    /// Read the next chunk of text from stderr.
     ///
     /// Returns null when the stream is closed (EOF).
     ///
@@ -207,7 +217,7 @@ impl JsExecution {
         let mut guard = self.execution.lock().await;
         match guard.stdout() {
             Some(stream) => Ok(JsExecStdout {
-                stream: Arc::new(Mutex::new(stream)),
+                stream: Arc::new(Mutex::new(stream.text())),
             }),
             None => Err(Error::from_reason(ERR_STDOUT_UNAVAILABLE)),
         }
@@ -231,7 +241,7 @@ impl JsExecution {
         let mut guard = self.execution.lock().await;
         match guard.stderr() {
             Some(stream) => Ok(JsExecStderr {
-                stream: Arc::new(Mutex::new(stream)),
+                stream: Arc::new(Mutex::new(stream.text())),
             }),
             None => Err(Error::from_reason(ERR_STDERR_UNAVAILABLE)),
         }
@@ -257,6 +267,9 @@ impl JsExecution {
         let exec_result = guard.wait().await.map_err(map_err)?;
         Ok(JsExecResult {
             exit_code: exec_result.exit_code,
+            reason: exec_result.reason.map(|r| r.as_str().to_string()),
+            signal: exec_result.signal(),
+            oom_killed: exec_result.oom_killed,
             error_message: exec_result.error_message,
         })
     }
@@ -1,6 +1,8 @@
 use boxlite::runtime::types::{BoxInfo, BoxStatus};
 use napi_derive::napi;
 
+use crate::options::JsPortSpec;
+
 // ============================================================================
 // BoxStateInfo - Runtime state (Docker-like State object)
 // ============================================================================
@@ -20,6 +22,9 @@ pub struct JsBoxStateInfo {
 
     /// Process ID of the VMM subprocess (undefined if not running)
     pub pid: Option<u32>,
+
+    /// A command was killed out of memory since the last start
+    pub oom_killed: bool,
 }
 
 fn status_to_string(status: BoxStatus) -> String {
@@ -27,6 +32,7 @@ fn status_to_string(status: BoxStatus) -> String {
         BoxStatus::Unknown => "unknown",
         BoxStatus::Configured => "configured",
         BoxStatus::Running => "running",
+        BoxStatus::Paused => "paused",
         BoxStatus::Stopping => "stopping",
         BoxStatus::Stopped => "stopped",
     }
@@ -64,6 +70,9 @@ pub struct JsBoxInfo {
 
     /// Allocated memory in MiB
     pub memory_mib: u32,
+
+    /// Published ports of the running box, with assigned host ports
+    pub ports: Vec<JsPortSpec>,
 }
 
 impl From<BoxInfo> for JsBoxInfo {
@@ -72,6 +81,7 @@ impl From<BoxInfo> for JsBoxInfo {
             status: status_to_string(info.status),
             running: info.status.is_running(),
             pid: info.pid,
+            oom_killed: info.oom_killed,
         };
 
         Self {
@@ -82,6 +92,7 @@ impl From<BoxInfo> for JsBoxInfo {
             image: info.image,
             cpus: info.cpus,
             memory_mib: info.memory_mib,
+            ports: info.ports.into_iter().map(JsPortSpec::from).collect(),
         }
     }
 }
@@ -11,8 +11,10 @@ mod exec;
 mod info;
 mod metrics;
 mod options;
+mod python;
 mod runtime;
 mod util;
+mod watch;
 
 // Re-export all public types
 pub use box_handle::JsBox;
@@ -21,4 +23,6 @@ pub use exec::{JsExecResult, JsExecStderr, JsExecStdin, JsExecStdout, JsExecutio
 pub use info::JsBoxInfo;
 pub use metrics::{JsBoxMetrics, JsRuntimeMetrics};
 pub use options::{JsBoxOptions, JsEnvVar, JsOptions, JsPortSpec, JsVolumeSpec};
+pub use python::{JsCellError, JsCellResult, JsPythonSession};
 pub use runtime::JsBoxlite; // re-export for dist bundling
+pub use watch::{JsFsEvent, JsFsWatch};
@@ -48,6 +48,8 @@ pub struct JsBoxMetrics {
     pub bytes_sent_total: f64,
     /// Bytes received from this box (via stdout/stderr)
     pub bytes_received_total: f64,
+    /// Stdout/stderr bytes on the host-guest channel (after exec compression)
+    pub bytes_received_wire_total: f64,
 
     // Lifecycle timing
     /// Total time from create() call to LiteBox ready (milliseconds)
@@ -71,6 +73,12 @@ pub struct JsBoxMetrics {
     /// Total TCP connection errors
     pub network_tcp_errors: Option<f64>,
 
+    // Guest cache reclaim metrics
+    /// Guest free memory (MemFree) after the last cache reclaim (bytes)
+    pub cache_reclaim_free_bytes: Option<f64>,
+    /// Guest free memory gained by cache reclaims over the box lifetime (bytes)
+    pub cache_reclaimed_bytes_total: f64,
+
     // Stage-level timing breakdown
     /// Time to create box directory structure (milliseconds)
     pub stage_filesystem_setup_ms: Option<f64>,
@@ -94,6 +102,7 @@ impl From<BoxMetrics> for JsBoxMetrics {
             exec_errors_total: m.exec_errors_total as f64,
             bytes_sent_total: m.bytes_sent_total as f64,
             bytes_received_total: m.bytes_received_total as f64,
+            bytes_received_wire_total: m.bytes_received_wire_total as f64,
 
             // Lifecycle timing (convert u128 to f64 for JavaScript)
             total_create_duration_ms: m.total_create_duration_ms.map(|v| v as f64),
@@ -109,6 +118,10 @@ impl From<BoxMetrics> for JsBoxMetrics {
             network_tcp_connections: m.network_tcp_connections.map(|v| v as f64),
             network_tcp_errors: m.network_tcp_errors.map(|v| v as f64),
 
+            // Guest cache reclaim metrics
+            cache_reclaim_free_bytes: m.cache_reclaim_free_bytes.map(|v| v as f64),
+            cache_reclaimed_bytes_total: m.cache_reclaimed_bytes_total as f64,
+
             // Stage timing (convert u128 to f64 for JavaScript)
             stage_filesystem_setup_ms: m.stage_filesystem_setup_ms.map(|v| v as f64),
             stage_image_prepare_ms: m.stage_image_prepare_ms.map(|v| v as f64),
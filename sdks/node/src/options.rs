@@ -66,7 +66,7 @@ pub struct JsBoxOptions {
     /// Volume mounts as array of volume specs
     pub volumes: Option<Vec<JsVolumeSpec>>,
 
-    /// Network mode ("isolated" - only option currently)
+    /// Network mode: "isolated" (default) or "disabled" (no network device)
     pub network: Option<String>,
 
     /// Port mappings as array of port specs
@@ -126,6 +126,7 @@ impl From<JsVolumeSpec> for VolumeSpec {
             host_path: v.host_path,
             guest_path: v.guest_path,
             read_only: v.read_only.unwrap_or(false),
+            ..Default::default()
         }
     }
 }
@@ -168,6 +169,22 @@ impl From<JsPortSpec> for PortSpec {
     }
 }
 
+impl From<PortSpec> for JsPortSpec {
+    fn from(p: PortSpec) -> Self {
+        let protocol = match p.protocol {
+            PortProtocol::Tcp => "tcp",
+            PortProtocol::Udp => "udp",
+        };
+
+        Self {
+            host_port: p.host_port,
+            guest_port: p.guest_port,
+            protocol: Some(protocol.to_string()),
+            host_ip: p.host_ip,
+        }
+    }
+}
+
 impl From<JsBoxOptions> for BoxOptions {
     fn from(js_opts: JsBoxOptions) -> Self {
         // Convert volumes
@@ -181,6 +198,7 @@ impl From<JsBoxOptions> for BoxOptions {
         // Convert network spec
         let network = match js_opts.network.as_deref() {
             Some(s) if s.eq_ignore_ascii_case("isolated") => NetworkSpec::Isolated,
+            Some(s) if s.eq_ignore_ascii_case("disabled") => NetworkSpec::Disabled,
             _ => NetworkSpec::Isolated,
         };
 
@@ -218,10 +236,17 @@ impl From<JsBoxOptions> for BoxOptions {
             disk_size_gb: js_opts.disk_size_gb.map(|v| v as u64),
             working_dir: js_opts.working_dir,
             env,
+            env_passthrough: Vec::new(), // Not exposed in JS API yet
+            timezone: None,              // Not exposed in JS API yet
+            locale: None,                // Not exposed in JS API yet
             rootfs,
             volumes,
+            tmpfs: Vec::new(),  // Not exposed in JS API yet
+            shm_size_mib: None, // Not exposed in JS API yet
             network,
             ports,
+            publish_all: false,    // Not exposed in JS API yet
+            publish_retry: false,  // Not exposed in JS API yet
             isolate_mounts: false, // Not exposed in JS API yet
             auto_remove: js_opts.auto_remove.unwrap_or(false),
             detach: js_opts.detach.unwrap_or(false),
@@ -229,6 +254,22 @@ impl From<JsBoxOptions> for BoxOptions {
             entrypoint: js_opts.entrypoint,
             cmd: js_opts.cmd,
             user: js_opts.user,
+            kernel: None,                         // Not exposed in JS API yet
+            network_limit_mbps: None,             // Not exposed in JS API yet
+            priority: Default::default(),         // Not exposed in JS API yet
+            network_policy: None,                 // Not exposed in JS API yet
+            hostname: None,                       // Not exposed in JS API yet
+            mac_address: None,                    // Not exposed in JS API yet
+            ip_address: None,                     // Not exposed in JS API yet
+            host_loopback: true,                  // Not exposed in JS API yet
+            log_connections: false,               // Not exposed in JS API yet
+            ssh: None,                            // Not exposed in JS API yet
+            core_dumps: None,                     // Not exposed in JS API yet
+            timeouts: Default::default(),         // Not exposed in JS API yet
+            exec_compression: Default::default(), // Not exposed in JS API yet
+            exec_limit: None,                     // Not exposed in JS API yet
+            init_commands: Vec::new(),            // Not exposed in JS API yet
+            sidecars: Vec::new(),                 // Not exposed in JS API yet
         }
     }
 }
@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use tokio::sync::RwLock;
+
+use crate::util::map_err;
+
+/// An exception raised by a code cell.
+#[napi(object)]
+#[derive(Clone, Debug)]
+pub struct JsCellError {
+    /// Exception class, e.g. "ZeroDivisionError"
+    pub name: String,
+    /// `str()` of the exception
+    pub value: String,
+    /// Formatted traceback lines
+    pub traceback: Vec<String>,
+}
+
+/// Result of a code cell run in a Python session.
+#[napi(object)]
+#[derive(Clone, Debug)]
+pub struct JsCellResult {
+    /// Text the cell printed to stdout
+    pub stdout: String,
+    /// Text the cell printed to stderr
+    pub stderr: String,
+    /// Value of the trailing expression as JSON text (parse with `JSON.parse`),
+    /// when it is not None and can be encoded as JSON
+    pub value_json: Option<String>,
+    /// `repr()` of the trailing expression's value, when it is not None
+    pub repr: Option<String>,
+    /// The exception the cell raised, if any
+    pub error: Option<JsCellError>,
+}
+
+impl From<boxlite::CellResult> for JsCellResult {
+    fn from(cell: boxlite::CellResult) -> Self {
+        Self {
+            stdout: cell.stdout,
+            stderr: cell.stderr,
+            value_json: cell.value.map(|value| value.to_string()),
+            repr: cell.repr,
+            error: cell.error.map(|error| JsCellError {
+                name: error.name,
+                value: error.value,
+                traceback: error.traceback,
+            }),
+        }
+    }
+}
+
+/// A long-lived Python interpreter in a box.
+///
+/// Cells share one namespace, so imports and variables carry over.
+#[napi]
+pub struct JsPythonSession {
+    pub(crate) session: Arc<RwLock<Option<boxlite::PythonSession>>>,
+}
+
+#[napi]
+impl JsPythonSession {
+    /// Run a code cell.
+    ///
+    /// An exception in the cell is reported in `error`; the promise only
+    /// rejects if the session itself failed.
+    ///
+    /// # Example
+    /// ```javascript
+    /// const session = await box.pythonSession();
+    /// await session.run('import math');
+    /// const cell = await session.run('math.sqrt(2)');
+    /// console.log(JSON.parse(cell.valueJson));
+    /// ```
+    #[napi]
+    pub async fn run(&self, code: String) -> Result<JsCellResult> {
+        let guard = self.session.read().await;
+        let session = guard
+            .as_ref()
+            .ok_or_else(|| Error::from_reason("python session is closed"))?;
+        let cell = session.run(&code).await.map_err(map_err)?;
+        Ok(JsCellResult::from(cell))
+    }
+
+    /// Interrupt the running cell, which then fails with KeyboardInterrupt.
+    #[napi]
+    pub async fn interrupt(&self) -> Result<()> {
+        let guard = self.session.read().await;
+        match guard.as_ref() {
+            Some(session) => session.interrupt().await.map_err(map_err),
+            None => Ok(()),
+        }
+    }
+
+    /// End the interpreter. Waits for a running cell to finish.
+    #[napi]
+    pub async fn close(&self) -> Result<()> {
+        let session = self.session.write().await.take();
+        match session {
+            Some(session) => session.close().await.map_err(map_err),
+            None => Ok(()),
+        }
+    }
+}
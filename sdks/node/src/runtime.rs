@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use boxlite::BoxliteRuntime;
+use boxlite::{BoxShutdown, BoxShutdownOutcome, BoxliteRuntime};
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
 
@@ -108,6 +108,27 @@ impl JsBoxlite {
         })
     }
 
+    /// Clone a stopped box into a new box (copy-on-write disks).
+    ///
+    /// # Arguments
+    /// * `source` - ID or name of the stopped box to clone
+    /// * `name` - Optional name for the new box
+    ///
+    /// # Example
+    /// ```javascript
+    /// const fork = await runtime.cloneBox('prepared-env', 'worker-1');
+    /// await fork.start();
+    /// ```
+    #[napi]
+    pub async fn clone_box(&self, source: String, name: Option<String>) -> Result<JsBox> {
+        let runtime = Arc::clone(&self.runtime);
+        let handle = runtime.clone_box(&source, name).await.map_err(map_err)?;
+
+        Ok(JsBox {
+            handle: Arc::new(handle),
+        })
+    }
+
     /// Get an existing box by name, or create a new one if it doesn't exist.
     ///
     /// Returns an object with `box` (the box handle) and `created` (true if
@@ -280,8 +301,9 @@ impl JsBoxlite {
 
     /// Gracefully shutdown all boxes in this runtime.
     ///
-    /// This method stops all running boxes, waiting up to `timeout` seconds
-    /// for each box to stop gracefully before force-killing it.
+    /// This method stops all running boxes concurrently, waiting up to
+    /// `timeout` seconds for each box to stop gracefully before force-killing
+    /// it. Resolves to a report of how each box ended.
     ///
     /// After calling this method, the runtime is permanently shut down and
     /// will return errors for any new operations (like `create()`).
@@ -295,7 +317,8 @@ impl JsBoxlite {
     /// # Example
     /// ```javascript
     /// // Default 10s timeout
-    /// await runtime.shutdown();
+    /// const report = await runtime.shutdown();
+    /// for (const b of report.boxes) console.log(b.id, b.outcome);
     ///
     /// // Custom 30s timeout
     /// await runtime.shutdown(30);
@@ -304,12 +327,59 @@ impl JsBoxlite {
     /// await runtime.shutdown(-1);
     /// ```
     #[napi]
-    pub async fn shutdown(&self, timeout: Option<i32>) -> Result<()> {
+    pub async fn shutdown(&self, timeout: Option<i32>) -> Result<JsShutdownReport> {
         let runtime = Arc::clone(&self.runtime);
-        runtime.shutdown(timeout).await.map_err(map_err)
+        let report = runtime.shutdown(timeout).await.map_err(map_err)?;
+        Ok(JsShutdownReport {
+            boxes: report.boxes.into_iter().map(JsBoxShutdown::from).collect(),
+        })
     }
 }
 
+/// How one box ended during `shutdown()`.
+#[napi(object)]
+#[derive(Clone, Debug)]
+pub struct JsBoxShutdown {
+    /// Box ID
+    pub id: String,
+
+    /// User-defined name (optional)
+    pub name: Option<String>,
+
+    /// "stopped" (gracefully), "killed" (after the timeout) or "failed"
+    pub outcome: String,
+
+    /// Why the box could not be stopped ("failed" only)
+    pub error: Option<String>,
+
+    /// Milliseconds from the start of the stop to its outcome
+    pub elapsed_ms: f64,
+}
+
+impl From<BoxShutdown> for JsBoxShutdown {
+    fn from(done: BoxShutdown) -> Self {
+        let (outcome, error) = match done.outcome {
+            BoxShutdownOutcome::Stopped => ("stopped", None),
+            BoxShutdownOutcome::Killed => ("killed", None),
+            BoxShutdownOutcome::Failed(e) => ("failed", Some(e)),
+        };
+        Self {
+            id: done.box_id.to_string(),
+            name: done.name,
+            outcome: outcome.to_string(),
+            error,
+            elapsed_ms: done.elapsed.as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+/// Result of `shutdown()`: one entry per box, in the order they finished.
+#[napi(object)]
+#[derive(Clone, Debug)]
+pub struct JsShutdownReport {
+    pub boxes: Vec<JsBoxShutdown>,
+}
+
 /// Result of a `getOrCreate` operation.
 #[napi]
 pub struct JsGetOrCreateResult {
@@ -3,6 +3,9 @@ use napi::Error as NapiError;
 
 /// Map BoxliteError to napi Error
 /// This is the single source of truth for error conversion (DRY principle)
+///
+/// The message is prefixed with the error code (`[IMAGE_NOT_FOUND] ...`);
+/// `fromNativeError` in lib/errors.ts turns it back into a typed error.
 pub(crate) fn map_err(err: BoxliteError) -> NapiError {
-    NapiError::from_reason(format!("{}", err))
+    NapiError::from_reason(format!("[{}] {}", err.code(), err))
 }
@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use tokio::sync::Mutex;
+
+use crate::util::map_err;
+
+/// A change to a file or directory inside the box.
+#[napi(object)]
+#[derive(Clone, Debug)]
+pub struct JsFsEvent {
+    /// "created", "modified", "removed", "moved_from", "moved_to" or "overflow"
+    pub kind: String,
+    /// Absolute path inside the container (empty for "overflow")
+    pub path: String,
+    pub is_dir: bool,
+    /// Pairs the "moved_from" and "moved_to" events of one rename (0 otherwise)
+    pub cookie: u32,
+}
+
+impl From<boxlite::FsEvent> for JsFsEvent {
+    fn from(event: boxlite::FsEvent) -> Self {
+        use boxlite::FsEventKind;
+        let kind = match event.kind {
+            FsEventKind::Created => "created",
+            FsEventKind::Modified => "modified",
+            FsEventKind::Removed => "removed",
+            FsEventKind::MovedFrom => "moved_from",
+            FsEventKind::MovedTo => "moved_to",
+            FsEventKind::Overflow => "overflow",
+        };
+        Self {
+            kind: kind.to_string(),
+            path: event.path,
+            is_dir: event.is_dir,
+            cookie: event.cookie,
+        }
+    }
+}
+
+/// File change events for a watched path.
+#[napi]
+pub struct JsFsWatch {
+    pub(crate) stream: Arc<Mutex<boxlite::FsWatch>>,
+}
+
+#[napi]
+impl JsFsWatch {
+    /// Wait for the next change.
+    ///
+    /// Returns null when the watch ends (path removed or box stopped).
+    ///
+    /// # Example
+    /// ```javascript
+    /// const watch = await box.watch('/app/src', true);
+    /// while (true) {
+    ///   const event = await watch.next();
+    ///   if (event === null) break;
+    ///   console.log(event.kind, event.path);
+    /// }
+    /// ```
+    #[napi]
+    pub async fn next(&self) -> Result<Option<JsFsEvent>> {
+        use futures::StreamExt;
+        let mut guard = self.stream.lock().await;
+        match guard.next().await {
+            Some(event) => Ok(Some(event.map_err(map_err)?.into())),
+            None => Ok(None),
+        }
+    }
+}
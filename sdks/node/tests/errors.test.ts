@@ -5,7 +5,17 @@
  */
 
 import { describe, test, expect } from 'vitest';
-import { BoxliteError, ExecError, TimeoutError, ParseError } from '../lib/errors.js';
+import {
+  BoxliteError,
+  ExecError,
+  TimeoutError,
+  ParseError,
+  ImageError,
+  ImageNotFoundError,
+  NameConflictError,
+  AlreadyExistsError,
+  fromNativeError,
+} from '../lib/errors.js';
 
 describe('BoxliteError', () => {
   test('is an Error instance', () => {
@@ -70,9 +80,9 @@ describe('ExecError', () => {
     }
   });
 
-  test('handles negative exit code (signal termination)', () => {
-    const err = new ExecError('sleep 100', -9, 'killed');
-    expect(err.exitCode).toBe(-9);
+  test('handles signal exit code', () => {
+    const err = new ExecError('sleep 100', 137, 'killed');
+    expect(err.exitCode).toBe(137);
   });
 
   test('handles empty stderr', () => {
@@ -159,3 +169,30 @@ describe('Error Hierarchy', () => {
     }
   });
 });
+
+describe('fromNativeError', () => {
+  test('maps coded native errors to typed classes', () => {
+    const err = fromNativeError(new Error('[IMAGE_NOT_FOUND] image not found: nope:latest'));
+    expect(err).toBeInstanceOf(ImageNotFoundError);
+    expect(err).toBeInstanceOf(ImageError);
+    expect((err as BoxliteError).code).toBe('IMAGE_NOT_FOUND');
+    expect((err as BoxliteError).message).toBe('image not found: nope:latest');
+  });
+
+  test('keeps the category hierarchy', () => {
+    const err = fromNativeError(new Error("[NAME_CONFLICT] name conflict: box with name 'a' already exists"));
+    expect(err).toBeInstanceOf(NameConflictError);
+    expect(err).toBeInstanceOf(AlreadyExistsError);
+  });
+
+  test('falls back to BoxliteError for codes without a class', () => {
+    const err = fromNativeError(new Error('[INTERNAL] internal error: oops'));
+    expect(err).toBeInstanceOf(BoxliteError);
+    expect((err as BoxliteError).code).toBe('INTERNAL');
+  });
+
+  test('passes through errors without a code', () => {
+    const original = new Error('plain');
+    expect(fromNativeError(original)).toBe(original);
+  });
+});
@@ -30,14 +30,16 @@ describe('ExecResult', () => {
     expect(result.exitCode).toBe(127);
   });
 
-  test('exitCode can be negative (signal termination)', () => {
+  test('signal termination is 128 + signal', () => {
     const result: ExecResult = {
-      exitCode: -9,
+      exitCode: 137,
+      reason: 'signaled',
+      signal: 9,
       stdout: '',
       stderr: 'killed',
     };
 
-    expect(result.exitCode).toBe(-9);
+    expect(result.exitCode).toBe(128 + result.signal!);
   });
 
   test('stdout can be empty', () => {
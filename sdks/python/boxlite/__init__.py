@@ -21,7 +21,14 @@ try:
         RuntimeMetrics,
         BoxMetrics,
         CopyOptions,
+        FsEvent,
+        FsWatch,
+        PythonSession,
+        CellResult,
+        CellError,
         RootfsSpec,
+        ShutdownReport,
+        BoxShutdown,
     )
 
     __all__ = [
@@ -38,7 +45,14 @@ try:
         "RuntimeMetrics",
         "BoxMetrics",
         "CopyOptions",
+        "FsEvent",
+        "FsWatch",
+        "PythonSession",
+        "CellResult",
+        "CellError",
         "RootfsSpec",
+        "ShutdownReport",
+        "BoxShutdown",
     ]
 except ImportError as e:
     warnings.warn(f"BoxLite native extension not available: {e}", ImportWarning)
@@ -49,7 +63,31 @@ try:
     from .simplebox import SimpleBox  # noqa: F401
     from .exec import ExecResult  # noqa: F401
     from .codebox import CodeBox  # noqa: F401
-    from .errors import BoxliteError, ExecError, TimeoutError, ParseError  # noqa: F401
+    from .errors import (  # noqa: F401
+        BoxliteError,
+        ExecError,
+        TimeoutError,
+        ParseError,
+        ConfigError,
+        StorageError,
+        ImageError,
+        ImageNotFoundError,
+        ImagePullAuthError,
+        IntegrityError,
+        NetworkError,
+        PortInUseError,
+        EngineError,
+        GuestTimeoutError,
+        UnsupportedError,
+        KvmUnavailableError,
+        NotFoundError,
+        AlreadyExistsError,
+        NameConflictError,
+        InvalidStateError,
+        InvalidArgumentError,
+        StoppedError,
+        ResourceExhaustedError,
+    )
 
     __all__.extend(
         [
@@ -62,6 +100,25 @@ try:
             "ExecError",
             "TimeoutError",
             "ParseError",
+            "ConfigError",
+            "StorageError",
+            "ImageError",
+            "ImageNotFoundError",
+            "ImagePullAuthError",
+            "IntegrityError",
+            "NetworkError",
+            "PortInUseError",
+            "EngineError",
+            "GuestTimeoutError",
+            "UnsupportedError",
+            "KvmUnavailableError",
+            "NotFoundError",
+            "AlreadyExistsError",
+            "NameConflictError",
+            "InvalidStateError",
+            "InvalidArgumentError",
+            "StoppedError",
+            "ResourceExhaustedError",
         ]
     )
 except ImportError:
@@ -2,15 +2,54 @@
 BoxLite error types.
 
 Provides a hierarchy of exceptions for different failure modes.
+
+Errors raised by the native runtime carry a machine-readable ``code``
+(e.g. ``"IMAGE_NOT_FOUND"``) and are raised as the most specific class
+below, so callers can catch ``ImageError`` or ``ImageNotFoundError``
+instead of matching on messages.
 """
 
-__all__ = ["BoxliteError", "ExecError", "TimeoutError", "ParseError"]
+from typing import Dict, Optional, Type
 
+__all__ = [
+    "BoxliteError",
+    "ExecError",
+    "TimeoutError",
+    "ParseError",
+    "ConfigError",
+    "StorageError",
+    "ImageError",
+    "ImageNotFoundError",
+    "ImagePullAuthError",
+    "IntegrityError",
+    "NetworkError",
+    "PortInUseError",
+    "EngineError",
+    "GuestTimeoutError",
+    "UnsupportedError",
+    "KvmUnavailableError",
+    "NotFoundError",
+    "AlreadyExistsError",
+    "NameConflictError",
+    "InvalidStateError",
+    "InvalidArgumentError",
+    "StoppedError",
+    "ResourceExhaustedError",
+]
 
-class BoxliteError(Exception):
-    """Base exception for all boxlite errors."""
 
-    pass
+class BoxliteError(RuntimeError):
+    """
+    Base exception for all boxlite errors.
+
+    Subclasses ``RuntimeError`` so code written against earlier releases,
+    where native errors were plain ``RuntimeError``, keeps working.
+
+    Attributes:
+        code: Machine-readable error code, or None for Python-side errors
+    """
+
+    code: Optional[str] = None
 
 
 class ExecError(BoxliteError):
@@ -42,3 +81,151 @@ class ParseError(BoxliteError):
     """Raised when output parsing fails."""
 
     pass
+
+
+class ConfigError(BoxliteError):
+    """Raised for invalid runtime or box configuration."""
+
+    code = "CONFIG"
+
+
+class StorageError(BoxliteError):
+    """Raised when reading or writing BoxLite storage fails."""
+
+    code = "STORAGE"
+
+
+class ImageError(BoxliteError):
+    """Raised when an image cannot be pulled, unpacked or used."""
+
+    code = "IMAGE"
+
+
+class ImageNotFoundError(ImageError):
+    """Raised when an image reference does not exist in any registry."""
+
+    code = "IMAGE_NOT_FOUND"
+
+
+class ImagePullAuthError(ImageError):
+    """Raised when a registry rejects (or requires) pull credentials."""
+
+    code = "IMAGE_PULL_AUTH"
+
+
+
+class IntegrityError(ImageError):
+    """Raised when cached image content does not match its pinned digest."""
+
+    code = "INTEGRITY"
+
+class NetworkError(BoxliteError):
+    """Raised for box networking failures."""
+
+    code = "NETWORK"
+
+
+class PortInUseError(NetworkError):
+    """Raised when a requested host port is already bound."""
+
+    code = "PORT_IN_USE"
+
+
+class EngineError(BoxliteError):
+    """Raised when the VM engine fails."""
+
+    code = "ENGINE"
+
+
+class GuestTimeoutError(EngineError, TimeoutError):
+    """Raised when the guest agent does not become ready in time."""
+
+    code = "GUEST_TIMEOUT"
+
+
+class UnsupportedError(BoxliteError):
+    """Raised when an operation is not supported on this host or box."""
+
+    code = "UNSUPPORTED"
+
+
+class KvmUnavailableError(UnsupportedError):
+    """Raised when hardware virtualization (KVM, Hypervisor.framework) is unavailable."""
+
+    code = "KVM_UNAVAILABLE"
+
+
+class NotFoundError(BoxliteError):
+    """Raised when a box or resource does not exist."""
+
+    code = "NOT_FOUND"
+
+
+class AlreadyExistsError(BoxliteError):
+    """Raised when a box or resource already exists."""
+
+    code = "ALREADY_EXISTS"
+
+
+class NameConflictError(AlreadyExistsError):
+    """Raised when a box name is already taken."""
+
+    code = "NAME_CONFLICT"
+
+
+class InvalidStateError(BoxliteError):
+    """Raised when a box is in the wrong state for an operation."""
+
+    code = "INVALID_STATE"
+
+
+class InvalidArgumentError(BoxliteError):
+    """Raised when an argument is invalid."""
+
+    code = "INVALID_ARGUMENT"
+
+
+class StoppedError(BoxliteError):
+    """Raised when the box or runtime has been stopped."""
+
+    code = "STOPPED"
+
+
+class ResourceExhaustedError(BoxliteError):
+    """Raised when runtime capacity limits would be exceeded."""
+
+    code = "RESOURCE_EXHAUSTED"
+
+
+_BY_CODE: Dict[str, Type[BoxliteError]] = {
+    cls.code: cls
+    for cls in (
+        ConfigError,
+        StorageError,
+        ImageError,
+        ImageNotFoundError,
+        ImagePullAuthError,
+        IntegrityError,
+        NetworkError,
+        PortInUseError,
+        EngineError,
+        GuestTimeoutError,
+        UnsupportedError,
+        KvmUnavailableError,
+        NotFoundError,
+        AlreadyExistsError,
+        NameConflictError,
+        InvalidStateError,
+        InvalidArgumentError,
+        StoppedError,
+        ResourceExhaustedError,
+    )
+}
+
+
+def _from_code(code: str, category: str, message: str) -> BoxliteError:
+    """Build the exception for a native error (called from the extension)."""
+    cls = _BY_CODE.get(code) or _BY_CODE.get(category) or BoxliteError
+    err = cls(message)
+    err.code = code
+    return err
@@ -17,14 +17,21 @@ class ExecResult:
     Result from a command execution.
 
     Attributes:
-        exit_code: Exit code from the command (negative if terminated by signal)
+        exit_code: Exit code from the command. Follows shell conventions:
+            127 if the command was not found, 126 if it was not executable,
+            128 + N if terminated by signal N
         stdout: Standard output as string
         stderr: Standard error as string
         error_message: Diagnostic message when process died unexpectedly
             (e.g., container init death). None if normal exit.
+        reason: Why the command did not exit on its own: "command_not_found",
+            "not_executable" or "signaled". None if normal exit.
+        signal: Signal that terminated the process, if any.
     """
 
     exit_code: int
     stdout: str
     stderr: str
     error_message: str | None = None
+    reason: str | None = None
+    signal: int | None = None
@@ -262,10 +262,14 @@ class SimpleBox:
         stderr = "".join(stderr_lines)
 
         error_message = None
+        reason = None
+        signal = None
         try:
             exec_result = await execution.wait()
             exit_code = exec_result.exit_code
             error_message = exec_result.error_message
+            reason = exec_result.reason
+            signal = exec_result.signal
         except Exception as e:
             logger.error(f"failed to wait execution: {e}")
             exit_code = -1
@@ -277,6 +281,8 @@ class SimpleBox:
             stdout=stdout,
             stderr=stderr,
             error_message=error_message,
+            reason=reason,
+            signal=signal,
         )
 
     def shutdown(self):
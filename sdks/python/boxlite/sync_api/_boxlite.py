@@ -12,7 +12,14 @@ from greenlet import greenlet
 
 if TYPE_CHECKING:
     from ._box import SyncBox
-    from ..boxlite import Boxlite, BoxOptions, BoxInfo, RuntimeMetrics, Options
+    from ..boxlite import (
+        Boxlite,
+        BoxOptions,
+        BoxInfo,
+        RuntimeMetrics,
+        Options,
+        ShutdownReport,
+    )
 
 __all__ = ["SyncBoxlite"]
 
@@ -316,6 +323,23 @@ class SyncBoxlite:
             return None
         return SyncBox(self, native_box)
 
+    def clone_box(self, source: str, name: Optional[str] = None) -> "SyncBox":
+        """
+        Clone a stopped box into a new box with copy-on-write disks.
+
+        Args:
+            source: ID or name of the stopped box to clone.
+            name: Optional name for the new box.
+
+        Returns:
+            SyncBox handle for the new (stopped) box.
+        """
+        self._require_started()
+        from ._box import SyncBox
+
+        native_box = self._sync(self._boxlite.clone_box(source, name=name))
+        return SyncBox(self, native_box)
+
     def list_info(self) -> List["BoxInfo"]:
         """
         List all boxes.
@@ -359,7 +383,7 @@ class SyncBoxlite:
         """
         self._sync(self._boxlite.remove(id_or_name, force))
 
-    def shutdown(self, timeout: Optional[int] = None) -> None:
+    def shutdown(self, timeout: Optional[int] = None) -> "ShutdownReport":
         """
         Gracefully shutdown all boxes in this runtime.
 
@@ -374,8 +398,11 @@ class SyncBoxlite:
                 - None (default) - Use default timeout (10 seconds)
                 - Positive integer - Wait that many seconds
                 - -1 - Wait indefinitely (no timeout)
+
+        Returns:
+            ShutdownReport saying which boxes stopped, were killed or failed.
         """
-        self._sync(self._boxlite.shutdown(timeout))
+        return self._sync(self._boxlite.shutdown(timeout))
 
     # ─────────────────────────────────────────────────────────────────────────
     # Properties for internal use by SyncBox/SyncExecution
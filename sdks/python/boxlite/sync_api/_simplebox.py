@@ -201,6 +201,8 @@ class SyncSimpleBox:
             stdout="".join(stdout_lines),
             stderr="".join(stderr_lines),
             error_message=result.error_message,
+            reason=result.reason,
+            signal=result.signal,
         )
 
     def stop(self) -> None:
@@ -3,7 +3,9 @@ use std::sync::Arc;
 use crate::exec::PyExecution;
 use crate::info::PyBoxInfo;
 use crate::metrics::PyBoxMetrics;
+use crate::python::PyPythonSession;
 use crate::util::map_err;
+use crate::watch::PyFsWatch;
 use boxlite::{BoxCommand, LiteBox};
 use pyo3::prelude::*;
 
@@ -97,6 +99,36 @@ impl PyBox {
         })
     }
 
+    /// Install packages with the box's package manager.
+    ///
+    /// Detects `apk`, `apt-get` or `dnf` and returns the one used. If the
+    /// install fails, the error lists every package that could not be
+    /// installed.
+    fn provision<'a>(&self, py: Python<'a>, packages: Vec<String>) -> PyResult<Bound<'a, PyAny>> {
+        let handle = Arc::clone(&self.handle);
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let manager = handle.provision(&packages).await.map_err(map_err)?;
+            Ok(manager.program().to_string())
+        })
+    }
+
+    /// Start a persistent Python interpreter in the box.
+    ///
+    /// Code cells run in one long-lived `python3` process, so imports and
+    /// variables carry over between cells without per-exec startup costs.
+    /// Requires `python3` in the image.
+    fn python_session<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let handle = Arc::clone(&self.handle);
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let session = handle.python_session().await.map_err(map_err)?;
+            Ok(PyPythonSession {
+                session: Arc::new(tokio::sync::RwLock::new(Some(session))),
+            })
+        })
+    }
+
     /// Copy from host into the box container rootfs.
     ///
     /// **Note:** Destinations under tmpfs mounts (e.g. `/tmp`, `/dev/shm`) will
@@ -147,6 +179,26 @@ impl PyBox {
         })
     }
 
+    /// Watch a container path for file changes.
+    ///
+    /// With `recursive`, subdirectories are watched too, including ones
+    /// created later. Sees the container rootfs, not tmpfs mounts like `/tmp`.
+    #[pyo3(signature = (path, recursive=false))]
+    fn watch<'a>(
+        &self,
+        py: Python<'a>,
+        path: String,
+        recursive: bool,
+    ) -> PyResult<Bound<'a, PyAny>> {
+        let handle = Arc::clone(&self.handle);
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let stream = handle.watch(&path, recursive).await.map_err(map_err)?;
+            Ok(PyFsWatch {
+                stream: Arc::new(tokio::sync::Mutex::new(stream)),
+            })
+        })
+    }
+
     /// Enter async context manager - auto-starts the box (Testcontainers pattern).
     fn __aenter__<'a>(slf: PyRefMut<'_, Self>, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
         let handle = Arc::clone(&slf.handle);
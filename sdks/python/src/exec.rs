@@ -6,7 +6,7 @@ use tokio::sync::Mutex;
 
 #[pyclass(name = "ExecStdout")]
 pub(crate) struct PyExecStdout {
-    pub(crate) stream: Arc<Mutex<boxlite::ExecStdout>>,
+    pub(crate) stream: Arc<Mutex<boxlite::OutputText<boxlite::ExecStdout>>>,
 }
 
 #[pymethods]
@@ -22,7 +22,7 @@ impl PyExecStdout {
             use futures::StreamExt;
             let mut guard = stream.lock().await;
             match guard.next().await {
-                Some(line) => Ok(line),
+                Some(text) => Ok(text),
                 None => Err(pyo3::exceptions::PyStopAsyncIteration::new_err("")),
             }
         })?;
@@ -81,7 +81,7 @@ impl PyExecStdin {
 
 #[pyclass(name = "ExecStderr")]
 pub(crate) struct PyExecStderr {
-    pub(crate) stream: Arc<Mutex<boxlite::ExecStderr>>,
+    pub(crate) stream: Arc<Mutex<boxlite::OutputText<boxlite::ExecStderr>>>,
 }
 
 #[pymethods]
@@ -97,7 +97,7 @@ impl PyExecStderr {
             use futures::StreamExt;
             let mut guard = stream.lock().await;
             match guard.next().await {
-                Some(line) => Ok(line),
+                Some(text) => Ok(text),
                 None => Err(pyo3::exceptions::PyStopAsyncIteration::new_err("")),
             }
         })?;
@@ -115,6 +115,12 @@ pub(crate) struct PyExecResult {
     #[pyo3(get, set)]
     pub(crate) exit_code: i32,
     #[pyo3(get, set)]
+    pub(crate) reason: Option<String>,
+    #[pyo3(get, set)]
+    pub(crate) signal: Option<i32>,
+    #[pyo3(get, set)]
+    pub(crate) oom_killed: bool,
+    #[pyo3(get, set)]
     pub(crate) error_message: Option<String>,
 }
 
@@ -145,7 +151,7 @@ impl PyExecution {
         let execution = unsafe { &mut *(Arc::as_ptr(&self.execution) as *mut Execution) };
         match execution.stdout() {
             Some(stream) => Ok(PyExecStdout {
-                stream: Arc::new(Mutex::new(stream)),
+                stream: Arc::new(Mutex::new(stream.text())),
             }),
             None => Err(pyo3::exceptions::PyRuntimeError::new_err(
                 "stdout stream not available",
@@ -157,7 +163,7 @@ impl PyExecution {
         let execution = unsafe { &mut *(Arc::as_ptr(&self.execution) as *mut Execution) };
         match execution.stderr() {
             Some(stream) => Ok(PyExecStderr {
-                stream: Arc::new(Mutex::new(stream)),
+                stream: Arc::new(Mutex::new(stream.text())),
             }),
             None => Err(pyo3::exceptions::PyRuntimeError::new_err(
                 "stderr stream not available",
@@ -173,6 +179,9 @@ impl PyExecution {
             let exec_result = execution_mut.wait().await.map_err(map_err)?;
             Ok(PyExecResult {
                 exit_code: exec_result.exit_code,
+                reason: exec_result.reason.map(|r| r.as_str().to_string()),
+                signal: exec_result.signal(),
+                oom_killed: exec_result.oom_killed,
                 error_message: exec_result.error_message,
             })
         })
@@ -1,3 +1,4 @@
+use boxlite::runtime::options::PortProtocol;
 use boxlite::{BoxInfo, BoxStateInfo, BoxStatus};
 use pyo3::prelude::*;
 
@@ -14,6 +15,8 @@ pub struct PyBoxStateInfo {
     pub(crate) running: bool,
     #[pyo3(get)]
     pub(crate) pid: Option<u32>,
+    #[pyo3(get)]
+    pub(crate) oom_killed: bool,
 }
 
 #[pymethods]
@@ -22,7 +25,8 @@ impl PyBoxStateInfo {
         serde_json::to_string_pretty(&serde_json::json!({
             "status": self.status,
             "running": self.running,
-            "pid": self.pid
+            "pid": self.pid,
+            "oom_killed": self.oom_killed
         }))
         .unwrap_or_default()
     }
@@ -33,6 +37,7 @@ fn status_to_string(status: BoxStatus) -> String {
         BoxStatus::Unknown => "unknown",
         BoxStatus::Configured => "configured",
         BoxStatus::Running => "running",
+        BoxStatus::Paused => "paused",
         BoxStatus::Stopping => "stopping",
         BoxStatus::Stopped => "stopped",
     }
@@ -45,6 +50,7 @@ impl From<BoxStateInfo> for PyBoxStateInfo {
             status: status_to_string(info.status),
             running: info.running,
             pid: info.pid,
+            oom_killed: info.oom_killed,
         }
     }
 }
@@ -70,6 +76,9 @@ pub(crate) struct PyBoxInfo {
     pub(crate) cpus: u8,
     #[pyo3(get)]
     pub(crate) memory_mib: u32,
+    /// Published ports of the running box: (host_port, guest_port, protocol, host_ip)
+    #[pyo3(get)]
+    pub(crate) ports: Vec<(u16, u16, String, Option<String>)>,
 }
 
 #[pymethods]
@@ -81,11 +90,13 @@ impl PyBoxInfo {
             "state": {
                 "status": self.state.status,
                 "running": self.state.running,
-                "pid": self.state.pid
+                "pid": self.state.pid,
+                "oom_killed": self.state.oom_killed
             },
             "image": self.image,
             "cpus": self.cpus,
             "memory_mib": self.memory_mib,
+            "ports": self.ports,
             "created_at": self.created_at
         }))
         .unwrap_or_default()
@@ -98,6 +109,7 @@ impl From<BoxInfo> for PyBoxInfo {
             status: status_to_string(info.status),
             running: info.status.is_running(),
             pid: info.pid,
+            oom_killed: info.oom_killed,
         };
 
         PyBoxInfo {
@@ -108,6 +120,22 @@ impl From<BoxInfo> for PyBoxInfo {
             image: info.image,
             cpus: info.cpus,
             memory_mib: info.memory_mib,
+            ports: info
+                .ports
+                .into_iter()
+                .map(|p| {
+                    let protocol = match p.protocol {
+                        PortProtocol::Tcp => "tcp",
+                        PortProtocol::Udp => "udp",
+                    };
+                    (
+                        p.host_port.unwrap_or(p.guest_port),
+                        p.guest_port,
+                        protocol.to_string(),
+                        p.host_ip,
+                    )
+                })
+                .collect(),
         }
     }
 }
@@ -5,15 +5,19 @@ mod exec;
 mod info;
 mod metrics;
 mod options;
+mod python;
 mod runtime;
 mod util;
+mod watch;
 
 use crate::box_handle::PyBox;
 use crate::exec::{PyExecStderr, PyExecStdin, PyExecStdout, PyExecution};
 use crate::info::{PyBoxInfo, PyBoxStateInfo};
 use crate::metrics::{PyBoxMetrics, PyRuntimeMetrics};
 use crate::options::{PyBoxOptions, PyCopyOptions, PyOptions, PySecurityOptions};
-use crate::runtime::PyBoxlite;
+use crate::python::{PyCellError, PyCellResult, PyPythonSession};
+use crate::runtime::{PyBoxShutdown, PyBoxlite, PyShutdownReport};
+use crate::watch::{PyFsEvent, PyFsWatch};
 use pyo3::prelude::*;
 
 #[pymodule(name = "boxlite")]
@@ -22,6 +26,8 @@ fn boxlite_python(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyBoxOptions>()?;
     m.add_class::<PySecurityOptions>()?;
     m.add_class::<PyBoxlite>()?;
+    m.add_class::<PyShutdownReport>()?;
+    m.add_class::<PyBoxShutdown>()?;
     m.add_class::<PyBox>()?;
     m.add_class::<PyExecution>()?;
     m.add_class::<PyExecStdin>()?;
@@ -32,6 +38,11 @@ fn boxlite_python(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyRuntimeMetrics>()?;
     m.add_class::<PyBoxMetrics>()?;
     m.add_class::<PyCopyOptions>()?;
+    m.add_class::<PyFsEvent>()?;
+    m.add_class::<PyFsWatch>()?;
+    m.add_class::<PyPythonSession>()?;
+    m.add_class::<PyCellResult>()?;
+    m.add_class::<PyCellError>()?;
 
     Ok(())
 }
@@ -54,6 +54,8 @@ pub(crate) struct PyBoxMetrics {
     #[pyo3(get)]
     pub(crate) bytes_received_total: u64,
     #[pyo3(get)]
+    pub(crate) bytes_received_wire_total: u64,
+    #[pyo3(get)]
     pub(crate) total_create_duration_ms: Option<u128>,
     #[pyo3(get)]
     pub(crate) guest_boot_duration_ms: Option<u128>,
@@ -69,6 +71,10 @@ pub(crate) struct PyBoxMetrics {
     pub(crate) network_tcp_connections: Option<u64>,
     #[pyo3(get)]
     pub(crate) network_tcp_errors: Option<u64>,
+    #[pyo3(get)]
+    pub(crate) cache_reclaim_free_bytes: Option<u64>,
+    #[pyo3(get)]
+    pub(crate) cache_reclaimed_bytes_total: u64,
     // Stage-level timing breakdown
     #[pyo3(get)]
     pub(crate) stage_filesystem_setup_ms: Option<u128>,
@@ -106,6 +112,7 @@ impl From<BoxMetrics> for PyBoxMetrics {
             exec_errors_total: metrics.exec_errors_total(),
             bytes_sent_total: metrics.bytes_sent_total(),
             bytes_received_total: metrics.bytes_received_total(),
+            bytes_received_wire_total: metrics.bytes_received_wire_total(),
             total_create_duration_ms: metrics.total_create_duration_ms(),
             guest_boot_duration_ms: metrics.guest_boot_duration_ms(),
             cpu_percent: metrics.cpu_percent(),
@@ -114,6 +121,8 @@ impl From<BoxMetrics> for PyBoxMetrics {
             network_bytes_received: metrics.network_bytes_received(),
             network_tcp_connections: metrics.network_tcp_connections(),
             network_tcp_errors: metrics.network_tcp_errors(),
+            cache_reclaim_free_bytes: metrics.cache_reclaim_free_bytes(),
+            cache_reclaimed_bytes_total: metrics.cache_reclaimed_bytes_total(),
             stage_filesystem_setup_ms: metrics.stage_filesystem_setup_ms(),
             stage_image_prepare_ms: metrics.stage_image_prepare_ms(),
             stage_guest_rootfs_ms: metrics.stage_guest_rootfs_ms(),
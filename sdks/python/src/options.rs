@@ -415,6 +415,7 @@ impl From<PyBoxOptions> for BoxOptions {
         let network = match py_opts.network {
             // Some(ref s) if s.eq_ignore_ascii_case("host") => NetworkSpec::Host,
             Some(ref s) if s.eq_ignore_ascii_case("isolated") => NetworkSpec::Isolated,
+            Some(ref s) if s.eq_ignore_ascii_case("disabled") => NetworkSpec::Disabled,
             // Some(s) if !s.is_empty() => NetworkSpec::Custom(s),
             _ => NetworkSpec::Isolated,
         };
@@ -480,6 +481,7 @@ impl From<PyVolumeSpec> for VolumeSpec {
             host_path: v.host,
             guest_path: v.guest,
             read_only: v.read_only,
+            ..Default::default()
         }
     }
 }
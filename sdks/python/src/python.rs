@@ -0,0 +1,147 @@
+use crate::util::map_err;
+use pyo3::prelude::*;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// An exception raised by a code cell.
+#[pyclass(name = "CellError")]
+#[derive(Clone)]
+pub(crate) struct PyCellError {
+    /// Exception class, e.g. "ZeroDivisionError"
+    #[pyo3(get)]
+    pub(crate) name: String,
+    /// str() of the exception
+    #[pyo3(get)]
+    pub(crate) value: String,
+    /// Formatted traceback lines
+    #[pyo3(get)]
+    pub(crate) traceback: Vec<String>,
+}
+
+#[pymethods]
+impl PyCellError {
+    fn __repr__(&self) -> String {
+        format!("CellError(name={:?}, value={:?})", self.name, self.value)
+    }
+}
+
+/// Result of a code cell run in a Python session.
+#[pyclass(name = "CellResult")]
+#[derive(Clone)]
+pub(crate) struct PyCellResult {
+    /// Text the cell printed to stdout
+    #[pyo3(get)]
+    pub(crate) stdout: String,
+    /// Text the cell printed to stderr
+    #[pyo3(get)]
+    pub(crate) stderr: String,
+    /// repr() of the trailing expression's value, if it is not None
+    #[pyo3(get)]
+    pub(crate) repr: Option<String>,
+    /// The exception the cell raised, if any
+    #[pyo3(get)]
+    pub(crate) error: Option<PyCellError>,
+    value_json: Option<String>,
+}
+
+#[pymethods]
+impl PyCellResult {
+    /// Value of the trailing expression, decoded from JSON; None when the
+    /// value was None or could not be encoded as JSON (see `repr`).
+    #[getter]
+    fn value(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        match &self.value_json {
+            Some(json) => Ok(py
+                .import("json")?
+                .call_method1("loads", (json.as_str(),))?
+                .unbind()),
+            None => Ok(py.None()),
+        }
+    }
+
+    /// Whether the cell ran without raising.
+    #[getter]
+    fn success(&self) -> bool {
+        self.error.is_none()
+    }
+
+    fn __repr__(&self) -> String {
+        match &self.error {
+            Some(error) => format!("CellResult(error={})", error.__repr__()),
+            None => format!("CellResult(repr={:?})", self.repr),
+        }
+    }
+}
+
+impl From<boxlite::CellResult> for PyCellResult {
+    fn from(cell: boxlite::CellResult) -> Self {
+        PyCellResult {
+            stdout: cell.stdout,
+            stderr: cell.stderr,
+            repr: cell.repr,
+            error: cell.error.map(|error| PyCellError {
+                name: error.name,
+                value: error.value,
+                traceback: error.traceback,
+            }),
+            value_json: cell.value.map(|value| value.to_string()),
+        }
+    }
+}
+
+/// A long-lived Python interpreter in a box.
+///
+/// Cells share one namespace, so imports and variables carry over.
+#[pyclass(name = "PythonSession")]
+pub(crate) struct PyPythonSession {
+    pub(crate) session: Arc<RwLock<Option<boxlite::PythonSession>>>,
+}
+
+#[pymethods]
+impl PyPythonSession {
+    /// Run a code cell.
+    ///
+    /// An exception in the cell is reported in `CellResult.error`; this only
+    /// raises if the session itself failed.
+    fn run<'a>(&self, py: Python<'a>, code: String) -> PyResult<Bound<'a, PyAny>> {
+        let session = Arc::clone(&self.session);
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let guard = session.read().await;
+            let session = guard.as_ref().ok_or_else(|| {
+                pyo3::exceptions::PyRuntimeError::new_err("python session is closed")
+            })?;
+            let cell = session.run(&code).await.map_err(map_err)?;
+            Ok(PyCellResult::from(cell))
+        })
+    }
+
+    /// Interrupt the running cell, which then fails with KeyboardInterrupt.
+    fn interrupt<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let session = Arc::clone(&self.session);
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            if let Some(session) = session.read().await.as_ref() {
+                session.interrupt().await.map_err(map_err)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// End the interpreter. Waits for a running cell to finish.
+    fn close<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let session = Arc::clone(&self.session);
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let session = session.write().await.take();
+            if let Some(session) = session {
+                session.close().await.map_err(map_err)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn __repr__(&self) -> String {
+        "PythonSession(...)".to_string()
+    }
+}
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use boxlite::BoxliteRuntime;
+use boxlite::{BoxShutdown, BoxShutdownOutcome, BoxliteRuntime};
 use pyo3::prelude::*;
 
 use crate::box_handle::PyBox;
@@ -55,6 +55,30 @@ impl PyBoxlite {
         })
     }
 
+    /// Clone a stopped box into a new box (copy-on-write disks).
+    ///
+    /// Args:
+    ///     source: ID or name of the stopped box to clone
+    ///     name: Optional name for the new box
+    ///
+    /// Returns:
+    ///     Handle to the new (stopped) box
+    #[pyo3(signature = (source, name=None))]
+    fn clone_box<'py>(
+        &self,
+        py: Python<'py>,
+        source: String,
+        name: Option<String>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let runtime = Arc::clone(&self.runtime);
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let handle = runtime.clone_box(&source, name).await.map_err(map_err)?;
+            Ok(PyBox {
+                handle: Arc::new(handle),
+            })
+        })
+    }
+
     #[pyo3(signature = (_state=None))]
     fn list_info<'py>(
         &self,
@@ -164,8 +188,9 @@ impl PyBoxlite {
 
     /// Gracefully shutdown all boxes in this runtime.
     ///
-    /// This method stops all running boxes, waiting up to `timeout` seconds
-    /// for each box to stop gracefully before force-killing it.
+    /// This method stops all running boxes concurrently, waiting up to
+    /// `timeout` seconds for each box to stop gracefully before force-killing
+    /// it.
     ///
     /// After calling this method, the runtime is permanently shut down and
     /// will return errors for any new operations (like `create()`).
@@ -175,12 +200,17 @@ impl PyBoxlite {
     ///         - None (default) - Use default timeout (10 seconds)
     ///         - Positive integer - Wait that many seconds
     ///         - -1 - Wait indefinitely (no timeout)
+    ///
+    /// Returns:
+    ///     ShutdownReport with one BoxShutdown per box
     #[pyo3(signature = (timeout=None))]
     fn shutdown<'py>(&self, py: Python<'py>, timeout: Option<i32>) -> PyResult<Bound<'py, PyAny>> {
         let runtime = Arc::clone(&self.runtime);
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
-            runtime.shutdown(timeout).await.map_err(map_err)?;
-            Ok(())
+            let report = runtime.shutdown(timeout).await.map_err(map_err)?;
+            Ok(PyShutdownReport {
+                boxes: report.boxes.into_iter().map(PyBoxShutdown::from).collect(),
+            })
         })
     }
 
@@ -201,3 +231,74 @@ impl PyBoxlite {
         "Boxlite(open=true)".to_string()
     }
 }
+
+/// How one box ended during `shutdown()`.
+#[pyclass(name = "BoxShutdown")]
+#[derive(Clone)]
+pub(crate) struct PyBoxShutdown {
+    #[pyo3(get)]
+    pub(crate) id: String,
+    #[pyo3(get)]
+    pub(crate) name: Option<String>,
+    /// "stopped" (gracefully), "killed" (after the timeout) or "failed"
+    #[pyo3(get)]
+    pub(crate) outcome: String,
+    /// Why the box could not be stopped ("failed" only)
+    #[pyo3(get)]
+    pub(crate) error: Option<String>,
+    /// Seconds from the start of the stop to its outcome
+    #[pyo3(get)]
+    pub(crate) elapsed_secs: f64,
+}
+
+#[pymethods]
+impl PyBoxShutdown {
+    fn __repr__(&self) -> String {
+        format!(
+            "BoxShutdown(id={:?}, outcome={:?}, elapsed_secs={:.3})",
+            self.id, self.outcome, self.elapsed_secs
+        )
+    }
+}
+
+impl From<BoxShutdown> for PyBoxShutdown {
+    fn from(done: BoxShutdown) -> Self {
+        let (outcome, error) = match done.outcome {
+            BoxShutdownOutcome::Stopped => ("stopped", None),
+            BoxShutdownOutcome::Killed => ("killed", None),
+            BoxShutdownOutcome::Failed(e) => ("failed", Some(e)),
+        };
+        Self {
+            id: done.box_id.to_string(),
+            name: done.name,
+            outcome: outcome.to_string(),
+            error,
+            elapsed_secs: done.elapsed.as_secs_f64(),
+        }
+    }
+}
+
+/// Result of `shutdown()`: one entry per box, in the order they finished.
+#[pyclass(name = "ShutdownReport")]
+#[derive(Clone)]
+pub(crate) struct PyShutdownReport {
+    #[pyo3(get)]
+    pub(crate) boxes: Vec<PyBoxShutdown>,
+}
+
+#[pymethods]
+impl PyShutdownReport {
+    /// Boxes that could not be stopped.
+    #[getter]
+    fn failed(&self) -> Vec<PyBoxShutdown> {
+        self.boxes
+            .iter()
+            .filter(|b| b.outcome == "failed")
+            .cloned()
+            .collect()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("ShutdownReport(boxes={})", self.boxes.len())
+    }
+}
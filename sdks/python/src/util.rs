@@ -1,5 +1,23 @@
+use boxlite::BoxliteError;
 use pyo3::{exceptions::PyRuntimeError, prelude::*};
 
-pub(crate) fn map_err(err: impl std::fmt::Display) -> PyErr {
-    PyRuntimeError::new_err(err.to_string())
+/// Convert a BoxliteError into the matching `boxlite.errors` exception.
+///
+/// The class is picked by error code (falling back to its category), and the
+/// code is kept on the exception's `code` attribute. If the errors module
+/// can't be imported, a plain `RuntimeError` is raised instead.
+pub(crate) fn map_err(err: BoxliteError) -> PyErr {
+    let code = err.code();
+    let message = err.to_string();
+    Python::attach(|py| {
+        py.import("boxlite.errors")
+            .and_then(|errors| {
+                errors.call_method1(
+                    "_from_code",
+                    (code.as_str(), code.category().as_str(), &message),
+                )
+            })
+            .map(PyErr::from_value)
+            .unwrap_or_else(|_| PyRuntimeError::new_err(message))
+    })
 }
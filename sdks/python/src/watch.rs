@@ -0,0 +1,82 @@
+use crate::util::map_err;
+use pyo3::prelude::*;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// A change to a file or directory inside the box.
+#[pyclass(name = "FsEvent")]
+#[derive(Clone)]
+pub(crate) struct PyFsEvent {
+    /// "created", "modified", "removed", "moved_from", "moved_to" or "overflow"
+    #[pyo3(get)]
+    pub(crate) kind: String,
+    /// Absolute path inside the container (empty for "overflow")
+    #[pyo3(get)]
+    pub(crate) path: String,
+    #[pyo3(get)]
+    pub(crate) is_dir: bool,
+    /// Pairs the "moved_from" and "moved_to" events of one rename (0 otherwise)
+    #[pyo3(get)]
+    pub(crate) cookie: u32,
+}
+
+#[pymethods]
+impl PyFsEvent {
+    fn __repr__(&self) -> String {
+        format!("FsEvent(kind={:?}, path={:?})", self.kind, self.path)
+    }
+}
+
+impl From<boxlite::FsEvent> for PyFsEvent {
+    fn from(event: boxlite::FsEvent) -> Self {
+        use boxlite::FsEventKind;
+        let kind = match event.kind {
+            FsEventKind::Created => "created",
+            FsEventKind::Modified => "modified",
+            FsEventKind::Removed => "removed",
+            FsEventKind::MovedFrom => "moved_from",
+            FsEventKind::MovedTo => "moved_to",
+            FsEventKind::Overflow => "overflow",
+        };
+        PyFsEvent {
+            kind: kind.to_string(),
+            path: event.path,
+            is_dir: event.is_dir,
+            cookie: event.cookie,
+        }
+    }
+}
+
+/// File change events for a watched path; iterate with `async for`.
+///
+/// Iteration ends when the path is removed or the box stops.
+#[pyclass(name = "FsWatch")]
+pub(crate) struct PyFsWatch {
+    pub(crate) stream: Arc<Mutex<boxlite::FsWatch>>,
+}
+
+#[pymethods]
+impl PyFsWatch {
+    fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __anext__<'a>(&self, py: Python<'a>) -> PyResult<Option<Bound<'a, PyAny>>> {
+        let stream = Arc::clone(&self.stream);
+
+        let future = pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            use futures::StreamExt;
+            let mut guard = stream.lock().await;
+            match guard.next().await {
+                Some(event) => Ok(PyFsEvent::from(event.map_err(map_err)?)),
+                None => Err(pyo3::exceptions::PyStopAsyncIteration::new_err("")),
+            }
+        })?;
+
+        Ok(Some(future))
+    }
+
+    fn __repr__(&self) -> String {
+        "FsWatch(...)".to_string()
+    }
+}
@@ -57,10 +57,10 @@ class TestExecError:
         with pytest.raises(BoxliteError):
             raise ExecError("cmd", 1, "error")
 
-    def test_negative_exit_code(self):
-        """Test ExecError with negative exit code (signal termination)."""
-        err = ExecError(command="sleep 100", exit_code=-9, stderr="killed")
-        assert err.exit_code == -9
+    def test_signal_exit_code(self):
+        """Test ExecError with a signal exit code (128 + N)."""
+        err = ExecError(command="sleep 100", exit_code=137, stderr="killed")
+        assert err.exit_code == 137
 
     def test_empty_stderr(self):
         """Test ExecError with empty stderr."""
@@ -136,6 +136,48 @@ class TestErrorHierarchy:
                 assert e is error
 
 
+class TestErrorCodes:
+    """Test native error codes mapping onto exception classes."""
+
+    def test_specific_code_picks_specific_class(self):
+        """Test that a known code maps to its class and keeps the code."""
+        from boxlite.errors import ImageError, ImageNotFoundError, _from_code
+
+        err = _from_code("IMAGE_NOT_FOUND", "IMAGE", "image not found: nope")
+        assert isinstance(err, ImageNotFoundError)
+        assert isinstance(err, ImageError)
+        assert err.code == "IMAGE_NOT_FOUND"
+        assert str(err) == "image not found: nope"
+
+    def test_unknown_code_falls_back_to_category(self):
+        """Test that an unknown code uses its category's class."""
+        from boxlite.errors import NetworkError, _from_code
+
+        err = _from_code("SOME_NEW_NETWORK_CODE", "NETWORK", "boom")
+        assert type(err) is NetworkError
+        assert err.code == "SOME_NEW_NETWORK_CODE"
+
+    def test_unknown_category_falls_back_to_base(self):
+        """Test that codes without a class are plain BoxliteError."""
+        from boxlite.errors import _from_code
+
+        err = _from_code("INTERNAL", "INTERNAL", "oops")
+        assert type(err) is BoxliteError
+        assert err.code == "INTERNAL"
+
+    def test_guest_timeout_is_timeout(self):
+        """Test that guest timeouts can be caught as TimeoutError."""
+        from boxlite.errors import GuestTimeoutError
+
+        assert issubclass(GuestTimeoutError, TimeoutError)
+
+    def test_native_errors_remain_runtime_errors(self):
+        """Test backwards compatibility with RuntimeError handlers."""
+        from boxlite.errors import NameConflictError
+
+        assert issubclass(NameConflictError, RuntimeError)
+
+
 class TestErrorExports:
     """Test that errors are properly exported."""
 
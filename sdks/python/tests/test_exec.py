@@ -57,10 +57,13 @@ class TestExecResultCreation:
         assert "Normal output" in result.stdout
         assert "Warning" in result.stderr
 
-    def test_create_negative_exit_code(self):
-        """Test creating result with negative exit code (signal)."""
-        result = ExecResult(exit_code=-9, stdout="", stderr="Killed")
-        assert result.exit_code == -9
+    def test_create_signal_exit_code(self):
+        """Test creating result for a process killed by a signal."""
+        result = ExecResult(
+            exit_code=137, stdout="", stderr="Killed", reason="signaled", signal=9
+        )
+        assert result.exit_code == 128 + result.signal
+        assert result.reason == "signaled"
 
     def test_create_multiline_output(self):
         """Test creating result with multiline output."""
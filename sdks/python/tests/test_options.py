@@ -120,7 +120,6 @@ class TestDetachOption:
 
     def test_detach_true_creates_box(self, runtime):
         """Test that detach=True creates box successfully."""
-        # Note: detach=True requires auto_remove=False (they are incompatible)
         box = runtime.create(
             boxlite.BoxOptions(
                 image="alpine:latest",
@@ -136,25 +135,6 @@ class TestDetachOption:
         runtime.remove(box.id)
 
 
-class TestInvalidCombinations:
-    """Test that invalid option combinations are rejected."""
-
-    @pytest.mark.skip(
-        reason="API behavior may have changed - combination no longer rejected"
-    )
-    def test_auto_remove_true_detach_true_rejected(self, runtime):
-        """Test that auto_remove=True + detach=True is rejected."""
-        with pytest.raises(RuntimeError) as exc_info:
-            runtime.create(
-                boxlite.BoxOptions(
-                    image="alpine:latest",
-                    auto_remove=True,
-                    detach=True,
-                )
-            )
-        assert "incompatible" in str(exc_info.value).lower()
-
-
 class TestCombinedOptions:
     """Test combinations of auto_remove and detach options."""
 
@@ -228,6 +208,26 @@ class TestCombinedOptions:
         # Cleanup
         runtime.remove(box_id)
 
+    def test_detached_ephemeral_service(self, runtime):
+        """Test detached ephemeral service: auto_remove=True, detach=True."""
+        box = runtime.create(
+            boxlite.BoxOptions(
+                image="alpine:latest",
+                auto_remove=True,
+                detach=True,
+            )
+        )
+        box_id = box.id
+
+        # Box exists
+        assert runtime.get_info(box_id) is not None
+
+        # Stop - should auto-remove
+        box.stop()
+
+        # Box gone
+        assert runtime.get_info(box_id) is None
+
 
 class TestCmdAndUserOptions:
     """Test cmd and user override options."""
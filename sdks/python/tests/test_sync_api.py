@@ -183,8 +183,9 @@ class TestSyncExecution:
         execution.kill()
 
         result = execution.wait()
-        # Killed processes typically have negative exit code (signal)
-        assert result.exit_code != 0
+        # Killed by SIGKILL: 128 + 9
+        assert result.exit_code == 137
+        assert result.signal == 9
         box.stop()
 
     def test_stdout_iteration(self, shared_sync_runtime):